@@ -5,22 +5,21 @@ use crate::evaluator::metrics::{
     BenchmarkConfigSummary, BenchmarkResult, EvaluationMetrics, LatencyCalculator,
     LatencyMeasurement, MetricsCalculator, QueryResult, RelevantDocument, RetrievedDocument,
 };
-use crate::search_client::{create_search_request, with_limit, with_offset, OmniSearchClient};
 use anyhow::Result;
 use chrono::Utc;
 use futures::stream::{self, StreamExt};
 use indicatif::{ProgressBar, ProgressStyle};
-use omni_searcher::models::SearchMode;
+use omni_client::{OmniClient, SearchMode, SearchRequest};
 use std::time::{Duration, Instant};
 use tokio::time::sleep;
 use tracing::{info, warn};
 
 pub struct BenchmarkEvaluator {
-    search_client: OmniSearchClient,
+    search_client: OmniClient,
 }
 
 impl BenchmarkEvaluator {
-    pub fn new(search_client: OmniSearchClient) -> Self {
+    pub fn new(search_client: OmniClient) -> Self {
         Self { search_client }
     }
 
@@ -58,7 +57,7 @@ impl BenchmarkEvaluator {
             let mode = Self::parse_search_mode(search_mode);
             for query_result in queries.iter().take(warmup_count) {
                 if let Ok(q) = query_result {
-                    let request = create_search_request(q.text.clone(), mode.clone());
+                    let request = Self::search_request(&q.text, mode, 20);
                     let _ = self.search_client.search(&request).await;
                     warmup_bar.inc(1);
                 }
@@ -174,9 +173,17 @@ impl BenchmarkEvaluator {
         }
     }
 
+    fn search_request(query: &str, mode: SearchMode, limit: i64) -> SearchRequest {
+        SearchRequest::new(query)
+            .with_mode(mode)
+            .with_limit(limit)
+            .with_offset(0)
+            .with_facets(false)
+    }
+
     async fn process_query(
         &self,
-        search_client: &OmniSearchClient,
+        search_client: &OmniClient,
         query: &crate::datasets::Query,
         search_mode: &str,
         config: &BenchmarkConfig,
@@ -187,9 +194,7 @@ impl BenchmarkEvaluator {
 
         let mode = Self::parse_search_mode(search_mode);
 
-        let search_request = create_search_request(query.text.clone(), mode);
-        let search_request = with_limit(search_request, config.max_results_per_query);
-        let search_request = with_offset(search_request, 0);
+        let search_request = Self::search_request(&query.text, mode, config.max_results_per_query);
 
         let start = Instant::now();
         let search_response = search_client.search(&search_request).await?;
@@ -243,7 +248,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_benchmark_evaluator() {
-        let client = OmniSearchClient::new("http://localhost:3001").unwrap();
+        let client = OmniClient::builder()
+            .searcher_url("http://localhost:3001")
+            .build()
+            .unwrap();
         let _evaluator = BenchmarkEvaluator::new(client);
     }
 }
@@ -391,6 +391,8 @@ impl BenchmarkIndexer {
             url: None,
             path: None,
             extra: Some(extra_metadata),
+            thumbnail: None,
+            parent: None,
         };
 
         let permissions = DocumentPermissions {
@@ -8,14 +8,12 @@ mod evaluator;
 mod indexer;
 mod prepare_nq;
 mod reporter;
-mod search_client;
 
 use config::BenchmarkConfig;
 use datasets::{BeirDataset, DatasetLoader, MsMarcoDataset, NaturalQuestionsDataset};
 use evaluator::BenchmarkEvaluator;
 use indexer::BenchmarkIndexer;
 use reporter::BenchmarkReporter;
-use search_client::OmniSearchClient;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -234,7 +232,9 @@ async fn run_benchmarks(
 
     let system_info = indexer.get_system_info().await.ok();
 
-    let search_client = OmniSearchClient::new(&config.searcher_url)?;
+    let search_client = omni_client::OmniClient::builder()
+        .searcher_url(&config.searcher_url)
+        .build()?;
     let evaluator = BenchmarkEvaluator::new(search_client);
 
     let search_modes = if search_mode == "all" {
@@ -0,0 +1,6 @@
+"""Airtable connector for Omni."""
+
+from .connector import AirtableConnector
+
+__version__ = "1.0.0"
+__all__ = ["AirtableConnector"]
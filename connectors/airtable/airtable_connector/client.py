@@ -0,0 +1,193 @@
+"""Thin async wrapper over the Airtable REST API with retry logic."""
+
+import asyncio
+import logging
+from collections.abc import AsyncIterator
+from functools import wraps
+from typing import Any
+
+import httpx
+
+from .config import AIRTABLE_API_URL, PAGE_SIZE
+
+logger = logging.getLogger(__name__)
+
+
+class AirtableError(Exception):
+    """Base exception for Airtable API errors."""
+
+    def __init__(self, message: str, status_code: int | None = None):
+        super().__init__(message)
+        self.status_code = status_code
+
+
+class AuthenticationError(AirtableError):
+    """Invalid or expired token (401)."""
+
+    pass
+
+
+class ForbiddenError(AirtableError):
+    """The token cannot access a base, or lacks a scope (403)."""
+
+    pass
+
+
+class NotFoundError(AirtableError):
+    """Base or table not found, or not granted to the token (404)."""
+
+    pass
+
+
+def with_retry(max_retries: int = 3, base_delay: float = 1.0):
+    """Decorator for retrying Airtable API calls with exponential backoff.
+
+    Handles:
+    - 429 Rate Limit: Wait for Retry-After header (unlimited retries)
+    - 5xx Server Error: Exponential backoff (limited retries)
+    - 401/403: Re-raise as AuthenticationError/ForbiddenError (non-retryable)
+    - 404: Re-raise as NotFoundError (non-retryable)
+    """
+
+    def decorator(func):
+        @wraps(func)
+        async def wrapper(*args, **kwargs):
+            last_exception = None
+            error_retries = 0
+
+            while True:
+                try:
+                    return await func(*args, **kwargs)
+                except httpx.HTTPStatusError as e:
+                    last_exception = e
+                    status = e.response.status_code
+
+                    if status == 401:
+                        raise AuthenticationError(
+                            "Invalid or expired Airtable token", status_code=401
+                        ) from e
+
+                    if status == 403:
+                        raise ForbiddenError(
+                            f"Forbidden: {e.request.url.path}: {e.response.text}",
+                            status_code=403,
+                        ) from e
+
+                    if status == 404:
+                        raise NotFoundError(
+                            f"Not found: {e.request.url.path}", status_code=404
+                        ) from e
+
+                    if status == 429:
+                        # Airtable asks for 30 seconds after exceeding 5 requests per second
+                        retry_after = int(e.response.headers.get("Retry-After", "30"))
+                        logger.warning("Rate limited. Waiting %ds", retry_after)
+                        await asyncio.sleep(retry_after)
+                        continue
+
+                    if status >= 500:
+                        error_retries += 1
+                        if error_retries > max_retries:
+                            break
+                        delay = base_delay * (2 ** (error_retries - 1))
+                        logger.warning(
+                            "Server error %d. Retrying in %.1fs (%d/%d)",
+                            status,
+                            delay,
+                            error_retries,
+                            max_retries,
+                        )
+                        await asyncio.sleep(delay)
+                        continue
+
+                    raise AirtableError(
+                        f"API error {status}: {e.response.text}",
+                        status_code=status,
+                    ) from e
+
+            raise AirtableError(f"Max retries exceeded: {last_exception}") from last_exception
+
+        return wrapper
+
+    return decorator
+
+
+class AirtableClient:
+    """Async client for the Airtable Web and Metadata APIs.
+
+    Takes a personal access token or an OAuth access token, both sent as a bearer token.
+    """
+
+    def __init__(
+        self,
+        token: str,
+        base_url: str | None = None,
+        http_client: httpx.AsyncClient | None = None,
+    ):
+        self._client = http_client or httpx.AsyncClient(
+            base_url=(base_url or AIRTABLE_API_URL).rstrip("/"),
+            headers={"Authorization": f"Bearer {token}"},
+            timeout=httpx.Timeout(60.0, connect=10.0),
+        )
+
+    async def close(self) -> None:
+        await self._client.aclose()
+
+    @with_retry(max_retries=3)
+    async def get(self, path: str, params: dict[str, Any] | None = None) -> dict[str, Any]:
+        response = await self._client.get(path, params=params)
+        response.raise_for_status()
+        return response.json()
+
+    async def list_bases(self) -> list[dict[str, Any]]:
+        """The bases the token can access, with their id, name and permissionLevel."""
+        bases: list[dict[str, Any]] = []
+        params: dict[str, Any] = {}
+        while True:
+            data = await self.get("/v0/meta/bases", params)
+            bases.extend(data.get("bases", []))
+            if not data.get("offset"):
+                return bases
+            params["offset"] = data["offset"]
+
+    async def get_tables(self, base_id: str) -> list[dict[str, Any]]:
+        """The schema of a base: its tables with their fields and views."""
+        data = await self.get(f"/v0/meta/bases/{base_id}/tables")
+        return data.get("tables", [])
+
+    async def get_collaborators(self, base_id: str) -> dict[str, Any]:
+        """A base with its collaborators. Requires an Enterprise plan."""
+        return await self.get(f"/v0/meta/bases/{base_id}", {"include": "collaborators"})
+
+    async def iter_records(
+        self,
+        base_id: str,
+        table_id: str,
+        view: str | None = None,
+        formula: str | None = None,
+        field_ids: list[str] | None = None,
+    ) -> AsyncIterator[dict[str, Any]]:
+        """
+        The records of a table, with their fields keyed by field ID.
+
+        Args:
+            base_id: Base of the table
+            table_id: Table to read
+            view: Only the records visible in this view, by name or ID
+            formula: Only the records this formula is true for
+            field_ids: Only return these fields
+        """
+        params: dict[str, Any] = {"pageSize": PAGE_SIZE, "returnFieldsByFieldId": "true"}
+        if view:
+            params["view"] = view
+        if formula:
+            params["filterByFormula"] = formula
+        if field_ids:
+            params["fields[]"] = field_ids
+        while True:
+            data = await self.get(f"/v0/{base_id}/{table_id}", params)
+            for record in data.get("records", []):
+                yield record
+            if not data.get("offset"):
+                return
+            params["offset"] = data["offset"]
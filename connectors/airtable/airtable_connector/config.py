@@ -0,0 +1,27 @@
+"""Configuration constants for Airtable connector."""
+
+AIRTABLE_API_URL = "https://api.airtable.com"
+AIRTABLE_APP_URL = "https://airtable.com"
+
+PAGE_SIZE = 100
+MAX_CONTENT_LENGTH = 100_000
+CHECKPOINT_INTERVAL = 100
+
+# Margin taken off a sync's start time before it becomes the next modified-time watermark,
+# for clock drift between this host and Airtable
+WATERMARK_MARGIN_SECONDS = 300
+
+# Permission levels that let a collaborator read a base's records
+READ_PERMISSION_LEVELS = {"read", "comment", "edit", "create"}
+
+# Field types rendered into content but kept out of attributes: long or unstructured
+# values, and references that mean nothing outside Airtable
+CONTENT_ONLY_FIELD_TYPES = {
+    "multilineText",
+    "richText",
+    "multipleAttachments",
+    "multipleRecordLinks",
+    "barcode",
+    "aiText",
+    "button",
+}
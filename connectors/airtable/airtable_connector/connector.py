@@ -0,0 +1,281 @@
+"""Main AirtableConnector class."""
+
+import hashlib
+import json
+import logging
+from datetime import datetime, timedelta, timezone
+from typing import Any
+
+from omni_connector import Connector, DocumentPermissions, SyncContext
+
+from .client import (
+    AirtableClient,
+    AirtableError,
+    AuthenticationError,
+    ForbiddenError,
+    NotFoundError,
+)
+from .config import CHECKPOINT_INTERVAL, WATERMARK_MARGIN_SECONDS
+from .mappers import (
+    base_permissions,
+    generate_record_content,
+    map_record_to_document,
+    record_external_id,
+)
+
+logger = logging.getLogger(__name__)
+
+
+class AirtableConnector(Connector):
+    """Airtable connector for Omni, syncing the records of a workspace's bases."""
+
+    @property
+    def name(self) -> str:
+        return "airtable"
+
+    @property
+    def version(self) -> str:
+        return "1.0.0"
+
+    @property
+    def sync_modes(self) -> list[str]:
+        return ["full", "incremental"]
+
+    async def sync(
+        self,
+        source_config: dict[str, Any],
+        credentials: dict[str, Any],
+        state: dict[str, Any] | None,
+        ctx: SyncContext,
+    ) -> None:
+        """
+        Sync the records of every base the token can access, or of the configured ones.
+
+        The state keeps, per table, a fingerprint of its schema, the time of its last sync
+        and the IDs of its records. A table whose schema or base permissions are unchanged
+        is read incrementally: only the records modified since its last sync are fetched,
+        and deletions are found from a listing of the record IDs alone.
+
+        Args:
+            source_config: May set base_ids, and views mapping table IDs or names to the
+                view their records are read through
+            credentials: 'token' (personal access token) or 'access_token' (OAuth)
+            state: Previous sync state, {"bases": {base_id: {...}}}
+            ctx: Sync context with emit(), complete(), etc.
+        """
+        token = credentials.get("token") or credentials.get("access_token")
+        if not token:
+            await ctx.fail("Missing 'token' in credentials")
+            return
+
+        client = AirtableClient(token, base_url=source_config.get("api_url"))
+        try:
+            try:
+                bases = await client.list_bases()
+            except (AuthenticationError, ForbiddenError) as e:
+                await ctx.fail(f"Authentication failed: {e}")
+                return
+            except AirtableError as e:
+                await ctx.fail(f"Connection test failed: {e}")
+                return
+
+            base_ids = set(source_config.get("base_ids") or [])
+            if base_ids:
+                bases = [base for base in bases if base["id"] in base_ids]
+            logger.info("Starting Airtable sync of %d bases", len(bases))
+
+            sync = _Sync(client, source_config, (state or {}).get("bases", {}), ctx)
+            try:
+                for base in bases:
+                    if ctx.is_cancelled():
+                        break
+                    await sync.sync_base(base)
+
+                if ctx.is_cancelled():
+                    await ctx.fail("Cancelled by user")
+                    return
+                await sync.delete_missing()
+                await ctx.complete(new_state=sync.state())
+                logger.info(
+                    "Sync completed: %d scanned, %d emitted",
+                    ctx.documents_scanned,
+                    ctx.documents_emitted,
+                )
+            except AuthenticationError as e:
+                logger.error("Authentication error during sync: %s", e)
+                await ctx.fail(f"Authentication failed: {e}")
+            except Exception as e:
+                logger.exception("Sync failed with unexpected error")
+                await ctx.fail(str(e))
+        finally:
+            await client.close()
+
+
+def _fingerprint(value: Any) -> str:
+    return hashlib.sha256(json.dumps(value, sort_keys=True).encode()).hexdigest()[:16]
+
+
+def schema_fingerprint(table: dict[str, Any]) -> str:
+    """Changes when a field is added, removed, renamed or changes type."""
+    fields = [(f["id"], f["name"], f.get("type")) for f in table.get("fields", [])]
+    return _fingerprint([table.get("name"), table.get("primaryFieldId"), fields])
+
+
+def permissions_fingerprint(permissions: DocumentPermissions) -> str:
+    return _fingerprint([sorted(permissions.users or []), sorted(permissions.groups or [])])
+
+
+def modified_since_formula(since: str) -> str:
+    """Formula matching the records created or modified after a time."""
+    when = f"DATETIME_PARSE('{since}')"
+    return f"OR(IS_AFTER(LAST_MODIFIED_TIME(), {when}), IS_AFTER(CREATED_TIME(), {when}))"
+
+
+class _Sync:
+    """State of one sync run: the bases synced so far and their new state."""
+
+    def __init__(
+        self,
+        client: AirtableClient,
+        source_config: dict[str, Any],
+        previous: dict[str, Any],
+        ctx: SyncContext,
+    ):
+        self.client = client
+        self.ctx = ctx
+        self.previous = previous
+        self.bases: dict[str, Any] = {}
+        self.views: dict[str, str] = source_config.get("views") or {}
+        # Collaborators can only be read on Enterprise plans; stop asking once refused
+        self.read_collaborators = True
+        self._since_checkpoint = 0
+
+    def state(self) -> dict[str, Any]:
+        return {"bases": {**self.previous, **self.bases}}
+
+    async def _permissions(self, base_id: str) -> DocumentPermissions:
+        base = None
+        if self.read_collaborators:
+            try:
+                base = await self.client.get_collaborators(base_id)
+            except (ForbiddenError, NotFoundError) as e:
+                logger.warning(
+                    "Cannot read base collaborators, sharing records with base groups only: %s",
+                    e,
+                )
+                self.read_collaborators = False
+        return base_permissions(base_id, base)
+
+    async def sync_base(self, base: dict[str, Any]) -> None:
+        base_id = base["id"]
+        prev = self.previous.get(base_id, {})
+        try:
+            tables = await self.client.get_tables(base_id)
+            permissions = await self._permissions(base_id)
+        except AuthenticationError:
+            raise
+        except AirtableError as e:
+            logger.error("Error fetching the schema of base %s: %s", base_id, e)
+            await self.ctx.emit_error(f"airtable:base:{base_id}", str(e))
+            # Keep the previous state so the base's records are not deleted
+            if prev:
+                self.bases[base_id] = prev
+            return
+
+        permissions_key = permissions_fingerprint(permissions)
+        regranted = prev.get("permissions") != permissions_key
+        entry: dict[str, Any] = {"permissions": permissions_key, "tables": {}}
+
+        logger.info("Syncing %d tables of base %s", len(tables), base.get("name"))
+        for table in tables:
+            if self.ctx.is_cancelled():
+                return
+            known = prev.get("tables", {}).get(table["id"])
+            try:
+                entry["tables"][table["id"]] = await self._sync_table(
+                    base, table, permissions, None if regranted else known, known
+                )
+            except AuthenticationError:
+                raise
+            except AirtableError as e:
+                logger.error("Error syncing table %s: %s", table.get("name"), e)
+                await self.ctx.emit_error(f"airtable:table:{table['id']}", str(e))
+                if known:
+                    # Read in full next time, its records are kept meanwhile
+                    entry["tables"][table["id"]] = {**known, "schema": None}
+
+        for table_id in set(prev.get("tables", {})) - set(entry["tables"]):
+            logger.info("Table %s was deleted, deleting its records", table_id)
+            for record_id in prev["tables"][table_id].get("records", []):
+                await self.ctx.emit_deleted(record_external_id(base_id, record_id))
+        self.bases[base_id] = entry
+
+    async def _sync_table(
+        self,
+        base: dict[str, Any],
+        table: dict[str, Any],
+        permissions: DocumentPermissions,
+        prev: dict[str, Any] | None,
+        known: dict[str, Any] | None,
+    ) -> dict[str, Any]:
+        """
+        Sync a table and return its new state.
+
+        Args:
+            prev: The table's previous state if it can be synced incrementally
+            known: The table's previous state, for its record IDs
+        """
+        base_id = base["id"]
+        schema = schema_fingerprint(table)
+        margin = timedelta(seconds=WATERMARK_MARGIN_SECONDS)
+        started = datetime.now(timezone.utc) - margin
+        view = self.views.get(table["id"]) or self.views.get(table.get("name", ""))
+        since = prev.get("synced_at") if prev and prev.get("schema") == schema else None
+        formula = modified_since_formula(since) if since else None
+
+        seen: set[str] = set()
+        async for record in self.client.iter_records(base_id, table["id"], view, formula):
+            if self.ctx.is_cancelled():
+                return known or {}
+            await self.ctx.increment_scanned()
+            seen.add(record["id"])
+            try:
+                content = generate_record_content(base, table, record)
+                content_id = await self.ctx.content_storage.save(content, "text/plain")
+                await self.ctx.emit(
+                    map_record_to_document(base, table, record, content_id, permissions)
+                )
+            except Exception as e:
+                logger.warning("Error processing record %s: %s", record["id"], e)
+                await self.ctx.emit_error(record_external_id(base_id, record["id"]), str(e))
+            await self._checkpoint()
+
+        if since:
+            # Modified-time filtering skips deleted records, so list what is left
+            primary = [table["primaryFieldId"]] if table.get("primaryFieldId") else None
+            records = self.client.iter_records(base_id, table["id"], view, field_ids=primary)
+            seen = {record["id"] async for record in records}
+
+        for record_id in set((known or {}).get("records", [])) - seen:
+            await self.ctx.emit_deleted(record_external_id(base_id, record_id))
+
+        return {
+            "schema": schema,
+            "synced_at": started.strftime("%Y-%m-%dT%H:%M:%S.000Z"),
+            "records": sorted(seen),
+        }
+
+    async def delete_missing(self) -> None:
+        """Delete the records of the bases no longer synced."""
+        for base_id in set(self.previous) - set(self.bases):
+            logger.info("Base %s is no longer synced, deleting its records", base_id)
+            for table in self.previous[base_id].get("tables", {}).values():
+                for record_id in table.get("records", []):
+                    await self.ctx.emit_deleted(record_external_id(base_id, record_id))
+        self.previous = {}
+
+    async def _checkpoint(self) -> None:
+        self._since_checkpoint += 1
+        if self._since_checkpoint >= CHECKPOINT_INTERVAL:
+            self._since_checkpoint = 0
+            await self.ctx.save_state(self.state())
@@ -0,0 +1,191 @@
+"""Record-to-Document mapping functions for Airtable, driven by each table's schema."""
+
+from datetime import datetime
+from typing import Any
+
+from omni_connector import Document, DocumentMetadata, DocumentPermissions
+
+from .config import (
+    AIRTABLE_APP_URL,
+    CONTENT_ONLY_FIELD_TYPES,
+    MAX_CONTENT_LENGTH,
+    READ_PERMISSION_LEVELS,
+)
+
+# Keys of the objects Airtable returns as cell values, by preference, that name the object:
+# collaborators, attachments, barcodes, AI text and buttons
+_OBJECT_TEXT_KEYS = ("name", "email", "filename", "text", "value", "label")
+
+
+def record_external_id(base_id: str, record_id: str) -> str:
+    return f"airtable:record:{base_id}:{record_id}"
+
+
+def parse_timestamp(value: str | None) -> datetime | None:
+    """Parse an Airtable ISO 8601 timestamp."""
+    if not value:
+        return None
+    try:
+        return datetime.fromisoformat(value.replace("Z", "+00:00"))
+    except ValueError:
+        return None
+
+
+def base_permissions(base_id: str, base: dict[str, Any] | None) -> DocumentPermissions:
+    """
+    Who can see a base's records.
+
+    Every base is shared with the `airtable:base:{base_id}` group. When its collaborators
+    could be read, the users and user groups that can read the base, directly or through
+    its workspace, are added.
+
+    Args:
+        base_id: ID of the base
+        base: The base with its collaborators, None when they could not be read
+    """
+    users: set[str] = set()
+    groups = {f"airtable:base:{base_id}"}
+    for collaborator in _collaborators(base, "individualCollaborators"):
+        if email := (collaborator.get("email") or "").strip().lower():
+            users.add(email)
+    for collaborator in _collaborators(base, "groupCollaborators"):
+        if group_id := collaborator.get("groupId"):
+            groups.add(f"airtable:group:{group_id}")
+    return DocumentPermissions(users=sorted(users), groups=sorted(groups))
+
+
+def _collaborators(base: dict[str, Any] | None, kind: str) -> list[dict[str, Any]]:
+    collaborators = (base or {}).get(kind) or {}
+    return [
+        collaborator
+        for scope in ("baseCollaborators", "workspaceCollaborators")
+        for collaborator in collaborators.get(scope) or []
+        if collaborator.get("permissionLevel") in READ_PERMISSION_LEVELS
+    ]
+
+
+def _scalar(value: Any) -> Any:
+    """A cell value, or an item of one, as a string, number or boolean."""
+    if isinstance(value, dict):
+        for key in _OBJECT_TEXT_KEYS:
+            if isinstance(value.get(key), str | int | float) and value[key] != "":
+                return value[key]
+        return None
+    return value
+
+
+def render_value(field: dict[str, Any], value: Any) -> str | None:
+    """The text of a cell, or None when it has none worth indexing."""
+    if field.get("type") in ("multipleRecordLinks", "button"):
+        return None
+    if isinstance(value, bool):
+        return "Yes" if value else "No"
+    if isinstance(value, list):
+        items = [_scalar(item) for item in value]
+        return ", ".join(str(item) for item in items if item is not None) or None
+    value = _scalar(value)
+    if value is None:
+        return None
+    return str(value).strip() or None
+
+
+def attribute_value(field: dict[str, Any], value: Any) -> Any:
+    """A cell as an attribute value, or None when its field type is kept to the content."""
+    if field.get("type") in CONTENT_ONLY_FIELD_TYPES:
+        return None
+    if isinstance(value, list):
+        items = [item for item in (_scalar(v) for v in value) if item is not None]
+        return items or None
+    return _scalar(value)
+
+
+def _title(table: dict[str, Any], record: dict[str, Any]) -> str:
+    primary = next(
+        (f for f in table.get("fields", []) if f["id"] == table.get("primaryFieldId")), None
+    )
+    cells = record.get("fields", {})
+    if primary and primary["id"] in cells:
+        if title := render_value(primary, cells[primary["id"]]):
+            return title
+    return "Untitled record"
+
+
+def generate_record_content(
+    base: dict[str, Any], table: dict[str, Any], record: dict[str, Any]
+) -> str:
+    """Searchable text of a record: one line per field, in the table's field order."""
+    lines = [_title(table, record), f"Base: {base.get('name')}", f"Table: {table.get('name')}"]
+    if description := table.get("description"):
+        lines.append(description)
+    lines.append("")
+    cells = record.get("fields", {})
+    for field in table.get("fields", []):
+        if field["id"] not in cells:
+            continue
+        text = render_value(field, cells[field["id"]])
+        if not text:
+            continue
+        if "\n" in text:
+            lines.extend([f"{field['name']}:", text, ""])
+        else:
+            lines.append(f"{field['name']}: {text}")
+    return "\n".join(lines).strip()[:MAX_CONTENT_LENGTH]
+
+
+def map_record_to_document(
+    base: dict[str, Any],
+    table: dict[str, Any],
+    record: dict[str, Any],
+    content_id: str,
+    permissions: DocumentPermissions,
+) -> Document:
+    """
+    Map an Airtable record to an Omni Document.
+
+    Field names become attribute keys, so records can be filtered on their fields. Long
+    text, attachments and linked records are only indexed as content.
+
+    Args:
+        base: Base of the record (id, name)
+        table: Table schema with its fields, keyed by ID in the record
+        record: Record with its fields keyed by field ID
+        content_id: ID from content storage
+        permissions: Who can see the base's records
+
+    Returns:
+        Omni Document instance
+    """
+    cells = record.get("fields", {})
+    attributes: dict[str, Any] = {}
+    updated_at = None
+    for field in table.get("fields", []):
+        if field["id"] not in cells:
+            continue
+        value = attribute_value(field, cells[field["id"]])
+        if value is not None:
+            attributes[field["name"]] = value
+        if field.get("type") == "lastModifiedTime" and updated_at is None:
+            updated_at = parse_timestamp(cells[field["id"]])
+
+    return Document(
+        external_id=record_external_id(base["id"], record["id"]),
+        title=_title(table, record),
+        content_id=content_id,
+        metadata=DocumentMetadata(
+            created_at=parse_timestamp(record.get("createdTime")),
+            updated_at=updated_at,
+            url=f"{AIRTABLE_APP_URL}/{base['id']}/{table['id']}/{record['id']}",
+            mime_type="text/plain",
+            path=f"{base.get('name')} / {table.get('name')}",
+            extra={"base_id": base["id"], "table_id": table["id"], "record_id": record["id"]},
+        ),
+        permissions=permissions,
+        # The connector's own keys win over fields of the same name
+        attributes={
+            **attributes,
+            "source_type": "airtable",
+            "record_type": "record",
+            "base": base.get("name"),
+            "table": table.get("name"),
+        },
+    )
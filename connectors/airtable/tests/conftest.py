@@ -0,0 +1,265 @@
+"""Integration test fixtures for the Airtable connector.
+
+Session-scoped: harness, mock Airtable API server, connector server, connector-manager.
+Function-scoped: seed helper, source_id, httpx client.
+"""
+
+from __future__ import annotations
+
+import logging
+import socket
+import threading
+import time
+from typing import Any
+
+import httpx
+import pytest
+import pytest_asyncio
+import uvicorn
+from starlette.applications import Starlette
+from starlette.requests import Request
+from starlette.responses import JSONResponse
+from starlette.routing import Route
+
+from omni_connector.testing import OmniTestHarness, SeedHelper
+
+logger = logging.getLogger(__name__)
+
+
+# ---------------------------------------------------------------------------
+# Mock data payload helpers
+# ---------------------------------------------------------------------------
+
+
+def _table_payload(table_id: str, name: str, *field_names: str) -> dict[str, Any]:
+    return {
+        "id": table_id,
+        "name": name,
+        "primaryFieldId": "fld0",
+        "fields": [
+            {"id": f"fld{i}", "name": field_name, "type": "singleLineText"}
+            for i, field_name in enumerate(field_names)
+        ],
+        "views": [],
+    }
+
+
+def _record_payload(record_id: str, name: str) -> dict[str, Any]:
+    return {
+        "id": record_id,
+        "createdTime": "2024-01-15T10:00:00.000Z",
+        "fields": {"fld0": name},
+    }
+
+
+# ---------------------------------------------------------------------------
+# Mock Airtable API
+# ---------------------------------------------------------------------------
+
+
+class MockAirtableAPI:
+    """Controllable mock of the Airtable Web and Metadata API endpoints.
+
+    Collaborators are refused as on plans below Enterprise. Record listings filtered by a
+    formula return the records in `modified` of their table, standing in for Airtable's
+    evaluation of the modified-time formula.
+    """
+
+    def __init__(self) -> None:
+        self.bases: dict[str, dict[str, Any]] = {}
+        self.tables: dict[str, list[dict[str, Any]]] = {}
+        self.records: dict[str, list[dict[str, Any]]] = {}
+        self.modified: dict[str, list[dict[str, Any]]] = {}
+        self.formulas: list[str | None] = []
+
+    def reset(self) -> None:
+        self.bases.clear()
+        self.tables.clear()
+        self.records.clear()
+        self.modified.clear()
+        self.formulas.clear()
+
+    def add_base(self, base_id: str, name: str) -> None:
+        self.bases[base_id] = {"id": base_id, "name": name, "permissionLevel": "create"}
+        self.tables[base_id] = []
+
+    def add_table(self, base_id: str, table: dict[str, Any]) -> None:
+        self.tables[base_id].append(table)
+        self.records.setdefault(table["id"], [])
+
+    def add_record(self, table_id: str, record: dict[str, Any]) -> None:
+        self.records[table_id].append(record)
+
+    def create_app(self) -> Starlette:
+        mock = self
+
+        async def list_bases(request: Request) -> JSONResponse:
+            return JSONResponse({"bases": list(mock.bases.values())})
+
+        async def get_tables(request: Request) -> JSONResponse:
+            base_id = request.path_params["base_id"]
+            if base_id not in mock.bases:
+                return JSONResponse({"error": "NOT_FOUND"}, status_code=404)
+            return JSONResponse({"tables": mock.tables[base_id]})
+
+        async def get_base(request: Request) -> JSONResponse:
+            return JSONResponse(
+                {
+                    "error": {
+                        "type": "INVALID_PERMISSIONS_OR_MODEL_NOT_FOUND",
+                        "message": "Collaborators require an Enterprise plan",
+                    }
+                },
+                status_code=403,
+            )
+
+        async def list_records(request: Request) -> JSONResponse:
+            table_id = request.path_params["table_id"]
+            formula = request.query_params.get("filterByFormula")
+            if not request.query_params.getlist("fields[]"):
+                mock.formulas.append(formula)
+            if formula:
+                records = mock.modified.get(table_id, [])
+            else:
+                records = mock.records.get(table_id, [])
+            return JSONResponse({"records": records})
+
+        routes = [
+            Route("/v0/meta/bases", list_bases),
+            Route("/v0/meta/bases/{base_id}/tables", get_tables),
+            Route("/v0/meta/bases/{base_id}", get_base),
+            Route("/v0/{base_id}/{table_id}", list_records),
+        ]
+        return Starlette(routes=routes)
+
+
+# ---------------------------------------------------------------------------
+# Helpers
+# ---------------------------------------------------------------------------
+
+
+def _free_port() -> int:
+    with socket.socket(socket.AF_INET, socket.SOCK_STREAM) as s:
+        s.bind(("", 0))
+        return s.getsockname()[1]
+
+
+def _wait_for_port(port: int, host: str = "localhost", timeout: float = 10) -> None:
+    deadline = time.monotonic() + timeout
+    while time.monotonic() < deadline:
+        try:
+            with socket.create_connection((host, port), timeout=1):
+                return
+        except OSError:
+            time.sleep(0.1)
+    raise TimeoutError(f"Port {port} not open after {timeout}s")
+
+
+# ---------------------------------------------------------------------------
+# Session-scoped fixtures
+# ---------------------------------------------------------------------------
+
+
+@pytest.fixture(scope="session")
+def mock_airtable_api() -> MockAirtableAPI:
+    return MockAirtableAPI()
+
+
+@pytest.fixture(scope="session")
+def mock_airtable_server(mock_airtable_api: MockAirtableAPI) -> str:
+    """Start mock Airtable API server in a daemon thread. Returns base URL."""
+    port = _free_port()
+    app = mock_airtable_api.create_app()
+    config = uvicorn.Config(app, host="0.0.0.0", port=port, log_level="warning")
+    server = uvicorn.Server(config)
+
+    thread = threading.Thread(target=server.run, daemon=True)
+    thread.start()
+
+    _wait_for_port(port)
+    return f"http://localhost:{port}"
+
+
+@pytest.fixture(scope="session")
+def connector_port() -> int:
+    return _free_port()
+
+
+@pytest.fixture(scope="session")
+def connector_server(connector_port: int) -> str:
+    """Start the Airtable connector as a uvicorn server in a daemon thread. Returns base URL."""
+    import os
+
+    os.environ.setdefault("CONNECTOR_MANAGER_URL", "http://localhost:0")
+
+    from airtable_connector import AirtableConnector
+    from omni_connector.server import create_app
+
+    app = create_app(AirtableConnector())
+    config = uvicorn.Config(
+        app, host="0.0.0.0", port=connector_port, log_level="warning"
+    )
+    server = uvicorn.Server(config)
+
+    thread = threading.Thread(target=server.run, daemon=True)
+    thread.start()
+
+    _wait_for_port(connector_port)
+    return f"http://localhost:{connector_port}"
+
+
+@pytest_asyncio.fixture(scope="session")
+async def harness(
+    connector_server: str,
+    connector_port: int,
+) -> OmniTestHarness:
+    """Session-scoped OmniTestHarness with all infrastructure started."""
+    import os
+
+    h = OmniTestHarness()
+    await h.start_infra()
+    await h.start_connector_manager(
+        {
+            "AIRTABLE_CONNECTOR_URL": f"http://host.docker.internal:{connector_port}",
+        }
+    )
+
+    os.environ["CONNECTOR_MANAGER_URL"] = h.connector_manager_url
+
+    yield h
+    await h.teardown()
+
+
+# ---------------------------------------------------------------------------
+# Function-scoped fixtures
+# ---------------------------------------------------------------------------
+
+
+@pytest_asyncio.fixture
+async def seed(harness: OmniTestHarness) -> SeedHelper:
+    return harness.seed()
+
+
+@pytest_asyncio.fixture
+async def source_id(
+    seed: SeedHelper,
+    mock_airtable_server: str,
+    mock_airtable_api: MockAirtableAPI,
+) -> str:
+    """Create an Airtable source with credentials pointing to the mock server."""
+    mock_airtable_api.reset()
+    sid = await seed.create_source(
+        source_type="airtable",
+        config={"api_url": mock_airtable_server},
+    )
+    await seed.create_credentials(sid, {"token": "test-token"}, provider="airtable")
+    return sid
+
+
+@pytest_asyncio.fixture
+async def cm_client(harness: OmniTestHarness) -> httpx.AsyncClient:
+    """Async httpx client pointed at the connector-manager."""
+    async with httpx.AsyncClient(
+        base_url=harness.connector_manager_url, timeout=30
+    ) as client:
+        yield client
@@ -0,0 +1,108 @@
+"""Integration tests: incremental sync reads only modified records and finds deleted ones."""
+
+import httpx
+import pytest
+
+from omni_connector.testing import get_events, wait_for_sync
+
+from .conftest import _record_payload, _table_payload
+
+pytestmark = pytest.mark.integration
+
+BASE_ID = "appBase"
+TABLE_ID = "tblCandidates"
+
+
+async def _sync(harness, cm_client: httpx.AsyncClient, source_id: str, sync_type: str):
+    resp = await cm_client.post(
+        "/sync",
+        json={"source_id": source_id, "sync_type": sync_type},
+    )
+    assert resp.status_code == 200, resp.text
+    row = await wait_for_sync(harness.db_pool, resp.json()["sync_run_id"], timeout=30)
+    assert (
+        row["status"] == "completed"
+    ), f"Sync ended with status={row['status']}, error={row.get('error_message')}"
+    return row
+
+
+async def _documents(harness, source_id: str, row, event_type: str) -> list[str]:
+    events = await get_events(harness.db_pool, source_id)
+    return [
+        e["payload"]["document_id"]
+        for e in events
+        if e["sync_run_id"] == row["id"] and e["event_type"] == event_type
+    ]
+
+
+def _seed_base(mock_airtable_api) -> None:
+    mock_airtable_api.add_base(BASE_ID, "Hiring")
+    mock_airtable_api.add_table(BASE_ID, _table_payload(TABLE_ID, "Candidates", "Name"))
+    mock_airtable_api.add_record(TABLE_ID, _record_payload("rec1", "Ada"))
+    mock_airtable_api.add_record(TABLE_ID, _record_payload("rec2", "Grace"))
+
+
+async def test_second_sync_reads_only_modified_records(
+    harness, source_id, mock_airtable_api, cm_client: httpx.AsyncClient
+):
+    _seed_base(mock_airtable_api)
+
+    row = await _sync(harness, cm_client, source_id, "full")
+    assert await _documents(harness, source_id, row, "document_created") == [
+        "airtable:record:appBase:rec1",
+        "airtable:record:appBase:rec2",
+    ]
+    assert mock_airtable_api.formulas == [None]
+
+    mock_airtable_api.modified[TABLE_ID] = [_record_payload("rec2", "Grace Hopper")]
+    row = await _sync(harness, cm_client, source_id, "incremental")
+
+    assert await _documents(harness, source_id, row, "document_created") == [
+        "airtable:record:appBase:rec2"
+    ]
+    assert "LAST_MODIFIED_TIME()" in mock_airtable_api.formulas[-1]
+    assert await _documents(harness, source_id, row, "document_deleted") == []
+
+
+async def test_deleted_records_are_found_by_listing_ids(
+    harness, seed, source_id, mock_airtable_api, cm_client: httpx.AsyncClient
+):
+    _seed_base(mock_airtable_api)
+    await _sync(harness, cm_client, source_id, "full")
+
+    del mock_airtable_api.records[TABLE_ID][1]
+    row = await _sync(harness, cm_client, source_id, "incremental")
+
+    assert await _documents(harness, source_id, row, "document_deleted") == [
+        "airtable:record:appBase:rec2"
+    ]
+    state = await seed.get_connector_state(source_id)
+    assert state["bases"][BASE_ID]["tables"][TABLE_ID]["records"] == ["rec1"]
+
+
+async def test_schema_change_reads_the_table_in_full(
+    harness, source_id, mock_airtable_api, cm_client: httpx.AsyncClient
+):
+    _seed_base(mock_airtable_api)
+    await _sync(harness, cm_client, source_id, "full")
+
+    mock_airtable_api.tables[BASE_ID] = [_table_payload(TABLE_ID, "Candidates", "Full name")]
+    row = await _sync(harness, cm_client, source_id, "incremental")
+
+    assert mock_airtable_api.formulas[-1] is None
+    assert len(await _documents(harness, source_id, row, "document_created")) == 2
+
+
+async def test_deleted_base_deletes_its_records(
+    harness, source_id, mock_airtable_api, cm_client: httpx.AsyncClient
+):
+    _seed_base(mock_airtable_api)
+    await _sync(harness, cm_client, source_id, "full")
+
+    del mock_airtable_api.bases[BASE_ID]
+    row = await _sync(harness, cm_client, source_id, "incremental")
+
+    assert sorted(await _documents(harness, source_id, row, "document_deleted")) == [
+        "airtable:record:appBase:rec1",
+        "airtable:record:appBase:rec2",
+    ]
@@ -0,0 +1,115 @@
+"""Tests for the Airtable record mappers."""
+
+from airtable_connector.mappers import (
+    base_permissions,
+    generate_record_content,
+    map_record_to_document,
+)
+
+BASE = {"id": "appBase", "name": "Hiring"}
+
+TABLE = {
+    "id": "tblCandidates",
+    "name": "Candidates",
+    "primaryFieldId": "fldName",
+    "fields": [
+        {"id": "fldName", "name": "Name", "type": "singleLineText"},
+        {"id": "fldStage", "name": "Stage", "type": "singleSelect"},
+        {"id": "fldSkills", "name": "Skills", "type": "multipleSelects"},
+        {"id": "fldScore", "name": "Score", "type": "number"},
+        {"id": "fldRemote", "name": "Remote", "type": "checkbox"},
+        {"id": "fldOwner", "name": "Recruiter", "type": "singleCollaborator"},
+        {"id": "fldNotes", "name": "Notes", "type": "multilineText"},
+        {"id": "fldCv", "name": "CV", "type": "multipleAttachments"},
+        {"id": "fldRole", "name": "Role", "type": "multipleRecordLinks"},
+        {"id": "fldModified", "name": "Modified", "type": "lastModifiedTime"},
+    ],
+}
+
+RECORD = {
+    "id": "recAda",
+    "createdTime": "2024-05-01T09:00:00.000Z",
+    "fields": {
+        "fldName": "Ada Lovelace",
+        "fldStage": "Onsite",
+        "fldSkills": ["Python", "Math"],
+        "fldScore": 4.5,
+        "fldRemote": True,
+        "fldOwner": {"id": "usr1", "email": "bob@example.com", "name": "Bob"},
+        "fldNotes": "Strong on algorithms.\nFollow up on availability.",
+        "fldCv": [{"id": "att1", "filename": "ada.pdf", "url": "https://..."}],
+        "fldRole": ["recRole1"],
+        "fldModified": "2024-05-03T12:00:00.000Z",
+    },
+}
+
+
+def test_content_renders_fields_in_schema_order():
+    content = generate_record_content(BASE, TABLE, RECORD)
+
+    assert content.splitlines()[:3] == ["Ada Lovelace", "Base: Hiring", "Table: Candidates"]
+    assert "Skills: Python, Math" in content
+    assert "Remote: Yes" in content
+    assert "Recruiter: Bob" in content
+    assert "Notes:\nStrong on algorithms.\nFollow up on availability." in content
+    assert "CV: ada.pdf" in content
+    # Linked record IDs mean nothing to a reader
+    assert "recRole1" not in content
+
+
+def test_field_names_become_attribute_keys():
+    permissions = base_permissions("appBase", None)
+    doc = map_record_to_document(BASE, TABLE, RECORD, "content-id", permissions)
+
+    assert doc.external_id == "airtable:record:appBase:recAda"
+    assert doc.title == "Ada Lovelace"
+    assert doc.metadata.url == "https://airtable.com/appBase/tblCandidates/recAda"
+    assert doc.metadata.updated_at.isoformat() == "2024-05-03T12:00:00+00:00"
+    assert doc.attributes == {
+        "Name": "Ada Lovelace",
+        "Stage": "Onsite",
+        "Skills": ["Python", "Math"],
+        "Score": 4.5,
+        "Remote": True,
+        "Recruiter": "Bob",
+        "Modified": "2024-05-03T12:00:00.000Z",
+        "source_type": "airtable",
+        "record_type": "record",
+        "base": "Hiring",
+        "table": "Candidates",
+    }
+
+
+def test_record_without_primary_value_is_untitled():
+    record = {"id": "recEmpty", "fields": {"fldStage": "Applied"}}
+    permissions = base_permissions("appBase", None)
+    doc = map_record_to_document(BASE, TABLE, record, "content-id", permissions)
+
+    assert doc.title == "Untitled record"
+
+
+def test_permissions_from_collaborators_with_read_access():
+    base = {
+        "individualCollaborators": {
+            "baseCollaborators": [
+                {"email": "Alice@Example.com", "permissionLevel": "edit"},
+                {"email": "nobody@example.com", "permissionLevel": "none"},
+            ],
+            "workspaceCollaborators": [{"email": "carol@example.com", "permissionLevel": "read"}],
+        },
+        "groupCollaborators": {
+            "baseCollaborators": [{"groupId": "ugpDesign", "permissionLevel": "comment"}],
+        },
+    }
+
+    permissions = base_permissions("appBase", base)
+
+    assert permissions.users == ["alice@example.com", "carol@example.com"]
+    assert permissions.groups == ["airtable:base:appBase", "airtable:group:ugpDesign"]
+
+
+def test_permissions_without_collaborators_use_the_base_group():
+    permissions = base_permissions("appBase", None)
+
+    assert permissions.users == []
+    assert permissions.groups == ["airtable:base:appBase"]
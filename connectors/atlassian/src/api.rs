@@ -9,12 +9,12 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use shared::models::SyncRequest;
-use shared::telemetry;
+use shared::{error_reporting, telemetry};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tower::ServiceBuilder;
 use tower_http::cors::CorsLayer;
-use tracing::{error, info};
+use tracing::{error, info, Instrument};
 
 use crate::models::{
     ActionRequest, ActionResponse, CancelRequest, CancelResponse, ConnectorManifest, SyncResponse,
@@ -35,9 +35,15 @@ pub struct HealthResponse {
 
 #[derive(Deserialize)]
 pub struct TestConnectionRequest {
-    pub base_url: String,
-    pub user_email: String,
-    pub api_token: String,
+    /// Test with the stored credentials of this source instead of the explicit ones.
+    #[serde(default)]
+    pub source_id: Option<String>,
+    #[serde(default)]
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub user_email: Option<String>,
+    #[serde(default)]
+    pub api_token: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -46,6 +52,15 @@ pub struct TestConnectionResponse {
     pub message: String,
     pub jira_projects: Vec<String>,
     pub confluence_spaces: Vec<String>,
+    /// The projects and spaces, for the connector manager's source setup preview
+    pub scopes: Vec<ConnectionScope>,
+}
+
+#[derive(Serialize)]
+pub struct ConnectionScope {
+    pub kind: &'static str,
+    pub id: String,
+    pub name: String,
 }
 
 #[derive(Serialize)]
@@ -103,12 +118,16 @@ async fn trigger_sync(
 
     let sync_manager = state.sync_manager.clone();
 
-    tokio::spawn(async move {
-        let mut manager = sync_manager.lock().await;
-        if let Err(e) = manager.sync_source(request).await {
-            error!("Sync {} failed: {}", sync_run_id, e);
+    let span = error_reporting::sync_span(&source_id, request.source_type, &sync_run_id);
+    tokio::spawn(
+        async move {
+            let mut manager = sync_manager.lock().await;
+            if let Err(e) = manager.sync_source(request).await {
+                error!("Sync {} failed: {}", sync_run_id, e);
+            }
         }
-    });
+        .instrument(span),
+    );
 
     Ok(Json(SyncResponse::started()))
 }
@@ -138,11 +157,42 @@ async fn test_connection(
     State(state): State<ApiState>,
     Json(request): Json<TestConnectionRequest>,
 ) -> Result<Json<TestConnectionResponse>, (StatusCode, Json<ErrorResponse>)> {
-    info!("Testing connection to Atlassian: {}", request.base_url);
+    let sync_manager = state.sync_manager.lock().await;
 
-    let config = (request.base_url, request.user_email, request.api_token);
+    let config = match request {
+        TestConnectionRequest {
+            source_id: Some(source_id),
+            ..
+        } => sync_manager
+            .source_connection_config(&source_id)
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        error: format!("Failed to load credentials: {}", e),
+                    }),
+                )
+            })?,
+        TestConnectionRequest {
+            base_url: Some(base_url),
+            user_email: Some(user_email),
+            api_token: Some(api_token),
+            ..
+        } => (base_url, user_email, api_token),
+        _ => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "Either source_id or base_url, user_email and api_token are required"
+                        .to_string(),
+                }),
+            ))
+        }
+    };
+
+    info!("Testing connection to Atlassian: {}", config.0);
 
-    let sync_manager = state.sync_manager.lock().await;
     match sync_manager.test_connection(&config).await {
         Ok((jira_projects, confluence_spaces)) => {
             info!(
@@ -150,6 +200,20 @@ async fn test_connection(
                 jira_projects.len(),
                 confluence_spaces.len()
             );
+            let scopes = jira_projects
+                .iter()
+                .map(|(key, name)| ("jira_project", key, name))
+                .chain(
+                    confluence_spaces
+                        .iter()
+                        .map(|(key, name)| ("confluence_space", key, name)),
+                )
+                .map(|(kind, key, name)| ConnectionScope {
+                    kind,
+                    id: key.clone(),
+                    name: name.clone(),
+                })
+                .collect();
             Ok(Json(TestConnectionResponse {
                 success: true,
                 message: format!(
@@ -157,8 +221,9 @@ async fn test_connection(
                     jira_projects.len(),
                     confluence_spaces.len()
                 ),
-                jira_projects,
-                confluence_spaces,
+                jira_projects: jira_projects.into_iter().map(|(key, _)| key).collect(),
+                confluence_spaces: confluence_spaces.into_iter().map(|(key, _)| key).collect(),
+                scopes,
             }))
         }
         Err(e) => {
@@ -168,6 +233,7 @@ async fn test_connection(
                 message: format!("Connection failed: {}", e),
                 jira_projects: vec![],
                 confluence_spaces: vec![],
+                scopes: vec![],
             }))
         }
     }
@@ -187,7 +187,11 @@ impl AuthManager {
         Ok(())
     }
 
-    pub async fn test_jira_permissions(&self, creds: &AtlassianCredentials) -> Result<Vec<String>> {
+    /// Keys and names of the JIRA projects the credentials can read.
+    pub async fn test_jira_permissions(
+        &self,
+        creds: &AtlassianCredentials,
+    ) -> Result<Vec<(String, String)>> {
         let auth_header = creds.get_basic_auth_header();
         let url = format!("{}/rest/api/3/project", creds.base_url);
 
@@ -210,19 +214,18 @@ impl AuthManager {
         }
 
         let projects: Vec<serde_json::Value> = response.json().await?;
-        let project_keys: Vec<String> = projects
-            .iter()
-            .filter_map(|p| p.get("key").and_then(|k| k.as_str().map(String::from)))
-            .collect();
+        let project_keys: Vec<(String, String)> =
+            projects.iter().filter_map(key_and_name).collect();
 
         debug!("Found {} accessible JIRA projects", project_keys.len());
         Ok(project_keys)
     }
 
+    /// Keys and names of the Confluence spaces the credentials can read.
     pub async fn test_confluence_permissions(
         &self,
         creds: &AtlassianCredentials,
-    ) -> Result<Vec<String>> {
+    ) -> Result<Vec<(String, String)>> {
         let auth_header = creds.get_basic_auth_header();
         let url = format!("{}/wiki/rest/api/space?limit=100", creds.base_url);
 
@@ -251,12 +254,19 @@ impl AuthManager {
             .and_then(|r| r.as_array())
             .unwrap_or(&empty_vec);
 
-        let space_keys: Vec<String> = spaces
-            .iter()
-            .filter_map(|s| s.get("key").and_then(|k| k.as_str().map(String::from)))
-            .collect();
+        let space_keys: Vec<(String, String)> = spaces.iter().filter_map(key_and_name).collect();
 
         debug!("Found {} accessible Confluence spaces", space_keys.len());
         Ok(space_keys)
     }
 }
+
+/// The `key` of a JIRA project or Confluence space, and its `name`, the key when it has none.
+fn key_and_name(value: &serde_json::Value) -> Option<(String, String)> {
+    let key = value.get("key")?.as_str()?.to_string();
+    let name = value
+        .get("name")
+        .and_then(|name| name.as_str())
+        .map_or_else(|| key.clone(), String::from);
+    Some((key, name))
+}
@@ -9,8 +9,8 @@ use tracing::{debug, warn};
 
 use crate::auth::AtlassianCredentials;
 use crate::models::{
-    ConfluenceGetPagesResponse, ConfluenceGetSpacesResponse, ConfluencePage, ConfluenceSpace,
-    JiraField, JiraIssue, JiraSearchResponse,
+    ConfluenceGetPagesResponse, ConfluenceGetSpacesResponse, ConfluencePage,
+    ConfluenceReadRestrictions, ConfluenceSpace, JiraField, JiraIssue, JiraSearchResponse,
 };
 
 pub struct AtlassianClient {
@@ -203,6 +203,29 @@ impl AtlassianClient {
         .await
     }
 
+    pub async fn get_confluence_page_read_restrictions(
+        &self,
+        creds: &AtlassianCredentials,
+        page_id: &str,
+    ) -> Result<ConfluenceReadRestrictions> {
+        let auth_header = creds.get_basic_auth_header();
+        let url = format!(
+            "{}/wiki/rest/api/content/{}/restriction/byOperation/read?expand=restrictions.user,restrictions.group",
+            creds.base_url, page_id
+        );
+
+        debug!("Fetching read restrictions of Confluence page: {}", page_id);
+
+        let client = self.client.clone();
+        self.make_request(move || {
+            client
+                .get(&url)
+                .header("Authorization", &auth_header)
+                .header("Accept", "application/json")
+        })
+        .await
+    }
+
     pub async fn get_confluence_pages_updated_since(
         &self,
         creds: &AtlassianCredentials,
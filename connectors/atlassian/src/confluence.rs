@@ -1,7 +1,7 @@
 use anyhow::{anyhow, Result};
 use futures::stream::StreamExt;
 use redis::Client as RedisClient;
-use shared::models::{ConnectorEvent, SyncType};
+use shared::models::{ConnectorEvent, DocumentPermissions, SyncType};
 use std::sync::atomic::{AtomicBool, Ordering};
 use tracing::{debug, error, info, warn};
 
@@ -70,6 +70,7 @@ impl ConfluenceProcessor {
             "Starting {} Confluence sync for source: {} (sync_run_id: {})",
             sync_type_str, source_id, sync_run_id
         );
+        self.sync_state.migrate_legacy_keys(source_id).await?;
 
         let spaces = self.get_accessible_spaces(creds).await?;
         let mut total_pages_processed = 0;
@@ -144,7 +145,7 @@ impl ConfluenceProcessor {
 
             if pages_batch.len() >= 100 {
                 let count = self
-                    .process_pages(pages_batch, source_id, sync_run_id, &creds.base_url)
+                    .process_pages(pages_batch, source_id, sync_run_id, creds)
                     .await?;
                 total_pages += count;
                 pages_batch = Vec::with_capacity(100);
@@ -153,7 +154,7 @@ impl ConfluenceProcessor {
 
         if !pages_batch.is_empty() {
             let count = self
-                .process_pages(pages_batch, source_id, sync_run_id, &creds.base_url)
+                .process_pages(pages_batch, source_id, sync_run_id, creds)
                 .await?;
             total_pages += count;
         }
@@ -182,7 +183,7 @@ impl ConfluenceProcessor {
         pages: Vec<ConfluencePage>,
         source_id: &str,
         sync_run_id: &str,
-        base_url: &str,
+        creds: &AtlassianCredentials,
     ) -> Result<u32> {
         let mut count = 0;
 
@@ -228,7 +229,25 @@ impl ConfluenceProcessor {
                 }
             };
 
+            // Restrictions can change without a new page version
+            let permissions = match self
+                .client
+                .get_confluence_page_read_restrictions(creds, &page.id)
+                .await
+            {
+                Ok(restrictions) => restrictions.to_permissions(),
+                Err(e) => {
+                    error!(
+                        "Failed to get restrictions for Confluence page {}: {}",
+                        page.title, e
+                    );
+                    continue;
+                }
+            };
+
             if !should_process {
+                self.sync_page_permissions(&page, source_id, sync_run_id, permissions)
+                    .await;
                 continue;
             }
 
@@ -261,8 +280,9 @@ impl ConfluenceProcessor {
             let event = page.to_connector_event(
                 sync_run_id.to_string(),
                 source_id.to_string(),
-                base_url,
+                &creds.base_url,
                 content_id,
+                permissions.clone(),
             );
 
             // Emit event via SDK
@@ -288,11 +308,79 @@ impl ConfluenceProcessor {
             {
                 warn!("Failed to update sync state for page {}: {}", page.id, e);
             }
+            if let Err(e) = self
+                .sync_state
+                .set_confluence_page_permissions(source_id, &page.space_id, &page.id, &permissions)
+                .await
+            {
+                warn!(
+                    "Failed to update permissions state for page {}: {}",
+                    page.id, e
+                );
+            }
         }
 
         Ok(count)
     }
 
+    /// Emit a permissions-only update for an unchanged page whose restrictions differ from
+    /// what was last emitted. Failures are logged, the next sync retries.
+    async fn sync_page_permissions(
+        &self,
+        page: &ConfluencePage,
+        source_id: &str,
+        sync_run_id: &str,
+        permissions: DocumentPermissions,
+    ) {
+        match self
+            .sync_state
+            .get_confluence_page_permissions(source_id, &page.space_id, &page.id)
+            .await
+        {
+            Ok(Some(previous)) if previous == permissions => return,
+            Ok(_) => {}
+            Err(e) => {
+                warn!(
+                    "Failed to get permissions state for page {}: {}",
+                    page.id, e
+                );
+                return;
+            }
+        }
+
+        debug!(
+            "Restrictions of page {} changed, updating permissions",
+            page.title
+        );
+        let event = page.to_permissions_updated_event(
+            sync_run_id.to_string(),
+            source_id.to_string(),
+            permissions.clone(),
+        );
+        if let Err(e) = self
+            .sdk_client
+            .emit_event(sync_run_id, source_id, event)
+            .await
+        {
+            error!(
+                "Failed to emit permissions update for Confluence page {}: {}",
+                page.title, e
+            );
+            return;
+        }
+
+        if let Err(e) = self
+            .sync_state
+            .set_confluence_page_permissions(source_id, &page.space_id, &page.id, &permissions)
+            .await
+        {
+            warn!(
+                "Failed to update permissions state for page {}: {}",
+                page.id, e
+            );
+        }
+    }
+
     pub async fn sync_single_page(
         &mut self,
         creds: &AtlassianCredentials,
@@ -328,6 +416,12 @@ impl ConfluenceProcessor {
             return Ok(());
         }
 
+        let permissions = self
+            .client
+            .get_confluence_page_read_restrictions(creds, page_id)
+            .await?
+            .to_permissions();
+
         // Create sync run via SDK
         let sync_run_id = self
             .sdk_client
@@ -353,6 +447,7 @@ impl ConfluenceProcessor {
                 source_id.to_string(),
                 &creds.base_url,
                 content_id,
+                permissions,
             );
             self.sdk_client
                 .emit_event(&sync_run_id, source_id, event)
@@ -15,6 +15,7 @@ mod models;
 mod sync;
 
 use config::AtlassianConnectorConfig;
+use shared::shutdown::Shutdown;
 use shared::SdkClient;
 
 use api::{create_router, ApiState};
@@ -35,7 +36,10 @@ async fn main() -> Result<()> {
 
     let sdk_client = SdkClient::from_env()?;
 
-    let sync_manager = Arc::new(Mutex::new(SyncManager::new(redis_client, sdk_client)));
+    let sync_manager = Arc::new(Mutex::new(SyncManager::new(
+        redis_client,
+        sdk_client.clone(),
+    )));
 
     let api_state = ApiState {
         sync_manager: Arc::clone(&sync_manager),
@@ -50,9 +54,12 @@ async fn main() -> Result<()> {
     info!("HTTP server listening on {}", addr);
 
     // Run HTTP server (connector-manager handles scheduling)
-    if let Err(e) = axum::serve(listener, app).await {
+    if let Err(e) = shared::tls::serve(listener, app, Shutdown::on_signal()).await {
         error!("HTTP server stopped: {:?}", e);
     }
 
+    // Syncs cut short by the shutdown are resumed once the connector is back
+    sdk_client.pause_active_syncs().await;
+
     Ok(())
 }
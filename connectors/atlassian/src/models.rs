@@ -2,9 +2,12 @@ use chrono::DateTime;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value as JsonValue};
 use shared::models::{ConnectorEvent, DocumentAttributes, DocumentMetadata, DocumentPermissions};
+use shared::utils::to_offset_datetime;
 use std::collections::HashMap;
 use time::OffsetDateTime;
 
+pub use shared::models::ActionRequest;
+
 // ============================================================================
 // Connector Protocol Models
 // ============================================================================
@@ -58,13 +61,6 @@ pub struct CancelResponse {
     pub status: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ActionRequest {
-    pub action: String,
-    pub params: JsonValue,
-    pub credentials: JsonValue,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActionResponse {
     pub status: String,
@@ -221,6 +217,68 @@ pub struct ConfluenceResponseLinks {
     pub next: Option<String>,
 }
 
+/// Read restrictions set on a Confluence page, from the content restriction API.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfluenceReadRestrictions {
+    #[serde(default)]
+    pub restrictions: ConfluenceRestrictionSubjects,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfluenceRestrictionSubjects {
+    #[serde(default)]
+    pub user: ConfluenceRestrictionResults<ConfluenceUser>,
+    #[serde(default)]
+    pub group: ConfluenceRestrictionResults<ConfluenceGroup>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
+pub struct ConfluenceRestrictionResults<T> {
+    #[serde(default)]
+    pub results: Vec<T>,
+}
+
+impl<T> Default for ConfluenceRestrictionResults<T> {
+    fn default() -> Self {
+        Self {
+            results: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfluenceGroup {
+    pub name: String,
+}
+
+impl ConfluenceReadRestrictions {
+    /// Unrestricted pages are visible to everyone with access to the site. Restricted users
+    /// whose email is hidden by their privacy settings cannot be mapped and are left out.
+    pub fn to_permissions(&self) -> DocumentPermissions {
+        let users = &self.restrictions.user.results;
+        let groups = &self.restrictions.group.results;
+        if users.is_empty() && groups.is_empty() {
+            return DocumentPermissions {
+                public: true,
+                users: vec![],
+                groups: vec![],
+            };
+        }
+
+        let mut users: Vec<String> = users.iter().filter_map(|u| u.email.clone()).collect();
+        users.sort();
+        let mut groups: Vec<String> = groups.iter().map(|g| g.name.clone()).collect();
+        groups.sort();
+
+        DocumentPermissions {
+            public: false,
+            users,
+            groups,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JiraIssue {
     pub id: String,
@@ -470,8 +528,9 @@ impl ConfluencePage {
         source_id: String,
         base_url: &str,
         content_id: String,
+        permissions: DocumentPermissions,
     ) -> ConnectorEvent {
-        let document_id = format!("confluence_page_{}_{}", self.space_id, self.id);
+        let document_id = self.document_id();
         let url = format!("{}/wiki{}", base_url, self.links.webui.clone());
         let path = self.title.clone();
 
@@ -494,12 +553,8 @@ impl ConfluencePage {
             url: Some(url),
             path: Some(path),
             extra: Some(extra),
-        };
-
-        let permissions = DocumentPermissions {
-            public: true,
-            users: vec![],
-            groups: vec![],
+            thumbnail: None,
+            parent: None,
         };
 
         let attributes = self.to_attributes().into_attributes();
@@ -514,6 +569,26 @@ impl ConfluencePage {
             attributes: Some(attributes),
         }
     }
+
+    pub fn document_id(&self) -> String {
+        format!("confluence_page_{}_{}", self.space_id, self.id)
+    }
+
+    /// Event carrying only the page's current restrictions, for pages whose content is
+    /// unchanged.
+    pub fn to_permissions_updated_event(
+        &self,
+        sync_run_id: String,
+        source_id: String,
+        permissions: DocumentPermissions,
+    ) -> ConnectorEvent {
+        ConnectorEvent::PermissionsUpdated {
+            sync_run_id,
+            source_id,
+            document_id: self.document_id(),
+            permissions,
+        }
+    }
 }
 
 impl JiraIssue {
@@ -645,17 +720,11 @@ impl JiraIssue {
 
         let created_at = DateTime::parse_from_rfc3339(&self.fields.created)
             .ok()
-            .map(|dt| {
-                OffsetDateTime::from_unix_timestamp(dt.timestamp())
-                    .unwrap_or(OffsetDateTime::UNIX_EPOCH)
-            });
+            .and_then(|dt| to_offset_datetime(&dt));
 
         let updated_at = DateTime::parse_from_rfc3339(&self.fields.updated)
             .ok()
-            .map(|dt| {
-                OffsetDateTime::from_unix_timestamp(dt.timestamp())
-                    .unwrap_or(OffsetDateTime::UNIX_EPOCH)
-            });
+            .and_then(|dt| to_offset_datetime(&dt));
 
         // Display metadata (not for filtering)
         let mut extra = HashMap::new();
@@ -690,6 +759,8 @@ impl JiraIssue {
             url,
             path: Some(format!("{}/{}", self.fields.project.name, self.key)),
             extra: Some(extra),
+            thumbnail: None,
+            parent: None,
         };
 
         let permissions = DocumentPermissions {
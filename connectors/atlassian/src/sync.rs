@@ -2,7 +2,9 @@ use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use redis::{AsyncCommands, Client as RedisClient};
-use shared::models::{ServiceCredentials, ServiceProvider, SourceType, SyncRequest};
+use shared::models::{
+    DocumentPermissions, ServiceCredentials, ServiceProvider, SourceType, SyncRequest,
+};
 use std::collections::HashSet;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -11,6 +13,7 @@ use tracing::{debug, error, info};
 use crate::auth::{AtlassianCredentials, AuthManager};
 use crate::confluence::ConfluenceProcessor;
 use crate::jira::JiraProcessor;
+use shared::cache::{keys, ttl};
 use shared::SdkClient;
 
 pub struct SyncManager {
@@ -21,6 +24,10 @@ pub struct SyncManager {
     active_syncs: DashMap<String, Arc<AtomicBool>>,
 }
 
+/// Names of the keys recording when each Confluence space and Jira project last synced.
+const CONFLUENCE_SPACE_PREFIX: &str = "atlassian:confluence:space:";
+const JIRA_PROJECT_PREFIX: &str = "atlassian:jira:project:";
+
 pub struct SyncState {
     redis_client: RedisClient,
 }
@@ -30,35 +37,48 @@ impl SyncState {
         Self { redis_client }
     }
 
-    pub fn get_confluence_sync_key(&self, source_id: &str, space_key: &str) -> String {
-        format!("atlassian:confluence:sync:{}:{}", source_id, space_key)
-    }
-
-    pub fn get_jira_sync_key(&self, source_id: &str, project_key: &str) -> String {
-        format!("atlassian:jira:sync:{}:{}", source_id, project_key)
+    /// Move the source's sync state written before keys were prefixed to its keys, so the first
+    /// sync after an upgrade does not see every space and page as new.
+    pub async fn migrate_legacy_keys(&self, source_id: &str) -> Result<()> {
+        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
+        let moved = shared::cache::migrate_legacy_source_keys(
+            &mut conn,
+            source_id,
+            &[
+                ("atlassian:confluence:sync:", CONFLUENCE_SPACE_PREFIX),
+                ("atlassian:jira:sync:", JIRA_PROJECT_PREFIX),
+                ("atlassian:confluence:page:", "atlassian:confluence:page:"),
+                (
+                    "atlassian:confluence:permissions:",
+                    "atlassian:confluence:permissions:",
+                ),
+            ],
+        )
+        .await?;
+        if moved > 0 {
+            info!(
+                "Migrated {} legacy sync state keys of source {}",
+                moved, source_id
+            );
+        }
+        Ok(())
     }
 
-    pub fn get_test_confluence_sync_key(&self, source_id: &str, space_key: &str) -> String {
-        format!("atlassian:confluence:sync:test:{}:{}", source_id, space_key)
+    pub fn get_confluence_sync_key(&self, source_id: &str, space_key: &str) -> String {
+        keys()
+            .source(source_id)
+            .key(format_args!("{}{}", CONFLUENCE_SPACE_PREFIX, space_key))
     }
 
-    pub fn get_test_jira_sync_key(&self, source_id: &str, project_key: &str) -> String {
-        format!("atlassian:jira:sync:test:{}:{}", source_id, project_key)
+    pub fn get_jira_sync_key(&self, source_id: &str, project_key: &str) -> String {
+        keys()
+            .source(source_id)
+            .key(format_args!("{}{}", JIRA_PROJECT_PREFIX, project_key))
     }
 
-    pub async fn get_confluence_last_sync(
-        &self,
-        source_id: &str,
-        space_key: &str,
-    ) -> Result<Option<DateTime<Utc>>> {
+    async fn get_last_sync(&self, key: &str) -> Result<Option<DateTime<Utc>>> {
         let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
-        let key = if cfg!(test) {
-            self.get_test_confluence_sync_key(source_id, space_key)
-        } else {
-            self.get_confluence_sync_key(source_id, space_key)
-        };
-
-        let result: Option<String> = conn.get(&key).await?;
+        let result: Option<String> = conn.get(key).await?;
         if let Some(timestamp_str) = result {
             if let Ok(timestamp) = timestamp_str.parse::<i64>() {
                 if let Some(dt) = DateTime::from_timestamp(timestamp, 0) {
@@ -69,22 +89,46 @@ impl SyncState {
         Ok(None)
     }
 
+    async fn set_last_sync(&self, key: &str, sync_time: DateTime<Utc>) -> Result<()> {
+        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
+        let _: () = conn
+            .set_ex(key, sync_time.timestamp(), ttl::SYNC_STATE)
+            .await?;
+        Ok(())
+    }
+
+    /// The names that follow `name_prefix` in the source's keys starting with it.
+    async fn synced_names(&self, source_id: &str, name_prefix: &str) -> Result<HashSet<String>> {
+        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
+        let source_keys = keys().source(source_id);
+        let synced = shared::cache::scan(&mut conn, &source_keys.pattern(name_prefix)).await?;
+        Ok(synced
+            .iter()
+            .filter_map(|key| source_keys.name(key)?.strip_prefix(name_prefix))
+            .map(|name| name.to_string())
+            .collect())
+    }
+
+    pub async fn get_confluence_last_sync(
+        &self,
+        source_id: &str,
+        space_key: &str,
+    ) -> Result<Option<DateTime<Utc>>> {
+        self.get_last_sync(&self.get_confluence_sync_key(source_id, space_key))
+            .await
+    }
+
     pub async fn set_confluence_last_sync(
         &self,
         source_id: &str,
         space_key: &str,
         sync_time: DateTime<Utc>,
     ) -> Result<()> {
-        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
-        let key = if cfg!(test) {
-            self.get_test_confluence_sync_key(source_id, space_key)
-        } else {
-            self.get_confluence_sync_key(source_id, space_key)
-        };
-
-        let timestamp = sync_time.timestamp();
-        let _: () = conn.set_ex(&key, timestamp, 30 * 24 * 60 * 60).await?; // 30 days expiry
-        Ok(())
+        self.set_last_sync(
+            &self.get_confluence_sync_key(source_id, space_key),
+            sync_time,
+        )
+        .await
     }
 
     pub async fn get_jira_last_sync(
@@ -92,22 +136,8 @@ impl SyncState {
         source_id: &str,
         project_key: &str,
     ) -> Result<Option<DateTime<Utc>>> {
-        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
-        let key = if cfg!(test) {
-            self.get_test_jira_sync_key(source_id, project_key)
-        } else {
-            self.get_jira_sync_key(source_id, project_key)
-        };
-
-        let result: Option<String> = conn.get(&key).await?;
-        if let Some(timestamp_str) = result {
-            if let Ok(timestamp) = timestamp_str.parse::<i64>() {
-                if let Some(dt) = DateTime::from_timestamp(timestamp, 0) {
-                    return Ok(Some(dt));
-                }
-            }
-        }
-        Ok(None)
+        self.get_last_sync(&self.get_jira_sync_key(source_id, project_key))
+            .await
     }
 
     pub async fn set_jira_last_sync(
@@ -116,65 +146,19 @@ impl SyncState {
         project_key: &str,
         sync_time: DateTime<Utc>,
     ) -> Result<()> {
-        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
-        let key = if cfg!(test) {
-            self.get_test_jira_sync_key(source_id, project_key)
-        } else {
-            self.get_jira_sync_key(source_id, project_key)
-        };
-
-        let timestamp = sync_time.timestamp();
-        let _: () = conn.set_ex(&key, timestamp, 30 * 24 * 60 * 60).await?; // 30 days expiry
-        Ok(())
+        self.set_last_sync(&self.get_jira_sync_key(source_id, project_key), sync_time)
+            .await
     }
 
     pub async fn get_all_synced_confluence_spaces(
         &self,
         source_id: &str,
     ) -> Result<HashSet<String>> {
-        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
-        let pattern = if cfg!(test) {
-            format!("atlassian:confluence:sync:test:{}:*", source_id)
-        } else {
-            format!("atlassian:confluence:sync:{}:*", source_id)
-        };
-
-        let keys: Vec<String> = conn.keys(&pattern).await?;
-        let prefix = if cfg!(test) {
-            format!("atlassian:confluence:sync:test:{}:", source_id)
-        } else {
-            format!("atlassian:confluence:sync:{}:", source_id)
-        };
-
-        let space_keys: HashSet<String> = keys
-            .into_iter()
-            .filter_map(|key| key.strip_prefix(&prefix).map(|s| s.to_string()))
-            .collect();
-
-        Ok(space_keys)
+        self.synced_names(source_id, CONFLUENCE_SPACE_PREFIX).await
     }
 
     pub async fn get_all_synced_jira_projects(&self, source_id: &str) -> Result<HashSet<String>> {
-        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
-        let pattern = if cfg!(test) {
-            format!("atlassian:jira:sync:test:{}:*", source_id)
-        } else {
-            format!("atlassian:jira:sync:{}:*", source_id)
-        };
-
-        let keys: Vec<String> = conn.keys(&pattern).await?;
-        let prefix = if cfg!(test) {
-            format!("atlassian:jira:sync:test:{}:", source_id)
-        } else {
-            format!("atlassian:jira:sync:{}:", source_id)
-        };
-
-        let project_keys: HashSet<String> = keys
-            .into_iter()
-            .filter_map(|key| key.strip_prefix(&prefix).map(|s| s.to_string()))
-            .collect();
-
-        Ok(project_keys)
+        self.synced_names(source_id, JIRA_PROJECT_PREFIX).await
     }
 
     pub fn get_confluence_page_sync_key(
@@ -183,17 +167,10 @@ impl SyncState {
         space_id: &str,
         page_id: &str,
     ) -> String {
-        if cfg!(test) {
-            format!(
-                "atlassian:confluence:page:test:{}:{}:{}",
-                source_id, space_id, page_id
-            )
-        } else {
-            format!(
-                "atlassian:confluence:page:{}:{}:{}",
-                source_id, space_id, page_id
-            )
-        }
+        keys().source(source_id).key(format_args!(
+            "atlassian:confluence:page:{}:{}",
+            space_id, page_id
+        ))
     }
 
     pub async fn get_confluence_page_version(
@@ -224,7 +201,49 @@ impl SyncState {
         let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
         let key = self.get_confluence_page_sync_key(source_id, space_id, page_id);
 
-        let _: () = conn.set_ex(&key, version, 30 * 24 * 60 * 60).await?; // 30 days expiry
+        let _: () = conn.set_ex(&key, version, ttl::SYNC_STATE).await?;
+        Ok(())
+    }
+
+    pub fn get_confluence_page_permissions_key(
+        &self,
+        source_id: &str,
+        space_id: &str,
+        page_id: &str,
+    ) -> String {
+        keys().source(source_id).key(format_args!(
+            "atlassian:confluence:permissions:{}:{}",
+            space_id, page_id
+        ))
+    }
+
+    /// Permissions last emitted for a page.
+    pub async fn get_confluence_page_permissions(
+        &self,
+        source_id: &str,
+        space_id: &str,
+        page_id: &str,
+    ) -> Result<Option<DocumentPermissions>> {
+        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
+        let key = self.get_confluence_page_permissions_key(source_id, space_id, page_id);
+
+        let result: Option<String> = conn.get(&key).await?;
+        Ok(result.and_then(|permissions| serde_json::from_str(&permissions).ok()))
+    }
+
+    pub async fn set_confluence_page_permissions(
+        &self,
+        source_id: &str,
+        space_id: &str,
+        page_id: &str,
+        permissions: &DocumentPermissions,
+    ) -> Result<()> {
+        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
+        let key = self.get_confluence_page_permissions_key(source_id, space_id, page_id);
+
+        let _: () = conn
+            .set_ex(&key, serde_json::to_string(permissions)?, ttl::SYNC_STATE)
+            .await?;
         Ok(())
     }
 }
@@ -261,6 +280,7 @@ impl SyncManager {
             "Starting sync for source: {} (sync_run_id: {})",
             source_id, sync_run_id
         );
+        self.sdk_client.start_sync(&request);
 
         // Fetch source via SDK
         let source = self
@@ -493,10 +513,19 @@ impl SyncManager {
             .await
     }
 
+    /// Connection settings of an existing source, as passed to [`Self::test_connection`].
+    pub async fn source_connection_config(
+        &self,
+        source_id: &str,
+    ) -> Result<(String, String, String)> {
+        let creds = self.get_service_credentials(source_id).await?;
+        self.extract_atlassian_credentials(&creds)
+    }
+
     pub async fn test_connection(
         &self,
         config: &(String, String, String),
-    ) -> Result<(Vec<String>, Vec<String>)> {
+    ) -> Result<(Vec<(String, String)>, Vec<(String, String)>)> {
         let (base_url, user_email, api_token) = config;
         let credentials = self
             .get_or_validate_credentials(base_url, user_email, api_token)
@@ -108,6 +108,8 @@ async fn test_event_queue_integration() -> Result<()> {
             url: Some("https://test.atlassian.net/wiki/spaces/TEST/pages/123".to_string()),
             parent_id: None,
             extra: None,
+            thumbnail: None,
+            parent: None,
         },
         permissions: shared::models::DocumentPermissions {
             public: false,
@@ -134,6 +136,8 @@ async fn test_event_queue_integration() -> Result<()> {
             url: Some("https://test.atlassian.net/browse/ISSUE-123".to_string()),
             parent_id: None,
             extra: None,
+            thumbnail: None,
+            parent: None,
         },
         permissions: shared::models::DocumentPermissions {
             public: false,
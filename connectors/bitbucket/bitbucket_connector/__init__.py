@@ -0,0 +1,6 @@
+"""Bitbucket connector for Omni."""
+
+from .connector import BitbucketConnector
+
+__version__ = "1.0.0"
+__all__ = ["BitbucketConnector"]
@@ -0,0 +1,234 @@
+"""Thin async wrapper over the Bitbucket REST API with retry logic."""
+
+import asyncio
+import logging
+from collections.abc import AsyncIterator
+from functools import wraps
+from typing import Any
+
+import httpx
+
+from .config import (
+    BITBUCKET_API_URL,
+    ITEMS_PER_PAGE,
+    PULL_REQUEST_STATES,
+    PULL_REQUESTS_PER_PAGE,
+    README_NAMES,
+    READ_PERMISSIONS,
+)
+
+logger = logging.getLogger(__name__)
+
+
+class BitbucketError(Exception):
+    """Base exception for Bitbucket API errors."""
+
+    def __init__(self, message: str, status_code: int | None = None):
+        super().__init__(message)
+        self.status_code = status_code
+
+
+class AuthenticationError(BitbucketError):
+    """Invalid credentials or expired token (401)."""
+
+    pass
+
+
+class ForbiddenError(BitbucketError):
+    """The credentials cannot access a resource, or lack a scope (403)."""
+
+    pass
+
+
+class NotFoundError(BitbucketError):
+    """Workspace, repository or file not found, or not visible to the credentials (404)."""
+
+    pass
+
+
+def with_retry(max_retries: int = 3, base_delay: float = 1.0):
+    """Decorator for retrying Bitbucket API calls with exponential backoff.
+
+    Handles:
+    - 429 Rate Limit: Wait for Retry-After header (unlimited retries)
+    - 5xx Server Error: Exponential backoff (limited retries)
+    - 401/403: Re-raise as AuthenticationError/ForbiddenError (non-retryable)
+    - 404: Re-raise as NotFoundError (non-retryable)
+    """
+
+    def decorator(func):
+        @wraps(func)
+        async def wrapper(*args, **kwargs):
+            last_exception = None
+            error_retries = 0
+
+            while True:
+                try:
+                    return await func(*args, **kwargs)
+                except httpx.HTTPStatusError as e:
+                    last_exception = e
+                    status = e.response.status_code
+
+                    if status == 401:
+                        raise AuthenticationError(
+                            "Invalid Bitbucket credentials", status_code=401
+                        ) from e
+
+                    if status == 403:
+                        raise ForbiddenError(
+                            f"Forbidden: {e.request.url.path}: {e.response.text}",
+                            status_code=403,
+                        ) from e
+
+                    if status == 404:
+                        raise NotFoundError(
+                            f"Not found: {e.request.url.path}", status_code=404
+                        ) from e
+
+                    if status == 429:
+                        retry_after = int(e.response.headers.get("Retry-After", "10"))
+                        logger.warning("Rate limited. Waiting %ds", retry_after)
+                        await asyncio.sleep(retry_after)
+                        continue
+
+                    if status >= 500:
+                        error_retries += 1
+                        if error_retries > max_retries:
+                            break
+                        delay = base_delay * (2 ** (error_retries - 1))
+                        logger.warning(
+                            "Server error %d. Retrying in %.1fs (%d/%d)",
+                            status,
+                            delay,
+                            error_retries,
+                            max_retries,
+                        )
+                        await asyncio.sleep(delay)
+                        continue
+
+                    raise BitbucketError(
+                        f"API error {status}: {e.response.text}",
+                        status_code=status,
+                    ) from e
+
+            raise BitbucketError(f"Max retries exceeded: {last_exception}") from last_exception
+
+        return wrapper
+
+    return decorator
+
+
+class BitbucketClient:
+    """Async client for the Bitbucket Cloud REST API.
+
+    Authenticates with a username and an app password or API token, or with a workspace,
+    project or repository access token sent as a bearer token.
+    """
+
+    def __init__(
+        self,
+        username: str | None = None,
+        password: str | None = None,
+        token: str | None = None,
+        base_url: str | None = None,
+        http_client: httpx.AsyncClient | None = None,
+    ):
+        self._client = http_client or httpx.AsyncClient(
+            base_url=(base_url or BITBUCKET_API_URL).rstrip("/"),
+            auth=(username, password) if username and password else None,
+            timeout=httpx.Timeout(60.0, connect=10.0),
+            follow_redirects=True,
+        )
+        if token:
+            self._client.headers["Authorization"] = f"Bearer {token}"
+
+    async def close(self) -> None:
+        await self._client.aclose()
+
+    @with_retry(max_retries=3)
+    async def get(self, path: str, params: dict[str, Any] | None = None) -> dict[str, Any]:
+        response = await self._client.get(path, params=params)
+        response.raise_for_status()
+        return response.json()
+
+    @with_retry(max_retries=3)
+    async def get_text(self, path: str) -> str:
+        response = await self._client.get(path)
+        response.raise_for_status()
+        return response.text
+
+    async def paginate(
+        self, path: str, params: dict[str, Any] | None = None
+    ) -> AsyncIterator[dict[str, Any]]:
+        """The values of every page of a listing, following its `next` links."""
+        page = await self.get(path, {"pagelen": ITEMS_PER_PAGE, **(params or {})})
+        while True:
+            for value in page.get("values", []):
+                yield value
+            if not page.get("next"):
+                return
+            # The next link is absolute and carries the query parameters
+            page = await self.get(page["next"])
+
+    async def get_user(self) -> dict[str, Any]:
+        """The user the credentials belong to."""
+        return await self.get("/user")
+
+    async def list_workspaces(self) -> AsyncIterator[str]:
+        """Slugs of the workspaces the user is a member of."""
+        async for membership in self.paginate("/user/permissions/workspaces"):
+            yield membership["workspace"]["slug"]
+
+    async def list_repos(self, workspace: str) -> AsyncIterator[dict[str, Any]]:
+        """Repositories of a workspace the credentials can see."""
+        async for repo in self.paginate(f"/repositories/{workspace}"):
+            yield repo
+
+    async def get_repo(self, workspace: str, slug: str) -> dict[str, Any]:
+        return await self.get(f"/repositories/{workspace}/{slug}")
+
+    async def get_readme(self, full_name: str, branch: str) -> str | None:
+        """The README at the root of a branch, if there is one."""
+        names = set()
+        async for entry in self.paginate(f"/repositories/{full_name}/src/{branch}/"):
+            if entry.get("type") == "commit_file":
+                names.add(entry["path"])
+        for name in README_NAMES:
+            if name in names:
+                return await self.get_text(f"/repositories/{full_name}/src/{branch}/{name}")
+        return None
+
+    async def list_pull_requests(
+        self, full_name: str, since: str | None = None
+    ) -> AsyncIterator[dict[str, Any]]:
+        """Pull requests in every state, least recently updated first."""
+        params: dict[str, Any] = {
+            "state": PULL_REQUEST_STATES,
+            "sort": "updated_on",
+            "pagelen": PULL_REQUESTS_PER_PAGE,
+        }
+        if since:
+            params["q"] = f"updated_on > {since}"
+        async for pr in self.paginate(f"/repositories/{full_name}/pullrequests", params):
+            yield pr
+
+    async def list_pr_comments(self, full_name: str, pr_id: int) -> list[dict[str, Any]]:
+        """Comments on a pull request, general and inline, without the deleted ones."""
+        path = f"/repositories/{full_name}/pullrequests/{pr_id}/comments"
+        return [
+            comment
+            async for comment in self.paginate(path, {"pagelen": PULL_REQUESTS_PER_PAGE})
+            if not comment.get("deleted")
+        ]
+
+    async def list_read_groups(self, path: str) -> list[str]:
+        """
+        Slugs of the groups with read access in a permissions config, repository or project.
+
+        Reading permissions requires admin access to the repository or project.
+        """
+        return [
+            grant["group"]["slug"]
+            async for grant in self.paginate(f"{path}/permissions-config/groups")
+            if grant.get("permission") in READ_PERMISSIONS
+        ]
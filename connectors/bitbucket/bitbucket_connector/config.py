@@ -0,0 +1,18 @@
+"""Configuration constants for Bitbucket connector."""
+
+BITBUCKET_API_URL = "https://api.bitbucket.org/2.0"
+
+MAX_CONTENT_LENGTH = 100_000
+ITEMS_PER_PAGE = 100
+# The pull request endpoints accept at most 50 items per page
+PULL_REQUESTS_PER_PAGE = 50
+CHECKPOINT_INTERVAL = 50
+
+# Pull requests in every state; the API only returns open ones by default
+PULL_REQUEST_STATES = ["OPEN", "MERGED", "DECLINED", "SUPERSEDED"]
+
+# File names taken as a repository's README, by preference
+README_NAMES = ["README.md", "README.rst", "README.txt", "README"]
+
+# Repository and project permissions that let a group read a repository
+READ_PERMISSIONS = {"read", "write", "create-repo", "admin"}
@@ -0,0 +1,261 @@
+"""Main BitbucketConnector class."""
+
+import logging
+from typing import Any
+
+from omni_connector import Connector, DocumentPermissions, SyncContext
+
+from .client import (
+    AuthenticationError,
+    BitbucketClient,
+    BitbucketError,
+    ForbiddenError,
+    NotFoundError,
+)
+from .config import CHECKPOINT_INTERVAL
+from .mappers import (
+    build_permissions,
+    generate_pr_content,
+    generate_repo_content,
+    map_pr_to_document,
+    map_repo_to_document,
+)
+
+logger = logging.getLogger(__name__)
+
+
+class BitbucketConnector(Connector):
+    """Bitbucket Cloud connector for Omni."""
+
+    @property
+    def name(self) -> str:
+        return "bitbucket"
+
+    @property
+    def version(self) -> str:
+        return "1.0.0"
+
+    @property
+    def sync_modes(self) -> list[str]:
+        return ["full", "incremental"]
+
+    async def sync(
+        self,
+        source_config: dict[str, Any],
+        credentials: dict[str, Any],
+        state: dict[str, Any] | None,
+        ctx: SyncContext,
+    ) -> None:
+        """
+        Sync repositories, with their README, and pull requests with their comments.
+
+        Each repository keeps the latest pull request update in the state; later syncs only
+        read the pull requests updated since, unless who can see the repository changed.
+
+        Args:
+            source_config: May set repos ("workspace/slug"), workspaces and include_forks;
+                every workspace of the user is synced when neither repos nor workspaces is set
+            credentials: 'username' and 'password' (app password or API token), or 'token'
+                (access token)
+            state: Previous sync state, {"repos": {full_name: {"prs_updated_at", "access"}}}
+            ctx: Sync context with emit(), complete(), etc.
+        """
+        username = credentials.get("username")
+        password = credentials.get("password")
+        token = credentials.get("token") or credentials.get("access_token")
+        if not token and not (username and password):
+            await ctx.fail("Missing username and password, or token, in credentials")
+            return
+
+        client = BitbucketClient(
+            username=username,
+            password=password,
+            token=token,
+            base_url=source_config.get("api_url"),
+        )
+        try:
+            try:
+                # Access tokens belong to no user, so check them against their repositories
+                if not token:
+                    user = await client.get_user()
+                    logger.info("Starting Bitbucket sync as '%s'", user.get("display_name"))
+                repos = await self._resolve_repos(client, source_config)
+            except (AuthenticationError, ForbiddenError) as e:
+                await ctx.fail(f"Authentication failed: {e}")
+                return
+            except BitbucketError as e:
+                await ctx.fail(f"Connection test failed: {e}")
+                return
+
+            sync = _Sync(client, (state or {}).get("repos", {}), ctx)
+            try:
+                for repo in repos:
+                    if ctx.is_cancelled():
+                        await ctx.fail("Cancelled by user")
+                        return
+                    await sync.sync_repo(repo)
+
+                if ctx.is_cancelled():
+                    await ctx.fail("Cancelled by user")
+                    return
+                await ctx.complete(new_state=sync.state())
+                logger.info(
+                    "Sync completed: %d scanned, %d emitted",
+                    ctx.documents_scanned,
+                    ctx.documents_emitted,
+                )
+            except AuthenticationError as e:
+                logger.error("Authentication error during sync: %s", e)
+                await ctx.fail(f"Authentication failed: {e}")
+            except Exception as e:
+                logger.exception("Sync failed with unexpected error")
+                await ctx.fail(str(e))
+        finally:
+            await client.close()
+
+    async def _resolve_repos(
+        self, client: BitbucketClient, source_config: dict[str, Any]
+    ) -> list[dict[str, Any]]:
+        """Determine which repos to sync based on source_config."""
+        repos: list[dict[str, Any]] = []
+        seen: set[str] = set()
+
+        def add(repo: dict[str, Any]) -> None:
+            if repo["full_name"] not in seen:
+                seen.add(repo["full_name"])
+                repos.append(repo)
+
+        explicit_repos = source_config.get("repos", [])
+        for repo_spec in explicit_repos:
+            parts = repo_spec.split("/", 1)
+            if len(parts) == 2:
+                try:
+                    add(await client.get_repo(parts[0], parts[1]))
+                except (ForbiddenError, NotFoundError) as e:
+                    logger.warning("Failed to fetch repo %s: %s", repo_spec, e)
+
+        workspaces = source_config.get("workspaces", [])
+        if not explicit_repos and not workspaces:
+            workspaces = [workspace async for workspace in client.list_workspaces()]
+        for workspace in workspaces:
+            async for repo in client.list_repos(workspace):
+                add(repo)
+
+        if not source_config.get("include_forks", False):
+            repos = [r for r in repos if not r.get("parent")]
+
+        logger.info("Resolved %d repositories to sync", len(repos))
+        return repos
+
+
+class _Sync:
+    """State of one sync run: the repositories synced so far and their watermarks."""
+
+    def __init__(self, client: BitbucketClient, previous: dict[str, Any], ctx: SyncContext):
+        self.client = client
+        self.ctx = ctx
+        self.previous = previous
+        self.repos: dict[str, Any] = {}
+        # Workspaces whose permissions cannot be read with these credentials
+        self._no_admin: set[str] = set()
+        self._project_groups: dict[str, list[str]] = {}
+        self._since_checkpoint = 0
+
+    def state(self) -> dict[str, Any]:
+        return {"repos": {**self.previous, **self.repos}}
+
+    async def sync_repo(self, repo: dict[str, Any]) -> None:
+        full_name = repo["full_name"]
+        self.repos[full_name] = dict(self.previous.get(full_name, {}))
+        try:
+            permissions = build_permissions(repo, await self._read_groups(repo))
+        except AuthenticationError:
+            raise
+        except BitbucketError as e:
+            logger.error("Error fetching the permissions of %s: %s", full_name, e)
+            await self.ctx.emit_error(f"bitbucket:repo:{full_name}", str(e))
+            return
+
+        # Pull requests are only read when updated, so read them all again on a new access
+        access = "public" if permissions.public else permissions.groups
+        if self.repos[full_name].get("access") != access:
+            self.repos[full_name] = {"access": access}
+
+        await self.ctx.increment_scanned()
+        try:
+            branch = (repo.get("mainbranch") or {}).get("name")
+            readme = await self.client.get_readme(full_name, branch) if branch else None
+            content = generate_repo_content(repo, readme)
+            content_id = await self.ctx.content_storage.save(content, "text/plain")
+            await self.ctx.emit(map_repo_to_document(repo, content_id, permissions))
+            await self._checkpoint()
+        except AuthenticationError:
+            raise
+        except Exception as e:
+            eid = f"bitbucket:repo:{full_name}"
+            logger.warning("Error processing %s: %s", eid, e)
+            await self.ctx.emit_error(eid, str(e))
+
+        await self._sync_pull_requests(full_name, permissions)
+
+    async def _sync_pull_requests(self, full_name: str, permissions: DocumentPermissions) -> None:
+        entry = self.repos[full_name]
+        try:
+            async for pr in self.client.list_pull_requests(
+                full_name, since=entry.get("prs_updated_at")
+            ):
+                if self.ctx.is_cancelled():
+                    return
+                await self.ctx.increment_scanned()
+                try:
+                    comments = await self.client.list_pr_comments(full_name, pr["id"])
+                    content = generate_pr_content(pr, comments)
+                    content_id = await self.ctx.content_storage.save(content, "text/plain")
+                    await self.ctx.emit(
+                        map_pr_to_document(pr, content_id, full_name, permissions)
+                    )
+                except AuthenticationError:
+                    raise
+                except Exception as e:
+                    eid = f"bitbucket:pr:{full_name}#{pr['id']}"
+                    logger.warning("Error processing %s: %s", eid, e)
+                    await self.ctx.emit_error(eid, str(e))
+                # Pull requests come least recently updated first
+                if pr.get("updated_on"):
+                    entry["prs_updated_at"] = pr["updated_on"]
+                await self._checkpoint()
+        except AuthenticationError:
+            raise
+        except BitbucketError as e:
+            logger.error("Error fetching PRs for %s: %s", full_name, e)
+            await self.ctx.emit_error(f"bitbucket:pr:{full_name}:*", str(e))
+
+    async def _read_groups(self, repo: dict[str, Any]) -> list[str]:
+        """Workspace groups granted read access to a private repository or its project."""
+        full_name = repo["full_name"]
+        workspace = full_name.split("/", 1)[0]
+        if not repo.get("is_private") or workspace in self._no_admin:
+            return []
+        try:
+            groups = await self.client.list_read_groups(f"/repositories/{full_name}")
+            if project_key := (repo.get("project") or {}).get("key"):
+                path = f"/workspaces/{workspace}/projects/{project_key}"
+                if path not in self._project_groups:
+                    self._project_groups[path] = await self.client.list_read_groups(path)
+                groups = groups + self._project_groups[path]
+            return groups
+        except (ForbiddenError, NotFoundError) as e:
+            logger.warning(
+                "Cannot read the permissions of workspace %s, sharing its private "
+                "repositories with repository groups only: %s",
+                workspace,
+                e,
+            )
+            self._no_admin.add(workspace)
+            return []
+
+    async def _checkpoint(self) -> None:
+        self._since_checkpoint += 1
+        if self._since_checkpoint >= CHECKPOINT_INTERVAL:
+            self._since_checkpoint = 0
+            await self.ctx.save_state(self.state())
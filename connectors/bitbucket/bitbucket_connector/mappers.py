@@ -0,0 +1,179 @@
+"""Bitbucket objects to Omni Document mapping functions."""
+
+import re
+from datetime import datetime
+from typing import Any
+
+from omni_connector import Document, DocumentMetadata, DocumentPermissions
+
+from .config import MAX_CONTENT_LENGTH
+
+# Jira issue keys, as referenced in pull request titles and branch names
+_JIRA_KEY = re.compile(r"\b[A-Z][A-Z0-9]+-\d+\b")
+
+
+def build_permissions(repo: dict[str, Any], read_groups: list[str]) -> DocumentPermissions:
+    """
+    Who can see a repository and its pull requests.
+
+    Public repositories are public. Private ones are shared with the
+    `bitbucket:repo:{workspace}/{slug}` group, and with a `bitbucket:group:{workspace}/{slug}`
+    group for each workspace group granted read access to the repository or its project.
+    """
+    if not repo.get("is_private"):
+        return DocumentPermissions(public=True)
+    full_name = repo["full_name"]
+    workspace = full_name.split("/", 1)[0]
+    groups = {f"bitbucket:repo:{full_name}"}
+    groups.update(f"bitbucket:group:{workspace}/{slug}" for slug in read_groups)
+    return DocumentPermissions(public=False, groups=sorted(groups))
+
+
+def jira_keys(*texts: str | None) -> list[str]:
+    """The Jira issue keys mentioned in some texts, in order of appearance."""
+    keys: list[str] = []
+    for text in texts:
+        for key in _JIRA_KEY.findall(text or ""):
+            if key not in keys:
+                keys.append(key)
+    return keys
+
+
+def map_repo_to_document(
+    repo: dict[str, Any],
+    content_id: str,
+    permissions: DocumentPermissions,
+) -> Document:
+    """Map a Bitbucket repository to an Omni Document."""
+    full_name = repo["full_name"]
+    project = repo.get("project") or {}
+
+    return Document(
+        external_id=f"bitbucket:repo:{full_name}",
+        title=full_name,
+        content_id=content_id,
+        metadata=DocumentMetadata(
+            author=(repo.get("owner") or {}).get("display_name"),
+            created_at=_parse_iso(repo.get("created_on")),
+            updated_at=_parse_iso(repo.get("updated_on")),
+            url=_html_url(repo),
+            mime_type="text/plain",
+            path=project.get("name"),
+        ),
+        permissions=permissions,
+        attributes={
+            "source_type": "bitbucket",
+            "content_type": "repository",
+            "language": repo.get("language") or "",
+            "visibility": "private" if repo.get("is_private") else "public",
+            "project": project.get("key") or "",
+        },
+    )
+
+
+def map_pr_to_document(
+    pr: dict[str, Any],
+    content_id: str,
+    repo_full_name: str,
+    permissions: DocumentPermissions,
+) -> Document:
+    """Map a Bitbucket pull request, its comments included, to an Omni Document."""
+    source_branch = _branch(pr, "source")
+
+    return Document(
+        external_id=f"bitbucket:pr:{repo_full_name}#{pr['id']}",
+        title=f"[{repo_full_name}] PR #{pr['id']}: {pr.get('title', '')}",
+        content_id=content_id,
+        metadata=DocumentMetadata(
+            author=(pr.get("author") or {}).get("display_name"),
+            created_at=_parse_iso(pr.get("created_on")),
+            updated_at=_parse_iso(pr.get("updated_on")),
+            url=_html_url(pr),
+            mime_type="text/plain",
+        ),
+        permissions=permissions,
+        attributes={
+            "source_type": "bitbucket",
+            "content_type": "pull_request",
+            "state": (pr.get("state") or "").lower(),
+            "merged": str(pr.get("state") == "MERGED").lower(),
+            "source_branch": source_branch,
+            "destination_branch": _branch(pr, "destination"),
+            "jira_issues": ",".join(jira_keys(pr.get("title"), source_branch)),
+        },
+    )
+
+
+def generate_repo_content(repo: dict[str, Any], readme_content: str | None) -> str:
+    """Generate searchable text content from a repository."""
+    lines: list[str] = []
+    lines.append(f"Repository: {repo['full_name']}")
+    if repo.get("description"):
+        lines.append(f"Description: {repo['description']}")
+    if repo.get("language"):
+        lines.append(f"Language: {repo['language']}")
+    if project := (repo.get("project") or {}).get("name"):
+        lines.append(f"Project: {project}")
+    lines.append("")
+    if readme_content:
+        lines.append("README:")
+        lines.append(readme_content)
+    return _truncate("\n".join(lines))
+
+
+def generate_pr_content(pr: dict[str, Any], comments: list[dict[str, Any]]) -> str:
+    """Generate searchable text content from a pull request and its comments."""
+    lines: list[str] = []
+    lines.append(f"Pull Request #{pr['id']}: {pr.get('title', '')}")
+    lines.append(f"State: {pr.get('state', '')}")
+    if author := (pr.get("author") or {}).get("display_name"):
+        lines.append(f"Author: {author}")
+    lines.append(f"Branch: {_branch(pr, 'source')} -> {_branch(pr, 'destination')}")
+    lines.append("")
+    if pr.get("description"):
+        lines.append(pr["description"])
+
+    general = [c for c in comments if not c.get("inline")]
+    inline = [c for c in comments if c.get("inline")]
+    if general:
+        lines.append("")
+        lines.append("--- Comments ---")
+        for c in general:
+            lines.append(f"\n{_commenter(c)}:")
+            if body := (c.get("content") or {}).get("raw"):
+                lines.append(body)
+    if inline:
+        lines.append("")
+        lines.append("--- Review Comments ---")
+        for c in inline:
+            lines.append(f"\n{_commenter(c)} on {c['inline'].get('path', '')}:")
+            if body := (c.get("content") or {}).get("raw"):
+                lines.append(body)
+    return _truncate("\n".join(lines))
+
+
+def _commenter(comment: dict[str, Any]) -> str:
+    return (comment.get("user") or {}).get("display_name") or "unknown"
+
+
+def _branch(pr: dict[str, Any], side: str) -> str:
+    return ((pr.get(side) or {}).get("branch") or {}).get("name") or ""
+
+
+def _html_url(obj: dict[str, Any]) -> str | None:
+    return ((obj.get("links") or {}).get("html") or {}).get("href")
+
+
+def _parse_iso(value: str | None) -> datetime | None:
+    if not value:
+        return None
+    try:
+        return datetime.fromisoformat(value.replace("Z", "+00:00"))
+    except (ValueError, TypeError):
+        return None
+
+
+def _truncate(content: str) -> str:
+    if len(content) > MAX_CONTENT_LENGTH:
+        return content[:MAX_CONTENT_LENGTH] + "\n... (truncated)"
+    return content
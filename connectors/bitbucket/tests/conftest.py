@@ -0,0 +1,299 @@
+"""Integration test fixtures for the Bitbucket connector.
+
+Session-scoped: harness, mock Bitbucket API server, connector server, connector-manager.
+Function-scoped: seed helper, source_id, httpx client.
+"""
+
+from __future__ import annotations
+
+import logging
+import socket
+import threading
+import time
+from typing import Any
+
+import httpx
+import pytest
+import pytest_asyncio
+import uvicorn
+from starlette.applications import Starlette
+from starlette.requests import Request
+from starlette.responses import JSONResponse, PlainTextResponse
+from starlette.routing import Route
+
+from omni_connector.testing import OmniTestHarness, SeedHelper
+
+logger = logging.getLogger(__name__)
+
+REPO = "acme/payments"
+
+
+# ---------------------------------------------------------------------------
+# Mock data payload helpers
+# ---------------------------------------------------------------------------
+
+
+def _repo_payload(full_name: str, project_key: str) -> dict[str, Any]:
+    workspace, slug = full_name.split("/", 1)
+    return {
+        "type": "repository",
+        "full_name": full_name,
+        "name": slug,
+        "slug": slug,
+        "is_private": True,
+        "description": "",
+        "mainbranch": {"type": "branch", "name": "main"},
+        "project": {"type": "project", "key": project_key},
+        "workspace": {"slug": workspace},
+        "created_on": "2024-01-10T08:00:00+00:00",
+        "updated_on": "2024-05-02T10:00:00+00:00",
+        "links": {"html": {"href": f"https://bitbucket.org/{full_name}"}},
+    }
+
+
+def _pr_payload(pr_id: int, updated_on: str) -> dict[str, Any]:
+    return {
+        "type": "pullrequest",
+        "id": pr_id,
+        "title": f"PR {pr_id}",
+        "description": "",
+        "state": "OPEN",
+        "author": {"display_name": "Alice"},
+        "source": {"branch": {"name": f"feature-{pr_id}"}},
+        "destination": {"branch": {"name": "main"}},
+        "created_on": "2024-05-01T09:00:00+00:00",
+        "updated_on": updated_on,
+        "links": {"html": {"href": f"https://bitbucket.org/pull-requests/{pr_id}"}},
+    }
+
+
+def _group_grant(slug: str, permission: str = "read") -> dict[str, Any]:
+    return {"type": "group_permission", "permission": permission, "group": {"slug": slug}}
+
+
+# ---------------------------------------------------------------------------
+# Mock Bitbucket API
+# ---------------------------------------------------------------------------
+
+
+class MockBitbucketAPI:
+    """Controllable mock of the Bitbucket Cloud REST API 2.0 endpoints.
+
+    Permissions configs are keyed by the path of their repository or project. When
+    `admin` is False they are refused, as for credentials without admin access.
+    """
+
+    def __init__(self) -> None:
+        self.repos: dict[str, dict[str, Any]] = {}
+        self.pull_requests: dict[str, list[dict[str, Any]]] = {}
+        self.group_grants: dict[str, list[dict[str, Any]]] = {}
+        self.admin = True
+        self.pr_queries: list[str | None] = []
+
+    def reset(self) -> None:
+        self.repos.clear()
+        self.pull_requests.clear()
+        self.group_grants.clear()
+        self.admin = True
+        self.pr_queries.clear()
+
+    def add_repo(self, repo: dict[str, Any]) -> None:
+        self.repos[repo["full_name"]] = repo
+        self.pull_requests.setdefault(repo["full_name"], [])
+
+    def create_app(self) -> Starlette:
+        mock = self
+
+        def _page(values: list[dict[str, Any]]) -> JSONResponse:
+            return JSONResponse({"pagelen": len(values), "values": values})
+
+        def _full_name(request: Request) -> str:
+            return f"{request.path_params['workspace']}/{request.path_params['slug']}"
+
+        async def get_user(request: Request) -> JSONResponse:
+            return JSONResponse({"display_name": "Alice", "uuid": "{user-001}"})
+
+        async def get_repo(request: Request) -> JSONResponse:
+            repo = mock.repos.get(_full_name(request))
+            if repo is None:
+                return JSONResponse({"type": "error"}, status_code=404)
+            return JSONResponse(repo)
+
+        async def list_src(request: Request) -> JSONResponse:
+            return _page([{"type": "commit_file", "path": "README.md"}])
+
+        async def get_src_file(request: Request) -> PlainTextResponse:
+            return PlainTextResponse("# Payments")
+
+        async def list_pull_requests(request: Request) -> JSONResponse:
+            query = request.query_params.get("q")
+            mock.pr_queries.append(query)
+            since = query.split(">", 1)[1].strip() if query else None
+            prs = [
+                pr
+                for pr in mock.pull_requests.get(_full_name(request), [])
+                if not since or pr["updated_on"] > since
+            ]
+            return _page(sorted(prs, key=lambda pr: pr["updated_on"]))
+
+        async def list_pr_comments(request: Request) -> JSONResponse:
+            return _page([])
+
+        async def permissions_config(request: Request) -> JSONResponse:
+            if not mock.admin:
+                return JSONResponse({"type": "error"}, status_code=403)
+            path = request.url.path.removesuffix("/permissions-config/groups")
+            return _page(mock.group_grants.get(path, []))
+
+        repo_path = "/repositories/{workspace}/{slug}"
+        routes = [
+            Route("/user", get_user),
+            Route(repo_path, get_repo),
+            Route(f"{repo_path}/src/{{branch}}/", list_src),
+            Route(f"{repo_path}/src/{{branch}}/{{name}}", get_src_file),
+            Route(f"{repo_path}/pullrequests", list_pull_requests),
+            Route(f"{repo_path}/pullrequests/{{pr_id}}/comments", list_pr_comments),
+            Route(f"{repo_path}/permissions-config/groups", permissions_config),
+            Route(
+                "/workspaces/{workspace}/projects/{key}/permissions-config/groups",
+                permissions_config,
+            ),
+        ]
+        return Starlette(routes=routes)
+
+
+# ---------------------------------------------------------------------------
+# Helpers
+# ---------------------------------------------------------------------------
+
+
+def _free_port() -> int:
+    with socket.socket(socket.AF_INET, socket.SOCK_STREAM) as s:
+        s.bind(("", 0))
+        return s.getsockname()[1]
+
+
+def _wait_for_port(port: int, host: str = "localhost", timeout: float = 10) -> None:
+    deadline = time.monotonic() + timeout
+    while time.monotonic() < deadline:
+        try:
+            with socket.create_connection((host, port), timeout=1):
+                return
+        except OSError:
+            time.sleep(0.1)
+    raise TimeoutError(f"Port {port} not open after {timeout}s")
+
+
+# ---------------------------------------------------------------------------
+# Session-scoped fixtures
+# ---------------------------------------------------------------------------
+
+
+@pytest.fixture(scope="session")
+def mock_bitbucket_api() -> MockBitbucketAPI:
+    return MockBitbucketAPI()
+
+
+@pytest.fixture(scope="session")
+def mock_bitbucket_server(mock_bitbucket_api: MockBitbucketAPI) -> str:
+    """Start mock Bitbucket API server in a daemon thread. Returns base URL."""
+    port = _free_port()
+    app = mock_bitbucket_api.create_app()
+    config = uvicorn.Config(app, host="0.0.0.0", port=port, log_level="warning")
+    server = uvicorn.Server(config)
+
+    thread = threading.Thread(target=server.run, daemon=True)
+    thread.start()
+
+    _wait_for_port(port)
+    return f"http://localhost:{port}"
+
+
+@pytest.fixture(scope="session")
+def connector_port() -> int:
+    return _free_port()
+
+
+@pytest.fixture(scope="session")
+def connector_server(connector_port: int) -> str:
+    """Start the Bitbucket connector as a uvicorn server in a daemon thread. Returns base URL."""
+    import os
+
+    os.environ.setdefault("CONNECTOR_MANAGER_URL", "http://localhost:0")
+
+    from bitbucket_connector import BitbucketConnector
+    from omni_connector.server import create_app
+
+    app = create_app(BitbucketConnector())
+    config = uvicorn.Config(
+        app, host="0.0.0.0", port=connector_port, log_level="warning"
+    )
+    server = uvicorn.Server(config)
+
+    thread = threading.Thread(target=server.run, daemon=True)
+    thread.start()
+
+    _wait_for_port(connector_port)
+    return f"http://localhost:{connector_port}"
+
+
+@pytest_asyncio.fixture(scope="session")
+async def harness(
+    connector_server: str,
+    connector_port: int,
+) -> OmniTestHarness:
+    """Session-scoped OmniTestHarness with all infrastructure started."""
+    import os
+
+    h = OmniTestHarness()
+    await h.start_infra()
+    await h.start_connector_manager(
+        {
+            "BITBUCKET_CONNECTOR_URL": f"http://host.docker.internal:{connector_port}",
+        }
+    )
+
+    os.environ["CONNECTOR_MANAGER_URL"] = h.connector_manager_url
+
+    yield h
+    await h.teardown()
+
+
+# ---------------------------------------------------------------------------
+# Function-scoped fixtures
+# ---------------------------------------------------------------------------
+
+
+@pytest_asyncio.fixture
+async def seed(harness: OmniTestHarness) -> SeedHelper:
+    return harness.seed()
+
+
+@pytest_asyncio.fixture
+async def source_id(
+    seed: SeedHelper,
+    mock_bitbucket_server: str,
+    mock_bitbucket_api: MockBitbucketAPI,
+) -> str:
+    """Create a Bitbucket source with credentials pointing to the mock server."""
+    mock_bitbucket_api.reset()
+    sid = await seed.create_source(
+        source_type="bitbucket",
+        config={"api_url": mock_bitbucket_server, "repos": [REPO]},
+    )
+    await seed.create_credentials(
+        sid,
+        {"username": "alice", "password": "app-password"},
+        provider="bitbucket",
+        auth_type="basic_auth",
+    )
+    return sid
+
+
+@pytest_asyncio.fixture
+async def cm_client(harness: OmniTestHarness) -> httpx.AsyncClient:
+    """Async httpx client pointed at the connector-manager."""
+    async with httpx.AsyncClient(
+        base_url=harness.connector_manager_url, timeout=30
+    ) as client:
+        yield client
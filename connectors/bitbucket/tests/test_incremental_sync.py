@@ -0,0 +1,95 @@
+"""Integration tests: incremental sync reads only updated pull requests, unless access changed."""
+
+import httpx
+import pytest
+
+from omni_connector.testing import get_events, wait_for_sync
+
+from .conftest import REPO, _group_grant, _pr_payload, _repo_payload
+
+pytestmark = pytest.mark.integration
+
+
+async def _sync(harness, cm_client: httpx.AsyncClient, source_id: str, sync_type: str):
+    resp = await cm_client.post(
+        "/sync",
+        json={"source_id": source_id, "sync_type": sync_type},
+    )
+    assert resp.status_code == 200, resp.text
+    row = await wait_for_sync(harness.db_pool, resp.json()["sync_run_id"], timeout=30)
+    assert (
+        row["status"] == "completed"
+    ), f"Sync ended with status={row['status']}, error={row.get('error_message')}"
+    return row
+
+
+async def _created(harness, source_id: str, row) -> list[dict]:
+    events = await get_events(harness.db_pool, source_id)
+    return [
+        e["payload"]
+        for e in events
+        if e["sync_run_id"] == row["id"] and e["event_type"] == "document_created"
+    ]
+
+
+def _seed_repo(mock_bitbucket_api) -> None:
+    mock_bitbucket_api.add_repo(_repo_payload(REPO, "PAY"))
+    mock_bitbucket_api.pull_requests[REPO] = [
+        _pr_payload(1, "2024-05-01T10:00:00+00:00"),
+        _pr_payload(2, "2024-05-02T10:00:00+00:00"),
+    ]
+    mock_bitbucket_api.group_grants["/workspaces/acme/projects/PAY"] = [
+        _group_grant("developers")
+    ]
+
+
+async def test_second_sync_reads_only_updated_pull_requests(
+    harness, seed, source_id, mock_bitbucket_api, cm_client: httpx.AsyncClient
+):
+    _seed_repo(mock_bitbucket_api)
+
+    row = await _sync(harness, cm_client, source_id, "full")
+    assert [doc["document_id"] for doc in await _created(harness, source_id, row)] == [
+        "bitbucket:repo:acme/payments",
+        "bitbucket:pr:acme/payments#1",
+        "bitbucket:pr:acme/payments#2",
+    ]
+    state = await seed.get_connector_state(source_id)
+    assert state["repos"][REPO]["prs_updated_at"] == "2024-05-02T10:00:00+00:00"
+
+    row = await _sync(harness, cm_client, source_id, "incremental")
+
+    assert mock_bitbucket_api.pr_queries[-1] == "updated_on > 2024-05-02T10:00:00+00:00"
+    assert [doc["document_id"] for doc in await _created(harness, source_id, row)] == [
+        "bitbucket:repo:acme/payments"
+    ]
+
+
+async def test_project_groups_grant_access(
+    harness, source_id, mock_bitbucket_api, cm_client: httpx.AsyncClient
+):
+    _seed_repo(mock_bitbucket_api)
+
+    row = await _sync(harness, cm_client, source_id, "full")
+
+    repo_doc = (await _created(harness, source_id, row))[0]
+    assert repo_doc["permissions"]["groups"] == [
+        "bitbucket:group:acme/developers",
+        "bitbucket:repo:acme/payments",
+    ]
+
+
+async def test_access_change_reads_pull_requests_again(
+    harness, source_id, mock_bitbucket_api, cm_client: httpx.AsyncClient
+):
+    _seed_repo(mock_bitbucket_api)
+    await _sync(harness, cm_client, source_id, "full")
+
+    # Permissions can no longer be read
+    mock_bitbucket_api.admin = False
+    row = await _sync(harness, cm_client, source_id, "incremental")
+
+    assert mock_bitbucket_api.pr_queries[-1] is None
+    created = await _created(harness, source_id, row)
+    assert len(created) == 3
+    assert created[0]["permissions"]["groups"] == ["bitbucket:repo:acme/payments"]
@@ -0,0 +1,65 @@
+"""Tests for the Bitbucket mappers."""
+
+from bitbucket_connector.mappers import (
+    build_permissions,
+    generate_pr_content,
+    jira_keys,
+    map_pr_to_document,
+)
+
+REPO = {"full_name": "acme/payments", "is_private": True}
+
+PR = {
+    "id": 7,
+    "title": "PAY-42: Retry failed captures",
+    "description": "Captures are retried three times.",
+    "state": "MERGED",
+    "author": {"display_name": "Alice"},
+    "created_on": "2024-05-01T09:00:00.000000+00:00",
+    "updated_on": "2024-05-02T10:00:00.000000+00:00",
+    "source": {"branch": {"name": "feature/PAY-42-retries"}},
+    "destination": {"branch": {"name": "main"}},
+    "links": {"html": {"href": "https://bitbucket.org/acme/payments/pull-requests/7"}},
+}
+
+COMMENTS = [
+    {"content": {"raw": "Looks good"}, "user": {"display_name": "Bob"}},
+    {
+        "content": {"raw": "Cap the backoff?"},
+        "user": {"display_name": "Carol"},
+        "inline": {"path": "capture.py", "to": 12},
+    },
+]
+
+
+def test_private_repo_is_shared_with_repo_and_granted_groups():
+    permissions = build_permissions(REPO, ["developers"])
+
+    assert permissions.public is False
+    assert permissions.groups == ["bitbucket:group:acme/developers", "bitbucket:repo:acme/payments"]
+
+
+def test_public_repo_is_public():
+    assert build_permissions({**REPO, "is_private": False}, ["developers"]).public is True
+
+
+def test_pr_content_splits_general_and_inline_comments():
+    content = generate_pr_content(PR, COMMENTS)
+
+    assert "Branch: feature/PAY-42-retries -> main" in content
+    assert "--- Comments ---\n\nBob:\nLooks good" in content
+    assert "--- Review Comments ---\n\nCarol on capture.py:\nCap the backoff?" in content
+
+
+def test_pr_document_attributes():
+    doc = map_pr_to_document(PR, "content-id", "acme/payments", build_permissions(REPO, []))
+
+    assert doc.external_id == "bitbucket:pr:acme/payments#7"
+    assert doc.title == "[acme/payments] PR #7: PAY-42: Retry failed captures"
+    assert doc.attributes["state"] == "merged"
+    assert doc.attributes["merged"] == "true"
+    assert doc.attributes["jira_issues"] == "PAY-42"
+
+
+def test_jira_keys_in_order_without_duplicates():
+    assert jira_keys("OPS-1 and PAY-42", "PAY-42-fix", None) == ["OPS-1", "PAY-42"]
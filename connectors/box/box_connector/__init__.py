@@ -0,0 +1,6 @@
+"""Box connector for Omni."""
+
+from .connector import BoxConnector
+
+__version__ = "1.0.0"
+__all__ = ["BoxConnector"]
@@ -0,0 +1,296 @@
+"""Thin async wrapper over the Box API with retry logic."""
+
+import asyncio
+import logging
+import time
+from collections.abc import AsyncIterator
+from functools import wraps
+from typing import Any
+from urllib.parse import urljoin
+
+import httpx
+
+from .config import (
+    BOX_API_URL,
+    BOX_TOKEN_URL,
+    EVENTS_LIMIT,
+    ITEM_FIELDS,
+    PAGE_LIMIT,
+    REPRESENTATION_POLL_ATTEMPTS,
+    REPRESENTATION_POLL_SECONDS,
+)
+
+logger = logging.getLogger(__name__)
+
+
+class BoxError(Exception):
+    """Base exception for Box API errors."""
+
+    def __init__(self, message: str, status_code: int | None = None):
+        super().__init__(message)
+        self.status_code = status_code
+
+
+class AuthenticationError(BoxError):
+    """Invalid client credentials, or a token Box no longer accepts (401)."""
+
+    pass
+
+
+class ForbiddenError(BoxError):
+    """The app cannot access an item, or lacks a scope (403)."""
+
+    pass
+
+
+class NotFoundError(BoxError):
+    """Item deleted, trashed, or not visible to the user acted as (404)."""
+
+    pass
+
+
+def with_retry(max_retries: int = 3, base_delay: float = 1.0):
+    """Decorator for retrying Box API calls with exponential backoff.
+
+    Handles:
+    - 429 Rate Limit: Wait for Retry-After header (unlimited retries)
+    - 5xx Server Error: Exponential backoff (limited retries)
+    - 401/403: Re-raise as AuthenticationError/ForbiddenError (non-retryable)
+    - 404: Re-raise as NotFoundError (non-retryable)
+    """
+
+    def decorator(func):
+        @wraps(func)
+        async def wrapper(*args, **kwargs):
+            last_exception = None
+            error_retries = 0
+
+            while True:
+                try:
+                    return await func(*args, **kwargs)
+                except httpx.HTTPStatusError as e:
+                    last_exception = e
+                    status = e.response.status_code
+
+                    if status == 401:
+                        raise AuthenticationError(
+                            "Invalid Box client credentials", status_code=401
+                        ) from e
+
+                    if status == 403:
+                        raise ForbiddenError(
+                            f"Forbidden: {e.request.url.path}: {e.response.text}",
+                            status_code=403,
+                        ) from e
+
+                    if status == 404:
+                        raise NotFoundError(
+                            f"Not found: {e.request.url.path}", status_code=404
+                        ) from e
+
+                    if status == 429:
+                        retry_after = int(e.response.headers.get("Retry-After", "10"))
+                        logger.warning("Rate limited. Waiting %ds", retry_after)
+                        await asyncio.sleep(retry_after)
+                        continue
+
+                    if status >= 500:
+                        error_retries += 1
+                        if error_retries > max_retries:
+                            break
+                        delay = base_delay * (2 ** (error_retries - 1))
+                        logger.warning(
+                            "Server error %d. Retrying in %.1fs (%d/%d)",
+                            status,
+                            delay,
+                            error_retries,
+                            max_retries,
+                        )
+                        await asyncio.sleep(delay)
+                        continue
+
+                    raise BoxError(
+                        f"API error {status}: {e.response.text}",
+                        status_code=status,
+                    ) from e
+
+            raise BoxError(f"Max retries exceeded: {last_exception}") from last_exception
+
+        return wrapper
+
+    return decorator
+
+
+class BoxClient:
+    """Async client for the Box API, authenticated with client credentials.
+
+    The app's service account acts for the enterprise. Requests made `as_user` act as one
+    of its managed users instead, to read their content.
+    """
+
+    def __init__(
+        self,
+        client_id: str,
+        client_secret: str,
+        enterprise_id: str,
+        base_url: str | None = None,
+        token_url: str | None = None,
+        http_client: httpx.AsyncClient | None = None,
+    ):
+        self._client = http_client or httpx.AsyncClient(
+            base_url=(base_url or BOX_API_URL).rstrip("/"),
+            timeout=httpx.Timeout(60.0, connect=10.0),
+            follow_redirects=True,
+        )
+        # The token endpoint is on the API's host, outside its versioned path
+        self._token_url = token_url or (
+            urljoin(base_url, "/oauth2/token") if base_url else BOX_TOKEN_URL
+        )
+        self._grant = {
+            "grant_type": "client_credentials",
+            "client_id": client_id,
+            "client_secret": client_secret,
+            "box_subject_type": "enterprise",
+            "box_subject_id": enterprise_id,
+        }
+        self._token: str | None = None
+        self._token_expires_at = 0.0
+
+    async def close(self) -> None:
+        await self._client.aclose()
+
+    async def _access_token(self) -> str:
+        # Renewed a minute early, so a token does not expire mid-request
+        if self._token is None or time.monotonic() > self._token_expires_at - 60:
+            response = await self._client.post(self._token_url, data=self._grant)
+            if response.status_code in (400, 401):
+                raise AuthenticationError(
+                    f"Box rejected the client credentials: {response.text}",
+                    status_code=response.status_code,
+                )
+            response.raise_for_status()
+            data = response.json()
+            self._token = data["access_token"]
+            self._token_expires_at = time.monotonic() + data.get("expires_in", 3600)
+        return self._token
+
+    @with_retry(max_retries=3)
+    async def _get(
+        self,
+        url: str,
+        params: dict[str, Any] | None = None,
+        as_user: str | None = None,
+        headers: dict[str, str] | None = None,
+    ) -> httpx.Response:
+        request_headers = {"Authorization": f"Bearer {await self._access_token()}"}
+        if as_user:
+            request_headers["As-User"] = as_user
+        response = await self._client.get(
+            url, params=params, headers={**request_headers, **(headers or {})}
+        )
+        response.raise_for_status()
+        return response
+
+    async def get(
+        self,
+        url: str,
+        params: dict[str, Any] | None = None,
+        as_user: str | None = None,
+        headers: dict[str, str] | None = None,
+    ) -> dict[str, Any]:
+        response = await self._get(url, params, as_user, headers)
+        return response.json()
+
+    async def paginate(
+        self, path: str, params: dict[str, Any] | None = None, as_user: str | None = None
+    ) -> AsyncIterator[dict[str, Any]]:
+        """The entries of every page of a listing, by marker."""
+        params = {**(params or {}), "usemarker": "true", "limit": PAGE_LIMIT}
+        while True:
+            data = await self.get(path, params, as_user)
+            for entry in data.get("entries", []):
+                yield entry
+            marker = data.get("next_marker")
+            if not marker:
+                return
+            params["marker"] = marker
+
+    async def get_service_account(self) -> dict[str, Any]:
+        return await self.get("/users/me")
+
+    async def list_users(self) -> AsyncIterator[dict[str, Any]]:
+        """Every active managed user of the enterprise."""
+        params = {"user_type": "managed", "fields": "id,login,name,status"}
+        async for user in self.paginate("/users", params):
+            if user.get("status") == "active":
+                yield user
+
+    async def list_folder_items(
+        self, folder_id: str, as_user: str | None = None
+    ) -> AsyncIterator[dict[str, Any]]:
+        """The files and folders directly in a folder."""
+        params = {"fields": ITEM_FIELDS}
+        async for item in self.paginate(f"/folders/{folder_id}/items", params, as_user):
+            yield item
+
+    async def get_item(
+        self, item_type: str, item_id: str, as_user: str | None = None
+    ) -> dict[str, Any]:
+        """A file or folder, with the fields needed to map and place it."""
+        return await self.get(f"/{item_type}s/{item_id}", {"fields": ITEM_FIELDS}, as_user)
+
+    async def list_collaborations(
+        self, item_type: str, item_id: str, as_user: str | None = None
+    ) -> list[dict[str, Any]]:
+        """Users and groups invited to a file or folder itself, inherited ones excluded."""
+        path = f"/{item_type}s/{item_id}/collaborations"
+        return [c async for c in self.paginate(path, {}, as_user)]
+
+    async def download(self, file_id: str, as_user: str | None = None) -> bytes:
+        response = await self._get(f"/files/{file_id}/content", None, as_user)
+        return response.content
+
+    async def get_extracted_text(self, file_id: str, as_user: str | None = None) -> str | None:
+        """Text Box extracted from a document, None when it has none or it is not ready."""
+        data = await self.get(
+            f"/files/{file_id}",
+            {"fields": "representations"},
+            as_user,
+            headers={"X-Rep-Hints": "[extracted_text]"},
+        )
+        entries = (data.get("representations") or {}).get("entries") or []
+        if not entries:
+            return None
+        representation = entries[0]
+        for _ in range(REPRESENTATION_POLL_ATTEMPTS):
+            state = (representation.get("status") or {}).get("state")
+            if state == "success":
+                url = representation["content"]["url_template"].replace("{+asset_path}", "")
+                response = await self._get(url, None, as_user)
+                return response.content.decode("utf-8", errors="replace")
+            if state not in ("none", "pending"):
+                return None
+            # Reading the info of a representation Box has not made yet starts making it
+            representation = await self.get(representation["info"]["url"], as_user=as_user)
+            if (representation.get("status") or {}).get("state") != "success":
+                await asyncio.sleep(REPRESENTATION_POLL_SECONDS)
+        return None
+
+    async def get_stream_position(self) -> str:
+        """The current position of the enterprise event stream."""
+        data = await self.get(
+            "/events", {"stream_type": "admin_logs_streaming", "stream_position": "now"}
+        )
+        return str(data["next_stream_position"])
+
+    async def get_events(self, stream_position: str) -> tuple[list[dict[str, Any]], str]:
+        """The enterprise events after a stream position, and the position after them."""
+        data = await self.get(
+            "/events",
+            {
+                "stream_type": "admin_logs_streaming",
+                "stream_position": stream_position,
+                "limit": EVENTS_LIMIT,
+            },
+        )
+        return data.get("entries", []), str(data.get("next_stream_position", stream_position))
@@ -0,0 +1,61 @@
+"""Configuration constants for Box connector."""
+
+BOX_API_URL = "https://api.box.com/2.0"
+BOX_TOKEN_URL = "https://api.box.com/oauth2/token"
+
+MAX_CONTENT_LENGTH = 500_000
+# Files larger than this are indexed by name and path only
+MAX_FILE_SIZE = 50 * 1024 * 1024
+PAGE_LIMIT = 1000
+EVENTS_LIMIT = 500
+CHECKPOINT_INTERVAL = 50
+
+# Fields requested for files and folders, enough to map and place them
+ITEM_FIELDS = (
+    "id,type,name,size,extension,created_at,modified_at,content_modified_at,"
+    "created_by,modified_by,owned_by,parent,path_collection,shared_link,item_status"
+)
+
+# Read as is, other files through Box's extracted text representation
+TEXT_EXTENSIONS = {
+    "txt",
+    "md",
+    "markdown",
+    "csv",
+    "tsv",
+    "json",
+    "xml",
+    "yaml",
+    "yml",
+    "html",
+    "htm",
+    "log",
+}
+
+# Box extracts the text of other files in the background. A pending extraction is polled
+# this many times, this many seconds apart, before falling back to name and path
+REPRESENTATION_POLL_ATTEMPTS = 5
+REPRESENTATION_POLL_SECONDS = 2.0
+
+# Shared link access levels that open an item to every user of the enterprise, or to anyone
+PUBLIC_LINK_ACCESS = {"open", "company"}
+
+# Enterprise events after which an item is read again, by file or folder ID
+CHANGE_EVENTS = {
+    "UPLOAD",
+    "EDIT",
+    "COPY",
+    "MOVE",
+    "RENAME",
+    "UNDELETE",
+    "SHARE",
+    "UNSHARE",
+    "ITEM_SHARED_UPDATE",
+    "CHANGE_FOLDER_PERMISSION",
+    "COLLABORATION_INVITE",
+    "COLLABORATION_ACCEPT",
+    "COLLABORATION_REMOVE",
+    "COLLABORATION_ROLE_CHANGE",
+    "COLLABORATION_EXPIRATION",
+}
+DELETE_EVENTS = {"DELETE"}
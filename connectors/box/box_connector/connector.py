@@ -0,0 +1,339 @@
+"""Main BoxConnector class."""
+
+import logging
+from typing import Any
+
+from omni_connector import Connector, DocumentPermissions, SyncContext
+
+from .client import AuthenticationError, BoxClient, BoxError, ForbiddenError, NotFoundError
+from .config import (
+    CHANGE_EVENTS,
+    CHECKPOINT_INTERVAL,
+    DELETE_EVENTS,
+    MAX_FILE_SIZE,
+    TEXT_EXTENSIONS,
+)
+from .mappers import (
+    build_permissions,
+    file_external_id,
+    generate_file_content,
+    map_file_to_document,
+)
+
+logger = logging.getLogger(__name__)
+
+
+class BoxConnector(Connector):
+    """Box connector for Omni."""
+
+    @property
+    def name(self) -> str:
+        return "box"
+
+    @property
+    def version(self) -> str:
+        return "1.0.0"
+
+    @property
+    def sync_modes(self) -> list[str]:
+        return ["full", "incremental"]
+
+    async def sync(
+        self,
+        source_config: dict[str, Any],
+        credentials: dict[str, Any],
+        state: dict[str, Any] | None,
+        ctx: SyncContext,
+    ) -> None:
+        """
+        Crawl files once, then follow the enterprise event stream.
+
+        The first sync notes the stream's position, then crawls the configured folders, or
+        every managed user's files. Later syncs read the events since: files uploaded,
+        edited, moved, shared or deleted are read again or deleted, and folders are crawled
+        again when their collaborators change.
+
+        Args:
+            source_config: May set folder_ids, crawled as the app's service account; every
+                managed user's files are crawled, acting as each user, when it is not set
+            credentials: 'client_id', 'client_secret' and 'enterprise_id' of a Box app using
+                client credentials
+            state: Previous sync state, {"stream_position", "folders", "files"}, the last two
+                mapping each folder and file ID to its folder's
+            ctx: Sync context with emit(), complete(), etc.
+        """
+        client_id = credentials.get("client_id")
+        client_secret = credentials.get("client_secret")
+        enterprise_id = credentials.get("enterprise_id")
+        if not client_id or not client_secret or not enterprise_id:
+            await ctx.fail("Missing client_id, client_secret or enterprise_id in credentials")
+            return
+
+        client = BoxClient(
+            client_id=client_id,
+            client_secret=client_secret,
+            enterprise_id=str(enterprise_id),
+            base_url=source_config.get("api_url"),
+        )
+        try:
+            try:
+                account = await client.get_service_account()
+                logger.info("Starting Box sync as '%s'", account.get("login"))
+            except (AuthenticationError, ForbiddenError) as e:
+                await ctx.fail(f"Authentication failed: {e}")
+                return
+            except BoxError as e:
+                await ctx.fail(f"Connection test failed: {e}")
+                return
+
+            sync = _Sync(client, state or {}, source_config.get("folder_ids") or [], ctx)
+            try:
+                if sync.stream_position:
+                    await sync.sync_events()
+                else:
+                    await sync.crawl()
+
+                if ctx.is_cancelled():
+                    await ctx.fail("Cancelled by user")
+                    return
+                await ctx.complete(new_state=sync.state())
+                logger.info(
+                    "Sync completed: %d scanned, %d emitted",
+                    ctx.documents_scanned,
+                    ctx.documents_emitted,
+                )
+            except AuthenticationError as e:
+                logger.error("Authentication error during sync: %s", e)
+                await ctx.fail(f"Authentication failed: {e}")
+            except Exception as e:
+                logger.exception("Sync failed with unexpected error")
+                await ctx.fail(str(e))
+        finally:
+            await client.close()
+
+
+class _Sync:
+    """State of one sync run: the stream position and the folders and files synced."""
+
+    def __init__(
+        self,
+        client: BoxClient,
+        previous: dict[str, Any],
+        folder_ids: list[str],
+        ctx: SyncContext,
+    ):
+        self.client = client
+        self.ctx = ctx
+        self.folder_ids = [str(folder_id) for folder_id in folder_ids]
+        self.stream_position: str | None = previous.get("stream_position")
+        self.folders: dict[str, str] = dict(previous.get("folders", {}))
+        self.files: dict[str, str] = dict(previous.get("files", {}))
+        # Permissions of the folders read this run, for the items in them
+        self._folder_permissions: dict[str, DocumentPermissions] = {}
+        self._since_checkpoint = 0
+
+    def state(self) -> dict[str, Any]:
+        return {
+            "stream_position": self.stream_position,
+            "folders": self.folders,
+            "files": self.files,
+        }
+
+    async def crawl(self) -> None:
+        """Sync every file in scope, and note where the event stream starts."""
+        # Taken first, so what changes during the crawl is replayed by the next sync
+        self.stream_position = await self.client.get_stream_position()
+        self.folders, self.files = {}, {}
+
+        if self.folder_ids:
+            for folder_id in self.folder_ids:
+                try:
+                    folder = await self.client.get_item("folder", folder_id)
+                except (ForbiddenError, NotFoundError) as e:
+                    logger.warning("Cannot read folder %s: %s", folder_id, e)
+                    continue
+                await self.sync_folder(folder, await self._inherited_permissions(folder, None))
+            return
+
+        async for user in self.client.list_users():
+            if self.ctx.is_cancelled():
+                return
+            logger.info("Crawling the files of %s", user.get("login"))
+            owner = DocumentPermissions(public=False, users=[user["login"].lower()])
+            await self._crawl_folder("0", owner, user["id"])
+
+    async def sync_folder(
+        self,
+        folder: dict[str, Any],
+        inherited: DocumentPermissions,
+        as_user: str | None = None,
+    ) -> None:
+        """Sync a folder's files and subfolders, with the folder's permissions."""
+        collaborations = await self.client.list_collaborations("folder", folder["id"], as_user)
+        permissions = build_permissions(folder, collaborations, inherited)
+        self._folder_permissions[folder["id"]] = permissions
+        self.folders[folder["id"]] = (folder.get("parent") or {}).get("id", "0")
+        await self._crawl_folder(folder["id"], permissions, as_user)
+
+    async def _crawl_folder(
+        self,
+        folder_id: str,
+        permissions: DocumentPermissions,
+        as_user: str | None,
+    ) -> None:
+        async for item in self.client.list_folder_items(folder_id, as_user):
+            if self.ctx.is_cancelled():
+                return
+            if item["type"] == "folder":
+                # A folder shared with a user is crawled from its owner's files
+                if as_user and (item.get("owned_by") or {}).get("id") != as_user:
+                    continue
+                await self.sync_folder(item, permissions, as_user)
+            elif item["type"] == "file":
+                await self.sync_file(item, permissions, as_user)
+
+    async def sync_file(
+        self,
+        file: dict[str, Any],
+        inherited: DocumentPermissions,
+        as_user: str | None = None,
+    ) -> None:
+        await self.ctx.increment_scanned()
+        try:
+            collaborations = await self.client.list_collaborations("file", file["id"], as_user)
+            permissions = build_permissions(file, collaborations, inherited)
+            content = generate_file_content(file, await self._read_text(file, as_user))
+            content_id = await self.ctx.content_storage.save(content, "text/plain")
+            await self.ctx.emit(map_file_to_document(file, content_id, permissions))
+            self.files[file["id"]] = (file.get("parent") or {}).get("id", "0")
+        except AuthenticationError:
+            raise
+        except Exception as e:
+            eid = file_external_id(file["id"])
+            logger.warning("Error processing %s: %s", eid, e)
+            await self.ctx.emit_error(eid, str(e))
+        await self._checkpoint()
+
+    async def _read_text(self, file: dict[str, Any], as_user: str | None) -> str | None:
+        """A file's text, None when it is too large or Box cannot provide it."""
+        if (file.get("size") or 0) > MAX_FILE_SIZE:
+            return None
+        try:
+            if (file.get("extension") or "").lower() in TEXT_EXTENSIONS:
+                data = await self.client.download(file["id"], as_user)
+                return data.decode("utf-8", errors="replace")
+            return await self.client.get_extracted_text(file["id"], as_user)
+        except (ForbiddenError, NotFoundError) as e:
+            logger.warning("Cannot read the text of file %s: %s", file["id"], e)
+            return None
+
+    async def sync_events(self) -> None:
+        """Read the events since the last sync, and sync the items they changed."""
+        # Only the last event of each item matters, as items are read again as they are now
+        changes: dict[tuple[str, str], tuple[str, str | None]] = {}
+        position = self.stream_position
+        while True:
+            entries, position = await self.client.get_events(position)
+            for event in entries:
+                source = event.get("source") or {}
+                event_type = event.get("event_type")
+                item_type = source.get("item_type")
+                if item_type not in ("file", "folder"):
+                    continue
+                if event_type not in CHANGE_EVENTS and event_type not in DELETE_EVENTS:
+                    continue
+                key = (item_type, str(source["item_id"]))
+                owner = (source.get("owned_by") or {}).get("id")
+                changes.pop(key, None)
+                changes[key] = (event_type, owner)
+            if not entries:
+                break
+        logger.info("%d items changed since the last sync", len(changes))
+
+        for (item_type, item_id), (event_type, owner) in changes.items():
+            if self.ctx.is_cancelled():
+                return
+            if event_type in DELETE_EVENTS:
+                await self._delete(item_type, item_id)
+            else:
+                await self._sync_changed(item_type, item_id, owner)
+        self.stream_position = position
+
+    async def _sync_changed(self, item_type: str, item_id: str, owner: str | None) -> None:
+        # Configured folders are shared with the service account, other files are read as
+        # their owner
+        as_user = None if self.folder_ids else owner
+        try:
+            item = await self.client.get_item(item_type, item_id, as_user)
+        except NotFoundError:
+            await self._delete(item_type, item_id)
+            return
+        except ForbiddenError as e:
+            logger.warning("Cannot read %s %s: %s", item_type, item_id, e)
+            return
+        if item.get("item_status", "active") != "active" or not self._in_scope(item):
+            await self._delete(item_type, item_id)
+            return
+
+        inherited = await self._inherited_permissions(item, as_user)
+        if item_type == "file":
+            await self.sync_file(item, inherited, as_user)
+        else:
+            # Its files may have moved, or gained or lost collaborators with it
+            await self.sync_folder(item, inherited, as_user)
+
+    def _in_scope(self, item: dict[str, Any]) -> bool:
+        """Whether an item is in one of the configured folders, if any are configured."""
+        if not self.folder_ids:
+            return True
+        ancestors = (item.get("path_collection") or {}).get("entries") or []
+        ids = {item["id"], *(folder["id"] for folder in ancestors)}
+        return any(folder_id in ids for folder_id in self.folder_ids)
+
+    async def _inherited_permissions(
+        self, item: dict[str, Any], as_user: str | None
+    ) -> DocumentPermissions:
+        """Permissions an item inherits from the folders it is in."""
+        permissions: DocumentPermissions | None = None
+        for ancestor in (item.get("path_collection") or {}).get("entries") or []:
+            folder_id = ancestor["id"]
+            if folder_id == "0":
+                continue
+            if folder_id not in self._folder_permissions:
+                folder = await self.client.get_item("folder", folder_id, as_user)
+                collaborations = await self.client.list_collaborations(
+                    "folder", folder_id, as_user
+                )
+                self._folder_permissions[folder_id] = build_permissions(
+                    folder, collaborations, permissions
+                )
+            permissions = self._folder_permissions[folder_id]
+        return permissions or DocumentPermissions(public=False)
+
+    async def _delete(self, item_type: str, item_id: str) -> None:
+        """Delete a file, or every file in a folder and its subfolders."""
+        if item_type == "file":
+            if self.files.pop(item_id, None) is not None:
+                await self.ctx.emit_deleted(file_external_id(item_id))
+            return
+
+        deleted = {item_id}
+        children: dict[str, list[str]] = {}
+        for folder_id, parent_id in self.folders.items():
+            children.setdefault(parent_id, []).append(folder_id)
+        pending = [item_id]
+        while pending:
+            for child in children.get(pending.pop(), []):
+                deleted.add(child)
+                pending.append(child)
+        for folder_id in deleted:
+            self.folders.pop(folder_id, None)
+        for file_id in [f for f, parent in self.files.items() if parent in deleted]:
+            del self.files[file_id]
+            await self.ctx.emit_deleted(file_external_id(file_id))
+
+    async def _checkpoint(self) -> None:
+        self._since_checkpoint += 1
+        if self._since_checkpoint >= CHECKPOINT_INTERVAL:
+            self._since_checkpoint = 0
+            await self.ctx.save_state(self.state())
@@ -0,0 +1,125 @@
+"""File-to-Document mapping functions for Box."""
+
+import mimetypes
+from datetime import datetime
+from typing import Any
+
+from omni_connector import Document, DocumentMetadata, DocumentPermissions
+
+from .config import MAX_CONTENT_LENGTH, PUBLIC_LINK_ACCESS
+
+
+def file_external_id(file_id: str) -> str:
+    return f"box:file:{file_id}"
+
+
+def group_name(group_id: str) -> str:
+    return f"box:group:{group_id}"
+
+
+def parse_timestamp(value: str | None) -> datetime | None:
+    """Parse a Box ISO 8601 timestamp."""
+    if not value:
+        return None
+    try:
+        return datetime.fromisoformat(value.replace("Z", "+00:00"))
+    except ValueError:
+        return None
+
+
+def item_path(item: dict[str, Any]) -> str:
+    """Path of an item from its owner's root, e.g. `/Finance/2024/budget.xlsx`."""
+    ancestors = (item.get("path_collection") or {}).get("entries") or []
+    # The first ancestor is the owner's root, "All Files"
+    names = [folder.get("name", "") for folder in ancestors if folder.get("id") != "0"]
+    return "/" + "/".join([*names, item.get("name", "")])
+
+
+def build_permissions(
+    item: dict[str, Any],
+    collaborations: list[dict[str, Any]],
+    inherited: DocumentPermissions | None = None,
+) -> DocumentPermissions:
+    """
+    Who can see a file or folder: its owner, its collaborators and those of its folders.
+
+    Accepted collaborations grant access; pending invitations do not. A shared link open
+    to anyone or to the whole company makes the item, and what it contains, public.
+
+    Args:
+        item: The file or folder, with its owner and shared link
+        collaborations: Collaborations on the item itself
+        inherited: Permissions of the folder the item is in, if any
+    """
+    inherited = inherited or DocumentPermissions(public=False)
+    link = item.get("shared_link") or {}
+    link_access = link.get("effective_access") or link.get("access")
+    if inherited.public or link_access in PUBLIC_LINK_ACCESS:
+        return DocumentPermissions(public=True)
+
+    users = set(inherited.users)
+    groups = set(inherited.groups)
+    if owner := (item.get("owned_by") or {}).get("login"):
+        users.add(owner.lower())
+    for collaboration in collaborations:
+        if collaboration.get("status") != "accepted":
+            continue
+        accessible_by = collaboration.get("accessible_by") or {}
+        if accessible_by.get("type") == "group":
+            groups.add(group_name(accessible_by["id"]))
+        elif login := accessible_by.get("login"):
+            users.add(login.lower())
+    return DocumentPermissions(public=False, users=sorted(users), groups=sorted(groups))
+
+
+def generate_file_content(file: dict[str, Any], text: str | None) -> str:
+    """Searchable text of a file, its name and path when its text cannot be read."""
+    header = f"{file.get('name', '')}\nPath: {item_path(file)}"
+    if not text:
+        return header
+    return f"{header}\n\n{text}"[:MAX_CONTENT_LENGTH]
+
+
+def map_file_to_document(
+    file: dict[str, Any],
+    content_id: str,
+    permissions: DocumentPermissions,
+) -> Document:
+    """
+    Map a Box file to an Omni Document.
+
+    Args:
+        file: The file, with its path and owner
+        content_id: ID from content storage
+        permissions: Who can see the file
+
+    Returns:
+        Omni Document instance
+    """
+    name = file.get("name", "")
+    mime_type, _ = mimetypes.guess_type(name)
+    return Document(
+        external_id=file_external_id(file["id"]),
+        title=name,
+        content_id=content_id,
+        metadata=DocumentMetadata(
+            author=(file.get("created_by") or {}).get("name"),
+            created_at=parse_timestamp(file.get("created_at")),
+            updated_at=parse_timestamp(file.get("content_modified_at") or file.get("modified_at")),
+            mime_type=mime_type or "application/octet-stream",
+            size=str(file["size"]) if file.get("size") is not None else None,
+            url=f"https://app.box.com/file/{file['id']}",
+            path=item_path(file),
+            extra={
+                "file_id": file["id"],
+                "folder_id": (file.get("parent") or {}).get("id"),
+                "owner": (file.get("owned_by") or {}).get("login"),
+            },
+        ),
+        permissions=permissions,
+        attributes={
+            "source_type": "box",
+            "content_type": "file",
+            "extension": (file.get("extension") or "").lower(),
+        },
+    )
@@ -0,0 +1,298 @@
+"""Integration test fixtures for the Box connector.
+
+Session-scoped: harness, mock Box API server, connector server, connector-manager.
+Function-scoped: seed helper, source_id, httpx client.
+"""
+
+from __future__ import annotations
+
+import logging
+import socket
+import threading
+import time
+from typing import Any
+
+import httpx
+import pytest
+import pytest_asyncio
+import uvicorn
+from starlette.applications import Starlette
+from starlette.requests import Request
+from starlette.responses import JSONResponse, Response
+from starlette.routing import Route
+
+from omni_connector.testing import OmniTestHarness, SeedHelper
+
+logger = logging.getLogger(__name__)
+
+CREDENTIALS = {"client_id": "test-client", "client_secret": "test-secret", "enterprise_id": "42"}
+
+
+# ---------------------------------------------------------------------------
+# Mock data payload helpers
+# ---------------------------------------------------------------------------
+
+ROOT: dict[str, Any] = {"id": "0", "type": "folder", "name": "All Files"}
+
+
+def _folder_payload(folder_id: str, parent: dict[str, Any] | None = None) -> dict[str, Any]:
+    parent = parent or ROOT
+    path = (parent.get("path_collection") or {}).get("entries", [])
+    return {
+        "id": folder_id,
+        "type": "folder",
+        "name": f"Folder {folder_id}",
+        "owned_by": {"type": "user", "id": "11", "login": "alice@acme.com"},
+        "parent": {"type": "folder", "id": parent["id"], "name": parent["name"]},
+        "path_collection": {
+            "total_count": len(path) + 1,
+            "entries": [*path, {"type": "folder", "id": parent["id"], "name": parent["name"]}],
+        },
+        "item_status": "active",
+    }
+
+
+def _file_payload(file_id: str, parent: dict[str, Any]) -> dict[str, Any]:
+    return {
+        **_folder_payload(file_id, parent),
+        "type": "file",
+        "name": f"{file_id}.txt",
+        "extension": "txt",
+        "size": 5,
+        "modified_at": "2024-05-02T10:00:00-07:00",
+    }
+
+
+def _event_payload(event_type: str, item_type: str, item_id: str) -> dict[str, Any]:
+    return {
+        "type": "event",
+        "event_type": event_type,
+        "source": {"item_type": item_type, "item_id": item_id, "owned_by": {"id": "11"}},
+    }
+
+
+# ---------------------------------------------------------------------------
+# Mock Box API
+# ---------------------------------------------------------------------------
+
+
+class MockBoxAPI:
+    """Controllable mock of the Box API endpoints, client credentials token included.
+
+    The enterprise event stream is at position `p0`. Each batch in `events` is read once,
+    moving the position forward by a `+`.
+    """
+
+    def __init__(self) -> None:
+        self.items: dict[str, dict[str, Any]] = {}
+        self.collaborations: dict[str, list[dict[str, Any]]] = {}
+        self.events: list[list[dict[str, Any]]] = []
+
+    def reset(self) -> None:
+        self.items.clear()
+        self.collaborations.clear()
+        self.events.clear()
+
+    def add_item(self, item: dict[str, Any]) -> None:
+        self.items[item["id"]] = item
+
+    def create_app(self) -> Starlette:
+        mock = self
+
+        def _entries(entries: list[dict[str, Any]]) -> JSONResponse:
+            return JSONResponse({"entries": entries, "limit": 1000, "next_marker": None})
+
+        def _item(request: Request, item_type: str) -> dict[str, Any] | None:
+            item = mock.items.get(request.path_params["item_id"])
+            return item if item and item["type"] == item_type else None
+
+        def _not_found() -> JSONResponse:
+            return JSONResponse({"type": "error", "status": 404}, status_code=404)
+
+        async def token(request: Request) -> JSONResponse:
+            return JSONResponse(
+                {"access_token": "test-access-token", "expires_in": 3600, "token_type": "bearer"}
+            )
+
+        async def get_me(request: Request) -> JSONResponse:
+            return JSONResponse({"type": "user", "id": "99", "login": "service@acme.com"})
+
+        async def list_users(request: Request) -> JSONResponse:
+            return _entries(
+                [{"type": "user", "id": "11", "login": "Alice@acme.com", "status": "active"}]
+            )
+
+        async def get_folder(request: Request) -> JSONResponse:
+            folder = _item(request, "folder")
+            return JSONResponse(folder) if folder else _not_found()
+
+        async def get_file(request: Request) -> JSONResponse:
+            file = _item(request, "file")
+            return JSONResponse(file) if file else _not_found()
+
+        async def folder_items(request: Request) -> JSONResponse:
+            folder_id = request.path_params["item_id"]
+            return _entries(
+                [item for item in mock.items.values() if item["parent"]["id"] == folder_id]
+            )
+
+        async def collaborations(request: Request) -> JSONResponse:
+            return _entries(mock.collaborations.get(request.path_params["item_id"], []))
+
+        async def download(request: Request) -> Response:
+            return Response(b"hello", media_type="application/octet-stream")
+
+        async def events(request: Request) -> JSONResponse:
+            position = request.query_params["stream_position"]
+            if position == "now":
+                return JSONResponse({"entries": [], "next_stream_position": "p0"})
+            if not mock.events:
+                return JSONResponse({"entries": [], "next_stream_position": position})
+            entries = mock.events.pop(0)
+            return JSONResponse({"entries": entries, "next_stream_position": f"{position}+"})
+
+        routes = [
+            Route("/oauth2/token", token, methods=["POST"]),
+            Route("/users/me", get_me),
+            Route("/users", list_users),
+            Route("/folders/{item_id}", get_folder),
+            Route("/folders/{item_id}/items", folder_items),
+            Route("/folders/{item_id}/collaborations", collaborations),
+            Route("/files/{item_id}", get_file),
+            Route("/files/{item_id}/collaborations", collaborations),
+            Route("/files/{item_id}/content", download),
+            Route("/events", events),
+        ]
+        return Starlette(routes=routes)
+
+
+# ---------------------------------------------------------------------------
+# Helpers
+# ---------------------------------------------------------------------------
+
+
+def _free_port() -> int:
+    with socket.socket(socket.AF_INET, socket.SOCK_STREAM) as s:
+        s.bind(("", 0))
+        return s.getsockname()[1]
+
+
+def _wait_for_port(port: int, host: str = "localhost", timeout: float = 10) -> None:
+    deadline = time.monotonic() + timeout
+    while time.monotonic() < deadline:
+        try:
+            with socket.create_connection((host, port), timeout=1):
+                return
+        except OSError:
+            time.sleep(0.1)
+    raise TimeoutError(f"Port {port} not open after {timeout}s")
+
+
+# ---------------------------------------------------------------------------
+# Session-scoped fixtures
+# ---------------------------------------------------------------------------
+
+
+@pytest.fixture(scope="session")
+def mock_box_api() -> MockBoxAPI:
+    return MockBoxAPI()
+
+
+@pytest.fixture(scope="session")
+def mock_box_server(mock_box_api: MockBoxAPI) -> str:
+    """Start mock Box API server in a daemon thread. Returns base URL."""
+    port = _free_port()
+    app = mock_box_api.create_app()
+    config = uvicorn.Config(app, host="0.0.0.0", port=port, log_level="warning")
+    server = uvicorn.Server(config)
+
+    thread = threading.Thread(target=server.run, daemon=True)
+    thread.start()
+
+    _wait_for_port(port)
+    return f"http://localhost:{port}"
+
+
+@pytest.fixture(scope="session")
+def connector_port() -> int:
+    return _free_port()
+
+
+@pytest.fixture(scope="session")
+def connector_server(connector_port: int) -> str:
+    """Start the Box connector as a uvicorn server in a daemon thread. Returns base URL."""
+    import os
+
+    os.environ.setdefault("CONNECTOR_MANAGER_URL", "http://localhost:0")
+
+    from box_connector import BoxConnector
+    from omni_connector.server import create_app
+
+    app = create_app(BoxConnector())
+    config = uvicorn.Config(
+        app, host="0.0.0.0", port=connector_port, log_level="warning"
+    )
+    server = uvicorn.Server(config)
+
+    thread = threading.Thread(target=server.run, daemon=True)
+    thread.start()
+
+    _wait_for_port(connector_port)
+    return f"http://localhost:{connector_port}"
+
+
+@pytest_asyncio.fixture(scope="session")
+async def harness(
+    connector_server: str,
+    connector_port: int,
+) -> OmniTestHarness:
+    """Session-scoped OmniTestHarness with all infrastructure started."""
+    import os
+
+    h = OmniTestHarness()
+    await h.start_infra()
+    await h.start_connector_manager(
+        {
+            "BOX_CONNECTOR_URL": f"http://host.docker.internal:{connector_port}",
+        }
+    )
+
+    os.environ["CONNECTOR_MANAGER_URL"] = h.connector_manager_url
+
+    yield h
+    await h.teardown()
+
+
+# ---------------------------------------------------------------------------
+# Function-scoped fixtures
+# ---------------------------------------------------------------------------
+
+
+@pytest_asyncio.fixture
+async def seed(harness: OmniTestHarness) -> SeedHelper:
+    return harness.seed()
+
+
+@pytest_asyncio.fixture
+async def source_id(
+    seed: SeedHelper,
+    mock_box_server: str,
+    mock_box_api: MockBoxAPI,
+) -> str:
+    """Create a Box source with credentials pointing to the mock server."""
+    mock_box_api.reset()
+    sid = await seed.create_source(
+        source_type="box",
+        config={"api_url": mock_box_server},
+    )
+    await seed.create_credentials(sid, CREDENTIALS, provider="box")
+    return sid
+
+
+@pytest_asyncio.fixture
+async def cm_client(harness: OmniTestHarness) -> httpx.AsyncClient:
+    """Async httpx client pointed at the connector-manager."""
+    async with httpx.AsyncClient(
+        base_url=harness.connector_manager_url, timeout=30
+    ) as client:
+        yield client
@@ -0,0 +1,73 @@
+"""Tests for the Box mappers."""
+
+from omni_connector import DocumentPermissions
+
+from box_connector.mappers import build_permissions, item_path, map_file_to_document
+
+FILE = {
+    "id": "1234",
+    "type": "file",
+    "name": "Budget.xlsx",
+    "size": 20480,
+    "extension": "xlsx",
+    "created_at": "2024-05-01T09:00:00-07:00",
+    "content_modified_at": "2024-05-02T10:30:00-07:00",
+    "created_by": {"name": "Alice", "login": "alice@acme.com"},
+    "owned_by": {"id": "11", "login": "Alice@acme.com"},
+    "parent": {"id": "200"},
+    "path_collection": {
+        "entries": [
+            {"id": "0", "name": "All Files"},
+            {"id": "100", "name": "Finance"},
+            {"id": "200", "name": "2024"},
+        ]
+    },
+}
+
+
+def _collaboration(status: str, accessible_by: dict) -> dict:
+    return {"status": status, "accessible_by": accessible_by}
+
+
+def test_item_path_skips_root():
+    assert item_path(FILE) == "/Finance/2024/Budget.xlsx"
+
+
+def test_permissions_combine_owner_collaborators_and_folders():
+    folder = DocumentPermissions(public=False, users=["bob@acme.com"], groups=[])
+    permissions = build_permissions(
+        FILE,
+        [
+            _collaboration("accepted", {"type": "user", "login": "Carol@acme.com"}),
+            _collaboration("accepted", {"type": "group", "id": "77"}),
+            _collaboration("pending", {"type": "user", "login": "dave@acme.com"}),
+        ],
+        folder,
+    )
+
+    assert permissions.public is False
+    assert permissions.users == ["alice@acme.com", "bob@acme.com", "carol@acme.com"]
+    assert permissions.groups == ["box:group:77"]
+
+
+def test_company_shared_link_makes_item_public():
+    shared = {**FILE, "shared_link": {"access": "collaborators", "effective_access": "company"}}
+    assert build_permissions(shared, []).public is True
+
+    restricted = {**FILE, "shared_link": {"access": "collaborators"}}
+    assert build_permissions(restricted, []).public is False
+
+
+def test_items_in_public_folders_are_public():
+    assert build_permissions(FILE, [], DocumentPermissions(public=True)).public is True
+
+
+def test_file_document():
+    doc = map_file_to_document(FILE, "content-id", DocumentPermissions(public=True))
+
+    assert doc.external_id == "box:file:1234"
+    assert doc.title == "Budget.xlsx"
+    assert doc.metadata.path == "/Finance/2024/Budget.xlsx"
+    assert doc.metadata.url == "https://app.box.com/file/1234"
+    assert doc.metadata.size == "20480"
+    assert doc.attributes["extension"] == "xlsx"
@@ -0,0 +1,162 @@
+"""Integration tests: the first sync crawls files, later ones follow the enterprise events."""
+
+import httpx
+import pytest
+
+from omni_connector.testing import get_events, wait_for_sync
+
+from .conftest import CREDENTIALS, ROOT, _event_payload, _file_payload, _folder_payload
+
+pytestmark = pytest.mark.integration
+
+
+async def _sync(harness, cm_client: httpx.AsyncClient, source_id: str, sync_type: str):
+    resp = await cm_client.post(
+        "/sync",
+        json={"source_id": source_id, "sync_type": sync_type},
+    )
+    assert resp.status_code == 200, resp.text
+    row = await wait_for_sync(harness.db_pool, resp.json()["sync_run_id"], timeout=30)
+    assert (
+        row["status"] == "completed"
+    ), f"Sync ended with status={row['status']}, error={row.get('error_message')}"
+    return row
+
+
+async def _events(harness, source_id: str, row, event_type: str) -> list[dict]:
+    events = await get_events(harness.db_pool, source_id)
+    return [
+        e["payload"]
+        for e in events
+        if e["sync_run_id"] == row["id"] and e["event_type"] == event_type
+    ]
+
+
+async def _documents(harness, source_id: str, row, event_type: str) -> list[str]:
+    return [e["document_id"] for e in await _events(harness, source_id, row, event_type)]
+
+
+async def _folders_source(seed, mock_box_server: str, mock_box_api, folder_ids: list[str]) -> str:
+    """A source syncing only the given folders, as the service account."""
+    mock_box_api.reset()
+    source_id = await seed.create_source(
+        source_type="box",
+        config={"api_url": mock_box_server, "folder_ids": folder_ids},
+    )
+    await seed.create_credentials(source_id, CREDENTIALS, provider="box")
+    return source_id
+
+
+def _seed_tree(mock_box_api) -> None:
+    """Files 1 in folder 100, 2 in its subfolder 200, and 3 at the root."""
+    projects = _folder_payload("100")
+    archive = _folder_payload("200", projects)
+    for item in (
+        projects,
+        archive,
+        _file_payload("1", projects),
+        _file_payload("2", archive),
+        _file_payload("3", ROOT),
+    ):
+        mock_box_api.add_item(item)
+
+
+async def test_crawl_records_tree_and_stream_position(
+    harness, seed, source_id, mock_box_api, cm_client: httpx.AsyncClient
+):
+    _seed_tree(mock_box_api)
+
+    row = await _sync(harness, cm_client, source_id, "full")
+
+    assert sorted(await _documents(harness, source_id, row, "document_created")) == [
+        "box:file:1",
+        "box:file:2",
+        "box:file:3",
+    ]
+    assert await seed.get_connector_state(source_id) == {
+        "stream_position": "p0",
+        "folders": {"100": "0", "200": "100"},
+        "files": {"1": "100", "2": "200", "3": "0"},
+    }
+
+
+async def test_configured_folders_limit_crawl(
+    harness, seed, mock_box_server, mock_box_api, cm_client: httpx.AsyncClient
+):
+    source_id = await _folders_source(seed, mock_box_server, mock_box_api, ["200"])
+    _seed_tree(mock_box_api)
+
+    await _sync(harness, cm_client, source_id, "full")
+
+    state = await seed.get_connector_state(source_id)
+    assert state["files"] == {"2": "200"}
+
+
+async def test_edited_file_is_synced_again(
+    harness, seed, source_id, mock_box_api, cm_client: httpx.AsyncClient
+):
+    _seed_tree(mock_box_api)
+    await _sync(harness, cm_client, source_id, "full")
+    mock_box_api.events = [
+        [_event_payload("UPLOAD", "file", "2"), _event_payload("EDIT", "file", "2")]
+    ]
+
+    row = await _sync(harness, cm_client, source_id, "incremental")
+
+    assert await _documents(harness, source_id, row, "document_created") == ["box:file:2"]
+    state = await seed.get_connector_state(source_id)
+    assert state["stream_position"] == "p0+"
+
+
+async def test_deleted_folder_removes_its_files(
+    harness, seed, source_id, mock_box_api, cm_client: httpx.AsyncClient
+):
+    _seed_tree(mock_box_api)
+    await _sync(harness, cm_client, source_id, "full")
+    mock_box_api.events = [[_event_payload("DELETE", "folder", "100")]]
+
+    row = await _sync(harness, cm_client, source_id, "incremental")
+
+    assert sorted(await _documents(harness, source_id, row, "document_deleted")) == [
+        "box:file:1",
+        "box:file:2",
+    ]
+    state = await seed.get_connector_state(source_id)
+    assert state["folders"] == {}
+    assert state["files"] == {"3": "0"}
+
+
+async def test_folder_collaborator_reaches_its_files(
+    harness, source_id, mock_box_api, cm_client: httpx.AsyncClient
+):
+    _seed_tree(mock_box_api)
+    await _sync(harness, cm_client, source_id, "full")
+    mock_box_api.collaborations["100"] = [
+        {"status": "accepted", "accessible_by": {"type": "user", "login": "bob@acme.com"}}
+    ]
+    mock_box_api.events = [[_event_payload("COLLABORATION_ACCEPT", "folder", "100")]]
+
+    row = await _sync(harness, cm_client, source_id, "incremental")
+
+    created = await _events(harness, source_id, row, "document_created")
+    permissions = {doc["document_id"]: doc["permissions"]["users"] for doc in created}
+    assert permissions == {
+        "box:file:1": ["alice@acme.com", "bob@acme.com"],
+        "box:file:2": ["alice@acme.com", "bob@acme.com"],
+    }
+
+
+async def test_file_moved_out_of_configured_folder_is_removed(
+    harness, seed, mock_box_server, mock_box_api, cm_client: httpx.AsyncClient
+):
+    source_id = await _folders_source(seed, mock_box_server, mock_box_api, ["200"])
+    _seed_tree(mock_box_api)
+    await _sync(harness, cm_client, source_id, "full")
+    mock_box_api.add_item(_file_payload("2", ROOT))
+    mock_box_api.events = [[_event_payload("MOVE", "file", "2")]]
+
+    row = await _sync(harness, cm_client, source_id, "incremental")
+
+    assert await _documents(harness, source_id, row, "document_deleted") == ["box:file:2"]
+    state = await seed.get_connector_state(source_id)
+    assert state["files"] == {}
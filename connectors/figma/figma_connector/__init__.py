@@ -0,0 +1,6 @@
+"""Figma connector for Omni."""
+
+from .connector import FigmaConnector
+
+__version__ = "1.0.0"
+__all__ = ["FigmaConnector"]
@@ -0,0 +1,165 @@
+"""Thin async wrapper over the Figma REST API with retry logic."""
+
+import asyncio
+import logging
+from functools import wraps
+from typing import Any
+
+import httpx
+
+from .config import FIGMA_API_URL
+
+logger = logging.getLogger(__name__)
+
+
+class FigmaError(Exception):
+    """Base exception for Figma API errors."""
+
+    def __init__(self, message: str, status_code: int | None = None):
+        super().__init__(message)
+        self.status_code = status_code
+
+
+class AuthenticationError(FigmaError):
+    """Invalid or expired token (401)."""
+
+    pass
+
+
+class ForbiddenError(FigmaError):
+    """The token's user cannot access a resource, or the token lacks a scope (403)."""
+
+    pass
+
+
+class NotFoundError(FigmaError):
+    """Team, project or file not found, or not shared with the token's user (404)."""
+
+    pass
+
+
+def with_retry(max_retries: int = 3, base_delay: float = 1.0):
+    """Decorator for retrying Figma API calls with exponential backoff.
+
+    Handles:
+    - 429 Rate Limit: Wait for Retry-After header (unlimited retries)
+    - 5xx Server Error: Exponential backoff (limited retries)
+    - 401/403: Re-raise as AuthenticationError/ForbiddenError (non-retryable)
+    - 404: Re-raise as NotFoundError (non-retryable)
+    """
+
+    def decorator(func):
+        @wraps(func)
+        async def wrapper(*args, **kwargs):
+            last_exception = None
+            error_retries = 0
+
+            while True:
+                try:
+                    return await func(*args, **kwargs)
+                except httpx.HTTPStatusError as e:
+                    last_exception = e
+                    status = e.response.status_code
+
+                    if status == 401:
+                        raise AuthenticationError(
+                            "Invalid or expired Figma token", status_code=401
+                        ) from e
+
+                    if status == 403:
+                        raise ForbiddenError(
+                            f"Forbidden: {e.request.url.path}: {e.response.text}",
+                            status_code=403,
+                        ) from e
+
+                    if status == 404:
+                        raise NotFoundError(
+                            f"Not found: {e.request.url.path}", status_code=404
+                        ) from e
+
+                    if status == 429:
+                        retry_after = int(e.response.headers.get("Retry-After", "10"))
+                        logger.warning("Rate limited. Waiting %ds", retry_after)
+                        await asyncio.sleep(retry_after)
+                        continue
+
+                    if status >= 500:
+                        error_retries += 1
+                        if error_retries > max_retries:
+                            break
+                        delay = base_delay * (2 ** (error_retries - 1))
+                        logger.warning(
+                            "Server error %d. Retrying in %.1fs (%d/%d)",
+                            status,
+                            delay,
+                            error_retries,
+                            max_retries,
+                        )
+                        await asyncio.sleep(delay)
+                        continue
+
+                    raise FigmaError(
+                        f"API error {status}: {e.response.text}",
+                        status_code=status,
+                    ) from e
+
+            raise FigmaError(f"Max retries exceeded: {last_exception}") from last_exception
+
+        return wrapper
+
+    return decorator
+
+
+class FigmaClient:
+    """Async client for the Figma REST API.
+
+    Personal access tokens are sent as `X-Figma-Token`, OAuth access tokens as a bearer token.
+    """
+
+    def __init__(
+        self,
+        token: str,
+        oauth: bool = False,
+        base_url: str | None = None,
+        http_client: httpx.AsyncClient | None = None,
+    ):
+        self._client = http_client or httpx.AsyncClient(
+            base_url=(base_url or FIGMA_API_URL).rstrip("/"),
+            timeout=httpx.Timeout(60.0, connect=10.0),
+        )
+        if oauth:
+            self._client.headers["Authorization"] = f"Bearer {token}"
+        else:
+            self._client.headers["X-Figma-Token"] = token
+
+    async def close(self) -> None:
+        await self._client.aclose()
+
+    @with_retry(max_retries=3)
+    async def get(self, path: str, params: dict[str, Any] | None = None) -> dict[str, Any]:
+        response = await self._client.get(path, params=params)
+        response.raise_for_status()
+        return response.json()
+
+    async def get_me(self) -> dict[str, Any]:
+        """The user the token belongs to."""
+        return await self.get("/v1/me")
+
+    async def get_team_projects(self, team_id: str) -> list[dict[str, Any]]:
+        """The projects of a team the token's user can see."""
+        data = await self.get(f"/v1/teams/{team_id}/projects")
+        return data.get("projects", [])
+
+    async def get_project_files(self, project_id: str) -> list[dict[str, Any]]:
+        """The files of a project, with their key, name and last_modified."""
+        data = await self.get(f"/v1/projects/{project_id}/files")
+        return data.get("files", [])
+
+    async def get_file(self, file_key: str) -> dict[str, Any]:
+        """A file's document tree, without vector geometry."""
+        return await self.get(f"/v1/files/{file_key}")
+
+    async def get_comments(self, file_key: str) -> list[dict[str, Any]]:
+        """Every comment on a file, replies included."""
+        data = await self.get(f"/v1/files/{file_key}/comments")
+        return data.get("comments", [])
@@ -0,0 +1,10 @@
+"""Configuration constants for Figma connector."""
+
+FIGMA_API_URL = "https://api.figma.com"
+FIGMA_APP_URL = "https://www.figma.com"
+
+MAX_CONTENT_LENGTH = 100_000
+CHECKPOINT_INTERVAL = 50
+
+# Node types that hold the layers of a page, shown as sections of a file's content
+FRAME_NODE_TYPES = {"FRAME", "COMPONENT", "COMPONENT_SET", "SECTION"}
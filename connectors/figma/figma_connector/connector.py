@@ -0,0 +1,249 @@
+"""Main FigmaConnector class."""
+
+import logging
+from typing import Any
+
+from omni_connector import Connector, SyncContext
+
+from .client import AuthenticationError, FigmaClient, FigmaError, ForbiddenError
+from .config import CHECKPOINT_INTERVAL
+from .mappers import (
+    file_external_id,
+    generate_file_content,
+    generate_thread_content,
+    group_threads,
+    map_file_to_document,
+    map_thread_to_document,
+    project_groups,
+    thread_activity,
+    thread_external_id,
+)
+
+logger = logging.getLogger(__name__)
+
+
+class FigmaConnector(Connector):
+    """Figma connector for Omni, syncing file text and comment threads."""
+
+    @property
+    def name(self) -> str:
+        return "figma"
+
+    @property
+    def version(self) -> str:
+        return "1.0.0"
+
+    @property
+    def sync_modes(self) -> list[str]:
+        return ["full", "incremental"]
+
+    async def sync(
+        self,
+        source_config: dict[str, Any],
+        credentials: dict[str, Any],
+        state: dict[str, Any] | None,
+        ctx: SyncContext,
+    ) -> None:
+        """
+        Sync the files and comment threads of the configured teams' projects.
+
+        The state keeps, per file, its last modification, the latest comment activity and
+        the comment threads synced. A file is read again only when it changed, and a thread
+        emitted again only when it got a reply or was resolved. Files and threads that are
+        gone are deleted.
+
+        Args:
+            source_config: Must contain 'team_ids'; may set restricted_projects and
+                include_comments
+            credentials: 'token' (personal access token) or 'access_token' (OAuth)
+            state: Previous sync state, {"files": {file_key: {...}}}
+            ctx: Sync context with emit(), complete(), etc.
+        """
+        team_ids: list[str] = [t for t in source_config.get("team_ids") or [] if t.strip()]
+        if not team_ids:
+            await ctx.fail("Missing team_ids in source config")
+            return
+
+        token = credentials.get("token") or credentials.get("access_token")
+        if not token:
+            await ctx.fail("Missing 'token' in credentials")
+            return
+
+        client = FigmaClient(
+            token,
+            oauth="token" not in credentials,
+            base_url=source_config.get("api_url"),
+        )
+        try:
+            try:
+                me = await client.get_me()
+            except (AuthenticationError, ForbiddenError) as e:
+                await ctx.fail(f"Authentication failed: {e}")
+                return
+            except FigmaError as e:
+                await ctx.fail(f"Connection test failed: {e}")
+                return
+
+            logger.info("Starting Figma sync as %s", me.get("handle") or me.get("email"))
+            sync = _Sync(client, source_config, (state or {}).get("files", {}), ctx)
+            try:
+                for team_id in team_ids:
+                    if ctx.is_cancelled():
+                        break
+                    await sync.sync_team(team_id)
+
+                if ctx.is_cancelled():
+                    await ctx.fail("Cancelled by user")
+                    return
+                await sync.delete_missing()
+                await ctx.complete(new_state=sync.state())
+                logger.info(
+                    "Sync completed: %d scanned, %d emitted",
+                    ctx.documents_scanned,
+                    ctx.documents_emitted,
+                )
+            except AuthenticationError as e:
+                logger.error("Authentication error during sync: %s", e)
+                await ctx.fail(f"Authentication failed: {e}")
+            except Exception as e:
+                logger.exception("Sync failed with unexpected error")
+                await ctx.fail(str(e))
+        finally:
+            await client.close()
+
+
+class _Sync:
+    """State of one sync run: the files seen so far and their new state."""
+
+    def __init__(
+        self,
+        client: FigmaClient,
+        source_config: dict[str, Any],
+        previous: dict[str, Any],
+        ctx: SyncContext,
+    ):
+        self.client = client
+        self.ctx = ctx
+        self.previous = previous
+        self.files: dict[str, Any] = {}
+        self.restricted = {str(p) for p in source_config.get("restricted_projects") or []}
+        self.include_comments = source_config.get("include_comments", True)
+        # A listing that failed leaves its files unseen, so nothing is deleted then
+        self.complete_listing = True
+        self._since_checkpoint = 0
+
+    def state(self) -> dict[str, Any]:
+        return {"files": {**self.previous, **self.files}}
+
+    async def sync_team(self, team_id: str) -> None:
+        try:
+            projects = await self.client.get_team_projects(team_id)
+        except AuthenticationError:
+            raise
+        except FigmaError as e:
+            logger.error("Error fetching projects of team %s: %s", team_id, e)
+            await self.ctx.emit_error(f"figma:team:{team_id}", str(e))
+            self.complete_listing = False
+            return
+
+        logger.info("Syncing %d projects of team %s", len(projects), team_id)
+        for project in projects:
+            if self.ctx.is_cancelled():
+                return
+            project_id = str(project["id"])
+            groups = project_groups(team_id, project_id, project_id in self.restricted)
+            try:
+                files = await self.client.get_project_files(project_id)
+            except AuthenticationError:
+                raise
+            except FigmaError as e:
+                logger.error("Error fetching files of project %s: %s", project_id, e)
+                await self.ctx.emit_error(f"figma:project:{project_id}", str(e))
+                self.complete_listing = False
+                continue
+
+            for file in files:
+                if self.ctx.is_cancelled():
+                    return
+                await self.ctx.increment_scanned()
+                try:
+                    await self._sync_file(file, project, team_id, groups)
+                except AuthenticationError:
+                    raise
+                except Exception as e:
+                    logger.warning("Error processing file %s: %s", file.get("key"), e)
+                    await self.ctx.emit_error(file_external_id(file["key"]), str(e))
+                    # Keep the previous state so the file is retried and not deleted
+                    if file["key"] in self.previous:
+                        self.files[file["key"]] = self.previous[file["key"]]
+
+    async def _sync_file(
+        self,
+        file: dict[str, Any],
+        project: dict[str, Any],
+        team_id: str,
+        groups: list[str],
+    ) -> None:
+        key = file["key"]
+        prev = self.previous.get(key, {})
+        regrouped = prev.get("groups") != groups
+        entry: dict[str, Any] = {
+            "last_modified": file.get("last_modified"),
+            "groups": groups,
+            "comments_at": prev.get("comments_at"),
+            "threads": prev.get("threads", []),
+        }
+
+        if regrouped or prev.get("last_modified") != file.get("last_modified"):
+            data = await self.client.get_file(key)
+            content = generate_file_content(
+                file.get("name") or "Untitled", project.get("name") or "", data["document"]
+            )
+            content_id = await self.ctx.content_storage.save(content, "text/plain")
+            await self.ctx.emit(map_file_to_document(file, project, team_id, content_id, groups))
+            await self._checkpoint()
+
+        if self.include_comments:
+            await self._sync_comments(file, entry, regrouped)
+        self.files[key] = entry
+
+    async def _sync_comments(
+        self, file: dict[str, Any], entry: dict[str, Any], regrouped: bool
+    ) -> None:
+        key = file["key"]
+        since = entry["comments_at"]
+        threads = group_threads(await self.client.get_comments(key))
+
+        for thread in threads:
+            activity = thread_activity(thread)
+            if regrouped or not since or activity > since:
+                content = generate_thread_content(file.get("name") or "Untitled", thread)
+                content_id = await self.ctx.content_storage.save(content, "text/plain")
+                await self.ctx.emit(
+                    map_thread_to_document(file, thread, content_id, entry["groups"])
+                )
+                await self._checkpoint()
+            if not entry["comments_at"] or activity > entry["comments_at"]:
+                entry["comments_at"] = activity
+
+        current = [thread[0]["id"] for thread in threads]
+        for comment_id in set(entry["threads"]) - set(current):
+            await self.ctx.emit_deleted(thread_external_id(key, comment_id))
+        entry["threads"] = current
+
+    async def delete_missing(self) -> None:
+        """Delete the files no longer listed, and their threads."""
+        if not self.complete_listing:
+            logger.info("Skipping deletions, some projects could not be listed")
+            return
+        for key in set(self.previous) - set(self.files):
+            await self.ctx.emit_deleted(file_external_id(key))
+            for comment_id in self.previous[key].get("threads", []):
+                await self.ctx.emit_deleted(thread_external_id(key, comment_id))
+        self.previous = {}
+
+    async def _checkpoint(self) -> None:
+        self._since_checkpoint += 1
+        if self._since_checkpoint >= CHECKPOINT_INTERVAL:
+            self._since_checkpoint = 0
+            await self.ctx.save_state(self.state())
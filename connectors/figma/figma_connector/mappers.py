@@ -0,0 +1,224 @@
+"""File and comment-to-Document mapping functions for Figma."""
+
+from datetime import datetime
+from typing import Any
+
+from omni_connector import (
+    Document,
+    DocumentMetadata,
+    DocumentParent,
+    DocumentPermissions,
+    RelationshipType,
+)
+
+from .config import FIGMA_APP_URL, FRAME_NODE_TYPES, MAX_CONTENT_LENGTH
+
+
+def file_external_id(file_key: str) -> str:
+    return f"figma:file:{file_key}"
+
+
+def thread_external_id(file_key: str, comment_id: str) -> str:
+    return f"figma:comment:{file_key}:{comment_id}"
+
+
+def project_groups(team_id: str, project_id: str, restricted: bool) -> list[str]:
+    """
+    Groups that can see a project's files.
+
+    Members of a team see its open projects, so they get the team group as well as the
+    project group. Restricted projects are only visible to their own members.
+    """
+    groups = [f"figma:project:{project_id}"]
+    if not restricted:
+        groups.append(f"figma:team:{team_id}")
+    return groups
+
+
+def parse_timestamp(value: str | None) -> datetime | None:
+    """Parse a Figma ISO 8601 timestamp."""
+    if not value:
+        return None
+    try:
+        return datetime.fromisoformat(value.replace("Z", "+00:00"))
+    except ValueError:
+        return None
+
+
+def _texts(node: dict[str, Any]) -> list[str]:
+    """The text layers of a node and its visible descendants, in layer order."""
+    if node.get("visible") is False:
+        return []
+    if node.get("type") == "TEXT":
+        text = (node.get("characters") or "").strip()
+        return [text] if text else []
+    return [text for child in node.get("children") or [] for text in _texts(child)]
+
+
+def extract_sections(document: dict[str, Any]) -> list[tuple[str, str | None, list[str]]]:
+    """
+    The text of a file by page and top-level frame.
+
+    Returns (page name, frame name, text layers) per frame, and per page for the layers
+    outside any frame, with frame name None.
+    """
+    sections: list[tuple[str, str | None, list[str]]] = []
+    for page in document.get("children") or []:
+        if page.get("type") != "CANVAS":
+            continue
+        page_name = page.get("name") or "Untitled page"
+        loose: list[str] = []
+        frames: list[tuple[str, str | None, list[str]]] = []
+        for node in page.get("children") or []:
+            if node.get("visible") is False:
+                continue
+            if node.get("type") in FRAME_NODE_TYPES:
+                frames.append((page_name, node.get("name") or "Untitled frame", _texts(node)))
+            else:
+                loose.extend(_texts(node))
+        sections.append((page_name, None, loose))
+        sections.extend(frames)
+    return sections
+
+
+def generate_file_content(file_name: str, project_name: str, document: dict[str, Any]) -> str:
+    """Searchable text of a file: its pages, frames and text layers."""
+    lines = [file_name, f"Project: {project_name}"]
+    for page_name, frame_name, texts in extract_sections(document):
+        if frame_name is None:
+            lines.extend(["", f"## {page_name}"])
+        else:
+            lines.extend(["", f"### {frame_name}"])
+        lines.extend(texts)
+    return "\n".join(lines)[:MAX_CONTENT_LENGTH]
+
+
+def map_file_to_document(
+    file: dict[str, Any],
+    project: dict[str, Any],
+    team_id: str,
+    content_id: str,
+    groups: list[str],
+) -> Document:
+    """
+    Map a Figma file to an Omni Document.
+
+    Args:
+        file: Project file listing entry (key, name, last_modified)
+        project: Project the file belongs to (id, name)
+        team_id: Team the project belongs to
+        content_id: ID from content storage
+        groups: Groups that can see the project's files
+
+    Returns:
+        Omni Document instance
+    """
+    key = file["key"]
+    return Document(
+        external_id=file_external_id(key),
+        title=file.get("name") or "Untitled",
+        content_id=content_id,
+        metadata=DocumentMetadata(
+            updated_at=parse_timestamp(file.get("last_modified")),
+            url=f"{FIGMA_APP_URL}/file/{key}",
+            mime_type="text/plain",
+            path=project.get("name"),
+            extra={"file_key": key, "project_id": str(project["id"])},
+        ),
+        permissions=DocumentPermissions(groups=groups),
+        attributes={
+            "source_type": "figma",
+            "record_type": "file",
+            "project": project.get("name"),
+            "team_id": team_id,
+        },
+    )
+
+
+def group_threads(comments: list[dict[str, Any]]) -> list[list[dict[str, Any]]]:
+    """Comments grouped into threads, each a root comment and its replies by creation time."""
+    threads: dict[str, list[dict[str, Any]]] = {}
+    for comment in sorted(comments, key=lambda c: c.get("created_at") or ""):
+        root_id = comment.get("parent_id") or comment["id"]
+        threads.setdefault(root_id, []).append(comment)
+    # Replies whose root was deleted do not make a thread
+    return [thread for root_id, thread in threads.items() if thread[0]["id"] == root_id]
+
+
+def thread_activity(thread: list[dict[str, Any]]) -> str:
+    """When a thread last changed: its latest comment or its resolution."""
+    return max(
+        value
+        for comment in thread
+        for value in (comment.get("created_at"), comment.get("resolved_at"))
+        if value
+    )
+
+
+def _author(comment: dict[str, Any]) -> str:
+    user = comment.get("user") or {}
+    return user.get("handle") or user.get("email") or "Unknown"
+
+
+def generate_thread_content(file_name: str, thread: list[dict[str, Any]]) -> str:
+    """Searchable text of a comment thread, one line per comment."""
+    lines = [f"Comments on {file_name}", ""]
+    for comment in thread:
+        lines.append(f"{_author(comment)}: {(comment.get('message') or '').strip()}")
+    if thread[0].get("resolved_at"):
+        lines.extend(["", "Resolved"])
+    return "\n".join(lines)[:MAX_CONTENT_LENGTH]
+
+
+def map_thread_to_document(
+    file: dict[str, Any],
+    thread: list[dict[str, Any]],
+    content_id: str,
+    groups: list[str],
+) -> Document:
+    """
+    Map a comment thread to an Omni Document, a comment of its file's document.
+
+    Args:
+        file: Project file listing entry the thread is on
+        thread: Root comment followed by its replies
+        content_id: ID from content storage
+        groups: Groups that can see the file
+
+    Returns:
+        Omni Document instance
+    """
+    key = file["key"]
+    root = thread[0]
+    first_line = (root.get("message") or "").strip().splitlines()
+    summary = first_line[0] if first_line else ""
+    if len(summary) > 80:
+        summary = summary[:77] + "..."
+    participants = sorted(
+        {handle for comment in thread if (handle := (comment.get("user") or {}).get("handle"))}
+    )
+
+    return Document(
+        external_id=thread_external_id(key, root["id"]),
+        title=f"Comment on {file.get('name') or 'Untitled'}: {summary}",
+        content_id=content_id,
+        metadata=DocumentMetadata(
+            author=(root.get("user") or {}).get("email") or _author(root),
+            created_at=parse_timestamp(root.get("created_at")),
+            updated_at=parse_timestamp(thread_activity(thread)),
+            url=f"{FIGMA_APP_URL}/file/{key}#{root['id']}",
+            mime_type="text/plain",
+            extra={"file_key": key, "comment_id": root["id"]},
+            parent=DocumentParent(
+                external_id=file_external_id(key),
+                relationship=RelationshipType.COMMENT,
+            ),
+        ),
+        permissions=DocumentPermissions(groups=groups),
+        attributes={
+            "source_type": "figma",
+            "record_type": "comment_thread",
+            "resolved": bool(root.get("resolved_at")),
+            "participants": participants,
+        },
+    )
@@ -0,0 +1,259 @@
+"""Integration test fixtures for the Figma connector.
+
+Session-scoped: harness, mock Figma API server, connector server, connector-manager.
+Function-scoped: seed helper, source_id, httpx client.
+"""
+
+from __future__ import annotations
+
+import logging
+import socket
+import threading
+import time
+from typing import Any
+
+import httpx
+import pytest
+import pytest_asyncio
+import uvicorn
+from starlette.applications import Starlette
+from starlette.requests import Request
+from starlette.responses import JSONResponse
+from starlette.routing import Route
+
+from omni_connector.testing import OmniTestHarness, SeedHelper
+
+logger = logging.getLogger(__name__)
+
+TEAM_ID = "team-1"
+
+
+# ---------------------------------------------------------------------------
+# Mock data payload helpers
+# ---------------------------------------------------------------------------
+
+
+def _file_payload(key: str, name: str, last_modified: str) -> dict[str, Any]:
+    return {
+        "key": key,
+        "name": name,
+        "thumbnail_url": f"https://figma.example.com/thumbnails/{key}.png",
+        "last_modified": last_modified,
+    }
+
+
+def _comment_payload(
+    comment_id: str,
+    message: str,
+    created_at: str,
+    parent_id: str = "",
+    resolved_at: str | None = None,
+) -> dict[str, Any]:
+    return {
+        "id": comment_id,
+        "parent_id": parent_id,
+        "message": message,
+        "created_at": created_at,
+        "resolved_at": resolved_at,
+        "user": {"id": "user-001", "handle": "alice"},
+    }
+
+
+# ---------------------------------------------------------------------------
+# Mock Figma API
+# ---------------------------------------------------------------------------
+
+
+class MockFigmaAPI:
+    """Controllable mock of the Figma REST API v1 endpoints."""
+
+    def __init__(self) -> None:
+        self.projects: dict[str, list[dict[str, Any]]] = {}
+        self.files: dict[str, list[dict[str, Any]]] = {}
+        self.comments: dict[str, list[dict[str, Any]]] = {}
+        self.fetched: list[str] = []
+
+    def reset(self) -> None:
+        self.projects.clear()
+        self.files.clear()
+        self.comments.clear()
+        self.fetched.clear()
+
+    def add_project(self, team_id: str, project_id: str, name: str) -> None:
+        self.projects.setdefault(team_id, []).append({"id": project_id, "name": name})
+        self.files.setdefault(project_id, [])
+
+    def add_file(self, project_id: str, file: dict[str, Any]) -> None:
+        self.files[project_id].append(file)
+        self.comments.setdefault(file["key"], [])
+
+    def create_app(self) -> Starlette:
+        mock = self
+
+        async def get_me(request: Request) -> JSONResponse:
+            return JSONResponse(
+                {"id": "user-001", "handle": "alice", "email": "alice@example.com"}
+            )
+
+        async def team_projects(request: Request) -> JSONResponse:
+            team_id = request.path_params["team_id"]
+            return JSONResponse({"name": "Design", "projects": mock.projects.get(team_id, [])})
+
+        async def project_files(request: Request) -> JSONResponse:
+            project_id = request.path_params["project_id"]
+            return JSONResponse({"name": "Project", "files": mock.files.get(project_id, [])})
+
+        async def get_file(request: Request) -> JSONResponse:
+            file_key = request.path_params["file_key"]
+            mock.fetched.append(file_key)
+            return JSONResponse(
+                {
+                    "name": file_key,
+                    "document": {"id": "0:0", "type": "DOCUMENT", "children": []},
+                }
+            )
+
+        async def file_comments(request: Request) -> JSONResponse:
+            file_key = request.path_params["file_key"]
+            return JSONResponse({"comments": mock.comments.get(file_key, [])})
+
+        routes = [
+            Route("/v1/me", get_me),
+            Route("/v1/teams/{team_id}/projects", team_projects),
+            Route("/v1/projects/{project_id}/files", project_files),
+            Route("/v1/files/{file_key}/comments", file_comments),
+            Route("/v1/files/{file_key}", get_file),
+        ]
+        return Starlette(routes=routes)
+
+
+# ---------------------------------------------------------------------------
+# Helpers
+# ---------------------------------------------------------------------------
+
+
+def _free_port() -> int:
+    with socket.socket(socket.AF_INET, socket.SOCK_STREAM) as s:
+        s.bind(("", 0))
+        return s.getsockname()[1]
+
+
+def _wait_for_port(port: int, host: str = "localhost", timeout: float = 10) -> None:
+    deadline = time.monotonic() + timeout
+    while time.monotonic() < deadline:
+        try:
+            with socket.create_connection((host, port), timeout=1):
+                return
+        except OSError:
+            time.sleep(0.1)
+    raise TimeoutError(f"Port {port} not open after {timeout}s")
+
+
+# ---------------------------------------------------------------------------
+# Session-scoped fixtures
+# ---------------------------------------------------------------------------
+
+
+@pytest.fixture(scope="session")
+def mock_figma_api() -> MockFigmaAPI:
+    return MockFigmaAPI()
+
+
+@pytest.fixture(scope="session")
+def mock_figma_server(mock_figma_api: MockFigmaAPI) -> str:
+    """Start mock Figma API server in a daemon thread. Returns base URL."""
+    port = _free_port()
+    app = mock_figma_api.create_app()
+    config = uvicorn.Config(app, host="0.0.0.0", port=port, log_level="warning")
+    server = uvicorn.Server(config)
+
+    thread = threading.Thread(target=server.run, daemon=True)
+    thread.start()
+
+    _wait_for_port(port)
+    return f"http://localhost:{port}"
+
+
+@pytest.fixture(scope="session")
+def connector_port() -> int:
+    return _free_port()
+
+
+@pytest.fixture(scope="session")
+def connector_server(connector_port: int) -> str:
+    """Start the Figma connector as a uvicorn server in a daemon thread. Returns base URL."""
+    import os
+
+    os.environ.setdefault("CONNECTOR_MANAGER_URL", "http://localhost:0")
+
+    from figma_connector import FigmaConnector
+    from omni_connector.server import create_app
+
+    app = create_app(FigmaConnector())
+    config = uvicorn.Config(
+        app, host="0.0.0.0", port=connector_port, log_level="warning"
+    )
+    server = uvicorn.Server(config)
+
+    thread = threading.Thread(target=server.run, daemon=True)
+    thread.start()
+
+    _wait_for_port(connector_port)
+    return f"http://localhost:{connector_port}"
+
+
+@pytest_asyncio.fixture(scope="session")
+async def harness(
+    connector_server: str,
+    connector_port: int,
+) -> OmniTestHarness:
+    """Session-scoped OmniTestHarness with all infrastructure started."""
+    import os
+
+    h = OmniTestHarness()
+    await h.start_infra()
+    await h.start_connector_manager(
+        {
+            "FIGMA_CONNECTOR_URL": f"http://host.docker.internal:{connector_port}",
+        }
+    )
+
+    os.environ["CONNECTOR_MANAGER_URL"] = h.connector_manager_url
+
+    yield h
+    await h.teardown()
+
+
+# ---------------------------------------------------------------------------
+# Function-scoped fixtures
+# ---------------------------------------------------------------------------
+
+
+@pytest_asyncio.fixture
+async def seed(harness: OmniTestHarness) -> SeedHelper:
+    return harness.seed()
+
+
+@pytest_asyncio.fixture
+async def source_id(
+    seed: SeedHelper,
+    mock_figma_server: str,
+    mock_figma_api: MockFigmaAPI,
+) -> str:
+    """Create a Figma source with credentials pointing to the mock server."""
+    mock_figma_api.reset()
+    sid = await seed.create_source(
+        source_type="figma",
+        config={"api_url": mock_figma_server, "team_ids": [TEAM_ID]},
+    )
+    await seed.create_credentials(sid, {"token": "test-token"}, provider="figma")
+    return sid
+
+
+@pytest_asyncio.fixture
+async def cm_client(harness: OmniTestHarness) -> httpx.AsyncClient:
+    """Async httpx client pointed at the connector-manager."""
+    async with httpx.AsyncClient(
+        base_url=harness.connector_manager_url, timeout=30
+    ) as client:
+        yield client
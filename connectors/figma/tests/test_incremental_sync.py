@@ -0,0 +1,92 @@
+"""Integration tests: unchanged files and threads are skipped, removed ones deleted."""
+
+import httpx
+import pytest
+
+from omni_connector.testing import get_events, wait_for_sync
+
+from .conftest import TEAM_ID, _comment_payload, _file_payload
+
+pytestmark = pytest.mark.integration
+
+FILE_KEY = "abc"
+
+
+async def _sync(harness, cm_client: httpx.AsyncClient, source_id: str, sync_type: str):
+    resp = await cm_client.post(
+        "/sync",
+        json={"source_id": source_id, "sync_type": sync_type},
+    )
+    assert resp.status_code == 200, resp.text
+    row = await wait_for_sync(harness.db_pool, resp.json()["sync_run_id"], timeout=30)
+    assert (
+        row["status"] == "completed"
+    ), f"Sync ended with status={row['status']}, error={row.get('error_message')}"
+    return row
+
+
+async def _documents(harness, source_id: str, row, event_type: str) -> list[str]:
+    events = await get_events(harness.db_pool, source_id)
+    return [
+        e["payload"]["document_id"]
+        for e in events
+        if e["sync_run_id"] == row["id"] and e["event_type"] == event_type
+    ]
+
+
+def _seed_file(mock_figma_api) -> None:
+    mock_figma_api.add_project(TEAM_ID, "42", "Payments")
+    mock_figma_api.add_file("42", _file_payload(FILE_KEY, "Checkout", "2024-05-02T10:00:00Z"))
+    mock_figma_api.comments[FILE_KEY] = [
+        _comment_payload("1", "Why two buttons?", "2024-05-02T09:00:00Z")
+    ]
+
+
+async def test_unchanged_files_and_threads_are_not_emitted_again(
+    harness, source_id, mock_figma_api, cm_client: httpx.AsyncClient
+):
+    _seed_file(mock_figma_api)
+
+    row = await _sync(harness, cm_client, source_id, "full")
+    assert await _documents(harness, source_id, row, "document_created") == [
+        "figma:file:abc",
+        "figma:comment:abc:1",
+    ]
+
+    row = await _sync(harness, cm_client, source_id, "incremental")
+    assert await _documents(harness, source_id, row, "document_created") == []
+    assert mock_figma_api.fetched == [FILE_KEY]
+
+    mock_figma_api.comments[FILE_KEY] = [
+        _comment_payload(
+            "1",
+            "Why two buttons?",
+            "2024-05-02T09:00:00Z",
+            resolved_at="2024-05-05T09:00:00Z",
+        ),
+        _comment_payload("2", "Can we align these?", "2024-05-04T09:00:00Z"),
+    ]
+    row = await _sync(harness, cm_client, source_id, "incremental")
+    assert await _documents(harness, source_id, row, "document_created") == [
+        "figma:comment:abc:1",
+        "figma:comment:abc:2",
+    ]
+
+
+async def test_removed_files_and_threads_are_deleted(
+    harness, seed, source_id, mock_figma_api, cm_client: httpx.AsyncClient
+):
+    _seed_file(mock_figma_api)
+    await _sync(harness, cm_client, source_id, "full")
+
+    mock_figma_api.comments[FILE_KEY] = []
+    row = await _sync(harness, cm_client, source_id, "incremental")
+    assert await _documents(harness, source_id, row, "document_deleted") == [
+        "figma:comment:abc:1"
+    ]
+
+    mock_figma_api.files["42"] = []
+    row = await _sync(harness, cm_client, source_id, "incremental")
+    assert await _documents(harness, source_id, row, "document_deleted") == ["figma:file:abc"]
+    state = await seed.get_connector_state(source_id)
+    assert state["files"] == {}
@@ -0,0 +1,119 @@
+"""Tests for Figma file and comment mapping."""
+
+from figma_connector.mappers import (
+    extract_sections,
+    generate_file_content,
+    generate_thread_content,
+    group_threads,
+    map_file_to_document,
+    map_thread_to_document,
+    project_groups,
+)
+
+DOCUMENT = {
+    "type": "DOCUMENT",
+    "children": [
+        {
+            "type": "CANVAS",
+            "name": "Checkout",
+            "children": [
+                {"type": "TEXT", "name": "Note", "characters": "Decision: one-page checkout"},
+                {
+                    "type": "FRAME",
+                    "name": "Payment",
+                    "children": [
+                        {"type": "TEXT", "characters": "Pay with card"},
+                        {
+                            "type": "GROUP",
+                            "children": [{"type": "TEXT", "characters": "Apple Pay"}],
+                        },
+                        {"type": "TEXT", "characters": "Old copy", "visible": False},
+                    ],
+                },
+                {"type": "FRAME", "name": "Draft", "visible": False, "children": []},
+            ],
+        }
+    ],
+}
+
+FILE = {"key": "abc123", "name": "Checkout flow", "last_modified": "2024-05-02T10:00:00Z"}
+PROJECT = {"id": 42, "name": "Payments"}
+
+
+def _comment(comment_id, message, created_at, parent_id="", resolved_at=None, handle="alice"):
+    return {
+        "id": comment_id,
+        "parent_id": parent_id,
+        "message": message,
+        "created_at": created_at,
+        "resolved_at": resolved_at,
+        "user": {"handle": handle, "email": f"{handle}@example.com"},
+    }
+
+
+class TestFileMapping:
+    """Tests for file text extraction and mapping."""
+
+    def test_extract_sections_by_page_and_frame(self):
+        assert extract_sections(DOCUMENT) == [
+            ("Checkout", None, ["Decision: one-page checkout"]),
+            ("Checkout", "Payment", ["Pay with card", "Apple Pay"]),
+        ]
+
+    def test_file_content(self):
+        content = generate_file_content("Checkout flow", "Payments", DOCUMENT)
+        assert content.startswith("Checkout flow\nProject: Payments")
+        assert "## Checkout\nDecision: one-page checkout" in content
+        assert "### Payment\nPay with card\nApple Pay" in content
+        assert "Old copy" not in content
+
+    def test_file_document(self):
+        groups = project_groups("team-1", "42", restricted=False)
+        doc = map_file_to_document(FILE, PROJECT, "team-1", "content-1", groups)
+
+        assert doc.external_id == "figma:file:abc123"
+        assert doc.title == "Checkout flow"
+        assert doc.metadata.url == "https://www.figma.com/file/abc123"
+        assert doc.permissions.public is False
+        assert doc.permissions.groups == ["figma:project:42", "figma:team:team-1"]
+        assert doc.attributes["project"] == "Payments"
+
+    def test_restricted_project_is_not_shared_with_the_team(self):
+        assert project_groups("team-1", "42", restricted=True) == ["figma:project:42"]
+
+
+class TestCommentThreads:
+    """Tests for comment thread grouping and mapping."""
+
+    def test_group_threads(self):
+        comments = [
+            _comment("2", "Agreed", "2024-05-03T09:00:00Z", parent_id="1", handle="bob"),
+            _comment("1", "Should we drop the address step?", "2024-05-02T09:00:00Z"),
+            _comment("4", "Orphaned reply", "2024-05-03T09:00:00Z", parent_id="3"),
+        ]
+        threads = group_threads(comments)
+        assert [[c["id"] for c in thread] for thread in threads] == [["1", "2"]]
+
+    def test_thread_document(self):
+        thread = [
+            _comment(
+                "1",
+                "Should we drop the address step?",
+                "2024-05-02T09:00:00Z",
+                resolved_at="2024-05-04T12:00:00Z",
+            ),
+            _comment("2", "Agreed", "2024-05-03T09:00:00Z", parent_id="1", handle="bob"),
+        ]
+        doc = map_thread_to_document(FILE, thread, "content-2", ["figma:project:42"])
+
+        assert doc.external_id == "figma:comment:abc123:1"
+        assert doc.title == "Comment on Checkout flow: Should we drop the address step?"
+        assert doc.metadata.author == "alice@example.com"
+        assert doc.metadata.updated_at.isoformat() == "2024-05-04T12:00:00+00:00"
+        assert doc.metadata.parent.external_id == "figma:file:abc123"
+        assert doc.attributes["resolved"] is True
+        assert doc.attributes["participants"] == ["alice", "bob"]
+
+        content = generate_thread_content("Checkout flow", thread)
+        assert "alice: Should we drop the address step?\nbob: Agreed" in content
+        assert content.endswith("Resolved")
@@ -67,6 +67,8 @@ impl FileSystemFile {
             url: None,
             path: Some(self.path.to_string_lossy().to_string()),
             extra: Some(extra),
+            thumbnail: None,
+            parent: None,
         };
 
         // For filesystem, we'll use basic read permissions
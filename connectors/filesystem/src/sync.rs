@@ -180,7 +180,7 @@ impl FileSystemSyncManager {
                             continue;
                         }
                     };
-                    if let Err(e) = Self::perform_full_scan(&scanner, &pool, &event_queue, &source_id, &content_storage).await {
+                    if let Err(e) = Self::perform_full_scan(&scanner, &pool, &source_id, &content_storage).await {
                         error!("Full scan failed for source {}: {}", source.name, e);
                     }
                 }
@@ -201,7 +201,6 @@ impl FileSystemSyncManager {
     async fn perform_full_scan(
         scanner: &FileSystemScanner,
         pool: &PgPool,
-        event_queue: &EventQueue,
         source_id: &str,
         content_storage: &Arc<dyn ObjectStorage>,
     ) -> Result<()> {
@@ -248,29 +247,22 @@ impl FileSystemSyncManager {
                 let event =
                     file.to_connector_event(sync_run.id.clone(), source_id.to_string(), content_id);
 
-                // Queue the event
-                if let Err(e) = event_queue.enqueue(source_id, &event).await {
+                // Queue the event together with the scanned count it adds
+                let mut tx = pool.begin().await?;
+                if let Err(e) = EventQueue::stage(&mut tx, source_id, &event).await {
                     error!("Failed to queue event for {}: {}", file_path.display(), e);
                     continue;
                 }
+                SyncRunRepository::record_activity_in(&mut tx, &sync_run.id, 1).await?;
+                tx.commit().await?;
 
                 files_processed += 1;
 
                 if files_processed % 100 == 0 {
                     info!("Processed {} files", files_processed);
-                    // Update scanned count in batches
-                    sync_run_repo.increment_scanned(&sync_run.id, 100).await?;
                 }
             }
 
-            // Update remaining count (files_processed % 100)
-            let remaining = files_processed % 100;
-            if remaining > 0 {
-                sync_run_repo
-                    .increment_scanned(&sync_run.id, remaining as i32)
-                    .await?;
-            }
-
             info!(
                 "Completed full scan for source_id: {}, processed {} files",
                 source_id, files_processed
@@ -348,6 +348,8 @@ impl FileSystemEventProcessor {
                     url: None,
                     path: Some(file.path.to_string_lossy().to_string()),
                     extra: None,
+                    thumbnail: None,
+                    parent: None,
                 },
                 permissions: Some(DocumentPermissions {
                     public: false,
@@ -8,13 +8,13 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use shared::models::SyncRequest;
-use shared::telemetry;
+use shared::models::{ActionRequest, SyncRequest};
+use shared::{error_reporting, telemetry};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tower::ServiceBuilder;
 use tower_http::cors::CorsLayer;
-use tracing::{error, info};
+use tracing::{error, info, Instrument};
 
 use crate::sync::SyncManager;
 
@@ -56,13 +56,6 @@ pub struct CancelResponse {
     pub status: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ActionRequest {
-    pub action: String,
-    pub params: serde_json::Value,
-    pub credentials: serde_json::Value,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActionResponse {
     pub status: String,
@@ -115,12 +108,16 @@ async fn trigger_sync(
 
     let sync_manager = state.sync_manager.clone();
 
-    tokio::spawn(async move {
-        let mut manager = sync_manager.lock().await;
-        if let Err(e) = manager.sync_source(request).await {
-            error!("Sync {} failed: {}", sync_run_id, e);
+    let span = error_reporting::sync_span(&source_id, request.source_type, &sync_run_id);
+    tokio::spawn(
+        async move {
+            let mut manager = sync_manager.lock().await;
+            if let Err(e) = manager.sync_source(request).await {
+                error!("Sync {} failed: {}", sync_run_id, e);
+            }
         }
-    });
+        .instrument(span),
+    );
 
     Ok(Json(SyncResponse::started()))
 }
@@ -11,6 +11,7 @@ mod config;
 mod models;
 mod sync;
 
+use shared::shutdown::Shutdown;
 use shared::SdkClient;
 
 use api::{create_router, ApiState};
@@ -26,7 +27,7 @@ async fn main() -> Result<()> {
     info!("Starting Fireflies Connector");
 
     let sdk_client = SdkClient::from_env()?;
-    let sync_manager = Arc::new(Mutex::new(SyncManager::new(sdk_client)));
+    let sync_manager = Arc::new(Mutex::new(SyncManager::new(sdk_client.clone())));
 
     let api_state = ApiState {
         sync_manager: Arc::clone(&sync_manager),
@@ -39,9 +40,12 @@ async fn main() -> Result<()> {
 
     info!("HTTP server listening on {}", addr);
 
-    if let Err(e) = axum::serve(listener, app).await {
+    if let Err(e) = shared::tls::serve(listener, app, Shutdown::on_signal()).await {
         error!("HTTP server stopped: {:?}", e);
     }
 
+    // Syncs cut short by the shutdown are resumed once the connector is back
+    sdk_client.pause_active_syncs().await;
+
     Ok(())
 }
@@ -1,6 +1,7 @@
 use serde::Deserialize;
 use serde_json::json;
-use shared::models::{ConnectorEvent, DocumentMetadata, DocumentPermissions};
+use shared::models::{ConnectorEvent, DocumentAttributes, DocumentMetadata, DocumentPermissions};
+use shared::utils::offset_datetime_from_unix_millis;
 use std::collections::HashMap;
 use time::OffsetDateTime;
 
@@ -140,6 +141,40 @@ impl Transcript {
         content.trim().to_string()
     }
 
+    /// Attributes meeting searches filter on: the meeting's `date` (YYYY-MM-DD), its
+    /// `attendees` by email and the names of its `speakers`.
+    pub fn attributes(&self, date: Option<OffsetDateTime>) -> DocumentAttributes {
+        let mut attrs = HashMap::new();
+        if let Some(date) = date {
+            attrs.insert("date".to_string(), json!(date.date().to_string()));
+        }
+        let mut attendees: Vec<String> = self
+            .participants
+            .iter()
+            .flatten()
+            .chain(self.organizer_email.iter())
+            .map(|email| email.trim().to_lowercase())
+            .filter(|email| !email.is_empty())
+            .collect();
+        attendees.sort();
+        attendees.dedup();
+        if !attendees.is_empty() {
+            attrs.insert("attendees".to_string(), json!(attendees));
+        }
+        let mut speakers: Vec<&str> = Vec::new();
+        for sentence in self.sentences.iter().flatten() {
+            if let Some(speaker) = sentence.speaker_name.as_deref().map(str::trim) {
+                if !speaker.is_empty() && !speakers.contains(&speaker) {
+                    speakers.push(speaker);
+                }
+            }
+        }
+        if !speakers.is_empty() {
+            attrs.insert("speakers".to_string(), json!(speakers));
+        }
+        attrs
+    }
+
     pub fn to_connector_event(
         &self,
         sync_run_id: String,
@@ -162,11 +197,11 @@ impl Transcript {
             .unwrap_or_else(|| "Untitled Meeting".to_string());
 
         let created_at = self.date.as_ref().and_then(|d| match d {
-            DateValue::Timestamp(ms) => OffsetDateTime::from_unix_timestamp(ms / 1000).ok(),
+            DateValue::Timestamp(ms) => offset_datetime_from_unix_millis(*ms),
             DateValue::Text(s) => s
                 .parse::<i64>()
                 .ok()
-                .and_then(|ms| OffsetDateTime::from_unix_timestamp(ms / 1000).ok())
+                .and_then(offset_datetime_from_unix_millis)
                 .or_else(|| {
                     OffsetDateTime::parse(s, &time::format_description::well_known::Rfc3339).ok()
                 }),
@@ -202,6 +237,8 @@ impl Transcript {
             url,
             path: None,
             extra: Some(extra),
+            thumbnail: None,
+            parent: None,
         };
 
         let permissions = DocumentPermissions {
@@ -215,9 +252,9 @@ impl Transcript {
             source_id,
             document_id,
             content_id,
+            attributes: Some(self.attributes(created_at)),
             metadata,
             permissions,
-            attributes: None,
         }
     }
 }
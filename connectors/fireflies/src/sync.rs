@@ -41,6 +41,7 @@ impl SyncManager {
             "Starting sync for source: {} (sync_run_id: {})",
             source_id, sync_run_id
         );
+        self.sdk_client.start_sync(&request);
 
         let source = self
             .sdk_client
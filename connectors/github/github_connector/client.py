@@ -1,5 +1,6 @@
 """GitHub API client wrapper using githubkit."""
 
+import base64
 import logging
 from collections.abc import AsyncIterator
 from typing import Any
@@ -101,13 +102,59 @@ class GitHubClient:
             )
             content = resp.parsed_data.content
             if content:
-                import base64
-
                 return base64.b64decode(content).decode("utf-8", errors="replace")
             return None
         except RequestFailed:
             return None
 
+    async def get_tree(
+        self, owner: str, repo: str, ref: str, etag: str | None = None
+    ) -> tuple[list[dict[str, Any]] | None, str | None]:
+        """
+        List the files of a branch, recursively, with their blob SHAs.
+
+        Sends `etag` as If-None-Match, so an unchanged branch costs no rate limit.
+        Returns the files, None when the branch is unchanged since `etag`, and the
+        ETag of the response.
+        """
+        headers = {"If-None-Match": etag} if etag else None
+        try:
+            resp = await self._github.arequest(
+                "GET",
+                f"/repos/{owner}/{repo}/git/trees/{ref}",
+                params={"recursive": "1"},
+                headers=headers,
+            )
+        except RequestFailed as e:
+            # 409 is returned for an empty repository
+            if e.response.status_code in (404, 409):
+                return [], None
+            raise GitHubError(f"Failed to get tree for {owner}/{repo}: {e}") from e
+
+        if resp.status_code == 304:
+            return None, etag
+        data = resp.json()
+        if data.get("truncated"):
+            logger.warning(
+                "Tree of %s/%s is truncated, some files are skipped", owner, repo
+            )
+        files = [entry for entry in data.get("tree", []) if entry.get("type") == "blob"]
+        return files, resp.headers.get("ETag")
+
+    async def get_blob_text(self, owner: str, repo: str, sha: str) -> str:
+        """Get the content of a file by its blob SHA."""
+        try:
+            resp = await self._github.rest.git.async_get_blob(
+                owner=owner, repo=repo, file_sha=sha
+            )
+        except RequestFailed as e:
+            raise GitHubError(
+                f"Failed to get blob {sha} of {owner}/{repo}: {e}"
+            ) from e
+        return base64.b64decode(resp.parsed_data.content).decode(
+            "utf-8", errors="replace"
+        )
+
     async def list_issues(
         self, owner: str, repo: str, since: str | None = None
     ) -> AsyncIterator[Any]:
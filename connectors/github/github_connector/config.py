@@ -4,6 +4,8 @@ MAX_COMMENT_COUNT = 100
 MAX_CONTENT_LENGTH = 100_000
 ITEMS_PER_PAGE = 100
 CHECKPOINT_INTERVAL = 50
+MARKDOWN_EXTENSIONS = (".md", ".mdx", ".markdown")
+MAX_MARKDOWN_FILE_SIZE = 1_000_000
 
 DISCUSSIONS_QUERY = """
 query($owner: String!, $name: String!, $cursor: String) {
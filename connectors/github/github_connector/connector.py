@@ -6,13 +6,15 @@ from typing import Any
 from omni_connector import Connector, SyncContext
 
 from .client import AuthenticationError, GitHubClient, GitHubError
-from .config import CHECKPOINT_INTERVAL
+from .config import CHECKPOINT_INTERVAL, MARKDOWN_EXTENSIONS, MAX_MARKDOWN_FILE_SIZE
 from .mappers import (
     generate_discussion_content,
+    generate_file_content,
     generate_issue_content,
     generate_pr_content,
     generate_repo_content,
     map_discussion_to_document,
+    map_file_to_document,
     map_issue_to_document,
     map_pr_to_document,
     map_repo_to_document,
@@ -51,6 +53,7 @@ class GitHubConnector(Connector):
         api_url = source_config.get("api_url")
         include_discussions = source_config.get("include_discussions", True)
         include_forks = source_config.get("include_forks", False)
+        include_markdown_files = source_config.get("include_markdown_files", True)
 
         client = GitHubClient(token=token, base_url=api_url)
 
@@ -85,7 +88,7 @@ class GitHubConnector(Connector):
                 prev = repo_states.get(full_name, {})
                 owner, name = full_name.split("/", 1)
 
-                new_state_entry: dict[str, str] = {}
+                new_state_entry: dict[str, Any] = {}
 
                 # Sync repo document
                 docs_since_checkpoint = await self._sync_repo(
@@ -98,6 +101,19 @@ class GitHubConnector(Connector):
                     new_repo_states,
                 )
 
+                # Sync markdown files
+                if include_markdown_files:
+                    docs_since_checkpoint = await self._sync_markdown_files(
+                        client,
+                        repo,
+                        owner,
+                        name,
+                        prev,
+                        new_state_entry,
+                        ctx,
+                        docs_since_checkpoint,
+                    )
+
                 # Sync issues
                 since_issues = prev.get("issues_updated_at")
                 latest_issue_ts = since_issues
@@ -270,6 +286,80 @@ class GitHubConnector(Connector):
             await ctx.emit_error(eid, str(e))
         return docs_since_checkpoint
 
+    async def _sync_markdown_files(
+        self,
+        client: GitHubClient,
+        repo: Any,
+        owner: str,
+        name: str,
+        prev: dict[str, Any],
+        new_state_entry: dict[str, Any],
+        ctx: SyncContext,
+        docs_since_checkpoint: int,
+    ) -> int:
+        """
+        Sync the markdown files of a repository's default branch.
+
+        The branch's tree is requested with the ETag of the last sync, and only the
+        files whose blob SHA changed are fetched. Files no longer in the tree are
+        deleted. Returns updated docs_since_checkpoint.
+        """
+        full_name = repo.full_name
+        prev_files: dict[str, str] = prev.get("files", {})
+        try:
+            tree, etag = await client.get_tree(
+                owner, name, repo.default_branch, prev.get("files_etag")
+            )
+        except GitHubError as e:
+            logger.error("Error fetching files for %s: %s", full_name, e)
+            await ctx.emit_error(f"github:file:{full_name}:*", str(e))
+            new_state_entry["files"] = prev_files
+            return docs_since_checkpoint
+
+        if tree is None:
+            logger.info("Files of %s unchanged since the last sync", full_name)
+            new_state_entry["files"] = prev_files
+            new_state_entry["files_etag"] = etag
+            return docs_since_checkpoint
+
+        files: dict[str, str] = {}
+        failed = False
+        for entry in tree:
+            path = entry["path"]
+            if not path.lower().endswith(MARKDOWN_EXTENSIONS):
+                continue
+            if entry.get("size", 0) > MAX_MARKDOWN_FILE_SIZE:
+                continue
+            if ctx.is_cancelled():
+                return docs_since_checkpoint
+            files[path] = entry["sha"]
+            if prev_files.get(path) == entry["sha"]:
+                continue
+
+            await ctx.increment_scanned()
+            try:
+                text = await client.get_blob_text(owner, name, entry["sha"])
+                content = generate_file_content(repo, path, text)
+                content_id = await ctx.content_storage.save(content, "text/plain")
+                doc = map_file_to_document(repo, path, content_id)
+                await ctx.emit(doc)
+                docs_since_checkpoint += 1
+            except Exception as e:
+                eid = f"github:file:{full_name}:{path}"
+                logger.warning("Error processing %s: %s", eid, e)
+                await ctx.emit_error(eid, str(e))
+                # Fetched again by the next sync
+                files[path] = prev_files.get(path, "")
+                failed = True
+
+        for path in sorted(prev_files.keys() - files.keys()):
+            await ctx.emit_deleted(f"github:file:{full_name}:{path}")
+
+        new_state_entry["files"] = files
+        if etag and not failed:
+            new_state_entry["files_etag"] = etag
+        return docs_since_checkpoint
+
     async def _resolve_repos(
         self,
         client: GitHubClient,
@@ -46,6 +46,33 @@ def map_repo_to_document(
     )
 
 
+def map_file_to_document(
+    repo: Any,
+    path: str,
+    content_id: str,
+) -> Document:
+    """Map a markdown file on a repository's default branch to an Omni Document."""
+    full_name = repo.full_name
+    is_private = repo.private
+
+    return Document(
+        external_id=f"github:file:{full_name}:{path}",
+        title=f"{full_name}/{path}",
+        content_id=content_id,
+        metadata=DocumentMetadata(
+            url=f"{repo.html_url}/blob/{repo.default_branch}/{path}",
+            mime_type="text/plain",
+        ),
+        permissions=_build_permissions(is_private, full_name),
+        attributes={
+            "source_type": "github",
+            "content_type": "file",
+            "path": path,
+            "visibility": "private" if is_private else "public",
+        },
+    )
+
+
 def map_issue_to_document(
     issue: Any,
     comments: list[Any],
@@ -168,6 +195,11 @@ def generate_repo_content(repo: Any, readme_content: str | None) -> str:
     return _truncate("\n".join(lines))
 
 
+def generate_file_content(repo: Any, path: str, text: str) -> str:
+    """Generate searchable text content from a markdown file."""
+    return _truncate(f"File: {repo.full_name}/{path}\n\n{text}")
+
+
 def generate_issue_content(issue: Any, comments: list[Any]) -> str:
     """Generate searchable text content from an issue and its comments."""
     lines: list[str] = []
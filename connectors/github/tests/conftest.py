@@ -7,6 +7,7 @@ Function-scoped: seed helper, source_id, httpx client.
 from __future__ import annotations
 
 import base64
+import hashlib
 import logging
 import socket
 import threading
@@ -19,7 +20,7 @@ import pytest_asyncio
 import uvicorn
 from starlette.applications import Starlette
 from starlette.requests import Request
-from starlette.responses import JSONResponse
+from starlette.responses import JSONResponse, Response
 from starlette.routing import Route
 
 from omni_connector.testing import OmniTestHarness, SeedHelper
@@ -339,6 +340,8 @@ class MockGitHubAPI:
         self.issue_comments: dict[str, list[dict[str, Any]]] = {}
         self.review_comments: dict[str, list[dict[str, Any]]] = {}
         self.readmes: dict[str, str] = {}
+        self.files: dict[str, dict[str, str]] = {}
+        self.blob_requests: list[str] = []
         self.should_fail_auth: bool = False
         self.authenticated_user: str = "testbot"
 
@@ -350,6 +353,8 @@ class MockGitHubAPI:
         self.issue_comments.clear()
         self.review_comments.clear()
         self.readmes.clear()
+        self.files.clear()
+        self.blob_requests.clear()
         self.should_fail_auth = False
 
     def add_repo(
@@ -432,6 +437,9 @@ class MockGitHubAPI:
     def add_readme(self, owner: str, name: str, content: str) -> None:
         self.readmes[f"{owner}/{name}"] = content
 
+    def add_file(self, owner: str, name: str, path: str, content: str) -> None:
+        self.files.setdefault(f"{owner}/{name}", {})[path] = content
+
     def create_app(self) -> Starlette:
         mock = self
 
@@ -480,6 +488,54 @@ class MockGitHubAPI:
                 }
             )
 
+        def _blob_sha(content: str) -> str:
+            return hashlib.sha1(content.encode()).hexdigest()
+
+        async def get_tree(request: Request) -> Response:
+            if mock.should_fail_auth:
+                return JSONResponse({"message": "Bad credentials"}, status_code=401)
+            owner = request.path_params["owner"]
+            repo = request.path_params["repo"]
+            files = mock.files.get(f"{owner}/{repo}", {})
+            tree = [
+                {
+                    "path": path,
+                    "mode": "100644",
+                    "type": "blob",
+                    "sha": _blob_sha(content),
+                    "size": len(content),
+                }
+                for path, content in sorted(files.items())
+            ]
+            etag = f'"{hashlib.sha1(repr(tree).encode()).hexdigest()}"'
+            if request.headers.get("If-None-Match") == etag:
+                return Response(status_code=304, headers={"ETag": etag})
+            return JSONResponse(
+                {"sha": "tree", "tree": tree, "truncated": False},
+                headers={"ETag": etag},
+            )
+
+        async def get_blob(request: Request) -> JSONResponse:
+            if mock.should_fail_auth:
+                return JSONResponse({"message": "Bad credentials"}, status_code=401)
+            owner = request.path_params["owner"]
+            repo = request.path_params["repo"]
+            sha = request.path_params["sha"]
+            mock.blob_requests.append(sha)
+            for content in mock.files.get(f"{owner}/{repo}", {}).values():
+                if _blob_sha(content) == sha:
+                    return JSONResponse(
+                        {
+                            "sha": sha,
+                            "node_id": f"B_{sha}",
+                            "size": len(content),
+                            "url": f"https://api.github.com/repos/{owner}/{repo}/git/blobs/{sha}",
+                            "content": base64.b64encode(content.encode()).decode(),
+                            "encoding": "base64",
+                        }
+                    )
+            return JSONResponse({"message": "Not Found"}, status_code=404)
+
         def _paginated(request: Request, items: list) -> JSONResponse:
             """Return items on page 1, empty list on subsequent pages."""
             page = int(request.query_params.get("page", "1"))
@@ -552,6 +608,8 @@ class MockGitHubAPI:
             Route("/user/repos", list_repos_for_user),
             Route("/repos/{owner}/{repo}", get_repo),
             Route("/repos/{owner}/{repo}/readme", get_readme),
+            Route("/repos/{owner}/{repo}/git/trees/{ref}", get_tree),
+            Route("/repos/{owner}/{repo}/git/blobs/{sha}", get_blob),
             Route("/repos/{owner}/{repo}/issues", list_issues),
             Route(
                 "/repos/{owner}/{repo}/issues/{number}/comments", list_issue_comments
@@ -3,7 +3,7 @@
 import pytest
 import httpx
 
-from omni_connector.testing import count_events, wait_for_sync
+from omni_connector.testing import count_events, get_events, wait_for_sync
 
 pytestmark = pytest.mark.integration
 
@@ -40,3 +40,49 @@ async def test_incremental_sync_after_full(
         f"Incremental sync should produce new events: "
         f"before={full_event_count}, after={total_events}"
     )
+
+
+async def test_incremental_sync_fetches_only_changed_markdown_files(
+    harness, seed, source_id, mock_github_api, cm_client: httpx.AsyncClient
+):
+    mock_github_api.add_repo("octocat", "Hello-World")
+    mock_github_api.add_file("octocat", "Hello-World", "docs/setup.md", "# Setup")
+    mock_github_api.add_file("octocat", "Hello-World", "docs/old.md", "# Old")
+    mock_github_api.add_file("octocat", "Hello-World", "src/main.py", "print()")
+
+    resp = await cm_client.post(
+        "/sync",
+        json={"source_id": source_id, "sync_type": "full"},
+    )
+    await wait_for_sync(harness.db_pool, resp.json()["sync_run_id"], timeout=30)
+    assert len(mock_github_api.blob_requests) == 2
+
+    state = await seed.get_connector_state(source_id)
+    repo_state = state["repos"]["octocat/Hello-World"]
+    assert sorted(repo_state["files"]) == ["docs/old.md", "docs/setup.md"]
+    assert repo_state["files_etag"]
+
+    # Unchanged branch: the tree request is answered with 304 Not Modified
+    resp = await cm_client.post(
+        "/sync",
+        json={"source_id": source_id, "sync_type": "incremental"},
+    )
+    await wait_for_sync(harness.db_pool, resp.json()["sync_run_id"], timeout=30)
+    assert len(mock_github_api.blob_requests) == 2
+
+    mock_github_api.add_file("octocat", "Hello-World", "docs/setup.md", "# Setup v2")
+    del mock_github_api.files["octocat/Hello-World"]["docs/old.md"]
+
+    resp = await cm_client.post(
+        "/sync",
+        json={"source_id": source_id, "sync_type": "incremental"},
+    )
+    row = await wait_for_sync(harness.db_pool, resp.json()["sync_run_id"], timeout=30)
+    assert row["status"] == "completed"
+    assert len(mock_github_api.blob_requests) == 3
+
+    events = await get_events(harness.db_pool, source_id)
+    deleted = [e for e in events if e["event_type"] == "document_deleted"]
+    assert [e["payload"]["document_id"] for e in deleted] == [
+        "github:file:octocat/Hello-World:docs/old.md"
+    ]
@@ -0,0 +1,6 @@
+"""Gong connector for Omni."""
+
+from .connector import GongConnector
+
+__version__ = "1.0.0"
+__all__ = ["GongConnector"]
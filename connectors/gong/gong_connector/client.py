@@ -0,0 +1,201 @@
+"""Thin async wrapper over the Gong API with retry logic."""
+
+import asyncio
+import logging
+import time
+from collections.abc import AsyncIterator
+from functools import wraps
+from typing import Any
+
+import httpx
+
+from .config import GONG_API_URL, MIN_REQUEST_INTERVAL_SECONDS, TRANSCRIPT_BATCH_SIZE
+
+logger = logging.getLogger(__name__)
+
+
+class GongError(Exception):
+    """Base exception for Gong API errors."""
+
+    def __init__(self, message: str, status_code: int | None = None):
+        super().__init__(message)
+        self.status_code = status_code
+
+
+class AuthenticationError(GongError):
+    """Invalid access key or secret (401)."""
+
+    pass
+
+
+class ForbiddenError(GongError):
+    """The key lacks a scope, such as reading call content (403)."""
+
+    pass
+
+
+class NotFoundError(GongError):
+    """Unknown resource, or no calls matching a filter (404)."""
+
+    pass
+
+
+def with_retry(max_retries: int = 3, base_delay: float = 1.0):
+    """Decorator for retrying Gong API calls with exponential backoff.
+
+    Handles:
+    - 429 Rate Limit: Wait for Retry-After header (unlimited retries)
+    - 5xx Server Error: Exponential backoff (limited retries)
+    - 401/403: Re-raise as AuthenticationError/ForbiddenError (non-retryable)
+    - 404: Re-raise as NotFoundError (non-retryable)
+    """
+
+    def decorator(func):
+        @wraps(func)
+        async def wrapper(*args, **kwargs):
+            last_exception = None
+            error_retries = 0
+
+            while True:
+                try:
+                    return await func(*args, **kwargs)
+                except httpx.HTTPStatusError as e:
+                    last_exception = e
+                    status = e.response.status_code
+
+                    if status == 401:
+                        raise AuthenticationError(
+                            "Invalid Gong access key or secret", status_code=401
+                        ) from e
+
+                    if status == 403:
+                        raise ForbiddenError(
+                            f"Forbidden: {e.request.url.path}: {e.response.text}",
+                            status_code=403,
+                        ) from e
+
+                    if status == 404:
+                        raise NotFoundError(
+                            f"Not found: {e.request.url.path}", status_code=404
+                        ) from e
+
+                    if status == 429:
+                        retry_after = int(e.response.headers.get("Retry-After", "10"))
+                        logger.warning("Rate limited. Waiting %ds", retry_after)
+                        await asyncio.sleep(retry_after)
+                        continue
+
+                    if status >= 500:
+                        error_retries += 1
+                        if error_retries > max_retries:
+                            break
+                        delay = base_delay * (2 ** (error_retries - 1))
+                        logger.warning(
+                            "Server error %d. Retrying in %.1fs (%d/%d)",
+                            status,
+                            delay,
+                            error_retries,
+                            max_retries,
+                        )
+                        await asyncio.sleep(delay)
+                        continue
+
+                    raise GongError(
+                        f"API error {status}: {e.response.text}",
+                        status_code=status,
+                    ) from e
+
+            raise GongError(f"Max retries exceeded: {last_exception}") from last_exception
+
+        return wrapper
+
+    return decorator
+
+
+class GongClient:
+    """Async client for the Gong API v2, authenticated with an access key and its secret."""
+
+    def __init__(
+        self,
+        access_key: str,
+        access_key_secret: str,
+        base_url: str | None = None,
+        http_client: httpx.AsyncClient | None = None,
+    ):
+        self._client = http_client or httpx.AsyncClient(
+            base_url=(base_url or GONG_API_URL).rstrip("/"),
+            auth=httpx.BasicAuth(access_key, access_key_secret),
+            timeout=httpx.Timeout(60.0, connect=10.0),
+        )
+        self._last_request = 0.0
+
+    async def close(self) -> None:
+        await self._client.aclose()
+
+    async def _throttle(self) -> None:
+        wait = self._last_request + MIN_REQUEST_INTERVAL_SECONDS - time.monotonic()
+        if wait > 0:
+            await asyncio.sleep(wait)
+        self._last_request = time.monotonic()
+
+    @with_retry(max_retries=3)
+    async def get(self, path: str, params: dict[str, Any] | None = None) -> dict[str, Any]:
+        await self._throttle()
+        response = await self._client.get(path, params=params)
+        response.raise_for_status()
+        return response.json()
+
+    @with_retry(max_retries=3)
+    async def post(self, path: str, body: dict[str, Any]) -> dict[str, Any]:
+        await self._throttle()
+        response = await self._client.post(path, json=body)
+        response.raise_for_status()
+        return response.json()
+
+    async def list_workspaces(self) -> list[dict[str, Any]]:
+        data = await self.get("/v2/workspaces")
+        return data.get("workspaces", [])
+
+    async def list_calls(
+        self,
+        from_date_time: str,
+        to_date_time: str,
+        content_selector: dict[str, Any],
+        workspace_id: str | None = None,
+    ) -> AsyncIterator[list[dict[str, Any]]]:
+        """Pages of the calls started in a time range, with their parties and content."""
+        call_filter: dict[str, Any] = {"fromDateTime": from_date_time, "toDateTime": to_date_time}
+        if workspace_id:
+            call_filter["workspaceId"] = workspace_id
+        body: dict[str, Any] = {"filter": call_filter, "contentSelector": content_selector}
+        while True:
+            try:
+                data = await self.post("/v2/calls/extensive", body)
+            except NotFoundError:
+                # Gong answers 404 when no call matches the filter
+                return
+            yield data.get("calls", [])
+            cursor = (data.get("records") or {}).get("cursor")
+            if not cursor:
+                return
+            body["cursor"] = cursor
+
+    async def get_transcripts(self, call_ids: list[str]) -> dict[str, list[dict[str, Any]]]:
+        """The transcript of each call that has one, as monologues, by call ID."""
+        transcripts: dict[str, list[dict[str, Any]]] = {}
+        for start in range(0, len(call_ids), TRANSCRIPT_BATCH_SIZE):
+            body: dict[str, Any] = {
+                "filter": {"callIds": call_ids[start : start + TRANSCRIPT_BATCH_SIZE]}
+            }
+            while True:
+                try:
+                    data = await self.post("/v2/calls/transcript", body)
+                except NotFoundError:
+                    break
+                for call in data.get("callTranscripts", []):
+                    transcripts[call["callId"]] = call.get("transcript") or []
+                cursor = (data.get("records") or {}).get("cursor")
+                if not cursor:
+                    break
+                body["cursor"] = cursor
+        return transcripts
@@ -0,0 +1,36 @@
+"""Configuration constants for Gong connector."""
+
+# Accounts are served from their own host, shown with their API keys, e.g.
+# https://us-12345.api.gong.io
+GONG_API_URL = "https://api.gong.io"
+
+MAX_CONTENT_LENGTH = 500_000
+# Call IDs per transcript request
+TRANSCRIPT_BATCH_SIZE = 100
+
+# How far back the first sync reads calls, unless configured
+DEFAULT_LOOKBACK_DAYS = 365
+# The first sync reads calls a window at a time, saving its progress after each
+BACKFILL_WINDOW_DAYS = 30
+
+# Gong transcribes and summarizes a call some hours after it ends, and its CRM context can
+# change after that, so every sync reads again the calls of this many past days
+REFRESH_DAYS = 3
+
+# Gong allows 3 requests a second per account
+MIN_REQUEST_INTERVAL_SECONDS = 0.35
+
+# The parts of a call read with its metadata
+CONTENT_SELECTOR = {
+    "context": "Extended",
+    "contextTiming": ["Now"],
+    "exposedFields": {
+        "parties": True,
+        "content": {
+            "brief": True,
+            "keyPoints": True,
+            "highlights": True,
+            "callOutcome": True,
+        },
+    },
+}
@@ -0,0 +1,174 @@
+"""Main GongConnector class."""
+
+import logging
+from datetime import datetime, timedelta, timezone
+from typing import Any
+
+from omni_connector import Connector, SyncContext
+
+from .client import AuthenticationError, ForbiddenError, GongClient, GongError
+from .config import (
+    BACKFILL_WINDOW_DAYS,
+    CONTENT_SELECTOR,
+    DEFAULT_LOOKBACK_DAYS,
+    REFRESH_DAYS,
+)
+from .mappers import (
+    build_permissions,
+    call_external_id,
+    generate_call_content,
+    map_call_to_document,
+    parse_timestamp,
+)
+
+logger = logging.getLogger(__name__)
+
+
+def _format(value: datetime) -> str:
+    return value.astimezone(timezone.utc).strftime("%Y-%m-%dT%H:%M:%SZ")
+
+
+class GongConnector(Connector):
+    """Gong connector for Omni."""
+
+    @property
+    def name(self) -> str:
+        return "gong"
+
+    @property
+    def version(self) -> str:
+        return "1.0.0"
+
+    @property
+    def sync_modes(self) -> list[str]:
+        return ["full", "incremental"]
+
+    async def sync(
+        self,
+        source_config: dict[str, Any],
+        credentials: dict[str, Any],
+        state: dict[str, Any] | None,
+        ctx: SyncContext,
+    ) -> None:
+        """
+        Sync calls, each with its brief, key points, highlights and transcript.
+
+        The first sync reads the calls of the lookback period. Later syncs read the calls
+        started since the previous sync, and those of the few days before it again, as Gong
+        transcribes calls after they end.
+
+        Args:
+            source_config: May set api_url, workspace_id and lookback_days
+            credentials: 'access_key' and 'access_key_secret'
+            state: Previous sync state, {"calls_since": timestamp}, or
+                {"backfill": {"started_at", "calls_since"}} for an interrupted first sync
+            ctx: Sync context with emit(), complete(), etc.
+        """
+        access_key = credentials.get("access_key")
+        access_key_secret = credentials.get("access_key_secret")
+        if not access_key or not access_key_secret:
+            await ctx.fail("Missing 'access_key' or 'access_key_secret' in credentials")
+            return
+
+        client = GongClient(
+            access_key=access_key,
+            access_key_secret=access_key_secret,
+            base_url=source_config.get("api_url"),
+        )
+        try:
+            try:
+                workspaces = await client.list_workspaces()
+                logger.info("Starting Gong sync, %d workspaces", len(workspaces))
+            except (AuthenticationError, ForbiddenError) as e:
+                await ctx.fail(f"Authentication failed: {e}")
+                return
+            except GongError as e:
+                await ctx.fail(f"Connection test failed: {e}")
+                return
+
+            sync = _Sync(client, source_config.get("workspace_id"), ctx)
+            try:
+                state = state or {}
+                now = datetime.now(timezone.utc)
+                if since := parse_timestamp(state.get("calls_since")):
+                    await sync.sync_calls(since, now)
+                    started_at = now
+                else:
+                    backfill = state.get("backfill") or {}
+                    started_at = parse_timestamp(backfill.get("started_at")) or now
+                    lookback = source_config.get("lookback_days") or DEFAULT_LOOKBACK_DAYS
+                    since = parse_timestamp(backfill.get("calls_since")) or (
+                        now - timedelta(days=lookback)
+                    )
+                    await sync.backfill(since, started_at, now)
+
+                if ctx.is_cancelled():
+                    await ctx.fail("Cancelled by user")
+                    return
+                watermark = started_at - timedelta(days=REFRESH_DAYS)
+                await ctx.complete(new_state={"calls_since": _format(watermark)})
+                logger.info(
+                    "Sync completed: %d scanned, %d emitted",
+                    ctx.documents_scanned,
+                    ctx.documents_emitted,
+                )
+            except AuthenticationError as e:
+                logger.error("Authentication error during sync: %s", e)
+                await ctx.fail(f"Authentication failed: {e}")
+            except Exception as e:
+                logger.exception("Sync failed with unexpected error")
+                await ctx.fail(str(e))
+        finally:
+            await client.close()
+
+
+class _Sync:
+    """State of one sync run: the workspace synced."""
+
+    def __init__(self, client: GongClient, workspace_id: str | None, ctx: SyncContext):
+        self.client = client
+        self.workspace_id = workspace_id
+        self.ctx = ctx
+
+    async def backfill(self, since: datetime, started_at: datetime, now: datetime) -> None:
+        """Sync the calls started since a time, a window at a time to resume from."""
+        window_start = since
+        while window_start < now:
+            if self.ctx.is_cancelled():
+                return
+            window_end = min(window_start + timedelta(days=BACKFILL_WINDOW_DAYS), now)
+            await self.sync_calls(window_start, window_end)
+            window_start = window_end
+            await self.ctx.save_state(
+                {
+                    "backfill": {
+                        "started_at": _format(started_at),
+                        "calls_since": _format(window_start),
+                    }
+                }
+            )
+
+    async def sync_calls(self, since: datetime, until: datetime) -> None:
+        """Sync the calls started in a time range."""
+        async for calls in self.client.list_calls(
+            _format(since), _format(until), CONTENT_SELECTOR, self.workspace_id
+        ):
+            if self.ctx.is_cancelled():
+                return
+            call_ids = [call["metaData"]["id"] for call in calls]
+            transcripts = await self.client.get_transcripts(call_ids)
+            for call in calls:
+                await self.sync_call(call, transcripts.get(call["metaData"]["id"], []))
+
+    async def sync_call(self, call: dict[str, Any], transcript: list[dict[str, Any]]) -> None:
+        await self.ctx.increment_scanned()
+        try:
+            content = generate_call_content(call, transcript)
+            content_id = await self.ctx.content_storage.save(content, "text/plain")
+            await self.ctx.emit(map_call_to_document(call, content_id, build_permissions(call)))
+        except AuthenticationError:
+            raise
+        except Exception as e:
+            eid = call_external_id(call["metaData"]["id"])
+            logger.warning("Error processing %s: %s", eid, e)
+            await self.ctx.emit_error(eid, str(e))
@@ -0,0 +1,182 @@
+"""Call-to-Document mapping functions for Gong."""
+
+from datetime import datetime
+from typing import Any
+
+from omni_connector import Document, DocumentMetadata, DocumentPermissions
+
+from .config import MAX_CONTENT_LENGTH
+
+
+def call_external_id(call_id: str) -> str:
+    return f"gong:call:{call_id}"
+
+
+def parse_timestamp(value: str | None) -> datetime | None:
+    """Parse a Gong ISO 8601 timestamp."""
+    if not value:
+        return None
+    try:
+        return datetime.fromisoformat(value.replace("Z", "+00:00"))
+    except ValueError:
+        return None
+
+
+def _crm_field(call: dict[str, Any], object_type: str, field: str) -> str | None:
+    """A field of the first CRM object of a type the call is linked to."""
+    for context in call.get("context") or []:
+        for crm_object in context.get("objects") or []:
+            if (crm_object.get("objectType") or "").lower() != object_type:
+                continue
+            for crm_field in crm_object.get("fields") or []:
+                if crm_field.get("name") == field and crm_field.get("value"):
+                    return str(crm_field["value"])
+    return None
+
+
+def account_name(call: dict[str, Any]) -> str | None:
+    return _crm_field(call, "account", "Name")
+
+
+def deal_name(call: dict[str, Any]) -> str | None:
+    return _crm_field(call, "opportunity", "Name")
+
+
+def deal_stage(call: dict[str, Any]) -> str | None:
+    """Stage of the call's deal, as it is now in the CRM."""
+    return _crm_field(call, "opportunity", "StageName")
+
+
+def _party_name(party: dict[str, Any]) -> str:
+    return party.get("name") or party.get("emailAddress") or "Unknown speaker"
+
+
+def participants(call: dict[str, Any]) -> list[str]:
+    """Names of the call's participants, its own team first."""
+    parties = call.get("parties") or []
+    internal = [p for p in parties if p.get("affiliation") == "Internal"]
+    others = [p for p in parties if p.get("affiliation") != "Internal"]
+    return [_party_name(party) for party in internal + others]
+
+
+def build_permissions(call: dict[str, Any]) -> DocumentPermissions:
+    """
+    Who can see a call.
+
+    Calls are shared with the whole company unless marked private, in which case only the
+    company's own participants can see them.
+    """
+    if not (call.get("metaData") or {}).get("isPrivate"):
+        return DocumentPermissions(public=True)
+    emails = {
+        party["emailAddress"].lower()
+        for party in call.get("parties") or []
+        if party.get("affiliation") == "Internal" and party.get("emailAddress")
+    }
+    return DocumentPermissions(public=False, users=sorted(emails))
+
+
+def _highlights(content: dict[str, Any]) -> list[str]:
+    lines = []
+    for highlight in content.get("highlights") or []:
+        items = [item.get("text") for item in highlight.get("items") or [] if item.get("text")]
+        if items:
+            lines.append(f"{highlight.get('title') or 'Highlights'}:")
+            lines.extend(f"- {item}" for item in items)
+    return lines
+
+
+def generate_call_content(call: dict[str, Any], transcript: list[dict[str, Any]]) -> str:
+    """Searchable text of a call: its summary, highlights and transcript."""
+    meta = call.get("metaData") or {}
+    content = call.get("content") or {}
+    lines = [meta.get("title") or "Untitled call"]
+    for label, value in (
+        ("Date", meta.get("started")),
+        ("Participants", ", ".join(participants(call))),
+        ("Account", account_name(call)),
+        ("Deal", deal_name(call)),
+        ("Deal stage", deal_stage(call)),
+        ("Outcome", (content.get("callOutcome") or {}).get("name")),
+    ):
+        if value:
+            lines.append(f"{label}: {value}")
+
+    if brief := content.get("brief"):
+        lines.extend(["", "--- Brief ---", brief.strip()])
+    key_points = [point.get("text") for point in content.get("keyPoints") or []]
+    if any(key_points):
+        lines.extend(["", "--- Key Points ---"])
+        lines.extend(f"- {point}" for point in key_points if point)
+    if highlights := _highlights(content):
+        lines.extend(["", "--- Highlights ---", *highlights])
+
+    if transcript:
+        speakers = {
+            party["speakerId"]: _party_name(party)
+            for party in call.get("parties") or []
+            if party.get("speakerId")
+        }
+        lines.extend(["", "--- Transcript ---"])
+        for monologue in transcript:
+            speaker = speakers.get(monologue.get("speakerId"), "Unknown speaker")
+            text = " ".join(s.get("text", "") for s in monologue.get("sentences") or [])
+            lines.append(f"{speaker}: {text}")
+    return "\n".join(lines)[:MAX_CONTENT_LENGTH]
+
+
+def map_call_to_document(
+    call: dict[str, Any],
+    content_id: str,
+    permissions: DocumentPermissions,
+) -> Document:
+    """
+    Map a Gong call to an Omni Document.
+
+    Args:
+        call: The call, with its metadata, parties, CRM context and content
+        content_id: ID from content storage, for its summary and transcript
+        permissions: Who can see the call
+
+    Returns:
+        Omni Document instance
+    """
+    meta = call.get("metaData") or {}
+    started = parse_timestamp(meta.get("started"))
+    primary_user = next(
+        (
+            _party_name(party)
+            for party in call.get("parties") or []
+            if party.get("userId") and party.get("userId") == meta.get("primaryUserId")
+        ),
+        None,
+    )
+    return Document(
+        external_id=call_external_id(meta["id"]),
+        title=meta.get("title") or "Untitled call",
+        content_id=content_id,
+        metadata=DocumentMetadata(
+            author=primary_user,
+            created_at=started,
+            updated_at=started,
+            url=meta.get("url"),
+            mime_type="text/plain",
+            path=account_name(call),
+            extra={
+                "call_id": meta["id"],
+                "duration_seconds": meta.get("duration"),
+            },
+        ),
+        permissions=permissions,
+        attributes={
+            "source_type": "gong",
+            "content_type": "call",
+            "account": account_name(call),
+            "deal": deal_name(call),
+            "deal_stage": deal_stage(call),
+            "participants": ", ".join(participants(call)),
+            "direction": meta.get("direction"),
+            "scope": meta.get("scope"),
+            "date": started.date().isoformat() if started else None,
+        },
+    )
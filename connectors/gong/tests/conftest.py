@@ -0,0 +1,260 @@
+"""Integration test fixtures for the Gong connector.
+
+Session-scoped: harness, mock Gong API server, connector server, connector-manager.
+Function-scoped: seed helper, source_id, httpx client.
+"""
+
+from __future__ import annotations
+
+import logging
+import socket
+import threading
+import time
+from datetime import datetime, timezone
+from typing import Any
+
+import httpx
+import pytest
+import pytest_asyncio
+import uvicorn
+from starlette.applications import Starlette
+from starlette.requests import Request
+from starlette.responses import JSONResponse
+from starlette.routing import Route
+
+from omni_connector.testing import OmniTestHarness, SeedHelper
+
+logger = logging.getLogger(__name__)
+
+
+# ---------------------------------------------------------------------------
+# Mock data payload helpers
+# ---------------------------------------------------------------------------
+
+
+def _call_payload(call_id: str, started: datetime) -> dict[str, Any]:
+    return {
+        "metaData": {
+            "id": call_id,
+            "title": f"Call {call_id}",
+            "started": started.astimezone(timezone.utc).isoformat(timespec="seconds"),
+            "duration": 1800,
+            "url": f"https://app.gong.io/call?id={call_id}",
+            "workspaceId": "ws-1",
+        },
+        "parties": [],
+        "content": {},
+    }
+
+
+# ---------------------------------------------------------------------------
+# Mock Gong API
+# ---------------------------------------------------------------------------
+
+
+class MockGongAPI:
+    """Controllable mock of the Gong API v2 endpoints.
+
+    Every call has a one-sentence transcript by a speaker who is not among its parties.
+    """
+
+    def __init__(self) -> None:
+        self.calls: list[dict[str, Any]] = []
+        self.ranges: list[tuple[str, str]] = []
+        self.transcript_requests: list[list[str]] = []
+
+    def reset(self) -> None:
+        self.calls.clear()
+        self.ranges.clear()
+        self.transcript_requests.clear()
+
+    def create_app(self) -> Starlette:
+        mock = self
+
+        def _not_found() -> JSONResponse:
+            # Gong answers 404 when no call matches a filter
+            return JSONResponse(
+                {"requestId": "req-1", "errors": ["No calls found for the provided filters"]},
+                status_code=404,
+            )
+
+        async def list_workspaces(request: Request) -> JSONResponse:
+            return JSONResponse({"workspaces": [{"id": "ws-1", "name": "Sales"}]})
+
+        async def list_calls(request: Request) -> JSONResponse:
+            call_filter = (await request.json())["filter"]
+            since, until = call_filter["fromDateTime"], call_filter["toDateTime"]
+            mock.ranges.append((since, until))
+            calls = [
+                call
+                for call in mock.calls
+                if since <= call["metaData"]["started"][:19] + "Z" < until
+            ]
+            if not calls:
+                return _not_found()
+            return JSONResponse({"records": {"totalRecords": len(calls)}, "calls": calls})
+
+        async def get_transcripts(request: Request) -> JSONResponse:
+            call_ids = (await request.json())["filter"]["callIds"]
+            mock.transcript_requests.append(call_ids)
+            return JSONResponse(
+                {
+                    "records": {"totalRecords": len(call_ids)},
+                    "callTranscripts": [
+                        {
+                            "callId": call_id,
+                            "transcript": [
+                                {
+                                    "speakerId": "s1",
+                                    "sentences": [{"text": f"Hello from {call_id}"}],
+                                }
+                            ],
+                        }
+                        for call_id in call_ids
+                    ],
+                }
+            )
+
+        routes = [
+            Route("/v2/workspaces", list_workspaces),
+            Route("/v2/calls/extensive", list_calls, methods=["POST"]),
+            Route("/v2/calls/transcript", get_transcripts, methods=["POST"]),
+        ]
+        return Starlette(routes=routes)
+
+
+# ---------------------------------------------------------------------------
+# Helpers
+# ---------------------------------------------------------------------------
+
+
+def _free_port() -> int:
+    with socket.socket(socket.AF_INET, socket.SOCK_STREAM) as s:
+        s.bind(("", 0))
+        return s.getsockname()[1]
+
+
+def _wait_for_port(port: int, host: str = "localhost", timeout: float = 10) -> None:
+    deadline = time.monotonic() + timeout
+    while time.monotonic() < deadline:
+        try:
+            with socket.create_connection((host, port), timeout=1):
+                return
+        except OSError:
+            time.sleep(0.1)
+    raise TimeoutError(f"Port {port} not open after {timeout}s")
+
+
+# ---------------------------------------------------------------------------
+# Session-scoped fixtures
+# ---------------------------------------------------------------------------
+
+
+@pytest.fixture(scope="session")
+def mock_gong_api() -> MockGongAPI:
+    return MockGongAPI()
+
+
+@pytest.fixture(scope="session")
+def mock_gong_server(mock_gong_api: MockGongAPI) -> str:
+    """Start mock Gong API server in a daemon thread. Returns base URL."""
+    port = _free_port()
+    app = mock_gong_api.create_app()
+    config = uvicorn.Config(app, host="0.0.0.0", port=port, log_level="warning")
+    server = uvicorn.Server(config)
+
+    thread = threading.Thread(target=server.run, daemon=True)
+    thread.start()
+
+    _wait_for_port(port)
+    return f"http://localhost:{port}"
+
+
+@pytest.fixture(scope="session")
+def connector_port() -> int:
+    return _free_port()
+
+
+@pytest.fixture(scope="session")
+def connector_server(connector_port: int) -> str:
+    """Start the Gong connector as a uvicorn server in a daemon thread. Returns base URL."""
+    import os
+
+    os.environ.setdefault("CONNECTOR_MANAGER_URL", "http://localhost:0")
+
+    from gong_connector import GongConnector
+    from omni_connector.server import create_app
+
+    app = create_app(GongConnector())
+    config = uvicorn.Config(
+        app, host="0.0.0.0", port=connector_port, log_level="warning"
+    )
+    server = uvicorn.Server(config)
+
+    thread = threading.Thread(target=server.run, daemon=True)
+    thread.start()
+
+    _wait_for_port(connector_port)
+    return f"http://localhost:{connector_port}"
+
+
+@pytest_asyncio.fixture(scope="session")
+async def harness(
+    connector_server: str,
+    connector_port: int,
+) -> OmniTestHarness:
+    """Session-scoped OmniTestHarness with all infrastructure started."""
+    import os
+
+    h = OmniTestHarness()
+    await h.start_infra()
+    await h.start_connector_manager(
+        {
+            "GONG_CONNECTOR_URL": f"http://host.docker.internal:{connector_port}",
+        }
+    )
+
+    os.environ["CONNECTOR_MANAGER_URL"] = h.connector_manager_url
+
+    yield h
+    await h.teardown()
+
+
+# ---------------------------------------------------------------------------
+# Function-scoped fixtures
+# ---------------------------------------------------------------------------
+
+
+@pytest_asyncio.fixture
+async def seed(harness: OmniTestHarness) -> SeedHelper:
+    return harness.seed()
+
+
+@pytest_asyncio.fixture
+async def source_id(
+    seed: SeedHelper,
+    mock_gong_server: str,
+    mock_gong_api: MockGongAPI,
+) -> str:
+    """Create a Gong source with credentials pointing to the mock server."""
+    mock_gong_api.reset()
+    sid = await seed.create_source(
+        source_type="gong",
+        config={"api_url": mock_gong_server, "lookback_days": 90},
+    )
+    await seed.create_credentials(
+        sid,
+        {"access_key": "test-key", "access_key_secret": "test-secret"},
+        provider="gong",
+        auth_type="basic_auth",
+    )
+    return sid
+
+
+@pytest_asyncio.fixture
+async def cm_client(harness: OmniTestHarness) -> httpx.AsyncClient:
+    """Async httpx client pointed at the connector-manager."""
+    async with httpx.AsyncClient(
+        base_url=harness.connector_manager_url, timeout=30
+    ) as client:
+        yield client
@@ -0,0 +1,95 @@
+"""Tests for the Gong mappers."""
+
+from gong_connector.mappers import (
+    build_permissions,
+    generate_call_content,
+    map_call_to_document,
+)
+
+CALL = {
+    "metaData": {
+        "id": "7782342274025937895",
+        "url": "https://app.gong.io/call?id=7782342274025937895",
+        "title": "Acme renewal review",
+        "started": "2024-05-02T16:00:00Z",
+        "duration": 1800,
+        "primaryUserId": "234599484848423",
+        "direction": "Conference",
+        "scope": "External",
+        "isPrivate": False,
+    },
+    "parties": [
+        {
+            "speakerId": "s2",
+            "name": "Jane Buyer",
+            "emailAddress": "jane@acme.com",
+            "affiliation": "External",
+        },
+        {
+            "speakerId": "s1",
+            "userId": "234599484848423",
+            "name": "Sam Seller",
+            "emailAddress": "Sam@ours.com",
+            "affiliation": "Internal",
+        },
+    ],
+    "context": [
+        {
+            "system": "Salesforce",
+            "objects": [
+                {"objectType": "Account", "fields": [{"name": "Name", "value": "Acme"}]},
+                {
+                    "objectType": "Opportunity",
+                    "fields": [
+                        {"name": "Name", "value": "Acme renewal 2024"},
+                        {"name": "StageName", "value": "Negotiation"},
+                    ],
+                },
+            ],
+        }
+    ],
+    "content": {
+        "brief": "Acme wants a two-year renewal.",
+        "keyPoints": [{"text": "Budget approved for Q3"}],
+        "highlights": [
+            {"title": "Next steps", "items": [{"text": "Send the revised quote"}]},
+        ],
+    },
+}
+
+TRANSCRIPT = [
+    {"speakerId": "s1", "sentences": [{"text": "Thanks for joining."}, {"text": "Shall we?"}]},
+    {"speakerId": "s2", "sentences": [{"text": "We need SSO."}]},
+]
+
+
+def test_content_has_summary_and_transcript():
+    content = generate_call_content(CALL, TRANSCRIPT)
+
+    assert content.startswith("Acme renewal review\nDate: 2024-05-02T16:00:00Z")
+    assert "Participants: Sam Seller, Jane Buyer" in content
+    assert "Deal stage: Negotiation" in content
+    assert "--- Key Points ---\n- Budget approved for Q3" in content
+    assert "Next steps:\n- Send the revised quote" in content
+    assert "Sam Seller: Thanks for joining. Shall we?\nJane Buyer: We need SSO." in content
+
+
+def test_call_document_attributes():
+    doc = map_call_to_document(CALL, "content-id", build_permissions(CALL))
+
+    assert doc.external_id == "gong:call:7782342274025937895"
+    assert doc.metadata.author == "Sam Seller"
+    assert doc.permissions.public is True
+    assert doc.attributes["account"] == "Acme"
+    assert doc.attributes["deal_stage"] == "Negotiation"
+    assert doc.attributes["participants"] == "Sam Seller, Jane Buyer"
+    assert doc.attributes["date"] == "2024-05-02"
+
+
+def test_private_calls_are_shared_with_internal_participants():
+    call = {**CALL, "metaData": {**CALL["metaData"], "isPrivate": True}}
+
+    permissions = build_permissions(call)
+
+    assert permissions.public is False
+    assert permissions.users == ["sam@ours.com"]
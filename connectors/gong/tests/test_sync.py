@@ -0,0 +1,78 @@
+"""Integration tests: calls are backfilled a window at a time, each with its transcript."""
+
+from datetime import datetime, timedelta, timezone
+
+import httpx
+import pytest
+
+from omni_connector.testing import get_events, wait_for_sync
+
+from .conftest import _call_payload
+
+pytestmark = pytest.mark.integration
+
+
+async def _sync(harness, cm_client: httpx.AsyncClient, source_id: str, sync_type: str):
+    resp = await cm_client.post(
+        "/sync",
+        json={"source_id": source_id, "sync_type": sync_type},
+    )
+    assert resp.status_code == 200, resp.text
+    row = await wait_for_sync(harness.db_pool, resp.json()["sync_run_id"], timeout=30)
+    assert (
+        row["status"] == "completed"
+    ), f"Sync ended with status={row['status']}, error={row.get('error_message')}"
+    return row
+
+
+async def _created(harness, source_id: str, row) -> list[dict]:
+    events = await get_events(harness.db_pool, source_id)
+    return [
+        e["payload"]
+        for e in events
+        if e["sync_run_id"] == row["id"] and e["event_type"] == "document_created"
+    ]
+
+
+async def test_backfill_reads_the_lookback_period_a_window_at_a_time(
+    harness, seed, source_id, mock_gong_api, cm_client: httpx.AsyncClient
+):
+    now = datetime.now(timezone.utc)
+    mock_gong_api.calls.append(_call_payload("1", now - timedelta(days=50)))
+    mock_gong_api.calls.append(_call_payload("2", now - timedelta(days=5)))
+
+    row = await _sync(harness, cm_client, source_id, "full")
+
+    created = await _created(harness, source_id, row)
+    assert [doc["document_id"] for doc in created] == ["gong:call:1", "gong:call:2"]
+    assert len(mock_gong_api.ranges) == 3
+    state = await seed.get_connector_state(source_id)
+    assert state["calls_since"] < mock_gong_api.ranges[-1][1]
+
+
+async def test_calls_are_synced_with_their_transcripts_in_one_request(
+    harness, seed, mock_gong_server, mock_gong_api, cm_client: httpx.AsyncClient
+):
+    mock_gong_api.reset()
+    sid = await seed.create_source(
+        source_type="gong",
+        config={"api_url": mock_gong_server, "lookback_days": 1},
+    )
+    await seed.create_credentials(
+        sid,
+        {"access_key": "test-key", "access_key_secret": "test-secret"},
+        provider="gong",
+        auth_type="basic_auth",
+    )
+    now = datetime.now(timezone.utc)
+    mock_gong_api.calls.append(_call_payload("1", now - timedelta(hours=2)))
+    mock_gong_api.calls.append(_call_payload("2", now - timedelta(hours=1)))
+
+    row = await _sync(harness, cm_client, sid, "full")
+
+    assert mock_gong_api.transcript_requests == [["1", "2"]]
+    created = await _created(harness, sid, row)
+    content = await harness.db_pool.fetchval(
+        "SELECT content FROM content_blobs WHERE id = $1", created[1]["content_id"]
+    )
+    assert b"Unknown speaker: Hello from 2" in content
@@ -9,11 +9,11 @@ use axum::{
 use dashmap::DashSet;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use shared::telemetry;
+use shared::{error_reporting, telemetry};
 use std::sync::Arc;
 use tower::ServiceBuilder;
 use tower_http::cors::CorsLayer;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, warn, Instrument};
 
 use crate::admin::AdminClient;
 use crate::auth::ServiceAccountAuth;
@@ -99,16 +99,20 @@ async fn trigger_sync(
     let sync_manager = state.sync_manager.clone();
     let active_syncs = state.active_syncs.clone();
 
-    tokio::spawn(async move {
-        let result = sync_manager.sync_source_from_request(request).await;
+    let span = error_reporting::sync_span(&source_id, request.source_type, &sync_run_id);
+    tokio::spawn(
+        async move {
+            let result = sync_manager.sync_source_from_request(request).await;
 
-        // Remove from active syncs when done
-        active_syncs.remove(&source_id);
+            // Remove from active syncs when done
+            active_syncs.remove(&source_id);
 
-        if let Err(e) = result {
-            error!("Sync {} failed: {}", sync_run_id, e);
+            if let Err(e) = result {
+                error!("Sync {} failed: {}", sync_run_id, e);
+            }
         }
-    });
+        .instrument(span),
+    );
 
     Ok(Json(SyncResponse::started()))
 }
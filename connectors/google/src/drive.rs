@@ -101,7 +101,7 @@ impl DriveClient {
 
             let mut params = vec![
                 ("pageSize", "100"),
-                ("fields", "nextPageToken,files(id,name,mimeType,webViewLink,createdTime,modifiedTime,size,parents,shared,permissions(id,type,emailAddress,role))"),
+                ("fields", "nextPageToken,files(id,name,mimeType,webViewLink,createdTime,modifiedTime,size,parents,shared,thumbnailLink,permissions(id,type,emailAddress,role))"),
                 ("q", query.as_str()),
                 ("includeItemsFromAllDrives", "true"),
                 ("supportsAllDrives", "true"),
@@ -208,7 +208,13 @@ impl DriveClient {
 
         let limiter = rate_limiters
             .entry(user_email.to_string())
-            .or_insert_with(|| Arc::new(RateLimiter::new(5, 5))) // 300 req/min for each user, 5 retry attempts
+            .or_insert_with(|| {
+                // 300 req/min for each user
+                Arc::new(
+                    self.rate_limiter
+                        .sub_bucket(&format!("docs:{}", user_email), 5),
+                )
+            })
             .clone();
 
         Ok(limiter)
@@ -504,6 +510,64 @@ impl DriveClient {
         .await
     }
 
+    /// Download the thumbnail of a file, with its content type. Returns `None` when the file
+    /// has no thumbnail.
+    pub async fn get_thumbnail(
+        &self,
+        auth: &ServiceAccountAuth,
+        user_email: &str,
+        file: &GoogleDriveFile,
+    ) -> Result<Option<(Vec<u8>, String)>> {
+        let Some(thumbnail_link) = file.thumbnail_link.clone() else {
+            return Ok(None);
+        };
+        let file_id = file.id.clone();
+
+        let thumbnail =
+            execute_with_auth_retry(auth, user_email, self.rate_limiter.clone(), |token| {
+                let thumbnail_link = thumbnail_link.clone();
+                let file_id = file_id.clone();
+                async move {
+                    let response = self
+                        .client
+                        .get(&thumbnail_link)
+                        .bearer_auth(&token)
+                        .send()
+                        .await
+                        .with_context(|| {
+                            format!("Failed to request thumbnail of file {}", file_id)
+                        })?;
+
+                    let status = response.status();
+                    if is_auth_error(status) {
+                        return Ok(ApiResult::AuthError);
+                    } else if !status.is_success() {
+                        return Ok(ApiResult::OtherError(anyhow!(
+                            "Failed to download thumbnail of file {}: HTTP {}",
+                            file_id,
+                            status
+                        )));
+                    }
+
+                    let content_type = response
+                        .headers()
+                        .get(reqwest::header::CONTENT_TYPE)
+                        .and_then(|value| value.to_str().ok())
+                        .unwrap_or("image/png")
+                        .to_string();
+                    let bytes = response
+                        .bytes()
+                        .await
+                        .with_context(|| format!("Failed to read thumbnail of file {}", file_id))?;
+
+                    Ok(ApiResult::Success((bytes.to_vec(), content_type)))
+                }
+            })
+            .await?;
+
+        Ok(Some(thumbnail))
+    }
+
     async fn get_pdf_content(
         &self,
         auth: &ServiceAccountAuth,
@@ -875,7 +939,7 @@ impl DriveClient {
             ("includeItemsFromAllDrives", "true"),
             ("supportsAllDrives", "true"),
             ("includeRemoved", "true"),
-            ("fields", "nextPageToken,changes(changeType,removed,file(id,name,mimeType,webViewLink,createdTime,modifiedTime,size,parents,shared,permissions(id,type,emailAddress,role)),fileId,time)"),
+            ("fields", "nextPageToken,changes(changeType,removed,file(id,name,mimeType,webViewLink,createdTime,modifiedTime,size,parents,shared,thumbnailLink,permissions(id,type,emailAddress,role)),fileId,time)"),
         ];
 
         let response = self
@@ -74,7 +74,13 @@ impl GmailClient {
 
         let limiter = rate_limiters
             .entry(user_email.to_string())
-            .or_insert_with(|| Arc::new(RateLimiter::new(25, 5))) // 1500 req/min for each user, 5 retry attempts
+            .or_insert_with(|| {
+                // 1500 req/min for each user
+                Arc::new(
+                    self.rate_limiter
+                        .sub_bucket(&format!("gmail:{}", user_email), 25),
+                )
+            })
             .clone();
 
         Ok(limiter)
@@ -647,6 +653,44 @@ impl GmailClient {
         .await
     }
 
+    /// Names of the user's labels, keyed by label ID.
+    pub async fn list_labels(
+        &self,
+        auth: &ServiceAccountAuth,
+        user_email: &str,
+    ) -> Result<HashMap<String, String>> {
+        let rate_limiter = self.get_or_create_user_rate_limiter(user_email)?;
+        execute_with_auth_retry(auth, user_email, rate_limiter.clone(), |token| async move {
+            let url = format!("{}/users/{}/labels", GMAIL_API_BASE, user_email);
+
+            let response = self.client.get(&url).bearer_auth(&token).send().await?;
+
+            let status = response.status();
+            if is_auth_error(status) {
+                return Ok(ApiResult::AuthError);
+            } else if !status.is_success() {
+                let error_text = response.text().await?;
+                return Ok(ApiResult::OtherError(anyhow!(
+                    "Failed to list labels: HTTP {} - {}",
+                    status,
+                    error_text
+                )));
+            }
+
+            let response_text = response.text().await?;
+            let labels: ListLabelsResponse = serde_json::from_str(&response_text)?;
+
+            Ok(ApiResult::Success(
+                labels
+                    .labels
+                    .into_iter()
+                    .map(|label| (label.id, label.name))
+                    .collect(),
+            ))
+        })
+        .await
+    }
+
     pub fn extract_message_content(&self, message: &GmailMessage) -> Result<String> {
         if let Some(ref payload) = message.payload {
             self.extract_text_from_payload(payload)
@@ -862,6 +906,18 @@ pub struct HistoryLabelRemoved {
     pub label_ids: Vec<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct GmailLabel {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListLabelsResponse {
+    #[serde(default)]
+    pub labels: Vec<GmailLabel>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct GmailProfile {
     #[serde(rename = "emailAddress")]
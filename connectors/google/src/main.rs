@@ -16,6 +16,7 @@ mod sync;
 
 use config::GoogleConnectorConfig;
 
+use shared::shutdown::Shutdown;
 use shared::SdkClient;
 
 use admin::AdminClient;
@@ -45,7 +46,12 @@ async fn main() -> Result<()> {
         .unwrap_or_else(|_| "5".to_string())
         .parse::<u32>()
         .unwrap_or(5);
-    let rate_limiter = Arc::new(RateLimiter::new(api_rate_limit, max_retries));
+    let rate_limiter = Arc::new(RateLimiter::distributed(
+        redis_client.clone(),
+        "google:admin",
+        api_rate_limit,
+        max_retries,
+    ));
     let admin_client = Arc::new(AdminClient::with_rate_limiter(rate_limiter.clone()));
 
     let sdk_client = SdkClient::from_env()?;
@@ -54,7 +60,7 @@ async fn main() -> Result<()> {
         redis_client,
         config.ai_service_url.clone(),
         Arc::clone(&admin_client),
-        sdk_client,
+        sdk_client.clone(),
     ));
 
     // Create API state with shared services
@@ -73,9 +79,12 @@ async fn main() -> Result<()> {
     info!("HTTP server listening on {}", addr);
 
     // Run HTTP server (connector-manager handles scheduling)
-    if let Err(e) = axum::serve(listener, app).await {
+    if let Err(e) = shared::tls::serve(listener, app, Shutdown::on_signal()).await {
         error!("HTTP server stopped: {:?}", e);
     }
 
+    // Syncs cut short by the shutdown are resumed once the connector is back
+    sdk_client.pause_active_syncs().await;
+
     Ok(())
 }
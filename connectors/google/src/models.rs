@@ -1,8 +1,12 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use shared::models::{ConnectorEvent, DocumentAttributes, DocumentMetadata, DocumentPermissions};
-use sqlx::types::time::OffsetDateTime;
+use shared::exclusion::{ContentExclusions, ExclusionCandidate};
+use shared::models::{
+    ConnectorEvent, DocumentAttributes, DocumentMetadata, DocumentPermissions, DocumentThumbnail,
+    SourceType,
+};
+use shared::utils::{offset_datetime_from_unix_millis, to_offset_datetime};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use uuid::Uuid;
@@ -32,6 +36,9 @@ pub struct GoogleDriveFile {
     pub shared: Option<bool>,
     pub permissions: Option<Vec<Permission>>,
     pub owners: Option<Vec<Owner>>,
+    /// Short-lived link to a thumbnail of the file, fetched with the user's credentials.
+    #[serde(rename = "thumbnailLink")]
+    pub thumbnail_link: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -123,17 +130,8 @@ impl GoogleDriveFile {
         source_id: &str,
         content_id: &str,
         path: Option<String>,
+        thumbnail: Option<DocumentThumbnail>,
     ) -> ConnectorEvent {
-        let mut users = Vec::new();
-
-        if let Some(file_permissions) = &self.permissions {
-            for perm in file_permissions {
-                if let Some(email) = &perm.email_address {
-                    users.push(email.clone());
-                }
-            }
-        }
-
         let mut extra = HashMap::new();
         extra.insert("file_id".to_string(), json!(self.id));
         extra.insert("shared".to_string(), json!(self.shared.unwrap_or(false)));
@@ -154,24 +152,20 @@ impl GoogleDriveFile {
             created_at: self.created_time.as_ref().and_then(|t| {
                 t.parse::<DateTime<Utc>>()
                     .ok()
-                    .map(|dt| OffsetDateTime::from_unix_timestamp(dt.timestamp()).unwrap())
+                    .and_then(|dt| to_offset_datetime(&dt))
             }),
             updated_at: self.modified_time.as_ref().and_then(|t| {
                 t.parse::<DateTime<Utc>>()
                     .ok()
-                    .map(|dt| OffsetDateTime::from_unix_timestamp(dt.timestamp()).unwrap())
+                    .and_then(|dt| to_offset_datetime(&dt))
             }),
             mime_type: Some(self.mime_type.clone()),
             size: self.size.clone(),
             url: self.web_view_link.clone(),
             path,
             extra: Some(extra),
-        };
-
-        let permissions = DocumentPermissions {
-            public: false,
-            users,
-            groups: vec![],
+            thumbnail,
+            parent: None,
         };
 
         let attributes = self.to_attributes().into_attributes();
@@ -182,10 +176,43 @@ impl GoogleDriveFile {
             document_id: self.id.clone(),
             content_id: content_id.to_string(),
             metadata,
-            permissions,
+            permissions: self.to_permissions(),
             attributes: Some(attributes),
         }
     }
+
+    /// Users the file is shared with, sorted so that unchanged sharing always produces the
+    /// same permissions.
+    pub fn to_permissions(&self) -> DocumentPermissions {
+        let mut users: Vec<String> = self
+            .permissions
+            .iter()
+            .flatten()
+            .filter_map(|perm| perm.email_address.clone())
+            .collect();
+        users.sort();
+        users.dedup();
+
+        DocumentPermissions {
+            public: false,
+            users,
+            groups: vec![],
+        }
+    }
+
+    /// Event carrying only the file's current sharing, for files whose content is unchanged.
+    pub fn to_permissions_updated_event(
+        &self,
+        sync_run_id: &str,
+        source_id: &str,
+    ) -> ConnectorEvent {
+        ConnectorEvent::PermissionsUpdated {
+            sync_run_id: sync_run_id.to_string(),
+            source_id: source_id.to_string(),
+            document_id: self.id.clone(),
+            permissions: self.to_permissions(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -441,6 +468,41 @@ impl GmailThread {
             .map(|h| h.value.clone())
     }
 
+    /// Name of the exclusion rule matching the thread's labels or the sender of any of its
+    /// messages. Labels are matched by their name in `label_names` as well as by ID, which
+    /// for system labels such as `IMPORTANT` is also their name.
+    pub fn excluded_by<'a>(
+        &self,
+        exclusions: &'a ContentExclusions,
+        label_names: &HashMap<String, String>,
+    ) -> Option<&'a str> {
+        let mut labels = self.to_attributes().labels;
+        let names: Vec<String> = labels
+            .iter()
+            .filter_map(|id| label_names.get(id).cloned())
+            .collect();
+        labels.extend(names);
+
+        let by_label = ExclusionCandidate {
+            labels: &labels,
+            ..Default::default()
+        };
+        if let Some(rule) = exclusions.excluded_by(SourceType::Gmail, &by_label) {
+            return Some(rule);
+        }
+
+        self.messages
+            .iter()
+            .filter_map(|message| self.extract_header_value(message, "From"))
+            .find_map(|sender| {
+                let by_sender = ExclusionCandidate {
+                    sender: Some(&sender),
+                    ..Default::default()
+                };
+                exclusions.excluded_by(SourceType::Gmail, &by_sender)
+            })
+    }
+
     pub fn aggregate_content(
         &self,
         gmail_client: &crate::gmail::GmailClient,
@@ -541,7 +603,7 @@ impl GmailThread {
             self.latest_date
                 .parse::<i64>()
                 .ok()
-                .and_then(|millis| OffsetDateTime::from_unix_timestamp(millis / 1000).ok())
+                .and_then(offset_datetime_from_unix_millis)
         } else {
             None
         };
@@ -563,6 +625,8 @@ impl GmailThread {
             )),
             path: Some(format!("/Gmail/{}", self.subject)),
             extra: Some(extra),
+            thumbnail: None,
+            parent: None,
         };
 
         let permissions = DocumentPermissions {
@@ -609,9 +673,10 @@ mod tests {
                 role: "reader".to_string(),
             }]),
             owners: None,
+            thumbnail_link: None,
         };
 
-        let event = file.to_connector_event("sync123", "source456", "content789", None);
+        let event = file.to_connector_event("sync123", "source456", "content789", None, None);
 
         match event {
             ConnectorEvent::DocumentCreated {
@@ -672,6 +737,7 @@ mod tests {
             shared: None,
             permissions: None,
             owners: None,
+            thumbnail_link: None,
         };
 
         let attrs = file.to_attributes();
@@ -738,6 +804,7 @@ mod tests {
             shared: None,
             permissions: None,
             owners: None,
+            thumbnail_link: None,
         };
 
         let folder: FolderMetadata = file.into();
@@ -788,9 +855,10 @@ mod tests {
             shared: None,
             permissions: None,
             owners: None,
+            thumbnail_link: None,
         };
 
-        let event = file.to_connector_event("sync1", "source1", "content1", None);
+        let event = file.to_connector_event("sync1", "source1", "content1", None, None);
 
         match event {
             ConnectorEvent::DocumentCreated { permissions, .. } => {
@@ -802,6 +870,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_drive_file_permissions_updated_event() {
+        let permission = |id: &str, email: &str| Permission {
+            id: id.to_string(),
+            permission_type: "user".to_string(),
+            email_address: Some(email.to_string()),
+            role: "reader".to_string(),
+        };
+        let file = GoogleDriveFile {
+            id: "file123".to_string(),
+            name: "test.txt".to_string(),
+            mime_type: "text/plain".to_string(),
+            web_view_link: None,
+            created_time: None,
+            modified_time: None,
+            size: None,
+            parents: None,
+            shared: Some(true),
+            permissions: Some(vec![
+                permission("p1", "zoe@example.com"),
+                permission("p2", "adam@example.com"),
+                permission("p3", "zoe@example.com"),
+            ]),
+            owners: None,
+            thumbnail_link: None,
+        };
+
+        match file.to_permissions_updated_event("sync1", "source1") {
+            ConnectorEvent::PermissionsUpdated {
+                document_id,
+                permissions,
+                ..
+            } => {
+                assert_eq!(document_id, "file123");
+                assert_eq!(
+                    permissions.users,
+                    vec![
+                        "adam@example.com".to_string(),
+                        "zoe@example.com".to_string()
+                    ]
+                );
+            }
+            _ => panic!("Expected PermissionsUpdated event"),
+        }
+    }
+
     #[test]
     fn test_drive_file_with_path() {
         let file = GoogleDriveFile {
@@ -816,6 +930,7 @@ mod tests {
             shared: None,
             permissions: None,
             owners: None,
+            thumbnail_link: None,
         };
 
         let event = file.to_connector_event(
@@ -823,6 +938,10 @@ mod tests {
             "source1",
             "content1",
             Some("/Documents/Reports/report.pdf".to_string()),
+            Some(DocumentThumbnail {
+                content_id: "thumbnail1".to_string(),
+                mime_type: "image/png".to_string(),
+            }),
         );
 
         match event {
@@ -831,6 +950,7 @@ mod tests {
                     metadata.path,
                     Some("/Documents/Reports/report.pdf".to_string())
                 );
+                assert_eq!(metadata.thumbnail.unwrap().content_id, "thumbnail1");
             }
             _ => panic!("Expected DocumentCreated event"),
         }
@@ -857,8 +977,8 @@ pub struct ActionDefinition {
     pub parameters: serde_json::Value,
 }
 
-// Import SyncRequest and SyncResponse from shared crate
-pub use shared::models::{SyncRequest, SyncResponse};
+// Import ActionRequest, SyncRequest and SyncResponse from shared crate
+pub use shared::models::{ActionRequest, SyncRequest, SyncResponse};
 
 /// Extension trait for SyncResponse helper methods
 pub trait SyncResponseExt {
@@ -892,13 +1012,6 @@ pub struct CancelResponse {
     pub status: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ActionRequest {
-    pub action: String,
-    pub params: serde_json::Value,
-    pub credentials: serde_json::Value,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActionResponse {
     pub status: String,
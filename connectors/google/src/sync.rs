@@ -2,7 +2,7 @@ use anyhow::{anyhow, Context, Result};
 use dashmap::DashMap;
 use futures::stream::{self, StreamExt};
 use redis::{AsyncCommands, Client as RedisClient};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use time::{self, OffsetDateTime};
@@ -16,8 +16,11 @@ use crate::gmail::{GmailClient, MessageFormat};
 use crate::models::{
     GmailThread, SyncRequest, UserFile, WebhookChannel, WebhookChannelResponse, WebhookNotification,
 };
+use shared::cache::{keys, ttl};
+use shared::exclusion::{ContentExclusions, ExclusionCandidate};
 use shared::models::{
-    ConnectorEvent, ServiceCredentials, ServiceProvider, Source, SourceType, SyncType,
+    ConnectorEvent, DocumentPermissions, DocumentThumbnail, ServiceCredentials, ServiceProvider,
+    Source, SourceType, SyncType,
 };
 use shared::SdkClient;
 use shared::{AIClient, RateLimiter};
@@ -46,12 +49,34 @@ impl SyncState {
         Self { redis_client }
     }
 
-    pub fn get_file_sync_key(&self, source_id: &str, file_id: &str) -> String {
-        format!("google:drive:{}:{}", source_id, file_id)
+    /// Move the source's sync state written before keys were prefixed to its keys, so the first
+    /// sync after an upgrade neither reprocesses every file and thread nor misses the files
+    /// deleted since the last one.
+    pub async fn migrate_legacy_keys(&self, source_id: &str) -> Result<()> {
+        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
+        let moved = shared::cache::migrate_legacy_source_keys(
+            &mut conn,
+            source_id,
+            &[
+                ("google:drive:", "google:drive:file:"),
+                ("google:drive:permissions:", "google:drive:permissions:"),
+                ("google:gmail:sync:", "google:gmail:thread:"),
+            ],
+        )
+        .await?;
+        if moved > 0 {
+            info!(
+                "Migrated {} legacy sync state keys of source {}",
+                moved, source_id
+            );
+        }
+        Ok(())
     }
 
-    pub fn get_test_file_sync_key(&self, source_id: &str, file_id: &str) -> String {
-        format!("google:drive:test:{}:{}", source_id, file_id)
+    pub fn get_file_sync_key(&self, source_id: &str, file_id: &str) -> String {
+        keys()
+            .source(source_id)
+            .key(format_args!("google:drive:file:{}", file_id))
     }
 
     pub async fn get_file_sync_state(
@@ -72,7 +97,7 @@ impl SyncState {
         file_id: &str,
         modified_time: &str,
     ) -> Result<()> {
-        self.set_file_sync_state_with_expiry(source_id, file_id, modified_time, 30 * 24 * 60 * 60)
+        self.set_file_sync_state_with_expiry(source_id, file_id, modified_time, ttl::SYNC_STATE)
             .await
     }
 
@@ -84,11 +109,7 @@ impl SyncState {
         expiry_seconds: u64,
     ) -> Result<()> {
         let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
-        let key = if cfg!(test) {
-            self.get_test_file_sync_key(source_id, file_id)
-        } else {
-            self.get_file_sync_key(source_id, file_id)
-        };
+        let key = self.get_file_sync_key(source_id, file_id);
 
         let _: () = conn.set_ex(&key, modified_time, expiry_seconds).await?;
         Ok(())
@@ -96,11 +117,7 @@ impl SyncState {
 
     pub async fn delete_file_sync_state(&self, source_id: &str, file_id: &str) -> Result<()> {
         let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
-        let key = if cfg!(test) {
-            self.get_test_file_sync_key(source_id, file_id)
-        } else {
-            self.get_file_sync_key(source_id, file_id)
-        };
+        let key = self.get_file_sync_key(source_id, file_id);
 
         let _: () = conn.del(&key).await?;
         Ok(())
@@ -108,33 +125,66 @@ impl SyncState {
 
     pub async fn get_all_synced_file_ids(&self, source_id: &str) -> Result<HashSet<String>> {
         let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
-        let pattern = if cfg!(test) {
-            format!("google:drive:test:{}:*", source_id)
-        } else {
-            format!("google:drive:{}:*", source_id)
-        };
-
-        let keys: Vec<String> = conn.keys(&pattern).await?;
-        let prefix = if cfg!(test) {
-            format!("google:drive:test:{}:", source_id)
-        } else {
-            format!("google:drive:{}:", source_id)
-        };
-        let file_ids: HashSet<String> = keys
-            .into_iter()
-            .filter_map(|key| key.strip_prefix(&prefix).map(|s| s.to_string()))
+        let source_keys = keys().source(source_id);
+        let prefix = "google:drive:file:";
+
+        let synced = shared::cache::scan(&mut conn, &source_keys.pattern(prefix)).await?;
+        let file_ids: HashSet<String> = synced
+            .iter()
+            .filter_map(|key| source_keys.name(key)?.strip_prefix(prefix))
+            .map(|file_id| file_id.to_string())
             .collect();
 
         Ok(file_ids)
     }
 
-    // Gmail thread sync state methods
-    pub fn get_thread_sync_key(&self, source_id: &str, thread_id: &str) -> String {
-        format!("google:gmail:sync:{}:{}", source_id, thread_id)
+    pub fn get_file_permissions_key(&self, source_id: &str, file_id: &str) -> String {
+        keys()
+            .source(source_id)
+            .key(format_args!("google:drive:permissions:{}", file_id))
+    }
+
+    /// Permissions last emitted for a file, serialized as JSON.
+    pub async fn get_file_permissions(
+        &self,
+        source_id: &str,
+        file_id: &str,
+    ) -> Result<Option<String>> {
+        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
+        let key = self.get_file_permissions_key(source_id, file_id);
+
+        let result: Option<String> = conn.get(&key).await?;
+        Ok(result)
+    }
+
+    pub async fn set_file_permissions(
+        &self,
+        source_id: &str,
+        file_id: &str,
+        permissions: &DocumentPermissions,
+    ) -> Result<()> {
+        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
+        let key = self.get_file_permissions_key(source_id, file_id);
+
+        let _: () = conn
+            .set_ex(&key, serde_json::to_string(permissions)?, ttl::SYNC_STATE)
+            .await?;
+        Ok(())
     }
 
-    pub fn get_test_thread_sync_key(&self, source_id: &str, thread_id: &str) -> String {
-        format!("google:gmail:sync:test:{}:{}", source_id, thread_id)
+    pub async fn delete_file_permissions(&self, source_id: &str, file_id: &str) -> Result<()> {
+        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
+        let key = self.get_file_permissions_key(source_id, file_id);
+
+        let _: () = conn.del(&key).await?;
+        Ok(())
+    }
+
+    // Gmail thread sync state methods
+    pub fn get_thread_sync_key(&self, source_id: &str, thread_id: &str) -> String {
+        keys()
+            .source(source_id)
+            .key(format_args!("google:gmail:thread:{}", thread_id))
     }
 
     pub async fn get_thread_sync_state(
@@ -143,11 +193,7 @@ impl SyncState {
         thread_id: &str,
     ) -> Result<Option<String>> {
         let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
-        let key = if cfg!(test) {
-            self.get_test_thread_sync_key(source_id, thread_id)
-        } else {
-            self.get_thread_sync_key(source_id, thread_id)
-        };
+        let key = self.get_thread_sync_key(source_id, thread_id);
 
         let result: Option<String> = conn.get(&key).await?;
         Ok(result)
@@ -159,7 +205,7 @@ impl SyncState {
         thread_id: &str,
         latest_date: &str,
     ) -> Result<()> {
-        self.set_thread_sync_state_with_expiry(source_id, thread_id, latest_date, 30 * 24 * 60 * 60)
+        self.set_thread_sync_state_with_expiry(source_id, thread_id, latest_date, ttl::SYNC_STATE)
             .await
     }
 
@@ -171,15 +217,19 @@ impl SyncState {
         expiry_seconds: u64,
     ) -> Result<()> {
         let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
-        let key = if cfg!(test) {
-            self.get_test_thread_sync_key(source_id, thread_id)
-        } else {
-            self.get_thread_sync_key(source_id, thread_id)
-        };
+        let key = self.get_thread_sync_key(source_id, thread_id);
 
         let _: () = conn.set_ex(&key, latest_date, expiry_seconds).await?;
         Ok(())
     }
+
+    pub async fn delete_thread_sync_state(&self, source_id: &str, thread_id: &str) -> Result<()> {
+        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
+        let key = self.get_thread_sync_key(source_id, thread_id);
+
+        let _: () = conn.del(&key).await?;
+        Ok(())
+    }
 }
 
 impl SyncManager {
@@ -194,6 +244,7 @@ impl SyncManager {
         //   - Docs API (get content, etc.): 3,000 req/min/project, 300 req/min/user
         // The below rate limit is for the Drive API only.
         // For the Docs API, we need to have a separate rate limiter for each user.
+        // Limits are shared by every replica of the connector, through Redis.
         let api_rate_limit = std::env::var("GOOGLE_API_RATE_LIMIT")
             .unwrap_or_else(|_| "50".to_string())
             .parse::<u32>()
@@ -204,7 +255,12 @@ impl SyncManager {
             .parse::<u32>()
             .unwrap_or(5);
 
-        let rate_limiter = Arc::new(RateLimiter::new(api_rate_limit, max_retries));
+        let rate_limiter = Arc::new(RateLimiter::distributed(
+            redis_client.clone(),
+            "google:api",
+            api_rate_limit,
+            max_retries,
+        ));
         let ai_client = AIClient::new(ai_service_url);
         let drive_client = DriveClient::with_rate_limiter(rate_limiter.clone(), ai_client.clone());
         let gmail_client = GmailClient::with_rate_limiter(rate_limiter);
@@ -230,6 +286,7 @@ impl SyncManager {
             "Starting sync for source {} (sync_run_id: {})",
             source_id, sync_run_id
         );
+        self.sdk_client.start_sync(&request);
 
         // Register this sync as active for cancellation tracking
         let active_sync = Arc::new(ActiveSync {
@@ -244,6 +301,9 @@ impl SyncManager {
             .get_source(&source_id)
             .await
             .context("Failed to fetch source via SDK")?;
+        SyncState::new(self.redis_client.clone())
+            .migrate_legacy_keys(&source_id)
+            .await?;
 
         // Determine sync type from mode
         let sync_type = match sync_mode.as_str() {
@@ -348,6 +408,7 @@ impl SyncManager {
         sync_state: &SyncState,
         current_files: Arc<std::sync::Mutex<HashSet<String>>>,
         created_after: Option<&str>,
+        exclusions: &ContentExclusions,
     ) -> Result<(usize, usize)> {
         info!("Processing Drive files for user: {}", user_email);
 
@@ -387,6 +448,15 @@ impl SyncManager {
 
             // Process files in this page
             for file in response.files {
+                // Excluded files are not tracked, so any previously indexed copy gets deleted
+                if let Some(rule) = self
+                    .drive_exclusion(exclusions, &service_auth, user_email, &file)
+                    .await
+                {
+                    debug!("File {} excluded by rule '{}'", file.name, rule);
+                    continue;
+                }
+
                 // Track this file as currently existing
                 {
                     let mut current_files_guard = current_files.lock().unwrap();
@@ -405,6 +475,13 @@ impl SyncManager {
                                     true
                                 } else {
                                     debug!("File {} unchanged, skipping", file.name);
+                                    self.sync_file_permissions(
+                                        &file,
+                                        source_id,
+                                        sync_run_id,
+                                        sync_state,
+                                    )
+                                    .await;
                                     false
                                 }
                             }
@@ -545,11 +622,16 @@ impl SyncManager {
                                         }
                                     };
 
+                                    let thumbnail = self
+                                        .store_thumbnail(&service_auth, &user_file, &sync_run_id)
+                                        .await;
+
                                     let event = user_file.file.to_connector_event(
                                         &sync_run_id,
                                         &source_id,
                                         &content_id,
                                         file_path,
+                                        thumbnail,
                                     );
 
                                     match sdk_client.emit_event(&sync_run_id, &source_id, event).await {
@@ -563,6 +645,12 @@ impl SyncManager {
                                                     return (1, 0); // Processed but not updated
                                                 }
                                             }
+                                            if let Err(e) = sync_state
+                                                .set_file_permissions(&source_id, &user_file.file.id, &user_file.file.to_permissions())
+                                                .await
+                                            {
+                                                warn!("Failed to record permissions of file {}: {:?}", user_file.file.name, e);
+                                            }
                                             (1, 1) // Processed and updated
                                         }
                                         Err(e) => {
@@ -644,6 +732,13 @@ impl SyncManager {
             filtered_users.len()
         );
 
+        let exclusions = self
+            .sdk_client
+            .get_content_exclusions()
+            .await
+            .context("Failed to fetch content exclusion rules")?;
+        let exclusions = &exclusions;
+
         let sync_state = SyncState::new(self.redis_client.clone());
         let synced_files = sync_state.get_all_synced_file_ids(&source.id).await?;
         let current_files = Arc::new(std::sync::Mutex::new(HashSet::new()));
@@ -684,6 +779,7 @@ impl SyncManager {
                             &sync_state,
                             current_files.clone(),
                             Some(&drive_cutoff_date),
+                            exclusions,
                         )
                         .await;
 
@@ -741,6 +837,9 @@ impl SyncManager {
             sync_state
                 .delete_file_sync_state(&source.id, deleted_file_id)
                 .await?;
+            sync_state
+                .delete_file_permissions(&source.id, deleted_file_id)
+                .await?;
         }
 
         info!(
@@ -797,6 +896,12 @@ impl SyncManager {
             filtered_users.len()
         );
 
+        let exclusions = self
+            .sdk_client
+            .get_content_exclusions()
+            .await
+            .context("Failed to fetch content exclusion rules")?;
+
         let processed_threads = Arc::new(std::sync::Mutex::new(HashSet::<String>::new()));
 
         info!(
@@ -828,6 +933,7 @@ impl SyncManager {
                             sync_run_id,
                             processed_threads.clone(),
                             Some(&gmail_cutoff_date),
+                            &exclusions,
                         )
                         .await
                     {
@@ -878,6 +984,59 @@ impl SyncManager {
         )
     }
 
+    /// Emit a permissions-only update for an unchanged file whose sharing differs from what
+    /// was last emitted. Failures are logged, the next sync retries.
+    async fn sync_file_permissions(
+        &self,
+        file: &crate::models::GoogleDriveFile,
+        source_id: &str,
+        sync_run_id: &str,
+        sync_state: &SyncState,
+    ) {
+        let permissions = file.to_permissions();
+
+        match sync_state.get_file_permissions(source_id, &file.id).await {
+            Ok(Some(previous))
+                if serde_json::from_str::<DocumentPermissions>(&previous)
+                    .is_ok_and(|previous| previous == permissions) =>
+            {
+                return
+            }
+            Ok(_) => {}
+            Err(e) => {
+                warn!(
+                    "Failed to get permissions state for file {}: {}",
+                    file.name, e
+                );
+                return;
+            }
+        }
+
+        debug!(
+            "Sharing of file {} changed, updating permissions",
+            file.name
+        );
+        let event = file.to_permissions_updated_event(sync_run_id, source_id);
+        if let Err(e) = self
+            .sdk_client
+            .emit_event(sync_run_id, source_id, event)
+            .await
+        {
+            error!(
+                "Failed to queue permissions update for file {}: {:?}",
+                file.name, e
+            );
+            return;
+        }
+
+        if let Err(e) = sync_state
+            .set_file_permissions(source_id, &file.id, &permissions)
+            .await
+        {
+            warn!("Failed to record permissions of file {}: {}", file.name, e);
+        }
+    }
+
     async fn publish_deletion_event(
         &self,
         sync_run_id: &str,
@@ -1126,6 +1285,79 @@ impl SyncManager {
         Ok(())
     }
 
+    /// Name of the exclusion rule matching an indexable Drive file. The file path is only
+    /// resolved when there are rules to check it against.
+    async fn drive_exclusion<'a>(
+        &self,
+        exclusions: &'a ContentExclusions,
+        auth: &ServiceAccountAuth,
+        user_email: &str,
+        file: &crate::models::GoogleDriveFile,
+    ) -> Option<&'a str> {
+        if exclusions.is_empty() || !self.should_index_file(file) {
+            return None;
+        }
+
+        let path = match self.resolve_file_path(auth, user_email, file).await {
+            Ok(path) => path,
+            Err(e) => {
+                warn!("Failed to resolve path for file {}: {}", file.name, e);
+                format!("/{}", file.name)
+            }
+        };
+
+        exclusions.excluded_by(
+            SourceType::GoogleDrive,
+            &ExclusionCandidate {
+                path: Some(&path),
+                mime_type: Some(&file.mime_type),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Download a file's thumbnail and store it for the indexer to use as the document's
+    /// preview. Failures are logged, as the file is indexed without a preview.
+    async fn store_thumbnail(
+        &self,
+        auth: &ServiceAccountAuth,
+        user_file: &UserFile,
+        sync_run_id: &str,
+    ) -> Option<DocumentThumbnail> {
+        let (bytes, mime_type) = match self
+            .drive_client
+            .get_thumbnail(auth, &user_file.user_email, &user_file.file)
+            .await
+        {
+            Ok(thumbnail) => thumbnail?,
+            Err(e) => {
+                warn!(
+                    "Failed to get thumbnail of file {}: {}",
+                    user_file.file.name, e
+                );
+                return None;
+            }
+        };
+
+        match self
+            .sdk_client
+            .store_binary_content(sync_run_id, bytes, &mime_type)
+            .await
+        {
+            Ok(content_id) => Some(DocumentThumbnail {
+                content_id,
+                mime_type,
+            }),
+            Err(e) => {
+                warn!(
+                    "Failed to store thumbnail of file {}: {}",
+                    user_file.file.name, e
+                );
+                None
+            }
+        }
+    }
+
     async fn resolve_file_path(
         &self,
         auth: &ServiceAccountAuth,
@@ -1262,6 +1494,7 @@ impl SyncManager {
         sync_run_id: &str,
         processed_threads: Arc<std::sync::Mutex<HashSet<String>>>,
         created_after: Option<&str>,
+        exclusions: &ContentExclusions,
     ) -> Result<(usize, usize)> {
         info!("Processing Gmail for user: {}", user_email);
 
@@ -1338,6 +1571,16 @@ impl SyncManager {
             user_email
         );
 
+        // Label names are only needed to check threads against exclusion rules
+        let label_names = if exclusions.is_empty() {
+            HashMap::new()
+        } else {
+            self.gmail_client
+                .list_labels(&service_auth, user_email)
+                .await
+                .with_context(|| format!("Failed to list Gmail labels for user {}", user_email))?
+        };
+
         // Step 2: Process threads in batches of 50
         let sync_state = SyncState::new(self.redis_client.clone());
         const THREAD_BATCH_SIZE: usize = 50;
@@ -1424,6 +1667,20 @@ impl SyncManager {
                     gmail_thread.add_message(message);
                 }
 
+                if let Some(rule) = gmail_thread.excluded_by(exclusions, &label_names) {
+                    debug!("Thread {} excluded by rule '{}'", thread_id, rule);
+                    if let Ok(Some(_)) =
+                        sync_state.get_thread_sync_state(source_id, thread_id).await
+                    {
+                        self.publish_deletion_event(sync_run_id, source_id, thread_id)
+                            .await?;
+                        sync_state
+                            .delete_thread_sync_state(source_id, thread_id)
+                            .await?;
+                    }
+                    continue;
+                }
+
                 // Check if we've already indexed this thread by comparing timestamps
                 if !gmail_thread.latest_date.is_empty() {
                     match sync_state
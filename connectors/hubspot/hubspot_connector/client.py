@@ -7,7 +7,12 @@ from typing import Any
 
 from hubspot import HubSpot
 
-from .config import BATCH_SIZE, HUBSPOT_OBJECT_CONFIGS
+from .config import (
+    BATCH_SIZE,
+    HUBSPOT_OBJECT_CONFIGS,
+    LAST_MODIFIED_PROPERTIES,
+    LAST_MODIFIED_PROPERTY,
+)
 
 logger = logging.getLogger(__name__)
 
@@ -164,23 +169,75 @@ class HubSpotClient:
             after=after,
         )
 
-    def _get_api_for_type(self, object_type: str) -> Any:
+    @with_retry(max_retries=3)
+    async def search_modified_since(
+        self,
+        object_type: str,
+        since_ms: int,
+        after: str | None = None,
+    ) -> Any:
+        """
+        Get a page of the objects modified at or after a time, oldest modification first.
+
+        Args:
+            object_type: Type of object (contacts, companies, deals, etc.)
+            since_ms: Milliseconds since epoch to search from
+            after: Cursor for pagination
+
+        Returns:
+            Response with results and paging info
+        """
+        config = HUBSPOT_OBJECT_CONFIGS.get(object_type, {})
+        modified_property = LAST_MODIFIED_PROPERTIES.get(object_type, LAST_MODIFIED_PROPERTY)
+        api = self._get_api_for_type(object_type, "search_api")
+
+        return await asyncio.to_thread(
+            api.do_search,
+            public_object_search_request={
+                "filterGroups": [
+                    {
+                        "filters": [
+                            {
+                                "propertyName": modified_property,
+                                "operator": "GTE",
+                                "value": str(since_ms),
+                            }
+                        ]
+                    }
+                ],
+                "sorts": [{"propertyName": modified_property, "direction": "ASCENDING"}],
+                "properties": config.get("properties", []),
+                "limit": BATCH_SIZE,
+                "after": after,
+            },
+        )
+
+    @with_retry(max_retries=3)
+    async def get_pipelines(self, object_type: str) -> Any:
+        """Get the pipelines of an object type, with their stages."""
+        api = self._client.crm.pipelines.pipelines_api
+        if self._base_url:
+            api.api_client.configuration.host = self._base_url
+        return await asyncio.to_thread(api.get_all, object_type=object_type)
+
+    def _get_api_for_type(self, object_type: str, api_name: str = "basic_api") -> Any:
         """Get the appropriate API client for the object type."""
-        api_map = {
-            "contacts": self._client.crm.contacts.basic_api,
-            "companies": self._client.crm.companies.basic_api,
-            "deals": self._client.crm.deals.basic_api,
-            "tickets": self._client.crm.tickets.basic_api,
-            "calls": self._client.crm.objects.calls.basic_api,
-            "emails": self._client.crm.objects.emails.basic_api,
-            "meetings": self._client.crm.objects.meetings.basic_api,
-            "notes": self._client.crm.objects.notes.basic_api,
-            "tasks": self._client.crm.objects.tasks.basic_api,
+        discovery_map = {
+            "contacts": self._client.crm.contacts,
+            "companies": self._client.crm.companies,
+            "deals": self._client.crm.deals,
+            "tickets": self._client.crm.tickets,
+            "calls": self._client.crm.objects.calls,
+            "emails": self._client.crm.objects.emails,
+            "meetings": self._client.crm.objects.meetings,
+            "notes": self._client.crm.objects.notes,
+            "tasks": self._client.crm.objects.tasks,
         }
 
-        api = api_map.get(object_type)
-        if not api:
+        discovery = discovery_map.get(object_type)
+        if not discovery:
             raise HubSpotError(f"Unsupported object type: {object_type}")
+        api = getattr(discovery, api_name)
 
         if self._base_url:
             api.api_client.configuration.host = self._base_url
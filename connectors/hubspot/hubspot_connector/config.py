@@ -3,6 +3,22 @@
 # Batch size for pagination (HubSpot max is 100)
 BATCH_SIZE = 100
 
+# The CRM search API pages through at most this many results of one query
+SEARCH_RESULT_LIMIT = 10_000
+
+# Property that tracks the last modification of each object type, for incremental syncs
+LAST_MODIFIED_PROPERTY = "hs_lastmodifieddate"
+LAST_MODIFIED_PROPERTIES = {
+    # Contacts keep their modification date in a property of their own
+    "contacts": "lastmodifieddate",
+}
+
+# Object types whose records sit in a pipeline stage, with the properties holding them
+PIPELINE_PROPERTIES = {
+    "deals": ("pipeline", "dealstage"),
+    "tickets": ("hs_pipeline", "hs_pipeline_stage"),
+}
+
 # Object types to sync
 HUBSPOT_OBJECT_TYPES = [
     # CRM Core Objects
@@ -31,6 +47,7 @@ HUBSPOT_OBJECT_CONFIGS: dict[str, dict[str, list[str]]] = {
             "jobtitle",
             "lifecyclestage",
             "createdate",
+            "lastmodifieddate",
             "hs_lastmodifieddate",
             "hubspot_owner_id",
         ],
@@ -53,6 +70,7 @@ HUBSPOT_OBJECT_CONFIGS: dict[str, dict[str, list[str]]] = {
         "title_fields": ["dealname"],
         "properties": [
             "dealname",
+            "description",
             "amount",
             "pipeline",
             "dealstage",
@@ -6,9 +6,14 @@ from typing import Any
 from omni_connector import Connector, Document, SyncContext
 
 from .client import AuthenticationError, ForbiddenError, HubSpotClient, HubSpotError
-from .config import HUBSPOT_OBJECT_TYPES
-from .mappers import generate_content, map_hubspot_object_to_document
-from .pagination import paginate_all
+from .config import HUBSPOT_OBJECT_TYPES, PIPELINE_PROPERTIES
+from .mappers import (
+    PipelineLabels,
+    generate_content,
+    last_modified_ms,
+    map_hubspot_object_to_document,
+)
+from .pagination import paginate_all, paginate_modified_since
 
 logger = logging.getLogger(__name__)
 
@@ -26,7 +31,7 @@ class HubSpotConnector(Connector):
 
     @property
     def sync_modes(self) -> list[str]:
-        return ["full"]
+        return ["full", "incremental"]
 
     async def sync(
         self,
@@ -36,12 +41,16 @@ class HubSpotConnector(Connector):
         ctx: SyncContext,
     ) -> None:
         """
-        Execute full sync of all HubSpot CRM objects.
+        Sync all HubSpot CRM objects.
+
+        The state keeps, per object type, when the last synced object was modified. Object
+        types with one are synced incrementally through the CRM search API, the others are
+        listed in full.
 
         Args:
             source_config: Source configuration (may contain portal_id)
             credentials: Must contain 'access_token'
-            state: Previous sync state (unused for full sync)
+            state: Previous sync state, {"last_modified": {object_type: ms since epoch}}
             ctx: Sync context with emit(), complete(), etc.
         """
         access_token = credentials.get("access_token")
@@ -65,6 +74,7 @@ class HubSpotConnector(Connector):
             return
 
         logger.info("Starting HubSpot sync for portal %s", portal_id or "unknown")
+        last_modified: dict[str, int] = dict((state or {}).get("last_modified", {}))
 
         try:
             # Sync each object type sequentially
@@ -73,9 +83,14 @@ class HubSpotConnector(Connector):
                     await ctx.fail("Cancelled by user")
                     return
 
-                await self._sync_object_type(client, object_type, portal_id, ctx)
+                modified = await self._sync_object_type(
+                    client, object_type, portal_id, last_modified.get(object_type), ctx
+                )
+                if modified is not None:
+                    last_modified[object_type] = modified
+                    await ctx.save_state({"last_modified": last_modified})
 
-            await ctx.complete(new_state={})
+            await ctx.complete(new_state={"last_modified": last_modified})
             logger.info(
                 "Sync completed: %d scanned, %d emitted",
                 ctx.documents_scanned,
@@ -93,37 +108,64 @@ class HubSpotConnector(Connector):
         client: HubSpotClient,
         object_type: str,
         portal_id: str | None,
+        since_ms: int | None,
         ctx: SyncContext,
-    ) -> None:
+    ) -> int | None:
         """
-        Sync all objects of a specific type.
+        Sync the objects of a specific type, only those modified since `since_ms` if set.
 
         Args:
             client: HubSpot API client
             object_type: Type of object to sync
             portal_id: HubSpot portal ID for URL generation
+            since_ms: Last modification synced before, in milliseconds since epoch
             ctx: Sync context
+
+        Returns:
+            The last modification of the synced objects, or None if the type was not synced
+            to the end
         """
-        logger.info("Syncing %s objects", object_type)
+        logger.info(
+            "Syncing %s objects%s",
+            object_type,
+            f" modified since {since_ms}" if since_ms is not None else "",
+        )
         count = 0
+        pipelines = await self._pipelines(client, object_type)
+        modified = since_ms or 0
+
+        if since_ms is not None:
+            objects = paginate_modified_since(
+                client,
+                object_type,
+                since_ms,
+                lambda obj: last_modified_ms(object_type, obj),
+            )
+        else:
+            objects = paginate_all(client, object_type)
 
         try:
-            async for obj in paginate_all(client, object_type):
+            async for obj in objects:
                 if ctx.is_cancelled():
                     logger.info("Sync cancelled during %s sync", object_type)
-                    return
+                    return None
 
                 await ctx.increment_scanned()
                 count += 1
+                modified = max(modified, last_modified_ms(object_type, obj) or 0)
 
                 try:
-                    doc = await self._process_object(object_type, obj, portal_id, ctx)
+                    doc = await self._process_object(
+                        object_type, obj, portal_id, pipelines, ctx
+                    )
                     if doc:
                         await ctx.emit(doc)
                 except Exception as e:
                     external_id = f"{object_type}:{obj.get('id', 'unknown')}"
                     logger.warning("Error processing %s: %s", external_id, e)
                     await ctx.emit_error(external_id, str(e))
+        except AuthenticationError:
+            raise
         except HubSpotError as e:
             logger.error("Error fetching %s objects: %s", object_type, e)
             # Report the failure so user knows this object type was skipped
@@ -131,14 +173,40 @@ class HubSpotConnector(Connector):
                 f"{object_type}:*",
                 f"Failed to fetch {object_type}: {e}",
             )
+            return None
 
         logger.info("Finished syncing %s: %d objects processed", object_type, count)
+        return modified or None
+
+    async def _pipelines(self, client: HubSpotClient, object_type: str) -> PipelineLabels:
+        """Labels of an object type's pipelines and stages, none if it has no pipelines."""
+        if object_type not in PIPELINE_PROPERTIES:
+            return {}
+        try:
+            response = await client.get_pipelines(object_type)
+        except AuthenticationError:
+            raise
+        except HubSpotError as e:
+            logger.warning("Failed to fetch %s pipelines, using their IDs: %s", object_type, e)
+            return {}
+
+        pipelines: PipelineLabels = {}
+        for pipeline in response.results:
+            pipeline = pipeline.to_dict() if hasattr(pipeline, "to_dict") else pipeline
+            pipelines[pipeline["id"]] = {
+                "label": pipeline.get("label"),
+                "stages": {
+                    stage["id"]: stage.get("label") for stage in pipeline.get("stages") or []
+                },
+            }
+        return pipelines
 
     async def _process_object(
         self,
         object_type: str,
         obj: dict[str, Any],
         portal_id: str | None,
+        pipelines: PipelineLabels,
         ctx: SyncContext,
     ) -> Document:
         """
@@ -148,13 +216,14 @@ class HubSpotConnector(Connector):
             object_type: Type of object
             obj: HubSpot object data
             portal_id: HubSpot portal ID
+            pipelines: Labels of the object type's pipelines
             ctx: Sync context
 
         Returns:
             Document instance or None if processing fails
         """
         # Generate content from object properties
-        content = generate_content(object_type, obj)
+        content = generate_content(object_type, obj, pipelines)
         content_id = await ctx.content_storage.save(content, "text/plain")
 
         return map_hubspot_object_to_document(
@@ -162,4 +231,5 @@ class HubSpotConnector(Connector):
             obj=obj,
             content_id=content_id,
             portal_id=portal_id,
+            pipelines=pipelines,
         )
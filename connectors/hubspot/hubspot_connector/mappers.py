@@ -5,7 +5,15 @@ from typing import Any
 
 from omni_connector import Document, DocumentMetadata, DocumentPermissions
 
-from .config import HUBSPOT_OBJECT_CONFIGS
+from .config import (
+    HUBSPOT_OBJECT_CONFIGS,
+    LAST_MODIFIED_PROPERTIES,
+    LAST_MODIFIED_PROPERTY,
+    PIPELINE_PROPERTIES,
+)
+
+# Pipeline labels by pipeline ID: {"label": ..., "stages": {stage ID: label}}
+PipelineLabels = dict[str, dict[str, Any]]
 
 
 def map_hubspot_object_to_document(
@@ -13,6 +21,7 @@ def map_hubspot_object_to_document(
     obj: dict[str, Any],
     content_id: str,
     portal_id: str | None = None,
+    pipelines: PipelineLabels | None = None,
 ) -> Document:
     """
     Map a HubSpot object to an Omni Document.
@@ -23,13 +32,15 @@ def map_hubspot_object_to_document(
     - content_id: Pre-stored content reference
     - metadata: Timestamps, URL, author, mime_type
     - permissions: Set to public (CRM data is org-wide)
-    - attributes: Object type, hubspot properties for filtering
+    - attributes: Object type, hubspot properties for filtering, and the pipeline and
+      stage of deals and tickets
 
     Args:
         object_type: Type of HubSpot object
         obj: HubSpot object data as dict
         content_id: ID from content storage
         portal_id: Optional HubSpot portal ID for URL generation
+        pipelines: Labels of the object type's pipelines, IDs are used without them
 
     Returns:
         Omni Document instance
@@ -49,7 +60,9 @@ def map_hubspot_object_to_document(
         properties.get("createdate") or properties.get("hs_createdate")
     )
     updated_at = _parse_timestamp(
-        properties.get("hs_lastmodifieddate") or properties.get("createdate")
+        properties.get("hs_lastmodifieddate")
+        or properties.get("lastmodifieddate")
+        or properties.get("createdate")
     )
 
     # Build HubSpot URL
@@ -75,17 +88,53 @@ def map_hubspot_object_to_document(
             "source_type": "hubspot",
             "object_type": object_type,
             "hubspot_id": hubspot_id,
+            **pipeline_attributes(object_type, properties, pipelines or {}),
         },
     )
 
 
-def generate_content(object_type: str, obj: dict[str, Any]) -> str:
+def pipeline_attributes(
+    object_type: str,
+    properties: dict[str, Any],
+    pipelines: PipelineLabels,
+) -> dict[str, str]:
+    """The pipeline and stage of a deal or ticket, by label when known."""
+    pipeline_property, stage_property = PIPELINE_PROPERTIES.get(object_type, (None, None))
+    if not pipeline_property or not stage_property:
+        return {}
+
+    attributes: dict[str, str] = {}
+    pipeline_id = properties.get(pipeline_property)
+    pipeline = pipelines.get(pipeline_id or "", {})
+    if pipeline_id:
+        attributes["pipeline"] = pipeline.get("label") or pipeline_id
+    if stage_id := properties.get(stage_property):
+        attributes["stage"] = pipeline.get("stages", {}).get(stage_id) or stage_id
+    return attributes
+
+
+def last_modified_ms(object_type: str, obj: dict[str, Any]) -> int | None:
+    """When an object was last modified, in milliseconds since epoch."""
+    properties = obj.get("properties", {})
+    modified_property = LAST_MODIFIED_PROPERTIES.get(object_type, LAST_MODIFIED_PROPERTY)
+    modified = _parse_timestamp(
+        properties.get(modified_property) or properties.get(LAST_MODIFIED_PROPERTY)
+    )
+    return int(modified.timestamp() * 1000) if modified else None
+
+
+def generate_content(
+    object_type: str,
+    obj: dict[str, Any],
+    pipelines: PipelineLabels | None = None,
+) -> str:
     """
     Generate searchable text content from a HubSpot object.
 
     Args:
         object_type: Type of HubSpot object
         obj: HubSpot object data as dict
+        pipelines: Labels of the object type's pipelines
 
     Returns:
         Plain text content for indexing
@@ -101,6 +150,8 @@ def generate_content(object_type: str, obj: dict[str, Any]) -> str:
     config = HUBSPOT_OBJECT_CONFIGS.get(object_type, {})
     title = _get_title(object_type, properties, config)
     lines.append(f"Title: {title}")
+    for key, value in pipeline_attributes(object_type, properties, pipelines or {}).items():
+        lines.append(f"{key.title()}: {value}")
     lines.append("")
 
     # Add all non-empty properties
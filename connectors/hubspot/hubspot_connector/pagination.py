@@ -1,10 +1,11 @@
 """Pagination utilities for HubSpot API."""
 
 import logging
-from collections.abc import AsyncIterator
+from collections.abc import AsyncIterator, Callable
 from typing import Any
 
 from .client import HubSpotClient
+from .config import BATCH_SIZE, SEARCH_RESULT_LIMIT
 
 logger = logging.getLogger(__name__)
 
@@ -57,3 +58,58 @@ async def paginate_all(
                 page_count,
             )
             break
+
+
+async def paginate_modified_since(
+    client: HubSpotClient,
+    object_type: str,
+    since_ms: int,
+    modified_ms: Callable[[dict[str, Any]], int | None],
+) -> AsyncIterator[dict[str, Any]]:
+    """
+    Async generator that yields the objects of a type modified since a time, oldest first.
+
+    The search API stops paging after SEARCH_RESULT_LIMIT results, so once a query reaches
+    it, the search starts over from the last modification seen. Objects modified at that
+    same moment may be yielded twice.
+
+    Args:
+        client: HubSpot API client
+        object_type: Type of object to fetch (contacts, companies, etc.)
+        since_ms: Milliseconds since epoch to search from
+        modified_ms: Last modification of an object, in milliseconds since epoch
+
+    Yields:
+        Individual HubSpot objects as dictionaries
+    """
+    after: str | None = None
+    fetched = 0
+
+    while True:
+        logger.debug("Searching %s modified since %d (after=%s)", object_type, since_ms, after)
+
+        response = await client.search_modified_since(object_type, since_ms, after=after)
+
+        last_modified: int | None = None
+        for obj in response.results:
+            obj = obj.to_dict() if hasattr(obj, "to_dict") else obj
+            last_modified = modified_ms(obj) or last_modified
+            yield obj
+        fetched += len(response.results)
+
+        if not (response.paging and response.paging.next and response.paging.next.after):
+            break
+        if fetched + BATCH_SIZE > SEARCH_RESULT_LIMIT:
+            if last_modified is None or last_modified <= since_ms:
+                logger.warning(
+                    "More than %d %s modified at %d, skipping the rest",
+                    SEARCH_RESULT_LIMIT,
+                    object_type,
+                    since_ms,
+                )
+                break
+            since_ms = last_modified
+            after = None
+            fetched = 0
+        else:
+            after = response.paging.next.after
@@ -10,6 +10,7 @@ import logging
 import socket
 import threading
 import time
+from datetime import datetime
 from typing import Any
 
 import httpx
@@ -66,6 +67,7 @@ def _contact_payload(
             "jobtitle": "Engineer",
             "lifecyclestage": "lead",
             "createdate": "2024-01-15T10:30:00.000Z",
+            "lastmodifieddate": "2024-06-01T14:00:00.000Z",
             "hs_lastmodifieddate": "2024-06-01T14:00:00.000Z",
             "hubspot_owner_id": "owner-1",
         },
@@ -97,6 +99,7 @@ def _company_payload(
 def _deal_payload(
     obj_id: str = "301",
     dealname: str = "Big Deal",
+    modified: str = "2024-06-10T11:00:00.000Z",
 ) -> dict[str, Any]:
     return _object_payload(
         "deals",
@@ -109,7 +112,7 @@ def _deal_payload(
             "closedate": "2024-12-31T00:00:00.000Z",
             "hs_deal_stage_probability": "0.5",
             "createdate": "2024-02-01T09:00:00.000Z",
-            "hs_lastmodifieddate": "2024-06-10T11:00:00.000Z",
+            "hs_lastmodifieddate": modified,
             "hubspot_owner_id": "owner-1",
         },
     )
@@ -222,6 +225,34 @@ def _task_payload(obj_id: str = "901") -> dict[str, Any]:
 # ---------------------------------------------------------------------------
 
 
+def _pipeline_payload() -> dict[str, Any]:
+    timestamps = {"createdAt": "2024-01-01T00:00:00.000Z", "updatedAt": "2024-01-01T00:00:00.000Z"}
+    return {
+        "id": "default",
+        "label": "Sales Pipeline",
+        "displayOrder": 0,
+        "archived": False,
+        **timestamps,
+        "stages": [
+            {
+                "id": "qualifiedtobuy",
+                "label": "Qualified To Buy",
+                "displayOrder": 1,
+                "metadata": {"probability": "0.4"},
+                "archived": False,
+                "writePermissions": "CRM_PERMISSIONS_ENFORCEMENT",
+                **timestamps,
+            }
+        ],
+    }
+
+
+def _timestamp_ms(value: str | None) -> int:
+    if not value:
+        return 0
+    return int(datetime.fromisoformat(value.replace("Z", "+00:00")).timestamp() * 1000)
+
+
 class MockHubSpotAPI:
     """Controllable mock of the HubSpot CRM v3 API."""
 
@@ -321,8 +352,45 @@ class MockHubSpotAPI:
 
             return JSONResponse(body)
 
+        async def search_objects(request: Request) -> JSONResponse:
+            object_type = request.path_params["object_type"]
+            if object_type in mock.forbidden_types:
+                return JSONResponse({"status": "error"}, status_code=403)
+
+            search = await request.json()
+            items = mock.objects.get(object_type, [])
+            for group in search.get("filterGroups", []):
+                for f in group["filters"]:
+                    assert f["operator"] == "GTE"
+                    items = [
+                        item
+                        for item in items
+                        if _timestamp_ms(item["properties"].get(f["propertyName"]))
+                        >= int(f["value"])
+                    ]
+            for sort in search.get("sorts", []):
+                items = sorted(
+                    items,
+                    key=lambda item: _timestamp_ms(item["properties"].get(sort["propertyName"])),
+                )
+
+            limit = int(search.get("limit") or 100)
+            start = int(search.get("after") or 0)
+            body: dict[str, Any] = {"total": len(items), "results": items[start : start + limit]}
+            if start + limit < len(items):
+                body["paging"] = {"next": {"after": str(start + limit)}}
+            return JSONResponse(body)
+
+        async def list_pipelines(request: Request) -> JSONResponse:
+            object_type = request.path_params["object_type"]
+            if object_type != "deals":
+                return JSONResponse({"results": []})
+            return JSONResponse({"results": [_pipeline_payload()]})
+
         routes = [
             Route("/crm/v3/objects/{object_type}", list_objects),
+            Route("/crm/v3/objects/{object_type}/search", search_objects, methods=["POST"]),
+            Route("/crm/v3/pipelines/{object_type}", list_pipelines),
         ]
         return Starlette(routes=routes)
 
@@ -0,0 +1,40 @@
+"""Integration tests: incremental sync searches only the objects modified since the last sync."""
+
+import pytest
+import httpx
+
+from omni_connector.testing import wait_for_sync
+
+pytestmark = pytest.mark.integration
+
+
+async def _sync(harness, cm_client: httpx.AsyncClient, source_id: str, sync_type: str):
+    resp = await cm_client.post(
+        "/sync",
+        json={"source_id": source_id, "sync_type": sync_type},
+    )
+    assert resp.status_code == 200, resp.text
+    row = await wait_for_sync(harness.db_pool, resp.json()["sync_run_id"], timeout=30)
+    assert (
+        row["status"] == "completed"
+    ), f"Sync ended with status={row['status']}, error={row.get('error_message')}"
+    return row
+
+
+async def test_incremental_sync_fetches_modified_objects(
+    harness, seed, source_id, mock_hubspot_api, cm_client: httpx.AsyncClient
+):
+    mock_hubspot_api.add_contact("131")
+    mock_hubspot_api.add_deal("331", modified="2024-06-10T11:00:00.000Z")
+
+    await _sync(harness, cm_client, source_id, "full")
+    state = await seed.get_connector_state(source_id)
+    assert state["last_modified"]["deals"] == 1718017200000
+
+    mock_hubspot_api.add_deal("332", dealname="New Deal", modified="2024-07-01T09:00:00.000Z")
+    row = await _sync(harness, cm_client, source_id, "incremental")
+
+    # Objects modified at their type's watermark are searched again, with the new deal
+    assert row["documents_scanned"] == 3, f"Got {row['documents_scanned']} scanned"
+    state = await seed.get_connector_state(source_id)
+    assert state["last_modified"]["deals"] == 1719824400000
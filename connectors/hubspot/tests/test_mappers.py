@@ -4,9 +4,22 @@ from hubspot_connector.mappers import (
     _contact_title,
     _parse_timestamp,
     generate_content,
+    last_modified_ms,
     map_hubspot_object_to_document,
 )
 
+DEAL = {
+    "id": "301",
+    "properties": {
+        "dealname": "Big Deal",
+        "description": "Renewal with a seat expansion",
+        "pipeline": "default",
+        "dealstage": "qualifiedtobuy",
+        "hs_lastmodifieddate": "2024-06-10T11:00:00Z",
+    },
+}
+PIPELINES = {"default": {"label": "Sales Pipeline", "stages": {"qualifiedtobuy": "Qualified"}}}
+
 
 class TestMapHubSpotObjectToDocument:
     """Tests for the main mapping function."""
@@ -41,6 +54,23 @@ class TestMapHubSpotObjectToDocument:
 
         assert doc.metadata.url is None
 
+    def test_deal_pipeline_attributes(self):
+        """Test deals carry their pipeline and stage, by label when known."""
+        doc = map_hubspot_object_to_document("deals", DEAL, "content-id", pipelines=PIPELINES)
+        assert doc.attributes["pipeline"] == "Sales Pipeline"
+        assert doc.attributes["stage"] == "Qualified"
+
+        doc = map_hubspot_object_to_document("deals", DEAL, "content-id")
+        assert doc.attributes["pipeline"] == "default"
+        assert doc.attributes["stage"] == "qualifiedtobuy"
+
+    def test_note_has_no_pipeline_attributes(self):
+        """Test objects outside pipelines get no pipeline attributes."""
+        note = {"id": "801", "properties": {"hs_note_body": "Call notes"}}
+        doc = map_hubspot_object_to_document("notes", note, "content-id", pipelines=PIPELINES)
+        assert "pipeline" not in doc.attributes
+        assert "stage" not in doc.attributes
+
 
 class TestGetTitle:
     """Tests for title generation."""
@@ -84,6 +114,13 @@ class TestParseTimestamp:
         result = _parse_timestamp(None)
         assert result is None
 
+    def test_last_modified_ms(self):
+        """Test reading the modification date incremental syncs filter on."""
+        assert last_modified_ms("deals", DEAL) == 1718017200000
+        contact = {"properties": {"lastmodifieddate": "2024-06-10T11:00:00Z"}}
+        assert last_modified_ms("contacts", contact) == 1718017200000
+        assert last_modified_ms("notes", {"properties": {}}) is None
+
 
 class TestGenerateContent:
     """Tests for content generation."""
@@ -96,3 +133,11 @@ class TestGenerateContent:
         assert "Title: John Doe" in content
         assert "john.doe@example.com" in content
         assert "Acme Corp" in content
+
+    def test_deal_content_includes_description_and_stage(self):
+        """Test deal content carries the description and pipeline labels."""
+        content = generate_content("deals", DEAL, PIPELINES)
+
+        assert "Pipeline: Sales Pipeline" in content
+        assert "Stage: Qualified" in content
+        assert "Renewal with a seat expansion" in content
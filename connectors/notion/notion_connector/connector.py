@@ -4,11 +4,12 @@ import logging
 from datetime import datetime, timezone
 from typing import Any
 
-from omni_connector import Connector, SyncContext
+from omni_connector import Connector, DocumentPermissions, SyncContext
 
 from .client import AuthenticationError, NotionClient, NotionError
 from .config import CHECKPOINT_INTERVAL, RATE_LIMIT_DELAY
 from .mappers import (
+    build_permissions,
     generate_database_content,
     generate_page_content,
     map_database_to_document,
@@ -67,10 +68,11 @@ class NotionConnector(Connector):
         state = state or {}
 
         try:
+            members = await self._load_members(client)
             if state.get("last_sync_at"):
-                await self._incremental_sync(client, state, ctx)
+                await self._incremental_sync(client, state, members, ctx)
             else:
-                await self._full_sync(client, ctx)
+                await self._full_sync(client, state, members, ctx)
         except AuthenticationError as e:
             logger.error("Authentication error during sync: %s", e)
             await ctx.fail(f"Authentication failed: {e}")
@@ -80,263 +82,212 @@ class NotionConnector(Connector):
         finally:
             await client.close()
 
+    async def _load_members(self, client: NotionClient) -> list[str]:
+        """Emails of the workspace's members, who can see the pages shared with the bot."""
+        try:
+            users = await client.list_users()
+        except NotionError as e:
+            logger.warning(
+                "Cannot list workspace members, pages will only be visible if published: %s",
+                e,
+            )
+            return []
+        return [
+            user["person"]["email"].lower()
+            for user in users
+            if user.get("type") == "person" and (user.get("person") or {}).get("email")
+        ]
+
     async def _full_sync(
         self,
         client: NotionClient,
+        state: dict[str, Any],
+        members: list[str],
         ctx: SyncContext,
     ) -> None:
         """Full sync: index all accessible databases and pages."""
-        docs_emitted = 0
-        database_page_ids: set[str] = set()
-
-        # Phase 1: discover and index all databases + their entries
-        cursor: str | None = None
-        while True:
-            if ctx.is_cancelled():
-                await ctx.fail("Cancelled by user")
-                return
-
-            response = await client.search_databases(start_cursor=cursor)
-            databases = response.get("results", [])
-
-            for db in databases:
-                if ctx.is_cancelled():
-                    await ctx.fail("Cancelled by user")
-                    return
-
-                await ctx.increment_scanned()
-                db_id = db["id"]
-
-                try:
-                    docs_emitted = await self._sync_database(
-                        client, db, ctx, docs_emitted
-                    )
-                    entry_ids = await self._sync_database_entries(
-                        client, db_id, ctx, docs_emitted
-                    )
-                    database_page_ids.update(entry_ids[0])
-                    docs_emitted = entry_ids[1]
-                except NotionError as e:
-                    logger.error("Error syncing database %s: %s", db_id, e)
-                    await ctx.emit_error(f"notion:database:{db_id}", str(e))
-
-            if not response.get("has_more"):
-                break
-            cursor = response.get("next_cursor")
-
-        # Phase 2: index standalone pages (not in any database)
-        cursor = None
-        while True:
-            if ctx.is_cancelled():
-                await ctx.fail("Cancelled by user")
-                return
-
-            response = await client.search_pages(start_cursor=cursor)
-            pages = response.get("results", [])
-
-            for page in pages:
-                if ctx.is_cancelled():
-                    await ctx.fail("Cancelled by user")
-                    return
-
-                page_id = page["id"]
-                if page_id in database_page_ids:
-                    continue
-
-                await ctx.increment_scanned()
-                try:
-                    docs_emitted = await self._sync_page(
-                        client, page, ctx, docs_emitted, is_database_entry=False
-                    )
-                except Exception as e:
-                    eid = f"notion:page:{page_id}"
-                    logger.warning("Error processing %s: %s", eid, e)
-                    await ctx.emit_error(eid, str(e))
-
-                if docs_emitted >= CHECKPOINT_INTERVAL:
-                    await ctx.save_state({})
-                    docs_emitted = 0
-
-            if not response.get("has_more"):
-                break
-            cursor = response.get("next_cursor")
-
-        now = datetime.now(timezone.utc).isoformat()
-        await ctx.complete(new_state={"last_sync_at": now})
-        logger.info(
-            "Full sync completed: %d scanned, %d emitted",
-            ctx.documents_scanned,
-            ctx.documents_emitted,
-        )
+        run = _Run(client, state, members, ctx, since=None)
+        if await run.sync_all():
+            await run.complete("Full sync")
 
     async def _incremental_sync(
         self,
         client: NotionClient,
         state: dict[str, Any],
+        members: list[str],
         ctx: SyncContext,
     ) -> None:
         """Incremental sync: re-index pages/databases modified since last sync."""
-        last_sync_at = state["last_sync_at"]
-        docs_emitted = 0
+        run = _Run(client, state, members, ctx, since=state["last_sync_at"])
+        if await run.sync_all():
+            await run.complete("Incremental sync")
 
-        # Search pages sorted by last_edited_time (most recent first)
-        cursor: str | None = None
-        while True:
-            if ctx.is_cancelled():
-                await ctx.fail("Cancelled by user")
-                return
-
-            response = await client.search_pages(start_cursor=cursor)
-            pages = response.get("results", [])
-
-            found_old = False
-            for page in pages:
-                if ctx.is_cancelled():
-                    await ctx.fail("Cancelled by user")
-                    return
-
-                edited_time = page.get("last_edited_time", "")
-                if edited_time and edited_time < last_sync_at:
-                    found_old = True
-                    break
-
-                await ctx.increment_scanned()
-                page_id = page["id"]
-                is_db_entry = page.get("parent", {}).get("type") == "database_id"
 
-                try:
-                    docs_emitted = await self._sync_page(
-                        client, page, ctx, docs_emitted, is_database_entry=is_db_entry
-                    )
-                except Exception as e:
-                    eid = f"notion:page:{page_id}"
-                    logger.warning("Error processing %s: %s", eid, e)
-                    await ctx.emit_error(eid, str(e))
+class _Run:
+    """One sync run: what it has seen, to remove the pages and databases it no longer can."""
 
-                if docs_emitted >= CHECKPOINT_INTERVAL:
-                    await ctx.save_state({"last_sync_at": last_sync_at})
-                    docs_emitted = 0
+    def __init__(
+        self,
+        client: NotionClient,
+        state: dict[str, Any],
+        members: list[str],
+        ctx: SyncContext,
+        since: str | None,
+    ):
+        self.client = client
+        self.ctx = ctx
+        self.members = members
+        # Only pages and databases edited since this time are synced again, all when None
+        self.since = since
+        self.started_at = datetime.now(timezone.utc).isoformat()
+        self.previous_page_ids: set[str] = set(state.get("page_ids", []))
+        self.previous_database_ids: set[str] = set(state.get("database_ids", []))
+        self.page_ids: set[str] = set()
+        self.database_ids: set[str] = set()
+        # Deletions are only safe when every database and page could be listed
+        self.complete_listing = True
+        self.docs_emitted = 0
+
+    def _changed(self, item: dict[str, Any]) -> bool:
+        edited_time = item.get("last_edited_time", "")
+        return self.since is None or not edited_time or edited_time >= self.since
+
+    async def sync_all(self) -> bool:
+        """Sync every database, its entries, and standalone page. False when cancelled."""
+        # Phase 1: discover and index all databases + their entries
+        cursor: str | None = None
+        while not self.ctx.is_cancelled():
+            response = await self.client.search_databases(start_cursor=cursor)
+            for db in response.get("results", []):
+                if self.ctx.is_cancelled():
+                    break
+                await self._sync_database(db)
 
-            if found_old or not response.get("has_more"):
+            if not response.get("has_more"):
                 break
             cursor = response.get("next_cursor")
 
-        # Also check for modified databases
+        # Phase 2: index standalone pages (not in any database)
         cursor = None
-        while True:
-            if ctx.is_cancelled():
-                await ctx.fail("Cancelled by user")
-                return
-
-            response = await client.search_databases(start_cursor=cursor)
-            databases = response.get("results", [])
-
-            found_old = False
-            for db in databases:
-                edited_time = db.get("last_edited_time", "")
-                if edited_time and edited_time < last_sync_at:
-                    found_old = True
+        while not self.ctx.is_cancelled():
+            response = await self.client.search_pages(start_cursor=cursor)
+            for page in response.get("results", []):
+                if self.ctx.is_cancelled():
                     break
+                if page["id"] in self.page_ids:
+                    continue
+                is_db_entry = page.get("parent", {}).get("type") == "database_id"
+                self.page_ids.add(page["id"])
+                if self._changed(page):
+                    await self._sync_page(page, is_database_entry=is_db_entry)
 
-                await ctx.increment_scanned()
-                try:
-                    docs_emitted = await self._sync_database(
-                        client, db, ctx, docs_emitted
-                    )
-                except Exception as e:
-                    db_id = db["id"]
-                    logger.warning("Error processing database %s: %s", db_id, e)
-                    await ctx.emit_error(f"notion:database:{db_id}", str(e))
-
-            if found_old or not response.get("has_more"):
+            if not response.get("has_more"):
                 break
             cursor = response.get("next_cursor")
 
-        now = datetime.now(timezone.utc).isoformat()
-        await ctx.complete(new_state={"last_sync_at": now})
-        logger.info(
-            "Incremental sync completed: %d scanned, %d emitted",
-            ctx.documents_scanned,
-            ctx.documents_emitted,
-        )
-
-    async def _sync_database(
-        self,
-        client: NotionClient,
-        database: dict[str, Any],
-        ctx: SyncContext,
-        docs_emitted: int,
-    ) -> int:
-        """Emit a document for the database itself. Returns updated docs_emitted."""
-        content = generate_database_content(database)
-        content_id = await ctx.content_storage.save(content, "text/plain")
-        doc = map_database_to_document(database, content_id)
-        await ctx.emit(doc)
-        docs_emitted += 1
-        return docs_emitted
-
-    async def _sync_database_entries(
-        self,
-        client: NotionClient,
-        database_id: str,
-        ctx: SyncContext,
-        docs_emitted: int,
-    ) -> tuple[set[str], int]:
-        """Sync all pages within a database. Returns (page_ids, docs_emitted)."""
-        page_ids: set[str] = set()
-        cursor: str | None = None
+        if self.ctx.is_cancelled():
+            await self.ctx.fail("Cancelled by user")
+            return False
+        return True
 
-        while True:
-            if ctx.is_cancelled():
-                break
+    async def _sync_database(self, db: dict[str, Any]) -> None:
+        db_id = db["id"]
+        self.database_ids.add(db_id)
+        try:
+            if self._changed(db):
+                await self.ctx.increment_scanned()
+                content = generate_database_content(db)
+                content_id = await self.ctx.content_storage.save(content, "text/plain")
+                await self.ctx.emit(map_database_to_document(db, content_id, self._permissions(db)))
+                self.docs_emitted += 1
+            await self._sync_database_entries(db_id)
+        except NotionError as e:
+            self.complete_listing = False
+            logger.error("Error syncing database %s: %s", db_id, e)
+            await self.ctx.emit_error(f"notion:database:{db_id}", str(e))
 
-            response = await client.query_database(database_id, start_cursor=cursor)
-            pages = response.get("results", [])
+    async def _sync_database_entries(self, database_id: str) -> None:
+        """Sync the pages of a database edited since the last sync."""
+        cursor: str | None = None
 
-            for page in pages:
-                if ctx.is_cancelled():
+        while not self.ctx.is_cancelled():
+            response = await self.client.query_database(database_id, start_cursor=cursor)
+            for page in response.get("results", []):
+                if self.ctx.is_cancelled():
                     break
-
-                page_id = page["id"]
-                page_ids.add(page_id)
-                await ctx.increment_scanned()
-
-                try:
-                    docs_emitted = await self._sync_page(
-                        client, page, ctx, docs_emitted, is_database_entry=True
-                    )
-                except Exception as e:
-                    eid = f"notion:page:{page_id}"
-                    logger.warning("Error processing %s: %s", eid, e)
-                    await ctx.emit_error(eid, str(e))
-
-                if docs_emitted >= CHECKPOINT_INTERVAL:
-                    await ctx.save_state({})
-                    docs_emitted = 0
+                self.page_ids.add(page["id"])
+                if self._changed(page):
+                    await self._sync_page(page, is_database_entry=True)
 
             if not response.get("has_more"):
                 break
             cursor = response.get("next_cursor")
 
-        return page_ids, docs_emitted
-
-    async def _sync_page(
-        self,
-        client: NotionClient,
-        page: dict[str, Any],
-        ctx: SyncContext,
-        docs_emitted: int,
-        is_database_entry: bool,
-    ) -> int:
+    async def _sync_page(self, page: dict[str, Any], is_database_entry: bool) -> None:
         """Fetch blocks for a page, generate content, and emit document."""
         page_id = page["id"]
-        blocks = await client.get_all_blocks(page_id)
-        properties = page.get("properties") if is_database_entry else None
-        content = generate_page_content(page, blocks, properties)
-        content_id = await ctx.content_storage.save(content, "text/plain")
-        doc = map_page_to_document(
-            page, content_id, is_database_entry=is_database_entry
+        await self.ctx.increment_scanned()
+        try:
+            blocks = await self.client.get_all_blocks(page_id)
+            properties = page.get("properties") if is_database_entry else None
+            content = generate_page_content(page, blocks, properties)
+            content_id = await self.ctx.content_storage.save(content, "text/plain")
+            doc = map_page_to_document(
+                page,
+                content_id,
+                self._permissions(page),
+                is_database_entry=is_database_entry,
+            )
+            await self.ctx.emit(doc)
+            self.docs_emitted += 1
+        except AuthenticationError:
+            raise
+        except Exception as e:
+            eid = f"notion:page:{page_id}"
+            logger.warning("Error processing %s: %s", eid, e)
+            await self.ctx.emit_error(eid, str(e))
+
+        if self.docs_emitted >= CHECKPOINT_INTERVAL:
+            await self.ctx.save_state(self._checkpoint_state())
+            self.docs_emitted = 0
+
+    def _permissions(self, item: dict[str, Any]) -> DocumentPermissions:
+        return build_permissions(item, self.members)
+
+    def _checkpoint_state(self) -> dict[str, Any]:
+        """State to resume from: the previous sync's, as this one has not finished."""
+        if self.since is None:
+            return {}
+        return {
+            "last_sync_at": self.since,
+            "page_ids": sorted(self.previous_page_ids),
+            "database_ids": sorted(self.previous_database_ids),
+        }
+
+    async def _delete_missing(self) -> None:
+        """Delete the pages and databases deleted, or no longer shared, since the last sync."""
+        if not self.complete_listing:
+            logger.warning("Some databases could not be listed, skipping deletions")
+            self.page_ids |= self.previous_page_ids
+            self.database_ids |= self.previous_database_ids
+            return
+        for page_id in sorted(self.previous_page_ids - self.page_ids):
+            await self.ctx.emit_deleted(f"notion:page:{page_id}")
+        for db_id in sorted(self.previous_database_ids - self.database_ids):
+            await self.ctx.emit_deleted(f"notion:database:{db_id}")
+
+    async def complete(self, label: str) -> None:
+        await self._delete_missing()
+        await self.ctx.complete(
+            new_state={
+                "last_sync_at": self.started_at,
+                "page_ids": sorted(self.page_ids),
+                "database_ids": sorted(self.database_ids),
+            }
+        )
+        logger.info(
+            "%s completed: %d scanned, %d emitted",
+            label,
+            self.ctx.documents_scanned,
+            self.ctx.documents_emitted,
         )
-        await ctx.emit(doc)
-        docs_emitted += 1
-        return docs_emitted
@@ -8,9 +8,23 @@ from omni_connector import Document, DocumentMetadata, DocumentPermissions
 from .config import MAX_CONTENT_LENGTH
 
 
+def build_permissions(item: dict[str, Any], members: list[str]) -> DocumentPermissions:
+    """
+    Who can see a page or database.
+
+    The API does not expose who a page is shared with, so pages shared with the integration
+    are visible to every member of the workspace; guests are left out. Pages published to
+    the web are public.
+    """
+    if item.get("public_url"):
+        return DocumentPermissions(public=True)
+    return DocumentPermissions(public=False, users=members)
+
+
 def map_page_to_document(
     page: dict[str, Any],
     content_id: str,
+    permissions: DocumentPermissions,
     is_database_entry: bool = False,
 ) -> Document:
     """Map a Notion page to an Omni Document."""
@@ -37,7 +51,7 @@ def map_page_to_document(
             url=page.get("url"),
             mime_type="text/plain",
         ),
-        permissions=DocumentPermissions(public=False),
+        permissions=permissions,
         attributes=attributes,
     )
 
@@ -45,6 +59,7 @@ def map_page_to_document(
 def map_database_to_document(
     database: dict[str, Any],
     content_id: str,
+    permissions: DocumentPermissions,
 ) -> Document:
     """Map a Notion database to an Omni Document."""
     db_id = database["id"]
@@ -62,7 +77,7 @@ def map_database_to_document(
             url=database.get("url"),
             mime_type="text/plain",
         ),
-        permissions=DocumentPermissions(public=False),
+        permissions=permissions,
         attributes={
             "source_type": "notion",
             "content_type": "database",
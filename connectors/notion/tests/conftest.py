@@ -129,6 +129,7 @@ class MockNotionAPI:
         self.databases: dict[str, dict[str, Any]] = {}
         self.database_pages: dict[str, list[dict[str, Any]]] = {}
         self.blocks: dict[str, list[dict[str, Any]]] = {}
+        self.users: list[dict[str, Any]] = []
         self.should_fail_auth: bool = False
 
     def reset(self) -> None:
@@ -136,6 +137,7 @@ class MockNotionAPI:
         self.databases.clear()
         self.database_pages.clear()
         self.blocks.clear()
+        self.users.clear()
         self.should_fail_auth = False
 
     def add_page(
@@ -148,6 +150,17 @@ class MockNotionAPI:
         self.pages[page_id] = _page_payload(page_id, title, parent=parent)
         self.blocks[page_id] = blocks
 
+    def add_user(self, user_id: str, name: str, email: str) -> None:
+        self.users.append(
+            {
+                "object": "user",
+                "id": user_id,
+                "type": "person",
+                "name": name,
+                "person": {"email": email},
+            }
+        )
+
     def add_database(
         self,
         db_id: str,
@@ -196,6 +209,17 @@ class MockNotionAPI:
                 }
             )
 
+        async def list_users(request: Request) -> JSONResponse:
+            return JSONResponse(
+                {
+                    "object": "list",
+                    "results": mock.users,
+                    "has_more": False,
+                    "next_cursor": None,
+                    "type": "user",
+                }
+            )
+
         async def search(request: Request) -> JSONResponse:
             if mock.should_fail_auth:
                 return JSONResponse(
@@ -275,6 +299,7 @@ class MockNotionAPI:
 
         routes = [
             Route("/v1/users/me", users_me),
+            Route("/v1/users", list_users),
             Route("/v1/search", search, methods=["POST"]),
             Route(
                 "/v1/databases/{database_id}/query", query_database, methods=["POST"]
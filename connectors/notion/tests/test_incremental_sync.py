@@ -0,0 +1,57 @@
+"""Integration tests: incremental sync removes the pages no longer shared with the integration."""
+
+import pytest
+import httpx
+
+from omni_connector.testing import get_events, wait_for_sync
+
+from .conftest import _block_payload
+
+pytestmark = pytest.mark.integration
+
+KEPT_PAGE_ID = "pg-00000000-0000-0000-0000-000000000011"
+REMOVED_PAGE_ID = "pg-00000000-0000-0000-0000-000000000012"
+
+
+async def _sync(harness, cm_client: httpx.AsyncClient, source_id: str, sync_type: str):
+    resp = await cm_client.post(
+        "/sync",
+        json={"source_id": source_id, "sync_type": sync_type},
+    )
+    assert resp.status_code == 200, resp.text
+    row = await wait_for_sync(harness.db_pool, resp.json()["sync_run_id"], timeout=30)
+    assert (
+        row["status"] == "completed"
+    ), f"Sync ended with status={row['status']}, error={row.get('error_message')}"
+    return row
+
+
+async def test_incremental_sync_deletes_removed_pages(
+    harness, seed, source_id, mock_notion_api, cm_client: httpx.AsyncClient
+):
+    mock_notion_api.add_user("user-001", "Ada Lovelace", "Ada@example.com")
+    mock_notion_api.add_page(
+        KEPT_PAGE_ID, "Roadmap", [_block_payload("blk-011", "paragraph", "Q3 goals")]
+    )
+    mock_notion_api.add_page(
+        REMOVED_PAGE_ID, "Old Notes", [_block_payload("blk-012", "paragraph", "Draft")]
+    )
+
+    await _sync(harness, cm_client, source_id, "full")
+    state = await seed.get_connector_state(source_id)
+    assert state["page_ids"] == [KEPT_PAGE_ID, REMOVED_PAGE_ID]
+
+    events = await get_events(harness.db_pool, source_id)
+    created = [e for e in events if e["event_type"] == "document_created"]
+    permissions = created[0]["payload"]["permissions"]
+    assert permissions["public"] is False
+    assert permissions["users"] == ["ada@example.com"]
+
+    del mock_notion_api.pages[REMOVED_PAGE_ID]
+    await _sync(harness, cm_client, source_id, "incremental")
+
+    events = await get_events(harness.db_pool, source_id)
+    deleted = [e for e in events if e["event_type"] == "document_deleted"]
+    assert [e["payload"]["document_id"] for e in deleted] == [f"notion:page:{REMOVED_PAGE_ID}"]
+    state = await seed.get_connector_state(source_id)
+    assert state["page_ids"] == [KEPT_PAGE_ID]
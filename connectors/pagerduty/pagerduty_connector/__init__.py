@@ -0,0 +1,6 @@
+"""PagerDuty connector for Omni."""
+
+from .connector import PagerDutyConnector
+
+__version__ = "1.0.0"
+__all__ = ["PagerDutyConnector"]
@@ -0,0 +1,210 @@
+"""Thin async wrapper over the PagerDuty REST API with retry logic."""
+
+import asyncio
+import logging
+from collections.abc import AsyncIterator
+from functools import wraps
+from typing import Any
+
+import httpx
+
+from .config import PAGE_LIMIT, PAGERDUTY_API_URL
+
+logger = logging.getLogger(__name__)
+
+
+class PagerDutyError(Exception):
+    """Base exception for PagerDuty API errors."""
+
+    def __init__(self, message: str, status_code: int | None = None):
+        super().__init__(message)
+        self.status_code = status_code
+
+
+class AuthenticationError(PagerDutyError):
+    """Invalid API key or expired token (401)."""
+
+    pass
+
+
+class ForbiddenError(PagerDutyError):
+    """The key cannot access a resource, or lacks a scope (403)."""
+
+    pass
+
+
+class NotFoundError(PagerDutyError):
+    """Incident not found, or not visible to the key (404)."""
+
+    pass
+
+
+def with_retry(max_retries: int = 3, base_delay: float = 1.0):
+    """Decorator for retrying PagerDuty API calls with exponential backoff.
+
+    Handles:
+    - 429 Rate Limit: Wait for Retry-After header (unlimited retries)
+    - 5xx Server Error: Exponential backoff (limited retries)
+    - 401/403: Re-raise as AuthenticationError/ForbiddenError (non-retryable)
+    - 404: Re-raise as NotFoundError (non-retryable)
+    """
+
+    def decorator(func):
+        @wraps(func)
+        async def wrapper(*args, **kwargs):
+            last_exception = None
+            error_retries = 0
+
+            while True:
+                try:
+                    return await func(*args, **kwargs)
+                except httpx.HTTPStatusError as e:
+                    last_exception = e
+                    status = e.response.status_code
+
+                    if status == 401:
+                        raise AuthenticationError(
+                            "Invalid PagerDuty API key or token", status_code=401
+                        ) from e
+
+                    if status == 403:
+                        raise ForbiddenError(
+                            f"Forbidden: {e.request.url.path}: {e.response.text}",
+                            status_code=403,
+                        ) from e
+
+                    if status == 404:
+                        raise NotFoundError(
+                            f"Not found: {e.request.url.path}", status_code=404
+                        ) from e
+
+                    if status == 429:
+                        retry_after = int(e.response.headers.get("Retry-After", "10"))
+                        logger.warning("Rate limited. Waiting %ds", retry_after)
+                        await asyncio.sleep(retry_after)
+                        continue
+
+                    if status >= 500:
+                        error_retries += 1
+                        if error_retries > max_retries:
+                            break
+                        delay = base_delay * (2 ** (error_retries - 1))
+                        logger.warning(
+                            "Server error %d. Retrying in %.1fs (%d/%d)",
+                            status,
+                            delay,
+                            error_retries,
+                            max_retries,
+                        )
+                        await asyncio.sleep(delay)
+                        continue
+
+                    raise PagerDutyError(
+                        f"API error {status}: {e.response.text}",
+                        status_code=status,
+                    ) from e
+
+            raise PagerDutyError(f"Max retries exceeded: {last_exception}") from last_exception
+
+        return wrapper
+
+    return decorator
+
+
+class PagerDutyClient:
+    """Async client for the PagerDuty REST API v2.
+
+    REST API keys are sent as `Token token=...`, OAuth access tokens as a bearer token.
+    """
+
+    def __init__(
+        self,
+        token: str,
+        oauth: bool = False,
+        base_url: str | None = None,
+        http_client: httpx.AsyncClient | None = None,
+    ):
+        self._client = http_client or httpx.AsyncClient(
+            base_url=(base_url or PAGERDUTY_API_URL).rstrip("/"),
+            headers={"Accept": "application/vnd.pagerduty+json;version=2"},
+            timeout=httpx.Timeout(60.0, connect=10.0),
+        )
+        if oauth:
+            self._client.headers["Authorization"] = f"Bearer {token}"
+        else:
+            self._client.headers["Authorization"] = f"Token token={token}"
+
+    async def close(self) -> None:
+        await self._client.aclose()
+
+    @with_retry(max_retries=3)
+    async def get(self, path: str, params: dict[str, Any] | None = None) -> dict[str, Any]:
+        response = await self._client.get(path, params=params)
+        response.raise_for_status()
+        return response.json()
+
+    async def paginate(
+        self, path: str, key: str, params: dict[str, Any] | None = None
+    ) -> AsyncIterator[dict[str, Any]]:
+        """The items under `key` of every page of a listing, by offset."""
+        params = {**(params or {}), "limit": PAGE_LIMIT, "offset": 0}
+        while True:
+            data = await self.get(path, params)
+            for item in data.get(key, []):
+                yield item
+            if not data.get("more"):
+                return
+            params["offset"] += PAGE_LIMIT
+
+    async def get_abilities(self) -> list[str]:
+        """The account's abilities; works with account and user keys alike."""
+        data = await self.get("/abilities")
+        return data.get("abilities", [])
+
+    async def list_incidents(
+        self,
+        since: str,
+        until: str,
+        service_ids: list[str] | None = None,
+        team_ids: list[str] | None = None,
+    ) -> AsyncIterator[dict[str, Any]]:
+        """Incidents created in a time range, in every status, oldest first."""
+        params: dict[str, Any] = {
+            "since": since,
+            "until": until,
+            "statuses[]": ["triggered", "acknowledged", "resolved"],
+            "sort_by": "created_at:asc",
+            "time_zone": "UTC",
+        }
+        if service_ids:
+            params["service_ids[]"] = service_ids
+        if team_ids:
+            params["team_ids[]"] = team_ids
+        async for incident in self.paginate("/incidents", "incidents", params):
+            yield incident
+
+    async def get_incident(self, incident_id: str) -> dict[str, Any]:
+        data = await self.get(f"/incidents/{incident_id}")
+        return data["incident"]
+
+    async def list_notes(self, incident_id: str) -> list[dict[str, Any]]:
+        """The notes of an incident, oldest first."""
+        data = await self.get(f"/incidents/{incident_id}/notes")
+        return data.get("notes", [])
+
+    async def list_changed_incident_ids(self, since: str, until: str) -> list[str]:
+        """
+        IDs of the incidents with a major log entry in a time range, such as being
+        triggered, acknowledged, reassigned, annotated or resolved.
+        """
+        ids: dict[str, None] = {}
+        params = {"since": since, "until": until, "is_overview": "true", "time_zone": "UTC"}
+        async for entry in self.paginate("/log_entries", "log_entries", params):
+            if incident_id := (entry.get("incident") or {}).get("id"):
+                ids[incident_id] = None
+        return list(ids)
+
+    async def list_users(self) -> AsyncIterator[dict[str, Any]]:
+        """Every user, with their email and teams."""
+        async for user in self.paginate("/users", "users"):
+            yield user
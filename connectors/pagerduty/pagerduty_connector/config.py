@@ -0,0 +1,19 @@
+"""Configuration constants for PagerDuty connector."""
+
+PAGERDUTY_API_URL = "https://api.pagerduty.com"
+
+MAX_CONTENT_LENGTH = 100_000
+PAGE_LIMIT = 100
+CHECKPOINT_INTERVAL = 50
+
+# How far back the first sync reads incidents, unless configured
+DEFAULT_LOOKBACK_DAYS = 365
+# Incidents are listed by creation time in windows no longer than the API allows
+INCIDENT_WINDOW_DAYS = 90
+
+# Margin taken off a sync's start time before it becomes the next log entry watermark, for
+# entries PagerDuty records late
+WATERMARK_MARGIN_SECONDS = 300
+
+# Notes whose first line starts with one of these, ignoring case, are postmortems
+DEFAULT_POSTMORTEM_PREFIXES = ["postmortem", "post-mortem", "rca", "root cause"]
@@ -0,0 +1,237 @@
+"""Main PagerDutyConnector class."""
+
+import logging
+from collections import defaultdict
+from datetime import datetime, timedelta, timezone
+from typing import Any
+
+from omni_connector import Connector, SyncContext
+
+from .client import (
+    AuthenticationError,
+    ForbiddenError,
+    NotFoundError,
+    PagerDutyClient,
+    PagerDutyError,
+)
+from .config import (
+    CHECKPOINT_INTERVAL,
+    DEFAULT_LOOKBACK_DAYS,
+    DEFAULT_POSTMORTEM_PREFIXES,
+    INCIDENT_WINDOW_DAYS,
+    WATERMARK_MARGIN_SECONDS,
+)
+from .mappers import (
+    build_permissions,
+    generate_incident_content,
+    generate_postmortem_content,
+    incident_external_id,
+    is_postmortem,
+    map_incident_to_document,
+    map_postmortem_to_document,
+    parse_timestamp,
+)
+
+logger = logging.getLogger(__name__)
+
+
+def _format(value: datetime) -> str:
+    return value.astimezone(timezone.utc).strftime("%Y-%m-%dT%H:%M:%SZ")
+
+
+class PagerDutyConnector(Connector):
+    """PagerDuty connector for Omni."""
+
+    @property
+    def name(self) -> str:
+        return "pagerduty"
+
+    @property
+    def version(self) -> str:
+        return "1.0.0"
+
+    @property
+    def sync_modes(self) -> list[str]:
+        return ["full", "incremental"]
+
+    async def sync(
+        self,
+        source_config: dict[str, Any],
+        credentials: dict[str, Any],
+        state: dict[str, Any] | None,
+        ctx: SyncContext,
+    ) -> None:
+        """
+        Sync incidents, with their notes, and the postmortems written as notes.
+
+        The first sync reads the incidents created in the lookback period. Later syncs read
+        the incidents with a log entry since the previous sync: triggered, acknowledged,
+        reassigned, annotated or resolved.
+
+        Args:
+            source_config: May set service_ids, team_ids, lookback_days, postmortem_prefixes
+                and team_permissions
+            credentials: 'token' (REST API key) or 'access_token' (OAuth)
+            state: Previous sync state, {"log_entries_since": timestamp}, or
+                {"backfill": {"started_at", "created_since"}} for an interrupted first sync
+            ctx: Sync context with emit(), complete(), etc.
+        """
+        token = credentials.get("token") or credentials.get("api_key")
+        access_token = credentials.get("access_token")
+        if not token and not access_token:
+            await ctx.fail("Missing 'token' in credentials")
+            return
+
+        client = PagerDutyClient(
+            token=token or access_token,
+            oauth=not token,
+            base_url=source_config.get("api_url"),
+        )
+        try:
+            try:
+                abilities = await client.get_abilities()
+                logger.info("Starting PagerDuty sync, account abilities: %s", abilities)
+            except (AuthenticationError, ForbiddenError) as e:
+                await ctx.fail(f"Authentication failed: {e}")
+                return
+            except PagerDutyError as e:
+                await ctx.fail(f"Connection test failed: {e}")
+                return
+
+            sync = _Sync(client, source_config, ctx)
+            try:
+                if source_config.get("team_permissions", False):
+                    await sync.load_team_members()
+
+                state = state or {}
+                now = datetime.now(timezone.utc)
+                if since := state.get("log_entries_since"):
+                    await sync.sync_changed(since, _format(now))
+                    started_at = now
+                else:
+                    backfill = state.get("backfill") or {}
+                    started_at = parse_timestamp(backfill.get("started_at")) or now
+                    lookback = source_config.get("lookback_days") or DEFAULT_LOOKBACK_DAYS
+                    created_since = parse_timestamp(backfill.get("created_since")) or (
+                        now - timedelta(days=lookback)
+                    )
+                    await sync.backfill(created_since, started_at, now)
+
+                if ctx.is_cancelled():
+                    await ctx.fail("Cancelled by user")
+                    return
+                watermark = started_at - timedelta(seconds=WATERMARK_MARGIN_SECONDS)
+                await ctx.complete(new_state={"log_entries_since": _format(watermark)})
+                logger.info(
+                    "Sync completed: %d scanned, %d emitted",
+                    ctx.documents_scanned,
+                    ctx.documents_emitted,
+                )
+            except AuthenticationError as e:
+                logger.error("Authentication error during sync: %s", e)
+                await ctx.fail(f"Authentication failed: {e}")
+            except Exception as e:
+                logger.exception("Sync failed with unexpected error")
+                await ctx.fail(str(e))
+        finally:
+            await client.close()
+
+
+class _Sync:
+    """State of one sync run: the filters, team members and checkpoint counter."""
+
+    def __init__(self, client: PagerDutyClient, source_config: dict[str, Any], ctx: SyncContext):
+        self.client = client
+        self.ctx = ctx
+        self.service_ids: list[str] = source_config.get("service_ids") or []
+        self.team_ids: list[str] = source_config.get("team_ids") or []
+        self.prefixes: list[str] = (
+            source_config.get("postmortem_prefixes") or DEFAULT_POSTMORTEM_PREFIXES
+        )
+        # Emails of each team's members, when incidents are shared by team
+        self.team_members: dict[str, set[str]] | None = None
+        self._since_checkpoint = 0
+
+    async def load_team_members(self) -> None:
+        members: dict[str, set[str]] = defaultdict(set)
+        async for user in self.client.list_users():
+            if email := user.get("email"):
+                for team in user.get("teams") or []:
+                    members[team["id"]].add(email.lower())
+        self.team_members = dict(members)
+        logger.info("Loaded the members of %d teams", len(members))
+
+    async def backfill(self, created_since: datetime, started_at: datetime, now: datetime) -> None:
+        """Sync the incidents created since a time, in windows the API accepts."""
+        window_start = created_since
+        while window_start < now:
+            window_end = min(window_start + timedelta(days=INCIDENT_WINDOW_DAYS), now)
+            async for incident in self.client.list_incidents(
+                _format(window_start), _format(window_end), self.service_ids, self.team_ids
+            ):
+                if self.ctx.is_cancelled():
+                    return
+                await self.sync_incident(incident)
+                # Incidents come oldest first, so a resumed sync starts from this one
+                resume_from = incident.get("created_at") or _format(window_start)
+                await self._checkpoint(
+                    {"backfill": {"started_at": _format(started_at), "created_since": resume_from}}
+                )
+            window_start = window_end
+
+    async def sync_changed(self, since: str, until: str) -> None:
+        """Sync the incidents with a log entry in a time range."""
+        # Failing to read log entries fails the sync, so the watermark does not skip them
+        incident_ids = await self.client.list_changed_incident_ids(since, until)
+        logger.info("%d incidents changed since %s", len(incident_ids), since)
+
+        for incident_id in incident_ids:
+            if self.ctx.is_cancelled():
+                return
+            try:
+                incident = await self.client.get_incident(incident_id)
+            except (ForbiddenError, NotFoundError) as e:
+                logger.warning("Skipping incident %s: %s", incident_id, e)
+                continue
+            # The watermark only moves once the sync completes, so there is nothing to checkpoint
+            if self._selected(incident):
+                await self.sync_incident(incident)
+
+    async def sync_incident(self, incident: dict[str, Any]) -> None:
+        """Emit an incident, with its notes, and a document for each of its postmortems."""
+        await self.ctx.increment_scanned()
+        try:
+            notes = await self.client.list_notes(incident["id"])
+            permissions = build_permissions(incident, self.team_members)
+            content = generate_incident_content(incident, notes)
+            content_id = await self.ctx.content_storage.save(content, "text/plain")
+            await self.ctx.emit(map_incident_to_document(incident, content_id, permissions))
+
+            for note in notes:
+                if is_postmortem(note, self.prefixes):
+                    content = generate_postmortem_content(incident, note)
+                    content_id = await self.ctx.content_storage.save(content, "text/plain")
+                    await self.ctx.emit(
+                        map_postmortem_to_document(incident, note, content_id, permissions)
+                    )
+        except AuthenticationError:
+            raise
+        except Exception as e:
+            eid = incident_external_id(incident["id"])
+            logger.warning("Error processing %s: %s", eid, e)
+            await self.ctx.emit_error(eid, str(e))
+
+    def _selected(self, incident: dict[str, Any]) -> bool:
+        """Whether an incident is of the configured services and teams."""
+        if self.service_ids and (incident.get("service") or {}).get("id") not in self.service_ids:
+            return False
+        if self.team_ids:
+            teams = {team["id"] for team in incident.get("teams") or []}
+            return bool(teams.intersection(self.team_ids))
+        return True
+
+    async def _checkpoint(self, state: dict[str, Any]) -> None:
+        self._since_checkpoint += 1
+        if self._since_checkpoint >= CHECKPOINT_INTERVAL:
+            self._since_checkpoint = 0
+            await self.ctx.save_state(state)
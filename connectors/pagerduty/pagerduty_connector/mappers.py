@@ -0,0 +1,207 @@
+"""Incident and postmortem-to-Document mapping functions for PagerDuty."""
+
+from datetime import datetime
+from typing import Any
+
+from omni_connector import (
+    Document,
+    DocumentMetadata,
+    DocumentParent,
+    DocumentPermissions,
+    RelationshipType,
+)
+
+from .config import MAX_CONTENT_LENGTH
+
+
+def incident_external_id(incident_id: str) -> str:
+    return f"pagerduty:incident:{incident_id}"
+
+
+def postmortem_external_id(incident_id: str, note_id: str) -> str:
+    return f"pagerduty:postmortem:{incident_id}:{note_id}"
+
+
+def parse_timestamp(value: str | None) -> datetime | None:
+    """Parse a PagerDuty ISO 8601 timestamp."""
+    if not value:
+        return None
+    try:
+        return datetime.fromisoformat(value.replace("Z", "+00:00"))
+    except ValueError:
+        return None
+
+
+def is_postmortem(note: dict[str, Any], prefixes: list[str]) -> bool:
+    """Whether a note's first line starts with one of the postmortem prefixes."""
+    first_line = (note.get("content") or "").strip().split("\n", 1)[0].lower()
+    return any(first_line.startswith(prefix.lower()) for prefix in prefixes)
+
+
+def resolved_at(incident: dict[str, Any]) -> datetime | None:
+    """When a resolved incident was resolved, its last status change for older payloads."""
+    if incident.get("status") != "resolved":
+        return None
+    return parse_timestamp(incident.get("resolved_at") or incident.get("last_status_change_at"))
+
+
+def severity(incident: dict[str, Any]) -> str:
+    """The incident's priority, such as P1, or its urgency for accounts without priorities."""
+    return (incident.get("priority") or {}).get("summary") or incident.get("urgency") or ""
+
+
+def build_permissions(
+    incident: dict[str, Any], team_members: dict[str, set[str]] | None
+) -> DocumentPermissions:
+    """
+    Who can see an incident and its postmortems.
+
+    Without team permissions, every incident is visible to the whole account, so public.
+    With them, an incident of some teams is visible to their members, and one of no team
+    to everyone.
+
+    Args:
+        incident: The incident, with its teams
+        team_members: Emails of each team's members, None without team permissions
+    """
+    teams = [team["id"] for team in incident.get("teams") or []]
+    if team_members is None or not teams:
+        return DocumentPermissions(public=True)
+    users = set().union(*(team_members.get(team, set()) for team in teams))
+    return DocumentPermissions(public=False, users=sorted(users))
+
+
+def _attributes(incident: dict[str, Any]) -> dict[str, Any]:
+    """Attributes an incident and its postmortems share, to filter searches by outage."""
+    created = parse_timestamp(incident.get("created_at"))
+    resolved = resolved_at(incident)
+    return {
+        "source_type": "pagerduty",
+        "service": (incident.get("service") or {}).get("summary"),
+        "severity": severity(incident),
+        "urgency": incident.get("urgency"),
+        "status": incident.get("status"),
+        "teams": ", ".join(team.get("summary") or "" for team in incident.get("teams") or []),
+        "date": created.date().isoformat() if created else None,
+        "resolved_date": resolved.date().isoformat() if resolved else None,
+    }
+
+
+def _title(incident: dict[str, Any]) -> str:
+    return f"#{incident.get('incident_number')}: {incident.get('title') or 'Untitled incident'}"
+
+
+def _author(note: dict[str, Any]) -> str:
+    return (note.get("user") or {}).get("summary") or "Unknown"
+
+
+def generate_incident_content(incident: dict[str, Any], notes: list[dict[str, Any]]) -> str:
+    """Searchable text of an incident, its notes included."""
+    attributes = _attributes(incident)
+    lines = [_title(incident)]
+    for label, value in (
+        ("Service", (incident.get("service") or {}).get("summary")),
+        ("Status", incident.get("status")),
+        ("Severity", severity(incident)),
+        ("Teams", attributes["teams"]),
+        ("Escalation policy", (incident.get("escalation_policy") or {}).get("summary")),
+        ("Triggered", incident.get("created_at")),
+        ("Resolved", resolved.isoformat() if (resolved := resolved_at(incident)) else None),
+    ):
+        if value:
+            lines.append(f"{label}: {value}")
+    description = incident.get("description")
+    if description and description != incident.get("title"):
+        lines.extend(["", description])
+    if notes:
+        lines.extend(["", "--- Notes ---"])
+        for note in notes:
+            lines.append(f"\n{_author(note)} ({note.get('created_at')}):")
+            lines.append((note.get("content") or "").strip())
+    return "\n".join(lines)[:MAX_CONTENT_LENGTH]
+
+
+def map_incident_to_document(
+    incident: dict[str, Any],
+    content_id: str,
+    permissions: DocumentPermissions,
+) -> Document:
+    """
+    Map a PagerDuty incident to an Omni Document.
+
+    Args:
+        incident: The incident
+        content_id: ID from content storage, for its text and notes
+        permissions: Who can see the incident
+
+    Returns:
+        Omni Document instance
+    """
+    resolved = resolved_at(incident)
+    return Document(
+        external_id=incident_external_id(incident["id"]),
+        title=_title(incident),
+        content_id=content_id,
+        metadata=DocumentMetadata(
+            created_at=parse_timestamp(incident.get("created_at")),
+            updated_at=resolved or parse_timestamp(incident.get("last_status_change_at")),
+            url=incident.get("html_url"),
+            mime_type="text/plain",
+            path=(incident.get("service") or {}).get("summary"),
+            extra={
+                "incident_id": incident["id"],
+                "incident_number": incident.get("incident_number"),
+            },
+        ),
+        permissions=permissions,
+        attributes={**_attributes(incident), "content_type": "incident"},
+    )
+
+
+def generate_postmortem_content(incident: dict[str, Any], note: dict[str, Any]) -> str:
+    """Searchable text of a postmortem, headed by the incident it is about."""
+    lines = [f"Postmortem of {_title(incident)}"]
+    if service := (incident.get("service") or {}).get("summary"):
+        lines.append(f"Service: {service}")
+    lines.extend([f"Severity: {severity(incident)}", "", (note.get("content") or "").strip()])
+    return "\n".join(lines)[:MAX_CONTENT_LENGTH]
+
+
+def map_postmortem_to_document(
+    incident: dict[str, Any],
+    note: dict[str, Any],
+    content_id: str,
+    permissions: DocumentPermissions,
+) -> Document:
+    """
+    Map a postmortem note to an Omni Document, a comment of its incident's document.
+
+    Args:
+        incident: The incident the postmortem is about
+        note: The note holding the postmortem
+        content_id: ID from content storage
+        permissions: Who can see the incident
+
+    Returns:
+        Omni Document instance
+    """
+    return Document(
+        external_id=postmortem_external_id(incident["id"], note["id"]),
+        title=f"Postmortem: {incident.get('title') or 'Untitled incident'}",
+        content_id=content_id,
+        metadata=DocumentMetadata(
+            author=_author(note),
+            created_at=parse_timestamp(note.get("created_at")),
+            updated_at=parse_timestamp(note.get("created_at")),
+            url=incident.get("html_url"),
+            mime_type="text/plain",
+            path=(incident.get("service") or {}).get("summary"),
+            extra={"incident_id": incident["id"], "note_id": note["id"]},
+            parent=DocumentParent(
+                external_id=incident_external_id(incident["id"]),
+                relationship=RelationshipType.COMMENT,
+            ),
+        ),
+        permissions=permissions,
+        attributes={**_attributes(incident), "content_type": "postmortem"},
+    )
@@ -0,0 +1,264 @@
+"""Integration test fixtures for the PagerDuty connector.
+
+Session-scoped: harness, mock PagerDuty API server, connector server, connector-manager.
+Function-scoped: seed helper, source_id, httpx client.
+"""
+
+from __future__ import annotations
+
+import logging
+import socket
+import threading
+import time
+from datetime import datetime, timezone
+from typing import Any
+
+import httpx
+import pytest
+import pytest_asyncio
+import uvicorn
+from starlette.applications import Starlette
+from starlette.requests import Request
+from starlette.responses import JSONResponse
+from starlette.routing import Route
+
+from omni_connector.testing import OmniTestHarness, SeedHelper
+
+logger = logging.getLogger(__name__)
+
+
+# ---------------------------------------------------------------------------
+# Mock data payload helpers
+# ---------------------------------------------------------------------------
+
+
+def _incident_payload(incident_id: str, service_id: str, created_at: datetime) -> dict[str, Any]:
+    return {
+        "id": incident_id,
+        "type": "incident",
+        "incident_number": 1,
+        "title": f"Incident {incident_id}",
+        "status": "resolved",
+        "urgency": "high",
+        "service": {"id": service_id, "type": "service_reference", "summary": service_id},
+        "teams": [],
+        "created_at": created_at.astimezone(timezone.utc).strftime("%Y-%m-%dT%H:%M:%SZ"),
+        "html_url": f"https://acme.pagerduty.com/incidents/{incident_id}",
+    }
+
+
+def _note_payload(note_id: str, content: str) -> dict[str, Any]:
+    return {
+        "id": note_id,
+        "content": content,
+        "created_at": "2024-05-02T10:00:00Z",
+        "user": {"id": "PUSER1", "summary": "Alice"},
+    }
+
+
+# ---------------------------------------------------------------------------
+# Mock PagerDuty API
+# ---------------------------------------------------------------------------
+
+
+class MockPagerDutyAPI:
+    """Controllable mock of the PagerDuty REST API v2 endpoints.
+
+    Every incident is listed as changed in the log entries.
+    """
+
+    def __init__(self) -> None:
+        self.incidents: dict[str, dict[str, Any]] = {}
+        self.notes: dict[str, list[dict[str, Any]]] = {}
+        self.windows: list[tuple[str, str]] = []
+
+    def reset(self) -> None:
+        self.incidents.clear()
+        self.notes.clear()
+        self.windows.clear()
+
+    def add_incident(self, incident: dict[str, Any]) -> None:
+        self.incidents[incident["id"]] = incident
+
+    def create_app(self) -> Starlette:
+        mock = self
+
+        def _not_found() -> JSONResponse:
+            return JSONResponse(
+                {"error": {"message": "Not Found", "code": 2100}}, status_code=404
+            )
+
+        async def get_abilities(request: Request) -> JSONResponse:
+            return JSONResponse({"abilities": ["teams", "urgencies"]})
+
+        async def list_incidents(request: Request) -> JSONResponse:
+            since = request.query_params["since"]
+            until = request.query_params["until"]
+            service_ids = request.query_params.getlist("service_ids[]")
+            mock.windows.append((since, until))
+            incidents = [
+                incident
+                for incident in mock.incidents.values()
+                if since <= incident["created_at"] < until
+                and (not service_ids or incident["service"]["id"] in service_ids)
+            ]
+            incidents.sort(key=lambda incident: incident["created_at"])
+            return JSONResponse({"incidents": incidents, "more": False})
+
+        async def get_incident(request: Request) -> JSONResponse:
+            incident = mock.incidents.get(request.path_params["incident_id"])
+            if incident is None:
+                return _not_found()
+            return JSONResponse({"incident": incident})
+
+        async def list_notes(request: Request) -> JSONResponse:
+            notes = mock.notes.get(request.path_params["incident_id"], [])
+            return JSONResponse({"notes": notes})
+
+        async def list_log_entries(request: Request) -> JSONResponse:
+            entries = [
+                {"type": "annotate_log_entry", "incident": {"id": incident_id}}
+                for incident_id in mock.incidents
+            ]
+            return JSONResponse({"log_entries": entries, "more": False})
+
+        routes = [
+            Route("/abilities", get_abilities),
+            Route("/incidents", list_incidents),
+            Route("/incidents/{incident_id}", get_incident),
+            Route("/incidents/{incident_id}/notes", list_notes),
+            Route("/log_entries", list_log_entries),
+        ]
+        return Starlette(routes=routes)
+
+
+# ---------------------------------------------------------------------------
+# Helpers
+# ---------------------------------------------------------------------------
+
+
+def _free_port() -> int:
+    with socket.socket(socket.AF_INET, socket.SOCK_STREAM) as s:
+        s.bind(("", 0))
+        return s.getsockname()[1]
+
+
+def _wait_for_port(port: int, host: str = "localhost", timeout: float = 10) -> None:
+    deadline = time.monotonic() + timeout
+    while time.monotonic() < deadline:
+        try:
+            with socket.create_connection((host, port), timeout=1):
+                return
+        except OSError:
+            time.sleep(0.1)
+    raise TimeoutError(f"Port {port} not open after {timeout}s")
+
+
+# ---------------------------------------------------------------------------
+# Session-scoped fixtures
+# ---------------------------------------------------------------------------
+
+
+@pytest.fixture(scope="session")
+def mock_pagerduty_api() -> MockPagerDutyAPI:
+    return MockPagerDutyAPI()
+
+
+@pytest.fixture(scope="session")
+def mock_pagerduty_server(mock_pagerduty_api: MockPagerDutyAPI) -> str:
+    """Start mock PagerDuty API server in a daemon thread. Returns base URL."""
+    port = _free_port()
+    app = mock_pagerduty_api.create_app()
+    config = uvicorn.Config(app, host="0.0.0.0", port=port, log_level="warning")
+    server = uvicorn.Server(config)
+
+    thread = threading.Thread(target=server.run, daemon=True)
+    thread.start()
+
+    _wait_for_port(port)
+    return f"http://localhost:{port}"
+
+
+@pytest.fixture(scope="session")
+def connector_port() -> int:
+    return _free_port()
+
+
+@pytest.fixture(scope="session")
+def connector_server(connector_port: int) -> str:
+    """Start the PagerDuty connector as a uvicorn server in a daemon thread. Returns base URL."""
+    import os
+
+    os.environ.setdefault("CONNECTOR_MANAGER_URL", "http://localhost:0")
+
+    from pagerduty_connector import PagerDutyConnector
+    from omni_connector.server import create_app
+
+    app = create_app(PagerDutyConnector())
+    config = uvicorn.Config(
+        app, host="0.0.0.0", port=connector_port, log_level="warning"
+    )
+    server = uvicorn.Server(config)
+
+    thread = threading.Thread(target=server.run, daemon=True)
+    thread.start()
+
+    _wait_for_port(connector_port)
+    return f"http://localhost:{connector_port}"
+
+
+@pytest_asyncio.fixture(scope="session")
+async def harness(
+    connector_server: str,
+    connector_port: int,
+) -> OmniTestHarness:
+    """Session-scoped OmniTestHarness with all infrastructure started."""
+    import os
+
+    h = OmniTestHarness()
+    await h.start_infra()
+    await h.start_connector_manager(
+        {
+            "PAGERDUTY_CONNECTOR_URL": f"http://host.docker.internal:{connector_port}",
+        }
+    )
+
+    os.environ["CONNECTOR_MANAGER_URL"] = h.connector_manager_url
+
+    yield h
+    await h.teardown()
+
+
+# ---------------------------------------------------------------------------
+# Function-scoped fixtures
+# ---------------------------------------------------------------------------
+
+
+@pytest_asyncio.fixture
+async def seed(harness: OmniTestHarness) -> SeedHelper:
+    return harness.seed()
+
+
+@pytest_asyncio.fixture
+async def source_id(
+    seed: SeedHelper,
+    mock_pagerduty_server: str,
+    mock_pagerduty_api: MockPagerDutyAPI,
+) -> str:
+    """Create a PagerDuty source with credentials pointing to the mock server."""
+    mock_pagerduty_api.reset()
+    sid = await seed.create_source(
+        source_type="pagerduty",
+        config={"api_url": mock_pagerduty_server},
+    )
+    await seed.create_credentials(sid, {"token": "test-token"}, provider="pagerduty")
+    return sid
+
+
+@pytest_asyncio.fixture
+async def cm_client(harness: OmniTestHarness) -> httpx.AsyncClient:
+    """Async httpx client pointed at the connector-manager."""
+    async with httpx.AsyncClient(
+        base_url=harness.connector_manager_url, timeout=30
+    ) as client:
+        yield client
@@ -0,0 +1,90 @@
+"""Tests for the PagerDuty mappers."""
+
+from pagerduty_connector.config import DEFAULT_POSTMORTEM_PREFIXES
+from pagerduty_connector.mappers import (
+    build_permissions,
+    generate_incident_content,
+    is_postmortem,
+    map_incident_to_document,
+    map_postmortem_to_document,
+)
+
+INCIDENT = {
+    "id": "Q1ABC",
+    "incident_number": 1042,
+    "title": "Checkout API 5xx above 5%",
+    "description": "Checkout API 5xx above 5%",
+    "status": "resolved",
+    "urgency": "high",
+    "priority": {"summary": "P1"},
+    "service": {"id": "PSVC1", "summary": "Checkout API"},
+    "teams": [{"id": "PTEAM1", "summary": "Payments"}],
+    "created_at": "2024-05-01T09:00:00Z",
+    "last_status_change_at": "2024-05-01T10:30:00Z",
+    "html_url": "https://acme.pagerduty.com/incidents/Q1ABC",
+}
+
+NOTES = [
+    {
+        "id": "PN1",
+        "content": "Rolled back the 14:02 deploy",
+        "created_at": "2024-05-01T09:20:00Z",
+        "user": {"summary": "Alice"},
+    },
+    {
+        "id": "PN2",
+        "content": "Postmortem: connection pool exhausted after the driver upgrade",
+        "created_at": "2024-05-02T12:00:00Z",
+        "user": {"summary": "Bob"},
+    },
+]
+
+
+def test_incident_content_includes_notes():
+    content = generate_incident_content(INCIDENT, NOTES)
+
+    assert content.startswith("#1042: Checkout API 5xx above 5%\nService: Checkout API")
+    assert "Severity: P1" in content
+    assert "Alice (2024-05-01T09:20:00Z):\nRolled back the 14:02 deploy" in content
+
+
+def test_incident_document_attributes():
+    doc = map_incident_to_document(INCIDENT, "content-id", build_permissions(INCIDENT, None))
+
+    assert doc.external_id == "pagerduty:incident:Q1ABC"
+    assert doc.permissions.public is True
+    assert doc.attributes["service"] == "Checkout API"
+    assert doc.attributes["severity"] == "P1"
+    assert doc.attributes["teams"] == "Payments"
+    assert doc.attributes["resolved_date"] == "2024-05-01"
+
+
+def test_severity_falls_back_to_urgency():
+    incident = {**INCIDENT, "priority": None, "status": "triggered"}
+    doc = map_incident_to_document(incident, "content-id", build_permissions(incident, None))
+
+    assert doc.attributes["severity"] == "high"
+    assert doc.attributes["resolved_date"] is None
+
+
+def test_postmortem_is_a_comment_of_its_incident():
+    assert not is_postmortem(NOTES[0], DEFAULT_POSTMORTEM_PREFIXES)
+    assert is_postmortem(NOTES[1], DEFAULT_POSTMORTEM_PREFIXES)
+
+    permissions = build_permissions(INCIDENT, None)
+    doc = map_postmortem_to_document(INCIDENT, NOTES[1], "content-id", permissions)
+
+    assert doc.external_id == "pagerduty:postmortem:Q1ABC:PN2"
+    assert doc.metadata.parent.external_id == "pagerduty:incident:Q1ABC"
+    assert doc.attributes["content_type"] == "postmortem"
+    assert doc.attributes["resolved_date"] == "2024-05-01"
+
+
+def test_team_permissions_share_with_team_members():
+    members = {"PTEAM1": {"bob@acme.com", "alice@acme.com"}}
+
+    permissions = build_permissions(INCIDENT, members)
+    assert permissions.public is False
+    assert permissions.users == ["alice@acme.com", "bob@acme.com"]
+
+    assert build_permissions({**INCIDENT, "teams": []}, members).public is True
@@ -0,0 +1,74 @@
+"""Integration tests: the first sync backfills incidents, later ones read the changed ones."""
+
+from datetime import datetime, timedelta, timezone
+
+import httpx
+import pytest
+
+from omni_connector.testing import get_events, wait_for_sync
+
+from .conftest import _incident_payload, _note_payload
+
+pytestmark = pytest.mark.integration
+
+
+async def _sync(harness, cm_client: httpx.AsyncClient, source_id: str, sync_type: str):
+    resp = await cm_client.post(
+        "/sync",
+        json={"source_id": source_id, "sync_type": sync_type},
+    )
+    assert resp.status_code == 200, resp.text
+    row = await wait_for_sync(harness.db_pool, resp.json()["sync_run_id"], timeout=30)
+    assert (
+        row["status"] == "completed"
+    ), f"Sync ended with status={row['status']}, error={row.get('error_message')}"
+    return row
+
+
+async def _created(harness, source_id: str, row) -> list[str]:
+    events = await get_events(harness.db_pool, source_id)
+    return [
+        e["payload"]["document_id"]
+        for e in events
+        if e["sync_run_id"] == row["id"] and e["event_type"] == "document_created"
+    ]
+
+
+def _seed_incidents(mock_pagerduty_api) -> None:
+    now = datetime.now(timezone.utc)
+    mock_pagerduty_api.add_incident(_incident_payload("Q1", "PSVC1", now - timedelta(days=200)))
+    mock_pagerduty_api.add_incident(_incident_payload("Q2", "PSVC2", now - timedelta(days=10)))
+    mock_pagerduty_api.notes["Q1"] = [_note_payload("PN1", "RCA: disk full")]
+
+
+async def test_backfill_reads_lookback_in_windows(
+    harness, source_id, mock_pagerduty_api, cm_client: httpx.AsyncClient
+):
+    _seed_incidents(mock_pagerduty_api)
+
+    row = await _sync(harness, cm_client, source_id, "full")
+
+    # The default lookback of 365 days, in windows of 90 days
+    assert len(mock_pagerduty_api.windows) == 5
+    assert await _created(harness, source_id, row) == [
+        "pagerduty:incident:Q1",
+        "pagerduty:postmortem:Q1:PN1",
+        "pagerduty:incident:Q2",
+    ]
+
+
+async def test_changed_incidents_are_filtered_by_service(
+    harness, seed, mock_pagerduty_server, mock_pagerduty_api, cm_client: httpx.AsyncClient
+):
+    mock_pagerduty_api.reset()
+    source_id = await seed.create_source(
+        source_type="pagerduty",
+        config={"api_url": mock_pagerduty_server, "service_ids": ["PSVC2"]},
+    )
+    await seed.create_credentials(source_id, {"token": "test-token"}, provider="pagerduty")
+    _seed_incidents(mock_pagerduty_api)
+    await _sync(harness, cm_client, source_id, "full")
+
+    row = await _sync(harness, cm_client, source_id, "incremental")
+
+    assert await _created(harness, source_id, row) == ["pagerduty:incident:Q2"]
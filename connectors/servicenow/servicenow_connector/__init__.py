@@ -0,0 +1,6 @@
+"""ServiceNow connector for Omni."""
+
+from .connector import ServiceNowConnector
+
+__version__ = "1.0.0"
+__all__ = ["ServiceNowConnector"]
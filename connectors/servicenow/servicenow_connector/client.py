@@ -0,0 +1,192 @@
+"""Thin async wrapper over the ServiceNow Table API with retry logic."""
+
+import asyncio
+import logging
+from collections.abc import AsyncIterator
+from functools import wraps
+from typing import Any
+
+import httpx
+
+from .config import PAGE_SIZE
+
+logger = logging.getLogger(__name__)
+
+
+class ServiceNowError(Exception):
+    """Base exception for ServiceNow API errors."""
+
+    def __init__(self, message: str, status_code: int | None = None):
+        super().__init__(message)
+        self.status_code = status_code
+
+
+class AuthenticationError(ServiceNowError):
+    """Invalid credentials (401)."""
+
+    pass
+
+
+class ForbiddenError(ServiceNowError):
+    """The integration user lacks read access to a table (403)."""
+
+    pass
+
+
+def with_retry(max_retries: int = 3, base_delay: float = 1.0):
+    """Decorator for retrying Table API calls with exponential backoff.
+
+    Handles:
+    - 429 Rate Limit: Wait for Retry-After header (unlimited retries)
+    - 5xx Server Error: Exponential backoff (limited retries)
+    - 401/403: Re-raise as AuthenticationError/ForbiddenError (non-retryable)
+    """
+
+    def decorator(func):
+        @wraps(func)
+        async def wrapper(*args, **kwargs):
+            last_exception = None
+            error_retries = 0
+
+            while True:
+                try:
+                    return await func(*args, **kwargs)
+                except httpx.HTTPStatusError as e:
+                    last_exception = e
+                    status = e.response.status_code
+
+                    if status == 401:
+                        raise AuthenticationError(
+                            "Invalid ServiceNow credentials", status_code=401
+                        ) from e
+
+                    if status == 403:
+                        raise ForbiddenError(
+                            f"Insufficient permissions: {e.response.text}",
+                            status_code=403,
+                        ) from e
+
+                    if status == 429:
+                        retry_after = int(e.response.headers.get("Retry-After", "10"))
+                        logger.warning("Rate limited. Waiting %ds", retry_after)
+                        await asyncio.sleep(retry_after)
+                        continue
+
+                    if status >= 500:
+                        error_retries += 1
+                        if error_retries > max_retries:
+                            break
+                        delay = base_delay * (2 ** (error_retries - 1))
+                        logger.warning(
+                            "Server error %d. Retrying in %.1fs (%d/%d)",
+                            status,
+                            delay,
+                            error_retries,
+                            max_retries,
+                        )
+                        await asyncio.sleep(delay)
+                        continue
+
+                    raise ServiceNowError(
+                        f"API error {status}: {e.response.text}",
+                        status_code=status,
+                    ) from e
+
+            raise ServiceNowError(
+                f"Max retries exceeded: {last_exception}"
+            ) from last_exception
+
+        return wrapper
+
+    return decorator
+
+
+class ServiceNowClient:
+    """Async client for the ServiceNow Table API.
+
+    Authenticates with basic auth for an integration user, or with an OAuth bearer token.
+    Records are read with raw values (`sysparm_display_value=false`), so dates are UTC
+    `YYYY-MM-DD HH:MM:SS` strings and choice fields are their stored values.
+    """
+
+    def __init__(
+        self,
+        instance_url: str,
+        username: str | None = None,
+        password: str | None = None,
+        token: str | None = None,
+        http_client: httpx.AsyncClient | None = None,
+    ):
+        self._client = http_client or httpx.AsyncClient(
+            base_url=instance_url.rstrip("/"),
+            timeout=httpx.Timeout(30.0, connect=10.0),
+        )
+        self._client.headers["Accept"] = "application/json"
+        if token:
+            self._client.headers["Authorization"] = f"Bearer {token}"
+        elif username and password:
+            self._client.auth = httpx.BasicAuth(username, password)
+
+    async def close(self) -> None:
+        await self._client.aclose()
+
+    @with_retry(max_retries=3)
+    async def get_records(
+        self,
+        table: str,
+        query: str,
+        fields: list[str],
+        offset: int = 0,
+        limit: int = PAGE_SIZE,
+    ) -> list[dict[str, Any]]:
+        """Fetch one page of a table's records matching an encoded query."""
+        response = await self._client.get(
+            f"/api/now/table/{table}",
+            params={
+                "sysparm_query": query,
+                "sysparm_fields": ",".join(fields),
+                "sysparm_limit": limit,
+                "sysparm_offset": offset,
+                "sysparm_display_value": "false",
+                "sysparm_exclude_reference_link": "true",
+            },
+        )
+        response.raise_for_status()
+        return response.json().get("result", [])
+
+    async def iter_records(
+        self,
+        table: str,
+        query: str,
+        fields: list[str],
+    ) -> AsyncIterator[dict[str, Any]]:
+        """Page through all records matching an encoded query, in the query's order."""
+        offset = 0
+        while True:
+            records = await self.get_records(table, query, fields, offset=offset)
+            for record in records:
+                yield record
+            if len(records) < PAGE_SIZE:
+                return
+            offset += len(records)
+
+    async def users_with_roles(self, roles: list[str]) -> dict[str, set[str]]:
+        """Emails of the active users holding each role, directly or inherited."""
+        holders: dict[str, set[str]] = {role: set() for role in roles}
+        if not roles:
+            return holders
+        query = (
+            f"role.nameIN{','.join(roles)}^user.active=true^user.emailISNOTEMPTY"
+        )
+        async for grant in self.iter_records(
+            "sys_user_has_role", query, ["role.name", "user.email"]
+        ):
+            role = grant.get("role.name")
+            email = (grant.get("user.email") or "").strip().lower()
+            if role in holders and email:
+                holders[role].add(email)
+        return holders
+
+    async def test_connection(self) -> None:
+        """Validate credentials by reading one role grant, which permissions are mapped from."""
+        await self.get_records("sys_user_has_role", "", ["sys_id"], limit=1)
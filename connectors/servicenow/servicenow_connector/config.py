@@ -0,0 +1,64 @@
+"""Configuration constants for ServiceNow connector."""
+
+# Records per Table API page (ServiceNow caps sysparm_limit at 10000)
+PAGE_SIZE = 100
+MAX_CONTENT_LENGTH = 100_000
+CHECKPOINT_INTERVAL = 50
+
+# Roles whose holders can read every incident, unless the source config names others
+DEFAULT_INCIDENT_READ_ROLES = ["itil"]
+
+KNOWLEDGE_FIELDS = [
+    "sys_id",
+    "number",
+    "short_description",
+    "text",
+    "workflow_state",
+    "roles",
+    "kb_knowledge_base",
+    "kb_knowledge_base.title",
+    "kb_category.label",
+    "author.email",
+    "sys_created_on",
+    "sys_updated_on",
+]
+
+INCIDENT_FIELDS = [
+    "sys_id",
+    "number",
+    "short_description",
+    "description",
+    "state",
+    "priority",
+    "impact",
+    "urgency",
+    "category",
+    "assignment_group.name",
+    "assigned_to.email",
+    "caller_id.email",
+    "opened_by.email",
+    "opened_at",
+    "resolved_at",
+    "close_code",
+    "close_notes",
+    "sys_created_on",
+    "sys_updated_on",
+]
+
+# Labels of the out-of-the-box choice values
+INCIDENT_STATES = {
+    "1": "New",
+    "2": "In Progress",
+    "3": "On Hold",
+    "6": "Resolved",
+    "7": "Closed",
+    "8": "Canceled",
+}
+
+INCIDENT_PRIORITIES = {
+    "1": "Critical",
+    "2": "High",
+    "3": "Moderate",
+    "4": "Low",
+    "5": "Planning",
+}
@@ -0,0 +1,230 @@
+"""Main ServiceNowConnector class."""
+
+import logging
+from typing import Any
+
+from omni_connector import Connector, SyncContext
+
+from .client import AuthenticationError, ServiceNowClient, ServiceNowError
+from .config import (
+    CHECKPOINT_INTERVAL,
+    DEFAULT_INCIDENT_READ_ROLES,
+    INCIDENT_FIELDS,
+    KNOWLEDGE_FIELDS,
+)
+from .mappers import (
+    article_external_id,
+    generate_article_content,
+    generate_incident_content,
+    map_article_to_document,
+    map_incident_to_document,
+    split_roles,
+)
+
+logger = logging.getLogger(__name__)
+
+
+class ServiceNowConnector(Connector):
+    """ServiceNow connector for Omni, syncing knowledge articles and incidents."""
+
+    @property
+    def name(self) -> str:
+        return "servicenow"
+
+    @property
+    def version(self) -> str:
+        return "1.0.0"
+
+    @property
+    def sync_modes(self) -> list[str]:
+        return ["full", "incremental"]
+
+    async def sync(
+        self,
+        source_config: dict[str, Any],
+        credentials: dict[str, Any],
+        state: dict[str, Any] | None,
+        ctx: SyncContext,
+    ) -> None:
+        """
+        Sync knowledge articles and incidents.
+
+        Each table keeps a `sys_updated_on` watermark in the state. With one, only records
+        updated since are read, and articles that are no longer published are deleted;
+        without one, every published article and every incident is read.
+
+        Args:
+            source_config: Must contain 'instance_url'; may set sync_knowledge,
+                sync_incidents, knowledge_bases and incident_read_roles
+            credentials: 'username' and 'password', or 'token'
+            state: Previous sync state with the watermarks
+            ctx: Sync context with emit(), complete(), etc.
+        """
+        instance_url = source_config.get("instance_url")
+        if not instance_url:
+            await ctx.fail("Missing instance_url in source config")
+            return
+
+        token = credentials.get("token")
+        username = credentials.get("username")
+        password = credentials.get("password")
+        if not token and not (username and password):
+            await ctx.fail("Missing username and password, or token, in credentials")
+            return
+
+        client = ServiceNowClient(
+            instance_url, username=username, password=password, token=token
+        )
+        try:
+            try:
+                await client.test_connection()
+            except AuthenticationError as e:
+                await ctx.fail(f"Authentication failed: {e}")
+                return
+            except ServiceNowError as e:
+                await ctx.fail(f"Connection test failed: {e}")
+                return
+
+            sync = _Sync(client, instance_url, source_config, dict(state or {}), ctx)
+            logger.info("Starting ServiceNow sync for %s", instance_url)
+            try:
+                if source_config.get("sync_knowledge", True):
+                    await sync.sync_knowledge()
+                if source_config.get("sync_incidents", True) and not ctx.is_cancelled():
+                    await sync.sync_incidents()
+
+                if ctx.is_cancelled():
+                    await ctx.fail("Cancelled by user")
+                    return
+                await ctx.complete(new_state=sync.state)
+                logger.info(
+                    "Sync completed: %d scanned, %d emitted",
+                    ctx.documents_scanned,
+                    ctx.documents_emitted,
+                )
+            except AuthenticationError as e:
+                logger.error("Authentication error during sync: %s", e)
+                await ctx.fail(f"Authentication failed: {e}")
+            except Exception as e:
+                logger.exception("Sync failed with unexpected error")
+                await ctx.fail(str(e))
+        finally:
+            await client.close()
+
+
+class _Sync:
+    """State of one sync run: the watermarks and the role holders looked up so far."""
+
+    def __init__(
+        self,
+        client: ServiceNowClient,
+        instance_url: str,
+        source_config: dict[str, Any],
+        state: dict[str, Any],
+        ctx: SyncContext,
+    ):
+        self.client = client
+        self.instance_url = instance_url
+        self.state = state
+        self.ctx = ctx
+        self.knowledge_bases: list[str] = source_config.get("knowledge_bases") or []
+        self.incident_read_roles: list[str] = (
+            source_config.get("incident_read_roles") or DEFAULT_INCIDENT_READ_ROLES
+        )
+        self._role_holders: dict[str, set[str]] = {}
+        self._since_checkpoint = 0
+
+    async def role_holders(self, roles: list[str]) -> dict[str, set[str]]:
+        """Holders of each role, looking up the roles not seen yet in this run."""
+        missing = [role for role in roles if role not in self._role_holders]
+        if missing:
+            self._role_holders.update(await self.client.users_with_roles(missing))
+        return {role: self._role_holders[role] for role in roles}
+
+    async def sync_knowledge(self) -> None:
+        since = self.state.get("knowledge_updated_on")
+        conditions = [] if since else ["workflow_state=published"]
+        if self.knowledge_bases:
+            conditions.append(f"kb_knowledge_base.titleIN{','.join(self.knowledge_bases)}")
+        logger.info("Syncing knowledge articles updated since %s", since or "the beginning")
+
+        records = self._updated_records("kb_knowledge", KNOWLEDGE_FIELDS, conditions, since)
+        async for record in records:
+            if record.get("workflow_state") != "published":
+                # Retired, drafted again or outdated: no longer readable in the portal
+                await self.ctx.emit_deleted(article_external_id(record))
+                await self._advance("knowledge_updated_on", record)
+                continue
+            try:
+                content = generate_article_content(record)
+                content_id = await self.ctx.content_storage.save(content, "text/plain")
+                roles = split_roles(record.get("roles"))
+                await self.ctx.emit(
+                    map_article_to_document(
+                        record, content_id, self.instance_url, await self.role_holders(roles)
+                    )
+                )
+            except AuthenticationError:
+                raise
+            except Exception as e:
+                logger.warning("Error processing article %s: %s", record.get("number"), e)
+                await self.ctx.emit_error(article_external_id(record), str(e))
+            await self._advance("knowledge_updated_on", record)
+
+    async def sync_incidents(self) -> None:
+        since = self.state.get("incident_updated_on")
+        logger.info("Syncing incidents updated since %s", since or "the beginning")
+        role_holders = await self.role_holders(self.incident_read_roles)
+
+        async for record in self._updated_records("incident", INCIDENT_FIELDS, [], since):
+            try:
+                content = generate_incident_content(record)
+                content_id = await self.ctx.content_storage.save(content, "text/plain")
+                await self.ctx.emit(
+                    map_incident_to_document(
+                        record,
+                        content_id,
+                        self.instance_url,
+                        self.incident_read_roles,
+                        role_holders,
+                    )
+                )
+            except AuthenticationError:
+                raise
+            except Exception as e:
+                logger.warning("Error processing incident %s: %s", record.get("number"), e)
+                await self.ctx.emit_error(f"incident:{record.get('sys_id')}", str(e))
+            await self._advance("incident_updated_on", record)
+
+    async def _updated_records(
+        self,
+        table: str,
+        fields: list[str],
+        conditions: list[str],
+        since: str | None,
+    ):
+        """Records of a table oldest update first, from the watermark on when there is one."""
+        if since:
+            conditions = [*conditions, f"sys_updated_on>={since}"]
+        query = "^".join([*conditions, "ORDERBYsys_updated_on"])
+        try:
+            async for record in self.client.iter_records(table, query, fields):
+                if self.ctx.is_cancelled():
+                    logger.info("Sync cancelled during %s sync", table)
+                    return
+                await self.ctx.increment_scanned()
+                yield record
+        except AuthenticationError:
+            raise
+        except ServiceNowError as e:
+            logger.error("Error fetching %s records: %s", table, e)
+            await self.ctx.emit_error(f"{table}:*", f"Failed to fetch {table}: {e}")
+
+    async def _advance(self, key: str, record: dict[str, Any]) -> None:
+        """Move a watermark to a processed record, checkpointing every few records."""
+        if updated_on := record.get("sys_updated_on"):
+            self.state[key] = updated_on
+        self._since_checkpoint += 1
+        if self._since_checkpoint >= CHECKPOINT_INTERVAL:
+            self._since_checkpoint = 0
+            await self.ctx.save_state(self.state)
@@ -0,0 +1,226 @@
+"""Record-to-Document mapping functions for ServiceNow knowledge articles and incidents."""
+
+from datetime import datetime, timezone
+from html.parser import HTMLParser
+from typing import Any
+
+from omni_connector import Document, DocumentMetadata, DocumentPermissions
+
+from .config import INCIDENT_PRIORITIES, INCIDENT_STATES, MAX_CONTENT_LENGTH
+
+# Tags that end a line of text when rendered
+_BLOCK_TAGS = {"p", "div", "br", "li", "tr", "h1", "h2", "h3", "h4", "h5", "h6", "pre"}
+
+
+class _TextExtractor(HTMLParser):
+    """Collects the text of an HTML fragment, one line per block element."""
+
+    def __init__(self) -> None:
+        super().__init__()
+        self.parts: list[str] = []
+        self._skip = 0
+
+    def handle_starttag(self, tag: str, attrs: list[tuple[str, str | None]]) -> None:
+        if tag in ("script", "style"):
+            self._skip += 1
+        elif tag in _BLOCK_TAGS:
+            self.parts.append("\n")
+
+    def handle_endtag(self, tag: str) -> None:
+        if tag in ("script", "style"):
+            self._skip = max(0, self._skip - 1)
+        elif tag in _BLOCK_TAGS:
+            self.parts.append("\n")
+
+    def handle_data(self, data: str) -> None:
+        if not self._skip:
+            self.parts.append(data)
+
+
+def html_to_text(html: str) -> str:
+    """Plain text of an article body, with blank lines collapsed."""
+    parser = _TextExtractor()
+    parser.feed(html)
+    parser.close()
+    lines = [" ".join(line.split()) for line in "".join(parser.parts).splitlines()]
+    return "\n".join(line for line in lines if line)
+
+
+def parse_datetime(value: str | None) -> datetime | None:
+    """Parse a raw ServiceNow date-time (UTC `YYYY-MM-DD HH:MM:SS`)."""
+    if not value:
+        return None
+    try:
+        return datetime.strptime(value, "%Y-%m-%d %H:%M:%S").replace(tzinfo=timezone.utc)
+    except ValueError:
+        return None
+
+
+def split_roles(value: str | None) -> list[str]:
+    """The roles of a comma-separated `roles` field."""
+    return [role.strip() for role in (value or "").split(",") if role.strip()]
+
+
+def _email(record: dict[str, Any], field: str) -> str | None:
+    email = (record.get(field) or "").strip().lower()
+    return email or None
+
+
+def _holders(roles: list[str], role_holders: dict[str, set[str]]) -> set[str]:
+    return set().union(*(role_holders.get(role, set()) for role in roles))
+
+
+def article_external_id(record: dict[str, Any]) -> str:
+    return f"kb_knowledge:{record['sys_id']}"
+
+
+def incident_external_id(record: dict[str, Any]) -> str:
+    return f"incident:{record['sys_id']}"
+
+
+def generate_article_content(record: dict[str, Any]) -> str:
+    """Searchable text of a knowledge article."""
+    lines = [record.get("short_description") or record.get("number") or ""]
+    if knowledge_base := record.get("kb_knowledge_base.title"):
+        lines.append(f"Knowledge base: {knowledge_base}")
+    if category := record.get("kb_category.label"):
+        lines.append(f"Category: {category}")
+    lines.append("")
+    lines.append(html_to_text(record.get("text") or ""))
+    return "\n".join(lines)[:MAX_CONTENT_LENGTH]
+
+
+def generate_incident_content(record: dict[str, Any]) -> str:
+    """Searchable text of an incident, its resolution included."""
+    state = INCIDENT_STATES.get(record.get("state") or "", record.get("state"))
+    priority = INCIDENT_PRIORITIES.get(record.get("priority") or "", record.get("priority"))
+    lines = [f"{record.get('number', '')}: {record.get('short_description') or ''}"]
+    for label, value in (
+        ("State", state),
+        ("Priority", priority),
+        ("Category", record.get("category")),
+        ("Assignment group", record.get("assignment_group.name")),
+        ("Assigned to", record.get("assigned_to.email")),
+        ("Caller", record.get("caller_id.email")),
+    ):
+        if value:
+            lines.append(f"{label}: {value}")
+    if description := record.get("description"):
+        lines.extend(["", description])
+    if close_notes := record.get("close_notes"):
+        resolution = record.get("close_code")
+        lines.extend(["", f"Resolution ({resolution}):" if resolution else "Resolution:"])
+        lines.append(close_notes)
+    return "\n".join(lines)[:MAX_CONTENT_LENGTH]
+
+
+def map_article_to_document(
+    record: dict[str, Any],
+    content_id: str,
+    instance_url: str,
+    role_holders: dict[str, set[str]],
+) -> Document:
+    """
+    Map a published knowledge article to an Omni Document.
+
+    An article without roles is readable by everyone with access to the knowledge base, so
+    it is public; otherwise only the holders of its roles can see it.
+
+    Args:
+        record: kb_knowledge record with KNOWLEDGE_FIELDS
+        content_id: ID from content storage
+        instance_url: Base URL of the ServiceNow instance
+        role_holders: Emails of the users holding each of the article's roles
+
+    Returns:
+        Omni Document instance
+    """
+    roles = split_roles(record.get("roles"))
+    permissions = (
+        DocumentPermissions(users=sorted(_holders(roles, role_holders)))
+        if roles
+        else DocumentPermissions(public=True)
+    )
+    number = record.get("number")
+
+    return Document(
+        external_id=article_external_id(record),
+        title=record.get("short_description") or number or "Untitled article",
+        content_id=content_id,
+        metadata=DocumentMetadata(
+            author=_email(record, "author.email"),
+            created_at=parse_datetime(record.get("sys_created_on")),
+            updated_at=parse_datetime(record.get("sys_updated_on")),
+            url=f"{instance_url.rstrip('/')}/kb_view.do?sysparm_article={number}",
+            mime_type="text/plain",
+            extra={"sys_id": record["sys_id"], "number": number},
+        ),
+        permissions=permissions,
+        attributes={
+            "source_type": "service_now",
+            "record_type": "knowledge_article",
+            "number": number,
+            "knowledge_base": record.get("kb_knowledge_base.title"),
+            "category": record.get("kb_category.label"),
+        },
+    )
+
+
+def map_incident_to_document(
+    record: dict[str, Any],
+    content_id: str,
+    instance_url: str,
+    read_roles: list[str],
+    role_holders: dict[str, set[str]],
+) -> Document:
+    """
+    Map an incident to an Omni Document.
+
+    Incidents are visible to the people on them (caller, opener and assignee) and to the
+    holders of the roles that can read every incident.
+
+    Args:
+        record: incident record with INCIDENT_FIELDS
+        content_id: ID from content storage
+        instance_url: Base URL of the ServiceNow instance
+        read_roles: Roles whose holders can read all incidents
+        role_holders: Emails of the users holding each read role
+
+    Returns:
+        Omni Document instance
+    """
+    users = _holders(read_roles, role_holders)
+    for field in ("caller_id.email", "opened_by.email", "assigned_to.email"):
+        if email := _email(record, field):
+            users.add(email)
+
+    number = record.get("number")
+    opened_at = parse_datetime(record.get("opened_at") or record.get("sys_created_on"))
+    short_description = record.get("short_description")
+
+    return Document(
+        external_id=incident_external_id(record),
+        title=f"{number}: {short_description}" if short_description else str(number),
+        content_id=content_id,
+        metadata=DocumentMetadata(
+            author=_email(record, "caller_id.email"),
+            created_at=opened_at,
+            updated_at=parse_datetime(record.get("sys_updated_on")),
+            url=f"{instance_url.rstrip('/')}/nav_to.do?uri=incident.do?sys_id={record['sys_id']}",
+            mime_type="text/plain",
+            extra={"sys_id": record["sys_id"], "number": number},
+        ),
+        permissions=DocumentPermissions(users=sorted(users)),
+        attributes={
+            "source_type": "service_now",
+            "record_type": "incident",
+            "number": number,
+            "state": INCIDENT_STATES.get(record.get("state") or "", record.get("state")),
+            "priority": INCIDENT_PRIORITIES.get(
+                record.get("priority") or "", record.get("priority")
+            ),
+            "assignment_group": record.get("assignment_group.name"),
+            "category": record.get("category"),
+            "date": opened_at.date().isoformat() if opened_at else None,
+        },
+    )
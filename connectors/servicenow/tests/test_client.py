@@ -0,0 +1,93 @@
+"""Tests for ServiceNowClient paging, errors and role lookups."""
+
+import httpx
+import pytest
+import respx
+
+from servicenow_connector import client as client_module
+from servicenow_connector.client import (
+    AuthenticationError,
+    ServiceNowClient,
+    ServiceNowError,
+)
+
+INSTANCE_URL = "https://example.service-now.com"
+
+
+@pytest.fixture
+def mock_router():
+    with respx.mock(assert_all_called=False) as router:
+        yield router
+
+
+@pytest.fixture
+def sn_client(mock_router):
+    return ServiceNowClient(INSTANCE_URL, username="omni", password="secret")
+
+
+async def test_iter_records_pages_with_offset(sn_client, mock_router, monkeypatch):
+    monkeypatch.setattr(client_module, "PAGE_SIZE", 2)
+    route = mock_router.get(f"{INSTANCE_URL}/api/now/table/incident")
+    route.side_effect = [
+        httpx.Response(200, json={"result": [{"sys_id": "1"}, {"sys_id": "2"}]}),
+        httpx.Response(200, json={"result": [{"sys_id": "3"}]}),
+    ]
+
+    records = [
+        record async for record in sn_client.iter_records("incident", "", ["sys_id"])
+    ]
+
+    assert [record["sys_id"] for record in records] == ["1", "2", "3"]
+    assert route.call_count == 2
+    second = route.calls[1].request
+    assert second.url.params["sysparm_offset"] == "2"
+    assert second.headers["Authorization"].startswith("Basic ")
+
+
+async def test_retry_on_429_and_auth_failure(sn_client, mock_router):
+    route = mock_router.get(f"{INSTANCE_URL}/api/now/table/kb_knowledge")
+    route.side_effect = [
+        httpx.Response(429, headers={"Retry-After": "0"}),
+        httpx.Response(200, json={"result": []}),
+    ]
+    assert await sn_client.get_records("kb_knowledge", "", ["sys_id"]) == []
+    assert route.call_count == 2
+
+    mock_router.get(f"{INSTANCE_URL}/api/now/table/incident").mock(
+        return_value=httpx.Response(401, json={"error": {"message": "User Not Authenticated"}})
+    )
+    with pytest.raises(AuthenticationError):
+        await sn_client.get_records("incident", "", ["sys_id"])
+
+    mock_router.get(f"{INSTANCE_URL}/api/now/table/problem").mock(
+        return_value=httpx.Response(400, text="Invalid table")
+    )
+    with pytest.raises(ServiceNowError, match="API error 400"):
+        await sn_client.get_records("problem", "", ["sys_id"])
+
+
+async def test_users_with_roles(sn_client, mock_router):
+    route = mock_router.get(f"{INSTANCE_URL}/api/now/table/sys_user_has_role")
+    route.mock(
+        return_value=httpx.Response(
+            200,
+            json={
+                "result": [
+                    {"role.name": "itil", "user.email": "Agent@Example.com"},
+                    {"role.name": "itil", "user.email": "agent@example.com"},
+                    {"role.name": "hr_admin", "user.email": "hr@example.com"},
+                    {"role.name": "itil", "user.email": ""},
+                ]
+            },
+        )
+    )
+
+    holders = await sn_client.users_with_roles(["itil", "hr_admin", "admin"])
+
+    assert holders == {
+        "itil": {"agent@example.com"},
+        "hr_admin": {"hr@example.com"},
+        "admin": set(),
+    }
+    query = route.calls[0].request.url.params["sysparm_query"]
+    assert query.startswith("role.nameINitil,hr_admin,admin^")
@@ -0,0 +1,132 @@
+"""Tests for ServiceNow record-to-document mapping."""
+
+from datetime import datetime, timezone
+
+from servicenow_connector.mappers import (
+    generate_incident_content,
+    html_to_text,
+    map_article_to_document,
+    map_incident_to_document,
+    parse_datetime,
+)
+
+INSTANCE_URL = "https://example.service-now.com/"
+
+
+def _article(**overrides):
+    record = {
+        "sys_id": "a1",
+        "number": "KB0010001",
+        "short_description": "Reset your VPN password",
+        "text": "<p>Open the <b>portal</b>.</p><p>Click reset.</p>",
+        "workflow_state": "published",
+        "roles": "",
+        "kb_knowledge_base.title": "IT",
+        "kb_category.label": "Network",
+        "author.email": "Author@Example.com",
+        "sys_created_on": "2024-05-01 09:30:00",
+        "sys_updated_on": "2024-05-02 10:00:00",
+    }
+    record.update(overrides)
+    return record
+
+
+def _incident(**overrides):
+    record = {
+        "sys_id": "i1",
+        "number": "INC0012345",
+        "short_description": "Email is down",
+        "description": "Outlook cannot connect.",
+        "state": "6",
+        "priority": "2",
+        "category": "software",
+        "assignment_group.name": "Service Desk",
+        "assigned_to.email": "agent@example.com",
+        "caller_id.email": "caller@example.com",
+        "opened_by.email": "",
+        "opened_at": "2024-05-03 08:00:00",
+        "close_code": "Solved (Permanently)",
+        "close_notes": "Restarted the mail relay.",
+        "sys_created_on": "2024-05-03 08:00:00",
+        "sys_updated_on": "2024-05-03 12:00:00",
+    }
+    record.update(overrides)
+    return record
+
+
+class TestMapArticleToDocument:
+    """Tests for knowledge article mapping."""
+
+    def test_article_without_roles_is_public(self):
+        doc = map_article_to_document(_article(), "content-1", INSTANCE_URL, {})
+
+        assert doc.external_id == "kb_knowledge:a1"
+        assert doc.title == "Reset your VPN password"
+        assert doc.permissions.public is True
+        assert doc.metadata.author == "author@example.com"
+        assert (
+            doc.metadata.url
+            == "https://example.service-now.com/kb_view.do?sysparm_article=KB0010001"
+        )
+        assert doc.metadata.updated_at == datetime(2024, 5, 2, 10, tzinfo=timezone.utc)
+        assert doc.attributes["record_type"] == "knowledge_article"
+        assert doc.attributes["knowledge_base"] == "IT"
+
+    def test_article_with_roles_is_limited_to_their_holders(self):
+        doc = map_article_to_document(
+            _article(roles="hr_admin, itil"),
+            "content-1",
+            INSTANCE_URL,
+            {"hr_admin": {"b@example.com"}, "itil": {"a@example.com", "b@example.com"}},
+        )
+
+        assert doc.permissions.public is False
+        assert doc.permissions.users == ["a@example.com", "b@example.com"]
+
+
+class TestMapIncidentToDocument:
+    """Tests for incident mapping."""
+
+    def test_incident_is_visible_to_its_people_and_read_roles(self):
+        doc = map_incident_to_document(
+            _incident(),
+            "content-1",
+            INSTANCE_URL,
+            ["itil"],
+            {"itil": {"desk@example.com"}},
+        )
+
+        assert doc.external_id == "incident:i1"
+        assert doc.title == "INC0012345: Email is down"
+        assert doc.permissions.public is False
+        assert doc.permissions.users == [
+            "agent@example.com",
+            "caller@example.com",
+            "desk@example.com",
+        ]
+        assert doc.attributes["state"] == "Resolved"
+        assert doc.attributes["priority"] == "High"
+        assert doc.attributes["date"] == "2024-05-03"
+        assert doc.metadata.url.endswith("/nav_to.do?uri=incident.do?sys_id=i1")
+
+    def test_incident_content_includes_resolution(self):
+        content = generate_incident_content(_incident())
+
+        assert content.startswith("INC0012345: Email is down")
+        assert "Assignment group: Service Desk" in content
+        assert "Resolution (Solved (Permanently)):\nRestarted the mail relay." in content
+
+
+class TestHelpers:
+    """Tests for text and date helpers."""
+
+    def test_html_to_text(self):
+        html = "<h1>Title</h1><p>First  line<br>second</p><script>x()</script><p></p>"
+        assert html_to_text(html) == "Title\nFirst line\nsecond"
+
+    def test_parse_datetime(self):
+        assert parse_datetime("2024-05-01 09:30:00") == datetime(
+            2024, 5, 1, 9, 30, tzinfo=timezone.utc
+        )
+        assert parse_datetime("") is None
+        assert parse_datetime("yesterday") is None
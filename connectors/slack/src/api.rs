@@ -9,12 +9,12 @@ use axum::{
 use dashmap::DashSet;
 use serde_json::json;
 use shared::models::SyncRequest;
-use shared::telemetry;
+use shared::{error_reporting, telemetry};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tower::ServiceBuilder;
 use tower_http::cors::CorsLayer;
-use tracing::{error, info};
+use tracing::{error, info, Instrument};
 
 use crate::models::{
     ActionRequest, ActionResponse, CancelRequest, CancelResponse, ConnectorManifest, SyncResponse,
@@ -89,17 +89,21 @@ async fn trigger_sync(
     let sync_manager = state.sync_manager.clone();
     let active_syncs = state.active_syncs.clone();
 
-    tokio::spawn(async move {
-        let manager = sync_manager.lock().await;
-        let result = manager.sync_source_from_request(request).await;
+    let span = error_reporting::sync_span(&source_id, request.source_type, &sync_run_id);
+    tokio::spawn(
+        async move {
+            let manager = sync_manager.lock().await;
+            let result = manager.sync_source_from_request(request).await;
 
-        // Remove from active syncs when done
-        active_syncs.remove(&source_id);
+            // Remove from active syncs when done
+            active_syncs.remove(&source_id);
 
-        if let Err(e) = result {
-            error!("Sync {} failed: {}", sync_run_id, e);
+            if let Err(e) = result {
+                error!("Sync {} failed: {}", sync_run_id, e);
+            }
         }
-    });
+        .instrument(span),
+    );
 
     Ok(Json(SyncResponse::started()))
 }
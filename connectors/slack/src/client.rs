@@ -12,13 +12,19 @@ const SLACK_API_BASE: &str = "https://slack.com/api";
 
 pub struct SlackClient {
     client: Client,
+    base_url: String,
     rate_limiter: RateLimiter,
 }
 
 impl SlackClient {
     pub fn new() -> Self {
+        Self::with_base_url(SLACK_API_BASE)
+    }
+
+    pub fn with_base_url(base_url: &str) -> Self {
         Self {
             client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
             // Slack Tier 3 allows ~50 req/min; 1 req/sec keeps us safely under.
             rate_limiter: RateLimiter::new(1, 5),
         }
@@ -86,7 +92,7 @@ impl SlackClient {
     ) -> Result<ConversationsListResponse> {
         let mut url = format!(
             "{}/conversations.list?types=public_channel,private_channel&limit=200",
-            SLACK_API_BASE
+            self.base_url
         );
 
         if let Some(cursor) = cursor {
@@ -116,7 +122,7 @@ impl SlackClient {
     ) -> Result<ConversationsHistoryResponse> {
         let mut url = format!(
             "{}/conversations.history?channel={}&limit=200",
-            SLACK_API_BASE, channel_id
+            self.base_url, channel_id
         );
 
         if let Some(cursor) = cursor {
@@ -155,7 +161,7 @@ impl SlackClient {
     ) -> Result<ConversationsHistoryResponse> {
         let mut url = format!(
             "{}/conversations.replies?channel={}&ts={}&limit=200",
-            SLACK_API_BASE, channel_id, thread_ts
+            self.base_url, channel_id, thread_ts
         );
 
         if let Some(cursor) = cursor {
@@ -180,7 +186,7 @@ impl SlackClient {
     }
 
     pub async fn join_conversation(&self, token: &str, channel_id: &str) -> Result<()> {
-        let url = format!("{}/conversations.join", SLACK_API_BASE);
+        let url = format!("{}/conversations.join", self.base_url);
         let payload = serde_json::json!({ "channel": channel_id });
 
         self.rate_limiter
@@ -220,7 +226,7 @@ impl SlackClient {
     }
 
     pub async fn list_users(&self, token: &str, cursor: Option<&str>) -> Result<UsersListResponse> {
-        let mut url = format!("{}/users.list?limit=200", SLACK_API_BASE);
+        let mut url = format!("{}/users.list?limit=200", self.base_url);
 
         if let Some(cursor) = cursor {
             url.push_str(&format!("&cursor={}", cursor));
@@ -14,6 +14,7 @@ mod models;
 mod sync;
 
 use api::{create_router, ApiState};
+use shared::shutdown::Shutdown;
 use shared::SdkClient;
 use sync::SyncManager;
 
@@ -28,7 +29,7 @@ async fn main() -> Result<()> {
 
     let sdk_client = SdkClient::from_env()?;
 
-    let sync_manager = Arc::new(Mutex::new(SyncManager::new(sdk_client)));
+    let sync_manager = Arc::new(Mutex::new(SyncManager::new(sdk_client.clone())));
 
     // Create API state
     let api_state = ApiState {
@@ -45,7 +46,10 @@ async fn main() -> Result<()> {
     info!("HTTP server listening on {}", addr);
 
     // Run HTTP server
-    axum::serve(listener, app).await?;
+    shared::tls::serve(listener, app, Shutdown::on_signal()).await?;
+
+    // Syncs cut short by the shutdown are resumed once the connector is back
+    sdk_client.pause_active_syncs().await;
 
     Ok(())
 }
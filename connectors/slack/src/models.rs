@@ -2,8 +2,10 @@ use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value as JsonValue};
 use shared::models::{ConnectorEvent, DocumentAttributes, DocumentMetadata, DocumentPermissions};
+use shared::utils::to_offset_datetime;
 use std::collections::HashMap;
-use time::OffsetDateTime;
+
+pub use shared::models::ActionRequest;
 
 // ============================================================================
 // Connector Protocol Models
@@ -58,13 +60,6 @@ pub struct CancelResponse {
     pub status: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ActionRequest {
-    pub action: String,
-    pub params: JsonValue,
-    pub credentials: JsonValue,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActionResponse {
     pub status: String,
@@ -328,10 +323,7 @@ impl MessageGroup {
                 .unwrap_or(0),
             0,
         )
-        .map(|dt| {
-            OffsetDateTime::from_unix_timestamp(dt.timestamp())
-                .unwrap_or_else(|_| OffsetDateTime::UNIX_EPOCH)
-        });
+        .and_then(|dt| to_offset_datetime(&dt));
 
         let updated_at = DateTime::from_timestamp(
             last_ts
@@ -342,10 +334,7 @@ impl MessageGroup {
                 .unwrap_or(0),
             0,
         )
-        .map(|dt| {
-            OffsetDateTime::from_unix_timestamp(dt.timestamp())
-                .unwrap_or_else(|_| OffsetDateTime::UNIX_EPOCH)
-        });
+        .and_then(|dt| to_offset_datetime(&dt));
 
         let mut extra = HashMap::new();
 
@@ -398,6 +387,8 @@ impl MessageGroup {
             )),
             path: Some(format!("#{}", self.channel_name)), // Display channel as path
             extra: Some(extra),
+            thumbnail: None,
+            parent: None,
         };
 
         let permissions = DocumentPermissions {
@@ -458,6 +449,8 @@ impl SlackFile {
             url: self.permalink.clone(),
             path: Some(format!("#{}/{}", channel_name, self.name)),
             extra: Some(extra),
+            thumbnail: None,
+            parent: None,
         };
 
         let permissions = DocumentPermissions {
@@ -61,6 +61,7 @@ impl SyncManager {
             "Starting sync for source: {} (sync_run_id: {})",
             source_id, sync_run_id
         );
+        self.sdk_client.start_sync(&request);
 
         // Register this sync for cancellation tracking
         let active_sync = Arc::new(ActiveSync {
@@ -306,43 +307,10 @@ impl SyncManager {
             Some(format!("{}.000000", start_of_day.and_utc().timestamp() - 1))
         });
 
-        let mut all_messages = Vec::new();
-        let mut cursor = None;
-        let mut latest_ts: Option<String> = last_ts.map(|s| s.to_string());
-
-        // Fetch channel messages
-        loop {
-            let response = self
-                .slack_client
-                .get_conversation_history(
-                    token,
-                    &channel.id,
-                    cursor.as_deref(),
-                    oldest.as_deref(),
-                    None,
-                )
-                .await?;
-
-            // Track the latest timestamp we've seen
-            if let Some(first_message) = response.messages.first() {
-                latest_ts = Some(first_message.ts.clone());
-            }
-
-            all_messages.extend(response.messages);
-
-            if !response.has_more {
-                break;
-            }
-
-            cursor = response
-                .response_metadata
-                .and_then(|meta| meta.next_cursor)
-                .filter(|c| !c.is_empty());
-
-            if cursor.is_none() {
-                break;
-            }
-        }
+        let (all_messages, newest_ts) = self
+            .fetch_channel_messages(token, channel, oldest.as_deref())
+            .await?;
+        let latest_ts = newest_ts.or_else(|| last_ts.map(|s| s.to_string()));
 
         // Group messages by date/thread
         let message_groups = content_processor.group_messages_by_date(
@@ -429,6 +397,105 @@ impl SyncManager {
         Ok((published_groups, published_files, latest_ts))
     }
 
+    /// Messages of a channel posted since `oldest`, with the replies of their threads, and the
+    /// timestamp the next sync of the channel reads from.
+    async fn fetch_channel_messages(
+        &self,
+        token: &str,
+        channel: &crate::models::SlackChannel,
+        oldest: Option<&str>,
+    ) -> Result<(Vec<crate::models::SlackMessage>, Option<String>)> {
+        let mut all_messages = Vec::new();
+        let mut cursor = None;
+        let mut latest_ts = None;
+
+        // Fetch channel messages
+        loop {
+            let response = self
+                .slack_client
+                .get_conversation_history(token, &channel.id, cursor.as_deref(), oldest, None)
+                .await?;
+
+            // Track the latest timestamp we've seen
+            if let Some(first_message) = response.messages.first() {
+                latest_ts = Some(first_message.ts.clone());
+            }
+
+            all_messages.extend(response.messages);
+
+            if !response.has_more {
+                break;
+            }
+
+            cursor = response
+                .response_metadata
+                .and_then(|meta| meta.next_cursor)
+                .filter(|c| !c.is_empty());
+
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        // History only holds thread parents; fetch the replies of each thread
+        let mut replies = Vec::new();
+        for parent in all_messages
+            .iter()
+            .filter(|m| m.reply_count.unwrap_or(0) > 0)
+        {
+            let Some(thread_ts) = parent.thread_ts.as_deref() else {
+                continue;
+            };
+            match self
+                .fetch_thread_replies(token, &channel.id, thread_ts)
+                .await
+            {
+                Ok(thread_replies) => replies.extend(thread_replies),
+                Err(e) => warn!(
+                    "Failed to fetch replies of thread {} in channel {}: {}",
+                    thread_ts, channel.name, e
+                ),
+            }
+        }
+        all_messages.extend(replies);
+
+        Ok((all_messages, latest_ts))
+    }
+
+    /// Replies of a thread, without its parent message.
+    async fn fetch_thread_replies(
+        &self,
+        token: &str,
+        channel_id: &str,
+        thread_ts: &str,
+    ) -> Result<Vec<crate::models::SlackMessage>> {
+        let mut replies = Vec::new();
+        let mut cursor = None;
+
+        loop {
+            let response = self
+                .slack_client
+                .get_thread_replies(token, channel_id, thread_ts, cursor.as_deref())
+                .await?;
+            replies.extend(response.messages.into_iter().filter(|m| m.ts != thread_ts));
+
+            if !response.has_more {
+                break;
+            }
+
+            cursor = response
+                .response_metadata
+                .and_then(|meta| meta.next_cursor)
+                .filter(|c| !c.is_empty());
+
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        Ok(replies)
+    }
+
     async fn get_bot_token(&self, source_id: &str) -> Result<String> {
         let creds = self
             .sdk_client
@@ -452,3 +519,137 @@ impl SyncManager {
             .ok_or_else(|| anyhow!("Missing bot_token in Slack credentials"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SlackChannel;
+    use axum::extract::{Query, State};
+    use axum::routing::get;
+    use axum::{Json, Router};
+    use serde_json::Value as JsonValue;
+    use std::sync::Mutex;
+
+    const OLD_PARENT_TS: &str = "1700000000.000100";
+    const PARENT_TS: &str = "1700100000.000100";
+
+    fn message(ts: &str, thread_ts: Option<&str>, reply_count: Option<i32>) -> JsonValue {
+        json!({
+            "type": "message",
+            "text": format!("Message {}", ts),
+            "user": "U1",
+            "ts": ts,
+            "thread_ts": thread_ts,
+            "reply_count": reply_count,
+        })
+    }
+
+    #[derive(Clone, Default)]
+    struct MockSlack {
+        /// Channel messages, newest first as Slack returns them.
+        history: Vec<JsonValue>,
+        /// Messages of each thread, its parent first.
+        threads: HashMap<String, Vec<JsonValue>>,
+        replies_requested: Arc<Mutex<Vec<String>>>,
+    }
+
+    /// Serve `conversations.history` and `conversations.replies` from `mock`, and return the
+    /// API base URL.
+    async fn slack_api(mock: MockSlack) -> String {
+        async fn history(
+            State(mock): State<MockSlack>,
+            Query(params): Query<HashMap<String, String>>,
+        ) -> Json<JsonValue> {
+            let oldest = params.get("oldest").cloned().unwrap_or_default();
+            let messages: Vec<&JsonValue> = mock
+                .history
+                .iter()
+                .filter(|m| m["ts"].as_str().unwrap() > oldest.as_str())
+                .collect();
+            Json(json!({ "ok": true, "messages": messages, "has_more": false }))
+        }
+
+        async fn replies(
+            State(mock): State<MockSlack>,
+            Query(params): Query<HashMap<String, String>>,
+        ) -> Json<JsonValue> {
+            let ts = params["ts"].clone();
+            mock.replies_requested.lock().unwrap().push(ts.clone());
+            let messages = mock.threads.get(&ts).cloned().unwrap_or_default();
+            Json(json!({ "ok": true, "messages": messages, "has_more": false }))
+        }
+
+        let app = Router::new()
+            .route("/conversations.history", get(history))
+            .route("/conversations.replies", get(replies))
+            .with_state(mock);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_thread_replies_are_grouped_into_their_thread() {
+        let reply_ts = ["1700100100.000100", "1700100200.000100"];
+        let mock = MockSlack {
+            history: vec![
+                message("1700100500.000100", None, None),
+                message(PARENT_TS, Some(PARENT_TS), Some(2)),
+                message(OLD_PARENT_TS, Some(OLD_PARENT_TS), Some(1)),
+            ],
+            threads: HashMap::from([
+                (
+                    PARENT_TS.to_string(),
+                    vec![
+                        message(PARENT_TS, Some(PARENT_TS), Some(2)),
+                        message(reply_ts[0], Some(PARENT_TS), None),
+                        message(reply_ts[1], Some(PARENT_TS), None),
+                    ],
+                ),
+                (
+                    OLD_PARENT_TS.to_string(),
+                    vec![
+                        message(OLD_PARENT_TS, Some(OLD_PARENT_TS), Some(1)),
+                        message("1700100300.000100", Some(OLD_PARENT_TS), None),
+                    ],
+                ),
+            ]),
+            ..Default::default()
+        };
+        let replies_requested = mock.replies_requested.clone();
+        let sync_manager = SyncManager {
+            slack_client: SlackClient::with_base_url(&slack_api(mock).await),
+            ..SyncManager::new(SdkClient::new("http://127.0.0.1:1"))
+        };
+        let channel = SlackChannel {
+            id: "C1".to_string(),
+            name: "general".to_string(),
+            is_public: true,
+            is_private: false,
+            is_member: true,
+            num_members: Some(2),
+        };
+
+        let (messages, _) = sync_manager
+            .fetch_channel_messages("xoxb-test", &channel, Some("1700050000.000000"))
+            .await
+            .unwrap();
+
+        // The parent older than the window is not in the history, so its thread is skipped
+        assert_eq!(*replies_requested.lock().unwrap(), vec![PARENT_TS]);
+
+        let groups = ContentProcessor::new()
+            .group_messages_by_date(channel.id.clone(), channel.name.clone(), messages)
+            .unwrap();
+        let threads: Vec<_> = groups.iter().filter(|group| group.is_thread).collect();
+        assert_eq!(threads.len(), 1);
+        assert_eq!(threads[0].thread_ts.as_deref(), Some(PARENT_TS));
+        let thread_messages: Vec<&str> = threads[0]
+            .messages
+            .iter()
+            .map(|(message, _)| message.ts.as_str())
+            .collect();
+        assert_eq!(thread_messages, vec![PARENT_TS, reply_ts[0], reply_ts[1]]);
+    }
+}
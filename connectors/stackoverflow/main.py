@@ -0,0 +1,19 @@
+#!/usr/bin/env python3
+"""Stack Overflow Connector entry point for Omni."""
+
+import logging
+import os
+
+from stackoverflow_connector import StackOverflowConnector
+
+logging.basicConfig(
+    level=logging.INFO,
+    format="%(asctime)s - %(name)s - %(levelname)s - %(message)s",
+)
+
+if __name__ == "__main__":
+    port = os.environ.get("PORT")
+    if not port:
+        raise SystemExit("PORT environment variable is required")
+    port = int(port)
+    StackOverflowConnector().serve(port=port)
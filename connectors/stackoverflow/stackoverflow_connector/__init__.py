@@ -0,0 +1,6 @@
+"""Stack Overflow for Teams connector for Omni."""
+
+from .connector import StackOverflowConnector
+
+__version__ = "1.0.0"
+__all__ = ["StackOverflowConnector"]
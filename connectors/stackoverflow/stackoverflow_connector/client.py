@@ -0,0 +1,213 @@
+"""Thin async wrapper over the Stack Overflow for Teams API with retry logic."""
+
+import asyncio
+import logging
+import re
+from collections.abc import AsyncIterator
+from functools import wraps
+from typing import Any
+
+import httpx
+
+from .config import BODY_FILTER, IDS_PER_REQUEST, PAGE_SIZE, STACK_API_URL
+
+logger = logging.getLogger(__name__)
+
+
+class StackOverflowError(Exception):
+    """Base exception for Stack Overflow API errors."""
+
+    def __init__(self, message: str, status_code: int | None = None):
+        super().__init__(message)
+        self.status_code = status_code
+
+
+class AuthenticationError(StackOverflowError):
+    """Invalid or expired access token (401)."""
+
+    pass
+
+
+class ForbiddenError(StackOverflowError):
+    """The token cannot read the team, or lacks a scope (403)."""
+
+    pass
+
+
+class NotFoundError(StackOverflowError):
+    """Unknown team or API method (404)."""
+
+    pass
+
+
+def _throttle_seconds(response: httpx.Response) -> int | None:
+    """Seconds to wait when the API refused a request for going too fast, None otherwise."""
+    try:
+        error = response.json()
+    except ValueError:
+        return None
+    if error.get("error_name") != "throttle_violation":
+        return None
+    match = re.search(r"(\d+) seconds", error.get("error_message", ""))
+    return int(match.group(1)) if match else 60
+
+
+def with_retry(max_retries: int = 3, base_delay: float = 1.0):
+    """Decorator for retrying Stack Overflow API calls with exponential backoff.
+
+    Handles:
+    - Throttle violations (400) and 429: Wait as long as the API asks (unlimited retries)
+    - 5xx Server Error: Exponential backoff (limited retries)
+    - 401/403: Re-raise as AuthenticationError/ForbiddenError (non-retryable)
+    - 404: Re-raise as NotFoundError (non-retryable)
+    """
+
+    def decorator(func):
+        @wraps(func)
+        async def wrapper(*args, **kwargs):
+            last_exception = None
+            error_retries = 0
+
+            while True:
+                try:
+                    return await func(*args, **kwargs)
+                except httpx.HTTPStatusError as e:
+                    last_exception = e
+                    status = e.response.status_code
+
+                    if status == 401:
+                        raise AuthenticationError(
+                            "Invalid or expired Stack Overflow access token", status_code=401
+                        ) from e
+
+                    if status == 403:
+                        raise ForbiddenError(
+                            f"Forbidden: {e.request.url.path}: {e.response.text}",
+                            status_code=403,
+                        ) from e
+
+                    if status == 404:
+                        raise NotFoundError(
+                            f"Not found: {e.request.url.path}", status_code=404
+                        ) from e
+
+                    wait = _throttle_seconds(e.response) if status == 400 else None
+                    if status == 429:
+                        wait = int(e.response.headers.get("Retry-After", "60"))
+                    if wait is not None:
+                        logger.warning("Rate limited. Waiting %ds", wait)
+                        await asyncio.sleep(wait)
+                        continue
+
+                    if status >= 500:
+                        error_retries += 1
+                        if error_retries > max_retries:
+                            break
+                        delay = base_delay * (2 ** (error_retries - 1))
+                        logger.warning(
+                            "Server error %d. Retrying in %.1fs (%d/%d)",
+                            status,
+                            delay,
+                            error_retries,
+                            max_retries,
+                        )
+                        await asyncio.sleep(delay)
+                        continue
+
+                    raise StackOverflowError(
+                        f"API error {status}: {e.response.text}",
+                        status_code=status,
+                    ) from e
+
+            raise StackOverflowError(f"Max retries exceeded: {last_exception}") from last_exception
+
+        return wrapper
+
+    return decorator
+
+
+class StackOverflowClient:
+    """Async client for the Stack Overflow for Teams API v2.3.
+
+    Basic and Business teams are addressed by their slug in every request. Enterprise sites
+    have an API of their own, and also need the key of a registered application.
+    """
+
+    def __init__(
+        self,
+        token: str,
+        team: str | None = None,
+        key: str | None = None,
+        base_url: str | None = None,
+        http_client: httpx.AsyncClient | None = None,
+    ):
+        self._client = http_client or httpx.AsyncClient(
+            base_url=(base_url or STACK_API_URL).rstrip("/"),
+            headers={"X-API-Access-Token": token},
+            timeout=httpx.Timeout(60.0, connect=10.0),
+        )
+        if key:
+            self._client.headers["X-API-Key"] = key
+        self._params = {"team": team} if team else {}
+
+    async def close(self) -> None:
+        await self._client.aclose()
+
+    @with_retry(max_retries=3)
+    async def _get(self, path: str, params: dict[str, Any]) -> dict[str, Any]:
+        response = await self._client.get(path, params={**self._params, **params})
+        response.raise_for_status()
+        return response.json()
+
+    async def get(self, path: str, params: dict[str, Any] | None = None) -> dict[str, Any]:
+        data = await self._get(path, params or {})
+        # The API asks clients to pause after heavy use, before calling the method again
+        if backoff := data.get("backoff"):
+            logger.info("Backing off for %ds", backoff)
+            await asyncio.sleep(backoff)
+        return data
+
+    async def paginate(
+        self, path: str, params: dict[str, Any] | None = None
+    ) -> AsyncIterator[dict[str, Any]]:
+        """The items of every page of a listing."""
+        params = {**(params or {}), "pagesize": PAGE_SIZE, "page": 1}
+        while True:
+            data = await self.get(path, params)
+            for item in data.get("items", []):
+                yield item
+            if not data.get("has_more"):
+                return
+            params["page"] += 1
+
+    async def get_me(self) -> dict[str, Any]:
+        """The user the token belongs to."""
+        data = await self.get("/me")
+        items = data.get("items", [])
+        return items[0] if items else {}
+
+    async def list_questions(self, since: int | None = None) -> AsyncIterator[dict[str, Any]]:
+        """Questions with their body, least recently active first, active since a time."""
+        params: dict[str, Any] = {"sort": "activity", "order": "asc", "filter": BODY_FILTER}
+        if since is not None:
+            params["min"] = since
+        async for question in self.paginate("/questions", params):
+            yield question
+
+    async def get_questions(self, question_ids: list[int]) -> list[dict[str, Any]]:
+        """The questions among the given IDs that still exist."""
+        return await self._by_ids("/questions", question_ids, {})
+
+    async def get_answers(self, answer_ids: list[int]) -> list[dict[str, Any]]:
+        """Answers with their body."""
+        return await self._by_ids("/answers", answer_ids, {"filter": BODY_FILTER})
+
+    async def _by_ids(
+        self, path: str, ids: list[int], params: dict[str, Any]
+    ) -> list[dict[str, Any]]:
+        items: list[dict[str, Any]] = []
+        for start in range(0, len(ids), IDS_PER_REQUEST):
+            batch = ";".join(str(i) for i in ids[start : start + IDS_PER_REQUEST])
+            async for item in self.paginate(f"{path}/{batch}", params):
+                items.append(item)
+        return items
@@ -0,0 +1,20 @@
+"""Configuration constants for Stack Overflow for Teams connector."""
+
+# Basic and Business teams; Enterprise sites serve the API at https://{site}/api/2.3
+STACK_API_URL = "https://api.stackoverflowteams.com/2.3"
+
+MAX_CONTENT_LENGTH = 100_000
+PAGE_SIZE = 100
+# Most IDs one request can look up
+IDS_PER_REQUEST = 100
+CHECKPOINT_INTERVAL = 50
+
+# Built-in API filter adding the body of questions and answers to the default fields
+BODY_FILTER = "withbody"
+
+# Margin taken off the latest question activity before it becomes the next sync's `min`,
+# for activity recorded late
+WATERMARK_MARGIN_SECONDS = 300
+
+# Deleted questions vanish from the API, so known questions are looked up again this often
+DELETION_CHECK_INTERVAL_SECONDS = 24 * 60 * 60
@@ -0,0 +1,205 @@
+"""Main StackOverflowConnector class."""
+
+import logging
+import time
+from typing import Any
+
+from omni_connector import Connector, SyncContext
+
+from .client import (
+    AuthenticationError,
+    ForbiddenError,
+    StackOverflowClient,
+    StackOverflowError,
+)
+from .config import (
+    CHECKPOINT_INTERVAL,
+    DELETION_CHECK_INTERVAL_SECONDS,
+    IDS_PER_REQUEST,
+    WATERMARK_MARGIN_SECONDS,
+)
+from .mappers import generate_question_content, map_question_to_document, question_external_id
+
+logger = logging.getLogger(__name__)
+
+
+class StackOverflowConnector(Connector):
+    """Stack Overflow for Teams connector for Omni."""
+
+    @property
+    def name(self) -> str:
+        return "stackoverflow"
+
+    @property
+    def version(self) -> str:
+        return "1.0.0"
+
+    @property
+    def sync_modes(self) -> list[str]:
+        return ["full", "incremental"]
+
+    async def sync(
+        self,
+        source_config: dict[str, Any],
+        credentials: dict[str, Any],
+        state: dict[str, Any] | None,
+        ctx: SyncContext,
+    ) -> None:
+        """
+        Sync questions, each with its accepted answer.
+
+        Questions are listed by last activity, which new answers and edits update, so later
+        syncs only read the questions active since the previous one.
+
+        Args:
+            source_config: 'team' (slug of a Basic or Business team), or 'api_url' of an
+                Enterprise site; may set 'tags' to sync only questions with one of them
+            credentials: 'token' (personal access token), and 'key' for Enterprise sites
+            state: Previous sync state, {"activity_since", "question_ids",
+                "deletions_checked_at"}
+            ctx: Sync context with emit(), complete(), etc.
+        """
+        token = credentials.get("token") or credentials.get("access_token")
+        if not token:
+            await ctx.fail("Missing 'token' in credentials")
+            return
+        team = source_config.get("team")
+        if not team and not source_config.get("api_url"):
+            await ctx.fail("Missing 'team' in source config")
+            return
+
+        client = StackOverflowClient(
+            token=token,
+            team=team,
+            key=credentials.get("key"),
+            base_url=source_config.get("api_url"),
+        )
+        try:
+            try:
+                me = await client.get_me()
+                logger.info("Starting Stack Overflow sync as '%s'", me.get("display_name"))
+            except (AuthenticationError, ForbiddenError) as e:
+                await ctx.fail(f"Authentication failed: {e}")
+                return
+            except StackOverflowError as e:
+                await ctx.fail(f"Connection test failed: {e}")
+                return
+
+            sync = _Sync(client, state or {}, source_config.get("tags") or [], ctx)
+            try:
+                await sync.sync_questions()
+                if ctx.is_cancelled():
+                    await ctx.fail("Cancelled by user")
+                    return
+                await sync.delete_missing()
+
+                await ctx.complete(new_state=sync.state())
+                logger.info(
+                    "Sync completed: %d scanned, %d emitted",
+                    ctx.documents_scanned,
+                    ctx.documents_emitted,
+                )
+            except AuthenticationError as e:
+                logger.error("Authentication error during sync: %s", e)
+                await ctx.fail(f"Authentication failed: {e}")
+            except Exception as e:
+                logger.exception("Sync failed with unexpected error")
+                await ctx.fail(str(e))
+        finally:
+            await client.close()
+
+
+class _Sync:
+    """State of one sync run: the activity watermark and the questions synced so far."""
+
+    def __init__(
+        self,
+        client: StackOverflowClient,
+        previous: dict[str, Any],
+        tags: list[str],
+        ctx: SyncContext,
+    ):
+        self.client = client
+        self.ctx = ctx
+        self.tags = {tag.lower() for tag in tags}
+        self.activity_since: int | None = previous.get("activity_since")
+        self.question_ids: set[int] = set(previous.get("question_ids", []))
+        self.deletions_checked_at: int = previous.get("deletions_checked_at", 0)
+        self._since_checkpoint = 0
+
+    def state(self) -> dict[str, Any]:
+        return {
+            "activity_since": self.activity_since,
+            "question_ids": sorted(self.question_ids),
+            "deletions_checked_at": self.deletions_checked_at,
+        }
+
+    async def sync_questions(self) -> None:
+        since = self.activity_since
+        if since is not None:
+            since -= WATERMARK_MARGIN_SECONDS
+        logger.info("Syncing questions active since %s", since or "the beginning")
+
+        # Questions are synced in batches, to look their accepted answers up together
+        batch: list[dict[str, Any]] = []
+        async for question in self.client.list_questions(since):
+            if self.ctx.is_cancelled():
+                return
+            batch.append(question)
+            if len(batch) == IDS_PER_REQUEST:
+                await self._sync_batch(batch)
+                batch = []
+        await self._sync_batch(batch)
+
+    async def _sync_batch(self, questions: list[dict[str, Any]]) -> None:
+        answer_ids = [q["accepted_answer_id"] for q in questions if q.get("accepted_answer_id")]
+        answers = {a["answer_id"]: a for a in await self.client.get_answers(answer_ids)}
+
+        for question in questions:
+            question_id = question["question_id"]
+            await self.ctx.increment_scanned()
+            if self._selected(question):
+                try:
+                    answer = answers.get(question.get("accepted_answer_id"))
+                    content = generate_question_content(question, answer)
+                    content_id = await self.ctx.content_storage.save(content, "text/plain")
+                    await self.ctx.emit(map_question_to_document(question, answer, content_id))
+                    self.question_ids.add(question_id)
+                except AuthenticationError:
+                    raise
+                except Exception as e:
+                    eid = question_external_id(question_id)
+                    logger.warning("Error processing %s: %s", eid, e)
+                    await self.ctx.emit_error(eid, str(e))
+            elif question_id in self.question_ids:
+                # Retagged out of the configured tags
+                await self.ctx.emit_deleted(question_external_id(question_id))
+                self.question_ids.discard(question_id)
+
+            # Questions come least recently active first
+            self.activity_since = question.get("last_activity_date", self.activity_since)
+            await self._checkpoint()
+
+    def _selected(self, question: dict[str, Any]) -> bool:
+        """Whether a question has one of the configured tags, if any are configured."""
+        if not self.tags:
+            return True
+        return any(tag.lower() in self.tags for tag in question.get("tags") or [])
+
+    async def delete_missing(self) -> None:
+        """Delete the synced questions that no longer exist, once a day."""
+        now = int(time.time())
+        if now - self.deletions_checked_at < DELETION_CHECK_INTERVAL_SECONDS:
+            return
+        known = sorted(self.question_ids)
+        existing = {q["question_id"] for q in await self.client.get_questions(known)}
+        for question_id in set(known) - existing:
+            await self.ctx.emit_deleted(question_external_id(question_id))
+            self.question_ids.discard(question_id)
+        self.deletions_checked_at = now
+
+    async def _checkpoint(self) -> None:
+        self._since_checkpoint += 1
+        if self._since_checkpoint >= CHECKPOINT_INTERVAL:
+            self._since_checkpoint = 0
+            await self.ctx.save_state(self.state())
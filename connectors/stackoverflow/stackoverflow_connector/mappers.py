@@ -0,0 +1,117 @@
+"""Question-to-Document mapping functions for Stack Overflow for Teams."""
+
+import html
+from datetime import datetime, timezone
+from html.parser import HTMLParser
+from typing import Any
+
+from omni_connector import Document, DocumentMetadata, DocumentPermissions
+
+from .config import MAX_CONTENT_LENGTH
+
+# Tags that end a line of text when rendered
+_BLOCK_TAGS = {"p", "div", "br", "li", "tr", "h1", "h2", "h3", "h4", "h5", "h6", "pre"}
+
+
+class _TextExtractor(HTMLParser):
+    """Collects the text of a post body, one line per block element and per line of code."""
+
+    def __init__(self) -> None:
+        super().__init__()
+        self.parts: list[str] = []
+
+    def handle_starttag(self, tag: str, attrs: list[tuple[str, str | None]]) -> None:
+        if tag in _BLOCK_TAGS:
+            self.parts.append("\n")
+
+    def handle_endtag(self, tag: str) -> None:
+        if tag in _BLOCK_TAGS:
+            self.parts.append("\n")
+
+    def handle_data(self, data: str) -> None:
+        self.parts.append(data)
+
+
+def html_to_text(body: str) -> str:
+    """Plain text of a post body, with blank lines collapsed."""
+    parser = _TextExtractor()
+    parser.feed(body)
+    parser.close()
+    lines = [" ".join(line.split()) for line in "".join(parser.parts).splitlines()]
+    return "\n".join(line for line in lines if line)
+
+
+def question_external_id(question_id: int) -> str:
+    return f"stackoverflow:question:{question_id}"
+
+
+def parse_epoch(value: int | None) -> datetime | None:
+    """Parse an API date, in seconds since the epoch."""
+    if value is None:
+        return None
+    return datetime.fromtimestamp(value, tz=timezone.utc)
+
+
+def _author(post: dict[str, Any]) -> str:
+    return html.unescape((post.get("owner") or {}).get("display_name") or "Unknown")
+
+
+def generate_question_content(question: dict[str, Any], answer: dict[str, Any] | None) -> str:
+    """Searchable text of a question, followed by its accepted answer."""
+    lines = [html.unescape(question.get("title", ""))]
+    if tags := question.get("tags"):
+        lines.append(f"Tags: {', '.join(tags)}")
+    lines.append(f"Asked by: {_author(question)}")
+    lines.extend(["", html_to_text(question.get("body") or "")])
+    if answer:
+        lines.extend(["", f"--- Accepted Answer by {_author(answer)} ---", ""])
+        lines.append(html_to_text(answer.get("body") or ""))
+    return "\n".join(lines)[:MAX_CONTENT_LENGTH]
+
+
+def map_question_to_document(
+    question: dict[str, Any],
+    answer: dict[str, Any] | None,
+    content_id: str,
+) -> Document:
+    """
+    Map a question, and its accepted answer, to an Omni Document.
+
+    Every member of a team can read all of its questions, so questions are public.
+
+    Args:
+        question: The question, with its body
+        answer: The accepted answer, with its body, if any
+        content_id: ID from content storage
+
+    Returns:
+        Omni Document instance
+    """
+    tags = question.get("tags") or []
+    return Document(
+        external_id=question_external_id(question["question_id"]),
+        title=html.unescape(question.get("title", "")),
+        content_id=content_id,
+        metadata=DocumentMetadata(
+            author=_author(question),
+            created_at=parse_epoch(question.get("creation_date")),
+            updated_at=parse_epoch(question.get("last_activity_date")),
+            url=question.get("link"),
+            mime_type="text/plain",
+            extra={
+                "question_id": question["question_id"],
+                "accepted_answer_id": question.get("accepted_answer_id"),
+            },
+        ),
+        permissions=DocumentPermissions(public=True),
+        attributes={
+            "source_type": "stack_overflow",
+            "content_type": "question",
+            "tags": ",".join(tags),
+            # Net votes of the question, for the searcher's vote boost
+            "votes": question.get("score", 0),
+            "answer_votes": answer.get("score", 0) if answer else None,
+            "answered": "true" if answer else "false",
+            "answer_count": question.get("answer_count", 0),
+        },
+    )
@@ -0,0 +1,252 @@
+"""Integration test fixtures for the Stack Overflow for Teams connector.
+
+Session-scoped: harness, mock Stack Overflow API server, connector server, connector-manager.
+Function-scoped: seed helper, source_id, httpx client.
+"""
+
+from __future__ import annotations
+
+import logging
+import socket
+import threading
+import time
+from typing import Any
+
+import httpx
+import pytest
+import pytest_asyncio
+import uvicorn
+from starlette.applications import Starlette
+from starlette.requests import Request
+from starlette.responses import JSONResponse
+from starlette.routing import Route
+
+from omni_connector.testing import OmniTestHarness, SeedHelper
+
+logger = logging.getLogger(__name__)
+
+
+# ---------------------------------------------------------------------------
+# Mock data payload helpers
+# ---------------------------------------------------------------------------
+
+
+def _question_payload(
+    question_id: int,
+    activity: int,
+    tags: list[str],
+    accepted_answer_id: int | None = None,
+) -> dict[str, Any]:
+    question: dict[str, Any] = {
+        "question_id": question_id,
+        "title": f"Question {question_id}",
+        "body": f"<p>Body of question {question_id}</p>",
+        "tags": tags,
+        "score": 1,
+        "answer_count": 1 if accepted_answer_id else 0,
+        "is_answered": accepted_answer_id is not None,
+        "owner": {"display_name": "Alice"},
+        "creation_date": 500,
+        "last_activity_date": activity,
+        "link": f"https://stackoverflowteams.com/c/acme/questions/{question_id}",
+    }
+    if accepted_answer_id:
+        question["accepted_answer_id"] = accepted_answer_id
+    return question
+
+
+# ---------------------------------------------------------------------------
+# Mock Stack Overflow for Teams API
+# ---------------------------------------------------------------------------
+
+
+class MockStackOverflowAPI:
+    """Controllable mock of the Stack Overflow for Teams API v2.3 endpoints.
+
+    Every answer looked up exists, with a score of 5.
+    """
+
+    def __init__(self) -> None:
+        self.questions: dict[int, dict[str, Any]] = {}
+        self.activity_mins: list[int | None] = []
+
+    def reset(self) -> None:
+        self.questions.clear()
+        self.activity_mins.clear()
+
+    def add_question(self, question: dict[str, Any]) -> None:
+        self.questions[question["question_id"]] = question
+
+    def create_app(self) -> Starlette:
+        mock = self
+
+        def _items(items: list[dict[str, Any]]) -> JSONResponse:
+            return JSONResponse({"items": items, "has_more": False, "quota_remaining": 9999})
+
+        def _ids(request: Request) -> list[int]:
+            return [int(i) for i in request.path_params["ids"].split(";")]
+
+        async def get_me(request: Request) -> JSONResponse:
+            return _items([{"user_id": 1, "display_name": "Alice"}])
+
+        async def list_questions(request: Request) -> JSONResponse:
+            min_activity = request.query_params.get("min")
+            since = int(min_activity) if min_activity is not None else None
+            mock.activity_mins.append(since)
+            questions = [
+                question
+                for question in mock.questions.values()
+                if since is None or question["last_activity_date"] >= since
+            ]
+            return _items(sorted(questions, key=lambda q: q["last_activity_date"]))
+
+        async def get_questions(request: Request) -> JSONResponse:
+            return _items([mock.questions[i] for i in _ids(request) if i in mock.questions])
+
+        async def get_answers(request: Request) -> JSONResponse:
+            return _items(
+                [
+                    {"answer_id": i, "score": 5, "body": "<p>Answer</p>", "is_accepted": True}
+                    for i in _ids(request)
+                ]
+            )
+
+        routes = [
+            Route("/me", get_me),
+            Route("/questions", list_questions),
+            Route("/questions/{ids}", get_questions),
+            Route("/answers/{ids}", get_answers),
+        ]
+        return Starlette(routes=routes)
+
+
+# ---------------------------------------------------------------------------
+# Helpers
+# ---------------------------------------------------------------------------
+
+
+def _free_port() -> int:
+    with socket.socket(socket.AF_INET, socket.SOCK_STREAM) as s:
+        s.bind(("", 0))
+        return s.getsockname()[1]
+
+
+def _wait_for_port(port: int, host: str = "localhost", timeout: float = 10) -> None:
+    deadline = time.monotonic() + timeout
+    while time.monotonic() < deadline:
+        try:
+            with socket.create_connection((host, port), timeout=1):
+                return
+        except OSError:
+            time.sleep(0.1)
+    raise TimeoutError(f"Port {port} not open after {timeout}s")
+
+
+# ---------------------------------------------------------------------------
+# Session-scoped fixtures
+# ---------------------------------------------------------------------------
+
+
+@pytest.fixture(scope="session")
+def mock_stackoverflow_api() -> MockStackOverflowAPI:
+    return MockStackOverflowAPI()
+
+
+@pytest.fixture(scope="session")
+def mock_stackoverflow_server(mock_stackoverflow_api: MockStackOverflowAPI) -> str:
+    """Start mock Stack Overflow API server in a daemon thread. Returns base URL."""
+    port = _free_port()
+    app = mock_stackoverflow_api.create_app()
+    config = uvicorn.Config(app, host="0.0.0.0", port=port, log_level="warning")
+    server = uvicorn.Server(config)
+
+    thread = threading.Thread(target=server.run, daemon=True)
+    thread.start()
+
+    _wait_for_port(port)
+    return f"http://localhost:{port}"
+
+
+@pytest.fixture(scope="session")
+def connector_port() -> int:
+    return _free_port()
+
+
+@pytest.fixture(scope="session")
+def connector_server(connector_port: int) -> str:
+    """Start the Stack Overflow connector as a uvicorn server in a thread. Returns base URL."""
+    import os
+
+    os.environ.setdefault("CONNECTOR_MANAGER_URL", "http://localhost:0")
+
+    from stackoverflow_connector import StackOverflowConnector
+    from omni_connector.server import create_app
+
+    app = create_app(StackOverflowConnector())
+    config = uvicorn.Config(
+        app, host="0.0.0.0", port=connector_port, log_level="warning"
+    )
+    server = uvicorn.Server(config)
+
+    thread = threading.Thread(target=server.run, daemon=True)
+    thread.start()
+
+    _wait_for_port(connector_port)
+    return f"http://localhost:{connector_port}"
+
+
+@pytest_asyncio.fixture(scope="session")
+async def harness(
+    connector_server: str,
+    connector_port: int,
+) -> OmniTestHarness:
+    """Session-scoped OmniTestHarness with all infrastructure started."""
+    import os
+
+    h = OmniTestHarness()
+    await h.start_infra()
+    await h.start_connector_manager(
+        {
+            "STACKOVERFLOW_CONNECTOR_URL": f"http://host.docker.internal:{connector_port}",
+        }
+    )
+
+    os.environ["CONNECTOR_MANAGER_URL"] = h.connector_manager_url
+
+    yield h
+    await h.teardown()
+
+
+# ---------------------------------------------------------------------------
+# Function-scoped fixtures
+# ---------------------------------------------------------------------------
+
+
+@pytest_asyncio.fixture
+async def seed(harness: OmniTestHarness) -> SeedHelper:
+    return harness.seed()
+
+
+@pytest_asyncio.fixture
+async def source_id(
+    seed: SeedHelper,
+    mock_stackoverflow_server: str,
+    mock_stackoverflow_api: MockStackOverflowAPI,
+) -> str:
+    """Create a Stack Overflow source with credentials pointing to the mock server."""
+    mock_stackoverflow_api.reset()
+    sid = await seed.create_source(
+        source_type="stack_overflow",
+        config={"api_url": mock_stackoverflow_server},
+    )
+    await seed.create_credentials(sid, {"token": "test-token"}, provider="stackoverflow")
+    return sid
+
+
+@pytest_asyncio.fixture
+async def cm_client(harness: OmniTestHarness) -> httpx.AsyncClient:
+    """Async httpx client pointed at the connector-manager."""
+    async with httpx.AsyncClient(
+        base_url=harness.connector_manager_url, timeout=30
+    ) as client:
+        yield client
@@ -0,0 +1,118 @@
+"""Integration tests: incremental sync reads the active questions and removes the gone ones."""
+
+import httpx
+import pytest
+
+from omni_connector.testing import get_events, wait_for_sync
+
+from .conftest import _question_payload
+
+pytestmark = pytest.mark.integration
+
+
+async def _sync(harness, cm_client: httpx.AsyncClient, source_id: str, sync_type: str):
+    resp = await cm_client.post(
+        "/sync",
+        json={"source_id": source_id, "sync_type": sync_type},
+    )
+    assert resp.status_code == 200, resp.text
+    row = await wait_for_sync(harness.db_pool, resp.json()["sync_run_id"], timeout=30)
+    assert (
+        row["status"] == "completed"
+    ), f"Sync ended with status={row['status']}, error={row.get('error_message')}"
+    return row
+
+
+async def _events(harness, source_id: str, row, event_type: str) -> list[dict]:
+    events = await get_events(harness.db_pool, source_id)
+    return [
+        e["payload"]
+        for e in events
+        if e["sync_run_id"] == row["id"] and e["event_type"] == event_type
+    ]
+
+
+async def _documents(harness, source_id: str, row, event_type: str) -> list[str]:
+    return [e["document_id"] for e in await _events(harness, source_id, row, event_type)]
+
+
+def _seed_questions(mock_stackoverflow_api) -> None:
+    mock_stackoverflow_api.add_question(
+        _question_payload(1, 1000, ["vault"], accepted_answer_id=10)
+    )
+    mock_stackoverflow_api.add_question(_question_payload(2, 2000, ["kafka"]))
+
+
+async def test_second_sync_reads_only_active_questions(
+    harness, seed, source_id, mock_stackoverflow_api, cm_client: httpx.AsyncClient
+):
+    _seed_questions(mock_stackoverflow_api)
+
+    row = await _sync(harness, cm_client, source_id, "full")
+    created = await _events(harness, source_id, row, "document_created")
+    assert [doc["document_id"] for doc in created] == [
+        "stackoverflow:question:1",
+        "stackoverflow:question:2",
+    ]
+    assert created[0]["attributes"]["answer_votes"] == 5
+    state = await seed.get_connector_state(source_id)
+    assert state["activity_since"] == 2000
+
+    row = await _sync(harness, cm_client, source_id, "incremental")
+
+    # The watermark less its margin
+    assert mock_stackoverflow_api.activity_mins[-1] == 1700
+    assert await _documents(harness, source_id, row, "document_created") == [
+        "stackoverflow:question:2"
+    ]
+
+
+async def test_deleted_questions_are_removed_once_a_day(
+    harness, seed, source_id, mock_stackoverflow_api, cm_client: httpx.AsyncClient
+):
+    _seed_questions(mock_stackoverflow_api)
+    await _sync(harness, cm_client, source_id, "full")
+
+    del mock_stackoverflow_api.questions[1]
+    row = await _sync(harness, cm_client, source_id, "incremental")
+    assert await _documents(harness, source_id, row, "document_deleted") == []
+
+    await harness.db_pool.execute(
+        "UPDATE sources SET connector_state = connector_state || "
+        """'{"deletions_checked_at": 0}'::jsonb WHERE id = $1::char(26)""",
+        source_id,
+    )
+    row = await _sync(harness, cm_client, source_id, "incremental")
+    assert await _documents(harness, source_id, row, "document_deleted") == [
+        "stackoverflow:question:1"
+    ]
+    state = await seed.get_connector_state(source_id)
+    assert state["question_ids"] == [2]
+
+
+async def test_questions_retagged_out_of_tags_are_removed(
+    harness,
+    seed,
+    mock_stackoverflow_server,
+    mock_stackoverflow_api,
+    cm_client: httpx.AsyncClient,
+):
+    mock_stackoverflow_api.reset()
+    source_id = await seed.create_source(
+        source_type="stack_overflow",
+        config={"api_url": mock_stackoverflow_server, "tags": ["Vault"]},
+    )
+    await seed.create_credentials(source_id, {"token": "test-token"}, provider="stackoverflow")
+    _seed_questions(mock_stackoverflow_api)
+    await _sync(harness, cm_client, source_id, "full")
+    state = await seed.get_connector_state(source_id)
+    assert state["question_ids"] == [1]
+
+    mock_stackoverflow_api.add_question(_question_payload(1, 3000, ["kafka"]))
+    row = await _sync(harness, cm_client, source_id, "incremental")
+
+    assert await _documents(harness, source_id, row, "document_deleted") == [
+        "stackoverflow:question:1"
+    ]
+    state = await seed.get_connector_state(source_id)
+    assert state["question_ids"] == []
@@ -0,0 +1,58 @@
+"""Tests for the Stack Overflow for Teams mappers."""
+
+from stackoverflow_connector.mappers import (
+    generate_question_content,
+    html_to_text,
+    map_question_to_document,
+)
+
+QUESTION = {
+    "question_id": 118,
+    "title": "How do I rotate the &quot;payments&quot; database credentials?",
+    "body": "<p>The vault lease expired.</p>\n<pre><code>vault read db/creds\n</code></pre>",
+    "tags": ["vault", "postgres"],
+    "score": 12,
+    "answer_count": 3,
+    "accepted_answer_id": 120,
+    "owner": {"display_name": "Alice"},
+    "creation_date": 1714550400,
+    "last_activity_date": 1714636800,
+    "link": "https://stackoverflowteams.com/c/acme/questions/118",
+}
+
+ANSWER = {
+    "answer_id": 120,
+    "score": 30,
+    "body": "<p>Run <code>make rotate</code> from the ops repo.</p>",
+    "owner": {"display_name": "Bob"},
+}
+
+
+def test_html_to_text_keeps_code_lines():
+    assert html_to_text(QUESTION["body"]) == "The vault lease expired.\nvault read db/creds"
+
+
+def test_content_includes_accepted_answer():
+    content = generate_question_content(QUESTION, ANSWER)
+
+    assert content.startswith('How do I rotate the "payments" database credentials?')
+    assert "Tags: vault, postgres" in content
+    assert "--- Accepted Answer by Bob ---\n\nRun make rotate from the ops repo." in content
+
+
+def test_question_document_attributes():
+    doc = map_question_to_document(QUESTION, ANSWER, "content-id")
+
+    assert doc.external_id == "stackoverflow:question:118"
+    assert doc.title == 'How do I rotate the "payments" database credentials?'
+    assert doc.attributes["tags"] == "vault,postgres"
+    assert doc.attributes["votes"] == 12
+    assert doc.attributes["answer_votes"] == 30
+    assert doc.attributes["answered"] == "true"
+
+
+def test_unanswered_question():
+    doc = map_question_to_document({**QUESTION, "accepted_answer_id": None}, None, "content-id")
+
+    assert doc.attributes["answered"] == "false"
+    assert doc.attributes["answer_votes"] is None
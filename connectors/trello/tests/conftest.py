@@ -0,0 +1,290 @@
+"""Integration test fixtures for the Trello connector.
+
+Session-scoped: harness, mock Trello API server, connector server, connector-manager.
+Function-scoped: seed helper, source_id, httpx client.
+"""
+
+from __future__ import annotations
+
+import logging
+import socket
+import threading
+import time
+from typing import Any
+
+import httpx
+import pytest
+import pytest_asyncio
+import uvicorn
+from starlette.applications import Starlette
+from starlette.requests import Request
+from starlette.responses import JSONResponse
+from starlette.routing import Route
+
+from omni_connector.testing import OmniTestHarness, SeedHelper
+
+logger = logging.getLogger(__name__)
+
+
+# ---------------------------------------------------------------------------
+# Mock data payload helpers
+# ---------------------------------------------------------------------------
+
+
+def _board_payload(board_id: str, name: str) -> dict[str, Any]:
+    return {
+        "id": board_id,
+        "name": name,
+        "desc": "",
+        "url": f"https://trello.com/b/{board_id}",
+        "closed": False,
+        "prefs": {"permissionLevel": "private"},
+    }
+
+
+def _card_payload(card_id: str, board_id: str, **fields: Any) -> dict[str, Any]:
+    return {
+        "id": card_id,
+        "name": f"Card {card_id}",
+        "desc": "",
+        "idBoard": board_id,
+        "idList": "l1",
+        "labels": [],
+        "closed": False,
+        "url": f"https://trello.com/c/{card_id}",
+        **fields,
+    }
+
+
+def _action_payload(
+    action_id: str, action_type: str, card_id: str | None = None, text: str = ""
+) -> dict[str, Any]:
+    data: dict[str, Any] = {"text": text}
+    if card_id:
+        data["card"] = {"id": card_id}
+    return {"id": action_id, "type": action_type, "data": data}
+
+
+# ---------------------------------------------------------------------------
+# Mock Trello API
+# ---------------------------------------------------------------------------
+
+
+class MockTrelloAPI:
+    """Controllable mock of the Trello REST API endpoints.
+
+    Boards have a single list and a single member; actions are kept newest first.
+    """
+
+    def __init__(self) -> None:
+        self.boards: dict[str, dict[str, Any]] = {}
+        self.cards: dict[str, dict[str, Any]] = {}
+        self.actions: list[dict[str, Any]] = []
+
+    def reset(self) -> None:
+        self.boards.clear()
+        self.cards.clear()
+        self.actions.clear()
+
+    def create_app(self) -> Starlette:
+        mock = self
+
+        def _not_found() -> JSONResponse:
+            return JSONResponse("The requested resource was not found.", status_code=404)
+
+        def _actions(request: Request, card_id: str | None = None) -> JSONResponse:
+            actions = mock.actions
+            if since := request.query_params.get("since"):
+                ids = [action["id"] for action in actions]
+                actions = actions[: ids.index(since)] if since in ids else actions
+            if action_filter := request.query_params.get("filter"):
+                actions = [a for a in actions if a["type"] in action_filter.split(",")]
+            if card_id:
+                actions = [a for a in actions if a["data"].get("card", {}).get("id") == card_id]
+            return JSONResponse(actions[: int(request.query_params.get("limit", "1000"))])
+
+        async def get_me(request: Request) -> JSONResponse:
+            return JSONResponse({"id": "m1", "username": "ada", "fullName": "Ada Lovelace"})
+
+        async def list_boards(request: Request) -> JSONResponse:
+            return JSONResponse(list(mock.boards.values()))
+
+        async def get_board(request: Request) -> JSONResponse:
+            board = mock.boards.get(request.path_params["board_id"])
+            return JSONResponse(board) if board else _not_found()
+
+        async def list_members(request: Request) -> JSONResponse:
+            return JSONResponse([{"id": "m1", "username": "ada", "email": "ada@example.com"}])
+
+        async def list_lists(request: Request) -> JSONResponse:
+            return JSONResponse([{"id": "l1", "name": "To Do", "closed": False}])
+
+        async def list_cards(request: Request) -> JSONResponse:
+            board_id = request.path_params["board_id"]
+            return JSONResponse(
+                [
+                    card
+                    for card in mock.cards.values()
+                    if card["idBoard"] == board_id and not card["closed"]
+                ]
+            )
+
+        async def list_board_actions(request: Request) -> JSONResponse:
+            return _actions(request)
+
+        async def get_card(request: Request) -> JSONResponse:
+            card = mock.cards.get(request.path_params["card_id"])
+            return JSONResponse(card) if card else _not_found()
+
+        async def list_card_actions(request: Request) -> JSONResponse:
+            return _actions(request, request.path_params["card_id"])
+
+        routes = [
+            Route("/1/members/me", get_me),
+            Route("/1/members/me/boards", list_boards),
+            Route("/1/boards/{board_id}", get_board),
+            Route("/1/boards/{board_id}/members", list_members),
+            Route("/1/boards/{board_id}/lists", list_lists),
+            Route("/1/boards/{board_id}/cards", list_cards),
+            Route("/1/boards/{board_id}/actions", list_board_actions),
+            Route("/1/cards/{card_id}", get_card),
+            Route("/1/cards/{card_id}/actions", list_card_actions),
+        ]
+        return Starlette(routes=routes)
+
+
+# ---------------------------------------------------------------------------
+# Helpers
+# ---------------------------------------------------------------------------
+
+
+def _free_port() -> int:
+    with socket.socket(socket.AF_INET, socket.SOCK_STREAM) as s:
+        s.bind(("", 0))
+        return s.getsockname()[1]
+
+
+def _wait_for_port(port: int, host: str = "localhost", timeout: float = 10) -> None:
+    deadline = time.monotonic() + timeout
+    while time.monotonic() < deadline:
+        try:
+            with socket.create_connection((host, port), timeout=1):
+                return
+        except OSError:
+            time.sleep(0.1)
+    raise TimeoutError(f"Port {port} not open after {timeout}s")
+
+
+# ---------------------------------------------------------------------------
+# Session-scoped fixtures
+# ---------------------------------------------------------------------------
+
+
+@pytest.fixture(scope="session")
+def mock_trello_api() -> MockTrelloAPI:
+    return MockTrelloAPI()
+
+
+@pytest.fixture(scope="session")
+def mock_trello_server(mock_trello_api: MockTrelloAPI) -> str:
+    """Start mock Trello API server in a daemon thread. Returns base URL."""
+    port = _free_port()
+    app = mock_trello_api.create_app()
+    config = uvicorn.Config(app, host="0.0.0.0", port=port, log_level="warning")
+    server = uvicorn.Server(config)
+
+    thread = threading.Thread(target=server.run, daemon=True)
+    thread.start()
+
+    _wait_for_port(port)
+    return f"http://localhost:{port}"
+
+
+@pytest.fixture(scope="session")
+def connector_port() -> int:
+    return _free_port()
+
+
+@pytest.fixture(scope="session")
+def connector_server(connector_port: int) -> str:
+    """Start the Trello connector as a uvicorn server in a daemon thread. Returns base URL."""
+    import os
+
+    os.environ.setdefault("CONNECTOR_MANAGER_URL", "http://localhost:0")
+
+    from trello_connector import TrelloConnector
+    from omni_connector.server import create_app
+
+    app = create_app(TrelloConnector())
+    config = uvicorn.Config(
+        app, host="0.0.0.0", port=connector_port, log_level="warning"
+    )
+    server = uvicorn.Server(config)
+
+    thread = threading.Thread(target=server.run, daemon=True)
+    thread.start()
+
+    _wait_for_port(connector_port)
+    return f"http://localhost:{connector_port}"
+
+
+@pytest_asyncio.fixture(scope="session")
+async def harness(
+    connector_server: str,
+    connector_port: int,
+) -> OmniTestHarness:
+    """Session-scoped OmniTestHarness with all infrastructure started."""
+    import os
+
+    h = OmniTestHarness()
+    await h.start_infra()
+    await h.start_connector_manager(
+        {
+            "TRELLO_CONNECTOR_URL": f"http://host.docker.internal:{connector_port}",
+        }
+    )
+
+    os.environ["CONNECTOR_MANAGER_URL"] = h.connector_manager_url
+
+    yield h
+    await h.teardown()
+
+
+# ---------------------------------------------------------------------------
+# Function-scoped fixtures
+# ---------------------------------------------------------------------------
+
+
+@pytest_asyncio.fixture
+async def seed(harness: OmniTestHarness) -> SeedHelper:
+    return harness.seed()
+
+
+@pytest_asyncio.fixture
+async def source_id(
+    seed: SeedHelper,
+    mock_trello_server: str,
+    mock_trello_api: MockTrelloAPI,
+) -> str:
+    """Create a Trello source with credentials pointing to the mock server."""
+    mock_trello_api.reset()
+    sid = await seed.create_source(
+        source_type="trello",
+        config={"api_url": mock_trello_server},
+    )
+    await seed.create_credentials(
+        sid,
+        {"api_key": "test-key", "token": "test-token"},
+        provider="trello",
+        auth_type="api_key",
+    )
+    return sid
+
+
+@pytest_asyncio.fixture
+async def cm_client(harness: OmniTestHarness) -> httpx.AsyncClient:
+    """Async httpx client pointed at the connector-manager."""
+    async with httpx.AsyncClient(
+        base_url=harness.connector_manager_url, timeout=30
+    ) as client:
+        yield client
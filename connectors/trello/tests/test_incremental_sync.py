@@ -0,0 +1,125 @@
+"""Integration tests: boards are synced whole, then through the actions on them."""
+
+import httpx
+import pytest
+
+from omni_connector.testing import get_events, wait_for_sync
+
+from .conftest import _action_payload, _board_payload, _card_payload
+
+pytestmark = pytest.mark.integration
+
+BOARD_ID = "b1"
+
+
+async def _sync(harness, cm_client: httpx.AsyncClient, source_id: str, sync_type: str):
+    resp = await cm_client.post(
+        "/sync",
+        json={"source_id": source_id, "sync_type": sync_type},
+    )
+    assert resp.status_code == 200, resp.text
+    row = await wait_for_sync(harness.db_pool, resp.json()["sync_run_id"], timeout=30)
+    assert (
+        row["status"] == "completed"
+    ), f"Sync ended with status={row['status']}, error={row.get('error_message')}"
+    return row
+
+
+async def _events(harness, source_id: str, row, event_type: str) -> list[dict]:
+    events = await get_events(harness.db_pool, source_id)
+    return [
+        e["payload"]
+        for e in events
+        if e["sync_run_id"] == row["id"] and e["event_type"] == event_type
+    ]
+
+
+async def _documents(harness, source_id: str, row, event_type: str) -> list[str]:
+    return [p["document_id"] for p in await _events(harness, source_id, row, event_type)]
+
+
+def _seed_board(mock_trello_api, *card_ids: str) -> None:
+    mock_trello_api.boards[BOARD_ID] = _board_payload(BOARD_ID, "Launch")
+    for card_id in card_ids:
+        mock_trello_api.cards[card_id] = _card_payload(card_id, BOARD_ID)
+    mock_trello_api.actions.append(_action_payload("a1", "createCard", card_ids[0]))
+
+
+async def test_first_sync_emits_board_and_cards_with_comments(
+    harness, seed, source_id, mock_trello_api, cm_client: httpx.AsyncClient
+):
+    _seed_board(mock_trello_api, "c1", "c2")
+    mock_trello_api.actions.insert(0, _action_payload("a2", "commentCard", "c1", "Looks good"))
+
+    row = await _sync(harness, cm_client, source_id, "full")
+
+    created = await _events(harness, source_id, row, "document_created")
+    assert [doc["document_id"] for doc in created] == [
+        "trello:board:b1",
+        "trello:card:c1",
+        "trello:card:c2",
+    ]
+    assert created[1]["permissions"]["groups"] == ["trello:board:b1"]
+    content = await harness.db_pool.fetchval(
+        "SELECT content FROM content_blobs WHERE id = $1", created[1]["content_id"]
+    )
+    assert content.endswith(b"Unknown: Looks good")
+    state = await seed.get_connector_state(source_id)
+    assert state == {"boards": {"b1": {"last_action_id": "a2", "cards": ["c1", "c2"]}}}
+
+
+async def test_actions_since_sync_changed_and_removed_cards(
+    harness, seed, source_id, mock_trello_api, cm_client: httpx.AsyncClient
+):
+    _seed_board(mock_trello_api, "c1", "c2")
+    await _sync(harness, cm_client, source_id, "full")
+
+    mock_trello_api.cards["c2"]["closed"] = True
+    mock_trello_api.cards["c3"] = _card_payload("c3", BOARD_ID)
+    mock_trello_api.actions[:0] = [
+        _action_payload("a4", "createCard", "c3"),
+        _action_payload("a3", "updateCard", "c2"),
+        _action_payload("a2", "commentCard", "c1", "Done"),
+    ]
+    row = await _sync(harness, cm_client, source_id, "incremental")
+
+    assert await _documents(harness, source_id, row, "document_created") == [
+        "trello:card:c1",
+        "trello:card:c3",
+    ]
+    assert await _documents(harness, source_id, row, "document_deleted") == ["trello:card:c2"]
+    state = await seed.get_connector_state(source_id)
+    assert state["boards"][BOARD_ID] == {"last_action_id": "a4", "cards": ["c1", "c3"]}
+
+
+async def test_member_change_syncs_the_whole_board_again(
+    harness, source_id, mock_trello_api, cm_client: httpx.AsyncClient
+):
+    _seed_board(mock_trello_api, "c1", "c9")
+    await _sync(harness, cm_client, source_id, "full")
+
+    del mock_trello_api.cards["c9"]
+    mock_trello_api.actions.insert(0, _action_payload("a2", "addMemberToBoard"))
+    row = await _sync(harness, cm_client, source_id, "incremental")
+
+    assert await _documents(harness, source_id, row, "document_created") == [
+        "trello:board:b1",
+        "trello:card:c1",
+    ]
+    assert await _documents(harness, source_id, row, "document_deleted") == ["trello:card:c9"]
+
+
+async def test_boards_no_longer_listed_are_deleted_with_their_cards(
+    harness, seed, source_id, mock_trello_api, cm_client: httpx.AsyncClient
+):
+    _seed_board(mock_trello_api, "c1")
+    await _sync(harness, cm_client, source_id, "full")
+
+    del mock_trello_api.boards[BOARD_ID]
+    row = await _sync(harness, cm_client, source_id, "incremental")
+
+    assert await _documents(harness, source_id, row, "document_deleted") == [
+        "trello:card:c1",
+        "trello:board:b1",
+    ]
+    assert await seed.get_connector_state(source_id) == {"boards": {}}
@@ -0,0 +1,92 @@
+"""Tests for the Trello mappers."""
+
+from trello_connector.mappers import (
+    build_permissions,
+    generate_board_content,
+    generate_card_content,
+    map_card_to_document,
+)
+
+BOARD = {
+    "id": "5abbe4b7ddc1b351ef961414",
+    "name": "Launch",
+    "desc": "Everything for the spring launch",
+    "url": "https://trello.com/b/nC8QJJoZ/launch",
+    "idOrganization": "5abbe4b7ddc1b351ef961400",
+    "prefs": {"permissionLevel": "org"},
+}
+
+CARD = {
+    "id": "5abbe4b7ddc1b351ef961420",
+    "name": "Write the press release",
+    "desc": "Draft and review with legal.",
+    "idBoard": BOARD["id"],
+    "idList": "list-1",
+    "labels": [{"name": "Marketing", "color": "green"}, {"name": "", "color": "red"}],
+    "due": "2024-05-02T16:00:00.000Z",
+    "dueComplete": False,
+    "dateLastActivity": "2024-04-20T09:30:00.000Z",
+    "url": "https://trello.com/c/AbCd1234/12-write-the-press-release",
+}
+
+MEMBERS = [
+    {"id": "m1", "fullName": "Ada Lovelace", "email": "Ada@example.com"},
+    {"id": "m2", "fullName": "Alan Turing"},
+]
+
+
+def test_private_board_is_shared_with_its_members_and_workspace():
+    permissions = build_permissions(BOARD, MEMBERS)
+
+    assert permissions.public is False
+    assert permissions.users == ["ada@example.com"]
+    assert permissions.groups == [
+        "trello:board:5abbe4b7ddc1b351ef961414",
+        "trello:workspace:5abbe4b7ddc1b351ef961400",
+    ]
+    assert build_permissions({**BOARD, "prefs": {"permissionLevel": "public"}}, []).public
+
+
+def test_card_content_has_its_list_labels_and_comments_in_order():
+    comments = [
+        {
+            "date": "2024-04-21T10:00:00.000Z",
+            "memberCreator": {"fullName": "Alan Turing"},
+            "data": {"text": "Legal approved"},
+        },
+        {
+            "date": "2024-04-20T10:00:00.000Z",
+            "memberCreator": {"fullName": "Ada Lovelace"},
+            "data": {"text": "First draft attached"},
+        },
+    ]
+
+    content = generate_card_content(CARD, BOARD, "Doing", comments)
+
+    assert "List: Doing\nLabels: Marketing, red" in content
+    assert content.endswith(
+        "--- Comments ---\nAda Lovelace: First draft attached\nAlan Turing: Legal approved"
+    )
+
+
+def test_card_document_has_label_attributes():
+    doc = map_card_to_document(CARD, BOARD, "Doing", "content-1", build_permissions(BOARD, []))
+
+    assert doc.external_id == "trello:card:5abbe4b7ddc1b351ef961420"
+    assert doc.metadata.path == "Launch/Doing"
+    assert doc.metadata.created_at.year == 2018
+    assert doc.attributes["labels"] == "Marketing,red"
+    assert doc.attributes["list"] == "Doing"
+    assert doc.attributes["due"] == "2024-05-02"
+
+
+def test_board_content_lists_open_lists_in_order():
+    lists = [
+        {"id": "l2", "name": "Done", "pos": 3},
+        {"id": "l1", "name": "To Do", "pos": 1},
+        {"id": "l3", "name": "Old", "pos": 2, "closed": True},
+    ]
+
+    content = generate_board_content(BOARD, lists)
+
+    assert content.endswith("--- Lists ---\n- To Do\n- Done")
@@ -0,0 +1,6 @@
+"""Trello connector for Omni."""
+
+from .connector import TrelloConnector
+
+__version__ = "1.0.0"
+__all__ = ["TrelloConnector"]
@@ -0,0 +1,208 @@
+"""Thin async wrapper over the Trello REST API with retry logic."""
+
+import asyncio
+import logging
+import time
+from collections.abc import AsyncIterator
+from functools import wraps
+from typing import Any
+
+import httpx
+
+from .config import (
+    BOARD_FIELDS,
+    CARD_FIELDS,
+    MIN_REQUEST_INTERVAL_SECONDS,
+    PAGE_SIZE,
+    TRELLO_API_URL,
+)
+
+logger = logging.getLogger(__name__)
+
+
+class TrelloError(Exception):
+    """Base exception for Trello API errors."""
+
+    def __init__(self, message: str, status_code: int | None = None):
+        super().__init__(message)
+        self.status_code = status_code
+
+
+class AuthenticationError(TrelloError):
+    """Invalid API key or token (401)."""
+
+    pass
+
+
+class NotFoundError(TrelloError):
+    """Deleted board or card, or one the token's member cannot see (404)."""
+
+    pass
+
+
+def with_retry(max_retries: int = 3, base_delay: float = 1.0):
+    """Decorator for retrying Trello API calls with exponential backoff.
+
+    Handles:
+    - 429 Rate Limit: Wait for Retry-After header (unlimited retries)
+    - 5xx Server Error: Exponential backoff (limited retries)
+    - 401: Re-raise as AuthenticationError (non-retryable)
+    - 404: Re-raise as NotFoundError (non-retryable)
+    """
+
+    def decorator(func):
+        @wraps(func)
+        async def wrapper(*args, **kwargs):
+            last_exception = None
+            error_retries = 0
+
+            while True:
+                try:
+                    return await func(*args, **kwargs)
+                except httpx.HTTPStatusError as e:
+                    last_exception = e
+                    status = e.response.status_code
+
+                    if status == 401:
+                        raise AuthenticationError(
+                            f"Unauthorized: {e.request.url.path}: {e.response.text}",
+                            status_code=401,
+                        ) from e
+
+                    if status == 404:
+                        raise NotFoundError(
+                            f"Not found: {e.request.url.path}", status_code=404
+                        ) from e
+
+                    if status == 429:
+                        retry_after = int(e.response.headers.get("Retry-After", "10"))
+                        logger.warning("Rate limited. Waiting %ds", retry_after)
+                        await asyncio.sleep(retry_after)
+                        continue
+
+                    if status >= 500:
+                        error_retries += 1
+                        if error_retries > max_retries:
+                            break
+                        delay = base_delay * (2 ** (error_retries - 1))
+                        logger.warning(
+                            "Server error %d. Retrying in %.1fs (%d/%d)",
+                            status,
+                            delay,
+                            error_retries,
+                            max_retries,
+                        )
+                        await asyncio.sleep(delay)
+                        continue
+
+                    raise TrelloError(
+                        f"API error {status}: {e.response.text}",
+                        status_code=status,
+                    ) from e
+
+            raise TrelloError(f"Max retries exceeded: {last_exception}") from last_exception
+
+        return wrapper
+
+    return decorator
+
+
+class TrelloClient:
+    """Async client for the Trello REST API, authenticated with an API key and a token."""
+
+    def __init__(
+        self,
+        api_key: str,
+        token: str,
+        base_url: str | None = None,
+        http_client: httpx.AsyncClient | None = None,
+    ):
+        self._client = http_client or httpx.AsyncClient(
+            base_url=(base_url or TRELLO_API_URL).rstrip("/"),
+            params={"key": api_key, "token": token},
+            timeout=httpx.Timeout(60.0, connect=10.0),
+        )
+        self._last_request = 0.0
+
+    async def close(self) -> None:
+        await self._client.aclose()
+
+    async def _throttle(self) -> None:
+        wait = self._last_request + MIN_REQUEST_INTERVAL_SECONDS - time.monotonic()
+        if wait > 0:
+            await asyncio.sleep(wait)
+        self._last_request = time.monotonic()
+
+    @with_retry(max_retries=3)
+    async def get(self, path: str, params: dict[str, Any] | None = None) -> Any:
+        await self._throttle()
+        response = await self._client.get(path, params=params)
+        response.raise_for_status()
+        return response.json()
+
+    async def get_me(self) -> dict[str, Any]:
+        return await self.get("/1/members/me", {"fields": "username,fullName"})
+
+    async def list_boards(self) -> list[dict[str, Any]]:
+        """The open boards the token's member belongs to."""
+        return await self.get("/1/members/me/boards", {"filter": "open", "fields": BOARD_FIELDS})
+
+    async def get_board(self, board_id: str) -> dict[str, Any]:
+        return await self.get(f"/1/boards/{board_id}", {"fields": BOARD_FIELDS})
+
+    async def list_members(self, board_id: str) -> list[dict[str, Any]]:
+        """A board's members, with their emails where Trello shares them."""
+        return await self.get(
+            f"/1/boards/{board_id}/members", {"fields": "fullName,username,email"}
+        )
+
+    async def list_lists(self, board_id: str) -> list[dict[str, Any]]:
+        return await self.get(
+            f"/1/boards/{board_id}/lists", {"filter": "all", "fields": "name,closed,pos"}
+        )
+
+    async def list_cards(self, board_id: str) -> AsyncIterator[list[dict[str, Any]]]:
+        """Pages of a board's open cards, newest first."""
+        params: dict[str, Any] = {"filter": "open", "fields": CARD_FIELDS, "limit": PAGE_SIZE}
+        while True:
+            cards = await self.get(f"/1/boards/{board_id}/cards", params)
+            if cards:
+                yield cards
+            if len(cards) < PAGE_SIZE:
+                return
+            params["before"] = min(card["id"] for card in cards)
+
+    async def get_card(self, card_id: str) -> dict[str, Any]:
+        return await self.get(f"/1/cards/{card_id}", {"fields": CARD_FIELDS})
+
+    async def list_actions(
+        self,
+        path: str,
+        since: str | None = None,
+        action_filter: str | None = None,
+    ) -> list[dict[str, Any]]:
+        """
+        Actions on a board or card, newest first.
+
+        Args:
+            path: The board or card, e.g. `/1/boards/{id}`
+            since: Only the actions after this action ID
+            action_filter: Comma-separated action types, all types when unset
+        """
+        params: dict[str, Any] = {"limit": PAGE_SIZE}
+        if since:
+            params["since"] = since
+        if action_filter:
+            params["filter"] = action_filter
+        actions: list[dict[str, Any]] = []
+        while True:
+            page = await self.get(f"{path}/actions", params)
+            actions.extend(page)
+            if len(page) < PAGE_SIZE:
+                return actions
+            params["before"] = page[-1]["id"]
+
+    async def get_last_action_id(self, board_id: str) -> str | None:
+        """ID of a board's newest action, where the next sync reads actions from."""
+        actions = await self.get(f"/1/boards/{board_id}/actions", {"limit": 1, "fields": "id"})
+        return actions[0]["id"] if actions else None
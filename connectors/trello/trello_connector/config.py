@@ -0,0 +1,48 @@
+"""Configuration constants for Trello connector."""
+
+TRELLO_API_URL = "https://api.trello.com"
+
+MAX_CONTENT_LENGTH = 500_000
+# Cards and actions per request, the most Trello returns
+PAGE_SIZE = 1000
+
+# Trello allows 100 requests per 10 seconds per token
+MIN_REQUEST_INTERVAL_SECONDS = 0.1
+
+BOARD_FIELDS = "name,desc,url,closed,idOrganization,idEnterprise,dateLastActivity,prefs"
+CARD_FIELDS = (
+    "name,desc,idBoard,idList,labels,closed,due,dueComplete,dateLastActivity,url,shortLink"
+)
+
+# Actions after which a board's cards are all synced again, as their list names, labels
+# or permissions may have changed
+BOARD_ACTIONS = frozenset(
+    {
+        "updateBoard",
+        "addMemberToBoard",
+        "removeMemberFromBoard",
+        "updateList",
+        "moveListToBoard",
+        "moveListFromBoard",
+        "updateLabel",
+        "deleteLabel",
+    }
+)
+# Actions after which a card is synced again
+CARD_ACTIONS = frozenset(
+    {
+        "createCard",
+        "copyCard",
+        "updateCard",
+        "moveCardToBoard",
+        "convertToCardFromCheckItem",
+        "emailCard",
+        "commentCard",
+        "updateComment",
+        "deleteComment",
+        "addLabelToCard",
+        "removeLabelFromCard",
+    }
+)
+# Actions after which a card is no longer on its board
+DELETE_ACTIONS = frozenset({"deleteCard", "moveCardFromBoard"})
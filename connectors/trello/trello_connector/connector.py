@@ -0,0 +1,285 @@
+"""Main TrelloConnector class."""
+
+import logging
+from typing import Any
+
+from omni_connector import Connector, DocumentPermissions, SyncContext
+
+from .client import AuthenticationError, NotFoundError, TrelloClient, TrelloError
+from .config import BOARD_ACTIONS, CARD_ACTIONS, DELETE_ACTIONS
+from .mappers import (
+    board_external_id,
+    build_permissions,
+    card_external_id,
+    generate_board_content,
+    generate_card_content,
+    map_board_to_document,
+    map_card_to_document,
+)
+
+logger = logging.getLogger(__name__)
+
+
+class TrelloConnector(Connector):
+    """Trello connector for Omni."""
+
+    @property
+    def name(self) -> str:
+        return "trello"
+
+    @property
+    def version(self) -> str:
+        return "1.0.0"
+
+    @property
+    def sync_modes(self) -> list[str]:
+        return ["full", "incremental"]
+
+    async def sync(
+        self,
+        source_config: dict[str, Any],
+        credentials: dict[str, Any],
+        state: dict[str, Any] | None,
+        ctx: SyncContext,
+    ) -> None:
+        """
+        Sync boards and their cards, then the actions on them.
+
+        A board is synced whole the first time: its lists, its open cards with their
+        comments, and its members for permissions. Later syncs read the board's actions
+        since the last one synced, and sync again the cards they changed, or the whole
+        board when its members, lists or labels changed.
+
+        Args:
+            source_config: May set api_url and board_ids, every open board of the token's
+                member is synced when board_ids is not set
+            credentials: 'api_key' and 'token' of a member of the boards
+            state: Previous sync state, {"boards": {board_id: {"last_action_id", "cards"}}}
+            ctx: Sync context with emit(), complete(), etc.
+        """
+        api_key = credentials.get("api_key")
+        token = credentials.get("token")
+        if not api_key or not token:
+            await ctx.fail("Missing 'api_key' or 'token' in credentials")
+            return
+
+        client = TrelloClient(
+            api_key=api_key, token=token, base_url=source_config.get("api_url")
+        )
+        try:
+            try:
+                member = await client.get_me()
+                logger.info("Starting Trello sync as '%s'", member.get("username"))
+            except AuthenticationError as e:
+                await ctx.fail(f"Authentication failed: {e}")
+                return
+            except TrelloError as e:
+                await ctx.fail(f"Connection test failed: {e}")
+                return
+
+            sync = _Sync(client, state or {}, source_config.get("board_ids") or [], ctx)
+            try:
+                await sync.sync_boards()
+
+                if ctx.is_cancelled():
+                    await ctx.fail("Cancelled by user")
+                    return
+                await ctx.complete(new_state=sync.state())
+                logger.info(
+                    "Sync completed: %d scanned, %d emitted",
+                    ctx.documents_scanned,
+                    ctx.documents_emitted,
+                )
+            except AuthenticationError as e:
+                logger.error("Authentication error during sync: %s", e)
+                await ctx.fail(f"Authentication failed: {e}")
+            except Exception as e:
+                logger.exception("Sync failed with unexpected error")
+                await ctx.fail(str(e))
+        finally:
+            await client.close()
+
+
+class _Sync:
+    """State of one sync run: the boards synced, with their newest action and cards."""
+
+    def __init__(
+        self,
+        client: TrelloClient,
+        previous: dict[str, Any],
+        board_ids: list[str],
+        ctx: SyncContext,
+    ):
+        self.client = client
+        self.ctx = ctx
+        self.board_ids = board_ids
+        self.boards: dict[str, dict[str, Any]] = dict(previous.get("boards", {}))
+
+    def state(self) -> dict[str, Any]:
+        return {"boards": self.boards}
+
+    async def sync_boards(self) -> None:
+        """Sync every board in scope, and delete the boards no longer in it."""
+        boards = await self._list_boards()
+        for board in boards:
+            if self.ctx.is_cancelled():
+                return
+            previous = self.boards.get(board["id"]) or {}
+            try:
+                if previous.get("last_action_id"):
+                    await self.sync_actions(board, previous)
+                else:
+                    await self.sync_board(board)
+            except AuthenticationError:
+                raise
+            except TrelloError as e:
+                logger.warning("Error syncing board %s: %s", board["id"], e)
+                await self.ctx.emit_error(board_external_id(board["id"]), str(e))
+                continue
+            if self.ctx.is_cancelled():
+                return
+            await self.ctx.save_state(self.state())
+
+        in_scope = {board["id"] for board in boards}
+        for board_id in [b for b in self.boards if b not in in_scope]:
+            await self._delete_board(board_id)
+
+    async def _list_boards(self) -> list[dict[str, Any]]:
+        if not self.board_ids:
+            return await self.client.list_boards()
+        boards = []
+        for board_id in self.board_ids:
+            try:
+                board = await self.client.get_board(board_id)
+            except NotFoundError as e:
+                logger.warning("Cannot read board %s: %s", board_id, e)
+                continue
+            if not board.get("closed"):
+                boards.append(board)
+        return boards
+
+    async def sync_board(self, board: dict[str, Any]) -> None:
+        """Sync a board, its open cards and their comments."""
+        board_id = board["id"]
+        # Taken first, so what changes during the sync is replayed by the next one
+        last_action_id = await self.client.get_last_action_id(board_id)
+        lists, permissions = await self._board_context(board)
+
+        await self.ctx.increment_scanned()
+        content = generate_board_content(board, list(lists.values()))
+        content_id = await self.ctx.content_storage.save(content, "text/plain")
+        await self.ctx.emit(map_board_to_document(board, content_id, permissions))
+
+        comments: dict[str, list[dict[str, Any]]] = {}
+        for action in await self.client.list_actions(
+            f"/1/boards/{board_id}", action_filter="commentCard"
+        ):
+            card_id = ((action.get("data") or {}).get("card") or {}).get("id")
+            if card_id:
+                comments.setdefault(card_id, []).append(action)
+
+        card_ids: set[str] = set()
+        async for cards in self.client.list_cards(board_id):
+            for card in cards:
+                if self.ctx.is_cancelled():
+                    return
+                card_ids.add(card["id"])
+                await self.sync_card(card, board, lists, comments.get(card["id"], []), permissions)
+
+        previous = self.boards.get(board_id) or {}
+        for card_id in sorted(set(previous.get("cards", [])) - card_ids):
+            await self.ctx.emit_deleted(card_external_id(card_id))
+        self.boards[board_id] = {"last_action_id": last_action_id, "cards": sorted(card_ids)}
+
+    async def sync_actions(self, board: dict[str, Any], previous: dict[str, Any]) -> None:
+        """Sync the cards of a board changed since its last synced action."""
+        board_id = board["id"]
+        actions = await self.client.list_actions(
+            f"/1/boards/{board_id}", since=previous["last_action_id"]
+        )
+        if not actions:
+            return
+        if any(action.get("type") in BOARD_ACTIONS for action in actions):
+            await self.sync_board(board)
+            return
+
+        # Only the last action on each card matters, as cards are read again as they are now
+        changes: dict[str, str] = {}
+        for action in reversed(actions):
+            action_type = action.get("type")
+            card_id = ((action.get("data") or {}).get("card") or {}).get("id")
+            if not card_id or not (action_type in CARD_ACTIONS or action_type in DELETE_ACTIONS):
+                continue
+            changes.pop(card_id, None)
+            changes[card_id] = action_type
+        logger.info("%d cards of board %s changed since the last sync", len(changes), board_id)
+
+        card_ids = set(previous.get("cards", []))
+        if changes:
+            await self._sync_changed_cards(board, changes, card_ids)
+            if self.ctx.is_cancelled():
+                return
+        self.boards[board_id] = {"last_action_id": actions[0]["id"], "cards": sorted(card_ids)}
+
+    async def _sync_changed_cards(
+        self, board: dict[str, Any], changes: dict[str, str], card_ids: set[str]
+    ) -> None:
+        """Sync the changed cards still open on a board, delete the others."""
+        lists, permissions = await self._board_context(board)
+        for card_id, action_type in changes.items():
+            if self.ctx.is_cancelled():
+                return
+            card = None
+            if action_type not in DELETE_ACTIONS:
+                try:
+                    card = await self.client.get_card(card_id)
+                except NotFoundError:
+                    pass
+            if not card or card.get("closed") or card.get("idBoard") != board["id"]:
+                if card_id in card_ids:
+                    card_ids.discard(card_id)
+                    await self.ctx.emit_deleted(card_external_id(card_id))
+                continue
+            comments = await self.client.list_actions(
+                f"/1/cards/{card_id}", action_filter="commentCard"
+            )
+            await self.sync_card(card, board, lists, comments, permissions)
+            card_ids.add(card_id)
+
+    async def _board_context(
+        self, board: dict[str, Any]
+    ) -> tuple[dict[str, dict[str, Any]], DocumentPermissions]:
+        """A board's lists by ID, and who can see it."""
+        lists = {lst["id"]: lst for lst in await self.client.list_lists(board["id"])}
+        members = await self.client.list_members(board["id"])
+        return lists, build_permissions(board, members)
+
+    async def sync_card(
+        self,
+        card: dict[str, Any],
+        board: dict[str, Any],
+        lists: dict[str, dict[str, Any]],
+        comments: list[dict[str, Any]],
+        permissions: DocumentPermissions,
+    ) -> None:
+        await self.ctx.increment_scanned()
+        try:
+            list_name = (lists.get(card.get("idList") or "") or {}).get("name")
+            content = generate_card_content(card, board, list_name, comments)
+            content_id = await self.ctx.content_storage.save(content, "text/plain")
+            await self.ctx.emit(
+                map_card_to_document(card, board, list_name, content_id, permissions)
+            )
+        except AuthenticationError:
+            raise
+        except Exception as e:
+            eid = card_external_id(card["id"])
+            logger.warning("Error processing %s: %s", eid, e)
+            await self.ctx.emit_error(eid, str(e))
+
+    async def _delete_board(self, board_id: str) -> None:
+        """Delete a board no longer in scope, and its cards."""
+        board = self.boards.pop(board_id)
+        for card_id in board.get("cards", []):
+            await self.ctx.emit_deleted(card_external_id(card_id))
+        await self.ctx.emit_deleted(board_external_id(board_id))
@@ -0,0 +1,196 @@
+"""Board- and card-to-Document mapping functions for Trello."""
+
+from datetime import datetime, timezone
+from typing import Any
+
+from omni_connector import Document, DocumentMetadata, DocumentPermissions
+
+from .config import MAX_CONTENT_LENGTH
+
+
+def board_external_id(board_id: str) -> str:
+    return f"trello:board:{board_id}"
+
+
+def card_external_id(card_id: str) -> str:
+    return f"trello:card:{card_id}"
+
+
+def parse_timestamp(value: str | None) -> datetime | None:
+    """Parse a Trello ISO 8601 timestamp."""
+    if not value:
+        return None
+    try:
+        return datetime.fromisoformat(value.replace("Z", "+00:00"))
+    except ValueError:
+        return None
+
+
+def created_at(object_id: str) -> datetime | None:
+    """When a board or card was created, which Trello encodes in the start of its ID."""
+    try:
+        return datetime.fromtimestamp(int(object_id[:8], 16), tz=timezone.utc)
+    except ValueError:
+        return None
+
+
+def label_names(card: dict[str, Any]) -> list[str]:
+    """Names of a card's labels, their colors for those without a name."""
+    names = [label.get("name") or label.get("color") for label in card.get("labels") or []]
+    return [name for name in names if name]
+
+
+def build_permissions(
+    board: dict[str, Any], members: list[dict[str, Any]]
+) -> DocumentPermissions:
+    """
+    Who can see a board and its cards.
+
+    Public boards are public. Other boards are shared with the board's group and the
+    members whose emails Trello shares, and with the Workspace or Enterprise group when
+    visible to them.
+    """
+    prefs = board.get("prefs") or {}
+    level = prefs.get("permissionLevel")
+    if level == "public":
+        return DocumentPermissions(public=True)
+
+    groups = [board_external_id(board["id"])]
+    if level == "org" and board.get("idOrganization"):
+        groups.append(f"trello:workspace:{board['idOrganization']}")
+    elif level == "enterprise" and board.get("idEnterprise"):
+        groups.append(f"trello:enterprise:{board['idEnterprise']}")
+    emails = {member["email"].lower() for member in members if member.get("email")}
+    return DocumentPermissions(public=False, users=sorted(emails), groups=groups)
+
+
+def generate_board_content(board: dict[str, Any], lists: list[dict[str, Any]]) -> str:
+    """Searchable text of a board: its description and open lists."""
+    lines = [board.get("name") or "Untitled board"]
+    if desc := (board.get("desc") or "").strip():
+        lines.extend(["", desc])
+    open_lists = sorted(
+        (lst for lst in lists if not lst.get("closed")), key=lambda lst: lst.get("pos") or 0
+    )
+    if open_lists:
+        lines.extend(["", "--- Lists ---"])
+        lines.extend(f"- {lst.get('name', '')}" for lst in open_lists)
+    return "\n".join(lines)[:MAX_CONTENT_LENGTH]
+
+
+def generate_card_content(
+    card: dict[str, Any],
+    board: dict[str, Any],
+    list_name: str | None,
+    comments: list[dict[str, Any]],
+) -> str:
+    """Searchable text of a card: its description and comments, oldest first."""
+    lines = [card.get("name") or "Untitled card"]
+    for label, value in (
+        ("Board", board.get("name")),
+        ("List", list_name),
+        ("Labels", ", ".join(label_names(card))),
+        ("Due", card.get("due")),
+    ):
+        if value:
+            lines.append(f"{label}: {value}")
+
+    if desc := (card.get("desc") or "").strip():
+        lines.extend(["", desc])
+    if comments:
+        lines.extend(["", "--- Comments ---"])
+        for comment in sorted(comments, key=lambda c: c.get("date") or ""):
+            author = (comment.get("memberCreator") or {}).get("fullName") or "Unknown"
+            text = (comment.get("data") or {}).get("text", "")
+            lines.append(f"{author}: {text}")
+    return "\n".join(lines)[:MAX_CONTENT_LENGTH]
+
+
+def map_board_to_document(
+    board: dict[str, Any],
+    content_id: str,
+    permissions: DocumentPermissions,
+) -> Document:
+    """
+    Map a Trello board to an Omni Document.
+
+    Args:
+        board: The board, with its description and visibility
+        content_id: ID from content storage, for its description and lists
+        permissions: Who can see the board
+
+    Returns:
+        Omni Document instance
+    """
+    return Document(
+        external_id=board_external_id(board["id"]),
+        title=board.get("name") or "Untitled board",
+        content_id=content_id,
+        metadata=DocumentMetadata(
+            created_at=created_at(board["id"]),
+            updated_at=parse_timestamp(board.get("dateLastActivity")),
+            url=board.get("url"),
+            mime_type="text/plain",
+            extra={
+                "board_id": board["id"],
+                "workspace_id": board.get("idOrganization"),
+            },
+        ),
+        permissions=permissions,
+        attributes={
+            "source_type": "trello",
+            "content_type": "board",
+            "board": board.get("name"),
+        },
+    )
+
+
+def map_card_to_document(
+    card: dict[str, Any],
+    board: dict[str, Any],
+    list_name: str | None,
+    content_id: str,
+    permissions: DocumentPermissions,
+) -> Document:
+    """
+    Map a Trello card to an Omni Document.
+
+    Args:
+        card: The card, with its labels
+        board: The board the card is on
+        list_name: Name of the card's list
+        content_id: ID from content storage, for its description and comments
+        permissions: Who can see the card's board
+
+    Returns:
+        Omni Document instance
+    """
+    path = f"{board.get('name', '')}/{list_name}" if list_name else board.get("name")
+    due = parse_timestamp(card.get("due"))
+    return Document(
+        external_id=card_external_id(card["id"]),
+        title=card.get("name") or "Untitled card",
+        content_id=content_id,
+        metadata=DocumentMetadata(
+            created_at=created_at(card["id"]),
+            updated_at=parse_timestamp(card.get("dateLastActivity")),
+            url=card.get("url"),
+            mime_type="text/plain",
+            path=path,
+            extra={
+                "card_id": card["id"],
+                "board_id": board["id"],
+                "list_id": card.get("idList"),
+            },
+        ),
+        permissions=permissions,
+        attributes={
+            "source_type": "trello",
+            "content_type": "card",
+            "board": board.get("name"),
+            "list": list_name,
+            "labels": ",".join(label_names(card)),
+            "due": due.date().isoformat() if due else None,
+            "due_complete": bool(card.get("dueComplete")),
+        },
+    )
@@ -7,11 +7,11 @@ use axum::{
     Router,
 };
 use serde_json::json;
-use shared::telemetry;
+use shared::{error_reporting, telemetry};
 use std::sync::Arc;
 use tower::ServiceBuilder;
 use tower_http::cors::CorsLayer;
-use tracing::{error, info};
+use tracing::{error, info, Instrument};
 
 use crate::models::{
     ActionRequest, ActionResponse, CancelRequest, CancelResponse, ConnectorManifest, SyncRequest,
@@ -67,11 +67,15 @@ async fn trigger_sync(
 
     let sync_manager = state.sync_manager.clone();
 
-    tokio::spawn(async move {
-        if let Err(e) = sync_manager.sync_source(request).await {
-            error!("Sync {} failed: {}", sync_run_id, e);
+    let span = error_reporting::sync_span(&source_id, request.source_type, &sync_run_id);
+    tokio::spawn(
+        async move {
+            if let Err(e) = sync_manager.sync_source(request).await {
+                error!("Sync {} failed: {}", sync_run_id, e);
+            }
         }
-    });
+        .instrument(span),
+    );
 
     Ok(Json(SyncResponse::started()))
 }
@@ -2,6 +2,7 @@ use anyhow::{Context, Result};
 use dotenvy::dotenv;
 use omni_web_connector::api::{create_router, ApiState};
 use omni_web_connector::sync::SyncManager;
+use shared::shutdown::Shutdown;
 use shared::telemetry::{self, TelemetryConfig};
 use shared::SdkClient;
 use std::sync::Arc;
@@ -26,7 +27,7 @@ async fn main() -> Result<()> {
 
     let sdk_client = SdkClient::from_env()?;
 
-    let sync_manager = Arc::new(SyncManager::new(redis_client, sdk_client));
+    let sync_manager = Arc::new(SyncManager::new(redis_client, sdk_client.clone()));
 
     let api_state = ApiState {
         sync_manager: Arc::clone(&sync_manager),
@@ -41,7 +42,10 @@ async fn main() -> Result<()> {
 
     info!("HTTP server listening on {}", addr);
 
-    axum::serve(listener, app).await?;
+    shared::tls::serve(listener, app, Shutdown::on_signal()).await?;
+
+    // Syncs cut short by the shutdown are resumed once the connector is back
+    sdk_client.pause_active_syncs().await;
 
     Ok(())
 }
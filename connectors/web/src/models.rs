@@ -6,11 +6,12 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use sha2::{Digest, Sha256};
 use shared::models::{ConnectorEvent, DocumentMetadata, DocumentPermissions};
+use shared::utils::to_offset_datetime;
 use spider::page::Page;
 use std::collections::HashMap;
 
-// Import SyncRequest and SyncResponse from shared crate
-pub use shared::models::{SyncRequest, SyncResponse};
+// Import ActionRequest, SyncRequest and SyncResponse from shared crate
+pub use shared::models::{ActionRequest, SyncRequest, SyncResponse};
 
 // ============================================================================
 // Connector Protocol Models
@@ -64,13 +65,6 @@ pub struct CancelResponse {
     pub status: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ActionRequest {
-    pub action: String,
-    pub params: JsonValue,
-    pub credentials: JsonValue,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActionResponse {
     pub status: String,
@@ -266,16 +260,14 @@ impl WebPage {
             title: self.title.clone(),
             author: None,
             created_at: None,
-            updated_at: updated_at
-                .map(|dt| {
-                    sqlx::types::time::OffsetDateTime::from_unix_timestamp(dt.timestamp()).ok()
-                })
-                .flatten(),
+            updated_at: updated_at.and_then(|dt| to_offset_datetime(&dt)),
             mime_type: Some("text/html".to_string()),
             size: Some(self.content.len().to_string()),
             url: Some(self.url.clone()),
             path: Some(Self::extract_path_from_url(&self.url)),
             extra: Some(extra),
+            thumbnail: None,
+            parent: None,
         };
 
         let permissions = DocumentPermissions {
@@ -2,6 +2,7 @@ use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use dashmap::DashMap;
 use redis::Client as RedisClient;
+use shared::cache::{keys, ttl};
 use shared::SdkClient;
 use spider::client::StatusCode;
 use std::collections::HashSet;
@@ -92,13 +93,46 @@ impl SyncState {
         Self { redis_client }
     }
 
+    /// Move the source's crawl state written before keys were prefixed to its keys, so the
+    /// first crawl after an upgrade neither reindexes every page nor misses the pages removed
+    /// since the last one.
+    pub async fn migrate_legacy_keys(&self, source_id: &str) -> Result<()> {
+        use redis::AsyncCommands;
+        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
+
+        let legacy_urls_key = format!("web:urls:{}", source_id);
+        let url_hashes: Vec<String> = conn.smembers(&legacy_urls_key).await?;
+        if !url_hashes.is_empty() {
+            let key = self.get_urls_set_key(source_id);
+            let _: () = conn.sadd(&key, &url_hashes).await?;
+            let _: () = conn.expire(&key, ttl::CRAWL_STATE as i64).await?;
+            let _: () = conn.del(&legacy_urls_key).await?;
+        }
+
+        let moved = shared::cache::migrate_legacy_source_keys(
+            &mut conn,
+            source_id,
+            &[("web:sync:", "web:page:")],
+        )
+        .await?;
+        if moved > 0 {
+            info!(
+                "Migrated {} legacy crawl state keys of source {}",
+                moved, source_id
+            );
+        }
+        Ok(())
+    }
+
     fn get_url_sync_key(&self, source_id: &str, url: &str) -> String {
         let url_hash = format!("{:x}", md5::compute(url));
-        format!("web:sync:{}:{}", source_id, url_hash)
+        keys()
+            .source(source_id)
+            .key(format_args!("web:page:{}", url_hash))
     }
 
     fn get_urls_set_key(&self, source_id: &str) -> String {
-        format!("web:urls:{}", source_id)
+        keys().source(source_id).key("web:urls")
     }
 
     pub async fn get_page_sync_state(
@@ -132,7 +166,7 @@ impl SyncState {
         let key = self.get_url_sync_key(source_id, url);
         let json_str = serde_json::to_string(state)?;
 
-        let _: () = conn.set_ex(&key, json_str, 90 * 24 * 60 * 60).await?;
+        let _: () = conn.set_ex(&key, json_str, ttl::CRAWL_STATE).await?;
         Ok(())
     }
 
@@ -143,7 +177,7 @@ impl SyncState {
         let url_hash = format!("{:x}", md5::compute(url));
 
         let _: () = conn.sadd(&key, url_hash).await?;
-        let _: () = conn.expire(&key, 90 * 24 * 60 * 60).await?;
+        let _: () = conn.expire(&key, ttl::CRAWL_STATE as i64).await?;
         Ok(())
     }
 
@@ -209,6 +243,7 @@ impl SyncManager {
             "Starting sync for source: {} (sync_run_id: {})",
             source_id, sync_run_id
         );
+        self.sdk_client.start_sync(&request);
 
         // Fetch source via SDK
         let source = self
@@ -226,6 +261,7 @@ impl SyncManager {
             .context("Failed to parse web source config")?;
 
         let sync_state = SyncState::new(self.redis_client.clone());
+        sync_state.migrate_legacy_keys(source_id).await?;
         let previous_urls = sync_state.get_all_synced_urls(source_id).await?;
         let current_urls: Arc<Mutex<HashSet<String>>> = Arc::new(Mutex::new(HashSet::new()));
 
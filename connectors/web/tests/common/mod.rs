@@ -35,6 +35,9 @@ impl WebConnectorTestFixture {
                 max_connections: 5,
                 acquire_timeout_seconds: 3,
                 require_ssl: false,
+                statement_timeout_ms: None,
+                slow_query_threshold_ms: 1000,
+                application_name: None,
             },
             redis: RedisConfig {
                 redis_url: "redis://localhost".to_string(),
@@ -45,6 +48,15 @@ impl WebConnectorTestFixture {
             max_concurrent_syncs_per_type: 3,
             scheduler_interval_seconds: 30,
             stale_sync_timeout_minutes: 10,
+            retention_rules: HashMap::new(),
+            retention_interval_seconds: 3600,
+            scim_bearer_token: None,
+            sync_run_retention_days: 90,
+            metadata_export_formats: Vec::new(),
+            metadata_export_interval_seconds: 86400,
+            webhook_delivery_interval_seconds: 0,
+            smtp: None,
+            sdk_tokens_required: false,
         };
 
         // Create connector-manager sync manager
@@ -171,8 +183,10 @@ impl WebConnectorTestFixture {
         SyncRequest {
             sync_run_id: sync_run_id.to_string(),
             source_id: source_id.to_string(),
+            source_type: None,
             sync_mode: "full".to_string(),
             last_sync_at: None,
+            sdk_token: None,
         }
     }
 
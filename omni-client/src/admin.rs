@@ -0,0 +1,143 @@
+use crate::{
+    EmbeddingMigration, EmbeddingMigrationStatus, EmbeddingModelConfig, GcResult, IndexSnapshot,
+    OmniClient, OrphanStats, Result,
+};
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize)]
+struct ReembedResponse {
+    queued: u64,
+}
+
+impl OmniClient {
+    /// Queue documents for re-embedding, all of them or those of one source. Returns how many
+    /// were queued.
+    pub async fn reembed(&self, source_id: Option<&str>) -> Result<u64> {
+        let response: ReembedResponse = self
+            .send_json(
+                &self.indexer,
+                Method::POST,
+                "/admin/reembed",
+                Some(&json!({ "source_id": source_id })),
+            )
+            .await?;
+        Ok(response.queued)
+    }
+
+    /// Delete content blobs no document references any more.
+    pub async fn run_gc(&self) -> Result<GcResult> {
+        self.send_json::<(), _>(&self.indexer, Method::POST, "/admin/gc/run", None)
+            .await
+    }
+
+    pub async fn gc_stats(&self) -> Result<OrphanStats> {
+        self.get(&self.indexer, "/admin/gc/stats").await
+    }
+
+    /// Start a snapshot of the index to object storage, of one source or of everything.
+    pub async fn create_snapshot(&self, source_id: Option<&str>) -> Result<IndexSnapshot> {
+        self.send_json(
+            &self.indexer,
+            Method::POST,
+            "/admin/snapshots",
+            Some(&json!({ "source_id": source_id })),
+        )
+        .await
+    }
+
+    /// Snapshots and restores, newest first.
+    pub async fn list_snapshots(&self) -> Result<Vec<IndexSnapshot>> {
+        self.get(&self.indexer, "/admin/snapshots").await
+    }
+
+    pub async fn get_snapshot(&self, id: &str) -> Result<IndexSnapshot> {
+        self.get(&self.indexer, &format!("/admin/snapshots/{}", id))
+            .await
+    }
+
+    /// Start restoring a snapshot, of one source or of everything in it. `created_by` owns
+    /// restored sources whose owner does not exist in this deployment.
+    pub async fn restore_snapshot(
+        &self,
+        snapshot_id: &str,
+        source_id: Option<&str>,
+        created_by: Option<&str>,
+    ) -> Result<IndexSnapshot> {
+        self.send_json(
+            &self.indexer,
+            Method::POST,
+            &format!("/admin/snapshots/{}/restore", snapshot_id),
+            Some(&json!({ "source_id": source_id, "created_by": created_by })),
+        )
+        .await
+    }
+
+    pub async fn embedding_migration(&self) -> Result<EmbeddingMigrationStatus> {
+        self.get(&self.indexer, "/admin/embedding-migration").await
+    }
+
+    /// Start migrating to another embedding model. Documents are embedded with both models
+    /// from now on, and `shadow_search_percentage` percent of semantic searches also run
+    /// against the target model for comparison, 10 when not given.
+    pub async fn start_embedding_migration(
+        &self,
+        target: &EmbeddingModelConfig,
+        shadow_search_percentage: Option<f64>,
+    ) -> Result<EmbeddingMigration> {
+        self.send_json(
+            &self.indexer,
+            Method::POST,
+            "/admin/embedding-migration",
+            Some(&json!({
+                "target": target,
+                "shadow_search_percentage": shadow_search_percentage,
+            })),
+        )
+        .await
+    }
+
+    /// Change the shadow search percentage, or cut searches over to the target model (or
+    /// back).
+    pub async fn update_embedding_migration(
+        &self,
+        shadow_search_percentage: Option<f64>,
+        cutover: Option<bool>,
+    ) -> Result<EmbeddingMigration> {
+        self.send_json(
+            &self.indexer,
+            Method::PATCH,
+            "/admin/embedding-migration",
+            Some(&json!({
+                "shadow_search_percentage": shadow_search_percentage,
+                "cutover": cutover,
+            })),
+        )
+        .await
+    }
+
+    /// Make the target model of a cut-over migration the current model. The previous
+    /// model's embeddings are deleted.
+    pub async fn finalize_embedding_migration(&self) -> Result<EmbeddingMigration> {
+        self.send_json::<(), _>(
+            &self.indexer,
+            Method::POST,
+            "/admin/embedding-migration/finalize",
+            None,
+        )
+        .await
+    }
+
+    /// Abandon a migration that has not been cut over. The target model's embeddings are
+    /// deleted.
+    pub async fn cancel_embedding_migration(&self) -> Result<EmbeddingMigration> {
+        self.send_json::<(), _>(
+            &self.indexer,
+            Method::DELETE,
+            "/admin/embedding-migration",
+            None,
+        )
+        .await
+    }
+}
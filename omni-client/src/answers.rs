@@ -0,0 +1,74 @@
+//! AI answers over search results. These are single-turn: the searcher answers one question
+//! from the documents the search finds, and multi-turn chats are kept by the web app.
+
+use crate::{OmniClient, Result, SearchRequest};
+use futures_util::stream::{Stream, StreamExt};
+use reqwest::Method;
+
+impl OmniClient {
+    /// Answer `request.query` from the documents a hybrid search finds, with citations.
+    pub async fn answer(&self, request: &SearchRequest) -> Result<String> {
+        let request = self
+            .request(&self.searcher, Method::POST, "/search/ai-answer")?
+            .json(request);
+        let response = self.send(&self.searcher, request).await?;
+        Ok(response.text().await?)
+    }
+
+    /// Like [`OmniClient::answer`], but yields the answer as it is generated.
+    pub async fn answer_stream(
+        &self,
+        request: &SearchRequest,
+    ) -> Result<impl Stream<Item = Result<String>>> {
+        let request = self
+            .request(&self.searcher, Method::POST, "/search/ai-answer")?
+            .json(request);
+        let response = self.send(&self.searcher, request).await?;
+
+        // Chunks can end in the middle of a multi-byte character, so hold back the incomplete
+        // tail until the next chunk arrives
+        let chunks = response.bytes_stream().scan(Vec::new(), |pending, chunk| {
+            let item = chunk.map_err(Into::into).map(|bytes| {
+                pending.extend_from_slice(&bytes);
+                take_utf8_prefix(pending)
+            });
+            futures_util::future::ready(Some(item))
+        });
+        Ok(chunks.filter(|item| {
+            futures_util::future::ready(!matches!(item, Ok(text) if text.is_empty()))
+        }))
+    }
+}
+
+/// Remove and return the longest prefix of `buffer` that is valid UTF-8, leaving an incomplete
+/// trailing character in the buffer. Invalid sequences are replaced.
+fn take_utf8_prefix(buffer: &mut Vec<u8>) -> String {
+    let valid_up_to = match std::str::from_utf8(buffer) {
+        Ok(_) => buffer.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => {
+            let text = String::from_utf8_lossy(buffer).into_owned();
+            buffer.clear();
+            return text;
+        }
+    };
+    let rest = buffer.split_off(valid_up_to);
+    String::from_utf8(std::mem::replace(buffer, rest)).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_utf8_prefix() {
+        let bytes = "héllo".as_bytes();
+        let mut buffer = bytes[..2].to_vec();
+        assert_eq!(take_utf8_prefix(&mut buffer), "h");
+        assert_eq!(buffer, vec![0xC3]);
+
+        buffer.extend_from_slice(&bytes[2..]);
+        assert_eq!(take_utf8_prefix(&mut buffer), "éllo");
+        assert!(buffer.is_empty());
+    }
+}
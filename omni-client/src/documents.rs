@@ -0,0 +1,50 @@
+use crate::{
+    encode, BulkDocumentOperation, BulkDocumentResponse, CreateDocumentRequest, Document,
+    OmniClient, Result, UpdateDocumentRequest,
+};
+use reqwest::Method;
+use serde_json::{json, Value};
+
+impl OmniClient {
+    pub async fn get_document(&self, id: &str) -> Result<Document> {
+        self.get(&self.indexer, &format!("/documents/{}", encode(id)))
+            .await
+    }
+
+    pub async fn create_document(&self, request: &CreateDocumentRequest) -> Result<Document> {
+        self.send_json(&self.indexer, Method::POST, "/documents", Some(request))
+            .await
+    }
+
+    pub async fn update_document(
+        &self,
+        id: &str,
+        request: &UpdateDocumentRequest,
+    ) -> Result<Document> {
+        let path = format!("/documents/{}", encode(id));
+        self.send_json(&self.indexer, Method::PUT, &path, Some(request))
+            .await
+    }
+
+    pub async fn delete_document(&self, id: &str) -> Result<()> {
+        let path = format!("/documents/{}", encode(id));
+        self.send_json::<(), Value>(&self.indexer, Method::DELETE, &path, None)
+            .await?;
+        Ok(())
+    }
+
+    /// Apply several document operations in one request. Failed operations are reported in
+    /// the response rather than failing the whole request.
+    pub async fn bulk_documents(
+        &self,
+        operations: &[BulkDocumentOperation],
+    ) -> Result<BulkDocumentResponse> {
+        self.send_json(
+            &self.indexer,
+            Method::POST,
+            "/documents/bulk",
+            Some(&json!({ "operations": operations })),
+        )
+        .await
+    }
+}
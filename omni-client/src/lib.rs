@@ -0,0 +1,301 @@
+//! Typed client for the Omni service APIs.
+//!
+//! [`OmniClient`] wraps the searcher (search, suggestions and AI answers), the indexer
+//! (documents) and the connector manager (sources and syncs) behind one set of typed methods,
+//! so tools talking to a deployment don't each carry their own request and response structs.
+//!
+//! ```no_run
+//! # async fn example() -> omni_client::Result<()> {
+//! use omni_client::{OmniClient, SearchMode, SearchRequest};
+//!
+//! let client = OmniClient::builder()
+//!     .searcher_url("http://localhost:3001")
+//!     .build()?;
+//! let response = client
+//!     .search(&SearchRequest::new("quarterly roadmap").with_mode(SearchMode::Hybrid))
+//!     .await?;
+//! for result in response.results {
+//!     println!("{}", result.document.title);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Services that run with mutual TLS expect a client certificate signed by the service CA,
+//! configured with [`OmniClientBuilder::identity`] and [`OmniClientBuilder::ca_certificate`].
+
+mod admin;
+mod answers;
+mod documents;
+pub mod models;
+mod search;
+mod sources;
+
+pub use models::*;
+
+use reqwest::{header, Certificate, Identity, Method, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::time::Duration;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("{service} returned {status}: {message}")]
+    Api {
+        service: &'static str,
+        status: u16,
+        message: String,
+    },
+    #[error("Invalid response: {0}")]
+    Decode(#[from] serde_json::Error),
+    #[error("Invalid client configuration: {0}")]
+    Config(String),
+}
+
+impl Error {
+    /// HTTP status of an API error, `None` for transport and decoding errors.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Error::Api { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+}
+
+/// One of the services the client talks to.
+#[derive(Debug, Clone)]
+struct Service {
+    name: &'static str,
+    base_url: Option<String>,
+}
+
+impl Service {
+    fn new(name: &'static str, base_url: Option<String>) -> Self {
+        Self {
+            name,
+            base_url: base_url.map(|url| url.trim_end_matches('/').to_string()),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct OmniClientBuilder {
+    searcher_url: Option<String>,
+    indexer_url: Option<String>,
+    connector_manager_url: Option<String>,
+    bearer_token: Option<String>,
+    identity_pem: Option<Vec<u8>>,
+    ca_pem: Option<Vec<u8>>,
+    timeout: Option<Duration>,
+    http_client: Option<reqwest::Client>,
+}
+
+impl OmniClientBuilder {
+    pub fn searcher_url(mut self, url: impl Into<String>) -> Self {
+        self.searcher_url = Some(url.into());
+        self
+    }
+
+    pub fn indexer_url(mut self, url: impl Into<String>) -> Self {
+        self.indexer_url = Some(url.into());
+        self
+    }
+
+    pub fn connector_manager_url(mut self, url: impl Into<String>) -> Self {
+        self.connector_manager_url = Some(url.into());
+        self
+    }
+
+    /// Send `Authorization: Bearer <token>` with every request, for deployments that put the
+    /// service APIs behind an authenticating proxy.
+    pub fn bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    /// Client certificate and private key, PEM encoded, presented to services that require
+    /// mutual TLS.
+    pub fn identity(mut self, cert_pem: &[u8], key_pem: &[u8]) -> Self {
+        let mut identity = cert_pem.to_vec();
+        identity.push(b'\n');
+        identity.extend_from_slice(key_pem);
+        self.identity_pem = Some(identity);
+        self
+    }
+
+    /// CA certificate, PEM encoded, that service certificates are signed with.
+    pub fn ca_certificate(mut self, ca_pem: &[u8]) -> Self {
+        self.ca_pem = Some(ca_pem.to_vec());
+        self
+    }
+
+    /// Request timeout, 30 seconds by default.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Send requests with an existing HTTP client, e.g. one set up for the services' mutual
+    /// TLS. The client's own settings then replace the token, TLS and timeout options.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    pub fn build(self) -> Result<OmniClient> {
+        let http = match &self.http_client {
+            Some(client) => client.clone(),
+            None => self.new_http_client()?,
+        };
+
+        Ok(OmniClient {
+            http,
+            searcher: Service::new("searcher", self.searcher_url),
+            indexer: Service::new("indexer", self.indexer_url),
+            connector_manager: Service::new("connector-manager", self.connector_manager_url),
+        })
+    }
+
+    fn new_http_client(&self) -> Result<reqwest::Client> {
+        let mut builder =
+            reqwest::Client::builder().timeout(self.timeout.unwrap_or(DEFAULT_TIMEOUT));
+
+        if let Some(token) = &self.bearer_token {
+            let mut value = header::HeaderValue::from_str(&format!("Bearer {}", token))
+                .map_err(|_| Error::Config("Bearer token is not a valid header value".into()))?;
+            value.set_sensitive(true);
+            let mut headers = header::HeaderMap::new();
+            headers.insert(header::AUTHORIZATION, value);
+            builder = builder.default_headers(headers);
+        }
+        if self.identity_pem.is_some() || self.ca_pem.is_some() {
+            builder = builder.use_rustls_tls();
+        }
+        if let Some(identity) = &self.identity_pem {
+            builder = builder.identity(Identity::from_pem(identity)?);
+        }
+        if let Some(ca) = &self.ca_pem {
+            builder = builder.add_root_certificate(Certificate::from_pem(ca)?);
+        }
+
+        Ok(builder.build()?)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OmniClient {
+    http: reqwest::Client,
+    searcher: Service,
+    indexer: Service,
+    connector_manager: Service,
+}
+
+impl OmniClient {
+    pub fn builder() -> OmniClientBuilder {
+        OmniClientBuilder::default()
+    }
+
+    fn request(&self, service: &Service, method: Method, path: &str) -> Result<RequestBuilder> {
+        let base_url = service
+            .base_url
+            .as_deref()
+            .ok_or_else(|| Error::Config(format!("No URL configured for the {}", service.name)))?;
+        Ok(self.http.request(method, format!("{}{}", base_url, path)))
+    }
+
+    /// Send a request and return the response if it succeeded, or the service's error
+    /// message otherwise.
+    async fn send(&self, service: &Service, request: RequestBuilder) -> Result<Response> {
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        let message = serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|v| v["error"].as_str().map(String::from))
+            .unwrap_or(body);
+        Err(Error::Api {
+            service: service.name,
+            status: status.as_u16(),
+            message,
+        })
+    }
+
+    async fn get<T: DeserializeOwned>(&self, service: &Service, path: &str) -> Result<T> {
+        let request = self.request(service, Method::GET, path)?;
+        let response = self.send(service, request).await?;
+        Ok(serde_json::from_slice(&response.bytes().await?)?)
+    }
+
+    async fn send_json<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        service: &Service,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<T> {
+        let mut request = self.request(service, method, path)?;
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        let response = self.send(service, request).await?;
+        Ok(serde_json::from_slice(&response.bytes().await?)?)
+    }
+
+    /// Whether the searcher answers its health check.
+    pub async fn health_check(&self) -> Result<bool> {
+        let request = self.request(&self.searcher, Method::GET, "/health")?;
+        Ok(request
+            .send()
+            .await
+            .map(|r| r.status().is_success())
+            .unwrap_or(false))
+    }
+}
+
+/// Percent-encode a value for use in a URL path segment or query string.
+fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode("q4 planning"), "q4%20planning");
+        assert_eq!(encode("a@b.com"), "a%40b.com");
+        assert_eq!(encode("ünïcode/&"), "%C3%BCn%C3%AFcode%2F%26");
+    }
+
+    #[test]
+    fn test_missing_service_url() {
+        let client = OmniClient::builder().build().unwrap();
+        let err = client
+            .request(&client.indexer, Method::GET, "/documents/1")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid client configuration: No URL configured for the indexer"
+        );
+    }
+}
@@ -0,0 +1,546 @@
+//! Request and response types of the Omni service APIs.
+//!
+//! Timestamps are kept as the ISO 8601 strings the services send, and JSON documents such as
+//! metadata and permissions as [`serde_json::Value`], so the client does not need to track
+//! every schema change in the services.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMode {
+    Fulltext,
+    Semantic,
+    Hybrid,
+}
+
+/// Document fields a full-text search can be restricted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchField {
+    Title,
+    /// Author and path
+    Metadata,
+    Content,
+}
+
+/// Relaxation that found the results of a search the query as given found nothing for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FallbackStrategy {
+    /// Source, content type and attribute filters dropped
+    DropFilters,
+    /// Matched in every field
+    AnyField,
+    /// Terms within two edits of the query's matched
+    SpellCorrected,
+    /// Semantic search only
+    SemanticOnly,
+}
+
+/// How attachments, comments and transcript segments are returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChildResults {
+    /// As results of their own
+    Flat,
+    /// Under their parent's result when the parent matched too
+    Nested,
+    /// Under their parent's result, brought in when it did not match
+    RolledUp,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchRequest {
+    pub query: String,
+    /// Source types to search, e.g. `google_drive` or `slack`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_types: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_types: Option<Vec<String>>,
+    /// Attribute filters, e.g. `{"status": "Done"}` or `{"date": {"gte": "2024-01-01"}}`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attribute_filters: Option<HashMap<String, Value>>,
+    /// Fields to match, title and content by default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<Vec<SearchField>>,
+    /// Only search this collection, by ID or name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<SearchMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_facets: Option<bool>,
+    /// Only return documents this user may see
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    /// Return how each result's score was computed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explain: Option<bool>,
+    /// Retry with relaxed strategies when nothing is found, on by default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<bool>,
+    /// How attachments, comments and transcript segments are returned, flat by default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub child_results: Option<ChildResults>,
+}
+
+impl SearchRequest {
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_mode(mut self, mode: SearchMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    pub fn with_limit(mut self, limit: i64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn with_offset(mut self, offset: i64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    pub fn with_source_types(mut self, source_types: Vec<String>) -> Self {
+        self.source_types = Some(source_types);
+        self
+    }
+
+    pub fn with_content_types(mut self, content_types: Vec<String>) -> Self {
+        self.content_types = Some(content_types);
+        self
+    }
+
+    pub fn with_attribute_filter(mut self, key: impl Into<String>, filter: Value) -> Self {
+        self.attribute_filters
+            .get_or_insert_with(HashMap::new)
+            .insert(key.into(), filter);
+        self
+    }
+
+    pub fn with_fields(mut self, fields: Vec<SearchField>) -> Self {
+        self.fields = Some(fields);
+        self
+    }
+
+    pub fn with_facets(mut self, include_facets: bool) -> Self {
+        self.include_facets = Some(include_facets);
+        self
+    }
+
+    pub fn with_child_results(mut self, child_results: ChildResults) -> Self {
+        self.child_results = Some(child_results);
+        self
+    }
+
+    /// Search as `user_email`, so results are filtered by that user's permissions.
+    pub fn as_user(mut self, user_email: impl Into<String>) -> Self {
+        self.user_email = Some(user_email.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResponse {
+    pub results: Vec<SearchResult>,
+    pub total_count: i64,
+    pub query_time_ms: u64,
+    pub has_more: bool,
+    pub query: String,
+    #[serde(default)]
+    pub facets: Option<Vec<Facet>>,
+    #[serde(default)]
+    pub fallback: Option<FallbackStrategy>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub document: Document,
+    pub score: f32,
+    #[serde(default)]
+    pub highlights: Vec<String>,
+    pub match_type: String,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub ranking: Option<RankingExplanation>,
+    /// Other copies of the same content, collapsed into this result
+    #[serde(default)]
+    pub alternates: Vec<AlternateDocument>,
+    /// Where in the document the best matching chunk is
+    #[serde(default)]
+    pub anchor: Option<ResultAnchor>,
+    /// Attachments, comments and transcript segments of the document that matched
+    #[serde(default)]
+    pub children: Vec<ChildResult>,
+}
+
+/// A result listed under the result of its parent document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChildResult {
+    /// `attachment`, `comment` or `transcript_segment`
+    pub relationship: String,
+    #[serde(flatten)]
+    pub result: SearchResult,
+}
+
+/// Location of a result's best matching chunk: its character offsets in the content and the
+/// heading or transcript timestamp it falls under.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultAnchor {
+    pub chunk_index: i32,
+    pub start_offset: i32,
+    pub end_offset: i32,
+    #[serde(default)]
+    pub heading: Option<String>,
+    #[serde(default)]
+    pub heading_level: Option<i16>,
+    #[serde(default)]
+    pub timestamp_seconds: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlternateDocument {
+    pub id: String,
+    pub source_id: String,
+    pub title: String,
+    pub url: Option<String>,
+}
+
+/// How a search result's score was computed. In hybrid mode the weighted full-text and
+/// semantic scores are summed and multiplied by the source, content type, flag and link boosts;
+/// `rerank_delta` is the change from search promotions after ranking.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankingExplanation {
+    pub fts_rank: Option<f32>,
+    /// Cosine similarity of each matching chunk, best first
+    #[serde(default)]
+    pub chunk_similarities: Vec<ChunkSimilarity>,
+    pub fts_weight: Option<f32>,
+    pub semantic_weight: Option<f32>,
+    pub fulltext_score: Option<f32>,
+    pub semantic_score: Option<f32>,
+    pub source_boost: f32,
+    pub content_type_boost: f32,
+    #[serde(default = "default_boost")]
+    pub flag_boost: f32,
+    #[serde(default = "default_boost")]
+    pub link_boost: f32,
+    #[serde(default = "default_boost")]
+    pub vote_boost: f32,
+    #[serde(default)]
+    pub rerank_delta: f32,
+}
+
+fn default_boost() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkSimilarity {
+    pub chunk_index: i32,
+    pub similarity: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Facet {
+    pub name: String,
+    pub values: Vec<FacetValue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FacetValue {
+    pub value: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Document {
+    pub id: String,
+    pub source_id: String,
+    pub external_id: String,
+    pub title: String,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub metadata: Value,
+    #[serde(default)]
+    pub permissions: Value,
+    #[serde(default)]
+    pub attributes: Value,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Set on search results that have a preview image
+    #[serde(default)]
+    pub preview: Option<DocumentPreview>,
+}
+
+/// A document's preview image, served by the searcher at `/documents/{id}/preview`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentPreview {
+    pub kind: PreviewKind,
+    pub mime_type: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreviewKind {
+    /// A thumbnail from the document's source.
+    Thumbnail,
+    /// A render of the document's first page.
+    Render,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Suggestion {
+    pub document_id: String,
+    pub title: String,
+    pub url: Option<String>,
+    pub source_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuickSearchResult {
+    pub document_id: String,
+    pub title: String,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestedQuestion {
+    pub question: String,
+    pub document_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateDocumentRequest {
+    pub source_id: String,
+    pub external_id: String,
+    pub title: String,
+    pub content: String,
+    pub metadata: Value,
+    pub permissions: Value,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateDocumentRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<Value>,
+}
+
+/// One operation of a bulk document request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "operation", rename_all = "lowercase")]
+pub enum BulkDocumentOperation {
+    Create {
+        document: CreateDocumentRequest,
+    },
+    Update {
+        document_id: String,
+        updates: UpdateDocumentRequest,
+    },
+    Delete {
+        document_id: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkDocumentResponse {
+    pub success_count: usize,
+    pub error_count: usize,
+    pub errors: Vec<String>,
+}
+
+/// A configured source and its latest sync.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceInfo {
+    pub id: String,
+    pub name: String,
+    pub source_type: String,
+    pub is_active: bool,
+    pub sync_interval_seconds: Option<i32>,
+    pub document_count: i64,
+    #[serde(default)]
+    pub last_sync: Option<SyncRunSummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncRunSummary {
+    pub id: String,
+    pub sync_type: String,
+    pub status: String,
+    #[serde(default)]
+    pub started_at: Option<String>,
+    #[serde(default)]
+    pub completed_at: Option<String>,
+    #[serde(default)]
+    pub duration_seconds: Option<i64>,
+    pub documents_scanned: i32,
+    pub documents_processed: i32,
+    pub documents_updated: i32,
+    #[serde(default)]
+    pub error_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerSyncResponse {
+    pub sync_run_id: String,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReindexSourceResponse {
+    pub sync_run_id: String,
+    pub status: String,
+    pub redis_keys_cleared: usize,
+    pub documents_deleted: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedSyncRun {
+    pub id: String,
+    pub source_id: String,
+    pub source_name: String,
+    pub sync_type: String,
+    #[serde(default)]
+    pub started_at: Option<String>,
+    pub completed_at: String,
+    #[serde(default)]
+    pub error_message: Option<String>,
+}
+
+/// Outcome of a content blob garbage collection run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GcResult {
+    pub orphans_marked: i64,
+    pub orphans_unmarked: i64,
+    pub blobs_deleted: i64,
+    pub bytes_reclaimed: i64,
+    pub errors: Vec<String>,
+}
+
+/// Orphaned content blobs that garbage collection would mark or delete.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanStats {
+    pub unmarked_orphans: i64,
+    pub pending_orphans: i64,
+    pub expired_orphans: i64,
+    pub orphan_size_bytes: i64,
+}
+
+/// A snapshot of the index to object storage, or a restore of one. `snapshot_id` of a
+/// restore is the snapshot it reads.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexSnapshot {
+    pub id: String,
+    pub kind: String,
+    pub snapshot_id: String,
+    #[serde(default)]
+    pub source_id: Option<String>,
+    pub status: String,
+    pub document_count: i64,
+    pub embedding_count: i64,
+    pub content_blob_count: i64,
+    #[serde(default)]
+    pub error_message: Option<String>,
+    #[serde(default)]
+    pub created_by: Option<String>,
+    pub created_at: String,
+    #[serde(default)]
+    pub started_at: Option<String>,
+    #[serde(default)]
+    pub completed_at: Option<String>,
+}
+
+/// Embedding model settings, as stored under the `embedding_config` configuration key.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingModelConfig {
+    pub provider: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    pub model: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_model_len: Option<i32>,
+}
+
+/// A blue/green migration to another embedding model. Searches are served by the target
+/// model once `cutover` is set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingMigration {
+    pub target: EmbeddingModelConfig,
+    pub shadow_search_percentage: f64,
+    pub cutover: bool,
+    pub started_at: String,
+}
+
+/// Documents with embeddings of one model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelCoverage {
+    pub model_name: String,
+    pub documents: i64,
+}
+
+/// Averages over the shadow searches recorded for one pair of active and shadow models.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShadowComparisonSummary {
+    pub active_model: String,
+    pub shadow_model: String,
+    pub comparisons: i64,
+    /// Mean fraction of the top results both models returned, from 0 to 1.
+    #[serde(default)]
+    pub mean_overlap: Option<f64>,
+    #[serde(default)]
+    pub mean_active_top_score: Option<f64>,
+    #[serde(default)]
+    pub mean_shadow_top_score: Option<f64>,
+    #[serde(default)]
+    pub mean_active_ms: Option<f64>,
+    #[serde(default)]
+    pub mean_shadow_ms: Option<f64>,
+    pub shadow_empty: i64,
+}
+
+/// State of the embedding model migration, if any. API keys are never returned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingMigrationStatus {
+    #[serde(default)]
+    pub current: Option<EmbeddingModelConfig>,
+    #[serde(default)]
+    pub migration: Option<EmbeddingMigration>,
+    #[serde(default)]
+    pub active_model: Option<String>,
+    pub total_documents: i64,
+    pub coverage: Vec<ModelCoverage>,
+    pub comparisons: Vec<ShadowComparisonSummary>,
+}
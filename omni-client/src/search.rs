@@ -0,0 +1,157 @@
+use crate::{
+    encode, OmniClient, QuickSearchResult, Result, SearchRequest, SearchResponse, SearchResult,
+    SuggestedQuestion, Suggestion,
+};
+use futures_util::stream::{self, Stream, TryStreamExt};
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::json;
+
+const DEFAULT_PAGE_SIZE: i64 = 20;
+
+#[derive(Deserialize)]
+struct BatchSearchResponse {
+    results: Vec<BatchSearchResult>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BatchSearchResult {
+    Success(SearchResponse),
+    Failure { error: String },
+}
+
+#[derive(Deserialize)]
+struct SuggestionsResponse {
+    results: Vec<Suggestion>,
+}
+
+#[derive(Deserialize)]
+struct QuickSearchResponse {
+    results: Vec<QuickSearchResult>,
+}
+
+#[derive(Deserialize)]
+struct RecentSearchesResponse {
+    searches: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SuggestedQuestionsResponse {
+    questions: Vec<SuggestedQuestion>,
+}
+
+impl OmniClient {
+    pub async fn search(&self, request: &SearchRequest) -> Result<SearchResponse> {
+        self.send_json(&self.searcher, Method::POST, "/search", Some(request))
+            .await
+    }
+
+    /// Run several searches as `user_id` in one request, at most 20. Returns one response per
+    /// request, in order, or the error message of a query that failed.
+    pub async fn batch_search(
+        &self,
+        user_id: &str,
+        requests: &[SearchRequest],
+    ) -> Result<Vec<std::result::Result<SearchResponse, String>>> {
+        let response: BatchSearchResponse = self
+            .send_json(
+                &self.searcher,
+                Method::POST,
+                "/search/batch",
+                Some(&json!({ "user_id": user_id, "queries": requests })),
+            )
+            .await?;
+        Ok(response
+            .results
+            .into_iter()
+            .map(|result| match result {
+                BatchSearchResult::Success(response) => Ok(response),
+                BatchSearchResult::Failure { error } => Err(error),
+            })
+            .collect())
+    }
+
+    /// Page through the results of a search, starting at `request.offset`. Each page holds
+    /// `request.limit` results, 20 if unset, and the stream ends after the last page.
+    pub fn search_pages(
+        &self,
+        request: SearchRequest,
+    ) -> impl Stream<Item = Result<SearchResponse>> + '_ {
+        let page_size = request.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        let offset = request.offset.unwrap_or(0);
+
+        stream::try_unfold(Some((request, offset)), move |state| async move {
+            let Some((request, offset)) = state else {
+                return Ok(None);
+            };
+            let page = self
+                .search(&request.clone().with_limit(page_size).with_offset(offset))
+                .await?;
+            let next = (page.has_more && !page.results.is_empty())
+                .then(|| (request, offset + page.results.len() as i64));
+            Ok::<_, crate::Error>(Some((page, next)))
+        })
+    }
+
+    /// Collect up to `max_results` results of a search across pages.
+    pub async fn search_all(
+        &self,
+        request: SearchRequest,
+        max_results: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let mut results = Vec::new();
+        let pages = self.search_pages(request);
+        futures_util::pin_mut!(pages);
+        while let Some(page) = pages.try_next().await? {
+            results.extend(page.results);
+            if results.len() >= max_results {
+                results.truncate(max_results);
+                break;
+            }
+        }
+        Ok(results)
+    }
+
+    /// Documents whose titles match what has been typed so far.
+    pub async fn suggestions(&self, query: &str, limit: Option<usize>) -> Result<Vec<Suggestion>> {
+        let mut path = format!("/typeahead?q={}", encode(query));
+        if let Some(limit) = limit {
+            path.push_str(&format!("&limit={}", limit));
+        }
+        let response: SuggestionsResponse = self.get(&self.searcher, &path).await?;
+        Ok(response.results)
+    }
+
+    /// The cached top results the browser extension shows, filtered to `user_email`.
+    pub async fn quick_search(
+        &self,
+        query: &str,
+        user_email: Option<&str>,
+    ) -> Result<Vec<QuickSearchResult>> {
+        let mut path = format!("/quick-search?q={}", encode(query));
+        if let Some(user_email) = user_email {
+            path.push_str(&format!("&user_email={}", encode(user_email)));
+        }
+        let response: QuickSearchResponse = self.get(&self.searcher, &path).await?;
+        Ok(response.results)
+    }
+
+    pub async fn recent_searches(&self, user_id: &str) -> Result<Vec<String>> {
+        let path = format!("/recent-searches?user_id={}", encode(user_id));
+        let response: RecentSearchesResponse = self.get(&self.searcher, &path).await?;
+        Ok(response.searches)
+    }
+
+    pub async fn suggested_questions(&self, user_id: &str) -> Result<Vec<SuggestedQuestion>> {
+        let response: SuggestedQuestionsResponse = self
+            .send_json(
+                &self.searcher,
+                Method::POST,
+                "/suggested-questions",
+                Some(&json!({ "user_id": user_id })),
+            )
+            .await?;
+        Ok(response.questions)
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{
+    encode, FailedSyncRun, OmniClient, ReindexSourceResponse, Result, SourceInfo,
+    TriggerSyncResponse,
+};
+use reqwest::Method;
+use serde_json::Value;
+
+impl OmniClient {
+    pub async fn list_sources(&self) -> Result<Vec<SourceInfo>> {
+        self.get(&self.connector_manager, "/sources").await
+    }
+
+    /// Start a full sync of a source.
+    pub async fn trigger_sync(&self, source_id: &str) -> Result<TriggerSyncResponse> {
+        let path = format!("/sync/{}", encode(source_id));
+        self.send_json::<(), _>(&self.connector_manager, Method::POST, &path, None)
+            .await
+    }
+
+    /// Clear all sync state of a source and start a full sync that indexes everything again.
+    /// With `delete_documents` the source's documents are deleted first, so documents gone
+    /// from the source do not linger.
+    pub async fn reindex_source(
+        &self,
+        source_id: &str,
+        delete_documents: bool,
+    ) -> Result<ReindexSourceResponse> {
+        let path = format!("/sources/{}/reindex", encode(source_id));
+        let body = serde_json::json!({ "delete_documents": delete_documents });
+        self.send_json(&self.connector_manager, Method::POST, &path, Some(&body))
+            .await
+    }
+
+    pub async fn cancel_sync(&self, sync_run_id: &str) -> Result<()> {
+        let path = format!("/sync/{}/cancel", encode(sync_run_id));
+        self.send_json::<(), Value>(&self.connector_manager, Method::POST, &path, None)
+            .await?;
+        Ok(())
+    }
+
+    /// Stop scheduling syncs of a source.
+    pub async fn pause_source(&self, source_id: &str) -> Result<()> {
+        let path = format!("/sources/{}/pause", encode(source_id));
+        self.send_json::<(), Value>(&self.connector_manager, Method::POST, &path, None)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn resume_source(&self, source_id: &str) -> Result<()> {
+        let path = format!("/sources/{}/resume", encode(source_id));
+        self.send_json::<(), Value>(&self.connector_manager, Method::POST, &path, None)
+            .await?;
+        Ok(())
+    }
+
+    /// Syncs that failed after `since`, an RFC 3339 timestamp, or in the last 24 hours.
+    pub async fn sync_errors(
+        &self,
+        since: Option<&str>,
+        limit: Option<i64>,
+    ) -> Result<Vec<FailedSyncRun>> {
+        let mut params = Vec::new();
+        if let Some(since) = since {
+            params.push(format!("since={}", encode(since)));
+        }
+        if let Some(limit) = limit {
+            params.push(format!("limit={}", limit));
+        }
+        let mut path = "/sync-errors".to_string();
+        if !params.is_empty() {
+            path = format!("{}?{}", path, params.join("&"));
+        }
+        self.get(&self.connector_manager, &path).await
+    }
+}
@@ -0,0 +1,137 @@
+use axum::{
+    extract::Path,
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use omni_client::{OmniClient, SearchRequest};
+use serde_json::{json, Value};
+
+/// Serve `router` on a random local port and return its URL.
+async fn serve(router: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+fn search_result(id: i64) -> Value {
+    json!({
+        "document": {
+            "id": format!("doc-{}", id),
+            "source_id": "source-1",
+            "external_id": format!("ext-{}", id),
+            "title": format!("Document {}", id),
+            "content_type": "text/plain",
+            "url": null,
+            "metadata": {},
+            "permissions": {},
+            "attributes": {},
+            "created_at": "+002026-01-01T00:00:00.000000000Z",
+            "updated_at": "+002026-01-01T00:00:00.000000000Z"
+        },
+        "score": 1.0,
+        "highlights": [],
+        "match_type": "fulltext"
+    })
+}
+
+/// A searcher with 5 matching documents that honours limit and offset.
+async fn mock_search(Json(request): Json<Value>) -> Json<Value> {
+    let total = 5;
+    let limit = request["limit"].as_i64().unwrap_or(20);
+    let offset = request["offset"].as_i64().unwrap_or(0);
+    let results: Vec<Value> = (offset..(offset + limit).min(total))
+        .map(search_result)
+        .collect();
+    Json(json!({
+        "results": results,
+        "total_count": total,
+        "query_time_ms": 1,
+        "has_more": offset + limit < total,
+        "query": request["query"],
+    }))
+}
+
+#[tokio::test]
+async fn test_search_all_pages_through_results() {
+    let url = serve(Router::new().route("/search", post(mock_search))).await;
+    let client = OmniClient::builder().searcher_url(url).build().unwrap();
+
+    let results = client
+        .search_all(SearchRequest::new("roadmap").with_limit(2), 100)
+        .await
+        .unwrap();
+    let ids: Vec<_> = results.iter().map(|r| r.document.id.as_str()).collect();
+    assert_eq!(ids, ["doc-0", "doc-1", "doc-2", "doc-3", "doc-4"]);
+
+    let results = client
+        .search_all(SearchRequest::new("roadmap").with_limit(2), 3)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 3);
+}
+
+#[tokio::test]
+async fn test_batch_search_keeps_query_order() {
+    let router = Router::new().route(
+        "/search/batch",
+        post(|Json(request): Json<Value>| async move {
+            let results: Vec<Value> = request["queries"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|query| match query["query"].as_str().unwrap() {
+                    "" => json!({ "error": "Query is empty" }),
+                    query => json!({
+                        "results": [search_result(0)],
+                        "total_count": 1,
+                        "query_time_ms": 1,
+                        "has_more": false,
+                        "query": query,
+                    }),
+                })
+                .collect();
+            Json(json!({ "results": results }))
+        }),
+    );
+    let url = serve(router).await;
+    let client = OmniClient::builder().searcher_url(url).build().unwrap();
+
+    let results = client
+        .batch_search(
+            "user-1",
+            &[SearchRequest::new("roadmap"), SearchRequest::new("")],
+        )
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 2);
+    let first = results[0].as_ref().unwrap();
+    assert_eq!(first.query, "roadmap");
+    assert_eq!(first.results.len(), 1);
+    assert_eq!(results[1].as_ref().unwrap_err(), "Query is empty");
+}
+
+#[tokio::test]
+async fn test_api_errors_carry_service_message() {
+    let router = Router::new().route(
+        "/documents/:id",
+        get(|Path(id): Path<String>| async move {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({ "error": format!("Document {} not found", id) })),
+            )
+        }),
+    );
+    let url = serve(router).await;
+    let client = OmniClient::builder().indexer_url(url).build().unwrap();
+
+    let err = client.get_document("missing").await.unwrap_err();
+    assert_eq!(err.status(), Some(404));
+    assert_eq!(
+        err.to_string(),
+        "indexer returned 404: Document missing not found"
+    );
+}
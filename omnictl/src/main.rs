@@ -0,0 +1,721 @@
+use anyhow::{anyhow, Result};
+use clap::{Args, Parser, Subcommand};
+use omni_client::{
+    EmbeddingMigrationStatus, EmbeddingModelConfig, IndexSnapshot, OmniClient, RankingExplanation,
+    SearchField, SearchMode, SearchRequest, SearchResponse, SourceInfo,
+};
+use serde::Serialize;
+use std::collections::HashSet;
+use time::format_description::well_known::{Iso8601, Rfc3339};
+use time::OffsetDateTime;
+
+#[derive(Parser)]
+#[command(name = "omnictl")]
+#[command(about = "Operate an Omni deployment through its service APIs")]
+struct Cli {
+    #[arg(
+        long,
+        env = "OMNI_CONNECTOR_MANAGER_URL",
+        default_value = "http://localhost:3004"
+    )]
+    connector_manager_url: String,
+
+    #[arg(
+        long,
+        env = "OMNI_SEARCHER_URL",
+        default_value = "http://localhost:3001"
+    )]
+    searcher_url: String,
+
+    #[arg(
+        long,
+        env = "OMNI_INDEXER_URL",
+        default_value = "http://localhost:3002"
+    )]
+    indexer_url: String,
+
+    /// Print raw JSON responses instead of tables
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Inspect configured sources
+    Sources {
+        #[command(subcommand)]
+        command: SourcesCommands,
+    },
+    /// Trigger, pause and inspect syncs
+    Sync {
+        #[command(subcommand)]
+        command: SyncCommands,
+    },
+    /// Run a search as a given user
+    Search {
+        query: String,
+        /// Email of the user to search as
+        #[arg(long = "as")]
+        user_email: String,
+        #[arg(long, default_value = "10")]
+        limit: i64,
+        /// fulltext, semantic or hybrid
+        #[arg(long, value_parser = parse_search_mode)]
+        mode: Option<SearchMode>,
+        /// Only match these fields: title, metadata or content
+        #[arg(long = "field", value_parser = parse_search_field)]
+        fields: Vec<SearchField>,
+        /// Show how each result's score was computed
+        #[arg(long)]
+        explain: bool,
+        /// Do not retry with relaxed strategies when nothing is found
+        #[arg(long)]
+        no_fallback: bool,
+    },
+    /// Queue documents for re-embedding
+    Reembed {
+        /// Only re-embed documents of this source
+        #[arg(long)]
+        source: Option<String>,
+    },
+    /// Garbage collect unreferenced content blobs
+    Gc {
+        #[command(subcommand)]
+        command: GcCommands,
+    },
+    /// Snapshot the index to object storage and restore snapshots
+    Snapshot {
+        #[command(subcommand)]
+        command: SnapshotCommands,
+    },
+    /// Migrate to another embedding model without downtime
+    EmbeddingMigration {
+        #[command(subcommand)]
+        command: EmbeddingMigrationCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum SourcesCommands {
+    /// List sources with their latest sync
+    List,
+}
+
+#[derive(Subcommand)]
+enum SyncCommands {
+    /// Start a full sync of a source
+    Trigger { source_id: String },
+    /// Clear a source's sync state and re-index it from scratch with a full sync
+    Reindex {
+        source_id: String,
+        /// Delete the source's documents first, so documents gone from the source are dropped
+        #[arg(long)]
+        delete_documents: bool,
+    },
+    /// Stop scheduling syncs of a source
+    Pause { source_id: String },
+    /// Resume scheduled syncs of a paused source
+    Resume { source_id: String },
+    /// Cancel a running sync
+    Cancel { sync_run_id: String },
+    /// Show failed syncs
+    Errors(SyncErrorsArgs),
+}
+
+#[derive(Args)]
+struct SyncErrorsArgs {
+    /// How far back to look, e.g. 30m, 6h or 7d
+    #[arg(long, default_value = "24h")]
+    since: String,
+    /// Keep polling for new failures
+    #[arg(long, short)]
+    follow: bool,
+    /// Seconds between polls when following
+    #[arg(long, default_value = "10")]
+    interval: u64,
+}
+
+#[derive(Subcommand)]
+enum GcCommands {
+    /// Delete orphaned content blobs
+    Run,
+    /// Show what a GC run would delete
+    Stats,
+}
+
+#[derive(Subcommand)]
+enum SnapshotCommands {
+    /// Snapshot documents, embeddings and content of one source or the whole index
+    Create {
+        #[arg(long)]
+        source: Option<String>,
+    },
+    /// List snapshots and restores
+    List,
+    /// Show the progress of a snapshot or restore
+    Show { id: String },
+    /// Restore a snapshot, which may have been taken by another deployment
+    Restore {
+        snapshot_id: String,
+        /// Only restore this source
+        #[arg(long)]
+        source: Option<String>,
+        /// Id of the user to own restored sources whose owner does not exist here
+        #[arg(long)]
+        created_by: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum EmbeddingMigrationCommands {
+    /// Show the migration, embedding coverage per model and shadow search comparisons
+    Status,
+    /// Start embedding documents with a target model alongside the current one
+    Start {
+        /// local, jina, openai, cohere or bedrock
+        #[arg(long)]
+        provider: String,
+        #[arg(long)]
+        model: String,
+        #[arg(long, env = "OMNI_EMBEDDING_API_KEY", hide_env_values = true)]
+        api_key: Option<String>,
+        #[arg(long)]
+        api_url: Option<String>,
+        #[arg(long)]
+        dimensions: Option<i32>,
+        #[arg(long)]
+        max_model_len: Option<i32>,
+        /// Percentage of semantic searches to also run against the target model
+        #[arg(long)]
+        shadow_percentage: Option<f64>,
+    },
+    /// Change the shadow search percentage, or cut searches over to the target model
+    Set {
+        #[arg(long)]
+        shadow_percentage: Option<f64>,
+        /// Serve searches from the target model
+        #[arg(long, conflicts_with = "rollback")]
+        cutover: bool,
+        /// Serve searches from the current model again
+        #[arg(long)]
+        rollback: bool,
+    },
+    /// Make the target model current and delete the previous model's embeddings
+    Finalize,
+    /// Abandon a migration that has not been cut over and delete the target's embeddings
+    Cancel,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    // Uses mutual TLS when `SERVICE_TLS_*` is configured, the same as the services use to
+    // call each other
+    let client = OmniClient::builder()
+        .connector_manager_url(&cli.connector_manager_url)
+        .searcher_url(&cli.searcher_url)
+        .indexer_url(&cli.indexer_url)
+        .http_client(shared::tls::client_builder()?.build()?)
+        .build()?;
+
+    match cli.command {
+        Commands::Sources {
+            command: SourcesCommands::List,
+        } => {
+            let sources = client.list_sources().await?;
+            if cli.json {
+                print_json(&sources);
+            } else {
+                print_sources(&sources);
+            }
+        }
+        Commands::Sync { command } => match command {
+            SyncCommands::Trigger { source_id } => {
+                let response = client.trigger_sync(&source_id).await?;
+                print_status(cli.json, &response, || {
+                    format!("Started sync {}", response.sync_run_id)
+                });
+            }
+            SyncCommands::Reindex {
+                source_id,
+                delete_documents,
+            } => {
+                let response = client.reindex_source(&source_id, delete_documents).await?;
+                print_status(cli.json, &response, || {
+                    format!(
+                        "Started full sync {} after clearing {} state keys and {} documents",
+                        response.sync_run_id,
+                        response.redis_keys_cleared,
+                        response.documents_deleted
+                    )
+                });
+            }
+            SyncCommands::Pause { source_id } => {
+                client.pause_source(&source_id).await?;
+                println!("Paused {}", source_id);
+            }
+            SyncCommands::Resume { source_id } => {
+                client.resume_source(&source_id).await?;
+                println!("Resumed {}", source_id);
+            }
+            SyncCommands::Cancel { sync_run_id } => {
+                client.cancel_sync(&sync_run_id).await?;
+                println!("Cancelled {}", sync_run_id);
+            }
+            SyncCommands::Errors(args) => tail_sync_errors(&client, &args, cli.json).await?,
+        },
+        Commands::Search {
+            query,
+            user_email,
+            limit,
+            mode,
+            fields,
+            explain,
+            no_fallback,
+        } => {
+            let mut request = SearchRequest::new(query)
+                .as_user(user_email)
+                .with_limit(limit);
+            request.mode = mode;
+            request.fields = (!fields.is_empty()).then_some(fields);
+            request.explain = explain.then_some(true);
+            request.fallback = no_fallback.then_some(false);
+            let response = client.search(&request).await?;
+            if cli.json {
+                print_json(&response);
+            } else {
+                print_search_results(&response);
+            }
+        }
+        Commands::Reembed { source } => {
+            let queued = client.reembed(source.as_deref()).await?;
+            print_status(cli.json, &serde_json::json!({ "queued": queued }), || {
+                format!("Queued {} documents for re-embedding", queued)
+            });
+        }
+        Commands::Gc { command } => match command {
+            GcCommands::Run => print_json(&client.run_gc().await?),
+            GcCommands::Stats => print_json(&client.gc_stats().await?),
+        },
+        Commands::Snapshot { command } => match command {
+            SnapshotCommands::Create { source } => {
+                let snapshot = client.create_snapshot(source.as_deref()).await?;
+                print_status(cli.json, &snapshot, || {
+                    format!("Started snapshot {}", snapshot.id)
+                });
+            }
+            SnapshotCommands::List => {
+                let snapshots = client.list_snapshots().await?;
+                if cli.json {
+                    print_json(&snapshots);
+                } else {
+                    print_snapshots(&snapshots);
+                }
+            }
+            SnapshotCommands::Show { id } => {
+                let snapshot = client.get_snapshot(&id).await?;
+                if cli.json {
+                    print_json(&snapshot);
+                } else {
+                    print_snapshots(std::slice::from_ref(&snapshot));
+                }
+            }
+            SnapshotCommands::Restore {
+                snapshot_id,
+                source,
+                created_by,
+            } => {
+                let restore = client
+                    .restore_snapshot(&snapshot_id, source.as_deref(), created_by.as_deref())
+                    .await?;
+                print_status(cli.json, &restore, || {
+                    format!("Started restore {} of snapshot {}", restore.id, snapshot_id)
+                });
+            }
+        },
+        Commands::EmbeddingMigration { command } => match command {
+            EmbeddingMigrationCommands::Status => {
+                let status = client.embedding_migration().await?;
+                if cli.json {
+                    print_json(&status);
+                } else {
+                    print_embedding_migration(&status);
+                }
+            }
+            EmbeddingMigrationCommands::Start {
+                provider,
+                model,
+                api_key,
+                api_url,
+                dimensions,
+                max_model_len,
+                shadow_percentage,
+            } => {
+                let target = EmbeddingModelConfig {
+                    provider,
+                    api_key,
+                    model,
+                    api_url,
+                    dimensions,
+                    max_model_len,
+                };
+                let migration = client
+                    .start_embedding_migration(&target, shadow_percentage)
+                    .await?;
+                print_status(cli.json, &migration, || {
+                    format!(
+                        "Started migration to {}, shadowing {}% of semantic searches",
+                        migration.target.model, migration.shadow_search_percentage
+                    )
+                });
+            }
+            EmbeddingMigrationCommands::Set {
+                shadow_percentage,
+                cutover,
+                rollback,
+            } => {
+                let cutover = match (cutover, rollback) {
+                    (true, _) => Some(true),
+                    (_, true) => Some(false),
+                    _ => None,
+                };
+                if shadow_percentage.is_none() && cutover.is_none() {
+                    return Err(anyhow!(
+                        "Nothing to change, pass --shadow-percentage, --cutover or --rollback"
+                    ));
+                }
+                let migration = client
+                    .update_embedding_migration(shadow_percentage, cutover)
+                    .await?;
+                print_status(cli.json, &migration, || {
+                    format!(
+                        "Searches served by {}, shadowing {}% of semantic searches",
+                        if migration.cutover {
+                            "the target model"
+                        } else {
+                            "the current model"
+                        },
+                        migration.shadow_search_percentage
+                    )
+                });
+            }
+            EmbeddingMigrationCommands::Finalize => {
+                let migration = client.finalize_embedding_migration().await?;
+                print_status(cli.json, &migration, || {
+                    format!(
+                        "{} is now the current embedding model",
+                        migration.target.model
+                    )
+                });
+            }
+            EmbeddingMigrationCommands::Cancel => {
+                let migration = client.cancel_embedding_migration().await?;
+                print_status(cli.json, &migration, || {
+                    format!("Cancelled migration to {}", migration.target.model)
+                });
+            }
+        },
+    }
+
+    Ok(())
+}
+
+/// Print failed syncs since `args.since`, then optionally poll for new ones.
+async fn tail_sync_errors(client: &OmniClient, args: &SyncErrorsArgs, json: bool) -> Result<()> {
+    let mut since = OffsetDateTime::now_utc() - parse_duration(&args.since)?;
+    let mut seen = HashSet::new();
+
+    loop {
+        let runs = client
+            .sync_errors(Some(&since.format(&Rfc3339)?), None)
+            .await?;
+
+        for run in runs {
+            if !seen.insert(run.id.clone()) {
+                continue;
+            }
+            // Timestamps are serialized as ISO 8601 with six digit years
+            let completed_at = OffsetDateTime::parse(&run.completed_at, &Iso8601::DEFAULT).ok();
+            if let Some(completed_at) = completed_at {
+                since = since.max(completed_at);
+            }
+            if json {
+                println!("{}", serde_json::to_string(&run)?);
+            } else {
+                println!(
+                    "{}  {} ({})  {}: {}",
+                    completed_at
+                        .and_then(|t| t.format(&Rfc3339).ok())
+                        .unwrap_or_else(|| "-".to_string()),
+                    run.source_name,
+                    run.source_id,
+                    run.sync_type,
+                    run.error_message.as_deref().unwrap_or("-"),
+                );
+            }
+        }
+
+        if !args.follow {
+            return Ok(());
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(args.interval)).await;
+    }
+}
+
+/// Parse durations like `90s`, `30m`, `6h` or `7d`.
+fn parse_duration(value: &str) -> Result<time::Duration> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| anyhow!("Duration '{}' needs a unit: s, m, h or d", value))?;
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .map_err(|_| anyhow!("Invalid duration '{}'", value))?;
+
+    match unit {
+        "s" => Ok(time::Duration::seconds(amount)),
+        "m" => Ok(time::Duration::minutes(amount)),
+        "h" => Ok(time::Duration::hours(amount)),
+        "d" => Ok(time::Duration::days(amount)),
+        _ => Err(anyhow!("Unknown duration unit '{}' in '{}'", unit, value)),
+    }
+}
+
+fn parse_search_field(value: &str) -> Result<SearchField> {
+    match value {
+        "title" => Ok(SearchField::Title),
+        "metadata" => Ok(SearchField::Metadata),
+        "content" => Ok(SearchField::Content),
+        _ => Err(anyhow!("Unknown search field '{}'", value)),
+    }
+}
+
+fn parse_search_mode(value: &str) -> Result<SearchMode> {
+    match value {
+        "fulltext" => Ok(SearchMode::Fulltext),
+        "semantic" => Ok(SearchMode::Semantic),
+        "hybrid" => Ok(SearchMode::Hybrid),
+        _ => Err(anyhow!("Unknown search mode '{}'", value)),
+    }
+}
+
+fn print_json(value: &impl Serialize) {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(value).unwrap_or_default()
+    );
+}
+
+fn print_status(json: bool, response: &impl Serialize, message: impl FnOnce() -> String) {
+    if json {
+        print_json(response);
+    } else {
+        println!("{}", message());
+    }
+}
+
+fn print_sources(sources: &[SourceInfo]) {
+    println!(
+        "{:<28} {:<24} {:<16} {:<8} {:>10}  LAST SYNC",
+        "ID", "NAME", "TYPE", "ACTIVE", "DOCUMENTS"
+    );
+    for source in sources {
+        let last_sync = match &source.last_sync {
+            Some(run) => format!(
+                "{} {}",
+                run.status,
+                run.completed_at
+                    .as_deref()
+                    .or(run.started_at.as_deref())
+                    .unwrap_or("")
+            ),
+            None => "never".to_string(),
+        };
+        println!(
+            "{:<28} {:<24} {:<16} {:<8} {:>10}  {}",
+            source.id,
+            source.name,
+            source.source_type,
+            source.is_active,
+            source.document_count,
+            last_sync
+        );
+    }
+}
+
+fn print_snapshots(snapshots: &[IndexSnapshot]) {
+    println!(
+        "{:<28} {:<8} {:<28} {:<10} {:>10} {:>10} {:>8}  ERROR",
+        "ID", "KIND", "SNAPSHOT", "STATUS", "DOCUMENTS", "EMBEDDINGS", "BLOBS"
+    );
+    for snapshot in snapshots {
+        println!(
+            "{:<28} {:<8} {:<28} {:<10} {:>10} {:>10} {:>8}  {}",
+            snapshot.id,
+            snapshot.kind,
+            snapshot.snapshot_id,
+            snapshot.status,
+            snapshot.document_count,
+            snapshot.embedding_count,
+            snapshot.content_blob_count,
+            snapshot.error_message.as_deref().unwrap_or("")
+        );
+    }
+}
+
+fn print_embedding_migration(status: &EmbeddingMigrationStatus) {
+    let current = status
+        .current
+        .as_ref()
+        .map(|config| format!("{} ({})", config.model, config.provider))
+        .unwrap_or_else(|| "from the AI service environment".to_string());
+    println!("Current model: {}", current);
+    match &status.migration {
+        Some(migration) => println!(
+            "Migrating to:  {} ({}), started {}, {}, shadowing {}% of semantic searches",
+            migration.target.model,
+            migration.target.provider,
+            migration.started_at,
+            if migration.cutover {
+                "cut over"
+            } else {
+                "not cut over"
+            },
+            migration.shadow_search_percentage
+        ),
+        None => println!("Migrating to:  no migration in progress"),
+    }
+
+    println!();
+    println!("{:<48} {:>10} {:>8}", "MODEL", "DOCUMENTS", "COVERAGE");
+    for coverage in &status.coverage {
+        let percent = if status.total_documents > 0 {
+            coverage.documents as f64 * 100.0 / status.total_documents as f64
+        } else {
+            0.0
+        };
+        println!(
+            "{:<48} {:>10} {:>7.1}%",
+            coverage.model_name, coverage.documents, percent
+        );
+    }
+
+    if !status.comparisons.is_empty() {
+        println!();
+        println!(
+            "{:<32} {:<32} {:>8} {:>8} {:>10} {:>10} {:>12}",
+            "ACTIVE", "SHADOW", "SEARCHES", "OVERLAP", "ACTIVE MS", "SHADOW MS", "SHADOW EMPTY"
+        );
+        for summary in &status.comparisons {
+            println!(
+                "{:<32} {:<32} {:>8} {:>7.1}% {:>10.0} {:>10.0} {:>12}",
+                summary.active_model,
+                summary.shadow_model,
+                summary.comparisons,
+                summary.mean_overlap.unwrap_or(0.0) * 100.0,
+                summary.mean_active_ms.unwrap_or(0.0),
+                summary.mean_shadow_ms.unwrap_or(0.0),
+                summary.shadow_empty
+            );
+        }
+    }
+}
+
+fn print_search_results(response: &SearchResponse) {
+    println!(
+        "{} results in {}ms",
+        response.total_count, response.query_time_ms
+    );
+    if let Some(fallback) = response.fallback {
+        println!(
+            "Nothing matched the query as given, showing results of fallback {:?}",
+            fallback
+        );
+    }
+    for (i, result) in response.results.iter().enumerate() {
+        println!(
+            "{:>3}. {} [{:.3} {}]",
+            i + 1,
+            result.document.title,
+            result.score,
+            result.match_type,
+        );
+        if let Some(url) = &result.document.url {
+            println!("     {}", url);
+        }
+        if let Some(highlight) = result.highlights.first() {
+            println!("     {}", highlight.replace('\n', " "));
+        }
+        if let Some(ranking) = &result.ranking {
+            print_ranking(ranking);
+        }
+    }
+}
+
+fn print_ranking(ranking: &RankingExplanation) {
+    let score = |score: Option<f32>| match score {
+        Some(score) => format!("{:.3}", score),
+        None => "-".to_string(),
+    };
+    let weight = |weight: Option<f32>| match weight {
+        Some(weight) => format!(" (weight {})", weight),
+        None => String::new(),
+    };
+    println!(
+        "     fulltext {}{} [ts_rank {}] + semantic {}{}",
+        score(ranking.fulltext_score),
+        weight(ranking.fts_weight),
+        score(ranking.fts_rank),
+        score(ranking.semantic_score),
+        weight(ranking.semantic_weight),
+    );
+    if !ranking.chunk_similarities.is_empty() {
+        let chunks: Vec<String> = ranking
+            .chunk_similarities
+            .iter()
+            .map(|chunk| format!("#{} {:.3}", chunk.chunk_index, chunk.similarity))
+            .collect();
+        println!("     chunks {}", chunks.join(", "));
+    }
+    println!(
+        "     x{} source, x{} content type, x{} flags, x{:.3} links, x{:.3} votes, {:+.3} from promotions",
+        ranking.source_boost,
+        ranking.content_type_boost,
+        ranking.flag_boost,
+        ranking.link_boost,
+        ranking.vote_boost,
+        ranking.rerank_delta,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90s").unwrap(), time::Duration::seconds(90));
+        assert_eq!(parse_duration("30m").unwrap(), time::Duration::minutes(30));
+        assert_eq!(parse_duration("6h").unwrap(), time::Duration::hours(6));
+        assert_eq!(parse_duration("7d").unwrap(), time::Duration::days(7));
+        assert!(parse_duration("7").is_err());
+        assert!(parse_duration("7w").is_err());
+        assert!(parse_duration("h").is_err());
+    }
+
+    #[test]
+    fn test_parse_search_mode() {
+        assert_eq!(parse_search_mode("hybrid").unwrap(), SearchMode::Hybrid);
+        assert!(parse_search_mode("vector").is_err());
+    }
+
+    #[test]
+    fn test_parse_search_field() {
+        assert_eq!(parse_search_field("title").unwrap(), SearchField::Title);
+        assert!(parse_search_field("body").is_err());
+    }
+}
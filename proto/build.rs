@@ -0,0 +1,7 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Use a bundled protoc so that building needs no system protobuf compiler
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    tonic_build::configure()
+        .compile_protos(&["proto/omni/searcher/v1/searcher.proto"], &["proto"])?;
+    Ok(())
+}
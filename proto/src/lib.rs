@@ -0,0 +1,8 @@
+//! Protobuf schemas of the Omni service APIs and the gRPC clients and servers generated from
+//! them. The schemas live under `proto/`, for consumers generating code in other languages.
+
+pub mod searcher {
+    pub mod v1 {
+        tonic::include_proto!("omni.searcher.v1");
+    }
+}
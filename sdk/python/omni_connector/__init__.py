@@ -18,8 +18,11 @@ from .models import (
     ConnectorManifest,
     Document,
     DocumentMetadata,
+    DocumentParent,
     DocumentPermissions,
+    DocumentThumbnail,
     EventType,
+    RelationshipType,
     SyncMode,
     SyncRequest,
     SyncResponse,
@@ -37,7 +40,10 @@ __all__ = [
     # Models
     "Document",
     "DocumentMetadata",
+    "DocumentParent",
     "DocumentPermissions",
+    "DocumentThumbnail",
+    "RelationshipType",
     "ConnectorEvent",
     "EventType",
     "ActionDefinition",
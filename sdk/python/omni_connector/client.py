@@ -1,15 +1,51 @@
 import logging
 import os
+import ssl
 from typing import Any
 
 import httpx
 
 from .exceptions import SdkClientError
-from .models import ConnectorEvent
+from .models import ConnectorEvent, SyncRequest
 
 logger = logging.getLogger(__name__)
 
 
+def _response_error(action: str, response: httpx.Response) -> SdkClientError:
+    """Error for a failed response, with the code connector-manager answered it with."""
+    try:
+        body = response.json()
+    except ValueError:
+        body = None
+    if not isinstance(body, dict):
+        body = {}
+    return SdkClientError(
+        f"{action}: {response.status_code} - {response.text}",
+        status_code=response.status_code,
+        code=body.get("code"),
+        retriable=bool(body.get("retriable", False)),
+        details=body.get("details"),
+    )
+
+
+def _service_tls_context() -> ssl.SSLContext | None:
+    """TLS context presenting this connector's certificate to the connector manager
+    when mutual TLS between services is configured with `SERVICE_TLS_*`."""
+    cert = os.environ.get("SERVICE_TLS_CERT_PATH")
+    key = os.environ.get("SERVICE_TLS_KEY_PATH")
+    ca = os.environ.get("SERVICE_TLS_CA_PATH")
+    if not (cert or key or ca):
+        return None
+    if not (cert and key and ca):
+        raise ValueError(
+            "SERVICE_TLS_CERT_PATH, SERVICE_TLS_KEY_PATH and SERVICE_TLS_CA_PATH "
+            "must be set together"
+        )
+    context = ssl.create_default_context(cafile=ca)
+    context.load_cert_chain(cert, key)
+    return context
+
+
 class SdkClient:
     """HTTP client for communicating with connector-manager SDK endpoints."""
 
@@ -21,6 +57,8 @@ class SdkClient:
             raise ValueError("CONNECTOR_MANAGER_URL environment variable not set")
         self._timeout = timeout
         self._client: httpx.AsyncClient | None = None
+        # Source tokens sent with sync requests, by source and sync run
+        self._tokens: dict[str, str] = {}
 
     @classmethod
     def from_env(cls) -> "SdkClient":
@@ -28,20 +66,33 @@ class SdkClient:
 
     async def _get_client(self) -> httpx.AsyncClient:
         if self._client is None:
-            self._client = httpx.AsyncClient(timeout=self._timeout)
+            tls = _service_tls_context()
+            self._client = httpx.AsyncClient(
+                timeout=self._timeout, verify=tls if tls is not None else True
+            )
         return self._client
 
+    def use_token(self, request: SyncRequest) -> None:
+        """Authenticate calls for the source and sync run of a sync request with
+        the token sent with it."""
+        if request.sdk_token:
+            self._tokens[request.source_id] = request.sdk_token
+            self._tokens[request.sync_run_id] = request.sdk_token
+
+    def _headers(self, scope: str) -> dict[str, str]:
+        token = self._tokens.get(scope)
+        return {"Authorization": f"Bearer {token}"} if token else {}
+
     async def fetch_source_config(self, source_id: str) -> dict[str, Any]:
         """Fetch source config, credentials, and state from connector-manager."""
         client = await self._get_client()
         response = await client.get(
-            f"{self.base_url}/sdk/source/{source_id}/sync-config"
+            f"{self.base_url}/sdk/source/{source_id}/sync-config",
+            headers=self._headers(source_id),
         )
 
         if not response.is_success:
-            raise SdkClientError(
-                f"Failed to fetch source config: {response.status_code} - {response.text}"
-            )
+            raise _response_error("Failed to fetch source config", response)
 
         return response.json()
 
@@ -64,12 +115,11 @@ class SdkClient:
         response = await client.post(
             f"{self.base_url}/sdk/events",
             json=payload,
+            headers=self._headers(source_id),
         )
 
         if not response.is_success:
-            raise SdkClientError(
-                f"Failed to emit event: {response.status_code} - {response.text}"
-            )
+            raise _response_error("Failed to emit event", response)
 
     async def store_content(
         self,
@@ -90,12 +140,37 @@ class SdkClient:
         response = await client.post(
             f"{self.base_url}/sdk/content",
             json=payload,
+            headers=self._headers(sync_run_id),
         )
 
         if not response.is_success:
-            raise SdkClientError(
-                f"Failed to store content: {response.status_code} - {response.text}"
-            )
+            raise _response_error("Failed to store content", response)
+
+        return response.json()["content_id"]
+
+    async def store_binary_content(
+        self,
+        sync_run_id: str,
+        content: bytes,
+        content_type: str,
+    ) -> str:
+        """Store binary content, such as a thumbnail image, and return content_id."""
+        logger.debug(
+            "SDK: Storing %d bytes of %s for sync_run=%s",
+            len(content),
+            content_type,
+            sync_run_id,
+        )
+
+        client = await self._get_client()
+        response = await client.post(
+            f"{self.base_url}/sdk/sync/{sync_run_id}/content",
+            content=content,
+            headers={"Content-Type": content_type, **self._headers(sync_run_id)},
+        )
+
+        if not response.is_success:
+            raise _response_error("Failed to store binary content", response)
 
         return response.json()["content_id"]
 
@@ -105,13 +180,12 @@ class SdkClient:
 
         client = await self._get_client()
         response = await client.post(
-            f"{self.base_url}/sdk/sync/{sync_run_id}/heartbeat"
+            f"{self.base_url}/sdk/sync/{sync_run_id}/heartbeat",
+            headers=self._headers(sync_run_id),
         )
 
         if not response.is_success:
-            raise SdkClientError(
-                f"Failed to heartbeat: {response.status_code} - {response.text}"
-            )
+            raise _response_error("Failed to heartbeat", response)
 
     async def increment_scanned(self, sync_run_id: str) -> None:
         """Increment scanned count and update heartbeat."""
@@ -121,12 +195,11 @@ class SdkClient:
         response = await client.post(
             f"{self.base_url}/sdk/sync/{sync_run_id}/scanned",
             json={"count": 1},
+            headers=self._headers(sync_run_id),
         )
 
         if not response.is_success:
-            raise SdkClientError(
-                f"Failed to increment scanned: {response.status_code} - {response.text}"
-            )
+            raise _response_error("Failed to increment scanned", response)
 
     async def complete(
         self,
@@ -149,12 +222,11 @@ class SdkClient:
         response = await client.post(
             f"{self.base_url}/sdk/sync/{sync_run_id}/complete",
             json=payload,
+            headers=self._headers(sync_run_id),
         )
 
         if not response.is_success:
-            raise SdkClientError(
-                f"Failed to complete: {response.status_code} - {response.text}"
-            )
+            raise _response_error("Failed to complete", response)
 
     async def fail(self, sync_run_id: str, error: str) -> None:
         """Mark sync as failed."""
@@ -164,12 +236,31 @@ class SdkClient:
         response = await client.post(
             f"{self.base_url}/sdk/sync/{sync_run_id}/fail",
             json={"error": error},
+            headers=self._headers(sync_run_id),
         )
 
         if not response.is_success:
-            raise SdkClientError(
-                f"Failed to mark as failed: {response.status_code} - {response.text}"
-            )
+            raise _response_error("Failed to mark as failed", response)
+
+    async def pause(
+        self, sync_run_id: str, new_state: dict[str, Any] | None = None
+    ) -> None:
+        """Mark sync as paused, to be resumed from new_state once the connector is back."""
+        logger.info("SDK: Pausing sync_run=%s", sync_run_id)
+
+        payload: dict[str, Any] = {}
+        if new_state is not None:
+            payload["new_state"] = new_state
+
+        client = await self._get_client()
+        response = await client.post(
+            f"{self.base_url}/sdk/sync/{sync_run_id}/pause",
+            json=payload,
+            headers=self._headers(sync_run_id),
+        )
+
+        if not response.is_success:
+            raise _response_error("Failed to pause", response)
 
     async def close(self) -> None:
         """Close the HTTP client."""
@@ -95,4 +95,5 @@ class Connector(ABC):
 
         app = create_app(self)
         logger.info("Starting %s connector on %s:%d", self.name, host, port)
-        uvicorn.run(app, host=host, port=port)
+        # Give requests in flight as long to finish on SIGTERM as the Rust services do
+        uvicorn.run(app, host=host, port=port, timeout_graceful_shutdown=30)
@@ -26,6 +26,7 @@ class SyncContext:
         self._source_type = source_type
         self._state = state or {}
         self._cancelled = asyncio.Event()
+        self._paused = False
         self._documents_emitted = 0
         self._documents_scanned = 0
         self._content_storage = ContentStorage(sdk_client, sync_run_id)
@@ -138,8 +139,18 @@ class SyncContext:
 
     async def fail(self, error: str) -> None:
         """Mark sync as failed with error message."""
+        if self._paused:
+            # Failures after pausing are the shutdown's doing, not the sync's
+            logger.info("Not failing paused sync %s: %s", self._sync_run_id, error)
+            return
         await self._client.fail(self._sync_run_id, error)
 
+    async def pause(self) -> None:
+        """Stop the sync as the connector shuts down, to be resumed from the last saved state."""
+        self._paused = True
+        self._cancelled.set()
+        await self._client.pause(self._sync_run_id, self._state)
+
     def is_cancelled(self) -> bool:
         """Check if sync was cancelled. Connector should poll this periodically."""
         return self._cancelled.is_set()
@@ -1,3 +1,6 @@
+from typing import Any
+
+
 class ConnectorError(Exception):
     """Base exception for connector errors."""
 
@@ -5,9 +8,25 @@ class ConnectorError(Exception):
 
 
 class SdkClientError(ConnectorError):
-    """Error communicating with connector-manager SDK endpoints."""
-
-    pass
+    """Error communicating with connector-manager SDK endpoints.
+
+    For errors answered by connector-manager, ``status_code`` is the response's status and
+    ``code``, ``retriable`` and ``details`` those of its error body, such as ``"not_found"``.
+    """
+
+    def __init__(
+        self,
+        message: str,
+        status_code: int | None = None,
+        code: str | None = None,
+        retriable: bool = False,
+        details: Any = None,
+    ):
+        super().__init__(message)
+        self.status_code = status_code
+        self.code = code
+        self.retriable = retriable
+        self.details = details
 
 
 class SyncCancelledError(ConnectorError):
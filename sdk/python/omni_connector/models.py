@@ -16,6 +16,26 @@ class EventType(str, Enum):
     DOCUMENT_DELETED = "document_deleted"
 
 
+class DocumentThumbnail(BaseModel):
+    """A thumbnail image stored with `store_binary_content`."""
+
+    content_id: str
+    mime_type: str
+
+
+class RelationshipType(str, Enum):
+    ATTACHMENT = "attachment"
+    COMMENT = "comment"
+    TRANSCRIPT_SEGMENT = "transcript_segment"
+
+
+class DocumentParent(BaseModel):
+    """The document of the same source a document belongs to, by external ID."""
+
+    external_id: str
+    relationship: RelationshipType
+
+
 class DocumentMetadata(BaseModel):
     title: str | None = None
     author: str | None = None
@@ -26,6 +46,8 @@ class DocumentMetadata(BaseModel):
     url: str | None = None
     path: str | None = None
     extra: dict[str, Any] | None = None
+    thumbnail: DocumentThumbnail | None = None
+    parent: DocumentParent | None = None
 
 
 class DocumentPermissions(BaseModel):
@@ -101,6 +123,10 @@ class SyncRequest(BaseModel):
     sync_run_id: str
     source_id: str
     sync_mode: str
+    # Type of the source, for the connector's logs and error reports
+    source_type: str | None = None
+    # Token to authenticate the sync's calls to the connector manager with
+    sdk_token: str | None = None
 
 
 class SyncResponse(BaseModel):
@@ -47,6 +47,15 @@ def create_app(connector: "Connector") -> FastAPI:
     )
     server = ConnectorServer(connector)
 
+    @app.on_event("shutdown")
+    async def pause_active_syncs() -> None:
+        for ctx in list(server.active_syncs.values()):
+            logger.info("Pausing sync %s for shutdown", ctx.sync_run_id)
+            try:
+                await ctx.pause()
+            except Exception as e:
+                logger.error("Failed to pause sync %s: %s", ctx.sync_run_id, e)
+
     @app.get("/health")
     async def health() -> dict[str, str]:
         return {"status": "healthy", "service": connector.name}
@@ -74,6 +83,7 @@ def create_app(connector: "Connector") -> FastAPI:
                 ).model_dump(),
             )
 
+        server.sdk_client.use_token(request)
         try:
             data = await server.sdk_client.fetch_source_config(source_id)
             source_config = data["config"]
@@ -81,8 +91,7 @@ def create_app(connector: "Connector") -> FastAPI:
             state = data.get("connector_state")
             source_type = data.get("source_type")
         except SdkClientError as e:
-            error_msg = str(e)
-            if "404" in error_msg:
+            if e.code == "not_found" or "404" in str(e):
                 return JSONResponse(
                     status_code=status.HTTP_404_NOT_FOUND,
                     content=SyncResponse.error(
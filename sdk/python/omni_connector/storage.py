@@ -2,6 +2,8 @@ import base64
 import logging
 from typing import TYPE_CHECKING
 
+from .models import DocumentThumbnail
+
 if TYPE_CHECKING:
     from .client import SdkClient
 
@@ -42,3 +44,12 @@ class ContentStorage:
             encoded,
             content_type,
         )
+
+    async def save_thumbnail(self, image: bytes, mime_type: str) -> DocumentThumbnail:
+        """Store a document's thumbnail image as is, for `DocumentMetadata.thumbnail`."""
+        content_id = await self._client.store_binary_content(
+            self._sync_run_id,
+            image,
+            mime_type,
+        )
+        return DocumentThumbnail(content_id=content_id, mime_type=mime_type)
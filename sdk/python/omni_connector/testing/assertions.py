@@ -30,6 +30,17 @@ async def wait_for_sync(
     raise TimeoutError(f"sync_run {sync_run_id} still running after {timeout}s")
 
 
+# Events staged in the outbox that the relay has not yet moved to the queue count too. The
+# relay moves an event in a single statement, so one query sees each event exactly once.
+_EVENTS_SQL = (
+    "SELECT id, sync_run_id, source_id, event_type, payload, status, created_at "
+    "FROM connector_events_queue WHERE source_id = $1::char(26) "
+    "UNION ALL "
+    "SELECT id, sync_run_id, source_id, event_type, payload, 'pending', created_at "
+    "FROM connector_events_outbox WHERE source_id = $1::char(26)"
+)
+
+
 async def count_events(
     pool: asyncpg.Pool,
     source_id: str,
@@ -37,15 +48,14 @@ async def count_events(
 ) -> int:
     if event_type:
         row = await pool.fetchrow(
-            "SELECT count(*) AS cnt FROM connector_events_queue "
-            "WHERE source_id = $1::char(26) AND event_type = $2",
+            f"SELECT count(*) AS cnt FROM ({_EVENTS_SQL}) events "
+            "WHERE event_type = $2",
             source_id,
             event_type,
         )
     else:
         row = await pool.fetchrow(
-            "SELECT count(*) AS cnt FROM connector_events_queue "
-            "WHERE source_id = $1::char(26)",
+            f"SELECT count(*) AS cnt FROM ({_EVENTS_SQL}) events",
             source_id,
         )
     return row["cnt"] if row else 0
@@ -56,8 +66,7 @@ async def get_events(
     source_id: str,
 ) -> list[dict[str, Any]]:
     rows = await pool.fetch(
-        "SELECT * FROM connector_events_queue "
-        "WHERE source_id = $1::char(26) ORDER BY created_at",
+        f"SELECT * FROM ({_EVENTS_SQL}) events ORDER BY created_at",
         source_id,
     )
     results = []
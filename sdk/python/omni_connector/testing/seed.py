@@ -8,6 +8,8 @@ from typing import Any
 import asyncpg
 import ulid
 
+from .assertions import _EVENTS_SQL
+
 
 def _new_ulid() -> str:
     return str(ulid.ULID())
@@ -95,7 +97,7 @@ class SeedHelper:
 
     async def get_events(self, source_id: str) -> list[asyncpg.Record]:
         return await self._pool.fetch(
-            "SELECT * FROM connector_events_queue WHERE source_id = $1::char(26) ORDER BY created_at",
+            f"SELECT * FROM ({_EVENTS_SQL}) events ORDER BY created_at",
             source_id,
         )
 
@@ -120,6 +122,10 @@ class SeedHelper:
             "DELETE FROM connector_events_queue WHERE source_id = $1::char(26)",
             source_id,
         )
+        await self._pool.execute(
+            "DELETE FROM connector_events_outbox WHERE source_id = $1::char(26)",
+            source_id,
+        )
         await self._pool.execute(
             "DELETE FROM sync_runs WHERE source_id = $1::char(26)", source_id
         )
@@ -33,6 +33,10 @@ def mock_connector_manager():
             return_value=Response(200, json={"status": "ok"})
         )
 
+        respx_mock.post(path__regex=r"/sdk/sync/.*/pause").mock(
+            return_value=Response(200, json={"status": "ok"})
+        )
+
         yield respx_mock
 
 
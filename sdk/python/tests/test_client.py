@@ -9,6 +9,7 @@ from omni_connector import (
     DocumentPermissions,
     EventType,
 )
+from omni_connector.client import SdkClient
 from omni_connector.exceptions import SdkClientError
 
 
@@ -142,6 +143,18 @@ async def test_fail_sends_error_message(sdk_client, mock_connector_manager):
     assert payload["error"] == "Connection timeout after 30s"
 
 
+@pytest.mark.asyncio
+async def test_pause_sends_state(sdk_client, mock_connector_manager):
+    """Verify pausing saves the state to resume from."""
+    await sdk_client.pause("sync-123", new_state={"cursor": "abc123"})
+
+    call = mock_connector_manager.calls[0]
+    assert "/sdk/sync/sync-123/pause" in str(call.request.url)
+
+    payload = json.loads(call.request.content)
+    assert payload["new_state"] == {"cursor": "abc123"}
+
+
 @pytest.mark.asyncio
 async def test_heartbeat_uses_correct_url(sdk_client, mock_connector_manager):
     """Verify heartbeat hits the right endpoint."""
@@ -212,6 +225,35 @@ async def test_store_content_raises_on_error(mock_connector_manager, monkeypatch
     assert "413" in str(exc_info.value)
 
 
+@pytest.mark.asyncio
+async def test_error_carries_code_from_response(mock_connector_manager, monkeypatch):
+    """Verify errors answered by connector-manager keep their code and retriable flag."""
+    monkeypatch.setenv("CONNECTOR_MANAGER_URL", "http://localhost:9000")
+
+    mock_connector_manager.post("/sdk/sync/sync-123/heartbeat").mock(
+        return_value=Response(
+            409,
+            json={
+                "error": "Sync already running for source: src-1",
+                "code": "conflict",
+                "retriable": True,
+            },
+        )
+    )
+
+    from omni_connector import SdkClient
+
+    client = SdkClient.from_env()
+
+    with pytest.raises(SdkClientError) as exc_info:
+        await client.heartbeat("sync-123")
+
+    assert exc_info.value.status_code == 409
+    assert exc_info.value.code == "conflict"
+    assert exc_info.value.retriable is True
+    assert exc_info.value.details is None
+
+
 @pytest.mark.asyncio
 async def test_fetch_source_config_sends_correct_request(
     sdk_client, mock_connector_manager
@@ -279,3 +321,14 @@ def test_client_strips_trailing_slash(monkeypatch):
     client = SdkClient.from_env()
 
     assert client.base_url == "http://localhost:9000"
+
+
+@pytest.mark.asyncio
+async def test_incomplete_service_tls_config_is_rejected(monkeypatch):
+    monkeypatch.setenv("SERVICE_TLS_CERT_PATH", "/certs/connector.pem")
+    monkeypatch.delenv("SERVICE_TLS_KEY_PATH", raising=False)
+    monkeypatch.delenv("SERVICE_TLS_CA_PATH", raising=False)
+
+    client = SdkClient(base_url="http://localhost:9000")
+    with pytest.raises(ValueError, match="must be set together"):
+        await client._get_client()
@@ -242,6 +242,26 @@ def test_cancellation_flag():
     assert ctx.is_cancelled() is True
 
 
+@pytest.mark.asyncio
+async def test_pause_saves_state_and_skips_failure(sdk_client, mock_connector_manager):
+    """Verify pausing saves the checkpointed state and later failures aren't reported."""
+    ctx = SyncContext(
+        sdk_client=sdk_client,
+        sync_run_id="sync-123",
+        source_id="source-456",
+    )
+    ctx._state = {"cursor": "page-7"}
+
+    await ctx.pause()
+    await ctx.fail("Connection closed")
+
+    assert ctx.is_cancelled() is True
+    assert len(mock_connector_manager.calls) == 1
+    call = mock_connector_manager.calls[0]
+    assert "/sdk/sync/sync-123/pause" in str(call.request.url)
+    assert json.loads(call.request.content)["new_state"] == {"cursor": "page-7"}
+
+
 def test_context_exposes_properties():
     """Verify context properties are accessible."""
 
@@ -1,10 +1,32 @@
 import { SdkClientError, ConfigurationError } from './errors.js';
-import type { ConnectorEventPayload } from './models.js';
+import type { ApiErrorBody } from './errors.js';
+import type { ConnectorEventPayload, SyncRequest } from './models.js';
 import { serializeConnectorEvent } from './models.js';
 
+/** Error for a failed response, with the code connector-manager answered it with. */
+async function responseError(action: string, response: Response): Promise<SdkClientError> {
+  const text = await response.text();
+  let body: ApiErrorBody = {};
+  try {
+    const parsed: unknown = JSON.parse(text);
+    if (parsed && typeof parsed === 'object') {
+      body = parsed as ApiErrorBody;
+    }
+  } catch {
+    // Not an error answered by connector-manager
+  }
+  return new SdkClientError(`${action}: ${response.status} - ${text}`, response.status, {
+    code: body.code,
+    retriable: body.retriable ?? false,
+    details: body.details,
+  });
+}
+
 export class SdkClient {
   private readonly baseUrl: string;
   private readonly timeout: number;
+  // Source tokens sent with sync requests, by source and sync run
+  private readonly tokens = new Map<string, string>();
 
   constructor(baseUrl?: string, timeout = 30000) {
     const url = baseUrl ?? process.env.CONNECTOR_MANAGER_URL;
@@ -19,6 +41,17 @@ export class SdkClient {
     return new SdkClient();
   }
 
+  /**
+   * Authenticate calls for the source and sync run of a sync request with the token sent
+   * with it.
+   */
+  useToken(request: SyncRequest): void {
+    if (request.sdk_token) {
+      this.tokens.set(request.source_id, request.sdk_token);
+      this.tokens.set(request.sync_run_id, request.sdk_token);
+    }
+  }
+
   async emitEvent(
     syncRunId: string,
     sourceId: string,
@@ -30,13 +63,9 @@ export class SdkClient {
       event: serializeConnectorEvent(event),
     };
 
-    const response = await this.post('/sdk/events', payload);
+    const response = await this.post('/sdk/events', sourceId, payload);
     if (!response.ok) {
-      const text = await response.text();
-      throw new SdkClientError(
-        `Failed to emit event: ${response.status} - ${text}`,
-        response.status
-      );
+      throw await responseError('Failed to emit event', response);
     }
   }
 
@@ -51,13 +80,9 @@ export class SdkClient {
       content_type: contentType,
     };
 
-    const response = await this.post('/sdk/content', payload);
+    const response = await this.post('/sdk/content', syncRunId, payload);
     if (!response.ok) {
-      const text = await response.text();
-      throw new SdkClientError(
-        `Failed to store content: ${response.status} - ${text}`,
-        response.status
-      );
+      throw await responseError('Failed to store content', response);
     }
 
     const data = (await response.json()) as { content_id: string };
@@ -65,24 +90,16 @@ export class SdkClient {
   }
 
   async heartbeat(syncRunId: string): Promise<void> {
-    const response = await this.post(`/sdk/sync/${syncRunId}/heartbeat`);
+    const response = await this.post(`/sdk/sync/${syncRunId}/heartbeat`, syncRunId);
     if (!response.ok) {
-      const text = await response.text();
-      throw new SdkClientError(
-        `Failed to heartbeat: ${response.status} - ${text}`,
-        response.status
-      );
+      throw await responseError('Failed to heartbeat', response);
     }
   }
 
   async incrementScanned(syncRunId: string): Promise<void> {
-    const response = await this.post(`/sdk/sync/${syncRunId}/scanned`);
+    const response = await this.post(`/sdk/sync/${syncRunId}/scanned`, syncRunId);
     if (!response.ok) {
-      const text = await response.text();
-      throw new SdkClientError(
-        `Failed to increment scanned: ${response.status} - ${text}`,
-        response.status
-      );
+      throw await responseError('Failed to increment scanned', response);
     }
   }
 
@@ -100,24 +117,28 @@ export class SdkClient {
       payload.new_state = newState;
     }
 
-    const response = await this.post(`/sdk/sync/${syncRunId}/complete`, payload);
+    const response = await this.post(`/sdk/sync/${syncRunId}/complete`, syncRunId, payload);
     if (!response.ok) {
-      const text = await response.text();
-      throw new SdkClientError(
-        `Failed to complete: ${response.status} - ${text}`,
-        response.status
-      );
+      throw await responseError('Failed to complete', response);
     }
   }
 
   async fail(syncRunId: string, error: string): Promise<void> {
-    const response = await this.post(`/sdk/sync/${syncRunId}/fail`, { error });
+    const response = await this.post(`/sdk/sync/${syncRunId}/fail`, syncRunId, { error });
     if (!response.ok) {
-      const text = await response.text();
-      throw new SdkClientError(
-        `Failed to mark as failed: ${response.status} - ${text}`,
-        response.status
-      );
+      throw await responseError('Failed to mark as failed', response);
+    }
+  }
+
+  async pause(syncRunId: string, newState?: Record<string, unknown>): Promise<void> {
+    const payload: Record<string, unknown> = {};
+    if (newState !== undefined) {
+      payload.new_state = newState;
+    }
+
+    const response = await this.post(`/sdk/sync/${syncRunId}/pause`, syncRunId, payload);
+    if (!response.ok) {
+      throw await responseError('Failed to pause', response);
     }
   }
 
@@ -126,13 +147,9 @@ export class SdkClient {
     credentials: Record<string, unknown>;
     connector_state: Record<string, unknown> | null;
   }> {
-    const response = await this.get(`/sdk/source/${sourceId}/sync-config`);
+    const response = await this.get(`/sdk/source/${sourceId}/sync-config`, sourceId);
     if (!response.ok) {
-      const text = await response.text();
-      throw new SdkClientError(
-        `Failed to fetch source config: ${response.status} - ${text}`,
-        response.status
-      );
+      throw await responseError('Failed to fetch source config', response);
     }
     return response.json() as Promise<{
       config: Record<string, unknown>;
@@ -141,20 +158,27 @@ export class SdkClient {
     }>;
   }
 
-  private async get(path: string): Promise<Response> {
+  private authHeaders(scope: string): Record<string, string> {
+    const token = this.tokens.get(scope);
+    return token ? { Authorization: `Bearer ${token}` } : {};
+  }
+
+  private async get(path: string, scope: string): Promise<Response> {
     const url = `${this.baseUrl}${path}`;
     return fetch(url, {
       method: 'GET',
+      headers: this.authHeaders(scope),
       signal: AbortSignal.timeout(this.timeout),
     });
   }
 
-  private async post(path: string, body?: unknown): Promise<Response> {
+  private async post(path: string, scope: string, body?: unknown): Promise<Response> {
     const url = `${this.baseUrl}${path}`;
     const options: RequestInit = {
       method: 'POST',
       headers: {
         'Content-Type': 'application/json',
+        ...this.authHeaders(scope),
       },
       signal: AbortSignal.timeout(this.timeout),
     };
@@ -52,8 +52,16 @@ export abstract class Connector {
     const host = options.host ?? '0.0.0.0';
 
     const app = createServer(this);
-    app.listen(port, host, () => {
+    const server = app.listen(port, host, () => {
       console.log(`Connector ${this.name} v${this.version} listening on ${host}:${port}`);
     });
+
+    process.once('SIGTERM', () => {
+      console.log(`Connector ${this.name} shutting down`);
+      server.close();
+      // Exit once syncs are paused, or after as long as the Rust services drain requests
+      setTimeout(() => process.exit(0), 30_000).unref();
+      void app.pauseActiveSyncs().finally(() => process.exit(0));
+    });
   }
 }
@@ -16,6 +16,7 @@ export class SyncContext {
   private readonly abortController: AbortController;
   private _documentsEmitted = 0;
   private _documentsScanned = 0;
+  private _paused = false;
   private readonly _contentStorage: ContentStorage;
 
   constructor(
@@ -123,9 +124,21 @@ export class SyncContext {
   }
 
   async fail(error: string): Promise<void> {
+    if (this._paused) {
+      // Failures after pausing are the shutdown's doing, not the sync's
+      console.log(`Not failing paused sync ${this._syncRunId}: ${error}`);
+      return;
+    }
     await this.client.fail(this._syncRunId, error);
   }
 
+  /** Stop the sync as the connector shuts down, to be resumed from the last saved state. */
+  async pause(): Promise<void> {
+    this._paused = true;
+    this.abortController.abort();
+    await this.client.pause(this._syncRunId, this._state);
+  }
+
   isCancelled(): boolean {
     return this.abortController.signal.aborted;
   }
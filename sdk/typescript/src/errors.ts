@@ -5,13 +5,36 @@ export class ConnectorError extends Error {
   }
 }
 
+/** Body of an error answered by connector-manager. */
+export interface ApiErrorBody {
+  error?: string;
+  code?: string;
+  retriable?: boolean;
+  details?: unknown;
+}
+
+/**
+ * Error communicating with connector-manager SDK endpoints. For errors answered by
+ * connector-manager, `code`, `retriable` and `details` are those of its error body, such as
+ * `not_found`.
+ */
 export class SdkClientError extends ConnectorError {
   public readonly statusCode?: number;
+  public readonly code?: string;
+  public readonly retriable: boolean;
+  public readonly details?: unknown;
 
-  constructor(message: string, statusCode?: number) {
+  constructor(
+    message: string,
+    statusCode?: number,
+    body: Pick<ApiErrorBody, 'code' | 'retriable' | 'details'> = {}
+  ) {
     super(message);
     this.name = 'SdkClientError';
     this.statusCode = statusCode;
+    this.code = body.code;
+    this.retriable = body.retriable ?? false;
+    this.details = body.details;
   }
 }
 
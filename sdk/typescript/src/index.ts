@@ -2,7 +2,7 @@ export { Connector, type ServeOptions } from './connector.js';
 export { SyncContext } from './context.js';
 export { ContentStorage } from './storage.js';
 export { SdkClient } from './client.js';
-export { createServer } from './server.js';
+export { createServer, type ConnectorServer } from './server.js';
 
 export {
   SyncMode,
@@ -47,4 +47,5 @@ export {
   SdkClientError,
   SyncCancelledError,
   ConfigurationError,
+  type ApiErrorBody,
 } from './errors.js';
@@ -81,6 +81,8 @@ export const SyncRequestSchema = z.object({
   sync_run_id: z.string(),
   source_id: z.string(),
   sync_mode: z.string(),
+  // Token to authenticate the sync's calls to the connector manager with
+  sdk_token: z.string().optional(),
 });
 export type SyncRequest = z.infer<typeof SyncRequestSchema>;
 
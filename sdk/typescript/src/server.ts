@@ -12,7 +12,12 @@ import {
   createActionResponseFailure,
 } from './models.js';
 
-export function createServer(connector: Connector): Express {
+/** A connector's app, with the syncs it runs to pause when shutting down. */
+export type ConnectorServer = Express & {
+  pauseActiveSyncs(): Promise<void>;
+};
+
+export function createServer(connector: Connector): ConnectorServer {
   const app = express();
   app.use(express.json());
 
@@ -57,6 +62,7 @@ export function createServer(connector: Connector): Express {
       credentials: Record<string, unknown>;
       state: Record<string, unknown> | null;
     };
+    getSdkClient().useToken(parseResult.data);
     try {
       const data = await getSdkClient().fetchSourceConfig(sourceId);
       sourceData = {
@@ -153,5 +159,16 @@ export function createServer(connector: Connector): Express {
     }
   });
 
-  return app;
+  const pauseActiveSyncs = async (): Promise<void> => {
+    for (const ctx of activeSyncs.values()) {
+      console.log(`Pausing sync ${ctx.syncRunId} for shutdown`);
+      try {
+        await ctx.pause();
+      } catch (error) {
+        console.error(`Failed to pause sync ${ctx.syncRunId}:`, error);
+      }
+    }
+  };
+
+  return Object.assign(app, { pauseActiveSyncs });
 }
@@ -2,6 +2,7 @@ import { describe, it, expect, beforeAll, afterAll, afterEach, vi } from 'vitest
 import { http, HttpResponse } from 'msw';
 import { setupServer } from 'msw/node';
 import { SdkClient } from '../src/client.js';
+import { SdkClientError } from '../src/errors.js';
 import { EventType, type ConnectorEventPayload } from '../src/models.js';
 
 const BASE_URL = 'http://test-connector-manager:8080';
@@ -216,6 +217,26 @@ describe('SdkClient', () => {
     });
   });
 
+  describe('pause', () => {
+    it('sends the state to resume from', async () => {
+      let capturedBody: unknown;
+
+      server.use(
+        http.post(`${BASE_URL}/sdk/sync/:id/pause`, async ({ request }) => {
+          capturedBody = await request.json();
+          return HttpResponse.json({ success: true });
+        })
+      );
+
+      const client = new SdkClient(BASE_URL);
+      await client.pause('sync-123', { cursor: 'page-7' });
+
+      expect(capturedBody).toEqual({
+        new_state: { cursor: 'page-7' },
+      });
+    });
+  });
+
   describe('fetchSourceConfig', () => {
     it('sends correct request and parses response', async () => {
       const mockData = {
@@ -277,5 +298,26 @@ describe('SdkClient', () => {
         client.emitEvent('sync-123', 'source-456', event)
       ).rejects.toThrow('Failed to emit event: 500');
     });
+
+    it('keeps the code and retriable flag of the error', async () => {
+      server.use(
+        http.post(`${BASE_URL}/sdk/sync/:syncRunId/heartbeat`, () => {
+          return HttpResponse.json(
+            {
+              error: 'Sync already running for source: src-1',
+              code: 'conflict',
+              retriable: true,
+            },
+            { status: 409 }
+          );
+        })
+      );
+
+      const client = new SdkClient(BASE_URL);
+      const error = await client.heartbeat('sync-123').catch((e: unknown) => e);
+
+      expect(error).toBeInstanceOf(SdkClientError);
+      expect(error).toMatchObject({ statusCode: 409, code: 'conflict', retriable: true });
+    });
   });
 });
@@ -78,6 +78,11 @@ EMBEDDING_API_KEY = get_optional_env("EMBEDDING_API_KEY", "")
 EMBEDDING_API_URL = get_optional_env("EMBEDDING_API_URL", "")
 EMBEDDING_MAX_MODEL_LEN = int(get_optional_env("EMBEDDING_MAX_MODEL_LEN", "8192"))
 
+# Reranker configuration, reranking is off without an API URL
+RERANK_API_URL = get_optional_env("RERANK_API_URL", "")
+RERANK_MODEL = get_optional_env("RERANK_MODEL", "")
+RERANK_API_KEY = get_optional_env("RERANK_API_KEY", "")
+
 DEFAULT_MAX_TOKENS = int(get_optional_env("DEFAULT_MAX_TOKENS", "8192"))
 DEFAULT_TEMPERATURE = float(get_optional_env("DEFAULT_TEMPERATURE", "0.0"))
 DEFAULT_TOP_P = float(get_optional_env("DEFAULT_TOP_P", "1.0"))
@@ -11,26 +11,31 @@ from .connection import get_db_pool
 logger = logging.getLogger(__name__)
 
 
-async def fetch_embedding_config() -> Optional[dict]:
-    """Fetch embedding configuration from database"""
+async def fetch_embedding_settings() -> Optional[dict]:
+    """Fetch the embedding configuration and the embedding model migration, if any.
+
+    Both are read in one query, so a migration being finalized (which replaces the
+    configuration and drops the migration in one transaction) is never seen half done.
+    Returns a dict keyed by configuration key, or None if the database is unavailable.
+    """
     try:
         pool = await get_db_pool()
         async with pool.acquire() as conn:
-            row = await conn.fetchrow(
+            rows = await conn.fetch(
                 """
-                SELECT value
+                SELECT key, value
                 FROM configuration
-                WHERE key = 'embedding_config'
-                LIMIT 1
+                WHERE key IN ('embedding_config', 'embedding_migration')
                 """
             )
 
-            if row and row["value"]:
+            settings = {}
+            for row in rows:
                 value = row["value"]
                 if isinstance(value, str):
-                    return json.loads(value)
-                return value
-            return None
+                    value = json.loads(value)
+                settings[row["key"]] = value
+            return settings
     except Exception as e:
-        logger.warning(f"Failed to fetch embedding config from database: {e}")
+        logger.warning(f"Failed to fetch embedding settings from database: {e}")
         return None
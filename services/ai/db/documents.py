@@ -97,3 +97,29 @@ class DocumentsRepository:
         logger.info(
             f"Updated {len(document_ids)} documents to embedding_status: {status}"
         )
+
+    async def get_ids_missing_embeddings(
+        self, model_name: str, limit: int, exclude_ids: List[str]
+    ) -> List[str]:
+        """Get IDs of embedded documents that have no embeddings of the given model"""
+        pool = await self._get_pool()
+
+        rows = await pool.fetch(
+            """
+            SELECT d.id
+            FROM documents d
+            WHERE d.embedding_status = 'completed'
+              AND d.content_id IS NOT NULL
+              AND NOT (d.id = ANY($3))
+              AND NOT EXISTS (
+                  SELECT 1 FROM embeddings e
+                  WHERE e.document_id = d.id AND e.model_name = $1
+              )
+            ORDER BY d.id
+            LIMIT $2
+            """,
+            model_name,
+            limit,
+            exclude_ids,
+        )
+        return [row["id"] for row in rows]
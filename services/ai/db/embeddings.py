@@ -20,6 +20,7 @@ class Embedding:
     chunk_index: int
     chunk_start_offset: int
     chunk_end_offset: int
+    content_sha256: Optional[str]
     embedding: list
     model_name: str
     dimensions: int
@@ -44,7 +45,7 @@ class EmbeddingsRepository:
         rows = await pool.fetch(
             """
             SELECT id, document_id, chunk_index, chunk_start_offset, chunk_end_offset,
-                   embedding, model_name, dimensions
+                   content_sha256, embedding, model_name, dimensions
             FROM embeddings
             WHERE document_id = $1
             ORDER BY chunk_index
@@ -53,8 +54,10 @@ class EmbeddingsRepository:
         )
         return [Embedding(**dict(row)) for row in rows]
 
-    async def delete_for_documents(self, document_ids: List[str]) -> None:
-        """Delete existing embeddings for documents"""
+    async def delete_for_documents(
+        self, document_ids: List[str], model_name: Optional[str] = None
+    ) -> None:
+        """Delete existing embeddings for documents, only those of `model_name` if given"""
         if not document_ids:
             return
 
@@ -64,8 +67,10 @@ class EmbeddingsRepository:
             """
             DELETE FROM embeddings
             WHERE document_id = ANY($1)
+              AND ($2::text IS NULL OR model_name = $2)
             """,
             document_ids,
+            model_name,
         )
         logger.info(f"Deleted existing embeddings for {len(document_ids)} documents")
 
@@ -78,6 +83,7 @@ class EmbeddingsRepository:
         - chunk_index: int
         - chunk_start_offset: int
         - chunk_end_offset: int
+        - content_sha256: str (optional), checksum of the content the offsets refer to
         - embedding: List[float]
         - model_name: str
         - dimensions: int
@@ -96,6 +102,7 @@ class EmbeddingsRepository:
                 emb["chunk_index"],
                 emb["chunk_start_offset"],
                 emb["chunk_end_offset"],
+                emb.get("content_sha256"),
                 emb["embedding"],
                 emb["model_name"],
                 emb["dimensions"],
@@ -114,6 +121,7 @@ class EmbeddingsRepository:
                 "chunk_index",
                 "chunk_start_offset",
                 "chunk_end_offset",
+                "content_sha256",
                 "embedding",
                 "model_name",
                 "dimensions",
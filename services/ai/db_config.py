@@ -4,6 +4,7 @@ Provides cached access to configuration stored in PostgreSQL,
 with fallback to environment variables.
 """
 
+import logging
 import time
 from typing import Optional
 from dataclasses import dataclass
@@ -16,7 +17,9 @@ from config import (
     EMBEDDING_DIMENSIONS,
     EMBEDDING_MAX_MODEL_LEN,
 )
-from db import fetch_embedding_config
+from db import fetch_embedding_settings
+
+logger = logging.getLogger(__name__)
 
 
 # =============================================================================
@@ -49,13 +52,44 @@ def parse_embedding_config(data: dict) -> EmbeddingConfig:
     )
 
 
+@dataclass
+class EmbeddingMigration:
+    """A blue/green migration to another embedding model.
+
+    Until cutover the current model serves searches and the target model is the shadow,
+    after cutover the roles swap. Both models' embeddings are written either way.
+    """
+
+    target: EmbeddingConfig
+    shadow_search_percentage: float = 0
+    cutover: bool = False
+
+
+def parse_embedding_migration(data: dict) -> EmbeddingMigration:
+    target = data.get("target")
+    if not target:
+        raise ValueError("Embedding migration missing 'target' field")
+
+    return EmbeddingMigration(
+        target=parse_embedding_config(target),
+        shadow_search_percentage=float(data.get("shadowSearchPercentage") or 0),
+        cutover=bool(data.get("cutover", False)),
+    )
+
+
+@dataclass
+class EmbeddingSettings:
+    config: EmbeddingConfig
+    migration: Optional[EmbeddingMigration] = None
+
+
 class EmbeddingConfigCache:
     """Cached embedding configuration reader with PostgreSQL backend."""
 
     CACHE_TTL_SECONDS = 90
 
     def __init__(self):
-        self._cache: Optional[EmbeddingConfig] = None
+        self._cache: Optional[EmbeddingSettings] = None
         self._cache_timestamp: float = 0
 
     def _is_cache_valid(self) -> bool:
@@ -64,11 +98,27 @@ class EmbeddingConfigCache:
         elapsed = time.time() - self._cache_timestamp
         return elapsed < self.CACHE_TTL_SECONDS
 
-    async def _fetch_from_database(self) -> Optional[EmbeddingConfig]:
-        config_data = await fetch_embedding_config()
-        if config_data:
-            return parse_embedding_config(config_data)
-        return None
+    async def _fetch_from_database(self) -> Optional[EmbeddingSettings]:
+        settings = await fetch_embedding_settings()
+        if not settings:
+            return None
+
+        config_data = settings.get("embedding_config")
+        config = (
+            parse_embedding_config(config_data)
+            if config_data
+            else self._get_env_fallback_config()
+        )
+
+        migration = None
+        migration_data = settings.get("embedding_migration")
+        if migration_data:
+            try:
+                migration = parse_embedding_migration(migration_data)
+            except ValueError as e:
+                logger.error(f"Ignoring invalid embedding migration: {e}")
+
+        return EmbeddingSettings(config=config, migration=migration)
 
     def _get_env_fallback_config(self) -> EmbeddingConfig:
         return EmbeddingConfig(
@@ -80,20 +130,20 @@ class EmbeddingConfigCache:
             max_model_len=EMBEDDING_MAX_MODEL_LEN,
         )
 
-    async def get_config(self) -> EmbeddingConfig:
+    async def get_settings(self) -> EmbeddingSettings:
         if self._is_cache_valid():
             return self._cache  # type: ignore
 
-        db_config = await self._fetch_from_database()
-        if db_config is not None:
-            self._cache = db_config
+        db_settings = await self._fetch_from_database()
+        if db_settings is not None:
+            self._cache = db_settings
             self._cache_timestamp = time.time()
-            return db_config
+            return db_settings
 
-        env_config = self._get_env_fallback_config()
-        self._cache = env_config
+        env_settings = EmbeddingSettings(config=self._get_env_fallback_config())
+        self._cache = env_settings
         self._cache_timestamp = time.time()
-        return env_config
+        return env_settings
 
     def invalidate_cache(self):
         self._cache = None
@@ -105,7 +155,12 @@ _embedding_config_cache = EmbeddingConfigCache()
 
 
 async def get_embedding_config() -> EmbeddingConfig:
-    return await _embedding_config_cache.get_config()
+    settings = await _embedding_config_cache.get_settings()
+    return settings.config
+
+
+async def get_embedding_settings() -> EmbeddingSettings:
+    return await _embedding_config_cache.get_settings()
 
 
 def invalidate_embedding_config_cache():
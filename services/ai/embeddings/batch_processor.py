@@ -7,6 +7,7 @@ For other providers (local, openai, jina): Uses online API calls in batches
 """
 
 import asyncio
+import hashlib
 import logging
 import json
 import time
@@ -31,6 +32,7 @@ from config import (
 
 from processing.chunking import Chunker
 from . import Chunk
+from .rate_limit import background_embedding
 from db import (
     get_db_pool,
     DocumentsRepository,
@@ -42,6 +44,12 @@ from db import (
     QueueStatus,
 )
 
+def content_checksum(content_text: str) -> str:
+    """SHA-256 of a document's content, as the searcher computes it to check that chunk
+    offsets still apply to the content it fetched"""
+    return hashlib.sha256(content_text.encode("utf-8")).hexdigest()
+
+
 # Import boto3 and smart_open lazily for Bedrock provider
 boto3 = None
 smart_open = None
@@ -252,7 +260,7 @@ class EmbeddingBatchProcessor:
         embeddings_repo: EmbeddingsRepository,
         batch_jobs_repo: EmbeddingBatchJobsRepository,
         content_storage,
-        embedding_provider,
+        embedding_providers,
         provider_type: str,
     ):
         self.documents_repo = documents_repo
@@ -260,7 +268,7 @@ class EmbeddingBatchProcessor:
         self.embeddings_repo = embeddings_repo
         self.batch_jobs_repo = batch_jobs_repo
         self.content_storage = content_storage
-        self.embedding_provider = embedding_provider
+        self.embedding_providers = embedding_providers
         self.provider_type = provider_type
 
         # Only initialize Bedrock-specific components when needed
@@ -289,6 +297,10 @@ class EmbeddingBatchProcessor:
         self._baseline_failed = 0
         self._last_progress_log_time: Optional[float] = None
 
+        # Documents the shadow backfill failed to embed, skipped until the target model changes
+        self._backfill_model: Optional[str] = None
+        self._backfill_failed: set[str] = set()
+
     def _create_storage_client(self) -> StorageClient:
         """Factory for storage client (Bedrock only)"""
         if EMBEDDING_BATCH_S3_BUCKET:
@@ -315,12 +327,20 @@ class EmbeddingBatchProcessor:
         """Main processing loop - routes to appropriate handler based on provider"""
         logger.info(f"Starting embedding processor for provider: {self.provider_type}")
 
-        if self.provider_type == "bedrock":
-            # Use cloud batch inference for Bedrock
-            await asyncio.gather(self.accumulation_loop(), self.monitoring_loop())
-        else:
-            # Use online processing for other providers (local, openai, jina)
-            await self.online_processing_loop()
+        # Document embedding yields to interactive requests within the rate limits
+        with background_embedding():
+            if self.provider_type == "bedrock":
+                # Use cloud batch inference for Bedrock
+                await asyncio.gather(
+                    self.accumulation_loop(),
+                    self.monitoring_loop(),
+                    self.shadow_backfill_loop(),
+                )
+            else:
+                # Use online processing for other providers (local, openai, jina)
+                await asyncio.gather(
+                    self.online_processing_loop(), self.shadow_backfill_loop()
+                )
 
     # ------------------------------------------------------------------------
     # Online Processing Loop (for local, openai, jina providers)
@@ -421,44 +441,9 @@ class EmbeddingBatchProcessor:
                 self._docs_failed += 1
                 return
 
-            # Generate embeddings using sliding window over the document
             try:
-                window_size = (
-                    EMBEDDING_MAX_MODEL_LEN * 3
-                )  # TODO: address 3 chars per token assumption here
-                overlap = window_size // 4
-                stride = window_size - overlap
-
-                all_chunks = []
-                offset = 0
-                while offset < len(content_text):
-                    piece = content_text[offset : offset + window_size]
-                    t0 = time.monotonic()
-                    chunk_results = await self.embedding_provider.generate_embeddings(
-                        text=piece,
-                        task="passage",
-                        chunk_size=512,
-                        chunking_mode="sentence",
-                    )
-                    elapsed_ms = (time.monotonic() - t0) * 1000
-                    n_chunks = len(chunk_results) if chunk_results else 0
-                    logger.debug(
-                        f"generate_embeddings: {n_chunks} chunks in {elapsed_ms:.0f}ms "
-                        f"({len(piece)} chars)"
-                    )
-                    self._embedding_time_ms += elapsed_ms
-
-                    if chunk_results:
-                        for chunk in chunk_results:
-                            adjusted_span = (
-                                offset + chunk.span[0],
-                                offset + chunk.span[1],
-                            )
-                            all_chunks.append(Chunk(adjusted_span, chunk.embedding))
-
-                    offset += stride
-
-                chunks = all_chunks
+                providers = await self.embedding_providers.resolve()
+                chunks = await self._embed_text(providers.active, content_text)
 
                 # Handle empty chunks
                 if not chunks:
@@ -471,28 +456,39 @@ class EmbeddingBatchProcessor:
                     self._docs_failed += 1
                     return
 
-                # Delete existing embeddings for this document
+                # During an embedding model migration, also embed with the shadow model.
+                # Failing to is not fatal: the document is left without shadow embeddings
+                # and the shadow backfill retries it.
+                shadow_chunks = []
+                if providers.shadow:
+                    try:
+                        shadow_chunks = await self._embed_text(
+                            providers.shadow, content_text
+                        )
+                    except Exception as e:
+                        logger.warning(
+                            f"Shadow embedding with {providers.shadow.get_model_name()} "
+                            f"failed for {item.document_id}: {e}"
+                        )
+
+                # Delete existing embeddings for this document, of every model
                 await self.embeddings_repo.delete_for_documents([item.document_id])
 
-                # Prepare embeddings for bulk insert
-                embeddings_to_insert = []
-                for chunk_idx, chunk in enumerate(chunks):
-                    embeddings_to_insert.append(
-                        {
-                            "id": str(ulid.ULID()),
-                            "document_id": item.document_id,
-                            "chunk_index": chunk_idx,
-                            "chunk_start_offset": chunk.span[0],
-                            "chunk_end_offset": chunk.span[1],
-                            "embedding": chunk.embedding,
-                            "model_name": self.embedding_provider.get_model_name(),
-                            "dimensions": len(chunk.embedding),
-                        }
+                content_sha256 = content_checksum(content_text)
+                await self._write_embeddings(
+                    item.document_id,
+                    providers.active.get_model_name(),
+                    chunks,
+                    content_sha256,
+                )
+                if shadow_chunks:
+                    await self._write_embeddings(
+                        item.document_id,
+                        providers.shadow.get_model_name(),
+                        shadow_chunks,
+                        content_sha256,
                     )
 
-                # Bulk insert embeddings
-                await self.embeddings_repo.bulk_insert(embeddings_to_insert)
-
                 # Mark queue item completed
                 await self.queue_repo.mark_completed([item.id])
 
@@ -502,7 +498,7 @@ class EmbeddingBatchProcessor:
                 )
 
                 self._docs_completed += 1
-                self._embeddings_written += len(chunks)
+                self._embeddings_written += len(chunks) + len(shadow_chunks)
                 logger.info(
                     f"Processed document {item.document_id}: {len(chunks)} chunks embedded"
                 )
@@ -515,6 +511,156 @@ class EmbeddingBatchProcessor:
                 await self.queue_repo.mark_failed([item.id], str(e))
                 self._docs_failed += 1
 
+    async def _embed_text(self, embedding_provider, content_text: str) -> List[Chunk]:
+        """Embed a document's text using a sliding window over it"""
+        window_size = (
+            EMBEDDING_MAX_MODEL_LEN * 3
+        )  # TODO: address 3 chars per token assumption here
+        overlap = window_size // 4
+        stride = window_size - overlap
+
+        all_chunks = []
+        offset = 0
+        while offset < len(content_text):
+            piece = content_text[offset : offset + window_size]
+            t0 = time.monotonic()
+            chunk_results = await embedding_provider.generate_embeddings(
+                text=piece,
+                task="passage",
+                chunk_size=512,
+                chunking_mode="sentence",
+            )
+            elapsed_ms = (time.monotonic() - t0) * 1000
+            n_chunks = len(chunk_results) if chunk_results else 0
+            logger.debug(
+                f"generate_embeddings: {n_chunks} chunks in {elapsed_ms:.0f}ms "
+                f"({len(piece)} chars)"
+            )
+            self._embedding_time_ms += elapsed_ms
+
+            if chunk_results:
+                for chunk in chunk_results:
+                    adjusted_span = (
+                        offset + chunk.span[0],
+                        offset + chunk.span[1],
+                    )
+                    all_chunks.append(Chunk(adjusted_span, chunk.embedding))
+
+            offset += stride
+
+        return all_chunks
+
+    async def _write_embeddings(
+        self,
+        document_id: str,
+        model_name: str,
+        chunks: List[Chunk],
+        content_sha256: str,
+    ):
+        """Bulk insert one model's embeddings of a document, whose chunks were computed
+        against the content with the given checksum"""
+        embeddings_to_insert = []
+        for chunk_idx, chunk in enumerate(chunks):
+            embeddings_to_insert.append(
+                {
+                    "id": str(ulid.ULID()),
+                    "document_id": document_id,
+                    "chunk_index": chunk_idx,
+                    "chunk_start_offset": chunk.span[0],
+                    "chunk_end_offset": chunk.span[1],
+                    "content_sha256": content_sha256,
+                    "embedding": chunk.embedding,
+                    "model_name": model_name,
+                    "dimensions": len(chunk.embedding),
+                }
+            )
+
+        await self.embeddings_repo.bulk_insert(embeddings_to_insert)
+
+    # ------------------------------------------------------------------------
+    # Shadow Backfill (embedding model migrations)
+    # ------------------------------------------------------------------------
+    async def shadow_backfill_loop(self):
+        """Embed documents that have no embeddings of an embedding migration's target model.
+
+        Documents embedded while a migration is in progress get target embeddings from the
+        queue; this covers those embedded before it started.
+        """
+        while True:
+            try:
+                backfilled_any = await self._backfill_target_embeddings()
+                await asyncio.sleep(
+                    ONLINE_BATCH_DELAY if backfilled_any else ONLINE_POLL_INTERVAL
+                )
+            except Exception as e:
+                logger.error(f"Shadow backfill loop error: {e}", exc_info=True)
+                await asyncio.sleep(10)
+
+    async def _backfill_target_embeddings(self) -> bool:
+        """Embed a batch of documents with the migration target model.
+
+        Returns:
+            True if any documents were embedded, False if there was nothing to do.
+        """
+        providers = await self.embedding_providers.resolve()
+        if providers.target is None:
+            self._backfill_model = None
+            self._backfill_failed.clear()
+            return False
+
+        target = providers.target
+        model_name = target.get_model_name()
+        if model_name != self._backfill_model:
+            self._backfill_model = model_name
+            self._backfill_failed.clear()
+
+        document_ids = await self.documents_repo.get_ids_missing_embeddings(
+            model_name,
+            limit=ONLINE_BATCH_SIZE,
+            exclude_ids=list(self._backfill_failed),
+        )
+        if not document_ids:
+            return False
+
+        backfilled_any = False
+        for document_id in document_ids:
+            try:
+                async with self._embedding_semaphore:
+                    doc = await self.documents_repo.get_by_id(document_id)
+                    content_text = (
+                        await self.content_storage.get_text(doc.content_id)
+                        if doc and doc.content_id
+                        else None
+                    )
+                    chunks = (
+                        await self._embed_text(target, content_text)
+                        if content_text and content_text.strip()
+                        else []
+                    )
+                    if not chunks:
+                        self._backfill_failed.add(document_id)
+                        continue
+
+                    await self.embeddings_repo.delete_for_documents(
+                        [document_id], model_name=model_name
+                    )
+                    await self._write_embeddings(
+                        document_id, model_name, chunks, content_checksum(content_text)
+                    )
+                    backfilled_any = True
+            except Exception as e:
+                logger.warning(
+                    f"Shadow backfill with {model_name} failed for {document_id}: {e}"
+                )
+                self._backfill_failed.add(document_id)
+            finally:
+                # Yield to allow higher-priority tasks (stream requests) to run
+                await asyncio.sleep(0)
+
+        if backfilled_any:
+            logger.info(f"Backfilled {model_name} embeddings for documents")
+        return backfilled_any
+
     async def _maybe_log_progress(self):
         """Log embedding progress periodically."""
         if self._last_progress_log_time is None:
@@ -717,13 +863,14 @@ class EmbeddingBatchProcessor:
 
             # Chunk the content
             chunk_spans = Chunker.chunk_sentences_by_chars(content_text, max_chars=4096)
+            content_sha256 = content_checksum(content_text)
 
             for chunk_idx, (start_char, end_char) in enumerate(chunk_spans):
                 chunk_text = content_text[start_char:end_char]
 
                 chunks.append(
                     {
-                        "recordId": f"{item.document_id}:{chunk_idx}:{start_char}:{end_char}",
+                        "recordId": f"{item.document_id}:{chunk_idx}:{start_char}:{end_char}:{content_sha256}",
                         "modelInput": {"inputText": chunk_text},
                     }
                 )
@@ -874,18 +1021,21 @@ class EmbeddingBatchProcessor:
                     logger.warning(f"No embedding in output for {record_id}")
                     continue
 
+                # Records of batches submitted before checksums were recorded have none
                 parts = record_id.split(":")
-                if len(parts) != 4:
+                if len(parts) not in (4, 5):
                     logger.warning(f"Invalid record_id format: {record_id}")
                     continue
 
-                doc_id, chunk_idx, start, end = parts
+                doc_id, chunk_idx, start, end = parts[:4]
+                content_sha256 = parts[4] if len(parts) == 5 else None
 
                 embeddings_by_doc[doc_id].append(
                     {
                         "chunk_index": int(chunk_idx),
                         "start": int(start),
                         "end": int(end),
+                        "content_sha256": content_sha256,
                         "embedding": embedding,
                     }
                 )
@@ -906,7 +1056,8 @@ class EmbeddingBatchProcessor:
         if not embeddings_by_doc:
             return
 
-        # Delete existing embeddings for these documents
+        # Delete existing embeddings for these documents. This drops an embedding
+        # migration's target embeddings too; the shadow backfill restores them.
         document_ids = list(embeddings_by_doc.keys())
         await self.embeddings_repo.delete_for_documents(document_ids)
 
@@ -921,6 +1072,7 @@ class EmbeddingBatchProcessor:
                         "chunk_index": chunk["chunk_index"],
                         "chunk_start_offset": chunk["start"],
                         "chunk_end_offset": chunk["end"],
+                        "content_sha256": chunk["content_sha256"],
                         "embedding": chunk["embedding"],
                         "model_name": EMBEDDING_MODEL,
                         "dimensions": len(chunk["embedding"]),
@@ -938,13 +1090,13 @@ class EmbeddingBatchProcessor:
 # Public API for Integration
 # ============================================================================
 async def start_batch_processing(
-    content_storage, embedding_provider, provider_type: str
+    content_storage, embedding_providers, provider_type: str
 ):
     """Start batch processing background tasks.
 
     Args:
         content_storage: Storage client for fetching document content
-        embedding_provider: The configured embedding provider
+        embedding_providers: Registry resolving the embedding providers in use
         provider_type: Type of provider ("bedrock", "local", "openai", "jina")
     """
     logger.info(f"Starting embedding batch processing with provider: {provider_type}")
@@ -964,7 +1116,7 @@ async def start_batch_processing(
         embeddings_repo=embeddings_repo,
         batch_jobs_repo=batch_jobs_repo,
         content_storage=content_storage,
-        embedding_provider=embedding_provider,
+        embedding_providers=embedding_providers,
         provider_type=provider_type,
     )
 
@@ -0,0 +1,223 @@
+"""Rate limiting of embedding requests against each provider's budgets.
+
+Providers limit requests and tokens per minute. Requests wait here until they fit the
+budget, instead of being sent and retried after a 429. Budgets refill continuously, so a
+bulk sync is smoothed out over the minute rather than spending the budget in a burst.
+
+Interactive requests, such as embedding search queries, always go ahead of waiting
+background work, and background work leaves part of each budget unused so that they rarely
+have to wait at all.
+"""
+
+import asyncio
+import contextvars
+import logging
+import time
+from collections import deque
+from contextlib import contextmanager
+from dataclasses import dataclass
+from typing import Callable
+
+from config import get_optional_env
+
+from . import Chunk, EmbeddingProvider
+
+logger = logging.getLogger(__name__)
+
+# Rough token estimate, matching the providers' own chunking estimates
+CHARS_PER_TOKEN = 3
+
+# Share of each budget that background work leaves to interactive requests
+INTERACTIVE_RESERVE = 0.1
+
+# Longest a waiting request sleeps before checking its turn again
+MAX_WAIT_SECONDS = 1.0
+
+_background = contextvars.ContextVar("embedding_background", default=False)
+
+
+@contextmanager
+def background_embedding():
+    """Mark embedding requests made in this context, and tasks it starts, as background work."""
+    token = _background.set(True)
+    try:
+        yield
+    finally:
+        _background.reset(token)
+
+
+@dataclass(frozen=True)
+class RateLimits:
+    """A provider's budgets. Zero means unlimited."""
+
+    requests_per_minute: int = 0
+    tokens_per_minute: int = 0
+    max_concurrent_requests: int = 0
+
+    @property
+    def unlimited(self) -> bool:
+        return not (
+            self.requests_per_minute
+            or self.tokens_per_minute
+            or self.max_concurrent_requests
+        )
+
+    @classmethod
+    def from_env(cls, provider_type: str) -> "RateLimits":
+        """Read a provider's budgets, e.g. `EMBEDDING_OPENAI_TOKENS_PER_MINUTE`, falling
+        back to the budgets for every provider, e.g. `EMBEDDING_TOKENS_PER_MINUTE`."""
+
+        def budget(name: str) -> int:
+            default = get_optional_env(f"EMBEDDING_{name}", "0")
+            return int(
+                get_optional_env(f"EMBEDDING_{provider_type.upper()}_{name}", default)
+            )
+
+        return cls(
+            requests_per_minute=budget("REQUESTS_PER_MINUTE"),
+            tokens_per_minute=budget("TOKENS_PER_MINUTE"),
+            max_concurrent_requests=budget("MAX_CONCURRENT_REQUESTS"),
+        )
+
+
+class _Bucket:
+    """A budget per minute, refilled continuously up to a minute's worth."""
+
+    def __init__(self, per_minute: int, now: float):
+        self.capacity = float(per_minute)
+        self.level = float(per_minute)
+        self._updated = now
+
+    def refill(self, now: float):
+        self.level = min(
+            self.capacity, self.level + (now - self._updated) * self.capacity / 60
+        )
+        self._updated = now
+
+    def seconds_until(self, amount: float) -> float:
+        return max(0.0, (amount - self.level) * 60 / self.capacity)
+
+
+class ProviderRateLimiter:
+    """Admits requests to one provider within its budgets, interactive requests first."""
+
+    def __init__(
+        self, limits: RateLimits, clock: Callable[[], float] = time.monotonic
+    ):
+        self.limits = limits
+        self._clock = clock
+        now = clock()
+        self._requests = (
+            _Bucket(limits.requests_per_minute, now)
+            if limits.requests_per_minute
+            else None
+        )
+        self._tokens = (
+            _Bucket(limits.tokens_per_minute, now) if limits.tokens_per_minute else None
+        )
+        self._in_flight = 0
+        self._waiting = {True: deque(), False: deque()}
+
+    def _costs(self, tokens: int) -> list[tuple[_Bucket, float]]:
+        costs = []
+        if self._requests:
+            costs.append((self._requests, 1.0))
+        if self._tokens:
+            # A request larger than a minute's budget is sent once the whole budget is free
+            costs.append((self._tokens, float(min(tokens, self._tokens.capacity))))
+        return costs
+
+    def _is_next(self, waiter: object, interactive: bool) -> bool:
+        if not interactive and self._waiting[True]:
+            return False
+        return self._waiting[interactive][0] is waiter
+
+    def _wait_seconds(self, tokens: int, interactive: bool) -> float:
+        """Seconds until the request fits the budgets, zero if it fits now."""
+        now = self._clock()
+        wait = 0.0
+        for bucket, cost in self._costs(tokens):
+            bucket.refill(now)
+            reserve = 0.0 if interactive else bucket.capacity * INTERACTIVE_RESERVE
+            wait = max(wait, bucket.seconds_until(min(cost + reserve, bucket.capacity)))
+        return wait
+
+    def _has_capacity(self) -> bool:
+        limit = self.limits.max_concurrent_requests
+        return not limit or self._in_flight < limit
+
+    async def acquire(self, tokens: int, interactive: bool):
+        """Wait until a request of about `tokens` tokens may be sent."""
+        waiter = object()
+        queue = self._waiting[interactive]
+        queue.append(waiter)
+        started = self._clock()
+        try:
+            while True:
+                wait = MAX_WAIT_SECONDS / 10
+                if self._is_next(waiter, interactive) and self._has_capacity():
+                    wait = self._wait_seconds(tokens, interactive)
+                    if wait == 0:
+                        break
+                await asyncio.sleep(min(wait, MAX_WAIT_SECONDS))
+        finally:
+            queue.remove(waiter)
+
+        for bucket, cost in self._costs(tokens):
+            bucket.level -= cost
+        self._in_flight += 1
+
+        waited = self._clock() - started
+        if waited > 5:
+            logger.info(
+                f"{'Interactive' if interactive else 'Background'} embedding request waited {waited:.1f}s for the provider's rate limits"
+            )
+
+    def release(self):
+        self._in_flight -= 1
+
+
+class RateLimitedEmbeddingProvider(EmbeddingProvider):
+    """An embedding provider whose requests go through a rate limiter."""
+
+    def __init__(self, provider: EmbeddingProvider, limiter: ProviderRateLimiter):
+        self.provider = provider
+        self.limiter = limiter
+
+    async def generate_embeddings(
+        self,
+        text: str,
+        task: str,
+        chunk_size: int | None,
+        chunking_mode: str,
+    ) -> list[Chunk]:
+        tokens = len(text) // CHARS_PER_TOKEN + 1
+        await self.limiter.acquire(tokens, interactive=not _background.get())
+        try:
+            return await self.provider.generate_embeddings(
+                text, task, chunk_size, chunking_mode
+            )
+        finally:
+            self.limiter.release()
+
+    def get_model_name(self) -> str:
+        return self.provider.get_model_name()
+
+
+_limiters: dict[str, ProviderRateLimiter] = {}
+
+
+def rate_limited(
+    provider: EmbeddingProvider, provider_type: str, limits: RateLimits | None = None
+) -> EmbeddingProvider:
+    """Rate limit a provider with its budgets from the environment. Providers of the same
+    type share one limiter, as they share the account whose budgets are limited."""
+    limits = limits or RateLimits.from_env(provider_type)
+    if limits.unlimited:
+        return provider
+
+    limiter = _limiters.get(provider_type)
+    if limiter is None or limiter.limits != limits:
+        limiter = ProviderRateLimiter(limits)
+        _limiters[provider_type] = limiter
+    return RateLimitedEmbeddingProvider(provider, limiter)
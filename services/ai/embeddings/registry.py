@@ -0,0 +1,160 @@
+"""Resolution of the embedding providers in use, including during model migrations."""
+
+import logging
+from dataclasses import dataclass
+from typing import Callable
+
+from config import AWS_REGION, EMBEDDING_MAX_MODEL_LEN
+from db_config import EmbeddingConfig, get_embedding_settings
+
+from . import EmbeddingProvider, create_embedding_provider
+from .rate_limit import rate_limited
+
+logger = logging.getLogger(__name__)
+
+
+def build_embedding_provider(embedding_config: EmbeddingConfig) -> EmbeddingProvider:
+    """Create the embedding provider described by an embedding configuration, limited to
+    the provider's rate limits."""
+    return rate_limited(
+        _create_provider(embedding_config), embedding_config.provider
+    )
+
+
+def _create_provider(embedding_config: EmbeddingConfig) -> EmbeddingProvider:
+    provider = embedding_config.provider
+    max_model_len = embedding_config.max_model_len or EMBEDDING_MAX_MODEL_LEN
+
+    if provider == "jina":
+        if not embedding_config.api_key:
+            raise ValueError("Embedding API key is required when using Jina provider")
+        return create_embedding_provider(
+            "jina",
+            api_key=embedding_config.api_key,
+            model=embedding_config.model,
+            api_url=embedding_config.api_url,
+            max_model_len=max_model_len,
+        )
+
+    elif provider == "bedrock":
+        region_name = AWS_REGION if AWS_REGION else None
+        return create_embedding_provider(
+            "bedrock",
+            model_id=embedding_config.model,
+            region_name=region_name,
+            max_model_len=max_model_len,
+        )
+
+    elif provider == "openai":
+        if not embedding_config.api_key:
+            raise ValueError("Embedding API key is required when using OpenAI provider")
+        return create_embedding_provider(
+            "openai",
+            api_key=embedding_config.api_key,
+            model=embedding_config.model,
+            dimensions=embedding_config.dimensions,
+            max_model_len=max_model_len,
+        )
+
+    elif provider == "cohere":
+        if not embedding_config.api_key:
+            raise ValueError("Embedding API key is required when using Cohere provider")
+        return create_embedding_provider(
+            "cohere",
+            api_key=embedding_config.api_key,
+            model=embedding_config.model,
+            api_url=embedding_config.api_url,
+            max_model_len=max_model_len,
+            dimensions=embedding_config.dimensions,
+        )
+
+    elif provider == "local":
+        return create_embedding_provider(
+            "local",
+            base_url=embedding_config.api_url or "",
+            model=embedding_config.model,
+            max_model_len=max_model_len,
+        )
+
+    else:
+        raise ValueError(f"Unknown embedding provider: {provider}")
+
+
+@dataclass
+class EmbeddingProviders:
+    """The embedding providers to use right now.
+
+    `active` embeds search queries. While an embedding model migration is in progress,
+    `shadow` is the other model and documents are embedded with both. `target` is the model
+    being migrated to: the shadow until cutover, the active model after.
+    """
+
+    active: EmbeddingProvider
+    shadow: EmbeddingProvider | None = None
+    target: EmbeddingProvider | None = None
+
+    @property
+    def all(self) -> list[EmbeddingProvider]:
+        return [self.active] + ([self.shadow] if self.shadow else [])
+
+
+class EmbeddingProviderRegistry:
+    """Builds embedding providers from the embedding settings in the database.
+
+    Settings are re-read through the config cache on every resolve, so a migration being
+    started, cut over or finalized takes effect without a restart. Providers are only
+    rebuilt when their settings change.
+    """
+
+    def __init__(
+        self,
+        factory: Callable[
+            [EmbeddingConfig], EmbeddingProvider
+        ] = build_embedding_provider,
+    ):
+        self._factory = factory
+        self._providers: list[tuple[EmbeddingConfig, EmbeddingProvider]] = []
+
+    def _provider_for(self, embedding_config: EmbeddingConfig) -> EmbeddingProvider:
+        for known_config, provider in self._providers:
+            if known_config == embedding_config:
+                return provider
+
+        provider = self._factory(embedding_config)
+        logger.info(
+            f"Initialized {embedding_config.provider} embedding provider with model: {provider.get_model_name()}"
+        )
+        self._providers.append((embedding_config, provider))
+        return provider
+
+    async def resolve(self) -> EmbeddingProviders:
+        settings = await get_embedding_settings()
+        current = self._provider_for(settings.config)
+        in_use = [settings.config]
+
+        providers = EmbeddingProviders(active=current)
+        migration = settings.migration
+        if migration is not None and migration.target.model != settings.config.model:
+            try:
+                target = self._provider_for(migration.target)
+                in_use.append(migration.target)
+                if migration.cutover:
+                    providers = EmbeddingProviders(
+                        active=target, shadow=current, target=target
+                    )
+                else:
+                    providers = EmbeddingProviders(
+                        active=current, shadow=target, target=target
+                    )
+            except Exception as e:
+                # A broken target must not take down embedding with the current model
+                logger.error(
+                    f"Failed to initialize embedding migration target {migration.target.model}: {e}"
+                )
+
+        self._providers = [
+            (known_config, provider)
+            for known_config, provider in self._providers
+            if known_config in in_use
+        ]
+        return providers
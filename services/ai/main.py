@@ -18,6 +18,7 @@ from routers import (
     chat_router,
     health_router,
     embeddings_router,
+    rerank_router,
     prompts_router,
     model_providers_router,
 )
@@ -41,6 +42,7 @@ init_telemetry(app, "omni-ai")
 # Include routers
 app.include_router(health_router)
 app.include_router(embeddings_router)
+app.include_router(rerank_router)
 app.include_router(prompts_router)
 app.include_router(chat_router)
 app.include_router(model_providers_router)
@@ -6,6 +6,15 @@ SOURCE_DISPLAY_NAMES = {
     "slack": "Slack",
     "hubspot": "HubSpot",
     "fireflies": "Fireflies",
+    "service_now": "ServiceNow",
+    "figma": "Figma",
+    "airtable": "Airtable",
+    "bitbucket": "Bitbucket",
+    "pagerduty": "PagerDuty",
+    "stack_overflow": "Stack Overflow",
+    "box": "Box",
+    "gong": "Gong",
+    "trello": "Trello",
     "web": "Web",
     "local_files": "Files",
     "github": "GitHub",
@@ -14,6 +23,7 @@ SOURCE_DISPLAY_NAMES = {
     "sharepoint": "SharePoint",
     "outlook": "Outlook",
     "outlook_calendar": "Outlook Calendar",
+    "people": "People",
 }
 
 CHAT_SYSTEM_PROMPT_TEMPLATE = """You are Omni AI, a workplace assistant that helps employees find information and complete tasks.
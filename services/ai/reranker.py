@@ -0,0 +1,71 @@
+"""Cross-encoder reranking of search results.
+
+The reranker is any API serving the `/rerank` format shared by Jina, Cohere, vLLM and
+Infinity: it takes a query and documents, and scores each document's relevance to the query.
+"""
+
+import logging
+
+import httpx
+
+from config import RERANK_API_KEY, RERANK_API_URL, RERANK_MODEL
+
+logger = logging.getLogger(__name__)
+
+
+class Reranker:
+    """Client for a `/rerank` API."""
+
+    def __init__(self, api_url: str, model: str, api_key: str = ""):
+        if not model:
+            raise ValueError("RERANK_MODEL is required when RERANK_API_URL is set")
+
+        self.api_url = api_url
+        self.model = model
+        self.api_key = api_key
+        self.client = httpx.AsyncClient(
+            timeout=httpx.Timeout(30.0, connect=5.0),
+            limits=httpx.Limits(max_keepalive_connections=5, max_connections=10),
+        )
+
+    def get_model_name(self) -> str:
+        return self.model
+
+    async def close(self):
+        await self.client.aclose()
+
+    async def rerank(self, query: str, documents: list[str]) -> list[float]:
+        """Score each document's relevance to the query, in the order of the documents."""
+        if not documents:
+            return []
+
+        headers = {"Content-Type": "application/json"}
+        if self.api_key:
+            headers["Authorization"] = f"Bearer {self.api_key}"
+        payload = {
+            "model": self.model,
+            "query": query,
+            "documents": documents,
+            "top_n": len(documents),
+        }
+
+        response = await self.client.post(self.api_url, headers=headers, json=payload)
+        if response.status_code != 200:
+            raise Exception(
+                f"Rerank API error: {response.status_code} - {response.text}"
+            )
+
+        # Results come sorted by score, each with the index of its document
+        scores = [0.0] * len(documents)
+        for result in response.json()["results"]:
+            scores[result["index"]] = result["relevance_score"]
+        return scores
+
+
+def create_reranker() -> Reranker | None:
+    """The configured reranker, `None` when reranking is not configured."""
+    if not RERANK_API_URL:
+        return None
+    reranker = Reranker(RERANK_API_URL, RERANK_MODEL, RERANK_API_KEY)
+    logger.info(f"Initialized reranker - model: {RERANK_MODEL}")
+    return reranker
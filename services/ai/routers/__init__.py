@@ -1,6 +1,7 @@
 from .chat import router as chat_router
 from .health import router as health_router
 from .embeddings import router as embeddings_router
+from .rerank import router as rerank_router
 from .prompts import router as prompts_router
 from .model_providers import router as model_providers_router
 
@@ -8,6 +9,7 @@ __all__ = [
     "chat_router",
     "health_router",
     "embeddings_router",
+    "rerank_router",
     "prompts_router",
     "model_providers_router",
 ]
@@ -34,12 +34,15 @@ async def health_check(request: Request):
             except Exception:
                 llm_health = False
 
-    # Get embedding model name from provider
-    embedding_model = (
-        request.app.state.embedding_provider.get_model_name()
-        if hasattr(request.app.state, "embedding_provider")
-        else "unknown"
-    )
+    # Get embedding model name from the provider serving searches
+    embedding_model = "unknown"
+    embedding_providers = getattr(request.app.state, "embedding_providers", None)
+    if embedding_providers:
+        try:
+            providers = await embedding_providers.resolve()
+            embedding_model = providers.active.get_model_name()
+        except Exception:
+            pass
 
     # Get current configurations
     embedding_config = await get_embedding_config()
@@ -0,0 +1,30 @@
+"""Rerank endpoint."""
+
+import logging
+
+from fastapi import APIRouter, HTTPException, Request
+
+from schemas import RerankRequest, RerankResponse
+
+logger = logging.getLogger(__name__)
+router = APIRouter(tags=["rerank"])
+
+
+@router.post("/rerank", response_model=RerankResponse)
+async def rerank(request: Request, body: RerankRequest):
+    """Score how relevant each document is to the query with the configured cross-encoder.
+
+    Scores are returned in the order of the documents, higher is more relevant.
+    """
+    reranker = request.app.state.reranker
+    if reranker is None:
+        raise HTTPException(status_code=503, detail="No reranker is configured")
+
+    logger.info(f"Reranking {len(body.documents)} documents")
+    try:
+        scores = await reranker.rerank(body.query, body.documents)
+    except Exception as e:
+        logger.error(f"Failed to rerank documents: {str(e)}")
+        raise HTTPException(status_code=502, detail=str(e))
+
+    return RerankResponse(scores=scores, model_name=reranker.get_model_name())
@@ -3,6 +3,8 @@ from .api import (
     PrioritizedRequest,
     EmbeddingRequest,
     EmbeddingResponse,
+    RerankRequest,
+    RerankResponse,
     PromptRequest,
     PromptResponse,
 )
@@ -12,6 +14,8 @@ __all__ = [
     "PrioritizedRequest",
     "EmbeddingRequest",
     "EmbeddingResponse",
+    "RerankRequest",
+    "RerankResponse",
     "PromptRequest",
     "PromptResponse",
 ]
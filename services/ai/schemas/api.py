@@ -36,6 +36,8 @@ class EmbeddingRequest(BaseModel):
     chunk_size: int | None = 512  # Chunk size in tokens
     chunking_mode: str | None = "sentence"  # "sentence", "fixed", or "none"
     priority: Literal["high", "normal", "low"] | None = "normal"
+    # Model of an embedding model migration to embed with, the shadow only exists mid-migration
+    model: Literal["active", "shadow"] | None = "active"
 
 
 class EmbeddingResponse(BaseModel):
@@ -47,6 +49,20 @@ class EmbeddingResponse(BaseModel):
     model_name: str
 
 
+class RerankRequest(BaseModel):
+    """Request to score documents' relevance to a query."""
+
+    query: str
+    documents: list[str]
+
+
+class RerankResponse(BaseModel):
+    """Relevance scores of the documents, in the order of the request's documents."""
+
+    scores: list[float]
+    model_name: str
+
+
 class PromptRequest(BaseModel):
     """Request to generate a response from the LLM."""
 
@@ -88,16 +88,23 @@ class EmbeddingQueueService:
                     )
 
                 try:
+                    providers = await self.app_state.embedding_providers.resolve()
+                    provider = (
+                        providers.shadow
+                        if request.model == "shadow"
+                        else providers.active
+                    )
+                    if provider is None:
+                        raise ValueError("No embedding model migration in progress")
+
                     # Process each text individually through the provider
                     chunk_batch = []
                     for text in request.texts:
-                        chunks = (
-                            await self.app_state.embedding_provider.generate_embeddings(
-                                text,
-                                request.task,
-                                request.chunk_size,
-                                request.chunking_mode,
-                            )
+                        chunks = await provider.generate_embeddings(
+                            text,
+                            request.task,
+                            request.chunk_size,
+                            request.chunking_mode,
                         )
                         chunk_batch.append(chunks)
 
@@ -107,7 +114,7 @@ class EmbeddingQueueService:
                         ],
                         chunks_count=[len(chunks) for chunks in chunk_batch],
                         chunks=[[c.span for c in chunks] for chunks in chunk_batch],
-                        model_name=self.app_state.embedding_provider.get_model_name(),
+                        model_name=provider.get_model_name(),
                     )
 
                     # Set the result on the future
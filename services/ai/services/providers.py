@@ -7,7 +7,6 @@ import redis.asyncio as aioredis
 
 from config import (
     AWS_REGION,
-    EMBEDDING_MAX_MODEL_LEN,
     REDIS_URL,
 )
 from db_config import (
@@ -15,7 +14,8 @@ from db_config import (
 )
 from db import ModelsRepository, ModelRecord
 from providers import create_llm_provider, LLMProvider
-from embeddings import create_embedding_provider
+from embeddings.registry import EmbeddingProviderRegistry
+from reranker import create_reranker
 from tools import SearcherTool
 from storage import create_content_storage
 from embeddings.batch_processor import start_batch_processing
@@ -96,69 +96,12 @@ async def load_models(app_state: AppState) -> None:
 
 async def initialize_providers(app_state: AppState) -> None:
     """Initialize all providers (embedding, LLM, tools, storage)."""
-    embedding_config = await get_embedding_config()
-    provider = embedding_config.provider
-    logger.info(f"Loaded embedding configuration (provider: {provider})")
-
-    max_model_len = embedding_config.max_model_len or EMBEDDING_MAX_MODEL_LEN
-
-    if provider == "jina":
-        if not embedding_config.api_key:
-            raise ValueError("Embedding API key is required when using Jina provider")
-        app_state.embedding_provider = create_embedding_provider(
-            "jina",
-            api_key=embedding_config.api_key,
-            model=embedding_config.model,
-            api_url=embedding_config.api_url,
-            max_model_len=max_model_len,
-        )
-
-    elif provider == "bedrock":
-        region_name = AWS_REGION if AWS_REGION else None
-        app_state.embedding_provider = create_embedding_provider(
-            "bedrock",
-            model_id=embedding_config.model,
-            region_name=region_name,
-            max_model_len=max_model_len,
-        )
-
-    elif provider == "openai":
-        if not embedding_config.api_key:
-            raise ValueError("Embedding API key is required when using OpenAI provider")
-        app_state.embedding_provider = create_embedding_provider(
-            "openai",
-            api_key=embedding_config.api_key,
-            model=embedding_config.model,
-            dimensions=embedding_config.dimensions,
-            max_model_len=max_model_len,
-        )
+    app_state.embedding_providers = EmbeddingProviderRegistry()
+    # Resolve once up front so a broken embedding configuration fails startup
+    await app_state.embedding_providers.resolve()
 
-    elif provider == "cohere":
-        if not embedding_config.api_key:
-            raise ValueError("Embedding API key is required when using Cohere provider")
-        app_state.embedding_provider = create_embedding_provider(
-            "cohere",
-            api_key=embedding_config.api_key,
-            model=embedding_config.model,
-            api_url=embedding_config.api_url,
-            max_model_len=max_model_len,
-            dimensions=embedding_config.dimensions,
-        )
-
-    elif provider == "local":
-        app_state.embedding_provider = create_embedding_provider(
-            "local",
-            base_url=embedding_config.api_url or "",
-            model=embedding_config.model,
-            max_model_len=max_model_len,
-        )
-
-    else:
-        raise ValueError(f"Unknown embedding provider: {provider}")
-
-    logger.info(
-        f"Initialized {provider} embedding provider with model: {app_state.embedding_provider.get_model_name()}"
-    )
+    # Initialize reranker, when configured
+    app_state.reranker = create_reranker()
 
     # Initialize models from database
     await load_models(app_state)
@@ -182,7 +125,7 @@ async def start_batch_processor(app_state: AppState) -> None:
     asyncio.create_task(
         start_batch_processing(
             app_state.content_storage,
-            app_state.embedding_provider,
+            app_state.embedding_providers,
             embedding_config.provider,
         )
     )
@@ -196,4 +139,6 @@ async def shutdown_providers(app_state: "AppState"):
     if app_state.redis_client:
         await app_state.redis_client.close()
         logger.info("Closed Redis client")
+    if app_state.reranker:
+        await app_state.reranker.close()
     logger.info("AI service shutdown complete")
@@ -4,8 +4,9 @@ from dataclasses import dataclass, field
 
 import redis.asyncio as aioredis
 
-from embeddings import EmbeddingProvider
+from embeddings.registry import EmbeddingProviderRegistry
 from providers import LLMProvider
+from reranker import Reranker
 from tools import SearcherTool
 from storage import ContentStorage
 
@@ -18,7 +19,8 @@ class AppState:
     when accessing app.state attributes.
     """
 
-    embedding_provider: EmbeddingProvider | None = None
+    embedding_providers: EmbeddingProviderRegistry | None = None
+    reranker: Reranker | None = None
     models: dict[str, LLMProvider] = field(default_factory=dict)
     default_model_id: str | None = None
     searcher_tool: SearcherTool | None = None
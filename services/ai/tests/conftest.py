@@ -46,6 +46,7 @@ from testcontainers.core.container import DockerContainer
 from testcontainers.core.waiting_utils import wait_for_logs
 from testcontainers.redis import RedisContainer
 
+from embeddings.registry import EmbeddingProviders
 from db import (
     DocumentsRepository,
     EmbeddingQueueRepository,
@@ -245,6 +246,16 @@ def mock_embedding_provider():
     return provider
 
 
+@pytest.fixture
+def mock_embedding_providers(mock_embedding_provider):
+    """Mock embedding provider registry resolving to the mock embedding provider."""
+    registry = MagicMock()
+    registry.resolve = AsyncMock(
+        return_value=EmbeddingProviders(active=mock_embedding_provider)
+    )
+    return registry
+
+
 @pytest.fixture
 def mock_llm_provider():
     """Mock LLM provider for unit tests."""
@@ -304,10 +315,10 @@ def mock_openai_api():
 
 
 @pytest.fixture
-def app_state(mock_embedding_provider, mock_llm_provider):
+def app_state(mock_embedding_providers, mock_llm_provider):
     """Create AppState with mocked providers for unit tests."""
     state = AppState()
-    state.embedding_provider = mock_embedding_provider
+    state.embedding_providers = mock_embedding_providers
     state.models = {"mock-model": mock_llm_provider}
     state.default_model_id = "mock-model"
     state.searcher_tool = AsyncMock()
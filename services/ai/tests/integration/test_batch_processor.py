@@ -8,7 +8,8 @@ import pytest
 import ulid
 from unittest.mock import AsyncMock, MagicMock
 
-from embeddings.batch_processor import EmbeddingBatchProcessor
+from embeddings.batch_processor import EmbeddingBatchProcessor, content_checksum
+from embeddings.registry import EmbeddingProviders
 
 
 # =============================================================================
@@ -98,7 +99,7 @@ async def online_processor(
     queue_repo,
     embeddings_repo,
     batch_jobs_repo,
-    mock_embedding_provider,
+    mock_embedding_providers,
 ):
     """Processor with real DB repos, mocked embedding provider."""
     # Mock content storage to fetch from DB
@@ -119,7 +120,7 @@ async def online_processor(
         embeddings_repo=embeddings_repo,
         batch_jobs_repo=batch_jobs_repo,
         content_storage=content_storage,
-        embedding_provider=mock_embedding_provider,
+        embedding_providers=mock_embedding_providers,
         provider_type="jina",
     )
 
@@ -131,7 +132,7 @@ async def bedrock_processor(
     queue_repo,
     embeddings_repo,
     batch_jobs_repo,
-    mock_embedding_provider,
+    mock_embedding_providers,
 ):
     """Bedrock processor with real DB, mocked S3/Bedrock clients."""
     processor = EmbeddingBatchProcessor(
@@ -140,7 +141,7 @@ async def bedrock_processor(
         embeddings_repo=embeddings_repo,
         batch_jobs_repo=batch_jobs_repo,
         content_storage=AsyncMock(),
-        embedding_provider=mock_embedding_provider,
+        embedding_providers=mock_embedding_providers,
         provider_type="bedrock",
     )
     # Mock S3 and Bedrock clients to avoid real AWS calls
@@ -171,6 +172,9 @@ async def test_online_processes_document_end_to_end(
     embeddings = await embeddings_repo.get_for_document(doc_id)
     assert len(embeddings) >= 1
     assert len(embeddings[0].embedding) == 1024
+    assert embeddings[0].content_sha256 == content_checksum(
+        "Test content for embedding."
+    )
 
     queue_item = await queue_repo.get_by_id(queue_id)
     assert queue_item.status == "completed"
@@ -229,14 +233,17 @@ def test_parse_bedrock_output_groups_by_document():
         embeddings_repo=None,
         batch_jobs_repo=None,
         content_storage=None,
-        embedding_provider=None,
+        embedding_providers=None,
         provider_type="jina",  # Avoid Bedrock client init
     )
 
     output_lines = [
         {"recordId": "doc1:0:0:100", "modelOutput": {"embedding": [0.1] * 1024}},
         {"recordId": "doc1:1:100:200", "modelOutput": {"embedding": [0.2] * 1024}},
-        {"recordId": "doc2:0:0:50", "modelOutput": {"embedding": [0.3] * 1024}},
+        {
+            "recordId": "doc2:0:0:50:abc123",
+            "modelOutput": {"embedding": [0.3] * 1024},
+        },
     ]
 
     result = processor._parse_bedrock_output(output_lines)
@@ -250,6 +257,10 @@ def test_parse_bedrock_output_groups_by_document():
     assert result["doc1"][0]["chunk_index"] == 0
     assert result["doc1"][1]["chunk_index"] == 1
 
+    # Records of batches submitted before checksums were recorded have none
+    assert result["doc1"][0]["content_sha256"] is None
+    assert result["doc2"][0]["content_sha256"] == "abc123"
+
 
 @pytest.mark.unit
 def test_parse_bedrock_output_skips_errors():
@@ -260,7 +271,7 @@ def test_parse_bedrock_output_skips_errors():
         embeddings_repo=None,
         batch_jobs_repo=None,
         content_storage=None,
-        embedding_provider=None,
+        embedding_providers=None,
         provider_type="jina",
     )
 
@@ -312,13 +323,18 @@ async def online_processor_with_sliding_window(
 
     provider.generate_embeddings.side_effect = generate_with_spans
 
+    embedding_providers = MagicMock()
+    embedding_providers.resolve = AsyncMock(
+        return_value=EmbeddingProviders(active=provider)
+    )
+
     return EmbeddingBatchProcessor(
         documents_repo=documents_repo,
         queue_repo=queue_repo,
         embeddings_repo=embeddings_repo,
         batch_jobs_repo=batch_jobs_repo,
         content_storage=content_storage,
-        embedding_provider=provider,
+        embedding_providers=embedding_providers,
         provider_type="jina",
     )
 
@@ -369,7 +385,8 @@ async def test_online_processes_large_document_with_sliding_window(
     for emb in embeddings:
         assert len(emb.embedding) == 1024
 
-    provider = online_processor_with_sliding_window.embedding_provider
+    providers = await online_processor_with_sliding_window.embedding_providers.resolve()
+    provider = providers.active
     assert provider.generate_embeddings.call_count == 7
 
     doc = await documents_repo.get_by_id(doc_id)
@@ -460,3 +477,131 @@ async def test_max_retries_exhausted_items_are_not_retried(
     item = await queue_repo.get_by_id(queue_id)
     assert item.status == "failed"
     assert item.retry_count == 5
+
+
+# =============================================================================
+# Embedding Model Migration Tests
+# =============================================================================
+
+
+def mock_provider(model_name: str):
+    provider = AsyncMock()
+    provider.get_model_name = MagicMock(return_value=model_name)
+
+    mock_chunk = MagicMock()
+    mock_chunk.span = (0, 100)
+    mock_chunk.embedding = [0.2] * 1024
+
+    provider.generate_embeddings.return_value = [mock_chunk]
+    return provider
+
+
+@pytest.fixture
+async def migrating_processor(
+    db_pool,
+    documents_repo,
+    queue_repo,
+    embeddings_repo,
+    batch_jobs_repo,
+    mock_embedding_provider,
+):
+    """Processor in the middle of a migration to another embedding model."""
+    content_storage = AsyncMock()
+
+    async def get_text_from_db(content_id):
+        async with db_pool.acquire() as conn:
+            row = await conn.fetchrow(
+                "SELECT content FROM content_blobs WHERE id = $1", content_id
+            )
+            return row["content"].decode() if row else None
+
+    content_storage.get_text = get_text_from_db
+
+    target = mock_provider(f"target-model-{ulid.ULID()}")
+    embedding_providers = MagicMock()
+    embedding_providers.resolve = AsyncMock(
+        return_value=EmbeddingProviders(
+            active=mock_embedding_provider, shadow=target, target=target
+        )
+    )
+
+    return EmbeddingBatchProcessor(
+        documents_repo=documents_repo,
+        queue_repo=queue_repo,
+        embeddings_repo=embeddings_repo,
+        batch_jobs_repo=batch_jobs_repo,
+        content_storage=content_storage,
+        embedding_providers=embedding_providers,
+        provider_type="jina",
+    )
+
+
+@pytest.mark.integration
+async def test_migration_embeds_documents_with_both_models(
+    db_pool, migrating_processor, queue_repo, embeddings_repo
+):
+    """During a migration, queued documents get embeddings of both models."""
+    providers = await migrating_processor.embedding_providers.resolve()
+
+    user_id = await create_test_user(db_pool)
+    source_id = await create_test_source(db_pool, user_id)
+    doc_id = await create_test_document(db_pool, source_id, "Content to embed twice.")
+    queue_id = await enqueue_document(db_pool, doc_id)
+
+    await migrating_processor._process_online_batch()
+
+    queue_item = await queue_repo.get_by_id(queue_id)
+    assert queue_item.status == "completed"
+
+    embeddings = await embeddings_repo.get_for_document(doc_id)
+    assert {e.model_name for e in embeddings} == {
+        providers.active.get_model_name(),
+        providers.shadow.get_model_name(),
+    }
+
+
+@pytest.mark.integration
+async def test_migration_shadow_failure_does_not_fail_document(
+    db_pool, migrating_processor, queue_repo, embeddings_repo
+):
+    """A failing shadow model leaves the document with active embeddings only."""
+    providers = await migrating_processor.embedding_providers.resolve()
+    providers.shadow.generate_embeddings.side_effect = RuntimeError("Shadow down")
+
+    user_id = await create_test_user(db_pool)
+    source_id = await create_test_source(db_pool, user_id)
+    doc_id = await create_test_document(db_pool, source_id, "Content to embed.")
+    queue_id = await enqueue_document(db_pool, doc_id)
+
+    await migrating_processor._process_online_batch()
+
+    queue_item = await queue_repo.get_by_id(queue_id)
+    assert queue_item.status == "completed"
+
+    embeddings = await embeddings_repo.get_for_document(doc_id)
+    assert {e.model_name for e in embeddings} == {providers.active.get_model_name()}
+
+
+@pytest.mark.integration
+async def test_migration_backfills_target_embeddings(
+    db_pool, online_processor, migrating_processor, embeddings_repo
+):
+    """Documents embedded before a migration get target embeddings from the backfill."""
+    providers = await migrating_processor.embedding_providers.resolve()
+
+    user_id = await create_test_user(db_pool)
+    source_id = await create_test_source(db_pool, user_id)
+    doc_id = await create_test_document(db_pool, source_id, "Content embedded early.")
+    await enqueue_document(db_pool, doc_id)
+
+    # Embedded with the current model only, before the migration started
+    await online_processor._process_online_batch()
+
+    while await migrating_processor._backfill_target_embeddings():
+        pass
+
+    embeddings = await embeddings_repo.get_for_document(doc_id)
+    assert {e.model_name for e in embeddings} == {
+        providers.active.get_model_name(),
+        providers.target.get_model_name(),
+    }
@@ -0,0 +1,119 @@
+#!/usr/bin/env python3
+"""
+Unit tests for embedding provider rate limiting.
+"""
+import asyncio
+
+import pytest
+
+from embeddings import Chunk, EmbeddingProvider
+from embeddings.rate_limit import (
+    ProviderRateLimiter,
+    RateLimitedEmbeddingProvider,
+    RateLimits,
+    background_embedding,
+)
+
+
+class FakeClock:
+    def __init__(self):
+        self.now = 0.0
+
+    def __call__(self) -> float:
+        return self.now
+
+
+class RecordingProvider(EmbeddingProvider):
+    def __init__(self):
+        self.texts: list[str] = []
+
+    async def generate_embeddings(self, text, task, chunk_size, chunking_mode):
+        self.texts.append(text)
+        return [Chunk(span=(0, len(text)), embedding=[0.0])]
+
+    def get_model_name(self) -> str:
+        return "recording"
+
+
+@pytest.mark.unit
+class TestRateLimits:
+    def test_provider_budgets_override_defaults(self, monkeypatch):
+        monkeypatch.setenv("EMBEDDING_TOKENS_PER_MINUTE", "1000")
+        monkeypatch.setenv("EMBEDDING_OPENAI_TOKENS_PER_MINUTE", "5000")
+        monkeypatch.setenv("EMBEDDING_REQUESTS_PER_MINUTE", "60")
+
+        limits = RateLimits.from_env("openai")
+        assert limits == RateLimits(requests_per_minute=60, tokens_per_minute=5000)
+        assert RateLimits.from_env("jina").tokens_per_minute == 1000
+
+    def test_unlimited_by_default(self):
+        assert RateLimits().unlimited
+
+
+@pytest.mark.unit
+class TestProviderRateLimiter:
+    async def test_waits_for_token_budget_to_refill(self, monkeypatch):
+        clock = FakeClock()
+        limiter = ProviderRateLimiter(RateLimits(tokens_per_minute=600), clock)
+
+        async def advance(seconds):
+            clock.now += seconds
+
+        monkeypatch.setattr(asyncio, "sleep", advance)
+
+        await limiter.acquire(600, interactive=True)
+        limiter.release()
+        assert clock.now == 0
+
+        # 300 tokens refill in 30 seconds
+        await limiter.acquire(300, interactive=True)
+        limiter.release()
+        assert clock.now == pytest.approx(30, abs=1)
+
+    async def test_background_work_leaves_a_reserve(self):
+        clock = FakeClock()
+        limiter = ProviderRateLimiter(RateLimits(requests_per_minute=10), clock)
+
+        for _ in range(9):
+            await limiter.acquire(1, interactive=False)
+            limiter.release()
+
+        background = asyncio.create_task(limiter.acquire(1, interactive=False))
+        await asyncio.sleep(0)
+        assert not background.done()
+
+        # The reserved request is still free for interactive work
+        await asyncio.wait_for(limiter.acquire(1, interactive=True), timeout=1)
+        background.cancel()
+
+    async def test_limits_concurrent_requests(self):
+        limiter = ProviderRateLimiter(RateLimits(max_concurrent_requests=1))
+        await limiter.acquire(1, interactive=True)
+
+        second = asyncio.create_task(limiter.acquire(1, interactive=True))
+        await asyncio.sleep(0.2)
+        assert not second.done()
+
+        limiter.release()
+        await asyncio.wait_for(second, timeout=1)
+        limiter.release()
+
+    async def test_background_context_marks_requests(self, monkeypatch):
+        limiter = ProviderRateLimiter(RateLimits(requests_per_minute=100))
+        calls = []
+        acquire = limiter.acquire
+
+        async def recording_acquire(tokens, interactive):
+            calls.append(interactive)
+            await acquire(tokens, interactive)
+
+        monkeypatch.setattr(limiter, "acquire", recording_acquire)
+        provider = RateLimitedEmbeddingProvider(RecordingProvider(), limiter)
+
+        await provider.generate_embeddings("query", "query", None, "none")
+        with background_embedding():
+            await asyncio.gather(
+                provider.generate_embeddings("document", "passage", 512, "sentence")
+            )
+
+        assert calls == [True, False]
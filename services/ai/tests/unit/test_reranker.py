@@ -0,0 +1,54 @@
+"""
+Unit tests for the reranker client.
+"""
+
+import httpx
+import pytest
+import respx
+
+from reranker import Reranker
+
+RERANK_URL = "https://api.jina.ai/v1/rerank"
+
+
+@pytest.mark.unit
+class TestReranker:
+    async def test_scores_follow_the_order_of_the_documents(self):
+        reranker = Reranker(RERANK_URL, "jina-reranker-v2-base-multilingual", "key")
+        with respx.mock:
+            route = respx.post(RERANK_URL).mock(
+                return_value=httpx.Response(
+                    200,
+                    json={
+                        "results": [
+                            {"index": 2, "relevance_score": 0.9},
+                            {"index": 0, "relevance_score": 0.4},
+                            {"index": 1, "relevance_score": 0.1},
+                        ]
+                    },
+                )
+            )
+
+            scores = await reranker.rerank("vacation policy", ["a", "b", "c"])
+
+        assert scores == [0.4, 0.1, 0.9]
+        request = route.calls.last.request
+        assert request.headers["Authorization"] == "Bearer key"
+        assert b'"top_n":3' in request.content.replace(b" ", b"")
+
+    async def test_no_documents_are_not_sent(self):
+        reranker = Reranker(RERANK_URL, "jina-reranker-v2-base-multilingual")
+        with respx.mock:
+            route = respx.post(RERANK_URL)
+
+            assert await reranker.rerank("vacation policy", []) == []
+
+        assert not route.called
+
+    async def test_api_errors_are_raised(self):
+        reranker = Reranker(RERANK_URL, "jina-reranker-v2-base-multilingual")
+        with respx.mock:
+            respx.post(RERANK_URL).mock(return_value=httpx.Response(429))
+
+            with pytest.raises(Exception, match="429"):
+                await reranker.rerank("vacation policy", ["a"])
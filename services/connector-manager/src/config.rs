@@ -1,5 +1,6 @@
+use crate::export::{parse_export_formats, ExportFormat};
 use shared::models::SourceType;
-use shared::{DatabaseConfig, RedisConfig};
+use shared::{DatabaseConfig, RedisConfig, SmtpConfig};
 use std::collections::HashMap;
 use std::env;
 use std::process;
@@ -14,11 +15,29 @@ pub struct ConnectorManagerConfig {
     pub max_concurrent_syncs_per_type: usize,
     pub scheduler_interval_seconds: u64,
     pub stale_sync_timeout_minutes: u64,
+    pub retention_rules: HashMap<SourceType, u32>,
+    pub retention_interval_seconds: u64,
+    /// Days sync runs are kept verbatim before being rolled into daily summaries, `0` keeps
+    /// every run.
+    pub sync_run_retention_days: u32,
+    /// Formats document metadata is exported in for analytics, none disables exports.
+    pub metadata_export_formats: Vec<ExportFormat>,
+    pub metadata_export_interval_seconds: u64,
+    /// Bearer token identity providers authenticate SCIM requests with. SCIM provisioning
+    /// is disabled when unset.
+    pub scim_bearer_token: Option<String>,
+    /// Seconds between checks for due webhook deliveries, `0` disables delivery.
+    pub webhook_delivery_interval_seconds: u64,
+    /// Reject SDK calls without a source token. Tokens that are sent are always checked, so
+    /// this is turned on once every connector sends them.
+    pub sdk_tokens_required: bool,
+    /// SMTP server sync failure emails are sent through, none disables them.
+    pub smtp: Option<SmtpConfig>,
 }
 
 impl ConnectorManagerConfig {
     pub fn from_env() -> Self {
-        let database = DatabaseConfig::from_env();
+        let database = DatabaseConfig::from_env().with_application_name("omni-connector-manager");
         let redis = RedisConfig::from_env();
 
         let port_str = env::var("PORT").unwrap_or_else(|_| "8090".to_string());
@@ -55,6 +74,33 @@ impl ConnectorManagerConfig {
         if let Ok(url) = env::var("FIREFLIES_CONNECTOR_URL") {
             connector_urls.insert(SourceType::Fireflies, url);
         }
+        if let Ok(url) = env::var("SERVICENOW_CONNECTOR_URL") {
+            connector_urls.insert(SourceType::ServiceNow, url);
+        }
+        if let Ok(url) = env::var("FIGMA_CONNECTOR_URL") {
+            connector_urls.insert(SourceType::Figma, url);
+        }
+        if let Ok(url) = env::var("AIRTABLE_CONNECTOR_URL") {
+            connector_urls.insert(SourceType::Airtable, url);
+        }
+        if let Ok(url) = env::var("BITBUCKET_CONNECTOR_URL") {
+            connector_urls.insert(SourceType::Bitbucket, url);
+        }
+        if let Ok(url) = env::var("PAGERDUTY_CONNECTOR_URL") {
+            connector_urls.insert(SourceType::Pagerduty, url);
+        }
+        if let Ok(url) = env::var("STACKOVERFLOW_CONNECTOR_URL") {
+            connector_urls.insert(SourceType::StackOverflow, url);
+        }
+        if let Ok(url) = env::var("BOX_CONNECTOR_URL") {
+            connector_urls.insert(SourceType::Box, url);
+        }
+        if let Ok(url) = env::var("GONG_CONNECTOR_URL") {
+            connector_urls.insert(SourceType::Gong, url);
+        }
+        if let Ok(url) = env::var("TRELLO_CONNECTOR_URL") {
+            connector_urls.insert(SourceType::Trello, url);
+        }
         if let Ok(url) = env::var("MICROSOFT_CONNECTOR_URL") {
             connector_urls.insert(SourceType::OneDrive, url.clone());
             connector_urls.insert(SourceType::SharePoint, url.clone());
@@ -82,6 +128,48 @@ impl ConnectorManagerConfig {
             .parse::<u64>()
             .unwrap_or(10);
 
+        let retention_rules = match env::var("RETENTION_RULES") {
+            Ok(value) => parse_retention_rules(&value).unwrap_or_else(|e| {
+                eprintln!("ERROR: Invalid 'RETENTION_RULES': {}", e);
+                process::exit(1);
+            }),
+            Err(_) => HashMap::new(),
+        };
+
+        let retention_interval_seconds = env::var("RETENTION_INTERVAL_SECONDS")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse::<u64>()
+            .unwrap_or(3600);
+
+        let sync_run_retention_days = env::var("SYNC_RUN_RETENTION_DAYS")
+            .unwrap_or_else(|_| "90".to_string())
+            .parse::<u32>()
+            .unwrap_or(90);
+
+        let metadata_export_formats = match env::var("METADATA_EXPORT_FORMATS") {
+            Ok(value) => parse_export_formats(&value).unwrap_or_else(|e| {
+                eprintln!("ERROR: Invalid 'METADATA_EXPORT_FORMATS': {}", e);
+                process::exit(1);
+            }),
+            Err(_) => Vec::new(),
+        };
+
+        let metadata_export_interval_seconds = env::var("METADATA_EXPORT_INTERVAL_SECONDS")
+            .unwrap_or_else(|_| "86400".to_string())
+            .parse::<u64>()
+            .unwrap_or(86400);
+
+        let scim_bearer_token =
+            shared::secrets::get("SCIM_BEARER_TOKEN").filter(|token| !token.is_empty());
+
+        let webhook_delivery_interval_seconds = env::var("WEBHOOK_DELIVERY_INTERVAL_SECONDS")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse::<u64>()
+            .unwrap_or(5);
+
+        let sdk_tokens_required =
+            env::var("SDK_TOKENS_REQUIRED").is_ok_and(|value| value == "true");
+
         Self {
             database,
             redis,
@@ -91,6 +179,15 @@ impl ConnectorManagerConfig {
             max_concurrent_syncs_per_type,
             scheduler_interval_seconds,
             stale_sync_timeout_minutes,
+            retention_rules,
+            retention_interval_seconds,
+            sync_run_retention_days,
+            metadata_export_formats,
+            metadata_export_interval_seconds,
+            scim_bearer_token,
+            webhook_delivery_interval_seconds,
+            sdk_tokens_required,
+            smtp: SmtpConfig::from_env(),
         }
     }
 
@@ -98,3 +195,47 @@ impl ConnectorManagerConfig {
         self.connector_urls.get(&source_type)
     }
 }
+
+/// Parse default retention rules of the form `gmail=730,fireflies=365`, mapping a source
+/// type to the maximum age in days of the documents indexed from it.
+pub fn parse_retention_rules(value: &str) -> Result<HashMap<SourceType, u32>, String> {
+    let mut rules = HashMap::new();
+
+    for rule in value.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+        let (source_type, days) = rule
+            .split_once('=')
+            .ok_or_else(|| format!("expected '<source_type>=<days>', got '{}'", rule))?;
+
+        let source_type: SourceType =
+            serde_json::from_value(serde_json::Value::String(source_type.trim().to_string()))
+                .map_err(|_| format!("unknown source type '{}'", source_type.trim()))?;
+        let days = days
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|days| *days > 0)
+            .ok_or_else(|| format!("invalid number of days in '{}'", rule))?;
+
+        rules.insert(source_type, days);
+    }
+
+    Ok(rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_retention_rules() {
+        let rules = parse_retention_rules("gmail=730, fireflies=365,").unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[&SourceType::Gmail], 730);
+        assert_eq!(rules[&SourceType::Fireflies], 365);
+
+        assert!(parse_retention_rules("").unwrap().is_empty());
+        assert!(parse_retention_rules("gmail").is_err());
+        assert!(parse_retention_rules("gmail=0").is_err());
+        assert!(parse_retention_rules("fax=30").is_err());
+    }
+}
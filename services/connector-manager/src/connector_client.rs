@@ -1,5 +1,8 @@
-use crate::models::{ActionRequest, ActionResponse, ConnectorManifest, SyncRequest, SyncResponse};
-use reqwest::Client;
+use crate::models::{
+    ActionRequest, ActionResponse, ConnectionTestResult, ConnectorManifest, SyncRequest,
+    SyncResponse, TestConnectionRequest,
+};
+use reqwest::{Client, StatusCode};
 use std::time::Duration;
 use tracing::{debug, error, warn};
 
@@ -10,7 +13,8 @@ pub struct ConnectorClient {
 
 impl ConnectorClient {
     pub fn new() -> Self {
-        let client = Client::builder()
+        let client = shared::tls::client_builder()
+            .expect("Failed to configure mutual TLS")
             .timeout(Duration::from_secs(30))
             .build()
             .expect("Failed to create HTTP client");
@@ -144,6 +148,51 @@ impl ConnectorClient {
             .map_err(|e| ClientError::InvalidResponse(e.to_string()))
     }
 
+    /// Ask the connector to connect to the service with the source's credentials. Returns
+    /// `None` when the connector doesn't implement connection tests.
+    pub async fn test_connection(
+        &self,
+        connector_url: &str,
+        request: &TestConnectionRequest,
+    ) -> Result<Option<ConnectionTestResult>, ClientError> {
+        let url = format!("{}/test-connection", connector_url);
+        debug!(
+            "Testing connection at {} for source {}",
+            url, request.source_id
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| ClientError::RequestFailed(e.to_string()))?;
+
+        if matches!(
+            response.status(),
+            StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED
+        ) {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            error!("Failed to test connection: {} - {}", status, body);
+            return Err(ClientError::ConnectorError {
+                status: status.as_u16(),
+                message: body,
+            });
+        }
+
+        response
+            .json()
+            .await
+            .map(Some)
+            .map_err(|e| ClientError::InvalidResponse(e.to_string()))
+    }
+
     pub async fn health_check(&self, connector_url: &str) -> bool {
         let url = format!("{}/health", connector_url);
         match self.client.get(&url).send().await {
@@ -0,0 +1,372 @@
+use crate::config::ConnectorManagerConfig;
+use arrow_array::{ArrayRef, Int64Array, RecordBatch, StringArray, TimestampMicrosecondArray};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use shared::db::repositories::{
+    DocumentExportRow, ExportWatermark, MetadataExport, MetadataExportRepository,
+};
+use shared::ObjectStorage;
+use sqlx::PgPool;
+use std::fmt;
+use std::sync::Arc;
+use time::format_description::well_known::Rfc3339;
+use time::{Duration as TimeDuration, OffsetDateTime};
+use tokio::time::{interval, Duration};
+use tracing::{debug, error, info};
+
+/// Maximum number of documents written to a single export file.
+const MAX_ROWS_PER_FILE: i64 = 50_000;
+
+/// Documents updated more recently than this are left for the next export, so rows from
+/// transactions still committing are not skipped by the watermark.
+const EXPORT_SETTLE_DELAY: TimeDuration = TimeDuration::minutes(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+}
+
+impl ExportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+
+    fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Parquet => "application/vnd.apache.parquet",
+        }
+    }
+
+    pub fn encode(&self, rows: &[DocumentExportRow]) -> anyhow::Result<Vec<u8>> {
+        match self {
+            ExportFormat::Csv => encode_csv(rows),
+            ExportFormat::Parquet => encode_parquet(rows),
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parse a comma separated list of export formats such as `parquet,csv`.
+pub fn parse_export_formats(value: &str) -> Result<Vec<ExportFormat>, String> {
+    let mut formats = Vec::new();
+    for format in value.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+        let format = match format.to_ascii_lowercase().as_str() {
+            "csv" => ExportFormat::Csv,
+            "parquet" => ExportFormat::Parquet,
+            _ => return Err(format!("unknown export format '{}'", format)),
+        };
+        if !formats.contains(&format) {
+            formats.push(format);
+        }
+    }
+    Ok(formats)
+}
+
+const COLUMNS: [&str; 14] = [
+    "id",
+    "source_id",
+    "source_type",
+    "external_id",
+    "title",
+    "url",
+    "content_type",
+    "file_size",
+    "file_extension",
+    "metadata",
+    "attributes",
+    "created_at",
+    "updated_at",
+    "last_indexed_at",
+];
+
+fn timestamp_micros(timestamp: OffsetDateTime) -> i64 {
+    (timestamp.unix_timestamp_nanos() / 1_000) as i64
+}
+
+fn encode_csv(rows: &[DocumentExportRow]) -> anyhow::Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(COLUMNS)?;
+
+    for row in rows {
+        writer.write_record([
+            row.id.clone(),
+            row.source_id.clone(),
+            row.source_type.clone(),
+            row.external_id.clone(),
+            row.title.clone(),
+            row.url.clone().unwrap_or_default(),
+            row.content_type.clone().unwrap_or_default(),
+            row.file_size
+                .map(|size| size.to_string())
+                .unwrap_or_default(),
+            row.file_extension.clone().unwrap_or_default(),
+            row.metadata.to_string(),
+            row.attributes.to_string(),
+            row.created_at.format(&Rfc3339)?,
+            row.updated_at.format(&Rfc3339)?,
+            row.last_indexed_at.format(&Rfc3339)?,
+        ])?;
+    }
+
+    Ok(writer.into_inner()?)
+}
+
+fn encode_parquet(rows: &[DocumentExportRow]) -> anyhow::Result<Vec<u8>> {
+    let timestamp = DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()));
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("source_id", DataType::Utf8, false),
+        Field::new("source_type", DataType::Utf8, false),
+        Field::new("external_id", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new("url", DataType::Utf8, true),
+        Field::new("content_type", DataType::Utf8, true),
+        Field::new("file_size", DataType::Int64, true),
+        Field::new("file_extension", DataType::Utf8, true),
+        Field::new("metadata", DataType::Utf8, false),
+        Field::new("attributes", DataType::Utf8, false),
+        Field::new("created_at", timestamp.clone(), false),
+        Field::new("updated_at", timestamp.clone(), false),
+        Field::new("last_indexed_at", timestamp, false),
+    ]));
+
+    let strings = |value: fn(&DocumentExportRow) -> Option<String>| -> ArrayRef {
+        Arc::new(rows.iter().map(value).collect::<StringArray>())
+    };
+    let timestamps = |value: fn(&DocumentExportRow) -> OffsetDateTime| -> ArrayRef {
+        Arc::new(
+            rows.iter()
+                .map(|row| Some(timestamp_micros(value(row))))
+                .collect::<TimestampMicrosecondArray>()
+                .with_timezone("UTC"),
+        )
+    };
+
+    let columns: Vec<ArrayRef> = vec![
+        strings(|row| Some(row.id.clone())),
+        strings(|row| Some(row.source_id.clone())),
+        strings(|row| Some(row.source_type.clone())),
+        strings(|row| Some(row.external_id.clone())),
+        strings(|row| Some(row.title.clone())),
+        strings(|row| row.url.clone()),
+        strings(|row| row.content_type.clone()),
+        Arc::new(rows.iter().map(|row| row.file_size).collect::<Int64Array>()),
+        strings(|row| row.file_extension.clone()),
+        strings(|row| Some(row.metadata.to_string())),
+        strings(|row| Some(row.attributes.to_string())),
+        timestamps(|row| row.created_at),
+        timestamps(|row| row.updated_at),
+        timestamps(|row| row.last_indexed_at),
+    ];
+    let batch = RecordBatch::try_new(schema.clone(), columns)?;
+
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer = ArrowWriter::try_new(Vec::new(), schema, Some(properties))?;
+    writer.write(&batch)?;
+    Ok(writer.into_inner()?)
+}
+
+/// Periodically exports document metadata and attributes, never content, to object storage
+/// for analytics.
+///
+/// Every format is exported incrementally: each run picks up documents updated after the
+/// watermark of the format's latest export, and writes them to files under
+/// `exports/documents/<format>/<date>/`.
+pub struct MetadataExporter {
+    pool: PgPool,
+    storage: Arc<dyn ObjectStorage>,
+    formats: Vec<ExportFormat>,
+    interval_seconds: u64,
+}
+
+impl MetadataExporter {
+    pub fn new(
+        pool: PgPool,
+        storage: Arc<dyn ObjectStorage>,
+        config: &ConnectorManagerConfig,
+    ) -> Self {
+        Self {
+            pool,
+            storage,
+            formats: config.metadata_export_formats.clone(),
+            interval_seconds: config.metadata_export_interval_seconds,
+        }
+    }
+
+    pub async fn run(&self) {
+        if self.formats.is_empty() || self.interval_seconds == 0 {
+            info!("Metadata export disabled");
+            return;
+        }
+
+        let mut export_interval = interval(Duration::from_secs(self.interval_seconds));
+        info!(
+            "Metadata export started, exporting {:?} every {} seconds",
+            self.formats, self.interval_seconds
+        );
+
+        loop {
+            export_interval.tick().await;
+            match self.run_once().await {
+                Ok(exports) if exports.is_empty() => debug!("No document changes to export"),
+                Ok(exports) => info!(
+                    "Exported {} documents in {} files",
+                    exports.iter().map(|e| e.row_count).sum::<i64>(),
+                    exports.len()
+                ),
+                Err(e) => error!("Metadata export failed: {}", e),
+            }
+        }
+    }
+
+    /// Export documents changed since the last export in every format, returning the files
+    /// written.
+    pub async fn run_once(&self) -> anyhow::Result<Vec<MetadataExport>> {
+        let mut exports = Vec::new();
+        for format in &self.formats {
+            exports.extend(self.export_format(*format).await?);
+        }
+        Ok(exports)
+    }
+
+    async fn export_format(&self, format: ExportFormat) -> anyhow::Result<Vec<MetadataExport>> {
+        let repo = MetadataExportRepository::new(&self.pool);
+        let now = OffsetDateTime::now_utc();
+        let until = now - EXPORT_SETTLE_DELAY;
+        let prefix = format!("exports/documents/{}/{}", format, now.date());
+        let mut watermark = repo.latest_watermark(format.as_str()).await?;
+        let mut exports = Vec::new();
+
+        loop {
+            let rows = repo
+                .find_documents_after(watermark.as_ref(), until, MAX_ROWS_PER_FILE)
+                .await?;
+            let Some(last) = rows.last() else {
+                break;
+            };
+            let next_watermark = ExportWatermark {
+                updated_at: last.updated_at,
+                document_id: last.id.clone(),
+            };
+
+            let content = format.encode(&rows)?;
+            let content_id = self
+                .storage
+                .store_content_with_type(&content, Some(format.content_type()), Some(&prefix))
+                .await?;
+            let export = repo
+                .record_export(
+                    format.as_str(),
+                    &content_id,
+                    rows.len() as i64,
+                    &next_watermark,
+                )
+                .await?;
+            debug!(
+                "Exported {} documents as {} to content {}",
+                export.row_count, format, content_id
+            );
+            exports.push(export);
+
+            if (rows.len() as i64) < MAX_ROWS_PER_FILE {
+                break;
+            }
+            watermark = Some(next_watermark);
+        }
+
+        Ok(exports)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::Array;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use serde_json::json;
+
+    fn row(id: &str, url: Option<&str>) -> DocumentExportRow {
+        let now = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        DocumentExportRow {
+            id: id.to_string(),
+            source_id: "src".to_string(),
+            source_type: "google_drive".to_string(),
+            external_id: format!("ext-{}", id),
+            title: "Quarterly plan, draft".to_string(),
+            url: url.map(str::to_string),
+            content_type: Some("application/pdf".to_string()),
+            file_size: Some(1024),
+            file_extension: None,
+            metadata: json!({ "author": "jane" }),
+            attributes: json!({ "status": "open" }),
+            created_at: now,
+            updated_at: now,
+            last_indexed_at: now,
+        }
+    }
+
+    #[test]
+    fn test_parse_export_formats() {
+        assert_eq!(
+            parse_export_formats("parquet, CSV,parquet,").unwrap(),
+            vec![ExportFormat::Parquet, ExportFormat::Csv]
+        );
+        assert!(parse_export_formats("").unwrap().is_empty());
+        assert!(parse_export_formats("xlsx").is_err());
+    }
+
+    #[test]
+    fn test_encode_csv() {
+        let content = ExportFormat::Csv
+            .encode(&[row("a", Some("https://example.com/a"))])
+            .unwrap();
+        let mut reader = csv::Reader::from_reader(content.as_slice());
+
+        assert_eq!(
+            reader.headers().unwrap(),
+            &csv::StringRecord::from(&COLUMNS[..])
+        );
+        let records: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+        assert_eq!(records.len(), 1);
+        assert_eq!(&records[0][4], "Quarterly plan, draft");
+        assert_eq!(&records[0][8], "");
+        assert_eq!(&records[0][10], r#"{"status":"open"}"#);
+        assert_eq!(&records[0][12], "2023-11-14T22:13:20Z");
+    }
+
+    #[test]
+    fn test_encode_parquet() {
+        let content = ExportFormat::Parquet
+            .encode(&[row("a", Some("https://example.com/a")), row("b", None)])
+            .unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(bytes::Bytes::from(content))
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(Result::unwrap).collect();
+
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 2);
+        let urls = batches[0]
+            .column_by_name("url")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(urls.value(0), "https://example.com/a");
+        assert!(urls.is_null(1));
+    }
+}
@@ -1,13 +1,20 @@
 use crate::connector_client::ConnectorClient;
 use crate::models::{
-    ActionRequest, ConnectorInfo, ExecuteActionRequest, ScheduleInfo, SyncProgress,
-    TriggerSyncRequest, TriggerSyncResponse, TriggerType,
+    ActionRequest, ConnectionTestResult, ConnectorInfo, CreateWebhookRequest,
+    CreateWebhookResponse, ExecuteActionRequest, ReindexSourceRequest, ReindexSourceResponse,
+    ScheduleInfo, SourceInfo, SourceRedisKey, SourceRedisKeysResponse, SourceSyncHistory,
+    SyncErrorsQuery, SyncHistoryQuery, SyncProgress, SyncRunSummary, TestConnectionRequest,
+    TriggerSyncRequest, TriggerSyncResponse, TriggerType, ValidateSourceRequest,
+    ValidateSourceResponse, WebhookDeliveriesQuery,
 };
+use crate::sdk_auth::SdkCaller;
 use crate::sync_manager::SyncError;
+use crate::webhooks;
 use crate::AppState;
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    body::Bytes,
+    extract::{Extension, Path, Query, State},
+    http::{header, HeaderMap},
     response::{
         sse::{Event, KeepAlive, Sse},
         IntoResponse,
@@ -16,10 +23,17 @@ use axum::{
 };
 use futures::stream::Stream;
 use serde_json::json;
-use shared::db::repositories::SyncRunRepository;
-use shared::models::{SourceType, SyncType};
+use shared::cache;
+use shared::db::repositories::{
+    FailedSyncRun, SyncRunRepository, Webhook, WebhookDelivery, WebhookEvent, WebhookRepository,
+};
+use shared::encryption::EncryptionService;
+use shared::exclusion::{self, ContentExclusions, ExclusionRules};
+use shared::models::{SourceConfig, SourceType, SyncRun, SyncStatus, SyncType};
 use shared::queue::EventQueue;
+use shared::sdk_client::IDEMPOTENCY_KEY_HEADER;
 use shared::utils;
+pub use shared::ApiError;
 use shared::{Repository, ServiceCredentialsRepo, SourceRepository};
 use std::collections::HashMap;
 use std::convert::Infallible;
@@ -76,6 +90,35 @@ pub async fn trigger_sync_by_id(
     }))
 }
 
+/// Clear all sync state of a source and start a full sync, so everything is indexed again.
+pub async fn reindex_source(
+    State(state): State<AppState>,
+    Path(source_id): Path<String>,
+    request: Option<Json<ReindexSourceRequest>>,
+) -> Result<Json<ReindexSourceResponse>, ApiError> {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    info!(
+        "Re-index requested for source {} (delete_documents={})",
+        source_id, request.delete_documents
+    );
+
+    let outcome = state
+        .sync_manager
+        .reindex_source(&source_id, request.delete_documents)
+        .await
+        .map_err(|e| {
+            error!("Failed to re-index source {}: {:?}", source_id, e);
+            ApiError::from(e)
+        })?;
+
+    Ok(Json(ReindexSourceResponse {
+        sync_run_id: outcome.sync_run_id,
+        status: "started".to_string(),
+        redis_keys_cleared: outcome.redis_keys_cleared,
+        documents_deleted: outcome.documents_deleted,
+    }))
+}
+
 pub async fn cancel_sync(
     State(state): State<AppState>,
     Path(sync_run_id): Path<String>,
@@ -174,13 +217,13 @@ pub async fn list_schedules(
     let sources = source_repo
         .find_active_sources()
         .await
-        .map_err(|e| ApiError::Internal(e.to_string()))?;
+        .map_err(|e| ApiError::internal(e.to_string()))?;
 
     let source_ids: Vec<String> = sources.iter().map(|s| s.id.clone()).collect();
     let latest_runs = sync_run_repo
         .find_latest_for_sources(&source_ids)
         .await
-        .map_err(|e| ApiError::Internal(e.to_string()))?;
+        .map_err(|e| ApiError::internal(e.to_string()))?;
 
     let runs_by_source: HashMap<String, &shared::models::SyncRun> = latest_runs
         .iter()
@@ -192,12 +235,7 @@ pub async fn list_schedules(
         .map(|source| {
             let latest_run = runs_by_source.get(&source.id);
             let last_sync_at = latest_run.and_then(|r| r.completed_at);
-            let next_sync_at = match (last_sync_at, source.sync_interval_seconds) {
-                (Some(completed), Some(interval)) => {
-                    Some(completed + time::Duration::seconds(interval as i64))
-                }
-                _ => None,
-            };
+            let next_sync_at = next_sync_time(last_sync_at, source.sync_interval_seconds);
 
             ScheduleInfo {
                 source_id: source.id,
@@ -222,6 +260,272 @@ pub async fn list_schedules(
     Ok(Json(schedules))
 }
 
+fn next_sync_time(
+    last_completed_at: Option<time::OffsetDateTime>,
+    sync_interval_seconds: Option<i32>,
+) -> Option<time::OffsetDateTime> {
+    match (last_completed_at, sync_interval_seconds) {
+        (Some(completed), Some(interval)) => {
+            Some(completed + time::Duration::seconds(interval as i64))
+        }
+        _ => None,
+    }
+}
+
+pub async fn list_sync_history(
+    State(state): State<AppState>,
+    Query(query): Query<SyncHistoryQuery>,
+) -> Result<Json<Vec<SourceSyncHistory>>, ApiError> {
+    let source_repo = SourceRepository::new(state.db_pool.pool());
+    let sync_run_repo = SyncRunRepository::new(state.db_pool.pool());
+
+    let sources = source_repo
+        .find_active_sources()
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+
+    let source_ids: Vec<String> = sources.iter().map(|s| s.id.clone()).collect();
+    let recent_runs = sync_run_repo
+        .find_recent_for_sources(&source_ids, query.limit())
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+
+    let mut runs_by_source: HashMap<String, Vec<SyncRun>> = HashMap::new();
+    for run in recent_runs {
+        runs_by_source
+            .entry(run.source_id.clone())
+            .or_default()
+            .push(run);
+    }
+
+    let history = sources
+        .into_iter()
+        .map(|source| {
+            let runs = runs_by_source.remove(&source.id).unwrap_or_default();
+
+            let last_completed_at = runs
+                .iter()
+                .find(|r| r.status == SyncStatus::Completed)
+                .and_then(|r| r.completed_at);
+            let finished_runs = runs
+                .iter()
+                .filter(|r| matches!(r.status, SyncStatus::Completed | SyncStatus::Failed))
+                .count();
+            let failed_runs = runs
+                .iter()
+                .filter(|r| r.status == SyncStatus::Failed)
+                .count();
+            let error_rate = if finished_runs > 0 {
+                failed_runs as f64 / finished_runs as f64
+            } else {
+                0.0
+            };
+
+            let summaries: Vec<SyncRunSummary> = runs.iter().map(SyncRunSummary::from).collect();
+            let durations: Vec<i64> = summaries
+                .iter()
+                .filter_map(|r| r.duration_seconds)
+                .collect();
+            let avg_duration_seconds = if durations.is_empty() {
+                None
+            } else {
+                Some(durations.iter().sum::<i64>() as f64 / durations.len() as f64)
+            };
+
+            SourceSyncHistory {
+                source_id: source.id,
+                source_name: source.name,
+                source_type: source.source_type,
+                sync_interval_seconds: source.sync_interval_seconds,
+                next_sync_at: next_sync_time(last_completed_at, source.sync_interval_seconds)
+                    .map(|t| t.to_string()),
+                total_runs: summaries.len(),
+                failed_runs,
+                error_rate,
+                avg_duration_seconds,
+                runs: summaries,
+            }
+        })
+        .collect();
+
+    Ok(Json(history))
+}
+
+pub async fn list_sources(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<SourceInfo>>, ApiError> {
+    let source_repo = SourceRepository::new(state.db_pool.pool());
+    let sync_run_repo = SyncRunRepository::new(state.db_pool.pool());
+
+    let sources = source_repo
+        .find_all(1000, 0)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+
+    let source_ids: Vec<String> = sources.iter().map(|s| s.id.clone()).collect();
+    let latest_runs = sync_run_repo
+        .find_latest_for_sources(&source_ids)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    let document_counts: HashMap<String, i64> = source_repo
+        .get_document_counts_by_source()
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .into_iter()
+        .collect();
+
+    let runs_by_source: HashMap<&str, &SyncRun> = latest_runs
+        .iter()
+        .map(|r| (r.source_id.as_str(), r))
+        .collect();
+
+    let sources = sources
+        .into_iter()
+        .map(|source| SourceInfo {
+            last_sync: runs_by_source
+                .get(source.id.as_str())
+                .map(|r| SyncRunSummary::from(*r)),
+            document_count: document_counts.get(&source.id).copied().unwrap_or(0),
+            id: source.id,
+            name: source.name,
+            source_type: source.source_type,
+            is_active: source.is_active,
+            sync_interval_seconds: source.sync_interval_seconds,
+        })
+        .collect();
+
+    Ok(Json(sources))
+}
+
+pub async fn pause_source(
+    State(state): State<AppState>,
+    Path(source_id): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    set_source_active(&state, &source_id, false).await?;
+    Ok(Json(json!({ "status": "paused" })))
+}
+
+pub async fn resume_source(
+    State(state): State<AppState>,
+    Path(source_id): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    set_source_active(&state, &source_id, true).await?;
+    Ok(Json(json!({ "status": "active" })))
+}
+
+/// Revoke the SDK tokens issued for a source. Syncs triggered afterwards are issued new ones.
+pub async fn rotate_sdk_token(
+    State(state): State<AppState>,
+    Path(source_id): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("Rotating SDK tokens of source {}", source_id);
+
+    let generation = SourceRepository::new(state.db_pool.pool())
+        .rotate_sdk_token(&source_id)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found(format!("Source not found: {}", source_id)))?;
+    Ok(Json(json!({ "generation": generation })))
+}
+
+/// List the connector sync state a source keeps in Redis.
+pub async fn list_source_redis_keys(
+    State(state): State<AppState>,
+    Path(source_id): Path<String>,
+) -> Result<Json<SourceRedisKeysResponse>, ApiError> {
+    let keys: Vec<SourceRedisKey> = state
+        .sync_manager
+        .sync_state_keys(&source_id)
+        .await?
+        .into_iter()
+        .map(|(name, ttl_seconds)| SourceRedisKey {
+            ttl_policy_seconds: cache::ttl::source_key(&name),
+            name,
+            ttl_seconds,
+        })
+        .collect();
+    let keys_without_ttl = keys.iter().filter(|key| key.ttl_seconds.is_none()).count();
+    Ok(Json(SourceRedisKeysResponse {
+        keys,
+        keys_without_ttl,
+    }))
+}
+
+/// Apply the TTL policy to the connector sync state keys of a source that do not expire.
+pub async fn expire_source_redis_keys(
+    State(state): State<AppState>,
+    Path(source_id): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let keys_expired = state
+        .sync_manager
+        .expire_sync_state_keys(&source_id)
+        .await?;
+    if keys_expired > 0 {
+        info!(
+            "Set the TTL of {} Redis sync state keys of source {}",
+            keys_expired, source_id
+        );
+    }
+    Ok(Json(json!({ "keys_expired": keys_expired })))
+}
+
+/// Delete the connector sync state a source keeps in Redis, so its next sync fetches every
+/// item again. Refused while the source syncs, as the connector would write it back.
+pub async fn clear_source_redis_keys(
+    State(state): State<AppState>,
+    Path(source_id): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("Clearing Redis sync state of source {}", source_id);
+
+    if state.sync_manager.is_sync_running(&source_id).await? {
+        return Err(SyncError::SyncAlreadyRunning(source_id).into());
+    }
+    let keys_cleared = state.sync_manager.clear_sync_state_keys(&source_id).await?;
+    Ok(Json(json!({ "keys_cleared": keys_cleared })))
+}
+
+async fn set_source_active(
+    state: &AppState,
+    source_id: &str,
+    is_active: bool,
+) -> Result<(), ApiError> {
+    info!("Setting source {} active={}", source_id, is_active);
+
+    let updated = SourceRepository::new(state.db_pool.pool())
+        .set_active(source_id, is_active)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    if !updated {
+        return Err(ApiError::not_found(format!(
+            "Source not found: {}",
+            source_id
+        )));
+    }
+    Ok(())
+}
+
+pub async fn list_sync_errors(
+    State(state): State<AppState>,
+    Query(query): Query<SyncErrorsQuery>,
+) -> Result<Json<Vec<FailedSyncRun>>, ApiError> {
+    let since = match &query.since {
+        Some(since) => {
+            time::OffsetDateTime::parse(since, &time::format_description::well_known::Rfc3339)
+                .map_err(|_| {
+                    ApiError::bad_request(format!("Invalid 'since' timestamp: {}", since))
+                })?
+        }
+        None => time::OffsetDateTime::now_utc() - time::Duration::days(1),
+    };
+
+    let runs = SyncRunRepository::new(state.db_pool.pool())
+        .find_failed_since(since, query.limit())
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+
+    Ok(Json(runs))
+}
+
 pub async fn list_connectors(
     State(state): State<AppState>,
 ) -> Result<Json<Vec<ConnectorInfo>>, ApiError> {
@@ -262,14 +566,14 @@ pub async fn execute_action(
             .bind(&request.source_id)
             .fetch_optional(state.db_pool.pool())
             .await
-            .map_err(|e| ApiError::Internal(e.to_string()))?;
+            .map_err(|e| ApiError::internal(e.to_string()))?;
 
     let source_type = source
-        .ok_or_else(|| ApiError::NotFound(format!("Source not found: {}", request.source_id)))?
+        .ok_or_else(|| ApiError::not_found(format!("Source not found: {}", request.source_id)))?
         .0;
 
     let connector_url = state.config.get_connector_url(source_type).ok_or_else(|| {
-        ApiError::NotFound(format!(
+        ApiError::not_found(format!(
             "Connector not configured for type: {:?}",
             source_type
         ))
@@ -277,13 +581,13 @@ pub async fn execute_action(
 
     // Get credentials
     let creds_repo = ServiceCredentialsRepo::new(state.db_pool.pool().clone())
-        .map_err(|e| ApiError::Internal(e.to_string()))?;
+        .map_err(|e| ApiError::internal(e.to_string()))?;
     let creds = creds_repo
         .get_by_source_id(&request.source_id)
         .await
-        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .map_err(|e| ApiError::internal(e.to_string()))?
         .ok_or_else(|| {
-            ApiError::NotFound(format!(
+            ApiError::not_found(format!(
                 "Credentials not found for source: {}",
                 request.source_id
             ))
@@ -299,7 +603,7 @@ pub async fn execute_action(
     let response = client
         .execute_action(connector_url, &action_request)
         .await
-        .map_err(|e| ApiError::Internal(e.to_string()))?;
+        .map_err(|e| ApiError::internal(e.to_string()))?;
 
     Ok(Json(json!({
         "status": response.status,
@@ -330,63 +634,248 @@ pub async fn list_actions(
     Ok(Json(json!({ "actions": all_actions })))
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum ApiError {
-    #[error("Not found: {0}")]
-    NotFound(String),
+/// Check a source config against its source type's schema and, for an existing source, have
+/// the connector test the connection before the source is activated.
+pub async fn validate_source(
+    State(state): State<AppState>,
+    Json(request): Json<ValidateSourceRequest>,
+) -> Result<Json<ValidateSourceResponse>, ApiError> {
+    if let Err(e) = SourceConfig::parse(request.source_type, &request.config) {
+        return Ok(Json(ValidateSourceResponse {
+            valid: false,
+            error: Some(e),
+            connection: None,
+        }));
+    }
+
+    let Some(source_id) = request.source_id else {
+        return Ok(Json(ValidateSourceResponse {
+            valid: true,
+            error: None,
+            connection: None,
+        }));
+    };
+
+    let connection =
+        test_connection(&state, request.source_type, source_id, request.config).await?;
+
+    Ok(Json(ValidateSourceResponse {
+        valid: connection.as_ref().is_none_or(|result| result.success),
+        error: None,
+        connection,
+    }))
+}
 
-    #[error("Bad request: {0}")]
-    BadRequest(String),
+/// Have the connector connect to the service with the source's stored credentials and
+/// `config`. Returns `None` when the connector doesn't implement connection tests.
+pub(crate) async fn test_connection(
+    state: &AppState,
+    source_type: SourceType,
+    source_id: String,
+    config: serde_json::Value,
+) -> Result<Option<ConnectionTestResult>, ApiError> {
+    let connector_url = state.config.get_connector_url(source_type).ok_or_else(|| {
+        ApiError::not_found(format!(
+            "Connector not configured for type: {:?}",
+            source_type
+        ))
+    })?;
 
-    #[error("Conflict: {0}")]
-    Conflict(String),
+    let test_request = TestConnectionRequest {
+        source_id,
+        source_type,
+        config,
+    };
+    let connection = match ConnectorClient::new()
+        .test_connection(connector_url, &test_request)
+        .await
+    {
+        Ok(result) => result,
+        Err(e) => Some(ConnectionTestResult {
+            success: false,
+            message: e.to_string(),
+            scopes: vec![],
+        }),
+    };
 
-    #[error("Internal error: {0}")]
-    Internal(String),
+    info!(
+        "Tested connection of source {}: {}",
+        test_request.source_id,
+        match &connection {
+            Some(result) if result.success => "passed",
+            Some(_) => "failed",
+            None => "not supported",
+        }
+    );
+    Ok(connection)
 }
 
 impl From<SyncError> for ApiError {
     fn from(err: SyncError) -> Self {
         match err {
             SyncError::SourceNotFound(id) => {
-                ApiError::NotFound(format!("Source not found: {}", id))
+                ApiError::not_found(format!("Source not found: {}", id))
             }
             SyncError::SyncRunNotFound(id) => {
-                ApiError::NotFound(format!("Sync run not found: {}", id))
+                ApiError::not_found(format!("Sync run not found: {}", id))
             }
             SyncError::ConnectorNotConfigured(t) => {
-                ApiError::NotFound(format!("Connector not configured for type: {}", t))
+                ApiError::not_found(format!("Connector not configured for type: {}", t))
             }
             SyncError::SourceInactive(id) => {
-                ApiError::BadRequest(format!("Source is inactive: {}", id))
+                ApiError::bad_request(format!("Source is inactive: {}", id))
             }
             SyncError::SyncAlreadyRunning(id) => {
-                ApiError::Conflict(format!("Sync already running for source: {}", id))
+                ApiError::conflict(format!("Sync already running for source: {}", id))
+                    .with_retriable(true)
             }
             SyncError::SyncNotRunning(id) => {
-                ApiError::BadRequest(format!("Sync is not running: {}", id))
+                ApiError::bad_request(format!("Sync is not running: {}", id))
             }
             SyncError::ConcurrencyLimitReached => {
-                ApiError::Conflict("Concurrency limit reached, try again later".to_string())
+                ApiError::conflict("Concurrency limit reached, try again later")
+                    .with_retriable(true)
             }
-            SyncError::DatabaseError(e) => ApiError::Internal(e),
-            SyncError::ConnectorError(e) => ApiError::Internal(e.to_string()),
+            SyncError::DatabaseError(e) => ApiError::internal(e),
+            SyncError::SyncStateError(e) => ApiError::internal(e),
+            SyncError::ConnectorError(e) => ApiError::internal(e.to_string()),
         }
     }
 }
 
-impl IntoResponse for ApiError {
-    fn into_response(self) -> axum::response::Response {
-        let (status, message) = match &self {
-            ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, msg.clone()),
-            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
-            ApiError::Conflict(msg) => (StatusCode::CONFLICT, msg.clone()),
-            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
-        };
+pub async fn get_exclusion_rules(
+    State(state): State<AppState>,
+) -> Result<Json<ExclusionRules>, ApiError> {
+    let rules = exclusion::load_rules(state.db_pool.pool())
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to load exclusion rules: {}", e)))?;
+
+    Ok(Json(rules))
+}
+
+pub async fn update_exclusion_rules(
+    State(state): State<AppState>,
+    Json(rules): Json<ExclusionRules>,
+) -> Result<Json<ExclusionRules>, ApiError> {
+    ContentExclusions::new(rules.clone()).map_err(ApiError::bad_request)?;
+
+    exclusion::save_rules(state.db_pool.pool(), &rules)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to save exclusion rules: {}", e)))?;
+
+    info!(
+        "Updated content exclusion rules ({} rules)",
+        rules.rules.len()
+    );
+    Ok(Json(rules))
+}
 
-        let body = json!({ "error": message });
-        (status, Json(body)).into_response()
+pub async fn list_webhooks(State(state): State<AppState>) -> Result<Json<Vec<Webhook>>, ApiError> {
+    let webhooks = WebhookRepository::new(state.db_pool.pool())
+        .find_all()
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+
+    Ok(Json(webhooks))
+}
+
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    Json(request): Json<CreateWebhookRequest>,
+) -> Result<Json<CreateWebhookResponse>, ApiError> {
+    let url = reqwest::Url::parse(&request.url)
+        .map_err(|_| ApiError::bad_request(format!("Invalid webhook URL: {}", request.url)))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(ApiError::bad_request(
+            "Webhook URLs must use http or https".to_string(),
+        ));
+    }
+
+    if request.event_types.is_empty() {
+        return Err(ApiError::bad_request(
+            "At least one event type is required".to_string(),
+        ));
     }
+    let event_types = request
+        .event_types
+        .iter()
+        .map(|name| {
+            WebhookEvent::parse(name).ok_or_else(|| {
+                ApiError::bad_request(format!(
+                    "Unknown event type '{}', expected one of: {}",
+                    name,
+                    WebhookEvent::ALL.map(|e| e.as_str()).join(", ")
+                ))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let encryption = EncryptionService::new().map_err(|e| ApiError::internal(e.to_string()))?;
+    let secret = webhooks::generate_secret().map_err(|e| ApiError::internal(e.to_string()))?;
+    let sealed_secret = webhooks::seal_secret(&encryption, &secret)
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+
+    let webhook = WebhookRepository::new(state.db_pool.pool())
+        .create(
+            url.as_str(),
+            &event_types,
+            &sealed_secret,
+            request.created_by.as_deref(),
+        )
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+
+    info!(
+        "Registered webhook {} for {:?} at {}",
+        webhook.id, webhook.event_types, webhook.url
+    );
+    Ok(Json(CreateWebhookResponse { webhook, secret }))
+}
+
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    Path(webhook_id): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let deleted = WebhookRepository::new(state.db_pool.pool())
+        .delete(&webhook_id)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    if !deleted {
+        return Err(ApiError::not_found(format!(
+            "Webhook not found: {}",
+            webhook_id
+        )));
+    }
+
+    info!("Deleted webhook {}", webhook_id);
+    Ok(Json(json!({ "status": "deleted" })))
+}
+
+/// Delivery log of a webhook, newest first.
+pub async fn list_webhook_deliveries(
+    State(state): State<AppState>,
+    Path(webhook_id): Path<String>,
+    Query(query): Query<WebhookDeliveriesQuery>,
+) -> Result<Json<Vec<WebhookDelivery>>, ApiError> {
+    let repo = WebhookRepository::new(state.db_pool.pool());
+    if repo
+        .find_by_id(&webhook_id)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .is_none()
+    {
+        return Err(ApiError::not_found(format!(
+            "Webhook not found: {}",
+            webhook_id
+        )));
+    }
+
+    let deliveries = repo
+        .find_deliveries(&webhook_id, query.status, query.limit())
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+
+    Ok(Json(deliveries))
 }
 
 // ============================================================================
@@ -396,34 +885,70 @@ impl IntoResponse for ApiError {
 use crate::models::{
     SdkCancelSyncRequest, SdkCancelSyncResponse, SdkCompleteRequest, SdkCreateSyncRequest,
     SdkCreateSyncResponse, SdkEmitEventRequest, SdkExpiringWebhookChannelsRequest, SdkFailRequest,
-    SdkIncrementScannedRequest, SdkSaveWebhookChannelRequest, SdkSourceSyncConfigResponse,
-    SdkStatusResponse, SdkStoreContentRequest, SdkStoreContentResponse, SdkUserEmailResponse,
-    SdkWebhookChannel, SdkWebhookNotification, SdkWebhookResponse,
+    SdkIncrementScannedRequest, SdkPauseRequest, SdkSaveWebhookChannelRequest,
+    SdkSourceSyncConfigResponse, SdkStatusResponse, SdkStoreContentRequest,
+    SdkStoreContentResponse, SdkUserEmailResponse, SdkWebhookChannel, SdkWebhookNotification,
+    SdkWebhookResponse,
 };
 
 pub async fn sdk_emit_event(
     State(state): State<AppState>,
+    Extension(caller): Extension<SdkCaller>,
+    headers: HeaderMap,
     Json(request): Json<SdkEmitEventRequest>,
 ) -> Result<Json<SdkStatusResponse>, ApiError> {
     debug!(
         "SDK: Emitting event for sync_run={}, source={}",
         request.sync_run_id, request.source_id
     );
+    caller.authorize_source(&request.source_id)?;
+    caller
+        .authorize_sync_run(state.db_pool.pool(), &request.sync_run_id)
+        .await?;
+
+    // Events retried by the SDK carry the same key, used as the event's ID
+    let idempotency_key = match headers.get(IDEMPOTENCY_KEY_HEADER) {
+        Some(value) => Some(
+            value
+                .to_str()
+                .ok()
+                .filter(|key| ulid::Ulid::from_string(key).is_ok())
+                .ok_or_else(|| ApiError::bad_request("Idempotency key must be a ULID"))?,
+        ),
+        None => None,
+    };
 
-    let event_queue = EventQueue::new(state.db_pool.pool().clone());
+    // Stage the event and update the heartbeat in one transaction
+    let mut tx = state
+        .db_pool
+        .pool()
+        .begin()
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to begin transaction: {}", e)))?;
+
+    match idempotency_key {
+        Some(key) => {
+            if !EventQueue::stage_with_id(&mut tx, key, &request.source_id, &request.event)
+                .await
+                .map_err(|e| ApiError::internal(format!("Failed to enqueue event: {}", e)))?
+            {
+                debug!("SDK: Event {} was already staged", key);
+            }
+        }
+        None => {
+            EventQueue::stage(&mut tx, &request.source_id, &request.event)
+                .await
+                .map_err(|e| ApiError::internal(format!("Failed to enqueue event: {}", e)))?;
+        }
+    }
 
-    // Enqueue the event
-    event_queue
-        .enqueue(&request.source_id, &request.event)
+    SyncRunRepository::record_activity_in(&mut tx, &request.sync_run_id, 0)
         .await
-        .map_err(|e| ApiError::Internal(format!("Failed to enqueue event: {}", e)))?;
+        .map_err(|e| ApiError::internal(format!("Failed to update activity: {}", e)))?;
 
-    // Update heartbeat
-    let sync_run_repo = SyncRunRepository::new(state.db_pool.pool());
-    sync_run_repo
-        .update_activity(&request.sync_run_id)
+    tx.commit()
         .await
-        .map_err(|e| ApiError::Internal(format!("Failed to update activity: {}", e)))?;
+        .map_err(|e| ApiError::internal(format!("Failed to commit event: {}", e)))?;
 
     Ok(Json(SdkStatusResponse {
         status: "ok".to_string(),
@@ -432,9 +957,13 @@ pub async fn sdk_emit_event(
 
 pub async fn sdk_store_content(
     State(state): State<AppState>,
+    Extension(caller): Extension<SdkCaller>,
     Json(request): Json<SdkStoreContentRequest>,
 ) -> Result<Json<SdkStoreContentResponse>, ApiError> {
     debug!("SDK: Storing content for sync_run={}", request.sync_run_id);
+    caller
+        .authorize_sync_run(state.db_pool.pool(), &request.sync_run_id)
+        .await?;
 
     let content_storage = state.content_storage.clone();
 
@@ -452,29 +981,83 @@ pub async fn sdk_store_content(
     let content_id = content_storage
         .store_text(&content, Some(&prefix))
         .await
-        .map_err(|e| ApiError::Internal(format!("Failed to store content: {}", e)))?;
+        .map_err(|e| ApiError::internal(format!("Failed to store content: {}", e)))?;
 
     // Update heartbeat
     let sync_run_repo = SyncRunRepository::new(state.db_pool.pool());
     sync_run_repo
         .update_activity(&request.sync_run_id)
         .await
-        .map_err(|e| ApiError::Internal(format!("Failed to update activity: {}", e)))?;
+        .map_err(|e| ApiError::internal(format!("Failed to update activity: {}", e)))?;
+
+    Ok(Json(SdkStoreContentResponse { content_id }))
+}
+
+/// Store a binary blob, such as a thumbnail image, sent as the raw request body with its
+/// `Content-Type`. Unlike text content it is stored as is.
+pub async fn sdk_store_binary_content(
+    State(state): State<AppState>,
+    Extension(caller): Extension<SdkCaller>,
+    Path(sync_run_id): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<SdkStoreContentResponse>, ApiError> {
+    debug!(
+        "SDK: Storing {} bytes of binary content for sync_run={}",
+        body.len(),
+        sync_run_id
+    );
+    caller
+        .authorize_sync_run(state.db_pool.pool(), &sync_run_id)
+        .await?;
+
+    if body.is_empty() {
+        return Err(ApiError::bad_request("Content cannot be empty".to_string()));
+    }
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("application/octet-stream");
+
+    let today = time::OffsetDateTime::now_utc();
+    let prefix = format!(
+        "{:04}-{:02}-{:02}/{}",
+        today.year(),
+        today.month() as u8,
+        today.day(),
+        sync_run_id
+    );
+
+    let content_id = state
+        .content_storage
+        .store_content_with_type(&body, Some(content_type), Some(&prefix))
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to store content: {}", e)))?;
+
+    let sync_run_repo = SyncRunRepository::new(state.db_pool.pool());
+    sync_run_repo
+        .update_activity(&sync_run_id)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to update activity: {}", e)))?;
 
     Ok(Json(SdkStoreContentResponse { content_id }))
 }
 
 pub async fn sdk_heartbeat(
     State(state): State<AppState>,
+    Extension(caller): Extension<SdkCaller>,
     Path(sync_run_id): Path<String>,
 ) -> Result<Json<SdkStatusResponse>, ApiError> {
     debug!("SDK: Heartbeat for sync_run={}", sync_run_id);
+    caller
+        .authorize_sync_run(state.db_pool.pool(), &sync_run_id)
+        .await?;
 
     let sync_run_repo = SyncRunRepository::new(state.db_pool.pool());
     sync_run_repo
         .update_activity(&sync_run_id)
         .await
-        .map_err(|e| ApiError::Internal(format!("Failed to update activity: {}", e)))?;
+        .map_err(|e| ApiError::internal(format!("Failed to update activity: {}", e)))?;
 
     Ok(Json(SdkStatusResponse {
         status: "ok".to_string(),
@@ -483,10 +1066,14 @@ pub async fn sdk_heartbeat(
 
 pub async fn sdk_complete(
     State(state): State<AppState>,
+    Extension(caller): Extension<SdkCaller>,
     Path(sync_run_id): Path<String>,
     Json(request): Json<SdkCompleteRequest>,
 ) -> Result<Json<SdkStatusResponse>, ApiError> {
     info!("SDK: Completing sync_run={}", sync_run_id);
+    caller
+        .authorize_sync_run(state.db_pool.pool(), &sync_run_id)
+        .await?;
 
     let sync_run_repo = SyncRunRepository::new(state.db_pool.pool());
 
@@ -498,7 +1085,8 @@ pub async fn sdk_complete(
             request.documents_updated.unwrap_or(0),
         )
         .await
-        .map_err(|e| ApiError::Internal(format!("Failed to mark completed: {}", e)))?;
+        .map_err(|e| ApiError::internal(format!("Failed to mark completed: {}", e)))?;
+    webhooks::notify_sync_finished(state.db_pool.pool(), &sync_run_id).await;
 
     // Store connector state if provided
     if let Some(new_state) = request.new_state {
@@ -517,10 +1105,14 @@ pub async fn sdk_complete(
 
 pub async fn sdk_fail(
     State(state): State<AppState>,
+    Extension(caller): Extension<SdkCaller>,
     Path(sync_run_id): Path<String>,
     Json(request): Json<SdkFailRequest>,
 ) -> Result<Json<SdkStatusResponse>, ApiError> {
     info!("SDK: Failing sync_run={}: {}", sync_run_id, request.error);
+    caller
+        .authorize_sync_run(state.db_pool.pool(), &sync_run_id)
+        .await?;
 
     let sync_run_repo = SyncRunRepository::new(state.db_pool.pool());
 
@@ -528,7 +1120,47 @@ pub async fn sdk_fail(
     sync_run_repo
         .mark_failed(&sync_run_id, &request.error)
         .await
-        .map_err(|e| ApiError::Internal(format!("Failed to mark failed: {}", e)))?;
+        .map_err(|e| ApiError::internal(format!("Failed to mark failed: {}", e)))?;
+    state.sync_manager.notify_sync_failed(&sync_run_id).await;
+
+    Ok(Json(SdkStatusResponse {
+        status: "ok".to_string(),
+    }))
+}
+
+/// Pause a sync its connector stopped on shutting down. The scheduler resumes it once the
+/// connector is back, from the connector state saved here.
+pub async fn sdk_pause(
+    State(state): State<AppState>,
+    Extension(caller): Extension<SdkCaller>,
+    Path(sync_run_id): Path<String>,
+    Json(request): Json<SdkPauseRequest>,
+) -> Result<Json<SdkStatusResponse>, ApiError> {
+    info!("SDK: Pausing sync_run={}", sync_run_id);
+    caller
+        .authorize_sync_run(state.db_pool.pool(), &sync_run_id)
+        .await?;
+
+    let sync_run_repo = SyncRunRepository::new(state.db_pool.pool());
+    let paused = sync_run_repo
+        .mark_paused(&sync_run_id)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to mark paused: {}", e)))?;
+    if !paused {
+        return Err(ApiError::conflict(format!(
+            "Sync run is not running: {}",
+            sync_run_id
+        )));
+    }
+
+    if let Some(new_state) = request.new_state {
+        if let Ok(Some(sync_run)) = sync_run_repo.find_by_id(&sync_run_id).await {
+            let source_repo = SourceRepository::new(state.db_pool.pool());
+            let _ = source_repo
+                .update_connector_state(&sync_run.source_id, new_state)
+                .await;
+        }
+    }
 
     Ok(Json(SdkStatusResponse {
         status: "ok".to_string(),
@@ -537,6 +1169,7 @@ pub async fn sdk_fail(
 
 pub async fn sdk_increment_scanned(
     State(state): State<AppState>,
+    Extension(caller): Extension<SdkCaller>,
     Path(sync_run_id): Path<String>,
     Json(request): Json<SdkIncrementScannedRequest>,
 ) -> Result<Json<SdkStatusResponse>, ApiError> {
@@ -544,12 +1177,15 @@ pub async fn sdk_increment_scanned(
         "SDK: Incrementing scanned for sync_run={} by {}",
         sync_run_id, request.count
     );
+    caller
+        .authorize_sync_run(state.db_pool.pool(), &sync_run_id)
+        .await?;
 
     let sync_run_repo = SyncRunRepository::new(state.db_pool.pool());
     sync_run_repo
         .increment_scanned(&sync_run_id, request.count)
         .await
-        .map_err(|e| ApiError::Internal(format!("Failed to increment scanned: {}", e)))?;
+        .map_err(|e| ApiError::internal(format!("Failed to increment scanned: {}", e)))?;
 
     Ok(Json(SdkStatusResponse {
         status: "ok".to_string(),
@@ -558,35 +1194,39 @@ pub async fn sdk_increment_scanned(
 
 pub async fn sdk_get_source(
     State(state): State<AppState>,
+    Extension(caller): Extension<SdkCaller>,
     Path(source_id): Path<String>,
 ) -> Result<Json<shared::models::Source>, ApiError> {
     debug!("SDK: Getting source config for source_id={}", source_id);
+    caller.authorize_source(&source_id)?;
 
     let source_repo = SourceRepository::new(state.db_pool.pool());
     let source = source_repo
         .find_by_id(source_id.clone())
         .await
-        .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?
-        .ok_or_else(|| ApiError::NotFound(format!("Source not found: {}", source_id)))?;
+        .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found(format!("Source not found: {}", source_id)))?;
 
-    Ok(Json(source))
+    Ok(Json(source.redacted()))
 }
 
 pub async fn sdk_get_credentials(
     State(state): State<AppState>,
+    Extension(caller): Extension<SdkCaller>,
     Path(source_id): Path<String>,
 ) -> Result<Json<shared::models::ServiceCredentials>, ApiError> {
     debug!("SDK: Getting credentials for source_id={}", source_id);
+    caller.authorize_source(&source_id)?;
 
     let creds_repo = ServiceCredentialsRepo::new(state.db_pool.pool().clone())
-        .map_err(|e| ApiError::Internal(format!("Failed to create credentials repo: {}", e)))?;
+        .map_err(|e| ApiError::internal(format!("Failed to create credentials repo: {}", e)))?;
 
     let creds = creds_repo
         .get_by_source_id(&source_id)
         .await
-        .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?
+        .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?
         .ok_or_else(|| {
-            ApiError::NotFound(format!("Credentials not found for source: {}", source_id))
+            ApiError::not_found(format!("Credentials not found for source: {}", source_id))
         })?;
 
     Ok(Json(creds))
@@ -594,27 +1234,29 @@ pub async fn sdk_get_credentials(
 
 pub async fn sdk_get_source_sync_config(
     State(state): State<AppState>,
+    Extension(caller): Extension<SdkCaller>,
     Path(source_id): Path<String>,
 ) -> Result<Json<SdkSourceSyncConfigResponse>, ApiError> {
     debug!(
         "SDK: Getting source sync config for source_id={}",
         source_id
     );
+    caller.authorize_source(&source_id)?;
 
     let source_repo = SourceRepository::new(state.db_pool.pool());
     let source = source_repo
         .find_by_id(source_id.clone())
         .await
-        .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?
-        .ok_or_else(|| ApiError::NotFound(format!("Source not found: {}", source_id)))?;
+        .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found(format!("Source not found: {}", source_id)))?;
 
     let creds_repo = ServiceCredentialsRepo::new(state.db_pool.pool().clone())
-        .map_err(|e| ApiError::Internal(format!("Failed to create credentials repo: {}", e)))?;
+        .map_err(|e| ApiError::internal(format!("Failed to create credentials repo: {}", e)))?;
 
     let credentials = creds_repo
         .get_by_source_id(&source_id)
         .await
-        .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?
+        .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?
         .map(|c| c.credentials)
         .unwrap_or_else(|| serde_json::json!({}));
 
@@ -626,20 +1268,34 @@ pub async fn sdk_get_source_sync_config(
     }))
 }
 
+pub async fn sdk_get_exclusion_rules(
+    State(state): State<AppState>,
+) -> Result<Json<ExclusionRules>, ApiError> {
+    debug!("SDK: Getting content exclusion rules");
+
+    let rules = exclusion::load_rules(state.db_pool.pool())
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to load exclusion rules: {}", e)))?;
+
+    Ok(Json(rules))
+}
+
 pub async fn sdk_create_sync(
     State(state): State<AppState>,
+    Extension(caller): Extension<SdkCaller>,
     Json(request): Json<SdkCreateSyncRequest>,
 ) -> Result<Json<SdkCreateSyncResponse>, ApiError> {
     info!(
         "SDK: Creating sync run for source={}, type={:?}",
         request.source_id, request.sync_type
     );
+    caller.authorize_source(&request.source_id)?;
 
     let sync_run_repo = SyncRunRepository::new(state.db_pool.pool());
     let sync_run = sync_run_repo
         .create(&request.source_id, request.sync_type, "manual")
         .await
-        .map_err(|e| ApiError::Internal(format!("Failed to create sync run: {}", e)))?;
+        .map_err(|e| ApiError::internal(format!("Failed to create sync run: {}", e)))?;
 
     Ok(Json(SdkCreateSyncResponse {
         sync_run_id: sync_run.id,
@@ -648,24 +1304,30 @@ pub async fn sdk_create_sync(
 
 pub async fn sdk_cancel_sync(
     State(state): State<AppState>,
+    Extension(caller): Extension<SdkCaller>,
     Json(request): Json<SdkCancelSyncRequest>,
 ) -> Result<Json<SdkCancelSyncResponse>, ApiError> {
     info!("SDK: Cancelling sync_run={}", request.sync_run_id);
+    caller
+        .authorize_sync_run(state.db_pool.pool(), &request.sync_run_id)
+        .await?;
 
     let sync_run_repo = SyncRunRepository::new(state.db_pool.pool());
     sync_run_repo
         .mark_cancelled(&request.sync_run_id)
         .await
-        .map_err(|e| ApiError::Internal(format!("Failed to cancel sync: {}", e)))?;
+        .map_err(|e| ApiError::internal(format!("Failed to cancel sync: {}", e)))?;
 
     Ok(Json(SdkCancelSyncResponse { success: true }))
 }
 
 pub async fn sdk_get_user_email(
     State(state): State<AppState>,
+    Extension(caller): Extension<SdkCaller>,
     Path(source_id): Path<String>,
 ) -> Result<Json<SdkUserEmailResponse>, ApiError> {
     debug!("SDK: Getting user email for source_id={}", source_id);
+    caller.authorize_source(&source_id)?;
 
     let email = sqlx::query_scalar::<_, String>(
         "SELECT u.email FROM sources s JOIN users u ON s.created_by = u.id WHERE s.id = $1",
@@ -673,19 +1335,21 @@ pub async fn sdk_get_user_email(
     .bind(&source_id)
     .fetch_one(state.db_pool.pool())
     .await
-    .map_err(|e| ApiError::Internal(format!("Failed to get user email: {}", e)))?;
+    .map_err(|e| ApiError::internal(format!("Failed to get user email: {}", e)))?;
 
     Ok(Json(SdkUserEmailResponse { email }))
 }
 
 pub async fn sdk_notify_webhook(
     State(state): State<AppState>,
+    Extension(caller): Extension<SdkCaller>,
     Json(request): Json<SdkWebhookNotification>,
 ) -> Result<Json<SdkWebhookResponse>, ApiError> {
     info!(
         "SDK: Webhook notification for source={}, event_type={}",
         request.source_id, request.event_type
     );
+    caller.authorize_source(&request.source_id)?;
 
     // Trigger a sync for this source (connector-manager handles sync run creation)
     let sync_run_id = state
@@ -696,7 +1360,7 @@ pub async fn sdk_notify_webhook(
             TriggerType::Webhook,
         )
         .await
-        .map_err(|e| ApiError::Internal(format!("Failed to trigger sync: {}", e)))?;
+        .map_err(|e| ApiError::internal(format!("Failed to trigger sync: {}", e)))?;
 
     Ok(Json(SdkWebhookResponse { sync_run_id }))
 }
@@ -707,12 +1371,14 @@ pub async fn sdk_notify_webhook(
 
 pub async fn sdk_save_webhook_channel(
     State(state): State<AppState>,
+    Extension(caller): Extension<SdkCaller>,
     Json(request): Json<SdkSaveWebhookChannelRequest>,
 ) -> Result<Json<SdkWebhookChannel>, ApiError> {
     info!(
         "SDK: Saving webhook channel for source={}, channel_id={}",
         request.source_id, request.channel_id
     );
+    caller.authorize_source(&request.source_id)?;
 
     let id = shared::utils::generate_ulid();
     let expires_at = request
@@ -739,7 +1405,7 @@ pub async fn sdk_save_webhook_channel(
     .bind(expires_at)
     .execute(state.db_pool.pool())
     .await
-    .map_err(|e| ApiError::Internal(format!("Failed to save webhook channel: {}", e)))?;
+    .map_err(|e| ApiError::internal(format!("Failed to save webhook channel: {}", e)))?;
 
     Ok(Json(SdkWebhookChannel {
         id,
@@ -754,6 +1420,7 @@ pub async fn sdk_save_webhook_channel(
 
 pub async fn sdk_get_webhook_channel_by_id(
     State(state): State<AppState>,
+    Extension(caller): Extension<SdkCaller>,
     Path(channel_id): Path<String>,
 ) -> Result<Json<SdkWebhookChannel>, ApiError> {
     debug!("SDK: Getting webhook channel by id={}", channel_id);
@@ -765,9 +1432,10 @@ pub async fn sdk_get_webhook_channel_by_id(
     .fetch_one(state.db_pool.pool())
     .await
     .map_err(|e| match e {
-        sqlx::Error::RowNotFound => ApiError::NotFound(format!("Webhook channel not found: {}", channel_id)),
-        _ => ApiError::Internal(format!("Failed to get webhook channel: {}", e)),
+        sqlx::Error::RowNotFound => ApiError::not_found(format!("Webhook channel not found: {}", channel_id)),
+        _ => ApiError::internal(format!("Failed to get webhook channel: {}", e)),
     })?;
+    caller.authorize_source(&row.1)?;
 
     Ok(Json(SdkWebhookChannel {
         id: row.0,
@@ -782,9 +1450,11 @@ pub async fn sdk_get_webhook_channel_by_id(
 
 pub async fn sdk_get_webhook_channel_by_source(
     State(state): State<AppState>,
+    Extension(caller): Extension<SdkCaller>,
     Path(source_id): Path<String>,
 ) -> Result<Json<Option<SdkWebhookChannel>>, ApiError> {
     debug!("SDK: Getting webhook channel by source_id={}", source_id);
+    caller.authorize_source(&source_id)?;
 
     let result: Option<(String, String, String, String, Option<String>, String, Option<time::OffsetDateTime>)> = sqlx::query_as(
         "SELECT id, source_id, channel_id, resource_id, resource_uri, webhook_url, expires_at FROM webhook_channels WHERE source_id = $1",
@@ -792,7 +1462,7 @@ pub async fn sdk_get_webhook_channel_by_source(
     .bind(&source_id)
     .fetch_optional(state.db_pool.pool())
     .await
-    .map_err(|e| ApiError::Internal(format!("Failed to get webhook channel: {}", e)))?;
+    .map_err(|e| ApiError::internal(format!("Failed to get webhook channel: {}", e)))?;
 
     Ok(Json(result.map(|row| SdkWebhookChannel {
         id: row.0,
@@ -807,15 +1477,26 @@ pub async fn sdk_get_webhook_channel_by_source(
 
 pub async fn sdk_delete_webhook_channel(
     State(state): State<AppState>,
+    Extension(caller): Extension<SdkCaller>,
     Path(channel_id): Path<String>,
 ) -> Result<Json<SdkStatusResponse>, ApiError> {
     info!("SDK: Deleting webhook channel id={}", channel_id);
 
+    let source_id: Option<String> =
+        sqlx::query_scalar("SELECT source_id FROM webhook_channels WHERE channel_id = $1")
+            .bind(&channel_id)
+            .fetch_optional(state.db_pool.pool())
+            .await
+            .map_err(|e| ApiError::internal(format!("Failed to get webhook channel: {}", e)))?;
+    if let Some(source_id) = source_id {
+        caller.authorize_source(&source_id)?;
+    }
+
     sqlx::query("DELETE FROM webhook_channels WHERE channel_id = $1")
         .bind(&channel_id)
         .execute(state.db_pool.pool())
         .await
-        .map_err(|e| ApiError::Internal(format!("Failed to delete webhook channel: {}", e)))?;
+        .map_err(|e| ApiError::internal(format!("Failed to delete webhook channel: {}", e)))?;
 
     Ok(Json(SdkStatusResponse {
         status: "ok".to_string(),
@@ -839,7 +1520,7 @@ pub async fn sdk_get_expiring_webhook_channels(
     .bind(threshold)
     .fetch_all(state.db_pool.pool())
     .await
-    .map_err(|e| ApiError::Internal(format!("Failed to get expiring channels: {}", e)))?;
+    .map_err(|e| ApiError::internal(format!("Failed to get expiring channels: {}", e)))?;
 
     let channels = rows
         .into_iter()
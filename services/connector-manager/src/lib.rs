@@ -1,27 +1,39 @@
 pub mod config;
 pub mod connector_client;
+pub mod export;
 pub mod handlers;
 pub mod models;
+pub mod notifications;
+pub mod onboarding;
+pub mod retention;
 pub mod scheduler;
+pub mod scim;
+pub mod sdk_auth;
 pub mod sync_manager;
+pub mod webhooks;
 
 use anyhow::Result as AnyhowResult;
 use axum::{
     middleware,
-    routing::{get, post},
+    routing::{get, post, put},
     Router,
 };
 use config::ConnectorManagerConfig;
 use shared::{
+    shutdown::Shutdown,
+    startup::{connect_database, Dependencies, Startup},
     telemetry::{self, TelemetryConfig},
-    DatabasePool, ObjectStorage,
+    DatabaseConfig, DatabasePool, ObjectStorage, ServiceCredentialsRepo,
 };
 use std::net::SocketAddr;
 use std::sync::Arc;
 use sync_manager::SyncManager;
 use tower::ServiceBuilder;
 use tower_http::cors::CorsLayer;
-use tracing::info;
+use tracing::{error, info};
+
+/// Migrations this build expects the database to be at, checked on startup.
+static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("../migrations");
 
 #[derive(Clone)]
 pub struct AppState {
@@ -40,15 +52,74 @@ pub fn create_app(state: AppState) -> Router {
         .route("/sync/:id/cancel", post(handlers::cancel_sync))
         .route("/sync/:id/progress", get(handlers::get_sync_progress))
         .route("/schedules", get(handlers::list_schedules))
+        .route("/sync-history", get(handlers::list_sync_history))
+        .route("/sync-errors", get(handlers::list_sync_errors))
+        .route("/sources", get(handlers::list_sources))
+        .route("/sources/:source_id/pause", post(handlers::pause_source))
+        .route("/sources/:source_id/resume", post(handlers::resume_source))
+        .route(
+            "/sources/:source_id/reindex",
+            post(handlers::reindex_source),
+        )
         .route("/connectors", get(handlers::list_connectors))
         .route("/action", post(handlers::execute_action))
         .route("/actions", get(handlers::list_actions))
+        .route("/sources/validate", post(handlers::validate_source))
+        .nest("/onboarding", onboarding::router())
+        .route("/admin/exclusion-rules", get(handlers::get_exclusion_rules))
+        .route(
+            "/admin/exclusion-rules",
+            put(handlers::update_exclusion_rules),
+        )
+        .route(
+            "/admin/webhooks",
+            get(handlers::list_webhooks).post(handlers::create_webhook),
+        )
+        .route(
+            "/admin/webhooks/:webhook_id",
+            axum::routing::delete(handlers::delete_webhook),
+        )
+        .route(
+            "/admin/webhooks/:webhook_id/deliveries",
+            get(handlers::list_webhook_deliveries),
+        )
+        .route(
+            "/admin/sources/:source_id/redis-keys",
+            get(handlers::list_source_redis_keys).delete(handlers::clear_source_redis_keys),
+        )
+        .route(
+            "/admin/sources/:source_id/redis-keys/expire",
+            post(handlers::expire_source_redis_keys),
+        )
+        .route(
+            "/sources/:source_id/sdk-token/rotate",
+            post(handlers::rotate_sdk_token),
+        )
         // SDK endpoints - called by connectors
+        .merge(sdk_router(state.clone()))
+        // SCIM provisioning, called by identity providers through the gateway
+        .nest("/scim/v2", scim::router(state.clone()))
+        .layer(
+            ServiceBuilder::new()
+                .layer(middleware::from_fn(telemetry::middleware::trace_layer))
+                .layer(CorsLayer::permissive()),
+        )
+        .with_state(state)
+}
+
+/// Endpoints called by connectors, authenticated by source token.
+fn sdk_router(state: AppState) -> Router<AppState> {
+    Router::new()
         .route("/sdk/events", post(handlers::sdk_emit_event))
         .route("/sdk/content", post(handlers::sdk_store_content))
+        .route(
+            "/sdk/sync/:id/content",
+            post(handlers::sdk_store_binary_content),
+        )
         .route("/sdk/sync/:id/heartbeat", post(handlers::sdk_heartbeat))
         .route("/sdk/sync/:id/complete", post(handlers::sdk_complete))
         .route("/sdk/sync/:id/fail", post(handlers::sdk_fail))
+        .route("/sdk/sync/:id/pause", post(handlers::sdk_pause))
         .route(
             "/sdk/sync/:id/scanned",
             post(handlers::sdk_increment_scanned),
@@ -62,6 +133,10 @@ pub fn create_app(state: AppState) -> Router {
             "/sdk/source/:source_id/sync-config",
             get(handlers::sdk_get_source_sync_config),
         )
+        .route(
+            "/sdk/exclusion-rules",
+            get(handlers::sdk_get_exclusion_rules),
+        )
         .route("/sdk/sync/create", post(handlers::sdk_create_sync))
         .route("/sdk/sync/cancel", post(handlers::sdk_cancel_sync))
         // User email endpoint
@@ -92,12 +167,10 @@ pub fn create_app(state: AppState) -> Router {
             "/sdk/webhook/channels/expiring",
             post(handlers::sdk_get_expiring_webhook_channels),
         )
-        .layer(
-            ServiceBuilder::new()
-                .layer(middleware::from_fn(telemetry::middleware::trace_layer))
-                .layer(CorsLayer::permissive()),
-        )
-        .with_state(state)
+        .route_layer(middleware::from_fn_with_state(
+            state,
+            sdk_auth::authenticate,
+        ))
 }
 
 pub async fn run_server() -> AnyhowResult<()> {
@@ -105,6 +178,7 @@ pub async fn run_server() -> AnyhowResult<()> {
 
     let telemetry_config = TelemetryConfig::from_env("omni-connector-manager");
     telemetry::init_telemetry(telemetry_config)?;
+    shared::secrets::init_from_env().await?;
 
     info!("Connector Manager service starting...");
 
@@ -115,10 +189,33 @@ pub async fn run_server() -> AnyhowResult<()> {
         config.connector_urls.keys().collect::<Vec<_>>()
     );
 
-    let db_pool = DatabasePool::from_config(&config.database)
+    // Listen while connecting, so probes can tell starting up from unhealthy
+    let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
+    info!("Connector Manager service listening on {}", addr);
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    let startup = Startup::default();
+    let server = startup.serve(listener, Shutdown::on_signal());
+
+    let db_pool = connect_database(&config.database)
         .await
         .map_err(|e| anyhow::anyhow!("Failed to create database pool: {}", e))?;
     info!("Database pool initialized");
+    shared::db::migrations::ensure_schema_current(
+        db_pool.pool(),
+        &MIGRATOR,
+        "omni-connector-manager",
+    )
+    .await?;
+    tokio::spawn(shared::db::metrics::report_pool_metrics(
+        db_pool.pool().clone(),
+        config.database.clone(),
+    ));
+    if let Err(e) = shared::feature_flags::init(db_pool.pool()).await {
+        error!(
+            "Failed to load feature flags, using the environment's: {}",
+            e
+        );
+    }
 
     let content_storage = shared::StorageFactory::from_env(db_pool.pool().clone())
         .await
@@ -136,18 +233,68 @@ pub async fn run_server() -> AnyhowResult<()> {
 
     // Start scheduler in background
     let scheduler = scheduler::Scheduler::new(db_pool.pool().clone(), config.clone(), sync_manager);
+    let retention =
+        retention::RetentionEnforcer::new(db_pool.pool().clone(), &config, scheduler.replica_id());
     tokio::spawn(async move {
         scheduler.run().await;
     });
     info!("Scheduler started");
 
-    let app = create_app(app_state);
+    tokio::spawn(async move {
+        retention.run().await;
+    });
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
-    info!("Connector Manager service listening on {}", addr);
+    let compactor = retention::SyncRunCompactor::new(db_pool.pool().clone(), &config);
+    tokio::spawn(async move {
+        compactor.run().await;
+    });
 
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    let exporter = export::MetadataExporter::new(
+        db_pool.pool().clone(),
+        app_state.content_storage.clone(),
+        &config,
+    );
+    tokio::spawn(async move {
+        exporter.run().await;
+    });
+
+    let webhook_dispatcher = webhooks::WebhookDispatcher::new(db_pool.pool().clone(), &config);
+    tokio::spawn(async move {
+        webhook_dispatcher.run().await;
+    });
+
+    let outbox_relay = shared::queue::OutboxRelay::new(db_pool.pool().clone());
+    tokio::spawn(async move {
+        outbox_relay.run().await;
+    });
+
+    startup.ready(
+        shared::ip_allowlist::restrict_admin_routes(create_app(app_state)),
+        Dependencies::new(db_pool.pool().clone()),
+    );
+    server.await??;
+
+    Ok(())
+}
+
+/// Re-encrypt all service credentials with the current `ENCRYPTION_KEY`. Used after a key
+/// rotation, with the retired key listed in `ENCRYPTION_KEY_PREVIOUS` until this completes.
+pub async fn reencrypt_credentials() -> AnyhowResult<()> {
+    dotenvy::dotenv().ok();
+    tracing_subscriber::fmt::init();
+    shared::secrets::init_from_env().await?;
+
+    let db_pool = DatabasePool::from_config(&DatabaseConfig::from_env())
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create database pool: {}", e))?;
+
+    let repo = ServiceCredentialsRepo::new(db_pool.pool().clone())?;
+    info!(
+        "Re-encrypting service credentials with key {}",
+        repo.current_key_id()
+    );
+    let count = repo.reencrypt_credentials().await?;
+    info!("Re-encrypted {} service credentials", count);
 
     Ok(())
 }
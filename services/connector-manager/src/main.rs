@@ -2,5 +2,12 @@ use anyhow::Result;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    omni_connector_manager::run_server().await
+    match std::env::args().nth(1).as_deref() {
+        None => omni_connector_manager::run_server().await,
+        Some("reencrypt-credentials") => omni_connector_manager::reencrypt_credentials().await,
+        Some(command) => anyhow::bail!(
+            "Unknown command: {}. Usage: omni-connector-manager [reencrypt-credentials]",
+            command
+        ),
+    }
 }
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use shared::models::SourceType;
+use shared::db::repositories::{Webhook, WebhookDeliveryStatus};
+use shared::models::{SourceType, SyncRun, SyncStatus, SyncType};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectorManifest {
@@ -18,15 +19,8 @@ pub struct ActionDefinition {
     pub parameters: JsonValue,
 }
 
-// SyncRequest and SyncResponse are imported from shared crate
-pub use shared::models::{SyncRequest, SyncResponse};
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ActionRequest {
-    pub action: String,
-    pub params: JsonValue,
-    pub credentials: JsonValue,
-}
+// ActionRequest, SyncRequest and SyncResponse are imported from shared crate
+pub use shared::models::{ActionRequest, SyncRequest, SyncResponse};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActionResponse {
@@ -84,6 +78,129 @@ pub struct ScheduleInfo {
     pub sync_status: Option<String>,
 }
 
+/// A configured source and the state of its latest sync, for operators.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceInfo {
+    pub id: String,
+    pub name: String,
+    pub source_type: SourceType,
+    pub is_active: bool,
+    pub sync_interval_seconds: Option<i32>,
+    pub document_count: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_sync: Option<SyncRunSummary>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SyncErrorsQuery {
+    /// Only return runs that failed after this time, RFC 3339. Defaults to the last 24 hours.
+    pub since: Option<String>,
+    pub limit: Option<i64>,
+}
+
+impl SyncErrorsQuery {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(100).clamp(1, 1000)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    /// Events to deliver, e.g. `sync.failed`
+    pub event_types: Vec<String>,
+    pub created_by: Option<String>,
+}
+
+/// A newly registered webhook. The signing secret is only ever returned here.
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateWebhookResponse {
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    pub secret: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookDeliveriesQuery {
+    pub status: Option<WebhookDeliveryStatus>,
+    pub limit: Option<i64>,
+}
+
+impl WebhookDeliveriesQuery {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(50).clamp(1, 500)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SyncHistoryQuery {
+    pub limit: Option<i64>,
+}
+
+impl SyncHistoryQuery {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(20).clamp(1, 100)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncRunSummary {
+    pub id: String,
+    pub sync_type: SyncType,
+    pub status: SyncStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<i64>,
+    pub documents_scanned: i32,
+    pub documents_processed: i32,
+    pub documents_updated: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+}
+
+impl From<&SyncRun> for SyncRunSummary {
+    fn from(run: &SyncRun) -> Self {
+        let duration_seconds = match (run.started_at, run.completed_at) {
+            (Some(started), Some(completed)) => Some((completed - started).whole_seconds()),
+            _ => None,
+        };
+
+        Self {
+            id: run.id.clone(),
+            sync_type: run.sync_type,
+            status: run.status,
+            started_at: run.started_at.map(|t| t.to_string()),
+            completed_at: run.completed_at.map(|t| t.to_string()),
+            duration_seconds,
+            documents_scanned: run.documents_scanned,
+            documents_processed: run.documents_processed,
+            documents_updated: run.documents_updated,
+            error_message: run.error_message.clone(),
+        }
+    }
+}
+
+/// Recent sync activity of one source, shaped for the admin dashboard.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceSyncHistory {
+    pub source_id: String,
+    pub source_name: String,
+    pub source_type: SourceType,
+    pub sync_interval_seconds: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_sync_at: Option<String>,
+    pub total_runs: usize,
+    pub failed_runs: usize,
+    /// Share of finished runs in the window that failed, between 0.0 and 1.0.
+    pub error_rate: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_duration_seconds: Option<f64>,
+    pub runs: Vec<SyncRunSummary>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectorInfo {
     pub source_type: SourceType,
@@ -106,6 +223,149 @@ pub struct TriggerSyncResponse {
     pub status: String,
 }
 
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReindexSourceRequest {
+    /// Delete the source's documents before the full sync instead of updating them in place
+    #[serde(default)]
+    pub delete_documents: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReindexSourceResponse {
+    pub sync_run_id: String,
+    pub status: String,
+    pub redis_keys_cleared: usize,
+    pub documents_deleted: u64,
+}
+
+/// A key of a source's connector sync state in Redis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceRedisKey {
+    /// The key without the source's prefix, e.g. `google:drive:file:<file_id>`
+    pub name: String,
+    /// Seconds until the key expires, `None` when it does not
+    pub ttl_seconds: Option<i64>,
+    /// Seconds the key is kept for when written, by the keyspace's TTL policy
+    pub ttl_policy_seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceRedisKeysResponse {
+    pub keys: Vec<SourceRedisKey>,
+    /// Keys that do not expire, against the TTL policy
+    pub keys_without_ttl: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidateSourceRequest {
+    pub source_type: SourceType,
+    #[serde(default = "empty_config")]
+    pub config: JsonValue,
+    /// Existing source whose credentials the connector tests the connection with. The
+    /// connection test is skipped without it.
+    #[serde(default)]
+    pub source_id: Option<String>,
+}
+
+fn empty_config() -> JsonValue {
+    JsonValue::Object(Default::default())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidateSourceResponse {
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connection: Option<ConnectionTestResult>,
+}
+
+/// Body of the connector protocol's `POST /test-connection`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestConnectionRequest {
+    pub source_id: String,
+    pub source_type: SourceType,
+    pub config: JsonValue,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionTestResult {
+    pub success: bool,
+    pub message: String,
+    /// What the credentials can reach, for connectors that list it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<SourceScope>,
+}
+
+/// A part of a service that a source syncs, e.g. a Confluence space, a Jira project or a
+/// drive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceScope {
+    pub kind: String,
+    pub id: String,
+    pub name: String,
+    /// Documents a sync of the scope would index, if the connector can count them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_count: Option<i64>,
+}
+
+/// What the setup of a source of a type can offer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceCapabilities {
+    pub source_type: SourceType,
+    /// Whether a connector is deployed for the source type.
+    pub configured: bool,
+    pub healthy: bool,
+    pub sync_modes: Vec<String>,
+    pub actions: Vec<String>,
+    /// Whether a source of the type has required settings, e.g. a root URL, rather than
+    /// syncing everything its credentials reach with an empty config.
+    pub config_required: bool,
+}
+
+/// A step of the setup of a source created with its credentials, before its first sync.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceSetupRequest {
+    pub source_id: String,
+    pub source_type: SourceType,
+    #[serde(default = "empty_config")]
+    pub config: JsonValue,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourcePreview {
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub scopes: Vec<SourceScope>,
+    /// Documents the first sync would index, if the connector counted every scope.
+    pub estimated_documents: Option<i64>,
+}
+
+impl SourcePreview {
+    pub fn new(scopes: Vec<SourceScope>) -> Self {
+        let estimated_documents = scopes
+            .iter()
+            .map(|scope| scope.document_count)
+            .sum::<Option<i64>>();
+        Self {
+            valid: true,
+            error: None,
+            scopes,
+            estimated_documents,
+        }
+    }
+
+    pub fn invalid(error: String) -> Self {
+        Self {
+            valid: false,
+            error: Some(error),
+            scopes: vec![],
+            estimated_documents: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecuteActionRequest {
     pub source_id: String,
@@ -113,7 +373,7 @@ pub struct ExecuteActionRequest {
     pub params: JsonValue,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct SdkSourceSyncConfigResponse {
     pub config: JsonValue,
     pub credentials: JsonValue,
@@ -121,6 +381,20 @@ pub struct SdkSourceSyncConfigResponse {
     pub source_type: SourceType,
 }
 
+impl std::fmt::Debug for SdkSourceSyncConfigResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SdkSourceSyncConfigResponse")
+            .field("config", &shared::redaction::redact_json(&self.config))
+            .field(
+                "credentials",
+                &shared::redaction::redact_all(&self.credentials),
+            )
+            .field("connector_state", &self.connector_state)
+            .field("source_type", &self.source_type)
+            .finish()
+    }
+}
+
 // ============================================================================
 // SDK Models - Used by connectors to communicate with connector-manager
 // ============================================================================
@@ -160,6 +434,13 @@ pub struct SdkFailRequest {
     pub error: String,
 }
 
+/// A connector stopping a sync as it shuts down, with the cursor to resume it from.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SdkPauseRequest {
+    #[serde(default)]
+    pub new_state: Option<JsonValue>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SdkIncrementScannedRequest {
     #[serde(default = "default_count")]
@@ -0,0 +1,153 @@
+use shared::db::repositories::SyncRunRepository;
+use shared::email::templates::SYNC_FAILED;
+use shared::models::{NotificationKind, SyncRun, SyncStatus, SyncType};
+use shared::{EmailMessage, EmailNotifier, Repository, Source, SourceRepository, UserRepository};
+use sqlx::PgPool;
+use time::format_description::well_known::Rfc3339;
+use tracing::{debug, error, warn};
+
+/// Email the people responsible for a source when one of its syncs fails: the user who
+/// created it and all admins, less anyone who turned sync failure emails off. Sending
+/// happens in the background and failures are logged, so email never holds up or fails
+/// the sync bookkeeping.
+pub fn notify_sync_failed(pool: &PgPool, notifier: &EmailNotifier, sync_run_id: &str) {
+    let pool = pool.clone();
+    let notifier = notifier.clone();
+    let sync_run_id = sync_run_id.to_string();
+    tokio::spawn(async move {
+        if let Err(e) = send_sync_failed(&pool, &notifier, &sync_run_id).await {
+            error!(
+                "Failed to send sync failure emails for sync run {}: {}",
+                sync_run_id, e
+            );
+        }
+    });
+}
+
+async fn send_sync_failed(
+    pool: &PgPool,
+    notifier: &EmailNotifier,
+    sync_run_id: &str,
+) -> anyhow::Result<()> {
+    let Some(run) = SyncRunRepository::new(pool).find_by_id(sync_run_id).await? else {
+        return Ok(());
+    };
+    if run.status != SyncStatus::Failed {
+        return Ok(());
+    }
+    let Some(source) = SourceRepository::new(pool)
+        .find_by_id(run.source_id.clone())
+        .await?
+    else {
+        return Ok(());
+    };
+
+    let recipients = UserRepository::new(pool)
+        .find_notification_recipients(
+            NotificationKind::SyncFailures,
+            std::slice::from_ref(&source.created_by),
+        )
+        .await?;
+    if recipients.is_empty() {
+        debug!("No recipients for sync failure of source {}", source.id);
+        return Ok(());
+    }
+
+    let message = sync_failed_message(&run, &source, notifier.app_url());
+    for recipient in &recipients {
+        if let Err(e) = notifier.send(recipient, &message).await {
+            warn!(
+                "Failed to email sync failure of source {} to {}: {}",
+                source.id, recipient, e
+            );
+        }
+    }
+    debug!(
+        "Emailed sync failure of source {} to {} recipients",
+        source.id,
+        recipients.len()
+    );
+
+    Ok(())
+}
+
+fn sync_failed_message(run: &SyncRun, source: &Source, app_url: &str) -> EmailMessage {
+    let source_type = serde_json::to_value(source.source_type)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let sync_type = match run.sync_type {
+        SyncType::Full => "full",
+        SyncType::Incremental => "incremental",
+    };
+    let completed_at = run
+        .completed_at
+        .unwrap_or(run.updated_at)
+        .format(&Rfc3339)
+        .unwrap_or_default();
+    let error = run.error_message.as_deref().unwrap_or("Unknown error");
+    let source_url = format!("{}/admin/settings/integrations", app_url);
+
+    SYNC_FAILED.render(&[
+        ("source_name", &source.name),
+        ("source_type", &source_type),
+        ("sync_type", sync_type),
+        ("completed_at", &completed_at),
+        ("error", error),
+        ("source_url", &source_url),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::models::{SourceType, UserFilterMode};
+    use time::OffsetDateTime;
+
+    #[test]
+    fn test_sync_failed_message() {
+        let timestamp = OffsetDateTime::from_unix_timestamp(1_772_366_400).unwrap();
+        let source = Source {
+            id: "source-1".to_string(),
+            name: "Engineering <Drive>".to_string(),
+            source_type: SourceType::GoogleDrive,
+            config: serde_json::json!({}),
+            is_active: true,
+            is_deleted: false,
+            user_filter_mode: UserFilterMode::All,
+            user_whitelist: None,
+            user_blacklist: None,
+            connector_state: None,
+            sync_interval_seconds: None,
+            created_at: timestamp,
+            updated_at: timestamp,
+            created_by: "user-1".to_string(),
+        };
+        let run = SyncRun {
+            id: "run-1".to_string(),
+            source_id: source.id.clone(),
+            sync_type: SyncType::Incremental,
+            started_at: Some(timestamp),
+            completed_at: Some(timestamp),
+            status: SyncStatus::Failed,
+            documents_scanned: 0,
+            documents_processed: 0,
+            documents_updated: 0,
+            error_message: Some("Token expired".to_string()),
+            created_at: timestamp,
+            updated_at: timestamp,
+        };
+
+        let message = sync_failed_message(&run, &source, "https://omni.example.com");
+        assert_eq!(message.subject, "Omni: sync of Engineering <Drive> failed");
+        assert!(message.text.contains(
+            "The incremental sync of Engineering <Drive> (google_drive) failed at 2026-03-01T12:00:00Z."
+        ));
+        assert!(message.text.contains("Error: Token expired"));
+        assert!(message
+            .text
+            .contains("https://omni.example.com/admin/settings/integrations"));
+        assert!(message.html.contains("Engineering &lt;Drive&gt;"));
+        assert!(!message.html.contains("{{"));
+    }
+}
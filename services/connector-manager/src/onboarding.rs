@@ -0,0 +1,110 @@
+//! Endpoints driving the step-by-step setup of a new source.
+//!
+//! 1. `GET /onboarding/:source_type` tells whether a connector is deployed for the source
+//!    type, what it supports and whether a new source needs settings.
+//! 2. Once the source is created with its credentials, `POST /onboarding/credentials` has
+//!    the connector test the connection with them.
+//! 3. `POST /onboarding/preview` lists what the credentials reach, e.g. spaces, projects or
+//!    drives, and how many documents the first sync would index where the connector can
+//!    count them, before the source is activated and its first sync scheduled.
+
+use crate::connector_client::ConnectorClient;
+use crate::handlers::test_connection;
+use crate::models::{
+    SourceCapabilities, SourcePreview, SourceSetupRequest, ValidateSourceResponse,
+};
+use crate::AppState;
+use axum::{
+    extract::{Path, State},
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::json;
+use shared::models::{SourceConfig, SourceType};
+use shared::ApiError;
+
+/// Onboarding routes, mounted under `/onboarding`.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/:source_type", get(get_capabilities))
+        .route("/credentials", post(validate_credentials))
+        .route("/preview", post(preview_source))
+}
+
+async fn get_capabilities(
+    State(state): State<AppState>,
+    Path(source_type): Path<SourceType>,
+) -> Json<SourceCapabilities> {
+    let mut capabilities = SourceCapabilities {
+        source_type,
+        configured: false,
+        healthy: false,
+        sync_modes: vec![],
+        actions: vec![],
+        config_required: SourceConfig::parse(source_type, &json!({})).is_err(),
+    };
+
+    let Some(url) = state.config.get_connector_url(source_type) else {
+        return Json(capabilities);
+    };
+    capabilities.configured = true;
+
+    let client = ConnectorClient::new();
+    capabilities.healthy = client.health_check(url).await;
+    if capabilities.healthy {
+        if let Ok(manifest) = client.get_manifest(url).await {
+            capabilities.sync_modes = manifest.sync_modes;
+            capabilities.actions = manifest
+                .actions
+                .into_iter()
+                .map(|action| action.name)
+                .collect();
+        }
+    }
+    Json(capabilities)
+}
+
+async fn validate_credentials(
+    State(state): State<AppState>,
+    Json(request): Json<SourceSetupRequest>,
+) -> Result<Json<ValidateSourceResponse>, ApiError> {
+    let connection = test_connection(
+        &state,
+        request.source_type,
+        request.source_id,
+        request.config,
+    )
+    .await?;
+    Ok(Json(ValidateSourceResponse {
+        valid: connection.as_ref().is_none_or(|result| result.success),
+        error: None,
+        connection,
+    }))
+}
+
+async fn preview_source(
+    State(state): State<AppState>,
+    Json(request): Json<SourceSetupRequest>,
+) -> Result<Json<SourcePreview>, ApiError> {
+    if let Err(e) = SourceConfig::parse(request.source_type, &request.config) {
+        return Ok(Json(SourcePreview::invalid(e)));
+    }
+
+    let preview = match test_connection(
+        &state,
+        request.source_type,
+        request.source_id,
+        request.config,
+    )
+    .await?
+    {
+        Some(result) if result.success => SourcePreview::new(result.scopes),
+        Some(result) => SourcePreview::invalid(result.message),
+        // Nothing is known about what the source will sync
+        None => SourcePreview {
+            estimated_documents: None,
+            ..SourcePreview::new(vec![])
+        },
+    };
+    Ok(Json(preview))
+}
@@ -0,0 +1,279 @@
+use crate::config::ConnectorManagerConfig;
+use crate::scheduler::LEASE_TICKS;
+use shared::db::repositories::{
+    DocumentRepository, SourceLeaseRepository, SourceRepository, SyncRunRepository,
+};
+use shared::models::{ConnectorEvent, Source, SourceType};
+use shared::queue::EventQueue;
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use time::{Duration as TimeDuration, OffsetDateTime};
+use tokio::time::{interval, Duration};
+use tracing::{debug, error, info, warn};
+use ulid::Ulid;
+
+/// Maximum number of deletions queued per source in a single pass. Larger backlogs are
+/// worked off over subsequent passes.
+const MAX_DELETIONS_PER_SOURCE: i64 = 5000;
+
+/// Number of sync runs rolled into daily summaries per statement.
+const SYNC_RUN_COMPACTION_BATCH_SIZE: i64 = 1000;
+
+/// Source config key overriding the default retention for a single source. A positive
+/// number of days replaces the default for the source type, `0` disables retention.
+pub const RETENTION_DAYS_CONFIG_KEY: &str = "retention_days";
+
+/// Periodically deletes documents older than their source's retention period by emitting
+/// `DocumentDeleted` events for the indexer, and records the cutoff on the source so the
+/// indexer drops those documents when a later sync sends them again.
+///
+/// Of several replicas, a source is enforced by the one holding its scheduling lease, see
+/// [`crate::scheduler::Scheduler`].
+///
+/// Retention passes are not sync runs: every pass generates its own id, which is used as
+/// the `sync_run_id` of the events it emits so they are processed as one batch.
+pub struct RetentionEnforcer {
+    pool: PgPool,
+    rules: HashMap<SourceType, u32>,
+    interval_seconds: u64,
+    lease_seconds: i64,
+    /// The scheduler's replica id, whose leases this enforcer shares.
+    replica_id: String,
+}
+
+impl RetentionEnforcer {
+    pub fn new(pool: PgPool, config: &ConnectorManagerConfig, replica_id: &str) -> Self {
+        Self {
+            pool,
+            rules: config.retention_rules.clone(),
+            interval_seconds: config.retention_interval_seconds,
+            lease_seconds: (config.scheduler_interval_seconds * LEASE_TICKS) as i64,
+            replica_id: replica_id.to_string(),
+        }
+    }
+
+    pub async fn run(&self) {
+        if self.interval_seconds == 0 {
+            info!("Retention enforcement disabled");
+            return;
+        }
+
+        let mut retention_interval = interval(Duration::from_secs(self.interval_seconds));
+        info!(
+            "Retention enforcement started, checking every {} seconds with default rules {:?}",
+            self.interval_seconds, self.rules
+        );
+
+        loop {
+            retention_interval.tick().await;
+            match self.run_once().await {
+                Ok(0) => debug!("Retention pass found no expired documents"),
+                Ok(queued) => info!("Retention pass queued {} document deletions", queued),
+                Err(e) => error!("Retention pass failed: {}", e),
+            }
+        }
+    }
+
+    /// Queue deletions for every expired document across the active sources this replica
+    /// holds the lease of, returning the number of deletions queued.
+    pub async fn run_once(&self) -> anyhow::Result<usize> {
+        let source_repo = SourceRepository::new(&self.pool);
+        let sources = source_repo.find_active_sources().await?;
+        let source_ids: Vec<String> = sources.iter().map(|source| source.id.clone()).collect();
+        let leased: HashSet<String> = SourceLeaseRepository::new(&self.pool)
+            .acquire(&self.replica_id, &source_ids, self.lease_seconds)
+            .await?
+            .into_iter()
+            .collect();
+        let document_repo = DocumentRepository::new(&self.pool);
+        let queue = EventQueue::new(self.pool.clone());
+        let pass_id = Ulid::new().to_string();
+        let now = OffsetDateTime::now_utc();
+        let mut queued = 0;
+
+        for source in sources.iter().filter(|source| leased.contains(&source.id)) {
+            let Some(days) = retention_days(&self.rules, source) else {
+                source_repo.set_retention_cutoff(&source.id, None).await?;
+                continue;
+            };
+            let cutoff = now - TimeDuration::days(days as i64);
+            // Recorded before queueing deletions, so a sync running meanwhile can't bring
+            // back what they delete
+            source_repo
+                .set_retention_cutoff(&source.id, Some(cutoff))
+                .await?;
+
+            let expired = document_repo
+                .find_expired_external_ids(&source.id, cutoff, MAX_DELETIONS_PER_SOURCE)
+                .await?;
+            if expired.is_empty() {
+                continue;
+            }
+
+            info!(
+                "Source {} ({:?}) has {} documents older than {} days, queueing deletion",
+                source.id,
+                source.source_type,
+                expired.len(),
+                days
+            );
+
+            for document_id in expired {
+                let event = ConnectorEvent::DocumentDeleted {
+                    sync_run_id: pass_id.clone(),
+                    source_id: source.id.clone(),
+                    document_id,
+                };
+                queue.enqueue(&source.id, &event).await?;
+                queued += 1;
+            }
+        }
+
+        Ok(queued)
+    }
+}
+
+/// Keeps recent sync runs verbatim and rolls runs older than the retention period into
+/// per-day summaries, so `sync_runs` stays bounded while history remains queryable.
+pub struct SyncRunCompactor {
+    pool: PgPool,
+    retention_days: u32,
+    interval_seconds: u64,
+}
+
+impl SyncRunCompactor {
+    pub fn new(pool: PgPool, config: &ConnectorManagerConfig) -> Self {
+        Self {
+            pool,
+            retention_days: config.sync_run_retention_days,
+            interval_seconds: config.retention_interval_seconds,
+        }
+    }
+
+    pub async fn run(&self) {
+        if self.interval_seconds == 0 || self.retention_days == 0 {
+            info!("Sync run compaction disabled");
+            return;
+        }
+
+        let mut compaction_interval = interval(Duration::from_secs(self.interval_seconds));
+        info!(
+            "Sync run compaction started, keeping {} days of runs",
+            self.retention_days
+        );
+
+        loop {
+            compaction_interval.tick().await;
+            match self.run_once().await {
+                Ok(0) => debug!("Sync run compaction found no expired runs"),
+                Ok(compacted) => info!("Compacted {} sync runs into daily summaries", compacted),
+                Err(e) => error!("Sync run compaction failed: {}", e),
+            }
+        }
+    }
+
+    /// Compact every expired run, returning the number of runs compacted.
+    pub async fn run_once(&self) -> anyhow::Result<u64> {
+        let repo = SyncRunRepository::new(&self.pool);
+        let cutoff = OffsetDateTime::now_utc() - TimeDuration::days(self.retention_days as i64);
+        let mut total = 0;
+
+        loop {
+            let compacted = repo
+                .compact_runs_before(cutoff, SYNC_RUN_COMPACTION_BATCH_SIZE)
+                .await?;
+            total += compacted;
+            if compacted < SYNC_RUN_COMPACTION_BATCH_SIZE as u64 {
+                return Ok(total);
+            }
+        }
+    }
+}
+
+/// Effective retention period for a source: its own override if set, otherwise the
+/// default for its source type. `None` means documents are kept indefinitely.
+pub fn retention_days(rules: &HashMap<SourceType, u32>, source: &Source) -> Option<u32> {
+    match source.config.get(RETENTION_DAYS_CONFIG_KEY) {
+        Some(value) => match value.as_u64() {
+            Some(0) => None,
+            Some(days) => Some(days.min(u32::MAX as u64) as u32),
+            None => {
+                warn!(
+                    "Ignoring invalid {} for source {}: {}",
+                    RETENTION_DAYS_CONFIG_KEY, source.id, value
+                );
+                rules.get(&source.source_type).copied()
+            }
+        },
+        None => rules.get(&source.source_type).copied(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use shared::models::UserFilterMode;
+
+    fn source(source_type: SourceType, config: serde_json::Value) -> Source {
+        let now = OffsetDateTime::now_utc();
+        Source {
+            id: "src".to_string(),
+            name: "Source".to_string(),
+            source_type,
+            config,
+            is_active: true,
+            is_deleted: false,
+            user_filter_mode: UserFilterMode::All,
+            user_whitelist: None,
+            user_blacklist: None,
+            connector_state: None,
+            sync_interval_seconds: None,
+            created_at: now,
+            updated_at: now,
+            created_by: "user".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_retention_days_resolution() {
+        let rules = HashMap::from([(SourceType::Gmail, 730)]);
+
+        assert_eq!(
+            retention_days(&rules, &source(SourceType::Gmail, json!({}))),
+            Some(730)
+        );
+        assert_eq!(
+            retention_days(&rules, &source(SourceType::Slack, json!({}))),
+            None
+        );
+        assert_eq!(
+            retention_days(
+                &rules,
+                &source(SourceType::Gmail, json!({ "retention_days": 90 }))
+            ),
+            Some(90)
+        );
+        assert_eq!(
+            retention_days(
+                &rules,
+                &source(SourceType::Gmail, json!({ "retention_days": 0 }))
+            ),
+            None
+        );
+        assert_eq!(
+            retention_days(
+                &rules,
+                &source(SourceType::Slack, json!({ "retention_days": 30 }))
+            ),
+            Some(30)
+        );
+        assert_eq!(
+            retention_days(
+                &rules,
+                &source(SourceType::Gmail, json!({ "retention_days": "forever" }))
+            ),
+            Some(730)
+        );
+    }
+}
@@ -1,18 +1,28 @@
 use crate::config::ConnectorManagerConfig;
 use crate::models::TriggerType;
 use crate::sync_manager::{SyncError, SyncManager};
-use shared::db::repositories::SourceRepository;
+use shared::db::repositories::{SourceLeaseRepository, SourceRepository, SyncRunRepository};
 use shared::models::SyncType;
 use sqlx::PgPool;
+use std::collections::HashSet;
 use std::sync::Arc;
 use time::OffsetDateTime;
 use tokio::time::{interval, Duration};
 use tracing::{debug, error, info, warn};
 
+/// Scheduler ticks a lease on scheduling a source lasts. Another replica takes over the
+/// sources of one that stopped within this many ticks.
+pub(crate) const LEASE_TICKS: u64 = 3;
+
+/// Triggers the syncs of sources that are due. Of several replicas, each source is scheduled
+/// by the one holding its lease: the first to find it due takes the lease, and renews it on
+/// every tick for as long as it runs.
 pub struct Scheduler {
     pool: PgPool,
     config: ConnectorManagerConfig,
     sync_manager: Arc<SyncManager>,
+    /// Identifies this replica as the holder of its leases.
+    replica_id: String,
 }
 
 impl Scheduler {
@@ -25,16 +35,21 @@ impl Scheduler {
             pool,
             config,
             sync_manager,
+            replica_id: shared::utils::generate_ulid(),
         }
     }
 
+    pub fn replica_id(&self) -> &str {
+        &self.replica_id
+    }
+
     pub async fn run(&self) {
         let mut scheduler_interval =
             interval(Duration::from_secs(self.config.scheduler_interval_seconds));
 
         info!(
-            "Scheduler started, checking every {} seconds",
-            self.config.scheduler_interval_seconds
+            "Scheduler {} started, checking every {} seconds",
+            self.replica_id, self.config.scheduler_interval_seconds
         );
 
         loop {
@@ -46,6 +61,10 @@ impl Scheduler {
     async fn tick(&self) {
         debug!("Scheduler tick");
 
+        if let Err(e) = self.resume_paused_syncs().await {
+            error!("Error resuming paused syncs: {}", e);
+        }
+
         // Check for sources due for sync
         if let Err(e) = self.process_due_sources().await {
             error!("Error processing due sources: {}", e);
@@ -64,9 +83,71 @@ impl Scheduler {
         }
     }
 
-    async fn process_due_sources(&self) -> Result<(), SchedulerError> {
+    /// Resume the syncs connectors paused on shutting down whose sources this replica holds or
+    /// can take the lease of, returning the sync runs started. Syncs of connectors that are not
+    /// back yet are left paused until a later tick.
+    pub async fn resume_paused_syncs(&self) -> Result<Vec<String>, SchedulerError> {
+        let sync_run_repo = SyncRunRepository::new(&self.pool);
+        let lease_repo = SourceLeaseRepository::new(&self.pool);
+        let lease_seconds = (self.config.scheduler_interval_seconds * LEASE_TICKS) as i64;
+
+        let paused = sync_run_repo
+            .find_paused_to_resume()
+            .await
+            .map_err(|e| SchedulerError::DatabaseError(e.to_string()))?;
+        if paused.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let source_ids: Vec<String> = paused.iter().map(|run| run.source_id.clone()).collect();
+        let leased: HashSet<String> = lease_repo
+            .acquire(&self.replica_id, &source_ids, lease_seconds)
+            .await
+            .map_err(|e| SchedulerError::DatabaseError(e.to_string()))?
+            .into_iter()
+            .collect();
+
+        let mut resumed = Vec::new();
+        for run in paused.iter().filter(|run| leased.contains(&run.source_id)) {
+            match self.sync_manager.resume_sync(run).await {
+                Ok(Some(sync_run_id)) => {
+                    info!(
+                        "Paused sync {} of source {} resumed as {}",
+                        run.id, run.source_id, sync_run_id
+                    );
+                    resumed.push(sync_run_id);
+                }
+                Ok(None) => {
+                    debug!(
+                        "Connector of paused sync {} is not back yet, will retry on next tick",
+                        run.id
+                    );
+                }
+                Err(SyncError::ConcurrencyLimitReached) => {
+                    debug!("Concurrency limit reached, will retry on next tick");
+                    break;
+                }
+                Err(e) => {
+                    warn!("Failed to resume paused sync {}: {}", run.id, e);
+                }
+            }
+        }
+
+        Ok(resumed)
+    }
+
+    /// Trigger the syncs of the due sources this replica holds or can take the lease of,
+    /// returning the sync runs started.
+    pub async fn process_due_sources(&self) -> Result<Vec<String>, SchedulerError> {
         let now = OffsetDateTime::now_utc();
         let source_repo = SourceRepository::new(&self.pool);
+        let lease_repo = SourceLeaseRepository::new(&self.pool);
+        let lease_seconds = (self.config.scheduler_interval_seconds * LEASE_TICKS) as i64;
+
+        lease_repo
+            .renew(&self.replica_id, lease_seconds)
+            .await
+            .map_err(|e| SchedulerError::DatabaseError(e.to_string()))?;
 
         let due_sources = source_repo
             .find_due_for_sync(now)
@@ -75,11 +156,28 @@ impl Scheduler {
 
         if due_sources.is_empty() {
             debug!("No sources due for sync");
-            return Ok(());
+            return Ok(vec![]);
+        }
+
+        let due_ids: Vec<String> = due_sources.iter().map(|s| s.id.clone()).collect();
+        let leased: HashSet<String> = lease_repo
+            .acquire(&self.replica_id, &due_ids, lease_seconds)
+            .await
+            .map_err(|e| SchedulerError::DatabaseError(e.to_string()))?
+            .into_iter()
+            .collect();
+        let due_sources: Vec<_> = due_sources
+            .into_iter()
+            .filter(|source| leased.contains(&source.id))
+            .collect();
+        if due_sources.is_empty() {
+            debug!("Sources due for sync are scheduled by other replicas");
+            return Ok(vec![]);
         }
 
         info!("Found {} sources due for sync", due_sources.len());
 
+        let mut triggered = Vec::new();
         for source in due_sources {
             if self
                 .sync_manager
@@ -101,6 +199,7 @@ impl Scheduler {
                         "Scheduled sync {} triggered for source {} ({:?})",
                         sync_run_id, source.name, source.source_type
                     );
+                    triggered.push(sync_run_id);
                 }
                 Err(SyncError::ConcurrencyLimitReached) => {
                     debug!("Concurrency limit reached, will retry on next tick");
@@ -115,7 +214,7 @@ impl Scheduler {
             }
         }
 
-        Ok(())
+        Ok(triggered)
     }
 }
 
@@ -0,0 +1,886 @@
+//! SCIM 2.0 provisioning endpoints (RFC 7643/7644).
+//!
+//! Identity providers push user and group lifecycle changes here, keeping the `users`,
+//! `groups` and `group_members` tables in sync. Search resolves group membership from
+//! these tables, so documents shared with a group become visible to its members.
+//!
+//! Supported: Users and Groups with create, get, list (filtering on `userName eq` and
+//! `displayName eq`), replace, patch and delete. Deleting a user deactivates it and removes
+//! it from all groups, since users own chats and other data that must outlive
+//! deprovisioning. Patch operations on attributes Omni does not store are ignored.
+
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value as JsonValue};
+use shared::db::repositories::{Group, GroupRepository};
+use shared::models::User;
+use shared::{DatabaseError, UserRepository};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use tracing::{error, info};
+
+const USER_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+const GROUP_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:Group";
+const LIST_RESPONSE_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+const ERROR_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+const SERVICE_PROVIDER_CONFIG_SCHEMA: &str =
+    "urn:ietf:params:scim:schemas:core:2.0:ServiceProviderConfig";
+const SCIM_CONTENT_TYPE: &str = "application/scim+json";
+
+const DEFAULT_PAGE_SIZE: i64 = 100;
+const MAX_PAGE_SIZE: i64 = 200;
+
+/// SCIM routes, mounted under `/scim/v2`. Every request must carry the configured bearer
+/// token; without one configured, the endpoints respond 404.
+pub fn router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/ServiceProviderConfig", get(service_provider_config))
+        .route("/Users", get(list_users).post(create_user))
+        .route(
+            "/Users/:id",
+            get(get_user)
+                .put(replace_user)
+                .patch(patch_user)
+                .delete(delete_user),
+        )
+        .route("/Groups", get(list_groups).post(create_group))
+        .route(
+            "/Groups/:id",
+            get(get_group)
+                .put(replace_group)
+                .patch(patch_group)
+                .delete(delete_group),
+        )
+        .route_layer(middleware::from_fn_with_state(state, require_bearer_token))
+}
+
+// ============================================================================
+// Errors and responses
+// ============================================================================
+
+#[derive(Debug)]
+pub struct ScimError {
+    status: StatusCode,
+    scim_type: Option<&'static str>,
+    detail: String,
+}
+
+impl ScimError {
+    fn new(status: StatusCode, detail: impl Into<String>) -> Self {
+        Self {
+            status,
+            scim_type: None,
+            detail: detail.into(),
+        }
+    }
+
+    fn bad_request(scim_type: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            scim_type: Some(scim_type),
+            detail: detail.into(),
+        }
+    }
+
+    fn not_found(resource: &str, id: &str) -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            format!("{} {} not found", resource, id),
+        )
+    }
+}
+
+impl From<DatabaseError> for ScimError {
+    fn from(err: DatabaseError) -> Self {
+        match err {
+            DatabaseError::ConstraintViolation(msg) => Self {
+                status: StatusCode::CONFLICT,
+                scim_type: Some("uniqueness"),
+                detail: msg,
+            },
+            other => {
+                error!("SCIM request failed: {}", other);
+                Self::new(StatusCode::INTERNAL_SERVER_ERROR, other.to_string())
+            }
+        }
+    }
+}
+
+impl IntoResponse for ScimError {
+    fn into_response(self) -> Response {
+        let mut body = json!({
+            "schemas": [ERROR_SCHEMA],
+            "status": self.status.as_u16().to_string(),
+            "detail": self.detail,
+        });
+        if let Some(scim_type) = self.scim_type {
+            body["scimType"] = json!(scim_type);
+        }
+        scim_response(self.status, body)
+    }
+}
+
+fn scim_response(status: StatusCode, body: JsonValue) -> Response {
+    (
+        status,
+        [(header::CONTENT_TYPE, SCIM_CONTENT_TYPE)],
+        Json(body),
+    )
+        .into_response()
+}
+
+type ScimResult = Result<Response, ScimError>;
+
+async fn require_bearer_token(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(expected) = state.config.scim_bearer_token.as_deref() else {
+        return ScimError::new(StatusCode::NOT_FOUND, "SCIM provisioning is not enabled")
+            .into_response();
+    };
+
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match provided {
+        Some(token) if constant_time_eq(token.as_bytes(), expected.as_bytes()) => {
+            next.run(request).await
+        }
+        _ => ScimError::new(StatusCode::UNAUTHORIZED, "Invalid bearer token").into_response(),
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// ============================================================================
+// Requests
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListQuery {
+    pub filter: Option<String>,
+    pub start_index: Option<i64>,
+    pub count: Option<i64>,
+}
+
+impl ListQuery {
+    /// SCIM indexes are 1-based
+    fn offset(&self) -> i64 {
+        self.start_index.unwrap_or(1).max(1) - 1
+    }
+
+    fn limit(&self) -> i64 {
+        self.count
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(0, MAX_PAGE_SIZE)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUserRequest {
+    pub user_name: String,
+    pub external_id: Option<String>,
+    pub display_name: Option<String>,
+    pub name: Option<ScimName>,
+    #[serde(default)]
+    pub emails: Vec<ScimEmail>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimName {
+    pub formatted: Option<String>,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScimEmail {
+    pub value: String,
+    #[serde(default)]
+    pub primary: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimGroupRequest {
+    pub display_name: String,
+    pub external_id: Option<String>,
+    #[serde(default)]
+    pub members: Vec<ScimMemberRef>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScimMemberRef {
+    pub value: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PatchRequest {
+    #[serde(rename = "Operations")]
+    pub operations: Vec<PatchOperation>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PatchOperation {
+    pub op: String,
+    pub path: Option<String>,
+    pub value: Option<JsonValue>,
+}
+
+/// User attributes managed by the identity provider
+#[derive(Debug, Clone, PartialEq)]
+struct ProvisionedUser {
+    email: String,
+    full_name: Option<String>,
+    external_id: Option<String>,
+    active: bool,
+}
+
+impl ProvisionedUser {
+    fn from_request(request: ScimUserRequest) -> Result<Self, ScimError> {
+        let email = request
+            .emails
+            .iter()
+            .find(|e| e.primary)
+            .or_else(|| request.emails.first())
+            .map(|e| e.value.clone())
+            .unwrap_or(request.user_name);
+        if !email.contains('@') {
+            return Err(ScimError::bad_request(
+                "invalidValue",
+                "userName or a primary email must be an email address",
+            ));
+        }
+
+        let full_name = request
+            .name
+            .and_then(|name| {
+                name.formatted.or_else(|| {
+                    let parts: Vec<String> = [name.given_name, name.family_name]
+                        .into_iter()
+                        .flatten()
+                        .collect();
+                    (!parts.is_empty()).then(|| parts.join(" "))
+                })
+            })
+            .or(request.display_name);
+
+        Ok(Self {
+            email,
+            full_name,
+            external_id: request.external_id,
+            active: request.active.unwrap_or(true),
+        })
+    }
+
+    fn from_user(user: &User) -> Self {
+        Self {
+            email: user.email.clone(),
+            full_name: user.full_name.clone(),
+            external_id: user.external_id.clone(),
+            active: user.is_active,
+        }
+    }
+
+    /// Apply a single attribute change from a PATCH operation
+    fn set(&mut self, path: &str, value: &JsonValue) -> Result<(), ScimError> {
+        match path {
+            "active" => {
+                self.active = as_bool(value).ok_or_else(|| {
+                    ScimError::bad_request("invalidValue", "active must be a boolean")
+                })?
+            }
+            "userName" => {
+                self.email = value
+                    .as_str()
+                    .filter(|email| email.contains('@'))
+                    .ok_or_else(|| {
+                        ScimError::bad_request("invalidValue", "userName must be an email address")
+                    })?
+                    .to_string()
+            }
+            "externalId" => self.external_id = value.as_str().map(str::to_string),
+            "displayName" | "name.formatted" => self.full_name = value.as_str().map(str::to_string),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Booleans arrive as strings from some identity providers
+fn as_bool(value: &JsonValue) -> Option<bool> {
+    match value {
+        JsonValue::Bool(b) => Some(*b),
+        JsonValue::String(s) => s.parse::<bool>().ok().or(match s.as_str() {
+            "True" => Some(true),
+            "False" => Some(false),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+/// Parse a filter of the form `<attribute> eq "<value>"`, the only form identity providers
+/// use for provisioning lookups.
+fn parse_eq_filter(filter: &str) -> Result<(String, String), ScimError> {
+    let invalid =
+        || ScimError::bad_request("invalidFilter", format!("Unsupported filter: {}", filter));
+
+    let mut parts = filter.trim().splitn(3, ' ');
+    let (Some(attribute), Some(op), Some(value)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+    if !op.eq_ignore_ascii_case("eq") {
+        return Err(invalid());
+    }
+    let value = value
+        .trim()
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .ok_or_else(invalid)?;
+
+    Ok((attribute.to_string(), value.replace("\\\"", "\"")))
+}
+
+/// Member ids from a `members[value eq "<id>"]` path
+fn parse_member_path(path: &str) -> Option<String> {
+    let filter = path.strip_prefix("members[")?.strip_suffix(']')?;
+    match parse_eq_filter(filter) {
+        Ok((attribute, value)) if attribute == "value" => Some(value),
+        _ => None,
+    }
+}
+
+fn member_ids(value: Option<&JsonValue>) -> Vec<String> {
+    let members = match value {
+        Some(JsonValue::Array(members)) => members.iter().collect(),
+        Some(member @ JsonValue::Object(_)) => vec![member],
+        _ => vec![],
+    };
+    members
+        .into_iter()
+        .filter_map(|m| m.get("value").and_then(|v| v.as_str()).map(str::to_string))
+        .collect()
+}
+
+// ============================================================================
+// Resource representations
+// ============================================================================
+
+fn format_time(time: OffsetDateTime) -> String {
+    time.format(&Rfc3339).unwrap_or_default()
+}
+
+fn user_resource(user: &User, groups: Option<&[Group]>) -> JsonValue {
+    let mut resource = json!({
+        "schemas": [USER_SCHEMA],
+        "id": user.id,
+        "externalId": user.external_id,
+        "userName": user.email,
+        "displayName": user.full_name,
+        "name": { "formatted": user.full_name },
+        "emails": [{ "value": user.email, "primary": true }],
+        "active": user.is_active,
+        "meta": {
+            "resourceType": "User",
+            "created": format_time(user.created_at),
+            "lastModified": format_time(user.updated_at),
+            "location": format!("/scim/v2/Users/{}", user.id),
+        },
+    });
+    if let Some(groups) = groups {
+        resource["groups"] = groups
+            .iter()
+            .map(|g| json!({ "value": g.id, "display": g.display_name }))
+            .collect();
+    }
+    resource
+}
+
+async fn group_resource(
+    repo: &GroupRepository,
+    group: &Group,
+    include_members: bool,
+) -> Result<JsonValue, ScimError> {
+    let mut resource = json!({
+        "schemas": [GROUP_SCHEMA],
+        "id": group.id,
+        "externalId": group.external_id,
+        "displayName": group.display_name,
+        "meta": {
+            "resourceType": "Group",
+            "created": format_time(group.created_at),
+            "lastModified": format_time(group.updated_at),
+            "location": format!("/scim/v2/Groups/{}", group.id),
+        },
+    });
+    if include_members {
+        resource["members"] = repo
+            .find_members(&group.id)
+            .await?
+            .into_iter()
+            .map(|m| json!({ "value": m.user_id, "display": m.email }))
+            .collect();
+    }
+    Ok(resource)
+}
+
+fn list_response(resources: Vec<JsonValue>, total: i64, query: &ListQuery) -> Response {
+    scim_response(
+        StatusCode::OK,
+        json!({
+            "schemas": [LIST_RESPONSE_SCHEMA],
+            "totalResults": total,
+            "startIndex": query.offset() + 1,
+            "itemsPerPage": resources.len(),
+            "Resources": resources,
+        }),
+    )
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+async fn service_provider_config() -> Response {
+    scim_response(
+        StatusCode::OK,
+        json!({
+            "schemas": [SERVICE_PROVIDER_CONFIG_SCHEMA],
+            "patch": { "supported": true },
+            "bulk": { "supported": false, "maxOperations": 0, "maxPayloadSize": 0 },
+            "filter": { "supported": true, "maxResults": MAX_PAGE_SIZE },
+            "changePassword": { "supported": false },
+            "sort": { "supported": false },
+            "etag": { "supported": false },
+            "authenticationSchemes": [{
+                "type": "oauthbearertoken",
+                "name": "Bearer Token",
+                "description": "Authentication with the configured SCIM bearer token",
+            }],
+        }),
+    )
+}
+
+/// Cached search responses are keyed by user email, not by the user's groups or whether they
+/// are active, so they are cleared whenever either changes. Failing to clear them only delays
+/// the change until the entries expire, so it isn't an error.
+async fn clear_cached_responses(state: &AppState) {
+    let cleared = async {
+        let client = redis::Client::open(state.config.redis.redis_url.as_str())?;
+        let mut conn = client.get_multiplexed_async_connection().await?;
+        shared::cache::clear_response_caches(&mut conn).await
+    }
+    .await;
+    match cleared {
+        Ok(0) => {}
+        Ok(cleared) => info!("SCIM: cleared {} cached search responses", cleared),
+        Err(e) => error!("Failed to clear cached search responses: {}", e),
+    }
+}
+
+async fn find_user(repo: &UserRepository, id: &str) -> Result<User, ScimError> {
+    repo.find_with_external_id(id)
+        .await?
+        .ok_or_else(|| ScimError::not_found("User", id))
+}
+
+async fn save_user(
+    state: &AppState,
+    id: &str,
+    user: ProvisionedUser,
+    status: StatusCode,
+) -> ScimResult {
+    let updated = UserRepository::new(state.db_pool.pool())
+        .update_provisioned(
+            id,
+            &user.email,
+            user.full_name.as_deref(),
+            user.external_id.as_deref(),
+            user.active,
+        )
+        .await?
+        .ok_or_else(|| ScimError::not_found("User", id))?;
+    let groups = GroupRepository::new(state.db_pool.pool())
+        .find_for_user(id)
+        .await?;
+
+    Ok(scim_response(
+        status,
+        user_resource(&updated, Some(&groups)),
+    ))
+}
+
+async fn list_users(State(state): State<AppState>, Query(query): Query<ListQuery>) -> ScimResult {
+    let email = match &query.filter {
+        Some(filter) => match parse_eq_filter(filter)? {
+            (attribute, value) if attribute == "userName" => Some(value),
+            _ => {
+                return Err(ScimError::bad_request(
+                    "invalidFilter",
+                    "Users can only be filtered by userName",
+                ))
+            }
+        },
+        None => None,
+    };
+
+    let (users, total) = UserRepository::new(state.db_pool.pool())
+        .list(email.as_deref(), query.limit(), query.offset())
+        .await?;
+    let resources = users.iter().map(|u| user_resource(u, None)).collect();
+
+    Ok(list_response(resources, total, &query))
+}
+
+async fn create_user(
+    State(state): State<AppState>,
+    Json(request): Json<ScimUserRequest>,
+) -> ScimResult {
+    let user = ProvisionedUser::from_request(request)?;
+    let created = UserRepository::new(state.db_pool.pool())
+        .provision(
+            &user.email,
+            user.full_name.as_deref(),
+            user.external_id.as_deref(),
+            user.active,
+        )
+        .await?;
+    info!("SCIM: provisioned user {} ({})", created.id, created.email);
+
+    Ok(scim_response(
+        StatusCode::CREATED,
+        user_resource(&created, Some(&[])),
+    ))
+}
+
+async fn get_user(State(state): State<AppState>, Path(id): Path<String>) -> ScimResult {
+    let user = find_user(&UserRepository::new(state.db_pool.pool()), &id).await?;
+    let groups = GroupRepository::new(state.db_pool.pool())
+        .find_for_user(&id)
+        .await?;
+
+    Ok(scim_response(
+        StatusCode::OK,
+        user_resource(&user, Some(&groups)),
+    ))
+}
+
+async fn replace_user(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(request): Json<ScimUserRequest>,
+) -> ScimResult {
+    let user = ProvisionedUser::from_request(request)?;
+    let response = save_user(&state, &id, user, StatusCode::OK).await?;
+    clear_cached_responses(&state).await;
+    Ok(response)
+}
+
+async fn patch_user(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(request): Json<PatchRequest>,
+) -> ScimResult {
+    let existing = find_user(&UserRepository::new(state.db_pool.pool()), &id).await?;
+    let mut user = ProvisionedUser::from_user(&existing);
+
+    for operation in &request.operations {
+        if !matches!(
+            operation.op.to_ascii_lowercase().as_str(),
+            "add" | "replace"
+        ) {
+            return Err(ScimError::bad_request(
+                "invalidValue",
+                format!("Unsupported operation on users: {}", operation.op),
+            ));
+        }
+        let value = operation.value.as_ref().unwrap_or(&JsonValue::Null);
+        match (&operation.path, value) {
+            (Some(path), value) => user.set(path, value)?,
+            (None, JsonValue::Object(attributes)) => {
+                for (path, value) in attributes {
+                    user.set(path, value)?;
+                }
+            }
+            (None, _) => {
+                return Err(ScimError::bad_request(
+                    "invalidValue",
+                    "Operations without a path need an object value",
+                ))
+            }
+        }
+    }
+
+    if !user.active && existing.is_active {
+        info!(
+            "SCIM: deactivated user {} ({})",
+            existing.id, existing.email
+        );
+    }
+    let response = save_user(&state, &id, user, StatusCode::OK).await?;
+    clear_cached_responses(&state).await;
+    Ok(response)
+}
+
+async fn delete_user(State(state): State<AppState>, Path(id): Path<String>) -> ScimResult {
+    let existing = find_user(&UserRepository::new(state.db_pool.pool()), &id).await?;
+    let mut user = ProvisionedUser::from_user(&existing);
+    user.active = false;
+    save_user(&state, &id, user, StatusCode::OK).await?;
+
+    GroupRepository::new(state.db_pool.pool())
+        .remove_user_from_all(&id)
+        .await?;
+    info!(
+        "SCIM: deprovisioned user {} ({})",
+        existing.id, existing.email
+    );
+    clear_cached_responses(&state).await;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+async fn find_group(repo: &GroupRepository, id: &str) -> Result<Group, ScimError> {
+    repo.find_by_id(id)
+        .await?
+        .ok_or_else(|| ScimError::not_found("Group", id))
+}
+
+async fn list_groups(State(state): State<AppState>, Query(query): Query<ListQuery>) -> ScimResult {
+    let display_name = match &query.filter {
+        Some(filter) => match parse_eq_filter(filter)? {
+            (attribute, value) if attribute == "displayName" => Some(value),
+            _ => {
+                return Err(ScimError::bad_request(
+                    "invalidFilter",
+                    "Groups can only be filtered by displayName",
+                ))
+            }
+        },
+        None => None,
+    };
+
+    let repo = GroupRepository::new(state.db_pool.pool());
+    let (groups, total) = repo
+        .list(display_name.as_deref(), query.limit(), query.offset())
+        .await?;
+
+    let mut resources = Vec::with_capacity(groups.len());
+    for group in &groups {
+        resources.push(group_resource(&repo, group, false).await?);
+    }
+
+    Ok(list_response(resources, total, &query))
+}
+
+async fn create_group(
+    State(state): State<AppState>,
+    Json(request): Json<ScimGroupRequest>,
+) -> ScimResult {
+    let repo = GroupRepository::new(state.db_pool.pool());
+    let group = repo
+        .create(&request.display_name, request.external_id.as_deref())
+        .await?;
+    let members: Vec<String> = request.members.into_iter().map(|m| m.value).collect();
+    repo.set_members(&group.id, &members).await?;
+    info!(
+        "SCIM: provisioned group {} ({}) with {} members",
+        group.id,
+        group.display_name,
+        members.len()
+    );
+    clear_cached_responses(&state).await;
+
+    Ok(scim_response(
+        StatusCode::CREATED,
+        group_resource(&repo, &group, true).await?,
+    ))
+}
+
+async fn get_group(State(state): State<AppState>, Path(id): Path<String>) -> ScimResult {
+    let repo = GroupRepository::new(state.db_pool.pool());
+    let group = find_group(&repo, &id).await?;
+
+    Ok(scim_response(
+        StatusCode::OK,
+        group_resource(&repo, &group, true).await?,
+    ))
+}
+
+async fn replace_group(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(request): Json<ScimGroupRequest>,
+) -> ScimResult {
+    let repo = GroupRepository::new(state.db_pool.pool());
+    let group = repo
+        .update(&id, &request.display_name, request.external_id.as_deref())
+        .await?
+        .ok_or_else(|| ScimError::not_found("Group", &id))?;
+    let members: Vec<String> = request.members.into_iter().map(|m| m.value).collect();
+    repo.set_members(&id, &members).await?;
+    clear_cached_responses(&state).await;
+
+    Ok(scim_response(
+        StatusCode::OK,
+        group_resource(&repo, &group, true).await?,
+    ))
+}
+
+async fn patch_group(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(request): Json<PatchRequest>,
+) -> ScimResult {
+    let repo = GroupRepository::new(state.db_pool.pool());
+    let mut group = find_group(&repo, &id).await?;
+
+    for operation in &request.operations {
+        let op = operation.op.to_ascii_lowercase();
+        let value = operation.value.as_ref();
+
+        match (op.as_str(), operation.path.as_deref()) {
+            ("add", Some("members")) => {
+                repo.add_members(&id, &member_ids(value)).await?;
+            }
+            ("replace", Some("members")) => {
+                repo.set_members(&id, &member_ids(value)).await?;
+            }
+            ("remove", Some("members")) => {
+                let members = member_ids(value);
+                if members.is_empty() {
+                    repo.set_members(&id, &[]).await?;
+                } else {
+                    repo.remove_members(&id, &members).await?;
+                }
+            }
+            ("remove", Some(path)) if path.starts_with("members[") => {
+                let member = parse_member_path(path).ok_or_else(|| {
+                    ScimError::bad_request("invalidPath", format!("Unsupported path: {}", path))
+                })?;
+                repo.remove_members(&id, &[member]).await?;
+            }
+            ("add" | "replace", path) => {
+                let attributes = match (path, value) {
+                    (Some(path), Some(value)) => {
+                        JsonValue::Object([(path.to_string(), value.clone())].into_iter().collect())
+                    }
+                    (None, Some(value @ JsonValue::Object(_))) => value.clone(),
+                    _ => {
+                        return Err(ScimError::bad_request(
+                            "invalidValue",
+                            "Operations without a path need an object value",
+                        ))
+                    }
+                };
+                if let Some(display_name) = attributes.get("displayName").and_then(|v| v.as_str()) {
+                    group.display_name = display_name.to_string();
+                }
+                if let Some(external_id) = attributes.get("externalId") {
+                    group.external_id = external_id.as_str().map(str::to_string);
+                }
+                if attributes.get("members").is_some() {
+                    repo.set_members(&id, &member_ids(attributes.get("members")))
+                        .await?;
+                }
+                group = repo
+                    .update(&id, &group.display_name, group.external_id.as_deref())
+                    .await?
+                    .ok_or_else(|| ScimError::not_found("Group", &id))?;
+            }
+            _ => {
+                return Err(ScimError::bad_request(
+                    "invalidValue",
+                    format!("Unsupported operation on groups: {}", operation.op),
+                ))
+            }
+        }
+    }
+    clear_cached_responses(&state).await;
+
+    Ok(scim_response(
+        StatusCode::OK,
+        group_resource(&repo, &group, true).await?,
+    ))
+}
+
+async fn delete_group(State(state): State<AppState>, Path(id): Path<String>) -> ScimResult {
+    if !GroupRepository::new(state.db_pool.pool())
+        .delete(&id)
+        .await?
+    {
+        return Err(ScimError::not_found("Group", &id));
+    }
+    info!("SCIM: deleted group {}", id);
+    clear_cached_responses(&state).await;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_eq_filter() {
+        assert_eq!(
+            parse_eq_filter(r#"userName eq "jane@example.com""#).unwrap(),
+            ("userName".to_string(), "jane@example.com".to_string())
+        );
+        assert_eq!(
+            parse_eq_filter(r#"displayName EQ "Eng \"Core\"""#).unwrap(),
+            ("displayName".to_string(), "Eng \"Core\"".to_string())
+        );
+        assert!(parse_eq_filter(r#"userName co "jane""#).is_err());
+        assert!(parse_eq_filter("userName eq jane").is_err());
+        assert_eq!(
+            parse_member_path(r#"members[value eq "01ABC"]"#),
+            Some("01ABC".to_string())
+        );
+    }
+
+    #[test]
+    fn test_provisioned_user_from_request() {
+        let request: ScimUserRequest = serde_json::from_value(json!({
+            "userName": "jdoe",
+            "name": { "givenName": "Jane", "familyName": "Doe" },
+            "emails": [
+                { "value": "jane.personal@example.com" },
+                { "value": "jane@example.com", "primary": true }
+            ]
+        }))
+        .unwrap();
+
+        let user = ProvisionedUser::from_request(request).unwrap();
+        assert_eq!(user.email, "jane@example.com");
+        assert_eq!(user.full_name.as_deref(), Some("Jane Doe"));
+        assert!(user.active);
+
+        let mut patched = user.clone();
+        patched.set("active", &json!("False")).unwrap();
+        assert!(!patched.active);
+        patched.set("title", &json!("Engineer")).unwrap();
+        assert_eq!(patched.email, user.email);
+    }
+}
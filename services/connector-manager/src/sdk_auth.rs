@@ -0,0 +1,170 @@
+//! Source-scoped tokens authenticating connectors' SDK calls.
+//!
+//! The connector manager sends a token for the source with every sync it triggers, and the
+//! connector sends it back as a bearer token on the SDK calls it makes for that source. A
+//! token is the source ID and a generation signed with the encryption master key, so any
+//! replica can check one without storing it, and rotating a source's tokens moves it to a new
+//! generation that revokes those issued before. A connector can then only act on the sources
+//! it was given tokens for, even when compromised.
+//!
+//! Calls without a token are let through until `SDK_TOKENS_REQUIRED` is set, so connectors
+//! can be upgraded to send them one at a time.
+
+use crate::handlers::ApiError;
+use crate::AppState;
+use axum::{
+    extract::{Request, State},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use shared::db::repositories::SyncRunRepository;
+use shared::encryption::EncryptionService;
+use shared::SourceRepository;
+use sqlx::PgPool;
+
+fn signed_data(source_id: &str, generation: i32) -> String {
+    format!("sdk-token:{}:{}", source_id, generation)
+}
+
+/// Issue a token for `source_id` at its current `generation`.
+pub fn issue(encryption: &EncryptionService, source_id: &str, generation: i32) -> String {
+    let signature = encryption.sign(signed_data(source_id, generation).as_bytes());
+    format!("{}.{}.{}", source_id, generation, signature)
+}
+
+/// The source and generation of a token signed by [`issue`], `None` for anything else.
+pub fn verify(encryption: &EncryptionService, token: &str) -> Option<(String, i32)> {
+    let mut parts = token.splitn(3, '.');
+    let source_id = parts.next()?;
+    let generation: i32 = parts.next()?.parse().ok()?;
+    let signature = parts.next()?;
+    encryption
+        .verify_signature(signed_data(source_id, generation).as_bytes(), signature)
+        .then(|| (source_id.to_string(), generation))
+}
+
+/// Who made an SDK call: the source its token is for, or nobody for calls without one.
+#[derive(Debug, Clone, Default)]
+pub struct SdkCaller {
+    source_id: Option<String>,
+}
+
+impl SdkCaller {
+    /// Allow the call only if it may act on `source_id`.
+    pub fn authorize_source(&self, source_id: &str) -> Result<(), ApiError> {
+        match &self.source_id {
+            Some(authorized) if authorized != source_id => Err(ApiError::forbidden(format!(
+                "Token is not valid for source {}",
+                source_id
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Allow the call only if it may act on the source of sync run `sync_run_id`.
+    pub async fn authorize_sync_run(
+        &self,
+        pool: &PgPool,
+        sync_run_id: &str,
+    ) -> Result<(), ApiError> {
+        if self.source_id.is_none() {
+            return Ok(());
+        }
+        let sync_run = SyncRunRepository::new(pool)
+            .find_by_id(sync_run_id)
+            .await
+            .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?
+            .ok_or_else(|| ApiError::not_found(format!("Sync run not found: {}", sync_run_id)))?;
+        self.authorize_source(&sync_run.source_id)
+    }
+}
+
+/// Authenticate an SDK call by its bearer token, if any, making its [`SdkCaller`] available
+/// to the handler.
+pub async fn authenticate(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::to_string);
+
+    let caller = match token {
+        Some(token) => match authenticate_token(state.db_pool.pool(), &token).await {
+            Ok(source_id) => SdkCaller {
+                source_id: Some(source_id),
+            },
+            Err(e) => return e.into_response(),
+        },
+        None if state.config.sdk_tokens_required => {
+            return ApiError::unauthorized("Missing source token".to_string()).into_response();
+        }
+        None => SdkCaller::default(),
+    };
+
+    request.extensions_mut().insert(caller);
+    next.run(request).await
+}
+
+async fn authenticate_token(pool: &PgPool, token: &str) -> Result<String, ApiError> {
+    let encryption = EncryptionService::new().map_err(|e| ApiError::internal(e.to_string()))?;
+    let invalid = || ApiError::unauthorized("Invalid source token".to_string());
+    let (source_id, generation) = verify(&encryption, token).ok_or_else(invalid)?;
+    let current = SourceRepository::new(pool)
+        .get_sdk_token_generation(&source_id)
+        .await
+        .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?;
+    match current {
+        Some(current) if current == generation => Ok(source_id),
+        _ => Err(invalid()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::ErrorCode;
+
+    #[test]
+    fn test_issued_tokens_verify() {
+        std::env::set_var(
+            "ENCRYPTION_KEY",
+            "test_master_key_that_is_long_enough_32_chars",
+        );
+        std::env::set_var("ENCRYPTION_SALT", "test_salt_16_chars");
+        let encryption = EncryptionService::new().unwrap();
+
+        let token = issue(&encryption, "01JSOURCE", 3);
+        assert_eq!(
+            verify(&encryption, &token),
+            Some(("01JSOURCE".to_string(), 3))
+        );
+
+        let forged = token.replacen("01JSOURCE", "01JOTHER0", 1);
+        assert_eq!(verify(&encryption, &forged), None);
+        let bumped = token.replacen(".3.", ".4.", 1);
+        assert_eq!(verify(&encryption, &bumped), None);
+        assert_eq!(verify(&encryption, "01JSOURCE"), None);
+    }
+
+    #[test]
+    fn test_caller_is_scoped_to_its_source() {
+        let caller = SdkCaller {
+            source_id: Some("01JSOURCE".to_string()),
+        };
+        assert!(caller.authorize_source("01JSOURCE").is_ok());
+        assert!(matches!(
+            caller.authorize_source("01JOTHER"),
+            Err(ApiError {
+                code: ErrorCode::Forbidden,
+                ..
+            })
+        ));
+        assert!(SdkCaller::default().authorize_source("01JOTHER").is_ok());
+    }
+}
@@ -1,9 +1,14 @@
 use crate::config::ConnectorManagerConfig;
 use crate::connector_client::{ClientError, ConnectorClient};
 use crate::models::{SyncRequest, TriggerType};
-use shared::db::repositories::SyncRunRepository;
-use shared::models::{SourceType, SyncStatus, SyncType};
-use shared::{DatabasePool, Repository, SourceRepository};
+use crate::sdk_auth;
+use redis::aio::MultiplexedConnection;
+use redis::AsyncCommands;
+use shared::cache;
+use shared::db::repositories::{DocumentRepository, SyncRunRepository};
+use shared::encryption::EncryptionService;
+use shared::models::{SourceType, SyncRun, SyncStatus, SyncType};
+use shared::{DatabasePool, EmailNotifier, Repository, SourceRepository};
 use sqlx::PgPool;
 use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
@@ -15,15 +20,54 @@ pub struct SyncManager {
     config: ConnectorManagerConfig,
     connector_client: ConnectorClient,
     sync_run_repo: SyncRunRepository,
+    email: Option<EmailNotifier>,
 }
 
 impl SyncManager {
     pub fn new(db_pool: &DatabasePool, config: ConnectorManagerConfig) -> Self {
+        let email = config
+            .smtp
+            .as_ref()
+            .and_then(|smtp| match EmailNotifier::new(smtp) {
+                Ok(email) => Some(email),
+                Err(e) => {
+                    error!("Sync failure emails disabled: {}", e);
+                    None
+                }
+            });
+
         Self {
             pool: db_pool.pool().clone(),
             config,
             connector_client: ConnectorClient::new(),
             sync_run_repo: SyncRunRepository::new(db_pool.pool()),
+            email,
+        }
+    }
+
+    /// Announce a failed sync run to webhooks and, when email is configured, to the people
+    /// responsible for the source.
+    pub async fn notify_sync_failed(&self, sync_run_id: &str) {
+        crate::webhooks::notify_sync_finished(&self.pool, sync_run_id).await;
+        if let Some(email) = &self.email {
+            crate::notifications::notify_sync_failed(&self.pool, email, sync_run_id);
+        }
+    }
+
+    /// A token for the connector's SDK calls for the source, unless there is no encryption key
+    /// to sign one with.
+    async fn issue_sdk_token(&self, source_id: &str) -> Result<Option<String>, SyncError> {
+        let generation = SourceRepository::new(&self.pool)
+            .get_sdk_token_generation(source_id)
+            .await
+            .map_err(|e| SyncError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| SyncError::SourceNotFound(source_id.to_string()))?;
+        match EncryptionService::new() {
+            Ok(encryption) => Ok(Some(sdk_auth::issue(&encryption, source_id, generation))),
+            Err(e) => {
+                warn!("Not issuing an SDK token for source {}: {}", source_id, e);
+                Ok(None)
+            }
         }
     }
 
@@ -87,6 +131,8 @@ impl SyncManager {
             None
         };
 
+        let sdk_token = self.issue_sdk_token(source_id).await?;
+
         let sync_run = self
             .sync_run_repo
             .create(source_id, effective_sync_type, &trigger_type.to_string())
@@ -96,6 +142,7 @@ impl SyncManager {
         let sync_request = SyncRequest {
             sync_run_id: sync_run.id.clone(),
             source_id: source_id.to_string(),
+            source_type: Some(source.source_type),
             // TODO: Change type of sync_mode to SyncType
             sync_mode: match effective_sync_type {
                 SyncType::Full => "full",
@@ -103,6 +150,7 @@ impl SyncManager {
             }
             .to_string(),
             last_sync_at,
+            sdk_token,
         };
 
         // Trigger sync (non-blocking call to connector)
@@ -125,6 +173,144 @@ impl SyncManager {
         }
     }
 
+    /// Resume a sync paused by its connector shutting down, starting a new run of the same
+    /// type from the connector state it saved. `None` while the connector is not back yet.
+    pub async fn resume_sync(&self, paused: &SyncRun) -> Result<Option<String>, SyncError> {
+        let source_repo = SourceRepository::new(&self.pool);
+        let source = source_repo
+            .find_by_id(paused.source_id.clone())
+            .await
+            .map_err(|e| SyncError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| SyncError::SourceNotFound(paused.source_id.clone()))?;
+        let connector_url = self
+            .config
+            .get_connector_url(source.source_type)
+            .ok_or_else(|| {
+                SyncError::ConnectorNotConfigured(format!("{:?}", source.source_type))
+            })?;
+
+        if !self.connector_client.health_check(connector_url).await {
+            return Ok(None);
+        }
+
+        self.trigger_sync(&paused.source_id, paused.sync_type, TriggerType::Scheduled)
+            .await
+            .map(Some)
+    }
+
+    /// Forget everything the connector remembers about a source and start a full sync, so
+    /// every item is fetched and indexed again. Clears the connector state cursor and every
+    /// Redis key naming the source (per-item versions, page hashes and the like), and with
+    /// `delete_documents` removes the indexed documents first so items gone from the source
+    /// do not linger. Documents are unsearchable until the new sync indexes them again.
+    pub async fn reindex_source(
+        &self,
+        source_id: &str,
+        delete_documents: bool,
+    ) -> Result<ReindexOutcome, SyncError> {
+        let source_repo = SourceRepository::new(&self.pool);
+        let source = source_repo
+            .find_by_id(source_id.to_string())
+            .await
+            .map_err(|e| SyncError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| SyncError::SourceNotFound(source_id.to_string()))?;
+
+        // Refuse before clearing anything, so a rejected request leaves the source as it was
+        if !source.is_active {
+            return Err(SyncError::SourceInactive(source_id.to_string()));
+        }
+        if self.is_sync_running(source_id).await? {
+            return Err(SyncError::SyncAlreadyRunning(source_id.to_string()));
+        }
+        if self.active_sync_count().await? >= self.config.max_concurrent_syncs {
+            return Err(SyncError::ConcurrencyLimitReached);
+        }
+
+        let redis_keys_cleared = self.clear_sync_state_keys(source_id).await?;
+        source_repo
+            .clear_connector_state(source_id)
+            .await
+            .map_err(|e| SyncError::DatabaseError(e.to_string()))?;
+
+        let documents_deleted = if delete_documents {
+            DocumentRepository::new(&self.pool)
+                .delete_by_source(source_id)
+                .await
+                .map_err(|e| SyncError::DatabaseError(e.to_string()))?
+        } else {
+            0
+        };
+
+        info!(
+            "Reset sync state of source {}: {} Redis keys cleared, {} documents deleted",
+            source_id, redis_keys_cleared, documents_deleted
+        );
+
+        let sync_run_id = self
+            .trigger_sync(source_id, SyncType::Full, TriggerType::Manual)
+            .await?;
+
+        Ok(ReindexOutcome {
+            sync_run_id,
+            redis_keys_cleared,
+            documents_deleted,
+        })
+    }
+
+    async fn redis_connection(&self) -> Result<MultiplexedConnection, SyncError> {
+        let client = redis::Client::open(self.config.redis.redis_url.as_str())
+            .map_err(|e| SyncError::SyncStateError(e.to_string()))?;
+        client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| SyncError::SyncStateError(e.to_string()))
+    }
+
+    /// The connector sync state keys of a source, by name, with their time to live in
+    /// seconds, `None` for keys that do not expire. See [`shared::cache`] for their layout.
+    pub async fn sync_state_keys(
+        &self,
+        source_id: &str,
+    ) -> Result<Vec<(String, Option<i64>)>, SyncError> {
+        let state_error = |e: redis::RedisError| SyncError::SyncStateError(e.to_string());
+        let source_keys = cache::keys().source(source_id);
+        let mut conn = self.redis_connection().await?;
+        let mut keys = cache::scan(&mut conn, &source_keys.pattern(""))
+            .await
+            .map_err(state_error)?;
+        keys.sort();
+
+        let mut described = Vec::with_capacity(keys.len());
+        for key in keys {
+            let ttl: i64 = conn.ttl(&key).await.map_err(state_error)?;
+            // -2 is a key that expired since the scan
+            if ttl == -2 {
+                continue;
+            }
+            let name = source_keys.name(&key).unwrap_or(&key).to_string();
+            described.push((name, (ttl >= 0).then_some(ttl)));
+        }
+        Ok(described)
+    }
+
+    /// Give the connector sync state keys of a source that do not expire the time to live
+    /// of [`cache::ttl::source_key`], returning the number of keys updated.
+    pub async fn expire_sync_state_keys(&self, source_id: &str) -> Result<usize, SyncError> {
+        let mut conn = self.redis_connection().await?;
+        cache::expire_source_keys(&mut conn, source_id)
+            .await
+            .map_err(|e| SyncError::SyncStateError(e.to_string()))
+    }
+
+    /// Delete the connector sync state kept in Redis for a source, returning the number of
+    /// keys deleted.
+    pub async fn clear_sync_state_keys(&self, source_id: &str) -> Result<usize, SyncError> {
+        let mut conn = self.redis_connection().await?;
+        cache::delete_matching(&mut conn, &cache::keys().source(source_id).pattern(""))
+            .await
+            .map_err(|e| SyncError::SyncStateError(e.to_string()))
+    }
+
     pub async fn cancel_sync(&self, sync_run_id: &str) -> Result<(), SyncError> {
         let sync_run = self
             .sync_run_repo
@@ -239,11 +425,20 @@ impl SyncManager {
             .mark_failed(sync_run_id, error)
             .await
             .map_err(|e| SyncError::DatabaseError(e.to_string()))?;
+        self.notify_sync_failed(sync_run_id).await;
 
         Ok(())
     }
 }
 
+/// What [`SyncManager::reindex_source`] cleared, and the full sync it started.
+#[derive(Debug, Clone)]
+pub struct ReindexOutcome {
+    pub sync_run_id: String,
+    pub redis_keys_cleared: usize,
+    pub documents_deleted: u64,
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum SyncError {
     #[error("Source not found: {0}")]
@@ -270,6 +465,9 @@ pub enum SyncError {
     #[error("Database error: {0}")]
     DatabaseError(String),
 
+    #[error("Sync state error: {0}")]
+    SyncStateError(String),
+
     #[error("Connector error: {0}")]
     ConnectorError(#[from] ClientError),
 }
@@ -0,0 +1,343 @@
+use crate::config::ConnectorManagerConfig;
+use crate::models::SyncRunSummary;
+use anyhow::{anyhow, Context};
+use futures::future::join_all;
+use serde_json::json;
+use shared::db::repositories::{
+    SyncRunRepository, Webhook, WebhookDelivery, WebhookEvent, WebhookRepository,
+};
+use shared::encryption::{EncryptedEnvelope, EncryptionService};
+use shared::models::SyncStatus;
+use shared::{Repository, SourceRepository};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use time::{Duration as TimeDuration, OffsetDateTime};
+use tokio::time::{interval, Duration};
+use tracing::{debug, error, info, warn};
+
+/// Deliveries claimed per pass.
+const DELIVERY_BATCH_SIZE: i64 = 50;
+
+/// Attempts before a delivery is given up on. With exponential backoff from 30 seconds the
+/// last attempt happens about an hour after the first.
+const MAX_DELIVERY_ATTEMPTS: i32 = 8;
+
+const RETRY_BASE_DELAY_SECONDS: i64 = 30;
+
+/// How long a claimed delivery is reserved for, after which a delivery interrupted by a
+/// restart is attempted again.
+const DELIVERY_LEASE_SECONDS: i64 = 120;
+
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Response bodies are kept in the delivery log up to this many bytes.
+const MAX_LOGGED_RESPONSE_BYTES: usize = 1024;
+
+pub const SIGNATURE_HEADER: &str = "X-Omni-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Omni-Timestamp";
+pub const EVENT_HEADER: &str = "X-Omni-Event";
+pub const DELIVERY_HEADER: &str = "X-Omni-Delivery";
+
+/// Posts queued webhook deliveries, retrying failed ones with exponential backoff.
+///
+/// Each request carries the delivery id, event type and a timestamp in headers, and an
+/// HMAC-SHA256 of `<timestamp>.<body>` under the webhook's secret in `X-Omni-Signature`, so
+/// receivers can check that the payload came from Omni and reject replays.
+pub struct WebhookDispatcher {
+    pool: PgPool,
+    client: reqwest::Client,
+    interval_seconds: u64,
+}
+
+impl WebhookDispatcher {
+    pub fn new(pool: PgPool, config: &ConnectorManagerConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(DELIVERY_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .expect("Failed to build webhook HTTP client");
+
+        Self {
+            pool,
+            client,
+            interval_seconds: config.webhook_delivery_interval_seconds,
+        }
+    }
+
+    pub async fn run(&self) {
+        if self.interval_seconds == 0 {
+            info!("Webhook delivery disabled");
+            return;
+        }
+
+        let encryption = match EncryptionService::new() {
+            Ok(encryption) => encryption,
+            Err(e) => {
+                error!("Webhook delivery disabled: {}", e);
+                return;
+            }
+        };
+
+        let mut delivery_interval = interval(Duration::from_secs(self.interval_seconds));
+        info!(
+            "Webhook delivery started, checking every {} seconds",
+            self.interval_seconds
+        );
+
+        loop {
+            delivery_interval.tick().await;
+            // Keep going while full batches are due, so a backlog is not throttled by the
+            // interval
+            loop {
+                match self.run_once(&encryption).await {
+                    Ok(0) => break,
+                    Ok(attempted) => {
+                        debug!("Attempted {} webhook deliveries", attempted);
+                        if (attempted as i64) < DELIVERY_BATCH_SIZE {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        error!("Webhook delivery pass failed: {}", e);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Attempt a batch of due deliveries, returning the number attempted.
+    pub async fn run_once(&self, encryption: &EncryptionService) -> anyhow::Result<usize> {
+        let repo = WebhookRepository::new(&self.pool);
+        let deliveries = repo
+            .claim_due_deliveries(DELIVERY_BATCH_SIZE, DELIVERY_LEASE_SECONDS)
+            .await?;
+        if deliveries.is_empty() {
+            return Ok(0);
+        }
+
+        let webhooks: HashMap<String, Webhook> = repo
+            .find_all()
+            .await?
+            .into_iter()
+            .map(|webhook| (webhook.id.clone(), webhook))
+            .collect();
+
+        let attempts = deliveries.iter().map(|delivery| async {
+            let outcome = match webhooks.get(&delivery.webhook_id) {
+                Some(webhook) => self.deliver(encryption, webhook, delivery).await,
+                None => Err((None, "Webhook was deleted".to_string())),
+            };
+            self.record_outcome(&repo, delivery, outcome).await
+        });
+        for result in join_all(attempts).await {
+            if let Err(e) = result {
+                error!("Failed to record webhook delivery outcome: {}", e);
+            }
+        }
+
+        Ok(deliveries.len())
+    }
+
+    /// Post one delivery. Returns the response status on success, or the status (if a
+    /// response was received) and an error message on failure.
+    async fn deliver(
+        &self,
+        encryption: &EncryptionService,
+        webhook: &Webhook,
+        delivery: &WebhookDelivery,
+    ) -> Result<u16, (Option<u16>, String)> {
+        let secret = open_secret(encryption, webhook).map_err(|e| (None, e.to_string()))?;
+        let body = delivery_body(delivery).to_string();
+        let timestamp = OffsetDateTime::now_utc().unix_timestamp();
+
+        let response = self
+            .client
+            .post(&webhook.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, &delivery.event_type)
+            .header(DELIVERY_HEADER, &delivery.id)
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, sign(&secret, timestamp, &body))
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| (None, e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(status.as_u16());
+        }
+
+        let mut message = response.text().await.unwrap_or_default();
+        if message.len() > MAX_LOGGED_RESPONSE_BYTES {
+            let end = (0..=MAX_LOGGED_RESPONSE_BYTES)
+                .rev()
+                .find(|&i| message.is_char_boundary(i))
+                .unwrap_or(0);
+            message.truncate(end);
+        }
+        Err((
+            Some(status.as_u16()),
+            format!("HTTP {}: {}", status, message),
+        ))
+    }
+
+    async fn record_outcome(
+        &self,
+        repo: &WebhookRepository,
+        delivery: &WebhookDelivery,
+        outcome: Result<u16, (Option<u16>, String)>,
+    ) -> anyhow::Result<()> {
+        match outcome {
+            Ok(status) => {
+                debug!(
+                    "Delivered {} {} to webhook {}",
+                    delivery.event_type, delivery.id, delivery.webhook_id
+                );
+                repo.mark_delivered(&delivery.id, status as i32).await?;
+            }
+            Err((status, message)) => {
+                let retry_at =
+                    retry_delay(delivery.attempts).map(|delay| OffsetDateTime::now_utc() + delay);
+                match retry_at {
+                    Some(retry_at) => debug!(
+                        "Webhook delivery {} failed, retrying at {}: {}",
+                        delivery.id, retry_at, message
+                    ),
+                    None => warn!(
+                        "Giving up on webhook delivery {} after {} attempts: {}",
+                        delivery.id, delivery.attempts, message
+                    ),
+                }
+                repo.mark_attempt_failed(&delivery.id, status.map(i32::from), &message, retry_at)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Delay before retrying a delivery that has failed `attempts` times, `None` once it has
+/// run out of attempts.
+pub fn retry_delay(attempts: i32) -> Option<TimeDuration> {
+    if attempts >= MAX_DELIVERY_ATTEMPTS {
+        return None;
+    }
+    let exponent = attempts.clamp(1, MAX_DELIVERY_ATTEMPTS) as u32 - 1;
+    Some(TimeDuration::seconds(
+        RETRY_BASE_DELAY_SECONDS * 2_i64.pow(exponent),
+    ))
+}
+
+/// The JSON body posted for a delivery.
+fn delivery_body(delivery: &WebhookDelivery) -> serde_json::Value {
+    json!({
+        "id": delivery.id,
+        "event": delivery.event_type,
+        "created_at": delivery
+            .created_at
+            .format(&time::format_description::well_known::Rfc3339)
+            .unwrap_or_default(),
+        "data": delivery.payload,
+    })
+}
+
+/// `sha256=<hex>` HMAC-SHA256 signature of `<timestamp>.<body>`.
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, secret.as_bytes());
+    let tag = ring::hmac::sign(&key, format!("{}.{}", timestamp, body).as_bytes());
+    format!("sha256={}", hex(tag.as_ref()))
+}
+
+/// A new random signing secret.
+pub fn generate_secret() -> anyhow::Result<String> {
+    let mut bytes = [0u8; 32];
+    ring::rand::SecureRandom::fill(&ring::rand::SystemRandom::new(), &mut bytes)
+        .map_err(|_| anyhow!("Failed to generate webhook secret"))?;
+    Ok(format!("whsec_{}", hex(&bytes)))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn seal_secret(
+    encryption: &EncryptionService,
+    secret: &str,
+) -> anyhow::Result<serde_json::Value> {
+    Ok(serde_json::to_value(encryption.seal(secret)?)?)
+}
+
+fn open_secret(encryption: &EncryptionService, webhook: &Webhook) -> anyhow::Result<String> {
+    let envelope: EncryptedEnvelope =
+        serde_json::from_value(webhook.secret.clone()).context("Invalid webhook secret")?;
+    encryption.open(&envelope)
+}
+
+/// Queue `sync.completed` or `sync.failed` for a sync run that just finished. Failures are
+/// logged rather than returned, so that webhooks never fail the sync bookkeeping.
+pub async fn notify_sync_finished(pool: &PgPool, sync_run_id: &str) {
+    if let Err(e) = queue_sync_finished(pool, sync_run_id).await {
+        error!(
+            "Failed to queue webhooks for sync run {}: {}",
+            sync_run_id, e
+        );
+    }
+}
+
+async fn queue_sync_finished(pool: &PgPool, sync_run_id: &str) -> anyhow::Result<()> {
+    let Some(run) = SyncRunRepository::new(pool).find_by_id(sync_run_id).await? else {
+        return Ok(());
+    };
+    let event = match run.status {
+        SyncStatus::Completed => WebhookEvent::SyncCompleted,
+        SyncStatus::Failed => WebhookEvent::SyncFailed,
+        _ => return Ok(()),
+    };
+    let source = SourceRepository::new(pool)
+        .find_by_id(run.source_id.clone())
+        .await?;
+
+    let payload = json!({
+        "source_id": run.source_id,
+        "source_name": source.as_ref().map(|s| s.name.as_str()),
+        "source_type": source.as_ref().map(|s| s.source_type),
+        "sync_run": SyncRunSummary::from(&run),
+    });
+    WebhookRepository::new(pool)
+        .enqueue(event, &[payload])
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign() {
+        assert_eq!(
+            sign("secret", 1_700_000_000, "{}"),
+            "sha256=b8569b78799ff9e3cbff0fc2d63a33a2b57f3282abd07c37ae5e8e7d79a5f163"
+        );
+        assert_ne!(sign("secret", 1, "{}"), sign("secret", 2, "{}"));
+        assert_ne!(sign("secret", 1, "{}"), sign("other", 1, "{}"));
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(1), Some(TimeDuration::seconds(30)));
+        assert_eq!(retry_delay(2), Some(TimeDuration::seconds(60)));
+        assert_eq!(retry_delay(7), Some(TimeDuration::seconds(1920)));
+        assert_eq!(retry_delay(MAX_DELIVERY_ATTEMPTS), None);
+    }
+
+    #[test]
+    fn test_generate_secret() {
+        let secret = generate_secret().unwrap();
+        assert!(secret.starts_with("whsec_"));
+        assert_eq!(secret.len(), "whsec_".len() + 64);
+        assert_ne!(secret, generate_secret().unwrap());
+    }
+}
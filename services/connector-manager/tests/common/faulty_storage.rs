@@ -0,0 +1,104 @@
+use async_trait::async_trait;
+use shared::storage::{ContentMetadata, ObjectStorage, StorageError};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Content storage that fails writes on demand, to simulate the storage backend going away
+/// in the middle of a sync. Reads always go through to the wrapped storage.
+pub struct FaultyStorage {
+    inner: Arc<dyn ObjectStorage>,
+    /// Writes that still succeed before every further write fails
+    writes_left: AtomicUsize,
+}
+
+impl FaultyStorage {
+    pub fn new(inner: Arc<dyn ObjectStorage>) -> Self {
+        Self {
+            inner,
+            writes_left: AtomicUsize::new(usize::MAX),
+        }
+    }
+
+    /// Let `writes` more writes succeed, then fail every write after them.
+    pub fn fail_writes_after(&self, writes: usize) {
+        self.writes_left.store(writes, Ordering::SeqCst);
+    }
+
+    fn check_write(&self) -> Result<(), StorageError> {
+        self.writes_left
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| {
+                left.checked_sub(1)
+            })
+            .map(|_| ())
+            .map_err(|_| StorageError::Backend("injected storage failure".to_string()))
+    }
+}
+
+#[async_trait]
+impl ObjectStorage for FaultyStorage {
+    async fn store_content(
+        &self,
+        content: &[u8],
+        prefix: Option<&str>,
+    ) -> Result<String, StorageError> {
+        self.check_write()?;
+        self.inner.store_content(content, prefix).await
+    }
+
+    async fn store_content_with_type(
+        &self,
+        content: &[u8],
+        content_type: Option<&str>,
+        prefix: Option<&str>,
+    ) -> Result<String, StorageError> {
+        self.check_write()?;
+        self.inner
+            .store_content_with_type(content, content_type, prefix)
+            .await
+    }
+
+    async fn get_content(&self, content_id: &str) -> Result<Vec<u8>, StorageError> {
+        self.inner.get_content(content_id).await
+    }
+
+    async fn delete_content(&self, content_id: &str) -> Result<(), StorageError> {
+        self.inner.delete_content(content_id).await
+    }
+
+    async fn get_content_size(&self, content_id: &str) -> Result<i64, StorageError> {
+        self.inner.get_content_size(content_id).await
+    }
+
+    async fn batch_get_text(
+        &self,
+        content_ids: Vec<String>,
+    ) -> Result<HashMap<String, String>, StorageError> {
+        self.inner.batch_get_text(content_ids).await
+    }
+
+    async fn get_content_metadata(
+        &self,
+        content_id: &str,
+    ) -> Result<ContentMetadata, StorageError> {
+        self.inner.get_content_metadata(content_id).await
+    }
+
+    async fn find_by_hash(&self, sha256_hash: &str) -> Result<Option<String>, StorageError> {
+        self.inner.find_by_hash(sha256_hash).await
+    }
+
+    async fn put_object(
+        &self,
+        key: &str,
+        content: &[u8],
+        content_type: Option<&str>,
+    ) -> Result<(), StorageError> {
+        self.check_write()?;
+        self.inner.put_object(key, content, content_type).await
+    }
+
+    async fn get_object(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        self.inner.get_object(key).await
+    }
+}
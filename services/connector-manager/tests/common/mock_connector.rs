@@ -7,6 +7,7 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value as JsonValue};
+use shared::models::{ConnectorEvent, DocumentMetadata, DocumentPermissions};
 use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
 use tokio::net::TcpListener;
@@ -19,6 +20,8 @@ pub struct RecordedSyncRequest {
     pub sync_mode: String,
     #[serde(default)]
     pub last_sync_at: Option<String>,
+    #[serde(default)]
+    pub sdk_token: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +29,66 @@ pub struct RecordedCancelRequest {
     pub sync_run_id: String,
 }
 
+/// Times a page is fetched before the connector gives up on the sync.
+pub const MAX_PAGE_ATTEMPTS: usize = 3;
+
+/// A fault injected into a scripted sync.
+#[derive(Debug, Clone)]
+pub enum Fault {
+    /// Fetching the page fails this many times, after which it succeeds.
+    PageFailure { page: usize, times: usize },
+    /// The connector stops responding on reaching the page: it neither sends anything more
+    /// nor finishes the sync, as when it times out talking to the source.
+    Hang { page: usize },
+}
+
+/// A sync for the mock connector to run against the connector manager, like a real
+/// connector would: each document's content is stored and an event emitted for it, page by
+/// page, and the sync completed, or failed on the first error it cannot recover from.
+#[derive(Debug, Clone, Default)]
+pub struct SyncScript {
+    /// External ids of the documents on each page.
+    pub pages: Vec<Vec<String>>,
+    pub faults: Vec<Fault>,
+}
+
+impl SyncScript {
+    /// `pages` pages of `page_size` documents each, named `doc-<page>-<index>`.
+    pub fn paged(pages: usize, page_size: usize) -> Self {
+        Self {
+            pages: (0..pages)
+                .map(|page| {
+                    (0..page_size)
+                        .map(|index| format!("doc-{}-{}", page, index))
+                        .collect()
+                })
+                .collect(),
+            faults: Vec::new(),
+        }
+    }
+
+    pub fn with_fault(mut self, fault: Fault) -> Self {
+        self.faults.push(fault);
+        self
+    }
+
+    fn page_failures(&self, page: usize) -> usize {
+        self.faults
+            .iter()
+            .map(|fault| match fault {
+                Fault::PageFailure { page: p, times } if *p == page => *times,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    fn hangs_at(&self, page: usize) -> bool {
+        self.faults
+            .iter()
+            .any(|fault| matches!(fault, Fault::Hang { page: p } if *p == page))
+    }
+}
+
 #[derive(Clone)]
 struct MockState {
     sync_requests: Arc<Mutex<Vec<RecordedSyncRequest>>>,
@@ -33,6 +96,9 @@ struct MockState {
     sync_response_status: Arc<Mutex<StatusCode>>,
     sync_response_body: Arc<Mutex<JsonValue>>,
     active_syncs: Arc<Mutex<HashSet<String>>>,
+    manager_url: Arc<Mutex<Option<String>>>,
+    sync_script: Arc<Mutex<Option<SyncScript>>>,
+    script_runs: Arc<Mutex<Vec<tokio::task::JoinHandle<()>>>>,
 }
 
 pub struct MockConnector {
@@ -41,6 +107,9 @@ pub struct MockConnector {
     pub cancel_requests: Arc<Mutex<Vec<RecordedCancelRequest>>>,
     sync_response_status: Arc<Mutex<StatusCode>>,
     sync_response_body: Arc<Mutex<JsonValue>>,
+    manager_url: Arc<Mutex<Option<String>>>,
+    sync_script: Arc<Mutex<Option<SyncScript>>>,
+    script_runs: Arc<Mutex<Vec<tokio::task::JoinHandle<()>>>>,
     _server_handle: tokio::task::JoinHandle<()>,
 }
 
@@ -52,6 +121,9 @@ impl MockConnector {
         let sync_response_status = Arc::new(Mutex::new(StatusCode::OK));
         let sync_response_body = Arc::new(Mutex::new(json!({"status": "accepted"})));
         let active_syncs = Arc::new(Mutex::new(HashSet::new()));
+        let manager_url = Arc::new(Mutex::new(None));
+        let sync_script = Arc::new(Mutex::new(None));
+        let script_runs = Arc::new(Mutex::new(Vec::new()));
 
         let state = MockState {
             sync_requests: sync_requests.clone(),
@@ -59,6 +131,9 @@ impl MockConnector {
             sync_response_status: sync_response_status.clone(),
             sync_response_body: sync_response_body.clone(),
             active_syncs: active_syncs.clone(),
+            manager_url: manager_url.clone(),
+            sync_script: sync_script.clone(),
+            script_runs: script_runs.clone(),
         };
 
         let app = Router::new()
@@ -76,6 +151,7 @@ impl MockConnector {
             )
             .route("/sync", post(handle_sync))
             .route("/cancel", post(handle_cancel))
+            .route("/test-connection", post(handle_test_connection))
             .with_state(state);
 
         let listener = TcpListener::bind("127.0.0.1:0").await?;
@@ -93,10 +169,28 @@ impl MockConnector {
             cancel_requests,
             sync_response_status,
             sync_response_body,
+            manager_url,
+            sync_script,
+            script_runs,
             _server_handle: server_handle,
         })
     }
 
+    /// Run `script` against the connector manager at `manager_url` for every sync requested
+    /// from now on, instead of only accepting the request.
+    pub fn set_sync_script(&self, manager_url: &str, script: SyncScript) {
+        *self.manager_url.lock().unwrap() = Some(manager_url.to_string());
+        *self.sync_script.lock().unwrap() = Some(script);
+    }
+
+    /// Wait for the scripted syncs started so far to finish running their script.
+    pub async fn wait_for_scripts(&self) {
+        let runs: Vec<_> = self.script_runs.lock().unwrap().drain(..).collect();
+        for run in runs {
+            run.await.unwrap();
+        }
+    }
+
     pub fn set_sync_response(&self, status: StatusCode, body: JsonValue) {
         *self.sync_response_status.lock().unwrap() = status;
         *self.sync_response_body.lock().unwrap() = body;
@@ -115,6 +209,22 @@ async fn health() -> StatusCode {
     StatusCode::OK
 }
 
+/// Connects unless the config's `base_path` is `/denied`, listing two folders with 120 and
+/// 30 documents.
+async fn handle_test_connection(Json(request): Json<JsonValue>) -> Json<JsonValue> {
+    if request["config"]["base_path"] == "/denied" {
+        return Json(json!({"success": false, "message": "Permission denied"}));
+    }
+    Json(json!({
+        "success": true,
+        "message": "Connected",
+        "scopes": [
+            {"kind": "folder", "id": "docs", "name": "Docs", "document_count": 120},
+            {"kind": "folder", "id": "wiki", "name": "Wiki", "document_count": 30}
+        ]
+    }))
+}
+
 async fn handle_sync(
     State(state): State<MockState>,
     Json(request): Json<RecordedSyncRequest>,
@@ -130,12 +240,155 @@ async fn handle_sync(
         }
         active.insert(source_id);
     }
-    state.sync_requests.lock().unwrap().push(request);
+    state.sync_requests.lock().unwrap().push(request.clone());
     let status = *state.sync_response_status.lock().unwrap();
     let body = state.sync_response_body.lock().unwrap().clone();
+
+    let script = state.sync_script.lock().unwrap().clone();
+    let manager_url = state.manager_url.lock().unwrap().clone();
+    if let (Some(script), Some(manager_url), true) = (script, manager_url, status.is_success()) {
+        let active_syncs = state.active_syncs.clone();
+        let run = tokio::spawn(async move {
+            // Like a real connector, answer the sync request before starting to sync
+            sleep(Duration::from_millis(50)).await;
+            run_script(&manager_url, &request, &script).await;
+            active_syncs.lock().unwrap().remove(&request.source_id);
+        });
+        state.script_runs.lock().unwrap().push(run);
+    }
     (status, Json(body))
 }
 
+async fn run_script(manager_url: &str, request: &RecordedSyncRequest, script: &SyncScript) {
+    // Authenticate with the sync's source token, as connectors do
+    let mut headers = reqwest::header::HeaderMap::new();
+    if let Some(token) = &request.sdk_token {
+        headers.insert(
+            reqwest::header::AUTHORIZATION,
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+    }
+    let client = reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .unwrap();
+    let sync_url = format!("{}/sdk/sync/{}", manager_url, request.sync_run_id);
+    let mut scanned = 0;
+
+    for (page, documents) in script.pages.iter().enumerate() {
+        if script.hangs_at(page) {
+            return;
+        }
+        // A failing page breaks off halfway through, and is synced again from its start
+        let failures = script.page_failures(page);
+        for attempt in 0.. {
+            let failing = attempt < failures;
+            let synced = if failing {
+                &documents[..documents.len() / 2]
+            } else {
+                &documents[..]
+            };
+            if let Err(error) = sync_documents(&client, manager_url, request, synced).await {
+                post_json(
+                    &client,
+                    &format!("{}/fail", sync_url),
+                    json!({ "error": error }),
+                )
+                .await;
+                return;
+            }
+            if !failing {
+                break;
+            }
+            if attempt + 1 == MAX_PAGE_ATTEMPTS {
+                let error = format!("Failed to fetch page {}: source returned 503", page);
+                post_json(
+                    &client,
+                    &format!("{}/fail", sync_url),
+                    json!({ "error": error }),
+                )
+                .await;
+                return;
+            }
+        }
+
+        scanned += documents.len() as i32;
+        post_json(
+            &client,
+            &format!("{}/scanned", sync_url),
+            json!({ "count": documents.len() }),
+        )
+        .await;
+    }
+
+    post_json(
+        &client,
+        &format!("{}/complete", sync_url),
+        json!({ "documents_scanned": scanned, "documents_updated": scanned }),
+    )
+    .await;
+}
+
+/// Store the content of each document and emit its event, stopping at the first error.
+async fn sync_documents(
+    client: &reqwest::Client,
+    manager_url: &str,
+    request: &RecordedSyncRequest,
+    documents: &[String],
+) -> Result<(), String> {
+    for external_id in documents {
+        let content_id = post_json(
+            client,
+            &format!("{}/sdk/content", manager_url),
+            json!({
+                "sync_run_id": request.sync_run_id,
+                "content": format!("Content of {}", external_id),
+            }),
+        )
+        .await
+        .and_then(|body| body["content_id"].as_str().map(String::from))
+        .ok_or_else(|| format!("Failed to store content of {}", external_id))?;
+
+        let event = ConnectorEvent::DocumentCreated {
+            sync_run_id: request.sync_run_id.clone(),
+            source_id: request.source_id.clone(),
+            document_id: external_id.clone(),
+            content_id,
+            metadata: DocumentMetadata {
+                title: Some(external_id.clone()),
+                ..Default::default()
+            },
+            permissions: DocumentPermissions {
+                public: true,
+                users: vec![],
+                groups: vec![],
+            },
+            attributes: None,
+        };
+        post_json(
+            client,
+            &format!("{}/sdk/events", manager_url),
+            json!({
+                "sync_run_id": request.sync_run_id,
+                "source_id": request.source_id,
+                "event": event,
+            }),
+        )
+        .await
+        .ok_or_else(|| format!("Failed to emit event for {}", external_id))?;
+    }
+    Ok(())
+}
+
+/// POST `body` to the connector manager, returning the response body when it succeeds.
+async fn post_json(client: &reqwest::Client, url: &str, body: JsonValue) -> Option<JsonValue> {
+    let response = client.post(url).json(&body).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response.json().await.ok()
+}
+
 async fn handle_cancel(
     State(state): State<MockState>,
     Json(request): Json<RecordedCancelRequest>,
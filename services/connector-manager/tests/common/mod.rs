@@ -1,6 +1,8 @@
+pub mod faulty_storage;
 pub mod mock_connector;
 
 use anyhow::Result;
+use faulty_storage::FaultyStorage;
 use mock_connector::MockConnector;
 use omni_connector_manager::{
     config::ConnectorManagerConfig, create_app, sync_manager::SyncManager, AppState,
@@ -13,15 +15,32 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 pub const TEST_SOURCE_ID: &str = "01JGF7V3E0Y2R1X8P5Q7W9T4N7";
+pub const SCIM_TOKEN: &str = "test-scim-token";
 
 pub struct TestFixture {
     pub state: AppState,
     pub app: axum::Router,
     pub mock_connector: MockConnector,
+    /// The content storage of `state`, which fails writes on demand.
+    pub storage: Arc<FaultyStorage>,
     #[allow(dead_code)]
     test_env: TestEnvironment,
 }
 
+impl TestFixture {
+    /// Serve the app on a local port, for a scripted mock connector to call back into as
+    /// real connectors do. Returns its base URL.
+    pub async fn serve(&self) -> Result<String> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let port = listener.local_addr()?.port();
+        let app = self.app.clone();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        Ok(format!("http://127.0.0.1:{}", port))
+    }
+}
+
 pub async fn setup_test_fixture() -> Result<TestFixture> {
     std::env::set_var(
         "ENCRYPTION_KEY",
@@ -44,10 +63,21 @@ pub async fn setup_test_fixture() -> Result<TestFixture> {
         max_concurrent_syncs_per_type: 3,
         scheduler_interval_seconds: 600,
         stale_sync_timeout_minutes: 1,
+        retention_rules: HashMap::new(),
+        retention_interval_seconds: 3600,
+        sync_run_retention_days: 90,
+        metadata_export_formats: Vec::new(),
+        metadata_export_interval_seconds: 86400,
+        scim_bearer_token: Some(SCIM_TOKEN.to_string()),
+        webhook_delivery_interval_seconds: 5,
+        sdk_tokens_required: false,
+        smtp: None,
     };
 
-    let content_storage: Arc<dyn ObjectStorage> =
-        Arc::new(PostgresStorage::new(test_env.db_pool.pool().clone()));
+    let storage = Arc::new(FaultyStorage::new(Arc::new(PostgresStorage::new(
+        test_env.db_pool.pool().clone(),
+    ))));
+    let content_storage: Arc<dyn ObjectStorage> = storage.clone();
 
     let sync_manager = Arc::new(SyncManager::new(&test_env.db_pool, config.clone()));
 
@@ -64,6 +94,7 @@ pub async fn setup_test_fixture() -> Result<TestFixture> {
         state: app_state,
         app,
         mock_connector,
+        storage,
         test_env,
     })
 }
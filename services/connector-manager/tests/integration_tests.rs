@@ -2,11 +2,14 @@ mod common;
 
 use axum::http::StatusCode;
 use axum_test::{TestServer, TestServerConfig};
+use common::mock_connector::{Fault, SyncScript, MAX_PAGE_ATTEMPTS};
 use common::TEST_SOURCE_ID;
+use omni_connector_manager::scheduler::Scheduler;
 use serde_json::json;
-use shared::db::repositories::SyncRunRepository;
+use shared::db::repositories::{SourceRepository, SyncRunRepository};
 use shared::models::{ConnectorEvent, DocumentMetadata, DocumentPermissions, SyncStatus};
 use shared::queue::EventQueue;
+use time::OffsetDateTime;
 
 fn test_server(fixture: &common::TestFixture) -> TestServer {
     let config = TestServerConfig::builder()
@@ -168,6 +171,8 @@ async fn test_sync_trigger_guards() {
         .unwrap()
         .to_lowercase()
         .contains("inactive"));
+    assert_eq!(body["code"], "bad_request");
+    assert_eq!(body["retriable"], false);
 
     // Already running → 409
     let resp = server
@@ -187,6 +192,8 @@ async fn test_sync_trigger_guards() {
         .unwrap()
         .to_lowercase()
         .contains("already running"));
+    assert_eq!(body["code"], "conflict");
+    assert_eq!(body["retriable"], true);
 
     // Concurrency limit (max_concurrent_syncs=2)
     let source2 = seed_source(pool, "local_files", true).await;
@@ -329,6 +336,8 @@ async fn test_sdk_event_and_content() {
             url: None,
             path: None,
             extra: None,
+            thumbnail: None,
+            parent: None,
         },
         permissions: DocumentPermissions {
             public: true,
@@ -349,6 +358,8 @@ async fn test_sdk_event_and_content() {
         .assert_status(StatusCode::OK);
 
     let event_queue = EventQueue::new(pool.clone());
+    assert_eq!(event_queue.get_outbox_count().await.unwrap(), 1);
+    assert_eq!(event_queue.relay_outbox(100).await.unwrap(), 1);
     let stats = event_queue.get_queue_stats().await.unwrap();
     assert!(
         stats.pending >= 1,
@@ -445,3 +456,1303 @@ async fn test_stale_sync_detection() {
         .await;
     resp.assert_status(StatusCode::OK);
 }
+
+// ============================================================================
+// 8. test_sync_history — dashboard view of recent runs per source
+// ============================================================================
+#[tokio::test]
+async fn test_sync_history() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = test_server(&fixture);
+
+    let failed_run_id = trigger_sync(&server).await;
+    server
+        .post(&format!("/sdk/sync/{}/fail", failed_run_id))
+        .json(&json!({"error": "Token expired"}))
+        .await
+        .assert_status(StatusCode::OK);
+
+    let completed_run_id = trigger_sync(&server).await;
+    server
+        .post(&format!("/sdk/sync/{}/complete", completed_run_id))
+        .json(&json!({"documents_scanned": 12, "documents_updated": 4}))
+        .await
+        .assert_status(StatusCode::OK);
+
+    let resp = server.get("/sync-history?limit=10").await;
+    resp.assert_status(StatusCode::OK);
+    let body: serde_json::Value = resp.json();
+
+    let source = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["source_id"] == TEST_SOURCE_ID)
+        .expect("test source missing from sync history");
+
+    assert_eq!(source["total_runs"], 2);
+    assert_eq!(source["failed_runs"], 1);
+    assert_eq!(source["error_rate"], 0.5);
+
+    let runs = source["runs"].as_array().unwrap();
+    assert_eq!(runs[0]["id"], completed_run_id.as_str());
+    assert_eq!(runs[0]["documents_scanned"], 12);
+    assert!(runs[0]["duration_seconds"].is_number());
+    assert_eq!(runs[1]["error_message"], "Token expired");
+}
+
+// ============================================================================
+// 9. test_retention_enforcement — expired documents are queued for deletion
+// ============================================================================
+#[tokio::test]
+async fn test_retention_enforcement() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let pool = fixture.state.db_pool.pool();
+
+    sqlx::query("UPDATE sources SET config = '{\"retention_days\": 30}' WHERE id = $1")
+        .bind(TEST_SOURCE_ID)
+        .execute(pool)
+        .await
+        .unwrap();
+
+    for (external_id, updated_at) in [
+        ("old-doc", "2020-01-01T00:00:00Z"),
+        ("new-doc", "2099-01-01T00:00:00Z"),
+    ] {
+        sqlx::query(
+            r#"
+            INSERT INTO documents (id, source_id, external_id, title, metadata)
+            VALUES ($1, $2, $3, $3, jsonb_build_object('updated_at', $4::text))
+            "#,
+        )
+        .bind(shared::utils::generate_ulid())
+        .bind(TEST_SOURCE_ID)
+        .bind(external_id)
+        .bind(updated_at)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    let enforcer = omni_connector_manager::retention::RetentionEnforcer::new(
+        pool.clone(),
+        &fixture.state.config,
+        "replica-a",
+    );
+    assert_eq!(enforcer.run_once().await.unwrap(), 1);
+
+    // The cutoff is recorded so the indexer drops the document if a sync sends it again
+    let cutoffs = SourceRepository::new(pool)
+        .find_retention_cutoffs(&[TEST_SOURCE_ID.to_string()])
+        .await
+        .unwrap();
+    assert!(cutoffs[TEST_SOURCE_ID] < OffsetDateTime::now_utc() - time::Duration::days(29));
+
+    let queued: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT payload->>'document_id' FROM connector_events_queue
+        WHERE source_id = $1 AND event_type = 'document_deleted'
+        "#,
+    )
+    .bind(TEST_SOURCE_ID)
+    .fetch_all(pool)
+    .await
+    .unwrap();
+    assert_eq!(queued, vec!["old-doc".to_string()]);
+
+    // Deletions already waiting in the queue are not queued again
+    assert_eq!(enforcer.run_once().await.unwrap(), 0);
+
+    // Another replica leaves the source to the one holding its lease
+    sqlx::query("DELETE FROM connector_events_queue WHERE source_id = $1")
+        .bind(TEST_SOURCE_ID)
+        .execute(pool)
+        .await
+        .unwrap();
+    let other = omni_connector_manager::retention::RetentionEnforcer::new(
+        pool.clone(),
+        &fixture.state.config,
+        "replica-b",
+    );
+    assert_eq!(other.run_once().await.unwrap(), 0);
+}
+
+// ============================================================================
+// 10. test_scim_provisioning — identity provider pushes users and groups
+// ============================================================================
+#[tokio::test]
+async fn test_scim_provisioning() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = test_server_no_expect(&fixture);
+
+    server
+        .get("/scim/v2/Users")
+        .authorization_bearer("wrong-token")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    let resp = server
+        .post("/scim/v2/Users")
+        .authorization_bearer(common::SCIM_TOKEN)
+        .json(&json!({
+            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
+            "userName": "jane@example.com",
+            "externalId": "okta-jane",
+            "name": {"givenName": "Jane", "familyName": "Doe"},
+            "active": true
+        }))
+        .await;
+    resp.assert_status(StatusCode::CREATED);
+    let user: serde_json::Value = resp.json();
+    let user_id = user["id"].as_str().unwrap().to_string();
+    assert_eq!(user["displayName"], "Jane Doe");
+
+    let resp = server
+        .get("/scim/v2/Users")
+        .authorization_bearer(common::SCIM_TOKEN)
+        .add_query_param("filter", r#"userName eq "JANE@example.com""#)
+        .await;
+    resp.assert_status(StatusCode::OK);
+    let list: serde_json::Value = resp.json();
+    assert_eq!(list["totalResults"], 1);
+    assert_eq!(list["Resources"][0]["id"], user_id.as_str());
+
+    let resp = server
+        .post("/scim/v2/Groups")
+        .authorization_bearer(common::SCIM_TOKEN)
+        .json(&json!({
+            "displayName": "Engineering",
+            "members": [{"value": user_id}]
+        }))
+        .await;
+    resp.assert_status(StatusCode::CREATED);
+    let group: serde_json::Value = resp.json();
+    let group_id = group["id"].as_str().unwrap().to_string();
+    assert_eq!(group["members"][0]["display"], "jane@example.com");
+
+    // Group membership grants access to documents shared with the group
+    let identifiers: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT g.display_name FROM groups g
+        JOIN group_members gm ON gm.group_id = g.id
+        JOIN users u ON u.id = gm.user_id
+        WHERE u.email = 'jane@example.com'
+        "#,
+    )
+    .fetch_all(fixture.state.db_pool.pool())
+    .await
+    .unwrap();
+    assert_eq!(identifiers, vec!["Engineering".to_string()]);
+
+    // Cached search responses aren't keyed by group, so membership changes drop them
+    let mut redis = redis::Client::open(fixture.state.config.redis.redis_url.as_str())
+        .unwrap()
+        .get_multiplexed_async_connection()
+        .await
+        .unwrap();
+    let cached = shared::cache::keys().cache(shared::cache::SEARCH_CACHE, "jane");
+    let _: () = redis::cmd("SET")
+        .arg(&cached)
+        .arg("{}")
+        .query_async(&mut redis)
+        .await
+        .unwrap();
+
+    let resp = server
+        .patch(&format!("/scim/v2/Groups/{}", group_id))
+        .authorization_bearer(common::SCIM_TOKEN)
+        .json(&json!({
+            "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
+            "Operations": [{"op": "remove", "path": format!("members[value eq \"{}\"]", user_id)}]
+        }))
+        .await;
+    resp.assert_status(StatusCode::OK);
+    let group: serde_json::Value = resp.json();
+    assert_eq!(group["members"], json!([]));
+    let cached_exists: bool = redis::cmd("EXISTS")
+        .arg(&cached)
+        .query_async(&mut redis)
+        .await
+        .unwrap();
+    assert!(!cached_exists);
+
+    let resp = server
+        .patch(&format!("/scim/v2/Users/{}", user_id))
+        .authorization_bearer(common::SCIM_TOKEN)
+        .json(&json!({
+            "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
+            "Operations": [{"op": "Replace", "path": "active", "value": "False"}]
+        }))
+        .await;
+    resp.assert_status(StatusCode::OK);
+    let user: serde_json::Value = resp.json();
+    assert_eq!(user["active"], false);
+
+    server
+        .delete(&format!("/scim/v2/Groups/{}", group_id))
+        .authorization_bearer(common::SCIM_TOKEN)
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    server
+        .get(&format!("/scim/v2/Groups/{}", group_id))
+        .authorization_bearer(common::SCIM_TOKEN)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+// ============================================================================
+// 11. test_content_exclusion_rules — admin-managed rules are served to connectors
+// ============================================================================
+#[tokio::test]
+async fn test_content_exclusion_rules() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = test_server(&fixture);
+
+    let resp = server.get("/sdk/exclusion-rules").await;
+    resp.assert_status(StatusCode::OK);
+    assert_eq!(resp.json::<serde_json::Value>(), json!({"rules": []}));
+
+    server
+        .put("/admin/exclusion-rules")
+        .json(&json!({"rules": [{"name": "broken", "path_globs": ["/a/[b"]}]}))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let rules = json!({
+        "rules": [
+            {"name": "hr", "source_types": ["google_drive"], "path_globs": ["/HR/**"]},
+            {"name": "legal", "labels": ["Privileged"], "sender_domains": ["lawfirm.com"]}
+        ]
+    });
+    server
+        .put("/admin/exclusion-rules")
+        .json(&rules)
+        .await
+        .assert_status(StatusCode::OK);
+
+    let resp = server.get("/sdk/exclusion-rules").await;
+    resp.assert_status(StatusCode::OK);
+    assert_eq!(resp.json::<serde_json::Value>(), rules);
+}
+
+// ============================================================================
+// 12. test_validate_source — configs are checked against their source type's schema
+// ============================================================================
+#[tokio::test]
+async fn test_validate_source() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = test_server(&fixture);
+
+    let resp = server
+        .post("/sources/validate")
+        .json(&json!({
+            "source_type": "web",
+            "config": {"root_url": "https://docs.example.com", "max_depth": 3}
+        }))
+        .await;
+    resp.assert_status(StatusCode::OK);
+    assert_eq!(resp.json::<serde_json::Value>(), json!({"valid": true}));
+
+    let resp = server
+        .post("/sources/validate")
+        .json(&json!({"source_type": "web", "config": {"root_url": "ftp://example.com"}}))
+        .await;
+    resp.assert_status(StatusCode::OK);
+    let body = resp.json::<serde_json::Value>();
+    assert_eq!(body["valid"], false);
+    assert!(body["error"].as_str().unwrap().contains("root_url"));
+
+    let resp = server
+        .post("/sources/validate")
+        .json(&json!({"source_type": "local_files", "config": {"max_depth": 3}}))
+        .await;
+    resp.assert_status(StatusCode::OK);
+    assert_eq!(resp.json::<serde_json::Value>()["valid"], false);
+}
+
+// ============================================================================
+// 13. test_sync_run_compaction — old runs are rolled into daily summaries
+// ============================================================================
+#[tokio::test]
+async fn test_sync_run_compaction() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let pool = fixture.state.db_pool.pool();
+
+    for (sync_type, status, started_at, scanned, error) in [
+        ("full", "completed", "2020-01-01T10:00:00Z", 10, None),
+        (
+            "full",
+            "failed",
+            "2020-01-01T12:00:00Z",
+            2,
+            Some("Token expired"),
+        ),
+        ("full", "completed", "2020-01-02T10:00:00Z", 5, None),
+        ("incremental", "completed", "2020-01-03T10:00:00Z", 1, None),
+        ("full", "completed", "2099-01-01T10:00:00Z", 7, None),
+    ] {
+        sqlx::query(
+            r#"
+            INSERT INTO sync_runs (id, source_id, sync_type, status, started_at, completed_at,
+                                   documents_scanned, error_message)
+            VALUES ($1, $2, $3, $4, $5::timestamptz, $5::timestamptz + INTERVAL '5 minutes',
+                    $6, $7)
+            "#,
+        )
+        .bind(shared::utils::generate_ulid())
+        .bind(TEST_SOURCE_ID)
+        .bind(sync_type)
+        .bind(status)
+        .bind(started_at)
+        .bind(scanned)
+        .bind(error)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    let compactor = omni_connector_manager::retention::SyncRunCompactor::new(
+        pool.clone(),
+        &fixture.state.config,
+    );
+    // The latest completed run of each sync type is kept even though it is old
+    assert_eq!(compactor.run_once().await.unwrap(), 3);
+    assert_eq!(compactor.run_once().await.unwrap(), 0);
+
+    let repo = SyncRunRepository::new(pool);
+    let since = time::OffsetDateTime::UNIX_EPOCH;
+
+    let remaining = repo
+        .find_for_source_since(TEST_SOURCE_ID, since)
+        .await
+        .unwrap();
+    assert_eq!(remaining.len(), 2);
+
+    let summaries = repo
+        .find_daily_summaries_for_source(TEST_SOURCE_ID, since)
+        .await
+        .unwrap();
+    assert_eq!(summaries.len(), 2);
+    assert_eq!(summaries[0].day, "2020-01-02");
+    assert_eq!(summaries[1].day, "2020-01-01");
+    assert_eq!(summaries[1].runs, 2);
+    assert_eq!(summaries[1].completed, 1);
+    assert_eq!(summaries[1].failed, 1);
+    assert_eq!(summaries[1].documents_scanned, 12);
+    assert_eq!(summaries[1].max_duration_seconds, 300.0);
+    assert_eq!(
+        summaries[1].last_error_message.as_deref(),
+        Some("Token expired")
+    );
+
+    let stats = repo.daily_stats(since).await.unwrap();
+    let first_day = stats.iter().find(|s| s.day == "2020-01-01").unwrap();
+    assert_eq!((first_day.completed, first_day.failed), (1, 1));
+    assert_eq!(
+        stats.iter().filter(|s| s.day.starts_with("2020")).count(),
+        3
+    );
+}
+
+// ============================================================================
+// 14. test_metadata_export — incremental exports of document metadata
+// ============================================================================
+#[tokio::test]
+async fn test_metadata_export() {
+    use omni_connector_manager::export::{ExportFormat, MetadataExporter};
+
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let pool = fixture.state.db_pool.pool();
+
+    let insert_document = |external_id: &'static str, updated_at: &'static str| async move {
+        sqlx::query(
+            r#"
+            INSERT INTO documents (id, source_id, external_id, title, metadata, attributes,
+                                   created_at, updated_at)
+            VALUES ($1, $2, $3, $3, '{}', '{"status": "open"}', $4::timestamptz, $4::timestamptz)
+            "#,
+        )
+        .bind(shared::utils::generate_ulid())
+        .bind(TEST_SOURCE_ID)
+        .bind(external_id)
+        .bind(updated_at)
+        .execute(pool)
+        .await
+        .unwrap();
+    };
+    insert_document("doc-1", "2020-01-01T00:00:00Z").await;
+    insert_document("doc-2", "2020-01-02T00:00:00Z").await;
+
+    let mut config = fixture.state.config.clone();
+    config.metadata_export_formats = vec![ExportFormat::Csv, ExportFormat::Parquet];
+    let exporter =
+        MetadataExporter::new(pool.clone(), fixture.state.content_storage.clone(), &config);
+
+    // Documents updated within the last minute are left for the next export
+    let exports = exporter.run_once().await.unwrap();
+    assert_eq!(exports.len(), 2);
+    assert!(exports.iter().all(|e| e.row_count == 2));
+
+    let csv = fixture
+        .state
+        .content_storage
+        .get_text(&exports[0].content_id)
+        .await
+        .unwrap();
+    assert!(csv.starts_with("id,source_id,source_type,external_id,title"));
+    assert!(csv.contains("doc-2"));
+
+    assert!(exporter.run_once().await.unwrap().is_empty());
+
+    insert_document("doc-3", "2020-01-03T00:00:00Z").await;
+    let exports = exporter.run_once().await.unwrap();
+    assert_eq!(exports.len(), 2);
+    assert!(exports.iter().all(|e| e.row_count == 1));
+
+    // Export files are never collected as orphaned content
+    let orphaned = shared::db::repositories::ContentBlobRepository::new(pool)
+        .filter_unreferenced(&[exports[0].content_id.clone()])
+        .await
+        .unwrap();
+    assert!(orphaned.is_empty());
+}
+
+// ============================================================================
+// 15. test_source_operations — listing, pausing and failed sync lookups for omnictl
+// ============================================================================
+#[tokio::test]
+async fn test_source_operations() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = test_server(&fixture);
+
+    let failed_run_id = trigger_sync(&server).await;
+    server
+        .post(&format!("/sdk/sync/{}/fail", failed_run_id))
+        .json(&json!({"error": "Token expired"}))
+        .await
+        .assert_status(StatusCode::OK);
+
+    let resp = server.get("/sync-errors").await;
+    resp.assert_status(StatusCode::OK);
+    let errors: serde_json::Value = resp.json();
+    let errors = errors.as_array().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0]["id"], failed_run_id.as_str());
+    assert_eq!(errors[0]["source_id"], TEST_SOURCE_ID);
+    assert_eq!(errors[0]["error_message"], "Token expired");
+
+    let resp = server.get("/sync-errors?since=2999-01-01T00:00:00Z").await;
+    assert_eq!(resp.json::<serde_json::Value>(), json!([]));
+
+    server
+        .post(&format!("/sources/{}/pause", TEST_SOURCE_ID))
+        .await
+        .assert_status(StatusCode::OK);
+
+    let resp = server.get("/sources").await;
+    resp.assert_status(StatusCode::OK);
+    let sources: serde_json::Value = resp.json();
+    let source = sources
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["id"] == TEST_SOURCE_ID)
+        .expect("paused source missing from source list");
+    assert_eq!(source["is_active"], false);
+    assert_eq!(source["last_sync"]["id"], failed_run_id.as_str());
+
+    // Paused sources cannot be synced until resumed
+    let server_no_expect = test_server_no_expect(&fixture);
+    server_no_expect
+        .post("/sync")
+        .json(&json!({"source_id": TEST_SOURCE_ID}))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    server_no_expect
+        .post("/sources/missing-source/pause")
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    server
+        .post(&format!("/sources/{}/resume", TEST_SOURCE_ID))
+        .await
+        .assert_status(StatusCode::OK);
+    trigger_sync(&server).await;
+}
+
+// ============================================================================
+// 16. test_webhooks — registration, signed delivery of sync events and the delivery log
+// ============================================================================
+#[tokio::test]
+async fn test_webhooks() {
+    use axum::{extract::State, http::HeaderMap, routing::post, Router};
+    use omni_connector_manager::webhooks::{self, WebhookDispatcher};
+    use shared::encryption::EncryptionService;
+    use std::sync::{Arc, Mutex};
+
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = test_server(&fixture);
+    let server_no_expect = test_server_no_expect(&fixture);
+
+    // Receiver recording every request, failing those to /fail
+    type Received = Arc<Mutex<Vec<(HeaderMap, String)>>>;
+    let received: Received = Arc::default();
+    let receiver = Router::new()
+        .route(
+            "/ok",
+            post(
+                |State(received): State<Received>, headers: HeaderMap, body: String| async move {
+                    received.lock().unwrap().push((headers, body));
+                    StatusCode::OK
+                },
+            ),
+        )
+        .route(
+            "/fail",
+            post(|| async { StatusCode::INTERNAL_SERVER_ERROR }),
+        )
+        .with_state(received.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let receiver_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, receiver).await.unwrap() });
+
+    server_no_expect
+        .post("/admin/webhooks")
+        .json(&json!({"url": format!("{}/ok", receiver_url), "event_types": ["alert.fired"]}))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    server_no_expect
+        .post("/admin/webhooks")
+        .json(&json!({"url": "ftp://example.com", "event_types": ["sync.failed"]}))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let resp = server
+        .post("/admin/webhooks")
+        .json(&json!({
+            "url": format!("{}/ok", receiver_url),
+            "event_types": ["sync.failed", "sync.completed"],
+        }))
+        .await;
+    let created: serde_json::Value = resp.json();
+    let webhook_id = created["id"].as_str().unwrap().to_string();
+    let secret = created["secret"].as_str().unwrap().to_string();
+
+    let resp = server
+        .post("/admin/webhooks")
+        .json(&json!({
+            "url": format!("{}/fail", receiver_url),
+            "event_types": ["sync.failed"],
+        }))
+        .await;
+    let failing_webhook_id = resp.json::<serde_json::Value>()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    // Secrets are only returned on registration
+    let resp = server.get("/admin/webhooks").await;
+    let listed: serde_json::Value = resp.json();
+    assert_eq!(listed.as_array().unwrap().len(), 2);
+    assert!(listed[0].get("secret").is_none());
+
+    let sync_run_id = trigger_sync(&server).await;
+    server
+        .post(&format!("/sdk/sync/{}/fail", sync_run_id))
+        .json(&json!({"error": "Token expired"}))
+        .await
+        .assert_status(StatusCode::OK);
+
+    let dispatcher =
+        WebhookDispatcher::new(fixture.state.db_pool.pool().clone(), &fixture.state.config);
+    let encryption = EncryptionService::new().unwrap();
+    assert_eq!(dispatcher.run_once(&encryption).await.unwrap(), 2);
+    // Nothing else is due until the failed delivery's retry
+    assert_eq!(dispatcher.run_once(&encryption).await.unwrap(), 0);
+
+    let (headers, body) = received
+        .lock()
+        .unwrap()
+        .pop()
+        .expect("no delivery received");
+    let payload: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(payload["event"], "sync.failed");
+    assert_eq!(payload["data"]["source_id"], TEST_SOURCE_ID);
+    assert_eq!(payload["data"]["sync_run"]["id"], sync_run_id.as_str());
+    assert_eq!(
+        payload["data"]["sync_run"]["error_message"],
+        "Token expired"
+    );
+    assert_eq!(headers[webhooks::EVENT_HEADER], "sync.failed");
+    let timestamp: i64 = headers[webhooks::TIMESTAMP_HEADER]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(
+        headers[webhooks::SIGNATURE_HEADER].to_str().unwrap(),
+        webhooks::sign(&secret, timestamp, &body)
+    );
+
+    let resp = server
+        .get(&format!("/admin/webhooks/{}/deliveries", webhook_id))
+        .await;
+    let deliveries: serde_json::Value = resp.json();
+    assert_eq!(deliveries.as_array().unwrap().len(), 1);
+    assert_eq!(deliveries[0]["status"], "delivered");
+    assert_eq!(deliveries[0]["response_status"], 200);
+
+    let resp = server
+        .get(&format!(
+            "/admin/webhooks/{}/deliveries?status=pending",
+            failing_webhook_id
+        ))
+        .await;
+    let deliveries: serde_json::Value = resp.json();
+    assert_eq!(deliveries.as_array().unwrap().len(), 1);
+    assert_eq!(deliveries[0]["attempts"], 1);
+    assert_eq!(deliveries[0]["response_status"], 500);
+
+    server
+        .delete(&format!("/admin/webhooks/{}", failing_webhook_id))
+        .await
+        .assert_status(StatusCode::OK);
+    server_no_expect
+        .get(&format!(
+            "/admin/webhooks/{}/deliveries",
+            failing_webhook_id
+        ))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+// ============================================================================
+// 17. test_reindex_source — sync state is cleared and a fresh full sync started
+// ============================================================================
+#[tokio::test]
+async fn test_reindex_source() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = test_server(&fixture);
+    let pool = fixture.state.db_pool.pool();
+
+    let other_source_id = seed_source(pool, "web", true).await;
+    let redis_client = redis::Client::open(fixture.state.config.redis.redis_url.as_str()).unwrap();
+    let mut conn = redis_client
+        .get_multiplexed_async_connection()
+        .await
+        .unwrap();
+    let keys = shared::cache::keys();
+    let source_keys = [
+        keys.source(TEST_SOURCE_ID).key("google:drive:file:file-1"),
+        keys.source(TEST_SOURCE_ID)
+            .key("google:drive:permissions:file-1"),
+        keys.source(TEST_SOURCE_ID).key("web:urls"),
+    ];
+    let other_key = keys.source(&other_source_id).key("web:urls");
+    for key in source_keys.iter().chain([&other_key]) {
+        let _: () = redis::cmd("SET")
+            .arg(key)
+            .arg("state")
+            .query_async(&mut conn)
+            .await
+            .unwrap();
+    }
+
+    sqlx::query("UPDATE sources SET connector_state = '{\"cursor\": \"abc\"}' WHERE id = $1")
+        .bind(TEST_SOURCE_ID)
+        .execute(pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO documents (id, source_id, external_id, title) VALUES ($1, $2, 'doc-1', 'Doc')",
+    )
+    .bind(shared::utils::generate_ulid())
+    .bind(TEST_SOURCE_ID)
+    .execute(pool)
+    .await
+    .unwrap();
+
+    let resp = server
+        .post(&format!("/sources/{}/reindex", TEST_SOURCE_ID))
+        .json(&json!({"delete_documents": true}))
+        .await;
+    resp.assert_status(StatusCode::OK);
+    let body: serde_json::Value = resp.json();
+    assert_eq!(body["status"], "started");
+    assert_eq!(body["redis_keys_cleared"], 3);
+    assert_eq!(body["documents_deleted"], 1);
+
+    for key in &source_keys {
+        let exists: bool = redis::cmd("EXISTS")
+            .arg(key)
+            .query_async(&mut conn)
+            .await
+            .unwrap();
+        assert!(!exists, "{} was not cleared", key);
+    }
+    let other_exists: bool = redis::cmd("EXISTS")
+        .arg(&other_key)
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+    assert!(other_exists, "state of another source was cleared");
+
+    let connector_state: Option<serde_json::Value> =
+        sqlx::query_scalar("SELECT connector_state FROM sources WHERE id = $1")
+            .bind(TEST_SOURCE_ID)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+    assert_eq!(connector_state, None);
+    let document_count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM documents WHERE source_id = $1")
+            .bind(TEST_SOURCE_ID)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+    assert_eq!(document_count, 0);
+
+    let requests = fixture.mock_connector.get_sync_requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        requests[0].sync_run_id,
+        body["sync_run_id"].as_str().unwrap()
+    );
+    assert_eq!(requests[0].sync_mode, "full");
+    assert_eq!(requests[0].last_sync_at, None);
+
+    // Nothing is cleared while the new sync is running
+    let server_no_expect = test_server_no_expect(&fixture);
+    server_no_expect
+        .post(&format!("/sources/{}/reindex", TEST_SOURCE_ID))
+        .await
+        .assert_status(StatusCode::CONFLICT);
+    server_no_expect
+        .post("/sources/missing-source/reindex")
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+/// Start a sync the mock connector runs with `script`, and wait for the script to finish.
+async fn run_scripted_sync(fixture: &common::TestFixture, script: SyncScript) -> String {
+    let manager_url = fixture.serve().await.unwrap();
+    fixture.mock_connector.set_sync_script(&manager_url, script);
+    let sync_run_id = trigger_sync(&test_server(fixture)).await;
+    fixture.mock_connector.wait_for_scripts().await;
+    sync_run_id
+}
+
+/// The documents a sync emitted events for, in order, whether still staged or already
+/// relayed to the event queue. Also checks that every event's content was stored.
+async fn emitted_documents(pool: &sqlx::PgPool, sync_run_id: &str) -> Vec<String> {
+    let events: Vec<(String, String, bool)> = sqlx::query_as(
+        r#"
+        SELECT e.payload->>'document_id', e.payload->>'content_id',
+               EXISTS (SELECT 1 FROM content_blobs cb WHERE cb.id = e.payload->>'content_id')
+        FROM (
+            SELECT payload, created_at FROM connector_events_outbox WHERE sync_run_id = $1
+            UNION ALL
+            SELECT payload, created_at FROM connector_events_queue WHERE sync_run_id = $1
+        ) e
+        ORDER BY e.created_at
+        "#,
+    )
+    .bind(sync_run_id)
+    .fetch_all(pool)
+    .await
+    .unwrap();
+
+    events
+        .into_iter()
+        .map(|(document_id, content_id, stored)| {
+            assert!(
+                stored,
+                "{} refers to missing content {}",
+                document_id, content_id
+            );
+            document_id
+        })
+        .collect()
+}
+
+// ============================================================================
+// 18. test_sync_partial_page_failure — a page breaking off halfway is synced again
+// ============================================================================
+#[tokio::test]
+async fn test_sync_partial_page_failure() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let pool = fixture.state.db_pool.pool();
+    let sync_run_repo = SyncRunRepository::new(pool);
+
+    let script = SyncScript::paged(3, 4).with_fault(Fault::PageFailure { page: 1, times: 2 });
+    let sync_run_id = run_scripted_sync(&fixture, script.clone()).await;
+
+    let run = sync_run_repo
+        .find_by_id(&sync_run_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(run.status, SyncStatus::Completed);
+    assert_eq!(run.documents_scanned, 12);
+
+    // Every document has an event, and the part of the page synced before each failure
+    // is emitted again on each retry
+    let emitted = emitted_documents(pool, &sync_run_id).await;
+    for document_id in script.pages.concat() {
+        let expected = match document_id.as_str() {
+            "doc-1-0" | "doc-1-1" => 3,
+            _ => 1,
+        };
+        assert_eq!(
+            emitted.iter().filter(|id| **id == document_id).count(),
+            expected,
+            "events for {}",
+            document_id
+        );
+    }
+}
+
+// ============================================================================
+// 19. test_sync_page_failure_exhausted — failing for good fails the sync, keeping events
+// ============================================================================
+#[tokio::test]
+async fn test_sync_page_failure_exhausted() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let pool = fixture.state.db_pool.pool();
+    let sync_run_repo = SyncRunRepository::new(pool);
+
+    let script = SyncScript::paged(3, 2).with_fault(Fault::PageFailure {
+        page: 1,
+        times: MAX_PAGE_ATTEMPTS,
+    });
+    let sync_run_id = run_scripted_sync(&fixture, script).await;
+
+    let run = sync_run_repo
+        .find_by_id(&sync_run_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(run.status, SyncStatus::Failed);
+    assert_eq!(
+        run.error_message.as_deref(),
+        Some("Failed to fetch page 1: source returned 503")
+    );
+    assert_eq!(run.documents_scanned, 2);
+
+    // What was synced before the failure is kept, and nothing after it was emitted
+    let emitted = emitted_documents(pool, &sync_run_id).await;
+    assert_eq!(
+        emitted,
+        vec!["doc-0-0", "doc-0-1", "doc-1-0", "doc-1-0", "doc-1-0"]
+    );
+}
+
+// ============================================================================
+// 20. test_sync_storage_failure — content storage failing mid-sync fails the sync
+// ============================================================================
+#[tokio::test]
+async fn test_sync_storage_failure() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let pool = fixture.state.db_pool.pool();
+    let sync_run_repo = SyncRunRepository::new(pool);
+
+    fixture.storage.fail_writes_after(4);
+    let sync_run_id = run_scripted_sync(&fixture, SyncScript::paged(2, 3)).await;
+
+    let run = sync_run_repo
+        .find_by_id(&sync_run_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(run.status, SyncStatus::Failed);
+    assert_eq!(
+        run.error_message.as_deref(),
+        Some("Failed to store content of doc-1-1")
+    );
+
+    // No event refers to content that failed to store
+    let emitted = emitted_documents(pool, &sync_run_id).await;
+    assert_eq!(emitted, vec!["doc-0-0", "doc-0-1", "doc-0-2", "doc-1-0"]);
+
+    // Once storage recovers, the next sync completes
+    fixture.storage.fail_writes_after(usize::MAX);
+    let sync_run_id = run_scripted_sync(&fixture, SyncScript::paged(2, 3)).await;
+    let run = sync_run_repo
+        .find_by_id(&sync_run_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(run.status, SyncStatus::Completed);
+    assert_eq!(emitted_documents(pool, &sync_run_id).await.len(), 6);
+}
+
+// ============================================================================
+// 21. test_sync_connector_timeout — a hung connector's sync fails, keeping its events
+// ============================================================================
+#[tokio::test]
+async fn test_sync_connector_timeout() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let pool = fixture.state.db_pool.pool();
+    let sync_run_repo = SyncRunRepository::new(pool);
+
+    let script = SyncScript::paged(3, 2).with_fault(Fault::Hang { page: 2 });
+    let sync_run_id = run_scripted_sync(&fixture, script).await;
+
+    let run = sync_run_repo
+        .find_by_id(&sync_run_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(run.status, SyncStatus::Running);
+
+    sqlx::query(
+        "UPDATE sync_runs SET last_activity_at = NOW() - INTERVAL '10 minutes' WHERE id = $1",
+    )
+    .bind(&sync_run_id)
+    .execute(pool)
+    .await
+    .unwrap();
+    let stale = fixture
+        .state
+        .sync_manager
+        .detect_stale_syncs()
+        .await
+        .unwrap();
+    assert_eq!(stale, vec![sync_run_id.clone()]);
+
+    let run = sync_run_repo
+        .find_by_id(&sync_run_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(run.status, SyncStatus::Failed);
+    assert!(run
+        .error_message
+        .as_deref()
+        .unwrap_or_default()
+        .contains("timed out"));
+    assert_eq!(run.documents_scanned, 4);
+    assert_eq!(
+        emitted_documents(pool, &sync_run_id).await,
+        vec!["doc-0-0", "doc-0-1", "doc-1-0", "doc-1-1"]
+    );
+}
+
+// ============================================================================
+// 22. test_sdk_tokens — connectors can only act on the sources they were given tokens for
+// ============================================================================
+#[tokio::test]
+async fn test_sdk_tokens() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = test_server_no_expect(&fixture);
+    let pool = fixture.state.db_pool.pool();
+
+    // A scripted sync authenticates each SDK call with the token it was sent
+    let sync_run_id = run_scripted_sync(&fixture, SyncScript::paged(1, 2)).await;
+    let run = SyncRunRepository::new(pool)
+        .find_by_id(&sync_run_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(run.status, SyncStatus::Completed);
+    let token = fixture.mock_connector.get_sync_requests()[0]
+        .sdk_token
+        .clone()
+        .expect("sync request carries a source token");
+
+    server
+        .get(&format!("/sdk/source/{}", TEST_SOURCE_ID))
+        .authorization_bearer(&token)
+        .await
+        .assert_status(StatusCode::OK);
+
+    // Another source's data and sync runs are off limits
+    let other_source_id = seed_source(pool, "local_files", true).await;
+    let other_sync_run_id = create_running_sync(pool, &other_source_id).await;
+    server
+        .get(&format!("/sdk/credentials/{}", other_source_id))
+        .authorization_bearer(&token)
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    server
+        .post(&format!("/sdk/sync/{}/heartbeat", other_sync_run_id))
+        .authorization_bearer(&token)
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    // Forged tokens are rejected, and so are rotated ones
+    let forged = token.replacen(TEST_SOURCE_ID, &other_source_id, 1);
+    server
+        .get(&format!("/sdk/source/{}", other_source_id))
+        .authorization_bearer(&forged)
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    server
+        .post(&format!("/sources/{}/sdk-token/rotate", TEST_SOURCE_ID))
+        .await
+        .assert_status(StatusCode::OK);
+    server
+        .get(&format!("/sdk/source/{}", TEST_SOURCE_ID))
+        .authorization_bearer(&token)
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+}
+
+// ============================================================================
+// 23. test_scheduler_leases — one replica schedules each source, another takes over
+// ============================================================================
+fn scheduler_replica(fixture: &common::TestFixture) -> Scheduler {
+    Scheduler::new(
+        fixture.state.db_pool.pool().clone(),
+        fixture.state.config.clone(),
+        fixture.state.sync_manager.clone(),
+    )
+}
+
+#[tokio::test]
+async fn test_scheduler_leases() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let pool = fixture.state.db_pool.pool();
+    let manager_url = fixture.serve().await.unwrap();
+    fixture
+        .mock_connector
+        .set_sync_script(&manager_url, SyncScript::paged(1, 1));
+    let (first, second) = (scheduler_replica(&fixture), scheduler_replica(&fixture));
+
+    sqlx::query("UPDATE sources SET sync_interval_seconds = 3600 WHERE id = $1")
+        .bind(TEST_SOURCE_ID)
+        .execute(pool)
+        .await
+        .unwrap();
+
+    // Both replicas find the source due at once, and only one triggers its sync
+    let (from_first, from_second) =
+        tokio::join!(first.process_due_sources(), second.process_due_sources());
+    let (from_first, from_second) = (from_first.unwrap(), from_second.unwrap());
+    assert_eq!(from_first.len() + from_second.len(), 1);
+    let (holder, standby) = if from_first.is_empty() {
+        (&second, &first)
+    } else {
+        (&first, &second)
+    };
+    fixture.mock_connector.wait_for_scripts().await;
+
+    // Due again while its scheduler runs, the source stays with it
+    sqlx::query(
+        "UPDATE sync_runs SET completed_at = NOW() - INTERVAL '2 hours' WHERE source_id = $1",
+    )
+    .bind(TEST_SOURCE_ID)
+    .execute(pool)
+    .await
+    .unwrap();
+    assert!(standby.process_due_sources().await.unwrap().is_empty());
+
+    // Once its lease runs out, the standby takes over
+    sqlx::query(
+        "UPDATE source_schedule_leases SET expires_at = NOW() - INTERVAL '1 second'
+         WHERE holder = $1",
+    )
+    .bind(holder.replica_id())
+    .execute(pool)
+    .await
+    .unwrap();
+    assert_eq!(standby.process_due_sources().await.unwrap().len(), 1);
+    assert!(holder.process_due_sources().await.unwrap().is_empty());
+}
+
+// ============================================================================
+// 24. test_paused_sync_resumes — a sync paused for shutdown is resumed by the scheduler
+// ============================================================================
+#[tokio::test]
+async fn test_paused_sync_resumes() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = test_server_no_expect(&fixture);
+    let pool = fixture.state.db_pool.pool();
+    let sync_run_repo = SyncRunRepository::new(pool);
+    let scheduler = scheduler_replica(&fixture);
+
+    let sync_run_id = create_running_sync(pool, TEST_SOURCE_ID).await;
+    server
+        .post(&format!("/sdk/sync/{}/pause", sync_run_id))
+        .json(&json!({"new_state": {"cursor": "page-7"}}))
+        .await
+        .assert_status(StatusCode::OK);
+
+    let run = sync_run_repo
+        .find_by_id(&sync_run_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(run.status, SyncStatus::Paused);
+    let connector_state: Option<serde_json::Value> =
+        sqlx::query_scalar("SELECT connector_state FROM sources WHERE id = $1")
+            .bind(TEST_SOURCE_ID)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+    assert_eq!(connector_state, Some(json!({"cursor": "page-7"})));
+
+    // Only running syncs can be paused
+    server
+        .post(&format!("/sdk/sync/{}/pause", sync_run_id))
+        .json(&json!({}))
+        .await
+        .assert_status(StatusCode::CONFLICT);
+
+    // The scheduler starts the sync again once, as the new run follows the paused one
+    let resumed = scheduler.resume_paused_syncs().await.unwrap();
+    assert_eq!(resumed.len(), 1);
+    assert_ne!(resumed[0], sync_run_id);
+    let requests = fixture.mock_connector.get_sync_requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].sync_run_id, resumed[0]);
+    assert_eq!(requests[0].sync_mode, "full");
+    assert!(scheduler.resume_paused_syncs().await.unwrap().is_empty());
+}
+
+// ============================================================================
+// 25. test_source_redis_keys — admins inspect, expire and clear a source's sync state keys
+// ============================================================================
+#[tokio::test]
+async fn test_source_redis_keys() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = test_server_no_expect(&fixture);
+    let pool = fixture.state.db_pool.pool();
+
+    let other_source_id = seed_source(pool, "web", true).await;
+    let redis_client = redis::Client::open(fixture.state.config.redis.redis_url.as_str()).unwrap();
+    let mut conn = redis_client
+        .get_multiplexed_async_connection()
+        .await
+        .unwrap();
+    let keys = shared::cache::keys();
+    let other_key = keys.source(&other_source_id).key("web:urls");
+    for (key, ttl) in [
+        (
+            keys.source(TEST_SOURCE_ID).key("google:drive:file:file-1"),
+            Some(60),
+        ),
+        (keys.source(TEST_SOURCE_ID).key("web:urls"), None),
+        (other_key.clone(), None),
+    ] {
+        let mut set = redis::cmd("SET");
+        set.arg(&key).arg("state");
+        if let Some(ttl) = ttl {
+            set.arg("EX").arg(ttl);
+        }
+        let _: () = set.query_async(&mut conn).await.unwrap();
+    }
+
+    let path = format!("/admin/sources/{}/redis-keys", TEST_SOURCE_ID);
+    let resp = server.get(&path).await;
+    resp.assert_status(StatusCode::OK);
+    let body: serde_json::Value = resp.json();
+    let listed = body["keys"].as_array().unwrap();
+    assert_eq!(listed.len(), 2);
+    assert_eq!(listed[0]["name"], "google:drive:file:file-1");
+    assert!(listed[0]["ttl_seconds"]
+        .as_i64()
+        .is_some_and(|ttl| ttl <= 60));
+    assert_eq!(listed[1]["name"], "web:urls");
+    assert!(listed[1]["ttl_seconds"].is_null());
+    assert_eq!(
+        listed[1]["ttl_policy_seconds"],
+        shared::cache::ttl::CRAWL_STATE
+    );
+    assert_eq!(body["keys_without_ttl"], 1);
+
+    // Keys that do not expire are given the policy's time to live
+    let resp = server.post(&format!("{}/expire", path)).await;
+    resp.assert_status(StatusCode::OK);
+    let body: serde_json::Value = resp.json();
+    assert_eq!(body["keys_expired"], 1);
+    let body: serde_json::Value = server.get(&path).await.json();
+    assert_eq!(body["keys_without_ttl"], 0);
+    let other_ttl: i64 = redis::cmd("TTL")
+        .arg(&other_key)
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+    assert_eq!(other_ttl, -1);
+
+    // Clearing is refused while the connector could write the state back
+    let sync_run_id = create_running_sync(pool, TEST_SOURCE_ID).await;
+    server
+        .delete(&path)
+        .await
+        .assert_status(StatusCode::CONFLICT);
+    SyncRunRepository::new(pool)
+        .mark_completed(&sync_run_id, 0, 0)
+        .await
+        .unwrap();
+
+    let resp = server.delete(&path).await;
+    resp.assert_status(StatusCode::OK);
+    let body: serde_json::Value = resp.json();
+    assert_eq!(body["keys_cleared"], 2);
+    let body: serde_json::Value = server.get(&path).await.json();
+    assert_eq!(body["keys"], json!([]));
+    let exists: bool = redis::cmd("EXISTS")
+        .arg(&other_key)
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+    assert!(exists);
+}
+
+// ============================================================================
+// 26. test_source_onboarding — capabilities, credentials and scope preview of a new source
+// ============================================================================
+#[tokio::test]
+async fn test_source_onboarding() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = test_server(&fixture);
+
+    let resp = server.get("/onboarding/local_files").await;
+    let capabilities = resp.json::<serde_json::Value>();
+    assert_eq!(capabilities["configured"], true);
+    assert_eq!(capabilities["healthy"], true);
+    assert_eq!(capabilities["sync_modes"], json!(["full", "incremental"]));
+    assert_eq!(capabilities["config_required"], true);
+
+    let resp = server.get("/onboarding/notion").await;
+    let capabilities = resp.json::<serde_json::Value>();
+    assert_eq!(capabilities["configured"], false);
+    assert_eq!(capabilities["config_required"], false);
+
+    let resp = server
+        .post("/onboarding/credentials")
+        .json(&json!({
+            "source_id": TEST_SOURCE_ID,
+            "source_type": "local_files",
+            "config": {"base_path": "/denied"}
+        }))
+        .await;
+    let body = resp.json::<serde_json::Value>();
+    assert_eq!(body["valid"], false);
+    assert_eq!(body["connection"]["message"], "Permission denied");
+
+    let resp = server
+        .post("/onboarding/preview")
+        .json(&json!({
+            "source_id": TEST_SOURCE_ID,
+            "source_type": "local_files",
+            "config": {"base_path": "/data"}
+        }))
+        .await;
+    let preview = resp.json::<serde_json::Value>();
+    assert_eq!(preview["valid"], true);
+    assert_eq!(preview["scopes"][0]["name"], "Docs");
+    assert_eq!(preview["estimated_documents"], 150);
+
+    let resp = server
+        .post("/onboarding/preview")
+        .json(&json!({
+            "source_id": TEST_SOURCE_ID,
+            "source_type": "local_files",
+            "config": {}
+        }))
+        .await;
+    assert_eq!(resp.json::<serde_json::Value>()["valid"], false);
+    assert!(fixture.mock_connector.get_sync_requests().is_empty());
+}
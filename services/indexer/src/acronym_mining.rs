@@ -0,0 +1,182 @@
+//! Mining of the acronym dictionary from indexed content.
+//!
+//! Documents commonly define an acronym the first time they use it, as in "Service Level
+//! Agreement (SLA)". The job scans the content of every document for that pattern, matching
+//! the letters of the parenthesised acronym against the words before it, and records each
+//! acronym and expansion found in enough documents as a pending dictionary entry. Admins
+//! review the entries in the searcher before they are used to expand queries.
+
+use crate::AppState;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use shared::db::repositories::{AcronymRepository, DocumentRepository, MinedAcronym};
+use std::collections::{HashMap, HashSet};
+use tracing::info;
+
+const DOCUMENT_PAGE_SIZE: i64 = 200;
+const MAX_ACRONYM_LEN: usize = 10;
+/// Characters that end the sentence an expansion is taken from.
+const SENTENCE_BREAKS: &[char] = &['.', ';', ':', '!', '?', '(', ')', '[', ']', '\n'];
+
+/// Body of `POST /admin/acronyms/mine`.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct MineAcronymsRequest {
+    /// Minimum number of documents an acronym and expansion must be found in, 2 by default.
+    pub min_documents: Option<i32>,
+}
+
+impl MineAcronymsRequest {
+    pub fn min_documents(&self) -> i32 {
+        self.min_documents.unwrap_or(2).max(1)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcronymMiningResult {
+    pub documents_scanned: u64,
+    /// Acronym and expansion pairs found in at least the minimum number of documents.
+    pub candidates: usize,
+    /// Candidates not yet in the dictionary, added for review.
+    pub added: u64,
+}
+
+#[derive(Default)]
+struct Candidate {
+    expansion: String,
+    occurrences: i32,
+    documents: i32,
+}
+
+/// Scan the content of all documents and record the acronyms found for review.
+pub async fn run(state: &AppState, min_documents: i32) -> Result<AcronymMiningResult> {
+    let pool = state.db_pool.pool();
+    let document_repo = DocumentRepository::new(pool);
+
+    // Keyed by acronym and lowercased expansion, keeping the first spelling seen
+    let mut candidates: HashMap<(String, String), Candidate> = HashMap::new();
+    let mut documents_scanned = 0;
+    let mut after_id: Option<String> = None;
+    loop {
+        let page = document_repo
+            .find_content_page(after_id.as_deref(), DOCUMENT_PAGE_SIZE)
+            .await?;
+        let Some((last_id, _)) = page.last() else {
+            break;
+        };
+        after_id = Some(last_id.clone());
+
+        for (_, content) in &page {
+            let mut seen = HashSet::new();
+            for (acronym, expansion) in extract(content) {
+                let key = (acronym, expansion.to_lowercase());
+                let candidate = candidates.entry(key.clone()).or_insert_with(|| Candidate {
+                    expansion,
+                    ..Default::default()
+                });
+                candidate.occurrences += 1;
+                if seen.insert(key) {
+                    candidate.documents += 1;
+                }
+            }
+            documents_scanned += 1;
+        }
+
+        if (page.len() as i64) < DOCUMENT_PAGE_SIZE {
+            break;
+        }
+    }
+
+    let mined: Vec<MinedAcronym> = candidates
+        .into_iter()
+        .filter(|(_, candidate)| candidate.documents >= min_documents)
+        .map(|((acronym, _), candidate)| MinedAcronym {
+            acronym,
+            expansion: candidate.expansion,
+            occurrences: candidate.occurrences,
+            document_count: candidate.documents,
+        })
+        .collect();
+    let added = AcronymRepository::new(pool).upsert_mined(&mined).await?;
+
+    info!(
+        "Acronym mining scanned {} documents, found {} candidates, added {} for review",
+        documents_scanned,
+        mined.len(),
+        added
+    );
+    Ok(AcronymMiningResult {
+        documents_scanned,
+        candidates: mined.len(),
+        added,
+    })
+}
+
+/// Acronym and expansion pairs defined in a text.
+fn extract(text: &str) -> Vec<(String, String)> {
+    let mut found = Vec::new();
+    for (open, _) in text.match_indices('(') {
+        let rest = &text[open + 1..];
+        let Some(close) = rest.find(')') else {
+            continue;
+        };
+        let acronym = rest[..close].trim();
+        if !is_acronym(acronym) {
+            continue;
+        }
+        if let Some(expansion) = expansion(&text[..open], acronym) {
+            found.push((acronym.to_string(), expansion));
+        }
+    }
+    found
+}
+
+/// Whether a parenthesised word looks like an acronym: short, alphanumeric, starting with a
+/// capital and with at least as many capitals as lowercase letters ("SLA", "SaaS", "K8s").
+fn is_acronym(word: &str) -> bool {
+    let len = word.chars().count();
+    let uppercase = word.chars().filter(|c| c.is_uppercase()).count();
+    let lowercase = word.chars().filter(|c| c.is_lowercase()).count();
+    (2..=MAX_ACRONYM_LEN).contains(&len)
+        && word.chars().all(char::is_alphanumeric)
+        && word.chars().next().is_some_and(char::is_uppercase)
+        && uppercase >= lowercase
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// The expansion of `acronym` at the end of `before`, found by matching the acronym's
+/// characters right to left against the preceding words, the first character at the start
+/// of a word (Schwartz & Hearst, 2003).
+fn expansion(before: &str, acronym: &str) -> Option<String> {
+    let sentence = before.rsplit(SENTENCE_BREAKS).next().unwrap_or(before);
+    let acronym_len = acronym.chars().count();
+    let max_words = (acronym_len + 5).min(acronym_len * 2);
+    let words: Vec<&str> = sentence.split_whitespace().collect();
+    let candidate = words[words.len().saturating_sub(max_words)..].join(" ");
+
+    let long: Vec<char> = candidate.chars().map(fold).collect();
+    let short: Vec<char> = acronym.chars().map(fold).collect();
+    let mut index = long.len();
+    for (short_index, &c) in short.iter().enumerate().rev() {
+        loop {
+            if index == 0 {
+                return None;
+            }
+            index -= 1;
+            let word_start = index == 0 || !long[index - 1].is_alphanumeric();
+            if long[index] == c && (short_index > 0 || word_start) {
+                break;
+            }
+        }
+    }
+
+    let expansion: String = candidate.chars().skip(index).collect();
+    let expansion = expansion.trim_end_matches(|c: char| !c.is_alphanumeric());
+    let defines_itself = expansion.split_whitespace().any(|word| word == acronym);
+    if expansion.chars().count() <= acronym_len || defines_itself {
+        return None;
+    }
+    Some(expansion.to_string())
+}
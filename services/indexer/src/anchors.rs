@@ -0,0 +1,84 @@
+//! Extraction of deep-link anchors from document content.
+//!
+//! Search results link into the section their best matching chunk is in. The indexer records
+//! where each markdown heading and each timestamped transcript line (`[12:34] Speaker: ...`,
+//! as written by the meeting connectors) starts, and the searcher looks up the anchors before
+//! a chunk's offset. Offsets count characters, like the chunk offsets of the embeddings.
+
+use shared::db::repositories::{AnchorKind, DocumentAnchor};
+
+const MAX_HEADING_LEVEL: usize = 6;
+
+/// The headings and transcript timestamps in a document's content, in order.
+pub fn extract(content: &str) -> Vec<DocumentAnchor> {
+    let mut anchors = Vec::new();
+    let mut offset = 0;
+    let mut in_code_block = false;
+    for line in content.split_inclusive('\n') {
+        let start_offset = offset as i32;
+        offset += line.chars().count();
+
+        let line = line.trim_end();
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        if let Some((level, label)) = heading(line) {
+            anchors.push(DocumentAnchor {
+                start_offset,
+                kind: AnchorKind::Heading,
+                label: label.to_string(),
+                level: Some(level as i16),
+                seconds: None,
+            });
+        } else if let Some((label, seconds)) = timestamp(line) {
+            anchors.push(DocumentAnchor {
+                start_offset,
+                kind: AnchorKind::Timestamp,
+                label: label.to_string(),
+                level: None,
+                seconds: Some(seconds),
+            });
+        }
+    }
+    anchors
+}
+
+/// The level and text of an ATX heading line, e.g. `## Action Items`.
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if level == 0 || level > MAX_HEADING_LEVEL {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    let label = rest.trim().trim_end_matches('#').trim_end();
+    (!label.is_empty()).then_some((level, label))
+}
+
+/// The timestamp and its seconds of a transcript line starting with `[MM:SS]` or
+/// `[HH:MM:SS]`. Minutes may exceed 59 when hours are left out.
+fn timestamp(line: &str) -> Option<(&str, i32)> {
+    let rest = line.strip_prefix('[')?;
+    let label = &rest[..rest.find(']')?];
+    let parts: Vec<&str> = label.split(':').collect();
+    if !(2..=3).contains(&parts.len())
+        || parts
+            .iter()
+            .any(|part| part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()))
+        || parts[1..].iter().any(|part| part.len() != 2)
+    {
+        return None;
+    }
+    let mut seconds: i32 = 0;
+    for part in &parts {
+        seconds = seconds.checked_mul(60)?.checked_add(part.parse().ok()?)?;
+    }
+    Some((label, seconds))
+}
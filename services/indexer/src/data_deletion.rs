@@ -0,0 +1,152 @@
+use crate::AppState;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use shared::db::repositories::{
+    ContentBlobRepository, DataDeletionRepository, DataDeletionRequest, DocumentRepository,
+    EmbeddingRepository, QueryLogRepository,
+};
+use shared::{cache, EncryptionService};
+use sqlx::types::time::OffsetDateTime;
+use tracing::{error, info};
+
+const DOCUMENT_DELETE_BATCH_SIZE: usize = 500;
+
+/// What a completed deletion request removed. The stored signature is
+/// [`EncryptionService::sign`] over the report serialized as compact JSON with sorted keys.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataDeletionReport {
+    pub request_id: String,
+    pub subject_email: String,
+    pub document_ids: Vec<String>,
+    pub documents_deleted: i64,
+    pub embeddings_deleted: u64,
+    pub content_blobs_deleted: usize,
+    pub query_logs_deleted: u64,
+    pub cache_entries_cleared: usize,
+    #[serde(with = "time::serde::iso8601")]
+    pub requested_at: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub completed_at: OffsetDateTime,
+}
+
+/// Run a deletion request in the background, recording the outcome on the request.
+pub fn spawn(state: AppState, request: DataDeletionRequest) {
+    tokio::spawn(async move {
+        let repo = DataDeletionRepository::new(state.db_pool.pool());
+        if let Err(e) = process(&state, &request).await {
+            error!("Data deletion request {} failed: {:#}", request.id, e);
+            if let Err(e) = repo.mark_failed(&request.id, &format!("{:#}", e)).await {
+                error!(
+                    "Failed to mark data deletion request {} as failed: {}",
+                    request.id, e
+                );
+            }
+        }
+    });
+}
+
+/// Resume requests left pending or running by a previous indexer process. Every step
+/// is idempotent, so re-running a partially processed request is safe.
+pub async fn resume_unfinished(state: &AppState) -> Result<()> {
+    let repo = DataDeletionRepository::new(state.db_pool.pool());
+    let requests = repo.find_unfinished().await?;
+    if !requests.is_empty() {
+        info!(
+            "Resuming {} unfinished data deletion requests",
+            requests.len()
+        );
+    }
+    for request in requests {
+        spawn(state.clone(), request);
+    }
+    Ok(())
+}
+
+async fn process(state: &AppState, request: &DataDeletionRequest) -> Result<DataDeletionReport> {
+    let pool = state.db_pool.pool();
+    let repo = DataDeletionRepository::new(pool);
+    repo.mark_running(&request.id).await?;
+
+    info!("Processing data deletion request {}", request.id);
+
+    let documents = DocumentRepository::new(pool)
+        .find_for_data_subject(&request.subject_email)
+        .await?;
+    let document_ids: Vec<String> = documents.iter().map(|(id, _)| id.clone()).collect();
+    let content_ids: Vec<String> = documents
+        .into_iter()
+        .filter_map(|(_, content_id)| content_id)
+        .collect();
+
+    let embedding_repo = EmbeddingRepository::new(pool);
+    let document_repo = DocumentRepository::new(pool);
+    let mut embeddings_deleted = 0;
+    let mut documents_deleted = 0;
+    for batch in document_ids.chunks(DOCUMENT_DELETE_BATCH_SIZE) {
+        embeddings_deleted += embedding_repo.bulk_delete_by_document_ids(batch).await?;
+        documents_deleted += document_repo.batch_delete(batch.to_vec()).await?;
+    }
+
+    // Blobs are content-addressed and may be shared with documents that are kept
+    let unreferenced = ContentBlobRepository::new(pool)
+        .filter_unreferenced(&content_ids)
+        .await?;
+    for content_id in &unreferenced {
+        state
+            .content_storage
+            .delete_content(content_id)
+            .await
+            .with_context(|| format!("Failed to delete content blob {}", content_id))?;
+    }
+
+    let query_logs_deleted = QueryLogRepository::new(pool)
+        .delete_for_user_email(&request.subject_email)
+        .await?;
+
+    let cache_entries_cleared = clear_response_caches(state).await?;
+
+    let report = DataDeletionReport {
+        request_id: request.id.clone(),
+        subject_email: request.subject_email.clone(),
+        document_ids,
+        documents_deleted,
+        embeddings_deleted,
+        content_blobs_deleted: unreferenced.len(),
+        query_logs_deleted,
+        cache_entries_cleared,
+        requested_at: request.created_at,
+        completed_at: OffsetDateTime::now_utc(),
+    };
+
+    let report_json = serde_json::to_value(&report)?;
+    let signature = EncryptionService::new()?.sign(&serde_json::to_vec(&report_json)?);
+    repo.mark_completed(&request.id, &report_json, &signature, report.completed_at)
+        .await?;
+
+    info!(
+        "Data deletion request {} completed: {} documents, {} embeddings, {} blobs, {} query logs",
+        request.id,
+        report.documents_deleted,
+        report.embeddings_deleted,
+        report.content_blobs_deleted,
+        report.query_logs_deleted
+    );
+
+    Ok(report)
+}
+
+/// Drop every cached search and AI answer response, returning the number of entries removed.
+/// Cache keys are hashes of the query, so entries mentioning a subject's documents cannot be
+/// targeted individually.
+pub(crate) async fn clear_response_caches(state: &AppState) -> Result<usize> {
+    let mut conn = state
+        .redis_client
+        .get_multiplexed_async_connection()
+        .await?;
+    let cleared = cache::clear_response_caches(&mut conn).await?;
+
+    if cleared > 0 {
+        info!("Cleared {} cached search responses", cleared);
+    }
+    Ok(cleared)
+}
@@ -0,0 +1,227 @@
+//! Daily and weekly email digests of the new documents matching each user's interests.
+//!
+//! Users opt in through the `digest` notification preference. When a user's period has passed
+//! since their last digest, the documents created or updated since then are scored against
+//! their recent searches, each search weighted by how recently it was made, and the best
+//! matches they can see and did not write or open yet are emailed to them. A digest is claimed
+//! before it is sent, so replicas running the job at once email each user only once, and no
+//! email is sent when nothing new matched.
+
+use crate::AppState;
+use anyhow::Result;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use shared::cache;
+use shared::db::repositories::{
+    DigestRepository, DocumentRepository, DueDigest, RecommendationRepository,
+};
+use shared::email::templates::{DIGEST, DIGEST_DOCUMENT};
+use shared::models::{DigestFrequency, Document};
+use shared::{EmailMessage, EmailNotifier, Repository, SourceRepository};
+use sqlx::types::time::OffsetDateTime;
+use std::collections::HashMap;
+use std::time::Duration;
+use time::format_description::well_known::Rfc3339;
+use tracing::{debug, error, info, warn};
+
+const MAX_DOCUMENTS: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestRunResult {
+    /// Users whose digest was due
+    pub users: usize,
+    /// Digests emailed, leaving out users nothing new matched
+    pub sent: usize,
+}
+
+/// Send the digests that are due every `interval`, the first time one interval after startup.
+pub async fn schedule(state: AppState, notifier: EmailNotifier, interval: Duration) {
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+    loop {
+        ticker.tick().await;
+        if let Err(e) = run(&state, &notifier).await {
+            error!("Sending digests failed: {:#}", e);
+        }
+    }
+}
+
+/// Send the digest of every user it is due for.
+pub async fn run(state: &AppState, notifier: &EmailNotifier) -> Result<DigestRunResult> {
+    let now = OffsetDateTime::now_utc();
+    let due = DigestRepository::new(state.db_pool.pool())
+        .find_due(now)
+        .await?;
+
+    let mut result = DigestRunResult {
+        users: due.len(),
+        sent: 0,
+    };
+    for digest in &due {
+        match send_digest(state, notifier, digest, now).await {
+            Ok(true) => result.sent += 1,
+            Ok(false) => {}
+            Err(e) => warn!("Failed to send digest to {}: {:#}", digest.user_id, e),
+        }
+    }
+
+    info!(
+        "Sent {} digests to the {} users due one",
+        result.sent, result.users
+    );
+    Ok(result)
+}
+
+/// Returns whether a digest was emailed.
+async fn send_digest(
+    state: &AppState,
+    notifier: &EmailNotifier,
+    digest: &DueDigest,
+    now: OffsetDateTime,
+) -> Result<bool> {
+    let pool = state.db_pool.pool();
+    let Some(period) = digest.frequency.period() else {
+        return Ok(false);
+    };
+    let since = digest.last_sent_at.unwrap_or(now - period);
+
+    if !DigestRepository::new(pool)
+        .claim(&digest.user_id, digest.last_sent_at, now)
+        .await?
+    {
+        debug!("Digest of {} was claimed by another run", digest.user_id);
+        return Ok(false);
+    }
+
+    let documents = find_documents(state, digest, since).await?;
+    if documents.is_empty() {
+        debug!("Nothing new matched the interests of {}", digest.user_id);
+        return Ok(false);
+    }
+
+    let source_repo = SourceRepository::new(pool);
+    let mut source_names: HashMap<String, String> = HashMap::new();
+    for document in &documents {
+        if !source_names.contains_key(&document.source_id) {
+            let name = source_repo
+                .find_by_id(document.source_id.clone())
+                .await?
+                .map(|source| source.name)
+                .unwrap_or_default();
+            source_names.insert(document.source_id.clone(), name);
+        }
+    }
+
+    let message = digest_message(digest, &documents, &source_names, notifier.app_url());
+    notifier.send(&digest.email, &message).await?;
+    DigestRepository::new(pool)
+        .record_sent(&digest.user_id, documents.len() as i32)
+        .await?;
+    Ok(true)
+}
+
+/// The documents updated since `since` that best match the user's recent searches, best first.
+async fn find_documents(
+    state: &AppState,
+    digest: &DueDigest,
+    since: OffsetDateTime,
+) -> Result<Vec<Document>> {
+    let pool = state.db_pool.pool();
+    let repo = RecommendationRepository::new(pool);
+
+    // Searches are listed most recent first, and count less the older they are
+    let mut scores: HashMap<String, f64> = HashMap::new();
+    for (position, query) in recent_searches(state, &digest.user_id)
+        .await
+        .iter()
+        .enumerate()
+    {
+        let weight = 1.0 / (1.0 + position as f64);
+        for candidate in repo.find_search_matches(query, since).await? {
+            *scores.entry(candidate.document_id).or_default() +=
+                weight * (1.0 + candidate.strength.max(0.0).ln_1p());
+        }
+    }
+    if scores.is_empty() {
+        return Ok(vec![]);
+    }
+    let known = repo
+        .find_known(&digest.user_id, &digest.email, since)
+        .await?;
+    scores.retain(|id, _| !known.contains(id));
+
+    let mut ranked: Vec<(String, f64)> = scores.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let document_repo = DocumentRepository::new(pool);
+    let source_ids = document_repo.fetch_active_source_ids(None).await?;
+    let ids: Vec<String> = ranked.iter().map(|(id, _)| id.clone()).collect();
+    let mut visible: HashMap<String, Document> = document_repo
+        .find_by_ids_with_filters(&ids, &source_ids, None, None, None, Some(&digest.email))
+        .await?
+        .into_iter()
+        .map(|document| (document.id.clone(), document))
+        .collect();
+
+    Ok(ranked
+        .into_iter()
+        .filter_map(|(id, _)| visible.remove(&id))
+        .take(MAX_DOCUMENTS)
+        .collect())
+}
+
+/// A user's recent searches, or none when they cannot be read.
+async fn recent_searches(state: &AppState, user_id: &str) -> Vec<String> {
+    let key = cache::keys().user(user_id, cache::SEARCH_HISTORY);
+    match state.redis_client.get_multiplexed_async_connection().await {
+        Ok(mut conn) => conn.lrange(&key, 0, -1).await.unwrap_or_default(),
+        Err(e) => {
+            warn!("Failed to read search history of {}: {}", user_id, e);
+            vec![]
+        }
+    }
+}
+
+fn digest_message(
+    digest: &DueDigest,
+    documents: &[Document],
+    source_names: &HashMap<String, String>,
+    app_url: &str,
+) -> EmailMessage {
+    let search_url = format!("{}/search", app_url);
+    let items: Vec<EmailMessage> = documents
+        .iter()
+        .map(|document| {
+            let url = document.url.as_deref().unwrap_or(&search_url);
+            let updated_at = document.updated_at.format(&Rfc3339).unwrap_or_default();
+            DIGEST_DOCUMENT.render(&[
+                ("title", &document.title),
+                ("url", url),
+                (
+                    "source_name",
+                    source_names
+                        .get(&document.source_id)
+                        .map(String::as_str)
+                        .unwrap_or_default(),
+                ),
+                ("updated_at", &updated_at),
+            ])
+        })
+        .collect();
+
+    let name = digest.full_name.as_deref().unwrap_or(&digest.email);
+    let period = match digest.frequency {
+        DigestFrequency::Weekly => "week",
+        _ => "day",
+    };
+    let count = documents.len().to_string();
+    DIGEST.render_with_parts(
+        &[
+            ("name", name),
+            ("frequency", digest.frequency.as_str()),
+            ("period", period),
+            ("count", &count),
+            ("search_url", &search_url),
+        ],
+        &[("documents", &EmailMessage::concat(&items))],
+    )
+}
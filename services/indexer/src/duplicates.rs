@@ -0,0 +1,137 @@
+//! Duplicate content analysis, run periodically to find clusters of identical or nearly
+//! identical documents, such as the same policy kept in Drive, Confluence and SharePoint.
+//!
+//! Documents are identical when their content hashes match, and near-identical when their
+//! first chunks' embeddings are nearly the same. Documents linked by either, directly or
+//! through other documents, form a cluster. Knowledge managers use the resulting report to
+//! pick the copy to keep and retire the others.
+
+use crate::AppState;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use shared::db::repositories::DuplicateDocumentRepository;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tracing::{error, info};
+
+/// Cosine similarity above which two documents are taken to be near-identical.
+const MIN_SIMILARITY: f32 = 0.95;
+/// Most near-identical documents looked for per document.
+const NEIGHBOURS_PER_DOCUMENT: i64 = 5;
+/// Smallest content compared by hash, so that empty and placeholder documents do not form
+/// one huge cluster.
+const MIN_CONTENT_BYTES: i64 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateRunResult {
+    pub clusters: usize,
+    pub documents: usize,
+}
+
+/// Analyze duplicate content every `interval`, the first time one interval after startup.
+pub async fn schedule(state: AppState, interval: Duration) {
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+    loop {
+        ticker.tick().await;
+        if let Err(e) = run(&state).await {
+            error!("Duplicate content analysis failed: {:#}", e);
+        }
+    }
+}
+
+/// Rebuild the duplicate clusters of every source.
+pub async fn run(state: &AppState) -> Result<DuplicateRunResult> {
+    let repo = DuplicateDocumentRepository::new(state.db_pool.pool());
+    let mut clusters = Clusters::default();
+    for group in repo.find_identical(MIN_CONTENT_BYTES).await? {
+        for pair in group.windows(2) {
+            clusters.link(&pair[0], &pair[1], 1.0, true);
+        }
+    }
+    for pair in repo
+        .find_similar(MIN_SIMILARITY, NEIGHBOURS_PER_DOCUMENT)
+        .await?
+    {
+        clusters.link(
+            &pair.document_id,
+            &pair.other_document_id,
+            pair.similarity,
+            false,
+        );
+    }
+
+    let members = clusters.members();
+    let result = DuplicateRunResult {
+        clusters: members
+            .iter()
+            .map(|(_, cluster_id, _, _)| cluster_id)
+            .collect::<HashSet<_>>()
+            .len(),
+        documents: members.len(),
+    };
+    repo.replace(&members).await?;
+
+    info!(
+        "Found {} duplicate clusters of {} documents",
+        result.clusters, result.documents
+    );
+    Ok(result)
+}
+
+/// Documents joined into clusters by the links found between them.
+#[derive(Default)]
+struct Clusters {
+    parents: HashMap<String, String>,
+    /// Highest similarity of each document to another, and whether that one is identical.
+    closest: HashMap<String, (f32, bool)>,
+}
+
+impl Clusters {
+    fn root(&mut self, id: &str) -> String {
+        let mut root = id.to_string();
+        while let Some(parent) = self.parents.get(&root).filter(|parent| **parent != root) {
+            root = parent.clone();
+        }
+        // Point every document on the way directly at the root
+        let mut current = id.to_string();
+        while current != root {
+            let next = self
+                .parents
+                .insert(current, root.clone())
+                .unwrap_or_else(|| root.clone());
+            current = next;
+        }
+        root
+    }
+
+    fn link(&mut self, a: &str, b: &str, similarity: f32, exact: bool) {
+        for id in [a, b] {
+            self.parents
+                .entry(id.to_string())
+                .or_insert_with(|| id.to_string());
+            let closest = self.closest.entry(id.to_string()).or_insert((0.0, false));
+            closest.0 = closest.0.max(similarity);
+            closest.1 |= exact;
+        }
+        let (root_a, root_b) = (self.root(a), self.root(b));
+        // The smallest document id is the root, so that it identifies the cluster
+        if root_a < root_b {
+            self.parents.insert(root_b, root_a);
+        } else if root_b < root_a {
+            self.parents.insert(root_a, root_b);
+        }
+    }
+
+    /// Each document with its cluster, highest similarity and whether it has an identical
+    /// copy.
+    fn members(mut self) -> Vec<(String, String, f32, bool)> {
+        let ids: Vec<String> = self.parents.keys().cloned().collect();
+        ids.into_iter()
+            .map(|id| {
+                let cluster_id = self.root(&id);
+                let (similarity, exact) = self.closest[&id];
+                (id, cluster_id, similarity, exact)
+            })
+            .collect()
+    }
+}
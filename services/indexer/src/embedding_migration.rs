@@ -0,0 +1,115 @@
+use crate::AppState;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use shared::db::repositories::{
+    DocumentRepository, EmbeddingRepository, ShadowComparisonRepository, ShadowComparisonSummary,
+};
+use shared::embedding_migration::{
+    load_current_config, load_migration, EmbeddingMigration, EmbeddingModelConfig,
+};
+use tracing::{error, info};
+
+const PURGE_BATCH_SIZE: i64 = 10_000;
+
+/// Documents with embeddings of one model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelCoverage {
+    pub model_name: String,
+    pub documents: i64,
+}
+
+/// State of the embedding model migration, if any, with API keys removed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingMigrationStatus {
+    /// The current model settings, `None` when the AI service uses its environment.
+    pub current: Option<EmbeddingModelConfig>,
+    pub migration: Option<EmbeddingMigration>,
+    /// Model serving searches, when known.
+    pub active_model: Option<String>,
+    pub total_documents: i64,
+    pub coverage: Vec<ModelCoverage>,
+    /// Shadow search comparisons since the migration started, per model pair.
+    pub comparisons: Vec<ShadowComparisonSummary>,
+}
+
+pub async fn status(state: &AppState) -> Result<EmbeddingMigrationStatus> {
+    let pool = state.db_pool.pool();
+    let current = load_current_config(pool).await?;
+    let migration = load_migration(pool).await?;
+
+    let active_model = match &migration {
+        Some(migration) if migration.cutover => Some(migration.target.model.clone()),
+        _ => current.as_ref().map(|config| config.model.clone()),
+    };
+    let coverage = EmbeddingRepository::new(pool)
+        .count_documents_by_model()
+        .await?
+        .into_iter()
+        .map(|(model_name, documents)| ModelCoverage {
+            model_name,
+            documents,
+        })
+        .collect();
+    let comparisons = match &migration {
+        Some(migration) => {
+            ShadowComparisonRepository::new(pool)
+                .summarize(migration.started_at)
+                .await?
+        }
+        None => Vec::new(),
+    };
+
+    Ok(EmbeddingMigrationStatus {
+        current: current.map(|config| config.redacted()),
+        migration: migration.map(redacted),
+        active_model,
+        total_documents: DocumentRepository::new(pool).count(None).await?,
+        coverage,
+        comparisons,
+    })
+}
+
+pub fn redacted(migration: EmbeddingMigration) -> EmbeddingMigration {
+    EmbeddingMigration {
+        target: migration.target.redacted(),
+        ..migration
+    }
+}
+
+/// Delete the embeddings of a model no longer in use in the background, in batches so the
+/// embeddings table is never locked for long. With `keep`, deletes the embeddings of every
+/// model but the given one instead.
+pub fn spawn_purge(state: AppState, model_name: String, keep: bool) {
+    tokio::spawn(async move {
+        let repo = EmbeddingRepository::new(state.db_pool.pool());
+        let mut deleted = 0;
+        loop {
+            match repo
+                .delete_batch_by_model(&model_name, keep, PURGE_BATCH_SIZE)
+                .await
+            {
+                Ok(count) => {
+                    deleted += count;
+                    if (count as i64) < PURGE_BATCH_SIZE {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    error!(
+                        "Failed to purge embeddings after embedding model migration: {}",
+                        e
+                    );
+                    return;
+                }
+            }
+        }
+        if keep {
+            info!(
+                "Purged {} embeddings of models other than {}",
+                deleted, model_name
+            );
+        } else {
+            info!("Purged {} embeddings of model {}", deleted, model_name);
+        }
+    });
+}
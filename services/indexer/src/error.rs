@@ -1,10 +1,6 @@
-use axum::{
-    http::StatusCode,
-    response::{IntoResponse, Response},
-    Json,
-};
-use serde_json::json;
+use axum::response::{IntoResponse, Response};
 use shared::db::error::DatabaseError;
+use shared::ApiError;
 use tracing::error;
 
 #[derive(Debug)]
@@ -14,6 +10,7 @@ pub enum IndexerError {
     Serialization(serde_json::Error),
     NotFound(String),
     BadRequest(String),
+    Forbidden(String),
     Internal(String),
 }
 
@@ -25,6 +22,7 @@ impl std::fmt::Display for IndexerError {
             IndexerError::Serialization(e) => write!(f, "Serialization error: {}", e),
             IndexerError::NotFound(msg) => write!(f, "Not found: {}", msg),
             IndexerError::BadRequest(msg) => write!(f, "Bad request: {}", msg),
+            IndexerError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
             IndexerError::Internal(msg) => write!(f, "Internal error: {}", msg),
         }
     }
@@ -61,37 +59,35 @@ impl From<DatabaseError> for IndexerError {
     }
 }
 
-impl IntoResponse for IndexerError {
-    fn into_response(self) -> Response {
-        let (status, error_message) = match &self {
+impl From<IndexerError> for ApiError {
+    fn from(err: IndexerError) -> Self {
+        match err {
             IndexerError::Database(db_err) => {
                 error!("Database error: {}", db_err);
-                let error_msg = format!("Database error: {}", db_err);
-                (StatusCode::INTERNAL_SERVER_ERROR, error_msg)
+                ApiError::internal(format!("Database error: {}", db_err))
             }
             IndexerError::Redis(redis_err) => {
                 error!("Redis error: {}", redis_err);
-                let error_msg = format!("Redis error: {}", redis_err);
-                (StatusCode::INTERNAL_SERVER_ERROR, error_msg)
+                ApiError::unavailable(format!("Redis error: {}", redis_err))
             }
             IndexerError::Serialization(ser_err) => {
                 error!("Serialization error: {}", ser_err);
-                (StatusCode::BAD_REQUEST, "Invalid data format".to_string())
+                ApiError::bad_request("Invalid data format")
             }
-            IndexerError::NotFound(msg) => (StatusCode::NOT_FOUND, msg.clone()),
-            IndexerError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
+            IndexerError::NotFound(msg) => ApiError::not_found(msg),
+            IndexerError::BadRequest(msg) => ApiError::bad_request(msg),
+            IndexerError::Forbidden(msg) => ApiError::forbidden(msg),
             IndexerError::Internal(msg) => {
                 error!("Internal error: {}", msg);
-                (StatusCode::INTERNAL_SERVER_ERROR, msg.clone())
+                ApiError::internal(msg)
             }
-        };
-
-        let body = Json(json!({
-            "error": error_message,
-            "timestamp": chrono::Utc::now().to_rfc3339()
-        }));
+        }
+    }
+}
 
-        (status, body).into_response()
+impl IntoResponse for IndexerError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
     }
 }
 
@@ -0,0 +1,52 @@
+//! Reading of queued connector events written under older event schemas.
+//!
+//! During a rolling deployment, the queue holds events written by connector managers on the
+//! previous release. Each payload carries the schema version it was written under, and is
+//! upcast one version at a time to [`ConnectorEvent::SCHEMA_VERSION`] before it is read.
+//! Payloads without a version predate versioning and are version 1.
+//!
+//! Payloads from a newer release are read as they are: new fields are ignored, so only
+//! additions the indexer does not know about yet are lost.
+
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+use shared::models::ConnectorEvent;
+
+/// Upgrades a payload of one schema version to the next.
+type Upcaster = fn(&mut Map<String, Value>);
+
+/// The upcaster at index `i` upgrades payloads of version `i + 1`.
+const UPCASTERS: [Upcaster; 1] = [add_attributes];
+
+// Bumping the schema version takes an upcaster for the version it replaces
+const _: () = assert!(UPCASTERS.len() as u32 + 1 == ConnectorEvent::SCHEMA_VERSION);
+
+/// Read a queued event, upcasting it from the schema version it was written under.
+pub fn decode(payload: &Value) -> Result<ConnectorEvent> {
+    let mut fields = payload
+        .as_object()
+        .cloned()
+        .ok_or_else(|| anyhow!("Event payload is not an object"))?;
+    let version = match fields.remove("schema_version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .filter(|version| *version >= 1)
+            .ok_or_else(|| anyhow!("Invalid event schema version {}", version))?,
+    };
+
+    for upcast in UPCASTERS.iter().skip(version as usize - 1) {
+        upcast(&mut fields);
+    }
+    Ok(serde_json::from_value(Value::Object(fields))?)
+}
+
+/// Version 2 added attributes to created and updated documents.
+fn add_attributes(fields: &mut Map<String, Value>) {
+    if matches!(
+        fields.get("type").and_then(Value::as_str),
+        Some("document_created" | "document_updated")
+    ) {
+        fields.entry("attributes").or_insert(Value::Null);
+    }
+}
@@ -0,0 +1,43 @@
+//! Content freshness analysis, run periodically to find documents that are likely stale.
+//!
+//! A document is stale when it has not been updated for a year and nobody opened it from
+//! search or the app. When a newer document of the same source has a first chunk nearly
+//! identical to its own, the stale document is reported as superseded by it. The resulting
+//! per-source report, with the authors owning stale documents, lets knowledge managers
+//! archive what has rotted.
+
+use crate::AppState;
+use anyhow::Result;
+use shared::db::repositories::{StaleDocumentRepository, StaleRebuildStats};
+use sqlx::types::time::OffsetDateTime;
+use std::time::Duration;
+use tracing::{error, info};
+
+const STALE_AFTER_DAYS: i64 = 365;
+/// Cosine similarity above which a newer document is taken to supersede a stale one.
+const SUPERSEDED_SIMILARITY: f32 = 0.9;
+
+/// Analyze content freshness every `interval`, the first time one interval after startup.
+pub async fn schedule(state: AppState, interval: Duration) {
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+    loop {
+        ticker.tick().await;
+        if let Err(e) = run(&state).await {
+            error!("Content freshness analysis failed: {:#}", e);
+        }
+    }
+}
+
+/// Rebuild the stale documents of every source.
+pub async fn run(state: &AppState) -> Result<StaleRebuildStats> {
+    let stale_before = OffsetDateTime::now_utc() - time::Duration::days(STALE_AFTER_DAYS);
+    let stats = StaleDocumentRepository::new(state.db_pool.pool())
+        .rebuild(stale_before, SUPERSEDED_SIMILARITY)
+        .await?;
+
+    info!(
+        "Found {} stale documents, {} of them superseded",
+        stats.stale_documents, stats.superseded
+    );
+    Ok(stats)
+}
@@ -0,0 +1,117 @@
+//! Index integrity checks, run on demand by admins. They verify that every document's content
+//! can be read, that every document with content is embedded by the current embedding model
+//! and that no embedding chunk points past the end of its document's content. Repairing
+//! queues the affected documents for re-embedding and marks content blobs nothing refers to
+//! as orphaned, so that garbage collection reclaims them.
+//!
+//! Documents whose content cannot be read are only reported: their content has to come from
+//! their source again, on its next sync.
+
+use crate::AppState;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use shared::db::repositories::{ContentBlobRepository, IndexIntegrityRepository};
+use shared::embedding_migration::load_current_config;
+use shared::storage::gc::GCConfig;
+use std::collections::BTreeSet;
+use tracing::info;
+
+/// Most document ids listed per broken invariant.
+const SAMPLE_SIZE: usize = 20;
+
+/// Documents breaking an invariant.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityIssue {
+    pub documents: usize,
+    pub sample: Vec<String>,
+}
+
+impl IntegrityIssue {
+    fn new(document_ids: &[String]) -> Self {
+        Self {
+            documents: document_ids.len(),
+            sample: document_ids.iter().take(SAMPLE_SIZE).cloned().collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityRepairs {
+    pub reembedding_queued: usize,
+    pub orphans_marked: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    /// The model embeddings are checked for, `None` when any model's embeddings count.
+    pub embedding_model: Option<String>,
+    pub documents_without_content: IntegrityIssue,
+    pub documents_missing_embeddings: IntegrityIssue,
+    pub documents_with_out_of_bounds_chunks: IntegrityIssue,
+    /// Content blobs nothing refers to that are not yet marked as orphaned.
+    pub unmarked_orphan_blobs: i64,
+    /// What was repaired, when repairing.
+    pub repairs: Option<IntegrityRepairs>,
+}
+
+/// Check the index's invariants, and repair what breaks them when `repair` is set.
+pub async fn run(state: &AppState, repair: bool) -> Result<IntegrityReport> {
+    let pool = state.db_pool.pool();
+    let repo = IndexIntegrityRepository::new(pool);
+    let blob_repo = ContentBlobRepository::new(pool);
+
+    // The AI service runs on the model from its environment until one is configured, in
+    // which case embeddings of any model count
+    let embedding_model = load_current_config(pool)
+        .await?
+        .map(|config| config.model)
+        .filter(|model| !model.is_empty());
+    let without_content = repo.find_documents_without_content().await?;
+    let missing_embeddings = repo
+        .find_documents_missing_embeddings(embedding_model.as_deref())
+        .await?;
+    let out_of_bounds = repo.find_documents_with_out_of_bounds_chunks().await?;
+    let unmarked_orphan_blobs = blob_repo
+        .get_orphan_stats(GCConfig::from_env().retention_days)
+        .await?
+        .unmarked_orphans;
+
+    let repairs = if repair {
+        let document_ids: Vec<String> = missing_embeddings
+            .iter()
+            .chain(&out_of_bounds)
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let reembedding_queued = document_ids.len();
+        if !document_ids.is_empty() {
+            state.embedding_queue.enqueue_batch(document_ids).await?;
+        }
+        let orphans_marked = blob_repo.mark_orphans().await?;
+        Some(IntegrityRepairs {
+            reembedding_queued,
+            orphans_marked,
+        })
+    } else {
+        None
+    };
+
+    let report = IntegrityReport {
+        embedding_model,
+        documents_without_content: IntegrityIssue::new(&without_content),
+        documents_missing_embeddings: IntegrityIssue::new(&missing_embeddings),
+        documents_with_out_of_bounds_chunks: IntegrityIssue::new(&out_of_bounds),
+        unmarked_orphan_blobs,
+        repairs,
+    };
+    info!(
+        "Index integrity check: {} documents without content, {} missing embeddings, {} with \
+         out-of-bounds chunks, {} unmarked orphan blobs",
+        report.documents_without_content.documents,
+        report.documents_missing_embeddings.documents,
+        report.documents_with_out_of_bounds_chunks.documents,
+        report.unmarked_orphan_blobs
+    );
+    Ok(report)
+}
@@ -1,21 +1,41 @@
+pub mod acronym_mining;
+pub mod anchors;
+pub mod data_deletion;
+pub mod digests;
+pub mod duplicates;
+pub mod embedding_migration;
 pub mod error;
+pub mod event_schema;
+pub mod freshness;
+pub mod integrity;
+pub mod links;
+pub mod metrics;
+pub mod people;
+pub mod previews;
 pub mod queue_processor;
+pub mod recommendations;
+pub mod snapshot;
 
 pub use error::{IndexerError, Result};
 pub use queue_processor::QueueProcessor;
-pub use shared::models::{ConnectorEvent, DocumentMetadata, DocumentPermissions};
+pub use shared::models::{
+    ConnectorEvent, DocumentMetadata, DocumentPermissions, PermissionOverrides,
+};
 
 pub use axum::Router;
 pub use redis::Client as RedisClient;
 pub use serde::{Deserialize, Serialize};
 pub use serde_json::Value;
 pub use shared::db::pool::DatabasePool;
+use shared::shutdown::Shutdown;
+use shared::startup::{connect_database, connect_redis, Dependencies, Startup};
 pub use shared::AIClient;
 use shared::ServiceCredentialsRepo;
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    http::StatusCode,
     middleware,
     response::Json,
     routing::{delete, get, post, put},
@@ -23,19 +43,33 @@ use axum::{
 use error::Result as IndexerResult;
 use serde_json::json;
 use shared::{
-    db::repositories::{DocumentRepository, OrphanStats},
-    models::Document,
+    db::repositories::{
+        AttributeCardinality, DataDeletionRepository, DataDeletionRequest, DocumentPermissionState,
+        DocumentRepository, DuplicateDocument, DuplicateDocumentRepository, IndexSnapshot,
+        IndexSnapshotRepository, OrphanStats, SourceIndexingLag, SourceRepository,
+        StaleContentOwner, StaleDocument, StaleDocumentRepository, StaleRebuildStats,
+        StaleSourceSummary, UserRepository,
+    },
+    embedding_migration::{
+        cancel_migration, finalize_migration, load_current_config, load_migration, save_migration,
+        start_migration, EmbeddingMigration, EmbeddingModelConfig,
+    },
+    models::{Document, UserRole},
     storage::gc::{ContentBlobGC, GCConfig, GCResult},
     telemetry::{self, TelemetryConfig},
-    IndexerConfig,
+    IndexerConfig, Repository,
 };
 use sqlx::types::time::OffsetDateTime;
+use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
 use tower::ServiceBuilder;
 use tower_http::cors::CorsLayer;
 use tracing::{error, info};
 use ulid::Ulid;
 
+/// Migrations this build expects the database to be at, checked on startup.
+static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("../migrations");
+
 #[derive(Clone)]
 pub struct AppState {
     pub db_pool: DatabasePool,
@@ -84,7 +118,7 @@ pub struct BulkDocumentResponse {
     pub errors: Vec<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Deserialize, Serialize)]
 pub struct CreateServiceCredentialsRequest {
     pub source_id: String,
     pub provider: shared::models::ServiceProvider,
@@ -94,12 +128,188 @@ pub struct CreateServiceCredentialsRequest {
     pub config: Value,
 }
 
+impl std::fmt::Debug for CreateServiceCredentialsRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CreateServiceCredentialsRequest")
+            .field("source_id", &self.source_id)
+            .field("provider", &self.provider)
+            .field("auth_type", &self.auth_type)
+            .field("principal_email", &self.principal_email)
+            .field(
+                "credentials",
+                &shared::redaction::redact_all(&self.credentials),
+            )
+            .field("config", &shared::redaction::redact_json(&self.config))
+            .finish()
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct CreateServiceCredentialsResponse {
     pub success: bool,
     pub message: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct IndexingLagQuery {
+    pub hours: Option<i64>,
+}
+
+impl IndexingLagQuery {
+    /// Lookback window in hours, defaulting to one day and capped at 30 days.
+    pub fn hours(&self) -> i64 {
+        self.hours.unwrap_or(24).clamp(1, 720)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AttributeCardinalityQuery {
+    pub source_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StaleContentQuery {
+    pub source_id: Option<String>,
+    pub limit: Option<i64>,
+}
+
+impl StaleContentQuery {
+    /// Most documents listed per source.
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(100).clamp(1, 1000)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DuplicateContentQuery {
+    pub source_id: Option<String>,
+    pub limit: Option<i64>,
+}
+
+impl DuplicateContentQuery {
+    /// Most clusters listed.
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(100).clamp(1, 1000)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateDataDeletionRequest {
+    pub email: String,
+}
+
+/// Body of `POST /admin/snapshots`. Without a source the whole index is snapshotted.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct CreateSnapshotRequest {
+    pub source_id: Option<String>,
+    pub created_by: Option<String>,
+}
+
+/// Body of `POST /admin/snapshots/:id/restore`. Without a source every source in the
+/// snapshot is restored. `created_by` is the id of the user restoring, who becomes the owner
+/// of restored sources whose owner does not exist in this deployment.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct RestoreSnapshotRequest {
+    pub source_id: Option<String>,
+    pub created_by: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SnapshotListQuery {
+    pub limit: Option<i64>,
+}
+
+impl SnapshotListQuery {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(50).clamp(1, 500)
+    }
+}
+
+/// Body of `PUT /documents/:id/permissions`. `requested_by` is the email of the user making
+/// the change, who must be an admin or the document's owner.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetPermissionOverridesRequest {
+    pub requested_by: String,
+    #[serde(flatten)]
+    pub overrides: PermissionOverrides,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PermissionOverridesQuery {
+    pub requested_by: String,
+}
+
+/// Body of `POST /admin/reembed`. Without a source every document is re-embedded.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ReembedRequest {
+    pub source_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReembedResponse {
+    pub queued: u64,
+}
+
+/// Body of `POST /admin/embedding-migration`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct StartEmbeddingMigrationRequest {
+    pub target: EmbeddingModelConfig,
+    pub shadow_search_percentage: Option<f64>,
+}
+
+impl StartEmbeddingMigrationRequest {
+    pub fn shadow_search_percentage(&self) -> f64 {
+        self.shadow_search_percentage.unwrap_or(10.0)
+    }
+}
+
+/// Body of `PATCH /admin/embedding-migration`. Setting `cutover` switches searches to the
+/// target model, clearing it switches them back.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct UpdateEmbeddingMigrationRequest {
+    pub shadow_search_percentage: Option<f64>,
+    pub cutover: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IndexingLagResponse {
+    pub window_hours: i64,
+    pub sources: Vec<SourceIndexingLag>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AttributeCardinalityResponse {
+    pub total_documents: i64,
+    pub attributes: Vec<AttributeCardinality>,
+}
+
+/// Stale documents of one source, with the people owning them.
+#[derive(Debug, Serialize)]
+pub struct StaleSourceReport {
+    #[serde(flatten)]
+    pub summary: StaleSourceSummary,
+    pub owners: Vec<StaleContentOwner>,
+    pub documents: Vec<StaleDocument>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StaleContentReport {
+    pub sources: Vec<StaleSourceReport>,
+}
+
+/// Documents with identical or near-identical content, most similar first.
+#[derive(Debug, Serialize)]
+pub struct DuplicateCluster {
+    pub cluster_id: String,
+    pub sources: usize,
+    pub documents: Vec<DuplicateDocument>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DuplicateContentReport {
+    pub clusters: Vec<DuplicateCluster>,
+}
+
 pub fn create_app(state: AppState) -> Router {
     Router::new()
         .route("/health", get(health_check))
@@ -109,9 +319,49 @@ pub fn create_app(state: AppState) -> Router {
         .route("/documents/:id", get(get_document))
         .route("/documents/:id", put(update_document))
         .route("/documents/:id", delete(delete_document))
+        .route("/documents/:id/permissions", get(get_permission_overrides))
+        .route("/documents/:id/permissions", put(set_permission_overrides))
+        .route(
+            "/documents/:id/permissions",
+            delete(delete_permission_overrides),
+        )
         .route("/service-credentials", post(create_service_credentials))
         .route("/admin/gc/run", post(run_gc))
         .route("/admin/gc/stats", get(gc_stats))
+        .route("/admin/acronyms/mine", post(mine_acronyms))
+        .route("/admin/people/aggregate", post(aggregate_people))
+        .route(
+            "/admin/recommendations/compute",
+            post(compute_recommendations),
+        )
+        .route("/admin/stale-content", get(stale_content))
+        .route("/admin/stale-content/analyze", post(analyze_stale_content))
+        .route("/admin/duplicates", get(duplicate_content))
+        .route("/admin/duplicates/analyze", post(analyze_duplicate_content))
+        .route("/admin/integrity", get(check_integrity))
+        .route("/admin/integrity/repair", post(repair_integrity))
+        .route("/admin/reembed", post(reembed))
+        .route(
+            "/admin/embedding-migration",
+            get(get_embedding_migration)
+                .post(create_embedding_migration)
+                .patch(update_embedding_migration)
+                .delete(delete_embedding_migration),
+        )
+        .route(
+            "/admin/embedding-migration/finalize",
+            post(finalize_embedding_migration),
+        )
+        .route("/admin/indexing-lag", get(indexing_lag))
+        .route("/admin/attribute-cardinality", get(attribute_cardinality))
+        .route("/admin/data-deletions", post(create_data_deletion))
+        .route("/admin/data-deletions/:id", get(get_data_deletion))
+        .route(
+            "/admin/snapshots",
+            get(list_snapshots).post(create_snapshot),
+        )
+        .route("/admin/snapshots/:id", get(get_snapshot))
+        .route("/admin/snapshots/:id/restore", post(restore_snapshot))
         .layer(
             ServiceBuilder::new()
                 .layer(middleware::from_fn(telemetry::middleware::trace_layer))
@@ -171,6 +421,7 @@ async fn create_document(
         created_at: now,
         updated_at: now,
         last_indexed_at: now,
+        preview: None,
     };
 
     let repo = DocumentRepository::new(state.db_pool.pool());
@@ -256,6 +507,100 @@ async fn delete_document(
     })))
 }
 
+/// Load a document's permission state, checking that `requested_by` may manage its
+/// overrides: active admins can manage any document, other users only documents they own.
+async fn authorize_permission_overrides(
+    state: &AppState,
+    id: &str,
+    requested_by: &str,
+) -> IndexerResult<DocumentPermissionState> {
+    let repo = DocumentRepository::new(state.db_pool.pool());
+    let permission_state = repo
+        .find_permission_state(id)
+        .await?
+        .ok_or_else(|| IndexerError::NotFound(format!("Document {} not found", id)))?;
+
+    let user = UserRepository::new(state.db_pool.pool())
+        .find_by_email(requested_by.trim())
+        .await?
+        .filter(|user| user.is_active);
+    let allowed = match user {
+        Some(user) if user.role == UserRole::Admin => true,
+        Some(user) => permission_state
+            .owner
+            .as_deref()
+            .is_some_and(|owner| owner.eq_ignore_ascii_case(&user.email)),
+        None => false,
+    };
+
+    if !allowed {
+        return Err(IndexerError::Forbidden(format!(
+            "{} may not change permissions of document {}",
+            requested_by, id
+        )));
+    }
+    Ok(permission_state)
+}
+
+async fn get_permission_overrides(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<PermissionOverridesQuery>,
+) -> IndexerResult<Json<DocumentPermissionState>> {
+    let permission_state = authorize_permission_overrides(&state, &id, &query.requested_by).await?;
+    Ok(Json(permission_state))
+}
+
+async fn set_permission_overrides(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(request): Json<SetPermissionOverridesRequest>,
+) -> IndexerResult<Json<DocumentPermissionState>> {
+    authorize_permission_overrides(&state, &id, &request.requested_by).await?;
+
+    let repo = DocumentRepository::new(state.db_pool.pool());
+    let permission_state = repo
+        .set_permission_overrides(&id, Some(&request.overrides))
+        .await?
+        .ok_or_else(|| IndexerError::NotFound(format!("Document {} not found", id)))?;
+
+    info!(
+        "Permission overrides of document {} set by {}",
+        id, request.requested_by
+    );
+    clear_cached_responses(&state).await;
+    Ok(Json(permission_state))
+}
+
+async fn delete_permission_overrides(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<PermissionOverridesQuery>,
+) -> IndexerResult<Json<DocumentPermissionState>> {
+    authorize_permission_overrides(&state, &id, &query.requested_by).await?;
+
+    let repo = DocumentRepository::new(state.db_pool.pool());
+    let permission_state = repo
+        .set_permission_overrides(&id, None)
+        .await?
+        .ok_or_else(|| IndexerError::NotFound(format!("Document {} not found", id)))?;
+
+    info!(
+        "Permission overrides of document {} removed by {}",
+        id, query.requested_by
+    );
+    clear_cached_responses(&state).await;
+    Ok(Json(permission_state))
+}
+
+/// Cached search responses may include documents whose visibility just changed. Failing to
+/// clear them only delays the change until the entries expire, so it isn't an error.
+pub(crate) async fn clear_cached_responses(state: &AppState) {
+    if let Err(e) = data_deletion::clear_response_caches(state).await {
+        error!("Failed to clear cached search responses: {:#}", e);
+    }
+}
+
 async fn bulk_documents(
     State(state): State<AppState>,
     Json(request): Json<BulkDocumentRequest>,
@@ -345,6 +690,7 @@ async fn process_create_operation(
         created_at: now,
         updated_at: now,
         last_indexed_at: now,
+        preview: None,
     };
 
     let repo = DocumentRepository::new(state.db_pool.pool());
@@ -488,24 +834,525 @@ async fn gc_stats(State(state): State<AppState>) -> IndexerResult<Json<OrphanSta
     Ok(Json(stats))
 }
 
+/// Mine indexed content for acronym definitions, adding new ones to the acronym dictionary
+/// for review.
+async fn mine_acronyms(
+    State(state): State<AppState>,
+    request: Option<Json<acronym_mining::MineAcronymsRequest>>,
+) -> IndexerResult<Json<acronym_mining::AcronymMiningResult>> {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    let result = acronym_mining::run(&state, request.min_documents())
+        .await
+        .map_err(|e| IndexerError::Internal(format!("Acronym mining failed: {:#}", e)))?;
+
+    Ok(Json(result))
+}
+
+/// Rebuild the people directory from connector data and queue the profiles for indexing.
+async fn aggregate_people(
+    State(state): State<AppState>,
+) -> IndexerResult<Json<people::PeopleAggregationResult>> {
+    let result = people::run(&state)
+        .await
+        .map_err(|e| IndexerError::Internal(format!("People aggregation failed: {:#}", e)))?;
+
+    Ok(Json(result))
+}
+
+/// Recompute the home-page recommendations of every user now, instead of on the next run.
+async fn compute_recommendations(
+    State(state): State<AppState>,
+) -> IndexerResult<Json<recommendations::RecommendationRunResult>> {
+    let result = recommendations::run(&state).await.map_err(|e| {
+        IndexerError::Internal(format!("Computing recommendations failed: {:#}", e))
+    })?;
+
+    Ok(Json(result))
+}
+
+/// Find stale documents now, instead of on the next scheduled analysis.
+async fn analyze_stale_content(
+    State(state): State<AppState>,
+) -> IndexerResult<Json<StaleRebuildStats>> {
+    let result = freshness::run(&state).await.map_err(|e| {
+        IndexerError::Internal(format!("Content freshness analysis failed: {:#}", e))
+    })?;
+
+    Ok(Json(result))
+}
+
+/// Stale documents found by the last analysis, per source.
+async fn stale_content(
+    State(state): State<AppState>,
+    Query(query): Query<StaleContentQuery>,
+) -> IndexerResult<Json<StaleContentReport>> {
+    let repo = StaleDocumentRepository::new(state.db_pool.pool());
+    let source_id = query.source_id.as_deref();
+    let summaries = repo.summarize(source_id).await?;
+    let mut owners: HashMap<String, Vec<StaleContentOwner>> = HashMap::new();
+    for owner in repo.find_owners(source_id).await? {
+        owners
+            .entry(owner.source_id.clone())
+            .or_default()
+            .push(owner);
+    }
+    let mut documents: HashMap<String, Vec<StaleDocument>> = HashMap::new();
+    for document in repo.find(source_id, query.limit()).await? {
+        documents
+            .entry(document.source_id.clone())
+            .or_default()
+            .push(document);
+    }
+
+    let sources = summaries
+        .into_iter()
+        .map(|summary| StaleSourceReport {
+            owners: owners.remove(&summary.source_id).unwrap_or_default(),
+            documents: documents.remove(&summary.source_id).unwrap_or_default(),
+            summary,
+        })
+        .collect();
+
+    Ok(Json(StaleContentReport { sources }))
+}
+
+/// Find duplicate content now, instead of on the next scheduled analysis.
+async fn analyze_duplicate_content(
+    State(state): State<AppState>,
+) -> IndexerResult<Json<duplicates::DuplicateRunResult>> {
+    let result = duplicates::run(&state).await.map_err(|e| {
+        IndexerError::Internal(format!("Duplicate content analysis failed: {:#}", e))
+    })?;
+
+    Ok(Json(result))
+}
+
+/// Duplicate clusters found by the last analysis, those spanning the most sources first.
+async fn duplicate_content(
+    State(state): State<AppState>,
+    Query(query): Query<DuplicateContentQuery>,
+) -> IndexerResult<Json<DuplicateContentReport>> {
+    let documents = DuplicateDocumentRepository::new(state.db_pool.pool())
+        .find_clusters(query.source_id.as_deref(), query.limit())
+        .await?;
+
+    let mut clusters: Vec<DuplicateCluster> = Vec::new();
+    for document in documents {
+        match clusters.last_mut() {
+            Some(cluster) if cluster.cluster_id == document.cluster_id => {
+                cluster.documents.push(document)
+            }
+            _ => clusters.push(DuplicateCluster {
+                cluster_id: document.cluster_id.clone(),
+                sources: 0,
+                documents: vec![document],
+            }),
+        }
+    }
+    for cluster in &mut clusters {
+        cluster
+            .documents
+            .sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+        cluster.sources = cluster
+            .documents
+            .iter()
+            .map(|document| &document.source_id)
+            .collect::<HashSet<_>>()
+            .len();
+    }
+
+    Ok(Json(DuplicateContentReport { clusters }))
+}
+
+/// Check the index's invariants and report the documents breaking them.
+async fn check_integrity(
+    State(state): State<AppState>,
+) -> IndexerResult<Json<integrity::IntegrityReport>> {
+    let report = integrity::run(&state, false)
+        .await
+        .map_err(|e| IndexerError::Internal(format!("Integrity check failed: {:#}", e)))?;
+
+    Ok(Json(report))
+}
+
+/// Check the index's invariants and repair what can be repaired.
+async fn repair_integrity(
+    State(state): State<AppState>,
+) -> IndexerResult<Json<integrity::IntegrityReport>> {
+    let report = integrity::run(&state, true)
+        .await
+        .map_err(|e| IndexerError::Internal(format!("Integrity repair failed: {:#}", e)))?;
+
+    Ok(Json(report))
+}
+
+async fn reembed(
+    State(state): State<AppState>,
+    Json(request): Json<ReembedRequest>,
+) -> IndexerResult<Json<ReembedResponse>> {
+    let queued = state
+        .embedding_queue
+        .enqueue_reembedding(request.source_id.as_deref())
+        .await
+        .map_err(|e| IndexerError::Internal(format!("Failed to queue re-embedding: {}", e)))?;
+    info!(
+        "Queued {} documents for re-embedding (source: {:?})",
+        queued, request.source_id
+    );
+
+    Ok(Json(ReembedResponse { queued }))
+}
+
+async fn get_embedding_migration(
+    State(state): State<AppState>,
+) -> IndexerResult<Json<embedding_migration::EmbeddingMigrationStatus>> {
+    let status = embedding_migration::status(&state)
+        .await
+        .map_err(|e| IndexerError::Internal(format!("{:#}", e)))?;
+    Ok(Json(status))
+}
+
+/// Start migrating to another embedding model. The AI service starts writing embeddings of
+/// the target model alongside the current ones and backfilling them for existing documents,
+/// while searches stay on the current model until cutover.
+async fn create_embedding_migration(
+    State(state): State<AppState>,
+    Json(request): Json<StartEmbeddingMigrationRequest>,
+) -> IndexerResult<(StatusCode, Json<EmbeddingMigration>)> {
+    request
+        .target
+        .validate()
+        .map_err(IndexerError::BadRequest)?;
+    let shadow_search_percentage = request.shadow_search_percentage();
+    EmbeddingMigration::validate_percentage(shadow_search_percentage)
+        .map_err(IndexerError::BadRequest)?;
+
+    let pool = state.db_pool.pool();
+    let current = load_current_config(pool)
+        .await
+        .map_err(|e| IndexerError::Internal(format!("{:#}", e)))?;
+    if current.is_some_and(|current| current.model == request.target.model) {
+        return Err(IndexerError::BadRequest(format!(
+            "{} is already the current embedding model",
+            request.target.model
+        )));
+    }
+
+    let migration = EmbeddingMigration {
+        target: request.target,
+        shadow_search_percentage,
+        cutover: false,
+        started_at: OffsetDateTime::now_utc(),
+    };
+    let started = start_migration(pool, &migration)
+        .await
+        .map_err(|e| IndexerError::Internal(format!("{:#}", e)))?;
+    if !started {
+        return Err(IndexerError::BadRequest(
+            "An embedding model migration is already in progress".to_string(),
+        ));
+    }
+    info!(
+        "Started embedding model migration to {} ({} provider)",
+        migration.target.model, migration.target.provider
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(embedding_migration::redacted(migration)),
+    ))
+}
+
+async fn update_embedding_migration(
+    State(state): State<AppState>,
+    Json(request): Json<UpdateEmbeddingMigrationRequest>,
+) -> IndexerResult<Json<EmbeddingMigration>> {
+    let pool = state.db_pool.pool();
+    let mut migration = load_migration(pool)
+        .await
+        .map_err(|e| IndexerError::Internal(format!("{:#}", e)))?
+        .ok_or_else(|| IndexerError::NotFound("Embedding model migration".to_string()))?;
+
+    if let Some(percentage) = request.shadow_search_percentage {
+        EmbeddingMigration::validate_percentage(percentage).map_err(IndexerError::BadRequest)?;
+        migration.shadow_search_percentage = percentage;
+    }
+    let cutover_changed = request
+        .cutover
+        .is_some_and(|cutover| cutover != migration.cutover);
+    if let Some(cutover) = request.cutover {
+        migration.cutover = cutover;
+    }
+
+    save_migration(pool, &migration)
+        .await
+        .map_err(|e| IndexerError::Internal(format!("{:#}", e)))?;
+    if cutover_changed {
+        info!(
+            "Embedding model migration to {} {}",
+            migration.target.model,
+            if migration.cutover {
+                "cut over"
+            } else {
+                "rolled back"
+            }
+        );
+        // Cached responses were ranked by the other model
+        clear_cached_responses(&state).await;
+    }
+
+    Ok(Json(embedding_migration::redacted(migration)))
+}
+
+/// Make the target model of a cut-over migration the current model, and delete the
+/// embeddings of the previous model.
+async fn finalize_embedding_migration(
+    State(state): State<AppState>,
+) -> IndexerResult<Json<EmbeddingMigration>> {
+    let migration = finalize_migration(state.db_pool.pool())
+        .await
+        .map_err(|e| IndexerError::Internal(format!("{:#}", e)))?
+        .ok_or_else(|| {
+            IndexerError::BadRequest(
+                "There is no embedding model migration that has been cut over".to_string(),
+            )
+        })?;
+    info!(
+        "Finalized embedding model migration, {} is now the current model",
+        migration.target.model
+    );
+
+    embedding_migration::spawn_purge(state, migration.target.model.clone(), true);
+    Ok(Json(embedding_migration::redacted(migration)))
+}
+
+/// Abandon a migration that has not been cut over, and delete the target model's embeddings.
+async fn delete_embedding_migration(
+    State(state): State<AppState>,
+) -> IndexerResult<Json<EmbeddingMigration>> {
+    let pool = state.db_pool.pool();
+    let migration = cancel_migration(pool)
+        .await
+        .map_err(|e| IndexerError::Internal(format!("{:#}", e)))?;
+    let Some(migration) = migration else {
+        return match load_migration(pool)
+            .await
+            .map_err(|e| IndexerError::Internal(format!("{:#}", e)))?
+        {
+            Some(_) => Err(IndexerError::BadRequest(
+                "The embedding model migration has been cut over, roll it back before cancelling"
+                    .to_string(),
+            )),
+            None => Err(IndexerError::NotFound(
+                "Embedding model migration".to_string(),
+            )),
+        };
+    };
+    info!(
+        "Cancelled embedding model migration to {}",
+        migration.target.model
+    );
+
+    embedding_migration::spawn_purge(state, migration.target.model.clone(), false);
+    Ok(Json(embedding_migration::redacted(migration)))
+}
+
+async fn indexing_lag(
+    State(state): State<AppState>,
+    Query(query): Query<IndexingLagQuery>,
+) -> IndexerResult<Json<IndexingLagResponse>> {
+    let window_hours = query.hours();
+    let since =
+        OffsetDateTime::now_utc() - std::time::Duration::from_secs(window_hours as u64 * 3600);
+
+    let repo = DocumentRepository::new(state.db_pool.pool());
+    let sources = repo.indexing_lag_by_source(since).await?;
+
+    Ok(Json(IndexingLagResponse {
+        window_hours,
+        sources,
+    }))
+}
+
+/// Attribute usage across indexed documents, for deciding which attributes to promote to
+/// real columns.
+async fn attribute_cardinality(
+    State(state): State<AppState>,
+    Query(query): Query<AttributeCardinalityQuery>,
+) -> IndexerResult<Json<AttributeCardinalityResponse>> {
+    let repo = DocumentRepository::new(state.db_pool.pool());
+    let source_id = query.source_id.as_deref();
+
+    Ok(Json(AttributeCardinalityResponse {
+        total_documents: repo.count(source_id).await?,
+        attributes: repo.attribute_cardinality(source_id).await?,
+    }))
+}
+
+async fn create_data_deletion(
+    State(state): State<AppState>,
+    Json(request): Json<CreateDataDeletionRequest>,
+) -> IndexerResult<(StatusCode, Json<DataDeletionRequest>)> {
+    let email = request.email.trim();
+    if email.is_empty() || !email.contains('@') {
+        return Err(IndexerError::BadRequest(
+            "A valid email address is required".to_string(),
+        ));
+    }
+
+    let repo = DataDeletionRepository::new(state.db_pool.pool());
+    let deletion = repo.create(email).await?;
+    info!("Queued data deletion request {}", deletion.id);
+
+    data_deletion::spawn(state, deletion.clone());
+
+    Ok((StatusCode::ACCEPTED, Json(deletion)))
+}
+
+async fn get_data_deletion(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> IndexerResult<Json<DataDeletionRequest>> {
+    let repo = DataDeletionRepository::new(state.db_pool.pool());
+    let deletion = repo
+        .find_by_id(&id)
+        .await?
+        .ok_or_else(|| IndexerError::NotFound(format!("Data deletion request {}", id)))?;
+
+    Ok(Json(deletion))
+}
+
+async fn create_snapshot(
+    State(state): State<AppState>,
+    request: Option<Json<CreateSnapshotRequest>>,
+) -> IndexerResult<(StatusCode, Json<IndexSnapshot>)> {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    if let Some(source_id) = &request.source_id {
+        SourceRepository::new(state.db_pool.pool())
+            .find_by_id(source_id.clone())
+            .await?
+            .ok_or_else(|| IndexerError::NotFound(format!("Source {}", source_id)))?;
+    }
+
+    let repo = IndexSnapshotRepository::new(state.db_pool.pool());
+    let index_snapshot = repo
+        .create_snapshot(request.source_id.as_deref(), request.created_by.as_deref())
+        .await?;
+    info!(
+        "Queued index snapshot {} (source: {:?})",
+        index_snapshot.id, index_snapshot.source_id
+    );
+
+    snapshot::spawn(state, index_snapshot.clone());
+
+    Ok((StatusCode::ACCEPTED, Json(index_snapshot)))
+}
+
+async fn list_snapshots(
+    State(state): State<AppState>,
+    Query(query): Query<SnapshotListQuery>,
+) -> IndexerResult<Json<Vec<IndexSnapshot>>> {
+    let repo = IndexSnapshotRepository::new(state.db_pool.pool());
+    Ok(Json(repo.list(query.limit()).await?))
+}
+
+async fn get_snapshot(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> IndexerResult<Json<IndexSnapshot>> {
+    let repo = IndexSnapshotRepository::new(state.db_pool.pool());
+    let index_snapshot = repo
+        .find_by_id(&id)
+        .await?
+        .ok_or_else(|| IndexerError::NotFound(format!("Index snapshot {}", id)))?;
+
+    Ok(Json(index_snapshot))
+}
+
+/// Restore a snapshot by id. The snapshot only needs to exist in object storage, so one
+/// taken by another deployment sharing the bucket can be restored here.
+async fn restore_snapshot(
+    State(state): State<AppState>,
+    Path(snapshot_id): Path<String>,
+    request: Option<Json<RestoreSnapshotRequest>>,
+) -> IndexerResult<(StatusCode, Json<IndexSnapshot>)> {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    let manifest = snapshot::read_manifest(state.content_storage.as_ref(), &snapshot_id)
+        .await
+        .map_err(|e| IndexerError::Internal(format!("{:#}", e)))?
+        .ok_or_else(|| IndexerError::NotFound(format!("Snapshot {}", snapshot_id)))?;
+    if manifest.version > snapshot::SNAPSHOT_FORMAT_VERSION {
+        return Err(IndexerError::BadRequest(format!(
+            "Snapshot {} has layout version {}, this build restores up to {}",
+            snapshot_id,
+            manifest.version,
+            snapshot::SNAPSHOT_FORMAT_VERSION
+        )));
+    }
+    if let Some(source_id) = &request.source_id {
+        if !manifest.sources.iter().any(|s| &s.id == source_id) {
+            return Err(IndexerError::BadRequest(format!(
+                "Source {} is not in snapshot {}",
+                source_id, snapshot_id
+            )));
+        }
+    }
+
+    let repo = IndexSnapshotRepository::new(state.db_pool.pool());
+    let restore = repo
+        .create_restore(
+            &snapshot_id,
+            request.source_id.as_deref(),
+            request.created_by.as_deref(),
+        )
+        .await?;
+    info!(
+        "Queued restore {} of index snapshot {} (source: {:?})",
+        restore.id, snapshot_id, restore.source_id
+    );
+
+    snapshot::spawn(state, restore.clone());
+
+    Ok((StatusCode::ACCEPTED, Json(restore)))
+}
+
 pub async fn run_server() -> anyhow::Result<()> {
     dotenvy::dotenv().ok();
 
     let telemetry_config = TelemetryConfig::from_env("omni-indexer");
     telemetry::init_telemetry(telemetry_config)?;
+    shared::secrets::init_from_env().await?;
 
     info!("Indexer service starting...");
 
     let config = IndexerConfig::from_env();
 
-    let db_pool = DatabasePool::from_config(&config.database)
+    // Listen while connecting, so probes can tell starting up from unhealthy
+    let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
+    info!("Indexer service listening on {}", addr);
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    let shutdown = Shutdown::on_signal();
+    let startup = Startup::default();
+    let mut server = startup.serve(listener, shutdown.clone());
+
+    let db_pool = connect_database(&config.database)
         .await
         .map_err(|e| anyhow::anyhow!("Failed to create database pool: {}", e))?;
+    // Migrations are applied by the migrator container, refuse to run against any other schema
+    shared::db::migrations::ensure_schema_current(db_pool.pool(), &MIGRATOR, "omni-indexer")
+        .await?;
+    tokio::spawn(shared::db::metrics::report_pool_metrics(
+        db_pool.pool().clone(),
+        config.database.clone(),
+    ));
+    if let Err(e) = shared::feature_flags::init(db_pool.pool()).await {
+        error!(
+            "Failed to load feature flags, using the environment's: {}",
+            e
+        );
+    }
 
-    // Migrations are now handled by a separate migrator container
-    info!("Database migrations handled by migrator container");
-
-    let redis_client = RedisClient::open(config.redis.redis_url)?;
+    let redis_client = connect_redis(&config.redis.redis_url).await?;
     info!("Redis client initialized");
 
     let ai_client = AIClient::new(config.ai_service_url.clone());
@@ -529,31 +1376,86 @@ pub async fn run_server() -> anyhow::Result<()> {
         service_credentials_repo,
     };
 
-    let app = create_app(app_state.clone());
+    let app = shared::ip_allowlist::restrict_admin_routes(create_app(app_state.clone()));
 
-    let queue_processor = queue_processor::QueueProcessor::new(app_state.clone());
-    let processor_handle = tokio::spawn(async move {
+    if let Err(e) = data_deletion::resume_unfinished(&app_state).await {
+        error!("Failed to resume unfinished data deletion requests: {}", e);
+    }
+    if let Err(e) = snapshot::resume_unfinished(&app_state).await {
+        error!("Failed to resume unfinished index snapshots: {}", e);
+    }
+
+    let queue_processor =
+        queue_processor::QueueProcessor::new(app_state.clone()).with_shutdown(shutdown.clone());
+    let mut processor_handle = tokio::spawn(async move {
         if let Err(e) = queue_processor.start().await {
             error!("Queue processor failed: {}", e);
         }
     });
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
-    info!("Indexer service listening on {}", addr);
+    tokio::spawn(metrics::report_indexing_lag(
+        app_state.db_pool.pool().clone(),
+    ));
 
-    let listener = tokio::net::TcpListener::bind(addr).await?;
+    if let Some(interval_seconds) = config.recommendation_interval_seconds {
+        tokio::spawn(recommendations::schedule(
+            app_state.clone(),
+            std::time::Duration::from_secs(interval_seconds),
+        ));
+    }
+
+    if let Some(interval_seconds) = config.duplicate_content_interval_seconds {
+        tokio::spawn(duplicates::schedule(
+            app_state.clone(),
+            std::time::Duration::from_secs(interval_seconds),
+        ));
+    }
+
+    if let Some(interval_seconds) = config.stale_content_interval_seconds {
+        tokio::spawn(freshness::schedule(
+            app_state.clone(),
+            std::time::Duration::from_secs(interval_seconds),
+        ));
+    }
+
+    if let (Some(interval_seconds), Some(smtp)) = (config.digest_interval_seconds, &config.smtp) {
+        match shared::EmailNotifier::new(smtp) {
+            Ok(notifier) => {
+                tokio::spawn(digests::schedule(
+                    app_state.clone(),
+                    notifier,
+                    std::time::Duration::from_secs(interval_seconds),
+                ));
+            }
+            Err(e) => error!("Digests disabled: {}", e),
+        }
+    }
+
+    let dependencies = Dependencies::new(app_state.db_pool.pool().clone())
+        .with_redis(app_state.redis_client.clone());
+    startup.ready(app, dependencies);
 
     tokio::select! {
-        result = axum::serve(listener, app) => {
-            if let Err(e) = result {
-                error!("HTTP server failed: {}", e);
+        result = &mut server => {
+            match result {
+                Ok(Err(e)) => error!("HTTP server failed: {}", e),
+                Err(e) => error!("HTTP server task failed: {}", e),
+                Ok(Ok(())) => {}
             }
         }
-        _ = processor_handle => {
+        _ = &mut processor_handle => {
             error!("Event processor task completed unexpectedly");
+            return Ok(());
         }
     }
 
+    // Let the queue processor finish the event batch it is processing
+    shutdown.request();
+    if let Err(e) = processor_handle.await {
+        error!("Event processor task failed: {}", e);
+    }
+    info!("Indexer service stopped");
+
     Ok(())
 }
 
@@ -0,0 +1,99 @@
+//! Extraction of links to other documents from document content.
+//!
+//! Content links to Confluence pages (`.../wiki/spaces/ENG/pages/12345/Title` or
+//! `viewpage.action?pageId=12345`), Jira issues (`.../browse/ENG-42` or just `ENG-42`) and
+//! Google Drive files (`docs.google.com/document/d/<id>/edit`, `drive.google.com/open?id=<id>`).
+//! Only the key of each target is extracted; the index resolves keys to documents, so
+//! anything that looks like an issue key but names no indexed issue is left unresolved.
+
+use shared::db::repositories::{LinkKind, LinkReference};
+use std::collections::HashSet;
+
+/// Most links kept per document, so link lists of generated pages stay bounded.
+const MAX_LINKS: usize = 500;
+const TOKEN_DELIMITERS: &[char] = &['(', ')', '<', '>', '[', ']', '{', '}', '"', '\'', '|', ','];
+const DRIVE_HOSTS: [&str; 2] = ["docs.google.com/", "drive.google.com/"];
+const MIN_DRIVE_ID_LEN: usize = 10;
+
+/// The distinct links in a document's content, in order of first appearance.
+pub fn extract(content: &str) -> Vec<LinkReference> {
+    let mut seen = HashSet::new();
+    let mut links = Vec::new();
+    for token in content.split(|c: char| c.is_whitespace() || TOKEN_DELIMITERS.contains(&c)) {
+        let token = token.trim_end_matches(['.', ':', ';', '!', '?']);
+        let link = if token.contains("://") {
+            url_link(token)
+        } else {
+            issue_key(token).map(|key| LinkReference {
+                kind: LinkKind::JiraIssue,
+                target_key: key.to_string(),
+            })
+        };
+        if let Some(link) = link {
+            if seen.insert(link.clone()) {
+                links.push(link);
+                if links.len() == MAX_LINKS {
+                    break;
+                }
+            }
+        }
+    }
+    links
+}
+
+fn url_link(url: &str) -> Option<LinkReference> {
+    if DRIVE_HOSTS.iter().any(|host| url.contains(host)) {
+        let id = path_segment_after(url, "/d/").or_else(|| query_param(url, "id"))?;
+        let valid = id.len() >= MIN_DRIVE_ID_LEN
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        return valid.then(|| LinkReference {
+            kind: LinkKind::DriveFile,
+            target_key: id.to_string(),
+        });
+    }
+    if url.contains("/wiki/") || url.contains("/confluence/") {
+        let page_id = path_segment_after(url, "/pages/").or_else(|| query_param(url, "pageId"))?;
+        let valid = !page_id.is_empty() && page_id.chars().all(|c| c.is_ascii_digit());
+        return valid.then(|| LinkReference {
+            kind: LinkKind::ConfluencePage,
+            target_key: page_id.to_string(),
+        });
+    }
+    let key = issue_key(path_segment_after(url, "/browse/")?)?;
+    Some(LinkReference {
+        kind: LinkKind::JiraIssue,
+        target_key: key.to_string(),
+    })
+}
+
+/// The path segment following `marker` in a URL.
+fn path_segment_after<'a>(url: &'a str, marker: &str) -> Option<&'a str> {
+    let rest = &url[url.find(marker)? + marker.len()..];
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    Some(&rest[..end])
+}
+
+fn query_param<'a>(url: &'a str, name: &str) -> Option<&'a str> {
+    let query = &url[url.find('?')? + 1..];
+    let query = &query[..query.find('#').unwrap_or(query.len())];
+    query.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        (key == name).then_some(value)
+    })
+}
+
+/// A Jira issue key, e.g. `ENG-42`. Project keys start with a capital letter followed by
+/// capitals, digits or underscores.
+fn issue_key(token: &str) -> Option<&str> {
+    let (project, number) = token.split_once('-')?;
+    let mut project_chars = project.chars();
+    let valid_project = project.len() >= 2
+        && project_chars.next()?.is_ascii_uppercase()
+        && project_chars.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+    let valid_number = !number.is_empty()
+        && !number.starts_with('0')
+        && number.chars().all(|c| c.is_ascii_digit());
+    (valid_project && valid_number).then_some(token)
+}
@@ -0,0 +1,77 @@
+use opentelemetry::{global, metrics::Gauge, KeyValue};
+use shared::db::repositories::{DocumentRepository, SourceIndexingLag};
+use sqlx::types::time::OffsetDateTime;
+use sqlx::PgPool;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Rolling window over which the exported indexing lag percentiles are computed.
+pub const INDEXING_LAG_METRIC_WINDOW: Duration = Duration::from_secs(60 * 60);
+const INDEXING_LAG_REPORT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Per-source indexing lag gauges, exported through the global OTel meter provider.
+///
+/// Lag percentiles are computed in Postgres from `documents.source_updated_at` and
+/// `documents.source_indexed_at`, so every indexer replica reports the same values
+/// regardless of which replica processed a given event.
+struct IndexingLagMetrics {
+    p50: Gauge<f64>,
+    p95: Gauge<f64>,
+    documents: Gauge<u64>,
+}
+
+impl IndexingLagMetrics {
+    fn new() -> Self {
+        let meter = global::meter("omni-indexer");
+        Self {
+            p50: meter
+                .f64_gauge("omni.indexer.indexing_lag.p50")
+                .with_unit("s")
+                .with_description("Median time from a source change to the document being indexed")
+                .build(),
+            p95: meter
+                .f64_gauge("omni.indexer.indexing_lag.p95")
+                .with_unit("s")
+                .with_description(
+                    "95th percentile time from a source change to the document being indexed",
+                )
+                .build(),
+            documents: meter
+                .u64_gauge("omni.indexer.indexing_lag.documents")
+                .with_description("Documents indexed within the indexing lag window")
+                .build(),
+        }
+    }
+
+    fn record(&self, lag: &SourceIndexingLag) {
+        let attributes = [
+            KeyValue::new("source_id", lag.source_id.clone()),
+            KeyValue::new("source_type", lag.source_type.clone()),
+        ];
+        self.p50.record(lag.p50_seconds, &attributes);
+        self.p95.record(lag.p95_seconds, &attributes);
+        self.documents.record(lag.documents as u64, &attributes);
+    }
+}
+
+/// Periodically recompute indexing lag per source and publish it as gauges.
+pub async fn report_indexing_lag(pool: PgPool) {
+    let metrics = IndexingLagMetrics::new();
+    let repo = DocumentRepository::new(&pool);
+    let mut interval = tokio::time::interval(INDEXING_LAG_REPORT_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let since = OffsetDateTime::now_utc() - INDEXING_LAG_METRIC_WINDOW;
+        match repo.indexing_lag_by_source(since).await {
+            Ok(lags) => {
+                debug!("Reporting indexing lag for {} sources", lags.len());
+                for lag in &lags {
+                    metrics.record(lag);
+                }
+            }
+            Err(e) => warn!("Failed to compute indexing lag: {}", e),
+        }
+    }
+}
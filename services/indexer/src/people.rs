@@ -0,0 +1,151 @@
+//! Aggregation of the people directory from connector data.
+//!
+//! Everyone seen as the author of a document, an attendee of a recorded meeting or a
+//! participant in an email thread gets a person profile. The job rebuilds each person's
+//! activity from the index, then indexes one `people` document per person under a system
+//! source, so "who is Jane" style searches find the profile like any other document. Profiles
+//! are visible to everyone, so their content only carries a person's name and activity
+//! counts; the documents and collaborators behind them are served by the searcher, filtered
+//! to what the asking user may see.
+
+use crate::AppState;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use shared::db::repositories::{DocumentRepository, Person, PersonRepository, SyncRunRepository};
+use shared::models::{ConnectorEvent, DocumentMetadata, DocumentPermissions, SourceType, SyncType};
+use shared::queue::EventQueue;
+use std::collections::HashSet;
+use tracing::info;
+
+/// Content type profiles are indexed with, for filtering searches to people.
+pub const PEOPLE_CONTENT_TYPE: &str = "people";
+/// Sources whose documents record meetings; everyone on them attended.
+const MEETING_SOURCE_TYPES: [SourceType; 2] = [SourceType::Fireflies, SourceType::OutlookCalendar];
+/// Sources whose documents are email threads; everyone on them took part.
+const EMAIL_SOURCE_TYPES: [SourceType; 2] = [SourceType::Gmail, SourceType::Outlook];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeopleAggregationResult {
+    pub source_id: String,
+    pub sync_run_id: String,
+    pub people: u64,
+    pub activities: u64,
+    /// Profiles of people no longer seen in any document, removed from the index.
+    pub removed: usize,
+}
+
+/// Rebuild the people directory and queue its profiles for indexing.
+pub async fn run(state: &AppState) -> Result<PeopleAggregationResult> {
+    let pool = state.db_pool.pool();
+    let person_repo = PersonRepository::new(pool);
+    let sync_run_repo = SyncRunRepository::new(pool);
+
+    let stats = person_repo
+        .rebuild(&MEETING_SOURCE_TYPES, &EMAIL_SOURCE_TYPES)
+        .await?;
+    let source_id = person_repo.ensure_source().await?;
+    let sync_run = sync_run_repo
+        .create(&source_id, SyncType::Full, "manual")
+        .await?;
+
+    match index_profiles(state, &person_repo, &source_id, &sync_run.id).await {
+        Ok((indexed, removed)) => {
+            sync_run_repo
+                .mark_completed(&sync_run.id, indexed as i32, (indexed + removed) as i32)
+                .await?;
+            info!(
+                "People aggregation found {} people with {} activities, removed {} profiles",
+                stats.people, stats.activities, removed
+            );
+            Ok(PeopleAggregationResult {
+                source_id,
+                sync_run_id: sync_run.id,
+                people: stats.people,
+                activities: stats.activities,
+                removed,
+            })
+        }
+        Err(e) => {
+            sync_run_repo
+                .mark_failed(&sync_run.id, &format!("{:#}", e))
+                .await?;
+            Err(e)
+        }
+    }
+}
+
+/// Queue a profile document for every person and deletions for profiles of people that are
+/// gone. Returns how many of each were queued.
+async fn index_profiles(
+    state: &AppState,
+    person_repo: &PersonRepository,
+    source_id: &str,
+    sync_run_id: &str,
+) -> Result<(usize, usize)> {
+    let pool = state.db_pool.pool();
+    let queue = EventQueue::new(pool.clone());
+    let people = person_repo.find_all().await?;
+
+    for person in &people {
+        let content_id = state
+            .content_storage
+            .store_text(&profile_content(person), None)
+            .await?;
+        let event = ConnectorEvent::DocumentCreated {
+            sync_run_id: sync_run_id.to_string(),
+            source_id: source_id.to_string(),
+            document_id: person.email.clone(),
+            content_id,
+            metadata: DocumentMetadata {
+                title: Some(person.name.clone().unwrap_or_else(|| person.email.clone())),
+                updated_at: person.last_active_at,
+                mime_type: Some(PEOPLE_CONTENT_TYPE.to_string()),
+                ..Default::default()
+            },
+            permissions: DocumentPermissions {
+                public: true,
+                users: vec![],
+                groups: vec![],
+            },
+            attributes: None,
+        };
+        queue.enqueue(source_id, &event).await?;
+    }
+
+    let current: HashSet<&str> = people.iter().map(|person| person.email.as_str()).collect();
+    let stale: Vec<String> = DocumentRepository::new(pool)
+        .find_by_source(source_id)
+        .await?
+        .into_iter()
+        .map(|document| document.external_id)
+        .filter(|email| !current.contains(email.as_str()))
+        .collect();
+    for email in &stale {
+        let event = ConnectorEvent::DocumentDeleted {
+            sync_run_id: sync_run_id.to_string(),
+            source_id: source_id.to_string(),
+            document_id: email.clone(),
+        };
+        queue.enqueue(source_id, &event).await?;
+    }
+
+    Ok((people.len(), stale.len()))
+}
+
+/// The indexed text of a person's profile.
+fn profile_content(person: &Person) -> String {
+    let mut content = match &person.name {
+        Some(name) => format!("{} <{}>\n", name, person.email),
+        None => format!("{}\n", person.email),
+    };
+    for (label, count) in [
+        ("Documents authored", person.authored_count),
+        ("Meetings attended", person.meeting_count),
+        ("Email threads", person.email_count),
+    ] {
+        if count > 0 {
+            content.push_str(&format!("{}: {}\n", label, count));
+        }
+    }
+    content
+}
@@ -0,0 +1,164 @@
+//! Preview images of documents, shown with search results.
+//!
+//! Connectors that can get a thumbnail of a document from its source store the image with the
+//! SDK and reference it in the document metadata, and it becomes the document's preview as is.
+//! Google Drive and Confluence documents without one get a render of their first page instead:
+//! the title above the start of the text, as an SVG image in the content storage.
+
+use crate::AppState;
+use anyhow::Result;
+use shared::db::repositories::{DocumentPreviewRepository, DocumentRepository};
+use shared::models::{Document, DocumentThumbnail, PreviewKind, SourceType};
+
+const RENDER_SOURCE_TYPES: [SourceType; 2] = [SourceType::GoogleDrive, SourceType::Confluence];
+const RENDER_MIME_TYPE: &str = "image/svg+xml";
+// US letter proportions
+const RENDER_WIDTH: usize = 400;
+const RENDER_HEIGHT: usize = 518;
+const MARGIN: usize = 24;
+const TITLE_CHARS: usize = 34;
+const LINE_CHARS: usize = 60;
+const LINE_HEIGHT: usize = 16;
+const MAX_LINES: usize = 28;
+/// Enough of the content to fill the page.
+const EXCERPT_CHARS: usize = LINE_CHARS * MAX_LINES;
+
+/// What a document's preview is made from, taken before the document is written.
+pub struct PreviewInput {
+    thumbnail: Option<DocumentThumbnail>,
+    title: String,
+    excerpt: String,
+}
+
+impl PreviewInput {
+    pub fn new(document: &Document, content: &str) -> Self {
+        let thumbnail = document
+            .metadata
+            .get("thumbnail")
+            .and_then(|thumbnail| serde_json::from_value(thumbnail.clone()).ok());
+        Self {
+            thumbnail,
+            title: document.title.clone(),
+            excerpt: content.chars().take(EXCERPT_CHARS).collect(),
+        }
+    }
+}
+
+/// Set the previews of documents just written to the index. Returns how many were set.
+pub async fn store(state: &AppState, documents: Vec<(&Document, PreviewInput)>) -> Result<usize> {
+    if documents.is_empty() {
+        return Ok(0);
+    }
+
+    let pool = state.db_pool.pool();
+    let render_source_ids = DocumentRepository::new(pool)
+        .fetch_active_source_ids(Some(&RENDER_SOURCE_TYPES[..]))
+        .await?;
+    let preview_repo = DocumentPreviewRepository::new(pool);
+
+    let mut stored = 0;
+    for (document, input) in documents {
+        let (content_id, kind, mime_type) = match input.thumbnail {
+            Some(thumbnail) => (
+                thumbnail.content_id,
+                PreviewKind::Thumbnail,
+                thumbnail.mime_type,
+            ),
+            None if render_source_ids.contains(&document.source_id) => {
+                let svg = render(&input.title, &input.excerpt);
+                let content_id = state
+                    .content_storage
+                    .store_content_with_type(svg.as_bytes(), Some(RENDER_MIME_TYPE), None)
+                    .await?;
+                (
+                    content_id,
+                    PreviewKind::Render,
+                    RENDER_MIME_TYPE.to_string(),
+                )
+            }
+            None => continue,
+        };
+        preview_repo
+            .upsert(&document.id, &content_id, kind, &mime_type)
+            .await?;
+        stored += 1;
+    }
+    Ok(stored)
+}
+
+/// An SVG image of the first page of a document.
+fn render(title: &str, text: &str) -> String {
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}"><rect width="{w}" height="{h}" fill="#ffffff"/>"##,
+        w = RENDER_WIDTH,
+        h = RENDER_HEIGHT
+    );
+    let mut y = MARGIN + 18;
+    svg.push_str(&format!(
+        r##"<text x="{}" y="{}" font-family="sans-serif" font-size="18" font-weight="bold" fill="#111827">{}</text>"##,
+        MARGIN,
+        y,
+        escape(&truncate(title, TITLE_CHARS))
+    ));
+    y += 12;
+    for line in wrap(text, LINE_CHARS).iter().take(MAX_LINES) {
+        y += LINE_HEIGHT;
+        svg.push_str(&format!(
+            r##"<text x="{}" y="{}" font-family="sans-serif" font-size="11" fill="#4b5563">{}</text>"##,
+            MARGIN,
+            y,
+            escape(line)
+        ));
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let truncated: String = text.chars().take(max_chars - 1).collect();
+    format!("{}…", truncated.trim_end())
+}
+
+/// The non-empty lines of a text, without markdown heading markers, word wrapped to `width`
+/// characters.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let paragraph = paragraph.trim().trim_start_matches('#').trim();
+        if paragraph.is_empty() {
+            continue;
+        }
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let word = truncate(word, width);
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Escape text for XML, dropping the control characters XML does not allow.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -1,16 +1,27 @@
-use crate::AppState;
+use crate::previews::{self, PreviewInput};
+use crate::{anchors, clear_cached_responses, event_schema, links, AppState};
 use anyhow::{Context, Result};
 use futures::future::join_all;
-use shared::db::repositories::{DocumentRepository, EmbeddingRepository, SyncRunRepository};
+use shared::db::repositories::{
+    DocumentAnchor, DocumentAnchorRepository, DocumentDelivery, DocumentLinkRepository,
+    DocumentRelationshipRepository, DocumentRepository, EmbeddingRepository,
+    IngestionKeyRepository, LinkReference, SourceRepository, SyncRunRepository, WebhookEvent,
+    WebhookRepository,
+};
 use shared::embedding_queue::EmbeddingQueue;
+use shared::index_version;
 use shared::models::{
     ConnectorEvent, ConnectorEventQueueItem, Document, DocumentAttributes, DocumentMetadata,
-    DocumentPermissions,
+    DocumentParent, DocumentPermissions,
 };
 use shared::queue::EventQueue;
+use shared::shutdown::Shutdown;
 use shared::storage::gc::{ContentBlobGC, GCConfig};
 use sqlx::postgres::PgListener;
+use std::collections::HashMap;
 use std::sync::Arc;
+use time::format_description::well_known::Iso8601;
+use time::OffsetDateTime;
 use tokio::sync::{Mutex, Semaphore};
 use tokio::time::{interval, Duration, Instant};
 use tracing::{debug, error, info, warn};
@@ -27,6 +38,19 @@ struct EventBatch {
     documents_created: Vec<(Document, Vec<String>)>, // (document, event_ids)
     documents_updated: Vec<(Document, Vec<String>)>, // (document, event_ids)
     documents_deleted: Vec<(String, String, Vec<String>)>, // (source_id, document_id, event_ids)
+    permissions_updated: Vec<(String, String, DocumentPermissions, Vec<String>)>, // (source_id, document_id, permissions, event_ids)
+}
+
+/// Update events for one document, merged in queue order before its existing row is fetched.
+#[derive(Debug)]
+struct PendingUpdate {
+    source_id: String,
+    document_id: String,
+    content_id: String,
+    metadata: DocumentMetadata,
+    permissions: Option<DocumentPermissions>,
+    attributes: Option<DocumentAttributes>,
+    event_ids: Vec<String>,
 }
 
 impl EventBatch {
@@ -36,6 +60,7 @@ impl EventBatch {
             documents_created: Vec::new(),
             documents_updated: Vec::new(),
             documents_deleted: Vec::new(),
+            permissions_updated: Vec::new(),
         }
     }
 
@@ -43,11 +68,15 @@ impl EventBatch {
         self.documents_created.is_empty()
             && self.documents_updated.is_empty()
             && self.documents_deleted.is_empty()
+            && self.permissions_updated.is_empty()
     }
 
     #[allow(dead_code)]
     fn total_documents(&self) -> usize {
-        self.documents_created.len() + self.documents_updated.len() + self.documents_deleted.len()
+        self.documents_created.len()
+            + self.documents_updated.len()
+            + self.documents_deleted.len()
+            + self.permissions_updated.len()
     }
 
     #[allow(dead_code)]
@@ -66,6 +95,11 @@ impl EventBatch {
                 .iter()
                 .map(|(_, _, event_ids)| event_ids.len())
                 .sum::<usize>()
+            + self
+                .permissions_updated
+                .iter()
+                .map(|(_, _, _, event_ids)| event_ids.len())
+                .sum::<usize>()
     }
 }
 
@@ -99,6 +133,7 @@ pub struct QueueProcessor {
     idle_timeout: Duration,
     max_accumulation_wait: Duration,
     batch_check_interval: Duration,
+    shutdown: Shutdown,
 }
 
 impl QueueProcessor {
@@ -121,6 +156,7 @@ impl QueueProcessor {
             idle_timeout: IDLE_TIMEOUT,
             max_accumulation_wait: MAX_ACCUMULATION_WAIT,
             batch_check_interval: BATCH_CHECK_INTERVAL,
+            shutdown: Shutdown::default(),
         }
     }
 
@@ -147,6 +183,12 @@ impl QueueProcessor {
         self
     }
 
+    /// Stop processing on `shutdown`, after the event batch in progress.
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
     pub async fn start(&self) -> Result<()> {
         info!(
             "Starting queue processor with batch size: {}, parallelism: {}",
@@ -209,6 +251,10 @@ impl QueueProcessor {
 
         loop {
             tokio::select! {
+                _ = self.shutdown.requested() => {
+                    info!("Queue processor stopped");
+                    return Ok(());
+                }
                 notification = listener.recv() => {
                     match notification {
                         Ok(_) => {
@@ -378,6 +424,16 @@ impl QueueProcessor {
         let mut total_processed = 0;
 
         loop {
+            // Events of the batches processed so far are marked done, so the rest are left
+            // pending for the next start
+            if self.shutdown.is_requested() {
+                info!(
+                    "Shutting down, stopped processing events after {}",
+                    total_processed
+                );
+                return Ok(());
+            }
+
             let events = self.event_queue.dequeue_batch(self.batch_size).await?;
 
             if events.is_empty() {
@@ -479,6 +535,9 @@ impl QueueProcessor {
 
                     let processed_count = batch_result.successful_event_ids.len();
                     total_processed += processed_count;
+                    if processed_count > 0 {
+                        self.index_changed().await;
+                    }
 
                     let batch_duration = batch_start_time.elapsed();
                     info!(
@@ -500,6 +559,9 @@ impl QueueProcessor {
                     match fallback_result {
                         Ok(processed_count) => {
                             total_processed += processed_count;
+                            if processed_count > 0 {
+                                self.index_changed().await;
+                            }
                             info!(
                                 "Fallback processing completed successfully: {} events",
                                 processed_count
@@ -514,6 +576,13 @@ impl QueueProcessor {
         }
     }
 
+    /// Invalidate caches of values computed from the index, such as the searcher's facets.
+    async fn index_changed(&self) {
+        if let Err(e) = index_version::bump(&self.state.redis_client).await {
+            warn!("Failed to bump the index version: {}", e);
+        }
+    }
+
     async fn group_events_by_type(
         &self,
         sync_run_id: String,
@@ -524,16 +593,20 @@ impl QueueProcessor {
         // Temporary storage for grouping events by document key
         let mut created_docs: std::collections::HashMap<String, (Document, Vec<String>)> =
             std::collections::HashMap::new();
-        let mut updated_docs: std::collections::HashMap<String, (Document, Vec<String>)> =
+        let mut updated_docs: std::collections::HashMap<String, PendingUpdate> =
             std::collections::HashMap::new();
         let mut deleted_docs: std::collections::HashMap<String, (String, String, Vec<String>)> =
             std::collections::HashMap::new();
+        let mut permission_updates: std::collections::HashMap<
+            String,
+            (String, String, DocumentPermissions, Vec<String>),
+        > = std::collections::HashMap::new();
 
         for event_item in events {
             let event_id = event_item.id.clone();
 
-            // Parse the event payload
-            let event: ConnectorEvent = serde_json::from_value(event_item.payload.clone())?;
+            // Parse the event payload, written under this or an older schema
+            let event = event_schema::decode(&event_item.payload)?;
 
             match event {
                 ConnectorEvent::DocumentCreated {
@@ -574,27 +647,29 @@ impl QueueProcessor {
                     attributes,
                     ..
                 } => {
-                    let document = self
-                        .create_document_from_event_update(
-                            source_id.clone(),
-                            document_id.clone(),
-                            content_id,
-                            metadata,
-                            permissions,
-                            attributes,
-                        )
-                        .await?;
-                    if let Some(doc) = document {
-                        // Use source_id + external_id as deduplication key
-                        let key = format!("{}:{}", source_id, document_id);
-
-                        if let Some((_, event_ids)) = updated_docs.get_mut(&key) {
-                            // Already have this document, just add the event_id
-                            event_ids.push(event_id);
-                        } else {
-                            // New document, create new entry
-                            updated_docs.insert(key, (doc, vec![event_id]));
-                        }
+                    // Use source_id + external_id as deduplication key
+                    let key = format!("{}:{}", source_id, document_id);
+
+                    if let Some(update) = updated_docs.get_mut(&key) {
+                        // Events are in queue order, so the last one holds the current version
+                        update.content_id = content_id;
+                        update.metadata = metadata;
+                        update.permissions = permissions.or(update.permissions.take());
+                        update.attributes = attributes.or(update.attributes.take());
+                        update.event_ids.push(event_id);
+                    } else {
+                        updated_docs.insert(
+                            key,
+                            PendingUpdate {
+                                source_id,
+                                document_id,
+                                content_id,
+                                metadata,
+                                permissions,
+                                attributes,
+                                event_ids: vec![event_id],
+                            },
+                        );
                     }
                 }
                 ConnectorEvent::DocumentDeleted {
@@ -613,20 +688,52 @@ impl QueueProcessor {
                         deleted_docs.insert(key, (source_id, document_id, vec![event_id]));
                     }
                 }
+                ConnectorEvent::PermissionsUpdated {
+                    source_id,
+                    document_id,
+                    permissions,
+                    ..
+                } => {
+                    // Use source_id + external_id as deduplication key
+                    let key = format!("{}:{}", source_id, document_id);
+
+                    if let Some((_, _, latest_permissions, event_ids)) =
+                        permission_updates.get_mut(&key)
+                    {
+                        // Events are in queue order, so the last one holds the current sharing
+                        *latest_permissions = permissions;
+                        event_ids.push(event_id);
+                    } else {
+                        permission_updates
+                            .insert(key, (source_id, document_id, permissions, vec![event_id]));
+                    }
+                }
             }
         }
 
         // Convert the HashMap results to Vec format for EventBatch
         batch.documents_created = created_docs.into_values().collect();
-        batch.documents_updated = updated_docs.into_values().collect();
+        batch.documents_updated = self
+            .resolve_document_updates(updated_docs.into_values().collect())
+            .await?;
         batch.documents_deleted = deleted_docs.into_values().collect();
+        batch.permissions_updated = permission_updates.into_values().collect();
 
         Ok(batch)
     }
 
-    async fn process_event_batch(&self, batch: EventBatch) -> Result<BatchProcessingResult> {
+    async fn process_event_batch(&self, mut batch: EventBatch) -> Result<BatchProcessingResult> {
         let mut result = BatchProcessingResult::new();
 
+        // Events delivered again for content already ingested in this sync run are completed
+        // without touching their documents
+        for documents in [&mut batch.documents_created, &mut batch.documents_updated] {
+            let redelivered = self.take_redelivered(&batch.sync_run_id, documents).await?;
+            result.successful_event_ids.extend(redelivered);
+            let expired = self.take_expired(documents).await?;
+            result.successful_event_ids.extend(expired);
+        }
+
         // Process document creations in batch
         if !batch.documents_created.is_empty() {
             let docs_count = batch.documents_created.len();
@@ -635,6 +742,8 @@ impl QueueProcessor {
                 .await
             {
                 Ok(successful_ids) => {
+                    self.record_ingested(&batch.sync_run_id, &batch.documents_created)
+                        .await;
                     result.successful_event_ids.extend(successful_ids);
                     result.successful_documents_count += docs_count;
                 }
@@ -658,6 +767,8 @@ impl QueueProcessor {
                 .await
             {
                 Ok(successful_ids) => {
+                    self.record_ingested(&batch.sync_run_id, &batch.documents_updated)
+                        .await;
                     result.successful_event_ids.extend(successful_ids);
                     result.successful_documents_count += docs_count;
                 }
@@ -696,9 +807,126 @@ impl QueueProcessor {
             }
         }
 
+        // Process permission changes in batch
+        if !batch.permissions_updated.is_empty() {
+            let docs_count = batch.permissions_updated.len();
+            match self
+                .process_permissions_updated_batch(&batch.permissions_updated)
+                .await
+            {
+                Ok(successful_ids) => {
+                    result.successful_event_ids.extend(successful_ids);
+                    result.successful_documents_count += docs_count;
+                }
+                Err(e) => {
+                    error!("Batch permissions update failed: {}", e);
+                    for (_, _, _, event_ids) in batch.permissions_updated {
+                        for event_id in event_ids {
+                            result.failed_events.push((event_id, e.to_string()));
+                        }
+                    }
+                }
+            }
+        }
+
         Ok(result)
     }
 
+    /// Remove the documents whose content was already ingested from the same delivery in
+    /// `sync_run_id`, returning the ids of their events.
+    async fn take_redelivered(
+        &self,
+        sync_run_id: &str,
+        documents: &mut Vec<(Document, Vec<String>)>,
+    ) -> Result<Vec<String>> {
+        if documents.is_empty() {
+            return Ok(vec![]);
+        }
+        let ingested = IngestionKeyRepository::new(self.state.db_pool.pool())
+            .find_ingested(sync_run_id.trim_end(), &deliveries(documents))
+            .await?;
+        if ingested.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut redelivered = Vec::new();
+        documents.retain(|(document, event_ids)| {
+            let key = (document.source_id.clone(), document.external_id.clone());
+            if ingested.contains(&key) {
+                redelivered.extend(event_ids.iter().cloned());
+                false
+            } else {
+                true
+            }
+        });
+        info!(
+            "Skipping {} redelivered events for {} documents already ingested",
+            redelivered.len(),
+            ingested.len()
+        );
+        Ok(redelivered)
+    }
+
+    /// Remove the documents last modified in the source before the source's retention cutoff,
+    /// which retention deleted and a sync sent again, returning the ids of their events.
+    async fn take_expired(
+        &self,
+        documents: &mut Vec<(Document, Vec<String>)>,
+    ) -> Result<Vec<String>> {
+        if documents.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut source_ids: Vec<String> = documents
+            .iter()
+            .map(|(document, _)| document.source_id.clone())
+            .collect();
+        source_ids.sort();
+        source_ids.dedup();
+        let cutoffs = SourceRepository::new(self.state.db_pool.pool())
+            .find_retention_cutoffs(&source_ids)
+            .await?;
+        if cutoffs.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut expired = Vec::new();
+        documents.retain(|(document, event_ids)| {
+            let updated_at = document
+                .metadata
+                .get("updated_at")
+                .and_then(|value| value.as_str())
+                .and_then(|value| OffsetDateTime::parse(value, &Iso8601::DEFAULT).ok());
+            if before_retention_cutoff(&cutoffs, &document.source_id, updated_at) {
+                expired.extend(event_ids.iter().cloned());
+                false
+            } else {
+                true
+            }
+        });
+        if !expired.is_empty() {
+            info!(
+                "Skipping {} events for documents past their source's retention cutoff",
+                expired.len()
+            );
+        }
+        Ok(expired)
+    }
+
+    /// Record the deliveries the documents were just ingested from. Failures are logged, as
+    /// they only mean a later redelivery is ingested again.
+    async fn record_ingested(&self, sync_run_id: &str, documents: &[(Document, Vec<String>)]) {
+        if let Err(e) = IngestionKeyRepository::new(self.state.db_pool.pool())
+            .record(sync_run_id.trim_end(), &deliveries(documents))
+            .await
+        {
+            warn!(
+                "Failed to record ingestion of {} documents: {}",
+                documents.len(),
+                e
+            );
+        }
+    }
+
     // Helper methods for batch processing
     fn convert_metadata_to_json(&self, metadata: &DocumentMetadata) -> Result<serde_json::Value> {
         let mut metadata_json = serde_json::to_value(metadata)?;
@@ -768,43 +996,75 @@ impl QueueProcessor {
             created_at: now,
             updated_at: now,
             last_indexed_at,
+            preview: None,
         })
     }
 
-    async fn create_document_from_event_update(
+    /// Apply update events to the documents they target, fetching the existing documents of
+    /// each source in one query. Updates for documents that don't exist are skipped.
+    async fn resolve_document_updates(
         &self,
-        source_id: String,
-        document_id: String,
-        content_id: String,
-        metadata: DocumentMetadata,
-        permissions: Option<DocumentPermissions>,
-        attributes: Option<DocumentAttributes>,
-    ) -> Result<Option<Document>> {
+        updates: Vec<PendingUpdate>,
+    ) -> Result<Vec<(Document, Vec<String>)>> {
         let repo = DocumentRepository::new(self.state.db_pool.pool());
 
-        if let Some(mut document) = repo.find_by_external_id(&source_id, &document_id).await? {
-            let now = sqlx::types::time::OffsetDateTime::now_utc();
-            let metadata_json = self.convert_metadata_to_json(&metadata)?;
+        let mut updates_by_source: std::collections::HashMap<String, Vec<PendingUpdate>> =
+            std::collections::HashMap::new();
+        for update in updates {
+            updates_by_source
+                .entry(update.source_id.clone())
+                .or_default()
+                .push(update);
+        }
 
-            document.title = metadata.title.unwrap_or(document.title);
-            document.content_id = Some(content_id);
-            document.metadata = metadata_json;
-            if let Some(perms) = permissions {
-                document.permissions = serde_json::to_value(&perms)?;
-            }
-            if let Some(attrs) = attributes {
-                document.attributes = serde_json::to_value(&attrs)?;
-            }
-            document.updated_at = now;
+        let mut resolved = Vec::new();
+        for (source_id, updates) in updates_by_source {
+            let external_ids: Vec<String> = updates.iter().map(|u| u.document_id.clone()).collect();
+            let mut existing: std::collections::HashMap<String, Document> = repo
+                .find_by_external_ids(&source_id, &external_ids)
+                .await?
+                .into_iter()
+                .map(|document| (document.external_id.clone(), document))
+                .collect();
+            let without_permissions: Vec<String> = updates
+                .iter()
+                .filter(|u| u.permissions.is_none())
+                .map(|u| u.document_id.clone())
+                .collect();
+            let mut source_permissions = repo
+                .find_source_permissions(&source_id, &without_permissions)
+                .await?;
 
-            Ok(Some(document))
-        } else {
-            warn!(
-                "Document not found for update: {} from source {}",
-                document_id, source_id
-            );
-            Ok(None)
+            for update in updates {
+                let Some(mut document) = existing.remove(&update.document_id) else {
+                    warn!(
+                        "Document not found for update: {} from source {}",
+                        update.document_id, source_id
+                    );
+                    continue;
+                };
+
+                document.title = update.metadata.title.clone().unwrap_or(document.title);
+                document.content_id = Some(update.content_id);
+                document.metadata = self.convert_metadata_to_json(&update.metadata)?;
+                match update.permissions {
+                    Some(perms) => document.permissions = serde_json::to_value(&perms)?,
+                    None => {
+                        if let Some(permissions) = source_permissions.remove(&update.document_id) {
+                            document.permissions = permissions;
+                        }
+                    }
+                }
+                if let Some(attrs) = update.attributes {
+                    document.attributes = serde_json::to_value(&attrs)?;
+                }
+                document.updated_at = sqlx::types::time::OffsetDateTime::now_utc();
+
+                resolved.push((document, update.event_ids));
+            }
         }
+
+        Ok(resolved)
     }
 
     async fn process_documents_created_batch(
@@ -830,33 +1090,42 @@ impl QueueProcessor {
             .batch_get_text(content_ids)
             .await?;
 
-        // Build contents vector in the same order as documents
-        let contents: Vec<String> = documents
-            .iter()
+        // Pair each document with its content
+        let documents_with_content: Vec<(Document, String)> = documents
+            .into_iter()
             .map(|doc| {
-                doc.content_id
+                let content = doc
+                    .content_id
                     .as_ref()
                     .and_then(|cid| content_map.get(cid).cloned())
-                    .with_context(|| format!("Failed to get content for document {}", doc.id))
+                    .with_context(|| format!("Failed to get content for document {}", doc.id))?;
+                Ok((doc, content))
             })
             .collect::<Result<Vec<_>>>()?;
 
         debug!(
             "Batch fetched content for {} documents in {:?}",
-            documents.len(),
+            documents_with_content.len(),
             content_fetch_start.elapsed()
         );
 
         let repo = DocumentRepository::new(self.state.db_pool.pool());
+        let derived = derive_from_content(
+            documents_with_content
+                .iter()
+                .map(|(document, content)| (document, content.as_str())),
+        );
 
         // Batch upsert documents with content
         let upsert_start = std::time::Instant::now();
-        let upserted_documents = repo.batch_upsert(documents, contents).await?;
+        let upserted_documents = repo.bulk_upsert(documents_with_content).await?;
         debug!(
             "Batch upsert of {} documents took {:?}",
             upserted_documents.len(),
             upsert_start.elapsed()
         );
+        notify_documents_indexed(&self.state, &upserted_documents).await;
+        store_derived(&self.state, &upserted_documents, derived).await;
 
         // Batch add documents to embedding queue
         let embedding_start = std::time::Instant::now();
@@ -919,38 +1188,35 @@ impl QueueProcessor {
             .batch_get_text(content_ids)
             .await?;
 
-        // For updates, we need to handle them individually since we need to find existing documents
-        let mut successful_event_ids = Vec::new();
-        let mut updated_documents = Vec::new();
-
-        for (document, event_ids) in documents_with_event_ids {
-            let content = document
-                .content_id
-                .as_ref()
-                .and_then(|cid| content_map.get(cid).cloned())
-                .unwrap_or_default();
-
-            match repo.update(&document.id, document.clone(), &content).await {
-                Ok(Some(updated_doc)) => {
-                    updated_documents.push((event_ids.clone(), updated_doc));
-                    successful_event_ids.extend(event_ids.clone());
-                }
-                Ok(None) => {
-                    warn!("Document not found for update: {}", document.external_id);
-                }
-                Err(e) => {
-                    error!("Failed to update document {}: {}", document.external_id, e);
-                    return Err(e.into());
-                }
-            }
-        }
+        // The documents were resolved against their existing rows, so the upsert updates them
+        let documents_with_content: Vec<(Document, String)> = documents
+            .into_iter()
+            .map(|document| {
+                let content = document
+                    .content_id
+                    .as_ref()
+                    .and_then(|cid| content_map.get(cid).cloned())
+                    .unwrap_or_default();
+                (document.clone(), content)
+            })
+            .collect();
+
+        let derived = derive_from_content(
+            documents_with_content
+                .iter()
+                .map(|(document, content)| (document, content.as_str())),
+        );
+        let updated_documents = repo.bulk_upsert(documents_with_content).await?;
+        notify_documents_indexed(&self.state, &updated_documents).await;
+        store_derived(&self.state, &updated_documents, derived).await;
+        let successful_event_ids: Vec<String> = documents_with_event_ids
+            .iter()
+            .flat_map(|(_, event_ids)| event_ids.clone())
+            .collect();
 
         if !updated_documents.is_empty() {
             // Collect document IDs for batch operations
-            let doc_ids: Vec<String> = updated_documents
-                .iter()
-                .map(|(_, doc)| doc.id.clone())
-                .collect();
+            let doc_ids: Vec<String> = updated_documents.iter().map(|doc| doc.id.clone()).collect();
 
             // Batch queue embeddings
             if let Err(e) = self
@@ -980,46 +1246,76 @@ impl QueueProcessor {
     ) -> Result<Vec<String>> {
         let start_time = std::time::Instant::now();
         let repo = DocumentRepository::new(self.state.db_pool.pool());
-        let embedding_repo = EmbeddingRepository::new(self.state.db_pool.pool());
 
-        let mut successful_event_ids = Vec::new();
-        let mut document_ids_to_delete = Vec::new();
+        let mut external_ids_by_source: std::collections::HashMap<&str, Vec<String>> =
+            std::collections::HashMap::new();
+        for (source_id, document_id, _) in deletions {
+            external_ids_by_source
+                .entry(source_id.as_str())
+                .or_default()
+                .push(document_id.clone());
+        }
 
-        // First, find all documents that exist
-        for (source_id, document_id, event_ids) in deletions {
-            if let Some(document) = repo.find_by_external_id(source_id, document_id).await? {
-                document_ids_to_delete.push(document.id.clone());
-                successful_event_ids.extend(event_ids.clone());
-            } else {
+        // Embeddings and queued embedding work are removed by ON DELETE CASCADE
+        let mut deleted_count = 0;
+        for (source_id, external_ids) in external_ids_by_source {
+            let deleted_ids = repo
+                .bulk_delete_by_external_ids(source_id, &external_ids)
+                .await?;
+            if deleted_ids.len() < external_ids.len() {
                 warn!(
-                    "Document not found for deletion: {} from source {}",
-                    document_id, source_id
+                    "{} documents not found for deletion from source {}",
+                    external_ids.len() - deleted_ids.len(),
+                    source_id
                 );
-                // Still count as successful since the document doesn't exist
-                successful_event_ids.extend(event_ids.clone());
             }
+            deleted_count += deleted_ids.len();
         }
 
-        if !document_ids_to_delete.is_empty() {
-            // Delete embeddings in batch
-            for doc_id in &document_ids_to_delete {
-                if let Err(e) = embedding_repo.delete_by_document_id(doc_id).await {
-                    error!("Failed to delete embeddings for document {}: {}", doc_id, e);
-                }
-            }
-
-            // Delete documents in batch
-            let delete_start = std::time::Instant::now();
-            let deleted_count = repo.batch_delete(document_ids_to_delete.clone()).await?;
-            debug!("Batch document deletion took {:?}", delete_start.elapsed());
-
-            let total_duration = start_time.elapsed();
+        if deleted_count > 0 {
             info!(
                 "Batch deleted {} documents and their embeddings (took {:?})",
-                deleted_count, total_duration
+                deleted_count,
+                start_time.elapsed()
             );
         }
 
+        // Deletions of documents that don't exist still count as successful
+        Ok(deletions
+            .iter()
+            .flat_map(|(_, _, event_ids)| event_ids.clone())
+            .collect())
+    }
+
+    async fn process_permissions_updated_batch(
+        &self,
+        updates: &[(String, String, DocumentPermissions, Vec<String>)], // (source_id, document_id, permissions, event_ids)
+    ) -> Result<Vec<String>> {
+        let repo = DocumentRepository::new(self.state.db_pool.pool());
+        let mut successful_event_ids = Vec::new();
+        let mut updated_count = 0;
+
+        for (source_id, document_id, permissions, event_ids) in updates {
+            let permissions_json = serde_json::to_value(permissions)?;
+            if repo
+                .update_permissions(source_id, document_id, &permissions_json)
+                .await?
+            {
+                updated_count += 1;
+            } else {
+                warn!(
+                    "Document not found for permissions update: {} from source {}",
+                    document_id, source_id
+                );
+            }
+            successful_event_ids.extend(event_ids.clone());
+        }
+
+        if updated_count > 0 {
+            info!("Batch updated permissions of {} documents", updated_count);
+            clear_cached_responses(&self.state).await;
+        }
+
         Ok(successful_event_ids)
     }
 
@@ -1093,6 +1389,154 @@ impl QueueProcessor {
     }
 }
 
+/// The deliveries of content the documents are ingested from. The sync run id of the
+/// queue is padded, so callers trim it to match the one events carry.
+/// Whether a document was last modified in the source before the source's retention cutoff.
+/// Documents without a source timestamp are kept, retention ages them from when they are
+/// indexed.
+fn before_retention_cutoff(
+    cutoffs: &HashMap<String, OffsetDateTime>,
+    source_id: &str,
+    updated_at: Option<OffsetDateTime>,
+) -> bool {
+    match (cutoffs.get(source_id), updated_at) {
+        (Some(cutoff), Some(updated_at)) => updated_at < *cutoff,
+        _ => false,
+    }
+}
+
+fn deliveries(documents: &[(Document, Vec<String>)]) -> Vec<DocumentDelivery> {
+    documents
+        .iter()
+        .filter_map(|(document, _)| {
+            Some(DocumentDelivery {
+                source_id: document.source_id.clone(),
+                external_id: document.external_id.clone(),
+                content_id: document.content_id.clone()?,
+            })
+        })
+        .collect()
+}
+
+/// Queue `document.indexed` webhooks for documents just written to the index. Failures are
+/// logged, so webhooks never hold up indexing.
+async fn notify_documents_indexed(state: &AppState, documents: &[Document]) {
+    let payloads: Vec<serde_json::Value> = documents
+        .iter()
+        .map(|document| {
+            serde_json::json!({
+                "document_id": document.id,
+                "source_id": document.source_id,
+                "external_id": document.external_id,
+                "title": document.title,
+                "url": document.url,
+            })
+        })
+        .collect();
+
+    if let Err(e) = WebhookRepository::new(state.db_pool.pool())
+        .enqueue(WebhookEvent::DocumentIndexed, &payloads)
+        .await
+    {
+        error!(
+            "Failed to queue document.indexed webhooks for {} documents: {}",
+            documents.len(),
+            e
+        );
+    }
+}
+
+/// What the index keeps about a document's content besides the content itself.
+struct ContentDerived {
+    anchors: Vec<DocumentAnchor>,
+    links: Vec<LinkReference>,
+    preview: PreviewInput,
+}
+
+/// Derive anchors, links and previews from the content of documents about to be written, keyed by
+/// source and external ID since new documents only get their final ID from the upsert.
+fn derive_from_content<'a>(
+    documents: impl IntoIterator<Item = (&'a Document, &'a str)>,
+) -> HashMap<(String, String), ContentDerived> {
+    documents
+        .into_iter()
+        .map(|(document, content)| {
+            (
+                (document.source_id.clone(), document.external_id.clone()),
+                ContentDerived {
+                    anchors: anchors::extract(content),
+                    links: links::extract(content),
+                    preview: PreviewInput::new(document, content),
+                },
+            )
+        })
+        .collect()
+}
+
+/// Store the anchors, links, previews and parents of documents just written to the index.
+/// Failures are logged, as they only enrich search results.
+async fn store_derived(
+    state: &AppState,
+    documents: &[Document],
+    mut derived: HashMap<(String, String), ContentDerived>,
+) {
+    let mut document_anchors = Vec::new();
+    let mut document_links = Vec::new();
+    let mut document_previews = Vec::new();
+    let mut document_parents = Vec::new();
+    for document in documents {
+        let parent: Option<DocumentParent> = document
+            .metadata
+            .get("parent")
+            .and_then(|parent| serde_json::from_value(parent.clone()).ok());
+        document_parents.push((document.id.clone(), parent));
+        let key = (document.source_id.clone(), document.external_id.clone());
+        if let Some(derived) = derived.remove(&key) {
+            document_anchors.push((document.id.clone(), derived.anchors));
+            document_links.push((document.id.clone(), derived.links));
+            document_previews.push((document, derived.preview));
+        }
+    }
+
+    if let Err(e) = DocumentAnchorRepository::new(state.db_pool.pool())
+        .replace_for_documents(&document_anchors)
+        .await
+    {
+        error!(
+            "Failed to store anchors for {} documents: {}",
+            documents.len(),
+            e
+        );
+    }
+    if let Err(e) = DocumentLinkRepository::new(state.db_pool.pool())
+        .replace_for_documents(&document_links)
+        .await
+    {
+        error!(
+            "Failed to store links for {} documents: {}",
+            documents.len(),
+            e
+        );
+    }
+    if let Err(e) = previews::store(state, document_previews).await {
+        error!(
+            "Failed to store previews for {} documents: {}",
+            documents.len(),
+            e
+        );
+    }
+    if let Err(e) = DocumentRelationshipRepository::new(state.db_pool.pool())
+        .replace_for_documents(&document_parents)
+        .await
+    {
+        error!(
+            "Failed to store parents for {} documents: {}",
+            documents.len(),
+            e
+        );
+    }
+}
+
 // Context for processing individual events concurrently
 struct ProcessorContext {
     state: AppState,
@@ -1110,10 +1554,67 @@ impl ProcessorContext {
 
     async fn process_event(&self, payload: &serde_json::Value) -> Result<()> {
         let start_time = std::time::Instant::now();
-        let event: ConnectorEvent = serde_json::from_value(payload.clone())?;
+        let event = event_schema::decode(payload)?;
         let sync_run_id = event.sync_run_id().to_string();
         debug!("Started processing event, sync_run_id: {}", sync_run_id);
 
+        let delivery = match &event {
+            ConnectorEvent::DocumentCreated {
+                source_id,
+                document_id,
+                content_id,
+                ..
+            }
+            | ConnectorEvent::DocumentUpdated {
+                source_id,
+                document_id,
+                content_id,
+                ..
+            } => Some(DocumentDelivery {
+                source_id: source_id.clone(),
+                external_id: document_id.clone(),
+                content_id: content_id.clone(),
+            }),
+            _ => None,
+        };
+        let ingestion_keys = IngestionKeyRepository::new(self.state.db_pool.pool());
+        if let Some(delivery) = &delivery {
+            let ingested = ingestion_keys
+                .find_ingested(&sync_run_id, std::slice::from_ref(delivery))
+                .await?;
+            if !ingested.is_empty() {
+                debug!(
+                    "Skipping redelivered event for document {} already ingested",
+                    delivery.external_id
+                );
+                return Ok(());
+            }
+        }
+        if let ConnectorEvent::DocumentCreated {
+            source_id,
+            document_id,
+            metadata,
+            ..
+        }
+        | ConnectorEvent::DocumentUpdated {
+            source_id,
+            document_id,
+            metadata,
+            ..
+        } = &event
+        {
+            let cutoffs = SourceRepository::new(self.state.db_pool.pool())
+                .find_retention_cutoffs(std::slice::from_ref(source_id))
+                .await?;
+            if before_retention_cutoff(&cutoffs, source_id, metadata.updated_at) {
+                debug!(
+                    "Skipping event for document {} past its source's retention cutoff",
+                    document_id
+                );
+                return Ok(());
+            }
+        }
+
         // Update sync run progress
         let sync_update_start = std::time::Instant::now();
         if let Err(e) = self.increment_sync_run_progress(&sync_run_id).await {
@@ -1173,6 +1674,27 @@ impl ProcessorContext {
             } => {
                 self.handle_document_deleted(source_id, document_id).await?;
             }
+            ConnectorEvent::PermissionsUpdated {
+                sync_run_id: _,
+                source_id,
+                document_id,
+                permissions,
+            } => {
+                self.handle_permissions_updated(source_id, document_id, permissions)
+                    .await?;
+            }
+        }
+
+        if let Some(delivery) = &delivery {
+            if let Err(e) = ingestion_keys
+                .record(&sync_run_id, std::slice::from_ref(delivery))
+                .await
+            {
+                warn!(
+                    "Failed to record ingestion of document {}: {}",
+                    delivery.external_id, e
+                );
+            }
         }
 
         debug!("Total event processing time: {:?}", start_time.elapsed());
@@ -1228,6 +1750,7 @@ impl ProcessorContext {
             created_at: now,
             updated_at: now,
             last_indexed_at: now,
+            preview: None,
         };
 
         // Fetch content from storage for tsvector generation and embedding queueing
@@ -1246,6 +1769,9 @@ impl ProcessorContext {
         let upsert_start = std::time::Instant::now();
         let upserted = repo.upsert(document, &content).await?;
         debug!("Document upsert took: {:?}", upsert_start.elapsed());
+        notify_documents_indexed(&self.state, std::slice::from_ref(&upserted)).await;
+        let derived = derive_from_content([(&upserted, content.as_str())]);
+        store_derived(&self.state, std::slice::from_ref(&upserted), derived).await;
 
         // Queue embeddings for async generation instead of generating them synchronously
         if content.trim().is_empty() {
@@ -1302,8 +1828,17 @@ impl ProcessorContext {
             document.title = metadata.title.unwrap_or(document.title);
             document.content_id = Some(content_id.clone());
             document.metadata = metadata_json;
-            if let Some(perms) = permissions {
-                document.permissions = serde_json::to_value(&perms)?;
+            match permissions {
+                Some(perms) => document.permissions = serde_json::to_value(&perms)?,
+                None => {
+                    if let Some(source_permissions) = repo
+                        .find_source_permissions(&source_id, std::slice::from_ref(&document_id))
+                        .await?
+                        .remove(&document_id)
+                    {
+                        document.permissions = source_permissions;
+                    }
+                }
             }
             if let Some(attrs) = attributes {
                 document.attributes = serde_json::to_value(&attrs)?;
@@ -1325,7 +1860,10 @@ impl ProcessorContext {
             let updated_document = repo.update(&doc_id, document, &content).await?;
 
             // Queue embeddings for async generation
-            if let Some(_updated_doc) = &updated_document {
+            if let Some(updated_doc) = &updated_document {
+                notify_documents_indexed(&self.state, std::slice::from_ref(updated_doc)).await;
+                let derived = derive_from_content([(updated_doc, content.as_str())]);
+                store_derived(&self.state, std::slice::from_ref(updated_doc), derived).await;
                 if !content.trim().is_empty() {
                     if let Err(e) = self.state.embedding_queue.enqueue(doc_id.clone()).await {
                         error!(
@@ -1376,6 +1914,35 @@ impl ProcessorContext {
         Ok(())
     }
 
+    async fn handle_permissions_updated(
+        &self,
+        source_id: String,
+        document_id: String,
+        permissions: DocumentPermissions,
+    ) -> Result<()> {
+        info!(
+            "Processing permissions updated: {} from source {}",
+            document_id, source_id
+        );
+
+        let repo = DocumentRepository::new(self.state.db_pool.pool());
+        let permissions_json = serde_json::to_value(&permissions)?;
+
+        if repo
+            .update_permissions(&source_id, &document_id, &permissions_json)
+            .await?
+        {
+            clear_cached_responses(&self.state).await;
+        } else {
+            warn!(
+                "Document not found for permissions update: {} from source {}",
+                document_id, source_id
+            );
+        }
+
+        Ok(())
+    }
+
     async fn increment_sync_run_progress(&self, sync_run_id: &str) -> Result<()> {
         self.sync_run_repo.increment_progress(sync_run_id).await?;
         Ok(())
@@ -0,0 +1,184 @@
+//! Home-page recommendations, computed periodically into a per-user cache.
+//!
+//! Each user is recommended documents from four signals over the last two weeks: documents
+//! their closest collaborators in the people directory edited, follow-ups to meetings they
+//! attended, documents matching their recent searches, and documents linked to or from ones
+//! they opened or wrote. A document's score sums its signals, each weighted by kind and by
+//! how recently the document was updated, and it is shown with the reason that contributed
+//! most. Documents the user wrote or already opened, and ones they cannot see, are left out.
+
+use crate::AppState;
+use anyhow::Result;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use shared::cache;
+use shared::db::repositories::{
+    DocumentRepository, RecommendationCandidate, RecommendationReason, RecommendationRepository,
+    UserRepository,
+};
+use shared::models::User;
+use sqlx::types::time::OffsetDateTime;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+const WINDOW_DAYS: i64 = 14;
+/// Days after a meeting in which attendees' new documents count as follow-ups.
+const FOLLOW_UP_DAYS: i32 = 7;
+const MAX_RECOMMENDATIONS: usize = 20;
+const UPDATE_HALF_LIFE_DAYS: f64 = 7.0;
+const USER_PAGE_SIZE: i64 = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecommendationRunResult {
+    pub users: usize,
+    pub recommendations: usize,
+}
+
+/// Recompute recommendations every `interval`, the first time one interval after startup.
+pub async fn schedule(state: AppState, interval: Duration) {
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+    loop {
+        ticker.tick().await;
+        if let Err(e) = run(&state).await {
+            error!("Computing recommendations failed: {:#}", e);
+        }
+    }
+}
+
+/// Recompute the recommendations of every active user.
+pub async fn run(state: &AppState) -> Result<RecommendationRunResult> {
+    let user_repo = UserRepository::new(state.db_pool.pool());
+    let mut result = RecommendationRunResult {
+        users: 0,
+        recommendations: 0,
+    };
+    let mut offset = 0;
+    loop {
+        let (users, _) = user_repo.list(None, USER_PAGE_SIZE, offset).await?;
+        for user in users.iter().filter(|user| user.is_active) {
+            match compute_for_user(state, user).await {
+                Ok(count) => {
+                    result.users += 1;
+                    result.recommendations += count;
+                }
+                Err(e) => warn!("Failed to compute recommendations for {}: {:#}", user.id, e),
+            }
+        }
+        if (users.len() as i64) < USER_PAGE_SIZE {
+            break;
+        }
+        offset += USER_PAGE_SIZE;
+    }
+
+    info!(
+        "Computed {} recommendations for {} users",
+        result.recommendations, result.users
+    );
+    Ok(result)
+}
+
+async fn compute_for_user(state: &AppState, user: &User) -> Result<usize> {
+    let pool = state.db_pool.pool();
+    let repo = RecommendationRepository::new(pool);
+    let now = OffsetDateTime::now_utc();
+    let since = now - time::Duration::days(WINDOW_DAYS);
+
+    let mut signals = vec![
+        (
+            RecommendationReason::CollaboratorEdit,
+            repo.find_collaborator_edits(&user.email, since).await?,
+        ),
+        (
+            RecommendationReason::MeetingFollowUp,
+            repo.find_meeting_follow_ups(&user.email, since, FOLLOW_UP_DAYS)
+                .await?,
+        ),
+        (
+            RecommendationReason::Related,
+            repo.find_related(&user.id, &user.email, since).await?,
+        ),
+    ];
+    for query in recent_searches(state, &user.id).await {
+        signals.push((
+            RecommendationReason::RecentSearch,
+            repo.find_search_matches(&query, since).await?,
+        ));
+    }
+    let known = repo.find_known(&user.id, &user.email, since).await?;
+
+    // Total score and the reason contributing most, with its score, per document
+    let mut scored: HashMap<String, (f64, RecommendationReason, f64)> = HashMap::new();
+    for (reason, candidates) in signals {
+        for candidate in candidates {
+            if known.contains(&candidate.document_id) {
+                continue;
+            }
+            let score = score(reason, &candidate, now);
+            let entry = scored
+                .entry(candidate.document_id)
+                .or_insert((0.0, reason, 0.0));
+            entry.0 += score;
+            if score > entry.2 {
+                entry.1 = reason;
+                entry.2 = score;
+            }
+        }
+    }
+    let mut ranked: Vec<(String, f64, RecommendationReason)> = scored
+        .into_iter()
+        .map(|(id, (total, reason, _))| (id, total, reason))
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let document_repo = DocumentRepository::new(pool);
+    let source_ids = document_repo.fetch_active_source_ids(None).await?;
+    let ids: Vec<String> = ranked.iter().map(|(id, _, _)| id.clone()).collect();
+    let visible: Vec<String> = document_repo
+        .find_by_ids_with_filters(&ids, &source_ids, None, None, None, Some(&user.email))
+        .await?
+        .into_iter()
+        .map(|document| document.id)
+        .collect();
+    let recommendations: Vec<(String, RecommendationReason, f32)> = ranked
+        .into_iter()
+        .filter(|(id, _, _)| visible.contains(id))
+        .take(MAX_RECOMMENDATIONS)
+        .map(|(id, score, reason)| (id, reason, score as f32))
+        .collect();
+
+    repo.replace_for_user(&user.id, &recommendations).await?;
+    Ok(recommendations.len())
+}
+
+/// A user's recent searches, or none when they cannot be read.
+async fn recent_searches(state: &AppState, user_id: &str) -> Vec<String> {
+    let key = cache::keys().user(user_id, cache::SEARCH_HISTORY);
+    match state.redis_client.get_multiplexed_async_connection().await {
+        Ok(mut conn) => conn.lrange(&key, 0, -1).await.unwrap_or_default(),
+        Err(e) => {
+            warn!("Failed to read search history of {}: {}", user_id, e);
+            vec![]
+        }
+    }
+}
+
+fn reason_weight(reason: RecommendationReason) -> f64 {
+    match reason {
+        RecommendationReason::MeetingFollowUp => 1.5,
+        RecommendationReason::CollaboratorEdit | RecommendationReason::RecentSearch => 1.0,
+        RecommendationReason::Related => 0.8,
+    }
+}
+
+/// How strongly a signal recommends a document, halving for every week since its update.
+fn score(
+    reason: RecommendationReason,
+    candidate: &RecommendationCandidate,
+    now: OffsetDateTime,
+) -> f64 {
+    let days_since_update = ((now - candidate.updated_at).as_seconds_f64() / 86_400.0).max(0.0);
+    reason_weight(reason)
+        * (1.0 + candidate.strength.max(0.0).ln_1p())
+        * 0.5f64.powf(days_since_update / UPDATE_HALF_LIFE_DAYS)
+}
@@ -0,0 +1,378 @@
+use crate::AppState;
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use shared::db::repositories::{
+    EmbeddingRepository, IndexSnapshot, IndexSnapshotKind, IndexSnapshotRepository, SnapshotCounts,
+    SnapshotDocument, UserRepository,
+};
+use shared::models::{Embedding, Source};
+use shared::{ObjectStorage, Repository, StorageError};
+use sqlx::types::time::OffsetDateTime;
+use std::collections::HashMap;
+use tracing::{error, info, warn};
+use ulid::Ulid;
+
+/// Layout version written by this build. Snapshots with a newer version are not restored.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// Documents per file. Files hold content blobs inline, so this bounds the memory a
+/// snapshot or restore needs.
+const DOCUMENTS_PER_FILE: i64 = 500;
+
+/// Describes a snapshot and lists its files. Written last, so a snapshot without a manifest
+/// never completed.
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub version: u32,
+    pub snapshot_id: String,
+    pub source_id: Option<String>,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+    /// Sources as configured when the snapshot was taken. Credentials are not included.
+    pub sources: Vec<Source>,
+    /// Documents files, relative to the snapshot prefix
+    pub files: Vec<String>,
+    pub document_count: i64,
+    pub embedding_count: i64,
+    pub content_blob_count: i64,
+}
+
+/// One line of a documents file: a document with its content blob and embeddings.
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    pub document: SnapshotDocument,
+    pub content: Option<SnapshotContent>,
+    pub embeddings: Vec<Embedding>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotContent {
+    pub content_type: Option<String>,
+    /// Base64 encoded blob
+    pub data: String,
+}
+
+/// Object storage prefix a snapshot's files are written under.
+pub fn snapshot_prefix(snapshot_id: &str) -> String {
+    format!("snapshots/{}", snapshot_id)
+}
+
+fn manifest_key(snapshot_id: &str) -> String {
+    format!("{}/manifest.json", snapshot_prefix(snapshot_id))
+}
+
+/// Read the manifest of a snapshot, `None` if the snapshot does not exist or never
+/// completed.
+pub async fn read_manifest(
+    storage: &dyn ObjectStorage,
+    snapshot_id: &str,
+) -> Result<Option<SnapshotManifest>> {
+    let content = match storage.get_object(&manifest_key(snapshot_id)).await {
+        Ok(content) => content,
+        Err(StorageError::NotFound(_)) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let manifest = serde_json::from_slice(&content)
+        .with_context(|| format!("Invalid manifest in snapshot {}", snapshot_id))?;
+    Ok(Some(manifest))
+}
+
+/// Run a snapshot or restore in the background, recording the outcome on it.
+pub fn spawn(state: AppState, snapshot: IndexSnapshot) {
+    tokio::spawn(async move {
+        let repo = IndexSnapshotRepository::new(state.db_pool.pool());
+        if let Err(e) = process(&state, &snapshot).await {
+            error!("Index snapshot {} failed: {:#}", snapshot.id, e);
+            if let Err(e) = repo.mark_failed(&snapshot.id, &format!("{:#}", e)).await {
+                error!(
+                    "Failed to mark index snapshot {} as failed: {}",
+                    snapshot.id, e
+                );
+            }
+        }
+    });
+}
+
+/// Resume snapshots and restores left pending or running by a previous indexer process.
+/// Files are written under fixed keys and restored documents are upserted, so re-running
+/// either from the start is safe.
+pub async fn resume_unfinished(state: &AppState) -> Result<()> {
+    let repo = IndexSnapshotRepository::new(state.db_pool.pool());
+    let snapshots = repo.find_unfinished().await?;
+    if !snapshots.is_empty() {
+        info!(
+            "Resuming {} unfinished index snapshots and restores",
+            snapshots.len()
+        );
+    }
+    for snapshot in snapshots {
+        spawn(state.clone(), snapshot);
+    }
+    Ok(())
+}
+
+async fn process(state: &AppState, snapshot: &IndexSnapshot) -> Result<SnapshotCounts> {
+    let repo = IndexSnapshotRepository::new(state.db_pool.pool());
+    repo.mark_running(&snapshot.id).await?;
+
+    let counts = match snapshot.kind {
+        IndexSnapshotKind::Snapshot => {
+            info!("Taking index snapshot {}", snapshot.id);
+            take_snapshot(state, snapshot).await?
+        }
+        IndexSnapshotKind::Restore => {
+            info!(
+                "Restoring index snapshot {} ({})",
+                snapshot.snapshot_id, snapshot.id
+            );
+            restore_snapshot(state, snapshot).await?
+        }
+    };
+    repo.mark_completed(&snapshot.id, &counts).await?;
+
+    info!(
+        "Index {:?} {} completed: {} documents, {} embeddings, {} blobs",
+        snapshot.kind, snapshot.id, counts.documents, counts.embeddings, counts.content_blobs
+    );
+    Ok(counts)
+}
+
+async fn take_snapshot(state: &AppState, snapshot: &IndexSnapshot) -> Result<SnapshotCounts> {
+    let repo = IndexSnapshotRepository::new(state.db_pool.pool());
+    let storage = state.content_storage.as_ref();
+    let prefix = snapshot_prefix(&snapshot.snapshot_id);
+
+    let sources = repo.find_sources(snapshot.source_id.as_deref()).await?;
+    if let Some(source_id) = &snapshot.source_id {
+        if sources.is_empty() {
+            bail!("Source {} not found", source_id);
+        }
+    }
+    let source_ids: Vec<String> = sources.iter().map(|s| s.id.clone()).collect();
+
+    let mut counts = SnapshotCounts::default();
+    let mut files = Vec::new();
+    let mut after: Option<String> = None;
+    loop {
+        let documents = repo
+            .find_documents_after(&source_ids, after.as_deref(), DOCUMENTS_PER_FILE)
+            .await?;
+        let Some(last) = documents.last() else {
+            break;
+        };
+        after = Some(last.id.clone());
+        let page_size = documents.len() as i64;
+
+        let document_ids: Vec<String> = documents.iter().map(|d| d.id.clone()).collect();
+        let mut embeddings: HashMap<String, Vec<Embedding>> = HashMap::new();
+        for embedding in repo.find_embeddings(&document_ids).await? {
+            embeddings
+                .entry(embedding.document_id.clone())
+                .or_default()
+                .push(embedding);
+        }
+
+        let mut lines = Vec::new();
+        for document in documents {
+            let content = match &document.content_id {
+                Some(content_id) => read_content(storage, content_id).await?,
+                None => None,
+            };
+            let entry = SnapshotEntry {
+                embeddings: embeddings.remove(&document.id).unwrap_or_default(),
+                content,
+                document,
+            };
+
+            counts.documents += 1;
+            counts.embeddings += entry.embeddings.len() as i64;
+            counts.content_blobs += entry.content.is_some() as i64;
+            serde_json::to_writer(&mut lines, &entry)?;
+            lines.push(b'\n');
+        }
+
+        let file = format!("documents-{:05}.jsonl", files.len());
+        storage
+            .put_object(
+                &format!("{}/{}", prefix, file),
+                &lines,
+                Some("application/x-ndjson"),
+            )
+            .await
+            .with_context(|| format!("Failed to write snapshot file {}", file))?;
+        files.push(file);
+        repo.update_progress(&snapshot.id, &counts).await?;
+
+        if page_size < DOCUMENTS_PER_FILE {
+            break;
+        }
+    }
+
+    let manifest = SnapshotManifest {
+        version: SNAPSHOT_FORMAT_VERSION,
+        snapshot_id: snapshot.snapshot_id.clone(),
+        source_id: snapshot.source_id.clone(),
+        created_at: snapshot.created_at,
+        sources,
+        files,
+        document_count: counts.documents,
+        embedding_count: counts.embeddings,
+        content_blob_count: counts.content_blobs,
+    };
+    storage
+        .put_object(
+            &manifest_key(&snapshot.snapshot_id),
+            &serde_json::to_vec_pretty(&manifest)?,
+            Some("application/json"),
+        )
+        .await
+        .context("Failed to write snapshot manifest")?;
+
+    Ok(counts)
+}
+
+/// A document's content blob, `None` if it has gone missing from storage.
+async fn read_content(
+    storage: &dyn ObjectStorage,
+    content_id: &str,
+) -> Result<Option<SnapshotContent>> {
+    let metadata = match storage.get_content_metadata(content_id).await {
+        Ok(metadata) => metadata,
+        Err(StorageError::NotFound(_)) => {
+            warn!(
+                "Content blob {} not found, snapshotting without it",
+                content_id
+            );
+            return Ok(None);
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let content = storage
+        .get_content(content_id)
+        .await
+        .with_context(|| format!("Failed to read content blob {}", content_id))?;
+
+    Ok(Some(SnapshotContent {
+        content_type: metadata.content_type,
+        data: STANDARD.encode(content),
+    }))
+}
+
+async fn restore_snapshot(state: &AppState, restore: &IndexSnapshot) -> Result<SnapshotCounts> {
+    let pool = state.db_pool.pool();
+    let repo = IndexSnapshotRepository::new(pool);
+    let storage = state.content_storage.as_ref();
+    let prefix = snapshot_prefix(&restore.snapshot_id);
+
+    let manifest = read_manifest(storage, &restore.snapshot_id)
+        .await?
+        .ok_or_else(|| anyhow!("Snapshot {} not found", restore.snapshot_id))?;
+    if manifest.version > SNAPSHOT_FORMAT_VERSION {
+        bail!(
+            "Snapshot {} has layout version {}, this build restores up to {}",
+            restore.snapshot_id,
+            manifest.version,
+            SNAPSHOT_FORMAT_VERSION
+        );
+    }
+
+    let sources: Vec<&Source> = manifest
+        .sources
+        .iter()
+        .filter(|s| restore.source_id.as_ref().is_none_or(|id| *id == s.id))
+        .collect();
+    if let Some(source_id) = &restore.source_id {
+        if sources.is_empty() {
+            bail!(
+                "Source {} is not in snapshot {}",
+                source_id,
+                restore.snapshot_id
+            );
+        }
+    }
+
+    // Sources keep their owner when that user exists here, otherwise the user restoring
+    // them becomes the owner
+    let users = UserRepository::new(pool);
+    for source in &sources {
+        let created_by = if users.find_by_id(source.created_by.clone()).await?.is_some() {
+            source.created_by.as_str()
+        } else {
+            restore.created_by.as_deref().ok_or_else(|| {
+                anyhow!(
+                    "The owner of source {} does not exist in this deployment, \
+                     created_by is required to restore it",
+                    source.id
+                )
+            })?
+        };
+        if repo.restore_source(source, created_by).await? {
+            info!("Restored source {} ({}) as paused", source.id, source.name);
+        }
+    }
+
+    let embedding_repo = EmbeddingRepository::new(pool);
+    let mut counts = SnapshotCounts::default();
+    for file in &manifest.files {
+        let content = storage
+            .get_object(&format!("{}/{}", prefix, file))
+            .await
+            .with_context(|| format!("Failed to read snapshot file {}", file))?;
+
+        let mut document_ids = Vec::new();
+        let mut unembedded_ids = Vec::new();
+        let mut embeddings = Vec::new();
+        for line in content.split(|&b| b == b'\n').filter(|l| !l.is_empty()) {
+            let entry: SnapshotEntry = serde_json::from_slice(line)
+                .with_context(|| format!("Invalid entry in snapshot file {}", file))?;
+            if !sources.iter().any(|s| s.id == entry.document.source_id) {
+                continue;
+            }
+
+            let content_id = match &entry.content {
+                Some(content) => {
+                    let bytes = STANDARD.decode(&content.data).with_context(|| {
+                        format!("Invalid content of document {}", entry.document.id)
+                    })?;
+                    counts.content_blobs += 1;
+                    Some(
+                        storage
+                            .store_content_with_type(&bytes, content.content_type.as_deref(), None)
+                            .await?,
+                    )
+                }
+                None => None,
+            };
+
+            let embedded = !entry.embeddings.is_empty();
+            let document_id = repo
+                .restore_document(&entry.document, content_id.as_deref(), embedded)
+                .await?;
+            counts.documents += 1;
+            counts.embeddings += entry.embeddings.len() as i64;
+
+            if !embedded {
+                unembedded_ids.push(document_id.clone());
+            }
+            embeddings.extend(entry.embeddings.into_iter().map(|embedding| Embedding {
+                id: Ulid::new().to_string(),
+                document_id: document_id.clone(),
+                ..embedding
+            }));
+            document_ids.push(document_id);
+        }
+
+        // Embeddings of documents that were already indexed are replaced wholesale
+        embedding_repo
+            .bulk_delete_by_document_ids(&document_ids)
+            .await?;
+        embedding_repo.bulk_create(embeddings).await?;
+        if !unembedded_ids.is_empty() {
+            state.embedding_queue.enqueue_batch(unembedded_ids).await?;
+        }
+        repo.update_progress(&restore.id, &counts).await?;
+    }
+
+    Ok(counts)
+}
@@ -218,6 +218,8 @@ pub async fn enqueue_dummy_events(
                 url: None,
                 path: None,
                 extra: None,
+                thumbnail: None,
+                parent: None,
             },
             permissions: DocumentPermissions {
                 public: true,
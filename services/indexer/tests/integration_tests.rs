@@ -5,9 +5,19 @@ use axum_test::TestServer;
 use common::fixtures::{create_document_request, update_document_request};
 use common::TEST_SOURCE_ID;
 use omni_indexer::{BulkDocumentOperation, BulkDocumentRequest, QueueProcessor};
+use pgvector::Vector;
 use serde_json::{json, Value};
-use shared::db::repositories::DocumentRepository;
-use shared::models::{ConnectorEvent, Document, DocumentMetadata, DocumentPermissions};
+use shared::db::repositories::{
+    AcronymInput, AcronymRepository, AcronymSource, AcronymStatus, AnchorKind,
+    DocumentAnchorRepository, DocumentLinkRepository, DocumentPreviewRepository,
+    DocumentRelationshipRepository, DocumentRepository, EmbeddingRepository, LinkKind,
+    PersonRepository, RecommendationReason, RecommendationRepository, UserRepository,
+};
+use shared::models::{
+    AttributeFilter, ConnectorEvent, Document, DocumentMetadata, DocumentParent,
+    DocumentPermissions, DocumentThumbnail, Embedding, PermissionOverrides, PreviewKind,
+    RelationshipType,
+};
 use shared::queue::EventQueue;
 use sqlx::types::time::OffsetDateTime;
 use std::collections::HashMap;
@@ -55,6 +65,8 @@ async fn test_event_driven_document_lifecycle() {
             url: Some("https://example.com/docs/report.pdf".to_string()),
             path: Some("/docs/lifecycle_document".to_string()),
             extra: Some(HashMap::from([("category".to_string(), json!("test"))])),
+            thumbnail: None,
+            parent: None,
         },
         permissions: DocumentPermissions {
             public: false,
@@ -135,6 +147,8 @@ async fn test_event_driven_document_lifecycle() {
             url: Some("https://example.com/docs/report-v2.md".to_string()),
             path: Some("/docs/updated_lifecycle_document".to_string()),
             extra: Some(HashMap::new()),
+            thumbnail: None,
+            parent: None,
         },
         permissions: Some(DocumentPermissions {
             public: true,
@@ -242,6 +256,8 @@ async fn test_batch_processing_and_deduplication() {
                 url: None,
                 path: None,
                 extra: None,
+                thumbnail: None,
+                parent: None,
             },
             permissions: DocumentPermissions {
                 public: true,
@@ -345,6 +361,8 @@ async fn test_recovery_and_dead_letter() {
             url: None,
             path: None,
             extra: None,
+            thumbnail: None,
+            parent: None,
         },
         permissions: DocumentPermissions {
             public: false,
@@ -419,6 +437,8 @@ async fn test_recovery_and_dead_letter() {
             url: None,
             path: None,
             extra: None,
+            thumbnail: None,
+            parent: None,
         },
         permissions: DocumentPermissions {
             public: true,
@@ -481,6 +501,7 @@ async fn test_recovery_and_dead_letter() {
         created_at: OffsetDateTime::now_utc(),
         updated_at: OffsetDateTime::now_utc(),
         last_indexed_at: OffsetDateTime::now_utc(),
+        preview: None,
     };
 
     repo.create(embed_doc).await.unwrap();
@@ -661,3 +682,2224 @@ async fn test_api_document_operations() {
     let get_deleted = server.get(&format!("/documents/{}", doc2.id)).await;
     assert_eq!(get_deleted.status_code(), StatusCode::NOT_FOUND);
 }
+
+#[tokio::test]
+async fn test_indexing_lag_per_source() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = TestServer::new(fixture.app().clone()).unwrap();
+
+    // Serialize through DocumentMetadata so updated_at uses the same format connectors emit
+    let source_updated_at = OffsetDateTime::now_utc() - Duration::from_secs(120);
+    let metadata = serde_json::to_value(DocumentMetadata {
+        title: None,
+        author: None,
+        created_at: None,
+        updated_at: Some(source_updated_at),
+        mime_type: None,
+        size: None,
+        url: None,
+        path: None,
+        extra: None,
+        thumbnail: None,
+        parent: None,
+    })
+    .unwrap();
+
+    let mut request = create_document_request();
+    request.metadata = metadata.clone();
+    let response = server.post("/documents").json(&request).await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let created_doc: Document = response.json();
+
+    let response = server.get("/admin/indexing-lag").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body: Value = response.json();
+    assert_eq!(body["window_hours"], 24);
+
+    let sources = body["sources"].as_array().unwrap();
+    assert_eq!(sources.len(), 1);
+    assert_eq!(sources[0]["source_id"], TEST_SOURCE_ID);
+    assert_eq!(sources[0]["documents"], 1);
+    let p95 = sources[0]["p95_seconds"].as_f64().unwrap();
+    assert!((120.0..180.0).contains(&p95), "unexpected p95 lag {p95}");
+
+    // Re-indexing the same source version must not reset the lag measurement
+    let (first_indexed_at,): (OffsetDateTime,) =
+        sqlx::query_as("SELECT source_indexed_at FROM documents WHERE id = $1")
+            .bind(&created_doc.id)
+            .fetch_one(fixture.state.db_pool.pool())
+            .await
+            .unwrap();
+
+    let response = server
+        .put(&format!("/documents/{}", created_doc.id))
+        .json(&json!({ "title": "Renamed", "metadata": metadata }))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+
+    let (reindexed_at,): (OffsetDateTime,) =
+        sqlx::query_as("SELECT source_indexed_at FROM documents WHERE id = $1")
+            .bind(&created_doc.id)
+            .fetch_one(fixture.state.db_pool.pool())
+            .await
+            .unwrap();
+    assert_eq!(reindexed_at, first_indexed_at);
+
+    let response = server.get("/admin/indexing-lag?hours=0").await;
+    let body: Value = response.json();
+    assert_eq!(body["window_hours"], 1);
+}
+
+#[tokio::test]
+async fn test_data_deletion_request() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = TestServer::new(fixture.app().clone()).unwrap();
+    let subject = "alice@example.com";
+
+    // Authored by the subject
+    let mut authored = create_document_request();
+    authored.external_id = "authored".to_string();
+    authored.metadata = json!({ "author": "Alice@Example.com" });
+    let authored: Document = server.post("/documents").json(&authored).await.json();
+
+    // Only the subject may see it
+    let mut private = create_document_request();
+    private.external_id = "private".to_string();
+    private.permissions = json!({ "public": false, "users": [subject], "groups": [] });
+    let private: Document = server.post("/documents").json(&private).await.json();
+
+    // Shared with someone else, so it must be kept
+    let mut shared_doc = create_document_request();
+    shared_doc.external_id = "shared".to_string();
+    shared_doc.permissions =
+        json!({ "public": false, "users": [subject, "bob@example.com"], "groups": [] });
+    let shared_doc: Document = server.post("/documents").json(&shared_doc).await.json();
+
+    let response = server
+        .post("/admin/data-deletions")
+        .json(&json!({ "email": subject }))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::ACCEPTED);
+    let request_id = response.json::<Value>()["id"].as_str().unwrap().to_string();
+
+    let mut deletion = Value::Null;
+    for _ in 0..50 {
+        deletion = server
+            .get(&format!("/admin/data-deletions/{}", request_id))
+            .await
+            .json();
+        if deletion["status"] == "completed" || deletion["status"] == "failed" {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(deletion["status"], "completed", "{deletion}");
+
+    let report = &deletion["report"];
+    assert_eq!(report["subject_email"], subject);
+    assert_eq!(report["documents_deleted"], 2);
+    let deleted_ids: Vec<&str> = report["document_ids"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|id| id.as_str().unwrap())
+        .collect();
+    assert!(deleted_ids.contains(&authored.id.as_str()));
+    assert!(deleted_ids.contains(&private.id.as_str()));
+
+    let signature = deletion["signature"].as_str().unwrap();
+    let encryption = shared::EncryptionService::new().unwrap();
+    assert!(encryption.verify_signature(&serde_json::to_vec(report).unwrap(), signature));
+
+    for id in [&authored.id, &private.id] {
+        let response = server.get(&format!("/documents/{}", id)).await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+    }
+    let response = server.get(&format!("/documents/{}", shared_doc.id)).await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+
+    let response = server
+        .post("/admin/data-deletions")
+        .json(&json!({ "email": "" }))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_acronym_mining() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = TestServer::new(fixture.app().clone()).unwrap();
+    let pool = fixture.state.db_pool.pool();
+
+    let contents = [
+        "Every customer has a Service Level Agreement (SLA). Incidents are triaged by the \
+         on-call team (OCT) within an hour.",
+        "The Service Level Agreement (SLA) covers uptime. See the FAQ (FAQ) for details, \
+         support is available (24x7).",
+    ];
+    for (i, content) in contents.iter().enumerate() {
+        let mut request = create_document_request();
+        request.external_id = format!("acronyms-{}", i);
+        let document: Document = server.post("/documents").json(&request).await.json();
+        sqlx::query("UPDATE documents SET content = $2 WHERE id = $1")
+            .bind(&document.id)
+            .bind(content)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    let response = server.post("/admin/acronyms/mine").json(&json!({})).await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let result: Value = response.json();
+    assert_eq!(result["documents_scanned"], 2);
+    // OCT is only defined in one document
+    assert_eq!(result["candidates"], 1);
+    assert_eq!(result["added"], 1);
+
+    let repo = AcronymRepository::new(pool);
+    let acronyms = repo.find_all(None).await.unwrap();
+    assert_eq!(acronyms.len(), 1);
+    let sla = &acronyms[0];
+    assert_eq!(sla.acronym, "SLA");
+    assert_eq!(sla.expansion, "Service Level Agreement");
+    assert_eq!(sla.status, AcronymStatus::Pending);
+    assert_eq!(sla.source, AcronymSource::Mined);
+    assert_eq!((sla.occurrences, sla.document_count), (2, 2));
+
+    // Reviewed entries keep their status when mined again
+    repo.update(
+        &sla.id,
+        &AcronymInput {
+            acronym: sla.acronym.clone(),
+            expansion: sla.expansion.clone(),
+            status: AcronymStatus::Active,
+        },
+    )
+    .await
+    .unwrap();
+
+    let response = server
+        .post("/admin/acronyms/mine")
+        .json(&json!({ "min_documents": 1 }))
+        .await;
+    let result: Value = response.json();
+    assert_eq!(result["candidates"], 2);
+    assert_eq!(result["added"], 1);
+
+    let acronyms = repo.find_all(Some(AcronymStatus::Pending)).await.unwrap();
+    assert_eq!(acronyms.len(), 1);
+    assert_eq!(acronyms[0].acronym, "OCT");
+    assert_eq!(acronyms[0].expansion, "on-call team");
+    let sla = repo.find_by_id(&sla.id).await.unwrap().unwrap();
+    assert_eq!(sla.status, AcronymStatus::Active);
+}
+
+#[tokio::test]
+async fn test_document_anchors() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let event_queue = EventQueue::new(fixture.state.db_pool.pool().clone());
+    let repo = DocumentRepository::new(fixture.state.db_pool.pool());
+
+    let processor = QueueProcessor::new(fixture.state.clone()).with_accumulation_config(
+        Duration::from_millis(200),
+        Duration::from_secs(30),
+        Duration::from_millis(50),
+    );
+    let processor_handle = tokio::spawn(async move {
+        let _ = processor.start().await;
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let content = "# Weekly Sync\n\n## Summary\nRoadmap review ✓\n\n```\n# not a heading\n```\n\n\
+                   ## Transcript\n[00:05] Ana: Hello\n[75:03] Ben: Wrapping up\n[1:15:10] Ana: Bye\n";
+    let content_id = fixture
+        .state
+        .content_storage
+        .store_content(content.as_bytes(), None)
+        .await
+        .unwrap();
+    let event = ConnectorEvent::DocumentCreated {
+        sync_run_id: "sync_anchors".to_string(),
+        source_id: TEST_SOURCE_ID.to_string(),
+        document_id: "anchors_doc".to_string(),
+        content_id,
+        metadata: DocumentMetadata {
+            title: Some("Weekly Sync".to_string()),
+            ..Default::default()
+        },
+        permissions: DocumentPermissions {
+            public: true,
+            users: vec![],
+            groups: vec![],
+        },
+        attributes: None,
+    };
+    event_queue.enqueue(TEST_SOURCE_ID, &event).await.unwrap();
+
+    let document = common::wait_for_document_exists(
+        &repo,
+        TEST_SOURCE_ID,
+        "anchors_doc",
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("Document should be created");
+    common::wait_for_completed(fixture.state.db_pool.pool(), 1, Duration::from_secs(5)).await;
+
+    let anchors = DocumentAnchorRepository::new(fixture.state.db_pool.pool())
+        .find_by_document_id(&document.id)
+        .await
+        .unwrap();
+    let summary: Vec<(i32, AnchorKind, &str, Option<i16>, Option<i32>)> = anchors
+        .iter()
+        .map(|a| (a.start_offset, a.kind, a.label.as_str(), a.level, a.seconds))
+        .collect();
+    // Offsets count characters, so the check mark counts once
+    assert_eq!(
+        summary,
+        vec![
+            (0, AnchorKind::Heading, "Weekly Sync", Some(1), None),
+            (15, AnchorKind::Heading, "Summary", Some(2), None),
+            (69, AnchorKind::Heading, "Transcript", Some(2), None),
+            (83, AnchorKind::Timestamp, "00:05", None, Some(5)),
+            (102, AnchorKind::Timestamp, "75:03", None, Some(4503)),
+            (127, AnchorKind::Timestamp, "1:15:10", None, Some(4510)),
+        ]
+    );
+
+    processor_handle.abort();
+}
+
+#[tokio::test]
+async fn test_document_previews() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let pool = fixture.state.db_pool.pool();
+    let event_queue = EventQueue::new(pool.clone());
+    let repo = DocumentRepository::new(pool);
+    sqlx::query("UPDATE sources SET source_type = 'confluence' WHERE id = $1")
+        .bind(TEST_SOURCE_ID)
+        .execute(pool)
+        .await
+        .unwrap();
+
+    let processor = QueueProcessor::new(fixture.state.clone()).with_accumulation_config(
+        Duration::from_millis(200),
+        Duration::from_secs(30),
+        Duration::from_millis(50),
+    );
+    let processor_handle = tokio::spawn(async move {
+        let _ = processor.start().await;
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let storage = &fixture.state.content_storage;
+    let thumbnail_id = storage
+        .store_content_with_type(b"\x89PNG thumbnail", Some("image/png"), None)
+        .await
+        .unwrap();
+    let documents = [
+        (
+            "thumbnail_doc",
+            "Design Review",
+            Some(DocumentThumbnail {
+                content_id: thumbnail_id.clone(),
+                mime_type: "image/png".to_string(),
+            }),
+        ),
+        ("render_doc", "Q3 Plan <draft> & notes", None),
+    ];
+    for (document_id, title, thumbnail) in &documents {
+        let content = "# Goals\n\nShip the onboarding flow & measure activation.\n";
+        let content_id = storage
+            .store_content(content.as_bytes(), None)
+            .await
+            .unwrap();
+        let event = ConnectorEvent::DocumentCreated {
+            sync_run_id: "sync_previews".to_string(),
+            source_id: TEST_SOURCE_ID.to_string(),
+            document_id: document_id.to_string(),
+            content_id,
+            metadata: DocumentMetadata {
+                title: Some(title.to_string()),
+                thumbnail: thumbnail.clone(),
+                parent: None,
+                ..Default::default()
+            },
+            permissions: DocumentPermissions {
+                public: true,
+                users: vec![],
+                groups: vec![],
+            },
+            attributes: None,
+        };
+        event_queue.enqueue(TEST_SOURCE_ID, &event).await.unwrap();
+    }
+
+    let mut document_ids = Vec::new();
+    for (document_id, _, _) in documents {
+        let document = common::wait_for_document_exists(
+            &repo,
+            TEST_SOURCE_ID,
+            document_id,
+            Duration::from_secs(5),
+        )
+        .await
+        .expect("Document should be created");
+        document_ids.push(document.id);
+    }
+    common::wait_for_completed(pool, 2, Duration::from_secs(5)).await;
+
+    let preview_repo = DocumentPreviewRepository::new(pool);
+    let thumbnail = preview_repo
+        .find_by_document_id(&document_ids[0])
+        .await
+        .unwrap()
+        .expect("Thumbnail should be the preview");
+    assert_eq!(thumbnail.kind, PreviewKind::Thumbnail);
+    assert_eq!(thumbnail.content_id, thumbnail_id);
+    assert_eq!(thumbnail.mime_type, "image/png");
+
+    let render = preview_repo
+        .find_by_document_id(&document_ids[1])
+        .await
+        .unwrap()
+        .expect("Confluence page should get a rendered preview");
+    assert_eq!(render.kind, PreviewKind::Render);
+    assert_eq!(render.mime_type, "image/svg+xml");
+    let svg = String::from_utf8(storage.get_content(&render.content_id).await.unwrap()).unwrap();
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.contains("Q3 Plan &lt;draft&gt; &amp; notes"));
+    assert!(svg.contains(">Goals<"));
+    assert!(svg.contains("Ship the onboarding flow &amp; measure activation."));
+
+    processor_handle.abort();
+}
+
+#[tokio::test]
+async fn test_document_permission_overrides() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = TestServer::new(fixture.app().clone()).unwrap();
+    let pool = fixture.state.db_pool.pool();
+    let owner = "owner@example.com";
+
+    let users = UserRepository::new(pool);
+    users.provision(owner, None, None, true).await.unwrap();
+    users
+        .provision("someone@example.com", None, None, true)
+        .await
+        .unwrap();
+    let admin = users
+        .provision("admin@example.com", None, None, true)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE users SET role = 'admin' WHERE id = $1")
+        .bind(&admin.id)
+        .execute(pool)
+        .await
+        .unwrap();
+
+    let mut request = create_document_request();
+    request.metadata = json!({ "author": "Owner@Example.com" });
+    request.permissions = json!({ "public": false, "users": [owner], "groups": [] });
+    let document: Document = server.post("/documents").json(&request).await.json();
+    let path = format!("/documents/{}/permissions", document.id);
+
+    // Neither the owner nor an admin
+    let response = server
+        .put(&path)
+        .json(&json!({ "requested_by": "someone@example.com", "public": true }))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+
+    let response = server
+        .put(&path)
+        .json(&json!({
+            "requested_by": owner,
+            "users": ["carol@example.com"],
+            "groups": ["engineering"]
+        }))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let state: Value = response.json();
+    assert_eq!(
+        state["permissions"],
+        json!({
+            "public": false,
+            "users": ["carol@example.com", owner],
+            "groups": ["engineering"]
+        })
+    );
+
+    // A sync replacing the connector permissions keeps the overrides
+    let updated: Document = server
+        .put(&format!("/documents/{}", document.id))
+        .json(&json!({
+            "permissions": { "public": false, "users": ["dave@example.com"], "groups": [] }
+        }))
+        .await
+        .json();
+    assert_eq!(
+        updated.permissions,
+        json!({
+            "public": false,
+            "users": ["carol@example.com", "dave@example.com"],
+            "groups": ["engineering"]
+        })
+    );
+
+    // As does an update that leaves the permissions alone
+    let updated: Document = server
+        .put(&format!("/documents/{}", document.id))
+        .json(&json!({ "title": "Renamed" }))
+        .await
+        .json();
+    assert_eq!(
+        updated.permissions["users"],
+        json!(["carol@example.com", "dave@example.com"])
+    );
+
+    let state: Value = server
+        .put(&path)
+        .json(&json!({ "requested_by": "admin@example.com", "hidden": true }))
+        .await
+        .json();
+    assert_eq!(
+        state["permissions"],
+        json!({ "public": false, "users": [], "groups": [], "hidden": true })
+    );
+    assert_eq!(
+        state["source_permissions"],
+        json!({ "public": false, "users": ["dave@example.com"], "groups": [] })
+    );
+
+    let response = server
+        .delete(&path)
+        .add_query_param("requested_by", "admin@example.com")
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let document: Document = server
+        .get(&format!("/documents/{}", document.id))
+        .await
+        .json();
+    assert_eq!(
+        document.permissions,
+        json!({ "public": false, "users": ["dave@example.com"], "groups": [] })
+    );
+
+    let response = server
+        .get("/documents/missing/permissions")
+        .add_query_param("requested_by", "admin@example.com")
+        .await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_permissions_updated_event() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let event_queue = EventQueue::new(fixture.state.db_pool.pool().clone());
+    let pool = fixture.state.db_pool.pool();
+    let repo = DocumentRepository::new(pool);
+
+    let processor = QueueProcessor::new(fixture.state.clone()).with_accumulation_config(
+        Duration::from_millis(200),
+        Duration::from_secs(30),
+        Duration::from_millis(50),
+    );
+    let processor_handle = tokio::spawn(async move {
+        let _ = processor.start().await;
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let doc_id = "shared_doc_1";
+    let content_id = fixture
+        .state
+        .content_storage
+        .store_content(b"Quarterly planning notes", None)
+        .await
+        .unwrap();
+
+    let create_event = ConnectorEvent::DocumentCreated {
+        sync_run_id: "sync_permissions".to_string(),
+        source_id: TEST_SOURCE_ID.to_string(),
+        document_id: doc_id.to_string(),
+        content_id: content_id.clone(),
+        metadata: DocumentMetadata {
+            title: Some("Planning Notes".to_string()),
+            ..Default::default()
+        },
+        permissions: DocumentPermissions {
+            public: false,
+            users: vec!["alice@example.com".to_string()],
+            groups: vec![],
+        },
+        attributes: None,
+    };
+    event_queue
+        .enqueue(TEST_SOURCE_ID, &create_event)
+        .await
+        .unwrap();
+    let document =
+        common::wait_for_document_exists(&repo, TEST_SOURCE_ID, doc_id, Duration::from_secs(5))
+            .await
+            .expect("Document should be created");
+
+    let permissions_event = ConnectorEvent::PermissionsUpdated {
+        sync_run_id: "sync_permissions".to_string(),
+        source_id: TEST_SOURCE_ID.to_string(),
+        document_id: doc_id.to_string(),
+        permissions: DocumentPermissions {
+            public: false,
+            users: vec![
+                "alice@example.com".to_string(),
+                "bob@example.com".to_string(),
+            ],
+            groups: vec!["engineering".to_string()],
+        },
+    };
+    event_queue
+        .enqueue(TEST_SOURCE_ID, &permissions_event)
+        .await
+        .unwrap();
+    assert_eq!(
+        common::wait_for_completed(pool, 2, Duration::from_secs(5)).await,
+        2
+    );
+
+    // Only the permissions change, the content and metadata stay as they were
+    let updated = repo
+        .find_by_external_id(TEST_SOURCE_ID, doc_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(updated.id, document.id);
+    assert_eq!(updated.title, "Planning Notes");
+    assert_eq!(updated.content_id, Some(content_id));
+    assert_eq!(
+        updated.permissions,
+        json!({
+            "public": false,
+            "users": ["alice@example.com", "bob@example.com"],
+            "groups": ["engineering"]
+        })
+    );
+
+    // Permission changes for documents that were never indexed are acknowledged and dropped
+    let unknown_event = ConnectorEvent::PermissionsUpdated {
+        sync_run_id: "sync_permissions".to_string(),
+        source_id: TEST_SOURCE_ID.to_string(),
+        document_id: "never_indexed".to_string(),
+        permissions: DocumentPermissions {
+            public: true,
+            users: vec![],
+            groups: vec![],
+        },
+    };
+    event_queue
+        .enqueue(TEST_SOURCE_ID, &unknown_event)
+        .await
+        .unwrap();
+    assert_eq!(
+        common::wait_for_completed(pool, 3, Duration::from_secs(5)).await,
+        3
+    );
+    assert!(repo
+        .find_by_external_id(TEST_SOURCE_ID, "never_indexed")
+        .await
+        .unwrap()
+        .is_none());
+
+    processor_handle.abort();
+}
+
+#[tokio::test]
+async fn test_document_updates_keep_connector_permissions() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let event_queue = EventQueue::new(fixture.state.db_pool.pool().clone());
+    let pool = fixture.state.db_pool.pool();
+    let repo = DocumentRepository::new(pool);
+
+    let processor = QueueProcessor::new(fixture.state.clone()).with_accumulation_config(
+        Duration::from_millis(200),
+        Duration::from_secs(30),
+        Duration::from_millis(50),
+    );
+    let processor_handle = tokio::spawn(async move {
+        let _ = processor.start().await;
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let doc_id = "overridden_doc";
+    let connector_permissions = json!({
+        "public": false,
+        "users": ["alice@example.com"],
+        "groups": []
+    });
+    let content_id = fixture
+        .state
+        .content_storage
+        .store_content(b"Incident review", None)
+        .await
+        .unwrap();
+    let create_event = ConnectorEvent::DocumentCreated {
+        sync_run_id: "sync_overrides".to_string(),
+        source_id: TEST_SOURCE_ID.to_string(),
+        document_id: doc_id.to_string(),
+        content_id: content_id.clone(),
+        metadata: DocumentMetadata {
+            title: Some("Incident Review".to_string()),
+            ..Default::default()
+        },
+        permissions: DocumentPermissions {
+            public: false,
+            users: vec!["alice@example.com".to_string()],
+            groups: vec![],
+        },
+        attributes: None,
+    };
+    event_queue
+        .enqueue(TEST_SOURCE_ID, &create_event)
+        .await
+        .unwrap();
+    let document =
+        common::wait_for_document_exists(&repo, TEST_SOURCE_ID, doc_id, Duration::from_secs(5))
+            .await
+            .expect("Document should be created");
+
+    let overrides = [
+        PermissionOverrides {
+            users: vec!["carol@example.com".to_string()],
+            ..Default::default()
+        },
+        PermissionOverrides {
+            hidden: true,
+            ..Default::default()
+        },
+    ];
+    for (i, override_) in overrides.iter().enumerate() {
+        repo.set_permission_overrides(&document.id, Some(override_))
+            .await
+            .unwrap();
+
+        // An update without permissions must not write the overrides into the connector ones
+        let update_event = ConnectorEvent::DocumentUpdated {
+            sync_run_id: "sync_overrides".to_string(),
+            source_id: TEST_SOURCE_ID.to_string(),
+            document_id: doc_id.to_string(),
+            content_id: content_id.clone(),
+            metadata: DocumentMetadata {
+                title: Some(format!("Incident Review v{}", i + 2)),
+                ..Default::default()
+            },
+            permissions: None,
+            attributes: None,
+        };
+        event_queue
+            .enqueue(TEST_SOURCE_ID, &update_event)
+            .await
+            .unwrap();
+        assert_eq!(
+            common::wait_for_completed(pool, i as i64 + 2, Duration::from_secs(5)).await,
+            i as i64 + 2
+        );
+
+        let state = repo
+            .set_permission_overrides(&document.id, None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            state.source_permissions,
+            Some(connector_permissions.clone())
+        );
+        assert_eq!(state.permissions, connector_permissions);
+    }
+
+    processor_handle.abort();
+}
+
+#[tokio::test]
+async fn test_bulk_document_writes() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let repo = DocumentRepository::new(fixture.state.db_pool.pool());
+
+    let document = |external_id: &str, title: &str| Document {
+        id: shared::utils::generate_ulid(),
+        source_id: TEST_SOURCE_ID.to_string(),
+        external_id: external_id.to_string(),
+        title: title.to_string(),
+        content_id: None,
+        content_type: Some("text/plain".to_string()),
+        file_size: None,
+        file_extension: None,
+        url: None,
+        metadata: json!({}),
+        permissions: json!({"public": true, "users": [], "groups": []}),
+        attributes: json!({}),
+        created_at: OffsetDateTime::now_utc(),
+        updated_at: OffsetDateTime::now_utc(),
+        last_indexed_at: OffsetDateTime::now_utc(),
+        preview: None,
+    };
+
+    // A document listed twice is written once, with its last entry
+    let upserted = repo
+        .bulk_upsert(vec![
+            (document("bulk_a", "A v1"), "first".to_string()),
+            (document("bulk_b", "B"), "second".to_string()),
+            (document("bulk_a", "A v2"), "third".to_string()),
+        ])
+        .await
+        .unwrap();
+    assert_eq!(upserted.len(), 2);
+
+    let external_ids = vec![
+        "bulk_a".to_string(),
+        "bulk_b".to_string(),
+        "bulk_missing".to_string(),
+    ];
+    let found = repo
+        .find_by_external_ids(TEST_SOURCE_ID, &external_ids)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 2);
+    let a = found.iter().find(|d| d.external_id == "bulk_a").unwrap();
+    assert_eq!(a.title, "A v2");
+
+    // Upserting an existing document keeps its id
+    let upserted = repo
+        .bulk_upsert(vec![(document("bulk_a", "A v3"), "fourth".to_string())])
+        .await
+        .unwrap();
+    assert_eq!(upserted[0].id, a.id);
+    assert_eq!(upserted[0].title, "A v3");
+
+    let mut deleted = repo
+        .bulk_delete_by_external_ids(TEST_SOURCE_ID, &external_ids)
+        .await
+        .unwrap();
+    deleted.sort();
+    let mut expected: Vec<String> = found.iter().map(|d| d.id.clone()).collect();
+    expected.sort();
+    assert_eq!(deleted, expected);
+    assert!(repo
+        .find_by_external_ids(TEST_SOURCE_ID, &external_ids)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_attribute_cardinality_and_filters() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = TestServer::new(fixture.app().clone()).unwrap();
+    let repo = DocumentRepository::new(fixture.state.db_pool.pool());
+
+    let document = |external_id: &str, attributes: Value| Document {
+        id: shared::utils::generate_ulid(),
+        source_id: TEST_SOURCE_ID.to_string(),
+        external_id: external_id.to_string(),
+        title: external_id.to_string(),
+        content_id: None,
+        content_type: None,
+        file_size: None,
+        file_extension: None,
+        url: None,
+        metadata: json!({}),
+        permissions: json!({"public": true, "users": [], "groups": []}),
+        attributes,
+        created_at: OffsetDateTime::now_utc(),
+        updated_at: OffsetDateTime::now_utc(),
+        last_indexed_at: OffsetDateTime::now_utc(),
+        preview: None,
+    };
+    repo.bulk_upsert(vec![
+        (
+            document("issue_1", json!({"status": "open", "points": 3})),
+            String::new(),
+        ),
+        (
+            document("issue_2", json!({"status": "closed", "points": 8})),
+            String::new(),
+        ),
+        (
+            document("issue_3", json!({"status": "open", "team": "core"})),
+            String::new(),
+        ),
+    ])
+    .await
+    .unwrap();
+
+    let response = server.get("/admin/attribute-cardinality").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body: Value = response.json();
+    assert_eq!(body["total_documents"], 3);
+    assert_eq!(
+        body["attributes"],
+        json!([
+            {"key": "status", "documents": 3, "distinct_values": 2},
+            {"key": "points", "documents": 2, "distinct_values": 2},
+            {"key": "team", "documents": 1, "distinct_values": 1}
+        ])
+    );
+
+    // Built directly: untagged deserialization would read every filter as an exact match
+    let find = |filters: Vec<(&str, AttributeFilter)>| {
+        let filters: HashMap<String, AttributeFilter> = filters
+            .into_iter()
+            .map(|(key, filter)| (key.to_string(), filter))
+            .collect();
+        let repo = &repo;
+        async move {
+            let mut ids: Vec<String> = repo
+                .find_by_attributes(Some(TEST_SOURCE_ID), &filters, 10)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|d| d.external_id)
+                .collect();
+            ids.sort();
+            ids
+        }
+    };
+    assert_eq!(
+        find(vec![("status", AttributeFilter::Exact(json!("open")))]).await,
+        vec!["issue_1", "issue_3"]
+    );
+    assert_eq!(
+        find(vec![
+            (
+                "status",
+                AttributeFilter::AnyOf(vec![json!("closed"), json!("open")])
+            ),
+            (
+                "points",
+                AttributeFilter::Range {
+                    gte: Some(json!(5)),
+                    lte: None
+                }
+            ),
+        ])
+        .await,
+        vec!["issue_2"]
+    );
+}
+
+#[tokio::test]
+async fn test_reembed_documents() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = TestServer::new(fixture.app().clone()).unwrap();
+    let pool = fixture.state.db_pool.pool();
+
+    let response = server
+        .post("/documents")
+        .json(&create_document_request())
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let created_doc: Document = response.json();
+    sqlx::query("DELETE FROM embedding_queue")
+        .execute(pool)
+        .await
+        .unwrap();
+
+    let response = server
+        .post("/admin/reembed")
+        .json(&json!({ "source_id": "some-other-source" }))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    assert_eq!(response.json::<Value>()["queued"], 0);
+
+    let response = server
+        .post("/admin/reembed")
+        .json(&json!({ "source_id": TEST_SOURCE_ID }))
+        .await;
+    assert_eq!(response.json::<Value>()["queued"], 1);
+
+    let (status,): (String,) =
+        sqlx::query_as("SELECT embedding_status FROM documents WHERE id = $1")
+            .bind(&created_doc.id)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+    assert_eq!(status, "pending");
+
+    // Documents already waiting for an embedding are not queued twice
+    let response = server.post("/admin/reembed").json(&json!({})).await;
+    assert_eq!(response.json::<Value>()["queued"], 0);
+}
+
+async fn wait_for_index_snapshot(server: &TestServer, id: &str) -> Value {
+    let mut snapshot = Value::Null;
+    for _ in 0..50 {
+        snapshot = server.get(&format!("/admin/snapshots/{}", id)).await.json();
+        if snapshot["status"] == "completed" || snapshot["status"] == "failed" {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    snapshot
+}
+
+#[tokio::test]
+async fn test_index_snapshot_and_restore() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = TestServer::new(fixture.app().clone()).unwrap();
+    let pool = fixture.state.db_pool.pool();
+
+    let embedded: Document = server
+        .post("/documents")
+        .json(&create_document_request())
+        .await
+        .json();
+    EmbeddingRepository::new(pool)
+        .create(Embedding {
+            id: "01JGF7V3E0Y2R1X8P5Q7W9T4E1".to_string(),
+            document_id: embedded.id.clone(),
+            chunk_index: 0,
+            chunk_start_offset: 0,
+            chunk_end_offset: 20,
+            content_sha256: None,
+            embedding: Vector::from(vec![0.1, 0.2, 0.3]),
+            model_name: "test-model".to_string(),
+            dimensions: 3,
+            created_at: OffsetDateTime::now_utc(),
+        })
+        .await
+        .unwrap();
+
+    let mut request = create_document_request();
+    request.external_id = "not-embedded".to_string();
+    let not_embedded: Document = server.post("/documents").json(&request).await.json();
+
+    let response = server
+        .post("/admin/snapshots")
+        .json(&json!({ "source_id": TEST_SOURCE_ID }))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::ACCEPTED);
+    let snapshot_id = response.json::<Value>()["id"].as_str().unwrap().to_string();
+
+    let snapshot = wait_for_index_snapshot(&server, &snapshot_id).await;
+    assert_eq!(snapshot["status"], "completed", "{snapshot}");
+    assert_eq!(snapshot["kind"], "snapshot");
+    assert_eq!(snapshot["document_count"], 2);
+    assert_eq!(snapshot["embedding_count"], 1);
+    assert_eq!(snapshot["content_blob_count"], 2);
+
+    // Lose the index, then restore it from the snapshot
+    for id in [&embedded.id, &not_embedded.id] {
+        server.delete(&format!("/documents/{}", id)).await;
+    }
+    sqlx::query("DELETE FROM embedding_queue")
+        .execute(pool)
+        .await
+        .unwrap();
+
+    let response = server
+        .post(&format!("/admin/snapshots/{}/restore", snapshot_id))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::ACCEPTED);
+    let restore_id = response.json::<Value>()["id"].as_str().unwrap().to_string();
+
+    let restore = wait_for_index_snapshot(&server, &restore_id).await;
+    assert_eq!(restore["status"], "completed", "{restore}");
+    assert_eq!(restore["kind"], "restore");
+    assert_eq!(restore["snapshot_id"], snapshot_id.as_str());
+    assert_eq!(restore["document_count"], 2);
+
+    let restored: Document = server
+        .get(&format!("/documents/{}", embedded.id))
+        .await
+        .json();
+    assert_eq!(restored.title, embedded.title);
+    assert_eq!(restored.permissions, embedded.permissions);
+    let content = fixture
+        .state
+        .content_storage
+        .get_text(restored.content_id.as_deref().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(content, create_document_request().content);
+
+    let embeddings = EmbeddingRepository::new(pool)
+        .find_by_document_id(&embedded.id)
+        .await
+        .unwrap();
+    assert_eq!(embeddings.len(), 1);
+    assert_eq!(embeddings[0].embedding.as_slice(), &[0.1, 0.2, 0.3]);
+
+    // Documents without embeddings are queued to be embedded
+    let (queued,): (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM embedding_queue WHERE document_id = $1")
+            .bind(&not_embedded.id)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+    assert_eq!(queued, 1);
+
+    let snapshots: Vec<Value> = server.get("/admin/snapshots").await.json();
+    assert_eq!(snapshots.len(), 2);
+    assert_eq!(snapshots[0]["id"], restore_id.as_str());
+
+    let response = server
+        .post("/admin/snapshots/01JGF7V3E0Y2R1X8P5Q7W9T4ZZ/restore")
+        .await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+
+    let response = server
+        .post(&format!("/admin/snapshots/{}/restore", snapshot_id))
+        .json(&json!({ "source_id": "some-other-source" }))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_embedding_model_migration() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = TestServer::new(fixture.app().clone()).unwrap();
+    let pool = fixture.state.db_pool.pool();
+
+    sqlx::query("INSERT INTO configuration (key, value) VALUES ('embedding_config', $1)")
+        .bind(json!({ "provider": "local", "model": "old-model", "apiUrl": "http://ai" }))
+        .execute(pool)
+        .await
+        .unwrap();
+    let document: Document = server
+        .post("/documents")
+        .json(&create_document_request())
+        .await
+        .json();
+    for (id, model_name) in [
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4E1", "old-model"),
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4E2", "new-model"),
+    ] {
+        EmbeddingRepository::new(pool)
+            .create(Embedding {
+                id: id.to_string(),
+                document_id: document.id.clone(),
+                chunk_index: 0,
+                chunk_start_offset: 0,
+                chunk_end_offset: 20,
+                content_sha256: None,
+                embedding: Vector::from(vec![0.1, 0.2, 0.3]),
+                model_name: model_name.to_string(),
+                dimensions: 3,
+                created_at: OffsetDateTime::now_utc(),
+            })
+            .await
+            .unwrap();
+    }
+
+    let target = json!({ "provider": "openai", "model": "new-model", "apiKey": "sk-test" });
+    let response = server
+        .post("/admin/embedding-migration")
+        .json(&json!({ "target": { "provider": "local", "model": "old-model" } }))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+    let response = server
+        .post("/admin/embedding-migration")
+        .json(&json!({ "target": target, "shadow_search_percentage": 25.0 }))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::CREATED);
+    let migration: Value = response.json();
+    assert_eq!(migration["shadowSearchPercentage"], 25.0);
+    assert!(migration["target"]["apiKey"].is_null());
+
+    let response = server
+        .post("/admin/embedding-migration")
+        .json(&json!({ "target": target }))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+    let status: Value = server.get("/admin/embedding-migration").await.json();
+    assert_eq!(status["active_model"], "old-model");
+    assert_eq!(status["total_documents"], 1);
+    assert_eq!(status["coverage"].as_array().unwrap().len(), 2);
+
+    // Finalizing needs a cutover, and a cut-over migration cannot be cancelled
+    let response = server.post("/admin/embedding-migration/finalize").await;
+    assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+    let response = server
+        .patch("/admin/embedding-migration")
+        .json(&json!({ "cutover": true }))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let status: Value = server.get("/admin/embedding-migration").await.json();
+    assert_eq!(status["active_model"], "new-model");
+    let response = server.delete("/admin/embedding-migration").await;
+    assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+    let response = server.post("/admin/embedding-migration/finalize").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let status: Value = server.get("/admin/embedding-migration").await.json();
+    assert!(status["migration"].is_null());
+    assert_eq!(status["current"]["model"], "new-model");
+    assert_eq!(status["current"]["provider"], "openai");
+
+    let mut coverage = Vec::new();
+    for _ in 0..50 {
+        coverage = EmbeddingRepository::new(pool)
+            .count_documents_by_model()
+            .await
+            .unwrap();
+        if coverage.len() == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(coverage, vec![("new-model".to_string(), 1)]);
+
+    let response = server.delete("/admin/embedding-migration").await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_document_links() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let pool = fixture.state.db_pool.pool();
+    let event_queue = EventQueue::new(pool.clone());
+    let repo = DocumentRepository::new(pool);
+    let link_repo = DocumentLinkRepository::new(pool);
+
+    let processor = QueueProcessor::new(fixture.state.clone()).with_accumulation_config(
+        Duration::from_millis(200),
+        Duration::from_secs(30),
+        Duration::from_millis(50),
+    );
+    let processor_handle = tokio::spawn(async move {
+        let _ = processor.start().await;
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let enqueue = |external_id: &'static str, title: &'static str, content: &'static str| {
+        let state = fixture.state.clone();
+        let event_queue = &event_queue;
+        async move {
+            let content_id = state
+                .content_storage
+                .store_content(content.as_bytes(), None)
+                .await
+                .unwrap();
+            let event = ConnectorEvent::DocumentCreated {
+                sync_run_id: "sync_links".to_string(),
+                source_id: TEST_SOURCE_ID.to_string(),
+                document_id: external_id.to_string(),
+                content_id,
+                metadata: DocumentMetadata {
+                    title: Some(title.to_string()),
+                    ..Default::default()
+                },
+                permissions: DocumentPermissions {
+                    public: true,
+                    users: vec![],
+                    groups: vec![],
+                },
+                attributes: None,
+            };
+            event_queue.enqueue(TEST_SOURCE_ID, &event).await.unwrap();
+        }
+    };
+
+    // Links to documents not indexed yet are kept until their targets arrive
+    enqueue(
+        "runbook",
+        "Runbook",
+        "Fixes for ENG-42 (see [the design](https://acme.atlassian.net/wiki/spaces/ENG/pages/\
+         12345/Design)) and the tracker \
+         https://docs.google.com/spreadsheets/d/1AbCdEfGhIjKlMnOp/edit. Files are UTF-8.",
+    )
+    .await;
+    let runbook =
+        common::wait_for_document_exists(&repo, TEST_SOURCE_ID, "runbook", Duration::from_secs(5))
+            .await
+            .expect("Document should be created");
+    common::wait_for_completed(pool, 1, Duration::from_secs(5)).await;
+    assert!(link_repo
+        .find_outbound(&runbook.id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    enqueue(
+        "jira_issue_ENG_ENG-42",
+        "ENG-42 Flaky deploys",
+        "Deploys fail.",
+    )
+    .await;
+    enqueue(
+        "confluence_page_ENG_12345",
+        "Design",
+        "Refer back to ENG-42.",
+    )
+    .await;
+    enqueue("1AbCdEfGhIjKlMnOp", "Tracker", "Status per team.").await;
+    common::wait_for_completed(pool, 4, Duration::from_secs(5)).await;
+
+    let issue = repo
+        .find_by_external_id(TEST_SOURCE_ID, "jira_issue_ENG_ENG-42")
+        .await
+        .unwrap()
+        .unwrap();
+    let design = repo
+        .find_by_external_id(TEST_SOURCE_ID, "confluence_page_ENG_12345")
+        .await
+        .unwrap()
+        .unwrap();
+    let tracker = repo
+        .find_by_external_id(TEST_SOURCE_ID, "1AbCdEfGhIjKlMnOp")
+        .await
+        .unwrap()
+        .unwrap();
+
+    let mut outbound: Vec<(String, LinkKind)> = link_repo
+        .find_outbound(&runbook.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|link| (link.document_id, link.kind))
+        .collect();
+    outbound.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut expected = vec![
+        (issue.id.clone(), LinkKind::JiraIssue),
+        (design.id.clone(), LinkKind::ConfluencePage),
+        (tracker.id.clone(), LinkKind::DriveFile),
+    ];
+    expected.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(outbound, expected);
+
+    // The issue is linked from both the runbook and the design page
+    let counts = link_repo
+        .count_inbound(&[issue.id.clone(), runbook.id.clone()])
+        .await
+        .unwrap();
+    assert_eq!(counts.get(&issue.id), Some(&2));
+    assert_eq!(counts.get(&runbook.id), None);
+
+    processor_handle.abort();
+}
+
+#[tokio::test]
+async fn test_document_relationships() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let pool = fixture.state.db_pool.pool();
+    let event_queue = EventQueue::new(pool.clone());
+    let repo = DocumentRepository::new(pool);
+    let relationship_repo = DocumentRelationshipRepository::new(pool);
+
+    let processor = QueueProcessor::new(fixture.state.clone()).with_accumulation_config(
+        Duration::from_millis(200),
+        Duration::from_secs(30),
+        Duration::from_millis(50),
+    );
+    let processor_handle = tokio::spawn(async move {
+        let _ = processor.start().await;
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let enqueue = |external_id: &'static str, parent: Option<DocumentParent>| {
+        let state = fixture.state.clone();
+        let event_queue = &event_queue;
+        async move {
+            let content_id = state
+                .content_storage
+                .store_content(external_id.as_bytes(), None)
+                .await
+                .unwrap();
+            let event = ConnectorEvent::DocumentCreated {
+                sync_run_id: "sync_relationships".to_string(),
+                source_id: TEST_SOURCE_ID.to_string(),
+                document_id: external_id.to_string(),
+                content_id,
+                metadata: DocumentMetadata {
+                    title: Some(external_id.to_string()),
+                    parent,
+                    ..Default::default()
+                },
+                permissions: DocumentPermissions {
+                    public: true,
+                    users: vec![],
+                    groups: vec![],
+                },
+                attributes: None,
+            };
+            event_queue.enqueue(TEST_SOURCE_ID, &event).await.unwrap();
+        }
+    };
+
+    // Children indexed before their parent are related to it once it arrives
+    enqueue(
+        "budget.xlsx",
+        Some(DocumentParent {
+            external_id: "thread-1".to_string(),
+            relationship: RelationshipType::Attachment,
+        }),
+    )
+    .await;
+    let attachment = common::wait_for_document_exists(
+        &repo,
+        TEST_SOURCE_ID,
+        "budget.xlsx",
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("Document should be created");
+    common::wait_for_completed(pool, 1, Duration::from_secs(5)).await;
+    assert!(relationship_repo
+        .find_parents(std::slice::from_ref(&attachment.id))
+        .await
+        .unwrap()
+        .is_empty());
+
+    enqueue("thread-1", None).await;
+    enqueue(
+        "comment-1",
+        Some(DocumentParent {
+            external_id: "thread-1".to_string(),
+            relationship: RelationshipType::Comment,
+        }),
+    )
+    .await;
+    common::wait_for_completed(pool, 3, Duration::from_secs(5)).await;
+
+    let thread = repo
+        .find_by_external_id(TEST_SOURCE_ID, "thread-1")
+        .await
+        .unwrap()
+        .unwrap();
+    let comment = repo
+        .find_by_external_id(TEST_SOURCE_ID, "comment-1")
+        .await
+        .unwrap()
+        .unwrap();
+
+    let parents = relationship_repo
+        .find_parents(&[attachment.id.clone(), comment.id.clone(), thread.id.clone()])
+        .await
+        .unwrap();
+    assert_eq!(parents.len(), 2);
+    assert!(parents.iter().all(|r| r.parent_document_id == thread.id));
+    let children: Vec<(String, RelationshipType)> = relationship_repo
+        .find_children(&thread.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| (r.document_id, r.relationship_type))
+        .collect();
+    assert_eq!(
+        children,
+        vec![
+            (attachment.id.clone(), RelationshipType::Attachment),
+            (comment.id.clone(), RelationshipType::Comment),
+        ]
+    );
+
+    processor_handle.abort();
+}
+
+#[tokio::test]
+async fn test_people_aggregation() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = TestServer::new(fixture.app().clone()).unwrap();
+    let pool = fixture.state.db_pool.pool();
+    let repo = DocumentRepository::new(pool);
+    let person_repo = PersonRepository::new(pool);
+
+    let processor = QueueProcessor::new(fixture.state.clone()).with_accumulation_config(
+        Duration::from_millis(200),
+        Duration::from_secs(30),
+        Duration::from_millis(50),
+    );
+    let processor_handle = tokio::spawn(async move {
+        let _ = processor.start().await;
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    for (id, source_type) in [
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4M1", "gmail"),
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4M2", "fireflies"),
+    ] {
+        sqlx::query(
+            "INSERT INTO sources (id, name, source_type, config, created_by) \
+             VALUES ($1, $2, $2, '{}', '01JGF7V3E0Y2R1X8P5Q7W9T4N6')",
+        )
+        .bind(id)
+        .bind(source_type)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+    sqlx::query(
+        "INSERT INTO users (id, email, password_hash, full_name) \
+         VALUES ('01JGF7V3E0Y2R1X8P5Q7W9T4M3', 'alice@example.com', 'hash', 'Alice Smith')",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+
+    // Only the author of a local file counts, everyone on an email thread or meeting does
+    let documents = [
+        (
+            TEST_SOURCE_ID,
+            "design",
+            "Alice@Example.com",
+            vec!["bob@example.com"],
+        ),
+        (
+            "01JGF7V3E0Y2R1X8P5Q7W9T4M1",
+            "thread",
+            "Bob",
+            vec!["alice@example.com", "bob@example.com"],
+        ),
+        (
+            "01JGF7V3E0Y2R1X8P5Q7W9T4M2",
+            "standup",
+            "carol@example.com",
+            vec!["alice@example.com", "carol@example.com"],
+        ),
+    ];
+    let mut document_ids = HashMap::new();
+    for (source_id, external_id, author, users) in documents {
+        let mut request = create_document_request();
+        request.source_id = source_id.to_string();
+        request.external_id = external_id.to_string();
+        request.title = external_id.to_string();
+        request.metadata = json!({ "author": author });
+        request.permissions = json!({ "public": false, "users": users, "groups": [] });
+        let document: Document = server.post("/documents").json(&request).await.json();
+        document_ids.insert(external_id, document.id);
+    }
+
+    let response = server.post("/admin/people/aggregate").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let result: Value = response.json();
+    assert_eq!(result["people"], 3);
+    assert_eq!(result["activities"], 6);
+    assert_eq!(result["removed"], 0);
+    let people_source_id = result["source_id"].as_str().unwrap().to_string();
+
+    let alice = person_repo
+        .find_by_email("Alice@example.com")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(alice.name.as_deref(), Some("Alice Smith"));
+    assert_eq!(
+        (alice.authored_count, alice.meeting_count, alice.email_count),
+        (1, 1, 1)
+    );
+
+    let profile = common::wait_for_document_exists(
+        &repo,
+        &people_source_id,
+        "alice@example.com",
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("Profile should be indexed");
+    assert_eq!(profile.title, "Alice Smith");
+    assert_eq!(profile.content_type.as_deref(), Some("people"));
+
+    // Collaborators only count the documents they are asked about
+    let carol = person_repo
+        .find_by_email("carol@example.com")
+        .await
+        .unwrap()
+        .unwrap();
+    let top = person_repo.find_top_documents(&carol.id, 10).await.unwrap();
+    assert_eq!(top.len(), 1);
+    assert_eq!(top[0].document_id, document_ids["standup"]);
+    assert_eq!(top[0].roles, vec!["attendee", "author"]);
+    let all_documents: Vec<String> = document_ids.values().cloned().collect();
+    let collaborators = person_repo
+        .find_collaborators(&alice.id, &all_documents, 10)
+        .await
+        .unwrap();
+    let emails: Vec<&str> = collaborators.iter().map(|c| c.email.as_str()).collect();
+    assert_eq!(emails, vec!["bob@example.com", "carol@example.com"]);
+    let collaborators = person_repo
+        .find_collaborators(&alice.id, &[document_ids["standup"].clone()], 10)
+        .await
+        .unwrap();
+    assert_eq!(collaborators.len(), 1);
+    assert_eq!(collaborators[0].email, "carol@example.com");
+
+    // Profiles of people no longer in any document are removed
+    repo.delete(&document_ids["thread"]).await.unwrap();
+    let response = server.post("/admin/people/aggregate").await;
+    let result: Value = response.json();
+    assert_eq!(result["source_id"], people_source_id.as_str());
+    assert_eq!(result["people"], 2);
+    assert_eq!(result["removed"], 1);
+    common::wait_for_document_deleted(
+        &repo,
+        &people_source_id,
+        "bob@example.com",
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("Profile should be removed");
+    assert!(person_repo
+        .find_by_email("bob@example.com")
+        .await
+        .unwrap()
+        .is_none());
+
+    processor_handle.abort();
+}
+
+#[tokio::test]
+async fn test_recommendations() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = TestServer::new(fixture.app().clone()).unwrap();
+    let pool = fixture.state.db_pool.pool();
+
+    let alice_id = "01JGF7V3E0Y2R1X8P5Q7W9T4R1";
+    sqlx::query(
+        "INSERT INTO users (id, email, password_hash) VALUES ($1, 'alice@example.com', 'hash')",
+    )
+    .bind(alice_id)
+    .execute(pool)
+    .await
+    .unwrap();
+
+    let mut document_ids = HashMap::new();
+    for (external_id, title, public) in [
+        ("spec", "Shared spec", true),
+        ("draft", "Bob's draft", true),
+        ("private", "Bob's private notes", false),
+        ("standup", "Standup", true),
+        ("actions", "Standup action items", true),
+        ("opened", "Onboarding", true),
+        ("linked", "Onboarding checklist", true),
+        ("roadmap", "Roadmap 2027", true),
+    ] {
+        let mut request = create_document_request();
+        request.external_id = external_id.to_string();
+        request.title = title.to_string();
+        request.permissions = json!({
+            "public": public,
+            "users": if public { vec![] } else { vec!["bob@example.com"] },
+            "groups": []
+        });
+        let document: Document = server.post("/documents").json(&request).await.json();
+        document_ids.insert(external_id, document.id);
+    }
+
+    for (id, email) in [
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4R2", "alice@example.com"),
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4R3", "bob@example.com"),
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4R4", "carol@example.com"),
+    ] {
+        sqlx::query("INSERT INTO people (id, email) VALUES ($1, $2)")
+            .bind(id)
+            .bind(email)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+    // Alice and Bob wrote the spec together, Alice and Carol met yesterday
+    for (person_id, document, role, days_ago) in [
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4R2", "spec", "author", 3),
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4R3", "spec", "author", 3),
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4R3", "draft", "author", 0),
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4R3", "private", "author", 0),
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4R2", "standup", "attendee", 1),
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4R4", "standup", "attendee", 1),
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4R4", "actions", "author", 0),
+    ] {
+        sqlx::query(
+            "INSERT INTO person_activity (person_id, document_id, role, occurred_at)
+             VALUES ($1, $2, $3, NOW() - make_interval(days => $4))",
+        )
+        .bind(person_id)
+        .bind(&document_ids[document])
+        .bind(role)
+        .bind(days_ago)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+    sqlx::query(
+        "INSERT INTO document_interactions (id, document_id, user_id, kind)
+         VALUES ('01JGF7V3E0Y2R1X8P5Q7W9T4R5', $1, $2, 'click')",
+    )
+    .bind(&document_ids["opened"])
+    .bind(alice_id)
+    .execute(pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "INSERT INTO document_links (source_document_id, kind, target_key, target_document_id)
+         VALUES ($1, 'drive_file', 'checklist', $2)",
+    )
+    .bind(&document_ids["opened"])
+    .bind(&document_ids["linked"])
+    .execute(pool)
+    .await
+    .unwrap();
+    let mut conn = fixture
+        .state
+        .redis_client
+        .get_multiplexed_async_connection()
+        .await
+        .unwrap();
+    let _: () = redis::AsyncCommands::rpush(
+        &mut conn,
+        shared::cache::keys().user(alice_id, shared::cache::SEARCH_HISTORY),
+        "roadmap",
+    )
+    .await
+    .unwrap();
+
+    let response = server.post("/admin/recommendations/compute").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+
+    let recommendations = RecommendationRepository::new(pool)
+        .find_for_user(alice_id)
+        .await
+        .unwrap();
+    let reasons: HashMap<String, RecommendationReason> = recommendations
+        .into_iter()
+        .map(|r| (r.document_id, r.reason))
+        .collect();
+    // What Alice wrote or opened and what she cannot see are left out
+    let expected = HashMap::from([
+        (
+            document_ids["draft"].clone(),
+            RecommendationReason::CollaboratorEdit,
+        ),
+        (
+            document_ids["actions"].clone(),
+            RecommendationReason::MeetingFollowUp,
+        ),
+        (
+            document_ids["linked"].clone(),
+            RecommendationReason::Related,
+        ),
+        (
+            document_ids["roadmap"].clone(),
+            RecommendationReason::RecentSearch,
+        ),
+    ]);
+    assert_eq!(reasons, expected);
+}
+
+#[tokio::test]
+async fn test_stale_content() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = TestServer::new(fixture.app().clone()).unwrap();
+    let pool = fixture.state.db_pool.pool();
+
+    let user_id = "01JGF7V3E0Y2R1X8P5Q7W9T4S1";
+    sqlx::query(
+        "INSERT INTO users (id, email, password_hash) VALUES ($1, 'alice@example.com', 'hash')",
+    )
+    .bind(user_id)
+    .execute(pool)
+    .await
+    .unwrap();
+    sqlx::query("INSERT INTO people (id, email, name) VALUES ($1, 'bob@example.com', 'Bob')")
+        .bind("01JGF7V3E0Y2R1X8P5Q7W9T4S2")
+        .execute(pool)
+        .await
+        .unwrap();
+
+    let mut document_ids = HashMap::new();
+    for (external_id, days_ago, embedding) in [
+        ("old_runbook", 700, vec![1.0, 0.0, 0.0]),
+        ("new_runbook", 10, vec![0.99, 0.05, 0.0]),
+        ("old_notes", 500, vec![0.0, 1.0, 0.0]),
+        ("old_but_read", 800, vec![0.0, 0.0, 1.0]),
+        ("recent", 30, vec![0.0, 1.0, 1.0]),
+    ] {
+        let mut request = create_document_request();
+        request.external_id = external_id.to_string();
+        request.title = external_id.to_string();
+        request.metadata = json!({ "author": "Bob@Example.com" });
+        let document: Document = server.post("/documents").json(&request).await.json();
+        // Updates otherwise set updated_at to now
+        let mut tx = pool.begin().await.unwrap();
+        sqlx::query("ALTER TABLE documents DISABLE TRIGGER update_documents_updated_at")
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        sqlx::query(
+            "UPDATE documents SET updated_at = NOW() - make_interval(days => $2) WHERE id = $1",
+        )
+        .bind(&document.id)
+        .bind(days_ago)
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+        sqlx::query("ALTER TABLE documents ENABLE TRIGGER update_documents_updated_at")
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+        EmbeddingRepository::new(pool)
+            .create(Embedding {
+                id: ulid::Ulid::new().to_string(),
+                document_id: document.id.clone(),
+                chunk_index: 0,
+                chunk_start_offset: 0,
+                chunk_end_offset: 20,
+                content_sha256: None,
+                embedding: Vector::from(embedding),
+                model_name: "test-model".to_string(),
+                dimensions: 3,
+                created_at: OffsetDateTime::now_utc(),
+            })
+            .await
+            .unwrap();
+        document_ids.insert(external_id, document.id);
+    }
+    sqlx::query(
+        "INSERT INTO document_interactions (id, document_id, user_id, kind)
+         VALUES ('01JGF7V3E0Y2R1X8P5Q7W9T4S3', $1, $2, 'view')",
+    )
+    .bind(&document_ids["old_but_read"])
+    .bind(user_id)
+    .execute(pool)
+    .await
+    .unwrap();
+
+    let response = server.post("/admin/stale-content/analyze").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let stats: Value = response.json();
+    assert_eq!(stats["stale_documents"], 2);
+    assert_eq!(stats["superseded"], 1);
+
+    let response = server.get("/admin/stale-content").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let report: Value = response.json();
+    let sources = report["sources"].as_array().unwrap();
+    assert_eq!(sources.len(), 1);
+    assert_eq!(sources[0]["source_id"], TEST_SOURCE_ID);
+    assert_eq!(sources[0]["stale_documents"], 2);
+    assert_eq!(sources[0]["superseded"], 1);
+    assert_eq!(
+        sources[0]["owners"],
+        json!([{
+            "source_id": TEST_SOURCE_ID,
+            "email": "bob@example.com",
+            "name": "Bob",
+            "stale_documents": 2
+        }])
+    );
+    // Least recently updated first
+    let documents = sources[0]["documents"].as_array().unwrap();
+    assert_eq!(documents.len(), 2);
+    assert_eq!(documents[0]["title"], "old_runbook");
+    assert_eq!(
+        documents[0]["superseded_by"],
+        document_ids["new_runbook"].as_str()
+    );
+    assert_eq!(documents[0]["superseded_by_title"], "new_runbook");
+    assert_eq!(documents[1]["title"], "old_notes");
+    assert!(documents[1]["superseded_by"].is_null());
+
+    let response = server.get("/admin/stale-content?limit=1").await;
+    let report: Value = response.json();
+    assert_eq!(
+        report["sources"][0]["documents"].as_array().unwrap().len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_duplicate_content() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = TestServer::new(fixture.app().clone()).unwrap();
+    let pool = fixture.state.db_pool.pool();
+
+    let wiki_source_id = "01JGF7V3E0Y2R1X8P5Q7W9T4D1";
+    sqlx::query(
+        "INSERT INTO sources (id, name, source_type, config, created_by) \
+         VALUES ($1, 'Wiki', 'confluence', '{}', '01JGF7V3E0Y2R1X8P5Q7W9T4N6')",
+    )
+    .bind(wiki_source_id)
+    .execute(pool)
+    .await
+    .unwrap();
+
+    let policy = "Employees may work remotely up to three days a week. ".repeat(5);
+    let mut document_ids = HashMap::new();
+    for (external_id, source_id, content, embedding) in [
+        (
+            "policy",
+            TEST_SOURCE_ID,
+            policy.clone(),
+            vec![1.0, 0.0, 0.0],
+        ),
+        (
+            "policy_copy",
+            wiki_source_id,
+            policy.clone(),
+            vec![1.0, 0.0, 0.0],
+        ),
+        (
+            "policy_draft",
+            TEST_SOURCE_ID,
+            format!("Draft: {}", policy),
+            vec![0.99, 0.05, 0.0],
+        ),
+        (
+            "unrelated",
+            TEST_SOURCE_ID,
+            "Lunch menu".repeat(20),
+            vec![0.0, 1.0, 0.0],
+        ),
+        // Identical, but too short to compare
+        (
+            "stub",
+            TEST_SOURCE_ID,
+            "TODO".to_string(),
+            vec![0.0, 0.0, 1.0],
+        ),
+        (
+            "other_stub",
+            wiki_source_id,
+            "TODO".to_string(),
+            vec![0.0, 1.0, 1.0],
+        ),
+    ] {
+        let mut request = create_document_request();
+        request.source_id = source_id.to_string();
+        request.external_id = external_id.to_string();
+        request.title = external_id.to_string();
+        request.content = content;
+        let document: Document = server.post("/documents").json(&request).await.json();
+        EmbeddingRepository::new(pool)
+            .create(Embedding {
+                id: ulid::Ulid::new().to_string(),
+                document_id: document.id.clone(),
+                chunk_index: 0,
+                chunk_start_offset: 0,
+                chunk_end_offset: 20,
+                content_sha256: None,
+                embedding: Vector::from(embedding),
+                model_name: "test-model".to_string(),
+                dimensions: 3,
+                created_at: OffsetDateTime::now_utc(),
+            })
+            .await
+            .unwrap();
+        document_ids.insert(external_id, document.id);
+    }
+
+    let response = server.post("/admin/duplicates/analyze").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let result: Value = response.json();
+    assert_eq!(result["clusters"], 1);
+    assert_eq!(result["documents"], 3);
+
+    let response = server
+        .get("/admin/duplicates")
+        .add_query_param("source_id", wiki_source_id)
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let report: Value = response.json();
+    let clusters = report["clusters"].as_array().unwrap();
+    assert_eq!(clusters.len(), 1);
+    assert_eq!(clusters[0]["sources"], 2);
+    // Identical copies first, then near-identical ones
+    let documents: Vec<(&str, bool)> = clusters[0]["documents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| (d["title"].as_str().unwrap(), d["exact"].as_bool().unwrap()))
+        .collect();
+    assert_eq!(documents.len(), 3);
+    assert!(documents[..2].contains(&("policy", true)));
+    assert!(documents[..2].contains(&("policy_copy", true)));
+    assert_eq!(documents[2], ("policy_draft", false));
+    let similarity = clusters[0]["documents"][2]["similarity"].as_f64().unwrap();
+    assert!(similarity > 0.95 && similarity < 1.0);
+}
+
+#[tokio::test]
+async fn test_integrity_check() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let server = TestServer::new(fixture.app().clone()).unwrap();
+    let pool = fixture.state.db_pool.pool();
+
+    let mut document_ids = HashMap::new();
+    for (external_id, chunk_end_offset) in [
+        ("embedded", Some(20)),
+        ("out_of_bounds", Some(1000)),
+        ("unembedded", None),
+        ("no_content", None),
+    ] {
+        let mut request = create_document_request();
+        request.external_id = external_id.to_string();
+        let document: Document = server.post("/documents").json(&request).await.json();
+        sqlx::query("UPDATE documents SET content = $2 WHERE id = $1")
+            .bind(&document.id)
+            .bind(&request.content)
+            .execute(pool)
+            .await
+            .unwrap();
+        if let Some(chunk_end_offset) = chunk_end_offset {
+            EmbeddingRepository::new(pool)
+                .create(Embedding {
+                    id: ulid::Ulid::new().to_string(),
+                    document_id: document.id.clone(),
+                    chunk_index: 0,
+                    chunk_start_offset: 0,
+                    chunk_end_offset,
+                    content_sha256: None,
+                    embedding: Vector::from(vec![0.1, 0.2, 0.3]),
+                    model_name: "test-model".to_string(),
+                    dimensions: 3,
+                    created_at: OffsetDateTime::now_utc(),
+                })
+                .await
+                .unwrap();
+        }
+        document_ids.insert(external_id, document.id);
+    }
+    sqlx::query("UPDATE documents SET content_id = NULL WHERE id = $1")
+        .bind(&document_ids["no_content"])
+        .execute(pool)
+        .await
+        .unwrap();
+    // One orphan from the document that lost its content, one never referenced
+    fixture
+        .state
+        .content_storage
+        .store_content(b"orphaned content", None)
+        .await
+        .unwrap();
+
+    let response = server.get("/admin/integrity").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let report: Value = response.json();
+    assert!(report["embedding_model"].is_null());
+    assert_eq!(
+        report["documents_without_content"]["sample"],
+        json!([document_ids["no_content"]])
+    );
+    assert_eq!(
+        report["documents_missing_embeddings"]["sample"],
+        json!([document_ids["unembedded"]])
+    );
+    assert_eq!(
+        report["documents_with_out_of_bounds_chunks"]["sample"],
+        json!([document_ids["out_of_bounds"]])
+    );
+    assert_eq!(report["unmarked_orphan_blobs"], 2);
+    assert!(report["repairs"].is_null());
+
+    let response = server.post("/admin/integrity/repair").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let report: Value = response.json();
+    assert_eq!(report["repairs"]["reembedding_queued"], 2);
+    assert_eq!(report["repairs"]["orphans_marked"], 2);
+
+    // Queued documents are no longer reported until they are embedded again
+    let report: Value = server.get("/admin/integrity").await.json();
+    assert_eq!(report["documents_without_content"]["documents"], 1);
+    assert_eq!(report["documents_missing_embeddings"]["documents"], 0);
+    assert_eq!(
+        report["documents_with_out_of_bounds_chunks"]["documents"],
+        0
+    );
+    assert_eq!(report["unmarked_orphan_blobs"], 0);
+}
+
+#[tokio::test]
+async fn test_versioned_event_payloads() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let pool = fixture.state.db_pool.pool();
+    let repo = DocumentRepository::new(pool);
+
+    let processor = QueueProcessor::new(fixture.state.clone()).with_accumulation_config(
+        Duration::from_millis(200),
+        Duration::from_secs(30),
+        Duration::from_millis(50),
+    );
+    let processor_handle = tokio::spawn(async move {
+        let _ = processor.start().await;
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let content_id = fixture
+        .state
+        .content_storage
+        .store_content(b"Onboarding checklist", None)
+        .await
+        .unwrap();
+    let event = |document_id: &str| {
+        json!({
+            "type": "document_created",
+            "sync_run_id": "sync_versions",
+            "source_id": TEST_SOURCE_ID,
+            "document_id": document_id,
+            "content_id": content_id,
+            "metadata": { "title": document_id },
+            "permissions": { "public": true, "users": [], "groups": [] },
+        })
+    };
+    // Queued before versioning, without attributes
+    let legacy = event("legacy_doc");
+    // Queued by a newer release, with a field this one does not know
+    let mut newer = event("newer_doc");
+    newer["schema_version"] = json!(ConnectorEvent::SCHEMA_VERSION + 1);
+    newer["attributes"] = json!({ "team": "people-ops" });
+    newer["labels"] = json!(["onboarding"]);
+
+    for payload in [legacy, newer] {
+        sqlx::query(
+            "INSERT INTO connector_events_queue (id, sync_run_id, source_id, event_type, payload)
+             VALUES ($1, 'sync_versions', $2, 'document_created', $3)",
+        )
+        .bind(ulid::Ulid::new().to_string())
+        .bind(TEST_SOURCE_ID)
+        .bind(&payload)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+    sqlx::query("NOTIFY indexer_queue")
+        .execute(pool)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        common::wait_for_completed(pool, 2, Duration::from_secs(5)).await,
+        2
+    );
+    for doc_id in ["legacy_doc", "newer_doc"] {
+        common::wait_for_document_exists(&repo, TEST_SOURCE_ID, doc_id, Duration::from_secs(5))
+            .await
+            .expect("Document should be created");
+    }
+    let newer = repo
+        .find_by_external_id(TEST_SOURCE_ID, "newer_doc")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(newer.attributes["team"], "people-ops");
+
+    processor_handle.abort();
+}
+
+#[tokio::test]
+async fn test_redelivered_events_are_idempotent() {
+    let fixture = common::setup_test_fixture().await.unwrap();
+    let event_queue = EventQueue::new(fixture.state.db_pool.pool().clone());
+    let pool = fixture.state.db_pool.pool();
+    let repo = DocumentRepository::new(pool);
+
+    let processor = QueueProcessor::new(fixture.state.clone()).with_accumulation_config(
+        Duration::from_millis(200),
+        Duration::from_secs(30),
+        Duration::from_millis(50),
+    );
+    let processor_handle = tokio::spawn(async move {
+        let _ = processor.start().await;
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let doc_id = "redelivered_doc";
+    let store = |content: &'static str| {
+        let storage = fixture.state.content_storage.clone();
+        async move {
+            storage
+                .store_content(content.as_bytes(), None)
+                .await
+                .unwrap()
+        }
+    };
+    let event = |sync_run_id: &str, content_id: String| ConnectorEvent::DocumentCreated {
+        sync_run_id: sync_run_id.to_string(),
+        source_id: TEST_SOURCE_ID.to_string(),
+        document_id: doc_id.to_string(),
+        content_id,
+        metadata: DocumentMetadata {
+            title: Some("Expense Policy".to_string()),
+            ..Default::default()
+        },
+        permissions: DocumentPermissions {
+            public: true,
+            users: vec![],
+            groups: vec![],
+        },
+        attributes: None,
+    };
+    let embeddings_queued = || async {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM embedding_queue q JOIN documents d ON d.id = q.document_id
+             WHERE d.external_id = $1",
+        )
+        .bind(doc_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    };
+
+    let content_id = store("Expenses over 500 need approval").await;
+    event_queue
+        .enqueue(
+            TEST_SOURCE_ID,
+            &event("sync_redelivery", content_id.clone()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        common::wait_for_completed(pool, 1, Duration::from_secs(5)).await,
+        1
+    );
+    let original = repo
+        .find_by_external_id(TEST_SOURCE_ID, doc_id)
+        .await
+        .unwrap()
+        .expect("Document should be created");
+    assert_eq!(embeddings_queued().await, 1);
+
+    // The same delivery again, as a retry or replay would make it
+    event_queue
+        .enqueue(
+            TEST_SOURCE_ID,
+            &event("sync_redelivery", content_id.clone()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        common::wait_for_completed(pool, 2, Duration::from_secs(5)).await,
+        2
+    );
+    let redelivered = repo
+        .find_by_external_id(TEST_SOURCE_ID, doc_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(redelivered.updated_at, original.updated_at);
+    assert_eq!(embeddings_queued().await, 1);
+
+    // New content, and the same content in a later sync run, are ingested again
+    let changed_content_id = store("Expenses over 1000 need approval").await;
+    event_queue
+        .enqueue(
+            TEST_SOURCE_ID,
+            &event("sync_redelivery", changed_content_id),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        common::wait_for_completed(pool, 3, Duration::from_secs(5)).await,
+        3
+    );
+    assert_eq!(embeddings_queued().await, 2);
+    event_queue
+        .enqueue(TEST_SOURCE_ID, &event("sync_next_run", content_id))
+        .await
+        .unwrap();
+    assert_eq!(
+        common::wait_for_completed(pool, 4, Duration::from_secs(5)).await,
+        4
+    );
+    assert_eq!(embeddings_queued().await, 3);
+
+    processor_handle.abort();
+}
@@ -0,0 +1,19 @@
+CREATE TABLE search_query_logs (
+    id CHAR(26) PRIMARY KEY,
+    query_hash CHAR(64) NOT NULL,
+    search_mode TEXT NOT NULL,
+    filters JSONB NOT NULL DEFAULT '{}',
+    user_id CHAR(26) REFERENCES users(id) ON DELETE SET NULL,
+    result_count INTEGER NOT NULL,
+    total_ms INTEGER NOT NULL,
+    cache_ms INTEGER NOT NULL DEFAULT 0,
+    fts_ms INTEGER,
+    vector_ms INTEGER,
+    rerank_ms INTEGER,
+    cache_hit BOOLEAN NOT NULL DEFAULT FALSE,
+    is_slow BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_search_query_logs_created_at ON search_query_logs(created_at);
+CREATE INDEX idx_search_query_logs_slow ON search_query_logs(created_at) WHERE is_slow = TRUE;
@@ -0,0 +1,56 @@
+-- Track source-side freshness so end-to-end indexing lag can be measured per source.
+-- source_updated_at: when the document was last modified in the source system (metadata.updated_at)
+-- source_indexed_at: when that version of the document was first indexed
+ALTER TABLE documents ADD COLUMN IF NOT EXISTS source_updated_at TIMESTAMPTZ;
+ALTER TABLE documents ADD COLUMN IF NOT EXISTS source_indexed_at TIMESTAMPTZ;
+
+-- Parse a timestamp from document metadata. The Rust connectors serialize timestamps with
+-- a six-digit signed year (e.g. +002025-01-15T10:30:00Z), which Postgres does not accept.
+-- Unparseable values yield NULL rather than failing the write.
+CREATE OR REPLACE FUNCTION parse_metadata_timestamp(value TEXT)
+RETURNS TIMESTAMPTZ AS $$
+BEGIN
+    IF value IS NULL OR value = '' THEN
+        RETURN NULL;
+    END IF;
+    RETURN regexp_replace(value, '^\+00(\d{4})', '\1')::TIMESTAMPTZ;
+EXCEPTION WHEN OTHERS THEN
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql IMMUTABLE;
+
+-- Re-indexing an unchanged document (e.g. during a full sync) keeps source_indexed_at,
+-- so lag reflects how long a source change took to become searchable.
+CREATE OR REPLACE FUNCTION update_document_source_timestamps()
+RETURNS TRIGGER AS $$
+BEGIN
+    NEW.source_updated_at := parse_metadata_timestamp(NEW.metadata->>'updated_at');
+
+    IF TG_OP = 'INSERT' OR NEW.source_updated_at IS DISTINCT FROM OLD.source_updated_at THEN
+        NEW.source_indexed_at := CURRENT_TIMESTAMP;
+    ELSE
+        NEW.source_indexed_at := OLD.source_indexed_at;
+    END IF;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS update_document_source_timestamps_trigger ON documents;
+CREATE TRIGGER update_document_source_timestamps_trigger
+    BEFORE INSERT OR UPDATE OF metadata ON documents
+    FOR EACH ROW EXECUTE FUNCTION update_document_source_timestamps();
+
+-- Backfill existing documents, treating their last index as the first index of the current version.
+-- The updated_at trigger is disabled so the backfill does not look like a document change.
+ALTER TABLE documents DISABLE TRIGGER update_documents_updated_at;
+
+UPDATE documents
+SET source_updated_at = parse_metadata_timestamp(metadata->>'updated_at'),
+    source_indexed_at = last_indexed_at;
+
+ALTER TABLE documents ENABLE TRIGGER update_documents_updated_at;
+
+CREATE INDEX IF NOT EXISTS idx_documents_source_indexed_at
+    ON documents(source_indexed_at)
+    WHERE source_updated_at IS NOT NULL;
@@ -0,0 +1,17 @@
+-- Right-to-be-forgotten requests. Each request removes everything indexed about a data subject
+-- and records a signed report of what was deleted.
+CREATE TABLE IF NOT EXISTS data_deletion_requests (
+    id CHAR(26) PRIMARY KEY,
+    subject_email TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'running', 'completed', 'failed')),
+    report JSONB,
+    signature TEXT,
+    error_message TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_data_deletion_requests_status ON data_deletion_requests(status);
+CREATE INDEX IF NOT EXISTS idx_data_deletion_requests_created_at ON data_deletion_requests(created_at DESC);
@@ -0,0 +1,27 @@
+-- Users and groups provisioned by identity providers over SCIM. Group membership is resolved
+-- at query time by matching a user's groups against the groups in document permissions.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS external_id TEXT;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_external_id ON users(external_id) WHERE external_id IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS groups (
+    id CHAR(26) PRIMARY KEY,
+    display_name TEXT NOT NULL UNIQUE,
+    external_id TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_groups_external_id ON groups(external_id);
+
+CREATE TRIGGER update_groups_updated_at
+    BEFORE UPDATE ON groups
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TABLE IF NOT EXISTS group_members (
+    group_id CHAR(26) NOT NULL REFERENCES groups(id) ON DELETE CASCADE,
+    user_id CHAR(26) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (group_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_group_members_user_id ON group_members(user_id);
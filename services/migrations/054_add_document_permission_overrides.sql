@@ -0,0 +1,84 @@
+-- Manual sharing and visibility overrides layered on top of connector-derived permissions.
+--
+-- `source_permissions` holds the permissions last written by the connector and
+-- `permission_overrides` the manual changes. `permissions`, which search filters on, is always
+-- derived from both, so a sync replacing the connector permissions keeps the overrides.
+ALTER TABLE documents ADD COLUMN IF NOT EXISTS source_permissions JSONB;
+ALTER TABLE documents ADD COLUMN IF NOT EXISTS permission_overrides JSONB;
+
+CREATE OR REPLACE FUNCTION jsonb_text_array_union(a JSONB, b JSONB)
+RETURNS JSONB AS $$
+    SELECT COALESCE(jsonb_agg(DISTINCT value ORDER BY value), '[]'::jsonb)
+    FROM (
+        SELECT jsonb_array_elements_text(a) AS value WHERE jsonb_typeof(a) = 'array'
+        UNION
+        SELECT jsonb_array_elements_text(b) WHERE jsonb_typeof(b) = 'array'
+    ) values_union
+$$ LANGUAGE sql IMMUTABLE;
+
+-- Overrides: "public" replaces the connector visibility, "users" and "groups" grant access in
+-- addition to the connector permissions, and "hidden" removes the document from search.
+CREATE OR REPLACE FUNCTION apply_permission_overrides(permissions JSONB, overrides JSONB)
+RETURNS JSONB AS $$
+BEGIN
+    IF overrides IS NULL OR overrides = '{}'::jsonb THEN
+        RETURN permissions;
+    END IF;
+
+    IF COALESCE((overrides->>'hidden')::boolean, false) THEN
+        RETURN jsonb_build_object(
+            'public', false,
+            'users', '[]'::jsonb,
+            'groups', '[]'::jsonb,
+            'hidden', true
+        );
+    END IF;
+
+    RETURN jsonb_build_object(
+        'public', COALESCE(
+            (overrides->>'public')::boolean,
+            CASE WHEN jsonb_typeof(permissions) = 'object'
+                 THEN (permissions->>'public')::boolean END,
+            false
+        ),
+        'users', jsonb_text_array_union(permissions->'users', overrides->'users'),
+        'groups', jsonb_text_array_union(permissions->'groups', overrides->'groups')
+    );
+END;
+$$ LANGUAGE plpgsql IMMUTABLE;
+
+-- Every write of `permissions` comes from a connector. Overrides are never written in the same
+-- statement, so the two triggers below don't interact.
+CREATE OR REPLACE FUNCTION layer_source_permissions()
+RETURNS TRIGGER AS $$
+BEGIN
+    NEW.source_permissions := NEW.permissions;
+    NEW.permissions := apply_permission_overrides(NEW.source_permissions, NEW.permission_overrides);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION layer_permission_overrides()
+RETURNS TRIGGER AS $$
+BEGIN
+    NEW.permissions := apply_permission_overrides(
+        COALESCE(NEW.source_permissions, OLD.permissions),
+        NEW.permission_overrides
+    );
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+ALTER TABLE documents DISABLE TRIGGER update_documents_updated_at;
+UPDATE documents SET source_permissions = permissions WHERE source_permissions IS NULL;
+ALTER TABLE documents ENABLE TRIGGER update_documents_updated_at;
+
+CREATE TRIGGER layer_source_permissions_trigger
+    BEFORE INSERT OR UPDATE OF permissions ON documents
+    FOR EACH ROW EXECUTE FUNCTION layer_source_permissions();
+
+CREATE TRIGGER layer_permission_overrides_trigger
+    BEFORE UPDATE OF permission_overrides ON documents
+    FOR EACH ROW
+    WHEN (NEW.permission_overrides IS DISTINCT FROM OLD.permission_overrides)
+    EXECUTE FUNCTION layer_permission_overrides();
@@ -0,0 +1,13 @@
+-- Transactional outbox for connector events. Emitters write events here in the same
+-- transaction as the sync run state changes they account for, and a relay moves them into
+-- connector_events_queue for the indexer.
+CREATE TABLE IF NOT EXISTS connector_events_outbox (
+    id CHAR(26) PRIMARY KEY,
+    sync_run_id CHAR(26) NOT NULL,
+    source_id CHAR(26) NOT NULL,
+    event_type VARCHAR(50) NOT NULL,
+    payload JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_outbox_created_at ON connector_events_outbox(created_at);
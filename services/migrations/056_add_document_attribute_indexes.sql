@@ -0,0 +1,9 @@
+-- Containment-optimised GIN index for attribute filters outside the BM25 index. jsonb_path_ops
+-- only supports @>, but is much smaller and faster than the default jsonb_ops index from 036,
+-- which is kept for key-existence queries.
+CREATE INDEX IF NOT EXISTS idx_documents_attributes_path_ops
+    ON documents USING GIN (attributes jsonb_path_ops);
+
+-- Metadata is filtered by containment too, e.g. by author or path.
+CREATE INDEX IF NOT EXISTS idx_documents_metadata_path_ops
+    ON documents USING GIN (metadata jsonb_path_ops);
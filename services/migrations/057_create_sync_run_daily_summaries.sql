@@ -0,0 +1,30 @@
+-- Daily rollups of sync runs past their retention period. Compaction deletes old runs and
+-- adds them to the summary for their source, day and sync type, so history stays queryable
+-- without keeping every run.
+CREATE TABLE IF NOT EXISTS sync_run_daily_summaries (
+    source_id CHAR(26) NOT NULL REFERENCES sources(id) ON DELETE CASCADE,
+    day DATE NOT NULL,
+    sync_type VARCHAR(20) NOT NULL,
+    runs INTEGER NOT NULL DEFAULT 0,
+    completed INTEGER NOT NULL DEFAULT 0,
+    failed INTEGER NOT NULL DEFAULT 0,
+    cancelled INTEGER NOT NULL DEFAULT 0,
+    documents_scanned BIGINT NOT NULL DEFAULT 0,
+    documents_processed BIGINT NOT NULL DEFAULT 0,
+    documents_updated BIGINT NOT NULL DEFAULT 0,
+    total_duration_seconds DOUBLE PRECISION NOT NULL DEFAULT 0,
+    max_duration_seconds DOUBLE PRECISION NOT NULL DEFAULT 0,
+    last_error_message TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (source_id, day, sync_type)
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_run_daily_summaries_day ON sync_run_daily_summaries(day);
+
+CREATE TRIGGER update_sync_run_daily_summaries_updated_at
+    BEFORE UPDATE ON sync_run_daily_summaries
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Compaction scans finished runs by start time
+CREATE INDEX IF NOT EXISTS idx_sync_runs_started_at ON sync_runs(started_at) WHERE status <> 'running';
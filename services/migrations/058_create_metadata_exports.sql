@@ -0,0 +1,18 @@
+-- Files of document metadata exported to object storage for analytics. Each format is
+-- exported incrementally from the (updated_at, id) watermark of its latest export.
+CREATE TABLE IF NOT EXISTS metadata_exports (
+    id CHAR(26) PRIMARY KEY,
+    format VARCHAR(10) NOT NULL,
+    content_id CHAR(26) NOT NULL,
+    row_count BIGINT NOT NULL,
+    watermark_updated_at TIMESTAMPTZ NOT NULL,
+    watermark_document_id CHAR(26) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT metadata_exports_format_check CHECK (format IN ('csv', 'parquet'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_metadata_exports_format_created_at
+    ON metadata_exports(format, created_at DESC);
+
+-- Incremental exports page through documents in (updated_at, id) order
+CREATE INDEX IF NOT EXISTS idx_documents_updated_at_id ON documents(updated_at, id);
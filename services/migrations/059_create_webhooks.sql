@@ -0,0 +1,32 @@
+-- Outbound webhooks. Events are written to webhook_deliveries, one row per subscribed
+-- webhook, and the connector manager posts them with retries.
+CREATE TABLE IF NOT EXISTS webhooks (
+    id CHAR(26) PRIMARY KEY,
+    url TEXT NOT NULL,
+    -- Signing secret, sealed with the master encryption key
+    secret JSONB NOT NULL,
+    event_types TEXT[] NOT NULL,
+    created_by CHAR(26) REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id CHAR(26) PRIMARY KEY,
+    webhook_id CHAR(26) NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+    event_type VARCHAR(50) NOT NULL,
+    payload JSONB NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    response_status INTEGER,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    delivered_at TIMESTAMPTZ,
+    CONSTRAINT webhook_deliveries_status_check CHECK (status IN ('pending', 'delivered', 'failed'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due
+    ON webhook_deliveries(next_attempt_at) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_webhook_created_at
+    ON webhook_deliveries(webhook_id, created_at DESC);
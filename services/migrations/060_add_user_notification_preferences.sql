@@ -0,0 +1,3 @@
+-- Which email notifications each user receives, e.g. {"sync_failures": false}. Missing keys
+-- take the application default, so new notification types need no migration.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS notification_preferences JSONB NOT NULL DEFAULT '{}';
@@ -0,0 +1,33 @@
+-- Objects written to object storage under a caller-chosen key, such as index snapshot files.
+-- The S3 backend writes these to the bucket directly, this table backs the Postgres backend.
+-- Unlike content_blobs, these are never garbage collected.
+CREATE TABLE IF NOT EXISTS storage_objects (
+    key TEXT PRIMARY KEY,
+    content BYTEA NOT NULL,
+    content_type TEXT,
+    size_bytes BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Snapshots of the index written to object storage, and restores of those snapshots. A restore
+-- refers to its snapshot by id only, since it may have been taken by another deployment.
+CREATE TABLE IF NOT EXISTS index_snapshots (
+    id CHAR(26) PRIMARY KEY,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('snapshot', 'restore')),
+    snapshot_id CHAR(26) NOT NULL,
+    source_id CHAR(26),
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'running', 'completed', 'failed')),
+    document_count BIGINT NOT NULL DEFAULT 0,
+    embedding_count BIGINT NOT NULL DEFAULT 0,
+    content_blob_count BIGINT NOT NULL DEFAULT 0,
+    error_message TEXT,
+    created_by TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_index_snapshots_status ON index_snapshots(status);
+CREATE INDEX IF NOT EXISTS idx_index_snapshots_created_at ON index_snapshots(created_at DESC);
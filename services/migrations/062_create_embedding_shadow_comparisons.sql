@@ -0,0 +1,21 @@
+-- Sampled searches run against both the active and the shadow embedding model while an
+-- embedding model migration is in progress, for comparing the two before cutting over.
+-- Query text is never stored, only its hash.
+CREATE TABLE IF NOT EXISTS embedding_shadow_comparisons (
+    id CHAR(26) PRIMARY KEY,
+    query_hash CHAR(64) NOT NULL,
+    active_model TEXT NOT NULL,
+    shadow_model TEXT NOT NULL,
+    k INTEGER NOT NULL,
+    overlap INTEGER NOT NULL,
+    active_result_count INTEGER NOT NULL,
+    shadow_result_count INTEGER NOT NULL,
+    active_top_score REAL,
+    shadow_top_score REAL,
+    active_ms INTEGER NOT NULL,
+    shadow_ms INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_embedding_shadow_comparisons_created_at
+    ON embedding_shadow_comparisons(created_at);
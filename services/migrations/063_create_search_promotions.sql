@@ -0,0 +1,24 @@
+-- Admin-managed promoted results ("best bets"). A promotion whose pattern matches a search
+-- query either pins its document above the ranked results or boosts the document's score
+-- when the ranking found it.
+CREATE TABLE IF NOT EXISTS search_promotions (
+    id CHAR(26) PRIMARY KEY,
+    query_pattern TEXT NOT NULL,
+    match_type VARCHAR(20) NOT NULL DEFAULT 'exact',
+    document_id CHAR(26) NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    action VARCHAR(20) NOT NULL DEFAULT 'pin',
+    -- Score multiplier of boost promotions
+    boost REAL NOT NULL DEFAULT 1.0,
+    -- Order of pinned documents, lowest first
+    position INTEGER NOT NULL DEFAULT 0,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by CHAR(26) REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT search_promotions_match_type_check
+        CHECK (match_type IN ('exact', 'prefix', 'contains', 'regex')),
+    CONSTRAINT search_promotions_action_check CHECK (action IN ('pin', 'boost'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_search_promotions_document_id
+    ON search_promotions(document_id);
@@ -0,0 +1,21 @@
+-- Acronym dictionary used to expand search queries, e.g. a query for "SLA" also matches
+-- "service level agreement". Entries are mined from "Full Term (FT)" patterns in indexed
+-- content or added by admins, and only expand queries once an admin marks them active.
+CREATE TABLE IF NOT EXISTS acronyms (
+    id CHAR(26) PRIMARY KEY,
+    acronym VARCHAR(20) NOT NULL,
+    expansion TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    source VARCHAR(20) NOT NULL DEFAULT 'manual',
+    -- How often the mining job found the pattern, and in how many documents
+    occurrences INTEGER NOT NULL DEFAULT 0,
+    document_count INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT acronyms_status_check CHECK (status IN ('pending', 'active', 'rejected')),
+    CONSTRAINT acronyms_source_check CHECK (source IN ('mined', 'manual'))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_acronyms_acronym_expansion
+    ON acronyms(acronym, LOWER(expansion));
+CREATE INDEX IF NOT EXISTS idx_acronyms_status ON acronyms(status);
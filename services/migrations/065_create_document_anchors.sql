@@ -0,0 +1,17 @@
+-- Section headings and transcript timestamps found in document content, used to deep-link
+-- search results into the part of a long document their best matching chunk is in.
+-- Offsets are character offsets into the content, like the chunk offsets of embeddings.
+CREATE TABLE IF NOT EXISTS document_anchors (
+    document_id CHAR(26) NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    start_offset INTEGER NOT NULL,
+    kind VARCHAR(20) NOT NULL,
+    label TEXT NOT NULL,
+    -- Heading level, 1 to 6
+    level SMALLINT,
+    -- Timestamp from the start of the recording
+    seconds INTEGER,
+    CONSTRAINT document_anchors_kind_check CHECK (kind IN ('heading', 'timestamp'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_anchors_document_offset
+    ON document_anchors(document_id, kind, start_offset);
@@ -0,0 +1,14 @@
+-- Preview images of documents shown with search results: thumbnails captured from the
+-- source by connectors, or renders of the first page of the content made by the indexer.
+-- The image is a content blob, so the blob garbage collector keeps it while referenced here.
+CREATE TABLE IF NOT EXISTS document_previews (
+    document_id CHAR(26) PRIMARY KEY REFERENCES documents(id) ON DELETE CASCADE,
+    content_id CHAR(26) NOT NULL,
+    kind VARCHAR(20) NOT NULL,
+    mime_type VARCHAR(100) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT document_previews_kind_check CHECK (kind IN ('thumbnail', 'render'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_previews_content_id ON document_previews(content_id);
@@ -0,0 +1,33 @@
+-- User-curated collections: named sets of documents that searches can be limited to with a
+-- `collection:` filter. A collection is edited by its owner and can be shared with other
+-- users, who can view and search it.
+CREATE TABLE IF NOT EXISTS collections (
+    id CHAR(26) PRIMARY KEY,
+    owner_id CHAR(26) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Also bumped when documents are added or removed, so cached searches of the collection
+    -- are not reused
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT collections_owner_name_unique UNIQUE (owner_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS collection_documents (
+    collection_id CHAR(26) NOT NULL REFERENCES collections(id) ON DELETE CASCADE,
+    document_id CHAR(26) NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    added_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (collection_id, document_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_collection_documents_document_id
+    ON collection_documents(document_id);
+
+CREATE TABLE IF NOT EXISTS collection_shares (
+    collection_id CHAR(26) NOT NULL REFERENCES collections(id) ON DELETE CASCADE,
+    user_id CHAR(26) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (collection_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_collection_shares_user_id ON collection_shares(user_id);
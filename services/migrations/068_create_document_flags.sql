@@ -0,0 +1,24 @@
+-- Flags users raise on search results they find outdated, incorrect or sensitive. Open flags
+-- are reviewed by the owners of the flagged documents' sources through the admin API, and can
+-- demote flagged documents in hybrid search.
+CREATE TABLE IF NOT EXISTS document_flags (
+    id CHAR(26) PRIMARY KEY,
+    document_id CHAR(26) NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    user_id CHAR(26) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    reason VARCHAR(20) NOT NULL,
+    comment TEXT,
+    status VARCHAR(20) NOT NULL DEFAULT 'open',
+    resolved_by CHAR(26) REFERENCES users(id) ON DELETE SET NULL,
+    resolved_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT document_flags_reason_check
+        CHECK (reason IN ('outdated', 'incorrect', 'sensitive')),
+    CONSTRAINT document_flags_status_check CHECK (status IN ('open', 'resolved', 'dismissed')),
+    -- Flagging a document again for the same reason reopens the user's flag
+    CONSTRAINT document_flags_document_user_reason_unique UNIQUE (document_id, user_id, reason)
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_flags_open_document_id
+    ON document_flags(document_id) WHERE status = 'open';
+CREATE INDEX IF NOT EXISTS idx_document_flags_status ON document_flags(status, created_at DESC);
@@ -0,0 +1,20 @@
+-- Links between documents found in their content: Confluence page links, Jira issue keys and
+-- Google Drive file links. A link's target is kept as the key the content refers to, and is
+-- resolved to a document once one with that key is indexed. The number of documents linking
+-- to a document is used as an authority signal in ranking.
+CREATE TABLE IF NOT EXISTS document_links (
+    source_document_id CHAR(26) NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    kind VARCHAR(20) NOT NULL,
+    -- Confluence page ID, Jira issue key or Drive file ID
+    target_key TEXT NOT NULL,
+    target_document_id CHAR(26) REFERENCES documents(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (source_document_id, kind, target_key),
+    CONSTRAINT document_links_kind_check
+        CHECK (kind IN ('confluence_page', 'jira_issue', 'drive_file'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_links_target_document_id
+    ON document_links(target_document_id);
+CREATE INDEX IF NOT EXISTS idx_document_links_unresolved
+    ON document_links(kind, target_key) WHERE target_document_id IS NULL;
@@ -0,0 +1,36 @@
+-- People directory aggregated from connector data. Each person seen as a document author,
+-- meeting attendee or email correspondent gets a profile, indexed as a `people` document
+-- under a system source of type 'people' so profiles are searchable like any document.
+ALTER TABLE sources
+DROP CONSTRAINT IF EXISTS sources_source_type_check;
+
+ALTER TABLE sources
+ADD CONSTRAINT sources_source_type_check
+CHECK (source_type IN ('google_drive', 'gmail', 'confluence', 'jira', 'slack',
+  'github', 'local_files', 'web', 'notion', 'hubspot',
+  'one_drive', 'share_point', 'outlook', 'outlook_calendar', 'fireflies', 'people'));
+
+CREATE TABLE IF NOT EXISTS people (
+    id CHAR(26) PRIMARY KEY,
+    -- Lowercased
+    email TEXT NOT NULL UNIQUE,
+    name TEXT,
+    authored_count INTEGER NOT NULL DEFAULT 0,
+    meeting_count INTEGER NOT NULL DEFAULT 0,
+    email_count INTEGER NOT NULL DEFAULT 0,
+    last_active_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- What each person did on each document, rebuilt by every aggregation run
+CREATE TABLE IF NOT EXISTS person_activity (
+    person_id CHAR(26) NOT NULL REFERENCES people(id) ON DELETE CASCADE,
+    document_id CHAR(26) NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    role VARCHAR(20) NOT NULL,
+    occurred_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (person_id, document_id, role),
+    CONSTRAINT person_activity_role_check CHECK (role IN ('author', 'attendee', 'correspondent'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_person_activity_document_id ON person_activity(document_id);
@@ -0,0 +1,15 @@
+-- Documents users opened from search results or viewed in the app. Recent interactions rank
+-- the trending feed.
+CREATE TABLE IF NOT EXISTS document_interactions (
+    id CHAR(26) PRIMARY KEY,
+    document_id CHAR(26) NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    user_id CHAR(26) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind VARCHAR(20) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT document_interactions_kind_check CHECK (kind IN ('view', 'click'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_interactions_created_at
+    ON document_interactions(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_document_interactions_document_id
+    ON document_interactions(document_id);
@@ -0,0 +1,16 @@
+-- Documents recommended to each user on the home page, recomputed periodically by the indexer
+-- from collaborators' edits, recent meetings, searches and the documents users worked on.
+CREATE TABLE IF NOT EXISTS user_recommendations (
+    user_id CHAR(26) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    document_id CHAR(26) NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    reason VARCHAR(30) NOT NULL,
+    score REAL NOT NULL,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, document_id),
+    CONSTRAINT user_recommendations_reason_check CHECK (
+        reason IN ('collaborator_edit', 'meeting_follow_up', 'recent_search', 'related')
+    )
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_recommendations_user_score
+    ON user_recommendations(user_id, score DESC);
@@ -0,0 +1,13 @@
+-- Documents found likely stale by the indexer's periodic freshness analysis: not updated in a
+-- long time and never opened, optionally superseded by a newer document with similar content.
+-- Rebuilt by every analysis run, for knowledge managers deciding what to archive.
+CREATE TABLE IF NOT EXISTS stale_documents (
+    document_id CHAR(26) PRIMARY KEY REFERENCES documents(id) ON DELETE CASCADE,
+    source_id CHAR(26) NOT NULL REFERENCES sources(id) ON DELETE CASCADE,
+    -- The most similar newer document, when it is similar enough to replace this one
+    superseded_by CHAR(26) REFERENCES documents(id) ON DELETE SET NULL,
+    similarity REAL,
+    analyzed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_stale_documents_source_id ON stale_documents(source_id);
@@ -0,0 +1,15 @@
+-- Clusters of identical or near-identical documents, possibly across sources, found by the
+-- indexer's periodic duplicate analysis. Rebuilt by every analysis run, for knowledge
+-- managers consolidating copies of the same content.
+CREATE TABLE IF NOT EXISTS duplicate_documents (
+    document_id CHAR(26) PRIMARY KEY REFERENCES documents(id) ON DELETE CASCADE,
+    -- The smallest document id in the cluster
+    cluster_id CHAR(26) NOT NULL,
+    -- Highest similarity to another document of the cluster, 1 for identical content
+    similarity REAL NOT NULL,
+    -- Whether another document of the cluster has identical content
+    exact BOOLEAN NOT NULL,
+    analyzed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_duplicate_documents_cluster_id ON duplicate_documents(cluster_id);
@@ -0,0 +1,8 @@
+-- The delivery each document was last ingested from: a hash of its source, external id,
+-- content hash and sync run. Events delivered again, by retries or replays, carry the same
+-- key, and the indexer completes them without rewriting the document or embedding it again.
+CREATE TABLE IF NOT EXISTS document_ingestion_keys (
+    document_id CHAR(26) PRIMARY KEY REFERENCES documents(id) ON DELETE CASCADE,
+    ingestion_key CHAR(64) NOT NULL,
+    ingested_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
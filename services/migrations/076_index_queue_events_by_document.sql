@@ -0,0 +1,6 @@
+-- Indexer replicas consume the queue concurrently, but only claim an event when no other
+-- event of its document is being processed or waiting ahead of it. This index serves that
+-- lookup over the unfinished events.
+CREATE INDEX IF NOT EXISTS idx_connector_events_queue_document
+    ON connector_events_queue (source_id, (payload->>'document_id'))
+    WHERE status IN ('pending', 'processing');
@@ -0,0 +1,13 @@
+CREATE TABLE feature_flags (
+    name TEXT PRIMARY KEY,
+    description TEXT NOT NULL DEFAULT '',
+    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    rollout_percentage SMALLINT NOT NULL DEFAULT 0
+        CHECK (rollout_percentage BETWEEN 0 AND 100),
+    tenants TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Flags each logged search ran with, so their variants can be compared
+ALTER TABLE search_query_logs ADD COLUMN feature_flags JSONB NOT NULL DEFAULT '{}';
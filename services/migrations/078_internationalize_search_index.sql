@@ -0,0 +1,30 @@
+-- Fold diacritics and tokenize Chinese and Japanese text in the BM25 index
+-- Titles and content are folded to ASCII where possible, so "cafe" matches "café".
+-- Content keeps each CJK character as a term of its own, so that the searcher can match
+-- bigrams of CJK queries as phrases; the default tokenizer keeps whole runs of CJK text,
+-- written without spaces, as single terms that no query matches.
+-- Existing documents are tokenized again when the index is rebuilt.
+
+DROP INDEX IF EXISTS document_search_idx;
+
+CREATE INDEX document_search_idx ON documents
+USING bm25 (
+    id,
+    (source_id::pdb.literal),
+    (external_id::pdb.literal),
+    (title::pdb.ngram(2, 3, 'ascii_folding=true')),
+    (content::pdb.chinese_compatible('ascii_folding=true')),
+    (content_type::pdb.literal),
+    file_size,
+    file_extension,
+    metadata,
+    permissions,
+    attributes,
+    created_at,
+    updated_at
+)
+WITH (
+    key_field = 'id',
+    background_layer_sizes = '100KB, 1MB, 10MB, 100MB, 1GB, 10GB',
+    mutable_segment_rows = 0
+);
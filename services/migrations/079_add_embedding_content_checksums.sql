@@ -0,0 +1,7 @@
+-- Record the checksum of the content an embedding's chunk offsets were computed against
+-- Offsets count characters of a document's stored content. When the content changes before
+-- the document is embedded again, the offsets no longer apply to it, so the searcher compares
+-- this checksum with the content it fetched before extracting chunk text.
+-- NULL for embeddings written before checksums were recorded.
+
+ALTER TABLE embeddings ADD COLUMN IF NOT EXISTS content_sha256 TEXT;
@@ -0,0 +1,16 @@
+-- Parents of attachments, comments and transcript segments, which are indexed as documents of
+-- their own. Connectors name the parent by external ID in the same source, so a child can be
+-- indexed before its parent; the parent document is looked up when the relationship is read.
+CREATE TABLE IF NOT EXISTS document_relationships (
+    document_id CHAR(26) PRIMARY KEY REFERENCES documents(id) ON DELETE CASCADE,
+    source_id CHAR(26) NOT NULL,
+    parent_external_id TEXT NOT NULL,
+    relationship_type VARCHAR(32) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT document_relationships_type_check
+        CHECK (relationship_type IN ('attachment', 'comment', 'transcript_segment'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_relationships_parent
+    ON document_relationships(source_id, parent_external_id);
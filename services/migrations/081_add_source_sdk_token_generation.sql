@@ -0,0 +1,4 @@
+-- Connectors authenticate their SDK calls with tokens the connector manager issues for each
+-- source, signed over the source ID and this generation. Rotating a source's tokens moves it
+-- to a new generation, which revokes every token issued before.
+ALTER TABLE sources ADD COLUMN IF NOT EXISTS sdk_token_generation INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,8 @@
+-- The connector manager replica scheduling each source's syncs. Replicas take over the
+-- sources of a replica that stopped renewing its leases once they expire.
+CREATE TABLE IF NOT EXISTS source_schedule_leases (
+    source_id CHAR(26) PRIMARY KEY REFERENCES sources(id) ON DELETE CASCADE,
+    holder TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    acquired_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,4 @@
+-- Syncs a connector stopped on shutting down, to be resumed once it is back
+ALTER TABLE sync_runs DROP CONSTRAINT IF EXISTS sync_runs_status_check;
+ALTER TABLE sync_runs ADD CONSTRAINT sync_runs_status_check
+CHECK (status IN ('running', 'completed', 'failed', 'cancelled', 'paused'));
@@ -0,0 +1,7 @@
+-- When each user was last emailed a digest of new documents. The next digest covers what
+-- changed since, and indexer replicas claim a digest by moving this forward.
+CREATE TABLE IF NOT EXISTS user_digests (
+    user_id CHAR(26) PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    last_sent_at TIMESTAMPTZ NOT NULL,
+    document_count INTEGER NOT NULL DEFAULT 0
+);
@@ -0,0 +1,3 @@
+-- Documents last modified in the source before a source's retention cutoff were deleted by
+-- retention enforcement. The indexer drops them when a sync sends them again.
+ALTER TABLE sources ADD COLUMN IF NOT EXISTS retention_cutoff TIMESTAMPTZ;
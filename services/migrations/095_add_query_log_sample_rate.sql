@@ -0,0 +1,3 @@
+-- Fraction of searches like each logged one that are logged: the sample rate, or 1 for slow
+-- searches, which are always logged. Analytics weight each row by its inverse.
+ALTER TABLE search_query_logs ADD COLUMN IF NOT EXISTS sample_rate REAL NOT NULL DEFAULT 1.0;
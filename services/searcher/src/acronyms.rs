@@ -0,0 +1,170 @@
+//! Acronym expansion of search queries.
+//!
+//! Active entries of the acronym dictionary add their expansion to the full-text query when
+//! the acronym appears in it, so a search for "SLA" also finds documents that only say
+//! "service level agreement". Entries are mined from indexed content by the indexer and only
+//! take effect once an admin has reviewed and activated them.
+
+use anyhow::Result;
+use shared::db::repositories::{Acronym, AcronymInput, AcronymRepository};
+use shared::DatabasePool;
+use std::collections::HashMap;
+
+/// Acronyms this short only expand when typed with the same case, so that common words such
+/// as "it" or "us" are not read as "IT" or "US".
+const CASE_SENSITIVE_MAX_LEN: usize = 2;
+const MAX_ACRONYM_LEN: usize = 20;
+
+/// Trim an entry and collapse the whitespace of its expansion.
+pub fn normalize(input: AcronymInput) -> AcronymInput {
+    AcronymInput {
+        acronym: input.acronym.trim().to_string(),
+        expansion: input
+            .expansion
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" "),
+        status: input.status,
+    }
+}
+
+/// Check a normalized dictionary entry before it is stored.
+pub fn validate(input: &AcronymInput) -> std::result::Result<(), String> {
+    let acronym = input.acronym.as_str();
+    if acronym.chars().count() < 2 || acronym.chars().count() > MAX_ACRONYM_LEN {
+        return Err(format!(
+            "Acronym must be between 2 and {} characters",
+            MAX_ACRONYM_LEN
+        ));
+    }
+    if !acronym.chars().all(char::is_alphanumeric) {
+        return Err(format!(
+            "Acronym '{}' may only contain letters and digits",
+            acronym
+        ));
+    }
+    if input.expansion.is_empty() {
+        return Err("Acronym expansion cannot be empty".to_string());
+    }
+    Ok(())
+}
+
+/// The active acronym dictionary, keyed by uppercased acronym.
+#[derive(Default)]
+pub struct AcronymDictionary {
+    entries: HashMap<String, Vec<Acronym>>,
+}
+
+impl AcronymDictionary {
+    pub fn new(acronyms: Vec<Acronym>) -> Self {
+        let mut entries: HashMap<String, Vec<Acronym>> = HashMap::new();
+        for acronym in acronyms {
+            entries
+                .entry(acronym.acronym.to_uppercase())
+                .or_default()
+                .push(acronym);
+        }
+        Self { entries }
+    }
+
+    pub async fn load(db_pool: &DatabasePool) -> Result<Self> {
+        let acronyms = AcronymRepository::new(db_pool.pool()).find_active().await?;
+        Ok(Self::new(acronyms))
+    }
+
+    /// Expansions of the acronyms in a query, in query order and without duplicates.
+    /// Expansions the query already spells out are left out.
+    pub fn expansions(&self, query: &str) -> Vec<String> {
+        if self.entries.is_empty() {
+            return vec![];
+        }
+        let lowercase_query = query.to_lowercase();
+        let mut expansions: Vec<String> = Vec::new();
+        for token in query.split(|c: char| !c.is_alphanumeric()) {
+            let Some(entries) = self.entries.get(&token.to_uppercase()) else {
+                continue;
+            };
+            for entry in entries {
+                let case_sensitive = entry.acronym.chars().count() <= CASE_SENSITIVE_MAX_LEN;
+                if case_sensitive && token != entry.acronym {
+                    continue;
+                }
+                let expansion = entry.expansion.to_lowercase();
+                if !lowercase_query.contains(&expansion) && !expansions.contains(&expansion) {
+                    expansions.push(expansion);
+                }
+            }
+        }
+        expansions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::db::repositories::{AcronymSource, AcronymStatus};
+    use time::OffsetDateTime;
+
+    fn acronym(acronym: &str, expansion: &str) -> Acronym {
+        let now = OffsetDateTime::now_utc();
+        Acronym {
+            id: format!("acronym-{}", acronym),
+            acronym: acronym.to_string(),
+            expansion: expansion.to_string(),
+            status: AcronymStatus::Active,
+            source: AcronymSource::Mined,
+            occurrences: 1,
+            document_count: 1,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_expansions() {
+        let dictionary = AcronymDictionary::new(vec![
+            acronym("SLA", "Service Level Agreement"),
+            acronym("IT", "Information Technology"),
+            acronym("PR", "Pull Request"),
+            acronym("PR", "Public Relations"),
+        ]);
+
+        assert_eq!(
+            dictionary.expansions("sla for support"),
+            vec!["service level agreement"]
+        );
+        assert_eq!(
+            dictionary.expansions("IT onboarding, SLA"),
+            vec!["information technology", "service level agreement"]
+        );
+        // Two letter acronyms need the exact case
+        assert!(dictionary.expansions("is it down").is_empty());
+        assert_eq!(
+            dictionary.expansions("PR review"),
+            vec!["pull request", "public relations"]
+        );
+        // Already spelled out
+        assert!(dictionary
+            .expansions("SLA service level agreement")
+            .is_empty());
+        assert!(dictionary.expansions("slack").is_empty());
+    }
+
+    #[test]
+    fn test_validate() {
+        let input = |acronym: &str, expansion: &str| AcronymInput {
+            acronym: acronym.to_string(),
+            expansion: expansion.to_string(),
+            status: AcronymStatus::Active,
+        };
+        assert!(validate(&input("SLA", "Service Level Agreement")).is_ok());
+        assert!(validate(&input("K8s", "Kubernetes")).is_ok());
+        assert!(validate(&input("S", "Single")).is_err());
+        assert!(validate(&input("R&D", "Research and Development")).is_err());
+        assert!(validate(&normalize(input("SLA", " "))).is_err());
+        assert_eq!(
+            normalize(input(" SLA ", " Service  Level\tAgreement ")).expansion,
+            "Service Level Agreement"
+        );
+    }
+}
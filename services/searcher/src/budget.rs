@@ -0,0 +1,204 @@
+//! Per-search cost limits and admission control.
+//!
+//! A search works within the configured [`QueryBudget`]: it ranks at most `max_candidates`
+//! full-text matches, lets the vector index compare the query with at most
+//! `max_vector_comparisons` candidates, re-ranks at most `max_rerank_candidates` hybrid
+//! results, and stops waiting for slow stages at its deadline. A search that hits a limit
+//! returns what it found within it, marked as degraded, rather than timing out.
+//!
+//! Searches started while `max_concurrent_searches` others are running are admitted to match
+//! full text only, without semantic search or fallbacks, so that bursts degrade results
+//! instead of queueing every search behind the slowest stage.
+
+use crate::query_log::LatencyBreakdown;
+use shared::QueryBudget;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+
+/// Time kept for ranking what a search found, after stages that stop at the deadline.
+const RANKING_RESERVE: Duration = Duration::from_millis(100);
+
+/// One search's budget, the time its stages took and whether the search exceeded it. The
+/// search counts as running until this is dropped.
+pub struct QueryCost {
+    budget: QueryBudget,
+    deadline: Instant,
+    full_text_only: bool,
+    degraded: AtomicBool,
+    latency: Mutex<LatencyBreakdown>,
+}
+
+impl QueryCost {
+    pub fn admit(budget: &QueryBudget) -> Self {
+        let running = IN_FLIGHT.fetch_add(1, Ordering::SeqCst);
+        let cost = Self {
+            budget: budget.clone(),
+            deadline: Instant::now() + Duration::from_millis(budget.deadline_ms),
+            full_text_only: running >= budget.max_concurrent_searches,
+            degraded: AtomicBool::new(false),
+            latency: Mutex::new(LatencyBreakdown::default()),
+        };
+        if cost.full_text_only {
+            cost.degrade(&format!(
+                "{} searches running, matching full text only",
+                running
+            ));
+        }
+        cost
+    }
+
+    /// Record that the search returns less than it would have within budget.
+    pub fn degrade(&self, reason: &str) {
+        info!("Search degraded: {}", reason);
+        self.degraded.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_degraded(&self) -> bool {
+        self.degraded.load(Ordering::SeqCst)
+    }
+
+    /// Record the time a stage of the search took.
+    pub fn record_latency(&self, update: impl FnOnce(&mut LatencyBreakdown)) {
+        if let Ok(mut latency) = self.latency.lock() {
+            update(&mut latency);
+        }
+    }
+
+    /// The time each stage of the search took so far.
+    pub fn latency(&self) -> LatencyBreakdown {
+        self.latency
+            .lock()
+            .map(|latency| latency.clone())
+            .unwrap_or_default()
+    }
+
+    /// Whether the search was admitted under load, to match full text only.
+    pub fn full_text_only(&self) -> bool {
+        self.full_text_only
+    }
+
+    /// Time left before the deadline.
+    pub fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+
+    pub fn expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// How long a stage that can be left out of the results may take, at most `timeout`.
+    pub fn stage_timeout(&self, timeout: Duration) -> Duration {
+        timeout.min(self.remaining().saturating_sub(RANKING_RESERVE))
+    }
+
+    /// How many of a page of `limit` full-text results at `offset` to fetch.
+    pub fn fulltext_limit(&self, offset: i64, limit: i64) -> i64 {
+        let within = page_within(offset, limit, self.budget.max_candidates);
+        if within < limit {
+            self.degrade(&format!(
+                "results past {} full-text candidates skipped",
+                self.budget.max_candidates
+            ));
+        }
+        within
+    }
+
+    /// How many of a page of `limit` semantic chunks at `offset` to fetch. The vector index
+    /// finds no more chunks than it compares the query with.
+    pub fn vector_limit(&self, offset: i64, limit: i64) -> i64 {
+        let within = page_within(offset, limit, self.budget.max_vector_comparisons);
+        if within < limit {
+            self.degrade(&format!(
+                "chunks past {} vector comparisons skipped",
+                self.budget.max_vector_comparisons
+            ));
+        }
+        within
+    }
+
+    pub fn max_vector_comparisons(&self) -> i64 {
+        self.budget.max_vector_comparisons
+    }
+
+    /// Keep the `results` that best fit so far within the re-ranking budget. They must be
+    /// sorted best first.
+    pub fn limit_rerank<T>(&self, results: &mut Vec<T>) {
+        if results.len() > self.budget.max_rerank_candidates {
+            self.degrade(&format!(
+                "{} results re-ranked out of {}",
+                self.budget.max_rerank_candidates,
+                results.len()
+            ));
+            results.truncate(self.budget.max_rerank_candidates);
+        }
+    }
+}
+
+impl Drop for QueryCost {
+    fn drop(&mut self) {
+        IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// The size of the part of a page of `limit` at `offset` that falls within the first `max`.
+fn page_within(offset: i64, limit: i64, max: i64) -> i64 {
+    (max - offset).clamp(0, limit.max(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn budget(max_concurrent_searches: usize) -> QueryBudget {
+        QueryBudget {
+            max_candidates: 100,
+            max_vector_comparisons: 40,
+            max_rerank_candidates: 3,
+            deadline_ms: 10_000,
+            max_concurrent_searches,
+        }
+    }
+
+    #[test]
+    fn test_page_within() {
+        assert_eq!(page_within(0, 20, 100), 20);
+        assert_eq!(page_within(90, 20, 100), 10);
+        assert_eq!(page_within(100, 20, 100), 0);
+        assert_eq!(page_within(150, 20, 100), 0);
+    }
+
+    #[test]
+    fn test_limits_degrade_only_when_exceeded() {
+        let cost = QueryCost::admit(&budget(usize::MAX));
+        assert_eq!(cost.fulltext_limit(0, 20), 20);
+        assert_eq!(cost.vector_limit(20, 20), 20);
+        let mut results = vec![1, 2, 3];
+        cost.limit_rerank(&mut results);
+        assert!(!cost.is_degraded());
+
+        assert_eq!(cost.vector_limit(30, 20), 10);
+        assert!(cost.is_degraded());
+
+        let cost = QueryCost::admit(&budget(usize::MAX));
+        let mut results = vec![1, 2, 3, 4, 5];
+        cost.limit_rerank(&mut results);
+        assert_eq!(results, vec![1, 2, 3]);
+        assert!(cost.is_degraded());
+    }
+
+    #[test]
+    fn test_admission_under_load() {
+        let overloaded = QueryCost::admit(&budget(0));
+        assert!(overloaded.full_text_only());
+        assert!(overloaded.is_degraded());
+
+        let cost = QueryCost::admit(&budget(usize::MAX));
+        assert!(!cost.full_text_only());
+        assert!(!cost.is_degraded());
+        assert!(!cost.expired());
+    }
+}
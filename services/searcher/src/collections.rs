@@ -0,0 +1,144 @@
+//! Searches limited to a user-curated collection.
+//!
+//! A search is limited to a collection by the request's `collection` or by a
+//! `collection:<name>` term in the query, quoted when the name has spaces, e.g.
+//! `collection:"Q3 planning" roadmap`. The term is taken out of the query before it is matched.
+//! Collections are named by ID or name, and only ones the user owns or has had shared with
+//! them can be searched.
+
+use shared::db::repositories::CollectionInput;
+
+const FILTER_PREFIX: &str = "collection:";
+const MAX_NAME_LEN: usize = 200;
+/// Most documents added to a collection in one request.
+pub const MAX_DOCUMENTS_PER_REQUEST: usize = 500;
+
+/// Collapse the whitespace of a collection's name and drop an empty description.
+pub fn normalize(input: CollectionInput) -> CollectionInput {
+    CollectionInput {
+        name: input.name.split_whitespace().collect::<Vec<_>>().join(" "),
+        description: input
+            .description
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty()),
+    }
+}
+
+/// Check a normalized collection before it is stored.
+pub fn validate(input: &CollectionInput) -> std::result::Result<(), String> {
+    if input.name.is_empty() {
+        return Err("Collection name cannot be empty".to_string());
+    }
+    if input.name.chars().count() > MAX_NAME_LEN {
+        return Err(format!(
+            "Collection name must be at most {} characters",
+            MAX_NAME_LEN
+        ));
+    }
+    // Names are quoted in `collection:` terms
+    if input.name.contains('"') {
+        return Err("Collection name cannot contain double quotes".to_string());
+    }
+    Ok(())
+}
+
+/// The query without its first `collection:` term, and the collection the term names.
+pub fn take_filter(query: &str) -> (String, Option<String>) {
+    let mut search_from = 0;
+    while let Some(found) = query[search_from..].find(FILTER_PREFIX) {
+        let start = search_from + found;
+        let value_start = start + FILTER_PREFIX.len();
+        search_from = value_start;
+        if query[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| !c.is_whitespace())
+        {
+            continue;
+        }
+
+        let rest = &query[value_start..];
+        let (value, len) = match rest.strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"').unwrap_or(quoted.len());
+                (&quoted[..end], (end + 2).min(rest.len()))
+            }
+            None => {
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                (&rest[..end], end)
+            }
+        };
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+
+        let remaining = format!("{} {}", &query[..start], &query[value_start + len..]);
+        let remaining = remaining.split_whitespace().collect::<Vec<_>>().join(" ");
+        return (remaining, Some(value.to_string()));
+    }
+    (query.to_string(), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_and_validate() {
+        let input = normalize(CollectionInput {
+            name: "  Q3   planning ".to_string(),
+            description: Some("  ".to_string()),
+        });
+        assert_eq!(input.name, "Q3 planning");
+        assert!(input.description.is_none());
+        assert!(validate(&input).is_ok());
+
+        let unnamed = normalize(CollectionInput {
+            name: " ".to_string(),
+            description: None,
+        });
+        assert!(validate(&unnamed).is_err());
+        let quoted = CollectionInput {
+            name: "\"Best\" docs".to_string(),
+            description: None,
+        };
+        assert!(validate(&quoted).is_err());
+    }
+
+    #[test]
+    fn test_take_filter() {
+        assert_eq!(
+            take_filter("roadmap collection:planning review"),
+            ("roadmap review".to_string(), Some("planning".to_string()))
+        );
+        assert_eq!(
+            take_filter("collection:\"Q3 planning\" roadmap"),
+            ("roadmap".to_string(), Some("Q3 planning".to_string()))
+        );
+        assert_eq!(
+            take_filter("roadmap collection:\"Q3 planning"),
+            ("roadmap".to_string(), Some("Q3 planning".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_take_filter_ignores_other_terms() {
+        assert_eq!(take_filter("roadmap"), ("roadmap".to_string(), None));
+        assert_eq!(
+            take_filter("subcollection:planning"),
+            ("subcollection:planning".to_string(), None)
+        );
+        assert_eq!(
+            take_filter("collection: roadmap"),
+            ("collection: roadmap".to_string(), None)
+        );
+        assert_eq!(
+            take_filter("collection:\"\" roadmap collection:planning"),
+            (
+                "collection:\"\" roadmap".to_string(),
+                Some("planning".to_string())
+            )
+        );
+    }
+}
@@ -0,0 +1,301 @@
+//! A Confluence-compatible search API, so that tools written against Confluence's REST search
+//! can be pointed at Omni while migrating. It accepts the subset of CQL such tools commonly
+//! send and answers in Confluence's response shape, searching everything the user can see in
+//! Omni rather than only Confluence pages.
+//!
+//! Supported clauses, joined with `AND`: `text ~`, `siteSearch ~`, `title ~`/`=`, `type =`/`in`
+//! (`page` and `blogpost`, which limit results to Confluence) and `space.id =`/`in`. A trailing
+//! `ORDER BY` is ignored, as results are always ranked by relevance.
+
+use crate::models::SearchRequest;
+use shared::models::{AttributeFilter, SearchField, SourceType};
+use std::collections::HashMap;
+
+pub const DEFAULT_LIMIT: i64 = 25;
+pub const MAX_LIMIT: i64 = 100;
+
+/// What a CQL query asks for, in terms Omni's search understands.
+#[derive(Debug, Default, PartialEq)]
+pub struct CqlQuery {
+    pub text: Vec<String>,
+    pub title: Vec<String>,
+    /// Set when the query limits results to Confluence content types.
+    pub confluence_only: bool,
+    pub space_ids: Vec<String>,
+}
+
+impl CqlQuery {
+    pub fn into_search_request(self, user_id: String, start: i64, limit: i64) -> SearchRequest {
+        let fields = self.text.is_empty().then(|| vec![SearchField::Title]);
+        let query = self
+            .text
+            .into_iter()
+            .chain(self.title)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let attribute_filters = (!self.space_ids.is_empty()).then(|| {
+            HashMap::from([(
+                "space_id".to_string(),
+                AttributeFilter::AnyOf(self.space_ids.into_iter().map(Into::into).collect()),
+            )])
+        });
+
+        SearchRequest {
+            query,
+            user_id: Some(user_id),
+            source_types: self.confluence_only.then(|| vec![SourceType::Confluence]),
+            attribute_filters,
+            fields,
+            limit: Some(limit.clamp(1, MAX_LIMIT)),
+            offset: Some(start.max(0)),
+            include_facets: Some(false),
+            // Dropping filters would answer a CQL query with results it excludes
+            fallback: Some(false),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Str(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+}
+
+fn tokenize(cql: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = cql.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' | ',' => {
+                chars.next();
+                tokens.push(match c {
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    _ => Token::Comma,
+                });
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => value.extend(chars.next()),
+                        Some(next) if next == c => break,
+                        Some(next) => value.push(next),
+                        None => return Err("Unterminated string in CQL".to_string()),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            '~' | '=' => {
+                chars.next();
+                tokens.push(Token::Op(if c == '~' { "~" } else { "=" }));
+            }
+            '!' => {
+                chars.next();
+                return Err(match chars.next() {
+                    Some('=') | Some('~') => "Negated CQL clauses are not supported".to_string(),
+                    _ => "Unexpected '!' in CQL".to_string(),
+                });
+            }
+            c if c.is_alphanumeric() || matches!(c, '_' | '.' | '-') => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_alphanumeric() || matches!(c, '_' | '.' | '-')) {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+            c => return Err(format!("Unexpected '{}' in CQL", c)),
+        }
+    }
+    Ok(tokens)
+}
+
+fn is_keyword(token: &Token, keyword: &str) -> bool {
+    matches!(token, Token::Word(word) if word.eq_ignore_ascii_case(keyword))
+}
+
+/// Parse the supported subset of CQL, or explain what is not supported.
+pub fn parse_cql(cql: &str) -> Result<CqlQuery, String> {
+    let tokens = tokenize(cql)?;
+    let mut query = CqlQuery::default();
+    let mut tokens = tokens.iter();
+
+    loop {
+        let field = match tokens.next() {
+            Some(Token::Word(field)) => field.to_lowercase(),
+            Some(_) => return Err("Expected a field name in CQL".to_string()),
+            None => return Err("Expected a CQL clause".to_string()),
+        };
+        let values = match tokens.next() {
+            Some(Token::Op(op)) => match tokens.next() {
+                Some(Token::Word(value) | Token::Str(value)) => (*op, vec![value.clone()]),
+                _ => return Err(format!("Expected a value after {} {}", field, op)),
+            },
+            Some(token) if is_keyword(token, "in") => {
+                if tokens.next() != Some(&Token::LParen) {
+                    return Err(format!("Expected a list after {} in", field));
+                }
+                let mut values = Vec::new();
+                loop {
+                    match tokens.next() {
+                        Some(Token::Word(value) | Token::Str(value)) => values.push(value.clone()),
+                        _ => return Err(format!("Expected a value in the list for {}", field)),
+                    }
+                    match tokens.next() {
+                        Some(Token::Comma) => continue,
+                        Some(Token::RParen) => break,
+                        _ => return Err(format!("Unterminated list for {}", field)),
+                    }
+                }
+                ("in", values)
+            }
+            _ => return Err(format!("Expected an operator after {}", field)),
+        };
+
+        match (field.as_str(), values) {
+            ("text" | "sitesearch", ("~", values)) => query.text.extend(values),
+            ("title", ("~" | "=", values)) => query.title.extend(values),
+            ("type", ("=" | "in", values)) => {
+                if let Some(other) = values
+                    .iter()
+                    .find(|value| !matches!(value.to_lowercase().as_str(), "page" | "blogpost"))
+                {
+                    return Err(format!("Unsupported content type: {}", other));
+                }
+                query.confluence_only = true;
+            }
+            ("space.id", ("=" | "in", values)) => query.space_ids.extend(values),
+            (field, (op, _)) => {
+                return Err(format!("Unsupported CQL clause: {} {}", field, op));
+            }
+        }
+
+        match tokens.next() {
+            None => break,
+            Some(token) if is_keyword(token, "and") => continue,
+            Some(token) if is_keyword(token, "order") => break,
+            Some(token) if is_keyword(token, "or") || is_keyword(token, "not") => {
+                return Err("Only clauses joined with AND are supported".to_string());
+            }
+            Some(_) => return Err("Expected AND between CQL clauses".to_string()),
+        }
+    }
+
+    if query
+        .text
+        .iter()
+        .chain(&query.title)
+        .all(|term| term.trim().is_empty())
+    {
+        return Err("CQL must contain a text, siteSearch or title clause".to_string());
+    }
+    Ok(query)
+}
+
+/// Rewrite a highlight's `**` match markers to the ones Confluence excerpts use.
+pub fn excerpt(highlight: &str) -> String {
+    let mut excerpt = String::with_capacity(highlight.len());
+    for (i, part) in highlight.split("**").enumerate() {
+        if i > 0 {
+            excerpt.push_str(if i % 2 == 1 {
+                "@@@hl@@@"
+            } else {
+                "@@@endhl@@@"
+            });
+        }
+        excerpt.push_str(part);
+    }
+    excerpt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cql() {
+        assert_eq!(
+            parse_cql(r#"text ~ "quarterly roadmap""#).unwrap(),
+            CqlQuery {
+                text: vec!["quarterly roadmap".to_string()],
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            parse_cql(
+                r#"siteSearch ~ 'on\'call' AND type IN (page, "blogpost") and space.id = 42 ORDER BY lastmodified DESC"#
+            )
+            .unwrap(),
+            CqlQuery {
+                text: vec!["on'call".to_string()],
+                confluence_only: true,
+                space_ids: vec!["42".to_string()],
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            parse_cql("title = Runbook").unwrap().title,
+            vec!["Runbook".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_parse_cql_rejects_unsupported() {
+        for cql in [
+            "",
+            "type = page",
+            r#"text ~ "a" OR title ~ "b""#,
+            r#"text != "a""#,
+            r#"text ~ "a" AND type = attachment"#,
+            r#"creator = jdoe AND text ~ "a""#,
+            r#"text ~ "unterminated"#,
+            r#"text ~ "a" title ~ "b""#,
+            "space.id in (1, 2",
+        ] {
+            assert!(parse_cql(cql).is_err(), "accepted {:?}", cql);
+        }
+    }
+
+    #[test]
+    fn test_into_search_request() {
+        let request = parse_cql(r#"title ~ "runbook" AND type = page AND space.id in (1, 2)"#)
+            .unwrap()
+            .into_search_request("user-1".to_string(), -5, 1000);
+        assert_eq!(request.query, "runbook");
+        assert_eq!(request.fields, Some(vec![SearchField::Title]));
+        assert_eq!(request.source_types, Some(vec![SourceType::Confluence]));
+        assert_eq!(request.offset, Some(0));
+        assert_eq!(request.limit, Some(MAX_LIMIT));
+        assert_eq!(request.fallback, Some(false));
+        assert!(request.attribute_filters.unwrap().contains_key("space_id"));
+
+        let request = parse_cql(r#"text ~ "deploy" AND title ~ "runbook""#)
+            .unwrap()
+            .into_search_request("user-1".to_string(), 10, 5);
+        assert_eq!(request.query, "deploy runbook");
+        assert_eq!(request.fields, None);
+        assert_eq!(request.source_types, None);
+    }
+
+    #[test]
+    fn test_excerpt() {
+        assert_eq!(
+            excerpt("the **deploy** runbook **steps**"),
+            "the @@@hl@@@deploy@@@endhl@@@ runbook @@@hl@@@steps@@@endhl@@@"
+        );
+        assert_eq!(excerpt("no matches"), "no matches");
+    }
+}
@@ -0,0 +1,209 @@
+//! Collapsing of duplicate search results.
+//!
+//! The same file is often indexed more than once, e.g. from Google Drive and as a Confluence
+//! attachment. Results whose content has the same SHA-256 are collapsed into one, keeping the
+//! copy the user has the most direct access to and listing the others as its alternates.
+
+use crate::models::{AlternateDocument, SearchResult};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+/// How directly a user can see a document, weakest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Access {
+    Public,
+    Group,
+    Direct,
+}
+
+fn access(permissions: &JsonValue, user_email: Option<&str>) -> Access {
+    let Some(user_email) = user_email else {
+        return Access::Public;
+    };
+    let granted = permissions
+        .get("users")
+        .and_then(|users| users.as_array())
+        .is_some_and(|users| {
+            users
+                .iter()
+                .filter_map(|user| user.as_str())
+                .any(|user| user.eq_ignore_ascii_case(user_email))
+        });
+    if granted {
+        Access::Direct
+    } else if permissions.get("public").and_then(|p| p.as_bool()) == Some(true) {
+        Access::Public
+    } else {
+        // Results are permission filtered, so the user must be in one of the groups
+        Access::Group
+    }
+}
+
+/// Collapse results with identical content. `content_hashes` maps document IDs to the hash of
+/// their content; results without one are never collapsed. Each group of duplicates keeps the
+/// copy with the most direct access for `user_email`, the best ranked among equals, at the
+/// position of the group's best ranked result.
+pub fn collapse(
+    results: Vec<SearchResult>,
+    content_hashes: &HashMap<String, String>,
+    user_email: Option<&str>,
+) -> Vec<SearchResult> {
+    let mut groups: Vec<Vec<SearchResult>> = Vec::with_capacity(results.len());
+    let mut group_by_hash: HashMap<&str, usize> = HashMap::new();
+    for result in results {
+        match content_hashes.get(&result.document.id) {
+            Some(hash) => match group_by_hash.get(hash.as_str()) {
+                Some(&index) => groups[index].push(result),
+                None => {
+                    group_by_hash.insert(hash.as_str(), groups.len());
+                    groups.push(vec![result]);
+                }
+            },
+            None => groups.push(vec![result]),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|mut group| {
+            if group.len() == 1 {
+                return group.remove(0);
+            }
+            // max_by_key keeps the last maximum, so search from the worst ranked copy
+            let keep = group
+                .iter()
+                .enumerate()
+                .rev()
+                .max_by_key(|(_, result)| access(&result.document.permissions, user_email))
+                .map(|(index, _)| index)
+                .unwrap_or(0);
+            let best_score = group[0].score;
+            let mut kept = group.remove(keep);
+            kept.score = kept.score.max(best_score);
+            kept.alternates
+                .extend(group.into_iter().map(|duplicate| AlternateDocument {
+                    id: duplicate.document.id,
+                    source_id: duplicate.document.source_id,
+                    title: duplicate.document.title,
+                    url: duplicate.document.url,
+                }));
+            kept
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use shared::models::Document;
+    use time::OffsetDateTime;
+
+    fn result(id: &str, score: f32, permissions: JsonValue) -> SearchResult {
+        let now = OffsetDateTime::now_utc();
+        SearchResult {
+            document: Document {
+                id: id.to_string(),
+                source_id: format!("source-{}", id),
+                external_id: id.to_string(),
+                title: id.to_string(),
+                content_id: None,
+                content_type: None,
+                file_size: None,
+                file_extension: None,
+                url: Some(format!("https://example.com/{}", id)),
+                metadata: json!({}),
+                permissions,
+                attributes: json!({}),
+                created_at: now,
+                updated_at: now,
+                last_indexed_at: now,
+                preview: None,
+            },
+            score,
+            highlights: vec![],
+            match_type: "fulltext".to_string(),
+            content: None,
+            pinned: false,
+            ranking: None,
+            alternates: vec![],
+            anchor: None,
+            children: vec![],
+        }
+    }
+
+    fn hashes(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(id, hash)| (id.to_string(), hash.to_string()))
+            .collect()
+    }
+
+    fn ids(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.document.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_keeps_most_direct_copy_in_best_position() {
+        let results = vec![
+            result(
+                "drive",
+                0.9,
+                json!({"public": true, "users": [], "groups": []}),
+            ),
+            result(
+                "other",
+                0.8,
+                json!({"public": true, "users": [], "groups": []}),
+            ),
+            result(
+                "confluence",
+                0.7,
+                json!({"public": false, "users": ["Alice@example.com"], "groups": []}),
+            ),
+        ];
+        let content_hashes = hashes(&[("drive", "abc"), ("other", "def"), ("confluence", "abc")]);
+
+        let collapsed = collapse(results, &content_hashes, Some("alice@example.com"));
+        assert_eq!(ids(&collapsed), vec!["confluence", "other"]);
+        assert_eq!(collapsed[0].score, 0.9);
+        assert_eq!(collapsed[0].alternates.len(), 1);
+        assert_eq!(collapsed[0].alternates[0].id, "drive");
+        assert_eq!(collapsed[0].alternates[0].source_id, "source-drive");
+        assert!(collapsed[1].alternates.is_empty());
+    }
+
+    #[test]
+    fn test_group_access_beats_public_and_ties_keep_best_ranked() {
+        let group_only = json!({"public": false, "users": [], "groups": ["eng@example.com"]});
+        let public = json!({"public": true, "users": [], "groups": []});
+        let results = vec![
+            result("a", 0.9, public.clone()),
+            result("b", 0.8, group_only.clone()),
+            result("c", 0.7, group_only),
+        ];
+        let content_hashes = hashes(&[("a", "abc"), ("b", "abc"), ("c", "abc")]);
+
+        let collapsed = collapse(results.clone(), &content_hashes, Some("bob@example.com"));
+        assert_eq!(ids(&collapsed), vec!["b"]);
+        assert_eq!(
+            collapsed[0]
+                .alternates
+                .iter()
+                .map(|a| a.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "c"]
+        );
+
+        // Without a user every copy is equally accessible
+        let collapsed = collapse(results, &content_hashes, None);
+        assert_eq!(ids(&collapsed), vec!["a"]);
+    }
+
+    #[test]
+    fn test_results_without_content_are_kept() {
+        let results = vec![result("a", 0.9, json!({})), result("b", 0.8, json!({}))];
+        let collapsed = collapse(results, &HashMap::new(), None);
+        assert_eq!(ids(&collapsed), vec!["a", "b"]);
+    }
+}
@@ -0,0 +1,300 @@
+//! Facet counts for search results, cached in Redis.
+//!
+//! Counts cover the documents the search that produced the results matched: the text
+//! matches of the request the results came from, after any fallback relaxed it, and the
+//! documents semantic search added. So they describe the result set rather than what the
+//! original query would have matched.
+//!
+//! Entries are keyed by the query, the filters, the permission scope (the user and the
+//! collection searched) and the index version the indexer bumps whenever it writes documents,
+//! so counts from before a change are not served after it. Entries live for
+//! [`CACHE_TTL_SECS`]; once older than [`REFRESH_AFTER_SECS`] they are still served, while one
+//! replica counts them again in the background.
+
+use crate::models::{SearchMode, SearchRequest, SearchResult};
+use anyhow::Result;
+use redis::{AsyncCommands, Client as RedisClient};
+use serde::{Deserialize, Serialize};
+use shared::db::repositories::DocumentRepository;
+use shared::models::{AttributeFilter, Facet, SearchField};
+use shared::DatabasePool;
+use shared::{cache, index_version};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::time::Instant;
+use time::OffsetDateTime;
+use tracing::{debug, info, warn};
+
+/// Seconds a cache entry is served for.
+pub const CACHE_TTL_SECS: u64 = 60;
+
+/// Age in seconds after which an entry is counted again in the background.
+pub const REFRESH_AFTER_SECS: i64 = 15;
+
+/// Seconds a replica holds the lock for counting an entry again.
+const REFRESH_LOCK_SECS: u64 = 10;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedFacets {
+    computed_at: i64,
+    facets: Vec<Facet>,
+}
+
+/// What the facets of one result set are counted over.
+#[derive(Debug, Clone)]
+struct FacetQuery {
+    /// The full-text query the results matched, none for semantic searches.
+    text: Option<String>,
+    fuzzy: bool,
+    fields: Vec<SearchField>,
+    source_ids: Vec<String>,
+    content_types: Option<Vec<String>>,
+    attribute_filters: Option<HashMap<String, AttributeFilter>>,
+    collection_id: Option<String>,
+    collection_updated_at: Option<OffsetDateTime>,
+    user_email: Option<String>,
+    /// The user's groups when resolved ahead. Left out of the cache key, as they follow from
+    /// the user.
+    user_groups: Option<Vec<String>>,
+    /// Results found by semantic search rather than by matching the text.
+    semantic_document_ids: Vec<String>,
+}
+
+impl FacetQuery {
+    fn new(request: &SearchRequest, source_ids: &[String], results: &[SearchResult]) -> Self {
+        let text = match request.search_mode() {
+            SearchMode::Semantic => None,
+            SearchMode::Fulltext | SearchMode::Hybrid => Some(request.fulltext_query()),
+        };
+        let mut semantic_document_ids: Vec<String> = results
+            .iter()
+            .filter(|r| r.match_type == "semantic")
+            .map(|r| r.document.id.clone())
+            .collect();
+        semantic_document_ids.sort();
+
+        Self {
+            text,
+            fuzzy: request.fuzzy,
+            fields: request.fields().to_vec(),
+            source_ids: source_ids.to_vec(),
+            content_types: request.content_types.clone(),
+            attribute_filters: request.attribute_filters.clone(),
+            collection_id: request.collection_id().map(str::to_string),
+            collection_updated_at: request.collection_filter.as_ref().map(|c| c.updated_at),
+            user_email: request.user_email().cloned(),
+            user_groups: request.user_groups.clone(),
+            semantic_document_ids,
+        }
+    }
+
+    fn cache_key(&self, index_version: u64) -> String {
+        let mut hasher = DefaultHasher::new();
+        self.text.hash(&mut hasher);
+        self.fuzzy.hash(&mut hasher);
+        self.fields.hash(&mut hasher);
+        self.source_ids.hash(&mut hasher);
+        self.content_types.hash(&mut hasher);
+        if let Some(attribute_filters) = &self.attribute_filters {
+            let sorted: BTreeMap<_, _> = attribute_filters.iter().collect();
+            let json = serde_json::to_string(&sorted).unwrap_or_default();
+            json.hash(&mut hasher);
+        }
+        self.collection_id.hash(&mut hasher);
+        self.collection_updated_at.hash(&mut hasher);
+        self.user_email.hash(&mut hasher);
+        self.semantic_document_ids.hash(&mut hasher);
+        index_version.hash(&mut hasher);
+
+        cache::keys().cache("facets", format_args!("{:x}", hasher.finish()))
+    }
+
+    async fn count(&self, db_pool: &DatabasePool) -> Result<Vec<Facet>> {
+        let start_time = Instant::now();
+        let facets = DocumentRepository::new(db_pool.pool())
+            .with_user_groups(self.user_groups.clone())
+            .get_facet_counts(
+                self.text.as_deref(),
+                &self.source_ids,
+                self.content_types.as_deref(),
+                self.attribute_filters.as_ref(),
+                &self.fields,
+                self.fuzzy,
+                &self.semantic_document_ids,
+                self.collection_id.as_deref(),
+                self.user_email.as_deref(),
+            )
+            .await?;
+        debug!("Facets counted in {:?}", start_time.elapsed());
+        Ok(facets)
+    }
+}
+
+pub struct FacetCounter {
+    db_pool: DatabasePool,
+    redis_client: RedisClient,
+}
+
+impl FacetCounter {
+    pub fn new(db_pool: DatabasePool, redis_client: RedisClient) -> Self {
+        Self {
+            db_pool,
+            redis_client,
+        }
+    }
+
+    /// Facet counts for `results`, found by searching `source_ids` with `request`.
+    pub async fn counts(
+        &self,
+        request: &SearchRequest,
+        source_ids: &[String],
+        results: &[SearchResult],
+    ) -> Result<Vec<Facet>> {
+        let query = FacetQuery::new(request, source_ids, results);
+        let version = match index_version::current(&self.redis_client).await {
+            Ok(version) => version,
+            Err(e) => {
+                warn!(
+                    "Failed to read the index version, not caching facets: {}",
+                    e
+                );
+                return query.count(&self.db_pool).await;
+            }
+        };
+        let cache_key = query.cache_key(version);
+
+        if let Some(cached) = self.get_cached(&cache_key).await {
+            let age = OffsetDateTime::now_utc().unix_timestamp() - cached.computed_at;
+            if age >= REFRESH_AFTER_SECS {
+                self.refresh_in_background(query, cache_key).await;
+            }
+            return Ok(cached.facets);
+        }
+
+        let facets = query.count(&self.db_pool).await?;
+        store(&self.redis_client, &cache_key, &facets).await;
+        Ok(facets)
+    }
+
+    async fn get_cached(&self, cache_key: &str) -> Option<CachedFacets> {
+        let mut conn = self
+            .redis_client
+            .get_multiplexed_async_connection()
+            .await
+            .ok()?;
+        let cached: Option<String> = conn.get(cache_key).await.ok()?;
+        serde_json::from_str(&cached?).ok()
+    }
+
+    /// Count an entry again, unless another request or replica already is.
+    async fn refresh_in_background(&self, query: FacetQuery, cache_key: String) {
+        let Ok(mut conn) = self.redis_client.get_multiplexed_async_connection().await else {
+            return;
+        };
+        let lock_key = format!("{}:refresh", cache_key);
+        let locked: Option<String> = redis::cmd("SET")
+            .arg(&lock_key)
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(REFRESH_LOCK_SECS)
+            .query_async(&mut conn)
+            .await
+            .unwrap_or(None);
+        if locked.is_none() {
+            return;
+        }
+
+        let db_pool = self.db_pool.clone();
+        let redis_client = self.redis_client.clone();
+        tokio::spawn(async move {
+            match query.count(&db_pool).await {
+                Ok(facets) => store(&redis_client, &cache_key, &facets).await,
+                Err(e) => info!("Failed to refresh facet counts: {}", e),
+            }
+        });
+    }
+}
+
+async fn store(redis_client: &RedisClient, cache_key: &str, facets: &[Facet]) {
+    let cached = CachedFacets {
+        computed_at: OffsetDateTime::now_utc().unix_timestamp(),
+        facets: facets.to_vec(),
+    };
+    if let Ok(mut conn) = redis_client.get_multiplexed_async_connection().await {
+        if let Ok(json) = serde_json::to_string(&cached) {
+            let _: Result<(), _> = conn.set_ex(cache_key, json, CACHE_TTL_SECS).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn request() -> SearchRequest {
+        SearchRequest {
+            query: "quarterly plan".to_string(),
+            user_email: Some("alice@example.com".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn key(request: &SearchRequest, index_version: u64) -> String {
+        FacetQuery::new(request, &["source-1".to_string()], &[]).cache_key(index_version)
+    }
+
+    #[test]
+    fn test_cache_key_scope() {
+        let base = key(&request(), 1);
+        assert_eq!(key(&request(), 1), base);
+        assert_ne!(key(&request(), 2), base);
+
+        let mut other_user = request();
+        other_user.user_email = Some("bob@example.com".to_string());
+        assert_ne!(key(&other_user, 1), base);
+
+        let mut filtered = request();
+        filtered.content_types = Some(vec!["spreadsheet".to_string()]);
+        assert_ne!(key(&filtered, 1), base);
+
+        let mut fuzzy = request();
+        fuzzy.fuzzy = true;
+        assert_ne!(key(&fuzzy, 1), base);
+
+        // Paging does not change the counts
+        let mut next_page = request();
+        next_page.offset = Some(20);
+        assert_eq!(key(&next_page, 1), base);
+    }
+
+    #[test]
+    fn test_cache_key_ignores_attribute_filter_order() {
+        let filters = |names: &[&str]| {
+            let mut request = request();
+            let mut filters = HashMap::new();
+            for name in names {
+                filters.insert(name.to_string(), AttributeFilter::Exact(json!(name)));
+            }
+            request.attribute_filters = Some(filters);
+            request
+        };
+        let names: Vec<String> = (0..20).map(|i| format!("attr{}", i)).collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        let reversed: Vec<&str> = names.iter().rev().copied().collect();
+        assert_eq!(key(&filters(&names), 1), key(&filters(&reversed), 1));
+    }
+
+    #[test]
+    fn test_semantic_searches_count_only_their_results() {
+        let mut semantic = request();
+        semantic.mode = Some(SearchMode::Semantic);
+        let query = FacetQuery::new(&semantic, &[], &[]);
+        assert!(query.text.is_none());
+
+        let query = FacetQuery::new(&request(), &[], &[]);
+        assert_eq!(query.text.as_deref(), Some("quarterly plan"));
+    }
+}
@@ -0,0 +1,112 @@
+//! Fallbacks for searches that find nothing.
+//!
+//! A query that returns no results is retried with progressively relaxed versions of the
+//! request, in the order of [`FallbackStrategy`], until one finds something. Only the first
+//! page falls back, so paging through fallback results stays consistent.
+
+use crate::models::{FallbackStrategy, SearchMode, SearchRequest};
+use shared::models::SearchField;
+
+pub const STRATEGIES: [FallbackStrategy; 4] = [
+    FallbackStrategy::DropFilters,
+    FallbackStrategy::AnyField,
+    FallbackStrategy::SpellCorrected,
+    FallbackStrategy::SemanticOnly,
+];
+
+/// The request relaxed by a strategy, or `None` when the strategy would not change it.
+pub fn relax(request: &SearchRequest, strategy: FallbackStrategy) -> Option<SearchRequest> {
+    let mut relaxed = request.clone();
+    match strategy {
+        FallbackStrategy::DropFilters => {
+            let filtered = request.source_types.as_ref().is_some_and(|s| !s.is_empty())
+                || request
+                    .content_types
+                    .as_ref()
+                    .is_some_and(|c| !c.is_empty())
+                || request
+                    .attribute_filters
+                    .as_ref()
+                    .is_some_and(|a| !a.is_empty());
+            if !filtered {
+                return None;
+            }
+            relaxed.source_types = None;
+            relaxed.content_types = None;
+            relaxed.attribute_filters = None;
+        }
+        FallbackStrategy::AnyField => {
+            if SearchField::ALL
+                .iter()
+                .all(|field| request.fields().contains(field))
+            {
+                return None;
+            }
+            relaxed.fields = Some(SearchField::ALL.to_vec());
+        }
+        FallbackStrategy::SpellCorrected => {
+            if request.fuzzy || *request.search_mode() == SearchMode::Semantic {
+                return None;
+            }
+            relaxed.fuzzy = true;
+        }
+        FallbackStrategy::SemanticOnly => {
+            // Embeddings are of document content
+            if *request.search_mode() == SearchMode::Semantic
+                || !request.fields().contains(&SearchField::Content)
+            {
+                return None;
+            }
+            relaxed.mode = Some(SearchMode::Semantic);
+        }
+    }
+    Some(relaxed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn request() -> SearchRequest {
+        SearchRequest {
+            query: "quarterly plan".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_drop_filters() {
+        assert!(relax(&request(), FallbackStrategy::DropFilters).is_none());
+
+        let mut filtered = request();
+        filtered.content_types = Some(vec!["spreadsheet".to_string()]);
+        filtered.attribute_filters = Some(HashMap::new());
+        let relaxed = relax(&filtered, FallbackStrategy::DropFilters).unwrap();
+        assert!(relaxed.content_types.is_none());
+        assert!(relaxed.attribute_filters.is_none());
+        assert_eq!(relaxed.query, "quarterly plan");
+    }
+
+    #[test]
+    fn test_any_field() {
+        let relaxed = relax(&request(), FallbackStrategy::AnyField).unwrap();
+        assert_eq!(relaxed.fields(), &SearchField::ALL[..]);
+        assert!(relax(&relaxed, FallbackStrategy::AnyField).is_none());
+    }
+
+    #[test]
+    fn test_spell_corrected_and_semantic_only() {
+        let relaxed = relax(&request(), FallbackStrategy::SpellCorrected).unwrap();
+        assert!(relaxed.fuzzy);
+        assert!(relax(&relaxed, FallbackStrategy::SpellCorrected).is_none());
+
+        let relaxed = relax(&relaxed, FallbackStrategy::SemanticOnly).unwrap();
+        assert_eq!(*relaxed.search_mode(), SearchMode::Semantic);
+        assert!(relax(&relaxed, FallbackStrategy::SpellCorrected).is_none());
+
+        let mut titles_only = request();
+        titles_only.fields = Some(vec![SearchField::Title]);
+        assert!(relax(&titles_only, FallbackStrategy::SemanticOnly).is_none());
+    }
+}
@@ -0,0 +1,53 @@
+//! Home-page feeds of trending and recently updated documents.
+//!
+//! The trending feed ranks documents by how many users opened them lately, each user counted
+//! once and their interaction decaying with a half-life of a few days, then lifts documents
+//! that were also updated recently. The recent feed lists the latest updated documents. Both
+//! only contain documents the user can see.
+
+pub const DEFAULT_LIMIT: usize = 20;
+pub const MAX_LIMIT: usize = 50;
+/// Interactions older than this are not counted.
+pub const TRENDING_WINDOW_DAYS: i64 = 14;
+pub const ACTIVITY_HALF_LIFE_HOURS: f64 = 72.0;
+/// Most active documents ranked before filtering them to those the user can see.
+pub const TRENDING_CANDIDATES: i64 = 200;
+const UPDATE_HALF_LIFE_DAYS: f64 = 7.0;
+/// How much more a document updated just now trends than one not updated in months.
+const UPDATE_RECENCY_WEIGHT: f64 = 0.5;
+
+/// The number of documents to return for a requested limit.
+pub fn limit(requested: Option<usize>) -> usize {
+    requested.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+}
+
+/// How much a document trends, from its decayed `activity` and how long ago it was updated.
+pub fn trending_score(activity: f64, days_since_update: f64) -> f64 {
+    let update_recency = 0.5f64.powf(days_since_update.max(0.0) / UPDATE_HALF_LIFE_DAYS);
+    activity * (1.0 + UPDATE_RECENCY_WEIGHT * update_recency)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit() {
+        assert_eq!(limit(None), DEFAULT_LIMIT);
+        assert_eq!(limit(Some(0)), 1);
+        assert_eq!(limit(Some(5)), 5);
+        assert_eq!(limit(Some(1000)), MAX_LIMIT);
+    }
+
+    #[test]
+    fn test_trending_score() {
+        assert!((trending_score(2.0, 0.0) - 3.0).abs() < 1e-9);
+        assert!((trending_score(2.0, UPDATE_HALF_LIFE_DAYS) - 2.5).abs() < 1e-9);
+        // Activity outweighs recency, which only breaks near ties
+        assert!(trending_score(2.0, 365.0) > trending_score(1.0, 0.0));
+        assert!(trending_score(1.0, 1.0) > trending_score(1.0, 30.0));
+        assert_eq!(trending_score(0.0, 0.0), 0.0);
+        // Clock skew does not lift a document beyond one updated just now
+        assert_eq!(trending_score(1.0, -3.0), trending_score(1.0, 0.0));
+    }
+}
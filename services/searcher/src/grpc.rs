@@ -0,0 +1,225 @@
+//! gRPC API of the searcher, served alongside the HTTP one when `GRPC_PORT` is set. It offers
+//! search, title suggestions and RAG context with the same permission filtering as the HTTP
+//! API, for internal consumers such as the AI service.
+
+use crate::models::{SearchMode, SearchRequest, SearchResult, TypeaheadQuery};
+use crate::search::SearchEngine;
+use crate::AppState;
+use omni_proto::searcher::v1 as proto;
+use omni_proto::searcher::v1::searcher_server::{Searcher, SearcherServer};
+use shared::models::SourceType;
+use shared::{Repository, UserRepository};
+use std::net::SocketAddr;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use tonic::{Request, Response, Status};
+use tracing::{error, info};
+
+pub struct SearcherService {
+    state: AppState,
+}
+
+impl SearcherService {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    pub fn into_server(self) -> SearcherServer<Self> {
+        SearcherServer::new(self)
+    }
+
+    async fn search_engine(&self) -> Result<SearchEngine, Status> {
+        SearchEngine::new(
+            self.state.db_pool.clone(),
+            self.state.redis_client.clone(),
+            self.state.ai_client.clone(),
+            self.state.search_config(),
+            self.state.redaction.current(),
+        )
+        .await
+        .map_err(internal)
+    }
+}
+
+/// Serve the gRPC API on `port` until the process exits.
+pub async fn serve(state: AppState, port: u16) -> anyhow::Result<()> {
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    info!("Searcher gRPC API listening on {}", addr);
+    tonic::transport::Server::builder()
+        .add_service(SearcherService::new(state).into_server())
+        .serve(addr)
+        .await?;
+    Ok(())
+}
+
+#[tonic::async_trait]
+impl Searcher for SearcherService {
+    async fn search(
+        &self,
+        request: Request<proto::SearchRequest>,
+    ) -> Result<Response<proto::SearchResponse>, Status> {
+        let request = request.into_inner();
+        if request.query.trim().is_empty() {
+            return Err(Status::invalid_argument("Query is required"));
+        }
+        let mode = match request.mode() {
+            proto::SearchMode::Unspecified | proto::SearchMode::Fulltext => SearchMode::Fulltext,
+            proto::SearchMode::Semantic => SearchMode::Semantic,
+            proto::SearchMode::Hybrid => SearchMode::Hybrid,
+        };
+        let search_request = SearchRequest {
+            query: request.query,
+            user_id: request.user_id,
+            user_email: request.user_email,
+            mode: Some(mode),
+            source_types: source_types(request.source_types).map_err(Status::invalid_argument)?,
+            content_types: (!request.content_types.is_empty()).then_some(request.content_types),
+            limit: request.limit,
+            offset: request.offset,
+            collection: request.collection,
+            include_facets: Some(false),
+            ..Default::default()
+        };
+
+        let response = self
+            .search_engine()
+            .await?
+            .search(search_request)
+            .await
+            .map_err(internal)?;
+
+        Ok(Response::new(proto::SearchResponse {
+            results: response.results.into_iter().map(search_result).collect(),
+            total_count: response.total_count,
+            query_time_ms: response.query_time_ms,
+            has_more: response.has_more,
+            query: response.query,
+            degraded: response.degraded,
+        }))
+    }
+
+    async fn suggest(
+        &self,
+        request: Request<proto::SuggestRequest>,
+    ) -> Result<Response<proto::SuggestResponse>, Status> {
+        let request = request.into_inner();
+        let query = TypeaheadQuery {
+            q: request.query,
+            limit: request.limit.map(|limit| limit as usize),
+        };
+        let suggestions = self
+            .state
+            .title_index
+            .search(&query.q, query.limit())
+            .await
+            .into_iter()
+            .map(|result| proto::Suggestion {
+                document_id: result.document_id,
+                title: result.title,
+                url: result.url,
+                source_id: result.source_id,
+            })
+            .collect();
+
+        Ok(Response::new(proto::SuggestResponse { suggestions }))
+    }
+
+    async fn get_rag_context(
+        &self,
+        request: Request<proto::RagContextRequest>,
+    ) -> Result<Response<proto::RagContextResponse>, Status> {
+        let request = request.into_inner();
+        if request.question.trim().is_empty() {
+            return Err(Status::invalid_argument("Question is required"));
+        }
+        // Unlike search, RAG context is only built for a known user, so that it is never
+        // drawn from documents nobody is allowed to see
+        let user = UserRepository::new(self.state.db_pool.pool())
+            .find_by_id(request.user_id.clone())
+            .await
+            .map_err(internal)?
+            .filter(|user| user.is_active)
+            .ok_or_else(|| {
+                Status::invalid_argument(format!("Unknown user: {}", request.user_id))
+            })?;
+        let search_request = SearchRequest {
+            query: request.question,
+            user_id: Some(user.id),
+            user_email: Some(user.email),
+            source_types: source_types(request.source_types).map_err(Status::invalid_argument)?,
+            ..Default::default()
+        };
+
+        let passages = self
+            .search_engine()
+            .await?
+            .get_rag_context(&search_request)
+            .await
+            .map_err(internal)?;
+
+        Ok(Response::new(proto::RagContextResponse {
+            passages: passages.into_iter().map(search_result).collect(),
+        }))
+    }
+}
+
+fn source_types(names: Vec<String>) -> Result<Option<Vec<SourceType>>, String> {
+    if names.is_empty() {
+        return Ok(None);
+    }
+    names
+        .into_iter()
+        .map(|name| {
+            serde_json::from_value(serde_json::Value::String(name.clone()))
+                .map_err(|_| format!("Unknown source type: {}", name))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+fn search_result(result: SearchResult) -> proto::SearchResult {
+    let document = result.document;
+    proto::SearchResult {
+        document: Some(proto::Document {
+            id: document.id,
+            source_id: document.source_id,
+            external_id: document.external_id,
+            title: document.title,
+            content_type: document.content_type,
+            url: document.url,
+            created_at: rfc3339(document.created_at),
+            updated_at: rfc3339(document.updated_at),
+            metadata_json: document.metadata.to_string(),
+        }),
+        score: result.score,
+        highlights: result.highlights,
+        match_type: result.match_type,
+        content: result.content,
+        pinned: result.pinned,
+    }
+}
+
+fn rfc3339(timestamp: OffsetDateTime) -> String {
+    timestamp.format(&Rfc3339).unwrap_or_default()
+}
+
+fn internal(e: impl std::fmt::Display) -> Status {
+    error!("gRPC request failed: {}", e);
+    Status::internal("Internal server error")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_types() {
+        assert_eq!(source_types(vec![]).unwrap(), None);
+        assert_eq!(
+            source_types(vec!["google_drive".to_string(), "slack".to_string()]).unwrap(),
+            Some(vec![SourceType::GoogleDrive, SourceType::Slack])
+        );
+        let err = source_types(vec!["fax".to_string()]).unwrap_err();
+        assert_eq!(err, "Unknown source type: fax");
+    }
+}
@@ -1,22 +1,60 @@
+use crate::acronyms;
+use crate::collections;
+use crate::confluence;
+use crate::feed;
+use crate::helpdesk;
+use crate::meetings;
 use crate::models::{
-    RecentSearchesRequest, SearchRequest, SuggestedQuestionsRequest, SuggestedQuestionsResponse,
-    TypeaheadQuery, TypeaheadResponse,
+    AddCollectionDocumentsRequest, BatchSearchRequest, BatchSearchResponse, BatchSearchResult,
+    ChildResults, CollectionDetail, CollectionDocument, CollectionRequest, CollectionsQuery,
+    ConfluenceContent, ConfluenceContentLinks, ConfluenceContentSearchResponse, ConfluenceLinks,
+    ConfluenceSearchQuery, ConfluenceSearchResponse, ConfluenceSearchResult,
+    CreatePromotionRequest, DocumentLinkResult, DocumentLinksQuery, DocumentLinksResponse,
+    DocumentPreviewQuery, FeatureFlagAnalyticsResponse, FeedDocument, FeedQuery, FeedResponse,
+    FlagDocumentRequest, ListAcronymsQuery, ListFlagsQuery, MeetingResult, MeetingSearchRequest,
+    MeetingSearchResponse, PersonDocumentResult, PersonProfileQuery, PersonProfileResponse,
+    QuickSearchQuery, QuickSearchResponse, QuickSearchResult, RecentSearchesRequest,
+    RecommendationsQuery, RecommendationsResponse, RecommendedDocument, RecordInteractionRequest,
+    SearchMode, SearchRequest, SearchResponse, ShareCollectionRequest, SuggestedArticle,
+    SuggestedArticlesRequest, SuggestedArticlesResponse, SuggestedQuestionsRequest,
+    SuggestedQuestionsResponse, TranscriptSnippet, TypeaheadQuery, TypeaheadResponse,
+    UpdateFlagRequest, UsageAnalyticsQuery, UsageAnalyticsResponse, MAX_BATCH_QUERIES,
+    MAX_FLAG_COMMENT_LEN,
 };
+use crate::promotions;
+use crate::ranking::{self, RankingBoosts};
+use crate::redaction::{self, RedactionRules, Redactor};
+use crate::result_fields;
 use crate::search::SearchEngine;
 use crate::suggested_questions::{self, SuggestedQuestionsGenerator};
+use crate::tuning::{self, TuningState};
 use crate::{AppState, Result as SearcherResult, SearcherError};
 use anyhow::anyhow;
 use axum::body::Body;
 use axum::{
-    extract::{Query, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
     response::Json,
 };
 use futures_util::Stream;
 use redis::AsyncCommands;
 use serde_json::{json, Value};
-use shared::{Repository, UserRepository};
+use shared::db::repositories::{
+    Acronym, AcronymInput, AcronymRepository, Collection, CollectionRepository, CollectionShare,
+    DocumentFlag, DocumentFlagRepository, DocumentInteractionRepository, DocumentLinkRepository,
+    DocumentPreviewRepository, FeatureFlag, FeatureFlagInput, FeatureFlagRepository,
+    GroupRepository, LinkKind, LinkedDocument, PersonRepository, QueryLogRepository,
+    RecommendationReason, RecommendationRepository, SearchPromotion, SearchPromotionInput,
+    SearchPromotionRepository, SourceRepository, SyncRunRepository,
+};
+use shared::db::DatabaseError;
+use shared::models::{Document, User};
+use shared::{cache, feature_flags};
+use shared::{DocumentRepository, Repository, UserRepository};
 use sqlx::types::time::OffsetDateTime;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -80,8 +118,9 @@ where
                     if !buffer.is_empty() {
                         if let Ok(mut conn) = redis_client.get_multiplexed_async_connection().await
                         {
-                            let _: Result<(), _> =
-                                conn.set_ex(&cache_key, buffer.as_str(), 600).await;
+                            let _: Result<(), _> = conn
+                                .set_ex(&cache_key, buffer.as_str(), AI_ANSWER_CACHE_TTL_SECS)
+                                .await;
                             info!("Cached AI response for key: {}", cache_key);
                         }
                     }
@@ -121,12 +160,17 @@ pub async fn search(
     Json(request): Json<SearchRequest>,
 ) -> SearcherResult<Json<Value>> {
     info!("Received search request: {:?}", request);
+    if let Some(fields) = &request.result_fields {
+        result_fields::validate(fields).map_err(SearcherError::BadRequest)?;
+    }
 
+    let config = state.search_config();
     let search_engine = SearchEngine::new(
         state.db_pool,
         state.redis_client,
         state.ai_client,
-        state.config,
+        config,
+        state.redaction.current(),
     )
     .await?;
 
@@ -158,7 +202,91 @@ pub async fn search(
         }
     }
 
-    Ok(Json(serde_json::to_value(response)?))
+    let mut response = serde_json::to_value(response)?;
+    if let Some(fields) = &request.result_fields {
+        result_fields::select(&mut response, fields);
+    }
+    Ok(Json(response))
+}
+
+/// Run several searches for one user concurrently. The user and their groups are looked up
+/// once and every query is filtered by their permissions. Queries are not added to the user's
+/// search history.
+pub async fn batch_search(
+    State(state): State<AppState>,
+    Json(request): Json<BatchSearchRequest>,
+) -> SearcherResult<Json<Value>> {
+    if request.queries.is_empty() {
+        return Err(SearcherError::BadRequest(
+            "At least one query is required".to_string(),
+        ));
+    }
+    if request.queries.len() > MAX_BATCH_QUERIES {
+        return Err(SearcherError::BadRequest(format!(
+            "At most {} queries can be run in one batch",
+            MAX_BATCH_QUERIES
+        )));
+    }
+    for query in &request.queries {
+        if let Some(fields) = &query.result_fields {
+            result_fields::validate(fields).map_err(SearcherError::BadRequest)?;
+        }
+    }
+    let user = active_user(&state, &request.user_id).await?;
+    info!(
+        "Received batch of {} searches for user {}",
+        request.queries.len(),
+        user.id
+    );
+
+    let user_groups = GroupRepository::new(state.db_pool.pool())
+        .find_user_group_identifiers(&user.email)
+        .await
+        .map_err(|e| anyhow!("Failed to look up the user's groups: {}", e))?;
+
+    let config = state.search_config();
+    let search_engine = SearchEngine::new(
+        state.db_pool,
+        state.redis_client,
+        state.ai_client,
+        config,
+        state.redaction.current(),
+    )
+    .await?;
+
+    let selected_fields: Vec<Option<Vec<String>>> = request
+        .queries
+        .iter()
+        .map(|query| query.result_fields.clone())
+        .collect();
+    let searches = request.queries.into_iter().map(|mut query| {
+        query.user_id = Some(user.id.clone());
+        query.user_email = Some(user.email.clone());
+        query.user_groups = Some(user_groups.clone());
+        let search_engine = &search_engine;
+        async move {
+            match search_engine.search(query).await {
+                Ok(response) => BatchSearchResult::Success(response),
+                Err(e) => {
+                    error!("Batch search query failed: {}", e);
+                    BatchSearchResult::Failure {
+                        error: e.to_string(),
+                    }
+                }
+            }
+        }
+    });
+    let results = futures_util::future::join_all(searches).await;
+
+    let mut response = serde_json::to_value(BatchSearchResponse { results })?;
+    if let Some(results) = response.get_mut("results").and_then(Value::as_array_mut) {
+        for (result, fields) in results.iter_mut().zip(&selected_fields) {
+            if let Some(fields) = fields {
+                result_fields::select(result, fields);
+            }
+        }
+    }
+    Ok(Json(response))
 }
 
 pub async fn recent_searches(
@@ -170,11 +298,13 @@ pub async fn recent_searches(
         query.user_id
     );
 
+    let config = state.search_config();
     let search_engine = SearchEngine::new(
         state.db_pool,
         state.redis_client,
         state.ai_client,
-        state.config,
+        config,
+        state.redaction.current(),
     )
     .await?;
 
@@ -193,7 +323,8 @@ pub async fn ai_answer(
         state.db_pool.clone(),
         state.redis_client.clone(),
         state.ai_client.clone(),
-        state.config.clone(),
+        state.search_config(),
+        state.redaction.current(),
     )
     .await
     .map_err(|_| axum::http::StatusCode::INTERNAL_SERVER_ERROR)?;
@@ -268,6 +399,136 @@ pub async fn typeahead(
     Ok(Json(serde_json::to_value(response)?))
 }
 
+/// Seconds a streamed AI answer is cached for.
+const AI_ANSWER_CACHE_TTL_SECS: u64 = 600;
+const QUICK_SEARCH_LIMIT: i64 = 5;
+const QUICK_SEARCH_CACHE_TTL_SECONDS: u64 = 60;
+
+/// Top fulltext matches for a user, for the browser extension. Responses are cached per user
+/// and query so repeated keystrokes are answered from Redis without touching the database.
+pub async fn quick_search(
+    State(state): State<AppState>,
+    Query(query): Query<QuickSearchQuery>,
+) -> SearcherResult<Json<QuickSearchResponse>> {
+    let q = query.q.trim();
+    if q.is_empty() {
+        return Ok(Json(QuickSearchResponse {
+            results: vec![],
+            query: query.q,
+        }));
+    }
+
+    let mut hasher = DefaultHasher::new();
+    q.to_lowercase().hash(&mut hasher);
+    query.user_email.hash(&mut hasher);
+    let cache_key = cache::keys().cache("quick_search", format_args!("{:x}", hasher.finish()));
+
+    if let Ok(mut conn) = state.redis_client.get_multiplexed_async_connection().await {
+        if let Ok(cached) = conn.get::<_, String>(&cache_key).await {
+            if let Ok(response) = serde_json::from_str::<QuickSearchResponse>(&cached) {
+                debug!("Quick search cache hit for query: '{}'", q);
+                return Ok(Json(response));
+            }
+        }
+    }
+
+    let config = state.search_config();
+    let search_engine = SearchEngine::new(
+        state.db_pool,
+        state.redis_client.clone(),
+        state.ai_client,
+        config,
+        state.redaction.current(),
+    )
+    .await?;
+    let search_response = search_engine
+        .search(SearchRequest {
+            query: q.to_string(),
+            limit: Some(QUICK_SEARCH_LIMIT),
+            mode: Some(SearchMode::Fulltext),
+            include_facets: Some(false),
+            user_email: query.user_email,
+            fallback: Some(false),
+            ..Default::default()
+        })
+        .await?;
+
+    let response = QuickSearchResponse {
+        results: search_response
+            .results
+            .into_iter()
+            .map(|r| QuickSearchResult {
+                document_id: r.document.id,
+                title: r.document.title,
+                url: r.document.url,
+            })
+            .collect(),
+        query: q.to_string(),
+    };
+
+    if let Ok(mut conn) = state.redis_client.get_multiplexed_async_connection().await {
+        if let Ok(response_json) = serde_json::to_string(&response) {
+            let _: Result<(), _> = conn
+                .set_ex(&cache_key, response_json, QUICK_SEARCH_CACHE_TTL_SECONDS)
+                .await;
+        }
+    }
+
+    Ok(Json(response))
+}
+
+/// The preview image of a document, for a user who can see the document.
+pub async fn document_preview(
+    State(state): State<AppState>,
+    Path(document_id): Path<String>,
+    Query(query): Query<DocumentPreviewQuery>,
+) -> SearcherResult<axum::response::Response<Body>> {
+    let pool = state.db_pool.pool();
+    let document_repo = DocumentRepository::new(pool);
+    let source_ids = document_repo
+        .fetch_active_source_ids(None)
+        .await
+        .map_err(|e| anyhow!("Failed to fetch active sources: {}", e))?;
+    let visible = document_repo
+        .find_by_ids_with_filters(
+            std::slice::from_ref(&document_id),
+            &source_ids,
+            None,
+            None,
+            None,
+            Some(&query.user_email),
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to look up document: {}", e))?;
+    let preview = if visible.is_empty() {
+        None
+    } else {
+        DocumentPreviewRepository::new(pool)
+            .find_by_document_id(&document_id)
+            .await
+            .map_err(|e| anyhow!("Failed to look up document preview: {}", e))?
+    };
+    // Documents the user cannot see are reported the same as ones without a preview
+    let Some(preview) = preview else {
+        return Err(SearcherError::NotFound(format!(
+            "No preview for document {}",
+            document_id
+        )));
+    };
+
+    let image = state
+        .content_storage
+        .get_content(&preview.content_id)
+        .await
+        .map_err(|e| anyhow!("Failed to read document preview: {}", e))?;
+    axum::response::Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", preview.mime_type)
+        .header("Cache-Control", "private, max-age=3600")
+        .body(Body::from(image))
+        .map_err(|e| anyhow!("Failed to build preview response: {}", e).into())
+}
+
 // TODO: Make this a GET request, this should not be POST
 pub async fn suggested_questions(
     State(state): State<AppState>,
@@ -306,3 +567,1242 @@ pub async fn suggested_questions(
 
     Ok(Json(response))
 }
+
+pub async fn usage_analytics(
+    State(state): State<AppState>,
+    Query(query): Query<UsageAnalyticsQuery>,
+) -> SearcherResult<Json<UsageAnalyticsResponse>> {
+    let days = query.days();
+    let since = OffsetDateTime::now_utc() - time::Duration::days(days);
+    info!("Building usage analytics for the last {} days", days);
+
+    let pool = state.db_pool.pool();
+    let query_log_repo = QueryLogRepository::new(pool);
+    let document_repo = DocumentRepository::new(pool);
+    let sync_run_repo = SyncRunRepository::new(pool);
+
+    let (daily_usage, queries_per_source_type, index_growth, sync_stats) = tokio::try_join!(
+        query_log_repo.daily_usage(since),
+        query_log_repo.queries_per_source_type(since),
+        document_repo.daily_index_growth(since),
+        sync_run_repo.daily_stats(since),
+    )
+    .map_err(|e| anyhow!("Failed to aggregate usage analytics: {}", e))?;
+
+    let total_queries: i64 = daily_usage.iter().map(|d| d.queries).sum();
+    let zero_result_queries: i64 = daily_usage.iter().map(|d| d.zero_result_queries).sum();
+    let zero_result_rate = if total_queries > 0 {
+        zero_result_queries as f64 / total_queries as f64
+    } else {
+        0.0
+    };
+
+    Ok(Json(UsageAnalyticsResponse {
+        days,
+        total_queries,
+        zero_result_rate,
+        daily_usage,
+        queries_per_source_type,
+        index_growth,
+        sync_stats,
+    }))
+}
+
+pub async fn list_feature_flags(
+    State(state): State<AppState>,
+) -> SearcherResult<Json<Vec<FeatureFlag>>> {
+    let flags = FeatureFlagRepository::new(state.db_pool.pool())
+        .find_all()
+        .await
+        .map_err(|e| anyhow!("Failed to list feature flags: {}", e))?;
+    Ok(Json(flags))
+}
+
+pub async fn update_feature_flag(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(input): Json<FeatureFlagInput>,
+) -> SearcherResult<Json<FeatureFlag>> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    {
+        return Err(SearcherError::BadRequest(
+            "Feature flag names are lowercase letters, digits and underscores".to_string(),
+        ));
+    }
+    input.validate().map_err(SearcherError::BadRequest)?;
+    let flag = FeatureFlagRepository::new(state.db_pool.pool())
+        .upsert(&name, &input)
+        .await
+        .map_err(|e| anyhow!("Failed to save feature flag: {}", e))?;
+    feature_flags::refresh().await?;
+    info!(
+        "Updated feature flag {} (enabled: {}, rollout: {}%, {} tenants)",
+        flag.name,
+        flag.enabled,
+        flag.rollout_percentage,
+        flag.tenants.len()
+    );
+    Ok(Json(flag))
+}
+
+pub async fn delete_feature_flag(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> SearcherResult<Json<Value>> {
+    let deleted = FeatureFlagRepository::new(state.db_pool.pool())
+        .delete(&name)
+        .await
+        .map_err(|e| anyhow!("Failed to delete feature flag: {}", e))?;
+    if !deleted {
+        return Err(SearcherError::NotFound(format!(
+            "Feature flag not found: {}",
+            name
+        )));
+    }
+    feature_flags::refresh().await?;
+
+    info!("Deleted feature flag {}", name);
+    Ok(Json(json!({ "status": "deleted" })))
+}
+
+/// Logged searches with a flag on compared with those with it off.
+pub async fn feature_flag_analytics(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Query(query): Query<UsageAnalyticsQuery>,
+) -> SearcherResult<Json<FeatureFlagAnalyticsResponse>> {
+    let days = query.days();
+    let since = OffsetDateTime::now_utc() - time::Duration::days(days);
+    let variants = FeatureFlagRepository::new(state.db_pool.pool())
+        .variant_stats(&name, since)
+        .await
+        .map_err(|e| anyhow!("Failed to compare feature flag variants: {}", e))?;
+    Ok(Json(FeatureFlagAnalyticsResponse {
+        flag: name,
+        days,
+        variants,
+    }))
+}
+
+pub async fn get_redaction_rules(
+    State(state): State<AppState>,
+) -> SearcherResult<Json<RedactionRules>> {
+    let rules = redaction::load_rules(&state.db_pool).await?;
+    Ok(Json(rules))
+}
+
+pub async fn update_redaction_rules(
+    State(state): State<AppState>,
+    Json(rules): Json<RedactionRules>,
+) -> SearcherResult<Json<RedactionRules>> {
+    let redactor = Redactor::new(rules.clone()).map_err(SearcherError::BadRequest)?;
+    state.redaction.save(&rules, redactor).await?;
+    info!(
+        "Updated search redaction rules ({} rules)",
+        rules.rules.len()
+    );
+    Ok(Json(rules))
+}
+
+pub async fn get_ranking_boosts(
+    State(state): State<AppState>,
+) -> SearcherResult<Json<RankingBoosts>> {
+    let boosts = ranking::load_boosts(&state.db_pool).await?;
+    Ok(Json(boosts))
+}
+
+pub async fn update_ranking_boosts(
+    State(state): State<AppState>,
+    Json(boosts): Json<RankingBoosts>,
+) -> SearcherResult<Json<RankingBoosts>> {
+    boosts.validate().map_err(SearcherError::BadRequest)?;
+    ranking::save_boosts(&state.db_pool, &boosts).await?;
+    info!(
+        "Updated search ranking boosts ({} sources, {} content types)",
+        boosts.sources.len(),
+        boosts.content_types.len()
+    );
+    Ok(Json(boosts))
+}
+
+pub async fn get_search_tuning(State(state): State<AppState>) -> SearcherResult<Json<TuningState>> {
+    Ok(Json(state.tuning.state().await?))
+}
+
+/// Override tunables, keeping other overrides. A `null` value drops a tunable's override.
+pub async fn update_search_tuning(
+    State(state): State<AppState>,
+    Json(changes): Json<serde_json::Map<String, Value>>,
+) -> SearcherResult<Json<TuningState>> {
+    let mut overrides = state.tuning.load_overrides().await?;
+    tuning::apply_changes(&mut overrides, changes);
+    let tuning =
+        tuning::resolve(&state.config.tuning, &overrides).map_err(SearcherError::BadRequest)?;
+    state.tuning.save_overrides(overrides, tuning).await?;
+    info!("Updated searcher tuning");
+    Ok(Json(state.tuning.state().await?))
+}
+
+/// Drop every override, returning to the tunables from the environment.
+pub async fn reset_search_tuning(
+    State(state): State<AppState>,
+) -> SearcherResult<Json<TuningState>> {
+    state
+        .tuning
+        .save_overrides(serde_json::Map::new(), state.config.tuning.clone())
+        .await?;
+    info!("Reset searcher tuning to its defaults");
+    Ok(Json(state.tuning.state().await?))
+}
+
+pub async fn list_promotions(
+    State(state): State<AppState>,
+) -> SearcherResult<Json<Vec<SearchPromotion>>> {
+    let promotions = SearchPromotionRepository::new(state.db_pool.pool())
+        .find_all()
+        .await
+        .map_err(|e| anyhow!("Failed to list search promotions: {}", e))?;
+    Ok(Json(promotions))
+}
+
+/// Check a promotion and the document it promotes before saving it.
+async fn validate_promotion(
+    state: &AppState,
+    promotion: &SearchPromotionInput,
+) -> SearcherResult<()> {
+    promotions::validate(promotion).map_err(SearcherError::BadRequest)?;
+    let document = DocumentRepository::new(state.db_pool.pool())
+        .find_by_id(&promotion.document_id)
+        .await
+        .map_err(|e| anyhow!("Failed to look up promoted document: {}", e))?;
+    if document.is_none() {
+        return Err(SearcherError::BadRequest(format!(
+            "Document not found: {}",
+            promotion.document_id
+        )));
+    }
+    Ok(())
+}
+
+pub async fn create_promotion(
+    State(state): State<AppState>,
+    Json(request): Json<CreatePromotionRequest>,
+) -> SearcherResult<Json<SearchPromotion>> {
+    validate_promotion(&state, &request.promotion).await?;
+    let promotion = SearchPromotionRepository::new(state.db_pool.pool())
+        .create(&request.promotion, request.created_by.as_deref())
+        .await
+        .map_err(|e| anyhow!("Failed to create search promotion: {}", e))?;
+
+    info!(
+        "Created search promotion {} ({:?} {} for '{}')",
+        promotion.id, promotion.action, promotion.document_id, promotion.query_pattern
+    );
+    Ok(Json(promotion))
+}
+
+pub async fn update_promotion(
+    State(state): State<AppState>,
+    Path(promotion_id): Path<String>,
+    Json(input): Json<SearchPromotionInput>,
+) -> SearcherResult<Json<SearchPromotion>> {
+    validate_promotion(&state, &input).await?;
+    let promotion = SearchPromotionRepository::new(state.db_pool.pool())
+        .update(&promotion_id, &input)
+        .await
+        .map_err(|e| anyhow!("Failed to update search promotion: {}", e))?
+        .ok_or_else(|| {
+            SearcherError::NotFound(format!("Search promotion not found: {}", promotion_id))
+        })?;
+
+    info!("Updated search promotion {}", promotion.id);
+    Ok(Json(promotion))
+}
+
+pub async fn delete_promotion(
+    State(state): State<AppState>,
+    Path(promotion_id): Path<String>,
+) -> SearcherResult<Json<Value>> {
+    let deleted = SearchPromotionRepository::new(state.db_pool.pool())
+        .delete(&promotion_id)
+        .await
+        .map_err(|e| anyhow!("Failed to delete search promotion: {}", e))?;
+    if !deleted {
+        return Err(SearcherError::NotFound(format!(
+            "Search promotion not found: {}",
+            promotion_id
+        )));
+    }
+
+    info!("Deleted search promotion {}", promotion_id);
+    Ok(Json(json!({ "status": "deleted" })))
+}
+
+pub async fn list_acronyms(
+    State(state): State<AppState>,
+    Query(query): Query<ListAcronymsQuery>,
+) -> SearcherResult<Json<Vec<Acronym>>> {
+    let acronyms = AcronymRepository::new(state.db_pool.pool())
+        .find_all(query.status)
+        .await
+        .map_err(|e| anyhow!("Failed to list acronyms: {}", e))?;
+    Ok(Json(acronyms))
+}
+
+fn acronym_error(action: &str, e: DatabaseError) -> SearcherError {
+    match e {
+        DatabaseError::ConstraintViolation(message) => SearcherError::BadRequest(message),
+        e => anyhow!("Failed to {} acronym: {}", action, e).into(),
+    }
+}
+
+pub async fn create_acronym(
+    State(state): State<AppState>,
+    Json(input): Json<AcronymInput>,
+) -> SearcherResult<Json<Acronym>> {
+    let input = acronyms::normalize(input);
+    acronyms::validate(&input).map_err(SearcherError::BadRequest)?;
+    let acronym = AcronymRepository::new(state.db_pool.pool())
+        .create(&input)
+        .await
+        .map_err(|e| acronym_error("create", e))?;
+
+    info!(
+        "Created acronym {} ({} = '{}', {:?})",
+        acronym.id, acronym.acronym, acronym.expansion, acronym.status
+    );
+    Ok(Json(acronym))
+}
+
+/// Edit a dictionary entry. Setting its status to `active` makes it expand queries.
+pub async fn update_acronym(
+    State(state): State<AppState>,
+    Path(acronym_id): Path<String>,
+    Json(input): Json<AcronymInput>,
+) -> SearcherResult<Json<Acronym>> {
+    let input = acronyms::normalize(input);
+    acronyms::validate(&input).map_err(SearcherError::BadRequest)?;
+    let acronym = AcronymRepository::new(state.db_pool.pool())
+        .update(&acronym_id, &input)
+        .await
+        .map_err(|e| acronym_error("update", e))?
+        .ok_or_else(|| SearcherError::NotFound(format!("Acronym not found: {}", acronym_id)))?;
+
+    info!(
+        "Updated acronym {} ({} = '{}', {:?})",
+        acronym.id, acronym.acronym, acronym.expansion, acronym.status
+    );
+    Ok(Json(acronym))
+}
+
+pub async fn delete_acronym(
+    State(state): State<AppState>,
+    Path(acronym_id): Path<String>,
+) -> SearcherResult<Json<Value>> {
+    let deleted = AcronymRepository::new(state.db_pool.pool())
+        .delete(&acronym_id)
+        .await
+        .map_err(|e| anyhow!("Failed to delete acronym: {}", e))?;
+    if !deleted {
+        return Err(SearcherError::NotFound(format!(
+            "Acronym not found: {}",
+            acronym_id
+        )));
+    }
+
+    info!("Deleted acronym {}", acronym_id);
+    Ok(Json(json!({ "status": "deleted" })))
+}
+
+/// The active user making a collection request.
+async fn active_user(state: &AppState, user_id: &str) -> SearcherResult<User> {
+    UserRepository::new(state.db_pool.pool())
+        .find_by_id(user_id.to_string())
+        .await
+        .map_err(|e| anyhow!("Failed to look up user: {}", e))?
+        .filter(|user| user.is_active)
+        .ok_or_else(|| SearcherError::BadRequest(format!("Unknown user: {}", user_id)))
+}
+
+/// A collection the user owns or has had shared with them. Other collections are reported as
+/// not found, so their existence is not revealed.
+async fn viewable_collection(
+    state: &AppState,
+    collection_id: &str,
+    user_id: &str,
+) -> SearcherResult<Collection> {
+    let repo = CollectionRepository::new(state.db_pool.pool());
+    let viewable = repo
+        .can_view(collection_id, user_id)
+        .await
+        .map_err(|e| anyhow!("Failed to check collection access: {}", e))?;
+    let collection = if viewable {
+        repo.find_by_id(collection_id)
+            .await
+            .map_err(|e| anyhow!("Failed to look up collection: {}", e))?
+    } else {
+        None
+    };
+    collection
+        .ok_or_else(|| SearcherError::NotFound(format!("Collection not found: {}", collection_id)))
+}
+
+/// A collection the user owns, which only they may change.
+async fn owned_collection(
+    state: &AppState,
+    collection_id: &str,
+    user_id: &str,
+) -> SearcherResult<Collection> {
+    let collection = viewable_collection(state, collection_id, user_id).await?;
+    if collection.owner_id != user_id {
+        return Err(SearcherError::Forbidden(format!(
+            "Only the owner of collection {} may change it",
+            collection_id
+        )));
+    }
+    Ok(collection)
+}
+
+/// Documents among `ids` the user with `user_email` can see, in the order of `ids`.
+async fn visible_documents(
+    state: &AppState,
+    ids: &[String],
+    user_email: &str,
+) -> SearcherResult<Vec<Document>> {
+    let repo = DocumentRepository::new(state.db_pool.pool());
+    let source_ids = repo
+        .fetch_active_source_ids(None)
+        .await
+        .map_err(|e| anyhow!("Failed to fetch active sources: {}", e))?;
+    let mut documents = repo
+        .find_by_ids_with_filters(ids, &source_ids, None, None, None, Some(user_email))
+        .await
+        .map_err(|e| anyhow!("Failed to look up documents: {}", e))?;
+    documents.sort_by_key(|document| ids.iter().position(|id| id == &document.id));
+    Ok(documents)
+}
+
+fn collection_error(action: &str, e: DatabaseError) -> SearcherError {
+    match e {
+        DatabaseError::ConstraintViolation(message) => SearcherError::BadRequest(message),
+        e => anyhow!("Failed to {} collection: {}", action, e).into(),
+    }
+}
+
+pub async fn list_collections(
+    State(state): State<AppState>,
+    Query(query): Query<CollectionsQuery>,
+) -> SearcherResult<Json<Vec<Collection>>> {
+    let collections = CollectionRepository::new(state.db_pool.pool())
+        .find_for_user(&query.user_id)
+        .await
+        .map_err(|e| anyhow!("Failed to list collections: {}", e))?;
+    Ok(Json(collections))
+}
+
+pub async fn create_collection(
+    State(state): State<AppState>,
+    Json(request): Json<CollectionRequest>,
+) -> SearcherResult<Json<Collection>> {
+    let input = collections::normalize(request.collection);
+    collections::validate(&input).map_err(SearcherError::BadRequest)?;
+    let user = active_user(&state, &request.user_id).await?;
+    let collection = CollectionRepository::new(state.db_pool.pool())
+        .create(&user.id, &input)
+        .await
+        .map_err(|e| collection_error("create", e))?;
+
+    info!(
+        "Created collection {} ('{}') for {}",
+        collection.id, collection.name, user.email
+    );
+    Ok(Json(collection))
+}
+
+/// A collection with the documents in it the user can see and who it is shared with.
+pub async fn get_collection(
+    State(state): State<AppState>,
+    Path(collection_id): Path<String>,
+    Query(query): Query<CollectionsQuery>,
+) -> SearcherResult<Json<CollectionDetail>> {
+    let user = active_user(&state, &query.user_id).await?;
+    let collection = viewable_collection(&state, &collection_id, &user.id).await?;
+    let repo = CollectionRepository::new(state.db_pool.pool());
+    let document_ids = repo
+        .find_document_ids(&collection_id)
+        .await
+        .map_err(|e| anyhow!("Failed to list collection documents: {}", e))?;
+    let shares = repo
+        .find_shares(&collection_id)
+        .await
+        .map_err(|e| anyhow!("Failed to list collection shares: {}", e))?;
+
+    let documents = visible_documents(&state, &document_ids, &user.email)
+        .await?
+        .into_iter()
+        .map(|document| CollectionDocument {
+            id: document.id,
+            source_id: document.source_id,
+            title: document.title,
+            url: document.url,
+            content_type: document.content_type,
+        })
+        .collect();
+    Ok(Json(CollectionDetail {
+        collection,
+        documents,
+        shares,
+    }))
+}
+
+pub async fn update_collection(
+    State(state): State<AppState>,
+    Path(collection_id): Path<String>,
+    Json(request): Json<CollectionRequest>,
+) -> SearcherResult<Json<Collection>> {
+    let input = collections::normalize(request.collection);
+    collections::validate(&input).map_err(SearcherError::BadRequest)?;
+    owned_collection(&state, &collection_id, &request.user_id).await?;
+    let collection = CollectionRepository::new(state.db_pool.pool())
+        .update(&collection_id, &input)
+        .await
+        .map_err(|e| collection_error("update", e))?
+        .ok_or_else(|| {
+            SearcherError::NotFound(format!("Collection not found: {}", collection_id))
+        })?;
+
+    info!(
+        "Updated collection {} ('{}')",
+        collection.id, collection.name
+    );
+    Ok(Json(collection))
+}
+
+pub async fn delete_collection(
+    State(state): State<AppState>,
+    Path(collection_id): Path<String>,
+    Query(query): Query<CollectionsQuery>,
+) -> SearcherResult<Json<Value>> {
+    owned_collection(&state, &collection_id, &query.user_id).await?;
+    CollectionRepository::new(state.db_pool.pool())
+        .delete(&collection_id)
+        .await
+        .map_err(|e| anyhow!("Failed to delete collection: {}", e))?;
+
+    info!("Deleted collection {}", collection_id);
+    Ok(Json(json!({ "status": "deleted" })))
+}
+
+/// Add documents to a collection. Only documents the owner can see may be added.
+pub async fn add_collection_documents(
+    State(state): State<AppState>,
+    Path(collection_id): Path<String>,
+    Json(request): Json<AddCollectionDocumentsRequest>,
+) -> SearcherResult<Json<Value>> {
+    let mut document_ids = request.document_ids;
+    document_ids.sort();
+    document_ids.dedup();
+    if document_ids.is_empty() || document_ids.len() > collections::MAX_DOCUMENTS_PER_REQUEST {
+        return Err(SearcherError::BadRequest(format!(
+            "Add between 1 and {} documents at a time",
+            collections::MAX_DOCUMENTS_PER_REQUEST
+        )));
+    }
+    let user = active_user(&state, &request.user_id).await?;
+    owned_collection(&state, &collection_id, &user.id).await?;
+
+    let visible = visible_documents(&state, &document_ids, &user.email).await?;
+    let missing: Vec<&str> = document_ids
+        .iter()
+        .filter(|id| !visible.iter().any(|document| &document.id == *id))
+        .map(|id| id.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Err(SearcherError::BadRequest(format!(
+            "Documents not found: {}",
+            missing.join(", ")
+        )));
+    }
+
+    let added = CollectionRepository::new(state.db_pool.pool())
+        .add_documents(&collection_id, &document_ids)
+        .await
+        .map_err(|e| anyhow!("Failed to add documents to collection: {}", e))?;
+    info!("Added {} documents to collection {}", added, collection_id);
+    Ok(Json(json!({ "added": added })))
+}
+
+pub async fn remove_collection_document(
+    State(state): State<AppState>,
+    Path((collection_id, document_id)): Path<(String, String)>,
+    Query(query): Query<CollectionsQuery>,
+) -> SearcherResult<Json<Value>> {
+    owned_collection(&state, &collection_id, &query.user_id).await?;
+    let removed = CollectionRepository::new(state.db_pool.pool())
+        .remove_document(&collection_id, &document_id)
+        .await
+        .map_err(|e| anyhow!("Failed to remove document from collection: {}", e))?;
+    if !removed {
+        return Err(SearcherError::NotFound(format!(
+            "Document {} is not in collection {}",
+            document_id, collection_id
+        )));
+    }
+
+    info!(
+        "Removed document {} from collection {}",
+        document_id, collection_id
+    );
+    Ok(Json(json!({ "status": "removed" })))
+}
+
+/// Share a collection with another user, who can then view and search it.
+pub async fn share_collection(
+    State(state): State<AppState>,
+    Path(collection_id): Path<String>,
+    Json(request): Json<ShareCollectionRequest>,
+) -> SearcherResult<Json<Vec<CollectionShare>>> {
+    let collection = owned_collection(&state, &collection_id, &request.user_id).await?;
+    let recipient = UserRepository::new(state.db_pool.pool())
+        .find_by_email(request.email.trim())
+        .await
+        .map_err(|e| anyhow!("Failed to look up user: {}", e))?
+        .filter(|user| user.is_active)
+        .ok_or_else(|| SearcherError::BadRequest(format!("Unknown user: {}", request.email)))?;
+    if recipient.id == collection.owner_id {
+        return Err(SearcherError::BadRequest(
+            "A collection cannot be shared with its owner".to_string(),
+        ));
+    }
+
+    let repo = CollectionRepository::new(state.db_pool.pool());
+    repo.share(&collection_id, &recipient.id)
+        .await
+        .map_err(|e| anyhow!("Failed to share collection: {}", e))?;
+    let shares = repo
+        .find_shares(&collection_id)
+        .await
+        .map_err(|e| anyhow!("Failed to list collection shares: {}", e))?;
+
+    info!(
+        "Shared collection {} with {}",
+        collection_id, recipient.email
+    );
+    Ok(Json(shares))
+}
+
+pub async fn unshare_collection(
+    State(state): State<AppState>,
+    Path((collection_id, shared_user_id)): Path<(String, String)>,
+    Query(query): Query<CollectionsQuery>,
+) -> SearcherResult<Json<Value>> {
+    owned_collection(&state, &collection_id, &query.user_id).await?;
+    let unshared = CollectionRepository::new(state.db_pool.pool())
+        .unshare(&collection_id, &shared_user_id)
+        .await
+        .map_err(|e| anyhow!("Failed to unshare collection: {}", e))?;
+    if !unshared {
+        return Err(SearcherError::NotFound(format!(
+            "Collection {} is not shared with user {}",
+            collection_id, shared_user_id
+        )));
+    }
+
+    info!(
+        "Stopped sharing collection {} with user {}",
+        collection_id, shared_user_id
+    );
+    Ok(Json(json!({ "status": "unshared" })))
+}
+
+/// Flag a search result as outdated, incorrect or sensitive. Only documents the user can see
+/// can be flagged.
+pub async fn flag_document(
+    State(state): State<AppState>,
+    Path(document_id): Path<String>,
+    Json(request): Json<FlagDocumentRequest>,
+) -> SearcherResult<Json<DocumentFlag>> {
+    let comment = request.comment();
+    if comment.is_some_and(|comment| comment.chars().count() > MAX_FLAG_COMMENT_LEN) {
+        return Err(SearcherError::BadRequest(format!(
+            "Flag comment must be at most {} characters",
+            MAX_FLAG_COMMENT_LEN
+        )));
+    }
+    let user = active_user(&state, &request.user_id).await?;
+    let visible =
+        visible_documents(&state, std::slice::from_ref(&document_id), &user.email).await?;
+    if visible.is_empty() {
+        return Err(SearcherError::NotFound(format!(
+            "Document not found: {}",
+            document_id
+        )));
+    }
+
+    let flag = DocumentFlagRepository::new(state.db_pool.pool())
+        .upsert(&document_id, &user.id, request.reason, comment)
+        .await
+        .map_err(|e| anyhow!("Failed to flag document: {}", e))?;
+
+    info!(
+        "User {} flagged document {} as {:?}",
+        user.id, document_id, flag.reason
+    );
+    Ok(Json(flag))
+}
+
+pub async fn list_flags(
+    State(state): State<AppState>,
+    Query(query): Query<ListFlagsQuery>,
+) -> SearcherResult<Json<Vec<DocumentFlag>>> {
+    let flags = DocumentFlagRepository::new(state.db_pool.pool())
+        .find_all(query.status, query.owner_id.as_deref())
+        .await
+        .map_err(|e| anyhow!("Failed to list document flags: {}", e))?;
+    Ok(Json(flags))
+}
+
+pub async fn update_flag(
+    State(state): State<AppState>,
+    Path(flag_id): Path<String>,
+    Json(request): Json<UpdateFlagRequest>,
+) -> SearcherResult<Json<DocumentFlag>> {
+    let flag = DocumentFlagRepository::new(state.db_pool.pool())
+        .set_status(&flag_id, request.status, request.user_id.as_deref())
+        .await
+        .map_err(|e| anyhow!("Failed to update document flag: {}", e))?
+        .ok_or_else(|| SearcherError::NotFound(format!("Document flag not found: {}", flag_id)))?;
+
+    info!(
+        "Marked flag {} on document {} as {:?}",
+        flag.id, flag.document_id, flag.status
+    );
+    Ok(Json(flag))
+}
+
+/// Documents linked from and to a document, limited to those the user can see.
+pub async fn document_links(
+    State(state): State<AppState>,
+    Path(document_id): Path<String>,
+    Query(query): Query<DocumentLinksQuery>,
+) -> SearcherResult<Json<DocumentLinksResponse>> {
+    let visible = visible_documents(
+        &state,
+        std::slice::from_ref(&document_id),
+        &query.user_email,
+    )
+    .await?;
+    if visible.is_empty() {
+        return Err(SearcherError::NotFound(format!(
+            "Document not found: {}",
+            document_id
+        )));
+    }
+
+    let repo = DocumentLinkRepository::new(state.db_pool.pool());
+    let outbound = repo
+        .find_outbound(&document_id)
+        .await
+        .map_err(|e| anyhow!("Failed to look up document links: {}", e))?;
+    let inbound = repo
+        .find_inbound(&document_id)
+        .await
+        .map_err(|e| anyhow!("Failed to look up document links: {}", e))?;
+
+    Ok(Json(DocumentLinksResponse {
+        outbound: linked_documents(&state, outbound, &query.user_email).await?,
+        inbound: linked_documents(&state, inbound, &query.user_email).await?,
+    }))
+}
+
+/// Record that a user opened a document, for the trending feed.
+pub async fn record_interaction(
+    State(state): State<AppState>,
+    Path(document_id): Path<String>,
+    Json(request): Json<RecordInteractionRequest>,
+) -> SearcherResult<Json<Value>> {
+    let user = active_user(&state, &request.user_id).await?;
+    let visible =
+        visible_documents(&state, std::slice::from_ref(&document_id), &user.email).await?;
+    if visible.is_empty() {
+        return Err(SearcherError::NotFound(format!(
+            "Document not found: {}",
+            document_id
+        )));
+    }
+
+    DocumentInteractionRepository::new(state.db_pool.pool())
+        .record(&document_id, &user.id, request.kind)
+        .await
+        .map_err(|e| anyhow!("Failed to record document interaction: {}", e))?;
+    Ok(Json(json!({ "status": "recorded" })))
+}
+
+/// Documents users have been opening lately, limited to those the user can see.
+pub async fn trending_feed(
+    State(state): State<AppState>,
+    Query(query): Query<FeedQuery>,
+) -> SearcherResult<Json<FeedResponse>> {
+    let now = OffsetDateTime::now_utc();
+    let active = DocumentInteractionRepository::new(state.db_pool.pool())
+        .find_most_active(
+            now - time::Duration::days(feed::TRENDING_WINDOW_DAYS),
+            feed::ACTIVITY_HALF_LIFE_HOURS,
+            feed::TRENDING_CANDIDATES,
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to look up document activity: {}", e))?;
+
+    let mut scored: Vec<(String, f64)> = active
+        .into_iter()
+        .map(|document| {
+            let days_since_update = (now - document.updated_at).as_seconds_f64() / 86_400.0;
+            let score = feed::trending_score(document.activity, days_since_update);
+            (document.document_id, score)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let ids: Vec<String> = scored.into_iter().map(|(id, _)| id).collect();
+    let documents = visible_documents(&state, &ids, &query.user_email).await?;
+
+    Ok(Json(FeedResponse {
+        documents: documents
+            .into_iter()
+            .take(feed::limit(query.limit))
+            .map(FeedDocument::from)
+            .collect(),
+    }))
+}
+
+/// The most recently updated documents the user can see. People profiles are left out, as
+/// they are all updated whenever the directory is rebuilt.
+pub async fn recent_feed(
+    State(state): State<AppState>,
+    Query(query): Query<FeedQuery>,
+) -> SearcherResult<Json<FeedResponse>> {
+    let pool = state.db_pool.pool();
+    let repo = DocumentRepository::new(pool);
+    let people_sources: Vec<String> = SourceRepository::new(pool)
+        .find_by_type("people")
+        .await
+        .map_err(|e| anyhow!("Failed to fetch sources: {}", e))?
+        .into_iter()
+        .map(|source| source.id)
+        .collect();
+    let source_ids: Vec<String> = repo
+        .fetch_active_source_ids(None)
+        .await
+        .map_err(|e| anyhow!("Failed to fetch active sources: {}", e))?
+        .into_iter()
+        .filter(|id| !people_sources.contains(id))
+        .collect();
+    let documents = repo
+        .find_recently_updated(
+            &source_ids,
+            &query.user_email,
+            feed::limit(query.limit) as i64,
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to look up recent documents: {}", e))?;
+
+    Ok(Json(FeedResponse {
+        documents: documents.into_iter().map(FeedDocument::from).collect(),
+    }))
+}
+
+/// Documents recommended to the user by the indexer's last run, re-checked against what they
+/// can see now.
+pub async fn recommendations(
+    State(state): State<AppState>,
+    Query(query): Query<RecommendationsQuery>,
+) -> SearcherResult<Json<RecommendationsResponse>> {
+    let user = active_user(&state, &query.user_id).await?;
+    let recommendations = RecommendationRepository::new(state.db_pool.pool())
+        .find_for_user(&user.id)
+        .await
+        .map_err(|e| anyhow!("Failed to look up recommendations: {}", e))?;
+    let computed_at = recommendations.first().map(|r| r.computed_at);
+
+    let ids: Vec<String> = recommendations
+        .iter()
+        .map(|r| r.document_id.clone())
+        .collect();
+    let mut by_id: HashMap<String, (RecommendationReason, f32)> = recommendations
+        .into_iter()
+        .map(|r| (r.document_id, (r.reason, r.score)))
+        .collect();
+    let documents = visible_documents(&state, &ids, &user.email)
+        .await?
+        .into_iter()
+        .take(feed::limit(query.limit))
+        .filter_map(|document| {
+            let (reason, score) = by_id.remove(&document.id)?;
+            Some(RecommendedDocument {
+                document: FeedDocument::from(document),
+                reason,
+                score,
+            })
+        })
+        .collect();
+
+    Ok(Json(RecommendationsResponse {
+        documents,
+        computed_at,
+    }))
+}
+
+/// Most documents of a person considered for their profile, before permission filtering.
+const PERSON_DOCUMENT_CANDIDATES: i64 = 200;
+const PROFILE_TOP_DOCUMENTS: usize = 10;
+const PROFILE_COLLABORATORS: i64 = 10;
+
+/// A person's profile from the people directory. Their top documents and collaborators only
+/// count documents the user can see, so a profile does not reveal private threads or meetings.
+pub async fn person_profile(
+    State(state): State<AppState>,
+    Path(email): Path<String>,
+    Query(query): Query<PersonProfileQuery>,
+) -> SearcherResult<Json<PersonProfileResponse>> {
+    let repo = PersonRepository::new(state.db_pool.pool());
+    let person = repo
+        .find_by_email(&email)
+        .await
+        .map_err(|e| anyhow!("Failed to look up person: {}", e))?
+        .ok_or_else(|| SearcherError::NotFound(format!("Person not found: {}", email)))?;
+
+    let activity = repo
+        .find_top_documents(&person.id, PERSON_DOCUMENT_CANDIDATES)
+        .await
+        .map_err(|e| anyhow!("Failed to look up person documents: {}", e))?;
+    let ids: Vec<String> = activity.iter().map(|a| a.document_id.clone()).collect();
+    let mut roles: HashMap<String, Vec<String>> = activity
+        .into_iter()
+        .map(|a| (a.document_id, a.roles))
+        .collect();
+    let documents = visible_documents(&state, &ids, &query.user_email).await?;
+    let visible_ids: Vec<String> = documents.iter().map(|d| d.id.clone()).collect();
+    let collaborators = repo
+        .find_collaborators(&person.id, &visible_ids, PROFILE_COLLABORATORS)
+        .await
+        .map_err(|e| anyhow!("Failed to look up collaborators: {}", e))?;
+
+    let top_documents = documents
+        .into_iter()
+        .take(PROFILE_TOP_DOCUMENTS)
+        .map(|document| PersonDocumentResult {
+            roles: roles.remove(&document.id).unwrap_or_default(),
+            document_id: document.id,
+            source_id: document.source_id,
+            title: document.title,
+            url: document.url,
+        })
+        .collect();
+
+    Ok(Json(PersonProfileResponse {
+        person,
+        top_documents,
+        collaborators,
+    }))
+}
+
+async fn linked_documents(
+    state: &AppState,
+    links: Vec<LinkedDocument>,
+    user_email: &str,
+) -> SearcherResult<Vec<DocumentLinkResult>> {
+    let ids: Vec<String> = links.iter().map(|link| link.document_id.clone()).collect();
+    let kinds: HashMap<String, LinkKind> = links
+        .into_iter()
+        .map(|link| (link.document_id, link.kind))
+        .collect();
+    let documents = visible_documents(state, &ids, user_email).await?;
+    Ok(documents
+        .into_iter()
+        .filter_map(|document| {
+            Some(DocumentLinkResult {
+                kind: *kinds.get(&document.id)?,
+                document_id: document.id,
+                source_id: document.source_id,
+                title: document.title,
+                url: document.url,
+            })
+        })
+        .collect())
+}
+
+/// Run a Confluence CQL search as the user, returning the search response with the start and
+/// limit it was run with.
+async fn confluence_search_results(
+    state: AppState,
+    query: &ConfluenceSearchQuery,
+) -> SearcherResult<(SearchResponse, i64, i64)> {
+    let cql = confluence::parse_cql(&query.cql).map_err(SearcherError::BadRequest)?;
+    let user = active_user(&state, &query.user_id).await?;
+    let mut request = cql.into_search_request(
+        user.id,
+        query.start.unwrap_or(0),
+        query.limit.unwrap_or(confluence::DEFAULT_LIMIT),
+    );
+    request.user_email = Some(user.email);
+    let (start, limit) = (request.offset(), request.limit());
+
+    let config = state.search_config();
+    let search_engine = SearchEngine::new(
+        state.db_pool,
+        state.redis_client,
+        state.ai_client,
+        config,
+        state.redaction.current(),
+    )
+    .await?;
+    let response = search_engine.search(request).await?;
+    Ok((response, start, limit))
+}
+
+fn confluence_content(document: &Document) -> ConfluenceContent {
+    ConfluenceContent {
+        id: document.id.clone(),
+        content_type: "page".to_string(),
+        status: "current".to_string(),
+        title: document.title.clone(),
+        links: ConfluenceContentLinks {
+            webui: document.url.clone().unwrap_or_default(),
+        },
+    }
+}
+
+/// Search in the shape of Confluence's `/rest/api/search`, for tools migrating from it.
+pub async fn confluence_search(
+    State(state): State<AppState>,
+    Query(query): Query<ConfluenceSearchQuery>,
+) -> SearcherResult<Json<ConfluenceSearchResponse>> {
+    let (response, start, limit) = confluence_search_results(state, &query).await?;
+    let results: Vec<ConfluenceSearchResult> = response
+        .results
+        .into_iter()
+        .map(|result| ConfluenceSearchResult {
+            content: confluence_content(&result.document),
+            excerpt: result
+                .highlights
+                .first()
+                .map(|highlight| confluence::excerpt(highlight))
+                .unwrap_or_default(),
+            url: result.document.url.clone().unwrap_or_default(),
+            title: result.document.title,
+            last_modified: result.document.updated_at,
+            entity_type: "content".to_string(),
+        })
+        .collect();
+
+    Ok(Json(ConfluenceSearchResponse {
+        size: results.len(),
+        results,
+        start,
+        limit,
+        total_size: response.total_count,
+        cql_query: query.cql,
+        search_duration: response.query_time_ms,
+        links: ConfluenceLinks::default(),
+    }))
+}
+
+/// Search in the shape of Confluence's `/rest/api/content/search`, for tools migrating from it.
+pub async fn confluence_content_search(
+    State(state): State<AppState>,
+    Query(query): Query<ConfluenceSearchQuery>,
+) -> SearcherResult<Json<ConfluenceContentSearchResponse>> {
+    let (response, start, limit) = confluence_search_results(state, &query).await?;
+    let results: Vec<ConfluenceContent> = response
+        .results
+        .iter()
+        .map(|result| confluence_content(&result.document))
+        .collect();
+
+    Ok(Json(ConfluenceContentSearchResponse {
+        size: results.len(),
+        results,
+        start,
+        limit,
+        links: ConfluenceLinks::default(),
+    }))
+}
+
+/// Documents that may answer a help desk ticket, for "suggested articles" widgets.
+pub async fn suggested_articles(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<SuggestedArticlesRequest>,
+) -> SearcherResult<Json<SuggestedArticlesResponse>> {
+    let Some(api_token) = state.config.helpdesk_api_token.as_deref() else {
+        return Err(SearcherError::NotFound(
+            "Help desk suggestions are not enabled".to_string(),
+        ));
+    };
+    if !helpdesk::authorized(&headers, api_token) {
+        return Err(SearcherError::Forbidden(
+            "Invalid help desk API token".to_string(),
+        ));
+    }
+    let query = helpdesk::ticket_query(&request.subject, request.description.as_deref());
+    if query.is_empty() {
+        return Err(SearcherError::BadRequest(
+            "A ticket subject or description is required".to_string(),
+        ));
+    }
+
+    let limit = helpdesk::limit(request.limit);
+    let public_only = request.user_email.is_none();
+    let candidates = if public_only {
+        limit * helpdesk::PUBLIC_CANDIDATES_PER_SUGGESTION
+    } else {
+        limit
+    };
+    let config = state.search_config();
+    let max_score = config.tuning.max_hybrid_score();
+    let min_confidence = request.min_confidence.unwrap_or(0.0);
+
+    let search_engine = SearchEngine::new(
+        state.db_pool,
+        state.redis_client,
+        state.ai_client,
+        config,
+        state.redaction.current(),
+    )
+    .await?;
+    let response = search_engine
+        .search(SearchRequest {
+            query,
+            user_email: request.user_email,
+            mode: Some(SearchMode::Hybrid),
+            limit: Some(candidates as i64),
+            include_facets: Some(false),
+            ..Default::default()
+        })
+        .await?;
+
+    let articles = response
+        .results
+        .into_iter()
+        .filter(|result| !public_only || result.document.permissions["public"] == json!(true))
+        .map(|result| SuggestedArticle {
+            snippet: helpdesk::snippet(
+                result.highlights.first().map(String::as_str),
+                result.content.as_deref(),
+            ),
+            confidence: helpdesk::confidence(result.score, max_score),
+            id: result.document.id,
+            title: result.document.title,
+            html_url: result.document.url,
+        })
+        .filter(|article| article.confidence >= min_confidence)
+        .take(limit)
+        .collect();
+
+    Ok(Json(SuggestedArticlesResponse { articles }))
+}
+
+/// Search meeting transcripts, returning meetings with the lines that matched.
+pub async fn search_meetings(
+    State(state): State<AppState>,
+    Json(request): Json<MeetingSearchRequest>,
+) -> SearcherResult<Json<MeetingSearchResponse>> {
+    let start_time = std::time::Instant::now();
+    let (query, filters) =
+        meetings::parse_query(&request.query).map_err(SearcherError::BadRequest)?;
+    if query.is_empty() {
+        return Err(SearcherError::BadRequest(
+            "A meeting search needs terms to match besides its filters".to_string(),
+        ));
+    }
+    let limit = meetings::limit(request.limit);
+    let offset = request.offset.unwrap_or(0);
+
+    let config = state.search_config();
+    let redactor = state.redaction.current();
+    let search_engine = SearchEngine::new(
+        state.db_pool,
+        state.redis_client,
+        state.ai_client,
+        config,
+        redactor.clone(),
+    )
+    .await?;
+    let response = search_engine
+        .search(SearchRequest {
+            query: query.clone(),
+            source_types: Some(meetings::MEETING_SOURCE_TYPES.to_vec()),
+            // Dropping filters would return documents other than meetings
+            fallback: Some(false),
+            attribute_filters: filters.attribute_filters(),
+            user_email: request.user_email,
+            user_id: request.user_id,
+            mode: Some(request.mode.unwrap_or(SearchMode::Hybrid)),
+            limit: Some(meetings::MAX_CANDIDATES),
+            include_facets: Some(false),
+            child_results: Some(ChildResults::RolledUp),
+            ..Default::default()
+        })
+        .await?;
+
+    let mut matching = response
+        .results
+        .into_iter()
+        .filter(|result| filters.matches(&result.document))
+        .skip(offset);
+    let mut results = Vec::new();
+    for result in matching.by_ref().take(limit) {
+        let mut snippets = Vec::new();
+        let transcripts = std::iter::once(&result)
+            .chain(result.children.iter().map(|child| &child.result))
+            .filter_map(|transcript| {
+                let content_id = transcript.document.content_id.as_deref()?;
+                Some((transcript, content_id))
+            });
+        for (transcript, content_id) in transcripts {
+            let content = match search_engine.document_text(content_id).await {
+                Ok(content) => content,
+                Err(e) => {
+                    debug!("Failed to read transcript {}: {}", content_id, e);
+                    continue;
+                }
+            };
+            // Snippets are previews of the transcript, redacted as its highlights are
+            let lines = meetings::matching_lines(&content, &query, &filters.speakers);
+            let texts = lines.iter().map(|line| line.text.clone()).collect();
+            let texts = redactor.apply_to_texts(transcript, texts);
+            snippets.extend(
+                lines
+                    .into_iter()
+                    .zip(texts)
+                    .map(|(line, text)| TranscriptSnippet {
+                        speaker: line.speaker,
+                        timestamp_seconds: line.timestamp_seconds,
+                        text,
+                    }),
+            );
+        }
+        snippets.sort_by_key(|snippet| snippet.timestamp_seconds);
+
+        let document = result.document;
+        results.push(MeetingResult {
+            date: document.attributes["date"].as_str().map(str::to_string),
+            attendees: meetings::string_list(&document.attributes, "attendees"),
+            speakers: meetings::string_list(&document.attributes, "speakers"),
+            score: result.score,
+            snippets,
+            id: document.id,
+            source_id: document.source_id,
+            title: document.title,
+            url: document.url,
+        });
+    }
+
+    Ok(Json(MeetingSearchResponse {
+        meetings: results,
+        query_time_ms: start_time.elapsed().as_millis() as u64,
+        has_more: matching.next().is_some(),
+    }))
+}
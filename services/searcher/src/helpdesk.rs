@@ -0,0 +1,129 @@
+//! Knowledge-base suggestions for help desk tickets, for "suggested articles" widgets in tools
+//! such as Jira Service Management and Zendesk. A ticket's subject and description are run as
+//! a hybrid search and each match gets a confidence between 0 and 1, so that widgets can hide
+//! weak suggestions rather than deflect a ticket to an unrelated document.
+//!
+//! Callers authenticate with the `HELPDESK_API_TOKEN` bearer token. Suggestions for an agent
+//! named by `user_email` come from the documents the agent can see; otherwise, as for tickets
+//! shown to the people who filed them, only public documents are suggested.
+
+use crate::highlighting;
+use axum::http::{header::AUTHORIZATION, HeaderMap};
+use sha2::{Digest, Sha256};
+
+pub const DEFAULT_LIMIT: usize = 5;
+pub const MAX_LIMIT: usize = 10;
+/// Most characters of a ticket searched for. Long descriptions are mostly quoted replies and
+/// logs, which dilute the match.
+pub const MAX_QUERY_CHARS: usize = 1000;
+/// Matches fetched per suggestion when only public documents can be suggested, as private
+/// ones are dropped after searching.
+pub const PUBLIC_CANDIDATES_PER_SUGGESTION: usize = 4;
+const MAX_SNIPPET_CHARS: usize = 300;
+
+/// Whether a request carries the configured API token. Digests are compared so that the
+/// comparison takes as long wherever the tokens differ.
+pub fn authorized(headers: &HeaderMap, api_token: &str) -> bool {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| Sha256::digest(token) == Sha256::digest(api_token))
+}
+
+/// The number of suggestions to return for a requested limit.
+pub fn limit(requested: Option<usize>) -> usize {
+    requested.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+}
+
+/// The search query for a ticket: its subject and description, with whitespace collapsed.
+pub fn ticket_query(subject: &str, description: Option<&str>) -> String {
+    let query = subject
+        .split_whitespace()
+        .chain(description.unwrap_or_default().split_whitespace())
+        .collect::<Vec<_>>()
+        .join(" ");
+    match query.char_indices().nth(MAX_QUERY_CHARS) {
+        Some((end, _)) => query[..end].to_string(),
+        None => query,
+    }
+}
+
+/// How confident a suggestion is, from its hybrid score and the highest score a search can
+/// give before boosts: a full-text and a semantic match both scoring 1.
+pub fn confidence(score: f32, max_score: f32) -> f32 {
+    if max_score <= 0.0 {
+        return 0.0;
+    }
+    (score / max_score).clamp(0.0, 1.0)
+}
+
+/// A plain-text snippet from a result's highlight or content, without match markers.
+pub fn snippet(highlight: Option<&str>, content: Option<&str>) -> String {
+    let text = highlight
+        .map(|highlight| highlight.replace("**", ""))
+        .or_else(|| content.map(str::to_string))
+        .unwrap_or_default();
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    highlighting::truncate(&text, MAX_SNIPPET_CHARS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorized() {
+        let mut headers = HeaderMap::new();
+        assert!(!authorized(&headers, "secret"));
+        headers.insert(AUTHORIZATION, "Bearer wrong".parse().unwrap());
+        assert!(!authorized(&headers, "secret"));
+        headers.insert(AUTHORIZATION, "secret".parse().unwrap());
+        assert!(!authorized(&headers, "secret"));
+        headers.insert(AUTHORIZATION, "Bearer secret".parse().unwrap());
+        assert!(authorized(&headers, "secret"));
+    }
+
+    #[test]
+    fn test_limit() {
+        assert_eq!(limit(None), DEFAULT_LIMIT);
+        assert_eq!(limit(Some(0)), 1);
+        assert_eq!(limit(Some(100)), MAX_LIMIT);
+    }
+
+    #[test]
+    fn test_ticket_query() {
+        assert_eq!(
+            ticket_query(" VPN  down ", Some("Cannot connect\nsince this morning")),
+            "VPN down Cannot connect since this morning"
+        );
+        assert_eq!(ticket_query("VPN down", None), "VPN down");
+        let long = "é".repeat(MAX_QUERY_CHARS * 2);
+        assert_eq!(
+            ticket_query("", Some(&long)).chars().count(),
+            MAX_QUERY_CHARS
+        );
+    }
+
+    #[test]
+    fn test_confidence() {
+        assert_eq!(confidence(0.7, 1.4), 0.5);
+        assert_eq!(confidence(2.0, 1.4), 1.0);
+        assert_eq!(confidence(-0.1, 1.4), 0.0);
+        assert_eq!(confidence(0.5, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_snippet() {
+        assert_eq!(
+            snippet(Some("reset your **VPN**\ntoken"), Some("content")),
+            "reset your VPN token"
+        );
+        assert_eq!(snippet(None, Some("content")), "content");
+        assert_eq!(snippet(None, None), "");
+        let long = "word ".repeat(100);
+        let snippet = snippet(None, Some(&long));
+        assert!(snippet.ends_with('…'));
+        assert!(snippet.chars().count() <= MAX_SNIPPET_CHARS + 1);
+    }
+}
@@ -0,0 +1,280 @@
+//! Highlight fragments cut from document content.
+//!
+//! Fragments start and end on grapheme cluster boundaries, so an emoji sequence, a flag or a
+//! letter with combining marks is never split across a fragment edge, and truncated fragments
+//! end on word boundaries. Snippets the index cuts by character count are trimmed of the
+//! partial clusters left at their edges.
+
+use shared::storage::content_sha256;
+use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
+
+/// A document's stored content, for cutting out the chunks its embeddings were computed from.
+pub struct ChunkSource<'a> {
+    content: &'a str,
+    sha256: String,
+}
+
+impl<'a> ChunkSource<'a> {
+    pub fn new(content: &'a str) -> Self {
+        Self {
+            content,
+            sha256: content_sha256(content.as_bytes()),
+        }
+    }
+
+    /// The text of a chunk from character `start_offset` to `end_offset`, widened to whole
+    /// grapheme clusters. `None` when the chunk was computed against other content, as its
+    /// `content_sha256` tells, or its offsets fall outside this content. Chunks without a
+    /// checksum are assumed to be of this content.
+    pub fn chunk(
+        &self,
+        start_offset: i32,
+        end_offset: i32,
+        content_sha256: Option<&str>,
+    ) -> Option<&'a str> {
+        if content_sha256.is_some_and(|sha256| sha256 != self.sha256) {
+            return None;
+        }
+        let start = usize::try_from(start_offset).ok()?;
+        let end = usize::try_from(end_offset).ok()?;
+        if start >= end {
+            return None;
+        }
+        let start = byte_offset(self.content, start)?;
+        let end = byte_offset(self.content, end)?;
+        Some(fragment(self.content, start, end))
+    }
+}
+
+/// The byte offset of character `char_offset` of `text`, which may be its end.
+fn byte_offset(text: &str, char_offset: usize) -> Option<usize> {
+    text.char_indices()
+        .map(|(idx, _)| idx)
+        .chain(std::iter::once(text.len()))
+        .nth(char_offset)
+}
+
+/// The part of `content` from byte `start` to `end`, widened to whole grapheme clusters.
+pub fn fragment(content: &str, start: usize, end: usize) -> &str {
+    let start = boundary_before(content, start);
+    let end = boundary_after(content, end.max(start));
+    &content[start..end]
+}
+
+/// `text` cut to at most `max_chars` characters at a word boundary, followed by an ellipsis
+/// when cut. A first word longer than that is cut between grapheme clusters.
+pub fn truncate(text: &str, max_chars: usize) -> String {
+    let Some((limit, _)) = text.char_indices().nth(max_chars) else {
+        return text.to_string();
+    };
+    let word_end = text
+        .split_word_bound_indices()
+        .map(|(idx, word)| idx + word.len())
+        .take_while(|end| *end <= limit)
+        .last()
+        .unwrap_or(0);
+    let cut = if word_end > 0 {
+        word_end
+    } else {
+        boundary_before(text, limit)
+    };
+    format!("{}…", text[..cut].trim_end())
+}
+
+/// `fragment` without the partial grapheme clusters left at its edges by cutting it from a
+/// longer text, such as a combining mark without its letter or an emoji sequence cut after
+/// its joiner.
+pub fn trim_partial_clusters(fragment: &str) -> &str {
+    let mut graphemes = fragment.grapheme_indices(true);
+    let start = match graphemes.next() {
+        Some((_, first)) if is_partial_start(first) => first.len(),
+        Some(_) => 0,
+        None => return fragment,
+    };
+    let end = match fragment[start..].graphemes(true).next_back() {
+        Some(last) if is_partial_end(last) => fragment.len() - last.len(),
+        _ => fragment.len(),
+    };
+    &fragment[start..end.max(start)]
+}
+
+fn boundary_before(text: &str, offset: usize) -> usize {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let mut cursor = GraphemeCursor::new(offset, text.len(), true);
+    match cursor.is_boundary(text, 0) {
+        Ok(true) => offset,
+        _ => cursor.prev_boundary(text, 0).ok().flatten().unwrap_or(0),
+    }
+}
+
+fn boundary_after(text: &str, offset: usize) -> usize {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset += 1;
+    }
+    let mut cursor = GraphemeCursor::new(offset, text.len(), true);
+    match cursor.is_boundary(text, 0) {
+        Ok(true) => offset,
+        _ => cursor
+            .next_boundary(text, 0)
+            .ok()
+            .flatten()
+            .unwrap_or(text.len()),
+    }
+}
+
+/// Whether a cluster at the start of a fragment is the rest of one cut off before it: it
+/// would extend a letter before it, or it is half of a flag.
+fn is_partial_start(grapheme: &str) -> bool {
+    let mut chars = grapheme.chars();
+    let lone_regional_indicator = matches!(
+        (chars.next(), chars.next()),
+        (Some(c), None) if is_regional_indicator(c)
+    );
+    lone_regional_indicator || format!("a{}", grapheme).graphemes(true).count() == 1
+}
+
+/// Whether a cluster at the end of a fragment was cut off before its end.
+fn is_partial_end(grapheme: &str) -> bool {
+    let mut chars = grapheme.chars();
+    match (chars.next(), chars.next_back()) {
+        (Some(c), None) => is_regional_indicator(c),
+        (_, Some(last)) => last == '\u{200d}',
+        (None, None) => false,
+    }
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1f1e6}'..='\u{1f1ff}').contains(&c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Texts mixing scripts, combining marks and emoji sequences, for checking every fragment
+    /// edge against.
+    const CORPUS: [&str; 10] = [
+        "Quarterly planning for Q4: budget, hiring and roadmap.",
+        "Cafe\u{301} au lait, cre\u{300}me bru\u{302}le\u{301}e et pa\u{302}tisserie",
+        "The team 👩‍👩‍👧‍👦 shipped 🚀 the release 👍🏽 on time ✅",
+        "Offices in 🇯🇵 Tokyo, 🇫🇷 Paris and 🇧🇷 São Paulo",
+        "東京都庁で会議を開きます。議事録は共有フォルダにあります。",
+        "नमस्ते, आज की बैठक में बजट पर चर्चा होगी",
+        "สวัสดีครับ การประชุมวันนี้เริ่มเวลาสิบโมง",
+        "مرحبا، سيبدأ الاجتماع في الساعة العاشرة",
+        "\u{1100}\u{1161}\u{11a8} 한국어 문서 검색",
+        "Z\u{351}\u{36b}a\u{35b}\u{36e}l\u{360}g\u{319}o text ❤\u{fe0f} #\u{fe0f}\u{20e3} 🏴\u{e0067}\u{e0062}\u{e0073}\u{e0063}\u{e0074}\u{e007f}",
+    ];
+
+    fn boundaries(text: &str) -> Vec<usize> {
+        text.grapheme_indices(true)
+            .map(|(idx, _)| idx)
+            .chain(std::iter::once(text.len()))
+            .collect()
+    }
+
+    fn offset_in(text: &str, part: &str) -> usize {
+        part.as_ptr() as usize - text.as_ptr() as usize
+    }
+
+    #[test]
+    fn test_chunk_source() {
+        let content = "Café menu 👩‍👩‍👧‍👦 and prices";
+        let source = ChunkSource::new(content);
+        assert_eq!(source.chunk(0, 9, None), Some("Café menu"));
+        assert_eq!(source.chunk(10, 11, None), Some("👩‍👩‍👧‍👦"));
+        let sha256 = content_sha256(content.as_bytes());
+        assert_eq!(source.chunk(5, 9, Some(&sha256)), Some("menu"));
+        assert_eq!(source.chunk(5, 9, Some("0123")), None);
+        assert_eq!(source.chunk(5, 500, None), None);
+        assert_eq!(source.chunk(9, 5, None), None);
+        assert_eq!(source.chunk(-1, 5, None), None);
+    }
+
+    #[test]
+    fn test_fragment() {
+        let text = "The team 👩‍👩‍👧‍👦 shipped";
+        let family = text.find('👩').unwrap();
+        assert_eq!(fragment(text, family + 5, family + 6), "👩‍👩‍👧‍👦");
+        assert_eq!(fragment(text, 0, 3), "The");
+        assert_eq!(fragment(text, 4, 4), "");
+    }
+
+    #[test]
+    fn test_fragments_of_corpus_cover_whole_clusters() {
+        for text in CORPUS {
+            let boundaries = boundaries(text);
+            for start in 0..=text.len() {
+                for end in start..=text.len() {
+                    let part = fragment(text, start, end);
+                    let part_start = offset_in(text, part);
+                    let part_end = part_start + part.len();
+                    assert!(boundaries.contains(&part_start), "{:?} at {}", text, start);
+                    assert!(boundaries.contains(&part_end), "{:?} at {}", text, end);
+                    assert!(part_start <= start && part_end >= end.min(text.len()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(
+            truncate("reset your VPN token", 100),
+            "reset your VPN token"
+        );
+        assert_eq!(truncate("reset your VPN token", 12), "reset your…");
+        assert_eq!(truncate("👍🏽👍🏽👍🏽", 3), "👍🏽…");
+    }
+
+    #[test]
+    fn test_truncations_of_corpus_end_on_clusters() {
+        for text in CORPUS {
+            let boundaries = boundaries(text);
+            for max_chars in 0..=text.chars().count() + 1 {
+                let truncated = truncate(text, max_chars);
+                let Some(kept) = truncated.strip_suffix('…') else {
+                    assert_eq!(truncated, text);
+                    continue;
+                };
+                assert!(text.starts_with(kept));
+                assert!(
+                    boundaries.contains(&kept.len()),
+                    "{:?} at {}",
+                    text,
+                    max_chars
+                );
+                assert!(kept.chars().count() <= max_chars);
+            }
+        }
+    }
+
+    #[test]
+    fn test_trim_partial_clusters() {
+        assert_eq!(trim_partial_clusters("\u{301}e au lait"), "e au lait");
+        assert_eq!(trim_partial_clusters("team 👩‍👩‍"), "team ");
+        assert_eq!(trim_partial_clusters("\u{1f1f5} Tokyo 🇫🇷"), " Tokyo 🇫🇷");
+        assert_eq!(trim_partial_clusters("**VPN** token"), "**VPN** token");
+        assert_eq!(trim_partial_clusters(""), "");
+    }
+
+    #[test]
+    fn test_trimmed_cuts_of_corpus_have_no_partial_clusters() {
+        for text in CORPUS {
+            let chars: Vec<usize> = text.char_indices().map(|(idx, _)| idx).collect();
+            for &start in &chars {
+                for &end in chars.iter().filter(|end| **end > start) {
+                    let trimmed = trim_partial_clusters(&text[start..end]);
+                    if let Some(first) = trimmed.graphemes(true).next() {
+                        assert!(!is_partial_start(first), "{:?}", trimmed);
+                    }
+                    assert!(!trimmed.ends_with('\u{200d}'), "{:?}", trimmed);
+                }
+            }
+        }
+    }
+}
@@ -1,19 +1,46 @@
+pub mod acronyms;
+pub mod budget;
+pub mod collections;
+pub mod confluence;
+pub mod duplicates;
+pub mod facets;
+pub mod fallback;
+pub mod feed;
+pub mod grpc;
 pub mod handlers;
+pub mod helpdesk;
+pub mod highlighting;
+pub mod meetings;
 pub mod models;
+pub mod promotions;
+pub mod query_embeddings;
+pub mod query_log;
+pub mod ranking;
+pub mod redaction;
+pub mod relationships;
+pub mod result_fields;
 pub mod search;
+pub mod shadow_search;
 pub mod suggested_questions;
+pub mod teams;
+pub mod tuning;
 pub mod typeahead;
+pub mod warmup;
 
 use anyhow::Result as AnyhowResult;
 use axum::{
     middleware,
-    routing::{get, post},
+    routing::{delete, get, post, put},
     Router,
 };
 use redis::Client as RedisClient;
 use shared::{
+    ip_allowlist,
+    shutdown::Shutdown,
+    startup::{connect_database, connect_redis, Dependencies, Startup},
     telemetry::{self, TelemetryConfig},
-    AIClient, DatabasePool, ObjectStorage, SearcherConfig, StorageFactory,
+    AIClient, ApiError, DatabasePool, ObjectStorage, SearcherConfig, StorageFactory,
+    TeamsBotConfig,
 };
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -21,9 +48,15 @@ use tower::ServiceBuilder;
 use tower_http::cors::CorsLayer;
 use tracing::{error, info};
 
+use crate::redaction::RedactionStore;
 use crate::suggested_questions::SuggestedQuestionsGenerator;
+use crate::teams::TeamsBot;
+use crate::tuning::TuningStore;
 use crate::typeahead::TitleIndex;
 
+/// Migrations this build expects the database to be at, checked on startup.
+static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("../migrations");
+
 pub type Result<T> = std::result::Result<T, SearcherError>;
 
 #[derive(thiserror::Error, Debug)]
@@ -40,36 +73,36 @@ pub enum SearcherError {
     NotFound(String),
     #[error("Bad request: {0}")]
     BadRequest(String),
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+}
+
+impl From<SearcherError> for ApiError {
+    fn from(err: SearcherError) -> Self {
+        match err {
+            SearcherError::Database(e) => {
+                error!("Database error: {}", e);
+                ApiError::internal("Database error")
+            }
+            SearcherError::Redis(e) => {
+                error!("Redis error: {}", e);
+                ApiError::unavailable("Cache error")
+            }
+            SearcherError::Serialization(_) => ApiError::bad_request("Invalid request format"),
+            SearcherError::Internal(e) => {
+                error!("Internal error: {}", e);
+                ApiError::internal("Internal server error")
+            }
+            SearcherError::NotFound(msg) => ApiError::not_found(msg),
+            SearcherError::BadRequest(msg) => ApiError::bad_request(msg),
+            SearcherError::Forbidden(msg) => ApiError::forbidden(msg),
+        }
+    }
 }
 
 impl axum::response::IntoResponse for SearcherError {
     fn into_response(self) -> axum::response::Response {
-        let (status, message) = match self {
-            SearcherError::Database(_) => (
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                "Database error".to_string(),
-            ),
-            SearcherError::Redis(_) => (
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                "Cache error".to_string(),
-            ),
-            SearcherError::Serialization(_) => (
-                axum::http::StatusCode::BAD_REQUEST,
-                "Invalid request format".to_string(),
-            ),
-            SearcherError::Internal(_) => (
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                "Internal server error".to_string(),
-            ),
-            SearcherError::NotFound(msg) => (axum::http::StatusCode::NOT_FOUND, msg),
-            SearcherError::BadRequest(msg) => (axum::http::StatusCode::BAD_REQUEST, msg),
-        };
-
-        let body = serde_json::json!({
-            "error": message,
-        });
-
-        (status, axum::Json(body)).into_response()
+        ApiError::from(self).into_response()
     }
 }
 
@@ -79,19 +112,135 @@ pub struct AppState {
     pub redis_client: RedisClient,
     pub ai_client: AIClient,
     pub config: SearcherConfig,
+    pub tuning: Arc<TuningStore>,
+    pub redaction: Arc<RedactionStore>,
     pub content_storage: Arc<dyn ObjectStorage>,
     pub suggested_questions_generator: Arc<SuggestedQuestionsGenerator>,
     pub title_index: Arc<TitleIndex>,
+    /// Set when a Teams bot registration is configured
+    pub teams_bot: Option<Arc<TeamsBot>>,
+}
+
+impl AppState {
+    /// The configuration with the relevance tunables currently in effect, for a search.
+    pub fn search_config(&self) -> SearcherConfig {
+        SearcherConfig {
+            tuning: self.tuning.current(),
+            ..self.config.clone()
+        }
+    }
 }
 
 pub fn create_app(state: AppState) -> Router {
     Router::new()
         .route("/health", get(handlers::health_check))
         .route("/search", post(handlers::search))
+        .route("/search/batch", post(handlers::batch_search))
         .route("/search/ai-answer", post(handlers::ai_answer))
+        .route("/search/meetings", post(handlers::search_meetings))
         .route("/recent-searches", get(handlers::recent_searches))
         .route("/typeahead", get(handlers::typeahead))
+        .route("/quick-search", get(handlers::quick_search))
+        .route(
+            "/documents/:document_id/preview",
+            get(handlers::document_preview),
+        )
+        .route(
+            "/documents/:document_id/links",
+            get(handlers::document_links),
+        )
+        .route(
+            "/documents/:document_id/flags",
+            post(handlers::flag_document),
+        )
+        .route(
+            "/documents/:document_id/interactions",
+            post(handlers::record_interaction),
+        )
+        .route("/feed/trending", get(handlers::trending_feed))
+        .route("/feed/recent", get(handlers::recent_feed))
+        .route("/recommendations", get(handlers::recommendations))
+        .route("/people/:email", get(handlers::person_profile))
+        .route("/confluence/search", get(handlers::confluence_search))
+        .route(
+            "/confluence/content/search",
+            get(handlers::confluence_content_search),
+        )
+        .route("/bots/teams/messages", post(teams::messages))
+        .route(
+            "/helpdesk/suggested-articles",
+            post(handlers::suggested_articles),
+        )
         .route("/suggested-questions", post(handlers::suggested_questions))
+        .route("/admin/analytics/usage", get(handlers::usage_analytics))
+        .route(
+            "/admin/analytics/feature-flags/:name",
+            get(handlers::feature_flag_analytics),
+        )
+        .route("/admin/feature-flags", get(handlers::list_feature_flags))
+        .route(
+            "/admin/feature-flags/:name",
+            put(handlers::update_feature_flag).delete(handlers::delete_feature_flag),
+        )
+        .route("/admin/redaction-rules", get(handlers::get_redaction_rules))
+        .route(
+            "/admin/redaction-rules",
+            put(handlers::update_redaction_rules),
+        )
+        .route(
+            "/admin/ranking-boosts",
+            get(handlers::get_ranking_boosts).put(handlers::update_ranking_boosts),
+        )
+        .route(
+            "/admin/config",
+            get(handlers::get_search_tuning)
+                .put(handlers::update_search_tuning)
+                .delete(handlers::reset_search_tuning),
+        )
+        .route(
+            "/admin/promotions",
+            get(handlers::list_promotions).post(handlers::create_promotion),
+        )
+        .route(
+            "/admin/promotions/:promotion_id",
+            put(handlers::update_promotion).delete(handlers::delete_promotion),
+        )
+        .route(
+            "/admin/acronyms",
+            get(handlers::list_acronyms).post(handlers::create_acronym),
+        )
+        .route(
+            "/admin/acronyms/:acronym_id",
+            put(handlers::update_acronym).delete(handlers::delete_acronym),
+        )
+        .route("/admin/flags", get(handlers::list_flags))
+        .route("/admin/flags/:flag_id", put(handlers::update_flag))
+        .route(
+            "/collections",
+            get(handlers::list_collections).post(handlers::create_collection),
+        )
+        .route(
+            "/collections/:collection_id",
+            get(handlers::get_collection)
+                .put(handlers::update_collection)
+                .delete(handlers::delete_collection),
+        )
+        .route(
+            "/collections/:collection_id/documents",
+            post(handlers::add_collection_documents),
+        )
+        .route(
+            "/collections/:collection_id/documents/:document_id",
+            delete(handlers::remove_collection_document),
+        )
+        .route(
+            "/collections/:collection_id/shares",
+            post(handlers::share_collection),
+        )
+        .route(
+            "/collections/:collection_id/shares/:user_id",
+            delete(handlers::unshare_collection),
+        )
         .layer(
             ServiceBuilder::new()
                 .layer(middleware::from_fn(telemetry::middleware::trace_layer))
@@ -105,16 +254,36 @@ pub async fn run_server() -> AnyhowResult<()> {
 
     let telemetry_config = TelemetryConfig::from_env("omni-searcher");
     telemetry::init_telemetry(telemetry_config)?;
+    shared::secrets::init_from_env().await?;
 
     info!("Searcher service starting...");
 
     let config = SearcherConfig::from_env();
 
-    let db_pool = DatabasePool::from_config(&config.database)
+    // Listen while connecting, so probes can tell starting up from unhealthy
+    let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
+    info!("Searcher service listening on {}", addr);
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    let startup = Startup::default();
+    let server = startup.serve(listener, Shutdown::on_signal());
+
+    let db_pool = connect_database(&config.database)
         .await
         .map_err(|e| anyhow::anyhow!("Failed to create database pool: {}", e))?;
+    shared::db::migrations::ensure_schema_current(db_pool.pool(), &MIGRATOR, "omni-searcher")
+        .await?;
+    tokio::spawn(shared::db::metrics::report_pool_metrics(
+        db_pool.pool().clone(),
+        config.database.clone(),
+    ));
+    if let Err(e) = shared::feature_flags::init(db_pool.pool()).await {
+        error!(
+            "Failed to load feature flags, using the environment's: {}",
+            e
+        );
+    }
 
-    let redis_client = RedisClient::open(config.redis.redis_url.clone())?;
+    let redis_client = connect_redis(&config.redis.redis_url).await?;
     info!("Redis client initialized");
 
     let ai_client = AIClient::new(config.ai_service_url.clone());
@@ -137,23 +306,58 @@ pub async fn run_server() -> AnyhowResult<()> {
     title_index.start_background_refresh(300);
     info!("Typeahead index initialized");
 
+    let tuning = Arc::new(TuningStore::new(db_pool.clone(), config.tuning.clone()));
+    if let Err(e) = tuning.refresh().await {
+        error!("Failed initial searcher tuning load: {}", e);
+    }
+    tuning.start_background_refresh(tuning::REFRESH_INTERVAL_SECS);
+    info!("Searcher tuning initialized");
+
+    let redaction = Arc::new(RedactionStore::new(db_pool.clone()));
+    if let Err(e) = redaction.refresh().await {
+        error!("Failed initial search redaction rules load: {}", e);
+    }
+    redaction.start_background_refresh(redaction::REFRESH_INTERVAL_SECS);
+
+    let teams_bot = TeamsBotConfig::from_env().map(|config| Arc::new(TeamsBot::new(config)));
+    if teams_bot.is_some() {
+        info!("Teams bot enabled");
+    }
+
     let app_state = AppState {
         db_pool,
         redis_client,
         ai_client,
         config: config.clone(),
+        tuning,
+        redaction,
         content_storage,
         suggested_questions_generator,
         title_index,
+        teams_bot,
     };
 
-    let app = create_app(app_state);
+    if let Some(interval) = config.warmup_interval_seconds {
+        warmup::start(app_state.clone(), interval);
+        info!("Search cache warm-up runs every {}s", interval);
+    }
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
-    info!("Searcher service listening on {}", addr);
+    if let Some(grpc_port) = config.grpc_port {
+        let state = app_state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = grpc::serve(state, grpc_port).await {
+                error!("Searcher gRPC API stopped: {:#}", e);
+            }
+        });
+    }
 
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    let dependencies = Dependencies::new(app_state.db_pool.pool().clone())
+        .with_redis(app_state.redis_client.clone());
+    startup.ready(
+        ip_allowlist::restrict_admin_routes(create_app(app_state)),
+        dependencies,
+    );
+    server.await??;
 
     Ok(())
 }
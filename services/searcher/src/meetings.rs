@@ -0,0 +1,317 @@
+//! Meeting search over transcript sources.
+//!
+//! `POST /search/meetings` returns meetings rather than documents: transcript segments are
+//! rolled up under their meeting, and each meeting lists the transcript lines that match the
+//! query with who said them and when. Queries may carry filters, quoted when the value has
+//! spaces, e.g. `attendee:alice after:2024-05-01 speaker:"Bob Smith" pricing`:
+//!
+//! - `attendee:` keeps meetings an attendee's email contains the value.
+//! - `speaker:` keeps meetings someone whose name contains the value spoke in, and only
+//!   their lines as snippets.
+//! - `after:` and `before:` keep meetings strictly after or before a date (YYYY-MM-DD).
+//!
+//! Dates filter the search itself through the meeting `date` attribute. People filters apply
+//! to the best matching candidates, so a meeting ranked beyond them is not found.
+
+use crate::highlighting;
+use serde_json::Value;
+use shared::models::{AttributeFilter, Document};
+use shared::SourceType;
+use std::collections::HashMap;
+use time::{Date, Month};
+
+/// Sources with meeting transcripts.
+pub const MEETING_SOURCE_TYPES: [SourceType; 1] = [SourceType::Fireflies];
+/// Matches meetings are grouped from, before people filters and paging.
+pub const MAX_CANDIDATES: i64 = 100;
+pub const DEFAULT_LIMIT: usize = 10;
+pub const MAX_LIMIT: usize = 50;
+const MAX_SNIPPETS_PER_MEETING: usize = 3;
+const MAX_SNIPPET_CHARS: usize = 300;
+
+/// Filters taken out of a meeting search query.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MeetingFilters {
+    pub attendees: Vec<String>,
+    pub speakers: Vec<String>,
+    pub after: Option<Date>,
+    pub before: Option<Date>,
+}
+
+impl MeetingFilters {
+    /// The attribute filters for the dates, applied by the search itself.
+    pub fn attribute_filters(&self) -> Option<HashMap<String, AttributeFilter>> {
+        if self.after.is_none() && self.before.is_none() {
+            return None;
+        }
+        let date = |date: Option<Date>| date.map(|date| Value::String(date.to_string()));
+        Some(HashMap::from([(
+            "date".to_string(),
+            AttributeFilter::Range {
+                gte: date(self.after.and_then(Date::next_day)),
+                lte: date(self.before.and_then(Date::previous_day)),
+            },
+        )]))
+    }
+
+    /// Whether a meeting passes the attendee and speaker filters.
+    pub fn matches(&self, meeting: &Document) -> bool {
+        let contains_all = |key: &str, wanted: &[String]| {
+            let values = string_list(&meeting.attributes, key);
+            wanted.iter().all(|wanted| {
+                values
+                    .iter()
+                    .any(|value| value.to_lowercase().contains(wanted.as_str()))
+            })
+        };
+        contains_all("attendees", &self.attendees) && contains_all("speakers", &self.speakers)
+    }
+}
+
+/// The query without its filters, and the filters. Fails on a date that is not YYYY-MM-DD.
+pub fn parse_query(query: &str) -> Result<(String, MeetingFilters), String> {
+    let mut filters = MeetingFilters::default();
+    let mut terms = Vec::new();
+    for term in split_terms(query) {
+        let Some((key, value)) = term.split_once(':') else {
+            terms.push(term);
+            continue;
+        };
+        let value = value.trim_matches('"').trim();
+        if value.is_empty() {
+            terms.push(term);
+            continue;
+        }
+        match key {
+            "attendee" => filters.attendees.push(value.to_lowercase()),
+            "speaker" => filters.speakers.push(value.to_lowercase()),
+            "after" => filters.after = Some(parse_date(key, value)?),
+            "before" => filters.before = Some(parse_date(key, value)?),
+            _ => terms.push(term),
+        }
+    }
+    Ok((terms.join(" "), filters))
+}
+
+/// Whitespace separated terms, keeping quoted values such as `speaker:"Bob Smith"` whole.
+fn split_terms(query: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    let mut term = String::new();
+    let mut quoted = false;
+    for c in query.chars() {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        if c.is_whitespace() && !quoted {
+            if !term.is_empty() {
+                terms.push(std::mem::take(&mut term));
+            }
+        } else {
+            term.push(c);
+        }
+    }
+    if !term.is_empty() {
+        terms.push(term);
+    }
+    terms
+}
+
+fn parse_date(key: &str, value: &str) -> Result<Date, String> {
+    let invalid = || format!("{}: expects a date as YYYY-MM-DD, got {}", key, value);
+    let parts: Vec<&str> = value.split('-').collect();
+    let [year, month, day] = parts.as_slice() else {
+        return Err(invalid());
+    };
+    let year = year.parse::<i32>().map_err(|_| invalid())?;
+    let month = month
+        .parse::<u8>()
+        .ok()
+        .and_then(|month| Month::try_from(month).ok())
+        .ok_or_else(invalid)?;
+    let day = day.parse::<u8>().map_err(|_| invalid())?;
+    Date::from_calendar_date(year, month, day).map_err(|_| invalid())
+}
+
+/// A transcript line matching the query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptLine {
+    pub speaker: String,
+    /// Seconds into the recording
+    pub timestamp_seconds: u32,
+    pub text: String,
+}
+
+/// The lines of a transcript (`[12:34] Speaker: text`, as the meeting connectors write them)
+/// that contain a query term, by the filtered speakers when there are any, in order.
+pub fn matching_lines(content: &str, query: &str, speakers: &[String]) -> Vec<TranscriptLine> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| {
+            term.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .filter(|term| term.chars().count() > 1)
+        .collect();
+    content
+        .lines()
+        .filter_map(transcript_line)
+        .filter(|line| {
+            let speaker = line.speaker.to_lowercase();
+            speakers
+                .iter()
+                .all(|wanted| speaker.contains(wanted.as_str()))
+        })
+        .filter(|line| {
+            let text = line.text.to_lowercase();
+            terms.iter().any(|term| text.contains(term.as_str()))
+        })
+        .take(MAX_SNIPPETS_PER_MEETING)
+        .map(|line| TranscriptLine {
+            text: highlighting::truncate(&line.text, MAX_SNIPPET_CHARS),
+            ..line
+        })
+        .collect()
+}
+
+fn transcript_line(line: &str) -> Option<TranscriptLine> {
+    let rest = line.trim().strip_prefix('[')?;
+    let (timestamp, rest) = rest.split_once(']')?;
+    let mut timestamp_seconds = 0u32;
+    for part in timestamp.split(':') {
+        timestamp_seconds = timestamp_seconds * 60 + part.parse::<u32>().ok()?;
+    }
+    let (speaker, text) = rest.split_once(':')?;
+    let speaker = speaker.trim();
+    let text = text.trim();
+    if speaker.is_empty() || text.is_empty() {
+        return None;
+    }
+    Some(TranscriptLine {
+        speaker: speaker.to_string(),
+        timestamp_seconds,
+        text: text.to_string(),
+    })
+}
+
+/// The values of a list attribute, or none when it is missing or not a list of strings.
+pub fn string_list(attributes: &Value, key: &str) -> Vec<String> {
+    attributes[key]
+        .as_array()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|value| value.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The number of meetings to return for a requested limit.
+pub fn limit(requested: Option<usize>) -> usize {
+    requested.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_query() {
+        let (query, filters) =
+            parse_query(r#"pricing attendee:Alice speaker:"Bob Smith" after:2024-05-01 review"#)
+                .unwrap();
+        assert_eq!(query, "pricing review");
+        assert_eq!(filters.attendees, vec!["alice"]);
+        assert_eq!(filters.speakers, vec!["bob smith"]);
+        assert_eq!(
+            filters.after,
+            Some(Date::from_calendar_date(2024, Month::May, 1).unwrap())
+        );
+        assert_eq!(filters.before, None);
+
+        let (query, filters) = parse_query("status: http://example.com").unwrap();
+        assert_eq!(query, "status: http://example.com");
+        assert_eq!(filters, MeetingFilters::default());
+
+        assert!(parse_query("before:2024-13-01 pricing").is_err());
+        assert!(parse_query("after:yesterday pricing").is_err());
+    }
+
+    #[test]
+    fn test_date_filters_are_exclusive() {
+        let (_, filters) = parse_query("after:2024-05-01 before:2024-06-01 roadmap").unwrap();
+        let Some(AttributeFilter::Range { gte, lte }) =
+            filters.attribute_filters().unwrap().remove("date")
+        else {
+            panic!("expected a date range");
+        };
+        assert_eq!(gte, Some(json!("2024-05-02")));
+        assert_eq!(lte, Some(json!("2024-05-31")));
+
+        assert!(MeetingFilters::default().attribute_filters().is_none());
+    }
+
+    #[test]
+    fn test_matching_lines() {
+        let content = "# Pricing sync\n\n## Transcript\n\
+            [00:05] Alice: Welcome everyone\n\
+            [01:02] Bob Smith: The new pricing tiers ship in June\n\
+            [1:00:30] Alice: Pricing page copy is ready\n\
+            [01:10] Bob Smith: Let's review the roadmap\n";
+
+        let lines = matching_lines(content, "pricing", &[]);
+        assert_eq!(
+            lines,
+            vec![
+                TranscriptLine {
+                    speaker: "Bob Smith".to_string(),
+                    timestamp_seconds: 62,
+                    text: "The new pricing tiers ship in June".to_string(),
+                },
+                TranscriptLine {
+                    speaker: "Alice".to_string(),
+                    timestamp_seconds: 3630,
+                    text: "Pricing page copy is ready".to_string(),
+                },
+            ]
+        );
+
+        let by_alice = matching_lines(content, "pricing", &["alice".to_string()]);
+        assert_eq!(by_alice.len(), 1);
+        assert_eq!(by_alice[0].speaker, "Alice");
+        assert!(matching_lines(content, "budget", &[]).is_empty());
+    }
+
+    #[test]
+    fn test_people_filters() {
+        let mut meeting: Document = serde_json::from_value(json!({
+            "id": "meeting-1",
+            "source_id": "source-1",
+            "external_id": "fireflies:transcript:1",
+            "title": "Pricing sync",
+            "content_id": null,
+            "content_type": null,
+            "file_size": null,
+            "file_extension": null,
+            "url": null,
+            "metadata": {},
+            "permissions": {},
+            "attributes": {},
+            "created_at": "2024-05-02T10:00:00Z",
+            "updated_at": "2024-05-02T10:00:00Z",
+            "last_indexed_at": "2024-05-02T10:00:00Z",
+        }))
+        .unwrap();
+        meeting.attributes = json!({
+            "attendees": ["alice@example.com", "bob@example.com"],
+            "speakers": ["Alice", "Bob Smith"],
+        });
+
+        let (_, filters) = parse_query("attendee:alice speaker:bob pricing").unwrap();
+        assert!(filters.matches(&meeting));
+        let (_, filters) = parse_query("attendee:carol pricing").unwrap();
+        assert!(!filters.matches(&meeting));
+        assert!(MeetingFilters::default().matches(&meeting));
+    }
+}
@@ -1,9 +1,16 @@
 use serde::{Deserialize, Serialize};
 use shared::{
-    models::{AttributeFilter, Document, Facet},
+    db::repositories::{
+        AcronymStatus, Collaborator, Collection, CollectionInput, CollectionShare,
+        DailyIndexGrowth, DailySearchUsage, DailySyncStats, FeatureFlagVariantStats, FlagReason,
+        FlagStatus, InteractionKind, LinkKind, Person, RecommendationReason, SearchPromotionInput,
+        SourceQueryCount,
+    },
+    models::{AttributeFilter, Document, Facet, RelationshipType, SearchField},
     SourceType,
 };
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use time::OffsetDateTime;
 
 #[derive(Debug, Clone, Deserialize, Serialize, Hash, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -13,6 +20,21 @@ pub enum SearchMode {
     Hybrid,
 }
 
+/// How results that are attachments, comments or transcript segments of another document are
+/// returned.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChildResults {
+    /// As results of their own.
+    #[default]
+    Flat,
+    /// Under their parent's result when the parent matched too.
+    Nested,
+    /// Under their parent's result, which takes the place of the best ranked child when the
+    /// parent did not match.
+    RolledUp,
+}
+
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct SearchRequest {
     pub query: String,
@@ -25,6 +47,12 @@ pub struct SearchRequest {
     /// - `{"labels": ["bug", "urgent"]}` - match any of these values
     /// - `{"date": {"gte": "2024-01-01", "lte": "2024-12-31"}}` - date range
     pub attribute_filters: Option<HashMap<String, AttributeFilter>>,
+    /// Fields the full-text search matches against, title and content by default. Hybrid
+    /// searches only add semantic matches when content is searched.
+    pub fields: Option<Vec<SearchField>>,
+    /// Fields of each result to return, e.g. `id`, `title`, `url` and `highlights`, all by
+    /// default. Fields of the result's document stay nested under `document`.
+    pub result_fields: Option<Vec<String>>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
     pub mode: Option<SearchMode>,
@@ -40,6 +68,41 @@ pub struct SearchRequest {
     // Both inclusive.
     pub document_content_start_line: Option<u32>,
     pub document_content_end_line: Option<u32>,
+    /// Return how each result's score was computed, for tuning relevance.
+    pub explain: Option<bool>,
+    /// Expansions of acronyms in the query, added to the full-text query. Set by the search
+    /// engine from the acronym dictionary.
+    #[serde(skip)]
+    pub query_expansions: Vec<String>,
+    /// Feature flags in effect for the search, recorded in the query log. Set by the search
+    /// engine.
+    #[serde(skip)]
+    pub feature_flags: BTreeMap<String, bool>,
+    /// Retry with relaxed strategies when the search finds nothing, on by default.
+    pub fallback: Option<bool>,
+    /// Order the best hybrid search results again with the AI service's cross-encoder, on by
+    /// default where the searcher is configured to rerank.
+    pub rerank: Option<bool>,
+    /// How attachments, comments and transcript segments are returned, as results of their
+    /// own by default.
+    pub child_results: Option<ChildResults>,
+    /// Match terms within a few edits of the query's. Set by the spell correction fallback.
+    #[serde(skip)]
+    pub fuzzy: bool,
+    /// Limit results to a collection, by ID or name. A `collection:<name>` term in the query
+    /// does the same.
+    pub collection: Option<String>,
+    /// The collection the search is limited to. Set by the search engine from `collection`.
+    #[serde(skip)]
+    pub collection_filter: Option<Collection>,
+    /// Identifiers of the user's groups, for permission filtering. Resolved once for every
+    /// query of a batch, otherwise looked up by each query.
+    #[serde(skip)]
+    pub user_groups: Option<Vec<String>>,
+    /// Run by the cache warm-up rather than a user: cached results are replaced rather than
+    /// served, and the search is not logged.
+    #[serde(skip)]
+    pub warmup: bool,
 }
 
 impl SearchRequest {
@@ -51,6 +114,22 @@ impl SearchRequest {
         self.offset.unwrap_or(0).max(0)
     }
 
+    pub fn fields(&self) -> &[SearchField] {
+        match &self.fields {
+            Some(fields) if !fields.is_empty() => fields.as_slice(),
+            _ => &SearchField::DEFAULT[..],
+        }
+    }
+
+    /// The query matched by full-text search: the query followed by its acronym expansions.
+    pub fn fulltext_query(&self) -> String {
+        std::iter::once(&self.query)
+            .chain(&self.query_expansions)
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
     pub fn search_mode(&self) -> &SearchMode {
         self.mode.as_ref().unwrap_or(&SearchMode::Fulltext)
     }
@@ -62,6 +141,42 @@ impl SearchRequest {
     pub fn user_email(&self) -> Option<&String> {
         self.user_email.as_ref()
     }
+
+    pub fn explain(&self) -> bool {
+        self.explain.unwrap_or(false)
+    }
+
+    pub fn fallback(&self) -> bool {
+        self.fallback.unwrap_or(true)
+    }
+
+    pub fn rerank(&self) -> bool {
+        self.rerank.unwrap_or(true)
+    }
+
+    pub fn child_results(&self) -> ChildResults {
+        self.child_results.unwrap_or_default()
+    }
+
+    pub fn collection_id(&self) -> Option<&str> {
+        self.collection_filter.as_ref().map(|c| c.id.as_str())
+    }
+}
+
+/// Relaxations retried, in this order, when a search finds nothing. Each keeps the
+/// relaxations before it, and the first to find results is reported in the response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FallbackStrategy {
+    /// Drop the source, content type and attribute filters.
+    DropFilters,
+    /// Match the query in every field. Query terms are always combined with OR, so this
+    /// widens a search restricted to some fields.
+    AnyField,
+    /// Also match terms within two edits of the query's, for misspelled queries.
+    SpellCorrected,
+    /// Search by meaning alone.
+    SemanticOnly,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -73,6 +188,39 @@ pub struct SearchResponse {
     pub query: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub facets: Option<Vec<Facet>>,
+    /// The relaxation that found the results, when the query as given found nothing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<FallbackStrategy>,
+    /// Set when the search ran out of budget, e.g. under load, and returned what it found
+    /// within it.
+    #[serde(default)]
+    pub degraded: bool,
+}
+
+/// Most queries in one batch search.
+pub const MAX_BATCH_QUERIES: usize = 20;
+
+/// Body of `POST /search/batch`, running several searches for the user at once. The user's
+/// permissions are resolved once and apply to every query, overriding any user set on them.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BatchSearchRequest {
+    pub user_id: String,
+    pub queries: Vec<SearchRequest>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchSearchResponse {
+    /// One result per query, in the order the queries were given.
+    pub results: Vec<BatchSearchResult>,
+}
+
+/// The response to one query of a batch, or why it failed. A failed query does not fail the
+/// others.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BatchSearchResult {
+    Success(SearchResponse),
+    Failure { error: String },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -83,6 +231,119 @@ pub struct SearchResult {
     pub match_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
+    /// Placed above the ranked results by a search promotion.
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ranking: Option<RankingExplanation>,
+    /// Other copies of the same content, e.g. the same file in another source, collapsed
+    /// into this result.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternates: Vec<AlternateDocument>,
+    /// Where in the document the best matching chunk is, for semantic matches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor: Option<ResultAnchor>,
+    /// Attachments, comments and transcript segments of the document that matched, when the
+    /// request nests or rolls them up.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<ChildResult>,
+}
+
+/// A result listed under the result of its parent document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChildResult {
+    pub relationship: RelationshipType,
+    #[serde(flatten)]
+    pub result: SearchResult,
+}
+
+/// The location of a result's best matching chunk, so that clients can link to the section
+/// (or, for transcripts, the moment) the match is in.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResultAnchor {
+    pub chunk_index: i32,
+    /// Character offsets of the chunk in the document content.
+    pub start_offset: i32,
+    pub end_offset: i32,
+    /// The closest heading before the chunk.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading_level: Option<i16>,
+    /// Seconds into the recording of the closest transcript line before the chunk.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp_seconds: Option<i32>,
+}
+
+/// A duplicate of a search result's document, listed under the result instead of as a result
+/// of its own.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlternateDocument {
+    pub id: String,
+    pub source_id: String,
+    pub title: String,
+    pub url: Option<String>,
+}
+
+/// How a search result's score was computed, returned when the request sets `explain`.
+///
+/// In hybrid mode the score is the sum of the weighted full-text and semantic scores,
+/// multiplied by the source and content type boosts. Cross-encoder reranking and search
+/// promotions applied afterwards show up as `rerank_delta`. Results the cross-encoder reranked
+/// carry its score in `cross_encoder_score`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RankingExplanation {
+    /// Raw `ts_rank` of the full-text match, if the full-text search found the document.
+    pub fts_rank: Option<f32>,
+    /// Cosine similarity of each matching chunk, best first. The best chunk scores the
+    /// document.
+    pub chunk_similarities: Vec<ChunkSimilarity>,
+    /// Weight of the full-text score in weighted hybrid fusion.
+    pub fts_weight: Option<f32>,
+    /// Weight of the semantic score in weighted hybrid fusion.
+    pub semantic_weight: Option<f32>,
+    /// Full-text contribution to the score, after weighting or rank fusion.
+    pub fulltext_score: Option<f32>,
+    /// Semantic contribution to the score, after weighting or rank fusion.
+    pub semantic_score: Option<f32>,
+    pub source_boost: f32,
+    pub content_type_boost: f32,
+    /// Demotion for open user flags on the document.
+    pub flag_boost: f32,
+    /// Boost for the number of documents linking to the document.
+    pub link_boost: f32,
+    /// Boost for the document's community votes.
+    pub vote_boost: f32,
+    /// Score change from cross-encoder reranking and search promotions after ranking.
+    pub rerank_delta: f32,
+    /// Relevance to the query according to the cross-encoder, if the result was reranked.
+    pub cross_encoder_score: Option<f32>,
+}
+
+impl Default for RankingExplanation {
+    fn default() -> Self {
+        Self {
+            fts_rank: None,
+            chunk_similarities: Vec::new(),
+            fts_weight: None,
+            semantic_weight: None,
+            fulltext_score: None,
+            semantic_score: None,
+            source_boost: 1.0,
+            content_type_boost: 1.0,
+            flag_boost: 1.0,
+            link_boost: 1.0,
+            vote_boost: 1.0,
+            rerank_delta: 0.0,
+            cross_encoder_score: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkSimilarity {
+    pub chunk_index: i32,
+    pub similarity: f32,
 }
 
 #[derive(Debug, Deserialize)]
@@ -123,6 +384,221 @@ impl TypeaheadQuery {
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct QuickSearchQuery {
+    pub q: String,
+    pub user_email: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CollectionsQuery {
+    pub user_id: String,
+}
+
+/// Body of `POST /collections` and `PUT /collections/:collection_id`. `user_id` is the user
+/// making the change, who owns the collection.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CollectionRequest {
+    pub user_id: String,
+    #[serde(flatten)]
+    pub collection: CollectionInput,
+}
+
+/// Body of `POST /collections/:collection_id/documents`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AddCollectionDocumentsRequest {
+    pub user_id: String,
+    pub document_ids: Vec<String>,
+}
+
+/// Body of `POST /collections/:collection_id/shares`, sharing the collection with the user
+/// with `email`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ShareCollectionRequest {
+    pub user_id: String,
+    pub email: String,
+}
+
+/// A collection with the documents in it the requesting user can see.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CollectionDetail {
+    #[serde(flatten)]
+    pub collection: Collection,
+    pub documents: Vec<CollectionDocument>,
+    pub shares: Vec<CollectionShare>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CollectionDocument {
+    pub id: String,
+    pub source_id: String,
+    pub title: String,
+    pub url: Option<String>,
+    pub content_type: Option<String>,
+}
+
+/// Most characters of a flag's comment.
+pub const MAX_FLAG_COMMENT_LEN: usize = 2000;
+
+/// Body of `POST /documents/:document_id/flags`, flagging the document for the user.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FlagDocumentRequest {
+    pub user_id: String,
+    pub reason: FlagReason,
+    pub comment: Option<String>,
+}
+
+impl FlagDocumentRequest {
+    pub fn comment(&self) -> Option<&str> {
+        self.comment
+            .as_deref()
+            .map(str::trim)
+            .filter(|comment| !comment.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListFlagsQuery {
+    pub status: Option<FlagStatus>,
+    /// Only flags on documents of sources this user owns
+    pub owner_id: Option<String>,
+}
+
+/// Body of `PUT /admin/flags/:flag_id`. `user_id` is the reviewer.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateFlagRequest {
+    pub status: FlagStatus,
+    pub user_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DocumentPreviewQuery {
+    pub user_email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DocumentLinksQuery {
+    pub user_email: String,
+}
+
+/// Documents a document links to and documents linking to it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DocumentLinksResponse {
+    pub outbound: Vec<DocumentLinkResult>,
+    pub inbound: Vec<DocumentLinkResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DocumentLinkResult {
+    pub document_id: String,
+    pub source_id: String,
+    pub title: String,
+    pub url: Option<String>,
+    pub kind: LinkKind,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PersonProfileQuery {
+    pub user_email: String,
+}
+
+/// A person's profile with the documents they were most active on and the people they work
+/// with most, limited to documents the user can see.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PersonProfileResponse {
+    pub person: Person,
+    pub top_documents: Vec<PersonDocumentResult>,
+    pub collaborators: Vec<Collaborator>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PersonDocumentResult {
+    pub document_id: String,
+    pub source_id: String,
+    pub title: String,
+    pub url: Option<String>,
+    /// What the person did on the document: `author`, `attendee` or `correspondent`.
+    pub roles: Vec<String>,
+}
+
+/// Body of `POST /documents/:document_id/interactions`.
+#[derive(Debug, Deserialize)]
+pub struct RecordInteractionRequest {
+    pub user_id: String,
+    pub kind: InteractionKind,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FeedQuery {
+    pub user_email: String,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FeedResponse {
+    pub documents: Vec<FeedDocument>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FeedDocument {
+    pub document_id: String,
+    pub source_id: String,
+    pub title: String,
+    pub url: Option<String>,
+    pub content_type: Option<String>,
+    #[serde(with = "time::serde::iso8601")]
+    pub updated_at: OffsetDateTime,
+}
+
+impl From<Document> for FeedDocument {
+    fn from(document: Document) -> Self {
+        Self {
+            document_id: document.id,
+            source_id: document.source_id,
+            title: document.title,
+            url: document.url,
+            content_type: document.content_type,
+            updated_at: document.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecommendationsQuery {
+    pub user_id: String,
+    pub limit: Option<usize>,
+}
+
+/// Documents recommended to a user, as of the last time recommendations were computed.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecommendationsResponse {
+    pub documents: Vec<RecommendedDocument>,
+    #[serde(with = "time::serde::iso8601::option")]
+    pub computed_at: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecommendedDocument {
+    #[serde(flatten)]
+    pub document: FeedDocument,
+    pub reason: RecommendationReason,
+    pub score: f32,
+}
+
+/// Top results of a quick search, trimmed to what a browser extension popup shows.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuickSearchResponse {
+    pub results: Vec<QuickSearchResult>,
+    pub query: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuickSearchResult {
+    pub document_id: String,
+    pub title: String,
+    pub url: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct TypeaheadResponse {
     pub results: Vec<TypeaheadResult>,
@@ -137,6 +613,194 @@ pub struct TypeaheadResult {
     pub source_id: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct UsageAnalyticsQuery {
+    pub days: Option<i64>,
+}
+
+impl UsageAnalyticsQuery {
+    pub fn days(&self) -> i64 {
+        self.days.unwrap_or(30).clamp(1, 365)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct UsageAnalyticsResponse {
+    pub days: i64,
+    pub total_queries: i64,
+    pub zero_result_rate: f64,
+    pub daily_usage: Vec<DailySearchUsage>,
+    pub queries_per_source_type: Vec<SourceQueryCount>,
+    pub index_growth: Vec<DailyIndexGrowth>,
+    pub sync_stats: Vec<DailySyncStats>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeatureFlagAnalyticsResponse {
+    pub flag: String,
+    pub days: i64,
+    pub variants: Vec<FeatureFlagVariantStats>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreatePromotionRequest {
+    #[serde(flatten)]
+    pub promotion: SearchPromotionInput,
+    pub created_by: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListAcronymsQuery {
+    pub status: Option<AcronymStatus>,
+}
+
+/// Query of the Confluence-compatible search, `GET /confluence/search` and
+/// `GET /confluence/content/search`.
+#[derive(Debug, Deserialize)]
+pub struct ConfluenceSearchQuery {
+    pub cql: String,
+    pub start: Option<i64>,
+    pub limit: Option<i64>,
+    pub user_id: String,
+}
+
+/// Links Confluence puts on every search response, relative to which content links resolve.
+/// Omni's content links are absolute, so both are empty.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ConfluenceLinks {
+    pub base: String,
+    pub context: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConfluenceContentLinks {
+    pub webui: String,
+}
+
+/// A result as Confluence describes content. Every document is reported as a current page.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConfluenceContent {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub content_type: String,
+    pub status: String,
+    pub title: String,
+    #[serde(rename = "_links")]
+    pub links: ConfluenceContentLinks,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfluenceSearchResult {
+    pub content: ConfluenceContent,
+    pub title: String,
+    pub excerpt: String,
+    pub url: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub last_modified: OffsetDateTime,
+    pub entity_type: String,
+}
+
+/// Response of `GET /confluence/search`, in the shape of Confluence's `/rest/api/search`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfluenceSearchResponse {
+    pub results: Vec<ConfluenceSearchResult>,
+    pub start: i64,
+    pub limit: i64,
+    pub size: usize,
+    pub total_size: i64,
+    pub cql_query: String,
+    pub search_duration: u64,
+    #[serde(rename = "_links")]
+    pub links: ConfluenceLinks,
+}
+
+/// Response of `GET /confluence/content/search`, in the shape of Confluence's
+/// `/rest/api/content/search`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConfluenceContentSearchResponse {
+    pub results: Vec<ConfluenceContent>,
+    pub start: i64,
+    pub limit: i64,
+    pub size: usize,
+    #[serde(rename = "_links")]
+    pub links: ConfluenceLinks,
+}
+
+/// Body of `POST /helpdesk/suggested-articles`, a help desk ticket to suggest documents for.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SuggestedArticlesRequest {
+    pub subject: String,
+    pub description: Option<String>,
+    /// The agent viewing the ticket. Without one, only public documents are suggested.
+    pub user_email: Option<String>,
+    pub limit: Option<usize>,
+    /// Leave out suggestions less confident than this, between 0 and 1.
+    pub min_confidence: Option<f32>,
+}
+
+/// A suggested document, with the fields help desk widgets show articles with.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SuggestedArticle {
+    pub id: String,
+    pub title: String,
+    pub html_url: Option<String>,
+    pub snippet: String,
+    /// How well the document matches the ticket, between 0 and 1.
+    pub confidence: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SuggestedArticlesResponse {
+    /// The most confident first.
+    pub articles: Vec<SuggestedArticle>,
+}
+
+/// Body of `POST /search/meetings`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MeetingSearchRequest {
+    /// Terms to match, with optional `attendee:`, `speaker:`, `after:` and `before:` filters.
+    pub query: String,
+    pub user_email: Option<String>,
+    pub user_id: Option<String>,
+    /// Hybrid by default.
+    pub mode: Option<SearchMode>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MeetingSearchResponse {
+    /// The best matching first.
+    pub meetings: Vec<MeetingResult>,
+    pub query_time_ms: u64,
+    pub has_more: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MeetingResult {
+    pub id: String,
+    pub source_id: String,
+    pub title: String,
+    pub url: Option<String>,
+    /// Day of the meeting, YYYY-MM-DD.
+    pub date: Option<String>,
+    pub attendees: Vec<String>,
+    pub speakers: Vec<String>,
+    pub score: f32,
+    /// Transcript lines matching the query, in the order they were said.
+    pub snippets: Vec<TranscriptSnippet>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TranscriptSnippet {
+    pub speaker: String,
+    /// Seconds into the recording.
+    pub timestamp_seconds: u32,
+    pub text: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -216,4 +880,11 @@ mod tests {
         let mode: SearchMode = serde_json::from_str("\"fulltext\"").unwrap();
         assert!(matches!(mode, SearchMode::Fulltext));
     }
+
+    #[test]
+    fn test_usage_analytics_days_bounds() {
+        assert_eq!(UsageAnalyticsQuery { days: None }.days(), 30);
+        assert_eq!(UsageAnalyticsQuery { days: Some(0) }.days(), 1);
+        assert_eq!(UsageAnalyticsQuery { days: Some(1000) }.days(), 365);
+    }
 }
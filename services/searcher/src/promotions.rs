@@ -0,0 +1,395 @@
+//! Promoted results ("best bets") for search queries.
+//!
+//! Admins attach documents to query patterns. When a query matches, pinned documents are
+//! shown above the ranked results with the `pinned` flag set, and boosted documents have their
+//! score multiplied where the ranking found them. Promotions are stored in the
+//! `search_promotions` table and applied after results are cached, so changes apply to the
+//! next query without a restart.
+
+use crate::models::SearchResult;
+use anyhow::Result;
+use regex::{Regex, RegexBuilder};
+use shared::db::repositories::{
+    PromotionAction, PromotionMatchType, SearchPromotion, SearchPromotionInput,
+    SearchPromotionRepository,
+};
+use shared::DatabasePool;
+use std::collections::{HashMap, HashSet};
+use tracing::error;
+
+/// Lowercase a query or pattern and collapse its whitespace, so promotions match however the
+/// query was typed.
+pub fn normalize_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Check a promotion before it is stored.
+pub fn validate(input: &SearchPromotionInput) -> std::result::Result<(), String> {
+    if input.query_pattern.trim().is_empty() {
+        return Err("Promotion query_pattern cannot be empty".to_string());
+    }
+    if input.document_id.trim().is_empty() {
+        return Err("Promotion document_id cannot be empty".to_string());
+    }
+    if input.match_type == PromotionMatchType::Regex {
+        compile_pattern(&input.query_pattern)
+            .map_err(|e| format!("Invalid query_pattern regex: {}", e))?;
+    }
+    if input.action == PromotionAction::Boost && !(input.boost.is_finite() && input.boost > 0.0) {
+        return Err(format!(
+            "Promotion boost must be a positive number, got {}",
+            input.boost
+        ));
+    }
+    if input.position < 0 {
+        return Err("Promotion position cannot be negative".to_string());
+    }
+    Ok(())
+}
+
+fn compile_pattern(pattern: &str) -> std::result::Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern).case_insensitive(true).build()
+}
+
+struct CompiledPromotion {
+    promotion: SearchPromotion,
+    normalized_pattern: String,
+    regex: Option<Regex>,
+}
+
+impl CompiledPromotion {
+    fn matches(&self, normalized_query: &str) -> bool {
+        match self.promotion.match_type {
+            PromotionMatchType::Exact => normalized_query == self.normalized_pattern,
+            PromotionMatchType::Prefix => normalized_query.starts_with(&self.normalized_pattern),
+            PromotionMatchType::Contains => normalized_query.contains(&self.normalized_pattern),
+            PromotionMatchType::Regex => self
+                .regex
+                .as_ref()
+                .is_some_and(|regex| regex.is_match(normalized_query)),
+        }
+    }
+}
+
+/// The active promotions, ready to match against queries.
+#[derive(Default)]
+pub struct Promoter {
+    promotions: Vec<CompiledPromotion>,
+}
+
+impl Promoter {
+    /// Compile promotions, in the order pins should appear. Promotions were validated when
+    /// saved, so a regex that no longer compiles is logged and skipped.
+    pub fn new(promotions: Vec<SearchPromotion>) -> Self {
+        let promotions = promotions
+            .into_iter()
+            .filter_map(|promotion| {
+                let regex = match promotion.match_type {
+                    PromotionMatchType::Regex => match compile_pattern(&promotion.query_pattern) {
+                        Ok(regex) => Some(regex),
+                        Err(e) => {
+                            error!("Skipping search promotion {}: {}", promotion.id, e);
+                            return None;
+                        }
+                    },
+                    _ => None,
+                };
+                Some(CompiledPromotion {
+                    normalized_pattern: normalize_query(&promotion.query_pattern),
+                    promotion,
+                    regex,
+                })
+            })
+            .collect();
+        Self { promotions }
+    }
+
+    pub async fn load(db_pool: &DatabasePool) -> Result<Self> {
+        let promotions = SearchPromotionRepository::new(db_pool.pool())
+            .find_active()
+            .await?;
+        Ok(Self::new(promotions))
+    }
+
+    /// Promotions matching a query, pins in display order.
+    pub fn matching(&self, query: &str) -> Vec<&SearchPromotion> {
+        let normalized_query = normalize_query(query);
+        self.promotions
+            .iter()
+            .filter(|compiled| compiled.matches(&normalized_query))
+            .map(|compiled| &compiled.promotion)
+            .collect()
+    }
+}
+
+/// Documents pinned by the promotions, in display order and without duplicates.
+pub fn pinned_document_ids(promotions: &[&SearchPromotion]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    promotions
+        .iter()
+        .filter(|promotion| promotion.action == PromotionAction::Pin)
+        .filter(|promotion| seen.insert(promotion.document_id.as_str()))
+        .map(|promotion| promotion.document_id.clone())
+        .collect()
+}
+
+/// Apply matching promotions to ranked results. Boosts rescale the scores of results they
+/// name and re-rank. On the first page, pinned documents move above the ranked results, taken
+/// from the results or from `unranked` for documents the ranking did not find; on later pages
+/// they are dropped, having been shown on the first.
+pub fn apply(
+    promotions: &[&SearchPromotion],
+    mut results: Vec<SearchResult>,
+    mut unranked: HashMap<String, SearchResult>,
+    first_page: bool,
+) -> Vec<SearchResult> {
+    let mut boosted = false;
+    for promotion in promotions
+        .iter()
+        .filter(|promotion| promotion.action == PromotionAction::Boost)
+    {
+        for result in results
+            .iter_mut()
+            .filter(|result| result.document.id == promotion.document_id)
+        {
+            let boosted_score = result.score * promotion.boost;
+            if let Some(ranking) = result.ranking.as_mut() {
+                ranking.rerank_delta += boosted_score - result.score;
+            }
+            result.score = boosted_score;
+            boosted = true;
+        }
+    }
+    if boosted {
+        results.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+    }
+
+    let pinned_ids = pinned_document_ids(promotions);
+    if pinned_ids.is_empty() {
+        return results;
+    }
+    if !first_page {
+        results.retain(|result| !pinned_ids.contains(&result.document.id));
+        return results;
+    }
+
+    let mut promoted = Vec::with_capacity(pinned_ids.len() + results.len());
+    for document_id in &pinned_ids {
+        let result = match results
+            .iter()
+            .position(|result| &result.document.id == document_id)
+        {
+            Some(index) => Some(results.remove(index)),
+            None => unranked.remove(document_id),
+        };
+        if let Some(mut result) = result {
+            result.pinned = true;
+            promoted.push(result);
+        }
+    }
+    promoted.extend(results);
+    promoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RankingExplanation;
+    use serde_json::json;
+    use shared::models::Document;
+    use time::OffsetDateTime;
+
+    fn promotion(
+        pattern: &str,
+        match_type: PromotionMatchType,
+        document_id: &str,
+        action: PromotionAction,
+    ) -> SearchPromotion {
+        let now = OffsetDateTime::now_utc();
+        SearchPromotion {
+            id: format!("promo-{}", document_id),
+            query_pattern: pattern.to_string(),
+            match_type,
+            document_id: document_id.to_string(),
+            action,
+            boost: 3.0,
+            position: 0,
+            is_active: true,
+            created_by: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn result(id: &str, score: f32) -> SearchResult {
+        let now = OffsetDateTime::now_utc();
+        SearchResult {
+            document: Document {
+                id: id.to_string(),
+                source_id: "source".to_string(),
+                external_id: id.to_string(),
+                title: id.to_string(),
+                content_id: None,
+                content_type: None,
+                file_size: None,
+                file_extension: None,
+                url: None,
+                metadata: json!({}),
+                permissions: json!({}),
+                attributes: json!({}),
+                created_at: now,
+                updated_at: now,
+                last_indexed_at: now,
+                preview: None,
+            },
+            score,
+            highlights: vec![],
+            match_type: "fulltext".to_string(),
+            content: None,
+            pinned: false,
+            ranking: None,
+            alternates: vec![],
+            anchor: None,
+            children: vec![],
+        }
+    }
+
+    fn ids(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.document.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_matching_by_type() {
+        let promoter = Promoter::new(vec![
+            promotion(
+                "Expense Policy",
+                PromotionMatchType::Exact,
+                "a",
+                PromotionAction::Pin,
+            ),
+            promotion("vpn", PromotionMatchType::Prefix, "b", PromotionAction::Pin),
+            promotion(
+                "benefits",
+                PromotionMatchType::Contains,
+                "c",
+                PromotionAction::Pin,
+            ),
+            promotion(
+                r"^q[1-4] (okrs|goals)$",
+                PromotionMatchType::Regex,
+                "d",
+                PromotionAction::Pin,
+            ),
+        ]);
+        let matched = |query: &str| -> Vec<String> {
+            promoter
+                .matching(query)
+                .iter()
+                .map(|p| p.document_id.clone())
+                .collect()
+        };
+
+        assert_eq!(matched("  expense   POLICY "), vec!["a"]);
+        assert!(matched("expense policy 2024").is_empty());
+        assert_eq!(matched("VPN setup"), vec!["b"]);
+        assert_eq!(matched("health benefits enrollment"), vec!["c"]);
+        assert_eq!(matched("Q3 OKRs"), vec!["d"]);
+        assert!(matched("q5 okrs").is_empty());
+    }
+
+    #[test]
+    fn test_invalid_regex_skipped() {
+        let promoter = Promoter::new(vec![promotion(
+            "(",
+            PromotionMatchType::Regex,
+            "a",
+            PromotionAction::Pin,
+        )]);
+        assert!(promoter.matching("(").is_empty());
+    }
+
+    #[test]
+    fn test_pins_move_above_ranked_results() {
+        let pin_ranked = promotion("q", PromotionMatchType::Exact, "c", PromotionAction::Pin);
+        let pin_unranked = promotion("q", PromotionMatchType::Exact, "x", PromotionAction::Pin);
+        let pin_hidden = promotion("q", PromotionMatchType::Exact, "y", PromotionAction::Pin);
+        let promotions = vec![&pin_unranked, &pin_hidden, &pin_ranked];
+        let results = vec![result("a", 0.9), result("b", 0.8), result("c", 0.7)];
+        let unranked = HashMap::from([("x".to_string(), result("x", 0.0))]);
+
+        let promoted = apply(&promotions, results.clone(), unranked.clone(), true);
+        assert_eq!(ids(&promoted), vec!["x", "c", "a", "b"]);
+        assert_eq!(
+            promoted.iter().map(|r| r.pinned).collect::<Vec<_>>(),
+            vec![true, true, false, false]
+        );
+
+        let later_page = apply(&promotions, results, unranked, false);
+        assert_eq!(ids(&later_page), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_boost_reranks_found_documents() {
+        let boost = promotion("q", PromotionMatchType::Exact, "c", PromotionAction::Boost);
+        let boost_missing = promotion("q", PromotionMatchType::Exact, "z", PromotionAction::Boost);
+        let mut explained = result("c", 0.4);
+        explained.ranking = Some(RankingExplanation::default());
+        let results = vec![result("a", 0.9), result("b", 0.5), explained];
+
+        let promoted = apply(&[&boost, &boost_missing], results, HashMap::new(), true);
+        assert_eq!(ids(&promoted), vec!["c", "a", "b"]);
+        assert!((promoted[0].score - 1.2).abs() < 1e-6);
+        let rerank_delta = promoted[0].ranking.as_ref().unwrap().rerank_delta;
+        assert!((rerank_delta - 0.8).abs() < 1e-6);
+        assert!(promoted.iter().all(|r| !r.pinned));
+    }
+
+    #[test]
+    fn test_validate() {
+        let input = |pattern: &str, match_type, action, boost| SearchPromotionInput {
+            query_pattern: pattern.to_string(),
+            match_type,
+            document_id: "doc".to_string(),
+            action,
+            boost,
+            position: 0,
+            is_active: true,
+        };
+        assert!(validate(&input(
+            "handbook",
+            PromotionMatchType::Exact,
+            PromotionAction::Pin,
+            1.0
+        ))
+        .is_ok());
+        assert!(validate(&input(
+            " ",
+            PromotionMatchType::Exact,
+            PromotionAction::Pin,
+            1.0
+        ))
+        .is_err());
+        assert!(validate(&input(
+            "(",
+            PromotionMatchType::Regex,
+            PromotionAction::Pin,
+            1.0
+        ))
+        .is_err());
+        assert!(validate(&input(
+            "handbook",
+            PromotionMatchType::Exact,
+            PromotionAction::Boost,
+            0.0
+        ))
+        .is_err());
+    }
+}
@@ -0,0 +1,169 @@
+//! Query embeddings cached in Redis.
+//!
+//! Embedding a query takes a round trip to the AI service, which dominates semantic search
+//! latency. Embeddings are cached by the normalized query text and the model that produced
+//! them, so repeated queries, and queries that only differ in case or spacing, skip it.
+//!
+//! Which model is current is learned from the AI service's responses and remembered for
+//! [`MODEL_TTL_SECS`], so that after an embedding model cutover searches stop using the
+//! previous model's cached embeddings within that time.
+
+use crate::search::{embed_query, QueryEmbedding};
+use anyhow::Result;
+use redis::{AsyncCommands, Client as RedisClient};
+use sha2::{Digest, Sha256};
+use shared::clients::ai::EmbeddingModelRole;
+use shared::{cache, AIClient};
+use tracing::debug;
+
+/// Seconds a query's embedding is cached for.
+pub const EMBEDDING_TTL_SECS: u64 = 24 * 3600;
+
+/// Seconds the model currently serving a role is remembered for.
+pub const MODEL_TTL_SECS: u64 = 60;
+
+/// The form of a query that is embedded: lowercase, with runs of whitespace collapsed.
+pub fn normalize(query: &str) -> String {
+    query
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn model_key(role: EmbeddingModelRole) -> String {
+    cache::keys().cache(
+        "query_embedding_model",
+        format!("{:?}", role).to_lowercase(),
+    )
+}
+
+fn embedding_key(model_name: &str, normalized_query: &str) -> String {
+    cache::keys().cache(
+        "query_embedding",
+        format_args!(
+            "{}:{:x}",
+            model_name,
+            Sha256::digest(normalized_query.as_bytes())
+        ),
+    )
+}
+
+fn encode(vector: &[f32]) -> Vec<u8> {
+    vector
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
+
+fn decode(bytes: &[u8]) -> Option<Vec<f32>> {
+    if bytes.is_empty() || !bytes.len().is_multiple_of(4) {
+        return None;
+    }
+    Some(
+        bytes
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect(),
+    )
+}
+
+pub struct QueryEmbeddingCache {
+    ai_client: AIClient,
+    redis_client: RedisClient,
+}
+
+impl QueryEmbeddingCache {
+    pub fn new(ai_client: AIClient, redis_client: RedisClient) -> Self {
+        Self {
+            ai_client,
+            redis_client,
+        }
+    }
+
+    /// The query's embedding with the model serving `role`. With `refresh`, the query is
+    /// embedded again and the cached embedding replaced.
+    pub(crate) async fn embed(
+        &self,
+        query: &str,
+        role: EmbeddingModelRole,
+        refresh: bool,
+    ) -> Result<QueryEmbedding> {
+        let normalized = normalize(query);
+        let mut conn = self
+            .redis_client
+            .get_multiplexed_async_connection()
+            .await
+            .ok();
+
+        if let (Some(conn), false) = (conn.as_mut(), refresh) {
+            if let Some(embedding) = cached(conn, role, &normalized).await {
+                debug!("Query embedding cache hit for '{}'", normalized);
+                return Ok(embedding);
+            }
+        }
+
+        let embedding = embed_query(&self.ai_client, &normalized, role).await?;
+        if let Some(conn) = conn.as_mut() {
+            let key = embedding_key(&embedding.model_name, &normalized);
+            let _: Result<(), _> = conn
+                .set_ex(&key, encode(&embedding.vector), EMBEDDING_TTL_SECS)
+                .await;
+            let _: Result<(), _> = conn
+                .set_ex(model_key(role), &embedding.model_name, MODEL_TTL_SECS)
+                .await;
+        }
+        Ok(embedding)
+    }
+}
+
+async fn cached(
+    conn: &mut redis::aio::MultiplexedConnection,
+    role: EmbeddingModelRole,
+    normalized: &str,
+) -> Option<QueryEmbedding> {
+    let model_name: Option<String> = conn.get(model_key(role)).await.ok()?;
+    let model_name = model_name?;
+    let bytes: Option<Vec<u8>> = conn
+        .get(embedding_key(&model_name, normalized))
+        .await
+        .ok()?;
+    Some(QueryEmbedding {
+        vector: decode(&bytes?)?,
+        model_name,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalize("  Quarterly   Plan\t2024 "),
+            "quarterly plan 2024"
+        );
+        assert_eq!(normalize("quarterly plan 2024"), "quarterly plan 2024");
+    }
+
+    #[test]
+    fn test_keys() {
+        assert_eq!(
+            model_key(EmbeddingModelRole::Active),
+            "omni:cache:query_embedding_model:active"
+        );
+        assert_ne!(
+            embedding_key("model-a", "quarterly plan"),
+            embedding_key("model-b", "quarterly plan")
+        );
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let vector = vec![0.25, -1.5, 3.0e-7, f32::MAX];
+        assert_eq!(decode(&encode(&vector)), Some(vector));
+        assert_eq!(decode(&[]), None);
+        assert_eq!(decode(&[1, 2, 3]), None);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::models::{SearchMode, SearchRequest};
+use rand::Rng;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use shared::db::repositories::{QueryLogRepository, SearchQueryLog};
+use shared::{DatabasePool, SearcherConfig};
+use time::OffsetDateTime;
+use tracing::{debug, error, warn};
+
+/// Time spent in each stage of a search, in milliseconds.
+/// Stages that did not run for a given search mode are left as `None`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LatencyBreakdown {
+    pub cache_ms: u64,
+    pub fts_ms: Option<u64>,
+    pub vector_ms: Option<u64>,
+    pub rerank_ms: Option<u64>,
+}
+
+/// Records searches to the `search_query_logs` table.
+///
+/// Only a sampled fraction of searches is persisted, except for searches exceeding the
+/// slow-query threshold, which are always persisted and additionally emitted as a warning
+/// with the raw query text and filters so they can be reproduced.
+#[derive(Clone)]
+pub struct QueryLogger {
+    db_pool: DatabasePool,
+    sample_rate: f64,
+    slow_query_threshold_ms: u64,
+}
+
+impl QueryLogger {
+    pub fn new(db_pool: DatabasePool, config: &SearcherConfig) -> Self {
+        Self {
+            db_pool,
+            sample_rate: config.query_log_sample_rate,
+            slow_query_threshold_ms: config.slow_query_threshold_ms,
+        }
+    }
+
+    pub fn record(
+        &self,
+        request: &SearchRequest,
+        result_count: usize,
+        total_ms: u64,
+        latency: LatencyBreakdown,
+        cache_hit: bool,
+    ) {
+        let is_slow = total_ms >= self.slow_query_threshold_ms;
+        let filters = request_filters(request);
+
+        if is_slow {
+            warn!(
+                query = %request.query,
+                mode = search_mode_name(request.search_mode()),
+                filters = %filters,
+                user_id = ?request.user_id,
+                result_count,
+                total_ms,
+                cache_ms = latency.cache_ms,
+                fts_ms = ?latency.fts_ms,
+                vector_ms = ?latency.vector_ms,
+                rerank_ms = ?latency.rerank_ms,
+                cache_hit,
+                "Slow search query ({}ms >= {}ms threshold)",
+                total_ms,
+                self.slow_query_threshold_ms
+            );
+        } else if !is_sampled(self.sample_rate) {
+            return;
+        }
+
+        let log = SearchQueryLog {
+            id: String::new(),
+            query_hash: hash_query(&request.query),
+            search_mode: search_mode_name(request.search_mode()).to_string(),
+            filters,
+            user_id: request.user_id.clone(),
+            result_count: result_count as i32,
+            total_ms: total_ms as i32,
+            cache_ms: latency.cache_ms as i32,
+            fts_ms: latency.fts_ms.map(|ms| ms as i32),
+            vector_ms: latency.vector_ms.map(|ms| ms as i32),
+            rerank_ms: latency.rerank_ms.map(|ms| ms as i32),
+            cache_hit,
+            is_slow,
+            sample_rate: if is_slow {
+                1.0
+            } else {
+                self.sample_rate.min(1.0) as f32
+            },
+            feature_flags: json!(request.feature_flags),
+            created_at: OffsetDateTime::now_utc(),
+        };
+
+        // Persist off the request path, a failed write must never fail the search
+        let repo = QueryLogRepository::new(self.db_pool.pool());
+        tokio::spawn(async move {
+            match repo.create(log).await {
+                Ok(log) => debug!("Recorded search query log {}", log.id),
+                Err(e) => error!("Failed to record search query log: {}", e),
+            }
+        });
+    }
+}
+
+/// Stable hash of the normalized query text, so identical queries can be grouped
+/// without storing what users searched for.
+pub fn hash_query(query: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(query.trim().to_lowercase().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+pub(crate) fn is_sampled(sample_rate: f64) -> bool {
+    if sample_rate >= 1.0 {
+        return true;
+    }
+    if sample_rate <= 0.0 {
+        return false;
+    }
+    rand::thread_rng().gen_bool(sample_rate)
+}
+
+fn search_mode_name(mode: &SearchMode) -> &'static str {
+    match mode {
+        SearchMode::Fulltext => "fulltext",
+        SearchMode::Semantic => "semantic",
+        SearchMode::Hybrid => "hybrid",
+    }
+}
+
+fn request_filters(request: &SearchRequest) -> Value {
+    json!({
+        "source_types": request.source_types,
+        "content_types": request.content_types,
+        "attribute_filters": request.attribute_filters,
+        "document_id": request.document_id,
+        "limit": request.limit(),
+        "offset": request.offset(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_query_normalizes_case_and_whitespace() {
+        assert_eq!(
+            hash_query("Quarterly Report"),
+            hash_query("  quarterly report ")
+        );
+        assert_ne!(hash_query("quarterly report"), hash_query("annual report"));
+        assert_eq!(hash_query("x").len(), 64);
+    }
+
+    #[test]
+    fn test_sampling_bounds() {
+        assert!(is_sampled(1.0));
+        assert!(!is_sampled(0.0));
+    }
+
+    #[test]
+    fn test_request_filters_uses_effective_paging() {
+        let request = SearchRequest {
+            query: "test".to_string(),
+            limit: Some(500),
+            content_types: Some(vec!["pdf".to_string()]),
+            ..Default::default()
+        };
+
+        let filters = request_filters(&request);
+        assert_eq!(filters["limit"], 100);
+        assert_eq!(filters["offset"], 0);
+        assert_eq!(filters["content_types"], json!(["pdf"]));
+        assert!(filters["source_types"].is_null());
+    }
+}
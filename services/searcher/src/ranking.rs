@@ -0,0 +1,428 @@
+//! Ranking of hybrid search results: fusion of the full-text and semantic scores, boosts,
+//! and reranking with a cross-encoder.
+
+use crate::models::SearchResult;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use shared::db::repositories::ConfigurationRepository;
+use shared::models::Document;
+use shared::{DatabasePool, HybridFusion, SearchTuning};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// Configuration key holding the boosts.
+pub const RANKING_BOOSTS_KEY: &str = "search_ranking_boosts";
+
+/// Document attribute holding its net votes, e.g. the score of a Stack Overflow question.
+pub const VOTES_ATTRIBUTE: &str = "votes";
+
+/// Score multipliers keyed by source ID and by content type. Factors above 1 boost, factors
+/// below 1 demote, and anything not listed keeps a factor of 1. Boosts are part of the search
+/// cache key, so changes apply to the next query.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RankingBoosts {
+    #[serde(default)]
+    pub sources: BTreeMap<String, f32>,
+    #[serde(default)]
+    pub content_types: BTreeMap<String, f32>,
+    /// Multiplier applied per open flag on a document, between 0 and 1. Flags do not affect
+    /// ranking when unset, and new flags apply once cached results expire.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flag_penalty: Option<f32>,
+    /// Weight of the boost for a document's votes. Votes do not affect ranking when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vote_weight: Option<f32>,
+}
+
+impl RankingBoosts {
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+            && self.content_types.is_empty()
+            && self.flag_penalty.is_none()
+            && self.vote_weight.is_none()
+    }
+
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        let factors = self
+            .sources
+            .iter()
+            .map(|(source_id, factor)| ("source", source_id, factor))
+            .chain(
+                self.content_types
+                    .iter()
+                    .map(|(content_type, factor)| ("content type", content_type, factor)),
+            );
+        for (kind, key, factor) in factors {
+            if !(factor.is_finite() && *factor > 0.0) {
+                return Err(format!(
+                    "Boost for {} '{}' must be a positive number, got {}",
+                    kind, key, factor
+                ));
+            }
+        }
+        if let Some(penalty) = self.flag_penalty {
+            if !(penalty.is_finite() && penalty > 0.0 && penalty <= 1.0) {
+                return Err(format!(
+                    "Flag penalty must be greater than 0 and at most 1, got {}",
+                    penalty
+                ));
+            }
+        }
+        if let Some(weight) = self.vote_weight {
+            if !(weight.is_finite() && weight >= 0.0) {
+                return Err(format!(
+                    "Vote weight must be a non-negative number, got {}",
+                    weight
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn source_boost(&self, document: &Document) -> f32 {
+        self.sources
+            .get(&document.source_id)
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    pub fn content_type_boost(&self, document: &Document) -> f32 {
+        document
+            .content_type
+            .as_ref()
+            .and_then(|content_type| self.content_types.get(content_type))
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    /// The factor for a document with `open_flags` open flags.
+    pub fn flag_boost(&self, open_flags: i64) -> f32 {
+        match self.flag_penalty {
+            Some(penalty) if open_flags > 0 => penalty.powi(open_flags.min(i32::MAX as i64) as i32),
+            _ => 1.0,
+        }
+    }
+
+    /// The factor for a document's `votes` attribute.
+    pub fn vote_boost(&self, document: &Document) -> f32 {
+        let Some(weight) = self.vote_weight else {
+            return 1.0;
+        };
+        let votes = document
+            .attributes
+            .get(VOTES_ATTRIBUTE)
+            .and_then(JsonValue::as_f64)
+            .unwrap_or(0.0);
+        1.0 + weight * (votes.max(0.0) as f32).ln_1p()
+    }
+}
+
+/// The factor for a document that `inbound_links` other documents link to.
+pub fn link_boost(weight: f32, inbound_links: i64) -> f32 {
+    1.0 + weight * (inbound_links.max(0) as f32).ln_1p()
+}
+
+/// Contributions to the hybrid score of the results of one search, given their `scores` in
+/// that search: the score times the search's `weight`, or with RRF `1 / (k + rank)`, the best
+/// score ranking 1. RRF only looks at the order, so it needs no retuning when `ts_rank` or
+/// cosine similarity drift.
+pub fn fused_scores(tuning: &SearchTuning, weight: f32, scores: &[f32]) -> Vec<f32> {
+    match tuning.hybrid_fusion {
+        HybridFusion::Weighted => scores.iter().map(|score| score * weight).collect(),
+        HybridFusion::Rrf => {
+            let mut by_score: Vec<usize> = (0..scores.len()).collect();
+            by_score.sort_by(|&a, &b| scores[b].partial_cmp(&scores[a]).unwrap_or(Ordering::Equal));
+            let mut fused = vec![0.0; scores.len()];
+            for (rank, i) in by_score.into_iter().enumerate() {
+                fused[i] = 1.0 / (tuning.hybrid_rrf_k as f32 + rank as f32 + 1.0);
+            }
+            fused
+        }
+    }
+}
+
+/// Characters of a result the cross-encoder reads, enough for the title and highlights.
+const RERANK_PASSAGE_CHARS: usize = 2000;
+
+/// The text of a result the cross-encoder reads together with the query to score it: its title
+/// followed by its content, or its highlights without content.
+pub fn rerank_passage(result: &SearchResult) -> String {
+    let mut passage = result.document.title.clone();
+    match &result.content {
+        Some(content) => {
+            passage.push('\n');
+            passage.push_str(content);
+        }
+        None => {
+            for highlight in &result.highlights {
+                passage.push('\n');
+                passage.push_str(highlight);
+            }
+        }
+    }
+    passage.chars().take(RERANK_PASSAGE_CHARS).collect()
+}
+
+/// Order results, best first, by their cross-encoder `scores`. Each result takes the score the
+/// result at its new place had, and the change shows up in its `rerank_delta`.
+pub fn rerank(results: &mut [SearchResult], scores: &[f32]) {
+    let hybrid_scores: Vec<f32> = results.iter().map(|result| result.score).collect();
+    for result in results.iter_mut() {
+        if let Some(ranking) = result.ranking.as_mut() {
+            ranking.rerank_delta -= result.score;
+        }
+    }
+    for (result, &score) in results.iter_mut().zip(scores) {
+        result.score = score;
+        if let Some(ranking) = result.ranking.as_mut() {
+            ranking.cross_encoder_score = Some(score);
+        }
+    }
+    results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+    for (result, score) in results.iter_mut().zip(hybrid_scores) {
+        result.score = score;
+        if let Some(ranking) = result.ranking.as_mut() {
+            ranking.rerank_delta += score;
+        }
+    }
+}
+
+pub async fn load_boosts(db_pool: &DatabasePool) -> Result<RankingBoosts> {
+    let repo = ConfigurationRepository::new(db_pool.pool());
+    match repo.get(RANKING_BOOSTS_KEY).await? {
+        Some(value) => Ok(serde_json::from_value(value)?),
+        None => Ok(RankingBoosts::default()),
+    }
+}
+
+pub async fn save_boosts(db_pool: &DatabasePool, boosts: &RankingBoosts) -> Result<()> {
+    let repo = ConfigurationRepository::new(db_pool.pool());
+    repo.set(RANKING_BOOSTS_KEY, &serde_json::to_value(boosts)?)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RankingExplanation;
+    use serde_json::json;
+    use time::OffsetDateTime;
+
+    fn document(source_id: &str, content_type: Option<&str>) -> Document {
+        let now = OffsetDateTime::now_utc();
+        Document {
+            id: "doc".to_string(),
+            source_id: source_id.to_string(),
+            external_id: "ext".to_string(),
+            title: "Title".to_string(),
+            content_id: None,
+            content_type: content_type.map(|ct| ct.to_string()),
+            file_size: None,
+            file_extension: None,
+            url: None,
+            metadata: json!({}),
+            permissions: json!({}),
+            attributes: json!({}),
+            created_at: now,
+            updated_at: now,
+            last_indexed_at: now,
+            preview: None,
+        }
+    }
+
+    #[test]
+    fn test_boost_factors() {
+        let boosts: RankingBoosts = serde_json::from_value(json!({
+            "sources": {"wiki": 1.5},
+            "content_types": {"archived_issue": 0.25}
+        }))
+        .unwrap();
+
+        let wiki_page = document("wiki", Some("page"));
+        assert_eq!(boosts.source_boost(&wiki_page), 1.5);
+        assert_eq!(boosts.content_type_boost(&wiki_page), 1.0);
+
+        let archived = document("jira", Some("archived_issue"));
+        assert_eq!(boosts.source_boost(&archived), 1.0);
+        assert_eq!(boosts.content_type_boost(&archived), 0.25);
+
+        let untyped = document("jira", None);
+        assert_eq!(boosts.content_type_boost(&untyped), 1.0);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(RankingBoosts::default().validate().is_ok());
+
+        let mut boosts = RankingBoosts::default();
+        boosts.sources.insert("wiki".to_string(), 2.0);
+        assert!(boosts.validate().is_ok());
+
+        boosts.content_types.insert("issue".to_string(), 0.0);
+        assert!(boosts.validate().is_err());
+
+        boosts.content_types.insert("issue".to_string(), f32::NAN);
+        assert!(boosts.validate().is_err());
+    }
+
+    #[test]
+    fn test_link_boost() {
+        assert_eq!(link_boost(0.1, 0), 1.0);
+        assert_eq!(link_boost(0.0, 50), 1.0);
+        assert!(link_boost(0.1, 1) > 1.0);
+        assert!(link_boost(0.1, 10) > link_boost(0.1, 1));
+        // Grows logarithmically, so heavily linked pages do not drown out relevance
+        assert!(link_boost(0.1, 1000) < 1.7);
+    }
+
+    fn tuning(hybrid_fusion: HybridFusion) -> SearchTuning {
+        SearchTuning {
+            hybrid_fusion,
+            hybrid_rrf_k: 60,
+            hybrid_search_fts_weight: 0.3,
+            hybrid_search_semantic_weight: 1.0,
+            link_authority_weight: 0.1,
+            semantic_search_timeout_ms: 5000,
+            rag_context_window: 2,
+            typo_tolerance: true,
+            ranking_boosts: true,
+        }
+    }
+
+    /// Document IDs by hybrid score, best first.
+    fn fuse(tuning: &SearchTuning, fts: &[(&str, f32)], semantic: &[(&str, f32)]) -> Vec<String> {
+        let mut combined: BTreeMap<String, f32> = BTreeMap::new();
+        for (results, weight) in [
+            (fts, tuning.hybrid_search_fts_weight),
+            (semantic, tuning.hybrid_search_semantic_weight),
+        ] {
+            let scores: Vec<f32> = results.iter().map(|(_, score)| *score).collect();
+            for ((id, _), fused) in results.iter().zip(fused_scores(tuning, weight, &scores)) {
+                *combined.entry(id.to_string()).or_default() += fused;
+            }
+        }
+        let mut ranked: Vec<(String, f32)> = combined.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        ranked.into_iter().map(|(id, _)| id).collect()
+    }
+
+    #[test]
+    fn test_fused_scores() {
+        let scores = [0.2, 0.9, 0.5];
+        assert_eq!(
+            fused_scores(&tuning(HybridFusion::Weighted), 0.5, &scores),
+            vec![0.1, 0.45, 0.25]
+        );
+        assert_eq!(
+            fused_scores(&tuning(HybridFusion::Rrf), 0.5, &scores),
+            vec![1.0 / 63.0, 1.0 / 61.0, 1.0 / 62.0]
+        );
+        assert!(fused_scores(&tuning(HybridFusion::Rrf), 0.5, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_rrf_ranks_results_of_both_searches_above_weighted_fusion() {
+        // ts_rank scores are far below cosine similarities, so with weights a document both
+        // searches find is outranked by a slightly closer semantic-only match
+        let fts = [("keyword-match", 0.09), ("both", 0.05)];
+        let semantic = [("semantic-only", 0.82), ("both", 0.80), ("loose", 0.30)];
+
+        let weighted = fuse(&tuning(HybridFusion::Weighted), &fts, &semantic);
+        assert_eq!(weighted[..2], ["semantic-only", "both"]);
+
+        let rrf = fuse(&tuning(HybridFusion::Rrf), &fts, &semantic);
+        assert_eq!(rrf[0], "both");
+        assert_eq!(rrf[3], "loose");
+    }
+
+    #[test]
+    fn test_vote_boost() {
+        let mut boosts = RankingBoosts::default();
+        let mut answered = document("stack", Some("question"));
+        answered.attributes = json!({ "votes": 42 });
+        assert_eq!(boosts.vote_boost(&answered), 1.0);
+
+        boosts.vote_weight = Some(0.1);
+        assert!(!boosts.is_empty());
+        assert!(boosts.validate().is_ok());
+        assert!(boosts.vote_boost(&answered) > 1.0);
+
+        let mut downvoted = document("stack", Some("question"));
+        downvoted.attributes = json!({ "votes": -3 });
+        assert_eq!(boosts.vote_boost(&downvoted), 1.0);
+        assert_eq!(boosts.vote_boost(&document("wiki", None)), 1.0);
+
+        boosts.vote_weight = Some(-1.0);
+        assert!(boosts.validate().is_err());
+    }
+
+    #[test]
+    fn test_flag_penalty() {
+        let mut boosts = RankingBoosts::default();
+        assert_eq!(boosts.flag_boost(3), 1.0);
+
+        boosts.flag_penalty = Some(0.5);
+        assert!(!boosts.is_empty());
+        assert!(boosts.validate().is_ok());
+        assert_eq!(boosts.flag_boost(0), 1.0);
+        assert_eq!(boosts.flag_boost(1), 0.5);
+        assert_eq!(boosts.flag_boost(2), 0.25);
+
+        boosts.flag_penalty = Some(1.5);
+        assert!(boosts.validate().is_err());
+        boosts.flag_penalty = Some(0.0);
+        assert!(boosts.validate().is_err());
+    }
+
+    fn result(id: &str, score: f32) -> SearchResult {
+        SearchResult {
+            document: Document {
+                id: id.to_string(),
+                ..document("wiki", None)
+            },
+            score,
+            highlights: vec!["first **match**".to_string(), "second".to_string()],
+            match_type: "hybrid".to_string(),
+            content: None,
+            pinned: false,
+            ranking: Some(RankingExplanation::default()),
+            alternates: vec![],
+            anchor: None,
+            children: vec![],
+        }
+    }
+
+    #[test]
+    fn test_rerank_keeps_hybrid_scores_in_order() {
+        let mut results = vec![result("a", 0.9), result("b", 0.6), result("c", 0.3)];
+
+        rerank(&mut results, &[0.2, 0.1, 0.8]);
+
+        let ids: Vec<&str> = results.iter().map(|r| r.document.id.as_str()).collect();
+        assert_eq!(ids, ["c", "a", "b"]);
+        let scores: Vec<f32> = results.iter().map(|r| r.score).collect();
+        assert_eq!(scores, [0.9, 0.6, 0.3]);
+        let ranking = results[0].ranking.as_ref().unwrap();
+        assert_eq!(ranking.cross_encoder_score, Some(0.8));
+        let deltas: Vec<f32> = results
+            .iter()
+            .map(|r| r.ranking.as_ref().unwrap().rerank_delta)
+            .collect();
+        for (delta, expected) in deltas.into_iter().zip([0.6, -0.3, -0.3]) {
+            assert!((delta - expected).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_rerank_passage() {
+        let mut result = result("a", 1.0);
+        assert_eq!(rerank_passage(&result), "Title\nfirst **match**\nsecond");
+
+        result.content = Some("x".repeat(5000));
+        let passage = rerank_passage(&result);
+        assert!(passage.starts_with("Title\nxxx"));
+        assert_eq!(passage.chars().count(), RERANK_PASSAGE_CHARS);
+    }
+}
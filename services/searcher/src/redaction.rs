@@ -0,0 +1,397 @@
+//! Redaction rules for search result previews.
+//!
+//! Documents matching a rule stay searchable, but their snippets and highlights are removed
+//! or masked before results leave the searcher. Rules are managed through the admin API and
+//! stored in the `configuration` table. Each replica keeps them compiled, reloading them
+//! periodically and right away when it saves them, so changes apply without a restart.
+
+use crate::models::SearchResult;
+use anyhow::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use shared::db::repositories::ConfigurationRepository;
+use shared::redaction::REDACTED;
+use shared::DatabasePool;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tracing::error;
+
+/// Configuration key holding the list of rules.
+pub const REDACTION_RULES_KEY: &str = "search_redaction_rules";
+
+/// Seconds between reloads of the rules saved by other replicas.
+pub const REFRESH_INTERVAL_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactionAction {
+    /// Drop all highlights and content of the result.
+    #[default]
+    Strip,
+    /// Replace the text matching `content_pattern` with a placeholder, or every snippet
+    /// when the rule has no pattern.
+    Mask,
+}
+
+/// A rule applies to a result when every condition it sets matches: the document belongs to
+/// one of `source_ids`, has all `attributes`, and has a snippet matching `content_pattern`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RedactionRule {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub source_ids: Vec<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub attributes: HashMap<String, JsonValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_pattern: Option<String>,
+    #[serde(default)]
+    pub action: RedactionAction,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RedactionRules {
+    pub rules: Vec<RedactionRule>,
+}
+
+struct CompiledRule {
+    rule: RedactionRule,
+    pattern: Option<Regex>,
+}
+
+impl CompiledRule {
+    fn compile(rule: RedactionRule) -> std::result::Result<Self, String> {
+        if rule.name.trim().is_empty() {
+            return Err("Redaction rule name cannot be empty".to_string());
+        }
+        if rule.source_ids.is_empty()
+            && rule.attributes.is_empty()
+            && rule.content_pattern.is_none()
+        {
+            return Err(format!(
+                "Redaction rule '{}' must set source_ids, attributes or content_pattern",
+                rule.name
+            ));
+        }
+
+        let pattern = rule
+            .content_pattern
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| format!("Invalid content_pattern in rule '{}': {}", rule.name, e))?;
+
+        Ok(Self { rule, pattern })
+    }
+
+    fn matches_document(&self, result: &SearchResult) -> bool {
+        let document = &result.document;
+        if !self.rule.source_ids.is_empty() && !self.rule.source_ids.contains(&document.source_id) {
+            return false;
+        }
+
+        self.rule
+            .attributes
+            .iter()
+            .all(|(key, expected)| match document.attributes.get(key) {
+                Some(JsonValue::Array(values)) => values.contains(expected),
+                Some(value) => value == expected,
+                None => false,
+            })
+    }
+
+    fn matches_content(&self, result: &SearchResult) -> bool {
+        let Some(pattern) = &self.pattern else {
+            return true;
+        };
+        result
+            .highlights
+            .iter()
+            .chain(result.content.iter())
+            .any(|text| pattern.is_match(text))
+    }
+
+    /// Apply the rule to a result, returning whether its previews were removed entirely.
+    fn apply(&self, result: &mut SearchResult) -> bool {
+        if !self.matches_document(result) || !self.matches_content(result) {
+            return false;
+        }
+
+        match (self.rule.action, &self.pattern) {
+            (RedactionAction::Strip, _) => {
+                result.highlights.clear();
+                result.content = None;
+                true
+            }
+            (RedactionAction::Mask, Some(pattern)) => {
+                for text in result
+                    .highlights
+                    .iter_mut()
+                    .chain(result.content.iter_mut())
+                {
+                    *text = pattern.replace_all(text, REDACTED).into_owned();
+                }
+                false
+            }
+            (RedactionAction::Mask, None) => {
+                for text in result
+                    .highlights
+                    .iter_mut()
+                    .chain(result.content.iter_mut())
+                {
+                    *text = REDACTED.to_string();
+                }
+                false
+            }
+        }
+    }
+}
+
+/// The compiled set of rules applied to search results.
+#[derive(Default)]
+pub struct Redactor {
+    rules: Vec<CompiledRule>,
+}
+
+impl Redactor {
+    /// Compile a rule set, rejecting rules without conditions or with an invalid pattern.
+    pub fn new(rules: RedactionRules) -> std::result::Result<Self, String> {
+        let rules = rules
+            .rules
+            .into_iter()
+            .map(CompiledRule::compile)
+            .collect::<std::result::Result<_, _>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Load the stored rules. Rules were validated when saved, so any that no longer compile
+    /// are logged and skipped rather than failing every search.
+    pub async fn load(db_pool: &DatabasePool) -> Result<Self> {
+        let rules = load_rules(db_pool).await?;
+        let rules = rules
+            .rules
+            .into_iter()
+            .filter_map(|rule| match CompiledRule::compile(rule) {
+                Ok(rule) => Some(rule),
+                Err(e) => {
+                    error!("Skipping redaction rule: {}", e);
+                    None
+                }
+            })
+            .collect();
+        Ok(Self { rules })
+    }
+
+    /// Redact previews of every result matching a rule. Rules are applied in order, and
+    /// stop at the first one that strips the result.
+    pub fn apply(&self, results: &mut [SearchResult]) {
+        for result in results {
+            for rule in &self.rules {
+                if rule.apply(result) {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Redact `texts` read from a result's document, such as transcript lines, as its
+    /// previews would be. Returns none of them when a rule strips the result.
+    pub fn apply_to_texts(&self, result: &SearchResult, texts: Vec<String>) -> Vec<String> {
+        let mut preview = result.clone();
+        let previews = preview.highlights.len() + preview.content.iter().count();
+        let count = texts.len();
+        preview.highlights.extend(texts);
+        self.apply(std::slice::from_mut(&mut preview));
+        if preview.highlights.len() + preview.content.iter().count() < previews + count {
+            return Vec::new();
+        }
+        preview
+            .highlights
+            .split_off(preview.highlights.len() - count)
+    }
+}
+
+/// The compiled rules in effect, shared by all requests.
+pub struct RedactionStore {
+    current: RwLock<Arc<Redactor>>,
+    db_pool: DatabasePool,
+}
+
+impl RedactionStore {
+    pub fn new(db_pool: DatabasePool) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(Redactor::default())),
+            db_pool,
+        }
+    }
+
+    pub fn current(&self) -> Arc<Redactor> {
+        match self.current.read() {
+            Ok(current) => Arc::clone(&current),
+            Err(_) => Arc::new(Redactor::default()),
+        }
+    }
+
+    /// Save rules that [`Redactor::new`] compiled, and put them into effect on this replica.
+    pub async fn save(&self, rules: &RedactionRules, redactor: Redactor) -> Result<()> {
+        save_rules(&self.db_pool, rules).await?;
+        self.set(redactor);
+        Ok(())
+    }
+
+    pub async fn refresh(&self) -> Result<()> {
+        let redactor = Redactor::load(&self.db_pool).await?;
+        self.set(redactor);
+        Ok(())
+    }
+
+    fn set(&self, redactor: Redactor) {
+        if let Ok(mut current) = self.current.write() {
+            *current = Arc::new(redactor);
+        }
+    }
+
+    pub fn start_background_refresh(self: &Arc<Self>, interval_secs: u64) {
+        let store = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+                if let Err(e) = store.refresh().await {
+                    error!("Failed to refresh search redaction rules: {}", e);
+                }
+            }
+        });
+    }
+}
+
+pub async fn load_rules(db_pool: &DatabasePool) -> Result<RedactionRules> {
+    let repo = ConfigurationRepository::new(db_pool.pool());
+    match repo.get(REDACTION_RULES_KEY).await? {
+        Some(value) => Ok(serde_json::from_value(value)?),
+        None => Ok(RedactionRules::default()),
+    }
+}
+
+pub async fn save_rules(db_pool: &DatabasePool, rules: &RedactionRules) -> Result<()> {
+    let repo = ConfigurationRepository::new(db_pool.pool());
+    repo.set(REDACTION_RULES_KEY, &serde_json::to_value(rules)?)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use shared::models::Document;
+    use time::OffsetDateTime;
+
+    fn result(source_id: &str, attributes: JsonValue, highlights: &[&str]) -> SearchResult {
+        let now = OffsetDateTime::now_utc();
+        SearchResult {
+            document: Document {
+                id: "doc".to_string(),
+                source_id: source_id.to_string(),
+                external_id: "ext".to_string(),
+                title: "Title".to_string(),
+                content_id: None,
+                content_type: None,
+                file_size: None,
+                file_extension: None,
+                url: None,
+                metadata: json!({}),
+                permissions: json!({}),
+                attributes,
+                created_at: now,
+                updated_at: now,
+                last_indexed_at: now,
+                preview: None,
+            },
+            score: 1.0,
+            highlights: highlights.iter().map(|h| h.to_string()).collect(),
+            match_type: "fulltext".to_string(),
+            content: None,
+            pinned: false,
+            ranking: None,
+            alternates: vec![],
+            anchor: None,
+            children: vec![],
+        }
+    }
+
+    fn redactor(rules: JsonValue) -> Redactor {
+        Redactor::new(serde_json::from_value(json!({ "rules": rules })).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_strip_by_source_and_attribute() {
+        let redactor = redactor(json!([
+            { "name": "hr", "source_ids": ["hr"], "attributes": { "labels": "confidential" } }
+        ]));
+        let mut results = vec![
+            result(
+                "hr",
+                json!({ "labels": ["confidential", "policy"] }),
+                &["salary bands"],
+            ),
+            result("hr", json!({ "labels": ["policy"] }), &["holiday policy"]),
+            result("eng", json!({ "labels": "confidential" }), &["design doc"]),
+        ];
+
+        redactor.apply(&mut results);
+
+        assert!(results[0].highlights.is_empty());
+        assert_eq!(results[1].highlights, vec!["holiday policy"]);
+        assert_eq!(results[2].highlights, vec!["design doc"]);
+    }
+
+    #[test]
+    fn test_mask_content_pattern() {
+        let redactor = redactor(json!([
+            { "name": "ssn", "content_pattern": r"\d{3}-\d{2}-\d{4}", "action": "mask" }
+        ]));
+        let mut results = vec![result(
+            "hr",
+            json!({}),
+            &["ssn 123-45-6789 on file", "no numbers"],
+        )];
+
+        redactor.apply(&mut results);
+
+        assert_eq!(
+            results[0].highlights,
+            vec![
+                format!("ssn {} on file", REDACTED),
+                "no numbers".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_to_texts() {
+        let redactor = redactor(json!([
+            { "name": "hr", "source_ids": ["hr"] },
+            { "name": "ssn", "content_pattern": r"\d{3}-\d{2}-\d{4}", "action": "mask" }
+        ]));
+        let lines = || vec!["my ssn is 123-45-6789".to_string(), "thanks".to_string()];
+
+        assert!(redactor
+            .apply_to_texts(&result("hr", json!({}), &["salary"]), lines())
+            .is_empty());
+        assert_eq!(
+            redactor.apply_to_texts(&result("eng", json!({}), &[]), lines()),
+            vec![format!("my ssn is {}", REDACTED), "thanks".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_invalid_rules_rejected() {
+        let parse = |rules: JsonValue| {
+            Redactor::new(serde_json::from_value(json!({ "rules": rules })).unwrap())
+        };
+        assert!(parse(json!([{ "name": "everything" }])).is_err());
+        assert!(parse(json!([{ "name": "bad", "content_pattern": "(" }])).is_err());
+        assert!(parse(json!([{ "name": "", "source_ids": ["a"] }])).is_err());
+    }
+}
@@ -0,0 +1,195 @@
+//! Nesting of attachments, comments and transcript segments under their parent's result.
+//!
+//! Children are indexed as documents of their own, so an email thread and the spreadsheet
+//! attached to it can both match a search. Nested searches list such children under their
+//! parent's result. Rolled up searches also replace children whose parent did not match with
+//! the parent, so results are always whole threads, pages and meetings. Nesting is one level
+//! deep: a child whose parent is nested itself stays a result of its own.
+
+use crate::models::{ChildResult, SearchResult};
+use shared::db::repositories::DocumentRelationship;
+use std::collections::{HashMap, HashSet};
+
+/// Nest results under their parents' results. `relationships` are the parents of the results
+/// that have one, and `unranked` the parents that did not match but may take the place of
+/// their best ranked child, by ID.
+pub fn nest(
+    results: Vec<SearchResult>,
+    relationships: &[DocumentRelationship],
+    mut unranked: HashMap<String, SearchResult>,
+) -> Vec<SearchResult> {
+    let parent_of: HashMap<&str, &DocumentRelationship> = relationships
+        .iter()
+        .map(|relationship| (relationship.document_id.as_str(), relationship))
+        .collect();
+    let result_ids: HashSet<&str> = results.iter().map(|r| r.document.id.as_str()).collect();
+    let has_parent = |id: &str| {
+        parent_of.get(id).is_some_and(|relationship| {
+            let parent_id = relationship.parent_document_id.as_str();
+            result_ids.contains(parent_id) || unranked.contains_key(parent_id)
+        })
+    };
+    let nested: HashMap<String, DocumentRelationship> = results
+        .iter()
+        .filter_map(|result| {
+            let relationship = parent_of.get(result.document.id.as_str())?;
+            let nests =
+                has_parent(&result.document.id) && !has_parent(&relationship.parent_document_id);
+            nests.then(|| (result.document.id.clone(), (*relationship).clone()))
+        })
+        .collect();
+
+    let mut nested_results: Vec<SearchResult> = Vec::with_capacity(results.len());
+    let mut children: HashMap<String, Vec<ChildResult>> = HashMap::new();
+    for result in results {
+        let Some(relationship) = nested.get(&result.document.id) else {
+            nested_results.push(result);
+            continue;
+        };
+        let parent_id = &relationship.parent_document_id;
+        if let Some(mut parent) = unranked.remove(parent_id) {
+            parent.score = result.score;
+            nested_results.push(parent);
+        }
+        children
+            .entry(parent_id.clone())
+            .or_default()
+            .push(ChildResult {
+                relationship: relationship.relationship_type,
+                result,
+            });
+    }
+
+    for result in &mut nested_results {
+        if let Some(children) = children.remove(&result.document.id) {
+            result.children.extend(children);
+        }
+    }
+    nested_results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use shared::models::{Document, RelationshipType};
+    use time::OffsetDateTime;
+
+    fn result(id: &str, score: f32) -> SearchResult {
+        let now = OffsetDateTime::now_utc();
+        SearchResult {
+            document: Document {
+                id: id.to_string(),
+                source_id: "source".to_string(),
+                external_id: id.to_string(),
+                title: id.to_string(),
+                content_id: None,
+                content_type: None,
+                file_size: None,
+                file_extension: None,
+                url: None,
+                metadata: json!({}),
+                permissions: json!({}),
+                attributes: json!({}),
+                created_at: now,
+                updated_at: now,
+                last_indexed_at: now,
+                preview: None,
+            },
+            score,
+            highlights: vec![],
+            match_type: "fulltext".to_string(),
+            content: None,
+            pinned: false,
+            ranking: None,
+            alternates: vec![],
+            anchor: None,
+            children: vec![],
+        }
+    }
+
+    fn relationship(
+        document_id: &str,
+        parent_document_id: &str,
+        relationship_type: RelationshipType,
+    ) -> DocumentRelationship {
+        DocumentRelationship {
+            document_id: document_id.to_string(),
+            parent_document_id: parent_document_id.to_string(),
+            relationship_type,
+        }
+    }
+
+    fn ids(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.document.id.as_str()).collect()
+    }
+
+    fn child_ids(result: &SearchResult) -> Vec<&str> {
+        result
+            .children
+            .iter()
+            .map(|child| child.result.document.id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_nests_children_under_matching_parent() {
+        let results = vec![
+            result("attachment", 0.9),
+            result("thread", 0.8),
+            result("other", 0.7),
+            result("comment", 0.6),
+            result("orphan", 0.5),
+        ];
+        let relationships = vec![
+            relationship("attachment", "thread", RelationshipType::Attachment),
+            relationship("comment", "thread", RelationshipType::Comment),
+            relationship("orphan", "unmatched", RelationshipType::Attachment),
+        ];
+
+        let nested = nest(results, &relationships, HashMap::new());
+        assert_eq!(ids(&nested), vec!["thread", "other", "orphan"]);
+        assert_eq!(nested[0].score, 0.8);
+        assert_eq!(child_ids(&nested[0]), vec!["attachment", "comment"]);
+        assert_eq!(
+            nested[0].children[1].relationship,
+            RelationshipType::Comment
+        );
+    }
+
+    #[test]
+    fn test_rolls_up_children_into_unranked_parent() {
+        let results = vec![
+            result("other", 0.9),
+            result("segment-2", 0.8),
+            result("segment-1", 0.7),
+        ];
+        let relationships = vec![
+            relationship("segment-1", "meeting", RelationshipType::TranscriptSegment),
+            relationship("segment-2", "meeting", RelationshipType::TranscriptSegment),
+        ];
+        let unranked = HashMap::from([("meeting".to_string(), result("meeting", 0.0))]);
+
+        let nested = nest(results, &relationships, unranked);
+        assert_eq!(ids(&nested), vec!["other", "meeting"]);
+        assert_eq!(nested[1].score, 0.8);
+        assert_eq!(child_ids(&nested[1]), vec!["segment-2", "segment-1"]);
+    }
+
+    #[test]
+    fn test_nests_one_level_deep() {
+        let results = vec![
+            result("reply", 0.9),
+            result("comment", 0.8),
+            result("page", 0.7),
+        ];
+        let relationships = vec![
+            relationship("reply", "comment", RelationshipType::Comment),
+            relationship("comment", "page", RelationshipType::Comment),
+        ];
+
+        let nested = nest(results, &relationships, HashMap::new());
+        assert_eq!(ids(&nested), vec!["reply", "page"]);
+        assert_eq!(child_ids(&nested[1]), vec!["comment"]);
+    }
+}
@@ -0,0 +1,156 @@
+//! Selection of the fields returned for each search result.
+//!
+//! Clients that only show a few fields, such as autocomplete and the mobile apps, name them in
+//! `result_fields` to leave the rest, notably document metadata, out of the response. Fields
+//! of the result's document are named directly (`id`, `title`, `url`) and stay nested under
+//! `document`, so responses keep their shape.
+
+use serde_json::{Map, Value};
+
+/// Fields of a search result.
+const RESULT_FIELDS: [&str; 10] = [
+    "document",
+    "score",
+    "highlights",
+    "match_type",
+    "content",
+    "pinned",
+    "ranking",
+    "alternates",
+    "anchor",
+    "children",
+];
+
+/// Fields of a search result's document.
+const DOCUMENT_FIELDS: [&str; 15] = [
+    "id",
+    "source_id",
+    "external_id",
+    "title",
+    "content_type",
+    "file_size",
+    "file_extension",
+    "url",
+    "metadata",
+    "permissions",
+    "attributes",
+    "created_at",
+    "updated_at",
+    "last_indexed_at",
+    "preview",
+];
+
+/// Check that every field can be selected, returning the first that cannot.
+pub fn validate(fields: &[String]) -> Result<(), String> {
+    match fields.iter().find(|field| {
+        !RESULT_FIELDS.contains(&field.as_str()) && !DOCUMENT_FIELDS.contains(&field.as_str())
+    }) {
+        Some(field) => Err(format!("Unknown result field: {}", field)),
+        None => Ok(()),
+    }
+}
+
+/// Keep only `fields` of the results in a serialized search response.
+pub fn select(response: &mut Value, fields: &[String]) {
+    let Some(results) = response.get_mut("results").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for result in results {
+        if let Value::Object(object) = result {
+            *object = select_fields(std::mem::take(object), fields);
+        }
+    }
+}
+
+fn select_fields(mut result: Map<String, Value>, fields: &[String]) -> Map<String, Value> {
+    let mut document = match result.remove("document") {
+        Some(Value::Object(document)) => document,
+        _ => Map::new(),
+    };
+    let mut selected = Map::new();
+    let mut selected_document = Map::new();
+    for field in fields {
+        if field == "document" {
+            selected_document.extend(std::mem::take(&mut document));
+        } else if let Some(value) = result.remove(field) {
+            selected.insert(field.clone(), value);
+        } else if let Some(value) = document.remove(field) {
+            selected_document.insert(field.clone(), value);
+        }
+    }
+    if !selected_document.is_empty() {
+        selected.insert("document".to_string(), Value::Object(selected_document));
+    }
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fields(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn response() -> Value {
+        json!({
+            "results": [{
+                "document": {
+                    "id": "doc-1",
+                    "title": "Quarterly plan",
+                    "url": "https://example.com/plan",
+                    "metadata": {"author": "alice"},
+                },
+                "score": 0.9,
+                "highlights": ["<b>quarterly</b> plan"],
+                "match_type": "fulltext",
+                "pinned": false,
+            }],
+            "total_count": 1,
+            "query": "quarterly plan",
+        })
+    }
+
+    #[test]
+    fn test_select_keeps_document_fields_nested() {
+        let mut response = response();
+        select(
+            &mut response,
+            &fields(&["id", "title", "url", "highlights"]),
+        );
+        assert_eq!(
+            response["results"][0],
+            json!({
+                "document": {
+                    "id": "doc-1",
+                    "title": "Quarterly plan",
+                    "url": "https://example.com/plan",
+                },
+                "highlights": ["<b>quarterly</b> plan"],
+            })
+        );
+        assert_eq!(response["total_count"], 1);
+    }
+
+    #[test]
+    fn test_select_whole_document() {
+        let mut response = response();
+        select(&mut response, &fields(&["document", "score"]));
+        assert_eq!(
+            response["results"][0]["document"]["metadata"]["author"],
+            "alice"
+        );
+        assert_eq!(response["results"][0]["score"], 0.9);
+        assert!(response["results"][0].get("highlights").is_none());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&fields(&["id", "highlights", "preview"])).is_ok());
+        assert_eq!(
+            validate(&fields(&["title", "content_id"])),
+            Err("Unknown result field: content_id".to_string())
+        );
+    }
+}
@@ -1,21 +1,45 @@
+use crate::acronyms::AcronymDictionary;
+use crate::budget::QueryCost;
+use crate::collections;
+use crate::duplicates;
+use crate::facets::FacetCounter;
+use crate::fallback;
+use crate::highlighting::{self, ChunkSource};
 use crate::models::{
-    RecentSearchesResponse, SearchMode, SearchRequest, SearchResponse, SearchResult,
+    ChildResults, ChunkSimilarity, FallbackStrategy, RankingExplanation, RecentSearchesResponse,
+    ResultAnchor, SearchMode, SearchRequest, SearchResponse, SearchResult,
 };
+use crate::promotions::{self, Promoter};
+use crate::query_embeddings::QueryEmbeddingCache;
+use crate::query_log::{LatencyBreakdown, QueryLogger};
+use crate::ranking::{self, RankingBoosts};
+use crate::redaction::Redactor;
+use crate::relationships;
+use crate::shadow_search::{ranked_document_ids, ActiveSearch, ShadowSearcher};
+use crate::warmup;
 use anyhow::Result;
 use redis::{AsyncCommands, Client as RedisClient};
-use shared::db::repositories::{DocumentRepository, EmbeddingRepository};
-use shared::models::ChunkResult;
-use shared::utils::safe_str_slice;
+use shared::clients::ai::EmbeddingModelRole;
+use shared::db::repositories::{
+    CollectionRepository, DocumentAnchorRepository, DocumentFlagRepository, DocumentLinkRepository,
+    DocumentPreviewRepository, DocumentRelationshipRepository, DocumentRepository,
+    EmbeddingRepository,
+};
+use shared::feature_flags::{self, FlagContext};
+use shared::models::{ChunkResult, SearchField};
 use shared::{
-    AIClient, DatabasePool, ObjectStorage, Repository, SearcherConfig, StorageFactory,
-    UserRepository,
+    cache, AIClient, DatabasePool, HybridFusion, ObjectStorage, Repository, SearcherConfig,
+    StorageFactory, UserRepository,
 };
 use std::collections::hash_map::DefaultHasher;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 use std::time::Instant;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
+
+/// Seconds a search response is cached for.
+const SEARCH_CACHE_TTL_SECS: u64 = 300;
 
 pub struct SearchEngine {
     db_pool: DatabasePool,
@@ -23,6 +47,49 @@ pub struct SearchEngine {
     ai_client: AIClient,
     content_storage: Arc<dyn ObjectStorage>,
     config: SearcherConfig,
+    query_logger: QueryLogger,
+    shadow_searcher: ShadowSearcher,
+    facet_counter: FacetCounter,
+    query_embeddings: QueryEmbeddingCache,
+    redactor: Arc<Redactor>,
+}
+
+/// A query embedding and the model that produced it. Only embeddings of the same model are
+/// comparable with it.
+pub(crate) struct QueryEmbedding {
+    pub vector: Vec<f32>,
+    pub model_name: String,
+}
+
+pub(crate) async fn embed_query(
+    ai_client: &AIClient,
+    query: &str,
+    model: EmbeddingModelRole,
+) -> Result<QueryEmbedding> {
+    debug!(
+        "Generating {:?} query embeddings for query '{}'",
+        model, query
+    );
+    let embeddings = ai_client
+        .generate_embeddings_with_model(
+            vec![query.to_string()],
+            Some("query".to_string()),
+            None,
+            Some("none".to_string()),
+            Some("high".to_string()), // High priority for search queries
+            Some(model),
+        )
+        .await?;
+    let Some(embedding) = embeddings.into_iter().next() else {
+        return Err(anyhow::anyhow!("Failed to generate embedding for query"));
+    };
+    let model_name = embedding
+        .model_name
+        .ok_or_else(|| anyhow::anyhow!("AI service did not report the embedding model"))?;
+    match embedding.chunk_embeddings.into_iter().next() {
+        Some(vector) => Ok(QueryEmbedding { vector, model_name }),
+        None => Err(anyhow::anyhow!("Failed to generate embedding for query")),
+    }
 }
 
 impl SearchEngine {
@@ -33,17 +100,45 @@ impl SearchEngine {
         redis_client: RedisClient,
         ai_client: AIClient,
         config: SearcherConfig,
+        redactor: Arc<Redactor>,
     ) -> Result<Self> {
         let content_storage = StorageFactory::from_env(db_pool.pool().clone()).await?;
+        let query_logger = QueryLogger::new(db_pool.clone(), &config);
+        let shadow_searcher = ShadowSearcher::new(db_pool.clone(), ai_client.clone());
+        let facet_counter = FacetCounter::new(db_pool.clone(), redis_client.clone());
+        let query_embeddings = QueryEmbeddingCache::new(ai_client.clone(), redis_client.clone());
         Ok(Self {
             db_pool,
             redis_client,
             ai_client,
             content_storage,
             config,
+            query_logger,
+            shadow_searcher,
+            facet_counter,
+            query_embeddings,
+            redactor,
         })
     }
 
+    /// The query's embedding with the active model. Warm-up searches embed it again, keeping
+    /// frequent queries' embeddings cached.
+    async fn active_query_embedding(&self, request: &SearchRequest) -> Result<QueryEmbedding> {
+        self.query_embeddings
+            .embed(&request.query, EmbeddingModelRole::Active, request.warmup)
+            .await
+    }
+
+    /// Repository of documents, filtering permissions by the request's resolved groups.
+    fn document_repo(&self, request: &SearchRequest) -> DocumentRepository {
+        DocumentRepository::new(self.db_pool.pool()).with_user_groups(request.user_groups.clone())
+    }
+
+    /// Repository of embeddings, filtering permissions by the request's resolved groups.
+    fn embedding_repo(&self, request: &SearchRequest) -> EmbeddingRepository {
+        EmbeddingRepository::new(self.db_pool.pool()).with_user_groups(request.user_groups.clone())
+    }
+
     fn prepare_document_for_response(
         &self,
         mut doc: shared::models::Document,
@@ -77,6 +172,194 @@ impl SearchEngine {
         doc
     }
 
+    /// Apply the configured redaction rules to results about to leave the searcher.
+    fn redact(&self, results: &mut [SearchResult]) {
+        self.redactor.apply(results);
+    }
+
+    /// Resolve the collection a search is limited to by its `collection` or a `collection:`
+    /// term in its query. Returns false when the collection does not exist or the user
+    /// cannot view it, leaving nothing to search.
+    async fn resolve_collection(&self, request: &mut SearchRequest) -> Result<bool> {
+        let (query, term) = collections::take_filter(&request.query);
+        if term.is_some() {
+            request.query = query;
+        }
+        let Some(collection) = term.or_else(|| request.collection.clone()) else {
+            return Ok(true);
+        };
+
+        let repo = CollectionRepository::new(self.db_pool.pool());
+        request.collection_filter = match request.user_email() {
+            Some(email) => repo.find_viewable_by_email(&collection, email).await?,
+            // Searches without a user are not permission filtered
+            None => repo.find_by_id(&collection).await?,
+        };
+        Ok(request.collection_filter.is_some())
+    }
+
+    /// Collapse results with identical content, such as the same file indexed from two
+    /// sources, into one result listing the other copies.
+    async fn collapse_duplicates(
+        &self,
+        request: &SearchRequest,
+        results: Vec<SearchResult>,
+    ) -> Result<Vec<SearchResult>> {
+        if results.len() < 2 {
+            return Ok(results);
+        }
+        let document_ids: Vec<String> = results.iter().map(|r| r.document.id.clone()).collect();
+        let content_hashes = DocumentRepository::new(self.db_pool.pool())
+            .find_content_hashes(&document_ids)
+            .await?;
+        Ok(duplicates::collapse(
+            results,
+            &content_hashes,
+            request.user_email().map(|e| e.as_str()),
+        ))
+    }
+
+    /// Set the preview of each result document that has one. Previews are attached after
+    /// promotions and outside the cached response, so pinned documents get theirs too.
+    async fn attach_previews(&self, results: &mut [SearchResult]) {
+        let document_ids: Vec<String> = results.iter().map(|r| r.document.id.clone()).collect();
+        let previews = match DocumentPreviewRepository::new(self.db_pool.pool())
+            .find_by_document_ids(&document_ids)
+            .await
+        {
+            Ok(previews) => previews,
+            Err(e) => {
+                error!("Failed to look up result previews: {}", e);
+                return;
+            }
+        };
+
+        let by_document: HashMap<&str, _> = previews
+            .iter()
+            .map(|stored| (stored.document_id.as_str(), stored))
+            .collect();
+        for result in results.iter_mut() {
+            result.document.preview = by_document
+                .get(result.document.id.as_str())
+                .map(|stored| stored.preview());
+        }
+    }
+
+    /// Apply the search promotions matching the query. Pinned documents the ranking did not
+    /// find are fetched with the request's filters, so promotions never reveal a document the
+    /// user could not have found.
+    async fn promote(
+        &self,
+        request: &SearchRequest,
+        results: Vec<SearchResult>,
+    ) -> Result<Vec<SearchResult>> {
+        let promoter = Promoter::load(&self.db_pool).await?;
+        let matching = promoter.matching(&request.query);
+        if matching.is_empty() {
+            return Ok(results);
+        }
+
+        let first_page = request.offset() == 0;
+        let missing: Vec<String> = promotions::pinned_document_ids(&matching)
+            .into_iter()
+            .filter(|id| !results.iter().any(|result| &result.document.id == id))
+            .collect();
+        let mut unranked = HashMap::new();
+        if first_page && !missing.is_empty() {
+            unranked = self.find_unranked(request, &missing, "pinned").await?;
+            for result in unranked.values_mut() {
+                result.pinned = true;
+            }
+        }
+
+        debug!(
+            "Applying {} search promotions to query '{}'",
+            matching.len(),
+            request.query
+        );
+        Ok(promotions::apply(&matching, results, unranked, first_page))
+    }
+
+    /// Documents the ranking did not find, as unscored results by ID. They are fetched with
+    /// the request's filters, so that they never reveal a document the user could not have
+    /// found.
+    async fn find_unranked(
+        &self,
+        request: &SearchRequest,
+        document_ids: &[String],
+        match_type: &str,
+    ) -> Result<HashMap<String, SearchResult>> {
+        let repo = self.document_repo(request);
+        let source_ids = repo
+            .fetch_active_source_ids(request.source_types.as_deref())
+            .await?;
+        let documents = repo
+            .find_by_ids_with_filters(
+                document_ids,
+                &source_ids,
+                request.content_types.as_deref(),
+                request.attribute_filters.as_ref(),
+                request.collection_id(),
+                request.user_email().map(|e| e.as_str()),
+            )
+            .await?;
+        let mut results: Vec<SearchResult> = documents
+            .into_iter()
+            .map(|doc| SearchResult {
+                document: self.prepare_document_for_response(doc),
+                score: 0.0,
+                highlights: vec![],
+                match_type: match_type.to_string(),
+                content: None,
+                pinned: false,
+                ranking: None,
+                alternates: vec![],
+                anchor: None,
+                children: vec![],
+            })
+            .collect();
+        self.redact(&mut results);
+        Ok(results
+            .into_iter()
+            .map(|result| (result.document.id.clone(), result))
+            .collect())
+    }
+
+    /// List attachments, comments and transcript segments under their parent's result, as the
+    /// request asks. Rolled up children whose parent did not match bring in the parent.
+    async fn nest_children(
+        &self,
+        request: &SearchRequest,
+        results: Vec<SearchResult>,
+    ) -> Result<Vec<SearchResult>> {
+        let child_results = request.child_results();
+        if child_results == ChildResults::Flat || results.is_empty() {
+            return Ok(results);
+        }
+        let document_ids: Vec<String> = results.iter().map(|r| r.document.id.clone()).collect();
+        let relationships = DocumentRelationshipRepository::new(self.db_pool.pool())
+            .find_parents(&document_ids)
+            .await?;
+        if relationships.is_empty() {
+            return Ok(results);
+        }
+
+        let mut unranked = HashMap::new();
+        if child_results == ChildResults::RolledUp {
+            let missing: Vec<String> = relationships
+                .iter()
+                .map(|r| r.parent_document_id.clone())
+                .filter(|id| !document_ids.contains(id))
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+            if !missing.is_empty() {
+                unranked = self.find_unranked(request, &missing, "parent").await?;
+            }
+        }
+        Ok(relationships::nest(results, &relationships, unranked))
+    }
+
     pub async fn search(&self, request: SearchRequest) -> Result<SearchResponse> {
         let start_time = Instant::now();
 
@@ -115,73 +398,147 @@ impl SearchEngine {
             return self.read_document_by_id(document_id, &request).await;
         }
 
+        let mut request = request;
+        request.feature_flags = feature_flags::evaluate_all(&FlagContext::for_user(
+            request.user_email().map(|e| e.as_str()),
+        ));
+        if !self.resolve_collection(&mut request).await? {
+            info!(
+                "Query '{}' is limited to a collection the user cannot view",
+                request.query
+            );
+            return Ok(SearchResponse {
+                results: vec![],
+                total_count: 0,
+                query_time_ms: start_time.elapsed().as_millis() as u64,
+                has_more: false,
+                query: request.query.clone(),
+                facets: None,
+                fallback: None,
+                degraded: false,
+            });
+        }
+        request.query_expansions = AcronymDictionary::load(&self.db_pool)
+            .await?
+            .expansions(&request.query);
+        if !request.query_expansions.is_empty() {
+            debug!(
+                "Expanded acronyms in query '{}' with {:?}",
+                request.query, request.query_expansions
+            );
+        }
+
+        let boosts = match request.search_mode() {
+            SearchMode::Hybrid if self.config.tuning.ranking_boosts => {
+                ranking::load_boosts(&self.db_pool).await?
+            }
+            _ => RankingBoosts::default(),
+        };
+
+        if self.config.warmup_interval_seconds.is_some() && !request.warmup {
+            warmup::remember_query(&self.redis_client, &request.query);
+        }
+
         // Generate cache key based on request parameters
-        let cache_key = self.generate_cache_key(&request);
-
-        // Try to get from cache first
-        if let Ok(mut conn) = self.redis_client.get_multiplexed_async_connection().await {
-            if let Ok(cached_response) = conn.get::<_, String>(&cache_key).await {
-                if let Ok(response) = serde_json::from_str::<SearchResponse>(&cached_response) {
-                    info!("Cache hit for query: '{}'", request.query);
-                    return Ok(response);
+        let cache_key = self.generate_cache_key(&request, &boosts);
+
+        // Try to get from cache first, unless warming it
+        let cache_start = Instant::now();
+        let mut cached = None;
+        if !request.warmup {
+            if let Ok(mut conn) = self.redis_client.get_multiplexed_async_connection().await {
+                if let Ok(cached_response) = conn.get::<_, String>(&cache_key).await {
+                    if let Ok(response) = serde_json::from_str::<SearchResponse>(&cached_response) {
+                        cached = Some(response);
+                    }
                 }
             }
         }
+        let cache_ms = cache_start.elapsed().as_millis() as u64;
+
+        if let Some(mut response) = cached {
+            info!("Cache hit for query: '{}'", request.query);
+            self.redact(&mut response.results);
+            response.results = self.promote(&request, response.results).await?;
+            self.attach_previews(&mut response.results).await;
+            self.query_logger.record(
+                &request,
+                response.results.len(),
+                start_time.elapsed().as_millis() as u64,
+                LatencyBreakdown {
+                    cache_ms,
+                    ..Default::default()
+                },
+                true,
+            );
+            return Ok(response);
+        }
 
-        let repo = DocumentRepository::new(self.db_pool.pool());
+        let repo = self.document_repo(&request);
         let limit = request.limit();
 
         if request.query.trim().is_empty() {
             return Err(anyhow::anyhow!("Search query cannot be empty"));
         }
 
+        let cost = QueryCost::admit(&self.config.budget);
+        cost.record_latency(|latency| latency.cache_ms = cache_ms);
         let source_ids = repo
             .fetch_active_source_ids(request.source_types.as_deref())
             .await?;
 
-        let search_future = async {
-            let start_ts = Instant::now();
-            let res = match request.search_mode() {
-                SearchMode::Fulltext => self.fulltext_search(&repo, &request, &source_ids).await,
-                SearchMode::Semantic => self.semantic_search(&request).await,
-                SearchMode::Hybrid => self.hybrid_search(&request).await,
-            };
-
-            debug!("Search future completed in: {:?}", start_ts.elapsed());
-            res
-        };
-
-        let facets_future = async {
-            if request.include_facets() {
-                let start_ts = Instant::now();
-                let content_types = request.content_types.as_deref();
-                let attribute_filters = request.attribute_filters.as_ref();
-                let facets = repo
-                    .get_facet_counts(
-                        &request.query,
-                        &source_ids,
-                        content_types,
-                        attribute_filters,
-                        request.user_email().map(|e| e.as_str()),
-                    )
-                    .await
-                    .unwrap_or_else(|e| {
-                        info!("Failed to get facet counts: {}", e);
-                        vec![]
-                    });
-
-                debug!("Facets fetched in {:?}", start_ts.elapsed());
-                facets
-            } else {
-                debug!("Facets not requested, returning empty array.");
-                vec![]
+        let start_ts = Instant::now();
+        let mut results = self
+            .run_search(&repo, &request, &source_ids, &boosts, &cost)
+            .await?;
+        debug!("Search completed in: {:?}", start_ts.elapsed());
+
+        let mut fallback = None;
+        let mut relaxed = None;
+        if results.is_empty()
+            && request.offset() == 0
+            && request.fallback()
+            && !cost.full_text_only()
+        {
+            if let Some((strategy, relaxed_request, relaxed_source_ids, fallback_results)) = self
+                .search_with_fallbacks(&repo, &request, &boosts, &cost)
+                .await?
+            {
+                fallback = Some(strategy);
+                results = fallback_results;
+                relaxed = Some((relaxed_request, relaxed_source_ids));
             }
+        }
+        // Facets are counted for the request that found the results, relaxed by the fallback
+        // when there was one
+        let facets = if request.include_facets() && cost.expired() {
+            cost.degrade("deadline passed before facets were counted");
+            vec![]
+        } else if request.include_facets() {
+            let (facet_request, facet_source_ids) = match &relaxed {
+                Some((relaxed_request, relaxed_source_ids)) => {
+                    (relaxed_request, relaxed_source_ids)
+                }
+                None => (&request, &source_ids),
+            };
+            self.facet_counter
+                .counts(facet_request, facet_source_ids, &results)
+                .await
+                .unwrap_or_else(|e| {
+                    info!("Failed to get facet counts: {}", e);
+                    vec![]
+                })
+        } else {
+            debug!("Facets not requested, returning empty array.");
+            vec![]
         };
 
-        let (search_result, facets) = tokio::join!(search_future, facets_future);
-        let results = search_result?;
-        let total_count = results.len() as i64;
+        // Checked before collapsing duplicates, which can shorten a full page
         let has_more = results.len() as i64 >= limit;
+        let mut results = self.collapse_duplicates(&request, results).await?;
+        self.redact(&mut results);
+        let results = self.nest_children(&request, results).await?;
+        let total_count = results.len() as i64;
         let query_time = start_time.elapsed().as_millis() as u64;
 
         info!(
@@ -190,7 +547,7 @@ impl SearchEngine {
             results.len()
         );
 
-        let response = SearchResponse {
+        let mut response = SearchResponse {
             results,
             total_count,
             query_time_ms: query_time,
@@ -201,37 +558,145 @@ impl SearchEngine {
             } else {
                 Some(facets)
             },
+            fallback,
+            degraded: cost.is_degraded(),
         };
 
-        // Cache the response for 5 minutes
-        if let Ok(mut conn) = self.redis_client.get_multiplexed_async_connection().await {
-            if let Ok(response_json) = serde_json::to_string(&response) {
-                let _: Result<(), _> = conn.set_ex(&cache_key, response_json, 300).await;
+        // Cache the response, unless it is missing results the next search may find within
+        // budget
+        if !response.degraded {
+            if let Ok(mut conn) = self.redis_client.get_multiplexed_async_connection().await {
+                if let Ok(response_json) = serde_json::to_string(&response) {
+                    let _: Result<(), _> = conn
+                        .set_ex(&cache_key, response_json, SEARCH_CACHE_TTL_SECS)
+                        .await;
+                }
             }
         }
 
+        // Promotions are applied after caching so rule changes take effect immediately
+        response.results = self.promote(&request, response.results).await?;
+        self.attach_previews(&mut response.results).await;
+
+        if !request.warmup {
+            self.query_logger.record(
+                &request,
+                response.results.len(),
+                query_time,
+                cost.latency(),
+                false,
+            );
+        }
+
         Ok(response)
     }
 
+    async fn run_search(
+        &self,
+        repo: &DocumentRepository,
+        request: &SearchRequest,
+        source_ids: &[String],
+        boosts: &RankingBoosts,
+        cost: &QueryCost,
+    ) -> Result<Vec<SearchResult>> {
+        let mode = if cost.full_text_only() {
+            &SearchMode::Fulltext
+        } else {
+            request.search_mode()
+        };
+        let search = async {
+            match mode {
+                SearchMode::Fulltext => self.fulltext_search(repo, request, source_ids, cost).await,
+                SearchMode::Semantic => self.semantic_search(request, cost).await,
+                SearchMode::Hybrid => self.hybrid_search(request, boosts, cost).await,
+            }
+        };
+        match tokio::time::timeout(cost.remaining(), search).await {
+            Ok(results) => results,
+            Err(_) => {
+                cost.degrade("deadline passed before the search found results");
+                Ok(vec![])
+            }
+        }
+    }
+
+    /// Retry a search that found nothing with each fallback strategy in turn, returning the
+    /// results of the first strategy that finds any, with the relaxed request and the sources
+    /// it searched.
+    async fn search_with_fallbacks(
+        &self,
+        repo: &DocumentRepository,
+        request: &SearchRequest,
+        boosts: &RankingBoosts,
+        cost: &QueryCost,
+    ) -> Result<
+        Option<(
+            FallbackStrategy,
+            SearchRequest,
+            Vec<String>,
+            Vec<SearchResult>,
+        )>,
+    > {
+        let mut relaxed = request.clone();
+        for strategy in fallback::STRATEGIES {
+            if cost.expired() {
+                cost.degrade("deadline passed before fallbacks found results");
+                break;
+            }
+            if strategy == FallbackStrategy::SpellCorrected && !self.config.tuning.typo_tolerance {
+                continue;
+            }
+            let Some(next) = fallback::relax(&relaxed, strategy) else {
+                continue;
+            };
+            relaxed = next;
+            let source_ids = repo
+                .fetch_active_source_ids(relaxed.source_types.as_deref())
+                .await?;
+            let results = self
+                .run_search(repo, &relaxed, &source_ids, boosts, cost)
+                .await?;
+            if !results.is_empty() {
+                info!(
+                    "Query '{}' found nothing, falling back to {:?} found {} results",
+                    request.query,
+                    strategy,
+                    results.len()
+                );
+                return Ok(Some((strategy, relaxed, source_ids, results)));
+            }
+        }
+        Ok(None)
+    }
+
     async fn fulltext_search(
         &self,
         repo: &DocumentRepository,
         request: &SearchRequest,
         source_ids: &[String],
+        cost: &QueryCost,
     ) -> Result<Vec<SearchResult>> {
+        let limit = cost.fulltext_limit(request.offset(), request.limit());
+        if limit == 0 {
+            return Ok(vec![]);
+        }
         let start_time = Instant::now();
         let content_types = request.content_types.as_deref();
         let attribute_filters = request.attribute_filters.as_ref();
 
-        debug!("Running fulltext search for {}", &request.query);
+        let query = request.fulltext_query();
+        debug!("Running fulltext search for {}", query);
         let search_hits = repo
             .search(
-                &request.query,
+                &query,
                 source_ids,
                 content_types,
                 attribute_filters,
-                request.limit(),
+                request.fields(),
+                request.fuzzy,
+                limit,
                 request.offset(),
+                request.collection_id(),
                 request.user_email().map(|e| e.as_str()),
                 request.document_id.as_deref(),
             )
@@ -251,48 +716,78 @@ impl SearchEngine {
                 .content_snippets
                 .unwrap_or_default()
                 .into_iter()
+                .map(|s| highlighting::trim_partial_clusters(&s).to_string())
                 .filter(|s| !s.is_empty())
                 .collect::<Vec<String>>();
 
+            let score = search_hit.score;
             results.push(SearchResult {
                 document: prepared_doc,
-                score: search_hit.score as f32,
+                score,
                 highlights,
                 match_type: "fulltext".to_string(),
                 content: None,
+                pinned: false,
+                ranking: request.explain().then(|| RankingExplanation {
+                    fts_rank: Some(score),
+                    fulltext_score: Some(score),
+                    ..Default::default()
+                }),
+                alternates: vec![],
+                anchor: None,
+                children: vec![],
             });
         }
 
-        info!(
-            "Fulltext search completed in {}ms",
-            start_time.elapsed().as_millis()
-        );
+        let fts_ms = start_time.elapsed().as_millis() as u64;
+        cost.record_latency(|latency| latency.fts_ms = Some(fts_ms));
+        info!("Fulltext search completed in {}ms", fts_ms);
         Ok(results)
     }
 
-    async fn semantic_search(&self, request: &SearchRequest) -> Result<Vec<SearchResult>> {
+    async fn semantic_search(
+        &self,
+        request: &SearchRequest,
+        cost: &QueryCost,
+    ) -> Result<Vec<SearchResult>> {
+        let limit = cost.vector_limit(request.offset(), request.limit());
+        if limit == 0 {
+            return Ok(vec![]);
+        }
         let start_time = Instant::now();
         info!("Performing semantic search for query: '{}'", request.query);
 
-        let query_embedding = self.generate_query_embedding(&request.query).await?;
+        let query_embedding = self.active_query_embedding(request).await?;
 
-        let embedding_repo = EmbeddingRepository::new(self.db_pool.pool());
-        let doc_repo = DocumentRepository::new(self.db_pool.pool());
+        let embedding_repo = self.embedding_repo(request);
+        let doc_repo = self.document_repo(request);
 
         let sources = request.source_types.as_deref();
         let content_types = request.content_types.as_deref();
 
         let chunk_results = embedding_repo
             .find_similar_with_filters(
-                query_embedding,
+                query_embedding.vector,
                 sources,
                 content_types,
-                request.limit(),
+                limit,
                 request.offset(),
+                request.collection_id(),
                 request.user_email().map(|e| e.as_str()),
                 request.document_id.as_deref(),
+                Some(query_embedding.model_name.as_str()),
+                Some(cost.max_vector_comparisons()),
             )
             .await?;
+        let active_search = ActiveSearch {
+            model_name: query_embedding.model_name,
+            document_ids: ranked_document_ids(&chunk_results),
+            top_score: chunk_results.first().map(|chunk| chunk.similarity_score),
+            elapsed_ms: start_time.elapsed().as_millis() as u64,
+        };
+        if !request.warmup {
+            self.shadow_searcher.compare(request, active_search);
+        }
 
         // Get unique document IDs and batch fetch documents
         let document_ids: Vec<String> = chunk_results
@@ -325,17 +820,37 @@ impl SearchEngine {
                     .iter()
                     .map(|chunk| chunk.similarity_score)
                     .fold(f32::NEG_INFINITY, f32::max);
+                let anchor = chunks
+                    .iter()
+                    .max_by(|a, b| {
+                        a.similarity_score
+                            .partial_cmp(&b.similarity_score)
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                    })
+                    .map(|chunk| ResultAnchor {
+                        chunk_index: chunk.chunk_index,
+                        start_offset: chunk.chunk_start_offset,
+                        end_offset: chunk.chunk_end_offset,
+                        ..Default::default()
+                    });
 
                 // Fetch document content and extract chunk text using offsets
                 let mut chunk_highlights: Vec<(f32, String)> = Vec::new();
                 if let Some(content_id) = &doc.content_id {
                     if let Ok(content) = self.content_storage.get_text(content_id).await {
-                        for chunk in chunks {
-                            let chunk_text = self.extract_chunk_from_content(
-                                &content,
+                        let source = ChunkSource::new(&content);
+                        for chunk in &chunks {
+                            let Some(chunk_text) = source.chunk(
                                 chunk.chunk_start_offset,
                                 chunk.chunk_end_offset,
-                            );
+                                chunk.content_sha256.as_deref(),
+                            ) else {
+                                debug!(
+                                    "Chunk {} of {} does not match its content, skipping",
+                                    chunk.chunk_index, document_id
+                                );
+                                continue;
+                            };
                             chunk_highlights
                                 .push((chunk.similarity_score, chunk_text.trim().to_string()));
                         }
@@ -353,6 +868,26 @@ impl SearchEngine {
                     .map(|(_, snippet)| snippet)
                     .collect();
 
+                let ranking = request.explain().then(|| {
+                    let mut chunk_similarities: Vec<ChunkSimilarity> = chunks
+                        .iter()
+                        .map(|chunk| ChunkSimilarity {
+                            chunk_index: chunk.chunk_index,
+                            similarity: chunk.similarity_score,
+                        })
+                        .collect();
+                    chunk_similarities.sort_by(|a, b| {
+                        b.similarity
+                            .partial_cmp(&a.similarity)
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                    });
+                    RankingExplanation {
+                        chunk_similarities,
+                        semantic_score: Some(max_score),
+                        ..Default::default()
+                    }
+                });
+
                 let prepared_doc = self.prepare_document_for_response(doc.clone());
                 results.push(SearchResult {
                     document: prepared_doc,
@@ -360,9 +895,15 @@ impl SearchEngine {
                     highlights: all_highlights,
                     match_type: "semantic".to_string(),
                     content: None, // Using highlights instead of single content snippet
+                    pinned: false,
+                    ranking,
+                    alternates: vec![],
+                    children: vec![],
+                    anchor,
                 });
             }
         }
+        self.locate_anchors(&mut results).await;
 
         // Sort results by score in descending order
         results.sort_by(|a, b| {
@@ -371,27 +912,46 @@ impl SearchEngine {
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
-        info!(
-            "Semantic search completed in {}ms",
-            start_time.elapsed().as_millis()
-        );
+        let vector_ms = start_time.elapsed().as_millis() as u64;
+        cost.record_latency(|latency| latency.vector_ms = Some(vector_ms));
+        info!("Semantic search completed in {}ms", vector_ms);
         Ok(results)
     }
 
-    fn extract_chunk_from_content(
-        &self,
-        content: &str,
-        start_offset: i32,
-        end_offset: i32,
-    ) -> String {
-        let start = start_offset as usize;
-        let end = end_offset as usize;
-
-        if start >= content.len() || end > content.len() || start >= end {
-            return String::new();
-        }
+    /// Fill in the heading and transcript timestamp each result's anchor chunk falls under.
+    async fn locate_anchors(&self, results: &mut [SearchResult]) {
+        let chunks: Vec<(String, i32)> = results
+            .iter()
+            .filter_map(|result| {
+                let anchor = result.anchor.as_ref()?;
+                Some((result.document.id.clone(), anchor.start_offset))
+            })
+            .collect();
+        let chunk_anchors = match DocumentAnchorRepository::new(self.db_pool.pool())
+            .find_at_offsets(&chunks)
+            .await
+        {
+            Ok(chunk_anchors) => chunk_anchors,
+            Err(e) => {
+                error!("Failed to look up result anchors: {}", e);
+                return;
+            }
+        };
 
-        safe_str_slice(content, start, end).to_string()
+        let by_document: HashMap<&str, _> = chunk_anchors
+            .iter()
+            .map(|chunk_anchor| (chunk_anchor.document_id.as_str(), chunk_anchor))
+            .collect();
+        for result in results.iter_mut() {
+            let Some(chunk_anchor) = by_document.get(result.document.id.as_str()) else {
+                continue;
+            };
+            if let Some(anchor) = result.anchor.as_mut() {
+                anchor.heading = chunk_anchor.heading.clone();
+                anchor.heading_level = chunk_anchor.heading_level;
+                anchor.timestamp_seconds = chunk_anchor.timestamp_seconds;
+            }
+        }
     }
 
     /// Read a specific document by ID, returning full content for small documents
@@ -404,14 +964,14 @@ impl SearchEngine {
         let start_time = Instant::now();
         info!("Reading document by ID: {}", document_id);
 
-        let doc_repo = DocumentRepository::new(self.db_pool.pool());
+        let doc_repo = self.document_repo(request);
         let doc = doc_repo
             .find_by_id(document_id)
             .await?
             .ok_or_else(|| anyhow::anyhow!("Document not found: {}", document_id))?;
 
         // Get actual content size (extracted text, not original file)
-        let results = if let Some(content_id) = &doc.content_id {
+        let mut results = if let Some(content_id) = &doc.content_id {
             match self.content_storage.get_text(content_id).await {
                 Ok(content) => {
                     let content_size = content.len();
@@ -427,6 +987,11 @@ impl SearchEngine {
                             highlights: vec![content],
                             match_type: "full_content".to_string(),
                             content: None,
+                            pinned: false,
+                            ranking: None,
+                            alternates: vec![],
+                            anchor: None,
+                            children: vec![],
                         }]
                     } else {
                         // Check if specific line range is requested
@@ -488,6 +1053,11 @@ impl SearchEngine {
                                     highlights: vec![selected_content],
                                     match_type: "line_range".to_string(),
                                     content: None,
+                                    pinned: false,
+                                    ranking: None,
+                                    alternates: vec![],
+                                    anchor: None,
+                                    children: vec![],
                                 }]
                             }
                             _ => {
@@ -515,6 +1085,7 @@ impl SearchEngine {
             vec![]
         };
 
+        self.redact(&mut results);
         let total_count = results.len() as i64;
         let query_time = start_time.elapsed().as_millis() as u64;
 
@@ -531,6 +1102,8 @@ impl SearchEngine {
             has_more: false,
             query: request.query.clone(),
             facets: None,
+            fallback: None,
+            degraded: false,
         })
     }
 
@@ -544,7 +1117,9 @@ impl SearchEngine {
         let results = if !request.query.trim().is_empty() {
             // Query provided: do hybrid search within document
             info!("Query provided, hybrid search within document");
-            self.hybrid_search(request).await?
+            let cost = QueryCost::admit(&self.config.budget);
+            self.hybrid_search(request, &RankingBoosts::default(), &cost)
+                .await?
         } else {
             info!(
                 "No query provided, returning first 500 lines from document ID {}",
@@ -563,10 +1138,8 @@ impl SearchEngine {
                     .join("\n");
 
                 // Apply character limit after line prefixing
-                let truncated: String = prefixed_content
-                    .chars()
-                    .take(Self::CONTENT_SIZE_THRESHOLD)
-                    .collect();
+                let truncated =
+                    highlighting::truncate(&prefixed_content, Self::CONTENT_SIZE_THRESHOLD);
 
                 vec![SearchResult {
                     document: doc.clone(),
@@ -574,6 +1147,11 @@ impl SearchEngine {
                     highlights: vec![truncated],
                     match_type: "fulltext".to_string(),
                     content: None,
+                    pinned: false,
+                    ranking: None,
+                    alternates: vec![],
+                    anchor: None,
+                    children: vec![],
                 }]
             } else {
                 error!(
@@ -587,26 +1165,6 @@ impl SearchEngine {
         Ok(results)
     }
 
-    async fn generate_query_embedding(&self, query: &str) -> Result<Vec<f32>> {
-        debug!("Generating query embeddings for query '{}'", query);
-        let embeddings = self
-            .ai_client
-            .generate_embeddings_with_options(
-                vec![query.to_string()],
-                Some("query".to_string()),
-                None,
-                Some("none".to_string()),
-                Some("high".to_string()), // High priority for search queries
-            )
-            .await?;
-        if let Some(first_embedding) = embeddings.first() {
-            if let Some(first_chunk) = first_embedding.chunk_embeddings.first() {
-                return Ok(first_chunk.clone());
-            }
-        }
-        Err(anyhow::anyhow!("Failed to generate embedding for query"))
-    }
-
     /// Get semantic search results enhanced with expanded context for RAG
     async fn get_enhanced_semantic_results_for_rag(
         &self,
@@ -618,9 +1176,9 @@ impl SearchEngine {
             request.query
         );
 
-        let query_embedding = self.generate_query_embedding(&request.query).await?;
-        let embedding_repo = EmbeddingRepository::new(self.db_pool.pool());
-        let doc_repo = DocumentRepository::new(self.db_pool.pool());
+        let query_embedding = self.active_query_embedding(request).await?;
+        let embedding_repo = self.embedding_repo(request);
+        let doc_repo = self.document_repo(request);
 
         let sources = request.source_types.as_deref();
         let content_types = request.content_types.as_deref();
@@ -628,13 +1186,16 @@ impl SearchEngine {
         // Get chunk results with indices
         let chunk_results = embedding_repo
             .find_similar_with_filters(
-                query_embedding,
+                query_embedding.vector,
                 sources,
                 content_types,
                 request.limit(),
                 request.offset(),
+                request.collection_id(),
                 request.user_email().map(|e| e.as_str()),
                 None,
+                Some(query_embedding.model_name.as_str()),
+                Some(self.config.budget.max_vector_comparisons),
             )
             .await?;
 
@@ -672,20 +1233,24 @@ impl SearchEngine {
                     .find_surrounding_chunks_for_document(
                         &document_id,
                         &chunk_indices,
-                        self.config.rag_context_window,
+                        self.config.tuning.rag_context_window,
+                        Some(query_embedding.model_name.as_str()),
                     )
                     .await?;
 
                 // Combine expanded chunks into continuous text
                 let expanded_context = if let Some(content_id) = &doc.content_id {
                     if let Ok(content) = self.content_storage.get_text(content_id).await {
+                        let source = ChunkSource::new(&content);
                         let mut chunk_texts = Vec::new();
                         for chunk in &expanded_chunks {
-                            let chunk_text = self.extract_chunk_from_content(
-                                &content,
-                                chunk.chunk_start_offset,
-                                chunk.chunk_end_offset,
-                            );
+                            let chunk_text = source
+                                .chunk(
+                                    chunk.chunk_start_offset,
+                                    chunk.chunk_end_offset,
+                                    chunk.content_sha256.as_deref(),
+                                )
+                                .unwrap_or_default();
                             if !chunk_text.trim().is_empty() {
                                 chunk_texts.push(chunk_text.trim().to_string());
                             }
@@ -709,6 +1274,11 @@ impl SearchEngine {
                     },
                     match_type: "semantic".to_string(),
                     content: None,
+                    pinned: false,
+                    ranking: None,
+                    alternates: vec![],
+                    anchor: None,
+                    children: vec![],
                 });
             }
         }
@@ -727,21 +1297,33 @@ impl SearchEngine {
         Ok(results)
     }
 
-    async fn hybrid_search(&self, request: &SearchRequest) -> Result<Vec<SearchResult>> {
+    async fn hybrid_search(
+        &self,
+        request: &SearchRequest,
+        boosts: &RankingBoosts,
+        cost: &QueryCost,
+    ) -> Result<Vec<SearchResult>> {
         info!("Performing hybrid search for query: '{}'", request.query);
         let start_time = Instant::now();
 
-        let repo = DocumentRepository::new(self.db_pool.pool());
+        let repo = self.document_repo(request);
         let source_ids = repo
             .fetch_active_source_ids(request.source_types.as_deref())
             .await?;
-        let fts_future = self.fulltext_search(&repo, request, &source_ids);
-
-        // Apply timeout to semantic search
-        let semantic_future = tokio::time::timeout(
-            std::time::Duration::from_millis(self.config.semantic_search_timeout_ms),
-            self.semantic_search(request),
-        );
+        let fts_future = self.fulltext_search(&repo, request, &source_ids, cost);
+
+        // Semantic matches come from content embeddings, so they only count when content is
+        // searched. Apply timeout to semantic search, ending it by the deadline
+        let search_content = request.fields().contains(&SearchField::Content);
+        let semantic_timeout = cost.stage_timeout(std::time::Duration::from_millis(
+            self.config.tuning.semantic_search_timeout_ms,
+        ));
+        let semantic_future = async {
+            if !search_content {
+                return Ok(Ok(vec![]));
+            }
+            tokio::time::timeout(semantic_timeout, self.semantic_search(request, cost)).await
+        };
 
         let (fts_results, semantic_results) = tokio::join!(fts_future, semantic_future);
         let fts_results = fts_results?;
@@ -754,13 +1336,16 @@ impl SearchEngine {
                 vec![]
             }
             Err(_) => {
-                info!(
-                    "Semantic search timed out after {}ms, falling back to FTS only",
-                    self.config.semantic_search_timeout_ms
-                );
+                let timeout_ms = semantic_timeout.as_millis() as u64;
+                cost.degrade(&format!(
+                    "semantic search timed out after {}ms, falling back to FTS only",
+                    timeout_ms
+                ));
+                cost.record_latency(|latency| latency.vector_ms = Some(timeout_ms));
                 vec![]
             }
         };
+        let rerank_start = Instant::now();
         info!("Retrieved {} results from FTS", fts_results.len());
         info!(
             "Retrieved {} results from semantic search",
@@ -771,9 +1356,19 @@ impl SearchEngine {
         let mut combined_results = HashMap::new();
 
         // Add FTS results with normalized scores
-        for result in fts_results {
+        let tuning = &self.config.tuning;
+        let normalized_scores: Vec<f32> = fts_results
+            .iter()
+            .map(|result| self.normalize_fts_score(result.score))
+            .collect();
+        let fts_scores =
+            ranking::fused_scores(tuning, tuning.hybrid_search_fts_weight, &normalized_scores);
+        for ((result, normalized_score), fts_score) in fts_results
+            .into_iter()
+            .zip(normalized_scores)
+            .zip(fts_scores)
+        {
             let doc_id = result.document.id.clone();
-            let normalized_score = self.normalize_fts_score(result.score);
             debug!(
                 "FTS result document {} [id={}], score={}",
                 result.document.title, doc_id, normalized_score
@@ -783,16 +1378,30 @@ impl SearchEngine {
                 doc_id,
                 SearchResult {
                     document: prepared_doc,
-                    score: normalized_score * self.config.hybrid_search_fts_weight,
+                    score: fts_score,
                     highlights: result.highlights,
                     match_type: "fulltext".to_string(),
                     content: result.content,
+                    pinned: false,
+                    ranking: result.ranking.map(|ranking| RankingExplanation {
+                        fulltext_score: Some(fts_score),
+                        ..ranking
+                    }),
+                    alternates: vec![],
+                    anchor: None,
+                    children: vec![],
                 },
             );
         }
 
         // Add or update with semantic results
-        for result in semantic_results {
+        let semantic_scores: Vec<f32> = semantic_results.iter().map(|r| r.score).collect();
+        let semantic_scores = ranking::fused_scores(
+            tuning,
+            tuning.hybrid_search_semantic_weight,
+            &semantic_scores,
+        );
+        for (result, semantic_score) in semantic_results.into_iter().zip(semantic_scores) {
             let doc_id = result.document.id.clone();
 
             debug!(
@@ -802,7 +1411,14 @@ impl SearchEngine {
             match combined_results.get_mut(&doc_id) {
                 Some(existing) => {
                     // Combine scores for documents found in both searches
-                    existing.score += result.score * self.config.hybrid_search_semantic_weight;
+                    existing.score += semantic_score;
+                    if let (Some(ranking), Some(semantic_ranking)) =
+                        (existing.ranking.as_mut(), result.ranking)
+                    {
+                        ranking.chunk_similarities = semantic_ranking.chunk_similarities;
+                        ranking.semantic_score = Some(semantic_score);
+                    }
+                    existing.anchor = result.anchor;
                 }
                 None => {
                     // Add new semantic-only result
@@ -811,28 +1427,101 @@ impl SearchEngine {
                         doc_id,
                         SearchResult {
                             document: prepared_doc,
-                            score: result.score * self.config.hybrid_search_semantic_weight,
+                            score: semantic_score,
                             highlights: result.highlights,
                             match_type: "semantic".to_string(),
                             content: result.content,
+                            pinned: false,
+                            ranking: result.ranking.map(|ranking| RankingExplanation {
+                                semantic_score: Some(semantic_score),
+                                ..ranking
+                            }),
+                            alternates: vec![],
+                            anchor: result.anchor,
+                            children: vec![],
                         },
                     );
                 }
             }
         }
 
-        // Convert to vector and sort by combined score
-        let mut final_results: Vec<SearchResult> = combined_results.into_values().collect();
+        // Only the best matches so far are re-ranked when there are more than the budget allows
+        let mut candidates: Vec<SearchResult> = combined_results.into_values().collect();
+        candidates.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        cost.limit_rerank(&mut candidates);
+
+        // Open flags only count when a flag penalty is configured
+        let document_ids: Vec<String> = candidates.iter().map(|r| r.document.id.clone()).collect();
+        let open_flags = match boosts.flag_penalty {
+            Some(_) => {
+                DocumentFlagRepository::new(self.db_pool.pool())
+                    .count_open(&document_ids)
+                    .await?
+            }
+            None => HashMap::new(),
+        };
+        let link_authority_weight = self.config.tuning.link_authority_weight;
+        let inbound_links = if link_authority_weight > 0.0 {
+            DocumentLinkRepository::new(self.db_pool.pool())
+                .count_inbound(&document_ids)
+                .await?
+        } else {
+            HashMap::new()
+        };
+
+        // Apply the configured source, content type, flag, link and vote boosts
+        let mut final_results: Vec<SearchResult> = candidates
+            .into_iter()
+            .map(|mut result| {
+                let source_boost = boosts.source_boost(&result.document);
+                let content_type_boost = boosts.content_type_boost(&result.document);
+                let flag_boost =
+                    boosts.flag_boost(open_flags.get(&result.document.id).copied().unwrap_or(0));
+                let link_boost = ranking::link_boost(
+                    link_authority_weight,
+                    inbound_links.get(&result.document.id).copied().unwrap_or(0),
+                );
+                let vote_boost = boosts.vote_boost(&result.document);
+                result.score *=
+                    source_boost * content_type_boost * flag_boost * link_boost * vote_boost;
+                if let Some(ranking) = result.ranking.as_mut() {
+                    if tuning.hybrid_fusion == HybridFusion::Weighted {
+                        ranking.fts_weight = Some(tuning.hybrid_search_fts_weight);
+                        ranking.semantic_weight = Some(tuning.hybrid_search_semantic_weight);
+                    }
+                    ranking.source_boost = source_boost;
+                    ranking.content_type_boost = content_type_boost;
+                    ranking.flag_boost = flag_boost;
+                    ranking.link_boost = link_boost;
+                    ranking.vote_boost = vote_boost;
+                }
+                result
+            })
+            .collect();
+
+        // Sort by combined score
         final_results.sort_by(|a, b| {
             b.score
                 .partial_cmp(&a.score)
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
+        if let Some(top_n) = self.config.rerank_top_n.filter(|_| request.rerank()) {
+            let top_n = top_n.min(final_results.len());
+            self.cross_encoder_rerank(request, &mut final_results[..top_n], cost)
+                .await;
+        }
+
         // Apply limit
         if final_results.len() > request.limit() as usize {
             final_results.truncate(request.limit() as usize);
         }
+        let rerank_ms = rerank_start.elapsed().as_millis() as u64;
+        cost.record_latency(|latency| latency.rerank_ms = Some(rerank_ms));
 
         info!(
             "Hybrid search completed in {}ms",
@@ -841,14 +1530,40 @@ impl SearchEngine {
         Ok(final_results)
     }
 
+    /// Order results by the AI service's cross-encoder scores. Results keep their hybrid order
+    /// when reranking fails or times out.
+    async fn cross_encoder_rerank(
+        &self,
+        request: &SearchRequest,
+        results: &mut [SearchResult],
+        cost: &QueryCost,
+    ) {
+        if results.len() < 2 {
+            return;
+        }
+        let passages = results.iter().map(ranking::rerank_passage).collect();
+        let timeout = cost.stage_timeout(std::time::Duration::from_millis(
+            self.config.rerank_timeout_ms,
+        ));
+        match tokio::time::timeout(timeout, self.ai_client.rerank(&request.query, passages)).await {
+            Ok(Ok(scores)) => ranking::rerank(results, &scores),
+            Ok(Err(e)) => warn!("Reranking failed: {}, keeping hybrid order", e),
+            Err(_) => cost.degrade(&format!(
+                "reranking timed out after {}ms, keeping hybrid order",
+                timeout.as_millis()
+            )),
+        }
+    }
+
     fn normalize_fts_score(&self, score: f32) -> f32 {
         // TODO
         score
     }
 
-    fn generate_cache_key(&self, request: &SearchRequest) -> String {
+    fn generate_cache_key(&self, request: &SearchRequest, boosts: &RankingBoosts) -> String {
         let mut hasher = DefaultHasher::new();
         request.query.hash(&mut hasher);
+        request.query_expansions.hash(&mut hasher);
         request.search_mode().hash(&mut hasher);
         request.limit().hash(&mut hasher);
         request.offset().hash(&mut hasher);
@@ -866,6 +1581,7 @@ impl SearchEngine {
         }
 
         request.include_facets().hash(&mut hasher);
+        request.fields().hash(&mut hasher);
 
         if let Some(attribute_filters) = &request.attribute_filters {
             let json = serde_json::to_string(attribute_filters).unwrap_or_default();
@@ -876,7 +1592,36 @@ impl SearchEngine {
             user_email.hash(&mut hasher);
         }
 
-        format!("search:{:x}", hasher.finish())
+        // Collections are updated when their documents change, so searches of a changed
+        // collection are not answered from the cache
+        if let Some(collection) = &request.collection_filter {
+            collection.id.hash(&mut hasher);
+            collection.updated_at.hash(&mut hasher);
+        }
+
+        request.explain().hash(&mut hasher);
+        request.fallback().hash(&mut hasher);
+        // Whether results are reranked, and how many
+        self.config
+            .rerank_top_n
+            .filter(|_| request.rerank())
+            .hash(&mut hasher);
+        request.child_results().hash(&mut hasher);
+        request.feature_flags.hash(&mut hasher);
+        if !boosts.is_empty() {
+            let json = serde_json::to_string(boosts).unwrap_or_default();
+            json.hash(&mut hasher);
+        }
+        // Results depend on the tunables, which change while the searcher runs
+        let tuning = serde_json::to_string(&self.config.tuning).unwrap_or_default();
+        tuning.hash(&mut hasher);
+
+        cache::keys().cache(cache::SEARCH_CACHE, format_args!("{:x}", hasher.finish()))
+    }
+
+    /// The stored text of a document's content.
+    pub async fn document_text(&self, content_id: &str) -> Result<String> {
+        Ok(self.content_storage.get_text(content_id).await?)
     }
 
     /// Store search history for a user in Redis
@@ -886,7 +1631,7 @@ impl SearchEngine {
             return Ok(());
         }
 
-        let key = format!("search_history:{}", user_id);
+        let key = cache::keys().user(user_id, cache::SEARCH_HISTORY);
         let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
 
         // Get all existing searches
@@ -911,8 +1656,7 @@ impl SearchEngine {
                 let _: () = conn.rpush(&key, search).await?;
             }
 
-            // Set TTL to 30 days for the search history
-            let _: () = conn.expire(&key, 30 * 24 * 60 * 60).await?;
+            let _: () = conn.expire(&key, cache::ttl::SEARCH_HISTORY as i64).await?;
         }
 
         debug!(
@@ -925,7 +1669,7 @@ impl SearchEngine {
 
     /// Get recent searches for a user from Redis
     pub async fn get_recent_searches(&self, user_id: &str) -> Result<RecentSearchesResponse> {
-        let key = format!("search_history:{}", user_id);
+        let key = cache::keys().user(user_id, cache::SEARCH_HISTORY);
         let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
 
         // Get all searches (up to 5 as we maintain that limit)
@@ -944,11 +1688,14 @@ impl SearchEngine {
     pub async fn get_rag_context(&self, request: &SearchRequest) -> Result<Vec<SearchResult>> {
         info!("Generating RAG context for query: '{}'", request.query);
 
-        let repo = DocumentRepository::new(self.db_pool.pool());
+        let repo = self.document_repo(request);
         let source_ids = repo
             .fetch_active_source_ids(request.source_types.as_deref())
             .await?;
-        let fts_results = self.fulltext_search(&repo, request, &source_ids).await?;
+        let cost = QueryCost::admit(&self.config.budget);
+        let fts_results = self
+            .fulltext_search(&repo, request, &source_ids, &cost)
+            .await?;
 
         // Get semantic search results enhanced with expanded context for RAG
         let semantic_results = self.get_enhanced_semantic_results_for_rag(request).await?;
@@ -974,6 +1721,7 @@ impl SearchEngine {
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
         combined_results.truncate(10);
+        self.redact(&mut combined_results);
 
         info!(
             "Generated RAG context with {} chunks",
@@ -989,7 +1737,10 @@ impl SearchEngine {
 
         let mut hasher = DefaultHasher::new();
         query.trim().to_lowercase().hash(&mut hasher);
-        format!("ai_answer:{:x}", hasher.finish())
+        cache::keys().cache(
+            cache::AI_ANSWER_CACHE,
+            format_args!("{:x}", hasher.finish()),
+        )
     }
 
     /// Build RAG prompt with context chunks and citation instructions
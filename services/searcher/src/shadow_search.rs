@@ -0,0 +1,181 @@
+use crate::models::SearchRequest;
+use crate::query_log::{hash_query, is_sampled};
+use crate::search::embed_query;
+use shared::clients::ai::EmbeddingModelRole;
+use shared::db::repositories::{EmbeddingRepository, ShadowComparison, ShadowComparisonRepository};
+use shared::embedding_migration::load_migration;
+use shared::models::ChunkResult;
+use shared::{AIClient, DatabasePool};
+use std::collections::HashSet;
+use std::time::Instant;
+use time::OffsetDateTime;
+use tracing::{debug, error, warn};
+
+/// Outcome of a semantic search against the active embedding model, to compare the shadow
+/// model's results with.
+#[derive(Debug, Clone)]
+pub struct ActiveSearch {
+    pub model_name: String,
+    /// Matching documents, best first.
+    pub document_ids: Vec<String>,
+    pub top_score: Option<f32>,
+    pub elapsed_ms: u64,
+}
+
+/// Repeats a sampled fraction of semantic searches against the shadow model while an
+/// embedding model migration is in progress, recording how the two models' results compare
+/// to the `embedding_shadow_comparisons` table.
+#[derive(Clone)]
+pub struct ShadowSearcher {
+    db_pool: DatabasePool,
+    ai_client: AIClient,
+}
+
+impl ShadowSearcher {
+    pub fn new(db_pool: DatabasePool, ai_client: AIClient) -> Self {
+        Self { db_pool, ai_client }
+    }
+
+    /// Compare a finished semantic search with the shadow model, if a migration is in
+    /// progress and the search is sampled. Runs in the background, so it never slows down or
+    /// fails the search.
+    pub fn compare(&self, request: &SearchRequest, active: ActiveSearch) {
+        let searcher = self.clone();
+        let request = request.clone();
+        tokio::spawn(async move {
+            if let Err(e) = searcher.run(&request, active).await {
+                warn!("Shadow search failed: {:#}", e);
+            }
+        });
+    }
+
+    async fn run(&self, request: &SearchRequest, active: ActiveSearch) -> anyhow::Result<()> {
+        let Some(migration) = load_migration(self.db_pool.pool()).await? else {
+            return Ok(());
+        };
+        if !is_sampled(migration.shadow_sample_rate()) {
+            return Ok(());
+        }
+
+        let start_time = Instant::now();
+        let query_embedding =
+            embed_query(&self.ai_client, &request.query, EmbeddingModelRole::Shadow).await?;
+        if query_embedding.model_name == active.model_name {
+            // The services have not all picked up a cutover yet
+            debug!(
+                "Skipping shadow search, both models are {}",
+                active.model_name
+            );
+            return Ok(());
+        }
+
+        let chunk_results = EmbeddingRepository::new(self.db_pool.pool())
+            .find_similar_with_filters(
+                query_embedding.vector,
+                request.source_types.as_deref(),
+                request.content_types.as_deref(),
+                request.limit(),
+                request.offset(),
+                request.collection_id(),
+                request.user_email().map(|e| e.as_str()),
+                request.document_id.as_deref(),
+                Some(query_embedding.model_name.as_str()),
+                None,
+            )
+            .await?;
+        let shadow_ms = start_time.elapsed().as_millis() as u64;
+        let shadow_document_ids = ranked_document_ids(&chunk_results);
+        let k = request.limit() as usize;
+
+        let comparison = ShadowComparison {
+            id: String::new(),
+            query_hash: hash_query(&request.query),
+            active_model: active.model_name,
+            shadow_model: query_embedding.model_name,
+            k: k as i32,
+            overlap: overlap_at_k(&active.document_ids, &shadow_document_ids, k) as i32,
+            active_result_count: active.document_ids.len() as i32,
+            shadow_result_count: shadow_document_ids.len() as i32,
+            active_top_score: active.top_score,
+            shadow_top_score: chunk_results.first().map(|chunk| chunk.similarity_score),
+            active_ms: active.elapsed_ms as i32,
+            shadow_ms: shadow_ms as i32,
+            created_at: OffsetDateTime::now_utc(),
+        };
+
+        match ShadowComparisonRepository::new(self.db_pool.pool())
+            .create(comparison)
+            .await
+        {
+            Ok(comparison) => debug!(
+                "Recorded shadow search comparison {} ({} of top {} shared)",
+                comparison.id, comparison.overlap, comparison.k
+            ),
+            Err(e) => error!("Failed to record shadow search comparison: {}", e),
+        }
+
+        Ok(())
+    }
+}
+
+/// Documents of the matched chunks, best first. Chunks arrive ordered by distance, so a
+/// document ranks by its best chunk.
+pub fn ranked_document_ids(chunks: &[ChunkResult]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    chunks
+        .iter()
+        .filter(|chunk| seen.insert(chunk.document_id.as_str()))
+        .map(|chunk| chunk.document_id.clone())
+        .collect()
+}
+
+/// Number of documents in both top `k` lists.
+pub fn overlap_at_k(active: &[String], shadow: &[String], k: usize) -> usize {
+    let active: HashSet<&String> = active.iter().take(k).collect();
+    shadow
+        .iter()
+        .take(k)
+        .filter(|document_id| active.contains(document_id))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(document_id: &str, similarity_score: f32) -> ChunkResult {
+        ChunkResult {
+            document_id: document_id.to_string(),
+            similarity_score,
+            chunk_start_offset: 0,
+            chunk_end_offset: 10,
+            chunk_index: 0,
+            content_sha256: None,
+        }
+    }
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_ranked_document_ids_keeps_best_chunk_order() {
+        let chunks = vec![
+            chunk("b", 0.9),
+            chunk("a", 0.8),
+            chunk("b", 0.7),
+            chunk("c", 0.6),
+        ];
+        assert_eq!(ranked_document_ids(&chunks), ids(&["b", "a", "c"]));
+        assert!(ranked_document_ids(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_overlap_at_k() {
+        let active = ids(&["a", "b", "c", "d"]);
+        let shadow = ids(&["c", "a", "e", "b"]);
+        assert_eq!(overlap_at_k(&active, &shadow, 4), 3);
+        assert_eq!(overlap_at_k(&active, &shadow, 2), 1);
+        assert_eq!(overlap_at_k(&active, &[], 4), 0);
+    }
+}
@@ -6,12 +6,12 @@ use futures_util::StreamExt;
 use redis::AsyncCommands;
 use redis::Client as RedisClient;
 use shared::utils::safe_str_slice;
-use shared::{AIClient, DatabasePool, DocumentRepository, ObjectStorage};
+use shared::{cache, AIClient, DatabasePool, DocumentRepository, ObjectStorage};
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 
-const REDIS_CACHE_KEY: &str = "suggested_questions:v1";
-const CACHE_TTL_SECONDS: u64 = 86400; // 7 days
+const CACHE_KIND: &str = "suggested_questions:v1";
+const CACHE_TTL_SECONDS: u64 = 24 * 60 * 60;
 const MAX_RETRIES: usize = 5;
 const QUESTION_PROMPT_TEMPLATE: &str = r#"Given the following document excerpt, generate ONE specific question or instruction related to the content.
 If you choose to generate a question, it should be natural, specific, and answerable by the document content. 
@@ -53,7 +53,7 @@ impl SuggestedQuestionsGenerator {
         let mut redis = self.redis_client.get_multiplexed_async_connection().await?;
 
         if let Ok(cached) = redis
-            .get::<_, String>(format!("{}:{}", REDIS_CACHE_KEY, user_email))
+            .get::<_, String>(cache::keys().cache(CACHE_KIND, user_email))
             .await
         {
             info!("Cache hit for suggested questions");
@@ -215,7 +215,7 @@ impl SuggestedQuestionsGenerator {
                                     .await
                                     .context("Failed to connect to Redis")?;
 
-                                let cache_key = format!("{}:{}", REDIS_CACHE_KEY, user_email);
+                                let cache_key = cache::keys().cache(CACHE_KIND, user_email);
                                 debug!(
                                     "Caching questions in Redis with key: {}, TTL: {}s",
                                     cache_key, CACHE_TTL_SECONDS
@@ -0,0 +1,500 @@
+//! Microsoft Teams bot, answering searches and questions inside Teams chats.
+//!
+//! Teams delivers messages as Bot Framework activities to `POST /bots/teams/messages`. Every
+//! request carries a JWT signed by the Bot Framework, which is verified against its published
+//! signing keys before the activity is looked at. The sender's Azure AD identity is mapped to
+//! an Omni user so answers only draw on documents that user can see, and the reply is posted
+//! back to the conversation through the Bot Connector API.
+
+use anyhow::{anyhow, Context, Result};
+use axum::{extract::State, http::HeaderMap, http::StatusCode, Json};
+use futures_util::StreamExt;
+use jsonwebtoken::{decode, decode_header, jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shared::models::User;
+use shared::{TeamsBotConfig, UserRepository};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, error, info, warn};
+
+use crate::models::{SearchMode, SearchRequest};
+use crate::search::SearchEngine;
+use crate::AppState;
+
+const OPENID_METADATA_URL: &str =
+    "https://login.botframework.com/v1/.well-known/openidconfiguration";
+const BOT_FRAMEWORK_ISSUER: &str = "https://api.botframework.com";
+const BOT_CONNECTOR_SCOPE: &str = "https://api.botframework.com/.default";
+const SIGNING_KEYS_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Unknown key ids trigger a refresh, but no more often than this.
+const SIGNING_KEYS_MIN_REFRESH: Duration = Duration::from_secs(5 * 60);
+const SEARCH_RESULT_LIMIT: i64 = 5;
+
+const HELP_TEXT: &str = "Ask me a question and I'll answer from the documents you have access to \
+in Omni, or send `search <terms>` for the top matching documents.";
+
+/// A Bot Framework activity, limited to the fields the bot reads.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Activity {
+    #[serde(rename = "type")]
+    pub activity_type: String,
+    pub id: Option<String>,
+    pub service_url: String,
+    pub text: Option<String>,
+    pub from: ChannelAccount,
+    pub recipient: ChannelAccount,
+    pub conversation: ConversationAccount,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelAccount {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aad_object_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationAccount {
+    pub id: String,
+}
+
+/// Conversation member as returned by the Teams flavour of the Bot Connector API.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TeamsChannelAccount {
+    email: Option<String>,
+    user_principal_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BotFrameworkClaims {
+    #[serde(rename = "serviceurl")]
+    service_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenIdMetadata {
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+/// What the user asked the bot to do.
+#[derive(Debug, PartialEq)]
+enum BotCommand {
+    Help,
+    Search(String),
+    Ask(String),
+}
+
+impl BotCommand {
+    fn parse(text: &str) -> Self {
+        let text = strip_mentions(text);
+        let text = text.trim();
+        let lowercase = text.to_lowercase();
+
+        if text.is_empty() || lowercase == "help" {
+            return BotCommand::Help;
+        }
+        match lowercase.strip_prefix("search") {
+            Some("") => BotCommand::Help,
+            Some(rest) if rest.starts_with(char::is_whitespace) => {
+                BotCommand::Search(text["search".len()..].trim().to_string())
+            }
+            _ => BotCommand::Ask(text.to_string()),
+        }
+    }
+}
+
+/// Remove `<at>Name</at>` mentions, which Teams includes in the text of channel messages.
+fn strip_mentions(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("<at>") {
+        result.push_str(&rest[..start]);
+        match rest[start..].find("</at>") {
+            Some(end) => rest = &rest[start + end + "</at>".len()..],
+            None => {
+                rest = "";
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+struct SigningKeys {
+    keys: JwkSet,
+    fetched_at: Instant,
+}
+
+struct AccessToken {
+    token: String,
+    expires_at: Instant,
+}
+
+pub struct TeamsBot {
+    config: TeamsBotConfig,
+    http: reqwest::Client,
+    signing_keys: RwLock<Option<SigningKeys>>,
+    access_token: RwLock<Option<AccessToken>>,
+}
+
+impl TeamsBot {
+    pub fn new(config: TeamsBotConfig) -> Self {
+        Self {
+            config,
+            http: reqwest::Client::new(),
+            signing_keys: RwLock::new(None),
+            access_token: RwLock::new(None),
+        }
+    }
+
+    /// Verify the Bot Framework token sent with `activity`.
+    async fn authenticate(&self, headers: &HeaderMap, activity: &Activity) -> Result<()> {
+        let token = headers
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| anyhow!("Missing bearer token"))?;
+
+        let kid = decode_header(token)?
+            .kid
+            .ok_or_else(|| anyhow!("Token has no key id"))?;
+        let key = self.signing_key(&kid).await?;
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&[&self.config.app_id]);
+        validation.set_issuer(&[BOT_FRAMEWORK_ISSUER]);
+        let claims = decode::<BotFrameworkClaims>(token, &key, &validation)?.claims;
+
+        // Tokens are bound to the service the activity came from, so a captured token cannot
+        // be used to make the bot post replies somewhere else
+        if claims.service_url.as_deref() != Some(activity.service_url.as_str()) {
+            return Err(anyhow!("Token service URL does not match the activity"));
+        }
+        Ok(())
+    }
+
+    async fn signing_key(&self, kid: &str) -> Result<DecodingKey> {
+        {
+            let cached = self.signing_keys.read().await;
+            if let Some(cached) = cached.as_ref() {
+                let fresh = cached.fetched_at.elapsed() < SIGNING_KEYS_TTL;
+                match cached.keys.find(kid) {
+                    Some(jwk) if fresh => return Ok(DecodingKey::from_jwk(jwk)?),
+                    None if cached.fetched_at.elapsed() < SIGNING_KEYS_MIN_REFRESH => {
+                        return Err(anyhow!("Unknown signing key {}", kid));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let metadata: OpenIdMetadata = self
+            .http
+            .get(OPENID_METADATA_URL)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let keys: JwkSet = self
+            .http
+            .get(&metadata.jwks_uri)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        debug!("Fetched {} Bot Framework signing keys", keys.keys.len());
+
+        let key = keys
+            .find(kid)
+            .map(DecodingKey::from_jwk)
+            .transpose()?
+            .ok_or_else(|| anyhow!("Unknown signing key {}", kid));
+        *self.signing_keys.write().await = Some(SigningKeys {
+            keys,
+            fetched_at: Instant::now(),
+        });
+        key
+    }
+
+    /// Token for calling the Bot Connector API as this bot.
+    async fn access_token(&self) -> Result<String> {
+        if let Some(token) = self.access_token.read().await.as_ref() {
+            if token.expires_at > Instant::now() {
+                return Ok(token.token.clone());
+            }
+        }
+
+        let tenant = self
+            .config
+            .tenant_id
+            .as_deref()
+            .unwrap_or("botframework.com");
+        let response: TokenResponse = self
+            .http
+            .post(format!(
+                "https://login.microsoftonline.com/{}/oauth2/v2.0/token",
+                tenant
+            ))
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", self.config.app_id.as_str()),
+                ("client_secret", self.config.app_password.as_str()),
+                ("scope", BOT_CONNECTOR_SCOPE),
+            ])
+            .send()
+            .await?
+            .error_for_status()
+            .context("Failed to get a Bot Connector token")?
+            .json()
+            .await?;
+
+        // Renew a minute early so a token never expires mid-request
+        let lifetime = Duration::from_secs(response.expires_in.saturating_sub(60));
+        *self.access_token.write().await = Some(AccessToken {
+            token: response.access_token.clone(),
+            expires_at: Instant::now() + lifetime,
+        });
+        Ok(response.access_token)
+    }
+
+    fn connector_url(&self, activity: &Activity, segments: &[&str]) -> Result<reqwest::Url> {
+        let mut url = reqwest::Url::parse(&activity.service_url)?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow!("Invalid service URL {}", activity.service_url))?
+            .pop_if_empty()
+            .extend(segments);
+        Ok(url)
+    }
+
+    /// Map the sender to an active Omni user. Users provisioned over SCIM from Azure AD are
+    /// matched on their object id, anyone else on the email Teams reports for them.
+    async fn resolve_user(&self, state: &AppState, activity: &Activity) -> Result<Option<User>> {
+        let user_repo = UserRepository::new(state.db_pool.pool());
+
+        if let Some(aad_object_id) = &activity.from.aad_object_id {
+            if let Some(user) = user_repo.find_by_external_id(aad_object_id).await? {
+                return Ok(Some(user).filter(|u| u.is_active));
+            }
+        }
+
+        let url = self.connector_url(
+            activity,
+            &[
+                "v3",
+                "conversations",
+                &activity.conversation.id,
+                "members",
+                &activity.from.id,
+            ],
+        )?;
+        let member: TeamsChannelAccount = self
+            .http
+            .get(url)
+            .bearer_auth(self.access_token().await?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        for email in [member.email, member.user_principal_name]
+            .into_iter()
+            .flatten()
+        {
+            if let Some(user) = user_repo.find_by_email(&email.to_lowercase()).await? {
+                return Ok(Some(user).filter(|u| u.is_active));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn reply(&self, activity: &Activity, text: &str) -> Result<()> {
+        let mut segments = vec!["v3", "conversations", activity.conversation.id.as_str()];
+        segments.push("activities");
+        if let Some(id) = &activity.id {
+            segments.push(id);
+        }
+        let url = self.connector_url(activity, &segments)?;
+
+        self.http
+            .post(url)
+            .bearer_auth(self.access_token().await?)
+            .json(&json!({
+                "type": "message",
+                "textFormat": "markdown",
+                "text": text,
+                "replyToId": activity.id,
+                "from": activity.recipient,
+                "recipient": activity.from,
+                "conversation": activity.conversation,
+            }))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    async fn handle_message(&self, state: AppState, activity: Activity) -> Result<()> {
+        let command = BotCommand::parse(activity.text.as_deref().unwrap_or_default());
+        if command == BotCommand::Help {
+            return self.reply(&activity, HELP_TEXT).await;
+        }
+
+        let Some(user) = self.resolve_user(&state, &activity).await? else {
+            info!(
+                "No active Omni user for Teams user {:?}",
+                activity.from.aad_object_id
+            );
+            return self
+                .reply(
+                    &activity,
+                    "I couldn't find an Omni account for you. Ask your administrator for access.",
+                )
+                .await;
+        };
+
+        let search_engine = SearchEngine::new(
+            state.db_pool.clone(),
+            state.redis_client.clone(),
+            state.ai_client.clone(),
+            state.search_config(),
+            state.redaction.current(),
+        )
+        .await?;
+
+        let text = match command {
+            BotCommand::Search(query) => {
+                let response = search_engine
+                    .search(SearchRequest {
+                        query: query.clone(),
+                        limit: Some(SEARCH_RESULT_LIMIT),
+                        mode: Some(SearchMode::Hybrid),
+                        user_email: Some(user.email),
+                        user_id: Some(user.id),
+                        ..Default::default()
+                    })
+                    .await?;
+                if response.results.is_empty() {
+                    format!("No documents found for **{}**.", query)
+                } else {
+                    response
+                        .results
+                        .iter()
+                        .enumerate()
+                        .map(|(i, r)| match &r.document.url {
+                            Some(url) => format!("{}. [{}]({})", i + 1, r.document.title, url),
+                            None => format!("{}. {}", i + 1, r.document.title),
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }
+            }
+            BotCommand::Ask(question) => {
+                let request = SearchRequest {
+                    query: question.clone(),
+                    user_email: Some(user.email),
+                    user_id: Some(user.id),
+                    ..Default::default()
+                };
+                let context = search_engine.get_rag_context(&request).await?;
+                if context.is_empty() {
+                    "I couldn't find anything in your documents about that.".to_string()
+                } else {
+                    let prompt = search_engine.build_rag_prompt(&question, &context);
+                    let mut stream = state.ai_client.stream_prompt(&prompt).await?;
+                    let mut answer = String::new();
+                    while let Some(chunk) = stream.next().await {
+                        answer.push_str(&chunk?);
+                    }
+                    answer
+                }
+            }
+            BotCommand::Help => HELP_TEXT.to_string(),
+        };
+
+        self.reply(&activity, &text).await
+    }
+}
+
+/// Bot Framework messaging endpoint. Activities are acknowledged as soon as they are
+/// authenticated and answered asynchronously, as Teams retries requests that take longer
+/// than a few seconds.
+pub async fn messages(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(activity): Json<Activity>,
+) -> StatusCode {
+    let Some(bot) = state.teams_bot.clone() else {
+        return StatusCode::NOT_FOUND;
+    };
+
+    if let Err(e) = bot.authenticate(&headers, &activity).await {
+        warn!("Rejected Teams activity: {}", e);
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    if activity.activity_type != "message" {
+        debug!("Ignoring Teams activity of type {}", activity.activity_type);
+        return StatusCode::OK;
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = bot.handle_message(state, activity.clone()).await {
+            error!("Failed to answer Teams message: {:#}", e);
+            let _ = bot
+                .reply(&activity, "Sorry, something went wrong. Please try again.")
+                .await;
+        }
+    });
+
+    StatusCode::OK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_mentions() {
+        assert_eq!(
+            strip_mentions("<at>Omni</at> what is our PTO policy?"),
+            " what is our PTO policy?"
+        );
+        assert_eq!(strip_mentions("no mentions"), "no mentions");
+        assert_eq!(strip_mentions("<at>Omni</at><at>Bob</at>hi"), "hi");
+        assert_eq!(strip_mentions("dangling <at>Omni"), "dangling ");
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(BotCommand::parse(""), BotCommand::Help);
+        assert_eq!(BotCommand::parse("<at>Omni</at> help"), BotCommand::Help);
+        assert_eq!(BotCommand::parse("search"), BotCommand::Help);
+        assert_eq!(
+            BotCommand::parse("<at>Omni</at> Search  roadmap Q3 "),
+            BotCommand::Search("roadmap Q3".to_string())
+        );
+        assert_eq!(
+            BotCommand::parse("searching for the roadmap?"),
+            BotCommand::Ask("searching for the roadmap?".to_string())
+        );
+        assert_eq!(
+            BotCommand::parse("What is our PTO policy?"),
+            BotCommand::Ask("What is our PTO policy?".to_string())
+        );
+    }
+}
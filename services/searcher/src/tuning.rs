@@ -0,0 +1,268 @@
+//! Relevance tunables changed while the searcher runs.
+//!
+//! The hybrid fusion and weights, the semantic search timeout, typo tolerance and the ranking
+//! boost toggle start out from the environment. Admins override them through `/admin/config`; the
+//! overrides are stored in the `configuration` table and laid over the environment's values,
+//! so settings without an override keep following the environment.
+//!
+//! Every replica reloads the overrides periodically, and the replica saving them right away.
+//! Searches read the tunables when they start, so tuning takes effect without a restart that
+//! would drop the searcher's in-memory indexes. The tunables are part of the search cache
+//! key, so results cached under old tunables are not served.
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use shared::db::repositories::ConfigurationRepository;
+use shared::{DatabasePool, SearchTuning};
+use std::sync::{Arc, RwLock};
+use tracing::{error, info};
+
+/// Configuration key holding the overrides.
+pub const SEARCH_TUNING_KEY: &str = "searcher_tuning";
+
+/// Seconds between reloads of the overrides saved by other replicas.
+pub const REFRESH_INTERVAL_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TuningState {
+    /// The tunables in effect.
+    pub tuning: SearchTuning,
+    /// The tunables from the environment.
+    pub defaults: SearchTuning,
+    pub overrides: Map<String, Value>,
+}
+
+/// The tunables in effect, shared by all requests.
+pub struct TuningStore {
+    defaults: SearchTuning,
+    current: RwLock<SearchTuning>,
+    db_pool: DatabasePool,
+}
+
+impl TuningStore {
+    pub fn new(db_pool: DatabasePool, defaults: SearchTuning) -> Self {
+        Self {
+            current: RwLock::new(defaults.clone()),
+            defaults,
+            db_pool,
+        }
+    }
+
+    pub fn current(&self) -> SearchTuning {
+        match self.current.read() {
+            Ok(current) => current.clone(),
+            Err(_) => self.defaults.clone(),
+        }
+    }
+
+    pub async fn state(&self) -> Result<TuningState> {
+        Ok(TuningState {
+            tuning: self.current(),
+            defaults: self.defaults.clone(),
+            overrides: self.load_overrides().await?,
+        })
+    }
+
+    pub async fn load_overrides(&self) -> Result<Map<String, Value>> {
+        let repo = ConfigurationRepository::new(self.db_pool.pool());
+        match repo.get(SEARCH_TUNING_KEY).await? {
+            Some(Value::Object(overrides)) => Ok(overrides),
+            _ => Ok(Map::new()),
+        }
+    }
+
+    /// Save overrides that [`resolve`] accepted, and put them into effect on this replica.
+    pub async fn save_overrides(
+        &self,
+        overrides: Map<String, Value>,
+        tuning: SearchTuning,
+    ) -> Result<()> {
+        let repo = ConfigurationRepository::new(self.db_pool.pool());
+        if overrides.is_empty() {
+            repo.delete(SEARCH_TUNING_KEY).await?;
+        } else {
+            repo.set(SEARCH_TUNING_KEY, &Value::Object(overrides))
+                .await?;
+        }
+        self.set(tuning);
+        Ok(())
+    }
+
+    /// Reload the overrides. Overrides that no longer apply, e.g. after a setting was
+    /// renamed, are logged and the environment's values are used instead.
+    pub async fn refresh(&self) -> Result<()> {
+        let overrides = self.load_overrides().await?;
+        match resolve(&self.defaults, &overrides) {
+            Ok(tuning) => self.set(tuning),
+            Err(e) => {
+                error!("Ignoring invalid searcher tuning overrides: {}", e);
+                self.set(self.defaults.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn set(&self, tuning: SearchTuning) {
+        if let Ok(mut current) = self.current.write() {
+            if *current != tuning {
+                info!("Searcher tuning changed to {:?}", tuning);
+                *current = tuning;
+            }
+        }
+    }
+
+    pub fn start_background_refresh(self: &Arc<Self>, interval_secs: u64) {
+        let store = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+                if let Err(e) = store.refresh().await {
+                    error!("Failed to refresh searcher tuning: {}", e);
+                }
+            }
+        });
+    }
+}
+
+/// Apply `changes` to `overrides`. A `null` change removes the setting's override.
+pub fn apply_changes(overrides: &mut Map<String, Value>, changes: Map<String, Value>) {
+    for (key, value) in changes {
+        if value.is_null() {
+            overrides.remove(&key);
+        } else {
+            overrides.insert(key, value);
+        }
+    }
+}
+
+/// The tunables with `overrides` laid over `defaults`.
+pub fn resolve(
+    defaults: &SearchTuning,
+    overrides: &Map<String, Value>,
+) -> std::result::Result<SearchTuning, String> {
+    let mut fields = match serde_json::to_value(defaults) {
+        Ok(Value::Object(fields)) => fields,
+        _ => return Err("Failed to read the default settings".to_string()),
+    };
+    for (key, value) in overrides {
+        if !fields.contains_key(key) {
+            return Err(format!("Unknown setting '{}'", key));
+        }
+        fields.insert(key.clone(), value.clone());
+    }
+
+    let tuning: SearchTuning = serde_json::from_value(Value::Object(fields))
+        .map_err(|e| format!("Invalid settings: {}", e))?;
+    validate(&tuning)?;
+    Ok(tuning)
+}
+
+fn validate(tuning: &SearchTuning) -> std::result::Result<(), String> {
+    let weights = [
+        ("hybrid_search_fts_weight", tuning.hybrid_search_fts_weight),
+        (
+            "hybrid_search_semantic_weight",
+            tuning.hybrid_search_semantic_weight,
+        ),
+        ("link_authority_weight", tuning.link_authority_weight),
+    ];
+    for (name, weight) in weights {
+        if !(weight.is_finite() && weight >= 0.0) {
+            return Err(format!(
+                "{} must be a non-negative number, got {}",
+                name, weight
+            ));
+        }
+    }
+    if tuning.semantic_search_timeout_ms == 0 {
+        return Err("semantic_search_timeout_ms must be positive".to_string());
+    }
+    if tuning.rag_context_window < 0 {
+        return Err(format!(
+            "rag_context_window must not be negative, got {}",
+            tuning.rag_context_window
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use shared::HybridFusion;
+
+    fn defaults() -> SearchTuning {
+        SearchTuning {
+            hybrid_fusion: HybridFusion::Weighted,
+            hybrid_rrf_k: 60,
+            hybrid_search_fts_weight: 0.3,
+            hybrid_search_semantic_weight: 1.0,
+            link_authority_weight: 0.1,
+            semantic_search_timeout_ms: 5000,
+            rag_context_window: 2,
+            typo_tolerance: true,
+            ranking_boosts: true,
+        }
+    }
+
+    fn overrides(value: Value) -> Map<String, Value> {
+        value.as_object().cloned().unwrap()
+    }
+
+    #[test]
+    fn test_resolve_overrides() {
+        assert_eq!(resolve(&defaults(), &Map::new()).unwrap(), defaults());
+
+        let tuning = resolve(
+            &defaults(),
+            &overrides(json!({"hybrid_search_fts_weight": 0.8, "typo_tolerance": false})),
+        )
+        .unwrap();
+        assert_eq!(tuning.hybrid_search_fts_weight, 0.8);
+        assert!(!tuning.typo_tolerance);
+        assert_eq!(tuning.hybrid_search_semantic_weight, 1.0);
+
+        let tuning = resolve(
+            &defaults(),
+            &overrides(json!({"hybrid_fusion": "rrf", "hybrid_rrf_k": 20})),
+        )
+        .unwrap();
+        assert_eq!(tuning.hybrid_fusion, HybridFusion::Rrf);
+        assert_eq!(tuning.hybrid_rrf_k, 20);
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_settings() {
+        assert!(resolve(&defaults(), &overrides(json!({"fts_weight": 0.8}))).is_err());
+        assert!(resolve(
+            &defaults(),
+            &overrides(json!({"hybrid_search_fts_weight": "high"}))
+        )
+        .is_err());
+        assert!(resolve(
+            &defaults(),
+            &overrides(json!({"link_authority_weight": -1.0}))
+        )
+        .is_err());
+        assert!(resolve(&defaults(), &overrides(json!({"hybrid_fusion": "sum"}))).is_err());
+        assert!(resolve(&defaults(), &overrides(json!({"hybrid_rrf_k": -1}))).is_err());
+        assert!(resolve(
+            &defaults(),
+            &overrides(json!({"semantic_search_timeout_ms": 0}))
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_apply_changes() {
+        let mut current = overrides(json!({"hybrid_search_fts_weight": 0.8}));
+        apply_changes(
+            &mut current,
+            overrides(json!({"hybrid_search_fts_weight": null, "ranking_boosts": false})),
+        );
+        assert_eq!(current, overrides(json!({"ranking_boosts": false})));
+    }
+}
@@ -1,5 +1,6 @@
 use fst::automaton::Str;
 use fst::{Automaton, IntoStreamer, Map, MapBuilder, Streamer};
+use shared::text_normalization::fold;
 use shared::{DatabasePool, DocumentRepository};
 use std::collections::HashSet;
 use std::sync::Arc;
@@ -158,7 +159,7 @@ impl TitleIndex {
 }
 
 pub fn normalize(title: &str) -> String {
-    let lowered = title.to_lowercase();
+    let lowered = fold(title).to_lowercase();
     let replaced: String = lowered
         .chars()
         .map(|c| if c.is_alphanumeric() { c } else { ' ' })
@@ -336,6 +337,12 @@ mod tests {
         assert_eq!(normalize("---"), "");
     }
 
+    #[test]
+    fn test_normalize_folds_diacritics() {
+        assert_eq!(normalize("Café Menü"), "cafe menu");
+        assert_eq!(normalize("ＡＰＩ ガイド"), "api ガイド");
+    }
+
     #[test]
     fn test_score_word_prefix_beats_character_level() {
         let score = score_match("budg", "budget q4 2024");
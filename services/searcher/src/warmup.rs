@@ -0,0 +1,187 @@
+//! Cache warm-up for the most frequent searches.
+//!
+//! Every `SEARCH_WARMUP_INTERVAL_SECONDS`, one replica runs the searches logged most often
+//! over the last [`WINDOW_HOURS`] again, as the users who ran them, replacing their cached
+//! results, facet counts and query embeddings before they expire. Searches at the start of
+//! the working day then find the caches warm instead of all missing at once.
+//!
+//! The query log only keeps hashes of what users searched for, so searches remember their
+//! query text in Redis, by hash, for as long as the window. Searches older than that are
+//! not warmed.
+
+use crate::models::{SearchMode, SearchRequest};
+use crate::query_log::hash_query;
+use crate::search::SearchEngine;
+use crate::AppState;
+use anyhow::Result;
+use redis::{AsyncCommands, Client as RedisClient};
+use serde::Deserialize;
+use shared::db::repositories::{FrequentSearch, QueryLogRepository};
+use shared::models::AttributeFilter;
+use shared::{cache, SourceType};
+use std::collections::HashMap;
+use std::time::Duration;
+use time::OffsetDateTime;
+use tracing::{debug, error, info};
+
+/// Hours of logged searches the most frequent are picked from.
+pub const WINDOW_HOURS: i64 = 24;
+
+fn query_text_key(query_hash: &str) -> String {
+    cache::keys().cache("query_text", query_hash)
+}
+
+/// Remember a searched query's text for warm-up, off the request path.
+pub fn remember_query(redis_client: &RedisClient, query: &str) {
+    let redis_client = redis_client.clone();
+    let key = query_text_key(&hash_query(query));
+    let query = query.trim().to_string();
+    tokio::spawn(async move {
+        if let Ok(mut conn) = redis_client.get_multiplexed_async_connection().await {
+            let ttl = (WINDOW_HOURS * 3600) as u64;
+            let _: Result<(), _> = conn.set_ex(&key, query, ttl).await;
+        }
+    });
+}
+
+/// The logged filters of a search, as recorded by the query logger.
+#[derive(Debug, Default, Deserialize)]
+struct LoggedFilters {
+    source_types: Option<Vec<SourceType>>,
+    content_types: Option<Vec<String>>,
+    attribute_filters: Option<HashMap<String, AttributeFilter>>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+/// The request that ran a frequent search, `None` when it cannot be rebuilt.
+fn warmup_request(search: &FrequentSearch, query: String) -> Option<SearchRequest> {
+    let mode: SearchMode = serde_json::from_value(search.search_mode.clone().into()).ok()?;
+    let filters: LoggedFilters = serde_json::from_value(search.filters.clone()).ok()?;
+    Some(SearchRequest {
+        query,
+        mode: Some(mode),
+        source_types: filters.source_types,
+        content_types: filters.content_types,
+        attribute_filters: filters.attribute_filters,
+        limit: filters.limit,
+        offset: filters.offset,
+        user_id: search.user_id.clone(),
+        warmup: true,
+        ..Default::default()
+    })
+}
+
+/// Run the most frequent searches again, returning how many ran. Does nothing when another
+/// replica warmed the caches within `interval`.
+pub async fn warm(state: &AppState, interval: Duration) -> Result<usize> {
+    let mut conn = state
+        .redis_client
+        .get_multiplexed_async_connection()
+        .await?;
+    let locked: Option<String> = redis::cmd("SET")
+        .arg(cache::keys().lock("search_warmup"))
+        .arg(1)
+        .arg("NX")
+        .arg("EX")
+        // Released just before the next run, which may then be any replica's
+        .arg(interval.as_secs().saturating_sub(1).max(1))
+        .query_async(&mut conn)
+        .await?;
+    if locked.is_none() {
+        debug!("Another replica is warming the search caches");
+        return Ok(0);
+    }
+
+    let since = OffsetDateTime::now_utc() - time::Duration::hours(WINDOW_HOURS);
+    let searches = QueryLogRepository::new(state.db_pool.pool())
+        .most_frequent(since, state.config.warmup_top_queries)
+        .await?;
+    let search_engine = SearchEngine::new(
+        state.db_pool.clone(),
+        state.redis_client.clone(),
+        state.ai_client.clone(),
+        state.search_config(),
+        state.redaction.current(),
+    )
+    .await?;
+
+    let mut warmed = 0;
+    for search in &searches {
+        let query: Option<String> = conn.get(query_text_key(&search.query_hash)).await?;
+        let Some(request) = query.and_then(|query| warmup_request(search, query)) else {
+            continue;
+        };
+        // One at a time, so that warm-up never competes with users' searches for long
+        match search_engine.search(request).await {
+            Ok(_) => warmed += 1,
+            Err(e) => debug!("Failed to warm search {}: {}", search.query_hash, e),
+        }
+    }
+    Ok(warmed)
+}
+
+/// Warm the caches every `interval_secs`.
+pub fn start(state: AppState, interval_secs: u64) {
+    tokio::spawn(async move {
+        let interval = Duration::from_secs(interval_secs);
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match warm(&state, interval).await {
+                Ok(0) => {}
+                Ok(warmed) => info!("Warmed the caches of {} frequent searches", warmed),
+                Err(e) => error!("Failed to warm the search caches: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn frequent(search_mode: &str, filters: serde_json::Value) -> FrequentSearch {
+        FrequentSearch {
+            query_hash: hash_query("quarterly plan"),
+            search_mode: search_mode.to_string(),
+            filters,
+            user_id: Some("01JUSER".to_string()),
+            searches: 12,
+        }
+    }
+
+    #[test]
+    fn test_warmup_request_from_logged_filters() {
+        let search = frequent(
+            "hybrid",
+            json!({
+                "source_types": ["google_drive"],
+                "content_types": ["pdf"],
+                "attribute_filters": null,
+                "document_id": null,
+                "limit": 20,
+                "offset": 0,
+            }),
+        );
+        let request = warmup_request(&search, "Quarterly plan".to_string()).unwrap();
+        assert_eq!(request.query, "Quarterly plan");
+        assert_eq!(*request.search_mode(), SearchMode::Hybrid);
+        assert_eq!(request.source_types, Some(vec![SourceType::GoogleDrive]));
+        assert_eq!(request.content_types, Some(vec!["pdf".to_string()]));
+        assert_eq!(request.limit(), 20);
+        assert_eq!(request.user_id.as_deref(), Some("01JUSER"));
+        assert!(request.warmup);
+    }
+
+    #[test]
+    fn test_warmup_request_rejects_unknown_logs() {
+        assert!(warmup_request(&frequent("lexical", json!({})), String::new()).is_none());
+        assert!(warmup_request(
+            &frequent("fulltext", json!({"source_types": ["nowhere"]})),
+            String::new()
+        )
+        .is_none());
+    }
+}
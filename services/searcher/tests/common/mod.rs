@@ -5,21 +5,26 @@ use axum::{
     Router,
 };
 use omni_searcher::{
-    create_app, suggested_questions::SuggestedQuestionsGenerator, typeahead::TitleIndex, AppState,
+    create_app, redaction::RedactionStore, suggested_questions::SuggestedQuestionsGenerator,
+    tuning::TuningStore, typeahead::TitleIndex, AppState,
 };
 use serde_json::{json, Value};
 use shared::storage::postgres::PostgresStorage;
 use shared::test_environment::TestEnvironment;
 use shared::test_utils::create_test_documents_with_embeddings;
-use shared::{AIClient, ObjectStorage, SearcherConfig};
+use shared::{AIClient, HybridFusion, ObjectStorage, QueryBudget, SearchTuning, SearcherConfig};
 use std::sync::Arc;
 use tower::ServiceExt;
 
+/// Token help desk requests to the test app authenticate with.
+pub const HELPDESK_API_TOKEN: &str = "test-helpdesk-token";
+
 /// Test fixture for searcher service integration tests
 pub struct SearcherTestFixture {
     pub test_env: TestEnvironment,
     pub app: Router,
     pub title_index: Arc<TitleIndex>,
+    pub state: AppState,
 }
 
 impl SearcherTestFixture {
@@ -33,10 +38,32 @@ impl SearcherTestFixture {
             database: test_env.database_config(),
             redis: test_env.redis_config(),
             ai_service_url: test_env.mock_ai_server.base_url.clone(),
-            hybrid_search_fts_weight: 0.6,
-            hybrid_search_semantic_weight: 0.4,
-            semantic_search_timeout_ms: 5000,
-            rag_context_window: 2,
+            tuning: SearchTuning {
+                hybrid_fusion: HybridFusion::Weighted,
+                hybrid_rrf_k: 60,
+                hybrid_search_fts_weight: 0.6,
+                hybrid_search_semantic_weight: 0.4,
+                link_authority_weight: 0.1,
+                semantic_search_timeout_ms: 5000,
+                rag_context_window: 2,
+                typo_tolerance: true,
+                ranking_boosts: true,
+            },
+            budget: QueryBudget {
+                max_candidates: 1000,
+                max_vector_comparisons: 400,
+                max_rerank_candidates: 200,
+                deadline_ms: 10_000,
+                max_concurrent_searches: 64,
+            },
+            warmup_interval_seconds: None,
+            warmup_top_queries: 50,
+            query_log_sample_rate: 1.0,
+            slow_query_threshold_ms: 1000,
+            grpc_port: None,
+            helpdesk_api_token: Some(HELPDESK_API_TOKEN.to_string()),
+            rerank_top_n: None,
+            rerank_timeout_ms: 5000,
         };
 
         // Create content storage using PostgresStorage directly
@@ -52,23 +79,31 @@ impl SearcherTestFixture {
         ));
 
         let title_index = Arc::new(TitleIndex::new(test_env.db_pool.clone()));
+        let tuning = Arc::new(TuningStore::new(
+            test_env.db_pool.clone(),
+            config.tuning.clone(),
+        ));
 
         let app_state = AppState {
             db_pool: test_env.db_pool.clone(),
             redis_client: test_env.redis_client.clone(),
             ai_client,
             config,
+            tuning,
+            redaction: Arc::new(RedactionStore::new(test_env.db_pool.clone())),
             content_storage,
             suggested_questions_generator,
             title_index: title_index.clone(),
+            teams_bot: None,
         };
 
-        let app = create_app(app_state);
+        let app = create_app(app_state.clone());
 
         Ok(Self {
             test_env,
             app,
             title_index,
+            state: app_state,
         })
     }
 
@@ -159,6 +194,55 @@ impl SearcherTestFixture {
         Ok((status, json))
     }
 
+    /// Helper method to make quick search requests
+    pub async fn quick_search(&self, query: &str, user_email: &str) -> Result<(StatusCode, Value)> {
+        let uri = format!(
+            "/quick-search?q={}&user_email={}",
+            urlencoding::encode(query),
+            urlencoding::encode(user_email)
+        );
+
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri(&uri)
+            .body(Body::empty())?;
+
+        let response = self.app.clone().oneshot(request).await?;
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+
+        Ok((status, serde_json::from_slice(&body)?))
+    }
+
+    /// Helper method to fetch a document's preview image, returning its content type and bytes
+    pub async fn document_preview(
+        &self,
+        document_id: &str,
+        user_email: &str,
+    ) -> Result<(StatusCode, Option<String>, Vec<u8>)> {
+        let uri = format!(
+            "/documents/{}/preview?user_email={}",
+            urlencoding::encode(document_id),
+            urlencoding::encode(user_email)
+        );
+
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri(&uri)
+            .body(Body::empty())?;
+
+        let response = self.app.clone().oneshot(request).await?;
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get("content-type")
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+
+        Ok((status, content_type, body.to_vec()))
+    }
+
     /// Helper method to make typeahead requests
     pub async fn typeahead(
         &self,
@@ -7,6 +7,8 @@ use axum::{
 };
 use common::SearcherTestFixture;
 use serde_json::{json, Value};
+use shared::db::repositories::{QueryLogRepository, SearchQueryLog};
+use time::OffsetDateTime;
 use tower::ServiceExt;
 
 /// Extract result titles from a search response in order.
@@ -294,7 +296,8 @@ async fn test_content_type_filtering() -> Result<()> {
         .search_with_body(json!({
             "query": "guide",
             "content_types": ["nonexistent_type"],
-            "limit": 10
+            "limit": 10,
+            "fallback": false
         }))
         .await?;
     assert_eq!(status, StatusCode::OK);
@@ -339,6 +342,33 @@ async fn test_permission_filtering() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_quick_search() -> Result<()> {
+    let fixture = SearcherTestFixture::new().await?;
+    let _doc_ids = fixture.seed_search_data().await?;
+
+    let (status, response) = fixture.quick_search("guide", "user1").await?;
+    assert_eq!(status, StatusCode::OK);
+    let results = response["results"].as_array().unwrap();
+    assert!(!results.is_empty(), "user1 should see quick search results");
+    assert!(results.len() <= 5);
+    assert!(results[0]["title"].is_string());
+    assert!(
+        results[0].get("content").is_none() && results[0].get("highlights").is_none(),
+        "quick search results should only carry titles and URLs"
+    );
+
+    // Cached per user, so another user's results never leak through the cache
+    let (status, response) = fixture.quick_search("guide", "nobody@example.com").await?;
+    assert_eq!(status, StatusCode::OK);
+    assert!(response["results"].as_array().unwrap().is_empty());
+
+    let (_, cached) = fixture.quick_search("Guide", "user1").await?;
+    assert_eq!(cached["results"].as_array().unwrap().len(), results.len());
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_highlighting() -> Result<()> {
     let fixture = SearcherTestFixture::new().await?;
@@ -443,7 +473,8 @@ async fn test_attribute_filtering() -> Result<()> {
         .search_with_body(json!({
             "query": "guide",
             "attribute_filters": {"category": "nonexistent"},
-            "limit": 10
+            "limit": 10,
+            "fallback": false
         }))
         .await?;
     assert_eq!(status, StatusCode::OK);
@@ -473,13 +504,12 @@ async fn test_cache_behavior() -> Result<()> {
         .redis_client
         .get_multiplexed_async_connection()
         .await?;
-    let keys: Vec<String> = redis::cmd("KEYS")
-        .arg("search:*")
-        .query_async(&mut conn)
-        .await?;
+    let pattern = shared::cache::keys().cache_pattern(shared::cache::SEARCH_CACHE);
+    let keys = shared::cache::scan(&mut conn, &pattern).await?;
     assert!(
         !keys.is_empty(),
-        "Expected at least one search:* cache key in Redis after first query"
+        "Expected at least one {} cache key in Redis after first query",
+        pattern
     );
 
     // Second identical query should return the same results
@@ -600,3 +630,1778 @@ async fn test_invalid_search_mode() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_usage_analytics_reflects_logged_searches() -> Result<()> {
+    let fixture = SearcherTestFixture::new().await?;
+    fixture.seed_search_data().await?;
+
+    let (status, _) = fixture.search("rust", None, None).await?;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = fixture.search("zzzznomatchzzzz", None, None).await?;
+    assert_eq!(status, StatusCode::OK);
+
+    // Query logs are written in the background
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri("/admin/analytics/usage?days=7")
+        .body(Body::empty())?;
+
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let json: Value = serde_json::from_slice(&body)?;
+
+    assert_eq!(json["days"], 7);
+    assert_eq!(json["total_queries"], 2);
+    assert_eq!(json["zero_result_rate"], 0.5);
+    assert!(!json["index_growth"].as_array().unwrap().is_empty());
+
+    // A search logged at a quarter sample rate stands for four
+    QueryLogRepository::new(fixture.test_env.db_pool.pool())
+        .create(SearchQueryLog {
+            id: String::new(),
+            query_hash: "hash".to_string(),
+            search_mode: "hybrid".to_string(),
+            filters: json!({ "source_types": ["slack"] }),
+            user_id: None,
+            result_count: 3,
+            total_ms: 40,
+            cache_ms: 0,
+            fts_ms: None,
+            vector_ms: None,
+            rerank_ms: None,
+            cache_hit: false,
+            is_slow: false,
+            sample_rate: 0.25,
+            feature_flags: json!({}),
+            created_at: OffsetDateTime::now_utc(),
+        })
+        .await?;
+
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri("/admin/analytics/usage?days=7")
+        .body(Body::empty())?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let json: Value = serde_json::from_slice(&body)?;
+    assert_eq!(json["total_queries"], 6);
+    assert_eq!(json["zero_result_rate"], 1.0 / 6.0);
+    let slack = json["queries_per_source_type"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|count| count["source_type"] == "slack")
+        .unwrap();
+    assert_eq!(slack["queries"], 4);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_feature_flags() -> Result<()> {
+    let fixture = SearcherTestFixture::new().await?;
+
+    let put_flag = |name: &str, body: Value| {
+        Request::builder()
+            .method(Method::PUT)
+            .uri(format!("/admin/feature-flags/{}", name))
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+    };
+
+    let request = put_flag(
+        "rrf_fusion",
+        json!({"enabled": true, "rollout_percentage": 150}),
+    )?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let request = put_flag("RRF Fusion", json!({"enabled": true}))?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = put_flag(
+        "rrf_fusion",
+        json!({"enabled": true, "rollout_percentage": 25, "tenants": ["example.com"]}),
+    )?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri("/admin/feature-flags")
+        .body(Body::empty())?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let flags: Value = serde_json::from_slice(&body)?;
+    assert_eq!(flags[0]["name"], "rrf_fusion");
+    assert_eq!(flags[0]["rollout_percentage"], 25);
+    assert_eq!(flags[0]["tenants"], json!(["example.com"]));
+
+    // Searches are compared by the flags they were logged with
+    let repo = QueryLogRepository::new(fixture.test_env.db_pool.pool());
+    for (enabled, result_count, total_ms) in [(true, 5, 100), (true, 0, 300), (false, 5, 50)] {
+        repo.create(SearchQueryLog {
+            id: String::new(),
+            query_hash: "hash".to_string(),
+            search_mode: "hybrid".to_string(),
+            filters: json!({}),
+            user_id: None,
+            result_count,
+            total_ms,
+            cache_ms: 0,
+            fts_ms: None,
+            vector_ms: None,
+            rerank_ms: None,
+            cache_hit: false,
+            is_slow: false,
+            sample_rate: 1.0,
+            feature_flags: json!({"rrf_fusion": enabled}),
+            created_at: OffsetDateTime::now_utc(),
+        })
+        .await?;
+    }
+
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri("/admin/analytics/feature-flags/rrf_fusion?days=7")
+        .body(Body::empty())?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let analytics: Value = serde_json::from_slice(&body)?;
+    let variants = analytics["variants"].as_array().unwrap();
+    assert_eq!(variants.len(), 2);
+    assert_eq!(variants[0]["enabled"], false);
+    assert_eq!(variants[0]["queries"], 1);
+    assert_eq!(variants[1]["enabled"], true);
+    assert_eq!(variants[1]["queries"], 2);
+    assert_eq!(variants[1]["zero_result_queries"], 1);
+    assert_eq!(variants[1]["avg_total_ms"], 200.0);
+
+    let request = Request::builder()
+        .method(Method::DELETE)
+        .uri("/admin/feature-flags/rrf_fusion")
+        .body(Body::empty())?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let request = Request::builder()
+        .method(Method::DELETE)
+        .uri("/admin/feature-flags/rrf_fusion")
+        .body(Body::empty())?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_search_promotions() -> Result<()> {
+    let fixture = SearcherTestFixture::new().await?;
+    let doc_ids = fixture.seed_search_data().await?;
+
+    let admin_request = |method: Method, uri: String, body: Option<Value>| {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        match body {
+            Some(body) => builder.body(Body::from(body.to_string())),
+            None => builder.body(Body::empty()),
+        }
+    };
+
+    let request = admin_request(
+        Method::POST,
+        "/admin/promotions".to_string(),
+        Some(json!({
+            "query_pattern": "Rust  Programming",
+            "document_id": doc_ids[4],
+        })),
+    )?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let promotion: Value = serde_json::from_slice(&body)?;
+    assert_eq!(promotion["match_type"], "exact");
+    assert_eq!(promotion["action"], "pin");
+    let promotion_id = promotion["id"].as_str().unwrap().to_string();
+
+    // The pinned document leads, ahead of the best ranked match
+    let (status, response) = fixture
+        .search("rust programming", Some("fulltext"), None)
+        .await?;
+    assert_eq!(status, StatusCode::OK);
+    let titles = result_titles(&response);
+    assert_eq!(titles[0], "Getting Started Guide");
+    assert_eq!(titles[1], "Rust Programming Guide");
+    assert_eq!(response["results"][0]["pinned"], true);
+    assert_eq!(response["results"][1]["pinned"], false);
+
+    // Other queries are unaffected
+    let (_, response) = fixture.search("API", Some("fulltext"), None).await?;
+    assert!(response["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|r| r["pinned"] == false));
+
+    let invalid = [
+        json!({"query_pattern": "(", "match_type": "regex", "document_id": doc_ids[0]}),
+        json!({"query_pattern": "rust", "document_id": "01JGF7V3E0Y2R1X8P5Q7W9T4XX"}),
+    ];
+    for body in invalid {
+        let request = admin_request(Method::POST, "/admin/promotions".to_string(), Some(body))?;
+        let response = fixture.app.clone().oneshot(request).await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    let request = admin_request(
+        Method::DELETE,
+        format!("/admin/promotions/{}", promotion_id),
+        None,
+    )?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Promotions are applied after caching, so removing one takes effect immediately
+    let (_, response) = fixture
+        .search("rust programming", Some("fulltext"), None)
+        .await?;
+    assert_eq!(result_titles(&response)[0], "Rust Programming Guide");
+
+    let request = admin_request(
+        Method::DELETE,
+        format!("/admin/promotions/{}", promotion_id),
+        None,
+    )?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_acronym_expansion() -> Result<()> {
+    let fixture = SearcherTestFixture::new().await?;
+    fixture.seed_search_data().await?;
+
+    let admin_request = |method: Method, uri: String, body: Option<Value>| {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        match body {
+            Some(body) => builder.body(Body::from(body.to_string())),
+            None => builder.body(Body::empty()),
+        }
+    };
+    let send = |request: Request<Body>| {
+        let app = fixture.app.clone();
+        async move {
+            let response = app.oneshot(request).await?;
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+            let body: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+            anyhow::Ok((status, body))
+        }
+    };
+
+    let (_, response) = fixture.search("VDB", Some("fulltext"), None).await?;
+    assert!(!result_titles(&response).contains(&"Search Engine Architecture".to_string()));
+
+    let (status, acronym) = send(admin_request(
+        Method::POST,
+        "/admin/acronyms".to_string(),
+        Some(json!({"acronym": " VDB ", "expansion": "Vector  Embeddings"})),
+    )?)
+    .await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(acronym["acronym"], "VDB");
+    assert_eq!(acronym["expansion"], "Vector Embeddings");
+    assert_eq!(acronym["status"], "pending");
+    assert_eq!(acronym["source"], "manual");
+    let acronym_id = acronym["id"].as_str().unwrap().to_string();
+
+    // Pending entries do not expand queries
+    let (_, response) = fixture.search("vdb", Some("fulltext"), None).await?;
+    assert!(!result_titles(&response).contains(&"Search Engine Architecture".to_string()));
+
+    let (status, acronym) = send(admin_request(
+        Method::PUT,
+        format!("/admin/acronyms/{}", acronym_id),
+        Some(json!({"acronym": "VDB", "expansion": "Vector Embeddings", "status": "active"})),
+    )?)
+    .await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(acronym["status"], "active");
+
+    let (_, response) = fixture.search("vdb", Some("fulltext"), None).await?;
+    assert_eq!(result_titles(&response)[0], "Search Engine Architecture");
+
+    let (status, acronyms) = send(admin_request(
+        Method::GET,
+        "/admin/acronyms?status=active".to_string(),
+        None,
+    )?)
+    .await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(acronyms.as_array().unwrap().len(), 1);
+    let (_, acronyms) = send(admin_request(
+        Method::GET,
+        "/admin/acronyms?status=pending".to_string(),
+        None,
+    )?)
+    .await?;
+    assert!(acronyms.as_array().unwrap().is_empty());
+
+    let invalid = [
+        json!({"acronym": "VDB", "expansion": "vector embeddings"}),
+        json!({"acronym": "R&D", "expansion": "Research and Development"}),
+        json!({"acronym": "QA", "expansion": " "}),
+    ];
+    for body in invalid {
+        let (status, _) = send(admin_request(
+            Method::POST,
+            "/admin/acronyms".to_string(),
+            Some(body),
+        )?)
+        .await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    let (status, _) = send(admin_request(
+        Method::DELETE,
+        format!("/admin/acronyms/{}", acronym_id),
+        None,
+    )?)
+    .await?;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(admin_request(
+        Method::DELETE,
+        format!("/admin/acronyms/{}", acronym_id),
+        None,
+    )?)
+    .await?;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_zero_result_fallbacks() -> Result<()> {
+    let fixture = SearcherTestFixture::new().await?;
+    fixture.seed_search_data().await?;
+
+    let filtered = |fallback: bool, offset: i64| {
+        json!({
+            "query": "rust programming",
+            "mode": "fulltext",
+            "content_types": ["nonexistent_type"],
+            "offset": offset,
+            "fallback": fallback
+        })
+    };
+
+    let (status, response) = fixture.search_with_body(filtered(true, 0)).await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["fallback"], "drop_filters");
+    assert_eq!(result_titles(&response)[0], "Rust Programming Guide");
+
+    // Disabled fallbacks, and later pages, report the empty result as is
+    for (fallback, offset) in [(false, 0), (true, 20)] {
+        let (_, response) = fixture.search_with_body(filtered(fallback, offset)).await?;
+        assert!(response["results"].as_array().unwrap().is_empty());
+        assert!(response.get("fallback").is_none());
+    }
+
+    // A search that finds results directly does not report a fallback
+    let (_, response) = fixture
+        .search("rust programming", Some("fulltext"), None)
+        .await?;
+    assert!(response.get("fallback").is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ranking_boosts() -> Result<()> {
+    let fixture = SearcherTestFixture::new().await?;
+    fixture.seed_search_data().await?;
+
+    let (_, response) = fixture
+        .search("rust programming", Some("hybrid"), None)
+        .await?;
+    assert_eq!(result_titles(&response)[0], "Rust Programming Guide");
+    assert!(response["results"][0].get("ranking").is_none());
+    let unboosted_score = response["results"][0]["score"].as_f64().unwrap();
+
+    let put_boosts = |boosts: Value| {
+        Request::builder()
+            .method(Method::PUT)
+            .uri("/admin/ranking-boosts")
+            .header("content-type", "application/json")
+            .body(Body::from(boosts.to_string()))
+    };
+
+    let request = put_boosts(json!({"content_types": {"documentation": -1.0}}))?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = put_boosts(json!({"content_types": {"documentation": 0.5}}))?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Boosts are part of the cache key, so the demotion applies right away
+    let (status, response) = fixture
+        .search_with_body(json!({
+            "query": "rust programming",
+            "mode": "hybrid",
+            "explain": true
+        }))
+        .await?;
+    assert_eq!(status, StatusCode::OK);
+    let rust_guide = response["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|r| r["document"]["title"] == "Rust Programming Guide")
+        .expect("Rust Programming Guide should still match");
+    let ranking = &rust_guide["ranking"];
+    assert_eq!(ranking["content_type_boost"], 0.5);
+    assert_eq!(ranking["source_boost"], 1.0);
+    assert!(ranking["fulltext_score"].is_number());
+    let score = rust_guide["score"].as_f64().unwrap();
+    assert!(
+        (score - unboosted_score * 0.5).abs() < 1e-4,
+        "Expected score {} to be half of {}",
+        score,
+        unboosted_score
+    );
+    assert_scores_descending(&response);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_search_tuning_hot_reload() -> Result<()> {
+    let fixture = SearcherTestFixture::new().await?;
+    fixture.seed_search_data().await?;
+
+    let explain = json!({
+        "query": "rust programming",
+        "mode": "hybrid",
+        "explain": true
+    });
+    let (_, response) = fixture.search_with_body(explain.clone()).await?;
+    let ranking = &response["results"][0]["ranking"];
+    assert!((ranking["fts_weight"].as_f64().unwrap() - 0.6).abs() < 1e-6);
+
+    let tuning_request = |method: Method, body: Value| {
+        Request::builder()
+            .method(method)
+            .uri("/admin/config")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+    };
+
+    let request = tuning_request(Method::PUT, json!({"hybrid_search_fts_weight": -1.0}))?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let request = tuning_request(Method::PUT, json!({"unknown_setting": 1}))?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = tuning_request(
+        Method::PUT,
+        json!({"hybrid_search_fts_weight": 0.9, "typo_tolerance": false}),
+    )?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let state: Value = serde_json::from_slice(&body)?;
+    assert_eq!(state["tuning"]["typo_tolerance"], false);
+    assert_eq!(state["defaults"]["typo_tolerance"], true);
+    assert_eq!(state["overrides"]["typo_tolerance"], false);
+
+    // The new weight applies to the next search, not to results cached before
+    let (_, response) = fixture.search_with_body(explain.clone()).await?;
+    let ranking = &response["results"][0]["ranking"];
+    assert!((ranking["fts_weight"].as_f64().unwrap() - 0.9).abs() < 1e-6);
+
+    // Replicas that did not save the overrides pick them up on reload
+    let replica = omni_searcher::tuning::TuningStore::new(
+        fixture.test_env.db_pool.clone(),
+        fixture.state.config.tuning.clone(),
+    );
+    let request = tuning_request(Method::PUT, json!({"rag_context_window": 4}))?;
+    fixture.app.clone().oneshot(request).await?;
+    assert_eq!(replica.current().rag_context_window, 2);
+    replica.refresh().await?;
+    assert_eq!(replica.current().rag_context_window, 4);
+
+    let request = tuning_request(Method::DELETE, json!({}))?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(fixture.state.tuning.current(), fixture.state.config.tuning);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_search_explain() -> Result<()> {
+    let fixture = SearcherTestFixture::new().await?;
+    fixture.seed_search_data().await?;
+
+    let explain = |mode: &str| {
+        json!({
+            "query": "rust programming",
+            "mode": mode,
+            "explain": true
+        })
+    };
+
+    let (status, response) = fixture.search_with_body(explain("fulltext")).await?;
+    assert_eq!(status, StatusCode::OK);
+    let result = &response["results"][0];
+    let ranking = &result["ranking"];
+    assert_eq!(ranking["fts_rank"], result["score"]);
+    assert!(ranking["chunk_similarities"].as_array().unwrap().is_empty());
+    assert!(ranking["fts_weight"].is_null());
+
+    let (status, response) = fixture.search_with_body(explain("semantic")).await?;
+    assert_eq!(status, StatusCode::OK);
+    let result = &response["results"][0];
+    let chunks = result["ranking"]["chunk_similarities"].as_array().unwrap();
+    assert!(!chunks.is_empty());
+    assert_eq!(chunks[0]["similarity"], result["score"]);
+    assert!(result["ranking"]["fts_rank"].is_null());
+
+    let (status, response) = fixture.search_with_body(explain("hybrid")).await?;
+    assert_eq!(status, StatusCode::OK);
+    for result in response["results"].as_array().unwrap() {
+        let ranking = &result["ranking"];
+        assert!(ranking["fts_weight"].is_number());
+        assert!(ranking["semantic_weight"].is_number());
+        assert_eq!(ranking["rerank_delta"], 0.0);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_duplicate_results_collapsed() -> Result<()> {
+    let fixture = SearcherTestFixture::new().await?;
+    let doc_ids = fixture.seed_search_data().await?;
+    let pool = fixture.test_env.db_pool.pool();
+
+    // The same file indexed a second time, sharing the content blob
+    sqlx::query(
+        r#"
+        INSERT INTO documents (id, source_id, external_id, title, content_id, content_type, attributes, content, metadata, permissions, created_at, updated_at)
+        SELECT '01JGF7V3E0Y2R1X8P5Q7W9DUPE', source_id, 'tech_doc_1_copy', 'Rust Programming Guide (copy)',
+               content_id, content_type, attributes, content, metadata, permissions, NOW(), NOW()
+        FROM documents WHERE id = $1
+        "#,
+    )
+    .bind(&doc_ids[0])
+    .execute(pool)
+    .await?;
+
+    let (status, response) = fixture
+        .search("rust programming", Some("fulltext"), None)
+        .await?;
+    assert_eq!(status, StatusCode::OK);
+    let titles = result_titles(&response);
+    assert_eq!(
+        titles
+            .iter()
+            .filter(|title| title.starts_with("Rust Programming Guide"))
+            .count(),
+        1,
+        "Expected duplicates to be collapsed, got: {:?}",
+        titles
+    );
+    let alternates = response["results"][0]["alternates"].as_array().unwrap();
+    assert_eq!(alternates.len(), 1);
+    assert!(alternates[0]["title"]
+        .as_str()
+        .unwrap()
+        .starts_with("Rust Programming Guide"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_child_results_nested_under_parent() -> Result<()> {
+    let fixture = SearcherTestFixture::new().await?;
+    let doc_ids = fixture.seed_search_data().await?;
+    let pool = fixture.test_env.db_pool.pool();
+
+    // A comment on the guide, indexed as a document of its own
+    sqlx::query(
+        r#"
+        INSERT INTO documents (id, source_id, external_id, title, content_type, attributes, content, metadata, permissions, created_at, updated_at)
+        SELECT '01JGF7V3E0Y2R1X8P5Q7W9CMNT', source_id, 'tech_doc_1_comment', 'Comment on the guide',
+               content_type, attributes, 'Rust programming tip: prefer borrowing over cloning.',
+               metadata, permissions, NOW(), NOW()
+        FROM documents WHERE id = $1
+        "#,
+    )
+    .bind(&doc_ids[0])
+    .execute(pool)
+    .await?;
+    sqlx::query(
+        r#"
+        INSERT INTO document_relationships (document_id, source_id, parent_external_id, relationship_type)
+        SELECT '01JGF7V3E0Y2R1X8P5Q7W9CMNT', source_id, external_id, 'comment'
+        FROM documents WHERE id = $1
+        "#,
+    )
+    .bind(&doc_ids[0])
+    .execute(pool)
+    .await?;
+
+    let search = |child_results: &str| {
+        json!({
+            "query": "rust programming",
+            "mode": "fulltext",
+            "child_results": child_results,
+        })
+    };
+
+    let (status, response) = fixture.search_with_body(search("flat")).await?;
+    assert_eq!(status, StatusCode::OK);
+    assert!(result_titles(&response).contains(&"Comment on the guide".to_string()));
+
+    let (status, response) = fixture.search_with_body(search("nested")).await?;
+    assert_eq!(status, StatusCode::OK);
+    assert!(!result_titles(&response).contains(&"Comment on the guide".to_string()));
+    let parent = response["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|result| result["document"]["id"] == doc_ids[0].as_str())
+        .expect("Parent should be a result");
+    let children = parent["children"].as_array().unwrap();
+    assert_eq!(children.len(), 1);
+    assert_eq!(children[0]["relationship"], "comment");
+    assert_eq!(children[0]["document"]["title"], "Comment on the guide");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_search_fields() -> Result<()> {
+    let fixture = SearcherTestFixture::new().await?;
+    fixture.seed_search_data().await?;
+
+    // "roadmap" only appears in the body of the Q4 meeting notes
+    let search_fields = |fields: Value| {
+        json!({
+            "query": "roadmap",
+            "mode": "fulltext",
+            "fields": fields,
+            "fallback": false
+        })
+    };
+
+    let (status, response) = fixture
+        .search_with_body(search_fields(json!(["content"])))
+        .await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(result_titles(&response), vec!["Q4 Planning Meeting"]);
+
+    let (status, response) = fixture
+        .search_with_body(search_fields(json!(["title"])))
+        .await?;
+    assert_eq!(status, StatusCode::OK);
+    assert!(!result_titles(&response).contains(&"Q4 Planning Meeting".to_string()));
+
+    let (status, _) = fixture
+        .search_with_body(search_fields(json!(["body"])))
+        .await?;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_result_anchors() -> Result<()> {
+    use shared::db::repositories::{AnchorKind, DocumentAnchor, DocumentAnchorRepository};
+
+    let fixture = SearcherTestFixture::new().await?;
+    let doc_ids = fixture.seed_search_data().await?;
+
+    // Every seeded document has one chunk, spanning offsets 0 to 100
+    let heading = |label: &str, start_offset: i32| DocumentAnchor {
+        start_offset,
+        kind: AnchorKind::Heading,
+        label: label.to_string(),
+        level: Some(2),
+        seconds: None,
+    };
+    let timestamp = |seconds: i32, start_offset: i32| DocumentAnchor {
+        start_offset,
+        kind: AnchorKind::Timestamp,
+        label: format!("{:02}:{:02}", seconds / 60, seconds % 60),
+        level: None,
+        seconds: Some(seconds),
+    };
+    let anchors: Vec<(String, Vec<DocumentAnchor>)> = doc_ids
+        .iter()
+        .map(|id| {
+            (
+                id.clone(),
+                vec![
+                    heading("Overview", 0),
+                    timestamp(0, 0),
+                    heading("Details", 150),
+                    timestamp(95, 150),
+                ],
+            )
+        })
+        .collect();
+    DocumentAnchorRepository::new(fixture.test_env.db_pool.pool())
+        .replace_for_documents(&anchors)
+        .await?;
+
+    let (status, response) = fixture
+        .search("software architecture patterns", Some("semantic"), None)
+        .await?;
+    assert_eq!(status, StatusCode::OK);
+    let results = response["results"].as_array().unwrap();
+    assert!(!results.is_empty());
+    for result in results {
+        let anchor = &result["anchor"];
+        assert_eq!(anchor["chunk_index"], 0);
+        assert_eq!(anchor["start_offset"], 0);
+        assert_eq!(anchor["end_offset"], 100);
+        assert_eq!(anchor["heading"], "Overview");
+        assert_eq!(anchor["heading_level"], 2);
+        assert_eq!(anchor["timestamp_seconds"], 0);
+    }
+
+    // Full-text matches have no chunk to point at
+    let (status, response) = fixture
+        .search("rust programming", Some("fulltext"), None)
+        .await?;
+    assert_eq!(status, StatusCode::OK);
+    assert!(response["results"][0].get("anchor").is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_result_previews() -> Result<()> {
+    use shared::db::repositories::DocumentPreviewRepository;
+    use shared::models::PreviewKind;
+    use shared::storage::postgres::PostgresStorage;
+    use shared::ObjectStorage;
+
+    let fixture = SearcherTestFixture::new().await?;
+    let doc_ids = fixture.seed_search_data().await?;
+    let pool = fixture.test_env.db_pool.pool();
+
+    let svg = b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>";
+    let content_id = PostgresStorage::new(pool.clone())
+        .store_content_with_type(svg, Some("image/svg+xml"), None)
+        .await?;
+    let preview_repo = DocumentPreviewRepository::new(pool);
+    for id in &doc_ids {
+        preview_repo
+            .upsert(id, &content_id, PreviewKind::Render, "image/svg+xml")
+            .await?;
+    }
+
+    let (status, response) = fixture
+        .search("rust programming", Some("fulltext"), None)
+        .await?;
+    assert_eq!(status, StatusCode::OK);
+    let results = response["results"].as_array().unwrap();
+    assert!(!results.is_empty());
+    for result in results {
+        assert_eq!(
+            result["document"]["preview"],
+            json!({ "kind": "render", "mime_type": "image/svg+xml" })
+        );
+    }
+
+    let (_, quick) = fixture.quick_search("guide", "user1").await?;
+    let visible_id = quick["results"][0]["document_id"].as_str().unwrap();
+    let (status, content_type, body) = fixture.document_preview(visible_id, "user1").await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type.as_deref(), Some("image/svg+xml"));
+    assert_eq!(body, svg);
+
+    // Documents the user cannot see have no preview for them
+    let (status, _, _) = fixture
+        .document_preview(visible_id, "nobody@example.com")
+        .await?;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_collections() -> Result<()> {
+    use shared::db::repositories::UserRepository;
+
+    let fixture = SearcherTestFixture::new().await?;
+    let doc_ids = fixture.seed_search_data().await?;
+    let user_repo = UserRepository::new(fixture.test_env.db_pool.pool());
+    let owner = user_repo.provision("user1", None, None, true).await?;
+    let viewer = user_repo.provision("user2", None, None, true).await?;
+
+    let collection_request = |method: Method, uri: String, body: Option<Value>| {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        match body {
+            Some(body) => builder.body(Body::from(body.to_string())),
+            None => builder.body(Body::empty()),
+        }
+    };
+
+    let request = collection_request(
+        Method::POST,
+        "/collections".to_string(),
+        Some(json!({ "user_id": owner.id, "name": "  Onboarding " })),
+    )?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let collection: Value = serde_json::from_slice(&body)?;
+    assert_eq!(collection["name"], "Onboarding");
+    let collection_id = collection["id"].as_str().unwrap().to_string();
+
+    // Names are unique per owner
+    let request = collection_request(
+        Method::POST,
+        "/collections".to_string(),
+        Some(json!({ "user_id": owner.id, "name": "onboarding" })),
+    )?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = collection_request(
+        Method::POST,
+        format!("/collections/{}/documents", collection_id),
+        Some(json!({ "user_id": owner.id, "document_ids": [doc_ids[4], doc_ids[4]] })),
+    )?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    assert_eq!(serde_json::from_slice::<Value>(&body)?["added"], 1);
+
+    // Searches are limited to the collection
+    let (status, response) = fixture
+        .search_with_user(
+            "guide collection:Onboarding",
+            Some("fulltext"),
+            None,
+            Some("user1"),
+        )
+        .await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(result_titles(&response), vec!["Getting Started Guide"]);
+
+    // Until it is shared, other users cannot search it
+    let (_, response) = fixture
+        .search_with_user(
+            "guide collection:Onboarding",
+            Some("fulltext"),
+            None,
+            Some("user2"),
+        )
+        .await?;
+    assert!(response["results"].as_array().unwrap().is_empty());
+
+    let request = collection_request(
+        Method::POST,
+        format!("/collections/{}/shares", collection_id),
+        Some(json!({ "user_id": owner.id, "email": "user2" })),
+    )?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (_, response) = fixture
+        .search_with_user(
+            "guide collection:Onboarding",
+            Some("fulltext"),
+            None,
+            Some("user2"),
+        )
+        .await?;
+    assert_eq!(result_titles(&response), vec!["Getting Started Guide"]);
+
+    // Only the owner edits it
+    let request = collection_request(
+        Method::PUT,
+        format!("/collections/{}", collection_id),
+        Some(json!({ "user_id": viewer.id, "name": "Renamed" })),
+    )?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let request = collection_request(
+        Method::GET,
+        format!("/collections/{}?user_id={}", collection_id, viewer.id),
+        None,
+    )?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let detail: Value = serde_json::from_slice(&body)?;
+    assert_eq!(detail["document_count"], 1);
+    assert_eq!(detail["documents"][0]["id"], doc_ids[4]);
+    assert_eq!(detail["shares"][0]["user_id"], viewer.id);
+
+    let request = collection_request(
+        Method::DELETE,
+        format!("/collections/{}?user_id={}", collection_id, owner.id),
+        None,
+    )?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = collection_request(
+        Method::GET,
+        format!("/collections?user_id={}", viewer.id),
+        None,
+    )?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    assert!(serde_json::from_slice::<Value>(&body)?
+        .as_array()
+        .unwrap()
+        .is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_document_flags() -> Result<()> {
+    use shared::db::repositories::UserRepository;
+
+    let fixture = SearcherTestFixture::new().await?;
+    let doc_ids = fixture.seed_search_data().await?;
+    let user_repo = UserRepository::new(fixture.test_env.db_pool.pool());
+    let reporter = user_repo.provision("user1", None, None, true).await?;
+    let outsider = user_repo.provision("user3", None, None, true).await?;
+
+    let json_request = |method: Method, uri: String, body: Value| {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+    };
+
+    // Documents the user cannot see cannot be flagged
+    let request = json_request(
+        Method::POST,
+        format!("/documents/{}/flags", doc_ids[0]),
+        json!({ "user_id": outsider.id, "reason": "outdated" }),
+    )?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = json_request(
+        Method::POST,
+        format!("/documents/{}/flags", doc_ids[0]),
+        json!({ "user_id": reporter.id, "reason": "outdated", "comment": " Old syntax " }),
+    )?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let flag: Value = serde_json::from_slice(&body)?;
+    assert_eq!(flag["status"], "open");
+    assert_eq!(flag["comment"], "Old syntax");
+    assert_eq!(flag["document_title"], "Rust Programming Guide");
+    let flag_id = flag["id"].as_str().unwrap().to_string();
+    let source_owner_id = flag["source_owner_id"].as_str().unwrap().to_string();
+
+    // Source owners see the flags on their documents
+    let list_flags = |query: String| {
+        let app = fixture.app.clone();
+        async move {
+            let request = Request::builder()
+                .uri(format!("/admin/flags?{}", query))
+                .body(Body::empty())?;
+            let response = app.oneshot(request).await?;
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+            anyhow::Ok(serde_json::from_slice::<Vec<Value>>(&body)?)
+        }
+    };
+    let flags = list_flags(format!("status=open&owner_id={}", source_owner_id)).await?;
+    assert_eq!(flags.len(), 1);
+    assert_eq!(flags[0]["id"], flag_id);
+    assert!(list_flags(format!("owner_id={}", outsider.id))
+        .await?
+        .is_empty());
+
+    // With a flag penalty, open flags demote the document
+    let request = json_request(
+        Method::PUT,
+        "/admin/ranking-boosts".to_string(),
+        json!({ "flag_penalty": 0.5 }),
+    )?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let (_, response) = fixture
+        .search_with_body(json!({
+            "query": "rust programming",
+            "mode": "hybrid",
+            "explain": true
+        }))
+        .await?;
+    let rust_guide = response["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|r| r["document"]["title"] == "Rust Programming Guide")
+        .expect("Rust Programming Guide should still match");
+    assert_eq!(rust_guide["ranking"]["flag_boost"], 0.5);
+
+    let request = json_request(
+        Method::PUT,
+        format!("/admin/flags/{}", flag_id),
+        json!({ "status": "resolved", "user_id": source_owner_id }),
+    )?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let flag: Value = serde_json::from_slice(&body)?;
+    assert_eq!(flag["status"], "resolved");
+    assert!(flag["resolved_at"].is_string());
+    assert!(list_flags("status=open".to_string()).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_document_links() -> Result<()> {
+    let fixture = SearcherTestFixture::new().await?;
+    let doc_ids = fixture.seed_search_data().await?;
+
+    // The Rust guide (visible to user1) and the API docs (also visible to user2) link to the
+    // getting started guide
+    for (source, key) in [(&doc_ids[0], "ENG-1"), (&doc_ids[3], "ENG-1")] {
+        sqlx::query(
+            "INSERT INTO document_links (source_document_id, kind, target_key, target_document_id)
+             VALUES ($1, 'jira_issue', $2, $3)",
+        )
+        .bind(source)
+        .bind(key)
+        .bind(&doc_ids[4])
+        .execute(fixture.test_env.db_pool.pool())
+        .await?;
+    }
+
+    let document_links = |document_id: &str, user_email: &str| {
+        let request = Request::builder()
+            .uri(format!(
+                "/documents/{}/links?user_email={}",
+                document_id, user_email
+            ))
+            .body(Body::empty());
+        let app = fixture.app.clone();
+        async move {
+            let response = app.oneshot(request?).await?;
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+            anyhow::Ok((status, serde_json::from_slice::<Value>(&body).ok()))
+        }
+    };
+
+    let (status, links) = document_links(&doc_ids[4], "user1").await?;
+    assert_eq!(status, StatusCode::OK);
+    let links = links.unwrap();
+    assert_eq!(links["inbound"].as_array().unwrap().len(), 2);
+    assert!(links["outbound"].as_array().unwrap().is_empty());
+
+    // Linking documents the user cannot see are left out
+    let (_, links) = document_links(&doc_ids[4], "user2").await?;
+    let inbound = links.unwrap()["inbound"].as_array().unwrap().clone();
+    assert_eq!(inbound.len(), 1);
+    assert_eq!(inbound[0]["title"], "REST API Endpoints");
+    assert_eq!(inbound[0]["kind"], "jira_issue");
+
+    let (status, _) = document_links(&doc_ids[0], "user2").await?;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Linked documents rank with a link boost
+    let (_, response) = fixture
+        .search_with_body(json!({
+            "query": "guide",
+            "mode": "hybrid",
+            "explain": true
+        }))
+        .await?;
+    let results = response["results"].as_array().unwrap();
+    let getting_started = results
+        .iter()
+        .find(|r| r["document"]["title"] == "Getting Started Guide")
+        .expect("Getting Started Guide should match");
+    assert!(getting_started["ranking"]["link_boost"].as_f64().unwrap() > 1.0);
+    let rust_guide = results
+        .iter()
+        .find(|r| r["document"]["title"] == "Rust Programming Guide")
+        .expect("Rust Programming Guide should match");
+    assert_eq!(rust_guide["ranking"]["link_boost"], 1.0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_person_profile() -> Result<()> {
+    let fixture = SearcherTestFixture::new().await?;
+    let doc_ids = fixture.seed_search_data().await?;
+    let pool = fixture.test_env.db_pool.pool();
+
+    for (id, email, name) in [
+        (
+            "01JGF7V3E0Y2R1X8P5Q7W9T4P1",
+            "pat@example.com",
+            Some("Pat Lee"),
+        ),
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4P2", "sam@example.com", None),
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4P3", "kim@example.com", None),
+    ] {
+        sqlx::query("INSERT INTO people (id, email, name, authored_count) VALUES ($1, $2, $3, 2)")
+            .bind(id)
+            .bind(email)
+            .bind(name)
+            .execute(pool)
+            .await?;
+    }
+    // Pat wrote the Rust guide (visible to user1) with Sam, and the meeting notes (also
+    // visible to user2) with Kim, and attended the API review
+    for (person_id, document_id, role, days_ago) in [
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4P1", &doc_ids[0], "author", 0),
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4P1", &doc_ids[1], "author", 1),
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4P1", &doc_ids[3], "attendee", 2),
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4P2", &doc_ids[0], "author", 0),
+        ("01JGF7V3E0Y2R1X8P5Q7W9T4P3", &doc_ids[1], "attendee", 1),
+    ] {
+        sqlx::query(
+            "INSERT INTO person_activity (person_id, document_id, role, occurred_at)
+             VALUES ($1, $2, $3, NOW() - make_interval(days => $4))",
+        )
+        .bind(person_id)
+        .bind(document_id)
+        .bind(role)
+        .bind(days_ago)
+        .execute(pool)
+        .await?;
+    }
+
+    let person_profile = |email: &str, user_email: &str| {
+        let request = Request::builder()
+            .uri(format!("/people/{}?user_email={}", email, user_email))
+            .body(Body::empty());
+        let app = fixture.app.clone();
+        async move {
+            let response = app.oneshot(request?).await?;
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+            anyhow::Ok((status, serde_json::from_slice::<Value>(&body).ok()))
+        }
+    };
+
+    let (status, profile) = person_profile("Pat@example.com", "user1").await?;
+    assert_eq!(status, StatusCode::OK);
+    let profile = profile.unwrap();
+    assert_eq!(profile["person"]["name"], "Pat Lee");
+    let titles: Vec<&str> = profile["top_documents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| d["title"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        titles,
+        vec![
+            "Rust Programming Guide",
+            "Q4 Planning Meeting",
+            "REST API Endpoints"
+        ]
+    );
+    assert_eq!(profile["top_documents"][2]["roles"], json!(["attendee"]));
+    let collaborators: Vec<&str> = profile["collaborators"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["email"].as_str().unwrap())
+        .collect();
+    assert_eq!(collaborators, vec!["kim@example.com", "sam@example.com"]);
+
+    // Documents the user cannot see are left out, with the collaborators on them
+    let (_, profile) = person_profile("pat@example.com", "user2").await?;
+    let profile = profile.unwrap();
+    assert_eq!(profile["top_documents"].as_array().unwrap().len(), 2);
+    assert_eq!(profile["top_documents"][0]["title"], "Q4 Planning Meeting");
+    let collaborators = profile["collaborators"].as_array().unwrap();
+    assert_eq!(collaborators.len(), 1);
+    assert_eq!(collaborators[0]["email"], "kim@example.com");
+
+    let (status, _) = person_profile("nobody@example.com", "user1").await?;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_feeds() -> Result<()> {
+    use shared::db::repositories::UserRepository;
+
+    let fixture = SearcherTestFixture::new().await?;
+    let doc_ids = fixture.seed_search_data().await?;
+    let pool = fixture.test_env.db_pool.pool();
+    let user_repo = UserRepository::new(pool);
+    let user1 = user_repo.provision("user1", None, None, true).await?;
+    let user2 = user_repo.provision("user2", None, None, true).await?;
+
+    let record = |document_id: &str, user_id: &str, kind: &str| {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(format!("/documents/{}/interactions", document_id))
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({ "user_id": user_id, "kind": kind }).to_string(),
+            ));
+        let app = fixture.app.clone();
+        async move { anyhow::Ok(app.oneshot(request?).await?.status()) }
+    };
+    let feed = |feed: &str, user_email: &str, limit: usize| {
+        let request = Request::builder()
+            .uri(format!(
+                "/feed/{}?user_email={}&limit={}",
+                feed, user_email, limit
+            ))
+            .body(Body::empty());
+        let app = fixture.app.clone();
+        async move {
+            let response = app.oneshot(request?).await?;
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+            let feed: Value = serde_json::from_slice(&body)?;
+            let titles: Vec<String> = feed["documents"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|d| d["title"].as_str().unwrap().to_string())
+                .collect();
+            anyhow::Ok(titles)
+        }
+    };
+
+    // Repeated clicks by one user count once
+    for _ in 0..3 {
+        assert_eq!(
+            record(&doc_ids[0], &user1.id, "click").await?,
+            StatusCode::OK
+        );
+    }
+    record(&doc_ids[1], &user1.id, "click").await?;
+    record(&doc_ids[1], &user2.id, "view").await?;
+    record(&doc_ids[3], &user2.id, "click").await?;
+    // Documents the user cannot see cannot be recorded
+    assert_eq!(
+        record(&doc_ids[0], &user2.id, "click").await?,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        record(&doc_ids[1], &user1.id, "share").await?,
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+
+    let trending = feed("trending", "user1", 10).await?;
+    assert_eq!(trending.len(), 3);
+    assert_eq!(trending[0], "Q4 Planning Meeting");
+    let trending = feed("trending", "user2", 10).await?;
+    assert_eq!(trending, vec!["Q4 Planning Meeting", "REST API Endpoints"]);
+    let trending = feed("trending", "user3", 10).await?;
+    assert!(trending.is_empty());
+
+    // Updates otherwise set updated_at to now
+    let mut tx = pool.begin().await?;
+    sqlx::query("ALTER TABLE documents DISABLE TRIGGER update_documents_updated_at")
+        .execute(&mut *tx)
+        .await?;
+    for (document_id, days_ago) in [(&doc_ids[3], 0), (&doc_ids[1], 1), (&doc_ids[4], 2)] {
+        sqlx::query(
+            "UPDATE documents SET updated_at = NOW() - make_interval(days => $2) WHERE id = $1",
+        )
+        .bind(document_id)
+        .bind(days_ago)
+        .execute(&mut *tx)
+        .await?;
+    }
+    sqlx::query("ALTER TABLE documents ENABLE TRIGGER update_documents_updated_at")
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    let recent = feed("recent", "user2", 2).await?;
+    assert_eq!(recent, vec!["REST API Endpoints", "Q4 Planning Meeting"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_recommendations() -> Result<()> {
+    use shared::db::repositories::{
+        RecommendationReason, RecommendationRepository, UserRepository,
+    };
+
+    let fixture = SearcherTestFixture::new().await?;
+    let doc_ids = fixture.seed_search_data().await?;
+    let pool = fixture.test_env.db_pool.pool();
+    let user2 = UserRepository::new(pool)
+        .provision("user2", None, None, true)
+        .await?;
+
+    // The Rust guide was recommended before user2 lost access to it
+    RecommendationRepository::new(pool)
+        .replace_for_user(
+            &user2.id,
+            &[
+                (
+                    doc_ids[0].clone(),
+                    RecommendationReason::CollaboratorEdit,
+                    3.0,
+                ),
+                (
+                    doc_ids[3].clone(),
+                    RecommendationReason::MeetingFollowUp,
+                    2.0,
+                ),
+                (doc_ids[4].clone(), RecommendationReason::RecentSearch, 1.0),
+            ],
+        )
+        .await?;
+
+    let request = Request::builder()
+        .uri(format!("/recommendations?user_id={}", user2.id))
+        .body(Body::empty())?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let recommendations: Value = serde_json::from_slice(&body)?;
+    let documents = recommendations["documents"].as_array().unwrap();
+    assert_eq!(documents.len(), 2);
+    assert_eq!(documents[0]["title"], "REST API Endpoints");
+    assert_eq!(documents[0]["reason"], "meeting_follow_up");
+    assert_eq!(documents[1]["title"], "Getting Started Guide");
+    assert!(recommendations["computed_at"].is_string());
+
+    let request = Request::builder()
+        .uri("/recommendations?user_id=unknown")
+        .body(Body::empty())?;
+    let response = fixture.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_batch_search() -> Result<()> {
+    use shared::db::repositories::UserRepository;
+
+    let fixture = SearcherTestFixture::new().await?;
+    let _doc_ids = fixture.seed_search_data().await?;
+    let user_repo = UserRepository::new(fixture.test_env.db_pool.pool());
+    let user2 = user_repo.provision("user2", None, None, true).await?;
+
+    let batch = |body: Value| {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/search/batch")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()));
+        let app = fixture.app.clone();
+        async move {
+            let response = app.oneshot(request?).await?;
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+            anyhow::Ok((status, serde_json::from_slice::<Value>(&body)?))
+        }
+    };
+    let titles = |result: &Value| -> Vec<String> {
+        result["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["document"]["title"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let (status, response) = batch(json!({
+        "user_id": user2.id,
+        "queries": [
+            { "query": "planning", "mode": "fulltext", "result_fields": ["title", "highlights"] },
+            { "query": "architecture", "mode": "fulltext" },
+            { "query": "", "mode": "fulltext" },
+            // The batch's user applies, not one set on the query
+            { "query": "guide", "mode": "fulltext", "user_email": "user1" },
+        ]
+    }))
+    .await?;
+    assert_eq!(status, StatusCode::OK);
+    let results = response["results"].as_array().unwrap();
+    assert_eq!(results.len(), 4);
+    assert_eq!(titles(&results[0]), vec!["Q4 Planning Meeting"]);
+    let selected = &results[0]["results"][0];
+    assert_eq!(selected["document"].as_object().unwrap().len(), 1);
+    assert!(selected["highlights"].is_array());
+    assert!(selected.get("score").is_none());
+    assert!(results[3]["results"][0]["score"].is_number());
+    assert!(titles(&results[1]).is_empty());
+    assert!(results[2]["error"].is_string());
+    assert_eq!(titles(&results[3]), vec!["Getting Started Guide"]);
+
+    let (status, _) = batch(json!({ "user_id": user2.id, "queries": [] })).await?;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let queries: Vec<Value> = (0..21).map(|_| json!({ "query": "guide" })).collect();
+    let (status, _) = batch(json!({ "user_id": user2.id, "queries": queries })).await?;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = batch(json!({
+        "user_id": user2.id,
+        "queries": [{ "query": "guide", "result_fields": ["title", "content_id"] }]
+    }))
+    .await?;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = batch(json!({
+        "user_id": "unknown",
+        "queries": [{ "query": "guide" }]
+    }))
+    .await?;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_grpc_api() -> Result<()> {
+    use omni_proto::searcher::v1::{
+        searcher_client::SearcherClient, RagContextRequest, SearchMode, SearchRequest,
+        SuggestRequest,
+    };
+    use omni_searcher::grpc::SearcherService;
+
+    let fixture = SearcherTestFixture::new().await?;
+    let _doc_ids = fixture.seed_search_data().await?;
+    fixture.title_index.refresh().await?;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let server = SearcherService::new(fixture.state.clone()).into_server();
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(server)
+            .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener)),
+    );
+    let mut client = SearcherClient::connect(format!("http://{}", addr)).await?;
+
+    let response = client
+        .search(SearchRequest {
+            query: "guide".to_string(),
+            user_email: Some("user2".to_string()),
+            mode: SearchMode::Fulltext as i32,
+            ..Default::default()
+        })
+        .await?
+        .into_inner();
+    let titles: Vec<&str> = response
+        .results
+        .iter()
+        .map(|r| r.document.as_ref().unwrap().title.as_str())
+        .collect();
+    assert_eq!(titles, vec!["Getting Started Guide"]);
+    assert_eq!(response.total_count, 1);
+
+    let status = client
+        .search(SearchRequest {
+            query: "guide".to_string(),
+            source_types: vec!["fax".to_string()],
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    let suggestions = client
+        .suggest(SuggestRequest {
+            query: "rest".to_string(),
+            limit: None,
+        })
+        .await?
+        .into_inner()
+        .suggestions;
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(suggestions[0].title, "REST API Endpoints");
+
+    let status = client
+        .get_rag_context(RagContextRequest {
+            question: "How do I get started?".to_string(),
+            user_id: "unknown".to_string(),
+            source_types: vec![],
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_confluence_search() -> Result<()> {
+    use shared::db::repositories::UserRepository;
+
+    let fixture = SearcherTestFixture::new().await?;
+    let _doc_ids = fixture.seed_search_data().await?;
+    let user_repo = UserRepository::new(fixture.test_env.db_pool.pool());
+    let user2 = user_repo.provision("user2", None, None, true).await?;
+
+    let search = |path: &str, cql: &str| {
+        let request = Request::builder()
+            .uri(format!(
+                "/confluence/{}?cql={}&user_id={}&limit=10",
+                path,
+                urlencoding::encode(cql),
+                user2.id
+            ))
+            .body(Body::empty());
+        let app = fixture.app.clone();
+        async move {
+            let response = app.oneshot(request?).await?;
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+            anyhow::Ok((status, serde_json::from_slice::<Value>(&body)?))
+        }
+    };
+
+    let (status, response) = search("search", r#"siteSearch ~ "guide" ORDER BY created"#).await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["totalSize"], 1);
+    assert_eq!(response["size"], 1);
+    assert_eq!(response["start"], 0);
+    assert_eq!(response["limit"], 10);
+    let result = &response["results"][0];
+    assert_eq!(result["title"], "Getting Started Guide");
+    assert_eq!(result["content"]["type"], "page");
+    assert_eq!(result["entityType"], "content");
+    assert!(result["lastModified"].is_string());
+
+    let (status, response) = search("content/search", r#"title ~ "planning""#).await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["size"], 1);
+    assert_eq!(response["results"][0]["title"], "Q4 Planning Meeting");
+
+    // Limiting to Confluence pages leaves out the seeded documents of other sources
+    let (status, response) = search("search", r#"text ~ "guide" AND type = page"#).await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["size"], 0);
+
+    let (status, response) = search("search", r#"text ~ "guide" OR title ~ "api""#).await?;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(response["error"].is_string());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_helpdesk_suggested_articles() -> Result<()> {
+    let fixture = SearcherTestFixture::new().await?;
+    let doc_ids = fixture.seed_search_data().await?;
+    sqlx::query(
+        r#"UPDATE documents SET permissions = permissions || '{"public": true}' WHERE id = $1"#,
+    )
+    .bind(&doc_ids[4])
+    .execute(fixture.test_env.db_pool.pool())
+    .await?;
+
+    let suggest = |token: Option<&str>, body: Value| {
+        let mut request = Request::builder()
+            .method(Method::POST)
+            .uri("/helpdesk/suggested-articles")
+            .header("content-type", "application/json");
+        if let Some(token) = token {
+            request = request.header("authorization", format!("Bearer {}", token));
+        }
+        let request = request.body(Body::from(body.to_string()));
+        let app = fixture.app.clone();
+        async move {
+            let response = app.oneshot(request?).await?;
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+            anyhow::Ok((status, serde_json::from_slice::<Value>(&body)?))
+        }
+    };
+    let token = Some(common::HELPDESK_API_TOKEN);
+
+    // An agent is suggested the documents they can see
+    let (status, response) = suggest(
+        token,
+        json!({ "subject": "Rust guide", "description": "How do I get started?", "user_email": "user1" }),
+    )
+    .await?;
+    assert_eq!(status, StatusCode::OK);
+    let articles = response["articles"].as_array().unwrap();
+    assert!(!articles.is_empty());
+    let titles: Vec<&str> = articles
+        .iter()
+        .map(|a| a["title"].as_str().unwrap())
+        .collect();
+    assert!(
+        titles.contains(&"Rust Programming Guide") || titles.contains(&"Getting Started Guide")
+    );
+    for article in articles {
+        let confidence = article["confidence"].as_f64().unwrap();
+        assert!((0.0..=1.0).contains(&confidence));
+    }
+
+    // Without an agent, only public documents are suggested
+    let (status, response) = suggest(
+        token,
+        json!({ "subject": "Rust guide", "description": "How do I get started?" }),
+    )
+    .await?;
+    assert_eq!(status, StatusCode::OK);
+    let titles: Vec<&str> = response["articles"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| a["title"].as_str().unwrap())
+        .collect();
+    assert!(titles.iter().all(|title| *title == "Getting Started Guide"));
+
+    let (status, response) = suggest(
+        token,
+        json!({ "subject": "guide", "user_email": "user1", "min_confidence": 1.0 }),
+    )
+    .await?;
+    assert_eq!(status, StatusCode::OK);
+    for article in response["articles"].as_array().unwrap() {
+        assert_eq!(article["confidence"].as_f64().unwrap(), 1.0);
+    }
+
+    let (status, _) = suggest(token, json!({ "subject": "  " })).await?;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = suggest(None, json!({ "subject": "guide" })).await?;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = suggest(Some("wrong"), json!({ "subject": "guide" })).await?;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_query_budget() -> Result<()> {
+    let fixture = SearcherTestFixture::new().await?;
+    fixture.seed_search_data().await?;
+
+    let (status, response) = fixture
+        .search_with_body(json!({ "query": "rust programming", "limit": 10 }))
+        .await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["degraded"], false);
+
+    // Over budget, the search returns the matches within it rather than failing
+    let mut state = fixture.state.clone();
+    state.config.budget.max_candidates = 1;
+    let app = omni_searcher::create_app(state);
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/search")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({ "query": "programming", "limit": 10 }).to_string(),
+        ))?;
+    let response = app.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let response: Value = serde_json::from_slice(&body)?;
+    assert_eq!(response["degraded"], true);
+    assert!(response["results"].as_array().unwrap().len() <= 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cross_encoder_rerank() -> Result<()> {
+    let fixture = SearcherTestFixture::new().await?;
+    fixture.seed_search_data().await?;
+
+    let mut state = fixture.state.clone();
+    state.config.rerank_top_n = Some(100);
+    let app = omni_searcher::create_app(state);
+    let search = |body: Value| {
+        let app = app.clone();
+        async move {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri("/search")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))?;
+            let response = app.oneshot(request).await?;
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+            anyhow::Ok(serde_json::from_slice::<Value>(&body)?)
+        }
+    };
+    let ids = |response: &Value| -> Vec<String> {
+        response["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["document"]["id"].as_str().unwrap().to_string())
+            .collect()
+    };
+    let scores = |response: &Value| -> Vec<f64> {
+        response["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["score"].as_f64().unwrap())
+            .collect()
+    };
+
+    let hybrid = json!({ "query": "programming", "mode": "hybrid", "limit": 10, "explain": true });
+    let (status, unranked) = fixture.search_with_body(hybrid.clone()).await?;
+    assert_eq!(status, StatusCode::OK);
+    assert!(ids(&unranked).len() > 1);
+
+    // The mock cross-encoder scores results by position, reversing them. Results take the
+    // scores of their new places.
+    let reranked = search(hybrid.clone()).await?;
+    let cross_encoder_scores: Vec<f64> = reranked["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["ranking"]["cross_encoder_score"].as_f64().unwrap())
+        .collect();
+    assert!(cross_encoder_scores
+        .windows(2)
+        .all(|pair| pair[0] > pair[1]));
+    assert_eq!(scores(&reranked), scores(&unranked));
+    let (mut reranked_ids, mut unranked_ids) = (ids(&reranked), ids(&unranked));
+    reranked_ids.sort();
+    unranked_ids.sort();
+    assert_eq!(reranked_ids, unranked_ids);
+
+    // Requests can opt out
+    let mut opted_out = hybrid;
+    opted_out["rerank"] = json!(false);
+    let opted_out = search(opted_out).await?;
+    assert_eq!(ids(&opted_out), ids(&unranked));
+    assert!(opted_out["results"][0]["ranking"]["cross_encoder_score"].is_null());
+
+    Ok(())
+}
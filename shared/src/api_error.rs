@@ -0,0 +1,176 @@
+//! Errors answered by the services' HTTP APIs.
+//!
+//! Every service answers a failed request with the same body, so clients can tell errors
+//! apart by their code instead of their message:
+//!
+//! ```json
+//! { "error": "Source not found: 01J...", "code": "not_found", "retriable": false }
+//! ```
+//!
+//! `error` is the message for people, `code` one of [`ErrorCode`], and `details`, when
+//! present, data about the error such as the fields of a request that are invalid.
+//! `retriable` tells whether the same request may succeed later, such as once a running sync
+//! finished or a dependency is reachable again.
+
+use crate::db::error::DatabaseError;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    BadRequest,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    Conflict,
+    RateLimited,
+    Unavailable,
+    Internal,
+}
+
+impl ErrorCode {
+    pub fn status(self) -> StatusCode {
+        match self {
+            ErrorCode::BadRequest => StatusCode::BAD_REQUEST,
+            ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ErrorCode::Forbidden => StatusCode::FORBIDDEN,
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::Conflict => StatusCode::CONFLICT,
+            ErrorCode::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Whether requests failing with this code may succeed when retried, unless the error
+    /// says otherwise.
+    pub fn retriable(self) -> bool {
+        matches!(self, ErrorCode::RateLimited | ErrorCode::Unavailable)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, thiserror::Error)]
+#[error("{message}")]
+pub struct ApiError {
+    #[serde(rename = "error")]
+    pub message: String,
+    pub code: ErrorCode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+    #[serde(default)]
+    pub retriable: bool,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            code,
+            details: None,
+            retriable: code.retriable(),
+        }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::BadRequest, message)
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Unauthorized, message)
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Forbidden, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Conflict, message)
+    }
+
+    pub fn rate_limited(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::RateLimited, message)
+    }
+
+    pub fn unavailable(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Unavailable, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// Mark whether the request may succeed when retried, overriding its code's default.
+    pub fn with_retriable(mut self, retriable: bool) -> Self {
+        self.retriable = retriable;
+        self
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.code.status()
+    }
+}
+
+impl From<DatabaseError> for ApiError {
+    fn from(err: DatabaseError) -> Self {
+        match err {
+            DatabaseError::NotFound => ApiError::not_found("Entity not found"),
+            DatabaseError::ConstraintViolation(msg) => ApiError::conflict(msg),
+            DatabaseError::InvalidInput(msg) => ApiError::bad_request(msg),
+            other => ApiError::internal(other.to_string()),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status(), Json(self)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_error_body() {
+        let err = ApiError::conflict("Sync already running for source: abc")
+            .with_details(json!({ "source_id": "abc" }))
+            .with_retriable(true);
+        assert_eq!(err.status(), StatusCode::CONFLICT);
+
+        let body = serde_json::to_value(&err).unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "error": "Sync already running for source: abc",
+                "code": "conflict",
+                "details": { "source_id": "abc" },
+                "retriable": true,
+            })
+        );
+        assert_eq!(serde_json::from_value::<ApiError>(body).unwrap(), err);
+    }
+
+    #[test]
+    fn test_retriable_defaults_to_code() {
+        assert!(ApiError::unavailable("Redis is unreachable").retriable);
+        assert!(!ApiError::not_found("Source not found").retriable);
+        assert_eq!(
+            serde_json::to_value(ApiError::not_found("Source not found")).unwrap(),
+            json!({ "error": "Source not found", "code": "not_found", "retriable": false })
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use anyhow::Result;
+use shared::db::migrations::{applied_migrations, SchemaStatus};
+use shared::{DatabaseConfig, DatabasePool};
+use sqlx::migrate::Migrator;
+use tracing::info;
+
+static MIGRATOR: Migrator = sqlx::migrate!("../services/migrations");
+
+const USAGE: &str = "Usage: omni-migrate <run | revert [target_version] | verify>";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+    tracing_subscriber::fmt::init();
+    shared::secrets::init_from_env().await?;
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = args.first().map(String::as_str);
+    if !matches!(command, Some("run" | "revert" | "verify")) {
+        anyhow::bail!("{}", USAGE);
+    }
+
+    let db_pool = DatabasePool::from_config(
+        &DatabaseConfig::from_env().with_application_name("omni-migrate"),
+    )
+    .await
+    .map_err(|e| anyhow::anyhow!("Failed to create database pool: {}", e))?;
+    let pool = db_pool.pool();
+
+    match command {
+        Some("run") => {
+            MIGRATOR.run(pool).await?;
+            let status = SchemaStatus::compare(&MIGRATOR, &applied_migrations(pool).await?);
+            info!("Migrations applied, {}", status);
+        }
+        Some("revert") => {
+            let applied = applied_migrations(pool).await?;
+            let target = match args.get(1) {
+                Some(target) => target
+                    .parse::<i64>()
+                    .map_err(|_| anyhow::anyhow!("Invalid target version '{}'", target))?,
+                // Revert only the latest migration by default
+                None => applied.iter().rev().nth(1).map_or(0, |m| m.version),
+            };
+
+            for migration in applied.iter().filter(|m| m.version > target) {
+                let reversible = MIGRATOR.iter().any(|m| {
+                    m.version == migration.version && m.migration_type.is_down_migration()
+                });
+                if !reversible {
+                    anyhow::bail!(
+                        "Migration {} has no down migration and cannot be reverted",
+                        migration.version
+                    );
+                }
+            }
+
+            MIGRATOR.undo(pool, target).await?;
+            info!("Reverted migrations after version {}", target);
+        }
+        _ => {
+            let status = SchemaStatus::compare(&MIGRATOR, &applied_migrations(pool).await?);
+            if !status.is_current() {
+                anyhow::bail!("Database schema has drifted: {}", status);
+            }
+            info!("Database schema is current, {}", status);
+        }
+    }
+
+    db_pool.close().await;
+    Ok(())
+}
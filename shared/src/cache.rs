@@ -0,0 +1,398 @@
+//! Layout of the keys services and connectors keep in Redis.
+//!
+//! Every key starts with the deployment's prefix, `REDIS_KEY_PREFIX` (`omni` by default), so
+//! several deployments can share a Redis. After it comes what the key holds:
+//!
+//! - `source:{<source_id>}:<name>`: a connector's sync state for a source, such as the version
+//!   of each item it synced. The braces are a Redis Cluster hash tag, placing every key of a
+//!   source in one slot, so they can be scanned and deleted together. Each expires as
+//!   [`ttl::source_key`] sets, so the state of items gone from a source does not pile up.
+//! - `cache:<kind>:<id>`: a cached value, such as a search response. Caches can be dropped
+//!   at any time and always expire.
+//! - `lock:<name>`: a lock held by one replica of a service.
+//! - `rate_limit:{<name>}`: a token bucket shared by the replicas of a connector.
+//! - `user:{<user_id>}:<name>` and `index:<name>`: state kept across requests.
+//!
+//! Keys are scanned with `SCAN` in batches, never listed with `KEYS`, which blocks Redis
+//! while it walks the whole keyspace.
+
+use redis::aio::MultiplexedConnection;
+use redis::RedisResult;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+pub const DEFAULT_PREFIX: &str = "omni";
+
+/// Cached search responses.
+pub const SEARCH_CACHE: &str = "search";
+/// Cached AI answers.
+pub const AI_ANSWER_CACHE: &str = "ai_answer";
+/// Redis list of a user's latest searches, kept by the searcher.
+pub const SEARCH_HISTORY: &str = "search_history";
+
+/// Time to live of the keys that are not caches, in seconds.
+pub mod ttl {
+    /// A connector's sync state of an item, outliving the longest interval between syncs.
+    pub const SYNC_STATE: u64 = 30 * 24 * 60 * 60;
+    /// The web connector's state of a crawled page, kept longer as sites are crawled less
+    /// often than other sources are synced.
+    pub const CRAWL_STATE: u64 = 90 * 24 * 60 * 60;
+    /// A user's recent searches, from their last search.
+    pub const SEARCH_HISTORY: u64 = 30 * 24 * 60 * 60;
+
+    /// Time to live of the key `name` of a source's sync state.
+    pub fn source_key(name: &str) -> u64 {
+        if name.starts_with("web:") {
+            CRAWL_STATE
+        } else {
+            SYNC_STATE
+        }
+    }
+}
+
+/// Keys of the deployment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keys {
+    prefix: String,
+}
+
+impl Default for Keys {
+    fn default() -> Self {
+        Self::new(DEFAULT_PREFIX)
+    }
+}
+
+impl Keys {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+        }
+    }
+
+    /// Keys prefixed with `REDIS_KEY_PREFIX`, when set.
+    pub fn from_env() -> Self {
+        match std::env::var("REDIS_KEY_PREFIX") {
+            Ok(prefix) if !prefix.is_empty() => Self::new(prefix),
+            _ => Self::default(),
+        }
+    }
+
+    /// Keys of the sync state of a source.
+    pub fn source(&self, source_id: &str) -> SourceKeys {
+        SourceKeys {
+            prefix: format!("{}:source:{{{}}}:", self.prefix, source_id),
+        }
+    }
+
+    /// The entry `id` of cache `kind`.
+    pub fn cache(&self, kind: &str, id: impl Display) -> String {
+        format!("{}:cache:{}:{}", self.prefix, kind, id)
+    }
+
+    /// A pattern matching every entry of cache `kind`.
+    pub fn cache_pattern(&self, kind: &str) -> String {
+        format!("{}:cache:{}:*", self.prefix, kind)
+    }
+
+    pub fn lock(&self, name: &str) -> String {
+        format!("{}:lock:{}", self.prefix, name)
+    }
+
+    pub fn rate_limit(&self, name: &str) -> String {
+        format!("{}:rate_limit:{{{}}}", self.prefix, name)
+    }
+
+    pub fn user(&self, user_id: &str, name: &str) -> String {
+        format!("{}:user:{{{}}}:{}", self.prefix, user_id, name)
+    }
+
+    pub fn index(&self, name: &str) -> String {
+        format!("{}:index:{}", self.prefix, name)
+    }
+}
+
+/// Keys of the sync state of one source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceKeys {
+    prefix: String,
+}
+
+impl SourceKeys {
+    /// The key `name`, such as `google:drive:file:<file_id>`.
+    pub fn key(&self, name: impl Display) -> String {
+        format!("{}{}", self.prefix, name)
+    }
+
+    /// The name of a key of this source, `None` for keys of other sources.
+    pub fn name<'a>(&self, key: &'a str) -> Option<&'a str> {
+        key.strip_prefix(&self.prefix)
+    }
+
+    /// A pattern matching the keys whose name starts with `name_prefix`, every key of the
+    /// source when empty.
+    pub fn pattern(&self, name_prefix: &str) -> String {
+        format!("{}{}*", self.prefix, name_prefix)
+    }
+}
+
+/// Keys of the deployment, prefixed as configured in the environment.
+pub fn keys() -> &'static Keys {
+    static KEYS: OnceLock<Keys> = OnceLock::new();
+    KEYS.get_or_init(Keys::from_env)
+}
+
+/// Every key matching `pattern`.
+pub async fn scan(conn: &mut MultiplexedConnection, pattern: &str) -> RedisResult<Vec<String>> {
+    let mut matching = Vec::new();
+    let mut cursor: u64 = 0;
+    loop {
+        let (next_cursor, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+            .arg(cursor)
+            .arg("MATCH")
+            .arg(pattern)
+            .arg("COUNT")
+            .arg(500)
+            .query_async(conn)
+            .await?;
+        matching.extend(keys);
+
+        if next_cursor == 0 {
+            return Ok(matching);
+        }
+        cursor = next_cursor;
+    }
+}
+
+/// Delete every key matching `pattern`, returning how many were deleted.
+pub async fn delete_matching(
+    conn: &mut MultiplexedConnection,
+    pattern: &str,
+) -> RedisResult<usize> {
+    let mut deleted = 0;
+    for batch in scan(conn, pattern).await?.chunks(500) {
+        let count: usize = redis::cmd("DEL").arg(batch).query_async(conn).await?;
+        deleted += count;
+    }
+    Ok(deleted)
+}
+
+/// Drop every cached search response and AI answer, returning how many were dropped. They are
+/// cached per user and may include documents the user can no longer see.
+pub async fn clear_response_caches(conn: &mut MultiplexedConnection) -> RedisResult<usize> {
+    let mut cleared = 0;
+    for kind in [SEARCH_CACHE, AI_ANSWER_CACHE] {
+        cleared += delete_matching(conn, &keys().cache_pattern(kind)).await?;
+    }
+    Ok(cleared)
+}
+
+/// Set the time to live of a source's keys that do not expire, as [`ttl::source_key`] sets
+/// it, returning how many were set. Keys written before connectors set one would otherwise
+/// be kept forever.
+pub async fn expire_source_keys(
+    conn: &mut MultiplexedConnection,
+    source_id: &str,
+) -> RedisResult<usize> {
+    let source_keys = keys().source(source_id);
+    let mut expired = 0;
+    for key in scan(conn, &source_keys.pattern("")).await? {
+        // -1 is a key without a time to live, -2 one deleted since the scan
+        let ttl: i64 = redis::cmd("TTL").arg(&key).query_async(conn).await?;
+        if ttl != -1 {
+            continue;
+        }
+        let name = source_keys.name(&key).unwrap_or(&key);
+        let set: bool = redis::cmd("EXPIRE")
+            .arg(&key)
+            .arg(ttl::source_key(name))
+            .query_async(conn)
+            .await?;
+        if set {
+            expired += 1;
+        }
+    }
+    Ok(expired)
+}
+
+/// Name of the key marking that a source's legacy keys were migrated.
+const LEGACY_KEYS_MIGRATED: &str = "legacy_keys_migrated";
+
+/// Move a source's sync state written before keys were prefixed to the source's keys, keeping
+/// each value's time to live. `renames` pairs the prefix of the legacy keys, which the source
+/// ID followed, e.g. `google:drive:`, with the prefix of their new names, e.g.
+/// `google:drive:file:`. Only string values move, and those that did not expire are given the
+/// time to live of the source's keys. Runs once per source, later calls only check that it ran.
+/// Returns how many keys moved.
+pub async fn migrate_legacy_source_keys(
+    conn: &mut MultiplexedConnection,
+    source_id: &str,
+    renames: &[(&str, &str)],
+) -> RedisResult<usize> {
+    let source_keys = keys().source(source_id);
+    let marker = source_keys.key(LEGACY_KEYS_MIGRATED);
+    let migrated: bool = redis::cmd("EXISTS").arg(&marker).query_async(conn).await?;
+    if migrated {
+        return Ok(0);
+    }
+
+    let mut moved = 0;
+    for (legacy_prefix, name_prefix) in renames {
+        let legacy_prefix = format!("{}{}:", legacy_prefix, source_id);
+        for legacy_key in scan(conn, &format!("{}*", legacy_prefix)).await? {
+            let Some(name) = legacy_key.strip_prefix(&legacy_prefix) else {
+                continue;
+            };
+            let value: Option<String> =
+                redis::cmd("GET").arg(&legacy_key).query_async(conn).await?;
+            let ttl: i64 = redis::cmd("TTL").arg(&legacy_key).query_async(conn).await?;
+            if let Some(value) = value {
+                let name = format!("{}{}", name_prefix, name);
+                let ttl = if ttl > 0 {
+                    ttl as u64
+                } else {
+                    ttl::source_key(&name)
+                };
+                let _: () = redis::cmd("SET")
+                    .arg(source_keys.key(name))
+                    .arg(value)
+                    .arg("EX")
+                    .arg(ttl)
+                    .query_async(conn)
+                    .await?;
+                moved += 1;
+            }
+            let _: () = redis::cmd("DEL").arg(&legacy_key).query_async(conn).await?;
+        }
+    }
+
+    let _: () = redis::cmd("SET")
+        .arg(&marker)
+        .arg(1)
+        .arg("EX")
+        .arg(ttl::source_key(LEGACY_KEYS_MIGRATED))
+        .query_async(conn)
+        .await?;
+    Ok(moved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_keys_share_a_hash_tag() {
+        let keys = Keys::new("acme");
+        let source = keys.source("01JSOURCE");
+        let key = source.key("google:drive:file:abc");
+        assert_eq!(key, "acme:source:{01JSOURCE}:google:drive:file:abc");
+        assert_eq!(source.name(&key), Some("google:drive:file:abc"));
+        assert_eq!(keys.source("01JOTHER").name(&key), None);
+        assert_eq!(source.pattern(""), "acme:source:{01JSOURCE}:*");
+    }
+
+    #[test]
+    fn test_cache_keys() {
+        let keys = Keys::default();
+        assert_eq!(keys.cache(SEARCH_CACHE, "1f"), "omni:cache:search:1f");
+        assert_eq!(keys.cache_pattern(SEARCH_CACHE), "omni:cache:search:*");
+        assert_eq!(keys.lock("search_warmup"), "omni:lock:search_warmup");
+        assert_eq!(
+            keys.rate_limit("google:api"),
+            "omni:rate_limit:{google:api}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_migrate_legacy_source_keys() {
+        let redis = crate::test_environment::MemoryRedis::start().await.unwrap();
+        let client = redis::Client::open(format!("redis://localhost:{}", redis.port)).unwrap();
+        let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+        for (key, value) in [
+            ("google:drive:01JSOURCE:file1", "2024-01-01"),
+            ("google:drive:01JOTHER:file2", "2024-01-02"),
+        ] {
+            let _: () = redis::cmd("SET")
+                .arg(key)
+                .arg(value)
+                .arg("EX")
+                .arg(600)
+                .query_async(&mut conn)
+                .await
+                .unwrap();
+        }
+
+        let renames = [("google:drive:", "google:drive:file:")];
+        let moved = migrate_legacy_source_keys(&mut conn, "01JSOURCE", &renames)
+            .await
+            .unwrap();
+        assert_eq!(moved, 1);
+
+        let key = keys().source("01JSOURCE").key("google:drive:file:file1");
+        let value: Option<String> = redis::cmd("GET")
+            .arg(&key)
+            .query_async(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(value.as_deref(), Some("2024-01-01"));
+        let ttl: i64 = redis::cmd("TTL")
+            .arg(&key)
+            .query_async(&mut conn)
+            .await
+            .unwrap();
+        assert!(ttl > 0 && ttl <= 600);
+        let remaining = scan(&mut conn, "google:drive:*").await.unwrap();
+        assert_eq!(remaining, vec!["google:drive:01JOTHER:file2"]);
+
+        let moved = migrate_legacy_source_keys(&mut conn, "01JSOURCE", &renames)
+            .await
+            .unwrap();
+        assert_eq!(moved, 0);
+    }
+
+    #[tokio::test]
+    async fn test_expire_source_keys() {
+        let redis = crate::test_environment::MemoryRedis::start().await.unwrap();
+        let client = redis::Client::open(format!("redis://localhost:{}", redis.port)).unwrap();
+        let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+        let source = keys().source("01JSOURCE");
+        let expiring = source.key("google:drive:file:file1");
+        let persistent = [
+            source.key("google:drive:file:file2"),
+            source.key("web:page:1f"),
+        ];
+        let other = keys().source("01JOTHER").key("google:drive:file:file3");
+        let _: () = redis::cmd("SET")
+            .arg(&expiring)
+            .arg("2024-01-01")
+            .arg("EX")
+            .arg(600)
+            .query_async(&mut conn)
+            .await
+            .unwrap();
+        for key in persistent.iter().chain([&other]) {
+            let _: () = redis::cmd("SET")
+                .arg(key)
+                .arg("2024-01-01")
+                .query_async(&mut conn)
+                .await
+                .unwrap();
+        }
+
+        let expired = expire_source_keys(&mut conn, "01JSOURCE").await.unwrap();
+        assert_eq!(expired, 2);
+
+        let mut ttls = Vec::new();
+        for key in [&expiring, &persistent[0], &persistent[1], &other] {
+            let ttl: i64 = redis::cmd("TTL")
+                .arg(key)
+                .query_async(&mut conn)
+                .await
+                .unwrap();
+            ttls.push(ttl);
+        }
+        assert!(ttls[0] > 0 && ttls[0] <= 600);
+        assert!(ttls[1] > 600 && ttls[1] <= ttl::SYNC_STATE as i64);
+        assert!(ttls[2] > ttl::SYNC_STATE as i64 && ttls[2] <= ttl::CRAWL_STATE as i64);
+        assert_eq!(ttls[3], -1);
+    }
+}
@@ -8,6 +8,17 @@ use tracing::{debug, error};
 
 use crate::telemetry::http_client::RequestBuilderExt;
 
+/// Which model to embed with while an embedding model migration is in progress. The active
+/// model serves searches, the shadow model is the other one. Without a migration there is no
+/// shadow model and requests for it fail.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingModelRole {
+    #[default]
+    Active,
+    Shadow,
+}
+
 #[derive(Serialize)]
 pub struct EmbeddingRequest {
     pub texts: Vec<String>,
@@ -15,6 +26,8 @@ pub struct EmbeddingRequest {
     pub chunk_size: Option<i32>,
     pub chunking_mode: Option<String>,
     pub priority: Option<String>, // "high", "normal", or "low"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<EmbeddingModelRole>,
 }
 
 #[derive(Deserialize)]
@@ -32,6 +45,18 @@ pub struct TextEmbedding {
     pub model_name: Option<String>,   // name of the model used for embeddings
 }
 
+#[derive(Serialize)]
+pub struct RerankRequest {
+    pub query: String,
+    pub documents: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RerankResponse {
+    pub scores: Vec<f32>, // relevance score per document, in request order
+    pub model_name: String,
+}
+
 #[derive(Serialize)]
 pub struct PromptRequest {
     pub prompt: String,
@@ -85,6 +110,21 @@ impl AIClient {
         chunk_size: Option<i32>,
         chunking_mode: Option<String>,
         priority: Option<String>,
+    ) -> Result<Vec<TextEmbedding>> {
+        self.generate_embeddings_with_model(texts, task, chunk_size, chunking_mode, priority, None)
+            .await
+    }
+
+    /// Like `generate_embeddings_with_options`, embedding with the given model of an
+    /// embedding model migration. The model used is reported in each `TextEmbedding`.
+    pub async fn generate_embeddings_with_model(
+        &self,
+        texts: Vec<String>,
+        task: Option<String>,
+        chunk_size: Option<i32>,
+        chunking_mode: Option<String>,
+        priority: Option<String>,
+        model: Option<EmbeddingModelRole>,
     ) -> Result<Vec<TextEmbedding>> {
         let request = EmbeddingRequest {
             texts,
@@ -92,6 +132,7 @@ impl AIClient {
             chunk_size,
             chunking_mode,
             priority,
+            model,
         };
 
         let response = self
@@ -143,6 +184,49 @@ impl AIClient {
         }
     }
 
+    /// Score how relevant each document is to the query with the AI service's cross-encoder,
+    /// in the order of the documents. Fails when the AI service has no reranker configured.
+    pub async fn rerank(&self, query: &str, documents: Vec<String>) -> Result<Vec<f32>> {
+        let document_count = documents.len();
+        let request = RerankRequest {
+            query: query.to_string(),
+            documents,
+        };
+
+        let response = self
+            .client
+            .post(format!("{}/rerank", self.base_url))
+            .json(&request)
+            .with_trace_context()
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to connect to rerank API: {:?}.", e))?;
+
+        let status_code = response.status();
+        if !status_code.is_success() {
+            let resp_text = response.text().await?;
+            return Err(anyhow!(
+                "Rerank API failed with error: [{}] {:?}",
+                status_code,
+                resp_text
+            ));
+        }
+
+        let rerank_response: RerankResponse = response.json().await?;
+        if rerank_response.scores.len() != document_count {
+            return Err(anyhow!(
+                "Rerank API returned {} scores for {} documents",
+                rerank_response.scores.len(),
+                document_count
+            ));
+        }
+        debug!(
+            "Reranked {} documents with {}",
+            document_count, rerank_response.model_name
+        );
+        Ok(rerank_response.scores)
+    }
+
     // Keep backward compatibility method for single text
     #[deprecated(note = "Use generate_embeddings instead")]
     pub async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
@@ -1,3 +1,5 @@
+use crate::secrets;
+use serde::{Deserialize, Serialize};
 use std::env;
 use std::process;
 use url::Url;
@@ -8,6 +10,12 @@ pub struct DatabaseConfig {
     pub max_connections: u32,
     pub acquire_timeout_seconds: u64,
     pub require_ssl: bool,
+    /// Postgres `statement_timeout` set on every pooled connection, none when unset.
+    pub statement_timeout_ms: Option<u64>,
+    /// Queries running longer than this are reported by the pool metrics as slow.
+    pub slow_query_threshold_ms: u64,
+    /// Name the pool's connections report in `pg_stat_activity`.
+    pub application_name: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -15,16 +23,142 @@ pub struct RedisConfig {
     pub redis_url: String,
 }
 
+/// Certificates for mutual TLS between services. Servers only accept clients presenting a
+/// certificate signed by `ca_path`, and clients present `cert_path` to the services they call.
+#[derive(Debug, Clone)]
+pub struct ServiceTlsConfig {
+    pub cert_path: String,
+    pub key_path: String,
+    pub ca_path: String,
+    /// When false the service also accepts clients without a certificate, for services called
+    /// by the web gateway or TypeScript connectors, which can't present one.
+    pub require_client_cert: bool,
+}
+
+/// Azure bot registration used by the Microsoft Teams bot.
+#[derive(Debug, Clone)]
+pub struct TeamsBotConfig {
+    pub app_id: String,
+    pub app_password: String,
+    /// Tenant of a single-tenant bot registration. Multi-tenant bots authenticate against the
+    /// shared `botframework.com` tenant.
+    pub tenant_id: Option<String>,
+}
+
+/// SMTP server email notifications are sent through. Shares the `EMAIL_*` settings the web
+/// app sends sign-in links with.
+#[derive(Debug, Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Connect over TLS from the start rather than upgrading with STARTTLS when offered
+    pub secure: bool,
+    pub from: String,
+    /// Base URL of the web app, for links in messages
+    pub app_url: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct SearcherConfig {
     pub database: DatabaseConfig,
     pub redis: RedisConfig,
     pub port: u16,
     pub ai_service_url: String,
+    /// Relevance tunables, as read from the environment. The searcher overrides them at
+    /// runtime from its config store.
+    pub tuning: SearchTuning,
+    pub budget: QueryBudget,
+    /// How often the most frequent recent searches are run again to keep their caches warm,
+    /// `None` when they are not.
+    pub warmup_interval_seconds: Option<u64>,
+    /// How many of the most frequent searches are kept warm.
+    pub warmup_top_queries: i64,
+    pub query_log_sample_rate: f64,
+    pub slow_query_threshold_ms: u64,
+    /// Port of the gRPC API served alongside the HTTP one, `None` when it is not served.
+    pub grpc_port: Option<u16>,
+    /// Bearer token help desk integrations fetch suggested articles with, `None` when they
+    /// cannot.
+    pub helpdesk_api_token: Option<String>,
+    /// How many of the best hybrid search results the AI service's cross-encoder orders
+    /// again, `None` when results are not reranked.
+    pub rerank_top_n: Option<usize>,
+    /// How long reranking may take before results keep their hybrid search order.
+    pub rerank_timeout_ms: u64,
+}
+
+/// How hybrid search combines the full-text and semantic scores of a result.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HybridFusion {
+    /// Add the full-text and semantic scores, each times its weight
+    #[default]
+    Weighted,
+    /// Reciprocal Rank Fusion: add `1 / (k + rank)` for the result's rank in each search, so
+    /// the scores' scales don't matter
+    Rrf,
+}
+
+impl std::str::FromStr for HybridFusion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "weighted" => Ok(Self::Weighted),
+            "rrf" => Ok(Self::Rrf),
+            _ => Err(format!("Unknown hybrid fusion '{}'", s)),
+        }
+    }
+}
+
+/// Searcher settings that affect relevance, which admins tune while the searcher runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchTuning {
+    pub hybrid_fusion: HybridFusion,
+    /// RRF's `k`: the larger, the less the top ranks of either search outweigh the others
+    pub hybrid_rrf_k: u32,
     pub hybrid_search_fts_weight: f32,
     pub hybrid_search_semantic_weight: f32,
+    /// Weight of the number of documents linking to a result in hybrid ranking, 0 to ignore
+    /// links
+    pub link_authority_weight: f32,
     pub semantic_search_timeout_ms: u64,
     pub rag_context_window: i32,
+    /// Retry searches that find nothing with misspelled terms matched
+    pub typo_tolerance: bool,
+    /// Apply the admins' source, content type and flag boosts to hybrid results
+    pub ranking_boosts: bool,
+}
+
+impl SearchTuning {
+    /// Hybrid score of a result ranking first in both searches with full scores, before
+    /// boosts.
+    pub fn max_hybrid_score(&self) -> f32 {
+        match self.hybrid_fusion {
+            HybridFusion::Weighted => {
+                self.hybrid_search_fts_weight + self.hybrid_search_semantic_weight
+            }
+            HybridFusion::Rrf => 2.0 / (self.hybrid_rrf_k as f32 + 1.0),
+        }
+    }
+}
+
+/// Most work one search may do. Searches over budget return what they found within it,
+/// marked as degraded, rather than piling up under load.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryBudget {
+    /// Full-text matches ranked, counting those skipped by the offset
+    pub max_candidates: i64,
+    /// Size of the candidate list an HNSW index search compares the query vector against
+    pub max_vector_comparisons: i64,
+    /// Hybrid results re-ranked with boosts, flags and links
+    pub max_rerank_candidates: usize,
+    /// Time after which a search returns what it found so far
+    pub deadline_ms: u64,
+    /// Searches run at once before more are admitted with full-text matches only
+    pub max_concurrent_searches: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -33,6 +167,16 @@ pub struct IndexerConfig {
     pub redis: RedisConfig,
     pub port: u16,
     pub ai_service_url: String,
+    /// How often home-page recommendations are recomputed, `None` when they are not.
+    pub recommendation_interval_seconds: Option<u64>,
+    /// How often stale content is looked for, `None` when it is not.
+    pub stale_content_interval_seconds: Option<u64>,
+    /// How often duplicate content is looked for, `None` when it is not.
+    pub duplicate_content_interval_seconds: Option<u64>,
+    /// How often users due a digest are looked for, `None` when digests are not sent.
+    pub digest_interval_seconds: Option<u64>,
+    /// Where digests are sent through, `None` when email is not configured.
+    pub smtp: Option<SmtpConfig>,
 }
 
 #[derive(Debug, Clone)]
@@ -42,7 +186,7 @@ pub struct ConnectorConfig {
 }
 
 fn get_required_env(key: &str) -> String {
-    env::var(key).unwrap_or_else(|_| {
+    secrets::get(key).unwrap_or_else(|| {
         eprintln!("ERROR: Required environment variable '{}' is not set", key);
         eprintln!("Please set this variable in your .env file or environment");
         process::exit(1);
@@ -50,7 +194,7 @@ fn get_required_env(key: &str) -> String {
 }
 
 fn get_optional_env(key: &str, default: &str) -> String {
-    env::var(key).unwrap_or_else(|_| default.to_string())
+    secrets::get(key).unwrap_or_else(|| default.to_string())
 }
 
 fn parse_port(port_str: &str, var_name: &str) -> u16 {
@@ -64,6 +208,18 @@ fn parse_port(port_str: &str, var_name: &str) -> u16 {
     })
 }
 
+fn parse_positive<T: std::str::FromStr + PartialOrd + Default>(var_name: &str, default: &str) -> T {
+    get_optional_env(var_name, default)
+        .parse::<T>()
+        .ok()
+        .filter(|value| *value > T::default())
+        .unwrap_or_else(|| {
+            eprintln!("ERROR: Invalid value for {}", var_name);
+            eprintln!("Must be a positive integer");
+            process::exit(1);
+        })
+}
+
 fn validate_url(url: &str, var_name: &str) -> String {
     if url.is_empty() {
         eprintln!("ERROR: Environment variable '{}' cannot be empty", var_name);
@@ -137,13 +293,51 @@ impl DatabaseConfig {
             process::exit(1);
         });
 
+        let statement_timeout_str = get_optional_env("DB_STATEMENT_TIMEOUT_MS", "0");
+        let statement_timeout_ms = statement_timeout_str.parse::<u64>().unwrap_or_else(|_| {
+            eprintln!(
+                "ERROR: Invalid timeout in 'DB_STATEMENT_TIMEOUT_MS': '{}'",
+                statement_timeout_str
+            );
+            eprintln!("Must be a positive number, or 0 to disable the timeout");
+            process::exit(1);
+        });
+        let statement_timeout_ms = (statement_timeout_ms > 0).then_some(statement_timeout_ms);
+
+        let slow_query_threshold_str = get_optional_env("DB_SLOW_QUERY_THRESHOLD_MS", "1000");
+        let slow_query_threshold_ms =
+            slow_query_threshold_str.parse::<u64>().unwrap_or_else(|_| {
+                eprintln!(
+                    "ERROR: Invalid threshold in 'DB_SLOW_QUERY_THRESHOLD_MS': '{}'",
+                    slow_query_threshold_str
+                );
+                eprintln!("Must be a positive number");
+                process::exit(1);
+            });
+
         Self {
             database_url,
             max_connections,
             acquire_timeout_seconds,
             require_ssl,
+            statement_timeout_ms,
+            slow_query_threshold_ms,
+            application_name: None,
         }
     }
+
+    /// Tag the pool's connections with the service name, so its queries can be told apart
+    /// in `pg_stat_activity` and in the slow query metrics.
+    pub fn with_application_name(mut self, application_name: &str) -> Self {
+        self.application_name = Some(application_name.to_string());
+        self
+    }
+
+    /// Statement timeout to use when `DB_STATEMENT_TIMEOUT_MS` is not set.
+    pub fn with_default_statement_timeout(mut self, timeout_ms: u64) -> Self {
+        self.statement_timeout_ms.get_or_insert(timeout_ms);
+        self
+    }
 }
 
 impl RedisConfig {
@@ -155,9 +349,94 @@ impl RedisConfig {
     }
 }
 
+impl ServiceTlsConfig {
+    /// Returns `None` when mutual TLS is not configured.
+    pub fn from_env() -> Option<Self> {
+        let var = |key: &str| env::var(key).ok().filter(|value| !value.is_empty());
+
+        match (
+            var("SERVICE_TLS_CERT_PATH"),
+            var("SERVICE_TLS_KEY_PATH"),
+            var("SERVICE_TLS_CA_PATH"),
+        ) {
+            (Some(cert_path), Some(key_path), Some(ca_path)) => Some(Self {
+                cert_path,
+                key_path,
+                ca_path,
+                require_client_cert: var("SERVICE_TLS_REQUIRE_CLIENT_CERT")
+                    .is_none_or(|value| value != "false"),
+            }),
+            (None, None, None) => None,
+            _ => {
+                eprintln!("ERROR: Incomplete mutual TLS configuration");
+                eprintln!(
+                    "SERVICE_TLS_CERT_PATH, SERVICE_TLS_KEY_PATH and SERVICE_TLS_CA_PATH must be set together"
+                );
+                process::exit(1);
+            }
+        }
+    }
+}
+
+/// Facet and aggregate queries can scan large parts of the documents table, so the searcher
+/// cancels any statement running longer than this unless `DB_STATEMENT_TIMEOUT_MS` says otherwise.
+const SEARCHER_DEFAULT_STATEMENT_TIMEOUT_MS: u64 = 30_000;
+
+impl TeamsBotConfig {
+    /// Returns `None` when the Teams bot is not configured.
+    pub fn from_env() -> Option<Self> {
+        let var = |key: &str| secrets::get(key).filter(|value| !value.is_empty());
+
+        match (var("TEAMS_BOT_APP_ID"), var("TEAMS_BOT_APP_PASSWORD")) {
+            (Some(app_id), Some(app_password)) => Some(Self {
+                app_id,
+                app_password,
+                tenant_id: var("TEAMS_BOT_TENANT_ID"),
+            }),
+            (None, None) => None,
+            _ => {
+                eprintln!("ERROR: Incomplete Teams bot configuration");
+                eprintln!("TEAMS_BOT_APP_ID and TEAMS_BOT_APP_PASSWORD must be set together");
+                process::exit(1);
+            }
+        }
+    }
+}
+
+impl SmtpConfig {
+    /// Returns `None` when `EMAIL_HOST` is not set.
+    pub fn from_env() -> Option<Self> {
+        let var = |key: &str| secrets::get(key).filter(|value| !value.is_empty());
+
+        let host = var("EMAIL_HOST")?;
+        let Some(from) = var("EMAIL_FROM") else {
+            eprintln!("ERROR: Incomplete email configuration");
+            eprintln!("EMAIL_FROM must be set when EMAIL_HOST is set");
+            process::exit(1);
+        };
+        let port = var("EMAIL_PORT")
+            .map(|port| parse_port(&port, "EMAIL_PORT"))
+            .unwrap_or(587);
+
+        Some(Self {
+            host,
+            port,
+            username: var("EMAIL_USER"),
+            password: var("EMAIL_PASSWORD"),
+            secure: var("EMAIL_SECURE").is_some_and(|value| value == "true"),
+            from,
+            app_url: get_optional_env("APP_URL", "http://localhost:3000")
+                .trim_end_matches('/')
+                .to_string(),
+        })
+    }
+}
+
 impl SearcherConfig {
     pub fn from_env() -> Self {
-        let database = DatabaseConfig::from_env();
+        let database = DatabaseConfig::from_env()
+            .with_application_name("omni-searcher")
+            .with_default_statement_timeout(SEARCHER_DEFAULT_STATEMENT_TIMEOUT_MS);
         let redis = RedisConfig::from_env();
 
         let port_str = get_required_env("PORT");
@@ -166,6 +445,21 @@ impl SearcherConfig {
         let ai_service_url = get_required_env("AI_SERVICE_URL");
         let ai_service_url = validate_url(&ai_service_url, "AI_SERVICE_URL");
 
+        let hybrid_fusion = get_optional_env("HYBRID_SEARCH_FUSION", "weighted")
+            .parse::<HybridFusion>()
+            .unwrap_or_else(|_| {
+                eprintln!("ERROR: Invalid value for HYBRID_SEARCH_FUSION");
+                eprintln!("Must be 'weighted' or 'rrf'");
+                process::exit(1);
+            });
+        let hybrid_rrf_k = get_optional_env("HYBRID_SEARCH_RRF_K", "60")
+            .parse::<u32>()
+            .unwrap_or_else(|_| {
+                eprintln!("ERROR: Invalid value for HYBRID_SEARCH_RRF_K");
+                eprintln!("Must be a non-negative integer");
+                process::exit(1);
+            });
+
         let hybrid_search_fts_weight = get_optional_env("HYBRID_SEARCH_FTS_WEIGHT", "0.3")
             .parse::<f32>()
             .unwrap_or_else(|_| {
@@ -182,6 +476,15 @@ impl SearcherConfig {
                     eprintln!("Must be a float between 0.0 and 1.0");
                     process::exit(1);
                 });
+        let link_authority_weight = get_optional_env("LINK_AUTHORITY_WEIGHT", "0.1")
+            .parse::<f32>()
+            .ok()
+            .filter(|weight| weight.is_finite() && *weight >= 0.0)
+            .unwrap_or_else(|| {
+                eprintln!("ERROR: Invalid value for LINK_AUTHORITY_WEIGHT");
+                eprintln!("Must be a non-negative float");
+                process::exit(1);
+            });
         let semantic_search_timeout_ms = get_optional_env("SEMANTIC_SEARCH_TIMEOUT_MS", "5000")
             .parse::<u64>()
             .unwrap_or_else(|_| {
@@ -198,22 +501,86 @@ impl SearcherConfig {
                 process::exit(1);
             });
 
+        let query_log_sample_rate = get_optional_env("QUERY_LOG_SAMPLE_RATE", "1.0")
+            .parse::<f64>()
+            .ok()
+            .filter(|rate| (0.0..=1.0).contains(rate))
+            .unwrap_or_else(|| {
+                eprintln!("ERROR: Invalid value for QUERY_LOG_SAMPLE_RATE");
+                eprintln!("Must be a float between 0.0 and 1.0");
+                process::exit(1);
+            });
+
+        let slow_query_threshold_ms = get_optional_env("SLOW_QUERY_THRESHOLD_MS", "1000")
+            .parse::<u64>()
+            .unwrap_or_else(|_| {
+                eprintln!("ERROR: Invalid value for SLOW_QUERY_THRESHOLD_MS");
+                eprintln!("Must be a positive integer");
+                process::exit(1);
+            });
+
+        let budget = QueryBudget {
+            max_candidates: parse_positive("SEARCH_MAX_CANDIDATES", "1000"),
+            max_vector_comparisons: parse_positive("SEARCH_MAX_VECTOR_COMPARISONS", "400"),
+            max_rerank_candidates: parse_positive("SEARCH_MAX_RERANK_CANDIDATES", "200"),
+            deadline_ms: parse_positive("SEARCH_DEADLINE_MS", "10000"),
+            max_concurrent_searches: parse_positive("SEARCH_MAX_CONCURRENT", "64"),
+        };
+
+        let warmup_interval_seconds = get_optional_env("SEARCH_WARMUP_INTERVAL_SECONDS", "240")
+            .parse::<u64>()
+            .unwrap_or_else(|_| {
+                eprintln!("ERROR: Invalid value for SEARCH_WARMUP_INTERVAL_SECONDS");
+                eprintln!("Must be a positive integer, or 0 to disable cache warm-up");
+                process::exit(1);
+            });
+        let warmup_top_queries = parse_positive("SEARCH_WARMUP_TOP_QUERIES", "50");
+
+        let rerank_top_n = get_optional_env("SEARCH_RERANK_TOP_N", "0")
+            .parse::<usize>()
+            .unwrap_or_else(|_| {
+                eprintln!("ERROR: Invalid value for SEARCH_RERANK_TOP_N");
+                eprintln!("Must be a positive integer, or 0 to disable reranking");
+                process::exit(1);
+            });
+        let rerank_timeout_ms = parse_positive("SEARCH_RERANK_TIMEOUT_MS", "1000");
+
         Self {
             database,
             redis,
             port,
             ai_service_url,
-            hybrid_search_fts_weight,
-            hybrid_search_semantic_weight,
-            semantic_search_timeout_ms,
-            rag_context_window,
+            tuning: SearchTuning {
+                hybrid_fusion,
+                hybrid_rrf_k,
+                hybrid_search_fts_weight,
+                hybrid_search_semantic_weight,
+                link_authority_weight,
+                semantic_search_timeout_ms,
+                rag_context_window,
+                typo_tolerance: true,
+                ranking_boosts: true,
+            },
+            budget,
+            warmup_interval_seconds: (warmup_interval_seconds > 0)
+                .then_some(warmup_interval_seconds),
+            warmup_top_queries,
+            query_log_sample_rate,
+            slow_query_threshold_ms,
+            grpc_port: secrets::get("GRPC_PORT")
+                .filter(|port| !port.is_empty())
+                .map(|port| parse_port(&port, "GRPC_PORT")),
+            helpdesk_api_token: secrets::get("HELPDESK_API_TOKEN")
+                .filter(|token| !token.is_empty()),
+            rerank_top_n: (rerank_top_n > 0).then_some(rerank_top_n),
+            rerank_timeout_ms,
         }
     }
 }
 
 impl IndexerConfig {
     pub fn from_env() -> Self {
-        let database = DatabaseConfig::from_env();
+        let database = DatabaseConfig::from_env().with_application_name("omni-indexer");
         let redis = RedisConfig::from_env();
 
         let port_str = get_required_env("PORT");
@@ -222,11 +589,55 @@ impl IndexerConfig {
         let ai_service_url = get_required_env("AI_SERVICE_URL");
         let ai_service_url = validate_url(&ai_service_url, "AI_SERVICE_URL");
 
+        let recommendation_interval_seconds =
+            get_optional_env("RECOMMENDATION_INTERVAL_SECONDS", "3600")
+                .parse::<u64>()
+                .unwrap_or_else(|_| {
+                    eprintln!("ERROR: Invalid value for RECOMMENDATION_INTERVAL_SECONDS");
+                    eprintln!("Must be a positive integer, or 0 to disable recommendations");
+                    process::exit(1);
+                });
+
+        let stale_content_interval_seconds =
+            get_optional_env("STALE_CONTENT_INTERVAL_SECONDS", "86400")
+                .parse::<u64>()
+                .unwrap_or_else(|_| {
+                    eprintln!("ERROR: Invalid value for STALE_CONTENT_INTERVAL_SECONDS");
+                    eprintln!("Must be a positive integer, or 0 to disable the analysis");
+                    process::exit(1);
+                });
+
+        let duplicate_content_interval_seconds =
+            get_optional_env("DUPLICATE_CONTENT_INTERVAL_SECONDS", "86400")
+                .parse::<u64>()
+                .unwrap_or_else(|_| {
+                    eprintln!("ERROR: Invalid value for DUPLICATE_CONTENT_INTERVAL_SECONDS");
+                    eprintln!("Must be a positive integer, or 0 to disable the analysis");
+                    process::exit(1);
+                });
+
+        let digest_interval_seconds = get_optional_env("DIGEST_INTERVAL_SECONDS", "3600")
+            .parse::<u64>()
+            .unwrap_or_else(|_| {
+                eprintln!("ERROR: Invalid value for DIGEST_INTERVAL_SECONDS");
+                eprintln!("Must be a positive integer, or 0 to disable digests");
+                process::exit(1);
+            });
+
         Self {
             database,
             redis,
             port,
             ai_service_url,
+            recommendation_interval_seconds: (recommendation_interval_seconds > 0)
+                .then_some(recommendation_interval_seconds),
+            stale_content_interval_seconds: (stale_content_interval_seconds > 0)
+                .then_some(stale_content_interval_seconds),
+            duplicate_content_interval_seconds: (duplicate_content_interval_seconds > 0)
+                .then_some(duplicate_content_interval_seconds),
+            digest_interval_seconds: (digest_interval_seconds > 0)
+                .then_some(digest_interval_seconds),
+            smtp: SmtpConfig::from_env(),
         }
     }
 }
@@ -0,0 +1,124 @@
+use crate::config::DatabaseConfig;
+use opentelemetry::{
+    global,
+    metrics::{Gauge, Histogram},
+    KeyValue,
+};
+use sqlx::PgPool;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+const POOL_METRICS_REPORT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Connection pool and slow query gauges, exported through the global OTel meter provider.
+///
+/// Pool occupancy comes from the pool itself. Acquire wait is sampled by checking out a
+/// connection on every report, and slow queries are read from `pg_stat_activity`, filtered
+/// by the pool's application name so each service only reports its own statements.
+struct PoolMetrics {
+    size: Gauge<u64>,
+    idle: Gauge<u64>,
+    max: Gauge<u64>,
+    acquire_wait: Histogram<f64>,
+    slow_queries: Gauge<u64>,
+    longest_query: Gauge<f64>,
+}
+
+impl PoolMetrics {
+    fn new() -> Self {
+        let meter = global::meter("omni-db");
+        Self {
+            size: meter
+                .u64_gauge("omni.db.pool.size")
+                .with_description("Open connections in the database pool")
+                .build(),
+            idle: meter
+                .u64_gauge("omni.db.pool.idle")
+                .with_description("Idle connections in the database pool")
+                .build(),
+            max: meter
+                .u64_gauge("omni.db.pool.max")
+                .with_description("Maximum connections the database pool may open")
+                .build(),
+            acquire_wait: meter
+                .f64_histogram("omni.db.pool.acquire_wait")
+                .with_unit("ms")
+                .with_description("Time spent waiting for a connection from the database pool")
+                .build(),
+            slow_queries: meter
+                .u64_gauge("omni.db.slow_queries")
+                .with_description("Active queries running longer than the slow query threshold")
+                .build(),
+            longest_query: meter
+                .f64_gauge("omni.db.slow_queries.longest")
+                .with_unit("s")
+                .with_description("Run time of the longest active slow query")
+                .build(),
+        }
+    }
+}
+
+/// Periodically publish pool occupancy, acquire wait and slow query gauges for `pool`.
+pub async fn report_pool_metrics(pool: PgPool, config: DatabaseConfig) {
+    let metrics = PoolMetrics::new();
+    let attributes = [KeyValue::new(
+        "application_name",
+        config
+            .application_name
+            .clone()
+            .unwrap_or_else(|| "unknown".to_string()),
+    )];
+    let threshold_seconds = config.slow_query_threshold_ms as f64 / 1000.0;
+    let mut interval = tokio::time::interval(POOL_METRICS_REPORT_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        metrics.size.record(pool.size() as u64, &attributes);
+        metrics.idle.record(pool.num_idle() as u64, &attributes);
+        metrics
+            .max
+            .record(pool.options().get_max_connections() as u64, &attributes);
+
+        let started = Instant::now();
+        let mut conn = match pool.acquire().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!("Failed to acquire a connection for pool metrics: {}", e);
+                continue;
+            }
+        };
+        metrics
+            .acquire_wait
+            .record(started.elapsed().as_secs_f64() * 1000.0, &attributes);
+
+        let slow_queries = sqlx::query_as::<_, (i64, f64)>(
+            r#"
+            SELECT COUNT(*),
+                   COALESCE(EXTRACT(EPOCH FROM MAX(now() - query_start)), 0)::float8
+            FROM pg_stat_activity
+            WHERE datname = current_database()
+              AND state = 'active'
+              AND pid <> pg_backend_pid()
+              AND ($1::text IS NULL OR application_name = $1)
+              AND now() - query_start > make_interval(secs => $2)
+            "#,
+        )
+        .bind(&config.application_name)
+        .bind(threshold_seconds)
+        .fetch_one(&mut *conn)
+        .await;
+
+        match slow_queries {
+            Ok((count, longest_seconds)) => {
+                debug!(
+                    "Reporting {} slow queries, longest running {:.1}s",
+                    count, longest_seconds
+                );
+                metrics.slow_queries.record(count as u64, &attributes);
+                metrics.longest_query.record(longest_seconds, &attributes);
+            }
+            Err(e) => warn!("Failed to count slow queries: {}", e),
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! Schema version checks against the migrations in `services/migrations`.
+//!
+//! Each service embeds the migrations it was built against with `sqlx::migrate!` and calls
+//! [`ensure_schema_current`] on startup, so a service never runs against a database that is
+//! missing its migrations, or that has been migrated past the code that is deployed.
+
+use crate::db::error::DatabaseError;
+use sqlx::migrate::Migrator;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A migration recorded in `_sqlx_migrations`.
+#[derive(Debug, Clone)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub checksum: Vec<u8>,
+    pub success: bool,
+}
+
+/// How the migrations applied to the database compare to the ones a binary was built with.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaStatus {
+    /// Latest migration the binary knows about.
+    pub expected_version: Option<i64>,
+    /// Latest migration applied to the database.
+    pub database_version: Option<i64>,
+    /// Known migrations not yet applied.
+    pub pending: Vec<i64>,
+    /// Applied migrations the binary does not know about, typically from a newer release.
+    pub unknown: Vec<i64>,
+    /// Applied migrations whose SQL has changed since they ran.
+    pub modified: Vec<i64>,
+    /// Migrations that failed part way through and need manual repair.
+    pub dirty: Vec<i64>,
+}
+
+impl SchemaStatus {
+    /// Compare the up migrations of `migrator` with the migrations applied to the database.
+    pub fn compare(migrator: &Migrator, applied: &[AppliedMigration]) -> Self {
+        let expected: Vec<(i64, &[u8])> = migrator
+            .iter()
+            .filter(|m| m.migration_type.is_up_migration())
+            .map(|m| (m.version, m.checksum.as_ref()))
+            .collect();
+        Self::compare_versions(&expected, applied)
+    }
+
+    fn compare_versions(expected: &[(i64, &[u8])], applied: &[AppliedMigration]) -> Self {
+        let applied_by_version: HashMap<i64, &AppliedMigration> =
+            applied.iter().map(|m| (m.version, m)).collect();
+        let expected_versions: HashMap<i64, &[u8]> = expected.iter().copied().collect();
+
+        let mut status = Self {
+            expected_version: expected.iter().map(|(version, _)| *version).max(),
+            database_version: applied.iter().map(|m| m.version).max(),
+            ..Default::default()
+        };
+
+        for (version, checksum) in expected {
+            match applied_by_version.get(version) {
+                None => status.pending.push(*version),
+                Some(applied) if applied.checksum != *checksum => status.modified.push(*version),
+                Some(_) => {}
+            }
+        }
+        for migration in applied {
+            if !expected_versions.contains_key(&migration.version) {
+                status.unknown.push(migration.version);
+            }
+            if !migration.success {
+                status.dirty.push(migration.version);
+            }
+        }
+
+        status.pending.sort_unstable();
+        status.modified.sort_unstable();
+        status.unknown.sort_unstable();
+        status.dirty.sort_unstable();
+        status
+    }
+
+    pub fn is_current(&self) -> bool {
+        self.pending.is_empty()
+            && self.unknown.is_empty()
+            && self.modified.is_empty()
+            && self.dirty.is_empty()
+    }
+}
+
+impl fmt::Display for SchemaStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let version = |v: Option<i64>| v.map_or_else(|| "none".to_string(), |v| v.to_string());
+        write!(
+            f,
+            "expected schema version {}, database at {}",
+            version(self.expected_version),
+            version(self.database_version)
+        )?;
+        for (label, versions) in [
+            ("pending", &self.pending),
+            ("unknown", &self.unknown),
+            ("modified", &self.modified),
+            ("dirty", &self.dirty),
+        ] {
+            if !versions.is_empty() {
+                write!(f, "; {}: {:?}", label, versions)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Migrations recorded in the database, empty when none have ever been run.
+pub async fn applied_migrations(pool: &PgPool) -> Result<Vec<AppliedMigration>, DatabaseError> {
+    let table_exists: bool =
+        sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+            .fetch_one(pool)
+            .await?;
+    if !table_exists {
+        return Ok(Vec::new());
+    }
+
+    let rows: Vec<(i64, Vec<u8>, bool)> =
+        sqlx::query_as("SELECT version, checksum, success FROM _sqlx_migrations ORDER BY version")
+            .fetch_all(pool)
+            .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(version, checksum, success)| AppliedMigration {
+            version,
+            checksum,
+            success,
+        })
+        .collect())
+}
+
+pub async fn schema_status(
+    pool: &PgPool,
+    migrator: &Migrator,
+) -> Result<SchemaStatus, DatabaseError> {
+    let applied = applied_migrations(pool).await?;
+    Ok(SchemaStatus::compare(migrator, &applied))
+}
+
+/// Refuse to start `service` unless the database schema matches its embedded migrations.
+pub async fn ensure_schema_current(
+    pool: &PgPool,
+    migrator: &Migrator,
+    service: &str,
+) -> anyhow::Result<()> {
+    let status = schema_status(pool, migrator).await?;
+    if !status.is_current() {
+        anyhow::bail!(
+            "Database schema does not match {}: {}. Run `omni-migrate run` or deploy the matching release",
+            service,
+            status
+        );
+    }
+    tracing::info!(
+        "Database schema is at version {:?}",
+        status.database_version
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn applied(version: i64, checksum: &[u8]) -> AppliedMigration {
+        AppliedMigration {
+            version,
+            checksum: checksum.to_vec(),
+            success: true,
+        }
+    }
+
+    #[test]
+    fn test_schema_status_current() {
+        let expected: [(i64, &[u8]); 2] = [(1, b"a"), (2, b"b")];
+        let status =
+            SchemaStatus::compare_versions(&expected, &[applied(1, b"a"), applied(2, b"b")]);
+
+        assert!(status.is_current());
+        assert_eq!(status.expected_version, Some(2));
+        assert_eq!(status.database_version, Some(2));
+    }
+
+    #[test]
+    fn test_schema_status_drift() {
+        let expected: [(i64, &[u8]); 3] = [(1, b"a"), (2, b"b"), (3, b"c")];
+
+        let behind = SchemaStatus::compare_versions(&expected, &[applied(1, b"a")]);
+        assert_eq!(behind.pending, vec![2, 3]);
+        assert!(!behind.is_current());
+
+        let ahead = SchemaStatus::compare_versions(
+            &expected,
+            &[
+                applied(1, b"a"),
+                applied(2, b"b"),
+                applied(3, b"c"),
+                applied(4, b"d"),
+            ],
+        );
+        assert_eq!(ahead.unknown, vec![4]);
+        assert_eq!(ahead.database_version, Some(4));
+
+        let mut failed = applied(3, b"c");
+        failed.success = false;
+        let broken = SchemaStatus::compare_versions(
+            &expected,
+            &[applied(1, b"a"), applied(2, b"changed"), failed],
+        );
+        assert_eq!(broken.modified, vec![2]);
+        assert_eq!(broken.dirty, vec![3]);
+        assert!(broken.pending.is_empty());
+        assert_eq!(
+            broken.to_string(),
+            "expected schema version 3, database at 3; modified: [2]; dirty: [3]"
+        );
+    }
+}
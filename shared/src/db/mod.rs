@@ -1,4 +1,6 @@
 pub mod error;
+pub mod metrics;
+pub mod migrations;
 pub mod pool;
 pub mod repositories;
 
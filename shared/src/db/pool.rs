@@ -1,6 +1,10 @@
 use crate::config::DatabaseConfig;
 use crate::db::error::DatabaseError;
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use sqlx::{
+    postgres::{PgConnectOptions, PgPoolOptions},
+    PgPool,
+};
+use std::str::FromStr;
 use std::time::Duration;
 
 #[derive(Clone)]
@@ -41,10 +45,19 @@ impl DatabasePool {
     }
 
     pub async fn from_config(config: &DatabaseConfig) -> Result<Self, DatabaseError> {
+        let mut connect_options = PgConnectOptions::from_str(&config.database_url)?;
+        if let Some(application_name) = &config.application_name {
+            connect_options = connect_options.application_name(application_name);
+        }
+        if let Some(timeout_ms) = config.statement_timeout_ms {
+            connect_options =
+                connect_options.options([("statement_timeout", format!("{}ms", timeout_ms))]);
+        }
+
         let pool = PgPoolOptions::new()
             .max_connections(config.max_connections)
             .acquire_timeout(Duration::from_secs(config.acquire_timeout_seconds))
-            .connect(&config.database_url)
+            .connect_with(connect_options)
             .await?;
 
         Ok(Self {
@@ -0,0 +1,222 @@
+use crate::{db::error::DatabaseError, utils::generate_ulid};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use time::OffsetDateTime;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum AcronymStatus {
+    /// Awaiting review; not used for query expansion.
+    #[default]
+    Pending,
+    /// Reviewed and used to expand queries.
+    Active,
+    /// Reviewed and discarded. Kept so mining does not propose it again.
+    Rejected,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum AcronymSource {
+    /// Found in indexed content by the mining job.
+    Mined,
+    /// Added by an admin.
+    Manual,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Acronym {
+    pub id: String,
+    pub acronym: String,
+    pub expansion: String,
+    pub status: AcronymStatus,
+    pub source: AcronymSource,
+    pub occurrences: i32,
+    pub document_count: i32,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub updated_at: OffsetDateTime,
+}
+
+/// The admin-editable fields of a dictionary entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcronymInput {
+    pub acronym: String,
+    pub expansion: String,
+    #[serde(default)]
+    pub status: AcronymStatus,
+}
+
+/// An acronym and expansion found by the mining job, with how often it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinedAcronym {
+    pub acronym: String,
+    pub expansion: String,
+    pub occurrences: i32,
+    pub document_count: i32,
+}
+
+pub struct AcronymRepository {
+    pool: PgPool,
+}
+
+impl AcronymRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub async fn create(&self, input: &AcronymInput) -> Result<Acronym, DatabaseError> {
+        let acronym = sqlx::query_as::<_, Acronym>(
+            r#"
+            INSERT INTO acronyms (id, acronym, expansion, status, source)
+            VALUES ($1, $2, $3, $4, 'manual')
+            RETURNING id, acronym, expansion, status, source, occurrences, document_count,
+                      created_at, updated_at
+            "#,
+        )
+        .bind(generate_ulid())
+        .bind(&input.acronym)
+        .bind(&input.expansion)
+        .bind(input.status)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                DatabaseError::ConstraintViolation(format!(
+                    "Acronym {} is already defined as '{}'",
+                    input.acronym, input.expansion
+                ))
+            }
+            _ => DatabaseError::from(e),
+        })?;
+
+        Ok(acronym)
+    }
+
+    /// Replace the editable fields of an entry. Returns `None` when it does not exist.
+    pub async fn update(
+        &self,
+        id: &str,
+        input: &AcronymInput,
+    ) -> Result<Option<Acronym>, DatabaseError> {
+        let acronym = sqlx::query_as::<_, Acronym>(
+            r#"
+            UPDATE acronyms
+            SET acronym = $2,
+                expansion = $3,
+                status = $4,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, acronym, expansion, status, source, occurrences, document_count,
+                      created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(&input.acronym)
+        .bind(&input.expansion)
+        .bind(input.status)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                DatabaseError::ConstraintViolation(format!(
+                    "Acronym {} is already defined as '{}'",
+                    input.acronym, input.expansion
+                ))
+            }
+            _ => DatabaseError::from(e),
+        })?;
+
+        Ok(acronym)
+    }
+
+    /// Entries with the given status, or all of them, most frequently found first.
+    pub async fn find_all(
+        &self,
+        status: Option<AcronymStatus>,
+    ) -> Result<Vec<Acronym>, DatabaseError> {
+        let acronyms = sqlx::query_as::<_, Acronym>(
+            r#"
+            SELECT id, acronym, expansion, status, source, occurrences, document_count,
+                   created_at, updated_at
+            FROM acronyms
+            WHERE $1::varchar IS NULL OR status = $1
+            ORDER BY document_count DESC, occurrences DESC, acronym, expansion
+            "#,
+        )
+        .bind(status)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(acronyms)
+    }
+
+    pub async fn find_active(&self) -> Result<Vec<Acronym>, DatabaseError> {
+        self.find_all(Some(AcronymStatus::Active)).await
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<Acronym>, DatabaseError> {
+        let acronym = sqlx::query_as::<_, Acronym>(
+            r#"
+            SELECT id, acronym, expansion, status, source, occurrences, document_count,
+                   created_at, updated_at
+            FROM acronyms
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(acronym)
+    }
+
+    /// Record the results of a mining run. New pairs are added for review; pairs already in
+    /// the dictionary get their counts refreshed and keep their status. Returns how many
+    /// pairs were new.
+    pub async fn upsert_mined(&self, mined: &[MinedAcronym]) -> Result<u64, DatabaseError> {
+        let mut added = 0;
+        let mut tx = self.pool.begin().await?;
+        for entry in mined {
+            let (inserted,): (bool,) = sqlx::query_as(
+                r#"
+                INSERT INTO acronyms (
+                    id, acronym, expansion, status, source, occurrences, document_count
+                )
+                VALUES ($1, $2, $3, 'pending', 'mined', $4, $5)
+                ON CONFLICT (acronym, LOWER(expansion)) DO UPDATE
+                SET occurrences = EXCLUDED.occurrences,
+                    document_count = EXCLUDED.document_count,
+                    updated_at = NOW()
+                RETURNING (xmax = 0) AS inserted
+                "#,
+            )
+            .bind(generate_ulid())
+            .bind(&entry.acronym)
+            .bind(&entry.expansion)
+            .bind(entry.occurrences)
+            .bind(entry.document_count)
+            .fetch_one(&mut *tx)
+            .await?;
+            if inserted {
+                added += 1;
+            }
+        }
+        tx.commit().await?;
+
+        Ok(added)
+    }
+
+    /// Delete an entry. Returns whether it existed.
+    pub async fn delete(&self, id: &str) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM acronyms WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
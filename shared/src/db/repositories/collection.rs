@@ -0,0 +1,347 @@
+use crate::{db::error::DatabaseError, utils::generate_ulid};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use time::OffsetDateTime;
+
+/// Columns of a collection aliased `c`, with the number of documents in it.
+const COLLECTION_COLUMNS: &str = r#"
+    c.id, c.owner_id, c.name, c.description,
+    (SELECT COUNT(*) FROM collection_documents cd WHERE cd.collection_id = c.id) AS document_count,
+    c.created_at, c.updated_at
+"#;
+
+/// A user-curated set of documents. Only the owner edits it; users it is shared with can view
+/// it and limit searches to it.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Collection {
+    pub id: String,
+    pub owner_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub document_count: i64,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub updated_at: OffsetDateTime,
+}
+
+/// The owner-editable fields of a collection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionInput {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CollectionShare {
+    pub user_id: String,
+    pub email: String,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+}
+
+pub struct CollectionRepository {
+    pool: PgPool,
+}
+
+impl CollectionRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub async fn create(
+        &self,
+        owner_id: &str,
+        input: &CollectionInput,
+    ) -> Result<Collection, DatabaseError> {
+        let collection = sqlx::query_as::<_, Collection>(&format!(
+            r#"
+            WITH c AS (
+                INSERT INTO collections (id, owner_id, name, description)
+                VALUES ($1, $2, $3, $4)
+                RETURNING *
+            )
+            SELECT {} FROM c
+            "#,
+            COLLECTION_COLUMNS
+        ))
+        .bind(generate_ulid())
+        .bind(owner_id)
+        .bind(&input.name)
+        .bind(&input.description)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| duplicate_name_error(e, input))?;
+
+        Ok(collection)
+    }
+
+    /// Replace the editable fields of a collection. Returns `None` when it does not exist.
+    pub async fn update(
+        &self,
+        id: &str,
+        input: &CollectionInput,
+    ) -> Result<Option<Collection>, DatabaseError> {
+        let collection = sqlx::query_as::<_, Collection>(&format!(
+            r#"
+            WITH c AS (
+                UPDATE collections
+                SET name = $2,
+                    description = $3,
+                    updated_at = NOW()
+                WHERE id = $1
+                RETURNING *
+            )
+            SELECT {} FROM c
+            "#,
+            COLLECTION_COLUMNS
+        ))
+        .bind(id)
+        .bind(&input.name)
+        .bind(&input.description)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| duplicate_name_error(e, input))?;
+
+        Ok(collection)
+    }
+
+    /// Delete a collection. Returns whether it existed.
+    pub async fn delete(&self, id: &str) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM collections WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<Collection>, DatabaseError> {
+        let collection = sqlx::query_as::<_, Collection>(&format!(
+            "SELECT {} FROM collections c WHERE c.id = $1",
+            COLLECTION_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(collection)
+    }
+
+    /// Collections a user owns or that are shared with them, by name.
+    pub async fn find_for_user(&self, user_id: &str) -> Result<Vec<Collection>, DatabaseError> {
+        let collections = sqlx::query_as::<_, Collection>(&format!(
+            r#"
+            SELECT {}
+            FROM collections c
+            WHERE c.owner_id = $1
+               OR EXISTS (
+                   SELECT 1 FROM collection_shares s
+                   WHERE s.collection_id = c.id AND s.user_id = $1
+               )
+            ORDER BY lower(c.name), c.created_at
+            "#,
+            COLLECTION_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(collections)
+    }
+
+    /// The collection a `collection:` search filter names, by ID or case-insensitive name,
+    /// among those the user with this email can view. The user's own collections win over
+    /// ones shared with them.
+    pub async fn find_viewable_by_email(
+        &self,
+        id_or_name: &str,
+        email: &str,
+    ) -> Result<Option<Collection>, DatabaseError> {
+        let collection = sqlx::query_as::<_, Collection>(&format!(
+            r#"
+            SELECT {}
+            FROM collections c
+            JOIN users u ON lower(u.email) = lower($2)
+            WHERE (c.id = $1 OR lower(c.name) = lower($1))
+              AND (
+                  c.owner_id = u.id
+                  OR EXISTS (
+                      SELECT 1 FROM collection_shares s
+                      WHERE s.collection_id = c.id AND s.user_id = u.id
+                  )
+              )
+            ORDER BY c.id = $1 DESC, c.owner_id = u.id DESC, c.created_at
+            LIMIT 1
+            "#,
+            COLLECTION_COLUMNS
+        ))
+        .bind(id_or_name)
+        .bind(email)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(collection)
+    }
+
+    /// Whether a user owns a collection or has it shared with them.
+    pub async fn can_view(&self, id: &str, user_id: &str) -> Result<bool, DatabaseError> {
+        let can_view = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM collections c
+                WHERE c.id = $1
+                  AND (
+                      c.owner_id = $2
+                      OR EXISTS (
+                          SELECT 1 FROM collection_shares s
+                          WHERE s.collection_id = c.id AND s.user_id = $2
+                      )
+                  )
+            )
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(can_view)
+    }
+
+    /// Document IDs in a collection, most recently added first.
+    pub async fn find_document_ids(&self, id: &str) -> Result<Vec<String>, DatabaseError> {
+        let document_ids = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT document_id
+            FROM collection_documents
+            WHERE collection_id = $1
+            ORDER BY added_at DESC, document_id
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(document_ids)
+    }
+
+    /// Add documents to a collection. Returns how many were not in it already.
+    pub async fn add_documents(
+        &self,
+        id: &str,
+        document_ids: &[String],
+    ) -> Result<u64, DatabaseError> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query(
+            r#"
+            INSERT INTO collection_documents (collection_id, document_id)
+            SELECT $1, document_id FROM UNNEST($2::text[]) AS document_id
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(id)
+        .bind(document_ids)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() > 0 {
+            touch(&mut tx, id).await?;
+        }
+        tx.commit().await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Remove a document from a collection. Returns whether it was in it.
+    pub async fn remove_document(
+        &self,
+        id: &str,
+        document_id: &str,
+    ) -> Result<bool, DatabaseError> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query(
+            "DELETE FROM collection_documents WHERE collection_id = $1 AND document_id = $2",
+        )
+        .bind(id)
+        .bind(document_id)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() > 0 {
+            touch(&mut tx, id).await?;
+        }
+        tx.commit().await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn find_shares(&self, id: &str) -> Result<Vec<CollectionShare>, DatabaseError> {
+        let shares = sqlx::query_as::<_, CollectionShare>(
+            r#"
+            SELECT s.user_id, u.email, s.created_at
+            FROM collection_shares s
+            JOIN users u ON u.id = s.user_id
+            WHERE s.collection_id = $1
+            ORDER BY u.email
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(shares)
+    }
+
+    /// Share a collection with a user. Sharing it again is a no-op.
+    pub async fn share(&self, id: &str, user_id: &str) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO collection_shares (collection_id, user_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Stop sharing a collection with a user. Returns whether it was shared with them.
+    pub async fn unshare(&self, id: &str, user_id: &str) -> Result<bool, DatabaseError> {
+        let result =
+            sqlx::query("DELETE FROM collection_shares WHERE collection_id = $1 AND user_id = $2")
+                .bind(id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// Mark a collection's contents as changed.
+async fn touch(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    id: &str,
+) -> Result<(), DatabaseError> {
+    sqlx::query("UPDATE collections SET updated_at = NOW() WHERE id = $1")
+        .bind(id)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+fn duplicate_name_error(e: sqlx::Error, input: &CollectionInput) -> DatabaseError {
+    match e {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            DatabaseError::ConstraintViolation(format!(
+                "A collection named '{}' already exists",
+                input.name
+            ))
+        }
+        _ => DatabaseError::from(e),
+    }
+}
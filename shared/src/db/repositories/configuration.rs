@@ -0,0 +1,71 @@
+use crate::db::error::DatabaseError;
+use serde_json::Value as JsonValue;
+use sqlx::PgPool;
+
+/// Application settings managed from the admin UI, stored as one JSON value per key in the
+/// `configuration` table.
+pub struct ConfigurationRepository {
+    pool: PgPool,
+}
+
+impl ConfigurationRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Option<JsonValue>, DatabaseError> {
+        let value =
+            sqlx::query_scalar::<_, JsonValue>("SELECT value FROM configuration WHERE key = $1")
+                .bind(key)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(value)
+    }
+
+    pub async fn set(&self, key: &str, value: &JsonValue) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO configuration (key, value)
+            VALUES ($1, $2)
+            ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value
+            "#,
+        )
+        .bind(key)
+        .bind(value)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store a value unless the key already has one. Returns whether it was stored.
+    pub async fn insert_if_absent(
+        &self,
+        key: &str,
+        value: &JsonValue,
+    ) -> Result<bool, DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO configuration (key, value)
+            VALUES ($1, $2)
+            ON CONFLICT (key) DO NOTHING
+            "#,
+        )
+        .bind(key)
+        .bind(value)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM configuration WHERE key = $1")
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
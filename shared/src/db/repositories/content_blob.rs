@@ -31,8 +31,8 @@ impl ContentBlobRepository {
         Self { pool: pool.clone() }
     }
 
-    /// Mark blobs as orphaned if they are not referenced by any document
-    /// or any pending/processing queue event.
+    /// Mark blobs as orphaned if they are not referenced by any document, document preview,
+    /// metadata export or pending/processing queue event.
     /// Returns the number of blobs marked.
     pub async fn mark_orphans(&self) -> Result<i64, DatabaseError> {
         let result = sqlx::query(
@@ -45,11 +45,17 @@ impl ContentBlobRepository {
                 WHERE content_id IS NOT NULL
             )
             AND cb.id NOT IN (
-                SELECT DISTINCT payload->>'content_id'
+                SELECT payload->>'content_id'
                 FROM connector_events_queue
                 WHERE status IN ('pending', 'processing')
                 AND payload->>'content_id' IS NOT NULL
+                UNION
+                SELECT payload->>'content_id'
+                FROM connector_events_outbox
+                WHERE payload->>'content_id' IS NOT NULL
             )
+            AND cb.id NOT IN (SELECT content_id FROM metadata_exports)
+            AND cb.id NOT IN (SELECT content_id FROM document_previews)
             AND cb.orphaned_at IS NULL
             "#,
         )
@@ -59,6 +65,45 @@ impl ContentBlobRepository {
         Ok(result.rows_affected() as i64)
     }
 
+    /// Of the given blob ids, those no longer referenced by any document, document preview,
+    /// metadata export or pending/processing queue event. Used to delete blobs immediately
+    /// rather than waiting for the orphan retention period.
+    pub async fn filter_unreferenced(
+        &self,
+        content_ids: &[String],
+    ) -> Result<Vec<String>, DatabaseError> {
+        if content_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT cb.id
+            FROM content_blobs cb
+            WHERE cb.id = ANY($1)
+            AND NOT EXISTS (SELECT 1 FROM documents d WHERE d.content_id = cb.id)
+            AND NOT EXISTS (
+                SELECT 1
+                FROM connector_events_queue q
+                WHERE q.status IN ('pending', 'processing')
+                AND q.payload->>'content_id' = cb.id
+            )
+            AND NOT EXISTS (
+                SELECT 1
+                FROM connector_events_outbox o
+                WHERE o.payload->>'content_id' = cb.id
+            )
+            AND NOT EXISTS (SELECT 1 FROM metadata_exports e WHERE e.content_id = cb.id)
+            AND NOT EXISTS (SELECT 1 FROM document_previews p WHERE p.content_id = cb.id)
+            "#,
+        )
+        .bind(content_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
     /// Unmark blobs that are no longer orphaned (got re-referenced).
     /// Returns the number of blobs unmarked.
     pub async fn unmark_non_orphans(&self) -> Result<i64, DatabaseError> {
@@ -74,11 +119,17 @@ impl ContentBlobRepository {
                     WHERE content_id IS NOT NULL
                 )
                 OR cb.id IN (
-                    SELECT DISTINCT payload->>'content_id'
+                    SELECT payload->>'content_id'
                     FROM connector_events_queue
                     WHERE status IN ('pending', 'processing')
                     AND payload->>'content_id' IS NOT NULL
+                    UNION
+                    SELECT payload->>'content_id'
+                    FROM connector_events_outbox
+                    WHERE payload->>'content_id' IS NOT NULL
                 )
+                OR cb.id IN (SELECT content_id FROM metadata_exports)
+                OR cb.id IN (SELECT content_id FROM document_previews)
             )
             "#,
         )
@@ -128,10 +179,14 @@ impl ContentBlobRepository {
                         SELECT DISTINCT content_id FROM documents WHERE content_id IS NOT NULL
                     )
                     AND id NOT IN (
-                        SELECT DISTINCT payload->>'content_id'
+                        SELECT payload->>'content_id'
                         FROM connector_events_queue
                         WHERE status IN ('pending', 'processing')
                         AND payload->>'content_id' IS NOT NULL
+                        UNION
+                        SELECT payload->>'content_id'
+                        FROM connector_events_outbox
+                        WHERE payload->>'content_id' IS NOT NULL
                     )
                 ) as unmarked_orphans,
                 COUNT(*) FILTER (
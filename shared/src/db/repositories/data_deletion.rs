@@ -0,0 +1,152 @@
+use crate::{db::error::DatabaseError, utils::generate_ulid};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::{FromRow, PgPool};
+use time::OffsetDateTime;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum DataDeletionStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+/// A right-to-be-forgotten request for a single data subject.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DataDeletionRequest {
+    pub id: String,
+    pub subject_email: String,
+    pub status: DataDeletionStatus,
+    pub report: Option<JsonValue>,
+    pub signature: Option<String>,
+    pub error_message: Option<String>,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601::option")]
+    pub started_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::iso8601::option")]
+    pub completed_at: Option<OffsetDateTime>,
+}
+
+pub struct DataDeletionRepository {
+    pool: PgPool,
+}
+
+impl DataDeletionRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub async fn create(&self, subject_email: &str) -> Result<DataDeletionRequest, DatabaseError> {
+        let request = sqlx::query_as::<_, DataDeletionRequest>(
+            r#"
+            INSERT INTO data_deletion_requests (id, subject_email, status)
+            VALUES ($1, $2, $3)
+            RETURNING id, subject_email, status, report, signature, error_message,
+                      created_at, started_at, completed_at
+            "#,
+        )
+        .bind(generate_ulid())
+        .bind(subject_email)
+        .bind(DataDeletionStatus::Pending)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(request)
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<DataDeletionRequest>, DatabaseError> {
+        let request = sqlx::query_as::<_, DataDeletionRequest>(
+            r#"
+            SELECT id, subject_email, status, report, signature, error_message,
+                   created_at, started_at, completed_at
+            FROM data_deletion_requests
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(request)
+    }
+
+    /// Requests that have not reached a terminal state, oldest first. Used to resume
+    /// work interrupted by a restart.
+    pub async fn find_unfinished(&self) -> Result<Vec<DataDeletionRequest>, DatabaseError> {
+        let requests = sqlx::query_as::<_, DataDeletionRequest>(
+            r#"
+            SELECT id, subject_email, status, report, signature, error_message,
+                   created_at, started_at, completed_at
+            FROM data_deletion_requests
+            WHERE status IN ('pending', 'running')
+            ORDER BY created_at
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(requests)
+    }
+
+    pub async fn mark_running(&self, id: &str) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE data_deletion_requests
+            SET status = $2, started_at = COALESCE(started_at, NOW())
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(DataDeletionStatus::Running)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_completed(
+        &self,
+        id: &str,
+        report: &JsonValue,
+        signature: &str,
+        completed_at: OffsetDateTime,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE data_deletion_requests
+            SET status = $2, report = $3, signature = $4, error_message = NULL, completed_at = $5
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(DataDeletionStatus::Completed)
+        .bind(report)
+        .bind(signature)
+        .bind(completed_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_failed(&self, id: &str, error_message: &str) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE data_deletion_requests
+            SET status = $2, error_message = $3, completed_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(DataDeletionStatus::Failed)
+        .bind(error_message)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
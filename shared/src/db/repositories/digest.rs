@@ -0,0 +1,111 @@
+use crate::db::error::DatabaseError;
+use crate::models::DigestFrequency;
+use sqlx::{FromRow, PgPool};
+use time::OffsetDateTime;
+
+/// A user whose next digest is due.
+#[derive(Debug, Clone)]
+pub struct DueDigest {
+    pub user_id: String,
+    pub email: String,
+    pub full_name: Option<String>,
+    pub frequency: DigestFrequency,
+    /// When their last digest was sent, `None` before the first.
+    pub last_sent_at: Option<OffsetDateTime>,
+}
+
+#[derive(FromRow)]
+struct DueDigestRow {
+    id: String,
+    email: String,
+    full_name: Option<String>,
+    frequency: String,
+    last_sent_at: Option<OffsetDateTime>,
+}
+
+pub struct DigestRepository {
+    pool: PgPool,
+}
+
+impl DigestRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Active users who asked for digests and were not sent one within their period.
+    pub async fn find_due(&self, now: OffsetDateTime) -> Result<Vec<DueDigest>, DatabaseError> {
+        let rows = sqlx::query_as::<_, DueDigestRow>(
+            r#"
+                SELECT u.id, u.email, u.full_name,
+                       u.notification_preferences->>'digest' AS frequency,
+                       d.last_sent_at
+                FROM users u
+                LEFT JOIN user_digests d ON d.user_id = u.id
+                WHERE u.is_active
+                  AND u.notification_preferences->>'digest' IN ('daily', 'weekly')
+                  AND (d.last_sent_at IS NULL OR d.last_sent_at <= $1 - CASE
+                        WHEN u.notification_preferences->>'digest' = 'daily' THEN INTERVAL '1 day'
+                        ELSE INTERVAL '7 days'
+                      END)
+                ORDER BY u.id
+                "#,
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DueDigest {
+                user_id: row.id,
+                email: row.email,
+                full_name: row.full_name,
+                frequency: if row.frequency == "daily" {
+                    DigestFrequency::Daily
+                } else {
+                    DigestFrequency::Weekly
+                },
+                last_sent_at: row.last_sent_at,
+            })
+            .collect())
+    }
+
+    /// Claim the digest of a user as sent at `sent_at`, provided no one else did since it
+    /// was found due with `last_sent_at`. Returns whether this caller claimed it.
+    pub async fn claim(
+        &self,
+        user_id: &str,
+        last_sent_at: Option<OffsetDateTime>,
+        sent_at: OffsetDateTime,
+    ) -> Result<bool, DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO user_digests (user_id, last_sent_at)
+            VALUES ($1, $3)
+            ON CONFLICT (user_id) DO UPDATE SET last_sent_at = $3, document_count = 0
+            WHERE user_digests.last_sent_at IS NOT DISTINCT FROM $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(last_sent_at)
+        .bind(sent_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Record how many documents the user's last digest listed.
+    pub async fn record_sent(
+        &self,
+        user_id: &str,
+        document_count: i32,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE user_digests SET document_count = $2 WHERE user_id = $1")
+            .bind(user_id)
+            .bind(document_count)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -1,8 +1,11 @@
+use super::group::{group_identifiers_sql, user_group_identifiers_sql};
 use crate::{
     db::error::DatabaseError,
-    models::{AttributeFilter, Document, Facet, FacetValue},
+    models::{AttributeFilter, Document, Facet, FacetValue, PermissionOverrides, SearchField},
+    text_normalization::{fold, QueryTerms},
     SourceType,
 };
+use serde::Serialize;
 use serde_json::Value as JsonValue;
 use sqlx::{FromRow, PgPool};
 use std::collections::HashMap;
@@ -25,13 +28,72 @@ pub struct TitleEntry {
     pub source_id: String,
 }
 
+/// Documents added on a single day, with the running total at the end of that day.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DailyIndexGrowth {
+    pub day: String,
+    pub documents_added: i64,
+    pub total_documents: i64,
+}
+
+/// End-to-end indexing lag for one source: time from a document changing in the source
+/// system to that version being indexed, in seconds.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SourceIndexingLag {
+    pub source_id: String,
+    pub source_name: String,
+    pub source_type: String,
+    pub documents: i64,
+    pub p50_seconds: f64,
+    pub p95_seconds: f64,
+    pub max_seconds: f64,
+}
+
+/// How widely an attribute key is used, and how many distinct values it takes. Attributes
+/// present on most documents with few distinct values are candidates for real columns.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AttributeCardinality {
+    pub key: String,
+    pub documents: i64,
+    pub distinct_values: i64,
+}
+
+/// A document's connector permissions, the manual overrides layered on top of them and the
+/// resulting effective permissions used by search.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DocumentPermissionState {
+    pub document_id: String,
+    pub owner: Option<String>,
+    pub source_permissions: Option<JsonValue>,
+    pub permission_overrides: Option<JsonValue>,
+    pub permissions: JsonValue,
+}
+
 pub struct DocumentRepository {
     pool: PgPool,
+    user_groups: Option<Vec<String>>,
 }
 
 impl DocumentRepository {
     pub fn new(pool: &PgPool) -> Self {
-        Self { pool: pool.clone() }
+        Self {
+            pool: pool.clone(),
+            user_groups: None,
+        }
+    }
+
+    /// Filter permissions by the identifiers of the user's groups, resolved ahead, rather
+    /// than looking them up in each query.
+    pub fn with_user_groups(mut self, user_groups: Option<Vec<String>>) -> Self {
+        self.user_groups = user_groups;
+        self
+    }
+
+    fn user_groups_sql(&self, user_email: &str) -> String {
+        match &self.user_groups {
+            Some(identifiers) => group_identifiers_sql(identifiers),
+            None => user_group_identifiers_sql(user_email),
+        }
     }
 
     /// Generate SQL condition to check if user has permission to access document
@@ -40,9 +102,12 @@ impl DocumentRepository {
             r#"(
                 permissions @@@ 'public:true' OR
                 permissions @@@ 'users:{}' OR
-                permissions @@@ 'groups:{}'
+                permissions @@@ 'groups:{}' OR
+                permissions->'groups' ?| {}
             )"#,
-            user_email, user_email
+            user_email,
+            user_email,
+            self.user_groups_sql(user_email)
         )
     }
 
@@ -84,6 +149,151 @@ impl DocumentRepository {
         Ok(documents)
     }
 
+    /// Documents among `ids` that a search with these filters may return, for adding
+    /// promoted documents the ranking did not find.
+    pub async fn find_by_ids_with_filters(
+        &self,
+        ids: &[String],
+        source_ids: &[String],
+        content_types: Option<&[String]>,
+        attribute_filters: Option<&HashMap<String, AttributeFilter>>,
+        collection_id: Option<&str>,
+        user_email: Option<&str>,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        if ids.is_empty() || source_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut filters = vec!["id = ANY($1)".to_string()];
+        let mut param_idx = 2;
+        self.build_common_filters(
+            &mut filters,
+            &mut param_idx,
+            source_ids,
+            content_types,
+            attribute_filters,
+            collection_id,
+            user_email,
+        );
+
+        let query = format!(
+            r#"
+            SELECT id, source_id, external_id, title, content_id, content_type,
+                   file_size, file_extension, url,
+                   metadata, permissions, attributes, created_at, updated_at, last_indexed_at
+            FROM documents
+            WHERE {}
+            "#,
+            filters.join(" AND ")
+        );
+
+        let mut query = sqlx::query_as::<_, Document>(&query)
+            .bind(ids)
+            .bind(source_ids);
+        if let Some(ct) = content_types {
+            if !ct.is_empty() {
+                query = query.bind(ct);
+            }
+        }
+        if let Some(collection_id) = collection_id {
+            query = query.bind(collection_id);
+        }
+
+        Ok(query.fetch_all(&self.pool).await?)
+    }
+
+    /// The most recently updated documents of the sources that a user can see.
+    pub async fn find_recently_updated(
+        &self,
+        source_ids: &[String],
+        user_email: &str,
+        limit: i64,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        if source_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut filters = Vec::new();
+        let mut param_idx = 1;
+        self.build_common_filters(
+            &mut filters,
+            &mut param_idx,
+            source_ids,
+            None,
+            None,
+            None,
+            Some(user_email),
+        );
+
+        let query = format!(
+            r#"
+            SELECT id, source_id, external_id, title, content_id, content_type,
+                   file_size, file_extension, url,
+                   metadata, permissions, attributes, created_at, updated_at, last_indexed_at
+            FROM documents
+            WHERE {}
+            ORDER BY updated_at DESC, id
+            LIMIT ${}
+            "#,
+            filters.join(" AND "),
+            param_idx
+        );
+
+        Ok(sqlx::query_as::<_, Document>(&query)
+            .bind(source_ids)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    /// SHA-256 of the content of each of the documents that has content, keyed by document ID.
+    pub async fn find_content_hashes(
+        &self,
+        ids: &[String],
+    ) -> Result<HashMap<String, String>, DatabaseError> {
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            r#"
+            SELECT d.id, cb.sha256_hash
+            FROM documents d
+            JOIN content_blobs cb ON cb.id = d.content_id
+            WHERE d.id = ANY($1)
+            "#,
+        )
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// The indexed text of documents with IDs after `after_id`, in ID order, for jobs that
+    /// scan the whole corpus a page at a time.
+    pub async fn find_content_page(
+        &self,
+        after_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<(String, String)>, DatabaseError> {
+        let rows = sqlx::query_as(
+            r#"
+            SELECT id, content
+            FROM documents
+            WHERE content IS NOT NULL AND ($1::text IS NULL OR id > $1)
+            ORDER BY id
+            LIMIT $2
+            "#,
+        )
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
     pub async fn find_all(&self, limit: i64, offset: i64) -> Result<Vec<Document>, DatabaseError> {
         let documents = sqlx::query_as::<_, Document>(
             r#"
@@ -172,6 +382,7 @@ impl DocumentRepository {
         source_ids: &[String],
         content_types: Option<&[String]>,
         attribute_filters: Option<&HashMap<String, AttributeFilter>>,
+        collection_id: Option<&str>,
         user_email: Option<&str>,
     ) {
         if !source_ids.is_empty() {
@@ -234,6 +445,14 @@ impl DocumentRepository {
             }
         }
 
+        if collection_id.is_some() {
+            filters.push(format!(
+                "documents.id IN (SELECT document_id FROM collection_documents WHERE collection_id = ${})",
+                param_idx
+            ));
+            *param_idx += 1;
+        }
+
         if let Some(email) = user_email {
             filters.push(self.generate_permission_filter(email));
         }
@@ -245,8 +464,11 @@ impl DocumentRepository {
         source_ids: &[String],
         content_types: Option<&[String]>,
         attribute_filters: Option<&HashMap<String, AttributeFilter>>,
+        fields: &[SearchField],
+        fuzzy: bool,
         limit: i64,
         offset: i64,
+        collection_id: Option<&str>,
         user_email: Option<&str>,
         document_id: Option<&str>,
     ) -> Result<Vec<SearchHit>, DatabaseError> {
@@ -254,8 +476,9 @@ impl DocumentRepository {
             return Ok(vec![]);
         }
 
-        let mut filters = vec!["(title ||| $1 OR content ||| $2)".to_string()];
-        let mut param_idx = 3;
+        let mut param_idx = 1;
+        let (match_clause, match_values) = match_condition(query, fields, fuzzy, &mut param_idx);
+        let mut filters = vec![match_clause];
 
         self.build_common_filters(
             &mut filters,
@@ -263,6 +486,7 @@ impl DocumentRepository {
             source_ids,
             content_types,
             attribute_filters,
+            collection_id,
             user_email,
         );
 
@@ -293,11 +517,11 @@ impl DocumentRepository {
         );
         debug!("Full search query: {}", full_query);
 
-        let title_query = format!("{}::pdb.boost(2)", query);
-        let mut query = sqlx::query_as::<_, SearchHit>(&full_query)
-            .bind(title_query)
-            .bind(query)
-            .bind(source_ids);
+        let mut query = sqlx::query_as::<_, SearchHit>(&full_query);
+        for value in match_values {
+            query = query.bind(value);
+        }
+        query = query.bind(source_ids);
 
         if let Some(ct) = content_types {
             if !ct.is_empty() {
@@ -305,6 +529,10 @@ impl DocumentRepository {
             }
         }
 
+        if let Some(collection_id) = collection_id {
+            query = query.bind(collection_id);
+        }
+
         if let Some(doc_id) = document_id {
             query = query.bind(doc_id);
         }
@@ -438,7 +666,7 @@ impl DocumentRepository {
             SET title = COALESCE($2, title),
                 content_id = COALESCE($3, content_id),
                 metadata = COALESCE($4, metadata),
-                permissions = COALESCE($5, permissions),
+                permissions = COALESCE($5, source_permissions, permissions),
                 updated_at = $6
             WHERE id = $1
             RETURNING id, source_id, external_id, title, content_id, content_type,
@@ -458,6 +686,106 @@ impl DocumentRepository {
         Ok(updated_document)
     }
 
+    /// Replace the connector permissions of a document, leaving its content and metadata as
+    /// they are. Returns whether the document exists.
+    pub async fn update_permissions(
+        &self,
+        source_id: &str,
+        external_id: &str,
+        permissions: &JsonValue,
+    ) -> Result<bool, DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE documents
+            SET permissions = $3, updated_at = $4
+            WHERE source_id = $1 AND external_id = $2
+            "#,
+        )
+        .bind(source_id)
+        .bind(external_id)
+        .bind(permissions)
+        .bind(sqlx::types::time::OffsetDateTime::now_utc())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn find_permission_state(
+        &self,
+        id: &str,
+    ) -> Result<Option<DocumentPermissionState>, DatabaseError> {
+        let state = sqlx::query_as::<_, DocumentPermissionState>(
+            r#"
+            SELECT id AS document_id, metadata->>'author' AS owner,
+                   source_permissions, permission_overrides, permissions
+            FROM documents
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(state)
+    }
+
+    /// The connector permissions of the documents of a source with any of the given external
+    /// ids, keyed by external id. Updates that don't carry permissions write these back, as the
+    /// effective `permissions` already include the manual overrides.
+    pub async fn find_source_permissions(
+        &self,
+        source_id: &str,
+        external_ids: &[String],
+    ) -> Result<HashMap<String, JsonValue>, DatabaseError> {
+        if external_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows: Vec<(String, JsonValue)> = sqlx::query_as(
+            r#"
+            SELECT external_id, COALESCE(source_permissions, permissions)
+            FROM documents
+            WHERE source_id = $1 AND external_id = ANY($2)
+            "#,
+        )
+        .bind(source_id)
+        .bind(external_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Replace a document's permission overrides, or remove them with `None`. The effective
+    /// permissions are recomputed by the database.
+    pub async fn set_permission_overrides(
+        &self,
+        id: &str,
+        overrides: Option<&PermissionOverrides>,
+    ) -> Result<Option<DocumentPermissionState>, DatabaseError> {
+        let overrides = overrides
+            .filter(|overrides| !overrides.is_empty())
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| DatabaseError::InvalidInput(e.to_string()))?;
+
+        let state = sqlx::query_as::<_, DocumentPermissionState>(
+            r#"
+            UPDATE documents SET permission_overrides = $2
+            WHERE id = $1
+            RETURNING id AS document_id, metadata->>'author' AS owner,
+                      source_permissions, permission_overrides, permissions
+            "#,
+        )
+        .bind(id)
+        .bind(overrides)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(state)
+    }
+
     pub async fn delete(&self, id: &str) -> Result<bool, DatabaseError> {
         let result = sqlx::query("DELETE FROM documents WHERE id = $1")
             .bind(id)
@@ -514,20 +842,44 @@ impl DocumentRepository {
         Ok(upserted_document)
     }
 
+    /// Facet counts over the documents a search matched: those matching `query` the way
+    /// [`Self::search`] matches them, if the search matched text at all, and the
+    /// `matched_document_ids` it found another way, such as by semantic search.
     pub async fn get_facet_counts(
         &self,
-        query: &str,
+        query: Option<&str>,
         source_ids: &[String],
         content_types: Option<&[String]>,
         attribute_filters: Option<&HashMap<String, AttributeFilter>>,
+        fields: &[SearchField],
+        fuzzy: bool,
+        matched_document_ids: &[String],
+        collection_id: Option<&str>,
         user_email: Option<&str>,
     ) -> Result<Vec<Facet>, DatabaseError> {
-        if source_ids.is_empty() {
+        if source_ids.is_empty() || (query.is_none() && matched_document_ids.is_empty()) {
             return Ok(vec![]);
         }
 
-        let mut filters = vec!["(title ||| $1 OR content ||| $2)".to_string()];
-        let mut param_idx = 3;
+        let mut param_idx = 1;
+        let (text_clause, match_values) = match query {
+            Some(query) => {
+                let (clause, values) = match_condition(query, fields, fuzzy, &mut param_idx);
+                (Some(clause), values)
+            }
+            None => (None, vec![]),
+        };
+        let ids_clause = (!matched_document_ids.is_empty()).then(|| {
+            let clause = format!("documents.id = ANY(${})", param_idx);
+            param_idx += 1;
+            clause
+        });
+        let match_clause = match (text_clause, ids_clause) {
+            (Some(text), Some(ids)) => format!("({} OR {})", text, ids),
+            (Some(clause), None) | (None, Some(clause)) => clause,
+            (None, None) => unreachable!("checked above"),
+        };
+        let mut filters = vec![match_clause];
 
         self.build_common_filters(
             &mut filters,
@@ -535,6 +887,7 @@ impl DocumentRepository {
             source_ids,
             content_types,
             attribute_filters,
+            collection_id,
             user_email,
         );
 
@@ -543,8 +896,8 @@ impl DocumentRepository {
         let query_str = format!(
             r#"
             SELECT 'source_type' as facet, s.source_type as value, count(*) as count
-            FROM documents d
-            JOIN sources s ON d.source_id = s.id
+            FROM documents
+            JOIN sources s ON documents.source_id = s.id
             WHERE {}
             GROUP BY s.source_type
             ORDER BY count DESC
@@ -552,11 +905,14 @@ impl DocumentRepository {
             where_clause
         );
 
-        let title_query = format!("{}::pdb.boost(2)", query);
-        let mut query = sqlx::query_as::<_, (String, String, i64)>(&query_str)
-            .bind(title_query)
-            .bind(query)
-            .bind(source_ids);
+        let mut query = sqlx::query_as::<_, (String, String, i64)>(&query_str);
+        for value in match_values {
+            query = query.bind(value);
+        }
+        if !matched_document_ids.is_empty() {
+            query = query.bind(matched_document_ids);
+        }
+        query = query.bind(source_ids);
 
         if let Some(ct) = content_types {
             if !ct.is_empty() {
@@ -564,6 +920,10 @@ impl DocumentRepository {
             }
         }
 
+        if let Some(collection_id) = collection_id {
+            query = query.bind(collection_id);
+        }
+
         let facet_rows = query.fetch_all(&self.pool).await?;
 
         let mut facets_map: std::collections::HashMap<String, Vec<FacetValue>> =
@@ -584,16 +944,31 @@ impl DocumentRepository {
         Ok(facets)
     }
 
-    /// Directly populates the content field since we use the ParadeDB BM25 index now
-    pub async fn batch_upsert(
+    /// Insert or update documents with their content in a single statement, directly
+    /// populating the content field for the ParadeDB BM25 index. A document listed more than
+    /// once is written with its last entry, since one statement cannot update a row twice.
+    pub async fn bulk_upsert(
         &self,
-        documents: Vec<Document>,
-        contents: Vec<String>,
+        documents: Vec<(Document, String)>,
     ) -> Result<Vec<Document>, DatabaseError> {
         if documents.is_empty() {
             return Ok(vec![]);
         }
 
+        let mut positions = HashMap::new();
+        let mut deduplicated: Vec<(Document, String)> = Vec::with_capacity(documents.len());
+        for (document, content) in documents {
+            let key = (document.source_id.clone(), document.external_id.clone());
+            match positions.get(&key) {
+                Some(&position) => deduplicated[position] = (document, content),
+                None => {
+                    positions.insert(key, deduplicated.len());
+                    deduplicated.push((document, content));
+                }
+            }
+        }
+        let (documents, contents): (Vec<Document>, Vec<String>) = deduplicated.into_iter().unzip();
+
         // Build arrays for the batch upsert
         let ids: Vec<String> = documents.iter().map(|d| d.id.clone()).collect();
         let source_ids: Vec<String> = documents.iter().map(|d| d.source_id.clone()).collect();
@@ -695,6 +1070,391 @@ impl DocumentRepository {
 
         Ok(result.rows_affected() as i64)
     }
+
+    /// Delete every document of a source, returning the number deleted. Embeddings go with
+    /// them; content blobs are left to garbage collection.
+    pub async fn delete_by_source(&self, source_id: &str) -> Result<u64, DatabaseError> {
+        let result = sqlx::query("DELETE FROM documents WHERE source_id = $1")
+            .bind(source_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Documents of a source with any of the given external ids.
+    pub async fn find_by_external_ids(
+        &self,
+        source_id: &str,
+        external_ids: &[String],
+    ) -> Result<Vec<Document>, DatabaseError> {
+        if external_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let documents = sqlx::query_as::<_, Document>(
+            r#"
+            SELECT id, source_id, external_id, title, content_id, content_type,
+                   file_size, file_extension, url,
+                   metadata, permissions, attributes, created_at, updated_at, last_indexed_at
+            FROM documents
+            WHERE source_id = $1 AND external_id = ANY($2)
+            "#,
+        )
+        .bind(source_id)
+        .bind(external_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(documents)
+    }
+
+    /// Delete the documents of a source with any of the given external ids, returning the ids
+    /// of the deleted documents. External ids without a document are ignored.
+    pub async fn bulk_delete_by_external_ids(
+        &self,
+        source_id: &str,
+        external_ids: &[String],
+    ) -> Result<Vec<String>, DatabaseError> {
+        if external_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let deleted_ids = sqlx::query_scalar::<_, String>(
+            "DELETE FROM documents WHERE source_id = $1 AND external_id = ANY($2) RETURNING id",
+        )
+        .bind(source_id)
+        .bind(external_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(deleted_ids)
+    }
+
+    /// Documents whose attributes match every filter, optionally within one source, most
+    /// recently updated first. Filters compile to JSONB containment so the GIN index applies.
+    pub async fn find_by_attributes(
+        &self,
+        source_id: Option<&str>,
+        attribute_filters: &HashMap<String, AttributeFilter>,
+        limit: i64,
+    ) -> Result<Vec<Document>, DatabaseError> {
+        let mut conditions = vec!["($1::text IS NULL OR source_id = $1)".to_string()];
+        let mut binds = Vec::new();
+        let mut param_idx = 3;
+        for (key, filter) in attribute_filters {
+            if let Some(condition) =
+                attribute_containment_condition(key, filter, &mut param_idx, &mut binds)
+            {
+                conditions.push(condition);
+            }
+        }
+
+        let sql = format!(
+            r#"
+            SELECT id, source_id, external_id, title, content_id, content_type,
+                   file_size, file_extension, url,
+                   metadata, permissions, attributes, created_at, updated_at, last_indexed_at
+            FROM documents
+            WHERE {}
+            ORDER BY updated_at DESC
+            LIMIT $2
+            "#,
+            conditions.join(" AND ")
+        );
+
+        let mut query = sqlx::query_as::<_, Document>(&sql)
+            .bind(source_id)
+            .bind(limit);
+        for bind in binds {
+            query = query.bind(bind);
+        }
+
+        Ok(query.fetch_all(&self.pool).await?)
+    }
+
+    /// Number of documents carrying each attribute key and its number of distinct values,
+    /// optionally within one source, most used first.
+    pub async fn attribute_cardinality(
+        &self,
+        source_id: Option<&str>,
+    ) -> Result<Vec<AttributeCardinality>, DatabaseError> {
+        let cardinality = sqlx::query_as::<_, AttributeCardinality>(
+            r#"
+            SELECT a.key,
+                   COUNT(*) AS documents,
+                   COUNT(DISTINCT a.value) AS distinct_values
+            FROM documents d, jsonb_each(d.attributes) a
+            WHERE $1::text IS NULL OR d.source_id = $1
+            GROUP BY a.key
+            ORDER BY documents DESC, a.key
+            "#,
+        )
+        .bind(source_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(cardinality)
+    }
+
+    pub async fn count(&self, source_id: Option<&str>) -> Result<i64, DatabaseError> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM documents WHERE $1::text IS NULL OR source_id = $1",
+        )
+        .bind(source_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Index growth per day since `since`, based on document creation time.
+    pub async fn daily_index_growth(
+        &self,
+        since: time::OffsetDateTime,
+    ) -> Result<Vec<DailyIndexGrowth>, DatabaseError> {
+        let growth = sqlx::query_as::<_, DailyIndexGrowth>(
+            r#"
+            WITH daily AS (
+                SELECT date_trunc('day', created_at) AS day, COUNT(*) AS documents_added
+                FROM documents
+                WHERE created_at >= $1
+                GROUP BY 1
+            ),
+            baseline AS (
+                SELECT COUNT(*) AS total FROM documents WHERE created_at < $1
+            )
+            SELECT to_char(daily.day, 'YYYY-MM-DD') AS day,
+                   daily.documents_added,
+                   (baseline.total + SUM(daily.documents_added) OVER (ORDER BY daily.day))::BIGINT
+                       AS total_documents
+            FROM daily, baseline
+            ORDER BY daily.day
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(growth)
+    }
+
+    /// p50/p95/max indexing lag per source for document versions indexed since `since`.
+    /// Documents without a source-side `updated_at` are excluded.
+    pub async fn indexing_lag_by_source(
+        &self,
+        since: time::OffsetDateTime,
+    ) -> Result<Vec<SourceIndexingLag>, DatabaseError> {
+        let lag = sqlx::query_as::<_, SourceIndexingLag>(
+            r#"
+            WITH lag AS (
+                SELECT source_id,
+                       GREATEST(EXTRACT(EPOCH FROM source_indexed_at - source_updated_at), 0)::FLOAT8
+                           AS seconds
+                FROM documents
+                WHERE source_updated_at IS NOT NULL
+                  AND source_indexed_at >= $1
+            )
+            SELECT lag.source_id,
+                   s.name AS source_name,
+                   s.source_type,
+                   COUNT(*) AS documents,
+                   percentile_cont(0.5) WITHIN GROUP (ORDER BY lag.seconds) AS p50_seconds,
+                   percentile_cont(0.95) WITHIN GROUP (ORDER BY lag.seconds) AS p95_seconds,
+                   MAX(lag.seconds) AS max_seconds
+            FROM lag
+            JOIN sources s ON s.id = lag.source_id
+            GROUP BY lag.source_id, s.name, s.source_type
+            ORDER BY p95_seconds DESC
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(lag)
+    }
+
+    /// External ids of documents in a source last modified before `cutoff`, oldest first.
+    /// Documents without a source-side timestamp are aged by when they were first indexed.
+    /// Documents with a deletion already queued are skipped so repeated passes do not
+    /// enqueue duplicates.
+    pub async fn find_expired_external_ids(
+        &self,
+        source_id: &str,
+        cutoff: time::OffsetDateTime,
+        limit: i64,
+    ) -> Result<Vec<String>, DatabaseError> {
+        let external_ids = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT d.external_id
+            FROM documents d
+            WHERE d.source_id = $1
+              AND COALESCE(d.source_updated_at, d.created_at) < $2
+              AND NOT EXISTS (
+                  SELECT 1
+                  FROM connector_events_queue q
+                  WHERE q.source_id = d.source_id
+                    AND q.event_type = 'document_deleted'
+                    AND q.status IN ('pending', 'processing')
+                    AND q.payload->>'document_id' = d.external_id
+              )
+              AND NOT EXISTS (
+                  SELECT 1
+                  FROM connector_events_outbox o
+                  WHERE o.source_id = d.source_id
+                    AND o.event_type = 'document_deleted'
+                    AND o.payload->>'document_id' = d.external_id
+              )
+            ORDER BY COALESCE(d.source_updated_at, d.created_at)
+            LIMIT $3
+            "#,
+        )
+        .bind(source_id)
+        .bind(cutoff)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(external_ids)
+    }
+
+    /// Documents belonging to a data subject: those authored by `email`, plus non-public
+    /// documents whose only permitted principal is `email`. Returns `(id, content_id)` pairs.
+    pub async fn find_for_data_subject(
+        &self,
+        email: &str,
+    ) -> Result<Vec<(String, Option<String>)>, DatabaseError> {
+        let documents = sqlx::query_as::<_, (String, Option<String>)>(
+            r#"
+            SELECT id, content_id
+            FROM documents
+            WHERE lower(metadata->>'author') = lower($1)
+               OR (
+                    COALESCE((permissions->>'public')::boolean, false) = false
+                    AND COALESCE(jsonb_array_length(
+                            CASE WHEN jsonb_typeof(permissions->'groups') = 'array'
+                                 THEN permissions->'groups' END), 0) = 0
+                    AND jsonb_typeof(permissions->'users') = 'array'
+                    AND (
+                        SELECT array_agg(DISTINCT lower(u))
+                        FROM jsonb_array_elements_text(permissions->'users') AS u
+                    ) = ARRAY[lower($1)]
+               )
+            "#,
+        )
+        .bind(email)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(documents)
+    }
+}
+
+/// SQL condition matching documents whose `attributes` satisfy `filter`. Exact and any-of
+/// filters use containment (`@>`), which the `jsonb_path_ops` GIN index serves; ranges compare
+/// the JSONB value, so numbers compare numerically and strings lexically. Values are appended
+/// to `binds` as parameters numbered from `param_idx`. Returns `None` for filters without
+/// values, such as an empty any-of or a range without bounds.
+pub fn attribute_containment_condition(
+    key: &str,
+    filter: &AttributeFilter,
+    param_idx: &mut usize,
+    binds: &mut Vec<JsonValue>,
+) -> Option<String> {
+    let mut bind = |value: JsonValue| {
+        binds.push(value);
+        *param_idx += 1;
+        *param_idx - 1
+    };
+
+    match filter {
+        AttributeFilter::Exact(value) => {
+            let idx = bind(serde_json::json!({ key: value }));
+            Some(format!("attributes @> ${}::jsonb", idx))
+        }
+        AttributeFilter::AnyOf(values) => {
+            let conditions: Vec<String> = values
+                .iter()
+                .map(|value| {
+                    let idx = bind(serde_json::json!({ key: value }));
+                    format!("attributes @> ${}::jsonb", idx)
+                })
+                .collect();
+            (!conditions.is_empty()).then(|| format!("({})", conditions.join(" OR ")))
+        }
+        AttributeFilter::Range { gte, lte } => {
+            let key = key.replace('\'', "''");
+            let mut conditions = Vec::new();
+            if let Some(gte) = gte {
+                let idx = bind(gte.clone());
+                conditions.push(format!("attributes -> '{}' >= ${}::jsonb", key, idx));
+            }
+            if let Some(lte) = lte {
+                let idx = bind(lte.clone());
+                conditions.push(format!("attributes -> '{}' <= ${}::jsonb", key, idx));
+            }
+            (!conditions.is_empty()).then(|| conditions.join(" AND "))
+        }
+    }
+}
+
+/// Condition matching `query` against the given fields, with parameters from `param_idx`,
+/// and the values to bind to them in order. Fuzzy conditions also match terms within two
+/// edits of the query's, so misspelled queries still find documents. The query is folded as
+/// the index is, and its Chinese and Japanese text matches content by bigram phrases.
+fn match_condition(
+    query: &str,
+    fields: &[SearchField],
+    fuzzy: bool,
+    param_idx: &mut usize,
+) -> (String, Vec<String>) {
+    let fields = if fields.is_empty() {
+        &SearchField::DEFAULT[..]
+    } else {
+        fields
+    };
+    let cast = if fuzzy { "::pdb.fuzzy(2)" } else { "" };
+    let terms = QueryTerms::new(query);
+    let query = fold(query);
+
+    let mut conditions = Vec::new();
+    let mut values = Vec::new();
+    for field in fields {
+        match field {
+            SearchField::Title => {
+                conditions.push(format!("title ||| ${}{}", param_idx, cast));
+                if fuzzy {
+                    values.push(query.to_string());
+                } else {
+                    values.push(format!("{}::pdb.boost(2)", query));
+                }
+                *param_idx += 1;
+            }
+            SearchField::Metadata => {
+                conditions.push(format!(
+                    "metadata->>'author' ||| ${0}{1} OR metadata->>'path' ||| ${0}{1}",
+                    param_idx, cast
+                ));
+                values.push(query.to_string());
+                *param_idx += 1;
+            }
+            SearchField::Content => {
+                if !terms.words.is_empty() || terms.cjk_bigrams.is_empty() {
+                    conditions.push(format!("content ||| ${}{}", param_idx, cast));
+                    values.push(terms.words.clone());
+                    *param_idx += 1;
+                }
+                for bigram in &terms.cjk_bigrams {
+                    conditions.push(format!("content ### ${}", param_idx));
+                    values.push(bigram.clone());
+                    *param_idx += 1;
+                }
+            }
+        }
+    }
+
+    (format!("({})", conditions.join(" OR ")), values)
 }
 
 /// Convert a JSON value to a string suitable for ParadeDB term queries
@@ -708,3 +1468,110 @@ fn json_value_to_term_string(value: &JsonValue) -> String {
         _ => value.to_string(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_match_condition() {
+        let mut param_idx = 1;
+        let (condition, values) = match_condition("q3 planning", &[], false, &mut param_idx);
+        assert_eq!(condition, "(title ||| $1 OR content ||| $2)");
+        assert_eq!(values, vec!["q3 planning::pdb.boost(2)", "q3 planning"]);
+        assert_eq!(param_idx, 3);
+
+        let mut param_idx = 1;
+        let (condition, values) =
+            match_condition("alice", &[SearchField::Metadata], false, &mut param_idx);
+        assert_eq!(
+            condition,
+            "(metadata->>'author' ||| $1 OR metadata->>'path' ||| $1)"
+        );
+        assert_eq!(values, vec!["alice"]);
+        assert_eq!(param_idx, 2);
+
+        let mut param_idx = 4;
+        let (condition, values) = match_condition("plannign", &[], true, &mut param_idx);
+        assert_eq!(
+            condition,
+            "(title ||| $4::pdb.fuzzy(2) OR content ||| $5::pdb.fuzzy(2))"
+        );
+        assert_eq!(values, vec!["plannign", "plannign"]);
+    }
+
+    #[test]
+    fn test_match_condition_folds_and_splits_cjk() {
+        let mut param_idx = 1;
+        let (condition, values) = match_condition("Café menu", &[], false, &mut param_idx);
+        assert_eq!(condition, "(title ||| $1 OR content ||| $2)");
+        assert_eq!(values, vec!["Cafe menu::pdb.boost(2)", "Cafe menu"]);
+
+        let mut param_idx = 1;
+        let (condition, values) = match_condition("東京都 plan", &[], false, &mut param_idx);
+        assert_eq!(
+            condition,
+            "(title ||| $1 OR content ||| $2 OR content ### $3 OR content ### $4)"
+        );
+        assert_eq!(
+            values,
+            vec!["東京都 plan::pdb.boost(2)", "plan", "東京", "京都"]
+        );
+        assert_eq!(param_idx, 5);
+
+        let mut param_idx = 1;
+        let (condition, values) =
+            match_condition("議事録", &[SearchField::Content], false, &mut param_idx);
+        assert_eq!(condition, "(content ### $1 OR content ### $2)");
+        assert_eq!(values, vec!["議事", "事録"]);
+    }
+
+    #[test]
+    fn test_attribute_containment_condition() {
+        let mut param_idx = 3;
+        let mut binds = Vec::new();
+
+        let exact = AttributeFilter::Exact(json!("open"));
+        assert_eq!(
+            attribute_containment_condition("status", &exact, &mut param_idx, &mut binds)
+                .as_deref(),
+            Some("attributes @> $3::jsonb")
+        );
+
+        let any_of = AttributeFilter::AnyOf(vec![json!("P1"), json!("P2")]);
+        assert_eq!(
+            attribute_containment_condition("priority", &any_of, &mut param_idx, &mut binds)
+                .as_deref(),
+            Some("(attributes @> $4::jsonb OR attributes @> $5::jsonb)")
+        );
+
+        let range = AttributeFilter::Range {
+            gte: Some(json!(3)),
+            lte: None,
+        };
+        assert_eq!(
+            attribute_containment_condition("it's", &range, &mut param_idx, &mut binds).as_deref(),
+            Some("attributes -> 'it''s' >= $6::jsonb")
+        );
+
+        let unbounded = AttributeFilter::Range {
+            gte: None,
+            lte: None,
+        };
+        assert!(
+            attribute_containment_condition("x", &unbounded, &mut param_idx, &mut binds).is_none()
+        );
+
+        assert_eq!(param_idx, 7);
+        assert_eq!(
+            binds,
+            vec![
+                json!({"status": "open"}),
+                json!({"priority": "P1"}),
+                json!({"priority": "P2"}),
+                json!(3)
+            ]
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use crate::db::error::DatabaseError;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum AnchorKind {
+    /// A section heading, e.g. a markdown `## Heading` line.
+    Heading,
+    /// The start of a transcript line, e.g. `[12:34] Speaker: ...`.
+    Timestamp,
+}
+
+impl AnchorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnchorKind::Heading => "heading",
+            AnchorKind::Timestamp => "timestamp",
+        }
+    }
+}
+
+/// A point in a document's content that results can link to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct DocumentAnchor {
+    /// Character offset of the anchor in the content.
+    pub start_offset: i32,
+    pub kind: AnchorKind,
+    /// The heading text, or the timestamp as written.
+    pub label: String,
+    pub level: Option<i16>,
+    pub seconds: Option<i32>,
+}
+
+/// The heading and timestamp a chunk of a document falls under.
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct ChunkAnchor {
+    pub document_id: String,
+    pub start_offset: i32,
+    pub heading: Option<String>,
+    pub heading_level: Option<i16>,
+    pub timestamp_seconds: Option<i32>,
+}
+
+pub struct DocumentAnchorRepository {
+    pool: PgPool,
+}
+
+impl DocumentAnchorRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Replace the anchors of each document with the ones extracted from its current content.
+    pub async fn replace_for_documents(
+        &self,
+        documents: &[(String, Vec<DocumentAnchor>)],
+    ) -> Result<u64, DatabaseError> {
+        if documents.is_empty() {
+            return Ok(0);
+        }
+
+        let document_ids: Vec<String> = documents.iter().map(|(id, _)| id.clone()).collect();
+        let mut anchor_document_ids = Vec::new();
+        let mut offsets = Vec::new();
+        let mut kinds = Vec::new();
+        let mut labels = Vec::new();
+        let mut levels = Vec::new();
+        let mut seconds = Vec::new();
+        for (document_id, anchors) in documents {
+            for anchor in anchors {
+                anchor_document_ids.push(document_id.clone());
+                offsets.push(anchor.start_offset);
+                kinds.push(anchor.kind.as_str());
+                labels.push(anchor.label.clone());
+                levels.push(anchor.level);
+                seconds.push(anchor.seconds);
+            }
+        }
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM document_anchors WHERE document_id = ANY($1)")
+            .bind(&document_ids)
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query(
+            r#"
+            INSERT INTO document_anchors (document_id, start_offset, kind, label, level, seconds)
+            SELECT * FROM UNNEST(
+                $1::text[], $2::int4[], $3::text[], $4::text[], $5::int2[], $6::int4[]
+            )
+            "#,
+        )
+        .bind(&anchor_document_ids)
+        .bind(&offsets)
+        .bind(&kinds)
+        .bind(&labels)
+        .bind(&levels)
+        .bind(&seconds)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn find_by_document_id(
+        &self,
+        document_id: &str,
+    ) -> Result<Vec<DocumentAnchor>, DatabaseError> {
+        let anchors = sqlx::query_as::<_, DocumentAnchor>(
+            r#"
+            SELECT start_offset, kind, label, level, seconds
+            FROM document_anchors
+            WHERE document_id = $1
+            ORDER BY start_offset, kind
+            "#,
+        )
+        .bind(document_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(anchors)
+    }
+
+    /// The closest heading and timestamp at or before each `(document_id, start_offset)` chunk
+    /// start. Chunks with neither are left out.
+    pub async fn find_at_offsets(
+        &self,
+        chunks: &[(String, i32)],
+    ) -> Result<Vec<ChunkAnchor>, DatabaseError> {
+        if chunks.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let document_ids: Vec<String> = chunks.iter().map(|(id, _)| id.clone()).collect();
+        let offsets: Vec<i32> = chunks.iter().map(|(_, offset)| *offset).collect();
+        let anchors = sqlx::query_as::<_, ChunkAnchor>(
+            r#"
+            SELECT c.document_id, c.start_offset,
+                   h.label AS heading, h.level AS heading_level,
+                   t.seconds AS timestamp_seconds
+            FROM UNNEST($1::text[], $2::int4[]) AS c(document_id, start_offset)
+            LEFT JOIN LATERAL (
+                SELECT label, level
+                FROM document_anchors
+                WHERE document_id = c.document_id
+                  AND kind = 'heading'
+                  AND start_offset <= c.start_offset
+                ORDER BY start_offset DESC
+                LIMIT 1
+            ) h ON TRUE
+            LEFT JOIN LATERAL (
+                SELECT seconds
+                FROM document_anchors
+                WHERE document_id = c.document_id
+                  AND kind = 'timestamp'
+                  AND start_offset <= c.start_offset
+                ORDER BY start_offset DESC
+                LIMIT 1
+            ) t ON TRUE
+            WHERE h.label IS NOT NULL OR t.seconds IS NOT NULL
+            "#,
+        )
+        .bind(&document_ids)
+        .bind(&offsets)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(anchors)
+    }
+}
@@ -0,0 +1,190 @@
+use crate::{db::error::DatabaseError, utils::generate_ulid};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use std::collections::HashMap;
+use time::OffsetDateTime;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Hash)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum FlagReason {
+    Outdated,
+    Incorrect,
+    Sensitive,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum FlagStatus {
+    /// Waiting for the source owner to review it.
+    #[default]
+    Open,
+    /// The document was fixed.
+    Resolved,
+    /// The flag was reviewed and needed no change.
+    Dismissed,
+}
+
+/// Columns of a flag aliased `f`, with the flagged document, the owner of its source and the
+/// email of the user who raised it. Expects `documents d`, `sources s` and `users u` joined.
+const FLAG_COLUMNS: &str = r#"
+    f.id, f.document_id, d.title AS document_title, d.source_id,
+    s.created_by AS source_owner_id, f.user_id, u.email AS user_email,
+    f.reason, f.comment, f.status, f.resolved_by, f.resolved_at, f.created_at, f.updated_at
+"#;
+
+const FLAG_JOINS: &str = r#"
+    JOIN documents d ON d.id = f.document_id
+    JOIN sources s ON s.id = d.source_id
+    JOIN users u ON u.id = f.user_id
+"#;
+
+/// A user's report that a document is outdated, incorrect or sensitive.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DocumentFlag {
+    pub id: String,
+    pub document_id: String,
+    pub document_title: String,
+    pub source_id: String,
+    pub source_owner_id: String,
+    pub user_id: String,
+    pub user_email: String,
+    pub reason: FlagReason,
+    pub comment: Option<String>,
+    pub status: FlagStatus,
+    pub resolved_by: Option<String>,
+    #[serde(with = "time::serde::iso8601::option")]
+    pub resolved_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub updated_at: OffsetDateTime,
+}
+
+pub struct DocumentFlagRepository {
+    pool: PgPool,
+}
+
+impl DocumentFlagRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Flag a document. A user flagging a document again for the same reason reopens their
+    /// flag with the new comment.
+    pub async fn upsert(
+        &self,
+        document_id: &str,
+        user_id: &str,
+        reason: FlagReason,
+        comment: Option<&str>,
+    ) -> Result<DocumentFlag, DatabaseError> {
+        let flag = sqlx::query_as::<_, DocumentFlag>(&format!(
+            r#"
+            WITH f AS (
+                INSERT INTO document_flags (id, document_id, user_id, reason, comment)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (document_id, user_id, reason) DO UPDATE
+                SET comment = EXCLUDED.comment,
+                    status = 'open',
+                    resolved_by = NULL,
+                    resolved_at = NULL,
+                    updated_at = NOW()
+                RETURNING *
+            )
+            SELECT {} FROM f {}
+            "#,
+            FLAG_COLUMNS, FLAG_JOINS
+        ))
+        .bind(generate_ulid())
+        .bind(document_id)
+        .bind(user_id)
+        .bind(reason)
+        .bind(comment)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(flag)
+    }
+
+    /// Flags newest first, optionally only those with a status or on documents of sources a
+    /// user owns.
+    pub async fn find_all(
+        &self,
+        status: Option<FlagStatus>,
+        source_owner_id: Option<&str>,
+    ) -> Result<Vec<DocumentFlag>, DatabaseError> {
+        let flags = sqlx::query_as::<_, DocumentFlag>(&format!(
+            r#"
+            SELECT {}
+            FROM document_flags f {}
+            WHERE ($1::varchar IS NULL OR f.status = $1)
+              AND ($2::text IS NULL OR s.created_by = $2)
+            ORDER BY f.created_at DESC, f.id
+            "#,
+            FLAG_COLUMNS, FLAG_JOINS
+        ))
+        .bind(status)
+        .bind(source_owner_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(flags)
+    }
+
+    /// Set the status of a flag after review. Returns `None` when it does not exist.
+    pub async fn set_status(
+        &self,
+        id: &str,
+        status: FlagStatus,
+        resolved_by: Option<&str>,
+    ) -> Result<Option<DocumentFlag>, DatabaseError> {
+        let flag = sqlx::query_as::<_, DocumentFlag>(&format!(
+            r#"
+            WITH f AS (
+                UPDATE document_flags
+                SET status = $2,
+                    resolved_by = CASE WHEN $2 = 'open' THEN NULL ELSE $3 END,
+                    resolved_at = CASE WHEN $2 = 'open' THEN NULL ELSE NOW() END,
+                    updated_at = NOW()
+                WHERE id = $1
+                RETURNING *
+            )
+            SELECT {} FROM f {}
+            "#,
+            FLAG_COLUMNS, FLAG_JOINS
+        ))
+        .bind(id)
+        .bind(status)
+        .bind(resolved_by)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(flag)
+    }
+
+    /// The number of open flags on each of the documents that have any.
+    pub async fn count_open(
+        &self,
+        document_ids: &[String],
+    ) -> Result<HashMap<String, i64>, DatabaseError> {
+        if document_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let counts = sqlx::query_as::<_, (String, i64)>(
+            r#"
+            SELECT document_id, COUNT(*)
+            FROM document_flags
+            WHERE document_id = ANY($1) AND status = 'open'
+            GROUP BY document_id
+            "#,
+        )
+        .bind(document_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(counts.into_iter().collect())
+    }
+}
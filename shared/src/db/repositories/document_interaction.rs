@@ -0,0 +1,92 @@
+use crate::{db::error::DatabaseError, utils::generate_ulid};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use time::OffsetDateTime;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum InteractionKind {
+    /// The document was opened in the app, e.g. its preview.
+    View,
+    /// The document was opened from a search result.
+    Click,
+}
+
+/// A document's recent activity: the number of users who interacted with it, each counted
+/// once and decayed by how long ago they last did.
+#[derive(Debug, Clone, FromRow)]
+pub struct DocumentActivity {
+    pub document_id: String,
+    pub activity: f64,
+    pub updated_at: OffsetDateTime,
+}
+
+pub struct DocumentInteractionRepository {
+    pool: PgPool,
+}
+
+impl DocumentInteractionRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub async fn record(
+        &self,
+        document_id: &str,
+        user_id: &str,
+        kind: InteractionKind,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO document_interactions (id, document_id, user_id, kind)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(generate_ulid())
+        .bind(document_id)
+        .bind(user_id)
+        .bind(kind)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The documents with the most activity since `since`, where an interaction `half_life_hours`
+    /// old counts half as much as one now.
+    pub async fn find_most_active(
+        &self,
+        since: OffsetDateTime,
+        half_life_hours: f64,
+        limit: i64,
+    ) -> Result<Vec<DocumentActivity>, DatabaseError> {
+        let documents = sqlx::query_as::<_, DocumentActivity>(
+            r#"
+            SELECT d.id AS document_id, a.activity, d.updated_at
+            FROM (
+                SELECT document_id,
+                       SUM(power(0.5, EXTRACT(EPOCH FROM NOW() - last_at) / 3600 / $2))::float8
+                           AS activity
+                FROM (
+                    SELECT document_id, user_id, MAX(created_at) AS last_at
+                    FROM document_interactions
+                    WHERE created_at >= $1
+                    GROUP BY document_id, user_id
+                ) per_user
+                GROUP BY document_id
+            ) a
+            JOIN documents d ON d.id = a.document_id
+            ORDER BY a.activity DESC, d.id
+            LIMIT $3
+            "#,
+        )
+        .bind(since)
+        .bind(half_life_hours)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(documents)
+    }
+}
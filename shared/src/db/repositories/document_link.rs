@@ -0,0 +1,190 @@
+use crate::db::error::DatabaseError;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Hash)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum LinkKind {
+    /// A Confluence page, by page ID.
+    ConfluencePage,
+    /// A Jira issue, by issue key, e.g. `ENG-42`.
+    JiraIssue,
+    /// A Google Drive file, by file ID.
+    DriveFile,
+}
+
+impl LinkKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LinkKind::ConfluencePage => "confluence_page",
+            LinkKind::JiraIssue => "jira_issue",
+            LinkKind::DriveFile => "drive_file",
+        }
+    }
+}
+
+/// What a link in a document's content points to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LinkReference {
+    pub kind: LinkKind,
+    pub target_key: String,
+}
+
+/// A document linked to or from another one.
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct LinkedDocument {
+    pub document_id: String,
+    pub kind: LinkKind,
+}
+
+/// Whether document `d` is the target of link `l`, by the external IDs the connectors give
+/// Confluence pages (`confluence_page_<space>_<page ID>`), Jira issues
+/// (`jira_issue_<project>_<issue key>`) and Drive files (the file ID).
+const LINK_TARGET_MATCH: &str = r#"
+    (
+        (l.kind = 'confluence_page'
+            AND d.external_id LIKE 'confluence\_page\_%'
+            AND d.external_id LIKE '%\_' || l.target_key)
+        OR (l.kind = 'jira_issue'
+            AND d.external_id =
+                'jira_issue_' || split_part(l.target_key, '-', 1) || '_' || l.target_key)
+        OR (l.kind = 'drive_file' AND d.external_id = l.target_key)
+    )
+    AND d.id <> l.source_document_id
+"#;
+
+pub struct DocumentLinkRepository {
+    pool: PgPool,
+}
+
+impl DocumentLinkRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Replace the links of each document with the ones found in its current content, and
+    /// resolve the links between these documents and the rest of the index, in both
+    /// directions.
+    pub async fn replace_for_documents(
+        &self,
+        documents: &[(String, Vec<LinkReference>)],
+    ) -> Result<u64, DatabaseError> {
+        if documents.is_empty() {
+            return Ok(0);
+        }
+
+        let document_ids: Vec<String> = documents.iter().map(|(id, _)| id.clone()).collect();
+        let mut source_document_ids = Vec::new();
+        let mut kinds = Vec::new();
+        let mut target_keys = Vec::new();
+        for (document_id, links) in documents {
+            for link in links {
+                source_document_ids.push(document_id.clone());
+                kinds.push(link.kind.as_str());
+                target_keys.push(link.target_key.clone());
+            }
+        }
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM document_links WHERE source_document_id = ANY($1)")
+            .bind(&document_ids)
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query(
+            r#"
+            INSERT INTO document_links (source_document_id, kind, target_key)
+            SELECT * FROM UNNEST($1::text[], $2::text[], $3::text[])
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(&source_document_ids)
+        .bind(&kinds)
+        .bind(&target_keys)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(&format!(
+            r#"
+            UPDATE document_links l
+            SET target_document_id = d.id
+            FROM documents d
+            WHERE l.target_document_id IS NULL
+              AND (l.source_document_id = ANY($1) OR d.id = ANY($1))
+              AND {}
+            "#,
+            LINK_TARGET_MATCH
+        ))
+        .bind(&document_ids)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Documents a document links to.
+    pub async fn find_outbound(
+        &self,
+        document_id: &str,
+    ) -> Result<Vec<LinkedDocument>, DatabaseError> {
+        let links = sqlx::query_as::<_, LinkedDocument>(
+            r#"
+            SELECT DISTINCT ON (target_document_id)
+                   target_document_id AS document_id, kind
+            FROM document_links
+            WHERE source_document_id = $1 AND target_document_id IS NOT NULL
+            ORDER BY target_document_id, target_key
+            "#,
+        )
+        .bind(document_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(links)
+    }
+
+    /// Documents linking to a document, most recently linked first.
+    pub async fn find_inbound(
+        &self,
+        document_id: &str,
+    ) -> Result<Vec<LinkedDocument>, DatabaseError> {
+        let links = sqlx::query_as::<_, LinkedDocument>(
+            r#"
+            SELECT source_document_id AS document_id, kind
+            FROM document_links
+            WHERE target_document_id = $1
+            ORDER BY created_at DESC, source_document_id
+            "#,
+        )
+        .bind(document_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(links)
+    }
+
+    /// The number of documents linking to each of the documents that have any links to them.
+    pub async fn count_inbound(
+        &self,
+        document_ids: &[String],
+    ) -> Result<HashMap<String, i64>, DatabaseError> {
+        if document_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let counts = sqlx::query_as::<_, (String, i64)>(
+            r#"
+            SELECT target_document_id, COUNT(DISTINCT source_document_id)
+            FROM document_links
+            WHERE target_document_id = ANY($1)
+            GROUP BY target_document_id
+            "#,
+        )
+        .bind(document_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(counts.into_iter().collect())
+    }
+}
@@ -0,0 +1,101 @@
+use crate::db::error::DatabaseError;
+use crate::models::{DocumentPreview, PreviewKind};
+use sqlx::{FromRow, PgPool};
+
+/// A document's preview with the content blob holding the image.
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct StoredPreview {
+    pub document_id: String,
+    pub content_id: String,
+    pub kind: PreviewKind,
+    pub mime_type: String,
+}
+
+impl StoredPreview {
+    pub fn preview(&self) -> DocumentPreview {
+        DocumentPreview {
+            kind: self.kind,
+            mime_type: self.mime_type.clone(),
+        }
+    }
+}
+
+pub struct DocumentPreviewRepository {
+    pool: PgPool,
+}
+
+impl DocumentPreviewRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Set the preview of a document, replacing any previous one. The replaced image is left
+    /// to the blob garbage collector.
+    pub async fn upsert(
+        &self,
+        document_id: &str,
+        content_id: &str,
+        kind: PreviewKind,
+        mime_type: &str,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO document_previews (document_id, content_id, kind, mime_type)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (document_id) DO UPDATE
+            SET content_id = EXCLUDED.content_id,
+                kind = EXCLUDED.kind,
+                mime_type = EXCLUDED.mime_type,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(document_id)
+        .bind(content_id)
+        .bind(kind)
+        .bind(mime_type)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_document_id(
+        &self,
+        document_id: &str,
+    ) -> Result<Option<StoredPreview>, DatabaseError> {
+        let preview = sqlx::query_as::<_, StoredPreview>(
+            r#"
+            SELECT document_id, content_id, kind, mime_type
+            FROM document_previews
+            WHERE document_id = $1
+            "#,
+        )
+        .bind(document_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(preview)
+    }
+
+    pub async fn find_by_document_ids(
+        &self,
+        document_ids: &[String],
+    ) -> Result<Vec<StoredPreview>, DatabaseError> {
+        if document_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let previews = sqlx::query_as::<_, StoredPreview>(
+            r#"
+            SELECT document_id, content_id, kind, mime_type
+            FROM document_previews
+            WHERE document_id = ANY($1)
+            "#,
+        )
+        .bind(document_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(previews)
+    }
+}
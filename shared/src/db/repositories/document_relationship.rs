@@ -0,0 +1,117 @@
+use crate::db::error::DatabaseError;
+use crate::models::{DocumentParent, RelationshipType};
+use sqlx::{FromRow, PgPool};
+
+/// A child document with its parent, once the parent is indexed.
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct DocumentRelationship {
+    pub document_id: String,
+    pub parent_document_id: String,
+    pub relationship_type: RelationshipType,
+}
+
+pub struct DocumentRelationshipRepository {
+    pool: PgPool,
+}
+
+impl DocumentRelationshipRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Replace the parent of each document with the one its connector named, removing it from
+    /// documents named without one.
+    pub async fn replace_for_documents(
+        &self,
+        documents: &[(String, Option<DocumentParent>)],
+    ) -> Result<u64, DatabaseError> {
+        if documents.is_empty() {
+            return Ok(0);
+        }
+
+        let document_ids: Vec<String> = documents.iter().map(|(id, _)| id.clone()).collect();
+        let mut child_ids = Vec::new();
+        let mut parent_external_ids = Vec::new();
+        let mut relationship_types = Vec::new();
+        for (document_id, parent) in documents {
+            if let Some(parent) = parent {
+                child_ids.push(document_id.clone());
+                parent_external_ids.push(parent.external_id.clone());
+                relationship_types.push(parent.relationship.as_str());
+            }
+        }
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM document_relationships WHERE document_id = ANY($1)")
+            .bind(&document_ids)
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query(
+            r#"
+            INSERT INTO document_relationships
+                (document_id, source_id, parent_external_id, relationship_type)
+            SELECT d.id, d.source_id, r.parent_external_id, r.relationship_type
+            FROM UNNEST($1::text[], $2::text[], $3::text[])
+                AS r(document_id, parent_external_id, relationship_type)
+            JOIN documents d ON d.id = r.document_id
+            WHERE d.external_id <> r.parent_external_id
+            "#,
+        )
+        .bind(&child_ids)
+        .bind(&parent_external_ids)
+        .bind(&relationship_types)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// The parents of those of the documents that have one indexed.
+    pub async fn find_parents(
+        &self,
+        document_ids: &[String],
+    ) -> Result<Vec<DocumentRelationship>, DatabaseError> {
+        if document_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let relationships = sqlx::query_as::<_, DocumentRelationship>(
+            r#"
+            SELECT r.document_id, p.id AS parent_document_id, r.relationship_type
+            FROM document_relationships r
+            JOIN documents p
+                ON p.source_id = r.source_id AND p.external_id = r.parent_external_id
+            WHERE r.document_id = ANY($1)
+            "#,
+        )
+        .bind(document_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(relationships)
+    }
+
+    /// The children of a document, oldest first.
+    pub async fn find_children(
+        &self,
+        parent_document_id: &str,
+    ) -> Result<Vec<DocumentRelationship>, DatabaseError> {
+        let relationships = sqlx::query_as::<_, DocumentRelationship>(
+            r#"
+            SELECT r.document_id, p.id AS parent_document_id, r.relationship_type
+            FROM documents p
+            JOIN document_relationships r
+                ON r.source_id = p.source_id AND r.parent_external_id = p.external_id
+            JOIN documents d ON d.id = r.document_id
+            WHERE p.id = $1
+            ORDER BY d.created_at, d.id
+            "#,
+        )
+        .bind(parent_document_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(relationships)
+    }
+}
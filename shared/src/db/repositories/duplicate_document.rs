@@ -0,0 +1,176 @@
+use crate::db::error::DatabaseError;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use time::OffsetDateTime;
+
+/// Two documents with similar content.
+#[derive(Debug, Clone, FromRow)]
+pub struct SimilarPair {
+    pub document_id: String,
+    pub other_document_id: String,
+    pub similarity: f32,
+}
+
+/// A document of a duplicate cluster, where to find it and how close it is to the others.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DuplicateDocument {
+    pub cluster_id: String,
+    pub document_id: String,
+    pub title: String,
+    pub url: Option<String>,
+    pub source_id: String,
+    pub source_name: String,
+    pub source_type: String,
+    pub similarity: f32,
+    pub exact: bool,
+    #[serde(with = "time::serde::iso8601")]
+    pub updated_at: OffsetDateTime,
+}
+
+pub struct DuplicateDocumentRepository {
+    pool: PgPool,
+}
+
+impl DuplicateDocumentRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Groups of documents with identical content of at least `min_size_bytes`, by content
+    /// hash.
+    pub async fn find_identical(
+        &self,
+        min_size_bytes: i64,
+    ) -> Result<Vec<Vec<String>>, DatabaseError> {
+        let groups = sqlx::query_scalar::<_, Vec<String>>(
+            r#"
+            SELECT array_agg(d.id::text ORDER BY d.id)
+            FROM documents d
+            JOIN sources s ON s.id = d.source_id
+            JOIN content_blobs cb ON cb.id = d.content_id
+            WHERE NOT s.is_deleted
+              AND s.source_type <> 'people'
+              AND cb.sha256_hash IS NOT NULL
+              AND cb.size_bytes >= $1
+            GROUP BY cb.sha256_hash
+            HAVING COUNT(*) > 1
+            "#,
+        )
+        .bind(min_size_bytes)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(groups)
+    }
+
+    /// Pairs of documents, in any sources, whose first chunks are at least `min_similarity`
+    /// similar. Each document is paired with its `per_document` closest documents at most.
+    pub async fn find_similar(
+        &self,
+        min_similarity: f32,
+        per_document: i64,
+    ) -> Result<Vec<SimilarPair>, DatabaseError> {
+        let pairs = sqlx::query_as::<_, SimilarPair>(
+            r#"
+            SELECT d.id AS document_id, closest.id AS other_document_id, closest.similarity
+            FROM documents d
+            JOIN sources s ON s.id = d.source_id
+            JOIN embeddings e ON e.document_id = d.id AND e.chunk_index = 0
+            JOIN LATERAL (
+                SELECT other.id, (1 - (e.embedding <=> oe.embedding))::real AS similarity
+                FROM embeddings oe
+                JOIN documents other ON other.id = oe.document_id
+                JOIN sources os ON os.id = other.source_id
+                WHERE oe.chunk_index = 0
+                  AND oe.model_name = e.model_name
+                  AND oe.dimensions = e.dimensions
+                  AND other.id > d.id
+                  AND NOT os.is_deleted
+                  AND os.source_type <> 'people'
+                ORDER BY e.embedding <=> oe.embedding
+                LIMIT $2
+            ) closest ON closest.similarity >= $1
+            WHERE NOT s.is_deleted
+              AND s.source_type <> 'people'
+            "#,
+        )
+        .bind(min_similarity)
+        .bind(per_document)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(pairs)
+    }
+
+    /// Replace the duplicate clusters with `members`: each document with its cluster, its
+    /// highest similarity to another member and whether that one is identical.
+    pub async fn replace(
+        &self,
+        members: &[(String, String, f32, bool)],
+    ) -> Result<(), DatabaseError> {
+        let document_ids: Vec<&str> = members.iter().map(|m| m.0.as_str()).collect();
+        let cluster_ids: Vec<&str> = members.iter().map(|m| m.1.as_str()).collect();
+        let similarities: Vec<f32> = members.iter().map(|m| m.2).collect();
+        let exact: Vec<bool> = members.iter().map(|m| m.3).collect();
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM duplicate_documents")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO duplicate_documents (document_id, cluster_id, similarity, exact)
+            SELECT * FROM UNNEST($1::text[], $2::text[], $3::real[], $4::bool[])
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(&document_ids)
+        .bind(&cluster_ids)
+        .bind(&similarities)
+        .bind(&exact)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// The documents of up to `limit` clusters, optionally only of clusters with a document
+    /// in one source. Clusters spanning the most sources come first, then the largest.
+    pub async fn find_clusters(
+        &self,
+        source_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<DuplicateDocument>, DatabaseError> {
+        let documents = sqlx::query_as::<_, DuplicateDocument>(
+            r#"
+            WITH clusters AS (
+                SELECT dd.cluster_id,
+                       ROW_NUMBER() OVER (
+                           ORDER BY COUNT(DISTINCT d.source_id) DESC, COUNT(*) DESC, dd.cluster_id
+                       ) AS position
+                FROM duplicate_documents dd
+                JOIN documents d ON d.id = dd.document_id
+                GROUP BY dd.cluster_id
+                HAVING $1::text IS NULL OR bool_or(d.source_id = $1)
+                ORDER BY position
+                LIMIT $2
+            )
+            SELECT dd.cluster_id, d.id AS document_id, d.title, d.url,
+                   s.id AS source_id, s.name AS source_name, s.source_type,
+                   dd.similarity, dd.exact, d.updated_at
+            FROM clusters c
+            JOIN duplicate_documents dd ON dd.cluster_id = c.cluster_id
+            JOIN documents d ON d.id = dd.document_id
+            JOIN sources s ON s.id = d.source_id
+            ORDER BY c.position, d.updated_at DESC, d.id
+            "#,
+        )
+        .bind(source_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(documents)
+    }
+}
@@ -1,3 +1,4 @@
+use super::group::{group_identifiers_sql, user_group_identifiers_sql};
 use crate::{
     db::error::DatabaseError,
     models::{ChunkResult, Document, Embedding},
@@ -7,13 +8,34 @@ use pgvector::Vector;
 use sqlx::{PgPool, Row};
 use std::collections::HashSet;
 
+/// Largest `hnsw.ef_search` pgvector accepts.
+const MAX_HNSW_EF_SEARCH: i64 = 1000;
+
 pub struct EmbeddingRepository {
     pool: PgPool,
+    user_groups: Option<Vec<String>>,
 }
 
 impl EmbeddingRepository {
     pub fn new(pool: &PgPool) -> Self {
-        Self { pool: pool.clone() }
+        Self {
+            pool: pool.clone(),
+            user_groups: None,
+        }
+    }
+
+    /// Filter permissions by the identifiers of the user's groups, resolved ahead, rather
+    /// than looking them up in each query.
+    pub fn with_user_groups(mut self, user_groups: Option<Vec<String>>) -> Self {
+        self.user_groups = user_groups;
+        self
+    }
+
+    fn user_groups_sql(&self, user_email: &str) -> String {
+        match &self.user_groups {
+            Some(identifiers) => group_identifiers_sql(identifiers),
+            None => user_group_identifiers_sql(user_email),
+        }
     }
 
     /// Generate SQL condition to check if user has permission to access document
@@ -22,12 +44,11 @@ impl EmbeddingRepository {
             r#"(
                 (d.permissions->>'public')::boolean = true OR
                 d.permissions->'users' ? '{}' OR
-                d.permissions->'groups' ? ANY(
-                    -- TODO: Add group membership lookup here
-                    ARRAY['{}']::text[]
-                )
+                d.permissions->'groups' ?| (ARRAY['{}']::text[] || {})
             )"#,
-            user_email, user_email
+            user_email,
+            user_email,
+            self.user_groups_sql(user_email)
         )
     }
 
@@ -37,7 +58,7 @@ impl EmbeddingRepository {
     ) -> Result<Vec<Embedding>, DatabaseError> {
         let embeddings = sqlx::query_as::<_, Embedding>(
             r#"
-            SELECT id, document_id, chunk_index, chunk_start_offset, chunk_end_offset, embedding, model_name, dimensions, created_at
+            SELECT id, document_id, chunk_index, chunk_start_offset, chunk_end_offset, content_sha256, embedding, model_name, dimensions, created_at
             FROM embeddings
             WHERE document_id = $1
             ORDER BY chunk_index
@@ -53,9 +74,9 @@ impl EmbeddingRepository {
     pub async fn create(&self, embedding: Embedding) -> Result<Embedding, DatabaseError> {
         let created_embedding = sqlx::query_as::<_, Embedding>(
             r#"
-            INSERT INTO embeddings (id, document_id, chunk_index, chunk_start_offset, chunk_end_offset, embedding, model_name, dimensions)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            RETURNING id, document_id, chunk_index, chunk_start_offset, chunk_end_offset, embedding, model_name, dimensions, created_at
+            INSERT INTO embeddings (id, document_id, chunk_index, chunk_start_offset, chunk_end_offset, content_sha256, embedding, model_name, dimensions)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id, document_id, chunk_index, chunk_start_offset, chunk_end_offset, content_sha256, embedding, model_name, dimensions, created_at
             "#,
         )
         .bind(&embedding.id)
@@ -63,6 +84,7 @@ impl EmbeddingRepository {
         .bind(&embedding.chunk_index)
         .bind(&embedding.chunk_start_offset)
         .bind(&embedding.chunk_end_offset)
+        .bind(&embedding.content_sha256)
         .bind(&embedding.embedding)
         .bind(&embedding.model_name)
         .bind(&embedding.dimensions)
@@ -92,6 +114,10 @@ impl EmbeddingRepository {
         let chunk_start_offsets: Vec<i32> =
             embeddings.iter().map(|e| e.chunk_start_offset).collect();
         let chunk_end_offsets: Vec<i32> = embeddings.iter().map(|e| e.chunk_end_offset).collect();
+        let content_sha256s: Vec<Option<String>> = embeddings
+            .iter()
+            .map(|e| e.content_sha256.clone())
+            .collect();
         let embedding_vectors: Vec<Vector> =
             embeddings.iter().map(|e| e.embedding.clone()).collect();
         let model_names: Vec<String> = embeddings.iter().map(|e| e.model_name.clone()).collect();
@@ -101,11 +127,12 @@ impl EmbeddingRepository {
 
         sqlx::query(
             r#"
-            INSERT INTO embeddings (id, document_id, chunk_index, chunk_start_offset, chunk_end_offset, embedding, model_name, dimensions)
-            SELECT * FROM UNNEST($1::text[], $2::text[], $3::int4[], $4::int4[], $5::int4[], $6::vector[], $7::text[], $8::int2[])
+            INSERT INTO embeddings (id, document_id, chunk_index, chunk_start_offset, chunk_end_offset, content_sha256, embedding, model_name, dimensions)
+            SELECT * FROM UNNEST($1::text[], $2::text[], $3::int4[], $4::int4[], $5::int4[], $6::text[], $7::vector[], $8::text[], $9::int2[])
             ON CONFLICT (document_id, chunk_index, model_name) DO UPDATE
             SET chunk_start_offset = EXCLUDED.chunk_start_offset,
                 chunk_end_offset = EXCLUDED.chunk_end_offset,
+                content_sha256 = EXCLUDED.content_sha256,
                 embedding = EXCLUDED.embedding,
                 dimensions = EXCLUDED.dimensions
             "#,
@@ -115,6 +142,7 @@ impl EmbeddingRepository {
         .bind(&chunk_indices)
         .bind(&chunk_start_offsets)
         .bind(&chunk_end_offsets)
+        .bind(&content_sha256s)
         .bind(&embedding_vectors)
         .bind(&model_names)
         .bind(&dimensions_values)
@@ -132,8 +160,11 @@ impl EmbeddingRepository {
         content_types: Option<&[String]>,
         limit: i64,
         offset: i64,
+        collection_id: Option<&str>,
         user_email: Option<&str>,
         document_id: Option<&str>,
+        model_name: Option<&str>,
+        max_comparisons: Option<i64>,
     ) -> Result<Vec<ChunkResult>, DatabaseError> {
         let dims = embedding.len() as i16;
         let vector = Vector::from(embedding);
@@ -151,6 +182,13 @@ impl EmbeddingRepository {
             bind_index += 1;
         }
 
+        // Embeddings of other models are not comparable with this query embedding, even when
+        // they happen to have the same dimensions
+        if model_name.is_some() {
+            where_conditions.push(format!("e.model_name = ${}", bind_index));
+            bind_index += 1;
+        }
+
         if let Some(src) = source_types {
             if !src.is_empty() {
                 where_conditions.push(format!(
@@ -168,6 +206,13 @@ impl EmbeddingRepository {
             }
         }
 
+        if collection_id.is_some() {
+            where_conditions.push(format!(
+                "d.id IN (SELECT document_id FROM collection_documents WHERE collection_id = ${})",
+                bind_index
+            ));
+        }
+
         // Add permission filtering if user email is provided
         if let Some(email) = user_email {
             where_conditions.push(self.generate_permission_filter(email));
@@ -186,7 +231,8 @@ impl EmbeddingRepository {
                 e.embedding <=> $1 as distance,
                 e.chunk_start_offset,
                 e.chunk_end_offset,
-                e.chunk_index
+                e.chunk_index,
+                e.content_sha256
             FROM embeddings e
             JOIN documents d ON e.document_id = d.id
             {}
@@ -206,6 +252,10 @@ impl EmbeddingRepository {
             query = query.bind(doc_id);
         }
 
+        if let Some(model_name) = model_name {
+            query = query.bind(model_name);
+        }
+
         if let Some(src) = source_types {
             if !src.is_empty() {
                 query = query.bind(src);
@@ -218,7 +268,27 @@ impl EmbeddingRepository {
             }
         }
 
-        let results = query.fetch_all(&self.pool).await?;
+        if let Some(collection_id) = collection_id {
+            query = query.bind(collection_id);
+        }
+
+        // The HNSW candidate list bounds how many vectors the index search compares the query
+        // with, and how many chunks it can return
+        let results = match max_comparisons {
+            Some(max_comparisons) => {
+                let mut tx = self.pool.begin().await?;
+                sqlx::query(&format!(
+                    "SET LOCAL hnsw.ef_search = {}",
+                    max_comparisons.clamp(1, MAX_HNSW_EF_SEARCH)
+                ))
+                .execute(&mut *tx)
+                .await?;
+                let results = query.fetch_all(&mut *tx).await?;
+                tx.commit().await?;
+                results
+            }
+            None => query.fetch_all(&self.pool).await?,
+        };
         let chunk_results: Vec<ChunkResult> = results
             .into_iter()
             .map(|row| {
@@ -230,6 +300,7 @@ impl EmbeddingRepository {
                     chunk_start_offset: row.get("chunk_start_offset"),
                     chunk_end_offset: row.get("chunk_end_offset"),
                     chunk_index: row.get("chunk_index"),
+                    content_sha256: row.get("content_sha256"),
                 }
             })
             .collect();
@@ -243,6 +314,7 @@ impl EmbeddingRepository {
         document_id: &str,
         center_chunk_indices: &[i32],
         context_window: i32,
+        model_name: Option<&str>,
     ) -> Result<Vec<Embedding>, DatabaseError> {
         if center_chunk_indices.is_empty() {
             return Ok(vec![]);
@@ -263,14 +335,16 @@ impl EmbeddingRepository {
 
         let embeddings = sqlx::query_as::<_, Embedding>(
             r#"
-            SELECT id, document_id, chunk_index, chunk_start_offset, chunk_end_offset, embedding, model_name, dimensions, created_at
+            SELECT id, document_id, chunk_index, chunk_start_offset, chunk_end_offset, content_sha256, embedding, model_name, dimensions, created_at
             FROM embeddings
             WHERE document_id = $1 AND chunk_index = ANY($2)
+              AND ($3::text IS NULL OR model_name = $3)
             ORDER BY chunk_index
             "#,
         )
         .bind(document_id)
         .bind(&indices)
+        .bind(model_name)
         .fetch_all(&self.pool)
         .await?;
 
@@ -326,6 +400,7 @@ impl EmbeddingRepository {
                     created_at: row.get("created_at"),
                     updated_at: row.get("updated_at"),
                     last_indexed_at: row.get("last_indexed_at"),
+                    preview: None,
                 };
                 let distance: Option<f64> = row.get("distance");
                 let similarity = (1.0 - distance.unwrap_or(1.0)) as f32;
@@ -366,4 +441,47 @@ impl EmbeddingRepository {
 
         Ok(result.rows_affected())
     }
+
+    /// Number of documents with embeddings of each model, keyed by model name.
+    pub async fn count_documents_by_model(&self) -> Result<Vec<(String, i64)>, DatabaseError> {
+        let counts = sqlx::query_as::<_, (String, i64)>(
+            r#"
+            SELECT model_name, COUNT(DISTINCT document_id)
+            FROM embeddings
+            GROUP BY model_name
+            ORDER BY model_name
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(counts)
+    }
+
+    /// Delete up to `limit` embeddings of a model, or of every model but the given one when
+    /// `keep` is set. Returns the number deleted, callers loop until it drops below `limit`.
+    pub async fn delete_batch_by_model(
+        &self,
+        model_name: &str,
+        keep: bool,
+        limit: i64,
+    ) -> Result<u64, DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM embeddings
+            WHERE id IN (
+                SELECT id FROM embeddings
+                WHERE (model_name = $1) <> $2
+                LIMIT $3
+            )
+            "#,
+        )
+        .bind(model_name)
+        .bind(keep)
+        .bind(limit)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
 }
@@ -0,0 +1,150 @@
+use crate::db::error::DatabaseError;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use time::OffsetDateTime;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq)]
+pub struct FeatureFlag {
+    pub name: String,
+    pub description: String,
+    /// Off everywhere when unset, whatever the rollout.
+    pub enabled: bool,
+    /// Share of subjects the flag is on for, from 0 to 100.
+    pub rollout_percentage: i16,
+    /// Tenants the flag is on for regardless of the rollout.
+    pub tenants: Vec<String>,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub updated_at: OffsetDateTime,
+}
+
+/// The admin-editable fields of a flag.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureFlagInput {
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub rollout_percentage: i16,
+    #[serde(default)]
+    pub tenants: Vec<String>,
+}
+
+impl FeatureFlagInput {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0..=100).contains(&self.rollout_percentage) {
+            return Err(format!(
+                "Rollout percentage must be between 0 and 100, got {}",
+                self.rollout_percentage
+            ));
+        }
+        if self.tenants.iter().any(|tenant| tenant.trim().is_empty()) {
+            return Err("Tenants must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Searches logged with a flag on or off.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct FeatureFlagVariantStats {
+    pub enabled: bool,
+    pub queries: i64,
+    pub zero_result_queries: i64,
+    pub avg_total_ms: f64,
+    pub p95_total_ms: f64,
+}
+
+pub struct FeatureFlagRepository {
+    pool: PgPool,
+}
+
+impl FeatureFlagRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub async fn find_all(&self) -> Result<Vec<FeatureFlag>, DatabaseError> {
+        let flags = sqlx::query_as::<_, FeatureFlag>(
+            r#"
+            SELECT name, description, enabled, rollout_percentage, tenants, created_at, updated_at
+            FROM feature_flags
+            ORDER BY name
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(flags)
+    }
+
+    pub async fn upsert(
+        &self,
+        name: &str,
+        input: &FeatureFlagInput,
+    ) -> Result<FeatureFlag, DatabaseError> {
+        let flag = sqlx::query_as::<_, FeatureFlag>(
+            r#"
+            INSERT INTO feature_flags (name, description, enabled, rollout_percentage, tenants)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (name) DO UPDATE SET
+                description = EXCLUDED.description,
+                enabled = EXCLUDED.enabled,
+                rollout_percentage = EXCLUDED.rollout_percentage,
+                tenants = EXCLUDED.tenants,
+                updated_at = NOW()
+            RETURNING name, description, enabled, rollout_percentage, tenants, created_at, updated_at
+            "#,
+        )
+        .bind(name)
+        .bind(&input.description)
+        .bind(input.enabled)
+        .bind(input.rollout_percentage)
+        .bind(&input.tenants)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(flag)
+    }
+
+    pub async fn delete(&self, name: &str) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM feature_flags WHERE name = $1")
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Searches logged since `since` with the flag on, and with it off. Searches logged
+    /// without the flag evaluated are left out. Counts and the average are estimated from the
+    /// sampled logs, the 95th percentile is over the logged searches.
+    pub async fn variant_stats(
+        &self,
+        name: &str,
+        since: OffsetDateTime,
+    ) -> Result<Vec<FeatureFlagVariantStats>, DatabaseError> {
+        let stats = sqlx::query_as::<_, FeatureFlagVariantStats>(
+            r#"
+            SELECT (feature_flags->>$1)::boolean AS enabled,
+                   ROUND(SUM(1 / sample_rate))::BIGINT AS queries,
+                   COALESCE(ROUND(SUM(1 / sample_rate) FILTER (WHERE result_count = 0)), 0)::BIGINT
+                       AS zero_result_queries,
+                   (SUM(total_ms / sample_rate) / SUM(1 / sample_rate))::float8 AS avg_total_ms,
+                   percentile_cont(0.95) WITHIN GROUP (ORDER BY total_ms)::float8 AS p95_total_ms
+            FROM search_query_logs
+            WHERE created_at >= $2 AND feature_flags ? $1
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+        )
+        .bind(name)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(stats)
+    }
+}
@@ -0,0 +1,290 @@
+use crate::{db::error::DatabaseError, utils::generate_ulid};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use time::OffsetDateTime;
+
+/// A group of users, provisioned by an identity provider.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Group {
+    pub id: String,
+    pub display_name: String,
+    pub external_id: Option<String>,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub updated_at: OffsetDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GroupMember {
+    pub user_id: String,
+    pub email: String,
+}
+
+/// SQL expression evaluating to the identifiers of every group the user belongs to. A group
+/// matches document permissions by display name or by the identity provider's external id.
+pub(crate) fn user_group_identifiers_sql(user_email: &str) -> String {
+    format!(
+        r#"ARRAY(
+            SELECT ident FROM groups g
+            JOIN group_members gm ON gm.group_id = g.id
+            JOIN users u ON u.id = gm.user_id,
+            LATERAL unnest(ARRAY[g.display_name, g.external_id]) AS ident
+            WHERE u.email = '{}' AND u.is_active AND ident IS NOT NULL
+        )"#,
+        user_email.replace('\'', "''")
+    )
+}
+
+/// SQL expression evaluating to group identifiers resolved ahead of the query, with
+/// [`GroupRepository::find_user_group_identifiers`].
+pub(crate) fn group_identifiers_sql(identifiers: &[String]) -> String {
+    let quoted: Vec<String> = identifiers
+        .iter()
+        .map(|ident| format!("'{}'", ident.replace('\'', "''")))
+        .collect();
+    format!("ARRAY[{}]::text[]", quoted.join(", "))
+}
+
+pub struct GroupRepository {
+    pool: PgPool,
+}
+
+impl GroupRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub async fn create(
+        &self,
+        display_name: &str,
+        external_id: Option<&str>,
+    ) -> Result<Group, DatabaseError> {
+        let group = sqlx::query_as::<_, Group>(
+            r#"
+            INSERT INTO groups (id, display_name, external_id)
+            VALUES ($1, $2, $3)
+            RETURNING id, display_name, external_id, created_at, updated_at
+            "#,
+        )
+        .bind(generate_ulid())
+        .bind(display_name)
+        .bind(external_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                DatabaseError::ConstraintViolation("Group already exists".to_string())
+            }
+            _ => DatabaseError::from(e),
+        })?;
+
+        Ok(group)
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<Group>, DatabaseError> {
+        let group = sqlx::query_as::<_, Group>(
+            "SELECT id, display_name, external_id, created_at, updated_at FROM groups WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(group)
+    }
+
+    /// Groups ordered by creation, optionally filtered by exact display name, along with
+    /// the total number of matching groups.
+    pub async fn list(
+        &self,
+        display_name: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Group>, i64), DatabaseError> {
+        let groups = sqlx::query_as::<_, Group>(
+            r#"
+            SELECT id, display_name, external_id, created_at, updated_at
+            FROM groups
+            WHERE $1::text IS NULL OR display_name = $1
+            ORDER BY created_at, id
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(display_name)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM groups WHERE $1::text IS NULL OR display_name = $1",
+        )
+        .bind(display_name)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok((groups, total))
+    }
+
+    pub async fn update(
+        &self,
+        id: &str,
+        display_name: &str,
+        external_id: Option<&str>,
+    ) -> Result<Option<Group>, DatabaseError> {
+        let group = sqlx::query_as::<_, Group>(
+            r#"
+            UPDATE groups SET display_name = $2, external_id = $3
+            WHERE id = $1
+            RETURNING id, display_name, external_id, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(display_name)
+        .bind(external_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                DatabaseError::ConstraintViolation("Group already exists".to_string())
+            }
+            _ => DatabaseError::from(e),
+        })?;
+
+        Ok(group)
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM groups WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn find_members(&self, group_id: &str) -> Result<Vec<GroupMember>, DatabaseError> {
+        let members = sqlx::query_as::<_, GroupMember>(
+            r#"
+            SELECT u.id AS user_id, u.email
+            FROM group_members gm
+            JOIN users u ON u.id = gm.user_id
+            WHERE gm.group_id = $1
+            ORDER BY u.email
+            "#,
+        )
+        .bind(group_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(members)
+    }
+
+    /// Identifiers of every group the user belongs to, which document permissions match.
+    /// Resolving them once spares queries filtered for the same user from looking them up.
+    pub async fn find_user_group_identifiers(
+        &self,
+        user_email: &str,
+    ) -> Result<Vec<String>, DatabaseError> {
+        let identifiers = sqlx::query_scalar::<_, String>(&format!(
+            "SELECT unnest({})",
+            user_group_identifiers_sql(user_email)
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(identifiers)
+    }
+
+    /// Add users to a group. Unknown user ids are ignored, returning the number of
+    /// memberships created.
+    pub async fn add_members(
+        &self,
+        group_id: &str,
+        user_ids: &[String],
+    ) -> Result<u64, DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO group_members (group_id, user_id)
+            SELECT $1, u.id FROM users u WHERE u.id = ANY($2)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(group_id)
+        .bind(user_ids)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn remove_members(
+        &self,
+        group_id: &str,
+        user_ids: &[String],
+    ) -> Result<u64, DatabaseError> {
+        let result =
+            sqlx::query("DELETE FROM group_members WHERE group_id = $1 AND user_id = ANY($2)")
+                .bind(group_id)
+                .bind(user_ids)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Replace the full member list of a group.
+    pub async fn set_members(
+        &self,
+        group_id: &str,
+        user_ids: &[String],
+    ) -> Result<(), DatabaseError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM group_members WHERE group_id = $1")
+            .bind(group_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO group_members (group_id, user_id)
+            SELECT $1, u.id FROM users u WHERE u.id = ANY($2)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(group_id)
+        .bind(user_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn find_for_user(&self, user_id: &str) -> Result<Vec<Group>, DatabaseError> {
+        let groups = sqlx::query_as::<_, Group>(
+            r#"
+            SELECT g.id, g.display_name, g.external_id, g.created_at, g.updated_at
+            FROM groups g
+            JOIN group_members gm ON gm.group_id = g.id
+            WHERE gm.user_id = $1
+            ORDER BY g.display_name
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(groups)
+    }
+
+    pub async fn remove_user_from_all(&self, user_id: &str) -> Result<u64, DatabaseError> {
+        let result = sqlx::query("DELETE FROM group_members WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,98 @@
+use crate::db::error::DatabaseError;
+use sqlx::PgPool;
+
+/// Checks of invariants the index relies on, each returning the ids of the documents that
+/// break it.
+pub struct IndexIntegrityRepository {
+    pool: PgPool,
+}
+
+impl IndexIntegrityRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Documents of live sources whose content cannot be read: they have no content blob,
+    /// or the blob has neither inline content nor a key in external storage.
+    pub async fn find_documents_without_content(&self) -> Result<Vec<String>, DatabaseError> {
+        let ids = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT d.id
+            FROM documents d
+            JOIN sources s ON s.id = d.source_id
+            LEFT JOIN content_blobs cb ON cb.id = d.content_id
+            WHERE NOT s.is_deleted
+              AND (
+                  cb.id IS NULL
+                  OR (cb.storage_backend = 'postgres' AND cb.content IS NULL)
+                  OR (cb.storage_backend <> 'postgres' AND cb.storage_key IS NULL)
+              )
+            ORDER BY d.id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// Documents of live sources with content but no embeddings from `model_name`, or from
+    /// any model when it is `None`, that are not waiting to be embedded.
+    pub async fn find_documents_missing_embeddings(
+        &self,
+        model_name: Option<&str>,
+    ) -> Result<Vec<String>, DatabaseError> {
+        let ids = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT d.id
+            FROM documents d
+            JOIN sources s ON s.id = d.source_id
+            WHERE NOT s.is_deleted
+              AND d.content_id IS NOT NULL
+              AND COALESCE(d.content, 'x') <> ''
+              AND NOT EXISTS (
+                  SELECT 1 FROM embeddings e
+                  WHERE e.document_id = d.id AND ($1::text IS NULL OR e.model_name = $1)
+              )
+              AND NOT EXISTS (
+                  SELECT 1 FROM embedding_queue q
+                  WHERE q.document_id = d.id AND q.status IN ('pending', 'processing')
+              )
+            ORDER BY d.id
+            "#,
+        )
+        .bind(model_name)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// Documents with an embedding chunk ending past the end of their content, which points
+    /// search results at text that no longer exists. Documents waiting to be embedded are
+    /// left out, as embedding replaces their chunks.
+    pub async fn find_documents_with_out_of_bounds_chunks(
+        &self,
+    ) -> Result<Vec<String>, DatabaseError> {
+        let ids = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT d.id
+            FROM documents d
+            WHERE d.content IS NOT NULL
+              AND EXISTS (
+                  SELECT 1 FROM embeddings e
+                  WHERE e.document_id = d.id AND e.chunk_end_offset > char_length(d.content)
+              )
+              AND NOT EXISTS (
+                  SELECT 1 FROM embedding_queue q
+                  WHERE q.document_id = d.id AND q.status IN ('pending', 'processing')
+              )
+            ORDER BY d.id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+}
@@ -0,0 +1,446 @@
+use crate::{
+    db::error::DatabaseError,
+    models::{Embedding, Source},
+    utils::generate_ulid,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::{FromRow, PgPool};
+use time::OffsetDateTime;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum IndexSnapshotKind {
+    Snapshot,
+    Restore,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum IndexSnapshotStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+/// A snapshot of the index taken to object storage, or a restore of one. Restores carry the
+/// id of the snapshot they read, which may have been taken by another deployment.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct IndexSnapshot {
+    pub id: String,
+    pub kind: IndexSnapshotKind,
+    pub snapshot_id: String,
+    pub source_id: Option<String>,
+    pub status: IndexSnapshotStatus,
+    pub document_count: i64,
+    pub embedding_count: i64,
+    pub content_blob_count: i64,
+    pub error_message: Option<String>,
+    pub created_by: Option<String>,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601::option")]
+    pub started_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::iso8601::option")]
+    pub completed_at: Option<OffsetDateTime>,
+}
+
+/// Number of rows a snapshot or restore has written so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SnapshotCounts {
+    pub documents: i64,
+    pub embeddings: i64,
+    pub content_blobs: i64,
+}
+
+/// A document as stored in a snapshot. `permissions` are the connector permissions, with
+/// manual overrides kept apart so a restore layers them the same way the index does.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SnapshotDocument {
+    pub id: String,
+    pub source_id: String,
+    pub external_id: String,
+    pub title: String,
+    pub content_id: Option<String>,
+    pub content_type: Option<String>,
+    pub file_size: Option<i64>,
+    pub file_extension: Option<String>,
+    pub url: Option<String>,
+    pub metadata: JsonValue,
+    pub permissions: JsonValue,
+    pub permission_overrides: Option<JsonValue>,
+    pub attributes: JsonValue,
+    pub content: Option<String>,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub updated_at: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub last_indexed_at: OffsetDateTime,
+}
+
+const SNAPSHOT_COLUMNS: &str = "id, kind, snapshot_id, source_id, status, document_count, \
+     embedding_count, content_blob_count, error_message, created_by, created_at, started_at, \
+     completed_at";
+
+pub struct IndexSnapshotRepository {
+    pool: PgPool,
+}
+
+impl IndexSnapshotRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Record a new snapshot. Its files are written under its own id.
+    pub async fn create_snapshot(
+        &self,
+        source_id: Option<&str>,
+        created_by: Option<&str>,
+    ) -> Result<IndexSnapshot, DatabaseError> {
+        let id = generate_ulid();
+        self.create(&id, IndexSnapshotKind::Snapshot, &id, source_id, created_by)
+            .await
+    }
+
+    /// Record a restore of `snapshot_id`, optionally limited to one source.
+    pub async fn create_restore(
+        &self,
+        snapshot_id: &str,
+        source_id: Option<&str>,
+        created_by: Option<&str>,
+    ) -> Result<IndexSnapshot, DatabaseError> {
+        self.create(
+            &generate_ulid(),
+            IndexSnapshotKind::Restore,
+            snapshot_id,
+            source_id,
+            created_by,
+        )
+        .await
+    }
+
+    async fn create(
+        &self,
+        id: &str,
+        kind: IndexSnapshotKind,
+        snapshot_id: &str,
+        source_id: Option<&str>,
+        created_by: Option<&str>,
+    ) -> Result<IndexSnapshot, DatabaseError> {
+        let snapshot = sqlx::query_as::<_, IndexSnapshot>(&format!(
+            r#"
+            INSERT INTO index_snapshots (id, kind, snapshot_id, source_id, status, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {}
+            "#,
+            SNAPSHOT_COLUMNS
+        ))
+        .bind(id)
+        .bind(kind)
+        .bind(snapshot_id)
+        .bind(source_id)
+        .bind(IndexSnapshotStatus::Pending)
+        .bind(created_by)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(snapshot)
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<IndexSnapshot>, DatabaseError> {
+        let snapshot = sqlx::query_as::<_, IndexSnapshot>(&format!(
+            "SELECT {} FROM index_snapshots WHERE id = $1",
+            SNAPSHOT_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(snapshot)
+    }
+
+    /// Snapshots and restores, newest first.
+    pub async fn list(&self, limit: i64) -> Result<Vec<IndexSnapshot>, DatabaseError> {
+        let snapshots = sqlx::query_as::<_, IndexSnapshot>(&format!(
+            "SELECT {} FROM index_snapshots ORDER BY created_at DESC, id DESC LIMIT $1",
+            SNAPSHOT_COLUMNS
+        ))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(snapshots)
+    }
+
+    /// Snapshots and restores that have not reached a terminal state, oldest first. Used to
+    /// resume work interrupted by a restart.
+    pub async fn find_unfinished(&self) -> Result<Vec<IndexSnapshot>, DatabaseError> {
+        let snapshots = sqlx::query_as::<_, IndexSnapshot>(&format!(
+            r#"
+            SELECT {} FROM index_snapshots
+            WHERE status IN ('pending', 'running')
+            ORDER BY created_at
+            "#,
+            SNAPSHOT_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(snapshots)
+    }
+
+    pub async fn mark_running(&self, id: &str) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE index_snapshots
+            SET status = $2, started_at = COALESCE(started_at, NOW())
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(IndexSnapshotStatus::Running)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_progress(
+        &self,
+        id: &str,
+        counts: &SnapshotCounts,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE index_snapshots
+            SET document_count = $2, embedding_count = $3, content_blob_count = $4
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(counts.documents)
+        .bind(counts.embeddings)
+        .bind(counts.content_blobs)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_completed(
+        &self,
+        id: &str,
+        counts: &SnapshotCounts,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE index_snapshots
+            SET status = $2, document_count = $3, embedding_count = $4, content_blob_count = $5,
+                error_message = NULL, completed_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(IndexSnapshotStatus::Completed)
+        .bind(counts.documents)
+        .bind(counts.embeddings)
+        .bind(counts.content_blobs)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_failed(&self, id: &str, error_message: &str) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE index_snapshots
+            SET status = $2, error_message = $3, completed_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(IndexSnapshotStatus::Failed)
+        .bind(error_message)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Sources included in a snapshot: the given source, or every source that is not deleted.
+    pub async fn find_sources(
+        &self,
+        source_id: Option<&str>,
+    ) -> Result<Vec<Source>, DatabaseError> {
+        let sources = sqlx::query_as::<_, Source>(
+            r#"
+            SELECT id, name, source_type, config, is_active, is_deleted,
+                   user_filter_mode, user_whitelist, user_blacklist,
+                   connector_state, sync_interval_seconds, created_at, updated_at, created_by
+            FROM sources
+            WHERE is_deleted = false AND ($1::text IS NULL OR id = $1)
+            ORDER BY id
+            "#,
+        )
+        .bind(source_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(sources)
+    }
+
+    /// The next page of documents of the given sources in id order, after `after_id`.
+    pub async fn find_documents_after(
+        &self,
+        source_ids: &[String],
+        after_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<SnapshotDocument>, DatabaseError> {
+        let documents = sqlx::query_as::<_, SnapshotDocument>(
+            r#"
+            SELECT id, source_id, external_id, title, content_id, content_type, file_size,
+                   file_extension, url, metadata,
+                   COALESCE(source_permissions, permissions) AS permissions,
+                   permission_overrides, attributes, content,
+                   created_at, updated_at, last_indexed_at
+            FROM documents
+            WHERE source_id = ANY($1) AND ($2::text IS NULL OR id > $2)
+            ORDER BY id
+            LIMIT $3
+            "#,
+        )
+        .bind(source_ids)
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(documents)
+    }
+
+    pub async fn find_embeddings(
+        &self,
+        document_ids: &[String],
+    ) -> Result<Vec<Embedding>, DatabaseError> {
+        let embeddings = sqlx::query_as::<_, Embedding>(
+            r#"
+            SELECT id, document_id, chunk_index, chunk_start_offset, chunk_end_offset,
+                   content_sha256, embedding, model_name, dimensions, created_at
+            FROM embeddings
+            WHERE document_id = ANY($1)
+            ORDER BY document_id, model_name, chunk_index
+            "#,
+        )
+        .bind(document_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(embeddings)
+    }
+
+    /// Create a source from a snapshot unless one with its id already exists. Restored
+    /// sources are paused, since snapshots carry no credentials. Returns whether the source
+    /// was created.
+    pub async fn restore_source(
+        &self,
+        source: &Source,
+        created_by: &str,
+    ) -> Result<bool, DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO sources (id, name, source_type, config, is_active, user_filter_mode,
+                                 user_whitelist, user_blacklist, connector_state,
+                                 sync_interval_seconds, created_by)
+            VALUES ($1, $2, $3, $4, false, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (id) DO NOTHING
+            "#,
+        )
+        .bind(&source.id)
+        .bind(&source.name)
+        .bind(source.source_type)
+        .bind(&source.config)
+        .bind(source.user_filter_mode)
+        .bind(&source.user_whitelist)
+        .bind(&source.user_blacklist)
+        .bind(&source.connector_state)
+        .bind(source.sync_interval_seconds)
+        .bind(created_by)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Insert or update a document from a snapshot, pointing it at `content_id` in this
+    /// deployment's storage. Returns the id of the document in the index, which differs from
+    /// the snapshot's when the document was already indexed under another id.
+    pub async fn restore_document(
+        &self,
+        document: &SnapshotDocument,
+        content_id: Option<&str>,
+        embedded: bool,
+    ) -> Result<String, DatabaseError> {
+        let embedding_status = if embedded { "completed" } else { "pending" };
+        let id: String = sqlx::query_scalar(
+            r#"
+            INSERT INTO documents (id, source_id, external_id, title, content_id, content_type,
+                                   file_size, file_extension, url, metadata, permissions,
+                                   attributes, content, embedding_status, created_at,
+                                   updated_at, last_indexed_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+            ON CONFLICT (source_id, external_id) DO UPDATE SET
+                title = EXCLUDED.title,
+                content_id = EXCLUDED.content_id,
+                content_type = EXCLUDED.content_type,
+                file_size = EXCLUDED.file_size,
+                file_extension = EXCLUDED.file_extension,
+                url = EXCLUDED.url,
+                metadata = EXCLUDED.metadata,
+                permissions = EXCLUDED.permissions,
+                attributes = EXCLUDED.attributes,
+                content = EXCLUDED.content,
+                embedding_status = EXCLUDED.embedding_status,
+                last_indexed_at = EXCLUDED.last_indexed_at
+            RETURNING id
+            "#,
+        )
+        .bind(&document.id)
+        .bind(&document.source_id)
+        .bind(&document.external_id)
+        .bind(&document.title)
+        .bind(content_id)
+        .bind(&document.content_type)
+        .bind(document.file_size)
+        .bind(&document.file_extension)
+        .bind(&document.url)
+        .bind(&document.metadata)
+        .bind(&document.permissions)
+        .bind(&document.attributes)
+        .bind(&document.content)
+        .bind(embedding_status)
+        .bind(document.created_at)
+        .bind(document.updated_at)
+        .bind(document.last_indexed_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        // Overrides are written on their own, as the permission triggers expect
+        sqlx::query(
+            r#"
+            UPDATE documents SET permission_overrides = $2
+            WHERE id = $1 AND permission_overrides IS DISTINCT FROM $2
+            "#,
+        )
+        .bind(&id)
+        .bind(&document.permission_overrides)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(id)
+    }
+}
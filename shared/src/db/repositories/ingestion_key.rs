@@ -0,0 +1,99 @@
+use crate::db::error::DatabaseError;
+use sqlx::PgPool;
+use std::collections::HashSet;
+
+/// The key of one delivery of a document's content, computed from the columns of a
+/// `deliveries` row set, its content blob `cb` and the sync run id bound as `$1`. Content
+/// without a hash is keyed by its id.
+const INGESTION_KEY: &str = "encode(sha256(convert_to(\
+     deliveries.source_id || ':' || deliveries.external_id || ':' \
+     || COALESCE(cb.sha256_hash, deliveries.content_id) || ':' || $1, 'UTF8')), 'hex')";
+
+/// A document's content as delivered by an event.
+#[derive(Debug, Clone)]
+pub struct DocumentDelivery {
+    pub source_id: String,
+    pub external_id: String,
+    pub content_id: String,
+}
+
+/// Idempotency keys of document ingestion, so that events delivered again do not rewrite
+/// their documents.
+pub struct IngestionKeyRepository {
+    pool: PgPool,
+}
+
+impl IngestionKeyRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// The (source id, external id) of the `deliveries` made in `sync_run_id` whose document
+    /// was last ingested from the same delivery.
+    pub async fn find_ingested(
+        &self,
+        sync_run_id: &str,
+        deliveries: &[DocumentDelivery],
+    ) -> Result<HashSet<(String, String)>, DatabaseError> {
+        let (source_ids, external_ids, content_ids) = columns(deliveries);
+        let ingested = sqlx::query_as::<_, (String, String)>(&format!(
+            r#"
+            SELECT d.source_id, d.external_id
+            FROM UNNEST($2::text[], $3::text[], $4::text[])
+                AS deliveries(source_id, external_id, content_id)
+            JOIN documents d
+                ON d.source_id = deliveries.source_id AND d.external_id = deliveries.external_id
+            JOIN document_ingestion_keys k ON k.document_id = d.id
+            LEFT JOIN content_blobs cb ON cb.id = deliveries.content_id
+            WHERE k.ingestion_key = {}
+            "#,
+            INGESTION_KEY
+        ))
+        .bind(sync_run_id)
+        .bind(&source_ids)
+        .bind(&external_ids)
+        .bind(&content_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ingested.into_iter().collect())
+    }
+
+    /// Record that the documents of `deliveries` were ingested from them in `sync_run_id`.
+    pub async fn record(
+        &self,
+        sync_run_id: &str,
+        deliveries: &[DocumentDelivery],
+    ) -> Result<(), DatabaseError> {
+        let (source_ids, external_ids, content_ids) = columns(deliveries);
+        sqlx::query(&format!(
+            r#"
+            INSERT INTO document_ingestion_keys (document_id, ingestion_key)
+            SELECT d.id, {}
+            FROM UNNEST($2::text[], $3::text[], $4::text[])
+                AS deliveries(source_id, external_id, content_id)
+            JOIN documents d
+                ON d.source_id = deliveries.source_id AND d.external_id = deliveries.external_id
+            LEFT JOIN content_blobs cb ON cb.id = deliveries.content_id
+            ON CONFLICT (document_id) DO UPDATE
+            SET ingestion_key = EXCLUDED.ingestion_key, ingested_at = NOW()
+            "#,
+            INGESTION_KEY
+        ))
+        .bind(sync_run_id)
+        .bind(&source_ids)
+        .bind(&external_ids)
+        .bind(&content_ids)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+fn columns(deliveries: &[DocumentDelivery]) -> (Vec<&str>, Vec<&str>, Vec<&str>) {
+    let source_ids = deliveries.iter().map(|d| d.source_id.as_str()).collect();
+    let external_ids = deliveries.iter().map(|d| d.external_id.as_str()).collect();
+    let content_ids = deliveries.iter().map(|d| d.content_id.as_str()).collect();
+    (source_ids, external_ids, content_ids)
+}
@@ -0,0 +1,159 @@
+use crate::{db::error::DatabaseError, utils::generate_ulid};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::{FromRow, PgPool};
+use time::OffsetDateTime;
+
+/// Document metadata as exported for analytics. Content and permissions are never exported.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DocumentExportRow {
+    pub id: String,
+    pub source_id: String,
+    pub source_type: String,
+    pub external_id: String,
+    pub title: String,
+    pub url: Option<String>,
+    pub content_type: Option<String>,
+    pub file_size: Option<i64>,
+    pub file_extension: Option<String>,
+    pub metadata: JsonValue,
+    pub attributes: JsonValue,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub updated_at: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub last_indexed_at: OffsetDateTime,
+}
+
+/// Position in `(updated_at, id)` order up to which documents have been exported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportWatermark {
+    pub updated_at: OffsetDateTime,
+    pub document_id: String,
+}
+
+/// A file written by a metadata export.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MetadataExport {
+    pub id: String,
+    pub format: String,
+    pub content_id: String,
+    pub row_count: i64,
+    #[serde(with = "time::serde::iso8601")]
+    pub watermark_updated_at: OffsetDateTime,
+    pub watermark_document_id: String,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+}
+
+pub struct MetadataExportRepository {
+    pool: PgPool,
+}
+
+impl MetadataExportRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Watermark of the latest export in `format`, `None` if nothing has been exported yet.
+    pub async fn latest_watermark(
+        &self,
+        format: &str,
+    ) -> Result<Option<ExportWatermark>, DatabaseError> {
+        let watermark = sqlx::query_as::<_, (OffsetDateTime, String)>(
+            r#"
+            SELECT watermark_updated_at, watermark_document_id
+            FROM metadata_exports
+            WHERE format = $1
+            ORDER BY watermark_updated_at DESC, watermark_document_id DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(format)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(watermark.map(|(updated_at, document_id)| ExportWatermark {
+            updated_at,
+            document_id,
+        }))
+    }
+
+    /// The next `limit` documents after `after` in `(updated_at, id)` order, skipping any
+    /// updated at or after `until`.
+    pub async fn find_documents_after(
+        &self,
+        after: Option<&ExportWatermark>,
+        until: OffsetDateTime,
+        limit: i64,
+    ) -> Result<Vec<DocumentExportRow>, DatabaseError> {
+        let rows = sqlx::query_as::<_, DocumentExportRow>(
+            r#"
+            SELECT d.id, d.source_id, s.source_type, d.external_id, d.title, d.url,
+                   d.content_type, d.file_size, d.file_extension, d.metadata, d.attributes,
+                   d.created_at, d.updated_at, d.last_indexed_at
+            FROM documents d
+            JOIN sources s ON s.id = d.source_id
+            WHERE ($1::timestamptz IS NULL OR (d.updated_at, d.id) > ($1, $2))
+              AND d.updated_at < $3
+            ORDER BY d.updated_at, d.id
+            LIMIT $4
+            "#,
+        )
+        .bind(after.map(|w| w.updated_at))
+        .bind(after.map(|w| w.document_id.as_str()))
+        .bind(until)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    pub async fn record_export(
+        &self,
+        format: &str,
+        content_id: &str,
+        row_count: i64,
+        watermark: &ExportWatermark,
+    ) -> Result<MetadataExport, DatabaseError> {
+        let export = sqlx::query_as::<_, MetadataExport>(
+            r#"
+            INSERT INTO metadata_exports (id, format, content_id, row_count,
+                                          watermark_updated_at, watermark_document_id)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, format, content_id, row_count, watermark_updated_at,
+                      watermark_document_id, created_at
+            "#,
+        )
+        .bind(generate_ulid())
+        .bind(format)
+        .bind(content_id)
+        .bind(row_count)
+        .bind(watermark.updated_at)
+        .bind(&watermark.document_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(export)
+    }
+
+    /// Most recent exports across formats, newest first.
+    pub async fn list_recent(&self, limit: i64) -> Result<Vec<MetadataExport>, DatabaseError> {
+        let exports = sqlx::query_as::<_, MetadataExport>(
+            r#"
+            SELECT id, format, content_id, row_count, watermark_updated_at,
+                   watermark_document_id, created_at
+            FROM metadata_exports
+            ORDER BY created_at DESC
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(exports)
+    }
+}
@@ -1,15 +1,95 @@
+pub mod acronym;
+pub mod collection;
+pub mod configuration;
 pub mod content_blob;
+pub mod data_deletion;
+pub mod digest;
 pub mod document;
+pub mod document_anchor;
+pub mod document_flag;
+pub mod document_interaction;
+pub mod document_link;
+pub mod document_preview;
+pub mod document_relationship;
+pub mod duplicate_document;
 pub mod embedding;
+pub mod feature_flag;
+pub mod group;
+pub mod index_integrity;
+pub mod index_snapshot;
+pub mod ingestion_key;
+pub mod metadata_export;
+pub mod person;
+pub mod query_log;
+pub mod recommendation;
+pub mod search_promotion;
 pub mod service_credentials;
+pub mod shadow_comparison;
 pub mod source;
+pub mod source_lease;
+pub mod stale_document;
 pub mod sync_run;
 pub mod user;
+pub mod webhook;
 
+pub use acronym::{
+    Acronym, AcronymInput, AcronymRepository, AcronymSource, AcronymStatus, MinedAcronym,
+};
+pub use collection::{Collection, CollectionInput, CollectionRepository, CollectionShare};
+pub use configuration::ConfigurationRepository;
 pub use content_blob::{ContentBlobRepository, OrphanStats};
-pub use document::{DocumentRepository, TitleEntry};
+pub use data_deletion::{DataDeletionRepository, DataDeletionRequest, DataDeletionStatus};
+pub use digest::{DigestRepository, DueDigest};
+pub use document::{
+    attribute_containment_condition, AttributeCardinality, DailyIndexGrowth,
+    DocumentPermissionState, DocumentRepository, SourceIndexingLag, TitleEntry,
+};
+pub use document_anchor::{AnchorKind, ChunkAnchor, DocumentAnchor, DocumentAnchorRepository};
+pub use document_flag::{DocumentFlag, DocumentFlagRepository, FlagReason, FlagStatus};
+pub use document_interaction::{DocumentActivity, DocumentInteractionRepository, InteractionKind};
+pub use document_link::{DocumentLinkRepository, LinkKind, LinkReference, LinkedDocument};
+pub use document_preview::{DocumentPreviewRepository, StoredPreview};
+pub use document_relationship::{DocumentRelationship, DocumentRelationshipRepository};
+pub use duplicate_document::{DuplicateDocument, DuplicateDocumentRepository, SimilarPair};
 pub use embedding::EmbeddingRepository;
+pub use feature_flag::{
+    FeatureFlag, FeatureFlagInput, FeatureFlagRepository, FeatureFlagVariantStats,
+};
+pub use group::{Group, GroupMember, GroupRepository};
+pub use index_integrity::IndexIntegrityRepository;
+pub use index_snapshot::{
+    IndexSnapshot, IndexSnapshotKind, IndexSnapshotRepository, IndexSnapshotStatus, SnapshotCounts,
+    SnapshotDocument,
+};
+pub use ingestion_key::{DocumentDelivery, IngestionKeyRepository};
+pub use metadata_export::{
+    DocumentExportRow, ExportWatermark, MetadataExport, MetadataExportRepository,
+};
+pub use person::{
+    Collaborator, PeopleRebuildStats, Person, PersonDocument, PersonRepository, PEOPLE_SOURCE_NAME,
+};
+pub use query_log::{
+    DailySearchUsage, FrequentSearch, QueryLogRepository, SearchQueryLog, SourceQueryCount,
+};
+pub use recommendation::{
+    Recommendation, RecommendationCandidate, RecommendationReason, RecommendationRepository,
+};
+pub use search_promotion::{
+    PromotionAction, PromotionMatchType, SearchPromotion, SearchPromotionInput,
+    SearchPromotionRepository,
+};
 pub use service_credentials::ServiceCredentialsRepo;
+pub use shadow_comparison::{
+    ShadowComparison, ShadowComparisonRepository, ShadowComparisonSummary,
+};
 pub use source::SourceRepository;
-pub use sync_run::SyncRunRepository;
+pub use source_lease::SourceLeaseRepository;
+pub use stale_document::{
+    StaleContentOwner, StaleDocument, StaleDocumentRepository, StaleRebuildStats,
+    StaleSourceSummary,
+};
+pub use sync_run::{DailySyncStats, FailedSyncRun, SyncRunDailySummary, SyncRunRepository};
 pub use user::UserRepository;
+pub use webhook::{
+    Webhook, WebhookDelivery, WebhookDeliveryStatus, WebhookEvent, WebhookRepository,
+};
@@ -0,0 +1,301 @@
+use crate::models::SourceType;
+use crate::{db::error::DatabaseError, utils::generate_ulid};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use time::OffsetDateTime;
+
+/// Name of the system source people profiles are indexed under.
+pub const PEOPLE_SOURCE_NAME: &str = "People Directory";
+
+/// A person seen in connector data, with how much they did across the index.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Person {
+    pub id: String,
+    pub email: String,
+    pub name: Option<String>,
+    pub authored_count: i32,
+    pub meeting_count: i32,
+    pub email_count: i32,
+    #[serde(with = "time::serde::iso8601::option")]
+    pub last_active_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::iso8601")]
+    pub updated_at: OffsetDateTime,
+}
+
+/// A document a person was active on, with what they did on it.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PersonDocument {
+    pub document_id: String,
+    pub title: String,
+    pub roles: Vec<String>,
+    #[serde(with = "time::serde::iso8601")]
+    pub last_active_at: OffsetDateTime,
+}
+
+/// Another person active on the same documents.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Collaborator {
+    pub person_id: String,
+    pub email: String,
+    pub name: Option<String>,
+    pub shared_documents: i64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PeopleRebuildStats {
+    pub people: u64,
+    pub activities: u64,
+}
+
+const PERSON_COLUMNS: &str = r#"
+    p.id, p.email, p.name, p.authored_count, p.meeting_count, p.email_count,
+    p.last_active_at, p.updated_at
+"#;
+
+pub struct PersonRepository {
+    pool: PgPool,
+}
+
+impl PersonRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Rebuild the activity of every person from the documents in the index. Authors come
+    /// from document metadata; everyone permitted on a document from `meeting_source_types`
+    /// attended the meeting, and everyone permitted on one from `email_source_types` took
+    /// part in the thread. People without any activity left are removed.
+    pub async fn rebuild(
+        &self,
+        meeting_source_types: &[SourceType],
+        email_source_types: &[SourceType],
+    ) -> Result<PeopleRebuildStats, DatabaseError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            CREATE TEMPORARY TABLE rebuilt_activity ON COMMIT DROP AS
+            SELECT lower(trim(d.metadata->>'author')) AS email, d.id AS document_id,
+                   'author' AS role, d.updated_at AS occurred_at
+            FROM documents d
+            JOIN sources s ON s.id = d.source_id
+            WHERE NOT s.is_deleted
+              AND s.source_type <> 'people'
+              AND d.metadata->>'author' LIKE '%_@_%'
+            UNION
+            SELECT lower(trim(u.email)), d.id,
+                   CASE WHEN s.source_type = ANY($1) THEN 'attendee' ELSE 'correspondent' END,
+                   d.updated_at
+            FROM documents d
+            JOIN sources s ON s.id = d.source_id
+            CROSS JOIN LATERAL jsonb_array_elements_text(
+                CASE WHEN jsonb_typeof(d.permissions->'users') = 'array'
+                     THEN d.permissions->'users' ELSE '[]'::jsonb END
+            ) AS u(email)
+            WHERE NOT s.is_deleted
+              AND (s.source_type = ANY($1) OR s.source_type = ANY($2))
+              AND u.email LIKE '%_@_%'
+            "#,
+        )
+        .bind(meeting_source_types)
+        .bind(email_source_types)
+        .execute(&mut *tx)
+        .await?;
+
+        let new_emails = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT DISTINCT a.email
+            FROM rebuilt_activity a
+            WHERE NOT EXISTS (SELECT 1 FROM people p WHERE p.email = a.email)
+            "#,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        let new_ids: Vec<String> = new_emails.iter().map(|_| generate_ulid()).collect();
+        sqlx::query(
+            r#"
+            INSERT INTO people (id, email)
+            SELECT * FROM UNNEST($1::text[], $2::text[])
+            ON CONFLICT (email) DO NOTHING
+            "#,
+        )
+        .bind(&new_ids)
+        .bind(&new_emails)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("DELETE FROM person_activity")
+            .execute(&mut *tx)
+            .await?;
+        let activities = sqlx::query(
+            r#"
+            INSERT INTO person_activity (person_id, document_id, role, occurred_at)
+            SELECT p.id, a.document_id, a.role, a.occurred_at
+            FROM rebuilt_activity a
+            JOIN people p ON p.email = a.email
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        sqlx::query(
+            r#"
+            DELETE FROM people p
+            WHERE NOT EXISTS (SELECT 1 FROM person_activity a WHERE a.person_id = p.id)
+            "#,
+        )
+        .execute(&mut *tx)
+        .await?;
+        let people = sqlx::query(
+            r#"
+            UPDATE people p
+            SET name = COALESCE(
+                    (SELECT u.full_name FROM users u WHERE lower(u.email) = p.email LIMIT 1),
+                    p.name
+                ),
+                authored_count = c.authored,
+                meeting_count = c.meetings,
+                email_count = c.emails,
+                last_active_at = c.last_active_at,
+                updated_at = NOW()
+            FROM (
+                SELECT person_id,
+                       COUNT(*) FILTER (WHERE role = 'author') AS authored,
+                       COUNT(*) FILTER (WHERE role = 'attendee') AS meetings,
+                       COUNT(*) FILTER (WHERE role = 'correspondent') AS emails,
+                       MAX(occurred_at) AS last_active_at
+                FROM person_activity
+                GROUP BY person_id
+            ) c
+            WHERE c.person_id = p.id
+            "#,
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        tx.commit().await?;
+
+        Ok(PeopleRebuildStats { people, activities })
+    }
+
+    pub async fn find_all(&self) -> Result<Vec<Person>, DatabaseError> {
+        let people = sqlx::query_as::<_, Person>(&format!(
+            "SELECT {} FROM people p ORDER BY p.email",
+            PERSON_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(people)
+    }
+
+    pub async fn find_by_email(&self, email: &str) -> Result<Option<Person>, DatabaseError> {
+        let person = sqlx::query_as::<_, Person>(&format!(
+            "SELECT {} FROM people p WHERE p.email = lower($1)",
+            PERSON_COLUMNS
+        ))
+        .bind(email)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(person)
+    }
+
+    /// The documents a person was most active on: those they had most roles on, most recent
+    /// first.
+    pub async fn find_top_documents(
+        &self,
+        person_id: &str,
+        limit: i64,
+    ) -> Result<Vec<PersonDocument>, DatabaseError> {
+        let documents = sqlx::query_as::<_, PersonDocument>(
+            r#"
+            SELECT a.document_id, d.title,
+                   array_agg(a.role::text ORDER BY a.role) AS roles,
+                   MAX(a.occurred_at) AS last_active_at
+            FROM person_activity a
+            JOIN documents d ON d.id = a.document_id
+            WHERE a.person_id = $1
+            GROUP BY a.document_id, d.title
+            ORDER BY COUNT(*) DESC, MAX(a.occurred_at) DESC, a.document_id
+            LIMIT $2
+            "#,
+        )
+        .bind(person_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(documents)
+    }
+
+    /// The people most often active on the same documents as a person, counting only the
+    /// given documents so callers can restrict it to those a user may see.
+    pub async fn find_collaborators(
+        &self,
+        person_id: &str,
+        document_ids: &[String],
+        limit: i64,
+    ) -> Result<Vec<Collaborator>, DatabaseError> {
+        if document_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let collaborators = sqlx::query_as::<_, Collaborator>(
+            r#"
+            SELECT p.id AS person_id, p.email, p.name,
+                   COUNT(DISTINCT other.document_id) AS shared_documents
+            FROM person_activity own
+            JOIN person_activity other
+              ON other.document_id = own.document_id AND other.person_id <> own.person_id
+            JOIN people p ON p.id = other.person_id
+            WHERE own.person_id = $1 AND own.document_id = ANY($2)
+            GROUP BY p.id, p.email, p.name
+            ORDER BY shared_documents DESC, p.email
+            LIMIT $3
+            "#,
+        )
+        .bind(person_id)
+        .bind(document_ids)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(collaborators)
+    }
+
+    /// The ID of the system source profiles are indexed under, created on first use with
+    /// the earliest admin as its creator. It has no sync interval, so it is never scheduled
+    /// for connector syncs.
+    pub async fn ensure_source(&self) -> Result<String, DatabaseError> {
+        let existing = sqlx::query_scalar::<_, String>(
+            "SELECT id FROM sources WHERE source_type = 'people' AND NOT is_deleted LIMIT 1",
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        if let Some(source_id) = existing {
+            return Ok(source_id);
+        }
+
+        let source_id = sqlx::query_scalar::<_, String>(
+            r#"
+            INSERT INTO sources (id, name, source_type, config, is_active, created_by,
+                                 sync_interval_seconds)
+            SELECT $1, $2, 'people', '{}'::jsonb, true, u.id, NULL
+            FROM users u
+            ORDER BY u.role = 'admin' DESC, u.created_at
+            LIMIT 1
+            RETURNING id
+            "#,
+        )
+        .bind(generate_ulid())
+        .bind(PEOPLE_SOURCE_NAME)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        source_id.ok_or_else(|| {
+            DatabaseError::ConstraintViolation(
+                "A user must exist before the people directory can be built".to_string(),
+            )
+        })
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{db::error::DatabaseError, utils::generate_ulid};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::{FromRow, PgPool};
+use time::OffsetDateTime;
+
+/// A single searcher query log record. Query text is never stored, only its hash.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SearchQueryLog {
+    pub id: String,
+    pub query_hash: String,
+    pub search_mode: String,
+    pub filters: JsonValue,
+    pub user_id: Option<String>,
+    pub result_count: i32,
+    pub total_ms: i32,
+    pub cache_ms: i32,
+    pub fts_ms: Option<i32>,
+    pub vector_ms: Option<i32>,
+    pub rerank_ms: Option<i32>,
+    pub cache_hit: bool,
+    pub is_slow: bool,
+    /// Fraction of searches like this one that are logged, 1.0 for slow searches as they
+    /// are always logged. Counts weight each record by its inverse.
+    pub sample_rate: f32,
+    /// Feature flags evaluated for the search, by name.
+    pub feature_flags: JsonValue,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+}
+
+/// Search activity for a single day.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DailySearchUsage {
+    pub day: String,
+    pub queries: i64,
+    pub active_searchers: i64,
+    pub zero_result_queries: i64,
+}
+
+/// Number of logged queries restricted to a source type. Unfiltered queries are
+/// reported under `"all"`.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SourceQueryCount {
+    pub source_type: String,
+    pub queries: i64,
+}
+
+/// A search run the same way, by the same user, many times.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct FrequentSearch {
+    pub query_hash: String,
+    pub search_mode: String,
+    pub filters: JsonValue,
+    pub user_id: Option<String>,
+    pub searches: i64,
+}
+
+pub struct QueryLogRepository {
+    pool: PgPool,
+}
+
+impl QueryLogRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Insert a log record, assigning its id and creation time.
+    pub async fn create(&self, mut log: SearchQueryLog) -> Result<SearchQueryLog, DatabaseError> {
+        log.id = generate_ulid();
+        log.created_at = OffsetDateTime::now_utc();
+
+        sqlx::query(
+            r#"
+            INSERT INTO search_query_logs (
+                id, query_hash, search_mode, filters, user_id, result_count,
+                total_ms, cache_ms, fts_ms, vector_ms, rerank_ms, cache_hit, is_slow, sample_rate,
+                feature_flags, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+            "#,
+        )
+        .bind(&log.id)
+        .bind(&log.query_hash)
+        .bind(&log.search_mode)
+        .bind(&log.filters)
+        .bind(&log.user_id)
+        .bind(log.result_count)
+        .bind(log.total_ms)
+        .bind(log.cache_ms)
+        .bind(log.fts_ms)
+        .bind(log.vector_ms)
+        .bind(log.rerank_ms)
+        .bind(log.cache_hit)
+        .bind(log.is_slow)
+        .bind(log.sample_rate)
+        .bind(&log.feature_flags)
+        .bind(log.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(log)
+    }
+
+    /// Most recent slow queries, newest first.
+    pub async fn find_slow(&self, limit: i64) -> Result<Vec<SearchQueryLog>, DatabaseError> {
+        let logs = sqlx::query_as::<_, SearchQueryLog>(
+            r#"
+            SELECT id, query_hash, search_mode, filters, user_id, result_count,
+                   total_ms, cache_ms, fts_ms, vector_ms, rerank_ms, cache_hit, is_slow,
+                   sample_rate, feature_flags, created_at
+            FROM search_query_logs
+            WHERE is_slow = TRUE
+            ORDER BY created_at DESC
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(logs)
+    }
+
+    /// Per-day query volume, distinct searchers and zero-result queries since `since`.
+    /// Query counts are estimated from the sampled logs, distinct searchers are those with a
+    /// logged query. Days without any logged query are omitted.
+    pub async fn daily_usage(
+        &self,
+        since: OffsetDateTime,
+    ) -> Result<Vec<DailySearchUsage>, DatabaseError> {
+        let usage = sqlx::query_as::<_, DailySearchUsage>(
+            r#"
+            SELECT to_char(date_trunc('day', created_at), 'YYYY-MM-DD') AS day,
+                   ROUND(SUM(1 / sample_rate))::BIGINT AS queries,
+                   COUNT(DISTINCT user_id) AS active_searchers,
+                   COALESCE(ROUND(SUM(1 / sample_rate) FILTER (WHERE result_count = 0)), 0)::BIGINT
+                       AS zero_result_queries
+            FROM search_query_logs
+            WHERE created_at >= $1
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(usage)
+    }
+
+    /// The `limit` searches logged most often since `since`, most frequent first. Searches
+    /// reading a single document are left out.
+    pub async fn most_frequent(
+        &self,
+        since: OffsetDateTime,
+        limit: i64,
+    ) -> Result<Vec<FrequentSearch>, DatabaseError> {
+        let searches = sqlx::query_as::<_, FrequentSearch>(
+            r#"
+            SELECT query_hash, search_mode, filters, user_id,
+                   ROUND(SUM(1 / sample_rate))::BIGINT AS searches
+            FROM search_query_logs
+            WHERE created_at >= $1 AND filters->>'document_id' IS NULL
+            GROUP BY query_hash, search_mode, filters, user_id
+            ORDER BY searches DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(searches)
+    }
+
+    pub async fn queries_per_source_type(
+        &self,
+        since: OffsetDateTime,
+    ) -> Result<Vec<SourceQueryCount>, DatabaseError> {
+        let counts = sqlx::query_as::<_, SourceQueryCount>(
+            r#"
+            SELECT COALESCE(st.source_type, 'all') AS source_type,
+                   ROUND(SUM(1 / l.sample_rate))::BIGINT AS queries
+            FROM search_query_logs l
+            LEFT JOIN LATERAL jsonb_array_elements_text(
+                CASE WHEN jsonb_typeof(l.filters->'source_types') = 'array'
+                     THEN l.filters->'source_types'
+                     ELSE '[]'::jsonb END
+            ) AS st(source_type) ON true
+            WHERE l.created_at >= $1
+            GROUP BY 1
+            ORDER BY queries DESC
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(counts)
+    }
+
+    /// Delete every query logged by the user with the given email.
+    pub async fn delete_for_user_email(&self, email: &str) -> Result<u64, DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM search_query_logs
+            WHERE user_id IN (SELECT id FROM users WHERE lower(email) = lower($1))
+            "#,
+        )
+        .bind(email)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,297 @@
+use crate::db::error::DatabaseError;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use std::collections::HashSet;
+use time::OffsetDateTime;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Hash)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum RecommendationReason {
+    /// Recently edited by someone the user often works with.
+    CollaboratorEdit,
+    /// Linked from, or written by an attendee after, a meeting the user attended.
+    MeetingFollowUp,
+    /// Matches one of the user's recent searches.
+    RecentSearch,
+    /// Linked to or from a document the user recently opened or wrote.
+    Related,
+}
+
+impl RecommendationReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecommendationReason::CollaboratorEdit => "collaborator_edit",
+            RecommendationReason::MeetingFollowUp => "meeting_follow_up",
+            RecommendationReason::RecentSearch => "recent_search",
+            RecommendationReason::Related => "related",
+        }
+    }
+}
+
+/// A document a signal suggests for a user, with how strongly it does.
+#[derive(Debug, Clone, FromRow)]
+pub struct RecommendationCandidate {
+    pub document_id: String,
+    pub strength: f64,
+    pub updated_at: OffsetDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Recommendation {
+    pub document_id: String,
+    pub reason: RecommendationReason,
+    pub score: f32,
+    #[serde(with = "time::serde::iso8601")]
+    pub computed_at: OffsetDateTime,
+}
+
+/// Most close collaborators whose edits are recommended.
+const MAX_COLLABORATORS: i64 = 20;
+/// Most documents recommended per recent search.
+const MAX_SEARCH_MATCHES: i64 = 20;
+
+pub struct RecommendationRepository {
+    pool: PgPool,
+}
+
+impl RecommendationRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Documents updated since `since` that the user's closest collaborators wrote. Strength
+    /// is the number of documents the user shares with the closest of their authors.
+    pub async fn find_collaborator_edits(
+        &self,
+        user_email: &str,
+        since: OffsetDateTime,
+    ) -> Result<Vec<RecommendationCandidate>, DatabaseError> {
+        let candidates = sqlx::query_as::<_, RecommendationCandidate>(
+            r#"
+            WITH collaborators AS (
+                SELECT other.person_id, COUNT(DISTINCT other.document_id)::float8 AS shared
+                FROM people me
+                JOIN person_activity own ON own.person_id = me.id
+                JOIN person_activity other
+                  ON other.document_id = own.document_id AND other.person_id <> me.id
+                WHERE me.email = lower($1)
+                GROUP BY other.person_id
+                ORDER BY shared DESC, other.person_id
+                LIMIT $3
+            )
+            SELECT d.id AS document_id, MAX(c.shared) AS strength, d.updated_at
+            FROM collaborators c
+            JOIN person_activity a ON a.person_id = c.person_id AND a.role = 'author'
+            JOIN documents d ON d.id = a.document_id
+            WHERE d.updated_at >= $2
+            GROUP BY d.id, d.updated_at
+            "#,
+        )
+        .bind(user_email)
+        .bind(since)
+        .bind(MAX_COLLABORATORS)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(candidates)
+    }
+
+    /// Follow-ups to meetings the user attended since `since`: documents the meeting links
+    /// to, and documents other attendees started within `follow_up_days` after it.
+    pub async fn find_meeting_follow_ups(
+        &self,
+        user_email: &str,
+        since: OffsetDateTime,
+        follow_up_days: i32,
+    ) -> Result<Vec<RecommendationCandidate>, DatabaseError> {
+        let candidates = sqlx::query_as::<_, RecommendationCandidate>(
+            r#"
+            WITH meetings AS (
+                SELECT a.document_id, a.occurred_at
+                FROM people me
+                JOIN person_activity a ON a.person_id = me.id AND a.role = 'attendee'
+                WHERE me.email = lower($1) AND a.occurred_at >= $2
+            ),
+            follow_ups AS (
+                SELECT l.target_document_id AS document_id
+                FROM meetings m
+                JOIN document_links l ON l.source_document_id = m.document_id
+                WHERE l.target_document_id IS NOT NULL
+                UNION ALL
+                SELECT written.document_id
+                FROM meetings m
+                JOIN person_activity attendee
+                  ON attendee.document_id = m.document_id AND attendee.role = 'attendee'
+                JOIN person_activity written
+                  ON written.person_id = attendee.person_id AND written.role = 'author'
+                JOIN documents d ON d.id = written.document_id
+                WHERE written.document_id <> m.document_id
+                  AND d.created_at >= m.occurred_at
+                  AND d.created_at < m.occurred_at + make_interval(days => $3)
+            )
+            SELECT d.id AS document_id, COUNT(*)::float8 AS strength, d.updated_at
+            FROM follow_ups f
+            JOIN documents d ON d.id = f.document_id
+            GROUP BY d.id, d.updated_at
+            "#,
+        )
+        .bind(user_email)
+        .bind(since)
+        .bind(follow_up_days)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(candidates)
+    }
+
+    /// Documents updated since `since` whose title matches a search. Strength is how well it
+    /// matches.
+    pub async fn find_search_matches(
+        &self,
+        query: &str,
+        since: OffsetDateTime,
+    ) -> Result<Vec<RecommendationCandidate>, DatabaseError> {
+        let candidates = sqlx::query_as::<_, RecommendationCandidate>(
+            r#"
+            SELECT d.id AS document_id,
+                   ts_rank(to_tsvector('simple', d.title), q)::float8 AS strength,
+                   d.updated_at
+            FROM documents d, plainto_tsquery('simple', $1) q
+            WHERE d.updated_at >= $2 AND to_tsvector('simple', d.title) @@ q
+            ORDER BY strength DESC, d.id
+            LIMIT $3
+            "#,
+        )
+        .bind(query)
+        .bind(since)
+        .bind(MAX_SEARCH_MATCHES)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(candidates)
+    }
+
+    /// Documents linked to or from the ones the user opened or wrote since `since`. Strength
+    /// is the number of those documents each is linked with.
+    pub async fn find_related(
+        &self,
+        user_id: &str,
+        user_email: &str,
+        since: OffsetDateTime,
+    ) -> Result<Vec<RecommendationCandidate>, DatabaseError> {
+        let candidates = sqlx::query_as::<_, RecommendationCandidate>(
+            r#"
+            WITH worked_on AS (
+                SELECT document_id
+                FROM document_interactions
+                WHERE user_id = $1 AND created_at >= $3
+                UNION
+                SELECT a.document_id
+                FROM people me
+                JOIN person_activity a ON a.person_id = me.id AND a.role = 'author'
+                WHERE me.email = lower($2) AND a.occurred_at >= $3
+            ),
+            related AS (
+                SELECT l.target_document_id AS document_id
+                FROM worked_on w
+                JOIN document_links l ON l.source_document_id = w.document_id
+                WHERE l.target_document_id IS NOT NULL
+                UNION ALL
+                SELECT l.source_document_id
+                FROM worked_on w
+                JOIN document_links l ON l.target_document_id = w.document_id
+            )
+            SELECT d.id AS document_id, COUNT(*)::float8 AS strength, d.updated_at
+            FROM related r
+            JOIN documents d ON d.id = r.document_id
+            GROUP BY d.id, d.updated_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(user_email)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(candidates)
+    }
+
+    /// Documents the user already knows: those they wrote, or opened since `since`.
+    pub async fn find_known(
+        &self,
+        user_id: &str,
+        user_email: &str,
+        since: OffsetDateTime,
+    ) -> Result<HashSet<String>, DatabaseError> {
+        let ids = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT document_id
+            FROM document_interactions
+            WHERE user_id = $1 AND created_at >= $3
+            UNION
+            SELECT a.document_id
+            FROM people me
+            JOIN person_activity a ON a.person_id = me.id AND a.role = 'author'
+            WHERE me.email = lower($2)
+            "#,
+        )
+        .bind(user_id)
+        .bind(user_email)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids.into_iter().collect())
+    }
+
+    /// Replace the user's cached recommendations.
+    pub async fn replace_for_user(
+        &self,
+        user_id: &str,
+        recommendations: &[(String, RecommendationReason, f32)],
+    ) -> Result<(), DatabaseError> {
+        let document_ids: Vec<&str> = recommendations.iter().map(|r| r.0.as_str()).collect();
+        let reasons: Vec<&str> = recommendations.iter().map(|r| r.1.as_str()).collect();
+        let scores: Vec<f32> = recommendations.iter().map(|r| r.2).collect();
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM user_recommendations WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO user_recommendations (user_id, document_id, reason, score)
+            SELECT $1::text, * FROM UNNEST($2::text[], $3::text[], $4::real[])
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(&document_ids)
+        .bind(&reasons)
+        .bind(&scores)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// The user's cached recommendations, best first.
+    pub async fn find_for_user(&self, user_id: &str) -> Result<Vec<Recommendation>, DatabaseError> {
+        let recommendations = sqlx::query_as::<_, Recommendation>(
+            r#"
+            SELECT document_id, reason, score, computed_at
+            FROM user_recommendations
+            WHERE user_id = $1
+            ORDER BY score DESC, document_id
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(recommendations)
+    }
+}
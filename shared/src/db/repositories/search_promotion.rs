@@ -0,0 +1,205 @@
+use crate::{db::error::DatabaseError, utils::generate_ulid};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use time::OffsetDateTime;
+
+/// How a promotion's `query_pattern` is compared with a search query. Queries and plain
+/// patterns are compared case-insensitively with whitespace collapsed.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PromotionMatchType {
+    #[default]
+    Exact,
+    Prefix,
+    Contains,
+    Regex,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PromotionAction {
+    /// Show the document above the ranked results.
+    #[default]
+    Pin,
+    /// Multiply the document's score by `boost` when the ranking found it.
+    Boost,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SearchPromotion {
+    pub id: String,
+    pub query_pattern: String,
+    pub match_type: PromotionMatchType,
+    pub document_id: String,
+    pub action: PromotionAction,
+    pub boost: f32,
+    pub position: i32,
+    pub is_active: bool,
+    pub created_by: Option<String>,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub updated_at: OffsetDateTime,
+}
+
+/// The admin-editable fields of a promotion.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchPromotionInput {
+    pub query_pattern: String,
+    #[serde(default)]
+    pub match_type: PromotionMatchType,
+    pub document_id: String,
+    #[serde(default)]
+    pub action: PromotionAction,
+    #[serde(default = "default_boost")]
+    pub boost: f32,
+    #[serde(default)]
+    pub position: i32,
+    #[serde(default = "default_is_active")]
+    pub is_active: bool,
+}
+
+fn default_boost() -> f32 {
+    1.0
+}
+
+fn default_is_active() -> bool {
+    true
+}
+
+pub struct SearchPromotionRepository {
+    pool: PgPool,
+}
+
+impl SearchPromotionRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub async fn create(
+        &self,
+        input: &SearchPromotionInput,
+        created_by: Option<&str>,
+    ) -> Result<SearchPromotion, DatabaseError> {
+        let promotion = sqlx::query_as::<_, SearchPromotion>(
+            r#"
+            INSERT INTO search_promotions (
+                id, query_pattern, match_type, document_id, action, boost, position, is_active,
+                created_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id, query_pattern, match_type, document_id, action, boost, position,
+                      is_active, created_by, created_at, updated_at
+            "#,
+        )
+        .bind(generate_ulid())
+        .bind(&input.query_pattern)
+        .bind(input.match_type)
+        .bind(&input.document_id)
+        .bind(input.action)
+        .bind(input.boost)
+        .bind(input.position)
+        .bind(input.is_active)
+        .bind(created_by)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(promotion)
+    }
+
+    /// Replace the editable fields of a promotion. Returns `None` when it does not exist.
+    pub async fn update(
+        &self,
+        id: &str,
+        input: &SearchPromotionInput,
+    ) -> Result<Option<SearchPromotion>, DatabaseError> {
+        let promotion = sqlx::query_as::<_, SearchPromotion>(
+            r#"
+            UPDATE search_promotions
+            SET query_pattern = $2,
+                match_type = $3,
+                document_id = $4,
+                action = $5,
+                boost = $6,
+                position = $7,
+                is_active = $8,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, query_pattern, match_type, document_id, action, boost, position,
+                      is_active, created_by, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(&input.query_pattern)
+        .bind(input.match_type)
+        .bind(&input.document_id)
+        .bind(input.action)
+        .bind(input.boost)
+        .bind(input.position)
+        .bind(input.is_active)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(promotion)
+    }
+
+    pub async fn find_all(&self) -> Result<Vec<SearchPromotion>, DatabaseError> {
+        let promotions = sqlx::query_as::<_, SearchPromotion>(
+            r#"
+            SELECT id, query_pattern, match_type, document_id, action, boost, position,
+                   is_active, created_by, created_at, updated_at
+            FROM search_promotions
+            ORDER BY query_pattern, position, created_at
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(promotions)
+    }
+
+    /// Active promotions, pins in display order.
+    pub async fn find_active(&self) -> Result<Vec<SearchPromotion>, DatabaseError> {
+        let promotions = sqlx::query_as::<_, SearchPromotion>(
+            r#"
+            SELECT id, query_pattern, match_type, document_id, action, boost, position,
+                   is_active, created_by, created_at, updated_at
+            FROM search_promotions
+            WHERE is_active
+            ORDER BY position, created_at
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(promotions)
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<SearchPromotion>, DatabaseError> {
+        let promotion = sqlx::query_as::<_, SearchPromotion>(
+            r#"
+            SELECT id, query_pattern, match_type, document_id, action, boost, position,
+                   is_active, created_by, created_at, updated_at
+            FROM search_promotions
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(promotion)
+    }
+
+    /// Delete a promotion. Returns whether it existed.
+    pub async fn delete(&self, id: &str) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM search_promotions WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -2,7 +2,7 @@ use anyhow::Result;
 use serde_json::Value as JsonValue;
 use sqlx::PgPool;
 
-use crate::encryption::{EncryptedData, EncryptionService};
+use crate::encryption::{EncryptedData, EncryptedEnvelope, EncryptionService};
 use crate::models::ServiceCredentials;
 
 /// Service credentials repository with encryption support
@@ -20,6 +20,11 @@ impl ServiceCredentialsRepo {
         })
     }
 
+    /// Identifier of the master key credentials are encrypted with
+    pub fn current_key_id(&self) -> &str {
+        self.encryption_service.current_key_id()
+    }
+
     pub async fn get_by_source_id(&self, source_id: &str) -> Result<Option<ServiceCredentials>> {
         let mut creds = sqlx::query_as::<_, ServiceCredentials>(
             "SELECT * FROM service_credentials WHERE source_id = $1",
@@ -37,23 +42,28 @@ impl ServiceCredentialsRepo {
 
     /// Decrypt credentials in place if they are encrypted
     fn decrypt_credentials_in_place(&self, creds: &mut ServiceCredentials) -> Result<()> {
-        // Check if credentials are already encrypted (new format)
-        if let Some(encrypted_data) = creds.credentials.get("encrypted_data") {
+        // Envelope-encrypted credentials (version 2)
+        if let Some(envelope) = creds.credentials.get("envelope") {
+            let envelope: EncryptedEnvelope = serde_json::from_value(envelope.clone())?;
+            creds.credentials = self.encryption_service.open_json(&envelope)?;
+        }
+        // Credentials encrypted directly with the master key (version 1)
+        else if let Some(encrypted_data) = creds.credentials.get("encrypted_data") {
             let encrypted_data: EncryptedData = serde_json::from_value(encrypted_data.clone())?;
             let decrypted_credentials: JsonValue =
                 self.encryption_service.decrypt_json(&encrypted_data)?;
             creds.credentials = decrypted_credentials;
         }
-        // If no encrypted_data field, credentials are in legacy unencrypted format - leave as is
+        // Otherwise credentials are in legacy unencrypted format - leave as is
         Ok(())
     }
 
     /// Encrypt credentials from application format to database format
     fn encrypt_credentials(&self, creds: &ServiceCredentials) -> Result<JsonValue> {
-        let encrypted_data = self.encryption_service.encrypt_json(&creds.credentials)?;
+        let envelope = self.encryption_service.seal_json(&creds.credentials)?;
         Ok(serde_json::json!({
-            "encrypted_data": encrypted_data,
-            "version": 1
+            "envelope": envelope,
+            "version": 2
         }))
     }
 
@@ -130,9 +140,9 @@ impl ServiceCredentialsRepo {
     pub async fn encrypt_existing_credentials(&self) -> Result<usize> {
         let mut count = 0;
 
-        // Get all credentials that are not encrypted (don't have encrypted_data field)
+        // Get all credentials that are not encrypted
         let unencrypted_creds = sqlx::query_as::<_, ServiceCredentials>(
-            "SELECT * FROM service_credentials WHERE NOT (credentials ? 'encrypted_data')",
+            "SELECT * FROM service_credentials WHERE NOT (credentials ?| ARRAY['encrypted_data', 'envelope'])",
         )
         .fetch_all(&self.pool)
         .await?;
@@ -145,4 +155,26 @@ impl ServiceCredentialsRepo {
 
         Ok(count)
     }
+
+    /// Re-encrypt every credential not sealed with the current master key: unencrypted
+    /// and version 1 credentials, and envelopes wrapped with a previous key. Run after
+    /// rotating `ENCRYPTION_KEY`, before removing the old key from `ENCRYPTION_KEY_PREVIOUS`.
+    pub async fn reencrypt_credentials(&self) -> Result<usize> {
+        let mut count = 0;
+
+        let stale_creds = sqlx::query_as::<_, ServiceCredentials>(
+            "SELECT * FROM service_credentials WHERE credentials->'envelope'->>'key_id' IS DISTINCT FROM $1",
+        )
+        .bind(self.encryption_service.current_key_id())
+        .fetch_all(&self.pool)
+        .await?;
+
+        for mut creds in stale_creds {
+            self.decrypt_credentials_in_place(&mut creds)?;
+            self.update_credentials(&creds).await?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
 }
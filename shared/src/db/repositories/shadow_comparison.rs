@@ -0,0 +1,119 @@
+use crate::{db::error::DatabaseError, utils::generate_ulid};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use time::OffsetDateTime;
+
+/// One search run against both the active and the shadow embedding model. `overlap` is the
+/// number of documents in both top `k` result lists. Query text is never stored, only its hash.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ShadowComparison {
+    pub id: String,
+    pub query_hash: String,
+    pub active_model: String,
+    pub shadow_model: String,
+    pub k: i32,
+    pub overlap: i32,
+    pub active_result_count: i32,
+    pub shadow_result_count: i32,
+    pub active_top_score: Option<f32>,
+    pub shadow_top_score: Option<f32>,
+    pub active_ms: i32,
+    pub shadow_ms: i32,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+}
+
+/// Averages over the comparisons recorded for one pair of active and shadow models.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ShadowComparisonSummary {
+    pub active_model: String,
+    pub shadow_model: String,
+    pub comparisons: i64,
+    /// Mean fraction of the top `k` documents both models returned, from 0 to 1.
+    pub mean_overlap: Option<f64>,
+    pub mean_active_top_score: Option<f64>,
+    pub mean_shadow_top_score: Option<f64>,
+    pub mean_active_ms: Option<f64>,
+    pub mean_shadow_ms: Option<f64>,
+    /// Comparisons where the shadow model found nothing while the active model did.
+    pub shadow_empty: i64,
+}
+
+pub struct ShadowComparisonRepository {
+    pool: PgPool,
+}
+
+impl ShadowComparisonRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Insert a comparison, assigning its id and creation time.
+    pub async fn create(
+        &self,
+        mut comparison: ShadowComparison,
+    ) -> Result<ShadowComparison, DatabaseError> {
+        comparison.id = generate_ulid();
+        comparison.created_at = OffsetDateTime::now_utc();
+
+        sqlx::query(
+            r#"
+            INSERT INTO embedding_shadow_comparisons (
+                id, query_hash, active_model, shadow_model, k, overlap,
+                active_result_count, shadow_result_count, active_top_score, shadow_top_score,
+                active_ms, shadow_ms, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            "#,
+        )
+        .bind(&comparison.id)
+        .bind(&comparison.query_hash)
+        .bind(&comparison.active_model)
+        .bind(&comparison.shadow_model)
+        .bind(comparison.k)
+        .bind(comparison.overlap)
+        .bind(comparison.active_result_count)
+        .bind(comparison.shadow_result_count)
+        .bind(comparison.active_top_score)
+        .bind(comparison.shadow_top_score)
+        .bind(comparison.active_ms)
+        .bind(comparison.shadow_ms)
+        .bind(comparison.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(comparison)
+    }
+
+    /// Summaries of the comparisons recorded since `since`, one per model pair, so the
+    /// comparisons from before and after a cutover are reported separately.
+    pub async fn summarize(
+        &self,
+        since: OffsetDateTime,
+    ) -> Result<Vec<ShadowComparisonSummary>, DatabaseError> {
+        let summaries = sqlx::query_as::<_, ShadowComparisonSummary>(
+            r#"
+            SELECT active_model,
+                   shadow_model,
+                   COUNT(*) AS comparisons,
+                   AVG(overlap::float8 / NULLIF(k, 0)) AS mean_overlap,
+                   AVG(active_top_score)::float8 AS mean_active_top_score,
+                   AVG(shadow_top_score)::float8 AS mean_shadow_top_score,
+                   AVG(active_ms)::float8 AS mean_active_ms,
+                   AVG(shadow_ms)::float8 AS mean_shadow_ms,
+                   COUNT(*) FILTER (
+                       WHERE shadow_result_count = 0 AND active_result_count > 0
+                   ) AS shadow_empty
+            FROM embedding_shadow_comparisons
+            WHERE created_at >= $1
+            GROUP BY active_model, shadow_model
+            ORDER BY active_model, shadow_model
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(summaries)
+    }
+}
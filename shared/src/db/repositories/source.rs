@@ -1,6 +1,7 @@
 use crate::{db::error::DatabaseError, models::Source, traits::Repository};
 use async_trait::async_trait;
 use sqlx::PgPool;
+use std::collections::HashMap;
 use time::OffsetDateTime;
 
 #[derive(Clone)]
@@ -48,6 +49,45 @@ impl SourceRepository {
         Ok(sources)
     }
 
+    /// Set the retention cutoff of a source, or clear it with `None` when the source keeps its
+    /// documents indefinitely.
+    pub async fn set_retention_cutoff(
+        &self,
+        id: &str,
+        cutoff: Option<OffsetDateTime>,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "UPDATE sources SET retention_cutoff = $2
+             WHERE id = $1 AND retention_cutoff IS DISTINCT FROM $2",
+        )
+        .bind(id)
+        .bind(cutoff)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Retention cutoffs of those of the sources that have one, keyed by source id.
+    pub async fn find_retention_cutoffs(
+        &self,
+        ids: &[String],
+    ) -> Result<HashMap<String, OffsetDateTime>, DatabaseError> {
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows: Vec<(String, OffsetDateTime)> = sqlx::query_as(
+            "SELECT id, retention_cutoff FROM sources
+             WHERE id = ANY($1) AND retention_cutoff IS NOT NULL",
+        )
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
     pub async fn update_user_filter_settings(
         &self,
         id: &str,
@@ -105,6 +145,50 @@ impl SourceRepository {
         Ok(sources)
     }
 
+    /// Pause or resume scheduled syncs of a source. Returns false if the source does not
+    /// exist.
+    pub async fn set_active(&self, id: &str, is_active: bool) -> Result<bool, DatabaseError> {
+        let result = sqlx::query(
+            "UPDATE sources SET is_active = $2, updated_at = NOW() WHERE id = $1 AND is_deleted = false",
+        )
+        .bind(id)
+        .bind(is_active)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The generation of a source's SDK tokens, `None` if the source does not exist.
+    pub async fn get_sdk_token_generation(&self, id: &str) -> Result<Option<i32>, DatabaseError> {
+        let generation = sqlx::query_scalar::<_, i32>(
+            "SELECT sdk_token_generation FROM sources WHERE id = $1 AND is_deleted = false",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(generation)
+    }
+
+    /// Start a new generation of a source's SDK tokens, revoking those issued before. Returns
+    /// the new generation, `None` if the source does not exist.
+    pub async fn rotate_sdk_token(&self, id: &str) -> Result<Option<i32>, DatabaseError> {
+        let generation = sqlx::query_scalar::<_, i32>(
+            r#"
+            UPDATE sources
+            SET sdk_token_generation = sdk_token_generation + 1, updated_at = NOW()
+            WHERE id = $1 AND is_deleted = false
+            RETURNING sdk_token_generation
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(generation)
+    }
+
     pub async fn update_connector_state(
         &self,
         id: &str,
@@ -121,6 +205,18 @@ impl SourceRepository {
         Ok(())
     }
 
+    /// Forget the connector's sync cursor, so its next sync starts from scratch.
+    pub async fn clear_connector_state(&self, id: &str) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "UPDATE sources SET connector_state = NULL, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn get_document_count(&self, id: &str) -> Result<i64, DatabaseError> {
         let result: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM documents WHERE source_id = $1")
             .bind(id)
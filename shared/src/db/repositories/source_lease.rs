@@ -0,0 +1,68 @@
+use crate::db::error::DatabaseError;
+use sqlx::PgPool;
+
+/// Leases on scheduling sources' syncs, so that of several connector manager replicas exactly
+/// one schedules each source.
+pub struct SourceLeaseRepository {
+    pool: PgPool,
+}
+
+impl SourceLeaseRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Acquire or renew for `lease_seconds` the leases on those of the sources that are free,
+    /// expired or already held by `holder`. Returns the sources `holder` now holds.
+    pub async fn acquire(
+        &self,
+        holder: &str,
+        source_ids: &[String],
+        lease_seconds: i64,
+    ) -> Result<Vec<String>, DatabaseError> {
+        if source_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let acquired = sqlx::query_scalar::<_, String>(
+            r#"
+            INSERT INTO source_schedule_leases (source_id, holder, expires_at)
+            SELECT source_id, $2, NOW() + make_interval(secs => $3)
+            FROM UNNEST($1::text[]) AS s(source_id)
+            ON CONFLICT (source_id) DO UPDATE
+            SET holder = EXCLUDED.holder,
+                expires_at = EXCLUDED.expires_at,
+                acquired_at = CASE
+                    WHEN source_schedule_leases.holder = EXCLUDED.holder
+                        THEN source_schedule_leases.acquired_at
+                    ELSE NOW()
+                END
+            WHERE source_schedule_leases.holder = EXCLUDED.holder
+               OR source_schedule_leases.expires_at <= NOW()
+            RETURNING source_id
+            "#,
+        )
+        .bind(source_ids)
+        .bind(holder)
+        .bind(lease_seconds as f64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(acquired)
+    }
+
+    /// Extend the leases `holder` holds by `lease_seconds` from now.
+    pub async fn renew(&self, holder: &str, lease_seconds: i64) -> Result<u64, DatabaseError> {
+        let result = sqlx::query(
+            "UPDATE source_schedule_leases
+             SET expires_at = NOW() + make_interval(secs => $2)
+             WHERE holder = $1 AND expires_at > NOW()",
+        )
+        .bind(holder)
+        .bind(lease_seconds as f64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
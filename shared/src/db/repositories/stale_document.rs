@@ -0,0 +1,198 @@
+use crate::db::error::DatabaseError;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use time::OffsetDateTime;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct StaleRebuildStats {
+    pub stale_documents: u64,
+    pub superseded: u64,
+}
+
+/// Stale documents of one source, and when they were last analyzed.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct StaleSourceSummary {
+    pub source_id: String,
+    pub source_name: String,
+    pub source_type: String,
+    pub stale_documents: i64,
+    pub superseded: i64,
+    #[serde(with = "time::serde::iso8601")]
+    pub analyzed_at: OffsetDateTime,
+}
+
+/// Someone owning stale documents of a source, by the documents' author.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct StaleContentOwner {
+    pub source_id: String,
+    pub email: String,
+    pub name: Option<String>,
+    pub stale_documents: i64,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct StaleDocument {
+    pub document_id: String,
+    pub source_id: String,
+    pub title: String,
+    pub url: Option<String>,
+    #[serde(with = "time::serde::iso8601")]
+    pub updated_at: OffsetDateTime,
+    pub owner_email: Option<String>,
+    pub superseded_by: Option<String>,
+    pub superseded_by_title: Option<String>,
+    pub similarity: Option<f32>,
+}
+
+pub struct StaleDocumentRepository {
+    pool: PgPool,
+}
+
+impl StaleDocumentRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// Replace the stale documents with the ones not updated since `stale_before` that nobody
+    /// opened. Each is superseded by the newer document of its source whose first chunk is
+    /// the most similar to its own, when they are at least `min_similarity` similar.
+    pub async fn rebuild(
+        &self,
+        stale_before: OffsetDateTime,
+        min_similarity: f32,
+    ) -> Result<StaleRebuildStats, DatabaseError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM stale_documents")
+            .execute(&mut *tx)
+            .await?;
+        let (stale_documents, superseded): (i64, i64) = sqlx::query_as(
+            r#"
+            WITH inserted AS (
+                INSERT INTO stale_documents (document_id, source_id, superseded_by, similarity)
+                SELECT d.id, d.source_id, newer.id, newer.similarity
+                FROM documents d
+                JOIN sources s ON s.id = d.source_id
+                LEFT JOIN embeddings e ON e.document_id = d.id AND e.chunk_index = 0
+                LEFT JOIN LATERAL (
+                    SELECT other.id, (1 - (e.embedding <=> oe.embedding))::real AS similarity
+                    FROM embeddings oe
+                    JOIN documents other ON other.id = oe.document_id
+                    WHERE oe.chunk_index = 0
+                      AND oe.model_name = e.model_name
+                      AND oe.dimensions = e.dimensions
+                      AND other.source_id = d.source_id
+                      AND other.id <> d.id
+                      AND other.updated_at > d.updated_at
+                    ORDER BY e.embedding <=> oe.embedding
+                    LIMIT 1
+                ) newer ON newer.similarity >= $2
+                WHERE NOT s.is_deleted
+                  AND s.source_type <> 'people'
+                  AND d.updated_at < $1
+                  AND NOT EXISTS (
+                      SELECT 1 FROM document_interactions i WHERE i.document_id = d.id
+                  )
+                ON CONFLICT (document_id) DO NOTHING
+                RETURNING superseded_by
+            )
+            SELECT COUNT(*), COUNT(superseded_by) FROM inserted
+            "#,
+        )
+        .bind(stale_before)
+        .bind(min_similarity)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(StaleRebuildStats {
+            stale_documents: stale_documents as u64,
+            superseded: superseded as u64,
+        })
+    }
+
+    /// Stale documents per source, optionally of one source, most first.
+    pub async fn summarize(
+        &self,
+        source_id: Option<&str>,
+    ) -> Result<Vec<StaleSourceSummary>, DatabaseError> {
+        let summaries = sqlx::query_as::<_, StaleSourceSummary>(
+            r#"
+            SELECT s.id AS source_id, s.name AS source_name, s.source_type,
+                   COUNT(*) AS stale_documents,
+                   COUNT(sd.superseded_by) AS superseded,
+                   MAX(sd.analyzed_at) AS analyzed_at
+            FROM stale_documents sd
+            JOIN sources s ON s.id = sd.source_id
+            WHERE $1::text IS NULL OR sd.source_id = $1
+            GROUP BY s.id, s.name, s.source_type
+            ORDER BY stale_documents DESC, s.name
+            "#,
+        )
+        .bind(source_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(summaries)
+    }
+
+    /// Authors of stale documents per source, optionally of one source, those owning the
+    /// most first.
+    pub async fn find_owners(
+        &self,
+        source_id: Option<&str>,
+    ) -> Result<Vec<StaleContentOwner>, DatabaseError> {
+        let owners = sqlx::query_as::<_, StaleContentOwner>(
+            r#"
+            SELECT sd.source_id, lower(trim(d.metadata->>'author')) AS email,
+                   MAX(p.name) AS name, COUNT(*) AS stale_documents
+            FROM stale_documents sd
+            JOIN documents d ON d.id = sd.document_id
+            LEFT JOIN people p ON p.email = lower(trim(d.metadata->>'author'))
+            WHERE ($1::text IS NULL OR sd.source_id = $1)
+              AND COALESCE(trim(d.metadata->>'author'), '') <> ''
+            GROUP BY sd.source_id, lower(trim(d.metadata->>'author'))
+            ORDER BY sd.source_id, stale_documents DESC, email
+            "#,
+        )
+        .bind(source_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(owners)
+    }
+
+    /// Up to `limit` stale documents per source, optionally of one source, least recently
+    /// updated first.
+    pub async fn find(
+        &self,
+        source_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<StaleDocument>, DatabaseError> {
+        let documents = sqlx::query_as::<_, StaleDocument>(
+            r#"
+            SELECT document_id, source_id, title, url, updated_at, owner_email,
+                   superseded_by, superseded_by_title, similarity
+            FROM (
+                SELECT sd.document_id, sd.source_id, d.title, d.url, d.updated_at,
+                       NULLIF(lower(trim(d.metadata->>'author')), '') AS owner_email,
+                       sd.superseded_by, newer.title AS superseded_by_title, sd.similarity,
+                       ROW_NUMBER() OVER (
+                           PARTITION BY sd.source_id ORDER BY d.updated_at, sd.document_id
+                       ) AS position
+                FROM stale_documents sd
+                JOIN documents d ON d.id = sd.document_id
+                LEFT JOIN documents newer ON newer.id = sd.superseded_by
+                WHERE $1::text IS NULL OR sd.source_id = $1
+            ) ranked
+            WHERE position <= $2
+            ORDER BY source_id, position
+            "#,
+        )
+        .bind(source_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(documents)
+    }
+}
@@ -3,9 +3,51 @@ use crate::{
     models::{SyncRun, SyncStatus, SyncType},
     utils::generate_ulid,
 };
-use sqlx::PgPool;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool, Postgres, Transaction};
 use time::OffsetDateTime;
 
+/// Sync run outcomes for a single day, bucketed by start time.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DailySyncStats {
+    pub day: String,
+    pub completed: i64,
+    pub failed: i64,
+    pub documents_processed: i64,
+}
+
+/// A failed sync run with the name of its source, for following sync errors.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct FailedSyncRun {
+    pub id: String,
+    pub source_id: String,
+    pub source_name: String,
+    pub sync_type: SyncType,
+    #[serde(with = "time::serde::iso8601::option")]
+    pub started_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::iso8601")]
+    pub completed_at: OffsetDateTime,
+    pub error_message: Option<String>,
+}
+
+/// Sync runs of one source, day and sync type that were rolled up by compaction.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SyncRunDailySummary {
+    pub source_id: String,
+    pub day: String,
+    pub sync_type: SyncType,
+    pub runs: i32,
+    pub completed: i32,
+    pub failed: i32,
+    pub cancelled: i32,
+    pub documents_scanned: i64,
+    pub documents_processed: i64,
+    pub documents_updated: i64,
+    pub total_duration_seconds: f64,
+    pub max_duration_seconds: f64,
+    pub last_error_message: Option<String>,
+}
+
 #[derive(Clone)]
 pub struct SyncRunRepository {
     pool: PgPool,
@@ -246,6 +288,47 @@ impl SyncRunRepository {
         Ok(())
     }
 
+    /// Mark a running sync paused, leaving it to be resumed.
+    pub async fn mark_paused(&self, id: &str) -> Result<bool, DatabaseError> {
+        let result = sqlx::query(
+            "UPDATE sync_runs
+             SET status = $1, completed_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+             WHERE id = $2 AND status = $3",
+        )
+        .bind(SyncStatus::Paused)
+        .bind(id)
+        .bind(SyncStatus::Running)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Paused syncs of active sources not followed by another run of their source, oldest
+    /// first.
+    pub async fn find_paused_to_resume(&self) -> Result<Vec<SyncRun>, DatabaseError> {
+        let sync_runs = sqlx::query_as::<_, SyncRun>(
+            r#"
+            SELECT latest.* FROM (
+                SELECT DISTINCT ON (source_id)
+                       id, source_id, sync_type, started_at, completed_at, status,
+                       documents_scanned, documents_processed, documents_updated, error_message,
+                       created_at, updated_at
+                FROM sync_runs
+                ORDER BY source_id, created_at DESC
+            ) latest
+            JOIN sources s ON s.id = latest.source_id
+            WHERE latest.status = $1 AND s.is_active
+            ORDER BY latest.created_at
+            "#,
+        )
+        .bind(SyncStatus::Paused)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(sync_runs)
+    }
+
     pub async fn update_activity(&self, id: &str) -> Result<(), DatabaseError> {
         sqlx::query(
             "UPDATE sync_runs
@@ -259,6 +342,28 @@ impl SyncRunRepository {
         Ok(())
     }
 
+    /// Record connector activity on a run inside the caller's transaction, adding `scanned`
+    /// to its scanned count, so the change commits together with the events it accounts for.
+    pub async fn record_activity_in(
+        tx: &mut Transaction<'_, Postgres>,
+        id: &str,
+        scanned: i32,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "UPDATE sync_runs
+             SET documents_scanned = documents_scanned + $1,
+                 last_activity_at = CURRENT_TIMESTAMP,
+                 updated_at = CURRENT_TIMESTAMP
+             WHERE id = $2",
+        )
+        .bind(scanned)
+        .bind(id)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn find_latest_for_sources(
         &self,
         source_ids: &[String],
@@ -281,6 +386,34 @@ impl SyncRunRepository {
         Ok(sync_runs)
     }
 
+    /// The most recent `per_source_limit` runs of each source, newest first within a source.
+    pub async fn find_recent_for_sources(
+        &self,
+        source_ids: &[String],
+        per_source_limit: i64,
+    ) -> Result<Vec<SyncRun>, DatabaseError> {
+        let sync_runs = sqlx::query_as::<_, SyncRun>(
+            r#"
+            SELECT id, source_id, sync_type, started_at, completed_at, status,
+                   documents_scanned, documents_processed, documents_updated, error_message,
+                   created_at, updated_at
+            FROM (
+                SELECT *, ROW_NUMBER() OVER (PARTITION BY source_id ORDER BY started_at DESC) AS rn
+                FROM sync_runs
+                WHERE source_id = ANY($1)
+            ) ranked
+            WHERE rn <= $2
+            ORDER BY source_id, started_at DESC
+            "#,
+        )
+        .bind(source_ids)
+        .bind(per_source_limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(sync_runs)
+    }
+
     pub async fn increment_scanned_with_activity(&self, id: &str) -> Result<(), DatabaseError> {
         sqlx::query(
             "UPDATE sync_runs
@@ -295,4 +428,202 @@ impl SyncRunRepository {
 
         Ok(())
     }
+
+    /// Daily run outcomes since `since`, combining runs kept verbatim with the daily
+    /// summaries of compacted runs.
+    pub async fn daily_stats(
+        &self,
+        since: OffsetDateTime,
+    ) -> Result<Vec<DailySyncStats>, DatabaseError> {
+        let stats = sqlx::query_as::<_, DailySyncStats>(
+            r#"
+            SELECT day,
+                   SUM(completed)::BIGINT AS completed,
+                   SUM(failed)::BIGINT AS failed,
+                   SUM(documents_processed)::BIGINT AS documents_processed
+            FROM (
+                SELECT to_char(date_trunc('day', started_at), 'YYYY-MM-DD') AS day,
+                       COUNT(*) FILTER (WHERE status = 'completed') AS completed,
+                       COUNT(*) FILTER (WHERE status = 'failed') AS failed,
+                       COALESCE(SUM(documents_processed), 0) AS documents_processed
+                FROM sync_runs
+                WHERE started_at >= $1
+                GROUP BY 1
+                UNION ALL
+                SELECT to_char(day, 'YYYY-MM-DD') AS day,
+                       SUM(completed) AS completed,
+                       SUM(failed) AS failed,
+                       SUM(documents_processed) AS documents_processed
+                FROM sync_run_daily_summaries
+                WHERE day >= date_trunc('day', $1::timestamptz)::date
+                GROUP BY 1
+            ) combined
+            GROUP BY day
+            ORDER BY day
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(stats)
+    }
+
+    /// Roll finished runs started before `cutoff` into their daily summaries and delete
+    /// them, oldest first and at most `limit` runs per call. The latest completed run of
+    /// each source and sync type is always kept, since incremental syncs resume from it.
+    /// Returns the number of runs compacted.
+    pub async fn compact_runs_before(
+        &self,
+        cutoff: OffsetDateTime,
+        limit: i64,
+    ) -> Result<u64, DatabaseError> {
+        let compacted: i64 = sqlx::query_scalar(
+            r#"
+            WITH latest_completed AS (
+                SELECT DISTINCT ON (source_id, sync_type) id
+                FROM sync_runs
+                WHERE status = 'completed'
+                ORDER BY source_id, sync_type, completed_at DESC
+            ),
+            compacted AS (
+                DELETE FROM sync_runs
+                WHERE id IN (
+                    SELECT id FROM sync_runs
+                    WHERE started_at < $1
+                      AND status <> 'running'
+                      AND id NOT IN (SELECT id FROM latest_completed)
+                    ORDER BY started_at
+                    LIMIT $2
+                )
+                RETURNING *
+            ),
+            summarized AS (
+                INSERT INTO sync_run_daily_summaries (
+                    source_id, day, sync_type, runs, completed, failed, cancelled,
+                    documents_scanned, documents_processed, documents_updated,
+                    total_duration_seconds, max_duration_seconds, last_error_message
+                )
+                SELECT source_id,
+                       date_trunc('day', started_at)::date,
+                       sync_type,
+                       COUNT(*),
+                       COUNT(*) FILTER (WHERE status = 'completed'),
+                       COUNT(*) FILTER (WHERE status = 'failed'),
+                       COUNT(*) FILTER (WHERE status = 'cancelled'),
+                       COALESCE(SUM(documents_scanned), 0),
+                       COALESCE(SUM(documents_processed), 0),
+                       COALESCE(SUM(documents_updated), 0),
+                       COALESCE(SUM(EXTRACT(EPOCH FROM completed_at - started_at)), 0)::float8,
+                       COALESCE(MAX(EXTRACT(EPOCH FROM completed_at - started_at)), 0)::float8,
+                       (ARRAY_AGG(error_message ORDER BY started_at DESC)
+                            FILTER (WHERE error_message IS NOT NULL))[1]
+                FROM compacted
+                GROUP BY source_id, date_trunc('day', started_at)::date, sync_type
+                ON CONFLICT (source_id, day, sync_type) DO UPDATE SET
+                    runs = sync_run_daily_summaries.runs + EXCLUDED.runs,
+                    completed = sync_run_daily_summaries.completed + EXCLUDED.completed,
+                    failed = sync_run_daily_summaries.failed + EXCLUDED.failed,
+                    cancelled = sync_run_daily_summaries.cancelled + EXCLUDED.cancelled,
+                    documents_scanned =
+                        sync_run_daily_summaries.documents_scanned + EXCLUDED.documents_scanned,
+                    documents_processed =
+                        sync_run_daily_summaries.documents_processed + EXCLUDED.documents_processed,
+                    documents_updated =
+                        sync_run_daily_summaries.documents_updated + EXCLUDED.documents_updated,
+                    total_duration_seconds =
+                        sync_run_daily_summaries.total_duration_seconds + EXCLUDED.total_duration_seconds,
+                    max_duration_seconds = GREATEST(
+                        sync_run_daily_summaries.max_duration_seconds,
+                        EXCLUDED.max_duration_seconds
+                    ),
+                    last_error_message = COALESCE(
+                        EXCLUDED.last_error_message,
+                        sync_run_daily_summaries.last_error_message
+                    )
+            )
+            SELECT COUNT(*) FROM compacted
+            "#,
+        )
+        .bind(cutoff)
+        .bind(limit)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(compacted as u64)
+    }
+
+    /// Daily summaries of a source's compacted runs since `since`, newest first.
+    pub async fn find_daily_summaries_for_source(
+        &self,
+        source_id: &str,
+        since: OffsetDateTime,
+    ) -> Result<Vec<SyncRunDailySummary>, DatabaseError> {
+        let summaries = sqlx::query_as::<_, SyncRunDailySummary>(
+            r#"
+            SELECT source_id, to_char(day, 'YYYY-MM-DD') AS day, sync_type, runs, completed,
+                   failed, cancelled, documents_scanned, documents_processed, documents_updated,
+                   total_duration_seconds, max_duration_seconds, last_error_message
+            FROM sync_run_daily_summaries
+            WHERE source_id = $1 AND day >= date_trunc('day', $2::timestamptz)::date
+            ORDER BY day DESC, sync_type
+            "#,
+        )
+        .bind(source_id)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(summaries)
+    }
+
+    /// Runs of a source kept verbatim that started at or after `since`, newest first.
+    pub async fn find_for_source_since(
+        &self,
+        source_id: &str,
+        since: OffsetDateTime,
+    ) -> Result<Vec<SyncRun>, DatabaseError> {
+        let sync_runs = sqlx::query_as::<_, SyncRun>(
+            r#"
+            SELECT id, source_id, sync_type, started_at, completed_at, status,
+                   documents_scanned, documents_processed, documents_updated, error_message,
+                   created_at, updated_at
+            FROM sync_runs
+            WHERE source_id = $1 AND started_at >= $2
+            ORDER BY started_at DESC
+            "#,
+        )
+        .bind(source_id)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(sync_runs)
+    }
+
+    /// Runs that failed after `since`, oldest first.
+    pub async fn find_failed_since(
+        &self,
+        since: OffsetDateTime,
+        limit: i64,
+    ) -> Result<Vec<FailedSyncRun>, DatabaseError> {
+        let runs = sqlx::query_as::<_, FailedSyncRun>(
+            r#"
+            SELECT r.id, r.source_id, s.name AS source_name, r.sync_type, r.started_at,
+                   r.completed_at, r.error_message
+            FROM sync_runs r
+            JOIN sources s ON s.id = r.source_id
+            WHERE r.status = $1 AND r.completed_at > $2
+            ORDER BY r.completed_at
+            LIMIT $3
+            "#,
+        )
+        .bind(SyncStatus::Failed)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(runs)
+    }
 }
@@ -1,10 +1,11 @@
 use crate::{
     db::error::DatabaseError,
-    models::{User, UserRole},
+    models::{NotificationKind, NotificationPreferences, User, UserRole},
     traits::Repository,
+    utils::generate_ulid,
 };
 use async_trait::async_trait;
-use sqlx::PgPool;
+use sqlx::{types::Json, PgPool};
 
 pub struct UserRepository {
     pool: PgPool,
@@ -18,8 +19,8 @@ impl UserRepository {
     pub async fn find_by_email(&self, email: &str) -> Result<Option<User>, DatabaseError> {
         let user = sqlx::query_as::<_, User>(
             r#"
-            SELECT id, email, password_hash, full_name, avatar_url,
-                   role, is_active, created_at, updated_at, last_login_at
+            SELECT id, email, password_hash, full_name, avatar_url, role, is_active,
+                   auth_method, domain, external_id, created_at, updated_at, last_login_at
             FROM users
             WHERE email = $1
             "#,
@@ -53,6 +54,202 @@ impl UserRepository {
 
         Ok(users)
     }
+
+    /// Users ordered by creation, optionally filtered by email, along with the total
+    /// number of matching users.
+    pub async fn list(
+        &self,
+        email: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<User>, i64), DatabaseError> {
+        let users = sqlx::query_as::<_, User>(
+            r#"
+            SELECT id, email, full_name, avatar_url, role, is_active, auth_method, domain,
+                   external_id, created_at, updated_at, last_login_at
+            FROM users
+            WHERE $1::text IS NULL OR LOWER(email) = LOWER($1)
+            ORDER BY created_at, id
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(email)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM users WHERE $1::text IS NULL OR LOWER(email) = LOWER($1)",
+        )
+        .bind(email)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok((users, total))
+    }
+
+    pub async fn find_with_external_id(&self, id: &str) -> Result<Option<User>, DatabaseError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            SELECT id, email, full_name, avatar_url, role, is_active, auth_method, domain,
+                   external_id, created_at, updated_at, last_login_at
+            FROM users
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    /// Look up a user by the id their identity provider assigned them.
+    pub async fn find_by_external_id(
+        &self,
+        external_id: &str,
+    ) -> Result<Option<User>, DatabaseError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            SELECT id, email, full_name, avatar_url, role, is_active, auth_method, domain,
+                   external_id, created_at, updated_at, last_login_at
+            FROM users
+            WHERE external_id = $1
+            "#,
+        )
+        .bind(external_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    /// Create a user on behalf of an identity provider. Provisioned users sign in with
+    /// magic links or SSO, so no password is set.
+    pub async fn provision(
+        &self,
+        email: &str,
+        full_name: Option<&str>,
+        external_id: Option<&str>,
+        is_active: bool,
+    ) -> Result<User, DatabaseError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            INSERT INTO users (id, email, full_name, role, is_active, auth_method, domain, external_id)
+            VALUES ($1, $2, $3, 'user', $4, 'magic_link', SUBSTRING($2 FROM '@(.*)$'), $5)
+            RETURNING id, email, full_name, avatar_url, role, is_active, auth_method, domain,
+                      external_id, created_at, updated_at, last_login_at
+            "#,
+        )
+        .bind(generate_ulid())
+        .bind(email)
+        .bind(full_name)
+        .bind(is_active)
+        .bind(external_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                DatabaseError::ConstraintViolation("User already exists".to_string())
+            }
+            _ => DatabaseError::from(e),
+        })?;
+
+        Ok(user)
+    }
+
+    /// Update the identity provider managed attributes of a user. Role and authentication
+    /// settings stay under Omni's control.
+    pub async fn update_provisioned(
+        &self,
+        id: &str,
+        email: &str,
+        full_name: Option<&str>,
+        external_id: Option<&str>,
+        is_active: bool,
+    ) -> Result<Option<User>, DatabaseError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET email = $2, full_name = $3, external_id = $4, is_active = $5,
+                domain = SUBSTRING($2 FROM '@(.*)$')
+            WHERE id = $1
+            RETURNING id, email, full_name, avatar_url, role, is_active, auth_method, domain,
+                      external_id, created_at, updated_at, last_login_at
+            "#,
+        )
+        .bind(id)
+        .bind(email)
+        .bind(full_name)
+        .bind(external_id)
+        .bind(is_active)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                DatabaseError::ConstraintViolation("User already exists".to_string())
+            }
+            _ => DatabaseError::from(e),
+        })?;
+
+        Ok(user)
+    }
+
+    /// A user's notification preferences, `None` if the user does not exist.
+    pub async fn find_notification_preferences(
+        &self,
+        id: &str,
+    ) -> Result<Option<NotificationPreferences>, DatabaseError> {
+        let preferences: Option<Json<NotificationPreferences>> =
+            sqlx::query_scalar("SELECT notification_preferences FROM users WHERE id = $1")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(preferences.map(|Json(preferences)| preferences))
+    }
+
+    /// Replace a user's notification preferences. Returns whether the user exists.
+    pub async fn update_notification_preferences(
+        &self,
+        id: &str,
+        preferences: &NotificationPreferences,
+    ) -> Result<bool, DatabaseError> {
+        let result = sqlx::query(
+            "UPDATE users SET notification_preferences = $1, updated_at = NOW() WHERE id = $2",
+        )
+        .bind(Json(preferences))
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Email addresses of the active users who should receive a `kind` notification: every
+    /// admin, plus the users in `user_ids`, less those who turned it off.
+    pub async fn find_notification_recipients(
+        &self,
+        kind: NotificationKind,
+        user_ids: &[String],
+    ) -> Result<Vec<String>, DatabaseError> {
+        let emails = sqlx::query_scalar(
+            r#"
+            SELECT email FROM users
+            WHERE is_active
+              AND (role = 'admin' OR id = ANY($1))
+              AND COALESCE((notification_preferences->>$2)::boolean, true)
+            ORDER BY email
+            "#,
+        )
+        .bind(user_ids)
+        .bind(kind.preference_key())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(emails)
+    }
 }
 
 #[async_trait]
@@ -0,0 +1,329 @@
+use crate::{db::error::DatabaseError, utils::generate_ulid};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::{FromRow, PgPool};
+use time::OffsetDateTime;
+
+/// Events webhooks can subscribe to.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum WebhookEvent {
+    #[serde(rename = "document.indexed")]
+    DocumentIndexed,
+    #[serde(rename = "sync.completed")]
+    SyncCompleted,
+    #[serde(rename = "sync.failed")]
+    SyncFailed,
+}
+
+impl WebhookEvent {
+    pub const ALL: [WebhookEvent; 3] = [
+        WebhookEvent::DocumentIndexed,
+        WebhookEvent::SyncCompleted,
+        WebhookEvent::SyncFailed,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::DocumentIndexed => "document.indexed",
+            WebhookEvent::SyncCompleted => "sync.completed",
+            WebhookEvent::SyncFailed => "sync.failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|event| event.as_str() == value)
+    }
+}
+
+/// A registered webhook. The signing secret is sealed with the master encryption key and
+/// never serialized.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    #[serde(skip_serializing)]
+    pub secret: JsonValue,
+    pub event_types: Vec<String>,
+    pub created_by: Option<String>,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub updated_at: OffsetDateTime,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+/// One event to be posted to one webhook, with the outcome of the latest attempt.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub webhook_id: String,
+    pub event_type: String,
+    pub payload: JsonValue,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    #[serde(with = "time::serde::iso8601")]
+    pub next_attempt_at: OffsetDateTime,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601::option")]
+    pub delivered_at: Option<OffsetDateTime>,
+}
+
+pub struct WebhookRepository {
+    pool: PgPool,
+}
+
+impl WebhookRepository {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub async fn create(
+        &self,
+        url: &str,
+        event_types: &[WebhookEvent],
+        secret: &JsonValue,
+        created_by: Option<&str>,
+    ) -> Result<Webhook, DatabaseError> {
+        let event_types: Vec<&str> = event_types.iter().map(WebhookEvent::as_str).collect();
+        let webhook = sqlx::query_as::<_, Webhook>(
+            r#"
+            INSERT INTO webhooks (id, url, secret, event_types, created_by)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, url, secret, event_types, created_by, created_at, updated_at
+            "#,
+        )
+        .bind(generate_ulid())
+        .bind(url)
+        .bind(secret)
+        .bind(&event_types)
+        .bind(created_by)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(webhook)
+    }
+
+    pub async fn find_all(&self) -> Result<Vec<Webhook>, DatabaseError> {
+        let webhooks = sqlx::query_as::<_, Webhook>(
+            r#"
+            SELECT id, url, secret, event_types, created_by, created_at, updated_at
+            FROM webhooks
+            ORDER BY created_at
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(webhooks)
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<Webhook>, DatabaseError> {
+        let webhook = sqlx::query_as::<_, Webhook>(
+            r#"
+            SELECT id, url, secret, event_types, created_by, created_at, updated_at
+            FROM webhooks
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(webhook)
+    }
+
+    /// Delete a webhook and its delivery log. Returns whether it existed.
+    pub async fn delete(&self, id: &str) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM webhooks WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Queue a delivery of each payload to every webhook subscribed to `event`, returning the
+    /// number of deliveries queued.
+    pub async fn enqueue(
+        &self,
+        event: WebhookEvent,
+        payloads: &[JsonValue],
+    ) -> Result<u64, DatabaseError> {
+        if payloads.is_empty() {
+            return Ok(0);
+        }
+
+        let webhook_ids: Vec<String> =
+            sqlx::query_scalar("SELECT id FROM webhooks WHERE $1 = ANY(event_types)")
+                .bind(event.as_str())
+                .fetch_all(&self.pool)
+                .await?;
+        if webhook_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let mut ids = Vec::new();
+        let mut delivery_webhook_ids = Vec::new();
+        let mut delivery_payloads = Vec::new();
+        for webhook_id in &webhook_ids {
+            for payload in payloads {
+                ids.push(generate_ulid());
+                delivery_webhook_ids.push(webhook_id.clone());
+                delivery_payloads.push(payload.clone());
+            }
+        }
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO webhook_deliveries (id, webhook_id, event_type, payload)
+            SELECT id, webhook_id, $3, payload
+            FROM UNNEST($1::text[], $2::text[], $4::jsonb[]) AS d(id, webhook_id, payload)
+            "#,
+        )
+        .bind(&ids)
+        .bind(&delivery_webhook_ids)
+        .bind(event.as_str())
+        .bind(&delivery_payloads)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Claim up to `limit` pending deliveries that are due, counting the attempt and pushing
+    /// their next attempt `lease_seconds` out so that a delivery interrupted by a restart is
+    /// retried once the lease expires.
+    pub async fn claim_due_deliveries(
+        &self,
+        limit: i64,
+        lease_seconds: i64,
+    ) -> Result<Vec<WebhookDelivery>, DatabaseError> {
+        let deliveries = sqlx::query_as::<_, WebhookDelivery>(
+            r#"
+            WITH due AS (
+                SELECT id FROM webhook_deliveries
+                WHERE status = 'pending' AND next_attempt_at <= NOW()
+                ORDER BY next_attempt_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            UPDATE webhook_deliveries d
+            SET attempts = d.attempts + 1,
+                next_attempt_at = NOW() + make_interval(secs => $2)
+            FROM due
+            WHERE d.id = due.id
+            RETURNING d.id, d.webhook_id, d.event_type, d.payload, d.status, d.attempts,
+                      d.next_attempt_at, d.response_status, d.last_error, d.created_at,
+                      d.delivered_at
+            "#,
+        )
+        .bind(limit)
+        .bind(lease_seconds as f64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(deliveries)
+    }
+
+    pub async fn mark_delivered(
+        &self,
+        id: &str,
+        response_status: i32,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "UPDATE webhook_deliveries
+             SET status = $1, response_status = $2, last_error = NULL, delivered_at = NOW()
+             WHERE id = $3",
+        )
+        .bind(WebhookDeliveryStatus::Delivered)
+        .bind(response_status)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record a failed attempt, retrying at `retry_at` or giving up when it is `None`.
+    pub async fn mark_attempt_failed(
+        &self,
+        id: &str,
+        response_status: Option<i32>,
+        error: &str,
+        retry_at: Option<OffsetDateTime>,
+    ) -> Result<(), DatabaseError> {
+        let status = match retry_at {
+            Some(_) => WebhookDeliveryStatus::Pending,
+            None => WebhookDeliveryStatus::Failed,
+        };
+        sqlx::query(
+            "UPDATE webhook_deliveries
+             SET status = $1, response_status = $2, last_error = $3,
+                 next_attempt_at = COALESCE($4, next_attempt_at)
+             WHERE id = $5",
+        )
+        .bind(status)
+        .bind(response_status)
+        .bind(error)
+        .bind(retry_at)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Deliveries of a webhook, newest first.
+    pub async fn find_deliveries(
+        &self,
+        webhook_id: &str,
+        status: Option<WebhookDeliveryStatus>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, DatabaseError> {
+        let deliveries = sqlx::query_as::<_, WebhookDelivery>(
+            r#"
+            SELECT id, webhook_id, event_type, payload, status, attempts, next_attempt_at,
+                   response_status, last_error, created_at, delivered_at
+            FROM webhook_deliveries
+            WHERE webhook_id = $1 AND ($2::varchar IS NULL OR status = $2)
+            ORDER BY created_at DESC, id DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(webhook_id)
+        .bind(status)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(deliveries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webhook_event_names() {
+        for event in WebhookEvent::ALL {
+            assert_eq!(WebhookEvent::parse(event.as_str()), Some(event));
+            assert_eq!(
+                serde_json::to_value(event).unwrap(),
+                JsonValue::String(event.as_str().to_string())
+            );
+        }
+        assert_eq!(WebhookEvent::parse("alert.matched"), None);
+    }
+}
@@ -0,0 +1,239 @@
+//! Email notifications sent over SMTP, rendered from the templates in [`templates`].
+
+pub mod templates;
+
+use crate::config::SmtpConfig;
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::client::{Tls, TlsParameters};
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+#[derive(Debug, thiserror::Error)]
+pub enum EmailError {
+    #[error("Invalid email address: {0}")]
+    Address(#[from] lettre::address::AddressError),
+    #[error("Failed to build message: {0}")]
+    Message(#[from] lettre::error::Error),
+    #[error("SMTP error: {0}")]
+    Smtp(#[from] lettre::transport::smtp::Error),
+}
+
+/// A message template with `{{name}}` placeholders. Values substituted into the HTML body
+/// are escaped.
+#[derive(Debug, Clone, Copy)]
+pub struct EmailTemplate {
+    pub subject: &'static str,
+    pub text: &'static str,
+    pub html: &'static str,
+}
+
+impl EmailTemplate {
+    pub fn render(&self, vars: &[(&str, &str)]) -> EmailMessage {
+        self.render_with_parts(vars, &[])
+    }
+
+    /// Render with `parts`, messages rendered from other templates such as the items of a
+    /// list, substituted into the bodies as they are. Their subjects are unused.
+    pub fn render_with_parts(
+        &self,
+        vars: &[(&str, &str)],
+        parts: &[(&str, &EmailMessage)],
+    ) -> EmailMessage {
+        let part = |name: &str| {
+            parts
+                .iter()
+                .find(|(part, _)| *part == name)
+                .map(|(_, m)| *m)
+        };
+        let var = |name: &str| vars.iter().find(|(var, _)| *var == name).map(|(_, v)| *v);
+        EmailMessage {
+            subject: substitute(self.subject, |name| {
+                var(name).map(|value| value.replace(['\r', '\n'], " "))
+            }),
+            text: substitute(self.text, |name| {
+                part(name)
+                    .map(|message| message.text.clone())
+                    .or_else(|| var(name).map(str::to_string))
+            }),
+            html: substitute(self.html, |name| {
+                part(name)
+                    .map(|message| message.html.clone())
+                    .or_else(|| var(name).map(escape_html))
+            }),
+        }
+    }
+}
+
+/// A rendered message, sent as plain text with an HTML alternative.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailMessage {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+impl EmailMessage {
+    /// The bodies of `messages` one after the other, to substitute as a part.
+    pub fn concat(messages: &[EmailMessage]) -> EmailMessage {
+        EmailMessage {
+            subject: String::new(),
+            text: messages.iter().map(|m| m.text.as_str()).collect(),
+            html: messages.iter().map(|m| m.html.as_str()).collect(),
+        }
+    }
+}
+
+/// Replace each `{{name}}` with its `value` in one pass, so placeholders inside substituted
+/// values are left alone. Unknown placeholders are kept as they are.
+fn substitute(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let value = after
+            .find("}}")
+            .and_then(|end| value(after[..end].trim()).map(|value| (value, end)));
+        match value {
+            Some((value, end)) => {
+                rendered.push_str(&value);
+                rest = &after[end + 2..];
+            }
+            None => {
+                rendered.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Sends email through the configured SMTP server. Cheap to clone; clones share the
+/// connection pool.
+#[derive(Clone)]
+pub struct EmailNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    app_url: String,
+}
+
+impl EmailNotifier {
+    pub fn new(config: &SmtpConfig) -> Result<Self, EmailError> {
+        let tls = TlsParameters::new(config.host.clone())?;
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)
+            .port(config.port)
+            .tls(if config.secure {
+                Tls::Wrapper(tls)
+            } else {
+                Tls::Opportunistic(tls)
+            });
+        if let (Some(username), Some(password)) = (&config.username, &config.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from: config.from.parse()?,
+            app_url: config.app_url.clone(),
+        })
+    }
+
+    /// Base URL of the web app, for links in messages.
+    pub fn app_url(&self) -> &str {
+        &self.app_url
+    }
+
+    pub async fn send(&self, to: &str, message: &EmailMessage) -> Result<(), EmailError> {
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(to.parse()?)
+            .subject(&message.subject)
+            .multipart(MultiPart::alternative_plain_html(
+                message.text.clone(),
+                message.html.clone(),
+            ))?;
+        self.transport.send(email).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEMPLATE: EmailTemplate = EmailTemplate {
+        subject: "Sync of {{source}} failed",
+        text: "{{source}}: {{error}}",
+        html: "<p>{{source}}: {{error}}</p>",
+    };
+
+    #[test]
+    fn test_render_substitutes_placeholders() {
+        let message = TEMPLATE.render(&[("source", "Drive"), ("error", "Token expired")]);
+        assert_eq!(message.subject, "Sync of Drive failed");
+        assert_eq!(message.text, "Drive: Token expired");
+        assert_eq!(message.html, "<p>Drive: Token expired</p>");
+
+        let message = TEMPLATE.render(&[("source", "{{error}}")]);
+        assert_eq!(message.text, "{{error}}: {{error}}");
+    }
+
+    #[test]
+    fn test_render_escapes_html_and_subject() {
+        let message = TEMPLATE.render(&[
+            ("source", "R&D\nWiki"),
+            ("error", "<script>alert('x')</script>"),
+        ]);
+        assert_eq!(message.subject, "Sync of R&D Wiki failed");
+        assert_eq!(message.text, "R&D\nWiki: <script>alert('x')</script>");
+        assert_eq!(
+            message.html,
+            "<p>R&amp;D\nWiki: &lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt;</p>"
+        );
+    }
+
+    #[test]
+    fn test_render_with_parts_keeps_their_markup() {
+        const LIST: EmailTemplate = EmailTemplate {
+            subject: "{{count}} documents",
+            text: "Documents:\n{{documents}}",
+            html: "<ul>{{documents}}</ul>",
+        };
+        const ITEM: EmailTemplate = EmailTemplate {
+            subject: "",
+            text: "- {{title}}\n",
+            html: "<li>{{title}}</li>",
+        };
+
+        let items: Vec<EmailMessage> = ["Q3 <plan>", "Roadmap"]
+            .iter()
+            .map(|title| ITEM.render(&[("title", title)]))
+            .collect();
+        let message = LIST.render_with_parts(
+            &[("count", "2"), ("documents", "<b>unused</b>")],
+            &[("documents", &EmailMessage::concat(&items))],
+        );
+        assert_eq!(message.subject, "2 documents");
+        assert_eq!(message.text, "Documents:\n- Q3 <plan>\n- Roadmap\n");
+        assert_eq!(
+            message.html,
+            "<ul><li>Q3 &lt;plan&gt;</li><li>Roadmap</li></ul>"
+        );
+    }
+}
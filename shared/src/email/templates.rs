@@ -0,0 +1,28 @@
+//! Templates of the email notifications. Bodies live next to this file so they can be edited
+//! without touching code.
+
+use super::EmailTemplate;
+
+/// A source failed to sync. Placeholders: `source_name`, `source_type`, `sync_type`,
+/// `error`, `completed_at` and `source_url`.
+pub const SYNC_FAILED: EmailTemplate = EmailTemplate {
+    subject: "Omni: sync of {{source_name}} failed",
+    text: include_str!("templates/sync_failed.txt"),
+    html: include_str!("templates/sync_failed.html"),
+};
+
+/// The new documents matching a user's interests. Placeholders: `name`, `frequency`, `period`,
+/// `count`, `search_url` and the part `documents`, rendered from [`DIGEST_DOCUMENT`].
+pub const DIGEST: EmailTemplate = EmailTemplate {
+    subject: "Omni: your {{frequency}} digest of {{count}} documents",
+    text: include_str!("templates/digest.txt"),
+    html: include_str!("templates/digest.html"),
+};
+
+/// A document listed in a [`DIGEST`]. Placeholders: `title`, `url`, `source_name` and
+/// `updated_at`.
+pub const DIGEST_DOCUMENT: EmailTemplate = EmailTemplate {
+    subject: "",
+    text: include_str!("templates/digest_document.txt"),
+    html: include_str!("templates/digest_document.html"),
+};
@@ -0,0 +1,260 @@
+//! Blue/green migration between embedding models.
+//!
+//! While a migration is in progress the AI service embeds documents with both the current
+//! model (the one in `embedding_config`) and the target model, and backfills target
+//! embeddings for documents embedded before the migration started. Searches are served by the
+//! active model, the current one until the migration is cut over and the target one after,
+//! and a sampled percentage of semantic searches also runs against the other, shadow, model
+//! so the two can be compared.
+//!
+//! Cutover is a single flag in the migration's configuration row. Every search filters stored
+//! embeddings by the model that produced its query embedding, so no search mixes the two
+//! models while services pick up a flipped flag. Finalizing makes the target model the
+//! current one and drops the migration in one transaction.
+
+use crate::db::repositories::ConfigurationRepository;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use time::OffsetDateTime;
+
+/// Configuration key holding the current embedding model settings.
+pub const EMBEDDING_CONFIG_KEY: &str = "embedding_config";
+
+/// Configuration key holding the migration in progress, absent when there is none.
+pub const EMBEDDING_MIGRATION_KEY: &str = "embedding_migration";
+
+pub const EMBEDDING_PROVIDERS: [&str; 5] = ["local", "jina", "openai", "cohere", "bedrock"];
+
+/// Embedding model settings, in the shape the admin UI stores under `embedding_config`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingModelConfig {
+    pub provider: String,
+    #[serde(default)]
+    pub api_key: Option<String>,
+    #[serde(default)]
+    pub model: String,
+    #[serde(default)]
+    pub api_url: Option<String>,
+    #[serde(default)]
+    pub dimensions: Option<i32>,
+    #[serde(default)]
+    pub max_model_len: Option<i32>,
+}
+
+impl EmbeddingModelConfig {
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if !EMBEDDING_PROVIDERS.contains(&self.provider.as_str()) {
+            return Err(format!(
+                "Unknown embedding provider '{}', expected one of: {}",
+                self.provider,
+                EMBEDDING_PROVIDERS.join(", ")
+            ));
+        }
+        if self.model.trim().is_empty() {
+            return Err("An embedding model is required".to_string());
+        }
+        let needs_api_key = matches!(self.provider.as_str(), "jina" | "openai" | "cohere");
+        if needs_api_key && self.api_key.as_deref().unwrap_or("").is_empty() {
+            return Err(format!(
+                "An API key is required for the {} provider",
+                self.provider
+            ));
+        }
+        Ok(())
+    }
+
+    /// The settings without the API key, for returning from admin endpoints.
+    pub fn redacted(&self) -> Self {
+        Self {
+            api_key: None,
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingMigration {
+    pub target: EmbeddingModelConfig,
+    /// Percentage of semantic searches, from 0 to 100, also run against the shadow model.
+    pub shadow_search_percentage: f64,
+    /// Whether searches are served by the target model.
+    pub cutover: bool,
+    #[serde(with = "time::serde::iso8601")]
+    pub started_at: OffsetDateTime,
+}
+
+impl EmbeddingMigration {
+    pub fn validate_percentage(percentage: f64) -> std::result::Result<(), String> {
+        if !(0.0..=100.0).contains(&percentage) {
+            return Err(format!(
+                "Shadow search percentage must be between 0 and 100, got {}",
+                percentage
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fraction of semantic searches to also run against the shadow model.
+    pub fn shadow_sample_rate(&self) -> f64 {
+        (self.shadow_search_percentage / 100.0).clamp(0.0, 1.0)
+    }
+}
+
+pub async fn load_migration(pool: &PgPool) -> Result<Option<EmbeddingMigration>> {
+    let repo = ConfigurationRepository::new(pool);
+    match repo.get(EMBEDDING_MIGRATION_KEY).await? {
+        Some(value) => Ok(Some(serde_json::from_value(value)?)),
+        None => Ok(None),
+    }
+}
+
+/// The current embedding model settings, or `None` when the AI service runs on the settings
+/// from its environment.
+pub async fn load_current_config(pool: &PgPool) -> Result<Option<EmbeddingModelConfig>> {
+    let repo = ConfigurationRepository::new(pool);
+    match repo.get(EMBEDDING_CONFIG_KEY).await? {
+        Some(value) => Ok(Some(serde_json::from_value(value)?)),
+        None => Ok(None),
+    }
+}
+
+/// Store a new migration. Returns `false`, storing nothing, when one is already in progress.
+pub async fn start_migration(pool: &PgPool, migration: &EmbeddingMigration) -> Result<bool> {
+    let repo = ConfigurationRepository::new(pool);
+    Ok(repo
+        .insert_if_absent(EMBEDDING_MIGRATION_KEY, &serde_json::to_value(migration)?)
+        .await?)
+}
+
+pub async fn save_migration(pool: &PgPool, migration: &EmbeddingMigration) -> Result<()> {
+    let repo = ConfigurationRepository::new(pool);
+    repo.set(EMBEDDING_MIGRATION_KEY, &serde_json::to_value(migration)?)
+        .await?;
+    Ok(())
+}
+
+/// Drop a migration that has not been cut over, returning it. Returns `None` when there is
+/// no migration or it has been cut over.
+pub async fn cancel_migration(pool: &PgPool) -> Result<Option<EmbeddingMigration>> {
+    let value: Option<serde_json::Value> = sqlx::query_scalar(
+        r#"
+        DELETE FROM configuration
+        WHERE key = $1 AND NOT COALESCE((value->>'cutover')::boolean, false)
+        RETURNING value
+        "#,
+    )
+    .bind(EMBEDDING_MIGRATION_KEY)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(value.map(serde_json::from_value).transpose()?)
+}
+
+/// Make the target model of a cut-over migration the current model and drop the migration,
+/// in one transaction, returning it. Returns `None` when there is no migration or it has not
+/// been cut over.
+pub async fn finalize_migration(pool: &PgPool) -> Result<Option<EmbeddingMigration>> {
+    let mut tx = pool.begin().await?;
+
+    let value: Option<serde_json::Value> = sqlx::query_scalar(
+        r#"
+        DELETE FROM configuration
+        WHERE key = $1 AND COALESCE((value->>'cutover')::boolean, false)
+        RETURNING value
+        "#,
+    )
+    .bind(EMBEDDING_MIGRATION_KEY)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let migration: EmbeddingMigration = serde_json::from_value(value)?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO configuration (key, value)
+        VALUES ($1, $2)
+        ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value
+        "#,
+    )
+    .bind(EMBEDDING_CONFIG_KEY)
+    .bind(serde_json::to_value(&migration.target)?)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some(migration))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_model_config_matches_admin_ui_shape() {
+        let config: EmbeddingModelConfig = serde_json::from_value(json!({
+            "provider": "openai",
+            "apiKey": "sk-test",
+            "model": "text-embedding-3-large",
+            "apiUrl": null,
+            "dimensions": 1024,
+            "maxModelLen": null
+        }))
+        .unwrap();
+        assert_eq!(config.model, "text-embedding-3-large");
+        assert_eq!(config.dimensions, Some(1024));
+        assert!(config.validate().is_ok());
+
+        let redacted = serde_json::to_value(config.redacted()).unwrap();
+        assert!(redacted["apiKey"].is_null());
+        assert_eq!(redacted["maxModelLen"], json!(null));
+    }
+
+    #[test]
+    fn test_model_config_validation() {
+        let config = EmbeddingModelConfig {
+            provider: "jina".to_string(),
+            model: "jina-embeddings-v4".to_string(),
+            ..Default::default()
+        };
+        assert!(config.validate().unwrap_err().contains("API key"));
+
+        let config = EmbeddingModelConfig {
+            provider: "local".to_string(),
+            model: "".to_string(),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = EmbeddingModelConfig {
+            provider: "mystery".to_string(),
+            model: "m".to_string(),
+            ..Default::default()
+        };
+        assert!(config.validate().unwrap_err().contains("Unknown"));
+    }
+
+    #[test]
+    fn test_shadow_sample_rate() {
+        assert!(EmbeddingMigration::validate_percentage(101.0).is_err());
+        assert!(EmbeddingMigration::validate_percentage(-1.0).is_err());
+        assert!(EmbeddingMigration::validate_percentage(12.5).is_ok());
+
+        let migration = EmbeddingMigration {
+            target: EmbeddingModelConfig::default(),
+            shadow_search_percentage: 25.0,
+            cutover: false,
+            started_at: OffsetDateTime::now_utc(),
+        };
+        assert_eq!(migration.shadow_sample_rate(), 0.25);
+
+        let value = serde_json::to_value(&migration).unwrap();
+        assert_eq!(value["shadowSearchPercentage"], json!(25.0));
+        assert_eq!(value["cutover"], json!(false));
+    }
+}
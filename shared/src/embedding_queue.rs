@@ -117,6 +117,65 @@ impl EmbeddingQueue {
         Ok(ids)
     }
 
+    /// Queue every document, or every document of one source, for re-embedding. Documents
+    /// that already have an embedding pending are skipped. Returns the number queued.
+    pub async fn enqueue_reembedding(&self, source_id: Option<&str>) -> Result<u64> {
+        const BATCH_SIZE: i64 = 1000;
+        let mut after = String::new();
+        let mut queued = 0;
+
+        loop {
+            let document_ids: Vec<String> = sqlx::query_scalar(
+                r#"
+                SELECT d.id FROM documents d
+                WHERE d.id > $1
+                  AND ($2::text IS NULL OR d.source_id = $2)
+                  AND NOT EXISTS (
+                      SELECT 1 FROM embedding_queue q
+                      WHERE q.document_id = d.id AND q.status IN ('pending', 'processing')
+                  )
+                ORDER BY d.id
+                LIMIT $3
+                "#,
+            )
+            .bind(&after)
+            .bind(source_id)
+            .bind(BATCH_SIZE)
+            .fetch_all(&self.pool)
+            .await?;
+
+            let Some(last) = document_ids.last() else {
+                break;
+            };
+            after = last.clone();
+            let ids: Vec<String> = document_ids
+                .iter()
+                .map(|_| Ulid::new().to_string())
+                .collect();
+
+            let mut tx = self.pool.begin().await?;
+            sqlx::query(
+                "INSERT INTO embedding_queue (id, document_id) SELECT * FROM UNNEST($1::text[], $2::text[])",
+            )
+            .bind(&ids)
+            .bind(&document_ids)
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query("UPDATE documents SET embedding_status = 'pending' WHERE id = ANY($1)")
+                .bind(&document_ids)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+
+            queued += document_ids.len() as u64;
+            if (document_ids.len() as i64) < BATCH_SIZE {
+                break;
+            }
+        }
+
+        Ok(queued)
+    }
+
     pub async fn dequeue_batch(&self, batch_size: i32) -> Result<Vec<EmbeddingQueueItem>> {
         let items = sqlx::query_as::<_, EmbeddingQueueItem>(
             r#"
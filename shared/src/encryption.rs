@@ -2,11 +2,23 @@ use anyhow::{anyhow, Result};
 use base64::{engine::general_purpose, Engine as _};
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
-use std::env;
+
+use crate::secrets;
 
 /// Encryption service for sensitive data using AES-256-GCM
+///
+/// The current master key comes from `ENCRYPTION_KEY`. Master keys retired by a rotation
+/// can be listed in `ENCRYPTION_KEY_PREVIOUS` (comma-separated) so that data encrypted
+/// under them stays readable until it has been re-encrypted.
 pub struct EncryptionService {
     key: [u8; 32], // 256-bit key
+    key_id: String,
+    previous_keys: Vec<MasterKey>,
+}
+
+struct MasterKey {
+    id: String,
+    key: [u8; 32],
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,14 +28,23 @@ pub struct EncryptedData {
     pub salt: String,  // Base64 encoded salt
 }
 
+/// Envelope-encrypted data. The payload is encrypted with a random per-value data key,
+/// which is itself encrypted ("wrapped") with the master key identified by `key_id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedEnvelope {
+    pub key_id: String,
+    pub wrapped_key: EncryptedData,
+    pub data: EncryptedData,
+}
+
 impl EncryptionService {
     /// Create a new encryption service with a key derived from environment variables
     pub fn new() -> Result<Self> {
-        let master_key = env::var("ENCRYPTION_KEY")
-            .map_err(|_| anyhow!("ENCRYPTION_KEY environment variable not set"))?;
+        let master_key = secrets::get("ENCRYPTION_KEY")
+            .ok_or_else(|| anyhow!("ENCRYPTION_KEY environment variable not set"))?;
 
-        let base_salt = env::var("ENCRYPTION_SALT")
-            .map_err(|_| anyhow!("ENCRYPTION_SALT environment variable not set"))?;
+        let base_salt = secrets::get("ENCRYPTION_SALT")
+            .ok_or_else(|| anyhow!("ENCRYPTION_SALT environment variable not set"))?;
 
         if master_key.len() < 32 {
             return Err(anyhow!(
@@ -40,7 +61,27 @@ impl EncryptionService {
         // Use HKDF to derive a proper 256-bit key from the master key and salt
         let key = Self::derive_key(&master_key, &base_salt)?;
 
-        Ok(Self { key })
+        let mut previous_keys = Vec::new();
+        if let Some(previous) = secrets::get("ENCRYPTION_KEY_PREVIOUS") {
+            for previous_key in previous.split(',').map(str::trim).filter(|k| !k.is_empty()) {
+                if previous_key.len() < 32 {
+                    return Err(anyhow!(
+                        "Keys in ENCRYPTION_KEY_PREVIOUS must be at least 32 characters long"
+                    ));
+                }
+                let key = Self::derive_key(previous_key, &base_salt)?;
+                previous_keys.push(MasterKey {
+                    id: Self::key_id(&key)?,
+                    key,
+                });
+            }
+        }
+
+        Ok(Self {
+            key,
+            key_id: Self::key_id(&key)?,
+            previous_keys,
+        })
     }
 
     /// Derive a 256-bit key using HKDF
@@ -61,8 +102,99 @@ impl EncryptionService {
         Ok(key)
     }
 
+    /// Public identifier of a derived master key, stored alongside envelopes so the key
+    /// needed for decryption can be found without trial decryption.
+    fn key_id(key: &[u8; 32]) -> Result<String> {
+        use ring::hkdf::{self, KeyType, HKDF_SHA256};
+
+        struct KeyIdLength;
+        impl KeyType for KeyIdLength {
+            fn len(&self) -> usize {
+                8
+            }
+        }
+
+        let prk = hkdf::Salt::new(HKDF_SHA256, &[]).extract(key);
+        let okm = prk
+            .expand(&[b"omni-key-id"], KeyIdLength)
+            .map_err(|_| anyhow!("Failed to derive key id"))?;
+
+        let mut id = [0u8; 8];
+        okm.fill(&mut id)
+            .map_err(|_| anyhow!("Failed to fill key id buffer"))?;
+
+        Ok(id.iter().map(|b| format!("{:02x}", b)).collect())
+    }
+
+    /// Identifier of the master key new data is encrypted with
+    pub fn current_key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    fn master_keys(&self) -> impl Iterator<Item = &[u8; 32]> {
+        std::iter::once(&self.key).chain(self.previous_keys.iter().map(|k| &k.key))
+    }
+
     /// Encrypt data using AES-256-GCM
     pub fn encrypt(&self, data: &str) -> Result<EncryptedData> {
+        Self::encrypt_with_key(&self.key, data.as_bytes())
+    }
+
+    /// Decrypt data using AES-256-GCM. Data encrypted under a previous master key is
+    /// decrypted as long as that key is still configured.
+    pub fn decrypt(&self, encrypted_data: &EncryptedData) -> Result<String> {
+        let plaintext = self
+            .master_keys()
+            .find_map(|key| Self::decrypt_with_key(key, encrypted_data).ok())
+            .ok_or_else(|| anyhow!("Failed to decrypt data"))?;
+
+        String::from_utf8(plaintext).map_err(|_| anyhow!("Decrypted data is not valid UTF-8"))
+    }
+
+    /// Encrypt data with a fresh data key, wrapped with the current master key
+    pub fn seal(&self, data: &str) -> Result<EncryptedEnvelope> {
+        let mut data_key = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut data_key);
+
+        Ok(EncryptedEnvelope {
+            key_id: self.key_id.clone(),
+            wrapped_key: Self::encrypt_with_key(&self.key, &data_key)?,
+            data: Self::encrypt_with_key(&data_key, data.as_bytes())?,
+        })
+    }
+
+    /// Decrypt an envelope produced by [`EncryptionService::seal`]
+    pub fn open(&self, envelope: &EncryptedEnvelope) -> Result<String> {
+        let master_key = if envelope.key_id == self.key_id {
+            &self.key
+        } else {
+            &self
+                .previous_keys
+                .iter()
+                .find(|k| k.id == envelope.key_id)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Master key {} is not configured, add it to ENCRYPTION_KEY_PREVIOUS",
+                        envelope.key_id
+                    )
+                })?
+                .key
+        };
+
+        let data_key: [u8; 32] = Self::decrypt_with_key(master_key, &envelope.wrapped_key)?
+            .try_into()
+            .map_err(|_| anyhow!("Invalid data key length"))?;
+        let plaintext = Self::decrypt_with_key(&data_key, &envelope.data)?;
+
+        String::from_utf8(plaintext).map_err(|_| anyhow!("Decrypted data is not valid UTF-8"))
+    }
+
+    /// Whether an envelope was sealed with a master key other than the current one
+    pub fn needs_rotation(&self, envelope: &EncryptedEnvelope) -> bool {
+        envelope.key_id != self.key_id
+    }
+
+    fn encrypt_with_key(key: &[u8; 32], data: &[u8]) -> Result<EncryptedData> {
         use ring::aead::{self, BoundKey, SealingKey, UnboundKey, AES_256_GCM};
 
         // Generate random nonce (96 bits for GCM)
@@ -74,7 +206,7 @@ impl EncryptionService {
         rand::thread_rng().fill_bytes(&mut salt_bytes);
 
         // Derive operation-specific key using the salt
-        let operation_key = Self::derive_operation_key(&self.key, &salt_bytes)?;
+        let operation_key = Self::derive_operation_key(key, &salt_bytes)?;
 
         // Create sealing key
         let unbound_key = UnboundKey::new(&AES_256_GCM, &operation_key)
@@ -83,7 +215,7 @@ impl EncryptionService {
         let mut sealing_key = SealingKey::new(unbound_key, OneNonceSequence(Some(nonce)));
 
         // Encrypt the data
-        let mut in_out = data.as_bytes().to_vec();
+        let mut in_out = data.to_vec();
         sealing_key
             .seal_in_place_append_tag(aead::Aad::empty(), &mut in_out)
             .map_err(|_| anyhow!("Failed to encrypt data"))?;
@@ -100,8 +232,7 @@ impl EncryptionService {
         })
     }
 
-    /// Decrypt data using AES-256-GCM
-    pub fn decrypt(&self, encrypted_data: &EncryptedData) -> Result<String> {
+    fn decrypt_with_key(key: &[u8; 32], encrypted_data: &EncryptedData) -> Result<Vec<u8>> {
         use ring::aead::{self, BoundKey, OpeningKey, UnboundKey, AES_256_GCM};
 
         // Decode base64 data
@@ -123,7 +254,7 @@ impl EncryptionService {
         }
 
         // Derive operation-specific key using the salt
-        let operation_key = Self::derive_operation_key(&self.key, &salt_bytes)?;
+        let operation_key = Self::derive_operation_key(key, &salt_bytes)?;
 
         // Create opening key
         let unbound_key = UnboundKey::new(&AES_256_GCM, &operation_key)
@@ -138,8 +269,7 @@ impl EncryptionService {
             .open_in_place(aead::Aad::empty(), &mut in_out)
             .map_err(|_| anyhow!("Failed to decrypt data"))?;
 
-        String::from_utf8(plaintext.to_vec())
-            .map_err(|_| anyhow!("Decrypted data is not valid UTF-8"))
+        Ok(plaintext.to_vec())
     }
 
     /// Derive operation-specific key from master key and salt
@@ -160,6 +290,33 @@ impl EncryptionService {
         Ok(key)
     }
 
+    /// Sign data with HMAC-SHA256, returning a base64 signature. The signing key is derived
+    /// from the master key, so signatures can be verified by any service sharing it.
+    pub fn sign(&self, data: &[u8]) -> String {
+        let tag = ring::hmac::sign(&Self::signing_key(&self.key), data);
+        general_purpose::STANDARD.encode(tag.as_ref())
+    }
+
+    /// Verify a signature produced by [`EncryptionService::sign`] under any configured
+    /// master key
+    pub fn verify_signature(&self, data: &[u8], signature: &str) -> bool {
+        let Ok(tag) = general_purpose::STANDARD.decode(signature) else {
+            return false;
+        };
+        self.master_keys()
+            .any(|key| ring::hmac::verify(&Self::signing_key(key), data, &tag).is_ok())
+    }
+
+    fn signing_key(master_key: &[u8; 32]) -> ring::hmac::Key {
+        use ring::hkdf::{self, HKDF_SHA256};
+
+        let prk = hkdf::Salt::new(HKDF_SHA256, &[]).extract(master_key);
+        let okm = prk
+            .expand(&[b"omni-signing-key"], ring::hmac::HMAC_SHA256)
+            .expect("HMAC-SHA256 key length is a valid HKDF output length");
+        ring::hmac::Key::from(okm)
+    }
+
     /// Encrypt JSON data (convenience method)
     pub fn encrypt_json<T: Serialize>(&self, data: &T) -> Result<EncryptedData> {
         let json_str =
@@ -175,6 +332,22 @@ impl EncryptionService {
         let json_str = self.decrypt(encrypted_data)?;
         serde_json::from_str(&json_str).map_err(|e| anyhow!("Failed to deserialize data: {}", e))
     }
+
+    /// Seal JSON data in an envelope (convenience method)
+    pub fn seal_json<T: Serialize>(&self, data: &T) -> Result<EncryptedEnvelope> {
+        let json_str =
+            serde_json::to_string(data).map_err(|e| anyhow!("Failed to serialize data: {}", e))?;
+        self.seal(&json_str)
+    }
+
+    /// Open a JSON envelope (convenience method)
+    pub fn open_json<T: for<'de> Deserialize<'de>>(
+        &self,
+        envelope: &EncryptedEnvelope,
+    ) -> Result<T> {
+        let json_str = self.open(envelope)?;
+        serde_json::from_str(&json_str).map_err(|e| anyhow!("Failed to deserialize data: {}", e))
+    }
 }
 
 /// Helper struct for nonce sequence (ring requirement)
@@ -308,4 +481,62 @@ mod tests {
         std::env::set_var("ENCRYPTION_SALT", "short");
         assert!(EncryptionService::new().is_err());
     }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let _lock = TEST_ENV_LOCK.lock().unwrap();
+        std::env::set_var(
+            "ENCRYPTION_KEY",
+            "test_master_key_that_is_long_enough_32_chars",
+        );
+        std::env::set_var("ENCRYPTION_SALT", "test_salt_16_chars");
+
+        let service = EncryptionService::new().unwrap();
+        let signature = service.sign(b"report");
+
+        assert_eq!(signature, service.sign(b"report"));
+        assert!(service.verify_signature(b"report", &signature));
+        assert!(!service.verify_signature(b"tampered", &signature));
+        assert!(!service.verify_signature(b"report", "not base64!"));
+    }
+
+    #[test]
+    fn test_envelope_survives_key_rotation() {
+        let _lock = TEST_ENV_LOCK.lock().unwrap();
+        std::env::set_var(
+            "ENCRYPTION_KEY",
+            "old_master_key_that_is_long_enough_32_chars",
+        );
+        std::env::set_var("ENCRYPTION_SALT", "test_salt_16_chars");
+        std::env::remove_var("ENCRYPTION_KEY_PREVIOUS");
+
+        let old_service = EncryptionService::new().unwrap();
+        let envelope = old_service.seal("api-token").unwrap();
+        let legacy = old_service.encrypt("legacy-token").unwrap();
+        assert_eq!(envelope.key_id, old_service.current_key_id());
+        assert_eq!(old_service.open(&envelope).unwrap(), "api-token");
+
+        std::env::set_var(
+            "ENCRYPTION_KEY",
+            "new_master_key_that_is_long_enough_32_chars",
+        );
+        let without_previous = EncryptionService::new().unwrap();
+        assert!(without_previous.open(&envelope).is_err());
+
+        std::env::set_var(
+            "ENCRYPTION_KEY_PREVIOUS",
+            "old_master_key_that_is_long_enough_32_chars",
+        );
+        let rotated = EncryptionService::new().unwrap();
+        std::env::remove_var("ENCRYPTION_KEY_PREVIOUS");
+
+        assert_ne!(rotated.current_key_id(), old_service.current_key_id());
+        assert!(rotated.needs_rotation(&envelope));
+        assert_eq!(rotated.open(&envelope).unwrap(), "api-token");
+        assert_eq!(rotated.decrypt(&legacy).unwrap(), "legacy-token");
+
+        let resealed = rotated.seal(&rotated.open(&envelope).unwrap()).unwrap();
+        assert!(!rotated.needs_rotation(&resealed));
+        assert_eq!(rotated.open(&resealed).unwrap(), "api-token");
+    }
 }
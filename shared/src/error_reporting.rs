@@ -0,0 +1,163 @@
+//! Sentry-compatible error reporting.
+//!
+//! When a DSN is configured, panics and `error!` events from every service are sent to the
+//! error tracker, with `warn!`/`info!` events attached as breadcrumbs. Events are tagged
+//! with the emitting service and, when available from the event or its enclosing spans,
+//! the `source_id`, `source_type` and `sync_run_id` they relate to. Use [`sync_span`] to
+//! attach sync context to everything that happens during a sync.
+
+use crate::models::SourceType;
+use crate::telemetry::TelemetryConfig;
+use sentry::protocol::{Context, Event};
+use sentry::types::Dsn;
+use std::borrow::Cow;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tracing::{Span, Subscriber};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Tracing fields promoted to searchable tags on reported events.
+const CONTEXT_TAGS: &[&str] = &["source_id", "source_type", "sync_run_id"];
+
+/// Key under which sentry-tracing stores event and span fields.
+const TRACING_FIELDS_CONTEXT: &str = "Rust Tracing Fields";
+
+static CLIENT_GUARD: OnceLock<sentry::ClientInitGuard> = OnceLock::new();
+
+/// Initialize the error reporting client. Returns `false` if no DSN is configured, in which
+/// case reporting is disabled and [`layer`] should not be installed.
+pub fn init(config: &TelemetryConfig) -> anyhow::Result<bool> {
+    let Some(dsn) = config.sentry_dsn.as_deref().filter(|dsn| !dsn.is_empty()) else {
+        return Ok(false);
+    };
+    let dsn: Dsn = dsn
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid SENTRY_DSN: {}", e))?;
+
+    let service_name = config.service_name.clone();
+    let deployment_id = config.deployment_id.clone();
+
+    let guard = sentry::init(sentry::ClientOptions {
+        dsn: Some(dsn),
+        release: Some(Cow::Owned(format!(
+            "{}@{}",
+            config.service_name, config.service_version
+        ))),
+        environment: Some(Cow::Owned(config.environment.clone())),
+        attach_stacktrace: true,
+        before_send: Some(Arc::new(move |event| {
+            Some(tag_event(event, &service_name, &deployment_id))
+        })),
+        ..Default::default()
+    });
+
+    let _ = CLIENT_GUARD.set(guard);
+    Ok(true)
+}
+
+/// Tracing layer forwarding error-level events to the error tracker.
+pub fn layer<S>() -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    sentry::integrations::tracing::layer()
+}
+
+/// Span carrying sync context. Errors logged while it is entered are tagged with the
+/// source, its type and the sync run they occurred in.
+pub fn sync_span(source_id: &str, source_type: Option<SourceType>, sync_run_id: &str) -> Span {
+    let span = tracing::info_span!(
+        "sync_run",
+        source_id = %source_id,
+        source_type = tracing::field::Empty,
+        sync_run_id = %sync_run_id
+    );
+    // Tagged by its serialized name, as stored in the database
+    if let Some(source_type) = source_type.and_then(|t| serde_json::to_value(t).ok()) {
+        if let Some(source_type) = source_type.as_str() {
+            span.record("source_type", source_type);
+        }
+    }
+    span
+}
+
+/// Flush pending events before the process exits.
+pub fn flush(timeout: Duration) {
+    if let Some(client) = sentry::Hub::main().client() {
+        client.flush(Some(timeout));
+    }
+}
+
+fn tag_event(mut event: Event<'static>, service_name: &str, deployment_id: &str) -> Event<'static> {
+    event
+        .tags
+        .insert("service".to_string(), service_name.to_string());
+    event
+        .tags
+        .insert("deployment.id".to_string(), deployment_id.to_string());
+
+    // Span fields are keyed as "<span name>:<field>", event fields by their bare name
+    if let Some(Context::Other(fields)) = event.contexts.get(TRACING_FIELDS_CONTEXT) {
+        for (key, value) in fields {
+            let name = key.rsplit(':').next().unwrap_or(key);
+            if !CONTEXT_TAGS.contains(&name) {
+                continue;
+            }
+            if let Some(value) = value.as_str() {
+                event
+                    .tags
+                    .entry(name.to_string())
+                    .or_insert_with(|| value.to_string());
+            }
+        }
+    }
+
+    event
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_tag_event_promotes_sync_context() {
+        let mut fields = BTreeMap::new();
+        fields.insert("sync_run:source_id".to_string(), json!("src_1"));
+        fields.insert("sync_run:source_type".to_string(), json!("google_drive"));
+        fields.insert("sync_run:sync_run_id".to_string(), json!("run_1"));
+        fields.insert("document_count".to_string(), json!(3));
+
+        let mut event = Event::default();
+        event
+            .contexts
+            .insert(TRACING_FIELDS_CONTEXT.to_string(), Context::Other(fields));
+
+        let event = tag_event(event, "omni-indexer", "deploy_1");
+        assert_eq!(event.tags["service"], "omni-indexer");
+        assert_eq!(event.tags["deployment.id"], "deploy_1");
+        assert_eq!(event.tags["source_id"], "src_1");
+        assert_eq!(event.tags["source_type"], "google_drive");
+        assert_eq!(event.tags["sync_run_id"], "run_1");
+        assert!(!event.tags.contains_key("document_count"));
+    }
+
+    #[test]
+    fn test_tag_event_prefers_existing_tags() {
+        let mut fields = BTreeMap::new();
+        fields.insert("source_id".to_string(), json!("from_field"));
+
+        let mut event = Event::default();
+        event
+            .tags
+            .insert("source_id".to_string(), "from_tag".to_string());
+        event
+            .contexts
+            .insert(TRACING_FIELDS_CONTEXT.to_string(), Context::Other(fields));
+
+        let event = tag_event(event, "omni-searcher", "deploy_1");
+        assert_eq!(event.tags["source_id"], "from_tag");
+    }
+}
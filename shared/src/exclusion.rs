@@ -0,0 +1,313 @@
+//! Workspace-level content exclusion rules.
+//!
+//! Admins list folders, MIME types, labels and sender domains whose content must never be
+//! indexed. Connectors fetch the rules through the SDK when a sync starts and skip matching
+//! items before downloading or emitting them, so excluded content never leaves the source.
+
+use crate::db::repositories::ConfigurationRepository;
+use crate::models::SourceType;
+use anyhow::Result;
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+/// Configuration key holding the list of rules.
+pub const EXCLUSION_RULES_KEY: &str = "content_exclusion_rules";
+
+const GLOB_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// An item is excluded when the rule applies to its source type and any one of the listed
+/// folders, MIME types, labels or sender domains matches it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExclusionRule {
+    pub name: String,
+    /// Source types the rule applies to, all of them when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub source_types: Vec<SourceType>,
+    /// Globs on the item path, e.g. `/HR/**` for everything under the top-level HR folder.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub path_globs: Vec<String>,
+    /// Exact MIME types, or a type with a wildcard subtype such as `video/*`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mime_types: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    /// Sender domains, also matching their subdomains.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sender_domains: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExclusionRules {
+    pub rules: Vec<ExclusionRule>,
+}
+
+/// What a connector knows about an item before fetching its content.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExclusionCandidate<'a> {
+    pub path: Option<&'a str>,
+    pub mime_type: Option<&'a str>,
+    pub labels: &'a [String],
+    /// Sender address, either bare or in `Name <address>` form.
+    pub sender: Option<&'a str>,
+}
+
+struct CompiledRule {
+    rule: ExclusionRule,
+    path_globs: Vec<Pattern>,
+}
+
+impl CompiledRule {
+    fn compile(rule: ExclusionRule) -> std::result::Result<Self, String> {
+        if rule.name.trim().is_empty() {
+            return Err("Exclusion rule name cannot be empty".to_string());
+        }
+        if rule.path_globs.is_empty()
+            && rule.mime_types.is_empty()
+            && rule.labels.is_empty()
+            && rule.sender_domains.is_empty()
+        {
+            return Err(format!(
+                "Exclusion rule '{}' must set path_globs, mime_types, labels or sender_domains",
+                rule.name
+            ));
+        }
+        if let Some(mime_type) = rule.mime_types.iter().find(|m| !m.contains('/')) {
+            return Err(format!(
+                "Invalid MIME type '{}' in rule '{}'",
+                mime_type, rule.name
+            ));
+        }
+
+        let path_globs = rule
+            .path_globs
+            .iter()
+            .map(|glob| Pattern::new(glob))
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|e| format!("Invalid path glob in rule '{}': {}", rule.name, e))?;
+
+        Ok(Self { rule, path_globs })
+    }
+
+    fn matches(&self, source_type: SourceType, candidate: &ExclusionCandidate) -> bool {
+        if !self.rule.source_types.is_empty() && !self.rule.source_types.contains(&source_type) {
+            return false;
+        }
+
+        let path_matches = candidate.path.is_some_and(|path| {
+            self.path_globs
+                .iter()
+                .any(|glob| glob.matches_with(path, GLOB_OPTIONS))
+        });
+
+        let mime_matches = candidate.mime_type.is_some_and(|mime_type| {
+            self.rule
+                .mime_types
+                .iter()
+                .any(|pattern| mime_type_matches(pattern, mime_type))
+        });
+
+        let label_matches = candidate.labels.iter().any(|label| {
+            self.rule
+                .labels
+                .iter()
+                .any(|excluded| excluded.eq_ignore_ascii_case(label))
+        });
+
+        let sender_matches = candidate
+            .sender
+            .and_then(sender_domain)
+            .is_some_and(|domain| {
+                self.rule
+                    .sender_domains
+                    .iter()
+                    .any(|excluded| domain_matches(excluded, &domain))
+            });
+
+        path_matches || mime_matches || label_matches || sender_matches
+    }
+}
+
+fn mime_type_matches(pattern: &str, mime_type: &str) -> bool {
+    let mime_type = mime_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let pattern = pattern.trim().to_ascii_lowercase();
+
+    match pattern.strip_suffix("/*") {
+        Some(top_level) => mime_type
+            .split_once('/')
+            .is_some_and(|(mime_top_level, _)| mime_top_level == top_level),
+        None => mime_type == pattern,
+    }
+}
+
+fn sender_domain(sender: &str) -> Option<String> {
+    let address = match (sender.rfind('<'), sender.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &sender[start + 1..end],
+        _ => sender,
+    };
+    address
+        .trim()
+        .rsplit_once('@')
+        .map(|(_, domain)| domain.to_ascii_lowercase())
+}
+
+fn domain_matches(excluded: &str, domain: &str) -> bool {
+    let excluded = excluded.trim().trim_start_matches('@').to_ascii_lowercase();
+    domain == excluded || domain.ends_with(&format!(".{}", excluded))
+}
+
+/// The compiled set of exclusion rules a connector checks items against.
+#[derive(Default)]
+pub struct ContentExclusions {
+    rules: Vec<CompiledRule>,
+}
+
+impl ContentExclusions {
+    /// Compile a rule set, rejecting rules without criteria or with an invalid glob.
+    pub fn new(rules: ExclusionRules) -> std::result::Result<Self, String> {
+        let rules = rules
+            .rules
+            .into_iter()
+            .map(CompiledRule::compile)
+            .collect::<std::result::Result<_, _>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Name of the first rule excluding the item, if any.
+    pub fn excluded_by(
+        &self,
+        source_type: SourceType,
+        candidate: &ExclusionCandidate,
+    ) -> Option<&str> {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(source_type, candidate))
+            .map(|rule| rule.rule.name.as_str())
+    }
+}
+
+pub async fn load_rules(pool: &PgPool) -> Result<ExclusionRules> {
+    let repo = ConfigurationRepository::new(pool);
+    match repo.get(EXCLUSION_RULES_KEY).await? {
+        Some(value) => Ok(serde_json::from_value(value)?),
+        None => Ok(ExclusionRules::default()),
+    }
+}
+
+pub async fn save_rules(pool: &PgPool, rules: &ExclusionRules) -> Result<()> {
+    let repo = ConfigurationRepository::new(pool);
+    repo.set(EXCLUSION_RULES_KEY, &serde_json::to_value(rules)?)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn exclusions(rules: serde_json::Value) -> ContentExclusions {
+        ContentExclusions::new(serde_json::from_value(json!({ "rules": rules })).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_path_and_mime_type_rules() {
+        let exclusions = exclusions(json!([
+            { "name": "hr", "source_types": ["google_drive"], "path_globs": ["/HR/**"] },
+            { "name": "media", "mime_types": ["video/*", "application/zip"] }
+        ]));
+        let at_path = |path| ExclusionCandidate {
+            path: Some(path),
+            ..Default::default()
+        };
+        let with_mime_type = |mime_type| ExclusionCandidate {
+            mime_type: Some(mime_type),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            exclusions.excluded_by(SourceType::GoogleDrive, &at_path("/hr/payroll/2024.xlsx")),
+            Some("hr")
+        );
+        assert_eq!(
+            exclusions.excluded_by(SourceType::GoogleDrive, &at_path("/Engineering/HR.md")),
+            None
+        );
+        assert_eq!(
+            exclusions.excluded_by(SourceType::OneDrive, &at_path("/HR/payroll.xlsx")),
+            None
+        );
+        assert_eq!(
+            exclusions.excluded_by(SourceType::OneDrive, &with_mime_type("video/mp4")),
+            Some("media")
+        );
+        assert_eq!(
+            exclusions.excluded_by(SourceType::Web, &with_mime_type("application/zip; x=1")),
+            Some("media")
+        );
+        assert_eq!(
+            exclusions.excluded_by(SourceType::Web, &with_mime_type("text/html")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_label_and_sender_rules() {
+        let exclusions = exclusions(json!([
+            { "name": "legal", "labels": ["Privileged"], "sender_domains": ["lawfirm.com"] }
+        ]));
+        let labels = vec!["INBOX".to_string(), "privileged".to_string()];
+
+        let with_labels = ExclusionCandidate {
+            labels: &labels,
+            ..Default::default()
+        };
+        assert!(exclusions
+            .excluded_by(SourceType::Gmail, &with_labels)
+            .is_some());
+
+        for (sender, excluded) in [
+            ("Jane <jane@lawfirm.com>", true),
+            ("partner@mail.LawFirm.com", true),
+            ("someone@notlawfirm.com", false),
+            ("not an address", false),
+        ] {
+            let candidate = ExclusionCandidate {
+                sender: Some(sender),
+                ..Default::default()
+            };
+            assert_eq!(
+                exclusions
+                    .excluded_by(SourceType::Gmail, &candidate)
+                    .is_some(),
+                excluded,
+                "{}",
+                sender
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_rules_rejected() {
+        let parse = |rules: serde_json::Value| {
+            ContentExclusions::new(serde_json::from_value(json!({ "rules": rules })).unwrap())
+        };
+        assert!(parse(json!([{ "name": "nothing" }])).is_err());
+        assert!(parse(json!([{ "name": "bad", "path_globs": ["/a/[b"] }])).is_err());
+        assert!(parse(json!([{ "name": "bad", "mime_types": ["pdf"] }])).is_err());
+        assert!(parse(json!([{ "name": "", "labels": ["x"] }])).is_err());
+    }
+}
@@ -0,0 +1,296 @@
+//! Feature flags for rolling out risky features gradually, shared by every service.
+//!
+//! Flags live in the `feature_flags` table, managed through the searcher's admin API, and
+//! each service keeps them cached after [`init`], refreshing them every
+//! `FEATURE_FLAGS_REFRESH_SECONDS`. A flag the table does not define falls back to the
+//! `FEATURE_FLAG_<NAME>` environment variable: `true`, `false`, or a rollout percentage. So do
+//! all flags in services that never ran [`init`], or when the first load failed.
+//!
+//! An enabled flag is on for the tenants it lists and, through its rollout percentage, for a
+//! stable share of subjects: a subject (usually a user) is bucketed by hashing it with the
+//! flag's name, so it sees the same variant in every service and raising the percentage only
+//! adds subjects. Tenants are whatever a service partitions its callers by; the searcher uses
+//! the domain of the user's email address.
+
+use crate::db::repositories::{FeatureFlag, FeatureFlagRepository};
+use crate::secrets;
+use anyhow::{anyhow, Result};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::Duration;
+use time::OffsetDateTime;
+use tracing::{error, info};
+
+static FLAGS: OnceLock<Arc<FeatureFlags>> = OnceLock::new();
+
+const ENV_PREFIX: &str = "FEATURE_FLAG_";
+
+/// Who a flag is evaluated for.
+#[derive(Debug, Clone, Default)]
+pub struct FlagContext {
+    pub tenant: Option<String>,
+    /// Bucketed into percentage rollouts. Without one, only full rollouts apply.
+    pub subject: Option<String>,
+}
+
+impl FlagContext {
+    /// A user's context, with the domain of their email address as the tenant.
+    pub fn for_user(user_email: Option<&str>) -> Self {
+        Self {
+            tenant: user_email
+                .and_then(|email| email.rsplit_once('@'))
+                .map(|(_, domain)| domain.to_lowercase()),
+            subject: user_email.map(|email| email.to_lowercase()),
+        }
+    }
+}
+
+/// Whether `flag` is on in `context`.
+pub fn evaluate(flag: &FeatureFlag, context: &FlagContext) -> bool {
+    if !flag.enabled {
+        return false;
+    }
+    if let Some(tenant) = &context.tenant {
+        if flag.tenants.iter().any(|t| t.eq_ignore_ascii_case(tenant)) {
+            return true;
+        }
+    }
+    if flag.rollout_percentage >= 100 {
+        return true;
+    }
+    context
+        .subject
+        .as_deref()
+        .is_some_and(|subject| bucket(&flag.name, subject) < flag.rollout_percentage.max(0) as u64)
+}
+
+/// The subject's bucket for a flag, from 0 to 99.
+fn bucket(name: &str, subject: &str) -> u64 {
+    let digest = Sha256::digest(format!("{}:{}", name, subject).as_bytes());
+    let mut prefix = [0u8; 8];
+    prefix.copy_from_slice(&digest[..8]);
+    u64::from_be_bytes(prefix) % 100
+}
+
+/// A flag defined by an environment variable's value, `None` when it is not one.
+fn from_env_value(name: &str, value: &str) -> Option<FeatureFlag> {
+    let rollout_percentage = match value.trim().to_lowercase().as_str() {
+        "true" | "on" => 100,
+        "false" | "off" => 0,
+        percentage => percentage
+            .trim_end_matches('%')
+            .parse::<i16>()
+            .ok()
+            .filter(|p| (0..=100).contains(p))?,
+    };
+    let now = OffsetDateTime::now_utc();
+    Some(FeatureFlag {
+        name: name.to_string(),
+        description: String::new(),
+        enabled: rollout_percentage > 0,
+        rollout_percentage,
+        tenants: vec![],
+        created_at: now,
+        updated_at: now,
+    })
+}
+
+fn env_flag(name: &str) -> Option<FeatureFlag> {
+    let value = secrets::get(&format!("{}{}", ENV_PREFIX, name.to_uppercase()))?;
+    from_env_value(name, &value)
+}
+
+fn env_flags() -> impl Iterator<Item = FeatureFlag> {
+    env::vars().filter_map(|(key, value)| {
+        let name = key.strip_prefix(ENV_PREFIX)?.to_lowercase();
+        from_env_value(&name, &value)
+    })
+}
+
+/// The flags defined in the database, cached.
+pub struct FeatureFlags {
+    pool: PgPool,
+    flags: RwLock<HashMap<String, FeatureFlag>>,
+}
+
+impl FeatureFlags {
+    pub async fn load(pool: &PgPool) -> Result<Self> {
+        let flags = Self {
+            pool: pool.clone(),
+            flags: RwLock::new(HashMap::new()),
+        };
+        flags.refresh().await?;
+        Ok(flags)
+    }
+
+    /// Reload the flags. On failure the previous flags stay in use.
+    pub async fn refresh(&self) -> Result<()> {
+        let flags = FeatureFlagRepository::new(&self.pool).find_all().await?;
+        let flags = flags.into_iter().map(|f| (f.name.clone(), f)).collect();
+        if let Ok(mut cached) = self.flags.write() {
+            *cached = flags;
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<FeatureFlag> {
+        self.flags.read().ok()?.get(name).cloned()
+    }
+
+    fn all(&self) -> Vec<FeatureFlag> {
+        self.flags
+            .read()
+            .map(|flags| flags.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn spawn_refresh(self: Arc<Self>, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.refresh().await {
+                    error!("Failed to refresh feature flags: {}", e);
+                }
+            }
+        });
+    }
+}
+
+/// Load the flags from the database and keep them refreshed. Until this runs, flags come
+/// from the environment only.
+pub async fn init(pool: &PgPool) -> Result<()> {
+    let interval = env::var("FEATURE_FLAGS_REFRESH_SECONDS")
+        .ok()
+        .and_then(|seconds| seconds.parse::<u64>().ok())
+        .filter(|seconds| *seconds > 0)
+        .unwrap_or(30);
+
+    let flags = Arc::new(FeatureFlags::load(pool).await?);
+    if FLAGS.set(flags.clone()).is_err() {
+        return Err(anyhow!("Feature flags already initialized"));
+    }
+    info!("Loaded {} feature flags", flags.all().len());
+    flags.spawn_refresh(Duration::from_secs(interval));
+    Ok(())
+}
+
+/// Reload the flags now, e.g. after an admin changed one, rather than on the next refresh.
+pub async fn refresh() -> Result<()> {
+    match FLAGS.get() {
+        Some(flags) => flags.refresh().await,
+        None => Ok(()),
+    }
+}
+
+/// Whether the flag `name` is on in `context`. Flags defined nowhere are off.
+pub fn is_enabled(name: &str, context: &FlagContext) -> bool {
+    FLAGS
+        .get()
+        .and_then(|flags| flags.get(name))
+        .or_else(|| env_flag(name))
+        .is_some_and(|flag| evaluate(&flag, context))
+}
+
+/// Every defined flag's value in `context`, by name, for recording alongside what a request
+/// did so that the variants can be compared.
+pub fn evaluate_all(context: &FlagContext) -> BTreeMap<String, bool> {
+    let mut flags: BTreeMap<String, FeatureFlag> =
+        env_flags().map(|flag| (flag.name.clone(), flag)).collect();
+    if let Some(cached) = FLAGS.get() {
+        flags.extend(cached.all().into_iter().map(|f| (f.name.clone(), f)));
+    }
+    flags
+        .into_iter()
+        .map(|(name, flag)| (name, evaluate(&flag, context)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flag(enabled: bool, rollout_percentage: i16, tenants: &[&str]) -> FeatureFlag {
+        let now = OffsetDateTime::now_utc();
+        FeatureFlag {
+            name: "rrf_fusion".to_string(),
+            description: String::new(),
+            enabled,
+            rollout_percentage,
+            tenants: tenants.iter().map(|t| t.to_string()).collect(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn user(email: &str) -> FlagContext {
+        FlagContext::for_user(Some(email))
+    }
+
+    #[test]
+    fn test_context_for_user() {
+        let context = user("Alice@Example.com");
+        assert_eq!(context.tenant.as_deref(), Some("example.com"));
+        assert_eq!(context.subject.as_deref(), Some("alice@example.com"));
+        assert!(FlagContext::for_user(None).subject.is_none());
+    }
+
+    #[test]
+    fn test_disabled_flags_are_off() {
+        let flag = flag(false, 100, &["example.com"]);
+        assert!(!evaluate(&flag, &user("alice@example.com")));
+    }
+
+    #[test]
+    fn test_tenants_are_always_on() {
+        let flag = flag(true, 0, &["Example.com"]);
+        assert!(evaluate(&flag, &user("alice@example.com")));
+        assert!(!evaluate(&flag, &user("bob@other.org")));
+    }
+
+    #[test]
+    fn test_percentage_rollout() {
+        let subjects: Vec<FlagContext> = (0..1000)
+            .map(|i| user(&format!("user{}@example.org", i)))
+            .collect();
+        let on = |percentage| {
+            let flag = flag(true, percentage, &[]);
+            subjects
+                .iter()
+                .filter(|subject| evaluate(&flag, subject))
+                .count()
+        };
+
+        assert_eq!(on(0), 0);
+        assert_eq!(on(100), 1000);
+        let quarter = on(25);
+        assert!((150..350).contains(&quarter), "{} of 1000 on", quarter);
+
+        // Raising the rollout keeps every subject that already had the flag on
+        let at_25 = flag(true, 25, &[]);
+        let at_50 = flag(true, 50, &[]);
+        for subject in &subjects {
+            if evaluate(&at_25, subject) {
+                assert!(evaluate(&at_50, subject));
+            }
+        }
+
+        // Anonymous callers only get full rollouts
+        assert!(!evaluate(&at_50, &FlagContext::default()));
+        assert!(evaluate(&flag(true, 100, &[]), &FlagContext::default()));
+    }
+
+    #[test]
+    fn test_env_values() {
+        assert_eq!(from_env_value("f", "true").unwrap().rollout_percentage, 100);
+        assert!(!from_env_value("f", "off").unwrap().enabled);
+        assert_eq!(from_env_value("f", "25%").unwrap().rollout_percentage, 25);
+        assert_eq!(from_env_value("f", "10").unwrap().rollout_percentage, 10);
+        assert!(from_env_value("f", "150").is_none());
+        assert!(from_env_value("f", "maybe").is_none());
+    }
+}
@@ -0,0 +1,25 @@
+//! A counter of changes to the search index, kept in Redis.
+//!
+//! The indexer bumps it whenever it has written documents. Caches of values computed from the
+//! index, such as facet counts, key their entries by it, so that entries computed before a
+//! change are not served after it.
+
+use crate::cache;
+use redis::{AsyncCommands, Client as RedisClient};
+
+fn version_key() -> String {
+    cache::keys().index("version")
+}
+
+/// Record that the index changed, returning the new version.
+pub async fn bump(redis_client: &RedisClient) -> redis::RedisResult<u64> {
+    let mut conn = redis_client.get_multiplexed_async_connection().await?;
+    conn.incr(version_key(), 1).await
+}
+
+/// The current version, 0 before the index first changed.
+pub async fn current(redis_client: &RedisClient) -> redis::RedisResult<u64> {
+    let mut conn = redis_client.get_multiplexed_async_connection().await?;
+    let version: Option<u64> = conn.get(version_key()).await?;
+    Ok(version.unwrap_or(0))
+}
@@ -0,0 +1,139 @@
+//! Restricts admin routes to a list of client networks.
+
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::StatusCode;
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router};
+use ipnet::IpNet;
+use std::env;
+use std::net::{IpAddr, SocketAddr};
+use std::process;
+use std::sync::Arc;
+use tracing::warn;
+
+#[derive(Debug, Clone)]
+pub struct IpAllowlist {
+    networks: Vec<IpNet>,
+}
+
+impl IpAllowlist {
+    /// Parse a comma separated list of networks in CIDR notation or single addresses,
+    /// e.g. `10.0.0.0/8, 192.168.1.20`.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let networks = value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                entry
+                    .parse::<IpNet>()
+                    .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from))
+                    .map_err(|_| format!("invalid network '{}'", entry))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if networks.is_empty() {
+            return Err("no networks listed".to_string());
+        }
+
+        Ok(Self { networks })
+    }
+
+    /// Read `ADMIN_IP_ALLOWLIST`. Returns `None` when unset, leaving admin routes open to
+    /// any client that can reach the service.
+    pub fn from_env() -> Option<Self> {
+        let value = env::var("ADMIN_IP_ALLOWLIST")
+            .ok()
+            .filter(|value| !value.trim().is_empty())?;
+
+        Some(Self::parse(&value).unwrap_or_else(|e| {
+            eprintln!("ERROR: Invalid 'ADMIN_IP_ALLOWLIST': {}", e);
+            process::exit(1);
+        }))
+    }
+
+    pub fn allows(&self, ip: IpAddr) -> bool {
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+            ip => ip,
+        };
+        self.networks.iter().any(|network| network.contains(&ip))
+    }
+}
+
+fn is_admin_path(path: &str) -> bool {
+    path == "/admin" || path.starts_with("/admin/")
+}
+
+async fn admin_allowlist_middleware(
+    State(allowlist): State<Arc<IpAllowlist>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !is_admin_path(request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+
+    match peer {
+        Some(ip) if allowlist.allows(ip) => next.run(request).await,
+        _ => {
+            warn!(
+                "Rejected admin request to {} from {:?}",
+                request.uri().path(),
+                peer
+            );
+            (
+                StatusCode::FORBIDDEN,
+                Json(serde_json::json!({ "error": "Forbidden" })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Reject requests to `/admin` routes from peers outside `ADMIN_IP_ALLOWLIST`, when set.
+/// Requires the app to be served with connect info, see [`crate::tls::serve`].
+pub fn restrict_admin_routes(app: Router) -> Router {
+    match IpAllowlist::from_env() {
+        Some(allowlist) => app.layer(middleware::from_fn_with_state(
+            Arc::new(allowlist),
+            admin_allowlist_middleware,
+        )),
+        None => app,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowlist() {
+        let allowlist = IpAllowlist::parse("10.0.0.0/8, 192.168.1.20,fd00::/8").unwrap();
+
+        assert!(allowlist.allows("10.1.2.3".parse().unwrap()));
+        assert!(allowlist.allows("192.168.1.20".parse().unwrap()));
+        assert!(allowlist.allows("::ffff:10.0.0.1".parse().unwrap()));
+        assert!(allowlist.allows("fd12::1".parse().unwrap()));
+        assert!(!allowlist.allows("192.168.1.21".parse().unwrap()));
+        assert!(!allowlist.allows("8.8.8.8".parse().unwrap()));
+
+        assert!(IpAllowlist::parse("").is_err());
+        assert!(IpAllowlist::parse("10.0.0.0/33").is_err());
+        assert!(IpAllowlist::parse("localhost").is_err());
+    }
+
+    #[test]
+    fn test_is_admin_path() {
+        assert!(is_admin_path("/admin"));
+        assert!(is_admin_path("/admin/gc/run"));
+        assert!(!is_admin_path("/administrators"));
+        assert!(!is_admin_path("/search"));
+    }
+}
@@ -1,18 +1,33 @@
+pub mod api_error;
+pub mod cache;
 pub mod clients;
 pub mod config;
 pub mod constants;
 pub mod content_chunker;
 pub mod content_storage;
 pub mod db;
+pub mod email;
+pub mod embedding_migration;
 pub mod embedding_queue;
 pub mod encryption;
+pub mod error_reporting;
+pub mod exclusion;
+pub mod feature_flags;
+pub mod index_version;
+pub mod ip_allowlist;
 pub mod models;
 pub mod queue;
 pub mod rate_limiter;
+pub mod redaction;
 pub mod sdk_client;
+pub mod secrets;
 pub mod service_auth;
+pub mod shutdown;
+pub mod startup;
 pub mod storage;
 pub mod telemetry;
+pub mod text_normalization;
+pub mod tls;
 pub mod traits;
 pub mod utils;
 
@@ -20,6 +35,7 @@ pub mod test_utils;
 
 pub mod test_environment;
 
+pub use api_error::{ApiError, ErrorCode};
 pub use clients::ai::AIClient;
 pub use config::*;
 pub use content_chunker::ContentChunker;
@@ -29,8 +45,9 @@ pub use db::repositories::{
     UserRepository,
 };
 pub use db::{DatabaseError, DatabasePool};
+pub use email::{EmailError, EmailMessage, EmailNotifier, EmailTemplate};
 pub use embedding_queue::{EmbeddingQueue, EmbeddingQueueItem};
-pub use encryption::{EncryptedData, EncryptionService};
+pub use encryption::{EncryptedData, EncryptedEnvelope, EncryptionService};
 pub use models::*;
 pub use queue::{EventQueue, QueueStats};
 pub use rate_limiter::{RateLimiter, RetryableError};
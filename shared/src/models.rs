@@ -4,6 +4,7 @@ use serde_json::Value as JsonValue;
 use sqlx::types::time::OffsetDateTime;
 use sqlx::FromRow;
 use std::collections::HashMap;
+use std::fmt;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq)]
 #[sqlx(type_name = "varchar", rename_all = "lowercase")]
@@ -33,6 +34,9 @@ pub struct User {
     pub is_active: bool,
     pub auth_method: AuthMethod,
     pub domain: Option<String>,
+    /// Identity provider id for users provisioned over SCIM
+    #[sqlx(default)]
+    pub external_id: Option<String>,
     #[serde(with = "time::serde::iso8601")]
     pub created_at: OffsetDateTime,
     #[serde(with = "time::serde::iso8601")]
@@ -41,6 +45,83 @@ pub struct User {
     pub last_login_at: Option<OffsetDateTime>,
 }
 
+/// Kinds of email notification a user can opt out of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    /// A sync of a source the user created, or of any source for admins, failed
+    SyncFailures,
+}
+
+impl NotificationKind {
+    /// Key of the setting in `users.notification_preferences`.
+    pub fn preference_key(&self) -> &'static str {
+        match self {
+            NotificationKind::SyncFailures => "sync_failures",
+        }
+    }
+}
+
+/// How often a user is emailed a digest of the new documents matching their interests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestFrequency {
+    #[default]
+    Off,
+    Daily,
+    Weekly,
+}
+
+impl DigestFrequency {
+    /// Time between two digests, `None` when none are sent.
+    pub fn period(&self) -> Option<time::Duration> {
+        match self {
+            DigestFrequency::Off => None,
+            DigestFrequency::Daily => Some(time::Duration::days(1)),
+            DigestFrequency::Weekly => Some(time::Duration::weeks(1)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DigestFrequency::Off => "off",
+            DigestFrequency::Daily => "daily",
+            DigestFrequency::Weekly => "weekly",
+        }
+    }
+}
+
+/// A user's email notification settings, stored in `users.notification_preferences`.
+/// Notifications missing from the stored document are enabled, while digests are sent only
+/// to users who asked for them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationPreferences {
+    #[serde(default = "enabled")]
+    pub sync_failures: bool,
+    #[serde(default)]
+    pub digest: DigestFrequency,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            sync_failures: true,
+            digest: DigestFrequency::Off,
+        }
+    }
+}
+
+impl NotificationPreferences {
+    pub fn is_enabled(&self, kind: NotificationKind) -> bool {
+        match kind {
+            NotificationKind::SyncFailures => self.sync_failures,
+        }
+    }
+}
+
+fn enabled() -> bool {
+    true
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq)]
 #[sqlx(type_name = "varchar", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
@@ -50,7 +131,7 @@ pub enum UserFilterMode {
     Blacklist,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Clone, Serialize, Deserialize, FromRow)]
 pub struct Source {
     pub id: String,
     pub name: String,
@@ -108,6 +189,388 @@ impl Source {
             }
         }
     }
+
+    /// Copy of the source with credential fields in its config redacted, for API
+    /// responses. Credentials themselves are only served from `service_credentials`.
+    pub fn redacted(&self) -> Source {
+        Source {
+            config: crate::redaction::redact_json(&self.config),
+            ..self.clone()
+        }
+    }
+}
+
+impl fmt::Debug for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Source")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("source_type", &self.source_type)
+            .field("config", &crate::redaction::redact_json(&self.config))
+            .field("is_active", &self.is_active)
+            .field("is_deleted", &self.is_deleted)
+            .field("user_filter_mode", &self.user_filter_mode)
+            .field("user_whitelist", &self.user_whitelist)
+            .field("user_blacklist", &self.user_blacklist)
+            .field("connector_state", &self.connector_state)
+            .field("sync_interval_seconds", &self.sync_interval_seconds)
+            .field("created_at", &self.created_at)
+            .field("updated_at", &self.updated_at)
+            .field("created_by", &self.created_by)
+            .finish()
+    }
+}
+
+/// Typed view of a source's `config`. Parsing checks field types and the values each
+/// connector relies on, so an invalid config is rejected when saved rather than at sync time.
+/// Fields connectors don't read are ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SourceConfig {
+    Confluence(ConfluenceSourceConfig),
+    Jira(JiraSourceConfig),
+    Github(GithubSourceConfig),
+    FileSystem(FileSystemSourceConfig),
+    Web(WebSourceConfig),
+    Notion(NotionSourceConfig),
+    Hubspot(HubspotSourceConfig),
+    Microsoft(MicrosoftSourceConfig),
+    ServiceNow(ServiceNowSourceConfig),
+    Figma(FigmaSourceConfig),
+    Airtable(AirtableSourceConfig),
+    Bitbucket(BitbucketSourceConfig),
+    Pagerduty(PagerdutySourceConfig),
+    StackOverflow(StackOverflowSourceConfig),
+    Box(BoxSourceConfig),
+    Gong(GongSourceConfig),
+    Trello(TrelloSourceConfig),
+    /// Source types whose settings all live in their service credentials.
+    Empty,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfluenceSourceConfig {
+    pub base_url: Option<String>,
+    pub space_filters: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JiraSourceConfig {
+    pub base_url: Option<String>,
+    pub project_filters: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GithubSourceConfig {
+    pub api_url: Option<String>,
+    pub include_discussions: Option<bool>,
+    pub include_forks: Option<bool>,
+    pub include_markdown_files: Option<bool>,
+    pub repos: Vec<String>,
+    pub orgs: Vec<String>,
+    pub users: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileSystemSourceConfig {
+    pub base_path: String,
+    pub scan_interval_seconds: Option<u64>,
+    pub file_extensions: Option<Vec<String>>,
+    pub exclude_patterns: Option<Vec<String>>,
+    pub max_file_size_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebSourceConfig {
+    pub root_url: String,
+    pub max_depth: Option<usize>,
+    pub max_pages: Option<usize>,
+    pub respect_robots_txt: Option<bool>,
+    pub user_agent: Option<String>,
+    #[serde(default)]
+    pub blacklist_patterns: Vec<String>,
+    pub include_subdomains: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotionSourceConfig {
+    pub api_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HubspotSourceConfig {
+    pub portal_id: Option<String>,
+    pub api_url: Option<String>,
+}
+
+/// Shared by the OneDrive, SharePoint, Outlook and Outlook Calendar sources.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MicrosoftSourceConfig {
+    pub graph_base_url: Option<String>,
+    pub calendar_past_months: Option<u32>,
+    pub calendar_future_months: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServiceNowSourceConfig {
+    /// Base URL of the instance, e.g. `https://acme.service-now.com`
+    pub instance_url: String,
+    /// Sync knowledge-base articles, on by default
+    pub sync_knowledge: Option<bool>,
+    /// Sync incident records, on by default
+    pub sync_incidents: Option<bool>,
+    /// Knowledge bases to sync by name, all when empty
+    pub knowledge_bases: Vec<String>,
+    /// Roles whose holders can read every incident, `itil` when empty
+    pub incident_read_roles: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FigmaSourceConfig {
+    /// Teams whose projects are synced, by the ID in their Figma URL
+    pub team_ids: Vec<String>,
+    /// Projects only their own members can see, rather than their whole team
+    pub restricted_projects: Vec<String>,
+    /// Sync comment threads, on by default
+    pub include_comments: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AirtableSourceConfig {
+    /// Bases to sync, every base the token can access when empty
+    pub base_ids: Vec<String>,
+    /// View to read each table through, keyed by table ID or name
+    pub views: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BitbucketSourceConfig {
+    /// Repositories to sync, as `workspace/slug`
+    pub repos: Vec<String>,
+    /// Workspaces whose repositories are all synced. With neither repos nor workspaces,
+    /// every workspace of the user is synced.
+    pub workspaces: Vec<String>,
+    /// Sync forked repositories too
+    pub include_forks: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PagerdutySourceConfig {
+    /// Services whose incidents are synced, all when empty
+    pub service_ids: Vec<String>,
+    /// Teams whose incidents are synced, all when empty
+    pub team_ids: Vec<String>,
+    /// How far back the first sync reads incidents, 365 days when unset
+    pub lookback_days: Option<u32>,
+    /// Notes whose first line starts with one of these are synced as postmortems
+    pub postmortem_prefixes: Vec<String>,
+    /// Share incidents with their teams' members instead of the whole organization
+    pub team_permissions: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StackOverflowSourceConfig {
+    /// Slug of a Basic or Business team, from `stackoverflowteams.com/c/{team}`
+    pub team: Option<String>,
+    /// API of an Enterprise site, e.g. `https://acme.stackenterprise.co/api/2.3`
+    pub api_url: Option<String>,
+    /// Only sync questions with one of these tags, all when empty
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BoxSourceConfig {
+    /// Only sync these folders, shared with the app's service account; every managed
+    /// user's files when empty
+    pub folder_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GongSourceConfig {
+    /// Base URL shown with the account's API keys, e.g. `https://us-12345.api.gong.io`
+    pub api_url: Option<String>,
+    /// Only sync the calls of this workspace, all workspaces when unset
+    pub workspace_id: Option<String>,
+    /// How far back the first sync reads calls
+    pub lookback_days: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrelloSourceConfig {
+    /// Only sync these boards, by ID or short link; every open board of the token's member
+    /// when empty
+    pub board_ids: Vec<String>,
+}
+
+impl SourceConfig {
+    pub fn parse(source_type: SourceType, config: &JsonValue) -> Result<Self, String> {
+        if !config.is_object() {
+            return Err("Source config must be a JSON object".to_string());
+        }
+
+        let parsed = match source_type {
+            SourceType::Confluence => Self::Confluence(from_config(config)?),
+            SourceType::Jira => Self::Jira(from_config(config)?),
+            SourceType::Github => Self::Github(from_config(config)?),
+            SourceType::FileSystem | SourceType::LocalFiles => {
+                Self::FileSystem(from_config(config)?)
+            }
+            SourceType::Web => Self::Web(from_config(config)?),
+            SourceType::Notion => Self::Notion(from_config(config)?),
+            SourceType::Hubspot => Self::Hubspot(from_config(config)?),
+            SourceType::OneDrive
+            | SourceType::SharePoint
+            | SourceType::Outlook
+            | SourceType::OutlookCalendar => Self::Microsoft(from_config(config)?),
+            SourceType::ServiceNow => Self::ServiceNow(from_config(config)?),
+            SourceType::Figma => Self::Figma(from_config(config)?),
+            SourceType::Airtable => Self::Airtable(from_config(config)?),
+            SourceType::Bitbucket => Self::Bitbucket(from_config(config)?),
+            SourceType::Pagerduty => Self::Pagerduty(from_config(config)?),
+            SourceType::StackOverflow => Self::StackOverflow(from_config(config)?),
+            SourceType::Box => Self::Box(from_config(config)?),
+            SourceType::Gong => Self::Gong(from_config(config)?),
+            SourceType::Trello => Self::Trello(from_config(config)?),
+            SourceType::GoogleDrive
+            | SourceType::Gmail
+            | SourceType::Slack
+            | SourceType::Fireflies
+            | SourceType::People => Self::Empty,
+        };
+
+        parsed.validate()?;
+        Ok(parsed)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Confluence(config) => check_url("base_url", config.base_url.as_deref()),
+            Self::Jira(config) => check_url("base_url", config.base_url.as_deref()),
+            Self::Github(config) => check_url("api_url", config.api_url.as_deref()),
+            Self::FileSystem(config) => {
+                if !config.base_path.starts_with('/') {
+                    return Err("base_path must be an absolute path".to_string());
+                }
+                check_positive("scan_interval_seconds", config.scan_interval_seconds)?;
+                check_positive("max_file_size_bytes", config.max_file_size_bytes)
+            }
+            Self::Web(config) => {
+                check_url("root_url", Some(&config.root_url))?;
+                check_positive("max_depth", config.max_depth.map(|v| v as u64))?;
+                check_positive("max_pages", config.max_pages.map(|v| v as u64))
+            }
+            Self::Notion(config) => check_url("api_url", config.api_url.as_deref()),
+            Self::Hubspot(config) => check_url("api_url", config.api_url.as_deref()),
+            Self::Microsoft(config) => {
+                check_url("graph_base_url", config.graph_base_url.as_deref())
+            }
+            Self::ServiceNow(config) => {
+                if config.instance_url.is_empty() {
+                    return Err("instance_url is required".to_string());
+                }
+                check_url("instance_url", Some(&config.instance_url))
+            }
+            Self::Figma(config) => {
+                if config.team_ids.iter().all(|id| id.trim().is_empty()) {
+                    return Err("team_ids must name at least one team".to_string());
+                }
+                Ok(())
+            }
+            Self::Airtable(config) => {
+                if let Some(id) = config.base_ids.iter().find(|id| !id.starts_with("app")) {
+                    return Err(format!("base_ids must be base IDs starting with app: {id}"));
+                }
+                Ok(())
+            }
+            Self::Bitbucket(config) => {
+                let malformed = |repo: &&String| match repo.split_once('/') {
+                    Some((workspace, slug)) => workspace.is_empty() || slug.is_empty(),
+                    None => true,
+                };
+                if let Some(repo) = config.repos.iter().find(malformed) {
+                    return Err(format!("repos must be given as workspace/slug: {repo}"));
+                }
+                Ok(())
+            }
+            Self::Pagerduty(config) => {
+                if config.lookback_days == Some(0) {
+                    return Err("lookback_days must be at least 1".to_string());
+                }
+                if config
+                    .postmortem_prefixes
+                    .iter()
+                    .any(|p| p.trim().is_empty())
+                {
+                    return Err("postmortem_prefixes must not be blank".to_string());
+                }
+                Ok(())
+            }
+            Self::StackOverflow(config) => {
+                if config.team.is_none() && config.api_url.is_none() {
+                    return Err("team or api_url is required".to_string());
+                }
+                check_url("api_url", config.api_url.as_deref())
+            }
+            Self::Box(config) => {
+                let malformed =
+                    |id: &&String| id.is_empty() || !id.bytes().all(|b| b.is_ascii_digit());
+                if let Some(id) = config.folder_ids.iter().find(malformed) {
+                    return Err(format!("folder_ids must be numeric folder IDs: {id}"));
+                }
+                Ok(())
+            }
+            Self::Gong(config) => {
+                if config.lookback_days == Some(0) {
+                    return Err("lookback_days must be at least 1".to_string());
+                }
+                check_url("api_url", config.api_url.as_deref())
+            }
+            Self::Trello(config) => {
+                let malformed =
+                    |id: &&String| id.is_empty() || !id.bytes().all(|b| b.is_ascii_alphanumeric());
+                if let Some(id) = config.board_ids.iter().find(malformed) {
+                    return Err(format!("board_ids must be board IDs or short links: {id}"));
+                }
+                Ok(())
+            }
+            Self::Empty => Ok(()),
+        }
+    }
+}
+
+fn from_config<T: serde::de::DeserializeOwned>(config: &JsonValue) -> Result<T, String> {
+    serde_json::from_value(config.clone()).map_err(|e| format!("Invalid source config: {}", e))
+}
+
+fn check_url(field: &str, value: Option<&str>) -> Result<(), String> {
+    let Some(value) = value else {
+        return Ok(());
+    };
+    match url::Url::parse(value) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(()),
+        _ => Err(format!("{} must be an http or https URL", field)),
+    }
+}
+
+fn check_positive(field: &str, value: Option<u64>) -> Result<(), String> {
+    match value {
+        Some(0) => Err(format!("{} must be greater than zero", field)),
+        _ => Ok(()),
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -130,6 +593,65 @@ pub struct Document {
     pub updated_at: OffsetDateTime,
     #[serde(with = "time::serde::iso8601")]
     pub last_indexed_at: OffsetDateTime,
+    /// Set on search results that have a preview image.
+    #[sqlx(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview: Option<DocumentPreview>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PreviewKind {
+    /// A thumbnail captured from the source by the connector.
+    Thumbnail,
+    /// A render of the first page of the content, generated by the indexer.
+    Render,
+}
+
+/// A document's preview image. The image itself is served by the searcher.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocumentPreview {
+    pub kind: PreviewKind,
+    pub mime_type: String,
+}
+
+/// An image of a document captured by its connector and stored with the SDK.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocumentThumbnail {
+    pub content_id: String,
+    pub mime_type: String,
+}
+
+/// How a child document relates to its parent.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Hash)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum RelationshipType {
+    /// A file attached to an email, page or issue.
+    Attachment,
+    /// A comment on a page, issue or file.
+    Comment,
+    /// A segment of a meeting transcript.
+    TranscriptSegment,
+}
+
+impl RelationshipType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelationshipType::Attachment => "attachment",
+            RelationshipType::Comment => "comment",
+            RelationshipType::TranscriptSegment => "transcript_segment",
+        }
+    }
+}
+
+/// The parent of a document, named by the connector in the document metadata. The parent is
+/// a document of the same source, by external ID, and need not be indexed yet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocumentParent {
+    pub external_id: String,
+    pub relationship: RelationshipType,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -139,6 +661,11 @@ pub struct Embedding {
     pub chunk_index: i32,
     pub chunk_start_offset: i32, // Character start offset in original document
     pub chunk_end_offset: i32,   // Character end offset in original document
+    /// Checksum of the content the offsets count characters of, as
+    /// [`crate::storage::content_sha256`] computes it. `None` for embeddings written before
+    /// checksums were recorded.
+    #[serde(default)]
+    pub content_sha256: Option<String>,
     pub embedding: Vector,
     pub model_name: String,
     pub dimensions: i16,
@@ -166,6 +693,17 @@ pub enum SourceType {
     Outlook,
     OutlookCalendar,
     Fireflies,
+    ServiceNow,
+    Figma,
+    Airtable,
+    Bitbucket,
+    Pagerduty,
+    StackOverflow,
+    Box,
+    Gong,
+    Trello,
+    /// Profiles aggregated by the indexer from other sources' documents.
+    People,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq)]
@@ -180,6 +718,15 @@ pub enum ServiceProvider {
     Notion,
     Hubspot,
     Fireflies,
+    ServiceNow,
+    Figma,
+    Airtable,
+    Bitbucket,
+    Pagerduty,
+    StackOverflow,
+    Box,
+    Gong,
+    Trello,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq)]
@@ -193,7 +740,7 @@ pub enum AuthType {
     BotToken,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Clone, Serialize, Deserialize, FromRow)]
 pub struct ServiceCredentials {
     pub id: String,
     pub source_id: String,
@@ -212,18 +759,25 @@ pub struct ServiceCredentials {
     pub updated_at: OffsetDateTime,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ConfluenceSourceConfig {
-    pub base_url: String,
-    #[serde(default)]
-    pub space_filters: Option<Vec<String>>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct JiraSourceConfig {
-    pub base_url: String,
-    #[serde(default)]
-    pub project_filters: Option<Vec<String>>,
+impl fmt::Debug for ServiceCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ServiceCredentials")
+            .field("id", &self.id)
+            .field("source_id", &self.source_id)
+            .field("provider", &self.provider)
+            .field("auth_type", &self.auth_type)
+            .field("principal_email", &self.principal_email)
+            .field(
+                "credentials",
+                &crate::redaction::redact_all(&self.credentials),
+            )
+            .field("config", &crate::redaction::redact_json(&self.config))
+            .field("expires_at", &self.expires_at)
+            .field("last_validated_at", &self.last_validated_at)
+            .field("created_at", &self.created_at)
+            .field("updated_at", &self.updated_at)
+            .finish()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -239,15 +793,46 @@ pub struct DocumentMetadata {
     pub url: Option<String>,
     pub path: Option<String>, // Generic display path for hierarchical context
     pub extra: Option<HashMap<String, JsonValue>>, // Connector-specific metadata
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<DocumentThumbnail>,
+    /// Set on attachments, comments and transcript segments, which are documents of their
+    /// own listed under their parent in search results.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<DocumentParent>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DocumentPermissions {
     pub public: bool,
     pub users: Vec<String>,
     pub groups: Vec<String>,
 }
 
+/// Manual permission changes made by an admin or the document owner. They are stored
+/// separately from the connector's [`DocumentPermissions`] and layered on top of them, so a
+/// sync replacing the connector permissions keeps the overrides.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PermissionOverrides {
+    /// Replaces the connector's visibility when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public: Option<bool>,
+    /// Users granted access in addition to the connector permissions.
+    #[serde(default)]
+    pub users: Vec<String>,
+    /// Groups granted access in addition to the connector permissions.
+    #[serde(default)]
+    pub groups: Vec<String>,
+    /// Removes the document from search for everyone.
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+impl PermissionOverrides {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
 /// Structured attributes for filtering and faceting.
 /// Stored as JSONB, indexed by ParadeDB for FTS and filtering.
 /// NOT included in embeddings - only textual content is embedded.
@@ -271,6 +856,27 @@ pub enum AttributeFilter {
     },
 }
 
+/// Document fields a full-text search can be restricted to. Navigational queries such as
+/// "Q3 planning doc" work best against titles alone, where body text cannot outrank them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchField {
+    Title,
+    /// The author and path of the document.
+    Metadata,
+    Content,
+}
+
+impl SearchField {
+    /// Fields searched when a request does not name any.
+    pub const DEFAULT: [SearchField; 2] = [SearchField::Title, SearchField::Content];
+    pub const ALL: [SearchField; 3] = [
+        SearchField::Title,
+        SearchField::Metadata,
+        SearchField::Content,
+    ];
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ConnectorEvent {
@@ -299,14 +905,44 @@ pub enum ConnectorEvent {
         source_id: String,
         document_id: String,
     },
+    /// Sharing of an already indexed document changed while its content did not.
+    PermissionsUpdated {
+        sync_run_id: String,
+        source_id: String,
+        document_id: String,
+        permissions: DocumentPermissions,
+    },
 }
 
 impl ConnectorEvent {
+    /// Version of the event schema written to the queue. Bump it whenever a change to the
+    /// events would break reading payloads queued before it, and add an upcaster for the
+    /// previous version to the indexer.
+    ///
+    /// Version 2 added `attributes` to created and updated documents.
+    pub const SCHEMA_VERSION: u32 = 2;
+
+    /// The event as queued: its fields and type, stamped with the schema version.
+    pub fn to_payload(&self) -> serde_json::Result<JsonValue> {
+        #[derive(Serialize)]
+        struct VersionedEvent<'a> {
+            schema_version: u32,
+            #[serde(flatten)]
+            event: &'a ConnectorEvent,
+        }
+
+        serde_json::to_value(VersionedEvent {
+            schema_version: Self::SCHEMA_VERSION,
+            event: self,
+        })
+    }
+
     pub fn sync_run_id(&self) -> &str {
         match self {
             ConnectorEvent::DocumentCreated { sync_run_id, .. } => sync_run_id,
             ConnectorEvent::DocumentUpdated { sync_run_id, .. } => sync_run_id,
             ConnectorEvent::DocumentDeleted { sync_run_id, .. } => sync_run_id,
+            ConnectorEvent::PermissionsUpdated { sync_run_id, .. } => sync_run_id,
         }
     }
 
@@ -315,6 +951,7 @@ impl ConnectorEvent {
             ConnectorEvent::DocumentCreated { source_id, .. } => source_id,
             ConnectorEvent::DocumentUpdated { source_id, .. } => source_id,
             ConnectorEvent::DocumentDeleted { source_id, .. } => source_id,
+            ConnectorEvent::PermissionsUpdated { source_id, .. } => source_id,
         }
     }
 
@@ -323,6 +960,7 @@ impl ConnectorEvent {
             ConnectorEvent::DocumentCreated { document_id, .. } => document_id,
             ConnectorEvent::DocumentUpdated { document_id, .. } => document_id,
             ConnectorEvent::DocumentDeleted { document_id, .. } => document_id,
+            ConnectorEvent::PermissionsUpdated { document_id, .. } => document_id,
         }
     }
 }
@@ -355,6 +993,8 @@ pub struct ChunkResult {
     pub chunk_start_offset: i32,
     pub chunk_end_offset: i32,
     pub chunk_index: i32,
+    /// Checksum of the content the chunk's offsets refer to, as for [`Embedding`].
+    pub content_sha256: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq)]
@@ -399,6 +1039,8 @@ pub enum SyncStatus {
     Completed,
     Failed,
     Cancelled,
+    /// Stopped by its connector shutting down, to be resumed once the connector is back.
+    Paused,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -427,9 +1069,15 @@ pub struct SyncRun {
 pub struct SyncRequest {
     pub sync_run_id: String,
     pub source_id: String,
+    /// Type of the source, for the connector's logs and error reports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_type: Option<SourceType>,
     pub sync_mode: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub last_sync_at: Option<String>,
+    /// Token the connector authenticates its SDK calls for the source with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sdk_token: Option<String>,
 }
 
 /// Response from connector after receiving a sync request.
@@ -440,6 +1088,28 @@ pub struct SyncResponse {
     pub message: Option<String>,
 }
 
+/// Request sent from connector-manager to connectors to run an action with a source's
+/// credentials. Its debug output redacts the credentials.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ActionRequest {
+    pub action: String,
+    pub params: JsonValue,
+    pub credentials: JsonValue,
+}
+
+impl fmt::Debug for ActionRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ActionRequest")
+            .field("action", &self.action)
+            .field("params", &self.params)
+            .field(
+                "credentials",
+                &crate::redaction::redact_all(&self.credentials),
+            )
+            .finish()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct WebhookChannel {
     pub id: String,
@@ -485,6 +1155,60 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn test_source_config_parse() {
+        let web = SourceConfig::parse(
+            SourceType::Web,
+            &json!({ "root_url": "https://docs.example.com", "max_depth": 3, "extra": true }),
+        )
+        .unwrap();
+        match web {
+            SourceConfig::Web(config) => assert_eq!(config.max_depth, Some(3)),
+            other => panic!("Expected web config, got {:?}", other),
+        }
+
+        assert_eq!(
+            SourceConfig::parse(SourceType::GoogleDrive, &json!({})).unwrap(),
+            SourceConfig::Empty
+        );
+        assert!(SourceConfig::parse(SourceType::Slack, &json!([])).is_err());
+
+        for (source_type, config) in [
+            (SourceType::Web, json!({})),
+            (SourceType::Web, json!({ "root_url": "ftp://example.com" })),
+            (
+                SourceType::Web,
+                json!({ "root_url": "https://a.com", "max_pages": 0 }),
+            ),
+            (
+                SourceType::FileSystem,
+                json!({ "base_path": "relative/path" }),
+            ),
+            (SourceType::Github, json!({ "repos": "omni" })),
+            (SourceType::OneDrive, json!({ "calendar_past_months": -1 })),
+            (SourceType::ServiceNow, json!({})),
+            (
+                SourceType::ServiceNow,
+                json!({ "instance_url": "acme.service-now.com" }),
+            ),
+            (SourceType::Figma, json!({ "team_ids": [] })),
+            (SourceType::Airtable, json!({ "base_ids": ["Hiring"] })),
+            (SourceType::Bitbucket, json!({ "repos": ["payments"] })),
+            (SourceType::Pagerduty, json!({ "lookback_days": 0 })),
+            (SourceType::StackOverflow, json!({ "tags": ["kubernetes"] })),
+            (SourceType::Box, json!({ "folder_ids": ["Finance"] })),
+            (SourceType::Gong, json!({ "lookback_days": 0 })),
+            (SourceType::Trello, json!({ "board_ids": ["not a board"] })),
+        ] {
+            assert!(
+                SourceConfig::parse(source_type, &config).is_err(),
+                "{:?} {}",
+                source_type,
+                config
+            );
+        }
+    }
+
     fn make_source(
         filter_mode: UserFilterMode,
         whitelist: Option<JsonValue>,
@@ -634,4 +1358,22 @@ mod tests {
         assert_eq!(deleted.source_id(), "src-2");
         assert_eq!(deleted.document_id(), "doc-2");
     }
+
+    #[test]
+    fn test_connector_event_payload_is_versioned() {
+        let event = ConnectorEvent::DocumentDeleted {
+            sync_run_id: "run-1".to_string(),
+            source_id: "src-1".to_string(),
+            document_id: "doc-1".to_string(),
+        };
+        let payload = event.to_payload().unwrap();
+        assert_eq!(
+            payload["schema_version"],
+            json!(ConnectorEvent::SCHEMA_VERSION)
+        );
+        assert_eq!(payload["type"], json!("document_deleted"));
+
+        let decoded: ConnectorEvent = serde_json::from_value(payload).unwrap();
+        assert_eq!(decoded.document_id(), "doc-1");
+    }
 }
@@ -1,9 +1,30 @@
+//! Postgres-backed queue of connector events waiting to be indexed.
+//!
+//! Events are staged in `connector_events_outbox` in the same transaction as the sync run
+//! progress they account for, and [`OutboxRelay`] moves them into `connector_events_queue`.
+
 use anyhow::Result;
-use sqlx::{PgPool, Row};
+use sqlx::postgres::PgListener;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::time::Duration;
+use tokio::time::interval;
+use tracing::{debug, error, info};
 use ulid::Ulid;
 
 use crate::models::{ConnectorEvent, ConnectorEventQueueItem};
 
+/// Channel notified when events are staged in the outbox.
+pub const OUTBOX_CHANNEL: &str = "connector_events_outbox";
+
+fn event_type(event: &ConnectorEvent) -> &'static str {
+    match event {
+        ConnectorEvent::DocumentCreated { .. } => "document_created",
+        ConnectorEvent::DocumentUpdated { .. } => "document_updated",
+        ConnectorEvent::DocumentDeleted { .. } => "document_deleted",
+        ConnectorEvent::PermissionsUpdated { .. } => "permissions_updated",
+    }
+}
+
 #[derive(Clone)]
 pub struct EventQueue {
     pool: PgPool,
@@ -14,13 +35,10 @@ impl EventQueue {
         Self { pool }
     }
 
+    /// Write an event straight to the processing queue. Emitters that also update sync run
+    /// state should [`stage`](Self::stage) the event instead.
     pub async fn enqueue(&self, source_id: &str, event: &ConnectorEvent) -> Result<String> {
         let id = Ulid::new().to_string();
-        let event_type = match event {
-            ConnectorEvent::DocumentCreated { .. } => "document_created",
-            ConnectorEvent::DocumentUpdated { .. } => "document_updated",
-            ConnectorEvent::DocumentDeleted { .. } => "document_deleted",
-        };
 
         let mut tx = self.pool.begin().await?;
 
@@ -33,8 +51,8 @@ impl EventQueue {
         .bind(&id)
         .bind(event.sync_run_id())
         .bind(source_id)
-        .bind(event_type)
-        .bind(serde_json::to_value(event)?)
+        .bind(event_type(event))
+        .bind(event.to_payload()?)
         .execute(&mut *tx)
         .await?;
 
@@ -47,27 +65,151 @@ impl EventQueue {
         Ok(id)
     }
 
+    /// Stage an event in the outbox as part of the caller's transaction. The event reaches
+    /// the processing queue only if the transaction commits.
+    pub async fn stage(
+        tx: &mut Transaction<'_, Postgres>,
+        source_id: &str,
+        event: &ConnectorEvent,
+    ) -> Result<String> {
+        let id = Ulid::new().to_string();
+        Self::stage_with_id(tx, &id, source_id, event).await?;
+        Ok(id)
+    }
+
+    /// Stage an event under `id`, the idempotency key a connector sent it with, so an event
+    /// sent again by a retried request is delivered once. Returns whether the event was
+    /// staged, `false` when it still waits in the outbox from an earlier request; events
+    /// relayed already are dropped by the relay.
+    pub async fn stage_with_id(
+        tx: &mut Transaction<'_, Postgres>,
+        id: &str,
+        source_id: &str,
+        event: &ConnectorEvent,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO connector_events_outbox (id, sync_run_id, source_id, event_type, payload)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (id) DO NOTHING
+            "#,
+        )
+        .bind(id)
+        .bind(event.sync_run_id())
+        .bind(source_id)
+        .bind(event_type(event))
+        .bind(event.to_payload()?)
+        .execute(&mut **tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query(&format!("NOTIFY {}", OUTBOX_CHANNEL))
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(true)
+    }
+
+    /// Move up to `batch_size` of the oldest staged events into the processing queue,
+    /// returning the number taken from the outbox. Events already in the queue are dropped
+    /// rather than duplicated, and count as taken.
+    pub async fn relay_outbox(&self, batch_size: i64) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+
+        let (staged, relayed): (i64, i64) = sqlx::query_as(
+            r#"
+            WITH staged AS (
+                DELETE FROM connector_events_outbox
+                WHERE id IN (
+                    SELECT id
+                    FROM connector_events_outbox
+                    ORDER BY created_at
+                    LIMIT $1
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING id, sync_run_id, source_id, event_type, payload, created_at
+            ), relayed AS (
+                INSERT INTO connector_events_queue
+                    (id, sync_run_id, source_id, event_type, payload, created_at)
+                SELECT id, sync_run_id, source_id, event_type, payload, created_at
+                FROM staged
+                ON CONFLICT (id) DO NOTHING
+                RETURNING id
+            )
+            SELECT (SELECT COUNT(*) FROM staged), (SELECT COUNT(*) FROM relayed)
+            "#,
+        )
+        .bind(batch_size)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if relayed > 0 {
+            sqlx::query("NOTIFY indexer_queue")
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(staged as u64)
+    }
+
+    pub async fn get_outbox_count(&self) -> Result<i64> {
+        let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM connector_events_outbox")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(row.0)
+    }
+
+    /// Claim up to `batch_size` pending events of a single sync run, the one with the most
+    /// events that can be claimed. Events whose document has another event in process, or an
+    /// older one pending in another sync run, are left for later.
     pub async fn dequeue_batch(&self, batch_size: i32) -> Result<Vec<ConnectorEventQueueItem>> {
-        // Dequeue events from a single sync_run (the one with most pending events)
-        // This ensures each batch contains events from only one sync_run
+        let mut tx = self.pool.begin().await?;
+
+        // Replicas dequeuing at the same time could otherwise both find a document free and
+        // claim different events of it
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('connector_events_queue:dequeue'))")
+            .execute(&mut *tx)
+            .await?;
+
         let rows = sqlx::query(
             r#"
-            WITH target_sync_run AS (
+            WITH claimable AS (
+                SELECT q.id, q.sync_run_id
+                FROM connector_events_queue q
+                WHERE q.status = 'pending'
+                  AND NOT EXISTS (
+                      SELECT 1
+                      FROM connector_events_queue other
+                      WHERE other.source_id = q.source_id
+                        AND other.payload->>'document_id' = q.payload->>'document_id'
+                        AND other.status IN ('pending', 'processing')
+                        AND other.id <> q.id
+                        AND (
+                            other.status = 'processing'
+                            OR (other.sync_run_id <> q.sync_run_id
+                                AND (other.created_at, other.id) < (q.created_at, q.id))
+                        )
+                  )
+            ),
+            target_sync_run AS (
                 SELECT sync_run_id
-                FROM connector_events_queue
-                WHERE status = 'pending'
+                FROM claimable
                 GROUP BY sync_run_id
                 ORDER BY COUNT(*) DESC
                 LIMIT 1
             ),
             batch AS (
-                SELECT id
-                FROM connector_events_queue
-                WHERE status = 'pending'
-                  AND sync_run_id = (SELECT sync_run_id FROM target_sync_run)
-                ORDER BY created_at
+                SELECT q.id
+                FROM connector_events_queue q
+                JOIN claimable c ON c.id = q.id
+                WHERE c.sync_run_id = (SELECT sync_run_id FROM target_sync_run)
+                ORDER BY q.created_at, q.id
                 LIMIT $1
-                FOR UPDATE SKIP LOCKED
+                FOR UPDATE OF q SKIP LOCKED
             )
             UPDATE connector_events_queue q
             SET status = 'processing',
@@ -89,9 +231,11 @@ impl EventQueue {
             "#,
         )
         .bind(batch_size)
-        .fetch_all(&self.pool)
+        .fetch_all(&mut *tx)
         .await?;
 
+        tx.commit().await?;
+
         let mut events = Vec::new();
         for row in rows {
             let status_str: String = row.get("status");
@@ -405,6 +549,85 @@ impl EventQueue {
     }
 }
 
+/// Background task delivering staged outbox events to the processing queue. Woken by
+/// notifications on [`OUTBOX_CHANNEL`], with periodic polling as a fallback.
+pub struct OutboxRelay {
+    queue: EventQueue,
+    pool: PgPool,
+    batch_size: i64,
+    poll_interval: Duration,
+}
+
+impl OutboxRelay {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            queue: EventQueue::new(pool.clone()),
+            pool,
+            batch_size: 500,
+            poll_interval: Duration::from_secs(5),
+        }
+    }
+
+    pub async fn run(&self) {
+        let mut listener = self.listen().await;
+        let mut poll_interval = interval(self.poll_interval);
+        info!("Outbox relay started");
+
+        loop {
+            match listener.as_mut() {
+                Some(active) => {
+                    let notification = tokio::select! {
+                        notification = active.recv() => Some(notification),
+                        _ = poll_interval.tick() => None,
+                    };
+                    if let Some(Err(e)) = notification {
+                        error!("Outbox relay lost its notification listener: {}", e);
+                        listener = None;
+                    }
+                }
+                None => {
+                    poll_interval.tick().await;
+                    listener = self.listen().await;
+                }
+            }
+
+            if let Err(e) = self.drain().await {
+                error!("Failed to relay outbox events: {}", e);
+            }
+        }
+    }
+
+    async fn listen(&self) -> Option<PgListener> {
+        let result = async {
+            let mut listener = PgListener::connect_with(&self.pool).await?;
+            listener.listen(OUTBOX_CHANNEL).await?;
+            Ok::<_, sqlx::Error>(listener)
+        }
+        .await;
+
+        result
+            .map_err(|e| error!("Failed to listen for outbox notifications: {}", e))
+            .ok()
+    }
+
+    /// Relay batches until the outbox is empty, returning the number of events moved.
+    pub async fn drain(&self) -> Result<u64> {
+        let mut total = 0;
+        loop {
+            let relayed = self.queue.relay_outbox(self.batch_size).await?;
+            total += relayed;
+            if relayed < self.batch_size as u64 {
+                break;
+            }
+        }
+
+        if total > 0 {
+            debug!("Relayed {} outbox events to the processing queue", total);
+        }
+        Ok(total)
+    }
+}
+
 #[derive(Debug, serde::Serialize)]
 pub struct QueueStats {
     pub pending: i64,
@@ -1,9 +1,20 @@
+//! Rate limiting of requests to provider APIs.
+//!
+//! A [`RateLimiter`] is local to the process by default. Connectors running several replicas
+//! against one provider quota use [`RateLimiter::distributed`] instead, whose replicas take
+//! their tokens from one bucket in Redis. The bucket refills continuously up to one second of
+//! requests, and a request finding it empty reserves the next token and waits for it, so
+//! waiting replicas are served in order. While Redis is unreachable, each replica falls back
+//! to limiting itself to the whole quota.
+
+use crate::cache;
 use anyhow::Result;
 use governor::{Quota, RateLimiter as GovernorRateLimiter};
 use rand::{thread_rng, Rng};
+use redis::{Client as RedisClient, Script};
 use std::num::NonZeroU32;
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 use std::time::Instant;
 use tokio::time::sleep;
@@ -48,6 +59,52 @@ impl From<anyhow::Error> for RetryableError {
     }
 }
 
+/// Take a token from the bucket at `KEYS[1]` refilling at `ARGV[1]` tokens per second,
+/// returning the milliseconds to wait for it. Times are Redis's, so replicas' clocks may drift.
+pub(crate) const TAKE_TOKEN_SCRIPT: &str = r#"
+local rate = tonumber(ARGV[1])
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local bucket = redis.call('HMGET', KEYS[1], 'tokens', 'at')
+local tokens = tonumber(bucket[1]) or rate
+local at = tonumber(bucket[2]) or now
+tokens = math.min(rate, tokens + math.max(0, now - at) * rate / 1000) - 1
+redis.call('HSET', KEYS[1], 'tokens', tokens, 'at', now)
+local wait = 0
+if tokens < 0 then
+    wait = math.ceil(-tokens * 1000 / rate)
+end
+redis.call('PEXPIRE', KEYS[1], wait + 60000)
+return wait
+"#;
+
+fn take_token_script() -> &'static Script {
+    static SCRIPT: OnceLock<Script> = OnceLock::new();
+    SCRIPT.get_or_init(|| Script::new(TAKE_TOKEN_SCRIPT))
+}
+
+/// A token bucket in Redis, shared by every replica limiting requests under its name.
+#[derive(Clone)]
+struct SharedBucket {
+    redis_client: RedisClient,
+    name: String,
+    /// Whether Redis was unreachable on the last request, to log only when that changes.
+    unavailable: Arc<AtomicBool>,
+}
+
+impl SharedBucket {
+    /// Take a token, returning how long to wait before making the request.
+    async fn take(&self, requests_per_second: u32) -> redis::RedisResult<Duration> {
+        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
+        let wait_ms: u64 = take_token_script()
+            .key(cache::keys().rate_limit(&self.name))
+            .arg(requests_per_second)
+            .invoke_async(&mut conn)
+            .await?;
+        Ok(Duration::from_millis(wait_ms))
+    }
+}
+
 #[derive(Clone)]
 pub struct RateLimiter {
     limiter: Arc<
@@ -57,6 +114,7 @@ pub struct RateLimiter {
             governor::clock::DefaultClock,
         >,
     >,
+    shared: Option<SharedBucket>,
     max_retries: u32,
     request_count: Arc<AtomicU64>,
     last_log_time: Arc<std::sync::Mutex<Instant>>,
@@ -83,6 +141,7 @@ impl RateLimiter {
         );
         Self {
             limiter,
+            shared: None,
             max_retries,
             request_count: Arc::new(AtomicU64::new(0)),
             last_log_time: Arc::new(std::sync::Mutex::new(Instant::now())),
@@ -90,8 +149,71 @@ impl RateLimiter {
         }
     }
 
+    /// A limiter sharing `requests_per_second` with every replica limiting requests under
+    /// `name`, such as `google:api`.
+    pub fn distributed(
+        redis_client: RedisClient,
+        name: &str,
+        requests_per_second: u32,
+        max_retries: u32,
+    ) -> Self {
+        let mut limiter = Self::new(requests_per_second, max_retries);
+        limiter.shared = Some(SharedBucket {
+            redis_client,
+            name: name.to_string(),
+            unavailable: Arc::new(AtomicBool::new(false)),
+        });
+        limiter
+    }
+
+    /// A limiter of `requests_per_second` for `name` within this limiter's provider, such as
+    /// one user's requests where the provider limits each user too. Distributed when this
+    /// limiter is, under this limiter's name followed by `name`.
+    pub fn sub_bucket(&self, name: &str, requests_per_second: u32) -> Self {
+        match &self.shared {
+            Some(shared) => Self::distributed(
+                shared.redis_client.clone(),
+                &format!("{}:{}", shared.name, name),
+                requests_per_second,
+                self.max_retries,
+            ),
+            None => Self::new(requests_per_second, self.max_retries),
+        }
+    }
+
+    /// Wait for a token from the shared bucket, or from this replica's limiter while the
+    /// bucket is unreachable.
+    async fn until_ready(&self) {
+        let Some(shared) = &self.shared else {
+            self.limiter.until_ready().await;
+            return;
+        };
+        match shared.take(self.configured_rps).await {
+            Ok(wait) => {
+                if shared.unavailable.swap(false, Ordering::Relaxed) {
+                    info!(
+                        "Rate limiter {} is shared across replicas again",
+                        shared.name
+                    );
+                }
+                if !wait.is_zero() {
+                    sleep(wait).await;
+                }
+            }
+            Err(e) => {
+                if !shared.unavailable.swap(true, Ordering::Relaxed) {
+                    warn!(
+                        "Rate limiter {} cannot reach Redis, limiting this replica alone: {}",
+                        shared.name, e
+                    );
+                }
+                self.limiter.until_ready().await;
+            }
+        }
+    }
+
     pub async fn check_rate_limit(&self) -> Result<()> {
-        self.limiter.until_ready().await;
+        self.until_ready().await;
 
         self.request_count.fetch_add(1, Ordering::Relaxed);
 
@@ -180,6 +302,46 @@ mod tests {
     use anyhow::anyhow;
     use std::sync::atomic::{AtomicU32, Ordering};
 
+    #[tokio::test]
+    async fn test_distributed_falls_back_without_redis() {
+        // Nothing listens on port 1
+        let redis_client = RedisClient::open("redis://127.0.0.1:1").unwrap();
+        let limiter = RateLimiter::distributed(redis_client, "test", 100, 3);
+        let user_limiter = limiter.sub_bucket("user@example.com", 100);
+
+        for limiter in [&limiter, &user_limiter] {
+            let result = limiter
+                .execute(|| async { Ok::<_, anyhow::Error>(42) })
+                .await;
+            assert_eq!(result.unwrap(), 42);
+        }
+        assert_eq!(
+            user_limiter
+                .shared
+                .as_ref()
+                .map(|shared| shared.name.as_str()),
+            Some("test:user@example.com")
+        );
+        assert!(limiter.shared.unwrap().unavailable.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn test_shared_bucket_waits_once_empty() {
+        let redis = crate::test_environment::MemoryRedis::start().await.unwrap();
+        let redis_client = RedisClient::open(format!("redis://localhost:{}", redis.port)).unwrap();
+        let bucket = SharedBucket {
+            redis_client,
+            name: "test:shared_bucket".to_string(),
+            unavailable: Arc::new(AtomicBool::new(false)),
+        };
+
+        for _ in 0..2 {
+            assert!(bucket.take(2).await.unwrap().is_zero());
+        }
+        let wait = bucket.take(2).await.unwrap();
+        assert!(!wait.is_zero() && wait <= Duration::from_millis(500));
+    }
+
     #[tokio::test]
     async fn test_execute_success() {
         let limiter = RateLimiter::new(100, 3);
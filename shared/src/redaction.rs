@@ -0,0 +1,101 @@
+//! Redaction of credential fields in values that end up in API responses and logs.
+
+use serde_json::Value as JsonValue;
+
+/// Placeholder replacing redacted values.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Field name suffixes that mark a value as a credential, e.g. `refresh_token` or
+/// `client_secret`. Matched against the lowercased field name.
+const SECRET_FIELD_SUFFIXES: &[&str] = &[
+    "token",
+    "secret",
+    "password",
+    "passphrase",
+    "api_key",
+    "apikey",
+    "private_key",
+    "access_key",
+    "credentials",
+];
+
+/// Whether a field with this name holds a credential.
+pub fn is_secret_field(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SECRET_FIELD_SUFFIXES
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+}
+
+/// Copy of `value` with every credential field, at any depth, replaced by [`REDACTED`].
+pub fn redact_json(value: &JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Object(map) => JsonValue::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let value = if is_secret_field(key) && !value.is_null() {
+                        JsonValue::String(REDACTED.to_string())
+                    } else {
+                        redact_json(value)
+                    };
+                    (key.clone(), value)
+                })
+                .collect(),
+        ),
+        JsonValue::Array(items) => JsonValue::Array(items.iter().map(redact_json).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Redact every value of a credentials object, keeping only its keys so logs still show
+/// which credential fields are present.
+pub fn redact_all(value: &JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Object(map) => JsonValue::Object(
+            map.keys()
+                .map(|key| (key.clone(), JsonValue::String(REDACTED.to_string())))
+                .collect(),
+        ),
+        JsonValue::Null => JsonValue::Null,
+        _ => JsonValue::String(REDACTED.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_json_masks_nested_secret_fields() {
+        let config = json!({
+            "api_url": "https://api.example.com",
+            "api_token": "tok_123",
+            "max_tokens": 100,
+            "token_uri": "https://oauth2.googleapis.com/token",
+            "oauth": { "client_id": "abc", "client_secret": "shh", "refresh_token": null },
+            "webhooks": [{ "url": "https://hooks.example.com", "signing_secret": "s" }]
+        });
+
+        assert_eq!(
+            redact_json(&config),
+            json!({
+                "api_url": "https://api.example.com",
+                "api_token": REDACTED,
+                "max_tokens": 100,
+                "token_uri": "https://oauth2.googleapis.com/token",
+                "oauth": { "client_id": "abc", "client_secret": REDACTED, "refresh_token": null },
+                "webhooks": [{ "url": "https://hooks.example.com", "signing_secret": REDACTED }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_redact_all_keeps_keys() {
+        assert_eq!(
+            redact_all(&json!({ "client_email": "svc@example.com", "private_key": "pem" })),
+            json!({ "client_email": REDACTED, "private_key": REDACTED })
+        );
+        assert_eq!(redact_all(&json!("raw-token")), json!(REDACTED));
+    }
+}
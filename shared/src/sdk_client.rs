@@ -1,9 +1,221 @@
-use anyhow::{Context, Result};
-use reqwest::Client;
+//! HTTP client of the connector-manager SDK endpoints.
+//!
+//! Requests failing transiently, on a connection error, a timeout or a 5xx or 429 answer, are
+//! sent again with exponential backoff, so a connector does not fail a whole sync page on a
+//! hiccup of connector-manager or the database behind it. Two limits keep retries from piling
+//! onto a struggling connector-manager:
+//!
+//! - A retry budget: each request earns a fraction of a retry, so retries stay a bounded
+//!   share of requests once the reserve is spent.
+//! - A circuit breaker: after consecutive failures, requests fail without being sent until
+//!   a cooldown passes, then the next request tries again.
+//!
+//! Events are sent with an idempotency key, which connector-manager stages them under, so an
+//! event whose request is retried after it was received is indexed once. Requests that are
+//! not idempotent, such as creating a sync run, are sent again only when connector-manager
+//! surely did not act on them.
+
+use anyhow::{anyhow, Context, Result};
+use bytes::Bytes;
+use reqwest::{Client, Method, RequestBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
-use tracing::debug;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+use crate::api_error::ApiError;
+use crate::exclusion::{ContentExclusions, ExclusionRules};
+use crate::models::{ConnectorEvent, ServiceCredentials, Source, SyncRequest, SyncType};
+use crate::utils::generate_ulid;
+
+/// Header carrying the key a request is deduplicated by when sent more than once.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Retries, timeouts and circuit breaking of an [`SdkClient`].
+#[derive(Debug, Clone)]
+pub struct SdkClientConfig {
+    /// Times a request failing transiently is sent again.
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub request_timeout: Duration,
+    /// Retries earned by each request.
+    pub retry_budget_ratio: f64,
+    /// Retries available before any request earned them, and the most that can be saved up.
+    pub retry_budget_reserve: f64,
+    /// Consecutive failures opening the circuit.
+    pub circuit_breaker_threshold: u32,
+    /// How long the circuit stays open.
+    pub circuit_breaker_cooldown: Duration,
+}
+
+impl Default for SdkClientConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+            request_timeout: Duration::from_secs(60),
+            retry_budget_ratio: 0.2,
+            retry_budget_reserve: 10.0,
+            circuit_breaker_threshold: 10,
+            circuit_breaker_cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+impl SdkClientConfig {
+    /// The defaults, overridden by `SDK_MAX_RETRIES`, `SDK_REQUEST_TIMEOUT_SECS`,
+    /// `SDK_CIRCUIT_BREAKER_THRESHOLD` and `SDK_CIRCUIT_BREAKER_COOLDOWN_SECS` when set.
+    pub fn from_env() -> Self {
+        fn env<T: std::str::FromStr>(name: &str) -> Option<T> {
+            std::env::var(name)
+                .ok()
+                .and_then(|value| value.parse().ok())
+        }
+
+        let mut config = Self::default();
+        if let Some(max_retries) = env("SDK_MAX_RETRIES") {
+            config.max_retries = max_retries;
+        }
+        if let Some(secs) = env::<u64>("SDK_REQUEST_TIMEOUT_SECS").filter(|secs| *secs > 0) {
+            config.request_timeout = Duration::from_secs(secs);
+        }
+        if let Some(threshold) = env::<u32>("SDK_CIRCUIT_BREAKER_THRESHOLD") {
+            config.circuit_breaker_threshold = threshold.max(1);
+        }
+        if let Some(secs) = env("SDK_CIRCUIT_BREAKER_COOLDOWN_SECS") {
+            config.circuit_breaker_cooldown = Duration::from_secs(secs);
+        }
+        config
+    }
+
+    /// Delay before the retry following attempt `attempt`, counted from zero.
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff)
+    }
+}
+
+/// Whether a request may be sent again when it failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Retry {
+    /// On any transient failure, as acting on the request twice does no harm.
+    Always,
+    /// Only when connector-manager surely did not act on it: the connection failed, or it
+    /// answered 429 or 503.
+    Unsent,
+}
+
+/// Retries left to spend, earned by the requests sent.
+#[derive(Debug)]
+struct RetryBudget {
+    ratio: f64,
+    reserve: f64,
+    balance: Mutex<f64>,
+}
+
+impl RetryBudget {
+    fn new(ratio: f64, reserve: f64) -> Self {
+        Self {
+            ratio,
+            reserve,
+            balance: Mutex::new(reserve),
+        }
+    }
 
-use crate::models::{ConnectorEvent, ServiceCredentials, Source, SyncType};
+    fn deposit(&self) {
+        let mut balance = self.balance.lock().unwrap();
+        *balance = (*balance + self.ratio).min(self.reserve);
+    }
+
+    fn withdraw(&self) -> bool {
+        let mut balance = self.balance.lock().unwrap();
+        if *balance < 1.0 {
+            return false;
+        }
+        *balance -= 1.0;
+        true
+    }
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+/// Stops requests to connector-manager for a while once they keep failing.
+#[derive(Debug)]
+struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreaker {
+    fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// How long requests are still refused, `None` once they may be sent.
+    fn open_for(&self) -> Option<Duration> {
+        let state = self.state.lock().unwrap();
+        state
+            .open_until
+            .map(|until| until.saturating_duration_since(Instant::now()))
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    fn record_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.open_until.is_some() {
+            info!("SDK: connector-manager is answering again, closing the circuit");
+        }
+        *state = BreakerState::default();
+    }
+
+    fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures += 1;
+        // Failing again after the cooldown reopens it at once
+        if state.consecutive_failures >= self.threshold {
+            if state.open_until.is_none() {
+                warn!(
+                    "SDK: {} consecutive requests to connector-manager failed, pausing requests for {:?}",
+                    state.consecutive_failures, self.cooldown
+                );
+            }
+            state.open_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+}
+
+/// Whether a response status tells of a failure that may pass, rather than of the request.
+fn is_transient(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+        || (status.is_server_error() && status != StatusCode::NOT_IMPLEMENTED)
+}
+
+/// The delay asked for by a `Retry-After` header in seconds.
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
 
 /// HTTP client for communicating with connector-manager SDK endpoints.
 /// This is the standard way for connectors to interact with the connector-manager
@@ -12,6 +224,15 @@ use crate::models::{ConnectorEvent, ServiceCredentials, Source, SyncType};
 pub struct SdkClient {
     client: Client,
     base_url: String,
+    config: SdkClientConfig,
+    retry_budget: Arc<RetryBudget>,
+    breaker: Arc<CircuitBreaker>,
+    /// Source tokens the connector manager sent with sync requests, by source and sync run.
+    tokens: Arc<RwLock<HashMap<String, String>>>,
+    /// Syncs started and not yet finished, to pause if the connector shuts down.
+    active_syncs: Arc<RwLock<HashSet<String>>>,
+    /// Set once active syncs are paused for shutdown.
+    paused: Arc<AtomicBool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -50,6 +271,11 @@ struct FailRequest {
     error: String,
 }
 
+#[derive(Debug, Serialize)]
+struct PauseRequest {
+    new_state: Option<serde_json::Value>,
+}
+
 #[derive(Debug, Serialize)]
 struct CreateSyncRequest {
     source_id: String,
@@ -110,16 +336,175 @@ struct ExpiringWebhookChannelsRequest {
 
 impl SdkClient {
     pub fn new(connector_manager_url: &str) -> Self {
+        Self::with_config(connector_manager_url, SdkClientConfig::default())
+    }
+
+    pub fn with_config(connector_manager_url: &str, config: SdkClientConfig) -> Self {
         Self {
-            client: Client::new(),
+            client: crate::tls::client_builder()
+                .expect("Failed to configure mutual TLS")
+                .timeout(config.request_timeout)
+                .build()
+                .expect("Failed to create HTTP client"),
             base_url: connector_manager_url.trim_end_matches('/').to_string(),
+            retry_budget: Arc::new(RetryBudget::new(
+                config.retry_budget_ratio,
+                config.retry_budget_reserve,
+            )),
+            breaker: Arc::new(CircuitBreaker::new(
+                config.circuit_breaker_threshold,
+                config.circuit_breaker_cooldown,
+            )),
+            config,
+            tokens: Arc::new(RwLock::new(HashMap::new())),
+            active_syncs: Arc::new(RwLock::new(HashSet::new())),
+            paused: Arc::new(AtomicBool::new(false)),
         }
     }
 
     pub fn from_env() -> Result<Self> {
         let url =
             std::env::var("CONNECTOR_MANAGER_URL").context("CONNECTOR_MANAGER_URL not set")?;
-        Ok(Self::new(&url))
+        Ok(Self::with_config(&url, SdkClientConfig::from_env()))
+    }
+
+    /// Track the sync of `request` until it finishes, authenticating calls for its source and
+    /// sync run with the token sent with it. Connectors call this on receiving a sync request.
+    pub fn start_sync(&self, request: &SyncRequest) {
+        self.active_syncs
+            .write()
+            .unwrap()
+            .insert(request.sync_run_id.clone());
+        let Some(token) = &request.sdk_token else {
+            return;
+        };
+        let mut tokens = self.tokens.write().unwrap();
+        tokens.insert(request.source_id.clone(), token.clone());
+        tokens.insert(request.sync_run_id.clone(), token.clone());
+    }
+
+    /// Track `sync_run_id`, authenticating calls for it with the token of its source.
+    fn share_token(&self, source_id: &str, sync_run_id: &str) {
+        self.active_syncs
+            .write()
+            .unwrap()
+            .insert(sync_run_id.to_string());
+        let mut tokens = self.tokens.write().unwrap();
+        if let Some(token) = tokens.get(source_id).cloned() {
+            tokens.insert(sync_run_id.to_string(), token);
+        }
+    }
+
+    fn finish_sync(&self, sync_run_id: &str) {
+        self.active_syncs.write().unwrap().remove(sync_run_id);
+        self.tokens.write().unwrap().remove(sync_run_id);
+    }
+
+    /// A request to `url` for the source or sync run `scope`, authenticated with its token when
+    /// there is one. Calls not for any one source use whichever token there is.
+    fn request(&self, method: Method, url: String, scope: Option<&str>) -> RequestBuilder {
+        let tokens = self.tokens.read().unwrap();
+        let token = match scope {
+            Some(scope) => tokens.get(scope),
+            None => tokens.values().next(),
+        };
+        let request = self.client.request(method, url);
+        match token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    fn get(&self, url: String, scope: Option<&str>) -> RequestBuilder {
+        self.request(Method::GET, url, scope)
+    }
+
+    fn post(&self, url: String, scope: Option<&str>) -> RequestBuilder {
+        self.request(Method::POST, url, scope)
+    }
+
+    fn delete(&self, url: String, scope: Option<&str>) -> RequestBuilder {
+        self.request(Method::DELETE, url, scope)
+    }
+
+    /// Send the request built by `request`, again as `retry` allows while it fails
+    /// transiently, returning its response once successful. `action` names the request in
+    /// errors, such as `emit event`.
+    async fn send(
+        &self,
+        action: &str,
+        retry: Retry,
+        request: impl Fn() -> RequestBuilder,
+    ) -> Result<Response> {
+        self.retry_budget.deposit();
+        let mut attempt = 0;
+        loop {
+            if let Some(remaining) = self.breaker.open_for() {
+                anyhow::bail!(
+                    "Failed to {}: connector-manager keeps failing, requests are paused for {:?}",
+                    action,
+                    remaining
+                );
+            }
+
+            let (error, retriable, delay) = match request().send().await {
+                Ok(response) if response.status().is_success() => {
+                    self.breaker.record_success();
+                    return Ok(response);
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    let delay = retry_after(&response);
+                    let body = response.text().await.unwrap_or_default();
+                    let transient = is_transient(status);
+                    if transient {
+                        self.breaker.record_failure();
+                    } else {
+                        self.breaker.record_success();
+                    }
+                    let retriable = match retry {
+                        Retry::Always => {
+                            transient
+                                || serde_json::from_str::<ApiError>(&body)
+                                    .is_ok_and(|error| error.retriable)
+                        }
+                        Retry::Unsent => matches!(
+                            status,
+                            StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+                        ),
+                    };
+                    let error = anyhow!("Failed to {}: {} - {}", action, status, body);
+                    (error, retriable, delay)
+                }
+                Err(e) => {
+                    self.breaker.record_failure();
+                    let retriable = match retry {
+                        Retry::Always => e.is_connect() || e.is_timeout() || e.is_request(),
+                        Retry::Unsent => e.is_connect(),
+                    };
+                    let error =
+                        anyhow::Error::new(e).context(format!("Failed to send {} request", action));
+                    (error, retriable, None)
+                }
+            };
+
+            if !retriable || attempt >= self.config.max_retries || !self.retry_budget.withdraw() {
+                return Err(error);
+            }
+            let delay = delay
+                .unwrap_or_else(|| self.config.backoff(attempt))
+                .min(self.config.max_backoff);
+            warn!(
+                "SDK: Retrying {} in {:?} (retry {}/{}): {:#}",
+                action,
+                delay,
+                attempt + 1,
+                self.config.max_retries,
+                error
+            );
+            sleep(delay).await;
+            attempt += 1;
+        }
     }
 
     /// Emit a document event to the queue
@@ -136,20 +521,15 @@ impl SdkClient {
             source_id: source_id.to_string(),
             event,
         };
+        // Sent with every attempt, so connector-manager stages the event once
+        let idempotency_key = generate_ulid();
 
-        let response = self
-            .client
-            .post(format!("{}/sdk/events", self.base_url))
-            .json(&request)
-            .send()
-            .await
-            .context("Failed to send emit event request")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to emit event: {} - {}", status, body);
-        }
+        self.send("emit event", Retry::Always, || {
+            self.post(format!("{}/sdk/events", self.base_url), Some(source_id))
+                .header(IDEMPOTENCY_KEY_HEADER, &idempotency_key)
+                .json(&request)
+        })
+        .await?;
 
         Ok(())
     }
@@ -165,18 +545,41 @@ impl SdkClient {
         };
 
         let response = self
-            .client
-            .post(format!("{}/sdk/content", self.base_url))
-            .json(&request)
-            .send()
-            .await
-            .context("Failed to send store content request")?;
+            .send("store content", Retry::Always, || {
+                self.post(format!("{}/sdk/content", self.base_url), Some(sync_run_id))
+                    .json(&request)
+            })
+            .await?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to store content: {} - {}", status, body);
-        }
+        let result: StoreContentResponse = response.json().await?;
+        Ok(result.content_id)
+    }
+
+    /// Store binary content, such as a thumbnail image, and return content_id
+    pub async fn store_binary_content(
+        &self,
+        sync_run_id: &str,
+        content: Vec<u8>,
+        content_type: &str,
+    ) -> Result<String> {
+        debug!(
+            "SDK: Storing {} bytes of {} for sync_run={}",
+            content.len(),
+            content_type,
+            sync_run_id
+        );
+        let content = Bytes::from(content);
+
+        let response = self
+            .send("store binary content", Retry::Always, || {
+                self.post(
+                    format!("{}/sdk/sync/{}/content", self.base_url, sync_run_id),
+                    Some(sync_run_id),
+                )
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .body(content.clone())
+            })
+            .await?;
 
         let result: StoreContentResponse = response.json().await?;
         Ok(result.content_id)
@@ -186,21 +589,13 @@ impl SdkClient {
     pub async fn heartbeat(&self, sync_run_id: &str) -> Result<()> {
         debug!("SDK: Heartbeat for sync_run={}", sync_run_id);
 
-        let response = self
-            .client
-            .post(format!(
-                "{}/sdk/sync/{}/heartbeat",
-                self.base_url, sync_run_id
-            ))
-            .send()
-            .await
-            .context("Failed to send heartbeat")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to heartbeat: {} - {}", status, body);
-        }
+        self.send("heartbeat", Retry::Always, || {
+            self.post(
+                format!("{}/sdk/sync/{}/heartbeat", self.base_url, sync_run_id),
+                Some(sync_run_id),
+            )
+        })
+        .await?;
 
         Ok(())
     }
@@ -212,22 +607,14 @@ impl SdkClient {
             sync_run_id, count
         );
 
-        let response = self
-            .client
-            .post(format!(
-                "{}/sdk/sync/{}/scanned",
-                self.base_url, sync_run_id
-            ))
+        self.send("increment scanned", Retry::Unsent, || {
+            self.post(
+                format!("{}/sdk/sync/{}/scanned", self.base_url, sync_run_id),
+                Some(sync_run_id),
+            )
             .json(&serde_json::json!({ "count": count }))
-            .send()
-            .await
-            .context("Failed to send increment scanned")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to increment scanned: {} - {}", status, body);
-        }
+        })
+        .await?;
 
         Ok(())
     }
@@ -248,67 +635,96 @@ impl SdkClient {
             new_state,
         };
 
-        let response = self
-            .client
-            .post(format!(
-                "{}/sdk/sync/{}/complete",
-                self.base_url, sync_run_id
-            ))
+        self.send("complete", Retry::Always, || {
+            self.post(
+                format!("{}/sdk/sync/{}/complete", self.base_url, sync_run_id),
+                Some(sync_run_id),
+            )
             .json(&request)
-            .send()
-            .await
-            .context("Failed to send complete request")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to complete: {} - {}", status, body);
-        }
+        })
+        .await?;
+        self.finish_sync(sync_run_id);
 
         Ok(())
     }
 
     /// Mark sync as failed
     pub async fn fail(&self, sync_run_id: &str, error: &str) -> Result<()> {
+        if self.paused.load(Ordering::SeqCst) {
+            // Failures after pausing are the shutdown's doing, not the sync's
+            info!(
+                "SDK: Not failing paused sync_run={}: {}",
+                sync_run_id, error
+            );
+            return Ok(());
+        }
         debug!("SDK: Failing sync_run={}: {}", sync_run_id, error);
 
         let request = FailRequest {
             error: error.to_string(),
         };
 
-        let response = self
-            .client
-            .post(format!("{}/sdk/sync/{}/fail", self.base_url, sync_run_id))
+        self.send("mark as failed", Retry::Always, || {
+            self.post(
+                format!("{}/sdk/sync/{}/fail", self.base_url, sync_run_id),
+                Some(sync_run_id),
+            )
             .json(&request)
-            .send()
-            .await
-            .context("Failed to send fail request")?;
+        })
+        .await?;
+        self.finish_sync(sync_run_id);
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to mark as failed: {} - {}", status, body);
-        }
+        Ok(())
+    }
+
+    /// Mark sync as paused, to be resumed from `new_state` once the connector is back. The
+    /// source's connector state is left as last saved without one.
+    pub async fn pause(
+        &self,
+        sync_run_id: &str,
+        new_state: Option<serde_json::Value>,
+    ) -> Result<()> {
+        debug!("SDK: Pausing sync_run={}", sync_run_id);
+        let request = PauseRequest { new_state };
+
+        self.send("pause", Retry::Always, || {
+            self.post(
+                format!("{}/sdk/sync/{}/pause", self.base_url, sync_run_id),
+                Some(sync_run_id),
+            )
+            .json(&request)
+        })
+        .await?;
+        self.finish_sync(sync_run_id);
 
         Ok(())
     }
 
+    /// Pause every sync started and not yet finished, as the connector shuts down. Failures
+    /// reported afterwards are dropped, as the shutdown caused them.
+    pub async fn pause_active_syncs(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+        let active: Vec<String> = self.active_syncs.read().unwrap().iter().cloned().collect();
+        for sync_run_id in active {
+            info!("Pausing sync {} for shutdown", sync_run_id);
+            if let Err(e) = self.pause(&sync_run_id, None).await {
+                warn!("Failed to pause sync {}: {}", sync_run_id, e);
+            }
+        }
+    }
+
     /// Get source configuration
     pub async fn get_source(&self, source_id: &str) -> Result<Source> {
         debug!("SDK: Getting source config for source_id={}", source_id);
 
         let response = self
-            .client
-            .get(format!("{}/sdk/source/{}", self.base_url, source_id))
-            .send()
-            .await
-            .context("Failed to send get source request")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to get source: {} - {}", status, body);
-        }
+            .send("get source", Retry::Always, || {
+                self.get(
+                    format!("{}/sdk/source/{}", self.base_url, source_id),
+                    Some(source_id),
+                )
+            })
+            .await?;
 
         let source: Source = response
             .json()
@@ -322,20 +738,13 @@ impl SdkClient {
         debug!("SDK: Getting connector state for source_id={}", source_id);
 
         let response = self
-            .client
-            .get(format!(
-                "{}/sdk/source/{}/sync-config",
-                self.base_url, source_id
-            ))
-            .send()
-            .await
-            .context("Failed to send get sync config request")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to get sync config: {} - {}", status, body);
-        }
+            .send("get sync config", Retry::Always, || {
+                self.get(
+                    format!("{}/sdk/source/{}/sync-config", self.base_url, source_id),
+                    Some(source_id),
+                )
+            })
+            .await?;
 
         let config: SyncConfigResponse = response
             .json()
@@ -349,17 +758,13 @@ impl SdkClient {
         debug!("SDK: Getting credentials for source_id={}", source_id);
 
         let response = self
-            .client
-            .get(format!("{}/sdk/credentials/{}", self.base_url, source_id))
-            .send()
-            .await
-            .context("Failed to send get credentials request")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to get credentials: {} - {}", status, body);
-        }
+            .send("get credentials", Retry::Always, || {
+                self.get(
+                    format!("{}/sdk/credentials/{}", self.base_url, source_id),
+                    Some(source_id),
+                )
+            })
+            .await?;
 
         let credentials: ServiceCredentials = response
             .json()
@@ -381,23 +786,20 @@ impl SdkClient {
         };
 
         let response = self
-            .client
-            .post(format!("{}/sdk/sync/create", self.base_url))
-            .json(&request)
-            .send()
-            .await
-            .context("Failed to send create sync run request")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to create sync run: {} - {}", status, body);
-        }
+            .send("create sync run", Retry::Unsent, || {
+                self.post(
+                    format!("{}/sdk/sync/create", self.base_url),
+                    Some(source_id),
+                )
+                .json(&request)
+            })
+            .await?;
 
         let result: CreateSyncResponse = response
             .json()
             .await
             .context("Failed to parse create sync response")?;
+        self.share_token(source_id, &result.sync_run_id);
         Ok(result.sync_run_id)
     }
 
@@ -409,42 +811,49 @@ impl SdkClient {
             sync_run_id: sync_run_id.to_string(),
         };
 
-        let response = self
-            .client
-            .post(format!("{}/sdk/sync/cancel", self.base_url))
+        self.send("cancel sync", Retry::Always, || {
+            self.post(
+                format!("{}/sdk/sync/cancel", self.base_url),
+                Some(sync_run_id),
+            )
             .json(&request)
-            .send()
-            .await
-            .context("Failed to send cancel request")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to cancel sync: {} - {}", status, body);
-        }
+        })
+        .await?;
+        self.finish_sync(sync_run_id);
 
         Ok(())
     }
 
+    /// Get the workspace content exclusion rules, to check items against before fetching
+    /// their content
+    pub async fn get_content_exclusions(&self) -> Result<ContentExclusions> {
+        debug!("SDK: Getting content exclusion rules");
+
+        let response = self
+            .send("get exclusion rules", Retry::Always, || {
+                self.get(format!("{}/sdk/exclusion-rules", self.base_url), None)
+            })
+            .await?;
+
+        let rules: ExclusionRules = response
+            .json()
+            .await
+            .context("Failed to parse exclusion rules response")?;
+        ContentExclusions::new(rules).map_err(anyhow::Error::msg)
+    }
+
     /// Get user email for a source
     pub async fn get_user_email_for_source(&self, source_id: &str) -> Result<String> {
         debug!("SDK: Getting user email for source_id={}", source_id);
 
         let response = self
-            .client
-            .get(format!(
-                "{}/sdk/source/{}/user-email",
-                self.base_url, source_id
-            ))
-            .send()
-            .await
-            .context("Failed to send get user email request")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to get user email: {} - {}", status, body);
-        }
+            .send("get user email", Retry::Always, || {
+                self.get(
+                    format!("{}/sdk/source/{}/user-email", self.base_url, source_id),
+                    Some(source_id),
+                )
+            })
+            .await?;
 
         let result: UserEmailResponse = response
             .json()
@@ -467,23 +876,20 @@ impl SdkClient {
         };
 
         let response = self
-            .client
-            .post(format!("{}/sdk/webhook/notify", self.base_url))
-            .json(&request)
-            .send()
-            .await
-            .context("Failed to send webhook notification")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to notify webhook: {} - {}", status, body);
-        }
+            .send("notify webhook", Retry::Unsent, || {
+                self.post(
+                    format!("{}/sdk/webhook/notify", self.base_url),
+                    Some(source_id),
+                )
+                .json(&request)
+            })
+            .await?;
 
         let result: WebhookNotificationResponse = response
             .json()
             .await
             .context("Failed to parse webhook notification response")?;
+        self.share_token(source_id, &result.sync_run_id);
         Ok(result.sync_run_id)
     }
 
@@ -512,18 +918,14 @@ impl SdkClient {
         };
 
         let response = self
-            .client
-            .post(format!("{}/sdk/webhook/channel", self.base_url))
-            .json(&request)
-            .send()
-            .await
-            .context("Failed to save webhook channel")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to save webhook channel: {} - {}", status, body);
-        }
+            .send("save webhook channel", Retry::Always, || {
+                self.post(
+                    format!("{}/sdk/webhook/channel", self.base_url),
+                    Some(source_id),
+                )
+                .json(&request)
+            })
+            .await?;
 
         let result: WebhookChannel = response
             .json()
@@ -537,20 +939,13 @@ impl SdkClient {
         debug!("SDK: Getting webhook channel by channel_id={}", channel_id);
 
         let response = self
-            .client
-            .get(format!(
-                "{}/sdk/webhook/channel/{}",
-                self.base_url, channel_id
-            ))
-            .send()
-            .await
-            .context("Failed to get webhook channel")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to get webhook channel: {} - {}", status, body);
-        }
+            .send("get webhook channel", Retry::Always, || {
+                self.get(
+                    format!("{}/sdk/webhook/channel/{}", self.base_url, channel_id),
+                    None,
+                )
+            })
+            .await?;
 
         let result: WebhookChannel = response
             .json()
@@ -567,24 +962,16 @@ impl SdkClient {
         debug!("SDK: Getting webhook channel by source_id={}", source_id);
 
         let response = self
-            .client
-            .get(format!(
-                "{}/sdk/webhook/channel/by-source/{}",
-                self.base_url, source_id
-            ))
-            .send()
-            .await
-            .context("Failed to get webhook channel by source")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!(
-                "Failed to get webhook channel by source: {} - {}",
-                status,
-                body
-            );
-        }
+            .send("get webhook channel by source", Retry::Always, || {
+                self.get(
+                    format!(
+                        "{}/sdk/webhook/channel/by-source/{}",
+                        self.base_url, source_id
+                    ),
+                    Some(source_id),
+                )
+            })
+            .await?;
 
         let result: Option<WebhookChannel> = response
             .json()
@@ -597,21 +984,13 @@ impl SdkClient {
     pub async fn delete_webhook_channel(&self, channel_id: &str) -> Result<()> {
         debug!("SDK: Deleting webhook channel channel_id={}", channel_id);
 
-        let response = self
-            .client
-            .delete(format!(
-                "{}/sdk/webhook/channel/{}",
-                self.base_url, channel_id
-            ))
-            .send()
-            .await
-            .context("Failed to delete webhook channel")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to delete webhook channel: {} - {}", status, body);
-        }
+        self.send("delete webhook channel", Retry::Always, || {
+            self.delete(
+                format!("{}/sdk/webhook/channel/{}", self.base_url, channel_id),
+                None,
+            )
+        })
+        .await?;
 
         Ok(())
     }
@@ -629,22 +1008,14 @@ impl SdkClient {
         let request = ExpiringWebhookChannelsRequest { hours_ahead };
 
         let response = self
-            .client
-            .post(format!("{}/sdk/webhook/channels/expiring", self.base_url))
-            .json(&request)
-            .send()
-            .await
-            .context("Failed to get expiring webhook channels")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!(
-                "Failed to get expiring webhook channels: {} - {}",
-                status,
-                body
-            );
-        }
+            .send("get expiring webhook channels", Retry::Always, || {
+                self.post(
+                    format!("{}/sdk/webhook/channels/expiring", self.base_url),
+                    None,
+                )
+                .json(&request)
+            })
+            .await?;
 
         let result: Vec<WebhookChannel> = response
             .json()
@@ -653,3 +1024,117 @@ impl SdkClient {
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::State;
+    use axum::http::HeaderMap;
+    use axum::routing::post;
+    use axum::Router;
+
+    fn quick_config() -> SdkClientConfig {
+        SdkClientConfig {
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(4),
+            ..SdkClientConfig::default()
+        }
+    }
+
+    /// Serve `/sdk/events`, answering 503 to the first `failures` requests, and return the
+    /// connector-manager URL and the idempotency keys of the requests received.
+    async fn flaky_events_endpoint(failures: usize) -> (String, Arc<Mutex<Vec<String>>>) {
+        let keys = Arc::new(Mutex::new(Vec::new()));
+        let app = Router::new()
+            .route(
+                "/sdk/events",
+                post(
+                    move |State(keys): State<Arc<Mutex<Vec<String>>>>, headers: HeaderMap| async move {
+                        let mut keys = keys.lock().unwrap();
+                        keys.push(
+                            headers
+                                .get(IDEMPOTENCY_KEY_HEADER)
+                                .and_then(|key| key.to_str().ok())
+                                .unwrap_or_default()
+                                .to_string(),
+                        );
+                        if keys.len() <= failures {
+                            axum::http::StatusCode::SERVICE_UNAVAILABLE
+                        } else {
+                            axum::http::StatusCode::OK
+                        }
+                    },
+                ),
+            )
+            .with_state(keys.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (url, keys)
+    }
+
+    fn event() -> ConnectorEvent {
+        ConnectorEvent::DocumentDeleted {
+            sync_run_id: "run-1".to_string(),
+            source_id: "source-1".to_string(),
+            document_id: "doc-1".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_event_retried_with_one_idempotency_key() {
+        let (url, keys) = flaky_events_endpoint(2).await;
+        let client = SdkClient::with_config(&url, quick_config());
+
+        client
+            .emit_event("run-1", "source-1", event())
+            .await
+            .unwrap();
+
+        let keys = keys.lock().unwrap();
+        assert_eq!(keys.len(), 3);
+        assert!(ulid::Ulid::from_string(&keys[0]).is_ok());
+        assert!(keys.iter().all(|key| *key == keys[0]));
+    }
+
+    #[tokio::test]
+    async fn test_circuit_opens_after_consecutive_failures() {
+        let (url, keys) = flaky_events_endpoint(usize::MAX).await;
+        let client = SdkClient::with_config(
+            &url,
+            SdkClientConfig {
+                max_retries: 1,
+                circuit_breaker_threshold: 3,
+                ..quick_config()
+            },
+        );
+
+        for _ in 0..2 {
+            assert!(client
+                .emit_event("run-1", "source-1", event())
+                .await
+                .is_err());
+        }
+        // The third failure opened the circuit, so the last attempts were never sent
+        assert_eq!(keys.lock().unwrap().len(), 3);
+        let error = client
+            .emit_event("run-1", "source-1", event())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("requests are paused"));
+        assert_eq!(keys.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_retry_budget_is_earned_by_requests() {
+        let budget = RetryBudget::new(0.5, 2.0);
+        assert!(budget.withdraw());
+        assert!(budget.withdraw());
+        assert!(!budget.withdraw());
+
+        budget.deposit();
+        assert!(!budget.withdraw());
+        budget.deposit();
+        assert!(budget.withdraw());
+    }
+}
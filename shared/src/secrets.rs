@@ -0,0 +1,268 @@
+//! Secrets providers for database credentials, API tokens and encryption keys.
+//!
+//! `SECRETS_PROVIDER` selects where secrets come from: `env` (default), `vault` or `aws`.
+//! With an external provider, [`init_from_env`] loads its secrets once at startup; services
+//! read their configuration once too, so they must be restarted to pick up rotated secrets.
+//! [`get`] serves the loaded value, falling back to the environment for keys the provider
+//! does not hold, so configuration code reads every value the same way regardless of the
+//! provider.
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::env;
+use std::sync::OnceLock;
+use tracing::info;
+
+static SECRETS: OnceLock<CachedSecrets> = OnceLock::new();
+
+#[async_trait]
+pub trait SecretsProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Fetch the current value of every secret the provider holds, keyed by the name of the
+    /// environment variable it replaces, e.g. `DATABASE_PASSWORD`.
+    async fn fetch(&self) -> Result<HashMap<String, String>>;
+}
+
+/// Secrets taken from the process environment.
+pub struct EnvSecretsProvider;
+
+#[async_trait]
+impl SecretsProvider for EnvSecretsProvider {
+    fn name(&self) -> &'static str {
+        "env"
+    }
+
+    async fn fetch(&self) -> Result<HashMap<String, String>> {
+        Ok(env::vars().collect())
+    }
+}
+
+/// Secrets stored as the fields of one secret in a HashiCorp Vault KV v2 engine.
+pub struct VaultSecretsProvider {
+    client: Client,
+    address: String,
+    token: String,
+    mount: String,
+    path: String,
+}
+
+#[derive(Deserialize)]
+struct VaultKvResponse {
+    data: VaultKvData,
+}
+
+#[derive(Deserialize)]
+struct VaultKvData {
+    data: HashMap<String, JsonValue>,
+}
+
+impl VaultSecretsProvider {
+    pub fn new(address: String, token: String, mount: String, path: String) -> Self {
+        Self {
+            client: Client::new(),
+            address: address.trim_end_matches('/').to_string(),
+            token,
+            mount: mount.trim_matches('/').to_string(),
+            path: path.trim_matches('/').to_string(),
+        }
+    }
+
+    /// Configured by `VAULT_ADDR`, `VAULT_TOKEN`, `VAULT_KV_MOUNT` (default `secret`) and
+    /// `VAULT_SECRET_PATH` (default `omni`).
+    pub fn from_env() -> Result<Self> {
+        let address = env::var("VAULT_ADDR").context("VAULT_ADDR not set")?;
+        let token = env::var("VAULT_TOKEN").context("VAULT_TOKEN not set")?;
+        let mount = env::var("VAULT_KV_MOUNT").unwrap_or_else(|_| "secret".to_string());
+        let path = env::var("VAULT_SECRET_PATH").unwrap_or_else(|_| "omni".to_string());
+        Ok(Self::new(address, token, mount, path))
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for VaultSecretsProvider {
+    fn name(&self) -> &'static str {
+        "vault"
+    }
+
+    async fn fetch(&self) -> Result<HashMap<String, String>> {
+        let url = format!("{}/v1/{}/data/{}", self.address, self.mount, self.path);
+        let response = self
+            .client
+            .get(&url)
+            .header("X-Vault-Token", &self.token)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Vault returned {} for secret '{}/{}'",
+                response.status(),
+                self.mount,
+                self.path
+            ));
+        }
+
+        let body: VaultKvResponse = response.json().await?;
+        Ok(into_strings(body.data.data))
+    }
+}
+
+/// Secrets stored as a JSON object in one AWS Secrets Manager secret.
+pub struct AwsSecretsManagerProvider {
+    client: aws_sdk_secretsmanager::Client,
+    secret_id: String,
+}
+
+impl AwsSecretsManagerProvider {
+    /// Configured by `AWS_SECRETS_MANAGER_SECRET_ID`, with credentials and region resolved
+    /// the usual AWS way.
+    pub async fn from_env() -> Result<Self> {
+        let secret_id = env::var("AWS_SECRETS_MANAGER_SECRET_ID")
+            .context("AWS_SECRETS_MANAGER_SECRET_ID not set")?;
+        let config = aws_config::from_env().load().await;
+
+        Ok(Self {
+            client: aws_sdk_secretsmanager::Client::new(&config),
+            secret_id,
+        })
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for AwsSecretsManagerProvider {
+    fn name(&self) -> &'static str {
+        "aws"
+    }
+
+    async fn fetch(&self) -> Result<HashMap<String, String>> {
+        let output = self
+            .client
+            .get_secret_value()
+            .secret_id(&self.secret_id)
+            .send()
+            .await
+            .with_context(|| format!("Failed to read secret '{}'", self.secret_id))?;
+
+        let secret = output
+            .secret_string()
+            .ok_or_else(|| anyhow!("Secret '{}' has no string value", self.secret_id))?;
+        let values: HashMap<String, JsonValue> = serde_json::from_str(secret)
+            .with_context(|| format!("Secret '{}' is not a JSON object", self.secret_id))?;
+
+        Ok(into_strings(values))
+    }
+}
+
+fn into_strings(values: HashMap<String, JsonValue>) -> HashMap<String, String> {
+    values
+        .into_iter()
+        .map(|(key, value)| match value {
+            JsonValue::String(value) => (key, value),
+            value => (key, value.to_string()),
+        })
+        .collect()
+}
+
+/// Secrets from a provider, kept in memory for the life of the process.
+pub struct CachedSecrets {
+    values: HashMap<String, String>,
+}
+
+impl CachedSecrets {
+    /// Load the provider's secrets, failing if the fetch does.
+    pub async fn load(provider: Box<dyn SecretsProvider>) -> Result<Self> {
+        let values = provider.fetch().await?;
+        info!(
+            "Loaded {} secrets from the {} provider",
+            values.len(),
+            provider.name()
+        );
+
+        Ok(Self { values })
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.values.get(key).cloned()
+    }
+}
+
+/// Set up the provider selected by `SECRETS_PROVIDER`. Must run before configuration is read
+/// for the provider's secrets to be used.
+pub async fn init_from_env() -> Result<()> {
+    let provider: Box<dyn SecretsProvider> = match env::var("SECRETS_PROVIDER")
+        .unwrap_or_else(|_| "env".to_string())
+        .to_lowercase()
+        .as_str()
+    {
+        "" | "env" => return Ok(()),
+        "vault" => Box::new(VaultSecretsProvider::from_env()?),
+        "aws" => Box::new(AwsSecretsManagerProvider::from_env().await?),
+        other => return Err(anyhow!("Unknown SECRETS_PROVIDER '{}'", other)),
+    };
+
+    let secrets = CachedSecrets::load(provider).await?;
+    if SECRETS.set(secrets).is_err() {
+        return Err(anyhow!("Secrets provider already initialized"));
+    }
+
+    Ok(())
+}
+
+/// Current value of `key` from the secrets provider, or the environment when the provider
+/// does not hold it.
+pub fn get(key: &str) -> Option<String> {
+    SECRETS
+        .get()
+        .and_then(|secrets| secrets.get(key))
+        .or_else(|| env::var(key).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StaticProvider(HashMap<String, String>);
+
+    #[async_trait]
+    impl SecretsProvider for StaticProvider {
+        fn name(&self) -> &'static str {
+            "static"
+        }
+
+        async fn fetch(&self) -> Result<HashMap<String, String>> {
+            if self.0.is_empty() {
+                return Err(anyhow!("unavailable"));
+            }
+            Ok(self.0.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_secrets_load() {
+        let provider = StaticProvider(HashMap::from([(
+            "DATABASE_PASSWORD".to_string(),
+            "hunter2".to_string(),
+        )]));
+        let secrets = CachedSecrets::load(Box::new(provider)).await.unwrap();
+        assert_eq!(secrets.get("DATABASE_PASSWORD").as_deref(), Some("hunter2"));
+        assert_eq!(secrets.get("REDIS_URL"), None);
+
+        let failing = StaticProvider(HashMap::new());
+        assert!(CachedSecrets::load(Box::new(failing)).await.is_err());
+    }
+
+    #[test]
+    fn test_into_strings() {
+        let values = into_strings(HashMap::from([
+            ("TOKEN".to_string(), JsonValue::String("abc".to_string())),
+            ("PORT".to_string(), serde_json::json!(5432)),
+        ]));
+        assert_eq!(values["TOKEN"], "abc");
+        assert_eq!(values["PORT"], "5432");
+    }
+}
@@ -0,0 +1,107 @@
+//! Graceful shutdown of services.
+//!
+//! On SIGTERM, as sent by Kubernetes and Docker before stopping a container, or Ctrl-C, a
+//! service stops accepting requests and finishes those in flight, and its background tasks
+//! wind down at the next point where they can stop without losing work: the indexer after
+//! the event batch it is processing, connectors after reporting their running syncs paused.
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::info;
+
+/// Longest requests in flight are given to finish once shutting down.
+pub const DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A service's shutdown, which the tasks it is shared with watch to wind down their work.
+#[derive(Clone)]
+pub struct Shutdown {
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self {
+            sender: Arc::new(watch::Sender::new(false)),
+        }
+    }
+}
+
+impl Shutdown {
+    /// A shutdown requested by SIGTERM or Ctrl-C.
+    pub fn on_signal() -> Self {
+        let shutdown = Self::default();
+        let requester = shutdown.clone();
+        tokio::spawn(async move {
+            signal().await;
+            info!("Shutdown requested, draining in-flight work");
+            requester.request();
+        });
+        shutdown
+    }
+
+    pub fn request(&self) {
+        self.sender.send_replace(true);
+    }
+
+    pub fn is_requested(&self) -> bool {
+        *self.sender.borrow()
+    }
+
+    /// Wait until shutdown is requested.
+    pub async fn requested(&self) {
+        let mut receiver = self.sender.subscribe();
+        // The sender lives as long as `self`, so waiting only ends on a request
+        let _ = receiver.wait_for(|requested| *requested).await;
+    }
+}
+
+async fn signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_requested_wakes_every_watcher() {
+        let shutdown = Shutdown::default();
+        assert!(!shutdown.is_requested());
+
+        let watcher = shutdown.clone();
+        let waiting = tokio::spawn(async move { watcher.requested().await });
+        tokio::task::yield_now().await;
+        assert!(!waiting.is_finished());
+
+        shutdown.request();
+        tokio::time::timeout(Duration::from_secs(1), waiting)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(shutdown.is_requested());
+        // Watchers arriving after the request do not wait
+        tokio::time::timeout(Duration::from_secs(1), shutdown.requested())
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,322 @@
+//! Startup of services alongside the dependencies they connect to.
+//!
+//! Services connect to Postgres and Redis with bounded exponential backoff, instead of exiting
+//! when they start before their dependencies accept connections. They listen from the start so
+//! orchestrators can tell a service still connecting from an unhealthy one:
+//!
+//! - `/live` answers as long as the process does.
+//! - `/ready` answers 503 `starting` until the service is up, then 200 `ready` while its
+//!   dependencies are reachable and 503 `unhealthy` while any is not.
+//!
+//! Other requests are answered 503 until the service is up.
+
+use crate::config::DatabaseConfig;
+use crate::db::error::DatabaseError;
+use crate::db::pool::DatabasePool;
+use crate::shutdown::Shutdown;
+use axum::extract::{Request, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::get;
+use axum::Router;
+use serde_json::{json, Map, Value};
+use sqlx::PgPool;
+use std::convert::Infallible;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tower::{service_fn, ServiceExt};
+use tracing::{info, warn};
+
+/// Longest a readiness check waits for a dependency to answer.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Bounded exponential backoff for connecting to a dependency.
+#[derive(Debug, Clone)]
+pub struct Retry {
+    /// Connection attempts made before giving up, the first included.
+    pub attempts: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Self {
+            attempts: 10,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl Retry {
+    /// The default backoff, making `STARTUP_RETRY_ATTEMPTS` attempts when set.
+    pub fn from_env() -> Self {
+        let mut retry = Self::default();
+        if let Some(attempts) = std::env::var("STARTUP_RETRY_ATTEMPTS")
+            .ok()
+            .and_then(|value| value.parse::<u32>().ok())
+        {
+            retry.attempts = attempts.max(1);
+        }
+        retry
+    }
+
+    /// Delay before the attempt following attempt `attempt`, counted from zero.
+    fn delay(&self, attempt: u32) -> Duration {
+        self.initial_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay)
+    }
+
+    /// Run `connect` until it succeeds or runs out of attempts, returning its last error.
+    pub async fn run<T, E, F, Fut>(&self, dependency: &str, mut connect: F) -> Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: Display,
+    {
+        let mut attempt = 0;
+        loop {
+            match connect().await {
+                Ok(value) => {
+                    if attempt > 0 {
+                        info!("Connected to {} after {} retries", dependency, attempt);
+                    }
+                    return Ok(value);
+                }
+                Err(e) if attempt + 1 >= self.attempts => return Err(e),
+                Err(e) => {
+                    let delay = self.delay(attempt);
+                    warn!(
+                        "{} is not ready ({}), retrying in {:?}",
+                        dependency, e, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Connect to Postgres, retrying while it does not accept connections yet.
+pub async fn connect_database(config: &DatabaseConfig) -> Result<DatabasePool, DatabaseError> {
+    Retry::from_env()
+        .run("Postgres", || DatabasePool::from_config(config))
+        .await
+}
+
+/// Connect to Redis, retrying while it does not answer yet.
+pub async fn connect_redis(redis_url: &str) -> Result<redis::Client, redis::RedisError> {
+    let client = redis::Client::open(redis_url)?;
+    Retry::from_env().run("Redis", || ping(&client)).await?;
+    Ok(client)
+}
+
+async fn ping(client: &redis::Client) -> Result<(), redis::RedisError> {
+    let mut conn = client.get_multiplexed_async_connection().await?;
+    redis::cmd("PING").query_async::<String>(&mut conn).await?;
+    Ok(())
+}
+
+/// The dependencies a service is ready only while it can reach.
+#[derive(Clone)]
+pub struct Dependencies {
+    postgres: PgPool,
+    redis: Option<redis::Client>,
+}
+
+impl Dependencies {
+    pub fn new(postgres: PgPool) -> Self {
+        Self {
+            postgres,
+            redis: None,
+        }
+    }
+
+    pub fn with_redis(mut self, redis: redis::Client) -> Self {
+        self.redis = Some(redis);
+        self
+    }
+
+    /// Whether each dependency is reachable, by name, with the error of those that are not.
+    async fn check(&self) -> Vec<(&'static str, Result<(), String>)> {
+        let postgres = async {
+            sqlx::query("SELECT 1")
+                .execute(&self.postgres)
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        };
+        let mut checks = vec![("postgres", within_timeout(postgres).await)];
+        if let Some(redis) = &self.redis {
+            let redis = async { ping(redis).await.map_err(|e| e.to_string()) };
+            checks.push(("redis", within_timeout(redis).await));
+        }
+        checks
+    }
+}
+
+async fn within_timeout(check: impl Future<Output = Result<(), String>>) -> Result<(), String> {
+    tokio::time::timeout(CHECK_TIMEOUT, check)
+        .await
+        .unwrap_or_else(|_| Err("timed out".to_string()))
+}
+
+struct Up {
+    app: Router,
+    dependencies: Dependencies,
+}
+
+/// A service starting up: answering probes right away, and requests once it is up.
+#[derive(Clone, Default)]
+pub struct Startup {
+    up: Arc<OnceLock<Up>>,
+}
+
+impl Startup {
+    /// Serve on `listener` until `shutdown`, see [`crate::tls::serve`].
+    pub fn serve(
+        &self,
+        listener: TcpListener,
+        shutdown: Shutdown,
+    ) -> JoinHandle<anyhow::Result<()>> {
+        tokio::spawn(crate::tls::serve(listener, self.router(), shutdown))
+    }
+
+    /// Serve `app` from now on, ready while `dependencies` are reachable.
+    pub fn ready(&self, app: Router, dependencies: Dependencies) {
+        if self.up.set(Up { app, dependencies }).is_ok() {
+            info!("Service is ready");
+        }
+    }
+
+    /// The probes, handing every other request to the app once it is up.
+    pub fn router(&self) -> Router {
+        let startup = self.clone();
+        let app = service_fn(move |request: Request| {
+            let app = startup.up.get().map(|up| up.app.clone());
+            async move {
+                let response = match app {
+                    Some(app) => app.oneshot(request).await.unwrap_or_else(|e| match e {}),
+                    None => starting(),
+                };
+                Ok::<_, Infallible>(response)
+            }
+        });
+
+        Router::new()
+            .route("/live", get(live))
+            .route("/ready", get(ready))
+            .fallback_service(app)
+            .with_state(self.clone())
+    }
+}
+
+fn starting() -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(json!({ "status": "starting" })),
+    )
+        .into_response()
+}
+
+async fn live() -> Json<Value> {
+    Json(json!({ "status": "alive" }))
+}
+
+async fn ready(State(startup): State<Startup>) -> Response {
+    let Some(up) = startup.up.get() else {
+        return starting();
+    };
+
+    let mut body = Map::new();
+    let mut healthy = true;
+    for (dependency, result) in up.dependencies.check().await {
+        let status = match result {
+            Ok(()) => "connected".to_string(),
+            Err(e) => {
+                healthy = false;
+                e
+            }
+        };
+        body.insert(dependency.to_string(), Value::String(status));
+    }
+
+    let (code, status) = if healthy {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "unhealthy")
+    };
+    body.insert("status".to_string(), Value::String(status.to_string()));
+    (code, Json(Value::Object(body))).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn quick_retry(attempts: u32) -> Retry {
+        Retry {
+            attempts,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(4),
+        }
+    }
+
+    #[test]
+    fn test_retry_delays_grow_to_the_bound() {
+        let retry = Retry::default();
+        let delays: Vec<u64> = (0..7).map(|i| retry.delay(i).as_millis() as u64).collect();
+        assert_eq!(delays, vec![500, 1000, 2000, 4000, 8000, 10000, 10000]);
+        assert_eq!(retry.delay(u32::MAX), retry.max_delay);
+    }
+
+    #[tokio::test]
+    async fn test_retry_until_connected_or_out_of_attempts() {
+        let calls = AtomicU32::new(0);
+        let connected = quick_retry(5)
+            .run("test", || async {
+                match calls.fetch_add(1, Ordering::SeqCst) {
+                    0 | 1 => Err("refused"),
+                    _ => Ok("connected"),
+                }
+            })
+            .await;
+        assert_eq!(connected, Ok("connected"));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let calls = AtomicU32::new(0);
+        let failed: Result<(), _> = quick_retry(3)
+            .run("test", || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err("refused")
+            })
+            .await;
+        assert_eq!(failed, Err("refused"));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_probes_while_starting() {
+        let router = Startup::default().router();
+        let status = |uri: &'static str| {
+            let router = router.clone();
+            async move {
+                let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+                router.oneshot(request).await.unwrap().status()
+            }
+        };
+
+        assert_eq!(status("/live").await, StatusCode::OK);
+        assert_eq!(status("/ready").await, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(status("/search").await, StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
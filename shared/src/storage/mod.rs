@@ -4,6 +4,7 @@ pub mod postgres;
 pub mod s3;
 
 use async_trait::async_trait;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use thiserror::Error;
 
@@ -19,6 +20,13 @@ pub enum StorageError {
     Config(String),
 }
 
+/// SHA-256 of stored content, hex encoded, as recorded for content blobs. Embeddings record
+/// the checksum of the content their chunk offsets were computed against, so that readers can
+/// tell whether the offsets still apply to the content they fetched.
+pub fn content_sha256(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
 #[derive(Debug, Clone)]
 pub struct ContentMetadata {
     pub content_type: Option<String>,
@@ -80,4 +88,17 @@ pub trait ObjectStorage: Send + Sync {
 
     /// Find content by SHA256 hash (for deduplication)
     async fn find_by_hash(&self, sha256_hash: &str) -> Result<Option<String>, StorageError>;
+
+    /// Write an object under a caller-chosen key, replacing any object already stored there.
+    /// Objects are not content blobs: they are addressed by key alone, so another deployment
+    /// sharing the storage can read them, and they are never garbage collected.
+    async fn put_object(
+        &self,
+        key: &str,
+        content: &[u8],
+        content_type: Option<&str>,
+    ) -> Result<(), StorageError>;
+
+    /// Retrieve an object written with `put_object`
+    async fn get_object(&self, key: &str) -> Result<Vec<u8>, StorageError>;
 }
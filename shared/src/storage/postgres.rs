@@ -1,7 +1,6 @@
-use super::{ContentMetadata, ObjectStorage, StorageError};
+use super::{content_sha256, ContentMetadata, ObjectStorage, StorageError};
 use crate::utils::generate_ulid;
 use async_trait::async_trait;
-use sha2::{Digest, Sha256};
 use sqlx::{PgPool, Row};
 use std::collections::HashMap;
 
@@ -36,9 +35,7 @@ impl ObjectStorage for PostgresStorage {
         let size_bytes = content.len() as i64;
 
         // Generate SHA256 hash for potential deduplication
-        let mut hasher = Sha256::new();
-        hasher.update(content);
-        let hash = format!("{:x}", hasher.finalize());
+        let hash = content_sha256(content);
 
         sqlx::query(
             r#"
@@ -172,6 +169,45 @@ impl ObjectStorage for PostgresStorage {
 
         Ok(result)
     }
+
+    async fn put_object(
+        &self,
+        key: &str,
+        content: &[u8],
+        content_type: Option<&str>,
+    ) -> Result<(), StorageError> {
+        sqlx::query(
+            r#"
+            INSERT INTO storage_objects (key, content, content_type, size_bytes)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (key) DO UPDATE SET
+                content = EXCLUDED.content,
+                content_type = EXCLUDED.content_type,
+                size_bytes = EXCLUDED.size_bytes,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(key)
+        .bind(content)
+        .bind(content_type)
+        .bind(content.len() as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Backend(format!("Failed to store object: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_object(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        let result: Option<Vec<u8>> =
+            sqlx::query_scalar("SELECT content FROM storage_objects WHERE key = $1")
+                .bind(key)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| StorageError::Backend(format!("Failed to get object: {}", e)))?;
+
+        result.ok_or_else(|| StorageError::NotFound(key.to_string()))
+    }
 }
 
 #[cfg(test)]
@@ -281,4 +317,27 @@ mod tests {
         let found_id = storage.find_by_hash(&metadata1.sha256_hash).await.unwrap();
         assert!(found_id.is_some());
     }
+
+    #[tokio::test]
+    async fn test_objects() {
+        let env = TestEnvironment::new().await.unwrap();
+        let storage = PostgresStorage::new(env.db_pool.pool().clone());
+
+        let key = "snapshots/test/manifest.json";
+        let result = storage.get_object(key).await;
+        assert!(matches!(result, Err(StorageError::NotFound(_))));
+
+        storage
+            .put_object(key, b"first", Some("application/json"))
+            .await
+            .unwrap();
+        storage
+            .put_object(key, b"second", Some("application/json"))
+            .await
+            .unwrap();
+        assert_eq!(storage.get_object(key).await.unwrap(), b"second");
+
+        // Objects are not content blobs
+        assert!(storage.get_content(key).await.is_err());
+    }
 }
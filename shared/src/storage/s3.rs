@@ -1,10 +1,9 @@
-use super::{ContentMetadata, ObjectStorage, StorageError};
+use super::{content_sha256, ContentMetadata, ObjectStorage, StorageError};
 use crate::utils::generate_ulid;
 use async_trait::async_trait;
 use aws_sdk_s3::{error::SdkError, primitives::ByteStream, Client as S3Client};
 use bytes::Bytes;
 use futures_util::future;
-use sha2::{Digest, Sha256};
 use sqlx::{PgPool, Row};
 use std::collections::HashMap;
 use tower::buffer::error::ServiceError;
@@ -69,9 +68,7 @@ impl S3Storage {
     }
 
     fn compute_hash(&self, content: &[u8]) -> String {
-        let mut hasher = Sha256::new();
-        hasher.update(content);
-        format!("{:x}", hasher.finalize())
+        content_sha256(content)
     }
 }
 
@@ -367,6 +364,57 @@ impl ObjectStorage for S3Storage {
 
         Ok(result)
     }
+
+    async fn put_object(
+        &self,
+        key: &str,
+        content: &[u8],
+        content_type: Option<&str>,
+    ) -> Result<(), StorageError> {
+        let mut put_request = self
+            .client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .body(ByteStream::from(Bytes::copy_from_slice(content)));
+        if let Some(ct) = content_type {
+            put_request = put_request.content_type(ct);
+        }
+
+        put_request
+            .send()
+            .await
+            .map_err(|e| StorageError::Backend(format!("Failed to put object to S3: {}", e)))?;
+
+        debug!("Stored object in S3: bucket={}, key={}", self.bucket, key);
+        Ok(())
+    }
+
+    async fn get_object(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        let response = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.to_string().contains("NoSuchKey") {
+                    StorageError::NotFound(key.to_string())
+                } else {
+                    StorageError::Backend(format!("Failed to get object from S3: {}", e))
+                }
+            })?;
+
+        let bytes = response
+            .body
+            .collect()
+            .await
+            .map_err(|e| StorageError::Backend(format!("Failed to read S3 response body: {}", e)))?
+            .into_bytes();
+
+        Ok(bytes.to_vec())
+    }
 }
 
 #[cfg(test)]
@@ -1,7 +1,9 @@
+use crate::error_reporting;
 use anyhow::Result;
 use opentelemetry::{global, trace::TracerProvider as _, KeyValue};
 use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_sdk::{
+    metrics::{PeriodicReader, SdkMeterProvider},
     trace::{RandomIdGenerator, Sampler, TracerProvider},
     Resource,
 };
@@ -9,15 +11,19 @@ use opentelemetry_semantic_conventions::{
     resource::{SERVICE_NAME, SERVICE_VERSION},
     SCHEMA_URL,
 };
+use std::sync::OnceLock;
 use std::time::Duration;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
 
+static METER_PROVIDER: OnceLock<SdkMeterProvider> = OnceLock::new();
+
 pub struct TelemetryConfig {
     pub service_name: String,
     pub otlp_endpoint: Option<String>,
     pub deployment_id: String,
     pub environment: String,
     pub service_version: String,
+    pub sentry_dsn: Option<String>,
 }
 
 impl TelemetryConfig {
@@ -31,6 +37,7 @@ impl TelemetryConfig {
                 .unwrap_or_else(|_| "development".to_string()),
             service_version: std::env::var("SERVICE_VERSION")
                 .unwrap_or_else(|_| env!("CARGO_PKG_VERSION").to_string()),
+            sentry_dsn: std::env::var("SENTRY_DSN").ok(),
         }
     }
 }
@@ -47,6 +54,24 @@ pub fn init_telemetry(config: TelemetryConfig) -> Result<()> {
     );
 
     let otlp_endpoint_for_log = config.otlp_endpoint.clone();
+    let error_reporting_enabled = error_reporting::init(&config)?;
+
+    if let Some(endpoint) = &config.otlp_endpoint {
+        let exporter = opentelemetry_otlp::MetricExporter::builder()
+            .with_http()
+            .with_endpoint(format!("{}/v1/metrics", endpoint.trim_end_matches('/')))
+            .with_timeout(Duration::from_secs(10))
+            .build()?;
+
+        let reader = PeriodicReader::builder(exporter, opentelemetry_sdk::runtime::Tokio).build();
+        let meter_provider = SdkMeterProvider::builder()
+            .with_reader(reader)
+            .with_resource(resource.clone())
+            .build();
+
+        global::set_meter_provider(meter_provider.clone());
+        let _ = METER_PROVIDER.set(meter_provider);
+    }
 
     let tracer_provider = if let Some(endpoint) = config.otlp_endpoint {
         let exporter = opentelemetry_otlp::SpanExporter::builder()
@@ -74,6 +99,8 @@ pub fn init_telemetry(config: TelemetryConfig) -> Result<()> {
     let tracer = tracer_provider.tracer(config.service_name.clone());
     let telemetry_layer = tracing_opentelemetry::layer().with_tracer(tracer);
 
+    let error_reporting_layer = error_reporting_enabled.then(error_reporting::layer);
+
     let env_filter = EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| EnvFilter::new("info"))
         .add_directive("sqlx=warn".parse()?)
@@ -92,6 +119,7 @@ pub fn init_telemetry(config: TelemetryConfig) -> Result<()> {
     tracing_subscriber::registry()
         .with(telemetry_layer)
         .with(fmt_layer)
+        .with(error_reporting_layer)
         .init();
 
     tracing::info!(
@@ -99,6 +127,7 @@ pub fn init_telemetry(config: TelemetryConfig) -> Result<()> {
         deployment_id = %config.deployment_id,
         environment = %config.environment,
         otlp_endpoint = ?otlp_endpoint_for_log,
+        error_reporting = error_reporting_enabled,
         "Telemetry initialized"
     );
 
@@ -108,6 +137,12 @@ pub fn init_telemetry(config: TelemetryConfig) -> Result<()> {
 pub async fn shutdown_telemetry() {
     tracing::info!("Shutting down telemetry");
     global::shutdown_tracer_provider();
+    error_reporting::flush(Duration::from_secs(2));
+    if let Some(meter_provider) = METER_PROVIDER.get() {
+        if let Err(e) = meter_provider.shutdown() {
+            tracing::warn!("Failed to shut down meter provider: {}", e);
+        }
+    }
 }
 
 pub mod middleware {
@@ -0,0 +1,99 @@
+//! Postgres run from downloaded binaries through pg-embed, so that tests need no Docker.
+//!
+//! The downloaded binaries come without the pgvector and ParadeDB extensions the migrations
+//! need. They are copied into the installation from `OMNI_TEST_PG_EXTENSIONS_DIR`, a
+//! directory laid out like a Postgres 15 installation (`lib/vector.so`, `lib/pg_search.so`,
+//! `share/extension/...`). CONTRIBUTING.md describes how to build them.
+
+use anyhow::{anyhow, Context, Result};
+use pg_embed::pg_enums::PgAuthMethod;
+use pg_embed::pg_fetch::{PgFetchSettings, PG_V15};
+use pg_embed::postgres::{PgEmbed, PgSettings};
+use std::io::Write;
+use std::path::Path;
+use tokio::time::Duration;
+use ulid::Ulid;
+
+pub const EXTENSIONS_DIR_ENV: &str = "OMNI_TEST_PG_EXTENSIONS_DIR";
+
+/// A Postgres server running until dropped, with its data in a temporary directory.
+pub struct EmbeddedPostgres {
+    pub database_url: String,
+    _postgres: PgEmbed,
+}
+
+impl EmbeddedPostgres {
+    /// Start a server with an empty `database` owned by `user`
+    pub async fn start(database: &str, user: &str, password: &str) -> Result<Self> {
+        let extensions_dir = std::env::var(EXTENSIONS_DIR_ENV).map_err(|_| {
+            anyhow!(
+                "{} must point at the pgvector and pg_search extensions built for Postgres 15, \
+                 see CONTRIBUTING.md",
+                EXTENSIONS_DIR_ENV
+            )
+        })?;
+
+        let database_dir = std::env::temp_dir().join(format!("omni-test-postgres-{}", Ulid::new()));
+        let settings = PgSettings {
+            database_dir: database_dir.clone(),
+            port: free_port()?,
+            user: user.to_string(),
+            password: password.to_string(),
+            auth_method: PgAuthMethod::Plain,
+            persistent: false,
+            timeout: Some(Duration::from_secs(60)),
+            migration_dir: None,
+        };
+        let fetch_settings = PgFetchSettings {
+            version: PG_V15,
+            ..Default::default()
+        };
+
+        let mut postgres = PgEmbed::new(settings, fetch_settings).await?;
+        // Downloads the binaries on first use and initializes the data directory
+        postgres.setup().await?;
+        copy_missing(Path::new(&extensions_dir), &postgres.pg_access.cache_dir)
+            .context("Failed to install the Postgres extensions")?;
+        // ParadeDB has to be loaded at startup on Postgres versions before 17
+        let mut config = std::fs::OpenOptions::new()
+            .append(true)
+            .open(database_dir.join("postgresql.conf"))?;
+        writeln!(config, "shared_preload_libraries = 'pg_search'")?;
+        postgres.start_db().await?;
+
+        let admin_pool = sqlx::PgPool::connect(&postgres.full_db_uri("postgres")).await?;
+        sqlx::query(&format!("CREATE DATABASE {}", database))
+            .execute(&admin_pool)
+            .await?;
+        admin_pool.close().await;
+
+        Ok(Self {
+            database_url: postgres.full_db_uri(database),
+            _postgres: postgres,
+        })
+    }
+}
+
+fn free_port() -> Result<u16> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    Ok(listener.local_addr()?.port())
+}
+
+/// Copy the files under `from` into `to` that `to` does not have yet. Tests starting servers
+/// concurrently share the installation, so files already in place are left alone.
+fn copy_missing(from: &Path, to: &Path) -> Result<()> {
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            std::fs::create_dir_all(&target)?;
+            copy_missing(&entry.path(), &target)?;
+        } else if !target.exists() {
+            // Copy under a temporary name first, so no server loads a partly copied file
+            let partial = target.with_extension(format!("partial-{}", Ulid::new()));
+            std::fs::copy(entry.path(), &partial)?;
+            std::fs::rename(&partial, &target)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,611 @@
+//! An in-memory stand-in for Redis, speaking enough of the Redis protocol for the commands
+//! the services use: strings and counters with expiry, lists, sets, hashes and key scans.
+//!
+//! Lua does not run here. `EVAL` and `EVALSHA` run the scripts the services use, such as the
+//! rate limiter's, implemented natively, and fail for any other script. Other commands fail
+//! with an unknown command error, so tests reaching code that needs more than this backend
+//! offers fail rather than pass on wrong behavior.
+
+use anyhow::Result;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Redis substitute listening on a local port, with one database shared by all connections.
+pub struct MemoryRedis {
+    pub port: u16,
+    _server_handle: tokio::task::JoinHandle<()>,
+}
+
+impl MemoryRedis {
+    /// Start the server on a free port
+    pub async fn start() -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let port = listener.local_addr()?.port();
+        let store = Arc::new(Mutex::new(Store::default()));
+
+        let server_handle = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, store.clone()));
+            }
+        });
+
+        Ok(Self {
+            port,
+            _server_handle: server_handle,
+        })
+    }
+}
+
+async fn serve(stream: TcpStream, store: Arc<Mutex<Store>>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    while let Ok(Some(args)) = read_command(&mut reader).await {
+        let reply = store.lock().unwrap().execute(&args);
+        let mut out = Vec::new();
+        reply.write(&mut out);
+        if writer.write_all(&out).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Read one command, sent as an array of bulk strings. `None` once the client disconnects.
+async fn read_command<R: AsyncBufRead + Unpin>(reader: &mut R) -> io::Result<Option<Vec<Vec<u8>>>> {
+    let mut line = Vec::new();
+    if reader.read_until(b'\n', &mut line).await? == 0 {
+        return Ok(None);
+    }
+    let count = parse_header(&line, b'*')?;
+    let mut args = Vec::with_capacity(count);
+    for _ in 0..count {
+        line.clear();
+        reader.read_until(b'\n', &mut line).await?;
+        let len = parse_header(&line, b'$')?;
+        // The argument is followed by its CRLF
+        let mut arg = vec![0; len + 2];
+        reader.read_exact(&mut arg).await?;
+        arg.truncate(len);
+        args.push(arg);
+    }
+    Ok(Some(args))
+}
+
+fn parse_header(line: &[u8], prefix: u8) -> io::Result<usize> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "malformed command");
+    let line = line.strip_suffix(b"\r\n").ok_or_else(invalid)?;
+    let (&first, rest) = line.split_first().ok_or_else(invalid)?;
+    if first != prefix {
+        return Err(invalid());
+    }
+    std::str::from_utf8(rest)
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(invalid)
+}
+
+enum Reply {
+    Status(&'static str),
+    Error(String),
+    Integer(i64),
+    Bulk(Option<Vec<u8>>),
+    Array(Vec<Reply>),
+}
+
+impl Reply {
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            Reply::Status(status) => out.extend_from_slice(format!("+{}\r\n", status).as_bytes()),
+            Reply::Error(message) => out.extend_from_slice(format!("-{}\r\n", message).as_bytes()),
+            Reply::Integer(n) => out.extend_from_slice(format!(":{}\r\n", n).as_bytes()),
+            Reply::Bulk(None) => out.extend_from_slice(b"$-1\r\n"),
+            Reply::Bulk(Some(value)) => {
+                out.extend_from_slice(format!("${}\r\n", value.len()).as_bytes());
+                out.extend_from_slice(value);
+                out.extend_from_slice(b"\r\n");
+            }
+            Reply::Array(items) => {
+                out.extend_from_slice(format!("*{}\r\n", items.len()).as_bytes());
+                for item in items {
+                    item.write(out);
+                }
+            }
+        }
+    }
+
+    fn array(values: impl IntoIterator<Item = Vec<u8>>) -> Self {
+        Reply::Array(values.into_iter().map(|v| Reply::Bulk(Some(v))).collect())
+    }
+
+    fn syntax_error() -> Self {
+        Reply::Error("ERR syntax error".to_string())
+    }
+
+    fn not_an_integer() -> Self {
+        Reply::Error("ERR value is not an integer or out of range".to_string())
+    }
+
+    fn wrong_type() -> Self {
+        Reply::Error(
+            "WRONGTYPE Operation against a key holding the wrong kind of value".to_string(),
+        )
+    }
+}
+
+enum Value {
+    String(Vec<u8>),
+    List(VecDeque<Vec<u8>>),
+    Set(HashSet<Vec<u8>>),
+    Hash(HashMap<Vec<u8>, Vec<u8>>),
+}
+
+/// A script run natively in place of its Lua, given its keys and arguments.
+type NativeScript = fn(&mut Store, &[Vec<u8>], &[Vec<u8>]) -> Reply;
+
+/// The natively implemented scripts, by the SHA1 of their Lua that `EVALSHA` refers to them by.
+fn native_scripts() -> &'static HashMap<String, NativeScript> {
+    static SCRIPTS: OnceLock<HashMap<String, NativeScript>> = OnceLock::new();
+    SCRIPTS.get_or_init(|| {
+        let scripts: [(&str, NativeScript); 1] =
+            [(crate::rate_limiter::TAKE_TOKEN_SCRIPT, Store::take_token)];
+        scripts
+            .into_iter()
+            .map(|(lua, script)| (redis::Script::new(lua).get_hash().to_string(), script))
+            .collect()
+    })
+}
+
+fn script_hash(lua: &[u8]) -> String {
+    redis::Script::new(&String::from_utf8_lossy(lua))
+        .get_hash()
+        .to_string()
+}
+
+struct Entry {
+    value: Value,
+    expires_at: Option<Instant>,
+}
+
+#[derive(Default)]
+struct Store {
+    entries: HashMap<Vec<u8>, Entry>,
+}
+
+impl Store {
+    fn execute(&mut self, args: &[Vec<u8>]) -> Reply {
+        let Some((name, args)) = args.split_first() else {
+            return Reply::Error("ERR empty command".to_string());
+        };
+        let name = String::from_utf8_lossy(name).to_ascii_uppercase();
+        match (name.as_str(), args) {
+            ("PING", []) => Reply::Status("PONG"),
+            ("PING", [message]) | ("ECHO", [message]) => Reply::Bulk(Some(message.clone())),
+            // Connection setup: client names, library info and database selection
+            ("CLIENT", _) | ("SELECT", [_]) => Reply::Status("OK"),
+            ("FLUSHDB", _) | ("FLUSHALL", _) => {
+                self.entries.clear();
+                Reply::Status("OK")
+            }
+            ("GET", [key]) => match self.live(key) {
+                None => Reply::Bulk(None),
+                Some(Entry {
+                    value: Value::String(value),
+                    ..
+                }) => Reply::Bulk(Some(value.clone())),
+                Some(_) => Reply::wrong_type(),
+            },
+            ("SET", [key, value, options @ ..]) => self.set(key, value, options),
+            ("SETEX", [key, seconds, value]) => match parse_int(seconds) {
+                Some(seconds) if seconds > 0 => {
+                    self.insert(key, value, Some(Duration::from_secs(seconds as u64)));
+                    Reply::Status("OK")
+                }
+                _ => Reply::Error("ERR invalid expire time in 'setex' command".to_string()),
+            },
+            ("DEL", keys) if !keys.is_empty() => {
+                let deleted = keys
+                    .iter()
+                    .filter(|key| self.live(key).is_some() && self.entries.remove(*key).is_some())
+                    .count();
+                Reply::Integer(deleted as i64)
+            }
+            ("EXISTS", keys) if !keys.is_empty() => {
+                let existing = keys.iter().filter(|key| self.live(key).is_some()).count();
+                Reply::Integer(existing as i64)
+            }
+            ("EXPIRE", [key, seconds]) => match parse_int(seconds) {
+                Some(seconds) => match self.live(key) {
+                    Some(entry) => {
+                        entry.expires_at =
+                            Some(Instant::now() + Duration::from_secs(seconds.max(0) as u64));
+                        Reply::Integer(1)
+                    }
+                    None => Reply::Integer(0),
+                },
+                None => Reply::syntax_error(),
+            },
+            ("TTL", [key]) => match self.live(key) {
+                None => Reply::Integer(-2),
+                Some(Entry {
+                    expires_at: None, ..
+                }) => Reply::Integer(-1),
+                Some(Entry {
+                    expires_at: Some(expires_at),
+                    ..
+                }) => Reply::Integer(
+                    expires_at
+                        .saturating_duration_since(Instant::now())
+                        .as_secs() as i64,
+                ),
+            },
+            ("KEYS", [pattern]) => Reply::array(self.matching(Some(pattern))),
+            // Every matching key is returned at once, which ends the scan
+            ("SCAN", [_cursor, options @ ..]) => {
+                let mut pattern = None;
+                for option in options.chunks(2) {
+                    match option {
+                        [name, value] if name.eq_ignore_ascii_case(b"MATCH") => {
+                            pattern = Some(value)
+                        }
+                        [name, _] if name.eq_ignore_ascii_case(b"COUNT") => {}
+                        _ => return Reply::syntax_error(),
+                    }
+                }
+                Reply::Array(vec![
+                    Reply::Bulk(Some(b"0".to_vec())),
+                    Reply::array(self.matching(pattern)),
+                ])
+            }
+            ("INCR", [key]) | ("DECR", [key]) => {
+                let delta = if name == "INCR" { 1 } else { -1 };
+                self.increment(key, delta)
+            }
+            ("INCRBY", [key, delta]) | ("DECRBY", [key, delta]) => match parse_int(delta) {
+                Some(delta) if name == "INCRBY" => self.increment(key, delta),
+                Some(delta) => self.increment(key, -delta),
+                None => Reply::not_an_integer(),
+            },
+            ("PEXPIRE", [key, millis]) => match parse_int(millis) {
+                Some(millis) => match self.live(key) {
+                    Some(entry) => {
+                        entry.expires_at =
+                            Some(Instant::now() + Duration::from_millis(millis.max(0) as u64));
+                        Reply::Integer(1)
+                    }
+                    None => Reply::Integer(0),
+                },
+                None => Reply::syntax_error(),
+            },
+            ("SADD", [key, members @ ..]) if !members.is_empty() => {
+                match self.entry_or_insert(key, || Value::Set(HashSet::new())) {
+                    Value::Set(set) => Reply::Integer(
+                        members.iter().filter(|m| set.insert((*m).clone())).count() as i64,
+                    ),
+                    _ => Reply::wrong_type(),
+                }
+            }
+            ("SREM", [key, members @ ..]) if !members.is_empty() => match self.live(key) {
+                None => Reply::Integer(0),
+                Some(Entry {
+                    value: Value::Set(set),
+                    ..
+                }) => Reply::Integer(members.iter().filter(|m| set.remove(*m)).count() as i64),
+                Some(_) => Reply::wrong_type(),
+            },
+            ("SMEMBERS", [key]) => match self.live(key) {
+                None => Reply::Array(vec![]),
+                Some(Entry {
+                    value: Value::Set(set),
+                    ..
+                }) => Reply::array(set.iter().cloned().collect::<Vec<_>>()),
+                Some(_) => Reply::wrong_type(),
+            },
+            ("SISMEMBER", [key, member]) => match self.live(key) {
+                None => Reply::Integer(0),
+                Some(Entry {
+                    value: Value::Set(set),
+                    ..
+                }) => Reply::Integer(set.contains(member) as i64),
+                Some(_) => Reply::wrong_type(),
+            },
+            ("SCARD", [key]) => match self.live(key) {
+                None => Reply::Integer(0),
+                Some(Entry {
+                    value: Value::Set(set),
+                    ..
+                }) => Reply::Integer(set.len() as i64),
+                Some(_) => Reply::wrong_type(),
+            },
+            ("HSET", [key, fields @ ..]) if !fields.is_empty() && fields.len() % 2 == 0 => {
+                match self.entry_or_insert(key, || Value::Hash(HashMap::new())) {
+                    Value::Hash(hash) => Reply::Integer(
+                        fields
+                            .chunks(2)
+                            .filter(|pair| hash.insert(pair[0].clone(), pair[1].clone()).is_none())
+                            .count() as i64,
+                    ),
+                    _ => Reply::wrong_type(),
+                }
+            }
+            ("HGET", [key, field]) => match self.hash_values(key, std::slice::from_ref(field)) {
+                Ok(mut values) => Reply::Bulk(values.remove(0)),
+                Err(reply) => reply,
+            },
+            ("HMGET", [key, fields @ ..]) if !fields.is_empty() => {
+                match self.hash_values(key, fields) {
+                    Ok(values) => Reply::Array(values.into_iter().map(Reply::Bulk).collect()),
+                    Err(reply) => reply,
+                }
+            }
+            ("TIME", []) => {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default();
+                Reply::array([
+                    now.as_secs().to_string().into_bytes(),
+                    now.subsec_micros().to_string().into_bytes(),
+                ])
+            }
+            ("SCRIPT", [subcommand, lua]) if subcommand.eq_ignore_ascii_case(b"LOAD") => {
+                Reply::Bulk(Some(script_hash(lua).into_bytes()))
+            }
+            ("EVAL", [lua, key_count, rest @ ..]) => self.eval(&script_hash(lua), key_count, rest),
+            ("EVALSHA", [sha, key_count, rest @ ..]) => self.eval(
+                &String::from_utf8_lossy(sha).to_lowercase(),
+                key_count,
+                rest,
+            ),
+            ("RPUSH", [key, values @ ..]) | ("LPUSH", [key, values @ ..]) if !values.is_empty() => {
+                if self.live(key).is_none() {
+                    self.entries.insert(
+                        key.clone(),
+                        Entry {
+                            value: Value::List(VecDeque::new()),
+                            expires_at: None,
+                        },
+                    );
+                }
+                let Some(Entry {
+                    value: Value::List(list),
+                    ..
+                }) = self.entries.get_mut(key)
+                else {
+                    return Reply::wrong_type();
+                };
+                for value in values {
+                    if name == "RPUSH" {
+                        list.push_back(value.clone());
+                    } else {
+                        list.push_front(value.clone());
+                    }
+                }
+                Reply::Integer(list.len() as i64)
+            }
+            ("LRANGE", [key, start, stop]) => {
+                let (Some(start), Some(stop)) = (parse_int(start), parse_int(stop)) else {
+                    return Reply::syntax_error();
+                };
+                match self.live(key) {
+                    None => Reply::Array(vec![]),
+                    Some(Entry {
+                        value: Value::List(list),
+                        ..
+                    }) => {
+                        let len = list.len() as i64;
+                        let index = |i: i64| if i < 0 { len + i } else { i };
+                        let (start, stop) = (index(start).max(0), index(stop).min(len - 1));
+                        if start > stop {
+                            Reply::Array(vec![])
+                        } else {
+                            Reply::array(
+                                list.range(start as usize..=stop as usize)
+                                    .cloned()
+                                    .collect::<Vec<_>>(),
+                            )
+                        }
+                    }
+                    Some(_) => Reply::wrong_type(),
+                }
+            }
+            (
+                "PING" | "ECHO" | "SELECT" | "GET" | "SET" | "SETEX" | "DEL" | "EXISTS" | "EXPIRE"
+                | "TTL" | "KEYS" | "SCAN" | "RPUSH" | "LPUSH" | "LRANGE" | "INCR" | "DECR"
+                | "INCRBY" | "DECRBY" | "PEXPIRE" | "SADD" | "SREM" | "SMEMBERS" | "SISMEMBER"
+                | "SCARD" | "HSET" | "HGET" | "HMGET" | "TIME" | "SCRIPT" | "EVAL" | "EVALSHA",
+                _,
+            ) => Reply::Error(format!(
+                "ERR wrong number of arguments for '{}' command",
+                name.to_lowercase()
+            )),
+            _ => Reply::Error(format!("ERR unknown command '{}'", name)),
+        }
+    }
+
+    /// Add `delta` to the integer under `key`, 0 when missing, keeping its expiry.
+    fn increment(&mut self, key: &[u8], delta: i64) -> Reply {
+        let value = match self.entry_or_insert(key, || Value::String(b"0".to_vec())) {
+            Value::String(value) => value,
+            _ => return Reply::wrong_type(),
+        };
+        match parse_int(value).and_then(|n| n.checked_add(delta)) {
+            Some(n) => {
+                *value = n.to_string().into_bytes();
+                Reply::Integer(n)
+            }
+            None => Reply::not_an_integer(),
+        }
+    }
+
+    /// The values of `fields` in the hash under `key`, or the error reply for a key of another
+    /// type.
+    fn hash_values(
+        &mut self,
+        key: &[u8],
+        fields: &[Vec<u8>],
+    ) -> Result<Vec<Option<Vec<u8>>>, Reply> {
+        match self.live(key) {
+            None => Ok(vec![None; fields.len()]),
+            Some(Entry {
+                value: Value::Hash(hash),
+                ..
+            }) => Ok(fields
+                .iter()
+                .map(|field| hash.get(field).cloned())
+                .collect()),
+            Some(_) => Err(Reply::wrong_type()),
+        }
+    }
+
+    fn eval(&mut self, sha: &str, key_count: &[u8], rest: &[Vec<u8>]) -> Reply {
+        let Some(key_count) = parse_int(key_count)
+            .and_then(|n| usize::try_from(n).ok())
+            .filter(|n| *n <= rest.len())
+        else {
+            return Reply::Error(
+                "ERR Number of keys can't be greater than number of args".to_string(),
+            );
+        };
+        match native_scripts().get(sha) {
+            Some(script) => {
+                let (keys, args) = rest.split_at(key_count);
+                script(self, keys, args)
+            }
+            None => Reply::Error(
+                "NOSCRIPT No matching script. The in-memory Redis runs only the scripts it \
+                 implements natively"
+                    .to_string(),
+            ),
+        }
+    }
+
+    /// The rate limiter's `TAKE_TOKEN_SCRIPT`: take a token from the bucket at `KEYS[1]`
+    /// refilling at `ARGV[1]` tokens per second, returning the milliseconds to wait for it.
+    fn take_token(&mut self, keys: &[Vec<u8>], args: &[Vec<u8>]) -> Reply {
+        let (Some(key), Some(rate)) = (keys.first(), args.first().and_then(|a| parse_float(a)))
+        else {
+            return Reply::Error("ERR take token script needs a key and a rate".to_string());
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as f64;
+        let bucket = match self.hash_values(key, &[b"tokens".to_vec(), b"at".to_vec()]) {
+            Ok(bucket) => bucket,
+            Err(reply) => return reply,
+        };
+        let field = |i: usize| bucket[i].as_deref().and_then(parse_float);
+        let tokens = field(0).unwrap_or(rate);
+        let at = field(1).unwrap_or(now);
+        let tokens = rate.min(tokens + (now - at).max(0.0) * rate / 1000.0) - 1.0;
+
+        let Value::Hash(hash) = self.entry_or_insert(key, || Value::Hash(HashMap::new())) else {
+            return Reply::wrong_type();
+        };
+        hash.insert(b"tokens".to_vec(), tokens.to_string().into_bytes());
+        hash.insert(b"at".to_vec(), now.to_string().into_bytes());
+        let wait = if tokens < 0.0 {
+            (-tokens * 1000.0 / rate).ceil() as i64
+        } else {
+            0
+        };
+        if let Some(entry) = self.live(key) {
+            entry.expires_at = Some(Instant::now() + Duration::from_millis(wait as u64 + 60_000));
+        }
+        Reply::Integer(wait)
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8], options: &[Vec<u8>]) -> Reply {
+        let mut ttl = None;
+        let (mut only_new, mut only_existing) = (false, false);
+        let mut options = options.iter();
+        while let Some(option) = options.next() {
+            match String::from_utf8_lossy(option)
+                .to_ascii_uppercase()
+                .as_str()
+            {
+                "NX" => only_new = true,
+                "XX" => only_existing = true,
+                unit @ ("EX" | "PX") => match options.next().and_then(|v| parse_int(v)) {
+                    Some(n) if n > 0 && unit == "EX" => ttl = Some(Duration::from_secs(n as u64)),
+                    Some(n) if n > 0 => ttl = Some(Duration::from_millis(n as u64)),
+                    _ => {
+                        return Reply::Error("ERR invalid expire time in 'set' command".to_string())
+                    }
+                },
+                _ => return Reply::syntax_error(),
+            }
+        }
+        let exists = self.live(key).is_some();
+        if (only_new && exists) || (only_existing && !exists) {
+            return Reply::Bulk(None);
+        }
+        self.insert(key, value, ttl);
+        Reply::Status("OK")
+    }
+
+    fn insert(&mut self, key: &[u8], value: &[u8], ttl: Option<Duration>) {
+        self.entries.insert(
+            key.to_vec(),
+            Entry {
+                value: Value::String(value.to_vec()),
+                expires_at: ttl.map(|ttl| Instant::now() + ttl),
+            },
+        );
+    }
+
+    /// The value under `key`, inserting `empty()` without expiry when there is none.
+    fn entry_or_insert(&mut self, key: &[u8], empty: impl FnOnce() -> Value) -> &mut Value {
+        if self.live(key).is_none() {
+            self.entries.insert(
+                key.to_vec(),
+                Entry {
+                    value: empty(),
+                    expires_at: None,
+                },
+            );
+        }
+        &mut self.entries.get_mut(key).expect("inserted above").value
+    }
+
+    /// The entry under `key`, unless it expired, in which case it is dropped.
+    fn live(&mut self, key: &[u8]) -> Option<&mut Entry> {
+        let expired = self
+            .entries
+            .get(key)?
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= Instant::now());
+        if expired {
+            self.entries.remove(key);
+            return None;
+        }
+        self.entries.get_mut(key)
+    }
+
+    /// Live keys matching a glob-style `pattern`, or all of them.
+    fn matching(&mut self, pattern: Option<&Vec<u8>>) -> Vec<Vec<u8>> {
+        let now = Instant::now();
+        self.entries
+            .retain(|_, entry| entry.expires_at.is_none_or(|expires_at| expires_at > now));
+        let pattern = pattern.and_then(|p| glob::Pattern::new(&String::from_utf8_lossy(p)).ok());
+        self.entries
+            .keys()
+            .filter(|key| {
+                pattern
+                    .as_ref()
+                    .is_none_or(|p| p.matches(&String::from_utf8_lossy(key)))
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+fn parse_int(value: &[u8]) -> Option<i64> {
+    std::str::from_utf8(value).ok()?.parse().ok()
+}
+
+fn parse_float(value: &[u8]) -> Option<f64> {
+    std::str::from_utf8(value).ok()?.parse().ok()
+}
@@ -51,6 +51,9 @@ impl BaseTestFixture {
             max_connections: 5,
             acquire_timeout_seconds: 30,
             require_ssl: false,
+            statement_timeout_ms: None,
+            slow_query_threshold_ms: 1000,
+            application_name: None,
         }
     }
 
@@ -448,7 +451,7 @@ pub async fn create_test_documents_with_embeddings(pool: &PgPool) -> Result<Vec<
         .bind(0) // chunk_start_offset
         .bind(100) // chunk_end_offset
         .bind(embedding)
-        .bind("intfloat/e5-large-v2") // model_name
+        .bind(TEST_EMBEDDING_MODEL)
         .bind(1024_i16) // dimensions
         .execute(pool)
         .await?;
@@ -457,5 +460,11 @@ pub async fn create_test_documents_with_embeddings(pool: &PgPool) -> Result<Vec<
     Ok(doc_ids)
 }
 
+/// Model name of the fixture embeddings, and of the embeddings returned by the mock AI server.
+pub const TEST_EMBEDDING_MODEL: &str = "test-model";
+
+/// Model name the mock AI server reports for shadow embedding requests.
+pub const TEST_SHADOW_EMBEDDING_MODEL: &str = "test-shadow-model";
+
 pub const TEST_USER_ID: &str = "01JGF7V3E0Y2R1X8P5Q7W9T4N6";
 pub const TEST_SOURCE_ID: &str = "01JGF7V3E0Y2R1X8P5Q7W9T4N7";
@@ -0,0 +1,130 @@
+//! Normalization of search queries across scripts and languages.
+//!
+//! The full-text index folds diacritics and compatibility forms away, so queries are folded
+//! the same way: "café" matches "cafe", and full-width "ＰＤＦ" matches "PDF".
+//!
+//! Chinese and Japanese are written without spaces between words, and the index keeps each
+//! of their characters as a term of its own. Runs of these characters in a query are split
+//! into overlapping bigrams, matched as phrases, which finds the words they contain without a
+//! dictionary of either language.
+
+use unicode_normalization::UnicodeNormalization;
+
+/// Most CJK bigrams matched for one query. Longer runs are matched by their first bigrams.
+pub const MAX_CJK_BIGRAMS: usize = 16;
+
+/// Fold `text` to the form the index keeps: compatibility forms replaced by their canonical
+/// equivalents and diacritics removed from Latin, Greek and Cyrillic letters, with runs of
+/// whitespace collapsed. Marks that change the sound of kana are kept.
+pub fn fold(text: &str) -> String {
+    let folded: String = text
+        .nfkd()
+        .filter(|c| !('\u{0300}'..='\u{036f}').contains(c))
+        .nfc()
+        .collect();
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Whether `c` is written without spaces between words: Han ideographs and Japanese kana.
+pub fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30ff}' // Hiragana and Katakana
+        | '\u{3400}'..='\u{4dbf}' // CJK Unified Ideographs Extension A
+        | '\u{4e00}'..='\u{9fff}' // CJK Unified Ideographs
+        | '\u{f900}'..='\u{faff}' // CJK Compatibility Ideographs
+        | '\u{20000}'..='\u{2ebef}' // CJK Unified Ideographs Extensions B to F
+    )
+}
+
+/// A query split into the words matched as terms and the CJK bigrams matched as phrases.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryTerms {
+    /// The folded query without its CJK characters.
+    pub words: String,
+    /// Overlapping bigrams of the query's CJK runs, in order. A run of one character is kept
+    /// as it is.
+    pub cjk_bigrams: Vec<String>,
+}
+
+impl QueryTerms {
+    pub fn new(query: &str) -> Self {
+        let folded = fold(query);
+        let mut words = String::new();
+        let mut cjk_bigrams: Vec<String> = Vec::new();
+        let mut run: Vec<char> = Vec::new();
+
+        for c in folded.chars().chain(std::iter::once(' ')) {
+            if is_cjk(c) {
+                run.push(c);
+                continue;
+            }
+            if !run.is_empty() {
+                if run.len() == 1 {
+                    cjk_bigrams.push(run[0].to_string());
+                } else {
+                    cjk_bigrams.extend(run.windows(2).map(|pair| pair.iter().collect::<String>()));
+                }
+                run.clear();
+                words.push(' ');
+            }
+            words.push(c);
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        cjk_bigrams.retain(|bigram| seen.insert(bigram.clone()));
+        cjk_bigrams.truncate(MAX_CJK_BIGRAMS);
+        Self {
+            words: words.split_whitespace().collect::<Vec<_>>().join(" "),
+            cjk_bigrams,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold() {
+        assert_eq!(fold("Café  crème"), "Cafe creme");
+        assert_eq!(fold("Ｑ３ ｐｌａｎ"), "Q3 plan");
+        assert_eq!(fold("Straße"), "Straße");
+        assert_eq!(fold("東京"), "東京");
+        assert_eq!(fold("ガイド"), "ガイド");
+    }
+
+    #[test]
+    fn test_query_terms_without_cjk() {
+        let terms = QueryTerms::new(" résumé template ");
+        assert_eq!(terms.words, "resume template");
+        assert!(terms.cjk_bigrams.is_empty());
+    }
+
+    #[test]
+    fn test_query_terms_cjk_bigrams() {
+        let terms = QueryTerms::new("東京都庁 report");
+        assert_eq!(terms.words, "report");
+        assert_eq!(terms.cjk_bigrams, vec!["東京", "京都", "都庁"]);
+
+        let terms = QueryTerms::new("会議の議事録");
+        assert_eq!(terms.words, "");
+        assert_eq!(
+            terms.cjk_bigrams,
+            vec!["会議", "議の", "の議", "議事", "事録"]
+        );
+
+        let terms = QueryTerms::new("Q3の計画");
+        assert_eq!(terms.words, "Q3");
+        assert_eq!(terms.cjk_bigrams, vec!["の計", "計画"]);
+
+        let terms = QueryTerms::new("本 book");
+        assert_eq!(terms.cjk_bigrams, vec!["本"]);
+    }
+
+    #[test]
+    fn test_query_terms_bigram_limit() {
+        let query: String = (0..40).filter_map(|i| char::from_u32(0x4e00 + i)).collect();
+        let terms = QueryTerms::new(&query);
+        assert_eq!(terms.cjk_bigrams.len(), MAX_CJK_BIGRAMS);
+    }
+}
@@ -0,0 +1,141 @@
+//! Mutual TLS between services.
+//!
+//! Disabled unless [`ServiceTlsConfig`] is set, in which case services serve HTTPS to clients
+//! holding a certificate signed by the service CA, and present their own certificate when
+//! calling other services. Services called by clients that can't present a certificate can
+//! still serve HTTPS without requiring one, see [`ServiceTlsConfig::require_client_cert`].
+
+use anyhow::{anyhow, Context, Result};
+use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
+use reqwest::{Certificate, ClientBuilder, Identity};
+use rustls::pki_types::CertificateDer;
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig};
+use std::fs::File;
+use std::future::IntoFuture;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tracing::info;
+
+use crate::config::ServiceTlsConfig;
+use crate::shutdown::{Shutdown, DRAIN_TIMEOUT};
+
+impl ServiceTlsConfig {
+    /// Trust the service CA and present this service's certificate on every request.
+    pub fn configure_client(&self, builder: ClientBuilder) -> Result<ClientBuilder> {
+        let ca = read(&self.ca_path)?;
+        let mut identity = read(&self.cert_path)?;
+        identity.push(b'\n');
+        identity.extend(read(&self.key_path)?);
+
+        Ok(builder
+            .use_rustls_tls()
+            .add_root_certificate(Certificate::from_pem(&ca)?)
+            .identity(Identity::from_pem(&identity)?))
+    }
+
+    /// Server configuration rejecting clients without a certificate signed by the service CA,
+    /// or only those presenting a certificate that isn't when client certificates are optional.
+    pub fn server_config(&self) -> Result<ServerConfig> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+
+        let mut roots = RootCertStore::empty();
+        for cert in read_certs(&self.ca_path)? {
+            roots.add(cert)?;
+        }
+        let mut verifier =
+            WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider.clone());
+        if !self.require_client_cert {
+            verifier = verifier.allow_unauthenticated();
+        }
+        let verifier = verifier.build()?;
+
+        let certs = read_certs(&self.cert_path)?;
+        let key = rustls_pemfile::private_key(&mut BufReader::new(open(&self.key_path)?))?
+            .ok_or_else(|| anyhow!("No private key found in {}", self.key_path))?;
+
+        let mut config = ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()?
+            .with_client_cert_verifier(verifier)
+            .with_single_cert(certs, key)?;
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+        Ok(config)
+    }
+}
+
+fn open(path: &str) -> Result<File> {
+    File::open(path).with_context(|| format!("Failed to open {}", path))
+}
+
+fn read(path: &str) -> Result<Vec<u8>> {
+    std::fs::read(path).with_context(|| format!("Failed to read {}", path))
+}
+
+fn read_certs(path: &str) -> Result<Vec<CertificateDer<'static>>> {
+    let certs = rustls_pemfile::certs(&mut BufReader::new(open(path)?))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .with_context(|| format!("Invalid certificate in {}", path))?;
+    if certs.is_empty() {
+        return Err(anyhow!("No certificates found in {}", path));
+    }
+    Ok(certs)
+}
+
+/// Builder for clients calling other services, set up for mutual TLS when configured.
+pub fn client_builder() -> Result<ClientBuilder> {
+    let builder = reqwest::Client::builder();
+    match ServiceTlsConfig::from_env() {
+        Some(tls) => tls.configure_client(builder),
+        None => Ok(builder),
+    }
+}
+
+/// Serve `app` on `listener`, over mutual TLS when configured, until `shutdown`. The peer
+/// address is made available to handlers and middleware as `ConnectInfo<SocketAddr>`. On
+/// shutdown no new connections are accepted, and requests in flight are given up to
+/// [`DRAIN_TIMEOUT`] to finish.
+pub async fn serve(listener: TcpListener, app: Router, shutdown: Shutdown) -> Result<()> {
+    let make_service = app.into_make_service_with_connect_info::<SocketAddr>();
+
+    match ServiceTlsConfig::from_env() {
+        Some(tls) => {
+            if tls.require_client_cert {
+                info!("Mutual TLS enabled");
+            } else {
+                info!("TLS enabled, client certificates optional");
+            }
+            let config = RustlsConfig::from_config(Arc::new(tls.server_config()?));
+            let handle = axum_server::Handle::new();
+            let draining = handle.clone();
+            tokio::spawn(async move {
+                shutdown.requested().await;
+                draining.graceful_shutdown(Some(DRAIN_TIMEOUT));
+            });
+            axum_server::from_tcp_rustls(listener.into_std()?, config)
+                .handle(handle)
+                .serve(make_service)
+                .await?;
+        }
+        None => {
+            let graceful = shutdown.clone();
+            let server = axum::serve(listener, make_service)
+                .with_graceful_shutdown(async move { graceful.requested().await })
+                .into_future();
+            // Unlike TLS connections, plain HTTP ones are drained without a deadline
+            let deadline = async move {
+                shutdown.requested().await;
+                tokio::time::sleep(DRAIN_TIMEOUT).await;
+            };
+            tokio::select! {
+                result = server => result?,
+                _ = deadline => {}
+            }
+        }
+    }
+
+    Ok(())
+}
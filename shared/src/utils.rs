@@ -1,7 +1,41 @@
-use ulid::Ulid;
+use chrono::{DateTime, TimeZone, Utc};
+use std::sync::{Mutex, PoisonError};
+use time::OffsetDateTime;
+use ulid::{Generator, Ulid};
 
+static ULID_GENERATOR: Mutex<Generator> = Mutex::new(Generator::new());
+
+/// A new ULID, greater than every other generated by this process. ULIDs generated within
+/// the same millisecond, as when inserting rows in bulk, then still sort in generation order,
+/// and so do the rows keyed by them.
 pub fn generate_ulid() -> String {
-    Ulid::new().to_string()
+    let mut generator = ULID_GENERATOR
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    // Only fails once a millisecond's random part is exhausted, after 2^80 ULIDs
+    generator
+        .generate()
+        .unwrap_or_else(|_| Ulid::new())
+        .to_string()
+}
+
+/// `datetime` as an `OffsetDateTime` in UTC, `None` outside the years `time` supports.
+pub fn to_offset_datetime<Tz: TimeZone>(datetime: &DateTime<Tz>) -> Option<OffsetDateTime> {
+    let nanos = i128::from(datetime.timestamp()) * 1_000_000_000
+        + i128::from(datetime.timestamp_subsec_nanos());
+    OffsetDateTime::from_unix_timestamp_nanos(nanos).ok()
+}
+
+/// `datetime` as a `chrono::DateTime` in UTC.
+pub fn to_chrono_datetime(datetime: OffsetDateTime) -> DateTime<Utc> {
+    Utc.timestamp_opt(datetime.unix_timestamp(), datetime.nanosecond())
+        .single()
+        .expect("chrono supports every year time does")
+}
+
+/// The time `millis` milliseconds after the Unix epoch, as connector APIs often report it.
+pub fn offset_datetime_from_unix_millis(millis: i64) -> Option<OffsetDateTime> {
+    OffsetDateTime::from_unix_timestamp_nanos(i128::from(millis) * 1_000_000).ok()
 }
 
 /// Safely slices a string at the given byte positions, adjusting to char boundaries.
@@ -130,6 +164,28 @@ pub fn normalize_whitespace(text: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_generate_ulid_is_monotonic() {
+        let ulids: Vec<String> = (0..10_000).map(|_| generate_ulid()).collect();
+        assert!(ulids.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_datetime_conversions() {
+        let chrono = DateTime::parse_from_rfc3339("2024-03-05T14:30:15.123456789+02:00").unwrap();
+        let offset = to_offset_datetime(&chrono).unwrap();
+        assert_eq!(offset.unix_timestamp(), chrono.timestamp());
+        assert_eq!(offset.nanosecond(), 123_456_789);
+        assert_eq!(to_chrono_datetime(offset), chrono.with_timezone(&Utc));
+
+        let far_future = Utc.with_ymd_and_hms(20_000, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(to_offset_datetime(&far_future), None);
+
+        let from_millis = offset_datetime_from_unix_millis(1_704_067_200_250).unwrap();
+        assert_eq!(from_millis.unix_timestamp(), 1_704_067_200);
+        assert_eq!(from_millis.millisecond(), 250);
+    }
+
     #[test]
     fn test_safe_str_slice_ascii() {
         let content = "hello world";
@@ -21,6 +21,7 @@ mod tests {
                 chunk_index: 0,
                 chunk_start_offset: 0,
                 chunk_end_offset: 100,
+                content_sha256: None,
                 embedding: Vector::from(vec![0.1, 0.2, 0.3]),
                 model_name: "test-model".to_string(),
                 dimensions: 3,
@@ -32,6 +33,7 @@ mod tests {
                 chunk_index: 1,
                 chunk_start_offset: 100,
                 chunk_end_offset: 200,
+                content_sha256: None,
                 embedding: Vector::from(vec![0.4, 0.5, 0.6]),
                 model_name: "test-model".to_string(),
                 dimensions: 3,
@@ -67,6 +69,7 @@ mod tests {
                 chunk_index: 0,
                 chunk_start_offset: 0,
                 chunk_end_offset: 100,
+                content_sha256: None,
                 embedding: Vector::from(vec![0.1, 0.2, 0.3]),
                 model_name: "test-model".to_string(),
                 dimensions: 3,
@@ -78,6 +81,7 @@ mod tests {
                 chunk_index: 1,
                 chunk_start_offset: 100,
                 chunk_end_offset: 200,
+                content_sha256: None,
                 embedding: Vector::from(vec![0.4, 0.5, 0.6]),
                 model_name: "test-model".to_string(),
                 dimensions: 3,
@@ -90,6 +94,7 @@ mod tests {
                 chunk_index: 0,
                 chunk_start_offset: 0,
                 chunk_end_offset: 150,
+                content_sha256: None,
                 embedding: Vector::from(vec![0.7, 0.8, 0.9]),
                 model_name: "test-model".to_string(),
                 dimensions: 3,
@@ -101,6 +106,7 @@ mod tests {
                 chunk_index: 1,
                 chunk_start_offset: 150,
                 chunk_end_offset: 300,
+                content_sha256: None,
                 embedding: Vector::from(vec![1.0, 1.1, 1.2]),
                 model_name: "test-model".to_string(),
                 dimensions: 3,
@@ -112,6 +118,7 @@ mod tests {
                 chunk_index: 2,
                 chunk_start_offset: 300,
                 chunk_end_offset: 450,
+                content_sha256: None,
                 embedding: Vector::from(vec![1.3, 1.4, 1.5]),
                 model_name: "test-model".to_string(),
                 dimensions: 3,
@@ -124,6 +131,7 @@ mod tests {
                 chunk_index: 0,
                 chunk_start_offset: 0,
                 chunk_end_offset: 50,
+                content_sha256: None,
                 embedding: Vector::from(vec![1.6, 1.7, 1.8]),
                 model_name: "test-model".to_string(),
                 dimensions: 3,
@@ -160,6 +168,7 @@ mod tests {
             chunk_index: 0,
             chunk_start_offset: 0,
             chunk_end_offset: 100,
+            content_sha256: None,
             embedding: Vector::from(vec![0.1, 0.2, 0.3]),
             model_name: "test-model".to_string(),
             dimensions: 3,
@@ -175,6 +184,7 @@ mod tests {
             chunk_index: 0,        // Same chunk_index
             chunk_start_offset: 0, // Different offsets
             chunk_end_offset: 150,
+            content_sha256: None,
             embedding: Vector::from(vec![0.9, 0.8, 0.7]), // Different embedding
             model_name: "test-model".to_string(),         // Same model_name
             dimensions: 3,
@@ -224,6 +234,7 @@ mod tests {
                     chunk_index: chunk_idx,
                     chunk_start_offset: chunk_idx * 100,
                     chunk_end_offset: (chunk_idx + 1) * 100,
+                    content_sha256: None,
                     embedding: Vector::from(vec![
                         doc_idx as f32 * 0.1,
                         chunk_idx as f32 * 0.1,
@@ -268,6 +279,7 @@ mod tests {
                 chunk_index: 0,
                 chunk_start_offset: 0,
                 chunk_end_offset: 100,
+                content_sha256: None,
                 embedding: Vector::from(vec![0.1, 0.2, 0.3]),
                 model_name: "test-model".to_string(),
                 dimensions: 3,
@@ -279,6 +291,7 @@ mod tests {
                 chunk_index: 1,
                 chunk_start_offset: 100,
                 chunk_end_offset: 200,
+                content_sha256: None,
                 embedding: Vector::from(vec![0.4, 0.5, 0.6]),
                 model_name: "test-model".to_string(),
                 dimensions: 3,
@@ -291,6 +304,7 @@ mod tests {
                 chunk_index: 0,
                 chunk_start_offset: 0,
                 chunk_end_offset: 150,
+                content_sha256: None,
                 embedding: Vector::from(vec![0.7, 0.8, 0.9]),
                 model_name: "test-model".to_string(),
                 dimensions: 3,
@@ -303,6 +317,7 @@ mod tests {
                 chunk_index: 0,
                 chunk_start_offset: 0,
                 chunk_end_offset: 50,
+                content_sha256: None,
                 embedding: Vector::from(vec![1.0, 1.1, 1.2]),
                 model_name: "test-model".to_string(),
                 dimensions: 3,
@@ -66,6 +66,69 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_dequeue_holds_back_documents_in_process() {
+        let env = TestEnvironment::new().await.unwrap();
+        let queue = EventQueue::new(env.db_pool.pool().clone());
+
+        let first_id = queue
+            .enqueue(TEST_SOURCE_ID, &make_event("run-1", "doc-1"))
+            .await
+            .unwrap();
+        let batch = queue.dequeue_batch(10).await.unwrap();
+        assert_eq!(batch.len(), 1);
+
+        // Another replica must not pick up doc-1 while its first event is in process
+        let second_id = queue
+            .enqueue(TEST_SOURCE_ID, &make_event("run-1", "doc-1"))
+            .await
+            .unwrap();
+        queue
+            .enqueue(TEST_SOURCE_ID, &make_event("run-1", "doc-2"))
+            .await
+            .unwrap();
+        let batch = queue.dequeue_batch(10).await.unwrap();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].payload["document_id"], "doc-2");
+
+        queue.mark_completed(&first_id).await.unwrap();
+        let batch = queue.dequeue_batch(10).await.unwrap();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].id, second_id);
+    }
+
+    #[tokio::test]
+    async fn test_dequeue_keeps_document_order_across_sync_runs() {
+        let env = TestEnvironment::new().await.unwrap();
+        let queue = EventQueue::new(env.db_pool.pool().clone());
+
+        let run_a = ulid::Ulid::new().to_string();
+        let run_b = ulid::Ulid::new().to_string();
+        queue
+            .enqueue(TEST_SOURCE_ID, &make_event(&run_a, "doc-1"))
+            .await
+            .unwrap();
+        for doc_id in ["doc-1", "doc-2", "doc-3"] {
+            queue
+                .enqueue(TEST_SOURCE_ID, &make_event(&run_b, doc_id))
+                .await
+                .unwrap();
+        }
+
+        // run_b's doc-1 event waits for the older one in run_a
+        let batch = queue.dequeue_batch(10).await.unwrap();
+        let mut documents: Vec<&str> = batch
+            .iter()
+            .map(|item| item.payload["document_id"].as_str().unwrap())
+            .collect();
+        documents.sort();
+        assert_eq!(documents, vec!["doc-2", "doc-3"]);
+
+        let batch = queue.dequeue_batch(10).await.unwrap();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].sync_run_id, run_a);
+    }
+
     #[tokio::test]
     async fn test_mark_completed() {
         let env = TestEnvironment::new().await.unwrap();
@@ -252,4 +315,75 @@ mod tests {
         queue.dequeue_batch(2).await.unwrap();
         assert_eq!(queue.get_pending_count().await.unwrap(), 3);
     }
+
+    #[tokio::test]
+    async fn test_staged_events_relayed_on_commit_only() {
+        let env = TestEnvironment::new().await.unwrap();
+        let pool = env.db_pool.pool().clone();
+        let queue = EventQueue::new(pool.clone());
+
+        let mut tx = pool.begin().await.unwrap();
+        EventQueue::stage(&mut tx, TEST_SOURCE_ID, &make_event("run-1", "doc-1"))
+            .await
+            .unwrap();
+        tx.rollback().await.unwrap();
+        assert_eq!(queue.get_outbox_count().await.unwrap(), 0);
+
+        let mut tx = pool.begin().await.unwrap();
+        let event_id = EventQueue::stage(&mut tx, TEST_SOURCE_ID, &make_event("run-1", "doc-2"))
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+        assert_eq!(queue.get_outbox_count().await.unwrap(), 1);
+        assert_eq!(queue.get_pending_count().await.unwrap(), 0);
+
+        assert_eq!(queue.relay_outbox(10).await.unwrap(), 1);
+        assert_eq!(queue.relay_outbox(10).await.unwrap(), 0);
+        assert_eq!(queue.get_outbox_count().await.unwrap(), 0);
+
+        let batch = queue.dequeue_batch(10).await.unwrap();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].id, event_id);
+    }
+
+    #[tokio::test]
+    async fn test_event_staged_with_id_is_delivered_once() {
+        let env = TestEnvironment::new().await.unwrap();
+        let pool = env.db_pool.pool().clone();
+        let queue = EventQueue::new(pool.clone());
+        let event_id = ulid::Ulid::new().to_string();
+        let event = make_event("run-1", "doc-1");
+
+        let mut tx = pool.begin().await.unwrap();
+        assert!(
+            EventQueue::stage_with_id(&mut tx, &event_id, TEST_SOURCE_ID, &event)
+                .await
+                .unwrap()
+        );
+        tx.commit().await.unwrap();
+
+        // Sent again before the relay moved it
+        let mut tx = pool.begin().await.unwrap();
+        assert!(
+            !EventQueue::stage_with_id(&mut tx, &event_id, TEST_SOURCE_ID, &event)
+                .await
+                .unwrap()
+        );
+        tx.commit().await.unwrap();
+        assert_eq!(queue.relay_outbox(10).await.unwrap(), 1);
+
+        // And after
+        let mut tx = pool.begin().await.unwrap();
+        EventQueue::stage_with_id(&mut tx, &event_id, TEST_SOURCE_ID, &event)
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+        // Taken from the outbox, but not queued a second time
+        assert_eq!(queue.relay_outbox(10).await.unwrap(), 1);
+        assert_eq!(queue.get_outbox_count().await.unwrap(), 0);
+
+        let batch = queue.dequeue_batch(10).await.unwrap();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].id, event_id);
+    }
 }
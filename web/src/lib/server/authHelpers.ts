@@ -39,3 +39,17 @@ export function requireActiveUser(locals: App.Locals, redirectTo = '/login') {
 
     return { user, session }
 }
+
+// The path to return to after signing in, when it is a page of this app. Anything else, e.g. a
+// URL on another site, returns to the home page.
+export function safeRedirectPath(path: string | null): string {
+    if (!path || !path.startsWith('/') || path.startsWith('//') || path.startsWith('/\\')) {
+        return '/'
+    }
+    return path
+}
+
+// The sign-in page, returning to `path` afterwards
+export function loginUrl(path: string): string {
+    return path === '/' ? '/login' : `/login?redirect=${encodeURIComponent(path)}`
+}
@@ -0,0 +1,86 @@
+import { env } from '$env/dynamic/private'
+import { json } from '@sveltejs/kit'
+import { verify } from '@node-rs/argon2'
+import { userRepository } from '$lib/server/db/users'
+
+export type ConfluenceSearchKind = 'search' | 'content/search'
+
+// Errors in the shape Confluence's REST API returns them
+function confluenceError(statusCode: number, message: string): Response {
+    return json({ statusCode, message }, { status: statusCode })
+}
+
+// The user a Confluence API call is made as: the signed-in user, or the user named by HTTP
+// Basic credentials of an Omni email and password, as tools calling Confluence send them
+async function confluenceUser(request: Request, locals: App.Locals): Promise<string | null> {
+    if (locals.user?.isActive) {
+        return locals.user.id
+    }
+
+    const header = request.headers.get('authorization')
+    if (!header?.startsWith('Basic ')) {
+        return null
+    }
+    const credentials = Buffer.from(header.slice('Basic '.length), 'base64').toString('utf8')
+    const separator = credentials.indexOf(':')
+    if (separator < 0) {
+        return null
+    }
+    const email = credentials.slice(0, separator)
+    const password = credentials.slice(separator + 1)
+
+    const foundUser = await userRepository.findByEmail(email)
+    if (!foundUser?.passwordHash || !foundUser.isActive) {
+        return null
+    }
+    return (await verify(foundUser.passwordHash, password)) ? foundUser.id : null
+}
+
+// Serve a Confluence search endpoint from Omni's index, for tools migrating from Confluence
+export async function confluenceSearch(
+    kind: ConfluenceSearchKind,
+    request: Request,
+    fetch: typeof globalThis.fetch,
+    locals: App.Locals,
+    url: URL,
+): Promise<Response> {
+    const userId = await confluenceUser(request, locals)
+    if (!userId) {
+        return confluenceError(401, 'Authentication required')
+    }
+    const cql = url.searchParams.get('cql')
+    if (!cql) {
+        return confluenceError(400, 'The cql parameter is required')
+    }
+
+    try {
+        const searchUrl = new URL(`${env.SEARCHER_URL}/confluence/${kind}`)
+        searchUrl.searchParams.set('cql', cql)
+        searchUrl.searchParams.set('user_id', userId)
+        for (const param of ['start', 'limit']) {
+            const value = url.searchParams.get(param)
+            if (value) {
+                searchUrl.searchParams.set(param, value)
+            }
+        }
+
+        const response = await fetch(searchUrl.toString())
+        if (response.status === 400) {
+            const body = await response.json()
+            return confluenceError(400, body.error)
+        }
+        if (!response.ok) {
+            locals.logger.error('Confluence search service error', undefined, {
+                kind,
+                status: response.status,
+                statusText: response.statusText,
+            })
+            return confluenceError(502, 'Search service unavailable')
+        }
+
+        return json(await response.json())
+    } catch (error) {
+        locals.logger.error('Error calling Confluence search service', error, { kind })
+        return confluenceError(500, 'Failed to perform search')
+    }
+}
@@ -13,6 +13,14 @@ import {
 import type { MessageParam } from '@anthropic-ai/sdk/resources/messages.js'
 import { sql } from 'drizzle-orm'
 
+/** Email notifications a user receives. Settings left unset are enabled. */
+export type DigestFrequency = 'off' | 'daily' | 'weekly'
+
+export type NotificationPreferences = {
+    sync_failures?: boolean
+    digest?: DigestFrequency
+}
+
 export const user = pgTable('users', {
     id: text('id').primaryKey(),
     email: text('email').notNull().unique(),
@@ -22,6 +30,10 @@ export const user = pgTable('users', {
     authMethod: text('auth_method').notNull().default('password'),
     domain: text('domain'),
     mustChangePassword: boolean('must_change_password').notNull().default(false),
+    notificationPreferences: jsonb('notification_preferences')
+        .$type<NotificationPreferences>()
+        .notNull()
+        .default({}),
     createdAt: timestamp('created_at', { withTimezone: true, mode: 'date' }).notNull().defaultNow(),
     updatedAt: timestamp('updated_at', { withTimezone: true, mode: 'date' }).notNull().defaultNow(),
 })
@@ -0,0 +1,42 @@
+import { env } from '$env/dynamic/private'
+import { json } from '@sveltejs/kit'
+
+export type FeedKind = 'trending' | 'recent'
+
+// Proxy a home-page feed from the searcher, filtered to documents the user can see
+export async function fetchFeed(
+    kind: FeedKind,
+    fetch: typeof globalThis.fetch,
+    locals: App.Locals,
+    url: URL,
+): Promise<Response> {
+    if (!locals.user?.email) {
+        return json({ error: 'Unauthorized' }, { status: 401 })
+    }
+
+    try {
+        const feedUrl = new URL(`${env.SEARCHER_URL}/feed/${kind}`)
+        feedUrl.searchParams.set('user_email', locals.user.email)
+        const limit = url.searchParams.get('limit')
+        if (limit) {
+            feedUrl.searchParams.set('limit', limit)
+        }
+
+        const response = await fetch(feedUrl.toString())
+        if (!response.ok) {
+            locals.logger.error('Feed service error', undefined, {
+                kind,
+                status: response.status,
+                statusText: response.statusText,
+            })
+            return json({ documents: [] }, { status: 502 })
+        }
+
+        return json(await response.json(), {
+            headers: { 'Cache-Control': 'private, max-age=60' },
+        })
+    } catch (error) {
+        locals.logger.error('Error calling feed service', error, { kind })
+        return json({ documents: [] }, { status: 500 })
+    }
+}
@@ -0,0 +1,52 @@
+import { error } from '@sveltejs/kit'
+import { getConfig } from './config'
+import { createLogger } from './logger.js'
+
+const logger = createLogger('source-validation')
+
+interface ValidateSourceResponse {
+    valid: boolean
+    error?: string
+    connection?: {
+        success: boolean
+        message: string
+    }
+}
+
+/**
+ * Check an active source's config against its schema and have the connector test the
+ * connection with the source's credentials. Throws a 400 describing the first problem found.
+ */
+export async function assertSourceValid(
+    source: { id: string; sourceType: string },
+    config: unknown,
+): Promise<void> {
+    const connectorManagerUrl = getConfig().services.connectorManagerUrl
+
+    let result: ValidateSourceResponse
+    try {
+        const response = await fetch(`${connectorManagerUrl}/sources/validate`, {
+            method: 'POST',
+            headers: { 'Content-Type': 'application/json' },
+            body: JSON.stringify({
+                source_type: source.sourceType,
+                config: config ?? {},
+                source_id: source.id,
+            }),
+        })
+        if (!response.ok) {
+            throw new Error(`${response.status} ${await response.text()}`)
+        }
+        result = await response.json()
+    } catch (err) {
+        logger.error(`Failed to validate source ${source.id}`, err)
+        throw error(503, 'Could not validate the source configuration')
+    }
+
+    if (result.error) {
+        throw error(400, `Invalid configuration: ${result.error}`)
+    }
+    if (result.connection && !result.connection.success) {
+        throw error(400, `Connection test failed: ${result.connection.message}`)
+    }
+}
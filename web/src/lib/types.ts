@@ -14,6 +14,16 @@ export enum SourceType {
     OUTLOOK = 'outlook',
     OUTLOOK_CALENDAR = 'outlook_calendar',
     FIREFLIES = 'fireflies',
+    SERVICE_NOW = 'service_now',
+    FIGMA = 'figma',
+    AIRTABLE = 'airtable',
+    BITBUCKET = 'bitbucket',
+    PAGERDUTY = 'pagerduty',
+    STACK_OVERFLOW = 'stack_overflow',
+    BOX = 'box',
+    GONG = 'gong',
+    TRELLO = 'trello',
+    PEOPLE = 'people',
 }
 
 export enum ServiceProvider {
@@ -24,6 +34,15 @@ export enum ServiceProvider {
     MICROSOFT = 'microsoft',
     HUBSPOT = 'hubspot',
     FIREFLIES = 'fireflies',
+    SERVICENOW = 'servicenow',
+    FIGMA = 'figma',
+    AIRTABLE = 'airtable',
+    BITBUCKET = 'bitbucket',
+    PAGERDUTY = 'pagerduty',
+    STACKOVERFLOW = 'stackoverflow',
+    BOX = 'box',
+    GONG = 'gong',
+    TRELLO = 'trello',
 }
 
 export enum AuthType {
@@ -73,3 +92,56 @@ export interface FilesystemSourceConfig {
 export interface HubspotSourceConfig {
     portal_id?: string
 }
+
+export interface ServiceNowSourceConfig {
+    instance_url: string
+    sync_knowledge?: boolean
+    sync_incidents?: boolean
+    knowledge_bases?: string[]
+    incident_read_roles?: string[]
+}
+
+export interface FigmaSourceConfig {
+    team_ids: string[]
+    restricted_projects?: string[]
+    include_comments?: boolean
+}
+
+export interface AirtableSourceConfig {
+    base_ids?: string[]
+    views?: Record<string, string>
+}
+
+export interface BitbucketSourceConfig {
+    repos?: string[]
+    workspaces?: string[]
+    include_forks?: boolean
+}
+
+export interface PagerdutySourceConfig {
+    service_ids?: string[]
+    team_ids?: string[]
+    lookback_days?: number
+    postmortem_prefixes?: string[]
+    team_permissions?: boolean
+}
+
+export interface StackOverflowSourceConfig {
+    team?: string
+    api_url?: string
+    tags?: string[]
+}
+
+export interface BoxSourceConfig {
+    folder_ids?: string[]
+}
+
+export interface GongSourceConfig {
+    api_url?: string
+    workspace_id?: string
+    lookback_days?: number
+}
+
+export interface TrelloSourceConfig {
+    board_ids?: string[]
+}
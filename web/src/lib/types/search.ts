@@ -6,6 +6,13 @@ export interface Document {
     content_type: string
     created_at: string
     updated_at: string
+    preview?: DocumentPreview
+}
+
+// Served at /api/documents/[id]/preview
+export interface DocumentPreview {
+    kind: 'thumbnail' | 'render'
+    mime_type: string
 }
 
 export interface SearchResult {
@@ -14,6 +21,30 @@ export interface SearchResult {
     highlights: string[]
     match_type: string
     content?: string
+    pinned?: boolean
+    alternates?: AlternateDocument[]
+    anchor?: ResultAnchor
+    children?: ChildResult[]
+}
+
+export interface ChildResult extends SearchResult {
+    relationship: 'attachment' | 'comment' | 'transcript_segment'
+}
+
+export interface ResultAnchor {
+    chunk_index: number
+    start_offset: number
+    end_offset: number
+    heading?: string
+    heading_level?: number
+    timestamp_seconds?: number
+}
+
+export interface AlternateDocument {
+    id: string
+    source_id: string
+    title: string
+    url: string | null
 }
 
 export interface FacetValue {
@@ -26,6 +57,8 @@ export interface Facet {
     values: FacetValue[]
 }
 
+export type FallbackStrategy = 'drop_filters' | 'any_field' | 'spell_corrected' | 'semantic_only'
+
 export interface SearchResponse {
     results: SearchResult[]
     total_count: number
@@ -33,6 +66,10 @@ export interface SearchResponse {
     has_more: boolean
     query: string
     facets?: Facet[]
+    // Set when the query as given found nothing and a relaxed search found these results
+    fallback?: FallbackStrategy
+    // Set when the search ran out of budget, e.g. under load, and returned what it found
+    degraded: boolean
 }
 
 export interface SearchRequest {
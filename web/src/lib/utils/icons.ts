@@ -190,6 +190,16 @@ export function getSourceDisplayName(sourceType: SourceType) {
         [SourceType.LOCAL_FILES]: 'Files',
         [SourceType.WEB]: 'Web',
         [SourceType.FIREFLIES]: 'Fireflies',
+        [SourceType.SERVICE_NOW]: 'ServiceNow',
+        [SourceType.FIGMA]: 'Figma',
+        [SourceType.AIRTABLE]: 'Airtable',
+        [SourceType.BITBUCKET]: 'Bitbucket',
+        [SourceType.PAGERDUTY]: 'PagerDuty',
+        [SourceType.STACK_OVERFLOW]: 'Stack Overflow',
+        [SourceType.BOX]: 'Box',
+        [SourceType.GONG]: 'Gong',
+        [SourceType.TRELLO]: 'Trello',
+        [SourceType.PEOPLE]: 'People',
     }
 
     return sourceDisplayNames[sourceType]
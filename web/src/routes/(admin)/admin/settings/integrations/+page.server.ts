@@ -70,6 +70,71 @@ export const load: PageServerLoad = async ({ locals }) => {
                 connected: connectedSources.some((source) => source.sourceType === 'fireflies'),
                 authType: 'api_key',
             },
+            {
+                id: 'servicenow',
+                name: 'ServiceNow',
+                description: 'Index knowledge base articles and incidents from ServiceNow',
+                connected: connectedSources.some((source) => source.sourceType === 'service_now'),
+                authType: 'basic_auth',
+            },
+            {
+                id: 'figma',
+                name: 'Figma',
+                description: 'Index design file text and comment threads from Figma',
+                connected: connectedSources.some((source) => source.sourceType === 'figma'),
+                authType: 'access_token',
+            },
+            {
+                id: 'airtable',
+                name: 'Airtable',
+                description: 'Index records from Airtable bases, with their fields as attributes',
+                connected: connectedSources.some((source) => source.sourceType === 'airtable'),
+                authType: 'access_token',
+            },
+            {
+                id: 'bitbucket',
+                name: 'Bitbucket',
+                description: 'Index repositories and pull requests from Bitbucket Cloud',
+                connected: connectedSources.some((source) => source.sourceType === 'bitbucket'),
+                authType: 'basic_auth',
+            },
+            {
+                id: 'pagerduty',
+                name: 'PagerDuty',
+                description: 'Index incidents, incident notes and postmortems from PagerDuty',
+                connected: connectedSources.some((source) => source.sourceType === 'pagerduty'),
+                authType: 'api_key',
+            },
+            {
+                id: 'stackoverflow',
+                name: 'Stack Overflow for Teams',
+                description: 'Index questions and accepted answers from Stack Overflow for Teams',
+                connected: connectedSources.some(
+                    (source) => source.sourceType === 'stack_overflow',
+                ),
+                authType: 'access_token',
+            },
+            {
+                id: 'box',
+                name: 'Box',
+                description: 'Index files and folders from Box, with their collaborators',
+                connected: connectedSources.some((source) => source.sourceType === 'box'),
+                authType: 'api_key',
+            },
+            {
+                id: 'gong',
+                name: 'Gong',
+                description: 'Index call transcripts and highlights from Gong',
+                connected: connectedSources.some((source) => source.sourceType === 'gong'),
+                authType: 'api_key',
+            },
+            {
+                id: 'trello',
+                name: 'Trello',
+                description: 'Index boards, cards and comments from Trello',
+                connected: connectedSources.some((source) => source.sourceType === 'trello'),
+                authType: 'api_key',
+            },
             {
                 id: 'microsoft',
                 name: 'Microsoft 365',
@@ -3,6 +3,7 @@ import type { PageServerLoad, Actions } from './$types'
 import { requireAdmin } from '$lib/server/authHelpers'
 import { getSourceById, updateSourceById } from '$lib/server/db/sources'
 import { getConfig } from '$lib/server/config'
+import { assertSourceValid } from '$lib/server/sourceValidation'
 import { SourceType, type ConfluenceSourceConfig } from '$lib/types'
 
 export const load: PageServerLoad = async ({ params, locals }) => {
@@ -45,17 +46,21 @@ export const actions: Actions = {
         const siteUrl = formData.get('siteUrl') as string | null
         const spaceFilters = formData.getAll('spaceFilters') as string[]
 
-        try {
-            const existingConfig = (source.config as ConfluenceSourceConfig) || {}
-            const config: ConfluenceSourceConfig = {
-                base_url: siteUrl
-                    ? siteUrl.startsWith('http')
-                        ? siteUrl
-                        : `https://${siteUrl}`
-                    : existingConfig.base_url,
-                space_filters: spaceFilters.length > 0 ? spaceFilters : undefined,
-            }
+        const existingConfig = (source.config as ConfluenceSourceConfig) || {}
+        const config: ConfluenceSourceConfig = {
+            base_url: siteUrl
+                ? siteUrl.startsWith('http')
+                    ? siteUrl
+                    : `https://${siteUrl}`
+                : existingConfig.base_url,
+            space_filters: spaceFilters.length > 0 ? spaceFilters : undefined,
+        }
 
+        if (isActive) {
+            await assertSourceValid(source, config)
+        }
+
+        try {
             await updateSourceById(source.id, {
                 isActive,
                 config,
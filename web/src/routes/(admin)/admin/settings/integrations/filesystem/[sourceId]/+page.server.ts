@@ -3,6 +3,7 @@ import type { PageServerLoad, Actions } from './$types'
 import { requireAdmin } from '$lib/server/authHelpers'
 import { getSourceById, updateSourceById } from '$lib/server/db/sources'
 import { getConfig } from '$lib/server/config'
+import { assertSourceValid } from '$lib/server/sourceValidation'
 import { SourceType, type FilesystemSourceConfig } from '$lib/types'
 
 export const load: PageServerLoad = async ({ params, locals }) => {
@@ -56,15 +57,19 @@ export const actions: Actions = {
             throw error(400, 'Base path must be an absolute path (starting with /)')
         }
 
-        try {
-            const config: FilesystemSourceConfig = {
-                base_path: basePath,
-                file_extensions: fileExtensions.length > 0 ? fileExtensions : undefined,
-                exclude_patterns: excludePatterns.length > 0 ? excludePatterns : undefined,
-                max_file_size_bytes: maxFileSizeMb * 1024 * 1024,
-                scan_interval_seconds: scanIntervalSeconds,
-            }
+        const config: FilesystemSourceConfig = {
+            base_path: basePath,
+            file_extensions: fileExtensions.length > 0 ? fileExtensions : undefined,
+            exclude_patterns: excludePatterns.length > 0 ? excludePatterns : undefined,
+            max_file_size_bytes: maxFileSizeMb * 1024 * 1024,
+            scan_interval_seconds: scanIntervalSeconds,
+        }
 
+        if (isActive) {
+            await assertSourceValid(source, config)
+        }
+
+        try {
             await updateSourceById(source.id, {
                 isActive,
                 config,
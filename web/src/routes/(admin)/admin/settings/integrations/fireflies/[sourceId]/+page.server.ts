@@ -3,6 +3,7 @@ import type { PageServerLoad, Actions } from './$types'
 import { requireAdmin } from '$lib/server/authHelpers'
 import { getSourceById, updateSourceById } from '$lib/server/db/sources'
 import { getConfig } from '$lib/server/config'
+import { assertSourceValid } from '$lib/server/sourceValidation'
 import { SourceType } from '$lib/types'
 
 export const load: PageServerLoad = async ({ params, locals }) => {
@@ -42,6 +43,10 @@ export const actions: Actions = {
         const formData = await request.formData()
         const isActive = formData.has('enabled')
 
+        if (isActive) {
+            await assertSourceValid(source, source.config)
+        }
+
         try {
             await updateSourceById(source.id, {
                 isActive,
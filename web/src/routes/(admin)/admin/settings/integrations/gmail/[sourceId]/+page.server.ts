@@ -3,6 +3,7 @@ import type { PageServerLoad, Actions } from './$types'
 import { requireAdmin } from '$lib/server/authHelpers'
 import { getSourceById, updateSourceById, type UserFilterMode } from '$lib/server/db/sources'
 import { getConfig } from '$lib/server/config'
+import { assertSourceValid } from '$lib/server/sourceValidation'
 import { SourceType } from '$lib/types'
 
 export const load: PageServerLoad = async ({ params, locals }) => {
@@ -56,6 +57,10 @@ export const actions: Actions = {
             throw error(400, 'Whitelist mode requires at least one user')
         }
 
+        if (isActive) {
+            await assertSourceValid(source, source.config)
+        }
+
         try {
             await updateSourceById(source.id, {
                 isActive,
@@ -3,6 +3,7 @@ import type { PageServerLoad, Actions } from './$types'
 import { requireAdmin } from '$lib/server/authHelpers'
 import { getSourceById, updateSourceById } from '$lib/server/db/sources'
 import { getConfig } from '$lib/server/config'
+import { assertSourceValid } from '$lib/server/sourceValidation'
 import { SourceType, type JiraSourceConfig } from '$lib/types'
 
 export const load: PageServerLoad = async ({ params, locals }) => {
@@ -45,17 +46,21 @@ export const actions: Actions = {
         const siteUrl = formData.get('siteUrl') as string | null
         const projectFilters = formData.getAll('projectFilters') as string[]
 
-        try {
-            const existingConfig = (source.config as JiraSourceConfig) || {}
-            const config: JiraSourceConfig = {
-                base_url: siteUrl
-                    ? siteUrl.startsWith('http')
-                        ? siteUrl
-                        : `https://${siteUrl}`
-                    : existingConfig.base_url,
-                project_filters: projectFilters.length > 0 ? projectFilters : undefined,
-            }
+        const existingConfig = (source.config as JiraSourceConfig) || {}
+        const config: JiraSourceConfig = {
+            base_url: siteUrl
+                ? siteUrl.startsWith('http')
+                    ? siteUrl
+                    : `https://${siteUrl}`
+                : existingConfig.base_url,
+            project_filters: projectFilters.length > 0 ? projectFilters : undefined,
+        }
 
+        if (isActive) {
+            await assertSourceValid(source, config)
+        }
+
+        try {
             await updateSourceById(source.id, {
                 isActive,
                 config,
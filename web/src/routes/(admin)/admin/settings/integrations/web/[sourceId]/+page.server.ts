@@ -3,6 +3,7 @@ import type { PageServerLoad, Actions } from './$types'
 import { requireAdmin } from '$lib/server/authHelpers'
 import { getSourceById, updateSourceById } from '$lib/server/db/sources'
 import { getConfig } from '$lib/server/config'
+import { assertSourceValid } from '$lib/server/sourceValidation'
 import { SourceType, type WebSourceConfig } from '$lib/types'
 
 export const load: PageServerLoad = async ({ params, locals }) => {
@@ -54,17 +55,21 @@ export const actions: Actions = {
             throw error(400, 'Root URL is required when web crawler is enabled')
         }
 
-        try {
-            const config: WebSourceConfig = {
-                root_url: rootUrl,
-                max_depth: maxDepth,
-                max_pages: maxPages,
-                respect_robots_txt: respectRobotsTxt,
-                include_subdomains: includeSubdomains,
-                blacklist_patterns: blacklistPatterns,
-                user_agent: userAgent,
-            }
+        const config: WebSourceConfig = {
+            root_url: rootUrl,
+            max_depth: maxDepth,
+            max_pages: maxPages,
+            respect_robots_txt: respectRobotsTxt,
+            include_subdomains: includeSubdomains,
+            blacklist_patterns: blacklistPatterns,
+            user_agent: userAgent,
+        }
 
+        if (isActive) {
+            await assertSourceValid(source, config)
+        }
+
+        try {
             await updateSourceById(source.id, {
                 isActive,
                 config,
@@ -1,10 +1,13 @@
 import { redirect } from '@sveltejs/kit'
 import type { LayoutServerLoad } from './$types.js'
 import { chatRepository } from '$lib/server/db/chats.js'
+import { loginUrl } from '$lib/server/authHelpers'
 
-export const load: LayoutServerLoad = async ({ locals, depends }) => {
+export const load: LayoutServerLoad = async ({ locals, depends, url, untrack }) => {
     if (!locals.user) {
-        throw redirect(302, '/login')
+        // Return to the requested page after signing in, e.g. a search from the address bar.
+        // Untracked so that the layout does not reload on every navigation.
+        throw redirect(302, loginUrl(untrack(() => url.pathname + url.search)))
     }
 
     if (!locals.user.isActive) {
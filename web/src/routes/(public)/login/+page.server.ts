@@ -10,6 +10,7 @@ import { encodeHexLowerCase } from '@oslojs/encoding'
 import { userRepository } from '$lib/server/db/users'
 import { SystemFlags } from '$lib/server/system-flags'
 import { verify } from '@node-rs/argon2'
+import { safeRedirectPath } from '$lib/server/authHelpers'
 import type { Actions, PageServerLoad } from './$types.js'
 
 // Rate limiting store (in production, use Redis)
@@ -42,7 +43,7 @@ function checkRateLimit(ip: string): boolean {
 
 export const load: PageServerLoad = async ({ cookies, locals, url }) => {
     if (locals.user) {
-        throw redirect(302, '/')
+        throw redirect(302, safeRedirectPath(url.searchParams.get('redirect')))
     }
 
     // Check if this is a first-time setup (system not initialized)
@@ -119,7 +120,7 @@ export const load: PageServerLoad = async ({ cookies, locals, url }) => {
 }
 
 export const actions: Actions = {
-    default: async ({ request, cookies, getClientAddress }) => {
+    default: async ({ request, cookies, getClientAddress, url }) => {
         const clientIP = getClientAddress()
 
         if (!checkRateLimit(clientIP)) {
@@ -203,6 +204,6 @@ export const actions: Actions = {
             })
         }
 
-        throw redirect(302, '/')
+        throw redirect(302, safeRedirectPath(url.searchParams.get('redirect')))
     },
 }
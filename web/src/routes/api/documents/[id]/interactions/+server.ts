@@ -0,0 +1,36 @@
+import { env } from '$env/dynamic/private'
+import { error, json } from '@sveltejs/kit'
+import type { RequestHandler } from './$types.js'
+
+const KINDS = ['view', 'click']
+
+// Record that the user opened a document, for the trending feed
+export const POST: RequestHandler = async ({ fetch, locals, params, request }) => {
+    if (!locals.user?.id) {
+        throw error(401, 'Unauthorized')
+    }
+
+    const body = await request.json().catch(() => ({}))
+    const kind = KINDS.includes(body.kind) ? body.kind : 'click'
+
+    const response = await fetch(
+        `${env.SEARCHER_URL}/documents/${encodeURIComponent(params.id)}/interactions`,
+        {
+            method: 'POST',
+            headers: { 'Content-Type': 'application/json' },
+            body: JSON.stringify({ user_id: locals.user.id, kind }),
+        },
+    )
+    if (response.status === 404) {
+        throw error(404, 'Document not found')
+    }
+    if (!response.ok) {
+        locals.logger.error('Document interaction service error', undefined, {
+            status: response.status,
+            statusText: response.statusText,
+        })
+        throw error(502, 'Failed to record interaction')
+    }
+
+    return json({ status: 'recorded' })
+}
@@ -0,0 +1,35 @@
+import { env } from '$env/dynamic/private'
+import { error } from '@sveltejs/kit'
+import type { RequestHandler } from './$types.js'
+
+export const GET: RequestHandler = async ({ fetch, locals, params }) => {
+    if (!locals.user?.email) {
+        throw error(401, 'Unauthorized')
+    }
+
+    const previewUrl = new URL(
+        `${env.SEARCHER_URL}/documents/${encodeURIComponent(params.id)}/preview`,
+    )
+    previewUrl.searchParams.set('user_email', locals.user.email)
+
+    const response = await fetch(previewUrl.toString())
+    if (response.status === 404) {
+        throw error(404, 'Preview not found')
+    }
+    if (!response.ok) {
+        locals.logger.error('Document preview service error', undefined, {
+            status: response.status,
+            statusText: response.statusText,
+        })
+        throw error(502, 'Failed to load preview')
+    }
+
+    return new Response(response.body, {
+        headers: {
+            'Content-Type': response.headers.get('Content-Type') || 'application/octet-stream',
+            'Cache-Control': 'private, max-age=3600',
+            // Rendered previews are SVG, which must not run scripts when opened directly
+            'Content-Security-Policy': "default-src 'none'; style-src 'unsafe-inline'",
+        },
+    })
+}
@@ -0,0 +1,6 @@
+import { fetchFeed } from '$lib/server/feed'
+import type { RequestHandler } from './$types.js'
+
+export const GET: RequestHandler = async ({ fetch, locals, url }) => {
+    return fetchFeed('trending', fetch, locals, url)
+}
@@ -0,0 +1,32 @@
+import { env } from '$env/dynamic/private'
+import { json } from '@sveltejs/kit'
+import type { RequestHandler } from './$types.js'
+
+// Address bar suggestions in the OpenSearch format: the query followed by matching titles
+export const GET: RequestHandler = async ({ fetch, locals, url }) => {
+    const query = url.searchParams.get('q') || ''
+    if (!locals.user?.id || query.trim() === '') {
+        return json([query, []])
+    }
+
+    try {
+        const typeaheadUrl = new URL(`${env.SEARCHER_URL}/typeahead`)
+        typeaheadUrl.searchParams.set('q', query)
+        typeaheadUrl.searchParams.set('limit', '8')
+
+        const response = await fetch(typeaheadUrl.toString())
+        if (!response.ok) {
+            locals.logger.error('Typeahead service error', undefined, {
+                status: response.status,
+                statusText: response.statusText,
+            })
+            return json([query, []])
+        }
+
+        const data: { results: { title: string }[] } = await response.json()
+        return json([query, data.results.map((result) => result.title)])
+    } catch (error) {
+        locals.logger.error('Error calling typeahead service', error)
+        return json([query, []])
+    }
+}
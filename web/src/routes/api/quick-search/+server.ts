@@ -0,0 +1,71 @@
+import { env } from '$env/dynamic/private'
+import { json } from '@sveltejs/kit'
+import type { RequestHandler } from './$types.js'
+
+// Browser extension origins allowed to call this endpoint with the user's session cookie,
+// e.g. `chrome-extension://<id>,moz-extension://<id>`
+function allowedOrigins(): string[] {
+    return (env.QUICK_SEARCH_ALLOWED_ORIGINS || '')
+        .split(',')
+        .map((origin) => origin.trim())
+        .filter(Boolean)
+}
+
+function corsHeaders(request: Request): Record<string, string> {
+    const origin = request.headers.get('origin')
+    if (!origin || !allowedOrigins().includes(origin)) {
+        return {}
+    }
+    return {
+        'Access-Control-Allow-Origin': origin,
+        'Access-Control-Allow-Credentials': 'true',
+        'Access-Control-Allow-Methods': 'GET, OPTIONS',
+        'Access-Control-Max-Age': '86400',
+        Vary: 'Origin',
+    }
+}
+
+export const OPTIONS: RequestHandler = async ({ request }) => {
+    return new Response(null, { status: 204, headers: corsHeaders(request) })
+}
+
+export const GET: RequestHandler = async ({ fetch, locals, url, request }) => {
+    const headers = corsHeaders(request)
+
+    if (!locals.user?.email) {
+        return json({ error: 'Unauthorized' }, { status: 401, headers })
+    }
+
+    const query = url.searchParams.get('q')?.trim() || ''
+    if (!query) {
+        return json({ results: [], query }, { headers })
+    }
+
+    try {
+        const quickSearchUrl = new URL(`${env.SEARCHER_URL}/quick-search`)
+        quickSearchUrl.searchParams.set('q', query)
+        quickSearchUrl.searchParams.set('user_email', locals.user.email)
+
+        const response = await fetch(quickSearchUrl.toString())
+
+        if (!response.ok) {
+            locals.logger.error('Quick search service error', undefined, {
+                status: response.status,
+                statusText: response.statusText,
+            })
+            return json({ results: [], query }, { status: 502, headers })
+        }
+
+        const data = await response.json()
+        return json(data, {
+            headers: {
+                ...headers,
+                // Results are per user, so only the user's own browser may reuse them
+                'Cache-Control': 'private, max-age=60',
+            },
+        })
+    } catch (error) {
+        locals.logger.error('Error calling quick search service', error)
+        return json({ results: [], query }, { status: 500, headers })
+    }
+}
@@ -0,0 +1,35 @@
+import { env } from '$env/dynamic/private'
+import { json } from '@sveltejs/kit'
+import type { RequestHandler } from './$types.js'
+
+// Documents recommended for the user's home page, recomputed periodically by the indexer
+export const GET: RequestHandler = async ({ fetch, locals, url }) => {
+    if (!locals.user?.id) {
+        return json({ error: 'Unauthorized' }, { status: 401 })
+    }
+
+    try {
+        const recommendationsUrl = new URL(`${env.SEARCHER_URL}/recommendations`)
+        recommendationsUrl.searchParams.set('user_id', locals.user.id)
+        const limit = url.searchParams.get('limit')
+        if (limit) {
+            recommendationsUrl.searchParams.set('limit', limit)
+        }
+
+        const response = await fetch(recommendationsUrl.toString())
+        if (!response.ok) {
+            locals.logger.error('Recommendations service error', undefined, {
+                status: response.status,
+                statusText: response.statusText,
+            })
+            return json({ documents: [], computed_at: null }, { status: 502 })
+        }
+
+        return json(await response.json(), {
+            headers: { 'Cache-Control': 'private, max-age=300' },
+        })
+    } catch (error) {
+        locals.logger.error('Error calling recommendations service', error)
+        return json({ documents: [], computed_at: null }, { status: 500 })
+    }
+}
@@ -0,0 +1,71 @@
+import { env } from '$env/dynamic/private'
+import { json } from '@sveltejs/kit'
+import type { RequestHandler } from './$types.js'
+import type { SearchRequest } from '$lib/types/search.js'
+
+// Runs several searches for the user in one round trip. The searcher applies the user's
+// permissions to every query, so user fields set on the queries are ignored.
+export const POST: RequestHandler = async ({ request, fetch, locals }) => {
+    const logger = locals.logger.child('search-batch-api')
+    if (!locals.user?.id) {
+        return json({ error: 'Unauthorized' }, { status: 401 })
+    }
+
+    let queries: SearchRequest[]
+    try {
+        const body = await request.json()
+        queries = body.queries
+    } catch (error) {
+        logger.warn('Invalid JSON in batch search request', error)
+        return json({ error: 'Invalid JSON in request body' }, { status: 400 })
+    }
+
+    if (!Array.isArray(queries) || queries.length === 0) {
+        return json({ error: 'At least one query is required' }, { status: 400 })
+    }
+
+    try {
+        const response = await fetch(`${env.SEARCHER_URL}/search/batch`, {
+            method: 'POST',
+            headers: {
+                'Content-Type': 'application/json',
+            },
+            body: JSON.stringify({
+                user_id: locals.user.id,
+                queries: queries.map((query) => ({
+                    ...query,
+                    mode: query.mode || 'fulltext',
+                })),
+            }),
+        })
+
+        if (response.status === 400) {
+            return json(await response.json(), { status: 400 })
+        }
+        if (!response.ok) {
+            logger.error('Batch search service error', undefined, {
+                status: response.status,
+                statusText: response.statusText,
+                queries: queries.length,
+            })
+            return json(
+                {
+                    error: 'Search service unavailable',
+                    details: `Status: ${response.status}`,
+                },
+                { status: 502 },
+            )
+        }
+
+        return json(await response.json())
+    } catch (error) {
+        logger.error('Error calling batch search service', error, { queries: queries.length })
+        return json(
+            {
+                error: 'Failed to perform search',
+                details: error instanceof Error ? error.message : 'Unknown error',
+            },
+            { status: 500 },
+        )
+    }
+}
@@ -0,0 +1,68 @@
+import { json, error } from '@sveltejs/kit'
+import type { RequestHandler } from './$types'
+import { userRepository } from '$lib/server/db/users'
+import type { DigestFrequency, NotificationPreferences } from '$lib/server/db/schema'
+
+const DIGEST_FREQUENCIES: DigestFrequency[] = ['off', 'daily', 'weekly']
+
+// Unset notifications are enabled and unset digests off, matching how the services read them
+function withDefaults(preferences: NotificationPreferences): Required<NotificationPreferences> {
+    return {
+        sync_failures: preferences.sync_failures ?? true,
+        digest: preferences.digest ?? 'off',
+    }
+}
+
+export const GET: RequestHandler = async ({ locals }) => {
+    if (!locals.user) {
+        throw error(401, 'Unauthorized')
+    }
+
+    const dbUser = await userRepository.findById(locals.user.id)
+    if (!dbUser) {
+        throw error(404, 'User not found')
+    }
+
+    return json(withDefaults(dbUser.notificationPreferences))
+}
+
+export const PUT: RequestHandler = async ({ request, locals }) => {
+    if (!locals.user) {
+        throw error(401, 'Unauthorized')
+    }
+
+    const body = await request.json()
+    if (typeof body !== 'object' || body === null || Array.isArray(body)) {
+        throw error(400, 'Expected an object of notification preferences')
+    }
+
+    const dbUser = await userRepository.findById(locals.user.id)
+    if (!dbUser) {
+        throw error(404, 'User not found')
+    }
+
+    const preferences: NotificationPreferences = { ...dbUser.notificationPreferences }
+    for (const [key, value] of Object.entries(body)) {
+        if (key === 'sync_failures') {
+            if (typeof value !== 'boolean') {
+                throw error(400, `Notification preference ${key} must be true or false`)
+            }
+            preferences.sync_failures = value
+        } else if (key === 'digest') {
+            if (!DIGEST_FREQUENCIES.includes(value as DigestFrequency)) {
+                const allowed = DIGEST_FREQUENCIES.join(', ')
+                throw error(400, `Notification preference ${key} must be one of ${allowed}`)
+            }
+            preferences.digest = value as DigestFrequency
+        } else {
+            throw error(400, `Unknown notification preference: ${key}`)
+        }
+    }
+
+    await userRepository.update(locals.user.id, {
+        notificationPreferences: preferences,
+        updatedAt: new Date(),
+    })
+
+    return json(withDefaults(preferences))
+}
@@ -0,0 +1,35 @@
+import type { RequestHandler } from './$types.js'
+import { app } from '$lib/server/config'
+
+function escapeXml(value: string): string {
+    return value
+        .replace(/&/g, '&amp;')
+        .replace(/</g, '&lt;')
+        .replace(/>/g, '&gt;')
+        .replace(/"/g, '&quot;')
+}
+
+// OpenSearch description, so browsers can add Omni as a search engine and search it from the
+// address bar. Searches open the search page, which sends signed-out users to sign in first.
+export const GET: RequestHandler = async () => {
+    const baseUrl = escapeXml(app.publicUrl.replace(/\/$/, ''))
+    const body = `<?xml version="1.0" encoding="UTF-8"?>
+<OpenSearchDescription xmlns="http://a9.com/-/spec/opensearch/1.1/" xmlns:moz="http://www.mozilla.org/2006/browser/search/">
+    <ShortName>Omni</ShortName>
+    <Description>Search your workplace documents with Omni</Description>
+    <InputEncoding>UTF-8</InputEncoding>
+    <Image width="16" height="16" type="image/png">${baseUrl}/favicon.png</Image>
+    <Url type="text/html" method="get" template="${baseUrl}/search?q={searchTerms}"/>
+    <Url type="application/x-suggestions+json" method="get" template="${baseUrl}/api/opensearch/suggestions?q={searchTerms}"/>
+    <Url type="application/opensearchdescription+xml" rel="self" template="${baseUrl}/opensearch.xml"/>
+    <moz:SearchForm>${baseUrl}/search</moz:SearchForm>
+</OpenSearchDescription>
+`
+
+    return new Response(body, {
+        headers: {
+            'Content-Type': 'application/opensearchdescription+xml; charset=utf-8',
+            'Cache-Control': 'public, max-age=86400',
+        },
+    })
+}
@@ -0,0 +1,6 @@
+import { confluenceSearch } from '$lib/server/confluence'
+import type { RequestHandler } from './$types.js'
+
+export const GET: RequestHandler = async ({ request, fetch, locals, url }) => {
+    return confluenceSearch('search', request, fetch, locals, url)
+}
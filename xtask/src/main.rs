@@ -0,0 +1,272 @@
+//! Repository tasks, run with `cargo xtask <task>`.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+
+const USAGE: &str = "Usage: cargo xtask <task>
+
+Tasks:
+    new-connector <name>    Scaffold a Rust connector crate in connectors/<name>";
+
+/// Files of a new connector, relative to its directory, with their templates
+const CONNECTOR_TEMPLATES: &[(&str, &str)] = &[
+    (
+        "Cargo.toml",
+        include_str!("../templates/connector/Cargo.toml.tmpl"),
+    ),
+    (
+        "Dockerfile",
+        include_str!("../templates/connector/Dockerfile.tmpl"),
+    ),
+    (
+        "src/main.rs",
+        include_str!("../templates/connector/src/main.rs.tmpl"),
+    ),
+    (
+        "src/lib.rs",
+        include_str!("../templates/connector/src/lib.rs.tmpl"),
+    ),
+    (
+        "src/api.rs",
+        include_str!("../templates/connector/src/api.rs.tmpl"),
+    ),
+    (
+        "src/client.rs",
+        include_str!("../templates/connector/src/client.rs.tmpl"),
+    ),
+    (
+        "src/config.rs",
+        include_str!("../templates/connector/src/config.rs.tmpl"),
+    ),
+    (
+        "src/models.rs",
+        include_str!("../templates/connector/src/models.rs.tmpl"),
+    ),
+    (
+        "src/sync.rs",
+        include_str!("../templates/connector/src/sync.rs.tmpl"),
+    ),
+    (
+        "tests/integration_test.rs",
+        include_str!("../templates/connector/tests/integration_test.rs.tmpl"),
+    ),
+];
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["new-connector", name] => new_connector(&repo_root(), name),
+        ["help"] | ["--help"] | ["-h"] | [] => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        _ => Err(format!("Unknown task: {}\n\n{}", args.join(" "), USAGE)),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn repo_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask is in the repository root")
+        .to_path_buf()
+}
+
+/// The ways a connector's name is spelled in its code, e.g. `google-chat`, `google_chat`,
+/// `GoogleChat` and `GOOGLE_CHAT`.
+#[derive(Debug, PartialEq)]
+struct ConnectorName {
+    kebab: String,
+    snake: String,
+    pascal: String,
+    upper: String,
+}
+
+impl ConnectorName {
+    fn parse(name: &str) -> Result<Self, String> {
+        let words: Vec<&str> = name.split('-').collect();
+        let valid = words.iter().all(|word| {
+            !word.is_empty()
+                && word
+                    .bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+        }) && name.starts_with(|c: char| c.is_ascii_lowercase());
+        if !valid {
+            return Err(format!(
+                "Invalid connector name '{}': use lowercase letters and digits, words \
+                 separated by hyphens, e.g. google-chat",
+                name
+            ));
+        }
+
+        let pascal = words
+            .iter()
+            .map(|word| word[..1].to_ascii_uppercase() + &word[1..])
+            .collect();
+        Ok(Self {
+            kebab: name.to_string(),
+            snake: words.join("_"),
+            pascal,
+            upper: words.join("_").to_ascii_uppercase(),
+        })
+    }
+
+    fn render(&self, template: &str) -> String {
+        template
+            .replace("{{name}}", &self.kebab)
+            .replace("{{snake}}", &self.snake)
+            .replace("{{Name}}", &self.pascal)
+            .replace("{{NAME}}", &self.upper)
+    }
+}
+
+fn new_connector(root: &Path, name: &str) -> Result<(), String> {
+    let name = ConnectorName::parse(name)?;
+    let dir = root.join("connectors").join(&name.kebab);
+    if dir.exists() {
+        return Err(format!("{} already exists", dir.display()));
+    }
+
+    for (path, template) in CONNECTOR_TEMPLATES {
+        let file = dir.join(path);
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        fs::write(&file, name.render(template))
+            .map_err(|e| format!("Failed to write {}: {}", file.display(), e))?;
+    }
+
+    // Lines wrap differently with the name's length
+    let formatted = Command::new("rustfmt")
+        .args([
+            "--edition",
+            "2021",
+            "src/main.rs",
+            "src/lib.rs",
+            "tests/integration_test.rs",
+        ])
+        .current_dir(&dir)
+        .status();
+    if !formatted.is_ok_and(|status| status.success()) {
+        eprintln!(
+            "warning: could not run rustfmt, format connectors/{} with cargo fmt",
+            name.kebab
+        );
+    }
+
+    let manifest = root.join("Cargo.toml");
+    let workspace = fs::read_to_string(&manifest)
+        .map_err(|e| format!("Failed to read {}: {}", manifest.display(), e))?;
+    let workspace = add_workspace_member(&workspace, &format!("connectors/{}", name.kebab))?;
+    fs::write(&manifest, workspace)
+        .map_err(|e| format!("Failed to write {}: {}", manifest.display(), e))?;
+
+    println!(
+        "Created connectors/{kebab} and added it to the workspace.
+
+Next steps:
+  1. Add SourceType::{pascal} and ServiceProvider::{pascal} to shared/src/models.rs, and a
+     migration allowing '{snake}' in the sources and service_credentials checks
+  2. Read {upper}_CONNECTOR_URL in services/connector-manager/src/config.rs
+  3. Add {kebab}-connector to docker/docker-compose.yml and docker/docker-compose.dev.yml,
+     and its port and URL to .env.example
+  4. Replace the TODOs and the example API in connectors/{kebab}/src, then run
+     cargo test -p omni-{kebab}-connector",
+        kebab = name.kebab,
+        snake = name.snake,
+        pascal = name.pascal,
+        upper = name.upper,
+    );
+    Ok(())
+}
+
+/// Add a crate to the workspace members, after the last connector.
+fn add_workspace_member(manifest: &str, member: &str) -> Result<String, String> {
+    let entry = format!("    \"{}\",\n", member);
+    if manifest.contains(&entry) {
+        return Err(format!("{} is already a workspace member", member));
+    }
+    let last_connector = manifest
+        .match_indices("    \"connectors/")
+        .last()
+        .map(|(i, _)| i)
+        .ok_or("No connector found in the workspace members")?;
+    let insert_at = last_connector
+        + manifest[last_connector..]
+            .find('\n')
+            .ok_or("Unterminated workspace member")?
+        + 1;
+
+    let mut updated = manifest.to_string();
+    updated.insert_str(insert_at, &entry);
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connector_name_spellings() {
+        let name = ConnectorName::parse("google-chat").unwrap();
+        assert_eq!(name.snake, "google_chat");
+        assert_eq!(name.pascal, "GoogleChat");
+        assert_eq!(name.upper, "GOOGLE_CHAT");
+        assert_eq!(
+            name.render("omni_{{snake}}_connector::{{Name}}Client at {{NAME}}_URL"),
+            "omni_google_chat_connector::GoogleChatClient at GOOGLE_CHAT_URL"
+        );
+
+        for invalid in [
+            "",
+            "Linear",
+            "9lives",
+            "google--chat",
+            "google_chat",
+            "chat-",
+        ] {
+            assert!(ConnectorName::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_add_workspace_member_after_last_connector() {
+        let manifest = "[workspace]\nmembers = [\n    \"connectors/slack\",\n    \
+                        \"connectors/web\",\n    \"shared\",\n]\n";
+
+        let updated = add_workspace_member(manifest, "connectors/linear").unwrap();
+
+        assert_eq!(
+            updated,
+            "[workspace]\nmembers = [\n    \"connectors/slack\",\n    \"connectors/web\",\n    \
+             \"connectors/linear\",\n    \"shared\",\n]\n"
+        );
+        assert!(add_workspace_member(&updated, "connectors/linear").is_err());
+    }
+
+    #[test]
+    fn test_templates_only_use_known_placeholders() {
+        let name = ConnectorName::parse("linear").unwrap();
+        for (path, template) in CONNECTOR_TEMPLATES {
+            let rendered = name.render(template);
+            for placeholder in ["{{name}}", "{{snake}}", "{{Name}}", "{{NAME}}"] {
+                assert!(
+                    !rendered.contains(placeholder),
+                    "{} in {}",
+                    placeholder,
+                    path
+                );
+            }
+        }
+    }
+}